@@ -1,10 +1,75 @@
 use serde::Serialize;
 
 #[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ApiKey {
     pub id: String,
     pub name: String,
     pub key_hash: String,
     pub created_at: String,
     pub last_used_at: Option<String>,
+    /// CIDR blocks the key may be used from. Empty means any address.
+    pub allowed_cidrs: Vec<String>,
+    /// Path prefixes the key may call (e.g. `/api/claude-runs`). Empty means
+    /// every authenticated route.
+    pub allowed_routes: Vec<String>,
+    /// Projects (by id) the key may read and change. Empty means every
+    /// project.
+    pub allowed_projects: Vec<String>,
+    /// The organization the key belongs to. Such a key only reaches the
+    /// organization's projects and runners; `None` is a server-wide key.
+    pub org_id: Option<String>,
+}
+
+impl ApiKey {
+    /// Whether `path` falls under one of the allowed route prefixes.
+    ///
+    /// Prefixes match whole path segments, so `/api/tasks` allows
+    /// `/api/tasks/123` but not `/api/tasks-archive`.
+    pub fn allows_route(&self, path: &str) -> bool {
+        if self.allowed_routes.is_empty() {
+            return true;
+        }
+        self.allowed_routes.iter().any(|prefix| {
+            let prefix = prefix.trim_end_matches('/');
+            match path.strip_prefix(prefix) {
+                Some(rest) => rest.is_empty() || rest.starts_with('/'),
+                None => false,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key_with_routes(routes: &[&str]) -> ApiKey {
+        ApiKey {
+            id: "k".into(),
+            name: String::new(),
+            key_hash: String::new(),
+            created_at: String::new(),
+            last_used_at: None,
+            allowed_cidrs: vec![],
+            allowed_routes: routes.iter().map(|r| r.to_string()).collect(),
+            allowed_projects: vec![],
+            org_id: None,
+        }
+    }
+
+    #[test]
+    fn no_routes_allows_everything() {
+        assert!(key_with_routes(&[]).allows_route("/api/projects"));
+    }
+
+    #[test]
+    fn route_prefix_matches_whole_segments() {
+        let key = key_with_routes(&["/api/claude-runs", "/api/runners/"]);
+        assert!(key.allows_route("/api/claude-runs"));
+        assert!(key.allows_route("/api/claude-runs/claim"));
+        assert!(key.allows_route("/api/runners/register"));
+        assert!(!key.allows_route("/api/claude-runs-archive"));
+        assert!(!key.allows_route("/api/projects"));
+    }
 }
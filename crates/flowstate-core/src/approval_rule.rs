@@ -0,0 +1,188 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::task::{ApprovalStatus, Task, UpdateTask};
+
+/// A workflow phase whose artifact is approved, named after its status
+/// field on [`Task`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalPhase {
+    Research,
+    Spec,
+    Plan,
+    Verify,
+}
+
+impl ApprovalPhase {
+    pub const ALL: &[ApprovalPhase] = &[
+        ApprovalPhase::Research,
+        ApprovalPhase::Spec,
+        ApprovalPhase::Plan,
+        ApprovalPhase::Verify,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApprovalPhase::Research => "research",
+            ApprovalPhase::Spec => "spec",
+            ApprovalPhase::Plan => "plan",
+            ApprovalPhase::Verify => "verify",
+        }
+    }
+
+    /// The design phase is accepted as "design" or "spec".
+    pub fn parse_str(s: &str) -> Option<Self> {
+        match s {
+            "research" => Some(ApprovalPhase::Research),
+            "spec" | "design" => Some(ApprovalPhase::Spec),
+            "plan" => Some(ApprovalPhase::Plan),
+            "verify" => Some(ApprovalPhase::Verify),
+            _ => None,
+        }
+    }
+
+    /// The task's approval status for this phase.
+    pub fn status(&self, task: &Task) -> ApprovalStatus {
+        match self {
+            ApprovalPhase::Research => task.research_status,
+            ApprovalPhase::Spec => task.spec_status,
+            ApprovalPhase::Plan => task.plan_status,
+            ApprovalPhase::Verify => task.verify_status,
+        }
+    }
+
+    /// The status this update sets for the phase, if any.
+    pub fn update_status(&self, update: &UpdateTask) -> Option<ApprovalStatus> {
+        match self {
+            ApprovalPhase::Research => update.research_status,
+            ApprovalPhase::Spec => update.spec_status,
+            ApprovalPhase::Plan => update.plan_status,
+            ApprovalPhase::Verify => update.verify_status,
+        }
+    }
+
+    pub fn set_status(&self, update: &mut UpdateTask, status: ApprovalStatus) {
+        let field = match self {
+            ApprovalPhase::Research => &mut update.research_status,
+            ApprovalPhase::Spec => &mut update.spec_status,
+            ApprovalPhase::Plan => &mut update.plan_status,
+            ApprovalPhase::Verify => &mut update.verify_status,
+        };
+        *field = Some(status);
+    }
+}
+
+impl fmt::Display for ApprovalPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How a project approves one phase. Phases without a rule are approved by
+/// hand, or by autopilot on autopilot tasks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalPolicy {
+    /// Approved as soon as the run that produced the artifact completes.
+    Auto,
+    /// Approved by a person; autopilot stops and waits.
+    Human,
+    /// Approved only by the task's reviewer; autopilot stops and waits.
+    Reviewer,
+}
+
+impl ApprovalPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApprovalPolicy::Auto => "auto",
+            ApprovalPolicy::Human => "human",
+            ApprovalPolicy::Reviewer => "reviewer",
+        }
+    }
+
+    pub fn parse_str(s: &str) -> Option<Self> {
+        match s {
+            "auto" => Some(ApprovalPolicy::Auto),
+            "human" => Some(ApprovalPolicy::Human),
+            "reviewer" => Some(ApprovalPolicy::Reviewer),
+            _ => None,
+        }
+    }
+}
+
+/// A project's policy for one phase.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ApprovalRule {
+    pub project_id: String,
+    pub phase: ApprovalPhase,
+    pub policy: ApprovalPolicy,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The policy `rules` set for `phase`, if any.
+pub fn policy_for(rules: &[ApprovalRule], phase: ApprovalPhase) -> Option<ApprovalPolicy> {
+    rules.iter().find(|r| r.phase == phase).map(|r| r.policy)
+}
+
+/// Whether `caller` is the reviewer named on a task. A caller identity such
+/// as `user:alice@example.com` or `key:alice` matches on the part after the
+/// prefix too, ignoring case. Nobody matches an empty reviewer.
+pub fn is_reviewer(reviewer: &str, caller: &str) -> bool {
+    let reviewer = reviewer.trim();
+    if reviewer.is_empty() {
+        return false;
+    }
+    let name = caller.split_once(':').map_or(caller, |(_, name)| name);
+    caller.eq_ignore_ascii_case(reviewer) || name.eq_ignore_ascii_case(reviewer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phases_and_policies_round_trip() {
+        for phase in ApprovalPhase::ALL {
+            assert_eq!(ApprovalPhase::parse_str(phase.as_str()), Some(*phase));
+        }
+        assert_eq!(
+            ApprovalPhase::parse_str("design"),
+            Some(ApprovalPhase::Spec)
+        );
+        assert_eq!(ApprovalPhase::parse_str("build"), None);
+        for policy in [
+            ApprovalPolicy::Auto,
+            ApprovalPolicy::Human,
+            ApprovalPolicy::Reviewer,
+        ] {
+            assert_eq!(ApprovalPolicy::parse_str(policy.as_str()), Some(policy));
+        }
+        assert_eq!(ApprovalPolicy::parse_str("manual"), None);
+    }
+
+    #[test]
+    fn reviewer_matches_caller_with_or_without_prefix() {
+        assert!(is_reviewer("alice@example.com", "user:alice@example.com"));
+        assert!(is_reviewer("Alice", "key:alice"));
+        assert!(is_reviewer("key:ci", "key:ci"));
+        assert!(!is_reviewer("alice", "user:bob"));
+        assert!(!is_reviewer("", "user:bob"));
+        assert!(!is_reviewer("  ", ""));
+    }
+
+    #[test]
+    fn update_status_reads_back_what_set_status_wrote() {
+        for phase in ApprovalPhase::ALL {
+            let mut update = UpdateTask::default();
+            assert_eq!(phase.update_status(&update), None);
+            phase.set_status(&mut update, ApprovalStatus::Approved);
+            assert_eq!(phase.update_status(&update), Some(ApprovalStatus::Approved));
+        }
+    }
+}
@@ -2,11 +2,57 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Attachment {
     pub id: String,
     pub task_id: String,
     pub filename: String,
     pub store_key: String,
     pub size_bytes: i64,
+    /// MIME type recorded at upload, e.g. `image/png`.
+    #[serde(default)]
+    pub content_type: String,
+    /// Lowercase hex SHA-256 of the stored bytes; empty for attachments
+    /// recorded before checksums were kept.
+    #[serde(default)]
+    pub sha256: String,
     pub created_at: DateTime<Utc>,
 }
+
+/// Fallback content type for uploads that don't send one, based on the
+/// filename's extension.
+pub fn guess_content_type(filename: &str) -> &'static str {
+    let ext = filename
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "pdf" => "application/pdf",
+        "json" => "application/json",
+        "zip" => "application/zip",
+        "gz" => "application/gzip",
+        "md" => "text/markdown",
+        "txt" | "log" => "text/plain",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guesses_content_type_from_extension() {
+        assert_eq!(guess_content_type("shot.PNG"), "image/png");
+        assert_eq!(guess_content_type("notes.md"), "text/markdown");
+        assert_eq!(guess_content_type("archive.tar.gz"), "application/gzip");
+        assert_eq!(guess_content_type("Makefile"), "application/octet-stream");
+    }
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+use crate::project::Project;
+use crate::task::{Status, Task};
+
+/// One project's swimlane on a cross-project roll-up board.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ProjectLane {
+    pub project: Project,
+    /// Top-level tasks of the project, in board order.
+    pub tasks: Vec<Task>,
+}
+
+impl ProjectLane {
+    /// The lane's tasks in one board column.
+    pub fn column(&self, status: Status) -> impl Iterator<Item = &Task> {
+        self.tasks.iter().filter(move |t| t.status == status)
+    }
+}
@@ -3,7 +3,10 @@ use std::fmt;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::run_window::RunWindow;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum ClaudeAction {
     Research,
@@ -15,9 +18,24 @@ pub enum ClaudeAction {
     DesignDistill,
     PlanDistill,
     VerifyDistill,
+    /// Address review comments on a task's open PR by pushing to its branch.
+    Revise,
 }
 
 impl ClaudeAction {
+    pub const ALL: &[ClaudeAction] = &[
+        ClaudeAction::Research,
+        ClaudeAction::Design,
+        ClaudeAction::Plan,
+        ClaudeAction::Build,
+        ClaudeAction::Verify,
+        ClaudeAction::ResearchDistill,
+        ClaudeAction::DesignDistill,
+        ClaudeAction::PlanDistill,
+        ClaudeAction::VerifyDistill,
+        ClaudeAction::Revise,
+    ];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             ClaudeAction::Research => "research",
@@ -29,6 +47,7 @@ impl ClaudeAction {
             ClaudeAction::DesignDistill => "design_distill",
             ClaudeAction::PlanDistill => "plan_distill",
             ClaudeAction::VerifyDistill => "verify_distill",
+            ClaudeAction::Revise => "revise",
         }
     }
 
@@ -43,9 +62,35 @@ impl ClaudeAction {
             "design_distill" => Some(ClaudeAction::DesignDistill),
             "plan_distill" => Some(ClaudeAction::PlanDistill),
             "verify_distill" => Some(ClaudeAction::VerifyDistill),
+            "revise" => Some(ClaudeAction::Revise),
+            _ => None,
+        }
+    }
+
+    /// The distill action that revises a phase's artifact from reviewer
+    /// feedback. The design phase is accepted as "design" or "spec".
+    pub fn distill_for_phase(phase: &str) -> Option<Self> {
+        match phase {
+            "research" => Some(ClaudeAction::ResearchDistill),
+            "design" | "spec" => Some(ClaudeAction::DesignDistill),
+            "plan" => Some(ClaudeAction::PlanDistill),
+            "verify" => Some(ClaudeAction::VerifyDistill),
             _ => None,
         }
     }
+
+    /// Whether someone is usually waiting on the result: a distill revises
+    /// an artifact right after its reviewer sent feedback. The scheduler
+    /// steers these to faster runners.
+    pub fn is_time_sensitive(&self) -> bool {
+        matches!(
+            self,
+            ClaudeAction::ResearchDistill
+                | ClaudeAction::DesignDistill
+                | ClaudeAction::PlanDistill
+                | ClaudeAction::VerifyDistill
+        )
+    }
 }
 
 impl fmt::Display for ClaudeAction {
@@ -55,6 +100,7 @@ impl fmt::Display for ClaudeAction {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum ClaudeRunStatus {
     Queued,
@@ -100,6 +146,7 @@ impl fmt::Display for ClaudeRunStatus {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct ClaudeRun {
     pub id: String,
     pub task_id: String,
@@ -121,20 +168,68 @@ pub struct ClaudeRun {
     pub finished_at: Option<DateTime<Utc>>,
     #[serde(default)]
     pub required_capability: Option<String>,
+    /// Scheduling weight: queued runs are claimed highest priority first,
+    /// then oldest first.
+    #[serde(default)]
+    pub priority: i32,
+    /// Reviewer feedback this run was queued to address, for distill runs
+    /// enqueued together with a rejection.
+    #[serde(default)]
+    pub feedback: Option<String>,
+    /// Client-chosen key; creating a run with a key already used on the
+    /// same task returns that run instead of queueing another.
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+    /// Daily window in which this run may be claimed, in place of its
+    /// project's.
+    #[serde(default)]
+    pub run_window: Option<RunWindow>,
+    /// W3C `traceparent` of the request that queued the run, so the runner
+    /// executing it continues the same trace.
+    #[serde(default)]
+    pub trace_context: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct CreateClaudeRun {
     pub task_id: String,
     pub action: ClaudeAction,
     #[serde(default)]
     pub required_capability: Option<String>,
+    #[serde(default)]
+    pub priority: i32,
+    /// See [`ClaudeRun::feedback`].
+    #[serde(default)]
+    pub feedback: Option<String>,
+    /// See [`ClaudeRun::idempotency_key`].
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+    /// See [`ClaudeRun::run_window`].
+    #[serde(default)]
+    pub run_window: Option<RunWindow>,
+    /// See [`ClaudeRun::trace_context`].
+    #[serde(default)]
+    pub trace_context: Option<String>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn distill_for_phase() {
+        assert_eq!(
+            ClaudeAction::distill_for_phase("spec"),
+            Some(ClaudeAction::DesignDistill)
+        );
+        assert_eq!(
+            ClaudeAction::distill_for_phase("verify"),
+            Some(ClaudeAction::VerifyDistill)
+        );
+        assert_eq!(ClaudeAction::distill_for_phase("build"), None);
+    }
+
     #[test]
     fn claude_action_parse_str_all() {
         assert_eq!(
@@ -167,6 +262,10 @@ mod tests {
             ClaudeAction::parse_str("verify_distill"),
             Some(ClaudeAction::VerifyDistill)
         );
+        assert_eq!(
+            ClaudeAction::parse_str("revise"),
+            Some(ClaudeAction::Revise)
+        );
         assert_eq!(ClaudeAction::parse_str("invalid"), None);
         assert_eq!(ClaudeAction::parse_str("compile"), None);
         assert_eq!(ClaudeAction::parse_str(""), None);
@@ -184,6 +283,7 @@ mod tests {
             ClaudeAction::DesignDistill,
             ClaudeAction::PlanDistill,
             ClaudeAction::VerifyDistill,
+            ClaudeAction::Revise,
         ];
         for a in &all {
             assert_eq!(ClaudeAction::parse_str(a.as_str()), Some(*a));
@@ -202,6 +302,7 @@ mod tests {
             ClaudeAction::DesignDistill,
             ClaudeAction::PlanDistill,
             ClaudeAction::VerifyDistill,
+            ClaudeAction::Revise,
         ];
         for a in &all {
             assert_eq!(format!("{a}"), a.as_str());
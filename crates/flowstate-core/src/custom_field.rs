@@ -0,0 +1,264 @@
+use std::fmt;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::FlowstateError;
+
+/// Format dates are stored and compared in.
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum CustomFieldType {
+    Text,
+    Number,
+    Enum,
+    Date,
+}
+
+impl CustomFieldType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CustomFieldType::Text => "text",
+            CustomFieldType::Number => "number",
+            CustomFieldType::Enum => "enum",
+            CustomFieldType::Date => "date",
+        }
+    }
+
+    pub fn parse_str(s: &str) -> Option<Self> {
+        match s {
+            "text" => Some(CustomFieldType::Text),
+            "number" => Some(CustomFieldType::Number),
+            "enum" => Some(CustomFieldType::Enum),
+            "date" => Some(CustomFieldType::Date),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CustomFieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A project-defined task attribute such as "customer" or "severity".
+/// Values live per task in `task_field_values`, keyed by the field's id.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CustomField {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: CustomFieldType,
+    /// Allowed values of an `enum` field; empty for every other type.
+    pub options: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateCustomField {
+    pub project_id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub field_type: CustomFieldType,
+    #[serde(default)]
+    pub options: Vec<String>,
+}
+
+/// A field's type is fixed once created, since existing values were
+/// validated against it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UpdateCustomField {
+    pub name: Option<String>,
+    pub options: Option<Vec<String>>,
+}
+
+/// One task's value for one custom field, stored in normalized form
+/// (see [`CustomField::normalize_value`]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TaskFieldValue {
+    pub task_id: String,
+    pub field_id: String,
+    pub value: String,
+}
+
+impl CreateCustomField {
+    pub fn validate(&self) -> Result<(), FlowstateError> {
+        validate_definition(&self.name, self.field_type, &self.options)
+    }
+}
+
+impl UpdateCustomField {
+    /// Check the update against the field it applies to.
+    pub fn validate(&self, field: &CustomField) -> Result<(), FlowstateError> {
+        validate_definition(
+            self.name.as_deref().unwrap_or(&field.name),
+            field.field_type,
+            self.options.as_deref().unwrap_or(&field.options),
+        )
+    }
+}
+
+impl CustomField {
+    /// Check `value` against the field's type and return the form it is
+    /// stored in: numbers without trailing zeros, dates as `YYYY-MM-DD`.
+    /// Storing one canonical form keeps equality filters exact.
+    pub fn normalize_value(&self, value: &str) -> Result<String, FlowstateError> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(invalid(format!(
+                "{}: value must not be empty; use null to clear it",
+                self.name
+            )));
+        }
+        match self.field_type {
+            CustomFieldType::Text => Ok(value.to_string()),
+            CustomFieldType::Number => match value.parse::<f64>() {
+                Ok(n) if n.is_finite() => Ok(n.to_string()),
+                _ => Err(invalid(format!("{}: {value:?} is not a number", self.name))),
+            },
+            CustomFieldType::Enum => {
+                if self.options.iter().any(|o| o == value) {
+                    Ok(value.to_string())
+                } else {
+                    Err(invalid(format!(
+                        "{}: {value:?} is not one of {}",
+                        self.name,
+                        self.options.join(", ")
+                    )))
+                }
+            }
+            CustomFieldType::Date => NaiveDate::parse_from_str(value, DATE_FORMAT)
+                .map(|d| d.format(DATE_FORMAT).to_string())
+                .map_err(|_| invalid(format!("{}: {value:?} is not a YYYY-MM-DD date", self.name))),
+        }
+    }
+}
+
+fn validate_definition(
+    name: &str,
+    field_type: CustomFieldType,
+    options: &[String],
+) -> Result<(), FlowstateError> {
+    if name.trim().is_empty() {
+        return Err(invalid("field name must not be empty".into()));
+    }
+    if field_type != CustomFieldType::Enum {
+        if !options.is_empty() {
+            return Err(invalid(format!("{field_type} fields take no options")));
+        }
+        return Ok(());
+    }
+    if options.is_empty() {
+        return Err(invalid("enum fields need at least one option".into()));
+    }
+    for (i, option) in options.iter().enumerate() {
+        if option.trim().is_empty() || option.trim() != option {
+            return Err(invalid(format!("invalid enum option {option:?}")));
+        }
+        if options[..i].contains(option) {
+            return Err(invalid(format!("duplicate enum option {option:?}")));
+        }
+    }
+    Ok(())
+}
+
+fn invalid(msg: String) -> FlowstateError {
+    FlowstateError::InvalidInput(msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(field_type: CustomFieldType, options: &[&str]) -> CustomField {
+        CustomField {
+            id: "f1".into(),
+            project_id: "p1".into(),
+            name: "severity".into(),
+            field_type,
+            options: options.iter().map(|o| o.to_string()).collect(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn field_type_as_str_roundtrip() {
+        let all = [
+            CustomFieldType::Text,
+            CustomFieldType::Number,
+            CustomFieldType::Enum,
+            CustomFieldType::Date,
+        ];
+        for t in &all {
+            assert_eq!(CustomFieldType::parse_str(t.as_str()), Some(*t));
+            assert_eq!(
+                serde_json::to_value(t).unwrap(),
+                serde_json::json!(t.as_str())
+            );
+        }
+        assert_eq!(CustomFieldType::parse_str("bool"), None);
+    }
+
+    #[test]
+    fn definition_validation() {
+        let create = |field_type, options: &[&str]| CreateCustomField {
+            project_id: "p1".into(),
+            name: "severity".into(),
+            field_type,
+            options: options.iter().map(|o| o.to_string()).collect(),
+        };
+        assert!(create(CustomFieldType::Text, &[]).validate().is_ok());
+        assert!(create(CustomFieldType::Enum, &["low", "high"])
+            .validate()
+            .is_ok());
+        assert!(create(CustomFieldType::Enum, &[]).validate().is_err());
+        assert!(create(CustomFieldType::Enum, &["low", "low"])
+            .validate()
+            .is_err());
+        assert!(create(CustomFieldType::Number, &["1"]).validate().is_err());
+
+        let severity = field(CustomFieldType::Enum, &["low"]);
+        let clear_options = UpdateCustomField {
+            options: Some(vec![]),
+            ..Default::default()
+        };
+        assert!(clear_options.validate(&severity).is_err());
+        let rename = UpdateCustomField {
+            name: Some("impact".into()),
+            ..Default::default()
+        };
+        assert!(rename.validate(&severity).is_ok());
+    }
+
+    #[test]
+    fn normalize_values() {
+        let number = field(CustomFieldType::Number, &[]);
+        assert_eq!(number.normalize_value(" 3.50 ").unwrap(), "3.5");
+        assert_eq!(number.normalize_value("2").unwrap(), "2");
+        assert!(number.normalize_value("NaN").is_err());
+        assert!(number.normalize_value("lots").is_err());
+
+        let date = field(CustomFieldType::Date, &[]);
+        assert_eq!(date.normalize_value("2026-3-7").unwrap(), "2026-03-07");
+        assert!(date.normalize_value("07/03/2026").is_err());
+
+        let severity = field(CustomFieldType::Enum, &["low", "high"]);
+        assert_eq!(severity.normalize_value("high").unwrap(), "high");
+        assert!(severity.normalize_value("High").is_err());
+
+        let text = field(CustomFieldType::Text, &[]);
+        assert_eq!(text.normalize_value("Acme").unwrap(), "Acme");
+        assert!(text.normalize_value("  ").is_err());
+    }
+}
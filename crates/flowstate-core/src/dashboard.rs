@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+
+use crate::claude_run::ClaudeAction;
+use crate::sprint::SprintStatus;
+use crate::task::{Priority, Status};
+
+/// Task, run and sprint figures for one project's dashboard.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ProjectDashboard {
+    pub project_id: String,
+    /// Unarchived tasks in every status, in workflow order.
+    pub tasks_by_status: Vec<StatusCount>,
+    /// Unarchived tasks at every priority, most urgent first.
+    pub tasks_by_priority: Vec<PriorityCount>,
+    /// Outcomes of finished runs per action, ordered by action name.
+    pub runs: Vec<RunOutcomes>,
+    /// Unarchived sprints, active first, then planned, then completed.
+    pub sprints: Vec<SprintProgress>,
+}
+
+impl ProjectDashboard {
+    /// Total unarchived tasks.
+    pub fn total_tasks(&self) -> i64 {
+        self.tasks_by_status.iter().map(|c| c.count).sum()
+    }
+
+    /// Outcomes across every action.
+    pub fn all_runs(&self) -> RunOutcomes {
+        self.runs
+            .iter()
+            .fold(RunOutcomes::default(), |mut total, r| {
+                total.completed += r.completed;
+                total.failed += r.failed;
+                total.timed_out += r.timed_out;
+                total.cancelled += r.cancelled;
+                total
+            })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct StatusCount {
+    pub status: Status,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PriorityCount {
+    pub priority: Priority,
+    pub count: i64,
+}
+
+/// How the finished runs of one action (or of all, when `action` is `None`)
+/// ended.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RunOutcomes {
+    pub action: Option<ClaudeAction>,
+    pub completed: i64,
+    pub failed: i64,
+    pub timed_out: i64,
+    pub cancelled: i64,
+}
+
+impl RunOutcomes {
+    /// Fraction of runs that completed, leaving out cancelled ones; `None`
+    /// before any run has completed or failed.
+    pub fn success_rate(&self) -> Option<f64> {
+        let decided = self.completed + self.failed + self.timed_out;
+        (decided > 0).then(|| self.completed as f64 / decided as f64)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SprintProgress {
+    pub sprint_id: String,
+    pub name: String,
+    pub status: SprintStatus,
+    /// Tasks in the sprint, archived ones included.
+    pub total_tasks: i64,
+    pub done_tasks: i64,
+}
+
+impl SprintProgress {
+    /// Fraction of the sprint's tasks that are done; 0 for an empty sprint.
+    pub fn progress(&self) -> f64 {
+        if self.total_tasks == 0 {
+            0.0
+        } else {
+            self.done_tasks as f64 / self.total_tasks as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn success_rate_ignores_cancelled_runs() {
+        let outcomes = RunOutcomes {
+            action: Some(ClaudeAction::Build),
+            completed: 3,
+            failed: 1,
+            timed_out: 0,
+            cancelled: 4,
+        };
+        assert_eq!(outcomes.success_rate(), Some(0.75));
+        assert_eq!(RunOutcomes::default().success_rate(), None);
+    }
+
+    #[test]
+    fn all_runs_sums_every_action() {
+        let dashboard = ProjectDashboard {
+            project_id: "p".into(),
+            tasks_by_status: vec![],
+            tasks_by_priority: vec![],
+            runs: vec![
+                RunOutcomes {
+                    action: Some(ClaudeAction::Research),
+                    completed: 2,
+                    ..RunOutcomes::default()
+                },
+                RunOutcomes {
+                    action: Some(ClaudeAction::Build),
+                    completed: 1,
+                    failed: 2,
+                    ..RunOutcomes::default()
+                },
+            ],
+            sprints: vec![],
+        };
+        let all = dashboard.all_runs();
+        assert_eq!((all.action, all.completed, all.failed), (None, 3, 2));
+    }
+}
@@ -0,0 +1,102 @@
+use crate::error::FlowstateError;
+
+/// Placeholder a link template uses for the task id.
+const ID_PLACEHOLDER: &str = ":id";
+
+/// Template used when none is configured; `flowstate open` handles it.
+pub const DEFAULT_TASK_LINK: &str = "flowstate://task/:id";
+
+/// Turns task ids into links and back, from a template such as
+/// `flowstate://task/:id` or `https://tasks.example.com/t/:id` where `:id`
+/// stands for the task id. A web template lets chat and code hosts that
+/// only make `http` links clickable redirect to the TUI handler.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskLinks {
+    prefix: String,
+    suffix: String,
+}
+
+impl TaskLinks {
+    pub fn new(template: &str) -> Result<Self, FlowstateError> {
+        let (prefix, suffix) = template.split_once(ID_PLACEHOLDER).ok_or_else(|| {
+            FlowstateError::InvalidInput(format!(
+                "task link template must contain {ID_PLACEHOLDER}: {template}"
+            ))
+        })?;
+        if prefix.is_empty() || suffix.contains(ID_PLACEHOLDER) {
+            return Err(FlowstateError::InvalidInput(format!(
+                "task link template must start with a scheme and contain {ID_PLACEHOLDER} once: {template}"
+            )));
+        }
+        Ok(Self {
+            prefix: prefix.to_string(),
+            suffix: suffix.to_string(),
+        })
+    }
+
+    /// The link that opens `task_id`.
+    pub fn task_url(&self, task_id: &str) -> String {
+        format!("{}{task_id}{}", self.prefix, self.suffix)
+    }
+
+    /// The task a link points at, if it matches this template. Query
+    /// strings and fragments that chat clients append are ignored.
+    pub fn task_id(&self, url: &str) -> Option<String> {
+        let id = url.strip_prefix(&self.prefix)?;
+        let id = if self.suffix.is_empty() {
+            id.split(['?', '#', '/']).next()?
+        } else {
+            &id[..id.find(&self.suffix)?]
+        };
+        (!id.is_empty() && !id.contains('/')).then(|| id.to_string())
+    }
+}
+
+impl Default for TaskLinks {
+    fn default() -> Self {
+        Self::new(DEFAULT_TASK_LINK).expect("default template is valid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_links_roundtrip() {
+        let links = TaskLinks::default();
+        assert_eq!(links.task_url("abc-123"), "flowstate://task/abc-123");
+        assert_eq!(
+            links.task_id("flowstate://task/abc-123").as_deref(),
+            Some("abc-123")
+        );
+        assert_eq!(
+            links
+                .task_id("flowstate://task/abc-123/?from=slack")
+                .as_deref(),
+            Some("abc-123")
+        );
+        assert_eq!(links.task_id("flowstate://task/"), None);
+        assert_eq!(links.task_id("https://example.com/abc-123"), None);
+    }
+
+    #[test]
+    fn web_template_with_suffix() {
+        let links = TaskLinks::new("https://tasks.example.com/t/:id/view").unwrap();
+        assert_eq!(links.task_url("t1"), "https://tasks.example.com/t/t1/view");
+        assert_eq!(
+            links
+                .task_id("https://tasks.example.com/t/t1/view")
+                .as_deref(),
+            Some("t1")
+        );
+        assert_eq!(links.task_id("https://tasks.example.com/t/t1"), None);
+    }
+
+    #[test]
+    fn template_needs_one_placeholder_after_a_prefix() {
+        assert!(TaskLinks::new("flowstate://task/").is_err());
+        assert!(TaskLinks::new(":id").is_err());
+        assert!(TaskLinks::new("x://:id/:id").is_err());
+    }
+}
@@ -0,0 +1,173 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use chrono_tz::Tz;
+
+use crate::error::FlowstateError;
+
+/// Past this many days, relative times give way to the date.
+const MAX_RELATIVE_DAYS: i64 = 30;
+
+/// The timezone timestamps are shown in. Timestamps are always stored and
+/// exchanged in UTC; this only affects how they are displayed.
+///
+/// Written as `UTC`, a fixed offset such as `+02:00`, or an IANA name such
+/// as `Europe/Berlin`, which follows daylight saving.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DisplayZone {
+    #[default]
+    Utc,
+    Fixed(FixedOffset),
+    Named(Tz),
+}
+
+impl DisplayZone {
+    pub fn parse_str(s: &str) -> Result<Self, FlowstateError> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("utc") || s == "Z" {
+            return Ok(Self::Utc);
+        }
+        if s.starts_with(['+', '-']) {
+            return parse_offset(s).map(Self::Fixed).ok_or_else(|| {
+                FlowstateError::InvalidInput(format!(
+                    "invalid UTC offset: {s} (expected e.g. +02:00)"
+                ))
+            });
+        }
+        s.parse::<Tz>().map(Self::Named).map_err(|_| {
+            FlowstateError::InvalidInput(format!(
+                "unknown timezone: {s} (expected UTC, an offset such as +02:00, or a name such as Europe/Berlin)"
+            ))
+        })
+    }
+
+    /// `at` as RFC 3339 with this zone's offset.
+    pub fn to_rfc3339(&self, at: DateTime<Utc>) -> String {
+        match self {
+            Self::Utc => at.to_rfc3339(),
+            Self::Fixed(offset) => at.with_timezone(offset).to_rfc3339(),
+            Self::Named(tz) => at.with_timezone(tz).to_rfc3339(),
+        }
+    }
+
+    /// `at` to the minute with the zone, e.g. `2026-10-16 14:30 CEST`.
+    pub fn format(&self, at: DateTime<Utc>) -> String {
+        match self {
+            Self::Utc => at.format("%Y-%m-%d %H:%M UTC").to_string(),
+            Self::Fixed(offset) => at
+                .with_timezone(offset)
+                .format("%Y-%m-%d %H:%M %:z")
+                .to_string(),
+            Self::Named(tz) => at.with_timezone(tz).format("%Y-%m-%d %H:%M %Z").to_string(),
+        }
+    }
+
+    /// The local date of `at`, e.g. `2026-10-16`.
+    pub fn format_date(&self, at: DateTime<Utc>) -> String {
+        match self {
+            Self::Utc => at.format("%Y-%m-%d").to_string(),
+            Self::Fixed(offset) => at.with_timezone(offset).format("%Y-%m-%d").to_string(),
+            Self::Named(tz) => at.with_timezone(tz).format("%Y-%m-%d").to_string(),
+        }
+    }
+
+    /// `at` relative to `now`: `just now`, `5m ago`, `3h ago`, `2d ago`, or
+    /// `in 3h` for future times. More than 30 days away, the local date.
+    pub fn relative(&self, at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+        let delta = now.signed_duration_since(at);
+        let secs = delta.num_seconds().abs();
+        if secs < 60 {
+            return "just now".into();
+        }
+        let amount = match secs {
+            s if s < 3600 => format!("{}m", s / 60),
+            s if s < 86_400 => format!("{}h", s / 3600),
+            s if s / 86_400 <= MAX_RELATIVE_DAYS => format!("{}d", s / 86_400),
+            _ => return self.format_date(at),
+        };
+        if delta.num_seconds() > 0 {
+            format!("{amount} ago")
+        } else {
+            format!("in {amount}")
+        }
+    }
+}
+
+impl std::fmt::Display for DisplayZone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Utc => write!(f, "UTC"),
+            Self::Fixed(offset) => write!(f, "{offset}"),
+            Self::Named(tz) => write!(f, "{}", tz.name()),
+        }
+    }
+}
+
+/// Parse `+HH:MM`, `-HH:MM` or `+HH`.
+fn parse_offset(s: &str) -> Option<FixedOffset> {
+    let sign = if s.starts_with('-') { -1 } else { 1 };
+    let (hours, minutes) = s[1..].split_once(':').unwrap_or((&s[1..], "0"));
+    let hours: i32 = hours.parse().ok()?;
+    let minutes: i32 = minutes.parse().ok()?;
+    if !(0..60).contains(&minutes) {
+        return None;
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn at() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 10, 16, 12, 30, 0).unwrap()
+    }
+
+    #[test]
+    fn parse_accepts_utc_offsets_and_names() {
+        assert_eq!(DisplayZone::parse_str("utc").unwrap(), DisplayZone::Utc);
+        assert_eq!(
+            DisplayZone::parse_str("+02:00").unwrap(),
+            DisplayZone::Fixed(FixedOffset::east_opt(7200).unwrap())
+        );
+        assert_eq!(
+            DisplayZone::parse_str("-05").unwrap(),
+            DisplayZone::Fixed(FixedOffset::west_opt(5 * 3600).unwrap())
+        );
+        assert_eq!(
+            DisplayZone::parse_str("Europe/Berlin").unwrap().to_string(),
+            "Europe/Berlin"
+        );
+        assert!(DisplayZone::parse_str("Mars/Olympus").is_err());
+        assert!(DisplayZone::parse_str("+02:75").is_err());
+        assert!(DisplayZone::parse_str("+25:00").is_err());
+    }
+
+    #[test]
+    fn formats_in_the_zone() {
+        let berlin = DisplayZone::parse_str("Europe/Berlin").unwrap();
+        assert_eq!(berlin.format(at()), "2026-10-16 14:30 CEST");
+        assert_eq!(berlin.to_rfc3339(at()), "2026-10-16T14:30:00+02:00");
+        // After the switch back from daylight saving
+        assert_eq!(
+            berlin.format(at() + Duration::days(30)),
+            "2026-11-15 13:30 CET"
+        );
+
+        let tokyo = DisplayZone::parse_str("+09:00").unwrap();
+        assert_eq!(tokyo.format(at()), "2026-10-16 21:30 +09:00");
+        assert_eq!(tokyo.format_date(at() + Duration::hours(12)), "2026-10-17");
+        assert_eq!(DisplayZone::Utc.format(at()), "2026-10-16 12:30 UTC");
+    }
+
+    #[test]
+    fn relative_times() {
+        let zone = DisplayZone::Utc;
+        let now = at();
+        assert_eq!(zone.relative(now - Duration::seconds(20), now), "just now");
+        assert_eq!(zone.relative(now - Duration::minutes(5), now), "5m ago");
+        assert_eq!(zone.relative(now - Duration::hours(3), now), "3h ago");
+        assert_eq!(zone.relative(now - Duration::days(2), now), "2d ago");
+        assert_eq!(zone.relative(now + Duration::hours(3), now), "in 3h");
+        assert_eq!(zone.relative(now - Duration::days(45), now), "2026-09-01");
+    }
+}
@@ -0,0 +1,113 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum EpicStatus {
+    Open,
+    InProgress,
+    Done,
+    Cancelled,
+}
+
+impl EpicStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EpicStatus::Open => "open",
+            EpicStatus::InProgress => "in_progress",
+            EpicStatus::Done => "done",
+            EpicStatus::Cancelled => "cancelled",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            EpicStatus::Open => "Open",
+            EpicStatus::InProgress => "In Progress",
+            EpicStatus::Done => "Done",
+            EpicStatus::Cancelled => "Cancelled",
+        }
+    }
+
+    pub fn parse_str(s: &str) -> Option<Self> {
+        match s {
+            "open" => Some(EpicStatus::Open),
+            "in_progress" => Some(EpicStatus::InProgress),
+            "done" => Some(EpicStatus::Done),
+            "cancelled" => Some(EpicStatus::Cancelled),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for EpicStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.display_name())
+    }
+}
+
+/// A large initiative grouping top-level tasks of one project. Unlike a
+/// parent task it has no pipeline of its own; tasks join it via `epic_id`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Epic {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    pub description: String,
+    pub status: EpicStatus,
+    pub target_date: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateEpic {
+    pub project_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub target_date: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UpdateEpic {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub status: Option<EpicStatus>,
+    pub target_date: Option<Option<DateTime<Utc>>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epic_status_as_str_roundtrip() {
+        let all = [
+            EpicStatus::Open,
+            EpicStatus::InProgress,
+            EpicStatus::Done,
+            EpicStatus::Cancelled,
+        ];
+        for s in &all {
+            assert_eq!(EpicStatus::parse_str(s.as_str()), Some(*s));
+            assert_eq!(
+                serde_json::to_value(s).unwrap(),
+                serde_json::json!(s.as_str())
+            );
+        }
+        assert_eq!(EpicStatus::parse_str("planned"), None);
+    }
+
+    #[test]
+    fn epic_status_display() {
+        assert_eq!(format!("{}", EpicStatus::InProgress), "In Progress");
+    }
+}
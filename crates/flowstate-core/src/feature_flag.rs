@@ -0,0 +1,77 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Approvals advance the task's board status to the next phase.
+pub const AUTO_PIPELINE: &str = "auto_pipeline";
+/// Runners try to salvage timed-out build runs into a PR.
+pub const SALVAGE: &str = "salvage";
+/// The pod manager may spin up GPU pods when the queue backs up.
+pub const AUTOSCALING: &str = "autoscaling";
+/// Research and distill runs go to light runners unless escalated.
+pub const COST_ROUTING: &str = "cost_routing";
+/// Completed runs on autopilot tasks approve their phase and queue the next.
+pub const AUTOPILOT: &str = "autopilot";
+
+/// Every flag the server understands. All default to enabled, matching the
+/// behavior before flags existed.
+pub const KNOWN_FLAGS: &[&str] = &[AUTO_PIPELINE, SALVAGE, AUTOSCALING, COST_ROUTING, AUTOPILOT];
+
+/// A stored override for one flag, either global (`project_id` is `None`)
+/// or scoped to a single project.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FeatureFlag {
+    pub key: String,
+    pub project_id: Option<String>,
+    pub enabled: bool,
+    pub updated_at: DateTime<Utc>,
+}
+
+pub fn is_known_flag(key: &str) -> bool {
+    KNOWN_FLAGS.contains(&key)
+}
+
+/// Resolve whether `key` is on for `project_id`: a project override wins over
+/// the global setting, which wins over the default (enabled).
+pub fn resolve(flags: &[FeatureFlag], key: &str, project_id: Option<&str>) -> bool {
+    let scoped = project_id.and_then(|pid| {
+        flags
+            .iter()
+            .find(|f| f.key == key && f.project_id.as_deref() == Some(pid))
+    });
+    let global = || {
+        flags
+            .iter()
+            .find(|f| f.key == key && f.project_id.is_none())
+    };
+    scoped.or_else(global).map(|f| f.enabled).unwrap_or(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flag(key: &str, project_id: Option<&str>, enabled: bool) -> FeatureFlag {
+        FeatureFlag {
+            key: key.into(),
+            project_id: project_id.map(String::from),
+            enabled,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn unset_flags_default_to_enabled() {
+        assert!(resolve(&[], SALVAGE, None));
+        assert!(resolve(&[], SALVAGE, Some("p1")));
+    }
+
+    #[test]
+    fn project_override_beats_global() {
+        let flags = vec![flag(SALVAGE, None, false), flag(SALVAGE, Some("p1"), true)];
+        assert!(!resolve(&flags, SALVAGE, None));
+        assert!(resolve(&flags, SALVAGE, Some("p1")));
+        assert!(!resolve(&flags, SALVAGE, Some("p2")));
+        assert!(resolve(&flags, AUTOSCALING, Some("p1")));
+    }
+}
@@ -0,0 +1,145 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::task::{ApprovalStatus, Task};
+
+/// One round of rejection feedback on a task's document. The task itself
+/// only keeps the latest feedback per phase; these entries keep the rest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FeedbackEntry {
+    pub id: String,
+    pub task_id: String,
+    /// research, design, plan or verify
+    pub phase: String,
+    pub feedback: String,
+    /// Caller identity of whoever rejected the document; empty when unknown.
+    pub author: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Normalize a phase name as accepted by `PUT /api/tasks/{id}/feedback`
+/// ("spec" is an alias of "design").
+pub fn normalize_phase(phase: &str) -> Option<&'static str> {
+    match phase {
+        "research" => Some("research"),
+        "design" | "spec" => Some("design"),
+        "plan" => Some("plan"),
+        "verify" => Some("verify"),
+        _ => None,
+    }
+}
+
+/// Feedback rounds started by an update from `before` to `after`: phases
+/// that are rejected afterwards with non-empty feedback, where either the
+/// feedback or the rejection is new. Returns `(phase, feedback)` pairs.
+pub fn new_rejections<'a>(before: &Task, after: &'a Task) -> Vec<(&'static str, &'a str)> {
+    let phases = [
+        (
+            "research",
+            (&before.research_status, &before.research_feedback),
+            (&after.research_status, &after.research_feedback),
+        ),
+        (
+            "design",
+            (&before.spec_status, &before.spec_feedback),
+            (&after.spec_status, &after.spec_feedback),
+        ),
+        (
+            "plan",
+            (&before.plan_status, &before.plan_feedback),
+            (&after.plan_status, &after.plan_feedback),
+        ),
+        (
+            "verify",
+            (&before.verify_status, &before.verify_feedback),
+            (&after.verify_status, &after.verify_feedback),
+        ),
+    ];
+    phases
+        .into_iter()
+        .filter(|(_, old, new)| {
+            *new.0 == ApprovalStatus::Rejected && !new.1.trim().is_empty() && old != new
+        })
+        .map(|(phase, _, new)| (phase, new.1.as_str()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{Priority, Status, TaskType};
+
+    fn task() -> Task {
+        Task {
+            id: "t1".into(),
+            project_id: "p1".into(),
+            sprint_id: None,
+            epic_id: None,
+            assignee_id: None,
+            parent_id: None,
+            title: "T".into(),
+            description: String::new(),
+            reviewer: String::new(),
+            research_status: ApprovalStatus::Pending,
+            spec_status: ApprovalStatus::Pending,
+            plan_status: ApprovalStatus::Pending,
+            verify_status: ApprovalStatus::Pending,
+            spec_approved_hash: String::new(),
+            research_approved_hash: String::new(),
+            research_feedback: String::new(),
+            spec_feedback: String::new(),
+            plan_feedback: String::new(),
+            verify_feedback: String::new(),
+            status: Status::Todo,
+            priority: Priority::Medium,
+            task_type: TaskType::Feature,
+            research_capability: None,
+            design_capability: None,
+            plan_capability: None,
+            build_capability: None,
+            verify_capability: None,
+            due_at: None,
+            sort_order: 0.0,
+            archived: false,
+            autopilot: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn rejections_with_feedback_are_new_rounds() {
+        let before = task();
+        let mut after = before.clone();
+        after.spec_status = ApprovalStatus::Rejected;
+        after.spec_feedback = "cover migrations".into();
+        // feedback without a rejection is just a note
+        after.plan_feedback = "looks thin".into();
+        assert_eq!(
+            new_rejections(&before, &after),
+            vec![("design", "cover migrations")]
+        );
+
+        // rewriting the same feedback is not a new round; new feedback is
+        assert!(new_rejections(&after, &after.clone()).is_empty());
+        let mut again = after.clone();
+        again.spec_feedback = "also cover rollback".into();
+        assert_eq!(
+            new_rejections(&after, &again),
+            vec![("design", "also cover rollback")]
+        );
+
+        // a rejection with no feedback has nothing to keep
+        let mut bare = task();
+        bare.verify_status = ApprovalStatus::Rejected;
+        assert!(new_rejections(&before, &bare).is_empty());
+    }
+
+    #[test]
+    fn phase_aliases() {
+        assert_eq!(normalize_phase("spec"), Some("design"));
+        assert_eq!(normalize_phase("verify"), Some("verify"));
+        assert_eq!(normalize_phase("build"), None);
+    }
+}
@@ -1,19 +1,56 @@
 pub mod api_key;
+pub mod approval_rule;
 pub mod attachment;
+pub mod board;
 pub mod claude_run;
 pub mod commit;
+pub mod custom_field;
+pub mod dashboard;
+pub mod deep_link;
+pub mod display_time;
+pub mod epic;
 pub mod error;
+pub mod feature_flag;
+pub mod feedback;
 pub mod label;
+pub mod notification;
+pub mod organization;
 pub mod project;
+pub mod run_metrics;
+pub mod run_window;
 pub mod runner;
+pub mod saved_filter;
+pub mod scope;
 pub mod sprint;
+pub mod subscription;
 pub mod subtask;
 pub mod task;
 pub mod task_link;
 pub mod task_pr;
+pub mod task_revision;
+pub mod user;
 pub mod verification;
+pub mod webhook;
 
+pub use board::ProjectLane;
+pub use custom_field::{
+    CreateCustomField, CustomField, CustomFieldType, TaskFieldValue, UpdateCustomField,
+};
+pub use dashboard::ProjectDashboard;
+pub use deep_link::TaskLinks;
+pub use display_time::DisplayZone;
+pub use epic::{CreateEpic, Epic, EpicStatus, UpdateEpic};
 pub use error::FlowstateError;
+pub use feedback::FeedbackEntry;
+pub use notification::{Notification, TaskWatcher};
+pub use organization::{CreateOrganization, Organization};
 pub use project::{Project, ProviderType};
+pub use run_window::RunWindow;
+pub use saved_filter::{CreateSavedFilter, FilterQuery, SavedFilter, UpdateSavedFilter};
+pub use scope::{FileChange, FindingKind, ScopeFinding};
 pub use sprint::{CreateSprint, Sprint, SprintStatus, UpdateSprint};
 pub use task::{ApprovalStatus, Priority, Status, Task};
+pub use user::{CreateUser, UpdateUser, User};
+pub use webhook::{
+    CreateWebhook, DeliveryStatus, UpdateWebhook, Webhook, WebhookDelivery, WebhookEvent,
+};
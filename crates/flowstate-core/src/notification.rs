@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::task_revision::FieldChange;
+
+/// A user's subscription to a task's events, independent of whether they
+/// are its assignee or reviewer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TaskWatcher {
+    pub task_id: String,
+    pub user_id: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A task change delivered to one of its watchers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Notification {
+    pub id: String,
+    pub user_id: String,
+    pub task_id: String,
+    /// Caller that made the change, as recorded on the task revision.
+    pub actor: String,
+    pub changes: Vec<FieldChange>,
+    pub read: bool,
+    pub created_at: DateTime<Utc>,
+}
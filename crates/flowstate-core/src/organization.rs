@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::FlowstateError;
+
+/// A team sharing the server with others. An organization owns projects,
+/// API keys and the runners those keys register; an organization's keys
+/// see nothing outside it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Organization {
+    pub id: String,
+    pub name: String,
+    /// Unique; names the organization on the command line.
+    pub slug: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateOrganization {
+    pub name: String,
+    pub slug: String,
+}
+
+impl CreateOrganization {
+    pub fn validate(&self) -> Result<(), FlowstateError> {
+        if self.name.trim().is_empty() {
+            return Err(FlowstateError::InvalidInput(
+                "organization name must not be empty".into(),
+            ));
+        }
+        let valid_slug = !self.slug.is_empty()
+            && self
+                .slug
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+        if !valid_slug {
+            return Err(FlowstateError::InvalidInput(format!(
+                "invalid organization slug {:?}: use lowercase letters, digits and '-'",
+                self.slug
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create(name: &str, slug: &str) -> CreateOrganization {
+        CreateOrganization {
+            name: name.into(),
+            slug: slug.into(),
+        }
+    }
+
+    #[test]
+    fn name_and_slug_are_validated() {
+        assert!(create("Acme", "acme-2").validate().is_ok());
+        assert!(create(" ", "acme").validate().is_err());
+        assert!(create("Acme", "").validate().is_err());
+        assert!(create("Acme", "Acme Corp").validate().is_err());
+    }
+}
@@ -2,12 +2,17 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+use crate::error::FlowstateError;
+use crate::run_window::RunWindow;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum ProviderType {
     #[default]
     Github,
     Gitea,
+    AzureDevops,
 }
 
 impl ProviderType {
@@ -15,6 +20,7 @@ impl ProviderType {
         match self {
             ProviderType::Github => "github",
             ProviderType::Gitea => "gitea",
+            ProviderType::AzureDevops => "azure_devops",
         }
     }
 
@@ -22,6 +28,7 @@ impl ProviderType {
         match s {
             "github" => Some(ProviderType::Github),
             "gitea" => Some(ProviderType::Gitea),
+            "azure_devops" => Some(ProviderType::AzureDevops),
             _ => None,
         }
     }
@@ -34,6 +41,7 @@ impl fmt::Display for ProviderType {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Project {
     pub id: String,
     pub name: String,
@@ -47,11 +55,74 @@ pub struct Project {
     pub provider_type: Option<ProviderType>,
     #[serde(default)]
     pub skip_tls_verify: bool,
+    /// Cap on this project's simultaneously Running runs; `None` is unlimited.
+    /// Queued runs over the cap stay queued until a slot frees up.
+    #[serde(default)]
+    pub max_concurrent_runs: Option<i32>,
+    /// Relative share of the runner fleet. Among queued runs of equal
+    /// priority, the project with the fewest running runs per unit of weight
+    /// is served first.
+    #[serde(default = "default_claim_weight")]
+    pub claim_weight: i32,
+    /// Daily window in which this project's queued runs may be claimed;
+    /// `None` is any time. A run's own window takes its place.
+    #[serde(default)]
+    pub run_window: Option<RunWindow>,
+    /// Builds commit the task's spec, plan and verification report to the
+    /// repository under `docs/flowstate/<task-slug>/`, on the task branch.
+    #[serde(default)]
+    pub docs_in_repo: bool,
+    /// Failed checks in a Verify run each open a follow-up bug task that
+    /// blocks this task until it is resolved.
+    #[serde(default)]
+    pub verify_followups: bool,
+    /// The organization owning the project; `None` for a project only
+    /// server-wide keys can reach.
+    #[serde(default)]
+    pub org_id: Option<String>,
+    /// Spending limit for a calendar month (UTC), summed from the cost the
+    /// runners report in run metrics; `None` is unlimited. Once it is spent,
+    /// no further Build runs are queued.
+    #[serde(default)]
+    pub monthly_budget_usd: Option<f64>,
+    /// Build runs may be queued past the budget until this instant.
+    #[serde(default)]
+    pub budget_override_until: Option<DateTime<Utc>>,
+    /// Private key, as a path on the runner host, for cloning and pushing
+    /// when `repo_url` is an SSH remote. `None` leaves it to the runner's
+    /// own SSH configuration.
+    #[serde(default)]
+    pub ssh_key_path: Option<String>,
+    /// Account or organization holding the runner's fork of `repo_url`.
+    /// When set, branches are pushed to the fork and pull requests are
+    /// opened from it, for repositories the runner cannot push to.
+    #[serde(default)]
+    pub fork_owner: Option<String>,
+    /// Package directory, such as `packages/web`, that runs are confined to
+    /// when the project is one part of a monorepo. `None` is the whole
+    /// repository.
+    #[serde(default)]
+    pub repo_subpath: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+impl Project {
+    /// `repo_subpath` without a leading `./` or surrounding slashes, or
+    /// `None` when it is unset or names the repository root.
+    pub fn subpath(&self) -> Option<&str> {
+        let path = self.repo_subpath.as_deref()?.trim();
+        let path = path.strip_prefix("./").unwrap_or(path).trim_matches('/');
+        (!path.is_empty() && path != ".").then_some(path)
+    }
+}
+
+fn default_claim_weight() -> i32 {
+    1
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct CreateProject {
     pub name: String,
     pub slug: String,
@@ -62,6 +133,7 @@ pub struct CreateProject {
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct UpdateProject {
     pub name: Option<String>,
     pub description: Option<String>,
@@ -69,6 +141,39 @@ pub struct UpdateProject {
     pub repo_token: Option<String>,
     pub provider_type: Option<ProviderType>,
     pub skip_tls_verify: Option<bool>,
+    pub max_concurrent_runs: Option<Option<i32>>,
+    pub claim_weight: Option<i32>,
+    pub run_window: Option<Option<RunWindow>>,
+    pub docs_in_repo: Option<bool>,
+    pub verify_followups: Option<bool>,
+    pub monthly_budget_usd: Option<Option<f64>>,
+    pub budget_override_until: Option<Option<DateTime<Utc>>>,
+    pub ssh_key_path: Option<Option<String>>,
+    pub fork_owner: Option<Option<String>>,
+    pub repo_subpath: Option<Option<String>>,
+}
+
+impl UpdateProject {
+    /// Reject scheduling and budget values the claim queue cannot work
+    /// with: a zero claim weight would divide the fair-share ratio by zero.
+    pub fn validate(&self) -> Result<(), FlowstateError> {
+        if matches!(self.max_concurrent_runs, Some(Some(n)) if n < 1) {
+            return Err(FlowstateError::InvalidInput(
+                "max_concurrent_runs must be at least 1".into(),
+            ));
+        }
+        if matches!(self.claim_weight, Some(n) if n < 1) {
+            return Err(FlowstateError::InvalidInput(
+                "claim_weight must be at least 1".into(),
+            ));
+        }
+        if matches!(self.monthly_budget_usd, Some(Some(b)) if b < 0.0) {
+            return Err(FlowstateError::InvalidInput(
+                "monthly_budget_usd must not be negative".into(),
+            ));
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -84,6 +189,7 @@ mod tests {
     fn provider_type_as_str() {
         assert_eq!(ProviderType::Github.as_str(), "github");
         assert_eq!(ProviderType::Gitea.as_str(), "gitea");
+        assert_eq!(ProviderType::AzureDevops.as_str(), "azure_devops");
     }
 
     #[test]
@@ -93,6 +199,10 @@ mod tests {
             Some(ProviderType::Github)
         );
         assert_eq!(ProviderType::parse_str("gitea"), Some(ProviderType::Gitea));
+        assert_eq!(
+            ProviderType::parse_str("azure_devops"),
+            Some(ProviderType::AzureDevops)
+        );
         assert_eq!(ProviderType::parse_str("unknown"), None);
         assert_eq!(ProviderType::parse_str(""), None);
     }
@@ -116,6 +226,12 @@ mod tests {
         assert_eq!(json, "\"gitea\"");
         let parsed: ProviderType = serde_json::from_str(&json).unwrap();
         assert_eq!(parsed, gitea);
+
+        let azure = ProviderType::AzureDevops;
+        let json = serde_json::to_string(&azure).unwrap();
+        assert_eq!(json, "\"azure_devops\"");
+        let parsed: ProviderType = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, azure);
     }
 
     #[test]
@@ -132,6 +248,34 @@ mod tests {
         assert_eq!(parsed.slug, "test");
     }
 
+    #[test]
+    fn subpath_is_normalized() {
+        let project = |subpath: Option<&str>| -> Project {
+            serde_json::from_value(serde_json::json!({
+                "id": "p",
+                "name": "P",
+                "slug": "p",
+                "description": "",
+                "repo_subpath": subpath,
+                "created_at": "2026-01-01T00:00:00Z",
+                "updated_at": "2026-01-01T00:00:00Z",
+            }))
+            .unwrap()
+        };
+        assert_eq!(project(None).subpath(), None);
+        assert_eq!(project(Some("")).subpath(), None);
+        assert_eq!(project(Some("./")).subpath(), None);
+        assert_eq!(project(Some(".")).subpath(), None);
+        assert_eq!(
+            project(Some("packages/web")).subpath(),
+            Some("packages/web")
+        );
+        assert_eq!(
+            project(Some(" ./packages/web/ ")).subpath(),
+            Some("packages/web")
+        );
+    }
+
     #[test]
     fn update_project_default() {
         let up = UpdateProject::default();
@@ -141,6 +285,8 @@ mod tests {
         assert!(up.repo_token.is_none());
         assert!(up.provider_type.is_none());
         assert!(up.skip_tls_verify.is_none());
+        assert!(up.max_concurrent_runs.is_none());
+        assert!(up.claim_weight.is_none());
     }
 
     #[test]
@@ -159,6 +305,41 @@ mod tests {
     }
 
     #[test]
+    fn update_project_validate_limits() {
+        for bad in [
+            UpdateProject {
+                claim_weight: Some(0),
+                ..Default::default()
+            },
+            UpdateProject {
+                claim_weight: Some(-3),
+                ..Default::default()
+            },
+            UpdateProject {
+                max_concurrent_runs: Some(Some(0)),
+                ..Default::default()
+            },
+            UpdateProject {
+                monthly_budget_usd: Some(Some(-1.0)),
+                ..Default::default()
+            },
+        ] {
+            assert!(matches!(
+                bad.validate(),
+                Err(FlowstateError::InvalidInput(_))
+            ));
+        }
+        let ok = UpdateProject {
+            claim_weight: Some(1),
+            max_concurrent_runs: Some(None),
+            monthly_budget_usd: Some(Some(0.0)),
+            ..Default::default()
+        };
+        assert!(ok.validate().is_ok());
+    }
+
+    #[test]
+    #[allow(clippy::clone_on_copy)]
     fn provider_type_copy_clone() {
         let a = ProviderType::Github;
         let b = a;
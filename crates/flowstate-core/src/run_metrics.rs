@@ -0,0 +1,57 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Resource accounting for one claude_run, reported by the runner when the
+/// run finishes (whatever the outcome).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RunMetrics {
+    pub run_id: String,
+    /// Wall-clock time from dispatch to completion, in milliseconds.
+    pub duration_ms: i64,
+    /// Total bytes of agent stdout across every backend invocation.
+    pub stdout_bytes: i64,
+    /// Token counts and cost are `None` when the backend does not report them.
+    pub input_tokens: Option<i64>,
+    pub output_tokens: Option<i64>,
+    pub cost_usd: Option<f64>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RecordRunMetrics {
+    pub duration_ms: i64,
+    pub stdout_bytes: i64,
+    #[serde(default)]
+    pub input_tokens: Option<i64>,
+    #[serde(default)]
+    pub output_tokens: Option<i64>,
+    #[serde(default)]
+    pub cost_usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::IntoParams))]
+pub struct RunMetricsFilter {
+    pub project_id: Option<String>,
+    /// Only runs whose metrics were recorded at or after this instant.
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// Aggregated metrics for every recorded run of one action.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RunMetricsSummary {
+    pub action: String,
+    pub runs: i64,
+    pub total_duration_ms: i64,
+    pub avg_duration_ms: i64,
+    pub total_stdout_bytes: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cost_usd: f64,
+    /// Runs whose backend reported no cost, so `cost_usd` leaves them out.
+    #[serde(default)]
+    pub unpriced_runs: i64,
+}
@@ -0,0 +1,308 @@
+use std::fmt;
+
+use chrono::{DateTime, Duration, Offset, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::error::FlowstateError;
+
+const MINUTES_PER_DAY: i32 = 24 * 60;
+
+/// Largest UTC offset accepted, in minutes (UTC+14:00).
+const MAX_UTC_OFFSET: i32 = 14 * 60;
+
+/// A daily time-of-day window during which queued runs may be claimed,
+/// e.g. heavy builds only between 20:00 and 06:00 local time. Outside it,
+/// runs stay queued until it opens.
+///
+/// Written as `HH:MM-HH:MM` followed by an optional UTC offset or IANA time
+/// zone, such as `20:00-06:00+02:00` or `20:00-06:00 Europe/Berlin`; a window
+/// with neither is in UTC. A start after the end wraps past midnight. A fixed
+/// offset ignores daylight saving time; a zone follows it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(try_from = "String", into = "String")]
+pub struct RunWindow {
+    /// Minutes after local midnight the window opens.
+    pub start: i32,
+    /// Minutes after local midnight the window closes.
+    pub end: i32,
+    /// Minutes east of UTC. Unused when `time_zone` is set.
+    pub utc_offset: i32,
+    /// Zone whose offset at the time of the check is used instead of
+    /// `utc_offset`.
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<String>))]
+    pub time_zone: Option<Tz>,
+}
+
+impl RunWindow {
+    pub fn new(start: i32, end: i32, utc_offset: i32) -> Result<Self, FlowstateError> {
+        let in_day = |m: i32| (0..MINUTES_PER_DAY).contains(&m);
+        if !in_day(start) || !in_day(end) {
+            return Err(FlowstateError::InvalidInput(
+                "run window times must be between 00:00 and 23:59".into(),
+            ));
+        }
+        if start == end {
+            return Err(FlowstateError::InvalidInput(
+                "run window must not start and end at the same time".into(),
+            ));
+        }
+        if utc_offset.abs() > MAX_UTC_OFFSET {
+            return Err(FlowstateError::InvalidInput(
+                "run window UTC offset must be between -14:00 and +14:00".into(),
+            ));
+        }
+        Ok(Self {
+            start,
+            end,
+            utc_offset,
+            time_zone: None,
+        })
+    }
+
+    /// The same window in local time of `tz`.
+    pub fn with_time_zone(self, tz: Tz) -> Self {
+        Self {
+            utc_offset: 0,
+            time_zone: Some(tz),
+            ..self
+        }
+    }
+
+    /// IANA name of the window's time zone, if it has one.
+    pub fn time_zone_name(&self) -> Option<&'static str> {
+        self.time_zone.map(|tz| tz.name())
+    }
+
+    pub fn parse_str(s: &str) -> Result<Self, FlowstateError> {
+        let invalid = || {
+            FlowstateError::InvalidInput(format!(
+                "invalid run window: {s} (expected HH:MM-HH:MM, optionally followed by an offset such as +02:00 or a time zone such as Europe/Berlin)"
+            ))
+        };
+        let s = s.trim();
+        let (start, rest) = s.split_once('-').ok_or_else(invalid)?;
+        let end = rest.get(..5).ok_or_else(invalid)?;
+        let offset = rest.get(5..).ok_or_else(invalid)?.trim();
+        let start = parse_hh_mm(start).ok_or_else(invalid)?;
+        let end = parse_hh_mm(end).ok_or_else(invalid)?;
+        let sign = match offset.as_bytes().first() {
+            None => return Self::new(start, end, 0),
+            Some(b'+') => 1,
+            Some(b'-') => -1,
+            Some(_) if offset == "Z" => return Self::new(start, end, 0),
+            Some(_) => {
+                let tz = offset.parse::<Tz>().map_err(|_| invalid())?;
+                return Ok(Self::new(start, end, 0)?.with_time_zone(tz));
+            }
+        };
+        Self::new(
+            start,
+            end,
+            sign * parse_hh_mm(&offset[1..]).ok_or_else(invalid)?,
+        )
+    }
+
+    /// Minutes east of UTC in effect at `now`.
+    pub fn offset_at(&self, now: DateTime<Utc>) -> i32 {
+        match self.time_zone {
+            Some(tz) => zone_offset(tz, now),
+            None => self.utc_offset,
+        }
+    }
+
+    /// Minutes after local midnight at `now`.
+    fn local_minute(&self, now: DateTime<Utc>) -> i32 {
+        let utc_minute = (now.hour() * 60 + now.minute()) as i32;
+        (utc_minute + self.offset_at(now)).rem_euclid(MINUTES_PER_DAY)
+    }
+
+    /// Whether runs may be claimed at `now`.
+    pub fn contains(&self, now: DateTime<Utc>) -> bool {
+        let local = self.local_minute(now);
+        if self.start < self.end {
+            local >= self.start && local < self.end
+        } else {
+            local >= self.start || local < self.end
+        }
+    }
+
+    /// When the window is next open: `now` if it already is.
+    pub fn next_open(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        if self.contains(now) {
+            return now;
+        }
+        let wait = (self.start - self.local_minute(now)).rem_euclid(MINUTES_PER_DAY);
+        let minute_start = now
+            .with_second(0)
+            .and_then(|t| t.with_nanosecond(0))
+            .unwrap_or(now);
+        let opens = minute_start + Duration::minutes(wait as i64);
+        // A daylight saving change before the window opens moves it by the
+        // difference in offsets.
+        let shift = self.offset_at(opens) - self.offset_at(now);
+        let shifted = opens - Duration::minutes(shift as i64);
+        if shifted > now {
+            shifted
+        } else {
+            opens
+        }
+    }
+}
+
+/// Minutes east of UTC in `tz` at `at`.
+pub fn zone_offset(tz: Tz, at: DateTime<Utc>) -> i32 {
+    tz.offset_from_utc_datetime(&at.naive_utc())
+        .fix()
+        .local_minus_utc()
+        / 60
+}
+
+fn parse_hh_mm(s: &str) -> Option<i32> {
+    let (h, m) = s.split_once(':')?;
+    if h.len() != 2 || m.len() != 2 {
+        return None;
+    }
+    let (h, m): (i32, i32) = (h.parse().ok()?, m.parse().ok()?);
+    (h < 24 && m < 60).then_some(h * 60 + m)
+}
+
+impl fmt::Display for RunWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02}:{:02}-{:02}:{:02}",
+            self.start / 60,
+            self.start % 60,
+            self.end / 60,
+            self.end % 60,
+        )?;
+        if let Some(tz) = self.time_zone {
+            return write!(f, " {tz}");
+        }
+        let sign = if self.utc_offset < 0 { '-' } else { '+' };
+        let offset = self.utc_offset.abs();
+        write!(f, "{sign}{:02}:{:02}", offset / 60, offset % 60)
+    }
+}
+
+impl TryFrom<String> for RunWindow {
+    type Error = FlowstateError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::parse_str(&s)
+    }
+}
+
+impl From<RunWindow> for String {
+    fn from(w: RunWindow) -> Self {
+        w.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(h: u32, m: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 1, h, m, 30).unwrap()
+    }
+
+    #[test]
+    fn parse_and_display_roundtrip() {
+        let w = RunWindow::parse_str("20:00-06:00+02:00").unwrap();
+        assert_eq!((w.start, w.end, w.utc_offset), (1200, 360, 120));
+        assert_eq!(w.to_string(), "20:00-06:00+02:00");
+
+        let utc = RunWindow::parse_str("09:30-17:00").unwrap();
+        assert_eq!(utc.utc_offset, 0);
+        assert_eq!(utc.to_string(), "09:30-17:00+00:00");
+
+        let west = RunWindow::parse_str("01:00-05:00-05:30").unwrap();
+        assert_eq!(west.utc_offset, -330);
+        assert_eq!(west.to_string(), "01:00-05:00-05:30");
+    }
+
+    #[test]
+    fn parse_rejects_bad_windows() {
+        for s in [
+            "",
+            "20:00",
+            "20:00-",
+            "24:00-06:00",
+            "20:00-06:60",
+            "8:00-17:00",
+            "08:00-08:00",
+            "08:00-17:00+15:00",
+            "08:00-17:00 Mars/Olympus",
+        ] {
+            assert!(RunWindow::parse_str(s).is_err(), "{s} should not parse");
+        }
+    }
+
+    #[test]
+    fn contains_handles_wrap_and_offset() {
+        let day = RunWindow::parse_str("09:00-17:00").unwrap();
+        assert!(day.contains(at(9, 0)));
+        assert!(day.contains(at(16, 59)));
+        assert!(!day.contains(at(17, 0)));
+        assert!(!day.contains(at(8, 59)));
+
+        // 20:00-06:00 at UTC+2 is 18:00-04:00 UTC
+        let night = RunWindow::parse_str("20:00-06:00+02:00").unwrap();
+        assert!(night.contains(at(18, 0)));
+        assert!(night.contains(at(23, 0)));
+        assert!(night.contains(at(3, 59)));
+        assert!(!night.contains(at(4, 0)));
+        assert!(!night.contains(at(12, 0)));
+    }
+
+    #[test]
+    fn next_open_waits_for_the_start() {
+        let night = RunWindow::parse_str("20:00-06:00+02:00").unwrap();
+        assert_eq!(night.next_open(at(23, 0)), at(23, 0));
+        let opens = night.next_open(at(12, 15));
+        assert_eq!(opens, Utc.with_ymd_and_hms(2026, 3, 1, 18, 0, 0).unwrap());
+
+        let morning = RunWindow::parse_str("06:00-08:00").unwrap();
+        let opens = morning.next_open(at(9, 0));
+        assert_eq!(opens, Utc.with_ymd_and_hms(2026, 3, 2, 6, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn time_zone_follows_daylight_saving() {
+        let w = RunWindow::parse_str("20:00-06:00 Europe/Berlin").unwrap();
+        assert_eq!(w.time_zone, Some(chrono_tz::Europe::Berlin));
+        assert_eq!(w.to_string(), "20:00-06:00 Europe/Berlin");
+        assert_eq!(RunWindow::parse_str(&w.to_string()).unwrap(), w);
+
+        // Berlin is UTC+1 in winter and UTC+2 in summer (from 29 March 2026)
+        let winter = Utc.with_ymd_and_hms(2026, 3, 28, 19, 0, 0).unwrap();
+        let summer = Utc.with_ymd_and_hms(2026, 3, 29, 19, 0, 0).unwrap();
+        assert_eq!(w.offset_at(winter), 60);
+        assert_eq!(w.offset_at(summer), 120);
+        assert!(w.contains(winter));
+        assert!(w.contains(summer));
+        assert!(!w.contains(Utc.with_ymd_and_hms(2026, 3, 28, 18, 30, 0).unwrap()));
+        assert!(w.contains(Utc.with_ymd_and_hms(2026, 3, 29, 18, 30, 0).unwrap()));
+        assert!(w.contains(Utc.with_ymd_and_hms(2026, 3, 29, 3, 30, 0).unwrap()));
+        assert!(!w.contains(Utc.with_ymd_and_hms(2026, 3, 30, 4, 30, 0).unwrap()));
+
+        // The wait spans the change back to UTC+1 on 25 October 2026
+        let morning = RunWindow::parse_str("06:00-08:00 Europe/Berlin").unwrap();
+        let opens = morning.next_open(Utc.with_ymd_and_hms(2026, 10, 24, 12, 0, 0).unwrap());
+        assert_eq!(opens, Utc.with_ymd_and_hms(2026, 10, 25, 5, 0, 0).unwrap());
+        assert!(morning.contains(opens));
+    }
+
+    #[test]
+    fn serde_uses_the_string_form() {
+        let w = RunWindow::parse_str("22:00-05:00-07:00").unwrap();
+        let json = serde_json::to_string(&w).unwrap();
+        assert_eq!(json, "\"22:00-05:00-07:00\"");
+        let parsed: RunWindow = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, w);
+        assert!(serde_json::from_str::<RunWindow>("\"nonsense\"").is_err());
+    }
+}
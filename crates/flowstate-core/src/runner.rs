@@ -1,5 +1,6 @@
 use std::fmt;
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::claude_run::ClaudeAction;
@@ -11,6 +12,7 @@ use crate::claude_run::ClaudeAction;
 /// capabilities [Light, Standard, Heavy]. A Light runner advertises
 /// only [Light].
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum RunnerCapability {
     /// Fast, cheap models suited for research and distill phases.
@@ -65,6 +67,7 @@ impl RunnerCapability {
             ClaudeAction::Plan => RunnerCapability::Standard,
             ClaudeAction::PlanDistill => RunnerCapability::Light,
             ClaudeAction::Build => RunnerCapability::Heavy,
+            ClaudeAction::Revise => RunnerCapability::Heavy,
             ClaudeAction::Verify => RunnerCapability::Standard,
             ClaudeAction::VerifyDistill => RunnerCapability::Light,
         }
@@ -77,6 +80,138 @@ impl fmt::Display for RunnerCapability {
     }
 }
 
+/// How fast a runner measured itself, slowest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum PerformanceClass {
+    Slow,
+    Standard,
+    Fast,
+}
+
+impl PerformanceClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PerformanceClass::Slow => "slow",
+            PerformanceClass::Standard => "standard",
+            PerformanceClass::Fast => "fast",
+        }
+    }
+}
+
+impl fmt::Display for PerformanceClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What a runner's optional startup benchmark measured. A measurement that
+/// could not be taken is `None`.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RunnerBenchmark {
+    /// Seconds to build the runner's small sample project from clean.
+    #[serde(default)]
+    pub compile_secs: Option<f64>,
+    /// Output tokens per second from the agent backend, including its
+    /// startup time.
+    #[serde(default)]
+    pub tokens_per_sec: Option<f64>,
+}
+
+impl RunnerBenchmark {
+    /// The class the measurements earn: the lower of the two, so a fast
+    /// model on a slow machine is not promoted. No measurements is Standard.
+    pub fn performance_class(&self) -> PerformanceClass {
+        let compile = self.compile_secs.map(|secs| match secs {
+            s if s <= 2.0 => PerformanceClass::Fast,
+            s if s <= 6.0 => PerformanceClass::Standard,
+            _ => PerformanceClass::Slow,
+        });
+        let tokens = self.tokens_per_sec.map(|rate| match rate {
+            r if r >= 50.0 => PerformanceClass::Fast,
+            r if r >= 15.0 => PerformanceClass::Standard,
+            _ => PerformanceClass::Slow,
+        });
+        match (compile, tokens) {
+            (Some(a), Some(b)) => a.min(b),
+            (Some(c), None) | (None, Some(c)) => c,
+            (None, None) => PerformanceClass::Standard,
+        }
+    }
+}
+
+/// Runner lifecycle status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum RunnerStatus {
+    Active,
+    Draining,
+    Drained,
+}
+
+impl RunnerStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RunnerStatus::Active => "active",
+            RunnerStatus::Draining => "draining",
+            RunnerStatus::Drained => "drained",
+        }
+    }
+
+    pub fn parse_str(s: &str) -> Option<Self> {
+        match s {
+            "active" => Some(RunnerStatus::Active),
+            "draining" => Some(RunnerStatus::Draining),
+            "drained" => Some(RunnerStatus::Drained),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RunnerStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Configuration changes the server hands a runner with its next
+/// registration heartbeat.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PendingRunnerConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub poll_interval: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drain: Option<bool>,
+}
+
+/// A runner as last registered, kept so drain commands and history survive
+/// a server restart.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct RunnerRecord {
+    pub runner_id: String,
+    /// Organization of the key the runner registered with; `None` for a
+    /// server-wide runner.
+    pub org_id: Option<String>,
+    pub backend_name: Option<String>,
+    pub capability: Option<String>,
+    pub poll_interval: Option<u64>,
+    pub max_concurrent: Option<usize>,
+    pub max_builds: Option<usize>,
+    pub active_count: Option<usize>,
+    pub active_builds: Option<usize>,
+    pub status: RunnerStatus,
+    /// Waiting for the runner's next heartbeat.
+    pub pending_config: Option<PendingRunnerConfig>,
+    pub benchmark: Option<RunnerBenchmark>,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -124,6 +259,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn benchmark_class_is_the_slower_measurement() {
+        let bench = |compile_secs, tokens_per_sec| RunnerBenchmark {
+            compile_secs,
+            tokens_per_sec,
+        };
+        assert_eq!(
+            bench(None, None).performance_class(),
+            PerformanceClass::Standard
+        );
+        assert_eq!(
+            bench(Some(1.0), Some(80.0)).performance_class(),
+            PerformanceClass::Fast
+        );
+        assert_eq!(
+            bench(Some(1.0), Some(20.0)).performance_class(),
+            PerformanceClass::Standard
+        );
+        assert_eq!(
+            bench(Some(30.0), Some(80.0)).performance_class(),
+            PerformanceClass::Slow
+        );
+        assert_eq!(
+            bench(None, Some(5.0)).performance_class(),
+            PerformanceClass::Slow
+        );
+        assert!(PerformanceClass::Fast > PerformanceClass::Standard);
+    }
+
     #[test]
     fn handled_tiers_heavy() {
         assert_eq!(
@@ -190,5 +354,21 @@ mod tests {
             RunnerCapability::default_for_action(ClaudeAction::VerifyDistill),
             RunnerCapability::Light
         );
+        assert_eq!(
+            RunnerCapability::default_for_action(ClaudeAction::Revise),
+            RunnerCapability::Heavy
+        );
+    }
+
+    #[test]
+    fn runner_status_as_str_roundtrip() {
+        for s in [
+            RunnerStatus::Active,
+            RunnerStatus::Draining,
+            RunnerStatus::Drained,
+        ] {
+            assert_eq!(RunnerStatus::parse_str(s.as_str()), Some(s));
+        }
+        assert_eq!(RunnerStatus::parse_str("paused"), None);
     }
 }
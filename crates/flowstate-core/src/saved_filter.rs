@@ -0,0 +1,153 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::FlowstateError;
+use crate::task::{Priority, Status, TaskFilter, TaskType};
+
+/// The task query a saved filter stands for. Every condition is optional
+/// and they combine with AND.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FilterQuery {
+    #[serde(default)]
+    pub status: Option<Status>,
+    #[serde(default)]
+    pub priority: Option<Priority>,
+    #[serde(default)]
+    pub task_type: Option<TaskType>,
+    #[serde(default)]
+    pub assignee_id: Option<String>,
+    /// Only tasks without an assignee; excludes `assignee_id`.
+    #[serde(default)]
+    pub unassigned: bool,
+    /// Only tasks this user (by id) is watching.
+    #[serde(default)]
+    pub watched_by: Option<String>,
+    #[serde(default)]
+    pub sprint_id: Option<String>,
+    /// Label ids; a task must carry all of them.
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Case-insensitive match against title and description.
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+impl FilterQuery {
+    /// The `TaskFilter` running this query in `project_id`.
+    pub fn to_task_filter(&self, project_id: &str) -> TaskFilter {
+        TaskFilter {
+            project_id: Some(project_id.to_string()),
+            status: self.status,
+            priority: self.priority,
+            task_type: self.task_type,
+            sprint_id: self.sprint_id.clone(),
+            assignee_id: self.assignee_id.clone(),
+            unassigned: self.unassigned,
+            watched_by: self.watched_by.clone(),
+            label_ids: self.labels.clone(),
+            text: self.text.clone().filter(|t| !t.trim().is_empty()),
+            ..Default::default()
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), FlowstateError> {
+        if self.unassigned && self.assignee_id.is_some() {
+            return Err(FlowstateError::InvalidInput(
+                "a filter cannot be both unassigned and for an assignee".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A named task query, shared by a project or private to one user, so
+/// common views like "urgent unassigned" are one pick away.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SavedFilter {
+    pub id: String,
+    pub project_id: String,
+    /// Owner; `None` for a filter shared with the whole project.
+    pub user_id: Option<String>,
+    pub name: String,
+    pub query: FilterQuery,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateSavedFilter {
+    pub project_id: String,
+    #[serde(default)]
+    pub user_id: Option<String>,
+    pub name: String,
+    #[serde(default)]
+    pub query: FilterQuery,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UpdateSavedFilter {
+    pub name: Option<String>,
+    pub query: Option<FilterQuery>,
+}
+
+impl CreateSavedFilter {
+    pub fn validate(&self) -> Result<(), FlowstateError> {
+        validate_name(&self.name)?;
+        self.query.validate()
+    }
+}
+
+impl UpdateSavedFilter {
+    pub fn validate(&self) -> Result<(), FlowstateError> {
+        if let Some(ref name) = self.name {
+            validate_name(name)?;
+        }
+        if let Some(ref query) = self.query {
+            query.validate()?;
+        }
+        Ok(())
+    }
+}
+
+fn validate_name(name: &str) -> Result<(), FlowstateError> {
+    if name.trim().is_empty() {
+        return Err(FlowstateError::InvalidInput(
+            "filter name must not be empty".into(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_maps_to_task_filter() {
+        let query: FilterQuery = serde_json::from_str(
+            r#"{"priority": "urgent", "task_type": "bug", "unassigned": true, "labels": ["bug"], "text": "  "}"#,
+        )
+        .unwrap();
+        assert!(query.validate().is_ok());
+        let filter = query.to_task_filter("p1");
+        assert_eq!(filter.project_id.as_deref(), Some("p1"));
+        assert_eq!(filter.priority, Some(Priority::Urgent));
+        assert_eq!(filter.task_type, Some(TaskType::Bug));
+        assert!(filter.unassigned);
+        assert_eq!(filter.label_ids, vec!["bug".to_string()]);
+        // blank text matches everything, so it is dropped
+        assert_eq!(filter.text, None);
+        assert_eq!(filter.status, None);
+
+        let conflicting = FilterQuery {
+            assignee_id: Some("u1".into()),
+            unassigned: true,
+            ..Default::default()
+        };
+        assert!(conflicting.validate().is_err());
+    }
+}
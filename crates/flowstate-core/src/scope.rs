@@ -0,0 +1,330 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How a file changed between the base branch and a build's branch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+}
+
+/// One file in a build's diff. Renames carry the new path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FileChange {
+    pub kind: ChangeKind,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum FindingKind {
+    /// A file the plan did not list.
+    OutOfScope,
+    /// A dependency manifest or lockfile the plan did not list.
+    NewDependency,
+    /// A CI configuration file the plan did not list.
+    CiChange,
+    /// A test file was deleted, whether the plan listed it or not.
+    DeletedTest,
+    /// A file outside the package directory of a monorepo project.
+    OutsideSubpath,
+}
+
+impl FindingKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FindingKind::OutOfScope => "out_of_scope",
+            FindingKind::NewDependency => "new_dependency",
+            FindingKind::CiChange => "ci_change",
+            FindingKind::DeletedTest => "deleted_test",
+            FindingKind::OutsideSubpath => "outside_subpath",
+        }
+    }
+}
+
+/// A change in a build's diff that a reviewer must acknowledge before the
+/// task can move to Done.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ScopeFinding {
+    /// `<kind>:<path>`, so the same finding from a rebuild keeps its
+    /// acknowledgment.
+    pub id: String,
+    pub kind: FindingKind,
+    pub path: String,
+    pub detail: String,
+    #[serde(default)]
+    pub acknowledged_by: Option<String>,
+    #[serde(default)]
+    pub acknowledged_at: Option<DateTime<Utc>>,
+}
+
+impl ScopeFinding {
+    pub fn new(kind: FindingKind, path: &str, detail: String) -> Self {
+        Self {
+            id: format!("{}:{path}", kind.as_str()),
+            kind,
+            path: path.to_string(),
+            detail,
+            acknowledged_by: None,
+            acknowledged_at: None,
+        }
+    }
+
+    pub fn is_acknowledged(&self) -> bool {
+        self.acknowledged_at.is_some()
+    }
+}
+
+/// Compare a diff against the files a plan declared. An empty `scope`
+/// means the plan declared none, so only dependency, CI and deleted-test
+/// changes are flagged.
+///
+/// Scope entries match a file exactly or as a directory prefix; a trailing
+/// `/`, `/*` or `/**` is allowed.
+pub fn check_scope(scope: &[String], changes: &[FileChange]) -> Vec<ScopeFinding> {
+    let scope: Vec<&str> = scope.iter().map(|s| normalize(s)).collect();
+    let in_scope = |path: &str| covers(&scope, path);
+
+    let mut findings = Vec::new();
+    for change in changes {
+        let path = change.path.as_str();
+        if change.kind == ChangeKind::Deleted && is_test_file(path) {
+            findings.push(ScopeFinding::new(
+                FindingKind::DeletedTest,
+                path,
+                "test file deleted".into(),
+            ));
+            continue;
+        }
+        if in_scope(path) {
+            continue;
+        }
+        if is_dependency_file(path) {
+            findings.push(ScopeFinding::new(
+                FindingKind::NewDependency,
+                path,
+                "dependency manifest changed but not in the plan".into(),
+            ));
+        } else if is_ci_file(path) {
+            findings.push(ScopeFinding::new(
+                FindingKind::CiChange,
+                path,
+                "CI configuration changed but not in the plan".into(),
+            ));
+        } else if !scope.is_empty() {
+            findings.push(ScopeFinding::new(
+                FindingKind::OutOfScope,
+                path,
+                format!("{} but not in the plan", describe(change.kind)),
+            ));
+        }
+    }
+    findings
+}
+
+/// Flag changes outside `subpath`, the package directory a monorepo
+/// project's runs are confined to. Changes under an `exempt` entry, such as
+/// the task's own documents, are allowed anywhere.
+pub fn check_subpath(
+    subpath: &str,
+    exempt: &[String],
+    changes: &[FileChange],
+) -> Vec<ScopeFinding> {
+    let subpath = [normalize(subpath)];
+    let exempt: Vec<&str> = exempt.iter().map(|s| normalize(s)).collect();
+    changes
+        .iter()
+        .filter(|c| !covers(&subpath, &c.path) && !covers(&exempt, &c.path))
+        .map(|c| {
+            ScopeFinding::new(
+                FindingKind::OutsideSubpath,
+                &c.path,
+                format!("{} outside {}", describe(c.kind), subpath[0]),
+            )
+        })
+        .collect()
+}
+
+/// Whether `path` is one of `entries` or lies in a directory among them.
+fn covers(entries: &[&str], path: &str) -> bool {
+    entries.iter().any(|entry| {
+        !entry.is_empty()
+            && (path == *entry
+                || path
+                    .strip_prefix(entry)
+                    .is_some_and(|rest| rest.starts_with('/')))
+    })
+}
+
+fn normalize(entry: &str) -> &str {
+    let entry = entry.trim();
+    let entry = entry.strip_prefix("./").unwrap_or(entry);
+    let entry = entry
+        .strip_suffix("/**")
+        .or_else(|| entry.strip_suffix("/*"))
+        .unwrap_or(entry);
+    entry.strip_suffix('/').unwrap_or(entry)
+}
+
+fn describe(kind: ChangeKind) -> &'static str {
+    match kind {
+        ChangeKind::Added => "added",
+        ChangeKind::Modified => "modified",
+        ChangeKind::Deleted => "deleted",
+        ChangeKind::Renamed => "renamed",
+    }
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+fn is_dependency_file(path: &str) -> bool {
+    let name = file_name(path);
+    matches!(
+        name,
+        "Cargo.toml"
+            | "Cargo.lock"
+            | "package.json"
+            | "package-lock.json"
+            | "yarn.lock"
+            | "pnpm-lock.yaml"
+            | "go.mod"
+            | "go.sum"
+            | "pyproject.toml"
+            | "poetry.lock"
+            | "Pipfile"
+            | "Pipfile.lock"
+            | "Gemfile"
+            | "Gemfile.lock"
+            | "pom.xml"
+            | "build.gradle"
+            | "build.gradle.kts"
+            | "mix.exs"
+            | "mix.lock"
+            | "composer.json"
+            | "composer.lock"
+    ) || (name.starts_with("requirements") && name.ends_with(".txt"))
+}
+
+fn is_ci_file(path: &str) -> bool {
+    [".github/workflows/", ".circleci/", ".buildkite/"]
+        .iter()
+        .any(|dir| path.starts_with(dir))
+        || matches!(
+            path,
+            ".gitlab-ci.yml" | ".travis.yml" | "azure-pipelines.yml" | "Jenkinsfile"
+        )
+}
+
+fn is_test_file(path: &str) -> bool {
+    let name = file_name(path);
+    let stem = name.split('.').next().unwrap_or(name);
+    path.starts_with("tests/")
+        || path.contains("/tests/")
+        || path.starts_with("test/")
+        || path.contains("/test/")
+        || path.contains("__tests__/")
+        || stem.ends_with("_test")
+        || stem.ends_with("_spec")
+        || stem.starts_with("test_")
+        || name.contains(".test.")
+        || name.contains(".spec.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(kind: ChangeKind, path: &str) -> FileChange {
+        FileChange {
+            kind,
+            path: path.into(),
+        }
+    }
+
+    #[test]
+    fn flags_changes_outside_the_plan() {
+        let scope = vec![
+            "crates/core/src/lib.rs".to_string(),
+            "./crates/server/".to_string(),
+            "docs/**".to_string(),
+        ];
+        let changes = [
+            change(ChangeKind::Modified, "crates/core/src/lib.rs"),
+            change(ChangeKind::Added, "crates/server/src/routes/new.rs"),
+            change(ChangeKind::Modified, "docs/server.md"),
+            change(ChangeKind::Modified, "crates/core/src/lib.rs.bak"),
+            change(ChangeKind::Modified, "crates/serverless/main.rs"),
+            change(ChangeKind::Modified, "Cargo.lock"),
+            change(ChangeKind::Added, ".github/workflows/ci.yml"),
+            change(ChangeKind::Deleted, "crates/server/tests/api.rs"),
+        ];
+        let findings = check_scope(&scope, &changes);
+        let ids: Vec<&str> = findings.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "out_of_scope:crates/core/src/lib.rs.bak",
+                "out_of_scope:crates/serverless/main.rs",
+                "new_dependency:Cargo.lock",
+                "ci_change:.github/workflows/ci.yml",
+                "deleted_test:crates/server/tests/api.rs",
+            ]
+        );
+        assert!(findings.iter().all(|f| !f.is_acknowledged()));
+    }
+
+    #[test]
+    fn flags_changes_outside_the_subpath() {
+        let changes = [
+            change(ChangeKind::Modified, "packages/web/src/app.ts"),
+            change(ChangeKind::Added, "packages/web-admin/index.ts"),
+            change(ChangeKind::Modified, "package-lock.json"),
+            change(ChangeKind::Added, "docs/flowstate/login/spec.md"),
+        ];
+        let exempt = vec!["docs/flowstate/login".to_string()];
+        let findings = check_subpath("./packages/web/", &exempt, &changes);
+        let ids: Vec<&str> = findings.iter().map(|f| f.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            [
+                "outside_subpath:packages/web-admin/index.ts",
+                "outside_subpath:package-lock.json",
+            ]
+        );
+        assert_eq!(findings[0].detail, "added outside packages/web");
+    }
+
+    #[test]
+    fn declared_dependencies_are_in_scope() {
+        let scope = vec!["crates/core/Cargo.toml".to_string()];
+        let changes = [change(ChangeKind::Modified, "crates/core/Cargo.toml")];
+        assert!(check_scope(&scope, &changes).is_empty());
+    }
+
+    #[test]
+    fn empty_scope_only_flags_risky_changes() {
+        let changes = [
+            change(ChangeKind::Modified, "src/main.rs"),
+            change(ChangeKind::Modified, "requirements-dev.txt"),
+            change(ChangeKind::Deleted, "src/parser_test.go"),
+            change(ChangeKind::Deleted, "src/old.rs"),
+        ];
+        let kinds: Vec<FindingKind> = check_scope(&[], &changes)
+            .into_iter()
+            .map(|f| f.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            [FindingKind::NewDependency, FindingKind::DeletedTest]
+        );
+    }
+}
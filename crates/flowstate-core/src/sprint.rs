@@ -3,7 +3,10 @@ use std::fmt;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::task::Task;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum SprintStatus {
     Planned,
@@ -45,6 +48,7 @@ impl fmt::Display for SprintStatus {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Sprint {
     pub id: String,
     pub project_id: String,
@@ -53,11 +57,15 @@ pub struct Sprint {
     pub starts_at: Option<DateTime<Utc>>,
     pub ends_at: Option<DateTime<Utc>>,
     pub status: SprintStatus,
+    /// Archiving a sprint also archives its tasks.
+    #[serde(default)]
+    pub archived: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct CreateSprint {
     pub project_id: String,
     pub name: String,
@@ -70,6 +78,7 @@ pub struct CreateSprint {
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct UpdateSprint {
     pub name: Option<String>,
     pub goal: Option<String>,
@@ -78,6 +87,30 @@ pub struct UpdateSprint {
     pub ends_at: Option<Option<DateTime<Utc>>>,
 }
 
+/// Request body for `POST /api/sprints/{id}/complete`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CompleteSprint {
+    /// Sprint that takes over the unfinished tasks; without one they go
+    /// back to the backlog.
+    #[serde(default)]
+    pub next_sprint_id: Option<String>,
+}
+
+/// What completing a sprint did with its tasks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct SprintRollover {
+    /// The sprint, now completed.
+    pub sprint: Sprint,
+    pub next_sprint_id: Option<String>,
+    /// Tasks the sprint finished.
+    pub done: usize,
+    pub cancelled: usize,
+    /// The unfinished tasks, as moved.
+    pub rolled_over: Vec<Task>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,227 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::FlowstateError;
+
+/// Something that happened which people can be notified about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyEvent {
+    /// A task's research, spec, plan or verification is waiting for review.
+    ApprovalPending,
+    /// A run failed or timed out.
+    RunFailed,
+    /// A pull request was opened for a task.
+    PrOpened,
+    /// A project's spend this month reached most or all of its budget.
+    BudgetWarning,
+}
+
+impl NotifyEvent {
+    pub const ALL: &'static [NotifyEvent] = &[
+        NotifyEvent::ApprovalPending,
+        NotifyEvent::RunFailed,
+        NotifyEvent::PrOpened,
+        NotifyEvent::BudgetWarning,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotifyEvent::ApprovalPending => "approval_pending",
+            NotifyEvent::RunFailed => "run_failed",
+            NotifyEvent::PrOpened => "pr_opened",
+            NotifyEvent::BudgetWarning => "budget_warning",
+        }
+    }
+
+    pub fn parse_str(s: &str) -> Option<Self> {
+        match s {
+            "approval_pending" => Some(NotifyEvent::ApprovalPending),
+            "run_failed" => Some(NotifyEvent::RunFailed),
+            "pr_opened" => Some(NotifyEvent::PrOpened),
+            "budget_warning" => Some(NotifyEvent::BudgetWarning),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for NotifyEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Where a subscription's notifications are sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum NotifyChannel {
+    /// An email to `target`, or to the subscribed user's address.
+    Email,
+    /// A message posted to the Slack incoming webhook at `target`.
+    Slack,
+    /// A JSON body POSTed to the URL at `target`.
+    Webhook,
+}
+
+impl NotifyChannel {
+    pub const ALL: &'static [NotifyChannel] = &[
+        NotifyChannel::Email,
+        NotifyChannel::Slack,
+        NotifyChannel::Webhook,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotifyChannel::Email => "email",
+            NotifyChannel::Slack => "slack",
+            NotifyChannel::Webhook => "webhook",
+        }
+    }
+
+    pub fn parse_str(s: &str) -> Option<Self> {
+        match s {
+            "email" => Some(NotifyChannel::Email),
+            "slack" => Some(NotifyChannel::Slack),
+            "webhook" => Some(NotifyChannel::Webhook),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for NotifyChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A rule sending some events to one channel target.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Subscription {
+    pub id: String,
+    pub channel: NotifyChannel,
+    /// Email address or URL; empty for an email to the subscribed user.
+    pub target: String,
+    /// Events sent; empty means all of them.
+    pub events: Vec<NotifyEvent>,
+    /// Only events from this project; `None` for every project.
+    pub project_id: Option<String>,
+    /// Only events on tasks this user is assigned, reviewing or watching;
+    /// `None` for every task.
+    pub user_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Subscription {
+    /// Whether `event` in `project_id` should be sent, before the user
+    /// filter is applied.
+    pub fn wants(&self, event: NotifyEvent, project_id: &str) -> bool {
+        (self.events.is_empty() || self.events.contains(&event))
+            && self.project_id.as_deref().is_none_or(|p| p == project_id)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateSubscription {
+    pub channel: NotifyChannel,
+    #[serde(default)]
+    pub target: String,
+    #[serde(default)]
+    pub events: Vec<NotifyEvent>,
+    #[serde(default)]
+    pub project_id: Option<String>,
+    #[serde(default)]
+    pub user_id: Option<String>,
+}
+
+impl CreateSubscription {
+    pub fn validate(&self) -> Result<(), FlowstateError> {
+        let target = self.target.trim();
+        match self.channel {
+            NotifyChannel::Email if target.is_empty() && self.user_id.is_none() => Err(
+                FlowstateError::InvalidInput("email target is required without a user_id".into()),
+            ),
+            NotifyChannel::Email if !target.is_empty() && !target.contains('@') => Err(
+                FlowstateError::InvalidInput(format!("invalid email address: {target}")),
+            ),
+            NotifyChannel::Slack | NotifyChannel::Webhook
+                if !(target.starts_with("https://") || target.starts_with("http://")) =>
+            {
+                Err(FlowstateError::InvalidInput(format!(
+                    "{} target must be an http:// or https:// url",
+                    self.channel
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create(channel: NotifyChannel, target: &str, user_id: Option<&str>) -> CreateSubscription {
+        CreateSubscription {
+            channel,
+            target: target.into(),
+            events: vec![],
+            project_id: None,
+            user_id: user_id.map(String::from),
+        }
+    }
+
+    #[test]
+    fn targets_are_validated_per_channel() {
+        assert!(create(NotifyChannel::Email, "ops@example.com", None)
+            .validate()
+            .is_ok());
+        assert!(create(NotifyChannel::Email, "", Some("u1"))
+            .validate()
+            .is_ok());
+        assert!(create(NotifyChannel::Email, "", None).validate().is_err());
+        assert!(create(NotifyChannel::Email, "ops", None)
+            .validate()
+            .is_err());
+        assert!(create(
+            NotifyChannel::Slack,
+            "https://hooks.slack.com/services/x",
+            None
+        )
+        .validate()
+        .is_ok());
+        assert!(create(NotifyChannel::Webhook, "ftp://example.com", None)
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn wants_filters_events_and_project() {
+        let mut sub = Subscription {
+            id: "s1".into(),
+            channel: NotifyChannel::Slack,
+            target: "https://hooks.slack.com/services/x".into(),
+            events: vec![],
+            project_id: None,
+            user_id: None,
+            created_at: Utc::now(),
+        };
+        assert!(sub.wants(NotifyEvent::PrOpened, "p1"));
+        sub.events = vec![NotifyEvent::RunFailed];
+        sub.project_id = Some("p1".into());
+        assert!(sub.wants(NotifyEvent::RunFailed, "p1"));
+        assert!(!sub.wants(NotifyEvent::PrOpened, "p1"));
+        assert!(!sub.wants(NotifyEvent::RunFailed, "p2"));
+        for event in NotifyEvent::ALL {
+            assert_eq!(NotifyEvent::parse_str(event.as_str()), Some(*event));
+        }
+        for channel in NotifyChannel::ALL {
+            assert_eq!(NotifyChannel::parse_str(channel.as_str()), Some(*channel));
+        }
+    }
+}
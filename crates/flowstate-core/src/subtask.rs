@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 /// A subtask definition parsed from a plan's structured output.
 /// Used by the runner to create child tasks after plan completion.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct SubtaskDefinition {
     /// Short title for the subtask
     pub title: String,
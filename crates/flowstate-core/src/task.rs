@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fmt;
 
 use chrono::{DateTime, Utc};
@@ -7,6 +8,7 @@ use crate::claude_run::ClaudeAction;
 use crate::runner::RunnerCapability;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum Status {
     Todo,
@@ -111,6 +113,7 @@ impl fmt::Display for Status {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum Priority {
     Urgent,
@@ -121,6 +124,14 @@ pub enum Priority {
 }
 
 impl Priority {
+    pub const ALL: &[Priority] = &[
+        Priority::Urgent,
+        Priority::High,
+        Priority::Medium,
+        Priority::Low,
+        Priority::None,
+    ];
+
     pub fn as_str(&self) -> &'static str {
         match self {
             Priority::Urgent => "urgent",
@@ -151,6 +162,17 @@ impl Priority {
         }
     }
 
+    /// Default scheduling weight for runs of a task with this priority.
+    pub fn run_weight(&self) -> i32 {
+        match self {
+            Priority::Urgent => 40,
+            Priority::High => 30,
+            Priority::Medium => 20,
+            Priority::Low => 10,
+            Priority::None => 0,
+        }
+    }
+
     pub fn parse_str(s: &str) -> Option<Self> {
         match s {
             "urgent" => Some(Priority::Urgent),
@@ -169,7 +191,71 @@ impl fmt::Display for Priority {
     }
 }
 
+/// What kind of work a task is. Selects the instructions given to agents
+/// and, for spikes, which phases run at all.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum TaskType {
+    #[default]
+    Feature,
+    Bug,
+    Chore,
+    /// Time-boxed investigation: research, design and plan only, never built.
+    Spike,
+}
+
+impl TaskType {
+    pub const ALL: &[TaskType] = &[
+        TaskType::Feature,
+        TaskType::Bug,
+        TaskType::Chore,
+        TaskType::Spike,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskType::Feature => "feature",
+            TaskType::Bug => "bug",
+            TaskType::Chore => "chore",
+            TaskType::Spike => "spike",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            TaskType::Feature => "Feature",
+            TaskType::Bug => "Bug",
+            TaskType::Chore => "Chore",
+            TaskType::Spike => "Spike",
+        }
+    }
+
+    /// Whether tasks of this type go through Build and Verify. A spike's
+    /// deliverable is its research, spec and plan.
+    pub fn is_buildable(&self) -> bool {
+        !matches!(self, TaskType::Spike)
+    }
+
+    pub fn parse_str(s: &str) -> Option<Self> {
+        match s {
+            "feature" => Some(TaskType::Feature),
+            "bug" => Some(TaskType::Bug),
+            "chore" => Some(TaskType::Chore),
+            "spike" => Some(TaskType::Spike),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TaskType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.display_name())
+    }
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 #[serde(rename_all = "snake_case")]
 pub enum ApprovalStatus {
     #[default]
@@ -216,10 +302,15 @@ impl fmt::Display for ApprovalStatus {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Task {
     pub id: String,
     pub project_id: String,
     pub sprint_id: Option<String>,
+    #[serde(default)]
+    pub epic_id: Option<String>,
+    #[serde(default)]
+    pub assignee_id: Option<String>,
     pub parent_id: Option<String>,
     pub title: String,
     pub description: String,
@@ -236,17 +327,29 @@ pub struct Task {
     pub verify_feedback: String,
     pub status: Status,
     pub priority: Priority,
+    #[serde(default)]
+    pub task_type: TaskType,
+    #[serde(default)]
+    pub due_at: Option<DateTime<Utc>>,
     pub research_capability: Option<RunnerCapability>,
     pub design_capability: Option<RunnerCapability>,
     pub plan_capability: Option<RunnerCapability>,
     pub build_capability: Option<RunnerCapability>,
     pub verify_capability: Option<RunnerCapability>,
     pub sort_order: f64,
+    /// Hidden from the board and from task lists unless asked for.
+    #[serde(default)]
+    pub archived: bool,
+    /// Each completed phase run is approved and the next phase queued
+    /// without waiting for a reviewer.
+    #[serde(default)]
+    pub autopilot: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct CreateTask {
     pub project_id: String,
     pub title: String,
@@ -255,10 +358,14 @@ pub struct CreateTask {
     pub status: Status,
     pub priority: Priority,
     #[serde(default)]
+    pub task_type: TaskType,
+    #[serde(default)]
     pub parent_id: Option<String>,
     #[serde(default)]
     pub reviewer: String,
     #[serde(default)]
+    pub due_at: Option<DateTime<Utc>>,
+    #[serde(default)]
     pub research_capability: Option<RunnerCapability>,
     #[serde(default)]
     pub design_capability: Option<RunnerCapability>,
@@ -271,12 +378,17 @@ pub struct CreateTask {
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct UpdateTask {
     pub title: Option<String>,
     pub description: Option<String>,
     pub status: Option<Status>,
     pub priority: Option<Priority>,
+    pub task_type: Option<TaskType>,
     pub sprint_id: Option<Option<String>>,
+    pub epic_id: Option<Option<String>>,
+    pub assignee_id: Option<Option<String>>,
+    pub due_at: Option<Option<DateTime<Utc>>>,
     pub sort_order: Option<f64>,
     pub parent_id: Option<Option<String>>,
     pub reviewer: Option<String>,
@@ -295,15 +407,87 @@ pub struct UpdateTask {
     pub plan_capability: Option<Option<RunnerCapability>>,
     pub build_capability: Option<Option<RunnerCapability>>,
     pub verify_capability: Option<Option<RunnerCapability>>,
+    pub autopilot: Option<bool>,
+    /// Custom field values by field id; `null` clears a value. Validated and
+    /// normalized server-side against the task's project's field definitions.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom_fields: BTreeMap<String, Option<String>>,
+    /// Who is making the change, recorded in the task's revision history.
+    /// Filled in server-side from the authenticated caller; never accepted
+    /// from a request body.
+    #[serde(skip)]
+    pub actor: Option<String>,
+}
+
+/// Request body for `PATCH /api/tasks/bulk`: one update applied to many tasks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct BulkUpdateTasks {
+    pub ids: Vec<String>,
+    pub update: UpdateTask,
+}
+
+/// Request body for `POST /api/tasks/{id}/reorder`. `after_id: None` moves
+/// the task to the top of its column.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct ReorderTask {
+    #[serde(default)]
+    pub after_id: Option<String>,
+}
+
+/// Request body for `PUT /api/tasks/{id}/feedback`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TaskFeedback {
+    /// research, design, plan or verify
+    pub phase: String,
+    pub feedback: String,
+    /// Also reject the phase and queue its distill run with this feedback.
+    #[serde(default)]
+    pub distill: bool,
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct TaskFilter {
     pub project_id: Option<String>,
+    /// Only tasks in one of these projects (by id).
+    pub project_ids: Vec<String>,
     pub status: Option<Status>,
+    /// Only tasks in one of these statuses.
+    pub statuses: Vec<Status>,
     pub priority: Option<Priority>,
+    pub task_type: Option<TaskType>,
     pub sprint_id: Option<String>,
+    pub epic_id: Option<String>,
+    pub assignee_id: Option<String>,
+    /// Only tasks assigned to one of these users (by id).
+    pub assignee_ids: Vec<String>,
+    /// Only tasks without an assignee.
+    pub unassigned: bool,
+    /// Only tasks this user (by id) is watching.
+    pub watched_by: Option<String>,
+    /// Only tasks carrying every one of these labels (by id).
+    pub label_ids: Vec<String>,
+    /// Case-insensitive substring of the title or description.
+    pub text: Option<String>,
+    /// Only tasks whose custom field (by id) has exactly this stored value.
+    pub custom_field: Option<(String, String)>,
+    /// Only tasks due at or after this instant.
+    pub due_after: Option<DateTime<Utc>>,
+    /// Only tasks due strictly before this instant.
+    pub due_before: Option<DateTime<Utc>>,
+    /// Only open tasks (not done or cancelled) whose due date has passed.
+    pub overdue: bool,
+    /// Only tasks created at or after this instant.
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only tasks created strictly before this instant.
+    pub created_before: Option<DateTime<Utc>>,
+    /// Only tasks last updated at or after this instant.
+    pub updated_after: Option<DateTime<Utc>>,
     pub parent_id: Option<Option<String>>,
+    /// Only archived tasks. Archived tasks are left out otherwise.
+    pub archived: bool,
     pub limit: Option<i64>,
 }
 
@@ -331,12 +515,18 @@ impl Task {
         None
     }
 
+    /// True if the task has a due date before `now` and is still open.
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        self.due_at.is_some_and(|due| due < now)
+            && !matches!(self.status, Status::Done | Status::Cancelled)
+    }
+
     pub fn capability_for_action(&self, action: ClaudeAction) -> Option<RunnerCapability> {
         match action {
             ClaudeAction::Research | ClaudeAction::ResearchDistill => self.research_capability,
             ClaudeAction::Design | ClaudeAction::DesignDistill => self.design_capability,
             ClaudeAction::Plan | ClaudeAction::PlanDistill => self.plan_capability,
-            ClaudeAction::Build => self.build_capability,
+            ClaudeAction::Build | ClaudeAction::Revise => self.build_capability,
             ClaudeAction::Verify | ClaudeAction::VerifyDistill => self.verify_capability,
         }
     }
@@ -374,6 +564,16 @@ pub fn status_after_approval(field: &str) -> Option<Status> {
     }
 }
 
+/// Board status a task advances to when its plan is approved. Spikes are
+/// never built, so an approved plan finishes them.
+pub fn status_after_plan_approval(task_type: TaskType) -> Option<Status> {
+    if task_type.is_buildable() {
+        status_after_approval("plan")
+    } else {
+        Some(Status::Done)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -385,6 +585,14 @@ mod tests {
         assert_eq!(status_after_approval("plan"), Some(Status::Build));
         assert_eq!(status_after_approval("verify"), Some(Status::Done));
         assert_eq!(status_after_approval("unknown"), None);
+        assert_eq!(
+            status_after_plan_approval(TaskType::Bug),
+            Some(Status::Build)
+        );
+        assert_eq!(
+            status_after_plan_approval(TaskType::Spike),
+            Some(Status::Done)
+        );
     }
 
     #[test]
@@ -436,6 +644,17 @@ mod tests {
         assert_eq!(Priority::parse_str(""), None);
     }
 
+    #[test]
+    fn parse_str_roundtrip_task_type() {
+        for t in TaskType::ALL {
+            assert_eq!(TaskType::parse_str(t.as_str()), Some(*t));
+        }
+        assert_eq!(TaskType::parse_str("epic"), None);
+        assert_eq!(TaskType::default(), TaskType::Feature);
+        assert!(!TaskType::Spike.is_buildable());
+        assert!(TaskType::Bug.is_buildable());
+    }
+
     #[test]
     fn parse_str_roundtrip_approval_status() {
         assert_eq!(
@@ -592,6 +811,8 @@ mod tests {
             id: "t1".into(),
             project_id: "p1".into(),
             sprint_id: None,
+            epic_id: None,
+            assignee_id: None,
             parent_id,
             title: "Test".into(),
             description: String::new(),
@@ -608,12 +829,16 @@ mod tests {
             verify_feedback: String::new(),
             status: Status::Todo,
             priority: Priority::None,
+            task_type: TaskType::Feature,
             research_capability: None,
             design_capability: None,
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
             sort_order: 0.0,
+            archived: false,
+            autopilot: false,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -621,6 +846,54 @@ mod tests {
         assert!(make_task(Some("parent".into())).is_subtask());
     }
 
+    #[test]
+    fn task_is_overdue() {
+        let now = Utc::now();
+        let task = |status: Status, due_at: Option<DateTime<Utc>>| Task {
+            id: "t1".into(),
+            project_id: "p1".into(),
+            sprint_id: None,
+            epic_id: None,
+            assignee_id: None,
+            parent_id: None,
+            title: "Test".into(),
+            description: String::new(),
+            reviewer: String::new(),
+            research_status: ApprovalStatus::None,
+            spec_status: ApprovalStatus::None,
+            plan_status: ApprovalStatus::None,
+            verify_status: ApprovalStatus::None,
+            spec_approved_hash: String::new(),
+            research_approved_hash: String::new(),
+            research_feedback: String::new(),
+            spec_feedback: String::new(),
+            plan_feedback: String::new(),
+            verify_feedback: String::new(),
+            status,
+            priority: Priority::None,
+            task_type: TaskType::Feature,
+            research_capability: None,
+            design_capability: None,
+            plan_capability: None,
+            build_capability: None,
+            verify_capability: None,
+            due_at,
+            sort_order: 0.0,
+            archived: false,
+            autopilot: false,
+            created_at: now,
+            updated_at: now,
+        };
+        let past = Some(now - chrono::Duration::hours(1));
+        let future = Some(now + chrono::Duration::hours(1));
+
+        assert!(task(Status::Build, past).is_overdue(now));
+        assert!(!task(Status::Build, future).is_overdue(now));
+        assert!(!task(Status::Build, None).is_overdue(now));
+        assert!(!task(Status::Done, past).is_overdue(now));
+        assert!(!task(Status::Cancelled, past).is_overdue(now));
+    }
+
     #[test]
     fn test_update_task_default() {
         let u = UpdateTask::default();
@@ -645,6 +918,8 @@ mod tests {
             id: "t1".into(),
             project_id: "p1".into(),
             sprint_id: None,
+            epic_id: None,
+            assignee_id: None,
             parent_id: None,
             title: "Test".into(),
             description: String::new(),
@@ -661,12 +936,16 @@ mod tests {
             verify_feedback: String::new(),
             status: Status::Todo,
             priority: Priority::None,
+            task_type: TaskType::Feature,
             research_capability: Some(RunnerCapability::Light),
             design_capability: Some(RunnerCapability::Standard),
             plan_capability: Some(RunnerCapability::Heavy),
             build_capability: Some(RunnerCapability::Light),
             verify_capability: None,
+            due_at: None,
             sort_order: 0.0,
+            archived: false,
+            autopilot: false,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
@@ -704,6 +983,11 @@ mod tests {
             Some(RunnerCapability::Heavy)
         );
         assert_eq!(t.capability_for_action(ClaudeAction::VerifyDistill), None);
+        // Revisions are builds
+        assert_eq!(
+            t.capability_for_action(ClaudeAction::Revise),
+            Some(RunnerCapability::Light)
+        );
     }
 
     fn make_attention_task(
@@ -716,6 +1000,8 @@ mod tests {
             id: "t1".into(),
             project_id: "p1".into(),
             sprint_id: None,
+            epic_id: None,
+            assignee_id: None,
             parent_id: None,
             title: "Test".into(),
             description: String::new(),
@@ -732,12 +1018,16 @@ mod tests {
             verify_feedback: String::new(),
             status: Status::Todo,
             priority: Priority::None,
+            task_type: TaskType::Feature,
             research_capability: None,
             design_capability: None,
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
             sort_order: 0.0,
+            archived: false,
+            autopilot: false,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
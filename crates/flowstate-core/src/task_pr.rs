@@ -2,6 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct TaskPr {
     pub id: String,
     pub task_id: String,
@@ -9,14 +10,230 @@ pub struct TaskPr {
     pub pr_url: String,
     pub pr_number: i64,
     pub branch_name: String,
+    /// Kept current by the GitHub webhook receiver and by merges and closes
+    /// made through flowstate; `open` otherwise.
+    #[serde(default)]
+    pub state: PrState,
+    /// Combined result of the PR's CI checks when last looked at; none
+    /// until checks have been seen.
+    #[serde(default)]
+    pub checks_status: Option<ChecksStatus>,
+    /// Opened as a draft and not yet marked ready for review.
+    #[serde(default)]
+    pub draft: bool,
     pub created_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum PrState {
+    #[default]
+    Open,
+    Merged,
+    /// Closed without merging.
+    Closed,
+}
+
+impl PrState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PrState::Open => "open",
+            PrState::Merged => "merged",
+            PrState::Closed => "closed",
+        }
+    }
+
+    pub fn parse_str(s: &str) -> Option<Self> {
+        match s {
+            "open" => Some(PrState::Open),
+            "merged" => Some(PrState::Merged),
+            "closed" => Some(PrState::Closed),
+            _ => None,
+        }
+    }
+}
+
+/// Where a CI check, or all of a PR's checks together, stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum ChecksStatus {
+    /// Queued or still running.
+    Pending,
+    Success,
+    /// Failed, errored, timed out or was cancelled.
+    Failure,
+}
+
+impl ChecksStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChecksStatus::Pending => "pending",
+            ChecksStatus::Success => "success",
+            ChecksStatus::Failure => "failure",
+        }
+    }
+
+    pub fn parse_str(s: &str) -> Option<Self> {
+        match s {
+            "pending" => Some(ChecksStatus::Pending),
+            "success" => Some(ChecksStatus::Success),
+            "failure" => Some(ChecksStatus::Failure),
+            _ => None,
+        }
+    }
+
+    /// A GitHub check run's status from its `status` and `conclusion`.
+    /// Neutral and skipped runs count as passed.
+    pub fn from_check_run(status: &str, conclusion: Option<&str>) -> Self {
+        match (status, conclusion) {
+            ("completed", Some("success" | "neutral" | "skipped")) => ChecksStatus::Success,
+            ("completed", _) => ChecksStatus::Failure,
+            _ => ChecksStatus::Pending,
+        }
+    }
+
+    /// A commit status's `state`, as GitHub and Gitea report it. Gitea's
+    /// `warning` counts as passed.
+    pub fn from_commit_state(state: &str) -> Self {
+        match state {
+            "success" | "warning" => ChecksStatus::Success,
+            "pending" => ChecksStatus::Pending,
+            _ => ChecksStatus::Failure,
+        }
+    }
+
+    /// The status of a set of checks: failed if any failed, else pending if
+    /// any is pending. None when there are no checks.
+    pub fn combine(checks: &[PrCheck]) -> Option<Self> {
+        let statuses = || checks.iter().map(|c| c.status);
+        if checks.is_empty() {
+            None
+        } else if statuses().any(|s| s == ChecksStatus::Failure) {
+            Some(ChecksStatus::Failure)
+        } else if statuses().any(|s| s == ChecksStatus::Pending) {
+            Some(ChecksStatus::Pending)
+        } else {
+            Some(ChecksStatus::Success)
+        }
+    }
+}
+
+/// One CI check reported on a pull request's head commit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PrCheck {
+    pub name: String,
+    pub status: ChecksStatus,
+    /// Where the check's details can be read, when the host gives one.
+    pub url: Option<String>,
+}
+
+/// How a pull request's commits land on its base branch.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    /// A merge commit joining the branch.
+    #[default]
+    Merge,
+    /// The branch's changes as a single new commit.
+    Squash,
+    /// The branch's commits replayed onto the base.
+    Rebase,
+}
+
+impl MergeStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MergeStrategy::Merge => "merge",
+            MergeStrategy::Squash => "squash",
+            MergeStrategy::Rebase => "rebase",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct CreateTaskPr {
     pub task_id: String,
     pub claude_run_id: Option<String>,
     pub pr_url: String,
     pub pr_number: i64,
     pub branch_name: String,
+    #[serde(default)]
+    pub draft: bool,
+}
+
+/// Title prefix that marks a Gitea pull request as a work in progress,
+/// Gitea's stand-in for a draft.
+pub const WIP_PREFIX: &str = "WIP: ";
+
+/// `title` without its work-in-progress prefix (`WIP:` or `[WIP]`, in any
+/// case), or `None` when it has none.
+pub fn strip_wip_prefix(title: &str) -> Option<&str> {
+    ["wip:", "[wip]"].iter().find_map(|prefix| {
+        title
+            .get(..prefix.len())
+            .filter(|head| head.eq_ignore_ascii_case(prefix))
+            .map(|_| title[prefix.len()..].trim_start())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(status: ChecksStatus) -> PrCheck {
+        PrCheck {
+            name: "ci".into(),
+            status,
+            url: None,
+        }
+    }
+
+    #[test]
+    fn combined_checks_status() {
+        use ChecksStatus::*;
+        assert_eq!(ChecksStatus::combine(&[]), None);
+        assert_eq!(
+            ChecksStatus::combine(&[check(Success), check(Success)]),
+            Some(Success)
+        );
+        assert_eq!(
+            ChecksStatus::combine(&[check(Success), check(Pending)]),
+            Some(Pending)
+        );
+        assert_eq!(
+            ChecksStatus::combine(&[check(Pending), check(Failure)]),
+            Some(Failure)
+        );
+        assert_eq!(ChecksStatus::from_check_run("queued", None), Pending);
+        assert_eq!(
+            ChecksStatus::from_check_run("completed", Some("skipped")),
+            Success
+        );
+        assert_eq!(
+            ChecksStatus::from_check_run("completed", Some("timed_out")),
+            Failure
+        );
+        assert_eq!(ChecksStatus::from_commit_state("pending"), Pending);
+        assert_eq!(ChecksStatus::from_commit_state("error"), Failure);
+        for s in [Pending, Success, Failure] {
+            assert_eq!(ChecksStatus::parse_str(s.as_str()), Some(s));
+        }
+    }
+
+    #[test]
+    fn wip_prefixes() {
+        assert_eq!(strip_wip_prefix("WIP: Add export"), Some("Add export"));
+        assert_eq!(strip_wip_prefix("[wip] Add export"), Some("Add export"));
+        assert_eq!(
+            strip_wip_prefix(&format!("{WIP_PREFIX}Add export")),
+            Some("Add export")
+        );
+        assert_eq!(strip_wip_prefix("Add export"), None);
+        assert_eq!(strip_wip_prefix("Wi"), None);
+    }
 }
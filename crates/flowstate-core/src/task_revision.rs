@@ -0,0 +1,129 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::task::Task;
+
+/// Fields that change on every write and carry no history value.
+const IGNORED_FIELDS: &[&str] = &["updated_at", "created_at"];
+
+/// One field of a task that changed in a single update.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+/// A recorded `UpdateTask`: who changed which fields, and when.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TaskRevision {
+    pub id: String,
+    pub task_id: String,
+    /// Caller identity as resolved by the server (e.g. `key:ci`, `runner:gpu-1`).
+    /// Empty when the change was made without an authenticated caller.
+    pub actor: String,
+    pub changes: Vec<FieldChange>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Compare two versions of a task and return every field whose serialized
+/// value differs, in field-name order.
+pub fn diff_tasks(before: &Task, after: &Task) -> Vec<FieldChange> {
+    let (Ok(Value::Object(old)), Ok(Value::Object(new))) =
+        (serde_json::to_value(before), serde_json::to_value(after))
+    else {
+        return Vec::new();
+    };
+
+    let mut changes: Vec<FieldChange> = new
+        .into_iter()
+        .filter(|(field, _)| !IGNORED_FIELDS.contains(&field.as_str()))
+        .filter_map(|(field, new_value)| {
+            let old_value = old.get(&field).cloned().unwrap_or(Value::Null);
+            (old_value != new_value).then_some(FieldChange {
+                field,
+                old: old_value,
+                new: new_value,
+            })
+        })
+        .collect();
+    changes.sort_by(|a, b| a.field.cmp(&b.field));
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::{ApprovalStatus, Priority, Status, TaskType};
+
+    fn task() -> Task {
+        Task {
+            id: "t1".into(),
+            project_id: "p1".into(),
+            sprint_id: None,
+            epic_id: None,
+            assignee_id: None,
+            parent_id: None,
+            title: "Title".into(),
+            description: String::new(),
+            reviewer: String::new(),
+            research_status: ApprovalStatus::None,
+            spec_status: ApprovalStatus::None,
+            plan_status: ApprovalStatus::None,
+            verify_status: ApprovalStatus::None,
+            spec_approved_hash: String::new(),
+            research_approved_hash: String::new(),
+            research_feedback: String::new(),
+            spec_feedback: String::new(),
+            plan_feedback: String::new(),
+            verify_feedback: String::new(),
+            status: Status::Todo,
+            priority: Priority::Medium,
+            task_type: TaskType::Feature,
+            research_capability: None,
+            design_capability: None,
+            plan_capability: None,
+            build_capability: None,
+            verify_capability: None,
+            due_at: None,
+            sort_order: 1.0,
+            archived: false,
+            autopilot: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn diff_identical_tasks_is_empty() {
+        let t = task();
+        assert!(diff_tasks(&t, &t).is_empty());
+    }
+
+    #[test]
+    fn diff_ignores_timestamps() {
+        let before = task();
+        let mut after = before.clone();
+        after.updated_at = before.updated_at + chrono::Duration::seconds(5);
+        assert!(diff_tasks(&before, &after).is_empty());
+    }
+
+    #[test]
+    fn diff_reports_changed_fields_sorted() {
+        let before = task();
+        let mut after = before.clone();
+        after.status = Status::Build;
+        after.priority = Priority::High;
+        after.sprint_id = Some("s1".into());
+
+        let changes = diff_tasks(&before, &after);
+        let fields: Vec<&str> = changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, vec!["priority", "sprint_id", "status"]);
+        assert_eq!(changes[2].old, Value::from("todo"));
+        assert_eq!(changes[2].new, Value::from("build"));
+        assert_eq!(changes[1].old, Value::Null);
+    }
+}
@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::FlowstateError;
+
+/// Someone tasks can be assigned to. Users are plain records for
+/// assignment; they are not tied to API keys or authentication.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct User {
+    pub id: String,
+    pub name: String,
+    /// Optional; unique among users that have one.
+    pub email: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateUser {
+    pub name: String,
+    #[serde(default)]
+    pub email: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UpdateUser {
+    pub name: Option<String>,
+    pub email: Option<String>,
+}
+
+impl CreateUser {
+    pub fn validate(&self) -> Result<(), FlowstateError> {
+        validate_name(&self.name)?;
+        validate_email(&self.email)
+    }
+}
+
+impl UpdateUser {
+    pub fn validate(&self) -> Result<(), FlowstateError> {
+        if let Some(ref name) = self.name {
+            validate_name(name)?;
+        }
+        if let Some(ref email) = self.email {
+            validate_email(email)?;
+        }
+        Ok(())
+    }
+}
+
+fn validate_name(name: &str) -> Result<(), FlowstateError> {
+    if name.trim().is_empty() {
+        return Err(FlowstateError::InvalidInput(
+            "user name must not be empty".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_email(email: &str) -> Result<(), FlowstateError> {
+    if email.is_empty() {
+        return Ok(());
+    }
+    match email.split_once('@') {
+        Some((local, domain))
+            if !local.is_empty() && !domain.is_empty() && !email.contains(char::is_whitespace) =>
+        {
+            Ok(())
+        }
+        _ => Err(FlowstateError::InvalidInput(format!(
+            "invalid email address {email:?}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn user_validation() {
+        let create = |name: &str, email: &str| CreateUser {
+            name: name.into(),
+            email: email.into(),
+        };
+        assert!(create("Alice", "").validate().is_ok());
+        assert!(create("Alice", "alice@example.com").validate().is_ok());
+        assert!(create("  ", "").validate().is_err());
+        assert!(create("Alice", "alice").validate().is_err());
+        assert!(create("Alice", "@example.com").validate().is_err());
+        assert!(create("Alice", "al ice@example.com").validate().is_err());
+
+        assert!(UpdateUser::default().validate().is_ok());
+        let clear_email = UpdateUser {
+            email: Some(String::new()),
+            ..Default::default()
+        };
+        assert!(clear_email.validate().is_ok());
+        let blank_name = UpdateUser {
+            name: Some(String::new()),
+            ..Default::default()
+        };
+        assert!(blank_name.validate().is_err());
+    }
+}
@@ -0,0 +1,242 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::FlowstateError;
+
+/// Something that happened which webhooks can be told about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// A task moved to another board status.
+    TaskStatusChanged,
+    /// A task's research, spec, plan or verification was approved.
+    TaskApproved,
+    /// A run ended: completed, failed, timed out or cancelled.
+    RunFinished,
+}
+
+impl WebhookEvent {
+    pub const ALL: &'static [WebhookEvent] = &[
+        WebhookEvent::TaskStatusChanged,
+        WebhookEvent::TaskApproved,
+        WebhookEvent::RunFinished,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::TaskStatusChanged => "task_status_changed",
+            WebhookEvent::TaskApproved => "task_approved",
+            WebhookEvent::RunFinished => "run_finished",
+        }
+    }
+
+    pub fn parse_str(s: &str) -> Option<Self> {
+        match s {
+            "task_status_changed" => Some(WebhookEvent::TaskStatusChanged),
+            "task_approved" => Some(WebhookEvent::TaskApproved),
+            "run_finished" => Some(WebhookEvent::RunFinished),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for WebhookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An outbound HTTP endpoint that is POSTed a signed JSON body for each
+/// event it subscribes to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Webhook {
+    pub id: String,
+    pub url: String,
+    /// HMAC signing secret, encrypted with the server key. Never sent to
+    /// clients.
+    #[serde(skip_serializing, default)]
+    pub secret: String,
+    /// Events delivered to this hook; empty means all of them.
+    pub events: Vec<WebhookEvent>,
+    /// Only events from this project; `None` for every project.
+    pub project_id: Option<String>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Webhook {
+    /// Whether `event` in `project_id` should be delivered to this hook.
+    pub fn wants(&self, event: WebhookEvent, project_id: &str) -> bool {
+        self.enabled
+            && (self.events.is_empty() || self.events.contains(&event))
+            && self.project_id.as_deref().is_none_or(|p| p == project_id)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct CreateWebhook {
+    pub url: String,
+    /// Already encrypted.
+    pub secret: String,
+    #[serde(default)]
+    pub events: Vec<WebhookEvent>,
+    #[serde(default)]
+    pub project_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct UpdateWebhook {
+    pub url: Option<String>,
+    /// Already encrypted.
+    pub secret: Option<String>,
+    pub events: Option<Vec<WebhookEvent>>,
+    pub project_id: Option<Option<String>>,
+    pub enabled: Option<bool>,
+}
+
+impl CreateWebhook {
+    pub fn validate(&self) -> Result<(), FlowstateError> {
+        validate_url(&self.url)
+    }
+}
+
+impl UpdateWebhook {
+    pub fn validate(&self) -> Result<(), FlowstateError> {
+        if let Some(ref url) = self.url {
+            validate_url(url)?;
+        }
+        Ok(())
+    }
+}
+
+fn validate_url(url: &str) -> Result<(), FlowstateError> {
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        return Err(FlowstateError::InvalidInput(
+            "webhook url must be http:// or https://".into(),
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    /// Waiting for its first attempt or a retry.
+    Pending,
+    Delivered,
+    /// Out of retries.
+    Failed,
+}
+
+impl DeliveryStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeliveryStatus::Pending => "pending",
+            DeliveryStatus::Delivered => "delivered",
+            DeliveryStatus::Failed => "failed",
+        }
+    }
+
+    pub fn parse_str(s: &str) -> Option<Self> {
+        match s {
+            "pending" => Some(DeliveryStatus::Pending),
+            "delivered" => Some(DeliveryStatus::Delivered),
+            "failed" => Some(DeliveryStatus::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// One event queued for one webhook, and how sending it has gone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct WebhookDelivery {
+    pub id: String,
+    pub webhook_id: String,
+    pub event: WebhookEvent,
+    /// The JSON body sent, exactly as signed.
+    pub payload: String,
+    pub status: DeliveryStatus,
+    pub attempts: i32,
+    /// When a pending delivery is next tried.
+    pub next_attempt_at: DateTime<Utc>,
+    /// HTTP status of the last attempt, if it got a response.
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+/// The outcome of one delivery attempt.
+#[derive(Debug, Clone)]
+pub struct DeliveryAttempt {
+    pub status: DeliveryStatus,
+    pub response_status: Option<i32>,
+    pub error: Option<String>,
+    /// When to retry; only meaningful while `status` is `Pending`.
+    pub next_attempt_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(events: Vec<WebhookEvent>, project_id: Option<&str>) -> Webhook {
+        Webhook {
+            id: "w1".into(),
+            url: "https://example.com/hook".into(),
+            secret: String::new(),
+            events,
+            project_id: project_id.map(String::from),
+            enabled: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn event_filter_and_project_scope() {
+        let all = hook(vec![], None);
+        assert!(all.wants(WebhookEvent::RunFinished, "p1"));
+
+        let runs_in_p1 = hook(vec![WebhookEvent::RunFinished], Some("p1"));
+        assert!(runs_in_p1.wants(WebhookEvent::RunFinished, "p1"));
+        assert!(!runs_in_p1.wants(WebhookEvent::RunFinished, "p2"));
+        assert!(!runs_in_p1.wants(WebhookEvent::TaskApproved, "p1"));
+
+        let disabled = Webhook {
+            enabled: false,
+            ..hook(vec![], None)
+        };
+        assert!(!disabled.wants(WebhookEvent::TaskApproved, "p1"));
+    }
+
+    #[test]
+    fn secrets_are_not_serialized() {
+        let mut webhook = hook(vec![WebhookEvent::TaskApproved], None);
+        webhook.secret = "ciphertext".into();
+        let json = serde_json::to_value(&webhook).unwrap();
+        assert!(json.get("secret").is_none());
+        assert_eq!(json["events"], serde_json::json!(["task_approved"]));
+    }
+
+    #[test]
+    fn urls_must_be_http() {
+        let create = |url: &str| CreateWebhook {
+            url: url.into(),
+            secret: String::new(),
+            events: vec![],
+            project_id: None,
+        };
+        assert!(create("https://hooks.example.com/x").validate().is_ok());
+        assert!(create("ftp://example.com").validate().is_err());
+    }
+}
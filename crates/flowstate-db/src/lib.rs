@@ -4,21 +4,54 @@ pub mod sqlite;
 #[cfg(feature = "postgres")]
 pub mod postgres;
 
+pub mod migrate;
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) mod query;
+pub mod snapshot;
+pub mod stats;
+
 use std::path::PathBuf;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use thiserror::Error;
+use tokio::sync::Notify;
 
 use flowstate_core::api_key::ApiKey;
+use flowstate_core::approval_rule::{ApprovalPhase, ApprovalPolicy, ApprovalRule};
 use flowstate_core::attachment::Attachment;
-use flowstate_core::claude_run::{ClaudeRun, ClaudeRunStatus, CreateClaudeRun};
+use flowstate_core::claude_run::{ClaudeAction, ClaudeRun, ClaudeRunStatus, CreateClaudeRun};
+use flowstate_core::custom_field::{
+    CreateCustomField, CustomField, TaskFieldValue, UpdateCustomField,
+};
+use flowstate_core::dashboard::ProjectDashboard;
+use flowstate_core::epic::{CreateEpic, Epic, UpdateEpic};
+use flowstate_core::feature_flag::FeatureFlag;
+use flowstate_core::feedback::FeedbackEntry;
+use flowstate_core::label::Label;
+use flowstate_core::notification::Notification;
+use flowstate_core::organization::{CreateOrganization, Organization};
 use flowstate_core::project::{CreateProject, Project, UpdateProject};
+use flowstate_core::run_metrics::{
+    RecordRunMetrics, RunMetrics, RunMetricsFilter, RunMetricsSummary,
+};
+use flowstate_core::runner::{PendingRunnerConfig, RunnerRecord};
+use flowstate_core::saved_filter::{CreateSavedFilter, SavedFilter, UpdateSavedFilter};
 use flowstate_core::sprint::{CreateSprint, Sprint, UpdateSprint};
+use flowstate_core::subscription::{CreateSubscription, Subscription};
 use flowstate_core::task::{CreateTask, Task, TaskFilter, UpdateTask};
 use flowstate_core::task_link::{CreateTaskLink, TaskLink};
-use flowstate_core::task_pr::{CreateTaskPr, TaskPr};
+use flowstate_core::task_pr::{ChecksStatus, CreateTaskPr, PrState, TaskPr};
+use flowstate_core::task_revision::TaskRevision;
+use flowstate_core::user::{CreateUser, UpdateUser, User};
+use flowstate_core::webhook::{
+    CreateWebhook, DeliveryAttempt, UpdateWebhook, Webhook, WebhookDelivery, WebhookEvent,
+};
+
+pub use migrate::MigrationPlan;
+pub use snapshot::Snapshot;
+pub use stats::{DbStats, MaintenanceReport, MaintenanceStep, QueueDemand};
 
 #[derive(Debug, Error)]
 pub enum DbError {
@@ -34,15 +67,18 @@ pub enum DbError {
 
 #[async_trait]
 pub trait Database: Send + Sync {
-    // -- Projects (6 methods) --
+    // -- Projects (7 methods) --
     async fn create_project(&self, input: &CreateProject) -> Result<Project, DbError>;
     async fn get_project(&self, id: &str) -> Result<Project, DbError>;
     async fn get_project_by_slug(&self, slug: &str) -> Result<Project, DbError>;
     async fn list_projects(&self) -> Result<Vec<Project>, DbError>;
     async fn update_project(&self, id: &str, update: &UpdateProject) -> Result<Project, DbError>;
     async fn delete_project(&self, id: &str) -> Result<(), DbError>;
+    /// Task counts by status and priority, run outcomes by action and the
+    /// progress of each sprint, gathered in a single query.
+    async fn project_dashboard(&self, project_id: &str) -> Result<ProjectDashboard, DbError>;
 
-    // -- Tasks (7 methods) --
+    // -- Tasks (14 methods) --
     async fn create_task(&self, input: &CreateTask) -> Result<Task, DbError>;
     async fn get_task(&self, id: &str) -> Result<Task, DbError>;
     async fn list_tasks(&self, filter: &TaskFilter) -> Result<Vec<Task>, DbError>;
@@ -50,6 +86,33 @@ pub trait Database: Send + Sync {
     async fn update_task(&self, id: &str, update: &UpdateTask) -> Result<Task, DbError>;
     async fn delete_task(&self, id: &str) -> Result<(), DbError>;
     async fn count_tasks_by_status(&self, project_id: &str) -> Result<Vec<(String, i64)>, DbError>;
+    /// Create every task in a single transaction; any failure creates none.
+    async fn bulk_create_tasks(&self, inputs: &[CreateTask]) -> Result<Vec<Task>, DbError>;
+    /// Apply one update to many tasks in a single transaction; a missing id
+    /// rolls back the whole batch.
+    async fn bulk_update_tasks(
+        &self,
+        ids: &[String],
+        update: &UpdateTask,
+    ) -> Result<Vec<Task>, DbError>;
+    /// Atomically move a task to just after `after_id` (or to the top) within
+    /// its project+status column, renumbering that column's `sort_order`.
+    async fn reorder_task(&self, id: &str, after_id: Option<&str>) -> Result<Task, DbError>;
+    /// Archive a task and its subtasks at any depth.
+    async fn archive_task(&self, id: &str) -> Result<Task, DbError>;
+    /// Field-level history recorded by `update_task`, newest first.
+    async fn list_task_revisions(&self, task_id: &str) -> Result<Vec<TaskRevision>, DbError>;
+    /// Rejection feedback recorded by `update_task`, newest first.
+    async fn list_feedback_history(&self, task_id: &str) -> Result<Vec<FeedbackEntry>, DbError>;
+    /// Record feedback that did not come from a rejection, such as a PR
+    /// review comment.
+    async fn add_feedback_entry(
+        &self,
+        task_id: &str,
+        phase: &str,
+        feedback: &str,
+        author: &str,
+    ) -> Result<FeedbackEntry, DbError>;
 
     // -- Claude Runs (11 methods) --
     async fn create_claude_run(&self, input: &CreateClaudeRun) -> Result<ClaudeRun, DbError>;
@@ -65,6 +128,26 @@ pub trait Database: Send + Sync {
     async fn claim_next_claude_run(
         &self,
         capabilities: &[&str],
+    ) -> Result<Option<ClaudeRun>, DbError> {
+        self.claim_next_claude_run_except(capabilities, &[]).await
+    }
+    /// Like `claim_next_claude_run`, but leaves runs of the `skip` actions
+    /// queued for another runner.
+    async fn claim_next_claude_run_except(
+        &self,
+        capabilities: &[&str],
+        skip: &[ClaudeAction],
+    ) -> Result<Option<ClaudeRun>, DbError> {
+        self.claim_next_claude_run_scoped(capabilities, skip, &[])
+            .await
+    }
+    /// Like `claim_next_claude_run_except`, but only claims runs for tasks in
+    /// `projects` (by id). Empty means any project.
+    async fn claim_next_claude_run_scoped(
+        &self,
+        capabilities: &[&str],
+        skip: &[ClaudeAction],
+        projects: &[String],
     ) -> Result<Option<ClaudeRun>, DbError>;
     async fn update_claude_run_progress(&self, id: &str, message: &str) -> Result<(), DbError>;
     async fn update_claude_run_pr(
@@ -88,43 +171,326 @@ pub trait Database: Send + Sync {
         error_message: &str,
     ) -> Result<Option<ClaudeRun>, DbError>;
     async fn set_claude_run_runner(&self, id: &str, runner_id: &str) -> Result<(), DbError>;
+    /// Atomically put a failed, timed-out or cancelled run back in the
+    /// queue, behind runs of the same priority: its error, exit code,
+    /// progress, runner and finish time are cleared. Returns `Ok(None)`,
+    /// changing nothing, if the run does not exist or is in another status.
+    async fn requeue_claude_run(&self, id: &str) -> Result<Option<ClaudeRun>, DbError>;
+    /// Change the priority of a queued run. Returns `Ok(None)`, changing
+    /// nothing, if the run does not exist or is no longer queued.
+    async fn set_claude_run_priority(
+        &self,
+        id: &str,
+        priority: i32,
+    ) -> Result<Option<ClaudeRun>, DbError>;
     async fn count_queued_runs(&self) -> Result<i64, DbError>;
+    /// Queued runs grouped by required capability, most runs first.
+    async fn queued_run_demand(&self) -> Result<Vec<QueueDemand>, DbError>;
+    /// Runs that finished at or after `since`, as `(finished, failed)`.
+    /// Completed, failed and timed-out runs count as finished; failed and
+    /// timed-out ones also count as failed. Cancelled runs are ignored.
+    async fn count_finished_runs(&self, since: DateTime<Utc>) -> Result<(i64, i64), DbError>;
+    /// Delete completed, failed, cancelled and timed-out runs that ended
+    /// before `cutoff`, except the latest run of each action on a task, which
+    /// prerequisite checks still read. Returns the deleted run ids so their
+    /// stored prompts and output can be removed too.
+    async fn delete_runs_older_than(&self, cutoff: DateTime<Utc>) -> Result<Vec<String>, DbError>;
+    /// Woken (`notify_waiters`) whenever a run is queued or re-queued, so a
+    /// claim can wait for work instead of polling. On Postgres this follows
+    /// LISTEN/NOTIFY and so also fires for runs queued by other servers.
+    fn work_available(&self) -> Arc<Notify>;
+
+    // -- Run Metrics (2 methods) --
+    /// Store the metrics for a run, replacing any earlier report for it.
+    async fn record_run_metrics(
+        &self,
+        run_id: &str,
+        input: &RecordRunMetrics,
+    ) -> Result<RunMetrics, DbError>;
+    /// Per-action totals over recorded runs, ordered by action.
+    async fn summarize_run_metrics(
+        &self,
+        filter: &RunMetricsFilter,
+    ) -> Result<Vec<RunMetricsSummary>, DbError>;
 
-    // -- Sprints (5 methods) --
+    // -- Sprints (6 methods) --
     async fn create_sprint(&self, input: &CreateSprint) -> Result<Sprint, DbError>;
     async fn get_sprint(&self, id: &str) -> Result<Sprint, DbError>;
     async fn list_sprints(&self, project_id: &str) -> Result<Vec<Sprint>, DbError>;
     async fn update_sprint(&self, id: &str, update: &UpdateSprint) -> Result<Sprint, DbError>;
     async fn delete_sprint(&self, id: &str) -> Result<(), DbError>;
-
-    // -- Task Links (3 methods) --
+    /// Archive a sprint together with its tasks and their subtasks.
+    async fn archive_sprint(&self, id: &str) -> Result<Sprint, DbError>;
+
+    // -- Epics (5 methods) --
+    async fn create_epic(&self, input: &CreateEpic) -> Result<Epic, DbError>;
+    async fn get_epic(&self, id: &str) -> Result<Epic, DbError>;
+    async fn list_epics(&self, project_id: &str) -> Result<Vec<Epic>, DbError>;
+    async fn update_epic(&self, id: &str, update: &UpdateEpic) -> Result<Epic, DbError>;
+    /// Delete an epic, detaching (not deleting) its tasks.
+    async fn delete_epic(&self, id: &str) -> Result<(), DbError>;
+
+    // -- Users (5 methods) --
+    async fn create_user(&self, input: &CreateUser) -> Result<User, DbError>;
+    async fn get_user(&self, id: &str) -> Result<User, DbError>;
+    async fn list_users(&self) -> Result<Vec<User>, DbError>;
+    async fn update_user(&self, id: &str, update: &UpdateUser) -> Result<User, DbError>;
+    /// Delete a user, unassigning (not deleting) their tasks.
+    async fn delete_user(&self, id: &str) -> Result<(), DbError>;
+
+    // -- Saved Filters (5 methods) --
+    async fn create_saved_filter(&self, input: &CreateSavedFilter) -> Result<SavedFilter, DbError>;
+    async fn get_saved_filter(&self, id: &str) -> Result<SavedFilter, DbError>;
+    /// A project's filters: all of them when `user_id` is `None`, otherwise
+    /// the shared ones plus that user's own.
+    async fn list_saved_filters(
+        &self,
+        project_id: &str,
+        user_id: Option<&str>,
+    ) -> Result<Vec<SavedFilter>, DbError>;
+    async fn update_saved_filter(
+        &self,
+        id: &str,
+        update: &UpdateSavedFilter,
+    ) -> Result<SavedFilter, DbError>;
+    async fn delete_saved_filter(&self, id: &str) -> Result<(), DbError>;
+
+    // -- Watchers and Notifications (6 methods) --
+    /// Subscribe a user to a task; watching twice is a no-op.
+    async fn watch_task(&self, task_id: &str, user_id: &str) -> Result<(), DbError>;
+    async fn unwatch_task(&self, task_id: &str, user_id: &str) -> Result<(), DbError>;
+    async fn list_task_watchers(&self, task_id: &str) -> Result<Vec<User>, DbError>;
+    /// Notifications recorded by `update_task` for a watcher, newest first.
+    async fn list_notifications(
+        &self,
+        user_id: &str,
+        unread_only: bool,
+    ) -> Result<Vec<Notification>, DbError>;
+    async fn get_notification(&self, id: &str) -> Result<Notification, DbError>;
+    async fn mark_notification_read(&self, id: &str) -> Result<Notification, DbError>;
+
+    // -- Custom Fields (6 methods) --
+    async fn create_custom_field(&self, input: &CreateCustomField) -> Result<CustomField, DbError>;
+    async fn get_custom_field(&self, id: &str) -> Result<CustomField, DbError>;
+    async fn list_custom_fields(&self, project_id: &str) -> Result<Vec<CustomField>, DbError>;
+    async fn update_custom_field(
+        &self,
+        id: &str,
+        update: &UpdateCustomField,
+    ) -> Result<CustomField, DbError>;
+    /// Delete a field along with every task's value for it.
+    async fn delete_custom_field(&self, id: &str) -> Result<(), DbError>;
+    /// A task's custom field values; written through `UpdateTask::custom_fields`.
+    async fn list_task_field_values(&self, task_id: &str) -> Result<Vec<TaskFieldValue>, DbError>;
+
+    // -- Task Links (4 methods) --
     async fn create_task_link(&self, input: &CreateTaskLink) -> Result<TaskLink, DbError>;
+    async fn get_task_link(&self, id: &str) -> Result<TaskLink, DbError>;
     async fn list_task_links(&self, task_id: &str) -> Result<Vec<TaskLink>, DbError>;
     async fn delete_task_link(&self, id: &str) -> Result<(), DbError>;
 
-    // -- Task PRs (2 methods) --
+    // -- Task PRs (8 methods) --
     async fn create_task_pr(&self, input: &CreateTaskPr) -> Result<TaskPr, DbError>;
     async fn list_task_prs(&self, task_id: &str) -> Result<Vec<TaskPr>, DbError>;
+    async fn get_task_pr(&self, id: &str) -> Result<TaskPr, DbError>;
+    async fn get_task_pr_by_url(&self, pr_url: &str) -> Result<TaskPr, DbError>;
+    async fn set_task_pr_state(&self, id: &str, state: PrState) -> Result<TaskPr, DbError>;
+    async fn set_task_pr_checks(
+        &self,
+        id: &str,
+        checks_status: Option<ChecksStatus>,
+    ) -> Result<TaskPr, DbError>;
+    async fn set_task_pr_draft(&self, id: &str, draft: bool) -> Result<TaskPr, DbError>;
+    /// Every open task PR, oldest first.
+    async fn list_open_task_prs(&self) -> Result<Vec<TaskPr>, DbError>;
+
+    // -- Labels (3 methods) --
+    /// The project's label called `name`, created with `color` if it does
+    /// not exist yet. An existing label keeps its color.
+    async fn ensure_label(
+        &self,
+        project_id: &str,
+        name: &str,
+        color: &str,
+    ) -> Result<Label, DbError>;
+    /// Put a label on a task; adding it twice is a no-op.
+    async fn add_task_label(&self, task_id: &str, label_id: &str) -> Result<(), DbError>;
+    async fn list_task_labels(&self, task_id: &str) -> Result<Vec<Label>, DbError>;
+
+    // -- Task Imports (2 methods) --
+    /// The id of the task imported into `project_id` from `source` (such as
+    /// an issue URL), if it was imported before.
+    async fn find_imported_task(
+        &self,
+        project_id: &str,
+        source: &str,
+    ) -> Result<Option<String>, DbError>;
+    /// Remember that `source` became `task_id`; recording it twice is a no-op.
+    async fn record_task_import(
+        &self,
+        project_id: &str,
+        source: &str,
+        task_id: &str,
+    ) -> Result<(), DbError>;
 
-    // -- Attachments (4 methods) --
+    // -- Attachments (5 methods) --
     async fn create_attachment(
         &self,
         task_id: &str,
         filename: &str,
         store_key: &str,
         size_bytes: i64,
+        content_type: &str,
+        sha256: &str,
     ) -> Result<Attachment, DbError>;
     async fn list_attachments(&self, task_id: &str) -> Result<Vec<Attachment>, DbError>;
     async fn get_attachment(&self, id: &str) -> Result<Attachment, DbError>;
     async fn delete_attachment(&self, id: &str) -> Result<Attachment, DbError>;
+    /// How many attachments point at `store_key`. Identical uploads share
+    /// one content-addressed blob, which can be deleted once this is zero.
+    async fn count_attachment_refs(&self, store_key: &str) -> Result<i64, DbError>;
 
-    // -- API Keys (6 methods) --
+    // -- API Keys (9 methods) --
     async fn insert_api_key(&self, name: &str, key_hash: &str) -> Result<ApiKey, DbError>;
     async fn find_api_key_by_hash(&self, key_hash: &str) -> Result<Option<ApiKey>, DbError>;
     async fn touch_api_key(&self, id: &str) -> Result<(), DbError>;
     async fn has_api_keys(&self) -> Result<bool, DbError>;
     async fn list_api_keys(&self) -> Result<Vec<ApiKey>, DbError>;
+    /// Replace a key's CIDR and route allowlists. Empty lists lift the restriction.
+    async fn set_api_key_policy(
+        &self,
+        id: &str,
+        allowed_cidrs: &[String],
+        allowed_routes: &[String],
+    ) -> Result<ApiKey, DbError>;
+    /// Replace the projects a key is restricted to. Empty lifts the restriction.
+    async fn set_api_key_projects(
+        &self,
+        id: &str,
+        project_ids: &[String],
+    ) -> Result<ApiKey, DbError>;
+    /// Move a key into an organization, or make it server-wide with `None`.
+    async fn set_api_key_org(&self, id: &str, org_id: Option<&str>) -> Result<ApiKey, DbError>;
     async fn delete_api_key(&self, id: &str) -> Result<(), DbError>;
+
+    // -- Organizations (6 methods) --
+    async fn create_organization(
+        &self,
+        input: &CreateOrganization,
+    ) -> Result<Organization, DbError>;
+    async fn get_organization(&self, id: &str) -> Result<Organization, DbError>;
+    async fn list_organizations(&self) -> Result<Vec<Organization>, DbError>;
+    /// Delete an organization along with its API keys. Projects it still
+    /// owns fall back to server-wide keys only.
+    async fn delete_organization(&self, id: &str) -> Result<(), DbError>;
+    async fn list_organization_project_ids(&self, org_id: &str) -> Result<Vec<String>, DbError>;
+    /// Hand a project to an organization, or take it back with `None`.
+    async fn set_project_org(
+        &self,
+        project_id: &str,
+        org_id: Option<&str>,
+    ) -> Result<Project, DbError>;
+
+    // -- Runners (5 methods) --
+    /// Record a registration heartbeat. A runner already on file keeps its
+    /// `first_seen_at` and pending config.
+    async fn upsert_runner(&self, runner: &RunnerRecord) -> Result<RunnerRecord, DbError>;
+    async fn get_runner(&self, runner_id: &str) -> Result<RunnerRecord, DbError>;
+    async fn list_runners(&self) -> Result<Vec<RunnerRecord>, DbError>;
+    /// Queue `config` for the runner's next heartbeat, or clear it with `None`.
+    async fn set_runner_pending_config(
+        &self,
+        runner_id: &str,
+        config: Option<&PendingRunnerConfig>,
+    ) -> Result<(), DbError>;
+    async fn delete_runner(&self, runner_id: &str) -> Result<(), DbError>;
+
+    // -- Feature Flags (3 methods) --
+    async fn list_feature_flags(&self) -> Result<Vec<FeatureFlag>, DbError>;
+    /// Insert or replace the override for `key` at global (`None`) or project scope.
+    async fn set_feature_flag(
+        &self,
+        key: &str,
+        project_id: Option<&str>,
+        enabled: bool,
+    ) -> Result<FeatureFlag, DbError>;
+    async fn delete_feature_flag(&self, key: &str, project_id: Option<&str>)
+        -> Result<(), DbError>;
+
+    // -- Approval Rules (3 methods) --
+    async fn list_approval_rules(&self, project_id: &str) -> Result<Vec<ApprovalRule>, DbError>;
+    /// Insert or replace the project's policy for `phase`.
+    async fn set_approval_rule(
+        &self,
+        project_id: &str,
+        phase: ApprovalPhase,
+        policy: ApprovalPolicy,
+    ) -> Result<ApprovalRule, DbError>;
+    async fn delete_approval_rule(
+        &self,
+        project_id: &str,
+        phase: ApprovalPhase,
+    ) -> Result<(), DbError>;
+
+    // -- Notification Subscriptions (4 methods) --
+    async fn create_subscription(
+        &self,
+        input: &CreateSubscription,
+    ) -> Result<Subscription, DbError>;
+    async fn get_subscription(&self, id: &str) -> Result<Subscription, DbError>;
+    async fn list_subscriptions(&self) -> Result<Vec<Subscription>, DbError>;
+    async fn delete_subscription(&self, id: &str) -> Result<(), DbError>;
+
+    // -- Webhooks (10 methods) --
+    async fn create_webhook(&self, input: &CreateWebhook) -> Result<Webhook, DbError>;
+    async fn get_webhook(&self, id: &str) -> Result<Webhook, DbError>;
+    async fn list_webhooks(&self) -> Result<Vec<Webhook>, DbError>;
+    async fn update_webhook(&self, id: &str, update: &UpdateWebhook) -> Result<Webhook, DbError>;
+    /// Delete a webhook and its deliveries.
+    async fn delete_webhook(&self, id: &str) -> Result<(), DbError>;
+    /// Queue `payload` for delivery to a webhook as soon as a worker is free.
+    async fn enqueue_webhook_delivery(
+        &self,
+        webhook_id: &str,
+        event: WebhookEvent,
+        payload: &str,
+    ) -> Result<WebhookDelivery, DbError>;
+    /// Take up to `limit` pending deliveries that are due, pushing their
+    /// `next_attempt_at` to `lease_until` so no other worker takes them
+    /// meanwhile.
+    async fn claim_webhook_deliveries(
+        &self,
+        lease_until: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, DbError>;
+    async fn record_webhook_attempt(
+        &self,
+        id: &str,
+        attempt: &DeliveryAttempt,
+    ) -> Result<WebhookDelivery, DbError>;
+    /// Most recent deliveries first.
+    async fn list_webhook_deliveries(
+        &self,
+        webhook_id: &str,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, DbError>;
+    /// Delete delivered and failed deliveries created before `before`.
+    async fn prune_webhook_deliveries(&self, before: DateTime<Utc>) -> Result<u64, DbError>;
+
+    // -- Backup / Restore (2 methods) --
+    /// Dump every project, sprint, epic, custom field, task, field value, run,
+    /// link, PR and attachment record.
+    async fn export_snapshot(&self) -> Result<Snapshot, DbError>;
+    /// Insert all records from a snapshot in a single transaction, preserving IDs.
+    async fn import_snapshot(&self, snapshot: &Snapshot) -> Result<(), DbError>;
+
+    // -- Diagnostics (2 methods) --
+    /// Row counts, schema version and backend-specific size or pool figures.
+    async fn stats(&self) -> Result<DbStats, DbError>;
+    /// Housekeeping for long-running instances: WAL checkpoint, vacuum and
+    /// integrity check on SQLite; ANALYZE and a reindex advisory on Postgres.
+    /// A failing step is recorded in the report rather than returned.
+    async fn run_maintenance(&self) -> Result<MaintenanceReport, DbError>;
 }
 
 // -- Configuration --
@@ -247,6 +613,225 @@ pub fn workspace_dir(project_id: &str) -> PathBuf {
     data_dir().join("workspaces").join(project_id)
 }
 
+/// A `LIKE` pattern matching `text` anywhere, with `%`, `_` and `\`
+/// escaped so they match literally (use with `ESCAPE '\'`).
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn like_pattern(text: &str) -> String {
+    let mut pattern = String::with_capacity(text.len() + 2);
+    pattern.push('%');
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+/// The run window stored in a row's `run_window_start`, `run_window_end`,
+/// `run_window_offset` and `run_window_tz` columns, if it has one.
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn run_window_from_columns(
+    start: Option<i32>,
+    end: Option<i32>,
+    offset: Option<i32>,
+    tz: Option<String>,
+) -> Option<flowstate_core::RunWindow> {
+    let window = flowstate_core::RunWindow::new(start?, end?, offset.unwrap_or(0)).ok()?;
+    match tz.and_then(|name| name.parse::<chrono_tz::Tz>().ok()) {
+        Some(tz) => Some(window.with_time_zone(tz)),
+        None => Some(window),
+    }
+}
+
+/// The zones named by project windows and queued runs' windows, for
+/// [`in_run_window_sql`].
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) const RUN_WINDOW_ZONES_SQL: &str =
+    "SELECT run_window_tz FROM projects WHERE run_window_tz IS NOT NULL
+     UNION
+     SELECT run_window_tz FROM claude_runs WHERE status = 'queued' AND run_window_tz IS NOT NULL";
+
+/// Claim filter: true when a queued run's window is open at `now`. A run's
+/// own window takes the place of its project's; runs with neither are
+/// always claimable. Each of `zones` is bound with its offset at `now`, so
+/// windows in a zone follow daylight saving time. The SQL is the same in
+/// both backends.
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn in_run_window_sql(
+    q: &mut query::SelectQuery,
+    now: DateTime<Utc>,
+    zones: &[String],
+) -> String {
+    use chrono::Timelike;
+
+    let mut offsets = String::new();
+    for name in zones {
+        let Ok(tz) = name.parse::<chrono_tz::Tz>() else {
+            continue;
+        };
+        let offset = flowstate_core::run_window::zone_offset(tz, now);
+        let (name, offset) = (q.bind(name), q.bind(i64::from(offset)));
+        offsets.push_str(&format!(" WHEN {name} THEN {offset}"));
+    }
+    let utc_minute = (now.hour() * 60 + now.minute()) as i32;
+    let run = window_open_sql("claude_runs", utc_minute, &offsets);
+    let project = window_open_sql("p", utc_minute, &offsets);
+    format!(
+        "COALESCE(
+            (SELECT {run} WHERE claude_runs.run_window_start IS NOT NULL),
+            (SELECT {project} FROM tasks t JOIN projects p ON p.id = t.project_id
+             WHERE t.id = claude_runs.task_id AND p.run_window_start IS NOT NULL),
+            TRUE
+        )"
+    )
+}
+
+/// Claim filter excluding runs of the `skip` actions. Action names are
+/// fixed identifiers, so they are inlined rather than bound.
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn skip_actions_sql(skip: &[ClaudeAction]) -> String {
+    if skip.is_empty() {
+        return "TRUE".to_string();
+    }
+    let names: Vec<String> = skip.iter().map(|a| format!("'{}'", a.as_str())).collect();
+    format!("claude_runs.action NOT IN ({})", names.join(", "))
+}
+
+/// The project of the `claude_runs` row being considered, for limiting a
+/// claim to the projects in an API key's scope.
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) const RUN_PROJECT_SQL: &str =
+    "(SELECT t.project_id FROM tasks t WHERE t.id = claude_runs.task_id)";
+
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+fn window_open_sql(alias: &str, utc_minute: i32, zone_offsets: &str) -> String {
+    let offset = if zone_offsets.is_empty() {
+        format!("{alias}.run_window_offset")
+    } else {
+        format!("CASE {alias}.run_window_tz{zone_offsets} ELSE {alias}.run_window_offset END")
+    };
+    let local = format!("((({utc_minute} + {offset}) % 1440) + 1440) % 1440");
+    format!(
+        "CASE WHEN {alias}.run_window_start < {alias}.run_window_end \
+         THEN {local} >= {alias}.run_window_start AND {local} < {alias}.run_window_end \
+         ELSE {local} >= {alias}.run_window_start OR {local} < {alias}.run_window_end END"
+    )
+}
+
+/// One row of the `project_dashboard` query. Both backends select the same
+/// columns: `kind` says which part of the dashboard the row belongs to.
+///
+/// - `status` / `priority`: `key` is the value, `total` the task count
+/// - `run`: `key` is the action, `state` the run status, `total` the count
+/// - `sprint`: `key` is the id, `name` and `state` the sprint's, `total` and
+///   `done` its task counts
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) struct DashboardRow {
+    pub kind: String,
+    pub key: String,
+    pub name: String,
+    pub state: String,
+    pub total: i64,
+    pub done: i64,
+}
+
+/// Fold the rows of the `project_dashboard` query into a dashboard, filling
+/// in zero counts for statuses and priorities without tasks.
+#[cfg(any(feature = "sqlite", feature = "postgres"))]
+pub(crate) fn dashboard_from_rows(
+    project_id: &str,
+    rows: Vec<DashboardRow>,
+) -> flowstate_core::ProjectDashboard {
+    use flowstate_core::claude_run::ClaudeAction;
+    use flowstate_core::dashboard::{PriorityCount, RunOutcomes, SprintProgress, StatusCount};
+    use flowstate_core::{Priority, SprintStatus, Status};
+
+    let mut tasks_by_status: Vec<StatusCount> = Status::ALL
+        .iter()
+        .map(|&status| StatusCount { status, count: 0 })
+        .collect();
+    let mut tasks_by_priority: Vec<PriorityCount> = Priority::ALL
+        .iter()
+        .map(|&priority| PriorityCount { priority, count: 0 })
+        .collect();
+    let mut runs: Vec<RunOutcomes> = Vec::new();
+    let mut sprints = Vec::new();
+
+    for row in rows {
+        match row.kind.as_str() {
+            "status" => {
+                if let Some(c) = tasks_by_status
+                    .iter_mut()
+                    .find(|c| c.status.as_str() == row.key)
+                {
+                    c.count = row.total;
+                }
+            }
+            "priority" => {
+                if let Some(c) = tasks_by_priority
+                    .iter_mut()
+                    .find(|c| c.priority.as_str() == row.key)
+                {
+                    c.count = row.total;
+                }
+            }
+            "run" => {
+                let Some(action) = ClaudeAction::parse_str(&row.key) else {
+                    continue;
+                };
+                let idx = match runs.iter().position(|r| r.action == Some(action)) {
+                    Some(idx) => idx,
+                    None => {
+                        runs.push(RunOutcomes {
+                            action: Some(action),
+                            ..RunOutcomes::default()
+                        });
+                        runs.len() - 1
+                    }
+                };
+                let outcomes = &mut runs[idx];
+                match row.state.as_str() {
+                    "completed" => outcomes.completed = row.total,
+                    "failed" => outcomes.failed = row.total,
+                    "timed_out" => outcomes.timed_out = row.total,
+                    "cancelled" => outcomes.cancelled = row.total,
+                    _ => {}
+                }
+            }
+            "sprint" => sprints.push(SprintProgress {
+                sprint_id: row.key,
+                name: row.name,
+                status: SprintStatus::parse_str(&row.state).unwrap_or(SprintStatus::Planned),
+                total_tasks: row.total,
+                done_tasks: row.done,
+            }),
+            _ => {}
+        }
+    }
+
+    runs.sort_by_key(|r| r.action.map(|a| a.as_str()));
+    let sprint_rank = |status: SprintStatus| match status {
+        SprintStatus::Active => 0,
+        SprintStatus::Planned => 1,
+        SprintStatus::Completed => 2,
+    };
+    sprints.sort_by(|a, b| {
+        sprint_rank(a.status)
+            .cmp(&sprint_rank(b.status))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+
+    flowstate_core::ProjectDashboard {
+        project_id: project_id.to_string(),
+        tasks_by_status,
+        tasks_by_priority,
+        runs,
+        sprints,
+    }
+}
+
 fn dirs_default_data_dir() -> PathBuf {
     if let Ok(xdg) = std::env::var("XDG_DATA_HOME") {
         PathBuf::from(xdg)
@@ -261,6 +846,13 @@ fn dirs_default_data_dir() -> PathBuf {
 mod tests {
     use super::*;
 
+    #[cfg(any(feature = "sqlite", feature = "postgres"))]
+    #[test]
+    fn like_pattern_escapes_wildcards() {
+        assert_eq!(like_pattern("login"), "%login%");
+        assert_eq!(like_pattern("100%_a\\b"), "%100\\%\\_a\\\\b%");
+    }
+
     #[test]
     fn task_dir_contains_task_id() {
         let path = task_dir("abc-123");
@@ -0,0 +1,225 @@
+//! Planning and applying schema migrations to a chosen version, forwards or
+//! backwards, for `flowstate-server migrate`.
+//!
+//! Opening a database normally migrates it straight to the latest version.
+//! The functions here open the backend *without* doing that, so an operator
+//! can preview pending SQL or roll back to an older schema before deploying
+//! an older binary.
+
+use crate::{DbConfig, DbError};
+
+/// One versioned schema change. `up`/`down` are `None` for steps that are
+/// implemented imperatively (early SQLite versions) or cannot be undone.
+#[cfg_attr(not(any(feature = "sqlite", feature = "postgres")), allow(dead_code))]
+pub(crate) struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub up: Option<&'static str>,
+    pub down: Option<&'static str>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Down,
+}
+
+/// A single step of a [`MigrationPlan`].
+#[derive(Debug, Clone)]
+pub struct MigrationStep {
+    pub version: i64,
+    pub name: &'static str,
+    pub direction: Direction,
+    /// SQL that will run, or `None` when the step is applied in code.
+    pub sql: Option<&'static str>,
+}
+
+/// The steps needed to move a database from `current` to `target`.
+#[derive(Debug, Clone)]
+pub struct MigrationPlan {
+    /// "sqlite" or "postgres".
+    pub backend: &'static str,
+    pub current: i64,
+    pub target: i64,
+    pub steps: Vec<MigrationStep>,
+}
+
+impl MigrationPlan {
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+/// Work out the steps from `current` to `target` (default: latest).
+///
+/// Rolling back fails up front if any step on the way has no down script,
+/// naming the oldest version that is reachable.
+#[cfg_attr(not(any(feature = "sqlite", feature = "postgres")), allow(dead_code))]
+pub(crate) fn plan(
+    backend: &'static str,
+    migrations: &[Migration],
+    current: i64,
+    target: Option<i64>,
+) -> Result<MigrationPlan, DbError> {
+    let latest = migrations.last().map(|m| m.version).unwrap_or(0);
+    let target = target.unwrap_or(latest);
+    if target < 0 || target > latest {
+        return Err(DbError::Internal(format!(
+            "unknown schema version {target} (latest is {latest})"
+        )));
+    }
+
+    let steps = if target >= current {
+        migrations
+            .iter()
+            .filter(|m| m.version > current && m.version <= target)
+            .map(|m| MigrationStep {
+                version: m.version,
+                name: m.name,
+                direction: Direction::Up,
+                sql: m.up,
+            })
+            .collect()
+    } else {
+        let mut steps = Vec::new();
+        for m in migrations
+            .iter()
+            .rev()
+            .filter(|m| m.version > target && m.version <= current)
+        {
+            let Some(down) = m.down else {
+                return Err(DbError::Internal(format!(
+                    "v{} ({}) cannot be rolled back; the oldest reachable version is {}",
+                    m.version, m.name, m.version
+                )));
+            };
+            steps.push(MigrationStep {
+                version: m.version,
+                name: m.name,
+                direction: Direction::Down,
+                sql: Some(down),
+            });
+        }
+        steps
+    };
+
+    Ok(MigrationPlan {
+        backend,
+        current,
+        target,
+        steps,
+    })
+}
+
+/// Migrate the configured database to `target` (default: latest), or with
+/// `dry_run` only report what would run. Returns the plan either way.
+pub async fn migrate(
+    config: &DbConfig,
+    target: Option<i64>,
+    dry_run: bool,
+) -> Result<MigrationPlan, DbError> {
+    match config.backend.as_str() {
+        "sqlite" => {
+            #[cfg(feature = "sqlite")]
+            {
+                let path = crate::sqlite::SqliteDatabase::resolve_path(config)?;
+                tokio::task::spawn_blocking(move || {
+                    crate::sqlite::migrations::migrate_path(&path, target, dry_run)
+                })
+                .await
+                .map_err(|e| DbError::Internal(e.to_string()))?
+            }
+            #[cfg(not(feature = "sqlite"))]
+            {
+                let _ = (target, dry_run);
+                Err(DbError::Internal(
+                    "SQLite backend requested but the 'sqlite' feature is not enabled".into(),
+                ))
+            }
+        }
+        "postgres" => {
+            #[cfg(feature = "postgres")]
+            {
+                let url = config.database_url.as_deref().ok_or_else(|| {
+                    DbError::Internal(
+                        "Postgres backend requires FLOWSTATE_DATABASE_URL or DATABASE_URL".into(),
+                    )
+                })?;
+                crate::postgres::migrations::migrate_url(url, target, dry_run).await
+            }
+            #[cfg(not(feature = "postgres"))]
+            {
+                let _ = (target, dry_run);
+                Err(DbError::Internal(
+                    "Postgres backend requested but the 'postgres' feature is not enabled".into(),
+                ))
+            }
+        }
+        other => Err(DbError::Internal(format!(
+            "unknown database backend: {other}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_MIGRATIONS: &[Migration] = &[
+        Migration {
+            version: 1,
+            name: "initial",
+            up: None,
+            down: None,
+        },
+        Migration {
+            version: 2,
+            name: "add_a",
+            up: Some("UP 2"),
+            down: Some("DOWN 2"),
+        },
+        Migration {
+            version: 3,
+            name: "add_b",
+            up: Some("UP 3"),
+            down: Some("DOWN 3"),
+        },
+    ];
+
+    #[test]
+    fn plans_forward_and_back() {
+        let plan = plan("sqlite", TEST_MIGRATIONS, 0, None).unwrap();
+        assert_eq!(plan.target, 3);
+        assert_eq!(
+            plan.steps.iter().map(|s| s.version).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+
+        let plan = plan_back(3, 1);
+        assert_eq!(
+            plan.steps
+                .iter()
+                .map(|s| (s.version, s.direction, s.sql))
+                .collect::<Vec<_>>(),
+            vec![
+                (3, Direction::Down, Some("DOWN 3")),
+                (2, Direction::Down, Some("DOWN 2")),
+            ]
+        );
+
+        assert!(plan_back(2, 2).is_empty());
+    }
+
+    fn plan_back(current: i64, target: i64) -> MigrationPlan {
+        plan("sqlite", TEST_MIGRATIONS, current, Some(target)).unwrap()
+    }
+
+    #[test]
+    fn rejects_unknown_or_irreversible_targets() {
+        let err = plan("sqlite", TEST_MIGRATIONS, 3, Some(4)).unwrap_err();
+        assert!(err.to_string().contains("latest is 3"));
+
+        let err = plan("sqlite", TEST_MIGRATIONS, 3, Some(0)).unwrap_err();
+        assert!(err.to_string().contains("oldest reachable version is 1"));
+    }
+}
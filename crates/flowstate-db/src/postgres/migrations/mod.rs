@@ -1,12 +1,320 @@
+use sqlx::postgres::PgPoolOptions;
 use sqlx::PgPool;
 
+use super::pg_err;
+use crate::migrate::{self, Migration, MigrationPlan};
 use crate::DbError;
 
 /// Arbitrary but fixed key for the Postgres advisory lock that serialises
 /// migration runs so concurrent connections don't race.
 const MIGRATION_LOCK_KEY: i64 = 0x666C6F77_73746174; // "flowstat" as hex
 
+/// Every `V{n}` script with its `U{n}` undo script. Each script records (or
+/// removes) its own `schema_version` row. V1 cannot be undone.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial",
+        up: Some(include_str!("sql/V1__initial.sql")),
+        down: None,
+    },
+    Migration {
+        version: 2,
+        name: "add_required_capability",
+        up: Some(include_str!("sql/V2__add_required_capability.sql")),
+        down: Some(include_str!("sql/U2__add_required_capability.sql")),
+    },
+    Migration {
+        version: 3,
+        name: "add_provider_type",
+        up: Some(include_str!("sql/V3__add_provider_type.sql")),
+        down: Some(include_str!("sql/U3__add_provider_type.sql")),
+    },
+    Migration {
+        version: 4,
+        name: "add_task_capabilities",
+        up: Some(include_str!("sql/V4__add_task_capabilities.sql")),
+        down: Some(include_str!("sql/U4__add_task_capabilities.sql")),
+    },
+    Migration {
+        version: 5,
+        name: "add_task_revisions",
+        up: Some(include_str!("sql/V5__add_task_revisions.sql")),
+        down: Some(include_str!("sql/U5__add_task_revisions.sql")),
+    },
+    Migration {
+        version: 6,
+        name: "add_api_key_policy",
+        up: Some(include_str!("sql/V6__add_api_key_policy.sql")),
+        down: Some(include_str!("sql/U6__add_api_key_policy.sql")),
+    },
+    Migration {
+        version: 7,
+        name: "add_feature_flags",
+        up: Some(include_str!("sql/V7__add_feature_flags.sql")),
+        down: Some(include_str!("sql/U7__add_feature_flags.sql")),
+    },
+    Migration {
+        version: 8,
+        name: "add_run_metrics",
+        up: Some(include_str!("sql/V8__add_run_metrics.sql")),
+        down: Some(include_str!("sql/U8__add_run_metrics.sql")),
+    },
+    Migration {
+        version: 9,
+        name: "add_run_priority",
+        up: Some(include_str!("sql/V9__add_run_priority.sql")),
+        down: Some(include_str!("sql/U9__add_run_priority.sql")),
+    },
+    Migration {
+        version: 10,
+        name: "add_project_max_concurrent_runs",
+        up: Some(include_str!("sql/V10__add_project_max_concurrent_runs.sql")),
+        down: Some(include_str!("sql/U10__add_project_max_concurrent_runs.sql")),
+    },
+    Migration {
+        version: 11,
+        name: "add_project_claim_weight",
+        up: Some(include_str!("sql/V11__add_project_claim_weight.sql")),
+        down: Some(include_str!("sql/U11__add_project_claim_weight.sql")),
+    },
+    Migration {
+        version: 12,
+        name: "add_work_notify_trigger",
+        up: Some(include_str!("sql/V12__add_work_notify_trigger.sql")),
+        down: Some(include_str!("sql/U12__add_work_notify_trigger.sql")),
+    },
+    Migration {
+        version: 13,
+        name: "add_epics",
+        up: Some(include_str!("sql/V13__add_epics.sql")),
+        down: Some(include_str!("sql/U13__add_epics.sql")),
+    },
+    Migration {
+        version: 14,
+        name: "add_claude_run_feedback",
+        up: Some(include_str!("sql/V14__add_claude_run_feedback.sql")),
+        down: Some(include_str!("sql/U14__add_claude_run_feedback.sql")),
+    },
+    Migration {
+        version: 15,
+        name: "add_custom_fields",
+        up: Some(include_str!("sql/V15__add_custom_fields.sql")),
+        down: Some(include_str!("sql/U15__add_custom_fields.sql")),
+    },
+    Migration {
+        version: 16,
+        name: "add_feedback_history",
+        up: Some(include_str!("sql/V16__add_feedback_history.sql")),
+        down: Some(include_str!("sql/U16__add_feedback_history.sql")),
+    },
+    Migration {
+        version: 17,
+        name: "add_task_due_at",
+        up: Some(include_str!("sql/V17__add_task_due_at.sql")),
+        down: Some(include_str!("sql/U17__add_task_due_at.sql")),
+    },
+    Migration {
+        version: 18,
+        name: "add_users",
+        up: Some(include_str!("sql/V18__add_users.sql")),
+        down: Some(include_str!("sql/U18__add_users.sql")),
+    },
+    Migration {
+        version: 19,
+        name: "add_saved_filters",
+        up: Some(include_str!("sql/V19__add_saved_filters.sql")),
+        down: Some(include_str!("sql/U19__add_saved_filters.sql")),
+    },
+    Migration {
+        version: 20,
+        name: "add_attachment_metadata",
+        up: Some(include_str!("sql/V20__add_attachment_metadata.sql")),
+        down: Some(include_str!("sql/U20__add_attachment_metadata.sql")),
+    },
+    Migration {
+        version: 21,
+        name: "add_task_watchers",
+        up: Some(include_str!("sql/V21__add_task_watchers.sql")),
+        down: Some(include_str!("sql/U21__add_task_watchers.sql")),
+    },
+    Migration {
+        version: 22,
+        name: "add_run_idempotency_key",
+        up: Some(include_str!("sql/V22__add_run_idempotency_key.sql")),
+        down: Some(include_str!("sql/U22__add_run_idempotency_key.sql")),
+    },
+    Migration {
+        version: 23,
+        name: "add_archived",
+        up: Some(include_str!("sql/V23__add_archived.sql")),
+        down: Some(include_str!("sql/U23__add_archived.sql")),
+    },
+    Migration {
+        version: 24,
+        name: "add_run_windows",
+        up: Some(include_str!("sql/V24__add_run_windows.sql")),
+        down: Some(include_str!("sql/U24__add_run_windows.sql")),
+    },
+    Migration {
+        version: 25,
+        name: "add_attachment_store_key_index",
+        up: Some(include_str!("sql/V25__add_attachment_store_key_index.sql")),
+        down: Some(include_str!("sql/U25__add_attachment_store_key_index.sql")),
+    },
+    Migration {
+        version: 26,
+        name: "add_project_docs_in_repo",
+        up: Some(include_str!("sql/V26__add_project_docs_in_repo.sql")),
+        down: Some(include_str!("sql/U26__add_project_docs_in_repo.sql")),
+    },
+    Migration {
+        version: 27,
+        name: "add_task_type",
+        up: Some(include_str!("sql/V27__add_task_type.sql")),
+        down: Some(include_str!("sql/U27__add_task_type.sql")),
+    },
+    Migration {
+        version: 28,
+        name: "add_project_verify_followups",
+        up: Some(include_str!("sql/V28__add_project_verify_followups.sql")),
+        down: Some(include_str!("sql/U28__add_project_verify_followups.sql")),
+    },
+    Migration {
+        version: 29,
+        name: "add_webhooks",
+        up: Some(include_str!("sql/V29__add_webhooks.sql")),
+        down: Some(include_str!("sql/U29__add_webhooks.sql")),
+    },
+    Migration {
+        version: 30,
+        name: "add_task_pr_state",
+        up: Some(include_str!("sql/V30__add_task_pr_state.sql")),
+        down: Some(include_str!("sql/U30__add_task_pr_state.sql")),
+    },
+    Migration {
+        version: 31,
+        name: "add_api_key_projects",
+        up: Some(include_str!("sql/V31__add_api_key_projects.sql")),
+        down: Some(include_str!("sql/U31__add_api_key_projects.sql")),
+    },
+    Migration {
+        version: 32,
+        name: "add_task_imports",
+        up: Some(include_str!("sql/V32__add_task_imports.sql")),
+        down: Some(include_str!("sql/U32__add_task_imports.sql")),
+    },
+    Migration {
+        version: 33,
+        name: "add_task_autopilot",
+        up: Some(include_str!("sql/V33__add_task_autopilot.sql")),
+        down: Some(include_str!("sql/U33__add_task_autopilot.sql")),
+    },
+    Migration {
+        version: 34,
+        name: "add_approval_rules",
+        up: Some(include_str!("sql/V34__add_approval_rules.sql")),
+        down: Some(include_str!("sql/U34__add_approval_rules.sql")),
+    },
+    Migration {
+        version: 35,
+        name: "add_subscriptions",
+        up: Some(include_str!("sql/V35__add_subscriptions.sql")),
+        down: Some(include_str!("sql/U35__add_subscriptions.sql")),
+    },
+    Migration {
+        version: 36,
+        name: "add_organizations",
+        up: Some(include_str!("sql/V36__add_organizations.sql")),
+        down: Some(include_str!("sql/U36__add_organizations.sql")),
+    },
+    Migration {
+        version: 37,
+        name: "add_runners",
+        up: Some(include_str!("sql/V37__add_runners.sql")),
+        down: Some(include_str!("sql/U37__add_runners.sql")),
+    },
+    Migration {
+        version: 38,
+        name: "add_project_budgets",
+        up: Some(include_str!("sql/V38__add_project_budgets.sql")),
+        down: Some(include_str!("sql/U38__add_project_budgets.sql")),
+    },
+    Migration {
+        version: 39,
+        name: "add_run_trace_context",
+        up: Some(include_str!("sql/V39__add_run_trace_context.sql")),
+        down: Some(include_str!("sql/U39__add_run_trace_context.sql")),
+    },
+    Migration {
+        version: 40,
+        name: "add_project_ssh_key_path",
+        up: Some(include_str!("sql/V40__add_project_ssh_key_path.sql")),
+        down: Some(include_str!("sql/U40__add_project_ssh_key_path.sql")),
+    },
+    Migration {
+        version: 41,
+        name: "add_task_pr_checks_status",
+        up: Some(include_str!("sql/V41__add_task_pr_checks_status.sql")),
+        down: Some(include_str!("sql/U41__add_task_pr_checks_status.sql")),
+    },
+    Migration {
+        version: 42,
+        name: "add_task_pr_draft",
+        up: Some(include_str!("sql/V42__add_task_pr_draft.sql")),
+        down: Some(include_str!("sql/U42__add_task_pr_draft.sql")),
+    },
+    Migration {
+        version: 43,
+        name: "add_revise_action",
+        up: Some(include_str!("sql/V43__add_revise_action.sql")),
+        down: Some(include_str!("sql/U43__add_revise_action.sql")),
+    },
+    Migration {
+        version: 44,
+        name: "add_project_fork_owner",
+        up: Some(include_str!("sql/V44__add_project_fork_owner.sql")),
+        down: Some(include_str!("sql/U44__add_project_fork_owner.sql")),
+    },
+    Migration {
+        version: 45,
+        name: "add_project_repo_subpath",
+        up: Some(include_str!("sql/V45__add_project_repo_subpath.sql")),
+        down: Some(include_str!("sql/U45__add_project_repo_subpath.sql")),
+    },
+    Migration {
+        version: 46,
+        name: "add_run_window_tz",
+        up: Some(include_str!("sql/V46__add_run_window_tz.sql")),
+        down: Some(include_str!("sql/U46__add_run_window_tz.sql")),
+    },
+];
+
+/// Bring the schema up to the latest version.
 pub async fn run(pool: &PgPool) -> Result<(), DbError> {
+    migrate(pool, None, false).await.map(|_| ())
+}
+
+/// Connect to `url` without auto-migrating and move the schema to `target`,
+/// or only plan the move when `dry_run` is set.
+pub(crate) async fn migrate_url(
+    url: &str,
+    target: Option<i64>,
+    dry_run: bool,
+) -> Result<MigrationPlan, DbError> {
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .connect(url)
+        .await
+        .map_err(pg_err)?;
+    migrate(&pool, target, dry_run).await
+}
+
+async fn migrate(
+    pool: &PgPool,
+    target: Option<i64>,
+    dry_run: bool,
+) -> Result<MigrationPlan, DbError> {
     // Acquire a session-level advisory lock so only one connection migrates
     // at a time.  `pg_advisory_lock` blocks until the lock is available and
     // is automatically released when the session/connection is returned to
@@ -17,7 +325,7 @@ pub async fn run(pool: &PgPool) -> Result<(), DbError> {
         .await
         .map_err(|e| DbError::Internal(e.to_string()))?;
 
-    let result = run_inner(pool).await;
+    let result = run_inner(pool, target, dry_run).await;
 
     // Always release the advisory lock, even on error.
     let _ = sqlx::query("SELECT pg_advisory_unlock($1)")
@@ -28,7 +336,11 @@ pub async fn run(pool: &PgPool) -> Result<(), DbError> {
     result
 }
 
-async fn run_inner(pool: &PgPool) -> Result<(), DbError> {
+async fn run_inner(
+    pool: &PgPool,
+    target: Option<i64>,
+    dry_run: bool,
+) -> Result<MigrationPlan, DbError> {
     // Create schema_version if needed
     sqlx::query(
         "CREATE TABLE IF NOT EXISTS schema_version (
@@ -45,33 +357,21 @@ async fn run_inner(pool: &PgPool) -> Result<(), DbError> {
         .await
         .map_err(|e| DbError::Internal(e.to_string()))?;
 
-    if current < 1 {
-        sqlx::raw_sql(include_str!("sql/V1__initial.sql"))
-            .execute(pool)
-            .await
-            .map_err(|e| DbError::Internal(e.to_string()))?;
-    }
-
-    if current < 2 {
-        sqlx::raw_sql(include_str!("sql/V2__add_required_capability.sql"))
-            .execute(pool)
-            .await
-            .map_err(|e| DbError::Internal(e.to_string()))?;
-    }
-
-    if current < 3 {
-        sqlx::raw_sql(include_str!("sql/V3__add_provider_type.sql"))
-            .execute(pool)
-            .await
-            .map_err(|e| DbError::Internal(e.to_string()))?;
+    let plan = migrate::plan("postgres", MIGRATIONS, current.into(), target)?;
+    if dry_run {
+        return Ok(plan);
     }
 
-    if current < 4 {
-        sqlx::raw_sql(include_str!("sql/V4__add_task_capabilities.sql"))
-            .execute(pool)
+    // Each step runs in its own transaction so a failing script leaves the
+    // schema at the previous version rather than half-applied.
+    for step in &plan.steps {
+        let mut tx = pool.begin().await.map_err(pg_err)?;
+        sqlx::raw_sql(step.sql.unwrap_or_default())
+            .execute(&mut *tx)
             .await
-            .map_err(|e| DbError::Internal(e.to_string()))?;
+            .map_err(|e| DbError::Internal(format!("migration v{}: {e}", step.version)))?;
+        tx.commit().await.map_err(pg_err)?;
     }
 
-    Ok(())
+    Ok(plan)
 }
@@ -0,0 +1,2 @@
+ALTER TABLE projects DROP COLUMN IF EXISTS max_concurrent_runs;
+DELETE FROM schema_version WHERE version = 10;
@@ -0,0 +1,2 @@
+ALTER TABLE projects DROP COLUMN IF EXISTS claim_weight;
+DELETE FROM schema_version WHERE version = 11;
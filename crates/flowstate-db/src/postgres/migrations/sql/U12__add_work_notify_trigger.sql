@@ -0,0 +1,3 @@
+DROP TRIGGER IF EXISTS claude_runs_notify_work ON claude_runs;
+DROP FUNCTION IF EXISTS flowstate_notify_work();
+DELETE FROM schema_version WHERE version = 12;
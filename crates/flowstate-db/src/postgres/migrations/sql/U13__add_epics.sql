@@ -0,0 +1,3 @@
+ALTER TABLE tasks DROP COLUMN IF EXISTS epic_id;
+DROP TABLE IF EXISTS epics;
+DELETE FROM schema_version WHERE version = 13;
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS task_field_values;
+DROP TABLE IF EXISTS custom_fields;
+DELETE FROM schema_version WHERE version = 15;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS feedback_history;
+DELETE FROM schema_version WHERE version = 16;
@@ -0,0 +1,2 @@
+ALTER TABLE tasks DROP COLUMN IF EXISTS due_at;
+DELETE FROM schema_version WHERE version = 17;
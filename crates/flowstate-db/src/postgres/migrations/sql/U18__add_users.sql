@@ -0,0 +1,3 @@
+ALTER TABLE tasks DROP COLUMN IF EXISTS assignee_id;
+DROP TABLE IF EXISTS users;
+DELETE FROM schema_version WHERE version = 18;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS saved_filters;
+DELETE FROM schema_version WHERE version = 19;
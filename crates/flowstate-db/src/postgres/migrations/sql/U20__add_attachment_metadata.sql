@@ -0,0 +1,3 @@
+ALTER TABLE attachments DROP COLUMN IF EXISTS sha256;
+ALTER TABLE attachments DROP COLUMN IF EXISTS content_type;
+DELETE FROM schema_version WHERE version = 20;
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS notifications;
+DROP TABLE IF EXISTS task_watchers;
+DELETE FROM schema_version WHERE version = 21;
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_claude_runs_idempotency;
+ALTER TABLE claude_runs DROP COLUMN IF EXISTS idempotency_key;
+DELETE FROM schema_version WHERE version = 22;
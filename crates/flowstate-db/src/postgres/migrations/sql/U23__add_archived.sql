@@ -0,0 +1,3 @@
+ALTER TABLE tasks DROP COLUMN IF EXISTS archived;
+ALTER TABLE sprints DROP COLUMN IF EXISTS archived;
+DELETE FROM schema_version WHERE version = 23;
@@ -0,0 +1,7 @@
+ALTER TABLE projects DROP COLUMN IF EXISTS run_window_start;
+ALTER TABLE projects DROP COLUMN IF EXISTS run_window_end;
+ALTER TABLE projects DROP COLUMN IF EXISTS run_window_offset;
+ALTER TABLE claude_runs DROP COLUMN IF EXISTS run_window_start;
+ALTER TABLE claude_runs DROP COLUMN IF EXISTS run_window_end;
+ALTER TABLE claude_runs DROP COLUMN IF EXISTS run_window_offset;
+DELETE FROM schema_version WHERE version = 24;
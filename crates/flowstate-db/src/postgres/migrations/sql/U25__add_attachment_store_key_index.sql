@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_attachments_store_key;
+DELETE FROM schema_version WHERE version = 25;
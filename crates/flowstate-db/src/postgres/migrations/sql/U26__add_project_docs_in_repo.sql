@@ -0,0 +1,2 @@
+ALTER TABLE projects DROP COLUMN IF EXISTS docs_in_repo;
+DELETE FROM schema_version WHERE version = 26;
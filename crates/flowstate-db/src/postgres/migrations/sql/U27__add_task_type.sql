@@ -0,0 +1,2 @@
+ALTER TABLE tasks DROP COLUMN IF EXISTS task_type;
+DELETE FROM schema_version WHERE version = 27;
@@ -0,0 +1,2 @@
+ALTER TABLE projects DROP COLUMN IF EXISTS verify_followups;
+DELETE FROM schema_version WHERE version = 28;
@@ -0,0 +1,2 @@
+ALTER TABLE claude_runs DROP COLUMN IF EXISTS required_capability;
+DELETE FROM schema_version WHERE version = 2;
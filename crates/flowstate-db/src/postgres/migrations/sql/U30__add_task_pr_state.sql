@@ -0,0 +1,2 @@
+ALTER TABLE task_prs DROP COLUMN IF EXISTS state;
+DELETE FROM schema_version WHERE version = 30;
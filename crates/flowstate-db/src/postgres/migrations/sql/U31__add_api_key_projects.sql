@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS api_key_projects;
+DELETE FROM schema_version WHERE version = 31;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS task_imports;
+DELETE FROM schema_version WHERE version = 32;
@@ -0,0 +1,2 @@
+ALTER TABLE tasks DROP COLUMN IF EXISTS autopilot;
+DELETE FROM schema_version WHERE version = 33;
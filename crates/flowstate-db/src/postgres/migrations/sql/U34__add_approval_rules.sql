@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS approval_rules;
+DELETE FROM schema_version WHERE version = 34;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS subscriptions;
+DELETE FROM schema_version WHERE version = 35;
@@ -0,0 +1,5 @@
+ALTER TABLE api_keys DROP COLUMN IF EXISTS org_id;
+DROP INDEX IF EXISTS idx_projects_org;
+ALTER TABLE projects DROP COLUMN IF EXISTS org_id;
+DROP TABLE IF EXISTS organizations;
+DELETE FROM schema_version WHERE version = 36;
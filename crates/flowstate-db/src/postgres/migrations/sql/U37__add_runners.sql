@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS runners;
+DELETE FROM schema_version WHERE version = 37;
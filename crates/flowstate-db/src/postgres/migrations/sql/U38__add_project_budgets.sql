@@ -0,0 +1,3 @@
+ALTER TABLE projects DROP COLUMN IF EXISTS budget_override_until;
+ALTER TABLE projects DROP COLUMN IF EXISTS monthly_budget_usd;
+DELETE FROM schema_version WHERE version = 38;
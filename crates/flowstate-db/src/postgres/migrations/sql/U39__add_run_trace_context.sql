@@ -0,0 +1,2 @@
+ALTER TABLE claude_runs DROP COLUMN IF EXISTS trace_context;
+DELETE FROM schema_version WHERE version = 39;
@@ -0,0 +1,3 @@
+ALTER TABLE projects DROP COLUMN IF EXISTS skip_tls_verify;
+ALTER TABLE projects DROP COLUMN IF EXISTS provider_type;
+DELETE FROM schema_version WHERE version = 3;
@@ -0,0 +1,2 @@
+ALTER TABLE projects DROP COLUMN IF EXISTS ssh_key_path;
+DELETE FROM schema_version WHERE version = 40;
@@ -0,0 +1,2 @@
+ALTER TABLE task_prs DROP COLUMN IF EXISTS checks_status;
+DELETE FROM schema_version WHERE version = 41;
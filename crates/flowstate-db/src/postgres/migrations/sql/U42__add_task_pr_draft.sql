@@ -0,0 +1,2 @@
+ALTER TABLE task_prs DROP COLUMN IF EXISTS draft;
+DELETE FROM schema_version WHERE version = 42;
@@ -0,0 +1,7 @@
+DELETE FROM claude_runs WHERE action = 'revise';
+ALTER TABLE claude_runs DROP CONSTRAINT claude_runs_action_check;
+ALTER TABLE claude_runs ADD CONSTRAINT claude_runs_action_check CHECK(action IN (
+    'research', 'design', 'plan', 'build', 'verify',
+    'research_distill', 'design_distill', 'plan_distill', 'verify_distill'
+));
+DELETE FROM schema_version WHERE version = 43;
@@ -0,0 +1,2 @@
+ALTER TABLE projects DROP COLUMN IF EXISTS fork_owner;
+DELETE FROM schema_version WHERE version = 44;
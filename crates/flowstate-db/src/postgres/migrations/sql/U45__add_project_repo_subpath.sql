@@ -0,0 +1,2 @@
+ALTER TABLE projects DROP COLUMN IF EXISTS repo_subpath;
+DELETE FROM schema_version WHERE version = 45;
@@ -0,0 +1,3 @@
+ALTER TABLE projects DROP COLUMN IF EXISTS run_window_tz;
+ALTER TABLE claude_runs DROP COLUMN IF EXISTS run_window_tz;
+DELETE FROM schema_version WHERE version = 46;
@@ -0,0 +1,6 @@
+ALTER TABLE tasks DROP COLUMN IF EXISTS verify_capability;
+ALTER TABLE tasks DROP COLUMN IF EXISTS build_capability;
+ALTER TABLE tasks DROP COLUMN IF EXISTS plan_capability;
+ALTER TABLE tasks DROP COLUMN IF EXISTS design_capability;
+ALTER TABLE tasks DROP COLUMN IF EXISTS research_capability;
+DELETE FROM schema_version WHERE version = 4;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS task_revisions;
+DELETE FROM schema_version WHERE version = 5;
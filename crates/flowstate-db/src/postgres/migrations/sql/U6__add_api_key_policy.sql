@@ -0,0 +1,3 @@
+ALTER TABLE api_keys DROP COLUMN IF EXISTS allowed_routes;
+ALTER TABLE api_keys DROP COLUMN IF EXISTS allowed_cidrs;
+DELETE FROM schema_version WHERE version = 6;
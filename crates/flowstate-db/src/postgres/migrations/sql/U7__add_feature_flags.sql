@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS feature_flags;
+DELETE FROM schema_version WHERE version = 7;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS run_metrics;
+DELETE FROM schema_version WHERE version = 8;
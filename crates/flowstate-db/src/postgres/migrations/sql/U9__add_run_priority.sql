@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_claude_runs_queue;
+ALTER TABLE claude_runs DROP COLUMN IF EXISTS priority;
+DELETE FROM schema_version WHERE version = 9;
@@ -0,0 +1,2 @@
+ALTER TABLE projects ADD COLUMN max_concurrent_runs INTEGER;
+INSERT INTO schema_version (version, applied_at) VALUES (10, NOW());
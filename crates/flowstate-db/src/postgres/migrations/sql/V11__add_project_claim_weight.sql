@@ -0,0 +1,2 @@
+ALTER TABLE projects ADD COLUMN claim_weight INTEGER NOT NULL DEFAULT 1;
+INSERT INTO schema_version (version, applied_at) VALUES (11, NOW());
@@ -0,0 +1,14 @@
+-- Wake long-polling runners (via the server's LISTEN) whenever a run becomes claimable.
+CREATE OR REPLACE FUNCTION flowstate_notify_work() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify('flowstate_work', NEW.id);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER claude_runs_notify_work
+    AFTER INSERT OR UPDATE OF status ON claude_runs
+    FOR EACH ROW WHEN (NEW.status = 'queued')
+    EXECUTE FUNCTION flowstate_notify_work();
+
+INSERT INTO schema_version (version, applied_at) VALUES (12, NOW());
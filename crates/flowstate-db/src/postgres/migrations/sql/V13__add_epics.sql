@@ -0,0 +1,15 @@
+CREATE TABLE epics (
+    id          TEXT PRIMARY KEY,
+    project_id  TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    name        TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    status      TEXT NOT NULL DEFAULT 'open'
+                    CHECK(status IN ('open', 'in_progress', 'done', 'cancelled')),
+    target_date TIMESTAMPTZ,
+    created_at  TIMESTAMPTZ NOT NULL,
+    updated_at  TIMESTAMPTZ NOT NULL
+);
+CREATE INDEX idx_epics_project ON epics(project_id);
+ALTER TABLE tasks ADD COLUMN epic_id TEXT REFERENCES epics(id) ON DELETE SET NULL;
+CREATE INDEX idx_tasks_epic ON tasks(epic_id);
+INSERT INTO schema_version (version, applied_at) VALUES (13, NOW());
@@ -0,0 +1,2 @@
+ALTER TABLE claude_runs ADD COLUMN feedback TEXT;
+INSERT INTO schema_version (version, applied_at) VALUES (14, NOW());
@@ -0,0 +1,18 @@
+CREATE TABLE custom_fields (
+    id         TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    name       TEXT NOT NULL,
+    field_type TEXT NOT NULL CHECK(field_type IN ('text', 'number', 'enum', 'date')),
+    options    TEXT NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    UNIQUE(project_id, name)
+);
+CREATE TABLE task_field_values (
+    task_id  TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    field_id TEXT NOT NULL REFERENCES custom_fields(id) ON DELETE CASCADE,
+    value    TEXT NOT NULL,
+    PRIMARY KEY (task_id, field_id)
+);
+CREATE INDEX idx_task_field_values_field ON task_field_values(field_id, value);
+INSERT INTO schema_version (version, applied_at) VALUES (15, NOW());
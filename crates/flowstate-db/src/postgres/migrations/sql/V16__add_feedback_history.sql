@@ -0,0 +1,10 @@
+CREATE TABLE feedback_history (
+    id         TEXT PRIMARY KEY,
+    task_id    TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    phase      TEXT NOT NULL CHECK(phase IN ('research', 'design', 'plan', 'verify')),
+    feedback   TEXT NOT NULL,
+    author     TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMPTZ NOT NULL
+);
+CREATE INDEX idx_feedback_history_task ON feedback_history(task_id, created_at);
+INSERT INTO schema_version (version, applied_at) VALUES (16, NOW());
@@ -0,0 +1,3 @@
+ALTER TABLE tasks ADD COLUMN due_at TIMESTAMPTZ;
+CREATE INDEX idx_tasks_due ON tasks(due_at);
+INSERT INTO schema_version (version, applied_at) VALUES (17, NOW());
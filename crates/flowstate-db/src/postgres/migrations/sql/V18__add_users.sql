@@ -0,0 +1,11 @@
+CREATE TABLE users (
+    id         TEXT PRIMARY KEY,
+    name       TEXT NOT NULL,
+    email      TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+CREATE UNIQUE INDEX idx_users_email ON users(email) WHERE email != '';
+ALTER TABLE tasks ADD COLUMN assignee_id TEXT REFERENCES users(id) ON DELETE SET NULL;
+CREATE INDEX idx_tasks_assignee ON tasks(assignee_id);
+INSERT INTO schema_version (version, applied_at) VALUES (18, NOW());
@@ -0,0 +1,11 @@
+CREATE TABLE saved_filters (
+    id         TEXT PRIMARY KEY,
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    user_id    TEXT REFERENCES users(id) ON DELETE CASCADE,
+    name       TEXT NOT NULL,
+    query      TEXT NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL
+);
+CREATE INDEX idx_saved_filters_project ON saved_filters(project_id, user_id);
+INSERT INTO schema_version (version, applied_at) VALUES (19, NOW());
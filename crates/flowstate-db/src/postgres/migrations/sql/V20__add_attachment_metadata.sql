@@ -0,0 +1,3 @@
+ALTER TABLE attachments ADD COLUMN content_type TEXT NOT NULL DEFAULT '';
+ALTER TABLE attachments ADD COLUMN sha256 TEXT NOT NULL DEFAULT '';
+INSERT INTO schema_version (version, applied_at) VALUES (20, NOW());
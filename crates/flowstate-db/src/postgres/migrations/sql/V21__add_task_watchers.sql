@@ -0,0 +1,18 @@
+CREATE TABLE task_watchers (
+    task_id    TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    user_id    TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (task_id, user_id)
+);
+CREATE INDEX idx_task_watchers_user ON task_watchers(user_id);
+CREATE TABLE notifications (
+    id         TEXT PRIMARY KEY,
+    user_id    TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    task_id    TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    actor      TEXT NOT NULL DEFAULT '',
+    changes    TEXT NOT NULL DEFAULT '[]',
+    read       BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL
+);
+CREATE INDEX idx_notifications_user ON notifications(user_id, read);
+INSERT INTO schema_version (version, applied_at) VALUES (21, NOW());
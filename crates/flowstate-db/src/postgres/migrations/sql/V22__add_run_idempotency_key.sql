@@ -0,0 +1,5 @@
+ALTER TABLE claude_runs ADD COLUMN idempotency_key TEXT;
+CREATE UNIQUE INDEX idx_claude_runs_idempotency
+    ON claude_runs(task_id, idempotency_key)
+    WHERE idempotency_key IS NOT NULL;
+INSERT INTO schema_version (version, applied_at) VALUES (22, NOW());
@@ -0,0 +1,3 @@
+ALTER TABLE tasks ADD COLUMN archived BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE sprints ADD COLUMN archived BOOLEAN NOT NULL DEFAULT FALSE;
+INSERT INTO schema_version (version, applied_at) VALUES (23, NOW());
@@ -0,0 +1,7 @@
+ALTER TABLE projects ADD COLUMN run_window_start INTEGER;
+ALTER TABLE projects ADD COLUMN run_window_end INTEGER;
+ALTER TABLE projects ADD COLUMN run_window_offset INTEGER;
+ALTER TABLE claude_runs ADD COLUMN run_window_start INTEGER;
+ALTER TABLE claude_runs ADD COLUMN run_window_end INTEGER;
+ALTER TABLE claude_runs ADD COLUMN run_window_offset INTEGER;
+INSERT INTO schema_version (version, applied_at) VALUES (24, NOW());
@@ -0,0 +1,2 @@
+CREATE INDEX IF NOT EXISTS idx_attachments_store_key ON attachments(store_key);
+INSERT INTO schema_version (version, applied_at) VALUES (25, NOW());
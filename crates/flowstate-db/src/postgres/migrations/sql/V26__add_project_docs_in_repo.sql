@@ -0,0 +1,2 @@
+ALTER TABLE projects ADD COLUMN docs_in_repo BOOLEAN NOT NULL DEFAULT FALSE;
+INSERT INTO schema_version (version, applied_at) VALUES (26, NOW());
@@ -0,0 +1,2 @@
+ALTER TABLE tasks ADD COLUMN task_type TEXT NOT NULL DEFAULT 'feature';
+INSERT INTO schema_version (version, applied_at) VALUES (27, NOW());
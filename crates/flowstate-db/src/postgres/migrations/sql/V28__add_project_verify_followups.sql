@@ -0,0 +1,2 @@
+ALTER TABLE projects ADD COLUMN verify_followups BOOLEAN NOT NULL DEFAULT FALSE;
+INSERT INTO schema_version (version, applied_at) VALUES (28, NOW());
@@ -0,0 +1,2 @@
+ALTER TABLE task_prs ADD COLUMN state TEXT NOT NULL DEFAULT 'open';
+INSERT INTO schema_version (version, applied_at) VALUES (30, NOW());
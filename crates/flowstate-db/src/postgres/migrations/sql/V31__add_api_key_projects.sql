@@ -0,0 +1,6 @@
+CREATE TABLE api_key_projects (
+    api_key_id TEXT NOT NULL REFERENCES api_keys(id) ON DELETE CASCADE,
+    project_id TEXT NOT NULL,
+    PRIMARY KEY (api_key_id, project_id)
+);
+INSERT INTO schema_version (version, applied_at) VALUES (31, NOW());
@@ -0,0 +1,8 @@
+CREATE TABLE task_imports (
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    source     TEXT NOT NULL,
+    task_id    TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (project_id, source)
+);
+INSERT INTO schema_version (version, applied_at) VALUES (32, NOW());
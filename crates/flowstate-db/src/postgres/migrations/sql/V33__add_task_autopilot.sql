@@ -0,0 +1,2 @@
+ALTER TABLE tasks ADD COLUMN autopilot BOOLEAN NOT NULL DEFAULT FALSE;
+INSERT INTO schema_version (version, applied_at) VALUES (33, NOW());
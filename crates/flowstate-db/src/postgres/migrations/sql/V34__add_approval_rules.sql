@@ -0,0 +1,8 @@
+CREATE TABLE approval_rules (
+    project_id TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    phase      TEXT NOT NULL,
+    policy     TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (project_id, phase)
+);
+INSERT INTO schema_version (version, applied_at) VALUES (34, NOW());
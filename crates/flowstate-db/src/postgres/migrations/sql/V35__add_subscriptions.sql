@@ -0,0 +1,10 @@
+CREATE TABLE subscriptions (
+    id         TEXT PRIMARY KEY,
+    channel    TEXT NOT NULL,
+    target     TEXT NOT NULL DEFAULT '',
+    events     TEXT NOT NULL DEFAULT '[]',
+    project_id TEXT REFERENCES projects(id) ON DELETE CASCADE,
+    user_id    TEXT REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL
+);
+INSERT INTO schema_version (version, applied_at) VALUES (35, NOW());
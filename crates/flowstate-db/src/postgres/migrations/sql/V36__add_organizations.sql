@@ -0,0 +1,10 @@
+CREATE TABLE organizations (
+    id         TEXT PRIMARY KEY,
+    name       TEXT NOT NULL,
+    slug       TEXT NOT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL
+);
+ALTER TABLE projects ADD COLUMN org_id TEXT REFERENCES organizations(id);
+CREATE INDEX idx_projects_org ON projects(org_id);
+ALTER TABLE api_keys ADD COLUMN org_id TEXT REFERENCES organizations(id) ON DELETE CASCADE;
+INSERT INTO schema_version (version, applied_at) VALUES (36, NOW());
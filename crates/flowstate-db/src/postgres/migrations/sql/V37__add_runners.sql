@@ -0,0 +1,17 @@
+CREATE TABLE runners (
+    runner_id      TEXT PRIMARY KEY,
+    org_id         TEXT,
+    backend_name   TEXT,
+    capability     TEXT,
+    poll_interval  BIGINT,
+    max_concurrent BIGINT,
+    max_builds     BIGINT,
+    active_count   BIGINT,
+    active_builds  BIGINT,
+    status         TEXT NOT NULL DEFAULT 'active',
+    pending_config TEXT,
+    benchmark      TEXT,
+    first_seen_at  TIMESTAMPTZ NOT NULL,
+    last_seen_at   TIMESTAMPTZ NOT NULL
+);
+INSERT INTO schema_version (version, applied_at) VALUES (37, NOW());
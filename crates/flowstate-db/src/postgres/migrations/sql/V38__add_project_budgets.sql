@@ -0,0 +1,3 @@
+ALTER TABLE projects ADD COLUMN monthly_budget_usd DOUBLE PRECISION;
+ALTER TABLE projects ADD COLUMN budget_override_until TIMESTAMPTZ;
+INSERT INTO schema_version (version, applied_at) VALUES (38, NOW());
@@ -0,0 +1,2 @@
+ALTER TABLE claude_runs ADD COLUMN trace_context TEXT;
+INSERT INTO schema_version (version, applied_at) VALUES (39, NOW());
@@ -0,0 +1,2 @@
+ALTER TABLE projects ADD COLUMN ssh_key_path TEXT;
+INSERT INTO schema_version (version, applied_at) VALUES (40, NOW());
@@ -0,0 +1,2 @@
+ALTER TABLE task_prs ADD COLUMN checks_status TEXT;
+INSERT INTO schema_version (version, applied_at) VALUES (41, NOW());
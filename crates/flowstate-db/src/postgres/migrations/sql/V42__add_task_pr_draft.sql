@@ -0,0 +1,2 @@
+ALTER TABLE task_prs ADD COLUMN draft BOOLEAN NOT NULL DEFAULT FALSE;
+INSERT INTO schema_version (version, applied_at) VALUES (42, NOW());
@@ -0,0 +1,7 @@
+ALTER TABLE claude_runs DROP CONSTRAINT claude_runs_action_check;
+ALTER TABLE claude_runs ADD CONSTRAINT claude_runs_action_check CHECK(action IN (
+    'research', 'design', 'plan', 'build', 'verify',
+    'research_distill', 'design_distill', 'plan_distill', 'verify_distill',
+    'revise'
+));
+INSERT INTO schema_version (version, applied_at) VALUES (43, NOW());
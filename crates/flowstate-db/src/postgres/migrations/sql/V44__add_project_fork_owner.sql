@@ -0,0 +1,2 @@
+ALTER TABLE projects ADD COLUMN fork_owner TEXT;
+INSERT INTO schema_version (version, applied_at) VALUES (44, NOW());
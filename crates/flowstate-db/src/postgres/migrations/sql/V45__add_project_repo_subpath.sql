@@ -0,0 +1,2 @@
+ALTER TABLE projects ADD COLUMN repo_subpath TEXT;
+INSERT INTO schema_version (version, applied_at) VALUES (45, NOW());
@@ -0,0 +1,3 @@
+ALTER TABLE projects ADD COLUMN run_window_tz TEXT;
+ALTER TABLE claude_runs ADD COLUMN run_window_tz TEXT;
+INSERT INTO schema_version (version, applied_at) VALUES (46, NOW());
@@ -0,0 +1,9 @@
+CREATE TABLE task_revisions (
+    id         TEXT PRIMARY KEY,
+    task_id    TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+    actor      TEXT NOT NULL DEFAULT '',
+    changes    TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL
+);
+CREATE INDEX idx_task_revisions_task ON task_revisions(task_id, created_at);
+INSERT INTO schema_version (version, applied_at) VALUES (5, NOW());
@@ -0,0 +1,3 @@
+ALTER TABLE api_keys ADD COLUMN allowed_cidrs TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE api_keys ADD COLUMN allowed_routes TEXT NOT NULL DEFAULT '[]';
+INSERT INTO schema_version (version, applied_at) VALUES (6, NOW());
@@ -0,0 +1,8 @@
+CREATE TABLE feature_flags (
+    key        TEXT NOT NULL,
+    project_id TEXT NOT NULL DEFAULT '',
+    enabled    BOOLEAN NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (key, project_id)
+);
+INSERT INTO schema_version (version, applied_at) VALUES (7, NOW());
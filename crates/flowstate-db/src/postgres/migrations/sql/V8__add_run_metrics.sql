@@ -0,0 +1,11 @@
+CREATE TABLE run_metrics (
+    run_id        TEXT PRIMARY KEY REFERENCES claude_runs(id) ON DELETE CASCADE,
+    duration_ms   BIGINT NOT NULL,
+    stdout_bytes  BIGINT NOT NULL,
+    input_tokens  BIGINT,
+    output_tokens BIGINT,
+    cost_usd      DOUBLE PRECISION,
+    recorded_at   TIMESTAMPTZ NOT NULL
+);
+CREATE INDEX idx_run_metrics_recorded ON run_metrics(recorded_at);
+INSERT INTO schema_version (version, applied_at) VALUES (8, NOW());
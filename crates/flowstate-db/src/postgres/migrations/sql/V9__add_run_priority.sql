@@ -0,0 +1,3 @@
+ALTER TABLE claude_runs ADD COLUMN priority INTEGER NOT NULL DEFAULT 0;
+CREATE INDEX idx_claude_runs_queue ON claude_runs(status, priority DESC, started_at);
+INSERT INTO schema_version (version, applied_at) VALUES (9, NOW());
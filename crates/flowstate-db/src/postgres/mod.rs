@@ -1,21 +1,48 @@
 pub(crate) mod migrations;
 pub mod queries;
 
+use std::sync::Arc;
+use std::time::Duration;
+
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
-use sqlx::postgres::PgPoolOptions;
+use sqlx::postgres::{PgListener, PgPoolOptions};
 use sqlx::PgPool;
+use tokio::sync::Notify;
 
 use flowstate_core::api_key::ApiKey;
+use flowstate_core::approval_rule::{ApprovalPhase, ApprovalPolicy, ApprovalRule};
 use flowstate_core::attachment::Attachment;
-use flowstate_core::claude_run::{ClaudeRun, ClaudeRunStatus, CreateClaudeRun};
+use flowstate_core::claude_run::{ClaudeAction, ClaudeRun, ClaudeRunStatus, CreateClaudeRun};
+use flowstate_core::custom_field::{
+    CreateCustomField, CustomField, TaskFieldValue, UpdateCustomField,
+};
+use flowstate_core::dashboard::ProjectDashboard;
+use flowstate_core::epic::{CreateEpic, Epic, UpdateEpic};
+use flowstate_core::feature_flag::FeatureFlag;
+use flowstate_core::feedback::FeedbackEntry;
+use flowstate_core::label::Label;
+use flowstate_core::notification::Notification;
+use flowstate_core::organization::{CreateOrganization, Organization};
 use flowstate_core::project::{CreateProject, Project, UpdateProject};
+use flowstate_core::run_metrics::{
+    RecordRunMetrics, RunMetrics, RunMetricsFilter, RunMetricsSummary,
+};
+use flowstate_core::runner::{PendingRunnerConfig, RunnerRecord};
+use flowstate_core::saved_filter::{CreateSavedFilter, SavedFilter, UpdateSavedFilter};
 use flowstate_core::sprint::{CreateSprint, Sprint, UpdateSprint};
+use flowstate_core::subscription::{CreateSubscription, Subscription};
 use flowstate_core::task::{CreateTask, Task, TaskFilter, UpdateTask};
 use flowstate_core::task_link::{CreateTaskLink, TaskLink};
-use flowstate_core::task_pr::{CreateTaskPr, TaskPr};
+use flowstate_core::task_pr::{ChecksStatus, CreateTaskPr, PrState, TaskPr};
+use flowstate_core::task_revision::TaskRevision;
+use flowstate_core::user::{CreateUser, UpdateUser, User};
+use flowstate_core::webhook::{
+    CreateWebhook, DeliveryAttempt, UpdateWebhook, Webhook, WebhookDelivery, WebhookEvent,
+};
 
-use crate::{Database, DbError};
+use crate::query::SqlValue;
+use crate::{Database, DbError, DbStats, MaintenanceReport, QueueDemand, Snapshot};
 
 /// Map a sqlx::Error into a DbError::Internal.
 pub(crate) fn pg_err(e: sqlx::Error) -> DbError {
@@ -27,13 +54,34 @@ pub(crate) fn pg_not_found(entity: &str) -> DbError {
     DbError::NotFound(entity.to_string())
 }
 
+/// Bind a built query's values in placeholder order.
+pub(crate) fn bind_values<'q, O>(
+    mut query: sqlx::query::QueryAs<'q, sqlx::Postgres, O, sqlx::postgres::PgArguments>,
+    values: &'q [SqlValue],
+) -> sqlx::query::QueryAs<'q, sqlx::Postgres, O, sqlx::postgres::PgArguments> {
+    for value in values {
+        query = match value {
+            SqlValue::Text(s) => query.bind(s.as_str()),
+            SqlValue::Int(n) => query.bind(*n),
+            SqlValue::Bool(b) => query.bind(*b),
+            SqlValue::Timestamp(t) => query.bind(*t),
+        };
+    }
+    query
+}
+
+/// Channel the `claude_runs_notify_work` trigger notifies on (migration V12).
+const WORK_CHANNEL: &str = "flowstate_work";
+
 #[derive(Clone)]
 pub struct PostgresDatabase {
     pub(crate) pool: PgPool,
+    work: Arc<Notify>,
 }
 
 impl PostgresDatabase {
-    /// Connect to a Postgres database and run migrations.
+    /// Connect to a Postgres database, run migrations and start listening
+    /// for newly queued runs.
     pub async fn connect(url: &str) -> Result<Self, DbError> {
         let pool = PgPoolOptions::new()
             .max_connections(10)
@@ -41,14 +89,40 @@ impl PostgresDatabase {
             .await
             .map_err(pg_err)?;
 
-        let db = Self { pool };
+        let db = Self {
+            pool,
+            work: Arc::new(Notify::new()),
+        };
         migrations::run(&db.pool).await?;
+
+        let mut listener = PgListener::connect_with(&db.pool).await.map_err(pg_err)?;
+        listener.listen(WORK_CHANNEL).await.map_err(pg_err)?;
+        tokio::spawn(listen_for_work(listener, db.work.clone()));
         Ok(db)
     }
 }
 
+/// Forward `NOTIFY flowstate_work` from any server sharing this database to
+/// local claim waiters. Notifications sent while the listener is reconnecting
+/// are lost, so waiters are also woken after a dropped connection to re-check.
+async fn listen_for_work(mut listener: PgListener, work: Arc<Notify>) {
+    loop {
+        match listener.try_recv().await {
+            Ok(Some(_)) | Ok(None) => work.notify_waiters(),
+            Err(e) => {
+                tracing::warn!("work listener error: {e}");
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    }
+}
+
 #[async_trait]
 impl Database for PostgresDatabase {
+    fn work_available(&self) -> Arc<Notify> {
+        self.work.clone()
+    }
+
     // -- Projects --
     async fn create_project(&self, input: &CreateProject) -> Result<Project, DbError> {
         self.pg_create_project(input).await
@@ -68,6 +142,9 @@ impl Database for PostgresDatabase {
     async fn delete_project(&self, id: &str) -> Result<(), DbError> {
         self.pg_delete_project(id).await
     }
+    async fn project_dashboard(&self, project_id: &str) -> Result<ProjectDashboard, DbError> {
+        self.pg_project_dashboard(project_id).await
+    }
 
     // -- Tasks --
     async fn create_task(&self, input: &CreateTask) -> Result<Task, DbError> {
@@ -91,8 +168,41 @@ impl Database for PostgresDatabase {
     async fn count_tasks_by_status(&self, project_id: &str) -> Result<Vec<(String, i64)>, DbError> {
         self.pg_count_tasks_by_status(project_id).await
     }
+    async fn bulk_create_tasks(&self, inputs: &[CreateTask]) -> Result<Vec<Task>, DbError> {
+        self.pg_bulk_create_tasks(inputs).await
+    }
+    async fn bulk_update_tasks(
+        &self,
+        ids: &[String],
+        update: &UpdateTask,
+    ) -> Result<Vec<Task>, DbError> {
+        self.pg_bulk_update_tasks(ids, update).await
+    }
+    async fn reorder_task(&self, id: &str, after_id: Option<&str>) -> Result<Task, DbError> {
+        self.pg_reorder_task(id, after_id).await
+    }
+    async fn archive_task(&self, id: &str) -> Result<Task, DbError> {
+        self.pg_archive_task(id).await
+    }
+    async fn list_task_revisions(&self, task_id: &str) -> Result<Vec<TaskRevision>, DbError> {
+        self.pg_list_task_revisions(task_id).await
+    }
+    async fn list_feedback_history(&self, task_id: &str) -> Result<Vec<FeedbackEntry>, DbError> {
+        self.pg_list_feedback_history(task_id).await
+    }
+    async fn add_feedback_entry(
+        &self,
+        task_id: &str,
+        phase: &str,
+        feedback: &str,
+        author: &str,
+    ) -> Result<FeedbackEntry, DbError> {
+        self.pg_add_feedback_entry(task_id, phase, feedback, author)
+            .await
+    }
 
     // -- Claude Runs --
+    #[tracing::instrument(name = "db.create_claude_run", skip_all, fields(db.system = "postgresql", task.id = %input.task_id))]
     async fn create_claude_run(&self, input: &CreateClaudeRun) -> Result<ClaudeRun, DbError> {
         self.pg_create_claude_run(input).await
     }
@@ -102,6 +212,7 @@ impl Database for PostgresDatabase {
     async fn list_claude_runs_for_task(&self, task_id: &str) -> Result<Vec<ClaudeRun>, DbError> {
         self.pg_list_claude_runs_for_task(task_id).await
     }
+    #[tracing::instrument(name = "db.update_claude_run_status", skip_all, fields(db.system = "postgresql", run.id = %id))]
     async fn update_claude_run_status(
         &self,
         id: &str,
@@ -112,11 +223,15 @@ impl Database for PostgresDatabase {
         self.pg_update_claude_run_status(id, status, error_message, exit_code)
             .await
     }
-    async fn claim_next_claude_run(
+    #[tracing::instrument(name = "db.claim_next_claude_run_scoped", skip_all, fields(db.system = "postgresql"))]
+    async fn claim_next_claude_run_scoped(
         &self,
         capabilities: &[&str],
+        skip: &[ClaudeAction],
+        projects: &[String],
     ) -> Result<Option<ClaudeRun>, DbError> {
-        self.pg_claim_next_claude_run(capabilities).await
+        self.pg_claim_next_claude_run(capabilities, skip, projects)
+            .await
     }
     async fn update_claude_run_progress(&self, id: &str, message: &str) -> Result<(), DbError> {
         self.pg_update_claude_run_progress(id, message).await
@@ -150,12 +265,49 @@ impl Database for PostgresDatabase {
     ) -> Result<Option<ClaudeRun>, DbError> {
         self.pg_timeout_claude_run(id, error_message).await
     }
+    #[tracing::instrument(name = "db.set_claude_run_runner", skip_all, fields(db.system = "postgresql", run.id = %id))]
     async fn set_claude_run_runner(&self, id: &str, runner_id: &str) -> Result<(), DbError> {
         self.pg_set_claude_run_runner(id, runner_id).await
     }
+    async fn requeue_claude_run(&self, id: &str) -> Result<Option<ClaudeRun>, DbError> {
+        self.pg_requeue_claude_run(id).await
+    }
+    async fn set_claude_run_priority(
+        &self,
+        id: &str,
+        priority: i32,
+    ) -> Result<Option<ClaudeRun>, DbError> {
+        self.pg_set_claude_run_priority(id, priority).await
+    }
     async fn count_queued_runs(&self) -> Result<i64, DbError> {
         self.pg_count_queued_runs().await
     }
+    async fn queued_run_demand(&self) -> Result<Vec<QueueDemand>, DbError> {
+        self.pg_queued_run_demand().await
+    }
+    async fn delete_runs_older_than(&self, cutoff: DateTime<Utc>) -> Result<Vec<String>, DbError> {
+        self.pg_delete_runs_older_than(cutoff).await
+    }
+
+    async fn count_finished_runs(&self, since: DateTime<Utc>) -> Result<(i64, i64), DbError> {
+        self.pg_count_finished_runs(since).await
+    }
+
+    // -- Run Metrics --
+    #[tracing::instrument(name = "db.record_run_metrics", skip_all, fields(db.system = "postgresql", run.id = %run_id))]
+    async fn record_run_metrics(
+        &self,
+        run_id: &str,
+        input: &RecordRunMetrics,
+    ) -> Result<RunMetrics, DbError> {
+        self.pg_record_run_metrics(run_id, input).await
+    }
+    async fn summarize_run_metrics(
+        &self,
+        filter: &RunMetricsFilter,
+    ) -> Result<Vec<RunMetricsSummary>, DbError> {
+        self.pg_summarize_run_metrics(filter).await
+    }
 
     // -- Sprints --
     async fn create_sprint(&self, input: &CreateSprint) -> Result<Sprint, DbError> {
@@ -173,11 +325,124 @@ impl Database for PostgresDatabase {
     async fn delete_sprint(&self, id: &str) -> Result<(), DbError> {
         self.pg_delete_sprint(id).await
     }
+    async fn archive_sprint(&self, id: &str) -> Result<Sprint, DbError> {
+        self.pg_archive_sprint(id).await
+    }
+
+    // -- Epics --
+    async fn create_epic(&self, input: &CreateEpic) -> Result<Epic, DbError> {
+        self.pg_create_epic(input).await
+    }
+    async fn get_epic(&self, id: &str) -> Result<Epic, DbError> {
+        self.pg_get_epic(id).await
+    }
+    async fn list_epics(&self, project_id: &str) -> Result<Vec<Epic>, DbError> {
+        self.pg_list_epics(project_id).await
+    }
+    async fn update_epic(&self, id: &str, update: &UpdateEpic) -> Result<Epic, DbError> {
+        self.pg_update_epic(id, update).await
+    }
+    async fn delete_epic(&self, id: &str) -> Result<(), DbError> {
+        self.pg_delete_epic(id).await
+    }
+
+    // -- Users --
+    async fn create_user(&self, input: &CreateUser) -> Result<User, DbError> {
+        self.pg_create_user(input).await
+    }
+    async fn get_user(&self, id: &str) -> Result<User, DbError> {
+        self.pg_get_user(id).await
+    }
+    async fn list_users(&self) -> Result<Vec<User>, DbError> {
+        self.pg_list_users().await
+    }
+    async fn update_user(&self, id: &str, update: &UpdateUser) -> Result<User, DbError> {
+        self.pg_update_user(id, update).await
+    }
+    async fn delete_user(&self, id: &str) -> Result<(), DbError> {
+        self.pg_delete_user(id).await
+    }
+
+    // -- Saved Filters --
+    async fn create_saved_filter(&self, input: &CreateSavedFilter) -> Result<SavedFilter, DbError> {
+        self.pg_create_saved_filter(input).await
+    }
+    async fn get_saved_filter(&self, id: &str) -> Result<SavedFilter, DbError> {
+        self.pg_get_saved_filter(id).await
+    }
+    async fn list_saved_filters(
+        &self,
+        project_id: &str,
+        user_id: Option<&str>,
+    ) -> Result<Vec<SavedFilter>, DbError> {
+        self.pg_list_saved_filters(project_id, user_id).await
+    }
+    async fn update_saved_filter(
+        &self,
+        id: &str,
+        update: &UpdateSavedFilter,
+    ) -> Result<SavedFilter, DbError> {
+        self.pg_update_saved_filter(id, update).await
+    }
+    async fn delete_saved_filter(&self, id: &str) -> Result<(), DbError> {
+        self.pg_delete_saved_filter(id).await
+    }
+
+    // -- Watchers and Notifications --
+    async fn watch_task(&self, task_id: &str, user_id: &str) -> Result<(), DbError> {
+        self.pg_watch_task(task_id, user_id).await
+    }
+    async fn unwatch_task(&self, task_id: &str, user_id: &str) -> Result<(), DbError> {
+        self.pg_unwatch_task(task_id, user_id).await
+    }
+    async fn list_task_watchers(&self, task_id: &str) -> Result<Vec<User>, DbError> {
+        self.pg_list_task_watchers(task_id).await
+    }
+    async fn list_notifications(
+        &self,
+        user_id: &str,
+        unread_only: bool,
+    ) -> Result<Vec<Notification>, DbError> {
+        self.pg_list_notifications(user_id, unread_only).await
+    }
+    async fn get_notification(&self, id: &str) -> Result<Notification, DbError> {
+        self.pg_get_notification(id).await
+    }
+    async fn mark_notification_read(&self, id: &str) -> Result<Notification, DbError> {
+        self.pg_mark_notification_read(id).await
+    }
+
+    // -- Custom Fields --
+    async fn create_custom_field(&self, input: &CreateCustomField) -> Result<CustomField, DbError> {
+        self.pg_create_custom_field(input).await
+    }
+    async fn get_custom_field(&self, id: &str) -> Result<CustomField, DbError> {
+        self.pg_get_custom_field(id).await
+    }
+    async fn list_custom_fields(&self, project_id: &str) -> Result<Vec<CustomField>, DbError> {
+        self.pg_list_custom_fields(project_id).await
+    }
+    async fn update_custom_field(
+        &self,
+        id: &str,
+        update: &UpdateCustomField,
+    ) -> Result<CustomField, DbError> {
+        self.pg_update_custom_field(id, update).await
+    }
+    async fn delete_custom_field(&self, id: &str) -> Result<(), DbError> {
+        self.pg_delete_custom_field(id).await
+    }
+    async fn list_task_field_values(&self, task_id: &str) -> Result<Vec<TaskFieldValue>, DbError> {
+        self.pg_list_task_field_values(task_id).await
+    }
 
     // -- Task Links --
     async fn create_task_link(&self, input: &CreateTaskLink) -> Result<TaskLink, DbError> {
         self.pg_create_task_link(input).await
     }
+    async fn get_task_link(&self, id: &str) -> Result<TaskLink, DbError> {
+        self.pg_get_task_link(id).await
+    }
     async fn list_task_links(&self, task_id: &str) -> Result<Vec<TaskLink>, DbError> {
         self.pg_list_task_links(task_id).await
     }
@@ -192,6 +457,62 @@ impl Database for PostgresDatabase {
     async fn list_task_prs(&self, task_id: &str) -> Result<Vec<TaskPr>, DbError> {
         self.pg_list_task_prs(task_id).await
     }
+    async fn get_task_pr(&self, id: &str) -> Result<TaskPr, DbError> {
+        self.pg_get_task_pr(id).await
+    }
+    async fn get_task_pr_by_url(&self, pr_url: &str) -> Result<TaskPr, DbError> {
+        self.pg_get_task_pr_by_url(pr_url).await
+    }
+    async fn set_task_pr_state(&self, id: &str, state: PrState) -> Result<TaskPr, DbError> {
+        self.pg_set_task_pr_state(id, state).await
+    }
+    async fn set_task_pr_checks(
+        &self,
+        id: &str,
+        checks_status: Option<ChecksStatus>,
+    ) -> Result<TaskPr, DbError> {
+        self.pg_set_task_pr_checks(id, checks_status).await
+    }
+    async fn set_task_pr_draft(&self, id: &str, draft: bool) -> Result<TaskPr, DbError> {
+        self.pg_set_task_pr_draft(id, draft).await
+    }
+    async fn list_open_task_prs(&self) -> Result<Vec<TaskPr>, DbError> {
+        self.pg_list_open_task_prs().await
+    }
+
+    // -- Labels --
+    async fn ensure_label(
+        &self,
+        project_id: &str,
+        name: &str,
+        color: &str,
+    ) -> Result<Label, DbError> {
+        self.pg_ensure_label(project_id, name, color).await
+    }
+    async fn add_task_label(&self, task_id: &str, label_id: &str) -> Result<(), DbError> {
+        self.pg_add_task_label(task_id, label_id).await
+    }
+    async fn list_task_labels(&self, task_id: &str) -> Result<Vec<Label>, DbError> {
+        self.pg_list_task_labels(task_id).await
+    }
+
+    // -- Task Imports --
+    async fn find_imported_task(
+        &self,
+        project_id: &str,
+        source: &str,
+    ) -> Result<Option<String>, DbError> {
+        self.pg_find_imported_task(project_id, source).await
+    }
+    async fn record_task_import(
+        &self,
+        project_id: &str,
+        source: &str,
+        task_id: &str,
+    ) -> Result<(), DbError> {
+        self.pg_record_task_import(project_id, source, task_id)
+            .await
+    }
 
     // -- Attachments --
     async fn create_attachment(
@@ -200,9 +521,18 @@ impl Database for PostgresDatabase {
         filename: &str,
         store_key: &str,
         size_bytes: i64,
+        content_type: &str,
+        sha256: &str,
     ) -> Result<Attachment, DbError> {
-        self.pg_create_attachment(task_id, filename, store_key, size_bytes)
-            .await
+        self.pg_create_attachment(
+            task_id,
+            filename,
+            store_key,
+            size_bytes,
+            content_type,
+            sha256,
+        )
+        .await
     }
     async fn list_attachments(&self, task_id: &str) -> Result<Vec<Attachment>, DbError> {
         self.pg_list_attachments(task_id).await
@@ -213,6 +543,9 @@ impl Database for PostgresDatabase {
     async fn delete_attachment(&self, id: &str) -> Result<Attachment, DbError> {
         self.pg_delete_attachment(id).await
     }
+    async fn count_attachment_refs(&self, store_key: &str) -> Result<i64, DbError> {
+        self.pg_count_attachment_refs(store_key).await
+    }
 
     // -- API Keys --
     async fn insert_api_key(&self, name: &str, key_hash: &str) -> Result<ApiKey, DbError> {
@@ -230,7 +563,198 @@ impl Database for PostgresDatabase {
     async fn list_api_keys(&self) -> Result<Vec<ApiKey>, DbError> {
         self.pg_list_api_keys().await
     }
+    async fn set_api_key_policy(
+        &self,
+        id: &str,
+        allowed_cidrs: &[String],
+        allowed_routes: &[String],
+    ) -> Result<ApiKey, DbError> {
+        self.pg_set_api_key_policy(id, allowed_cidrs, allowed_routes)
+            .await
+    }
+    async fn set_api_key_projects(
+        &self,
+        id: &str,
+        project_ids: &[String],
+    ) -> Result<ApiKey, DbError> {
+        self.pg_set_api_key_projects(id, project_ids).await
+    }
+    async fn set_api_key_org(&self, id: &str, org_id: Option<&str>) -> Result<ApiKey, DbError> {
+        self.pg_set_api_key_org(id, org_id).await
+    }
     async fn delete_api_key(&self, id: &str) -> Result<(), DbError> {
         self.pg_delete_api_key(id).await
     }
+
+    // -- Organizations --
+    async fn create_organization(
+        &self,
+        input: &CreateOrganization,
+    ) -> Result<Organization, DbError> {
+        self.pg_create_organization(input).await
+    }
+    async fn get_organization(&self, id: &str) -> Result<Organization, DbError> {
+        self.pg_get_organization(id).await
+    }
+    async fn list_organizations(&self) -> Result<Vec<Organization>, DbError> {
+        self.pg_list_organizations().await
+    }
+    async fn delete_organization(&self, id: &str) -> Result<(), DbError> {
+        self.pg_delete_organization(id).await
+    }
+    async fn list_organization_project_ids(&self, org_id: &str) -> Result<Vec<String>, DbError> {
+        self.pg_list_organization_project_ids(org_id).await
+    }
+    async fn set_project_org(
+        &self,
+        project_id: &str,
+        org_id: Option<&str>,
+    ) -> Result<Project, DbError> {
+        self.pg_set_project_org(project_id, org_id).await
+    }
+
+    // -- Runners --
+    async fn upsert_runner(&self, runner: &RunnerRecord) -> Result<RunnerRecord, DbError> {
+        self.pg_upsert_runner(runner).await
+    }
+    async fn get_runner(&self, runner_id: &str) -> Result<RunnerRecord, DbError> {
+        self.pg_get_runner(runner_id).await
+    }
+    async fn list_runners(&self) -> Result<Vec<RunnerRecord>, DbError> {
+        self.pg_list_runners().await
+    }
+    async fn set_runner_pending_config(
+        &self,
+        runner_id: &str,
+        config: Option<&PendingRunnerConfig>,
+    ) -> Result<(), DbError> {
+        self.pg_set_runner_pending_config(runner_id, config).await
+    }
+    async fn delete_runner(&self, runner_id: &str) -> Result<(), DbError> {
+        self.pg_delete_runner(runner_id).await
+    }
+
+    // -- Feature Flags --
+    async fn list_feature_flags(&self) -> Result<Vec<FeatureFlag>, DbError> {
+        self.pg_list_feature_flags().await
+    }
+    async fn set_feature_flag(
+        &self,
+        key: &str,
+        project_id: Option<&str>,
+        enabled: bool,
+    ) -> Result<FeatureFlag, DbError> {
+        self.pg_set_feature_flag(key, project_id, enabled).await
+    }
+    async fn delete_feature_flag(
+        &self,
+        key: &str,
+        project_id: Option<&str>,
+    ) -> Result<(), DbError> {
+        self.pg_delete_feature_flag(key, project_id).await
+    }
+
+    // -- Approval Rules --
+    async fn list_approval_rules(&self, project_id: &str) -> Result<Vec<ApprovalRule>, DbError> {
+        self.pg_list_approval_rules(project_id).await
+    }
+    async fn set_approval_rule(
+        &self,
+        project_id: &str,
+        phase: ApprovalPhase,
+        policy: ApprovalPolicy,
+    ) -> Result<ApprovalRule, DbError> {
+        self.pg_set_approval_rule(project_id, phase, policy).await
+    }
+    async fn delete_approval_rule(
+        &self,
+        project_id: &str,
+        phase: ApprovalPhase,
+    ) -> Result<(), DbError> {
+        self.pg_delete_approval_rule(project_id, phase).await
+    }
+
+    // -- Notification Subscriptions --
+    async fn create_subscription(
+        &self,
+        input: &CreateSubscription,
+    ) -> Result<Subscription, DbError> {
+        self.pg_create_subscription(input).await
+    }
+    async fn get_subscription(&self, id: &str) -> Result<Subscription, DbError> {
+        self.pg_get_subscription(id).await
+    }
+    async fn list_subscriptions(&self) -> Result<Vec<Subscription>, DbError> {
+        self.pg_list_subscriptions().await
+    }
+    async fn delete_subscription(&self, id: &str) -> Result<(), DbError> {
+        self.pg_delete_subscription(id).await
+    }
+
+    // -- Webhooks --
+    async fn create_webhook(&self, input: &CreateWebhook) -> Result<Webhook, DbError> {
+        self.pg_create_webhook(input).await
+    }
+    async fn get_webhook(&self, id: &str) -> Result<Webhook, DbError> {
+        self.pg_get_webhook(id).await
+    }
+    async fn list_webhooks(&self) -> Result<Vec<Webhook>, DbError> {
+        self.pg_list_webhooks().await
+    }
+    async fn update_webhook(&self, id: &str, update: &UpdateWebhook) -> Result<Webhook, DbError> {
+        self.pg_update_webhook(id, update).await
+    }
+    async fn delete_webhook(&self, id: &str) -> Result<(), DbError> {
+        self.pg_delete_webhook(id).await
+    }
+    async fn enqueue_webhook_delivery(
+        &self,
+        webhook_id: &str,
+        event: WebhookEvent,
+        payload: &str,
+    ) -> Result<WebhookDelivery, DbError> {
+        self.pg_enqueue_webhook_delivery(webhook_id, event, payload)
+            .await
+    }
+    async fn claim_webhook_deliveries(
+        &self,
+        lease_until: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, DbError> {
+        self.pg_claim_webhook_deliveries(lease_until, limit).await
+    }
+    async fn record_webhook_attempt(
+        &self,
+        id: &str,
+        attempt: &DeliveryAttempt,
+    ) -> Result<WebhookDelivery, DbError> {
+        self.pg_record_webhook_attempt(id, attempt).await
+    }
+    async fn list_webhook_deliveries(
+        &self,
+        webhook_id: &str,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, DbError> {
+        self.pg_list_webhook_deliveries(webhook_id, limit).await
+    }
+    async fn prune_webhook_deliveries(&self, before: DateTime<Utc>) -> Result<u64, DbError> {
+        self.pg_prune_webhook_deliveries(before).await
+    }
+
+    // -- Backup / Restore --
+    async fn export_snapshot(&self) -> Result<Snapshot, DbError> {
+        self.pg_export_snapshot().await
+    }
+    async fn import_snapshot(&self, snapshot: &Snapshot) -> Result<(), DbError> {
+        self.pg_import_snapshot(snapshot).await
+    }
+
+    // -- Diagnostics --
+    async fn stats(&self) -> Result<DbStats, DbError> {
+        self.pg_stats().await
+    }
+
+    async fn run_maintenance(&self) -> Result<MaintenanceReport, DbError> {
+        self.pg_run_maintenance().await
+    }
 }
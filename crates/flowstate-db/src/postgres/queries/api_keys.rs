@@ -5,6 +5,13 @@ use flowstate_core::api_key::ApiKey;
 use super::super::{pg_err, pg_not_found, PostgresDatabase};
 use crate::DbError;
 
+/// Keys with their project scope gathered into a JSON array, like the other
+/// allowlists.
+const SELECT_API_KEYS: &str = "SELECT k.*,
+     COALESCE((SELECT json_agg(project_id ORDER BY project_id)::text
+               FROM api_key_projects WHERE api_key_id = k.id), '[]') AS allowed_projects
+     FROM api_keys k";
+
 /// ApiKey stores dates as plain TEXT strings (not TIMESTAMPTZ),
 /// matching the core ApiKey type which uses String for created_at/last_used_at.
 #[derive(sqlx::FromRow)]
@@ -14,17 +21,36 @@ struct ApiKeyRow {
     key_hash: String,
     created_at: String,
     last_used_at: Option<String>,
+    allowed_cidrs: String,
+    allowed_routes: String,
+    allowed_projects: String,
+    org_id: Option<String>,
 }
 
-impl From<ApiKeyRow> for ApiKey {
-    fn from(r: ApiKeyRow) -> Self {
-        ApiKey {
+impl TryFrom<ApiKeyRow> for ApiKey {
+    type Error = DbError;
+
+    /// A corrupt allowlist is an error, not an empty list, which would leave
+    /// the key unrestricted.
+    fn try_from(r: ApiKeyRow) -> Result<Self, DbError> {
+        let allowlist = |column: &str, json: &str| {
+            serde_json::from_str(json)
+                .map_err(|e| DbError::Internal(format!("api key {} {column}: {e}", r.id)))
+        };
+        let allowed_cidrs = allowlist("allowed_cidrs", &r.allowed_cidrs)?;
+        let allowed_routes = allowlist("allowed_routes", &r.allowed_routes)?;
+        let allowed_projects = allowlist("allowed_projects", &r.allowed_projects)?;
+        Ok(ApiKey {
             id: r.id,
             name: r.name,
             key_hash: r.key_hash,
             created_at: r.created_at,
             last_used_at: r.last_used_at,
-        }
+            allowed_cidrs,
+            allowed_routes,
+            allowed_projects,
+            org_id: r.org_id,
+        })
     }
 }
 
@@ -48,26 +74,27 @@ impl PostgresDatabase {
         .await
         .map_err(pg_err)?;
 
-        let row = sqlx::query_as::<_, ApiKeyRow>("SELECT * FROM api_keys WHERE id = $1")
+        let row = sqlx::query_as::<_, ApiKeyRow>(&format!("{SELECT_API_KEYS} WHERE k.id = $1"))
             .bind(&id)
             .fetch_one(&self.pool)
             .await
             .map_err(pg_err)?;
 
-        Ok(row.into())
+        row.try_into()
     }
 
     pub(crate) async fn pg_find_api_key_by_hash(
         &self,
         key_hash: &str,
     ) -> Result<Option<ApiKey>, DbError> {
-        let row = sqlx::query_as::<_, ApiKeyRow>("SELECT * FROM api_keys WHERE key_hash = $1")
-            .bind(key_hash)
-            .fetch_optional(&self.pool)
-            .await
-            .map_err(pg_err)?;
+        let row =
+            sqlx::query_as::<_, ApiKeyRow>(&format!("{SELECT_API_KEYS} WHERE k.key_hash = $1"))
+                .bind(key_hash)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(pg_err)?;
 
-        Ok(row.map(|r| r.into()))
+        row.map(ApiKey::try_from).transpose()
     }
 
     pub(crate) async fn pg_touch_api_key(&self, id: &str) -> Result<(), DbError> {
@@ -93,13 +120,116 @@ impl PostgresDatabase {
     }
 
     pub(crate) async fn pg_list_api_keys(&self) -> Result<Vec<ApiKey>, DbError> {
-        let rows =
-            sqlx::query_as::<_, ApiKeyRow>("SELECT * FROM api_keys ORDER BY created_at DESC")
-                .fetch_all(&self.pool)
-                .await
-                .map_err(pg_err)?;
+        let rows = sqlx::query_as::<_, ApiKeyRow>(&format!(
+            "{SELECT_API_KEYS} ORDER BY k.created_at DESC"
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        rows.into_iter().map(ApiKey::try_from).collect()
+    }
+
+    pub(crate) async fn pg_set_api_key_policy(
+        &self,
+        id: &str,
+        allowed_cidrs: &[String],
+        allowed_routes: &[String],
+    ) -> Result<ApiKey, DbError> {
+        let cidrs =
+            serde_json::to_string(allowed_cidrs).map_err(|e| DbError::Internal(e.to_string()))?;
+        let routes =
+            serde_json::to_string(allowed_routes).map_err(|e| DbError::Internal(e.to_string()))?;
+
+        let result = sqlx::query(
+            "UPDATE api_keys SET allowed_cidrs = $1, allowed_routes = $2 WHERE id = $3",
+        )
+        .bind(cidrs)
+        .bind(routes)
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        if result.rows_affected() == 0 {
+            return Err(pg_not_found(&format!("api_key {id}")));
+        }
+
+        let row = sqlx::query_as::<_, ApiKeyRow>(&format!("{SELECT_API_KEYS} WHERE k.id = $1"))
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(pg_err)?;
+
+        row.try_into()
+    }
+
+    pub(crate) async fn pg_set_api_key_projects(
+        &self,
+        id: &str,
+        project_ids: &[String],
+    ) -> Result<ApiKey, DbError> {
+        let mut tx = self.pool.begin().await.map_err(pg_err)?;
+
+        let exists: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM api_keys WHERE id = $1")
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        if exists == 0 {
+            return Err(pg_not_found(&format!("api_key {id}")));
+        }
+
+        sqlx::query("DELETE FROM api_key_projects WHERE api_key_id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        for project_id in project_ids {
+            sqlx::query(
+                "INSERT INTO api_key_projects (api_key_id, project_id) VALUES ($1, $2)
+                 ON CONFLICT DO NOTHING",
+            )
+            .bind(id)
+            .bind(project_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        let row = sqlx::query_as::<_, ApiKeyRow>(&format!("{SELECT_API_KEYS} WHERE k.id = $1"))
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        tx.commit().await.map_err(pg_err)?;
+
+        row.try_into()
+    }
+
+    pub(crate) async fn pg_set_api_key_org(
+        &self,
+        id: &str,
+        org_id: Option<&str>,
+    ) -> Result<ApiKey, DbError> {
+        let result = sqlx::query("UPDATE api_keys SET org_id = $1 WHERE id = $2")
+            .bind(org_id)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(pg_err)?;
+
+        if result.rows_affected() == 0 {
+            return Err(pg_not_found(&format!("api_key {id}")));
+        }
+
+        let row = sqlx::query_as::<_, ApiKeyRow>(&format!("{SELECT_API_KEYS} WHERE k.id = $1"))
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(pg_err)?;
 
-        Ok(rows.into_iter().map(|r| r.into()).collect())
+        row.try_into()
     }
 
     pub(crate) async fn pg_delete_api_key(&self, id: &str) -> Result<(), DbError> {
@@ -0,0 +1,84 @@
+use chrono::{DateTime, Utc};
+
+use flowstate_core::approval_rule::{ApprovalPhase, ApprovalPolicy, ApprovalRule};
+
+use super::super::{pg_err, pg_not_found, PostgresDatabase};
+use crate::DbError;
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct ApprovalRuleRow {
+    project_id: String,
+    phase: String,
+    policy: String,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<ApprovalRuleRow> for ApprovalRule {
+    fn from(r: ApprovalRuleRow) -> Self {
+        ApprovalRule {
+            project_id: r.project_id,
+            phase: ApprovalPhase::parse_str(&r.phase).unwrap_or(ApprovalPhase::Research),
+            policy: ApprovalPolicy::parse_str(&r.policy).unwrap_or(ApprovalPolicy::Human),
+            updated_at: r.updated_at,
+        }
+    }
+}
+
+impl PostgresDatabase {
+    pub(crate) async fn pg_list_approval_rules(
+        &self,
+        project_id: &str,
+    ) -> Result<Vec<ApprovalRule>, DbError> {
+        let rows = sqlx::query_as::<_, ApprovalRuleRow>(
+            "SELECT * FROM approval_rules WHERE project_id = $1 ORDER BY phase",
+        )
+        .bind(project_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    pub(crate) async fn pg_set_approval_rule(
+        &self,
+        project_id: &str,
+        phase: ApprovalPhase,
+        policy: ApprovalPolicy,
+    ) -> Result<ApprovalRule, DbError> {
+        let row = sqlx::query_as::<_, ApprovalRuleRow>(
+            "INSERT INTO approval_rules (project_id, phase, policy, updated_at)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (project_id, phase)
+             DO UPDATE SET policy = EXCLUDED.policy, updated_at = EXCLUDED.updated_at
+             RETURNING *",
+        )
+        .bind(project_id)
+        .bind(phase.as_str())
+        .bind(policy.as_str())
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        Ok(row.into())
+    }
+
+    pub(crate) async fn pg_delete_approval_rule(
+        &self,
+        project_id: &str,
+        phase: ApprovalPhase,
+    ) -> Result<(), DbError> {
+        let result = sqlx::query("DELETE FROM approval_rules WHERE project_id = $1 AND phase = $2")
+            .bind(project_id)
+            .bind(phase.as_str())
+            .execute(&self.pool)
+            .await
+            .map_err(pg_err)?;
+
+        if result.rows_affected() == 0 {
+            return Err(pg_not_found(&format!("approval_rule {phase}")));
+        }
+        Ok(())
+    }
+}
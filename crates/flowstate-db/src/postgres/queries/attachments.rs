@@ -6,12 +6,14 @@ use super::super::{pg_err, pg_not_found, PostgresDatabase};
 use crate::DbError;
 
 #[derive(sqlx::FromRow)]
-struct AttachmentRow {
+pub(crate) struct AttachmentRow {
     id: String,
     task_id: String,
     filename: String,
     store_key: String,
     size_bytes: i64,
+    content_type: String,
+    sha256: String,
     created_at: DateTime<Utc>,
 }
 
@@ -23,6 +25,8 @@ impl From<AttachmentRow> for Attachment {
             filename: r.filename,
             store_key: r.store_key,
             size_bytes: r.size_bytes,
+            content_type: r.content_type,
+            sha256: r.sha256,
             created_at: r.created_at,
         }
     }
@@ -35,19 +39,24 @@ impl PostgresDatabase {
         filename: &str,
         store_key: &str,
         size_bytes: i64,
+        content_type: &str,
+        sha256: &str,
     ) -> Result<Attachment, DbError> {
         let id = uuid::Uuid::new_v4().to_string();
         let now = Utc::now();
 
         sqlx::query(
-            "INSERT INTO attachments (id, task_id, filename, store_key, size_bytes, created_at)
-             VALUES ($1, $2, $3, $4, $5, $6)",
+            "INSERT INTO attachments
+                 (id, task_id, filename, store_key, size_bytes, content_type, sha256, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
         )
         .bind(&id)
         .bind(task_id)
         .bind(filename)
         .bind(store_key)
         .bind(size_bytes)
+        .bind(content_type)
+        .bind(sha256)
         .bind(now)
         .execute(&self.pool)
         .await
@@ -100,4 +109,12 @@ impl PostgresDatabase {
 
         Ok(attachment)
     }
+
+    pub(crate) async fn pg_count_attachment_refs(&self, store_key: &str) -> Result<i64, DbError> {
+        sqlx::query_scalar("SELECT COUNT(*) FROM attachments WHERE store_key = $1")
+            .bind(store_key)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(pg_err)
+    }
 }
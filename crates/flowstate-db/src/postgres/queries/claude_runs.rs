@@ -2,11 +2,35 @@ use chrono::{DateTime, Utc};
 
 use flowstate_core::claude_run::{ClaudeAction, ClaudeRun, ClaudeRunStatus, CreateClaudeRun};
 
-use super::super::{pg_err, pg_not_found, PostgresDatabase};
-use crate::DbError;
+use super::super::{bind_values, pg_err, pg_not_found, PostgresDatabase};
+use crate::query::{Dialect, SelectQuery};
+use crate::{DbError, QueueDemand};
+
+/// Claim prefilter: skip runs whose project is already at its
+/// `max_concurrent_runs` cap. Concurrent claimers can all pass it, so the
+/// claim rechecks the cap under a lock on the project row.
+const UNDER_PROJECT_CAP: &str = "NOT EXISTS (
+    SELECT 1 FROM tasks t JOIN projects p ON p.id = t.project_id
+    WHERE t.id = claude_runs.task_id
+      AND p.max_concurrent_runs IS NOT NULL
+      AND p.max_concurrent_runs <= (
+          SELECT COUNT(*) FROM claude_runs r JOIN tasks rt ON rt.id = r.task_id
+          WHERE rt.project_id = p.id AND r.status = 'running'
+      )
+)";
+
+/// Claim ordering key: the run's project's running runs per unit of
+/// `claim_weight`, so equal-priority work is shared fairly across projects.
+const PROJECT_SHARE: &str = "(
+    SELECT COUNT(r.id)::float8 / MAX(p.claim_weight)
+    FROM tasks t JOIN projects p ON p.id = t.project_id
+    LEFT JOIN tasks rt ON rt.project_id = p.id
+    LEFT JOIN claude_runs r ON r.task_id = rt.id AND r.status = 'running'
+    WHERE t.id = claude_runs.task_id
+)";
 
 #[derive(sqlx::FromRow)]
-struct ClaudeRunRow {
+pub(crate) struct ClaudeRunRow {
     id: String,
     task_id: String,
     action: String,
@@ -21,6 +45,14 @@ struct ClaudeRunRow {
     started_at: DateTime<Utc>,
     finished_at: Option<DateTime<Utc>>,
     required_capability: Option<String>,
+    priority: i32,
+    feedback: Option<String>,
+    idempotency_key: Option<String>,
+    run_window_start: Option<i32>,
+    run_window_end: Option<i32>,
+    run_window_offset: Option<i32>,
+    run_window_tz: Option<String>,
+    trace_context: Option<String>,
 }
 
 impl From<ClaudeRunRow> for ClaudeRun {
@@ -40,6 +72,16 @@ impl From<ClaudeRunRow> for ClaudeRun {
             started_at: r.started_at,
             finished_at: r.finished_at,
             required_capability: r.required_capability,
+            priority: r.priority,
+            feedback: r.feedback,
+            idempotency_key: r.idempotency_key,
+            run_window: crate::run_window_from_columns(
+                r.run_window_start,
+                r.run_window_end,
+                r.run_window_offset,
+                r.run_window_tz,
+            ),
+            trace_context: r.trace_context,
         }
     }
 }
@@ -52,24 +94,46 @@ impl PostgresDatabase {
         let id = uuid::Uuid::new_v4().to_string();
         let now = Utc::now();
 
+        // A key already used on this task leaves the insert a no-op, and
+        // the lookup below returns the earlier run.
         sqlx::query(
-            "INSERT INTO claude_runs (id, task_id, action, status, started_at, required_capability)
-             VALUES ($1, $2, $3, 'queued', $4, $5)",
+            "INSERT INTO claude_runs (
+                 id, task_id, action, status, started_at, required_capability, priority,
+                 feedback, idempotency_key, run_window_start, run_window_end,
+                 run_window_offset, run_window_tz, trace_context
+             ) VALUES ($1, $2, $3, 'queued', $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+             ON CONFLICT (task_id, idempotency_key) WHERE idempotency_key IS NOT NULL
+             DO NOTHING",
         )
         .bind(&id)
         .bind(&input.task_id)
         .bind(input.action.as_str())
         .bind(now)
         .bind(&input.required_capability)
+        .bind(input.priority)
+        .bind(&input.feedback)
+        .bind(&input.idempotency_key)
+        .bind(input.run_window.map(|w| w.start))
+        .bind(input.run_window.map(|w| w.end))
+        .bind(input.run_window.map(|w| w.utc_offset))
+        .bind(input.run_window.and_then(|w| w.time_zone_name()))
+        .bind(&input.trace_context)
         .execute(&self.pool)
         .await
         .map_err(pg_err)?;
 
-        let row = sqlx::query_as::<_, ClaudeRunRow>("SELECT * FROM claude_runs WHERE id = $1")
-            .bind(&id)
-            .fetch_one(&self.pool)
-            .await
-            .map_err(pg_err)?;
+        let row = match input.idempotency_key {
+            Some(ref key) => sqlx::query_as::<_, ClaudeRunRow>(
+                "SELECT * FROM claude_runs WHERE task_id = $1 AND idempotency_key = $2",
+            )
+            .bind(&input.task_id)
+            .bind(key),
+            None => sqlx::query_as::<_, ClaudeRunRow>("SELECT * FROM claude_runs WHERE id = $1")
+                .bind(&id),
+        }
+        .fetch_one(&self.pool)
+        .await
+        .map_err(pg_err)?;
 
         Ok(row.into())
     }
@@ -136,35 +200,49 @@ impl PostgresDatabase {
         self.pg_get_claude_run(id).await
     }
 
-    /// Atomically claim the oldest queued run, setting it to Running.
+    /// Atomically claim a queued run, setting it to Running. Runs are taken
+    /// highest priority first; ties go to the project with the smallest
+    /// running share (see `PROJECT_SHARE`), then to the oldest run. Runs
+    /// whose project is at its `max_concurrent_runs` cap, or outside their
+    /// run window, are skipped.
     /// Uses FOR UPDATE SKIP LOCKED for Postgres concurrency safety.
     /// If `capabilities` is non-empty, only claim runs whose `required_capability`
-    /// is NULL or matches one of the given values.
+    /// is NULL or matches one of the given values. Runs of the `skip` actions,
+    /// and runs outside `projects` when it is non-empty, are left queued.
     pub(crate) async fn pg_claim_next_claude_run(
         &self,
         capabilities: &[&str],
+        skip: &[ClaudeAction],
+        projects: &[String],
     ) -> Result<Option<ClaudeRun>, DbError> {
         let mut tx = self.pool.begin().await.map_err(pg_err)?;
         let now = Utc::now();
-
-        let maybe_row = if capabilities.is_empty() {
-            sqlx::query_as::<_, ClaudeRunRow>(
-                "SELECT * FROM claude_runs WHERE status = 'queued' ORDER BY started_at ASC LIMIT 1 FOR UPDATE SKIP LOCKED",
-            )
-            .fetch_optional(&mut *tx)
+        let zones: Vec<String> = sqlx::query_scalar(crate::RUN_WINDOW_ZONES_SQL)
+            .fetch_all(&mut *tx)
             .await
-            .map_err(pg_err)?
-        } else {
-            // Convert capabilities to a Vec<String> for sqlx binding
-            let caps: Vec<String> = capabilities.iter().map(|s| s.to_string()).collect();
-            sqlx::query_as::<_, ClaudeRunRow>(
-                "SELECT * FROM claude_runs WHERE status = 'queued' AND (required_capability IS NULL OR required_capability = ANY($1)) ORDER BY started_at ASC LIMIT 1 FOR UPDATE SKIP LOCKED",
-            )
-            .bind(&caps)
+            .map_err(pg_err)?;
+        let mut q = SelectQuery::new(Dialect::Postgres, "SELECT * FROM claude_runs");
+        let window = crate::in_run_window_sql(&mut q, now, &zones);
+        q.and("status = 'queued'");
+        if !capabilities.is_empty() {
+            let caps: Vec<String> = capabilities.iter().map(|c| q.bind(*c)).collect();
+            q.and(&format!(
+                "(required_capability IS NULL OR required_capability IN ({}))",
+                caps.join(", ")
+            ));
+        }
+        q.and(UNDER_PROJECT_CAP)
+            .and(&window)
+            .and(&crate::skip_actions_sql(skip))
+            .and_in(crate::RUN_PROJECT_SQL, projects)
+            .push(&format!(
+                "ORDER BY priority DESC, {PROJECT_SHARE} ASC, started_at ASC LIMIT 1 FOR UPDATE SKIP LOCKED"
+            ));
+
+        let maybe_row = bind_values(sqlx::query_as::<_, ClaudeRunRow>(q.sql()), q.values())
             .fetch_optional(&mut *tx)
             .await
-            .map_err(pg_err)?
-        };
+            .map_err(pg_err)?;
 
         let row = match maybe_row {
             Some(r) => r,
@@ -174,6 +252,39 @@ impl PostgresDatabase {
             }
         };
 
+        // Two claimers can each pick a different run from the same capped
+        // project. Lock the project row before counting, so a second claimer
+        // waits for the first to commit and then sees its running run.
+        let project_id: String = sqlx::query_scalar("SELECT project_id FROM tasks WHERE id = $1")
+            .bind(&row.task_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        let cap: Option<i32> = sqlx::query_scalar(
+            "SELECT max_concurrent_runs FROM projects
+             WHERE id = $1 AND max_concurrent_runs IS NOT NULL
+             FOR UPDATE",
+        )
+        .bind(&project_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(pg_err)?;
+        if let Some(cap) = cap {
+            let running: i64 = sqlx::query_scalar(
+                "SELECT COUNT(*) FROM claude_runs r
+                 JOIN tasks rt ON rt.id = r.task_id
+                 WHERE r.status = 'running' AND rt.project_id = $1",
+            )
+            .bind(&project_id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+            if running >= i64::from(cap) {
+                tx.commit().await.map_err(pg_err)?;
+                return Ok(None);
+            }
+        }
+
         sqlx::query("UPDATE claude_runs SET status = 'running', started_at = $1 WHERE id = $2")
             .bind(now)
             .bind(&row.id)
@@ -284,6 +395,52 @@ impl PostgresDatabase {
         Ok(Some(run))
     }
 
+    pub(crate) async fn pg_requeue_claude_run(
+        &self,
+        id: &str,
+    ) -> Result<Option<ClaudeRun>, DbError> {
+        let result = sqlx::query(
+            "UPDATE claude_runs
+             SET status = 'queued', error_message = NULL, exit_code = NULL,
+                 progress_message = NULL, runner_id = NULL, finished_at = NULL,
+                 started_at = $1
+             WHERE id = $2 AND status IN ('failed', 'timed_out', 'cancelled')",
+        )
+        .bind(Utc::now())
+        .bind(id)
+        .execute(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        let run = self.pg_get_claude_run(id).await?;
+        Ok(Some(run))
+    }
+
+    pub(crate) async fn pg_set_claude_run_priority(
+        &self,
+        id: &str,
+        priority: i32,
+    ) -> Result<Option<ClaudeRun>, DbError> {
+        let result =
+            sqlx::query("UPDATE claude_runs SET priority = $1 WHERE id = $2 AND status = 'queued'")
+                .bind(priority)
+                .bind(id)
+                .execute(&self.pool)
+                .await
+                .map_err(pg_err)?;
+
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        let run = self.pg_get_claude_run(id).await?;
+        Ok(Some(run))
+    }
+
     pub(crate) async fn pg_count_queued_runs(&self) -> Result<i64, DbError> {
         let (count,): (i64,) =
             sqlx::query_as("SELECT COUNT(*) FROM claude_runs WHERE status = 'queued'")
@@ -293,6 +450,66 @@ impl PostgresDatabase {
         Ok(count)
     }
 
+    pub(crate) async fn pg_queued_run_demand(&self) -> Result<Vec<QueueDemand>, DbError> {
+        let rows: Vec<(Option<String>, i64, DateTime<Utc>)> = sqlx::query_as(
+            "SELECT required_capability, COUNT(*), MIN(started_at)
+             FROM claude_runs WHERE status = 'queued'
+             GROUP BY required_capability
+             ORDER BY COUNT(*) DESC, required_capability",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(pg_err)?;
+        Ok(rows
+            .into_iter()
+            .map(|(capability, queued, oldest_queued_at)| QueueDemand {
+                capability,
+                queued,
+                oldest_queued_at,
+            })
+            .collect())
+    }
+
+    pub(crate) async fn pg_count_finished_runs(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<(i64, i64), DbError> {
+        sqlx::query_as(
+            "SELECT COUNT(*),
+                    COUNT(*) FILTER (WHERE status IN ('failed', 'timed_out'))
+             FROM claude_runs
+             WHERE status IN ('completed', 'failed', 'timed_out') AND finished_at >= $1",
+        )
+        .bind(since)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(pg_err)
+    }
+
+    pub(crate) async fn pg_delete_runs_older_than(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> Result<Vec<String>, DbError> {
+        sqlx::query_scalar(
+            "DELETE FROM claude_runs
+             WHERE status IN ('completed', 'failed', 'cancelled', 'timed_out')
+               AND COALESCE(finished_at, started_at) < $1
+               AND EXISTS (
+                   SELECT 1 FROM claude_runs newer
+                   WHERE newer.task_id = claude_runs.task_id
+                     AND newer.action = claude_runs.action
+                     AND (newer.started_at > claude_runs.started_at
+                          OR (newer.started_at = claude_runs.started_at
+                              AND newer.id > claude_runs.id))
+               )
+             RETURNING id",
+        )
+        .bind(cutoff)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(pg_err)
+    }
+
     pub(crate) async fn pg_set_claude_run_runner(
         &self,
         id: &str,
@@ -0,0 +1,243 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::PgConnection;
+
+use flowstate_core::custom_field::{
+    CreateCustomField, CustomField, CustomFieldType, TaskFieldValue, UpdateCustomField,
+};
+use flowstate_core::task_revision::FieldChange;
+
+use super::super::{pg_err, pg_not_found, PostgresDatabase};
+use crate::DbError;
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct CustomFieldRow {
+    id: String,
+    project_id: String,
+    name: String,
+    field_type: String,
+    options: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<CustomFieldRow> for CustomField {
+    fn from(r: CustomFieldRow) -> Self {
+        CustomField {
+            id: r.id,
+            project_id: r.project_id,
+            name: r.name,
+            field_type: CustomFieldType::parse_str(&r.field_type).unwrap_or(CustomFieldType::Text),
+            options: serde_json::from_str(&r.options).unwrap_or_default(),
+            created_at: r.created_at,
+            updated_at: r.updated_at,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct TaskFieldValueRow {
+    task_id: String,
+    field_id: String,
+    value: String,
+}
+
+impl From<TaskFieldValueRow> for TaskFieldValue {
+    fn from(r: TaskFieldValueRow) -> Self {
+        TaskFieldValue {
+            task_id: r.task_id,
+            field_id: r.field_id,
+            value: r.value,
+        }
+    }
+}
+
+/// Write `values` (field id → value, `None` clears) for a task of
+/// `project_id`, returning one revision change per value that moved.
+pub(crate) async fn pg_set_task_field_values_in(
+    conn: &mut PgConnection,
+    task_id: &str,
+    project_id: &str,
+    values: &BTreeMap<String, Option<String>>,
+) -> Result<Vec<FieldChange>, DbError> {
+    let mut changes = Vec::new();
+    for (field_id, value) in values {
+        let name: String =
+            sqlx::query_scalar("SELECT name FROM custom_fields WHERE id = $1 AND project_id = $2")
+                .bind(field_id)
+                .bind(project_id)
+                .fetch_optional(&mut *conn)
+                .await
+                .map_err(pg_err)?
+                .ok_or_else(|| pg_not_found(&format!("custom field {field_id}")))?;
+        let old: Option<String> = sqlx::query_scalar(
+            "SELECT value FROM task_field_values WHERE task_id = $1 AND field_id = $2",
+        )
+        .bind(task_id)
+        .bind(field_id)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(pg_err)?;
+        if old == *value {
+            continue;
+        }
+        match value {
+            Some(value) => sqlx::query(
+                "INSERT INTO task_field_values (task_id, field_id, value) VALUES ($1, $2, $3)
+                 ON CONFLICT (task_id, field_id) DO UPDATE SET value = EXCLUDED.value",
+            )
+            .bind(task_id)
+            .bind(field_id)
+            .bind(value),
+            None => {
+                sqlx::query("DELETE FROM task_field_values WHERE task_id = $1 AND field_id = $2")
+                    .bind(task_id)
+                    .bind(field_id)
+            }
+        }
+        .execute(&mut *conn)
+        .await
+        .map_err(pg_err)?;
+        changes.push(FieldChange {
+            field: format!("custom_fields.{name}"),
+            old: old.map_or(Value::Null, Value::String),
+            new: value.clone().map_or(Value::Null, Value::String),
+        });
+    }
+    Ok(changes)
+}
+
+impl PostgresDatabase {
+    pub(crate) async fn pg_create_custom_field(
+        &self,
+        input: &CreateCustomField,
+    ) -> Result<CustomField, DbError> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = Utc::now();
+        let options =
+            serde_json::to_string(&input.options).map_err(|e| DbError::Internal(e.to_string()))?;
+
+        sqlx::query(
+            "INSERT INTO custom_fields (id, project_id, name, field_type, options, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(&id)
+        .bind(&input.project_id)
+        .bind(&input.name)
+        .bind(input.field_type.as_str())
+        .bind(options)
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        self.pg_get_custom_field(&id).await
+    }
+
+    pub(crate) async fn pg_get_custom_field(&self, id: &str) -> Result<CustomField, DbError> {
+        let row = sqlx::query_as::<_, CustomFieldRow>("SELECT * FROM custom_fields WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(pg_err)?
+            .ok_or_else(|| pg_not_found(&format!("custom field {id}")))?;
+
+        Ok(row.into())
+    }
+
+    pub(crate) async fn pg_list_custom_fields(
+        &self,
+        project_id: &str,
+    ) -> Result<Vec<CustomField>, DbError> {
+        let rows = sqlx::query_as::<_, CustomFieldRow>(
+            "SELECT * FROM custom_fields WHERE project_id = $1 ORDER BY name",
+        )
+        .bind(project_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    pub(crate) async fn pg_update_custom_field(
+        &self,
+        id: &str,
+        update: &UpdateCustomField,
+    ) -> Result<CustomField, DbError> {
+        if update.name.is_none() && update.options.is_none() {
+            return self.pg_get_custom_field(id).await;
+        }
+
+        let mut sets = Vec::new();
+        let mut binds: Vec<String> = Vec::new();
+
+        if let Some(ref name) = update.name {
+            binds.push(name.clone());
+            sets.push(format!("name = ${}", binds.len()));
+        }
+        if let Some(ref options) = update.options {
+            binds.push(
+                serde_json::to_string(options).map_err(|e| DbError::Internal(e.to_string()))?,
+            );
+            sets.push(format!("options = ${}", binds.len()));
+        }
+
+        let sql = format!(
+            "UPDATE custom_fields SET {}, updated_at = ${} WHERE id = ${}",
+            sets.join(", "),
+            binds.len() + 1,
+            binds.len() + 2
+        );
+
+        let mut query = sqlx::query(&sql);
+        for bind in &binds {
+            query = query.bind(bind);
+        }
+        let result = query
+            .bind(Utc::now())
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(pg_err)?;
+
+        if result.rows_affected() == 0 {
+            return Err(pg_not_found(&format!("custom field {id}")));
+        }
+
+        self.pg_get_custom_field(id).await
+    }
+
+    /// Delete a field; its values go with it via `ON DELETE CASCADE`.
+    pub(crate) async fn pg_delete_custom_field(&self, id: &str) -> Result<(), DbError> {
+        let result = sqlx::query("DELETE FROM custom_fields WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(pg_err)?;
+
+        if result.rows_affected() == 0 {
+            return Err(pg_not_found(&format!("custom field {id}")));
+        }
+
+        Ok(())
+    }
+
+    pub(crate) async fn pg_list_task_field_values(
+        &self,
+        task_id: &str,
+    ) -> Result<Vec<TaskFieldValue>, DbError> {
+        let rows = sqlx::query_as::<_, TaskFieldValueRow>(
+            "SELECT * FROM task_field_values WHERE task_id = $1 ORDER BY field_id",
+        )
+        .bind(task_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+}
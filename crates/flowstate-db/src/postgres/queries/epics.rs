@@ -0,0 +1,176 @@
+use chrono::{DateTime, Utc};
+
+use flowstate_core::epic::{CreateEpic, Epic, EpicStatus, UpdateEpic};
+
+use super::super::{pg_err, pg_not_found, PostgresDatabase};
+use crate::DbError;
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct EpicRow {
+    id: String,
+    project_id: String,
+    name: String,
+    description: String,
+    status: String,
+    target_date: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<EpicRow> for Epic {
+    fn from(r: EpicRow) -> Self {
+        Epic {
+            id: r.id,
+            project_id: r.project_id,
+            name: r.name,
+            description: r.description,
+            status: EpicStatus::parse_str(&r.status).unwrap_or(EpicStatus::Open),
+            target_date: r.target_date,
+            created_at: r.created_at,
+            updated_at: r.updated_at,
+        }
+    }
+}
+
+impl PostgresDatabase {
+    pub(crate) async fn pg_create_epic(&self, input: &CreateEpic) -> Result<Epic, DbError> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = Utc::now();
+
+        sqlx::query(
+            "INSERT INTO epics (id, project_id, name, description, status, target_date, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+        )
+        .bind(&id)
+        .bind(&input.project_id)
+        .bind(&input.name)
+        .bind(&input.description)
+        .bind("open")
+        .bind(input.target_date)
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        self.pg_get_epic(&id).await
+    }
+
+    pub(crate) async fn pg_get_epic(&self, id: &str) -> Result<Epic, DbError> {
+        let row = sqlx::query_as::<_, EpicRow>("SELECT * FROM epics WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(pg_err)?
+            .ok_or_else(|| pg_not_found(&format!("epic {id}")))?;
+
+        Ok(row.into())
+    }
+
+    pub(crate) async fn pg_list_epics(&self, project_id: &str) -> Result<Vec<Epic>, DbError> {
+        let rows = sqlx::query_as::<_, EpicRow>(
+            "SELECT * FROM epics WHERE project_id = $1 ORDER BY created_at DESC",
+        )
+        .bind(project_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    pub(crate) async fn pg_update_epic(
+        &self,
+        id: &str,
+        update: &UpdateEpic,
+    ) -> Result<Epic, DbError> {
+        if update.name.is_none()
+            && update.description.is_none()
+            && update.status.is_none()
+            && update.target_date.is_none()
+        {
+            return self.pg_get_epic(id).await;
+        }
+
+        let mut sets = Vec::new();
+        let mut param_idx = 1usize;
+
+        enum BindValue {
+            Str(String),
+            OptDateTime(Option<DateTime<Utc>>),
+            DateTime(DateTime<Utc>),
+        }
+        let mut binds: Vec<BindValue> = Vec::new();
+
+        if let Some(ref name) = update.name {
+            sets.push(format!("name = ${param_idx}"));
+            binds.push(BindValue::Str(name.clone()));
+            param_idx += 1;
+        }
+        if let Some(ref description) = update.description {
+            sets.push(format!("description = ${param_idx}"));
+            binds.push(BindValue::Str(description.clone()));
+            param_idx += 1;
+        }
+        if let Some(ref status) = update.status {
+            sets.push(format!("status = ${param_idx}"));
+            binds.push(BindValue::Str(status.as_str().to_string()));
+            param_idx += 1;
+        }
+        if let Some(ref target_date) = update.target_date {
+            sets.push(format!("target_date = ${param_idx}"));
+            binds.push(BindValue::OptDateTime(*target_date));
+            param_idx += 1;
+        }
+
+        sets.push(format!("updated_at = ${param_idx}"));
+        binds.push(BindValue::DateTime(Utc::now()));
+        param_idx += 1;
+
+        let id_param = param_idx;
+        binds.push(BindValue::Str(id.to_string()));
+
+        let sql = format!(
+            "UPDATE epics SET {} WHERE id = ${id_param}",
+            sets.join(", ")
+        );
+
+        let mut query = sqlx::query(&sql);
+        for bind in &binds {
+            match bind {
+                BindValue::Str(s) => {
+                    query = query.bind(s);
+                }
+                BindValue::OptDateTime(dt) => {
+                    query = query.bind(dt);
+                }
+                BindValue::DateTime(dt) => {
+                    query = query.bind(dt);
+                }
+            }
+        }
+
+        let result = query.execute(&self.pool).await.map_err(pg_err)?;
+
+        if result.rows_affected() == 0 {
+            return Err(pg_not_found(&format!("epic {id}")));
+        }
+
+        self.pg_get_epic(id).await
+    }
+
+    /// Delete an epic; its tasks are detached by `ON DELETE SET NULL`.
+    pub(crate) async fn pg_delete_epic(&self, id: &str) -> Result<(), DbError> {
+        let result = sqlx::query("DELETE FROM epics WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(pg_err)?;
+
+        if result.rows_affected() == 0 {
+            return Err(pg_not_found(&format!("epic {id}")));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use chrono::{DateTime, Utc};
+
+use flowstate_core::feature_flag::FeatureFlag;
+
+use super::super::{pg_err, pg_not_found, PostgresDatabase};
+use crate::DbError;
+
+/// `project_id` is stored as `''` for the global scope so it can sit in the
+/// primary key.
+#[derive(sqlx::FromRow)]
+pub(crate) struct FeatureFlagRow {
+    key: String,
+    project_id: String,
+    enabled: bool,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<FeatureFlagRow> for FeatureFlag {
+    fn from(r: FeatureFlagRow) -> Self {
+        FeatureFlag {
+            key: r.key,
+            project_id: (!r.project_id.is_empty()).then_some(r.project_id),
+            enabled: r.enabled,
+            updated_at: r.updated_at,
+        }
+    }
+}
+
+impl PostgresDatabase {
+    pub(crate) async fn pg_list_feature_flags(&self) -> Result<Vec<FeatureFlag>, DbError> {
+        let rows = sqlx::query_as::<_, FeatureFlagRow>(
+            "SELECT * FROM feature_flags ORDER BY key, project_id",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    pub(crate) async fn pg_set_feature_flag(
+        &self,
+        key: &str,
+        project_id: Option<&str>,
+        enabled: bool,
+    ) -> Result<FeatureFlag, DbError> {
+        let row = sqlx::query_as::<_, FeatureFlagRow>(
+            "INSERT INTO feature_flags (key, project_id, enabled, updated_at)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (key, project_id)
+             DO UPDATE SET enabled = EXCLUDED.enabled, updated_at = EXCLUDED.updated_at
+             RETURNING *",
+        )
+        .bind(key)
+        .bind(project_id.unwrap_or(""))
+        .bind(enabled)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        Ok(row.into())
+    }
+
+    pub(crate) async fn pg_delete_feature_flag(
+        &self,
+        key: &str,
+        project_id: Option<&str>,
+    ) -> Result<(), DbError> {
+        let result = sqlx::query("DELETE FROM feature_flags WHERE key = $1 AND project_id = $2")
+            .bind(key)
+            .bind(project_id.unwrap_or(""))
+            .execute(&self.pool)
+            .await
+            .map_err(pg_err)?;
+
+        if result.rows_affected() == 0 {
+            return Err(pg_not_found(&format!("feature_flag {key}")));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+
+use flowstate_core::feedback::FeedbackEntry;
+
+use super::super::{pg_err, PostgresDatabase};
+use crate::DbError;
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct FeedbackEntryRow {
+    id: String,
+    task_id: String,
+    phase: String,
+    feedback: String,
+    author: String,
+    created_at: DateTime<Utc>,
+}
+
+impl From<FeedbackEntryRow> for FeedbackEntry {
+    fn from(r: FeedbackEntryRow) -> Self {
+        FeedbackEntry {
+            id: r.id,
+            task_id: r.task_id,
+            phase: r.phase,
+            feedback: r.feedback,
+            author: r.author,
+            created_at: r.created_at,
+        }
+    }
+}
+
+/// Record a feedback round through `executor`, so it lands in the same
+/// transaction as the rejection it belongs to.
+pub(crate) async fn pg_insert_feedback_entry(
+    executor: impl sqlx::PgExecutor<'_>,
+    task_id: &str,
+    phase: &str,
+    feedback: &str,
+    author: &str,
+) -> Result<(), DbError> {
+    sqlx::query(
+        "INSERT INTO feedback_history (id, task_id, phase, feedback, author, created_at)
+         VALUES ($1, $2, $3, $4, $5, $6)",
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(task_id)
+    .bind(phase)
+    .bind(feedback)
+    .bind(author)
+    .bind(Utc::now())
+    .execute(executor)
+    .await
+    .map_err(pg_err)?;
+    Ok(())
+}
+
+impl PostgresDatabase {
+    pub(crate) async fn pg_add_feedback_entry(
+        &self,
+        task_id: &str,
+        phase: &str,
+        feedback: &str,
+        author: &str,
+    ) -> Result<FeedbackEntry, DbError> {
+        let row = sqlx::query_as::<_, FeedbackEntryRow>(
+            "INSERT INTO feedback_history (id, task_id, phase, feedback, author, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             RETURNING *",
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(task_id)
+        .bind(phase)
+        .bind(feedback)
+        .bind(author)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        Ok(row.into())
+    }
+
+    pub(crate) async fn pg_list_feedback_history(
+        &self,
+        task_id: &str,
+    ) -> Result<Vec<FeedbackEntry>, DbError> {
+        let rows = sqlx::query_as::<_, FeedbackEntryRow>(
+            "SELECT * FROM feedback_history WHERE task_id = $1 ORDER BY created_at DESC",
+        )
+        .bind(task_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+}
@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+
+use flowstate_core::label::Label;
+
+use super::super::{pg_err, PostgresDatabase};
+use crate::snapshot::TaskLabel;
+use crate::DbError;
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct LabelRow {
+    id: String,
+    project_id: String,
+    name: String,
+    color: String,
+    created_at: DateTime<Utc>,
+}
+
+impl From<LabelRow> for Label {
+    fn from(r: LabelRow) -> Self {
+        Label {
+            id: r.id,
+            project_id: r.project_id,
+            name: r.name,
+            color: r.color,
+            created_at: r.created_at,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct TaskLabelRow {
+    task_id: String,
+    label_id: String,
+}
+
+impl From<TaskLabelRow> for TaskLabel {
+    fn from(r: TaskLabelRow) -> Self {
+        TaskLabel {
+            task_id: r.task_id,
+            label_id: r.label_id,
+        }
+    }
+}
+
+impl PostgresDatabase {
+    pub(crate) async fn pg_ensure_label(
+        &self,
+        project_id: &str,
+        name: &str,
+        color: &str,
+    ) -> Result<Label, DbError> {
+        sqlx::query(
+            "INSERT INTO labels (id, project_id, name, color, created_at)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (project_id, name) DO NOTHING",
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(project_id)
+        .bind(name)
+        .bind(color)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        let row = sqlx::query_as::<_, LabelRow>(
+            "SELECT * FROM labels WHERE project_id = $1 AND name = $2",
+        )
+        .bind(project_id)
+        .bind(name)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(pg_err)?;
+        Ok(row.into())
+    }
+
+    pub(crate) async fn pg_add_task_label(
+        &self,
+        task_id: &str,
+        label_id: &str,
+    ) -> Result<(), DbError> {
+        sqlx::query(
+            "INSERT INTO task_labels (task_id, label_id) VALUES ($1, $2)
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(task_id)
+        .bind(label_id)
+        .execute(&self.pool)
+        .await
+        .map_err(pg_err)?;
+        Ok(())
+    }
+
+    pub(crate) async fn pg_list_task_labels(&self, task_id: &str) -> Result<Vec<Label>, DbError> {
+        let rows = sqlx::query_as::<_, LabelRow>(
+            "SELECT labels.* FROM labels
+             JOIN task_labels ON task_labels.label_id = labels.id
+             WHERE task_labels.task_id = $1
+             ORDER BY labels.name",
+        )
+        .bind(task_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(pg_err)?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+}
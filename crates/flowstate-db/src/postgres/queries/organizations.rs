@@ -0,0 +1,125 @@
+use chrono::{DateTime, Utc};
+
+use flowstate_core::organization::{CreateOrganization, Organization};
+use flowstate_core::project::Project;
+
+use super::super::{pg_err, pg_not_found, PostgresDatabase};
+use super::projects::ProjectRow;
+use crate::DbError;
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct OrganizationRow {
+    id: String,
+    name: String,
+    slug: String,
+    created_at: DateTime<Utc>,
+}
+
+impl From<OrganizationRow> for Organization {
+    fn from(r: OrganizationRow) -> Self {
+        Organization {
+            id: r.id,
+            name: r.name,
+            slug: r.slug,
+            created_at: r.created_at,
+        }
+    }
+}
+
+impl PostgresDatabase {
+    pub(crate) async fn pg_create_organization(
+        &self,
+        input: &CreateOrganization,
+    ) -> Result<Organization, DbError> {
+        let row = sqlx::query_as::<_, OrganizationRow>(
+            "INSERT INTO organizations (id, name, slug, created_at) VALUES ($1, $2, $3, $4)
+             RETURNING *",
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(input.name.trim())
+        .bind(&input.slug)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        Ok(row.into())
+    }
+
+    pub(crate) async fn pg_get_organization(&self, id: &str) -> Result<Organization, DbError> {
+        let row = sqlx::query_as::<_, OrganizationRow>("SELECT * FROM organizations WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(pg_err)?
+            .ok_or_else(|| pg_not_found(&format!("organization {id}")))?;
+
+        Ok(row.into())
+    }
+
+    pub(crate) async fn pg_list_organizations(&self) -> Result<Vec<Organization>, DbError> {
+        let rows =
+            sqlx::query_as::<_, OrganizationRow>("SELECT * FROM organizations ORDER BY name")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(pg_err)?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    pub(crate) async fn pg_delete_organization(&self, id: &str) -> Result<(), DbError> {
+        let mut tx = self.pool.begin().await.map_err(pg_err)?;
+
+        sqlx::query("DELETE FROM api_keys WHERE org_id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        sqlx::query("UPDATE projects SET org_id = NULL WHERE org_id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        let result = sqlx::query("DELETE FROM organizations WHERE id = $1")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        if result.rows_affected() == 0 {
+            return Err(pg_not_found(&format!("organization {id}")));
+        }
+        tx.commit().await.map_err(pg_err)?;
+
+        Ok(())
+    }
+
+    pub(crate) async fn pg_list_organization_project_ids(
+        &self,
+        org_id: &str,
+    ) -> Result<Vec<String>, DbError> {
+        sqlx::query_scalar("SELECT id FROM projects WHERE org_id = $1 ORDER BY id")
+            .bind(org_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(pg_err)
+    }
+
+    pub(crate) async fn pg_set_project_org(
+        &self,
+        project_id: &str,
+        org_id: Option<&str>,
+    ) -> Result<Project, DbError> {
+        let row = sqlx::query_as::<_, ProjectRow>(
+            "UPDATE projects SET org_id = $1, updated_at = $2 WHERE id = $3 RETURNING *",
+        )
+        .bind(org_id)
+        .bind(Utc::now())
+        .bind(project_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(pg_err)?
+        .ok_or_else(|| pg_not_found(&format!("project {project_id}")))?;
+
+        Ok(row.into())
+    }
+}
@@ -1,12 +1,13 @@
 use chrono::{DateTime, Utc};
 
+use flowstate_core::dashboard::ProjectDashboard;
 use flowstate_core::project::{CreateProject, Project, ProviderType, UpdateProject};
 
 use super::super::{pg_err, pg_not_found, PostgresDatabase};
-use crate::DbError;
+use crate::{dashboard_from_rows, DashboardRow, DbError};
 
 #[derive(sqlx::FromRow)]
-struct ProjectRow {
+pub(crate) struct ProjectRow {
     id: String,
     name: String,
     slug: String,
@@ -15,6 +16,20 @@ struct ProjectRow {
     repo_token: Option<String>,
     provider_type: Option<String>,
     skip_tls_verify: bool,
+    max_concurrent_runs: Option<i32>,
+    claim_weight: i32,
+    run_window_start: Option<i32>,
+    run_window_end: Option<i32>,
+    run_window_offset: Option<i32>,
+    run_window_tz: Option<String>,
+    docs_in_repo: bool,
+    verify_followups: bool,
+    org_id: Option<String>,
+    monthly_budget_usd: Option<f64>,
+    budget_override_until: Option<DateTime<Utc>>,
+    ssh_key_path: Option<String>,
+    fork_owner: Option<String>,
+    repo_subpath: Option<String>,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
 }
@@ -30,6 +45,22 @@ impl From<ProjectRow> for Project {
             repo_token: r.repo_token,
             provider_type: r.provider_type.as_deref().and_then(ProviderType::parse_str),
             skip_tls_verify: r.skip_tls_verify,
+            max_concurrent_runs: r.max_concurrent_runs,
+            claim_weight: r.claim_weight,
+            run_window: crate::run_window_from_columns(
+                r.run_window_start,
+                r.run_window_end,
+                r.run_window_offset,
+                r.run_window_tz,
+            ),
+            docs_in_repo: r.docs_in_repo,
+            verify_followups: r.verify_followups,
+            org_id: r.org_id,
+            monthly_budget_usd: r.monthly_budget_usd,
+            budget_override_until: r.budget_override_until,
+            ssh_key_path: r.ssh_key_path,
+            fork_owner: r.fork_owner,
+            repo_subpath: r.repo_subpath,
             created_at: r.created_at,
             updated_at: r.updated_at,
         }
@@ -159,6 +190,74 @@ impl PostgresDatabase {
             bool_bind = Some((param_idx, skip_tls_verify));
             param_idx += 1;
         }
+        // Integer params are bound after the bool, in this order.
+        let mut max_runs_bind: Option<Option<i32>> = None;
+        if let Some(max_concurrent_runs) = update.max_concurrent_runs {
+            sets.push(format!("max_concurrent_runs = ${param_idx}"));
+            max_runs_bind = Some(max_concurrent_runs);
+            param_idx += 1;
+        }
+        let mut weight_bind: Option<i32> = None;
+        if let Some(claim_weight) = update.claim_weight {
+            sets.push(format!("claim_weight = ${param_idx}"));
+            weight_bind = Some(claim_weight);
+            param_idx += 1;
+        }
+        let mut window_bind: Option<Option<flowstate_core::RunWindow>> = None;
+        if let Some(run_window) = update.run_window {
+            sets.push(format!(
+                "run_window_start = ${}, run_window_end = ${}, run_window_offset = ${}, \
+                 run_window_tz = ${}",
+                param_idx,
+                param_idx + 1,
+                param_idx + 2,
+                param_idx + 3
+            ));
+            window_bind = Some(run_window);
+            param_idx += 4;
+        }
+        let mut docs_bind: Option<bool> = None;
+        if let Some(docs_in_repo) = update.docs_in_repo {
+            sets.push(format!("docs_in_repo = ${param_idx}"));
+            docs_bind = Some(docs_in_repo);
+            param_idx += 1;
+        }
+        let mut followups_bind: Option<bool> = None;
+        if let Some(verify_followups) = update.verify_followups {
+            sets.push(format!("verify_followups = ${param_idx}"));
+            followups_bind = Some(verify_followups);
+            param_idx += 1;
+        }
+        let mut budget_bind: Option<Option<f64>> = None;
+        if let Some(monthly_budget_usd) = update.monthly_budget_usd {
+            sets.push(format!("monthly_budget_usd = ${param_idx}"));
+            budget_bind = Some(monthly_budget_usd);
+            param_idx += 1;
+        }
+        let mut override_bind: Option<Option<DateTime<Utc>>> = None;
+        if let Some(budget_override_until) = update.budget_override_until {
+            sets.push(format!("budget_override_until = ${param_idx}"));
+            override_bind = Some(budget_override_until);
+            param_idx += 1;
+        }
+        let mut ssh_key_bind: Option<Option<String>> = None;
+        if let Some(ssh_key_path) = &update.ssh_key_path {
+            sets.push(format!("ssh_key_path = ${param_idx}"));
+            ssh_key_bind = Some(ssh_key_path.clone());
+            param_idx += 1;
+        }
+        let mut fork_owner_bind: Option<Option<String>> = None;
+        if let Some(fork_owner) = &update.fork_owner {
+            sets.push(format!("fork_owner = ${param_idx}"));
+            fork_owner_bind = Some(fork_owner.clone());
+            param_idx += 1;
+        }
+        let mut subpath_bind: Option<Option<String>> = None;
+        if let Some(repo_subpath) = &update.repo_subpath {
+            sets.push(format!("repo_subpath = ${param_idx}"));
+            subpath_bind = Some(repo_subpath.clone());
+            param_idx += 1;
+        }
 
         if sets.is_empty() {
             return self.pg_get_project(id).await;
@@ -182,6 +281,40 @@ impl PostgresDatabase {
         if let Some((_, val)) = bool_bind {
             query = query.bind(val);
         }
+        if let Some(val) = max_runs_bind {
+            query = query.bind(val);
+        }
+        if let Some(val) = weight_bind {
+            query = query.bind(val);
+        }
+        if let Some(window) = window_bind {
+            query = query
+                .bind(window.map(|w| w.start))
+                .bind(window.map(|w| w.end))
+                .bind(window.map(|w| w.utc_offset))
+                .bind(window.and_then(|w| w.time_zone_name()));
+        }
+        if let Some(val) = docs_bind {
+            query = query.bind(val);
+        }
+        if let Some(val) = followups_bind {
+            query = query.bind(val);
+        }
+        if let Some(val) = budget_bind {
+            query = query.bind(val);
+        }
+        if let Some(val) = override_bind {
+            query = query.bind(val);
+        }
+        if let Some(val) = ssh_key_bind {
+            query = query.bind(val);
+        }
+        if let Some(val) = fork_owner_bind {
+            query = query.bind(val);
+        }
+        if let Some(val) = subpath_bind {
+            query = query.bind(val);
+        }
         query = query.bind(now);
         query = query.bind(id);
 
@@ -207,4 +340,48 @@ impl PostgresDatabase {
 
         Ok(())
     }
+
+    pub(crate) async fn pg_project_dashboard(
+        &self,
+        project_id: &str,
+    ) -> Result<ProjectDashboard, DbError> {
+        let rows: Vec<(String, String, String, String, i64, i64)> = sqlx::query_as(
+            "SELECT 'status'::TEXT, status, ''::TEXT, ''::TEXT, COUNT(*), 0::BIGINT
+             FROM tasks WHERE project_id = $1 AND NOT archived
+             GROUP BY status
+             UNION ALL
+             SELECT 'priority', priority, '', '', COUNT(*), 0
+             FROM tasks WHERE project_id = $1 AND NOT archived
+             GROUP BY priority
+             UNION ALL
+             SELECT 'run', r.action, '', r.status, COUNT(*), 0
+             FROM claude_runs r JOIN tasks t ON t.id = r.task_id
+             WHERE t.project_id = $1
+               AND r.status IN ('completed', 'failed', 'timed_out', 'cancelled')
+             GROUP BY r.action, r.status
+             UNION ALL
+             SELECT 'sprint', s.id, s.name, s.status, COUNT(t.id),
+                    COALESCE(SUM(CASE WHEN t.status = 'done' THEN 1 ELSE 0 END), 0)
+             FROM sprints s LEFT JOIN tasks t ON t.sprint_id = s.id
+             WHERE s.project_id = $1 AND NOT s.archived
+             GROUP BY s.id, s.name, s.status",
+        )
+        .bind(project_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        let rows = rows
+            .into_iter()
+            .map(|(kind, key, name, state, total, done)| DashboardRow {
+                kind,
+                key,
+                name,
+                state,
+                total,
+                done,
+            })
+            .collect();
+        Ok(dashboard_from_rows(project_id, rows))
+    }
 }
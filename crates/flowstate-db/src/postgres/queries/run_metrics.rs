@@ -0,0 +1,139 @@
+use chrono::{DateTime, Utc};
+
+use flowstate_core::run_metrics::{
+    RecordRunMetrics, RunMetrics, RunMetricsFilter, RunMetricsSummary,
+};
+
+use super::super::{pg_err, pg_not_found, PostgresDatabase};
+use crate::DbError;
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct RunMetricsRow {
+    run_id: String,
+    duration_ms: i64,
+    stdout_bytes: i64,
+    input_tokens: Option<i64>,
+    output_tokens: Option<i64>,
+    cost_usd: Option<f64>,
+    recorded_at: DateTime<Utc>,
+}
+
+impl From<RunMetricsRow> for RunMetrics {
+    fn from(r: RunMetricsRow) -> Self {
+        RunMetrics {
+            run_id: r.run_id,
+            duration_ms: r.duration_ms,
+            stdout_bytes: r.stdout_bytes,
+            input_tokens: r.input_tokens,
+            output_tokens: r.output_tokens,
+            cost_usd: r.cost_usd,
+            recorded_at: r.recorded_at,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct SummaryRow {
+    action: String,
+    runs: i64,
+    total_duration_ms: i64,
+    total_stdout_bytes: i64,
+    input_tokens: i64,
+    output_tokens: i64,
+    cost_usd: f64,
+    unpriced_runs: i64,
+}
+
+impl From<SummaryRow> for RunMetricsSummary {
+    fn from(r: SummaryRow) -> Self {
+        RunMetricsSummary {
+            action: r.action,
+            runs: r.runs,
+            total_duration_ms: r.total_duration_ms,
+            avg_duration_ms: if r.runs > 0 {
+                r.total_duration_ms / r.runs
+            } else {
+                0
+            },
+            total_stdout_bytes: r.total_stdout_bytes,
+            input_tokens: r.input_tokens,
+            output_tokens: r.output_tokens,
+            cost_usd: r.cost_usd,
+            unpriced_runs: r.unpriced_runs,
+        }
+    }
+}
+
+impl PostgresDatabase {
+    pub(crate) async fn pg_record_run_metrics(
+        &self,
+        run_id: &str,
+        input: &RecordRunMetrics,
+    ) -> Result<RunMetrics, DbError> {
+        let exists: Option<i32> = sqlx::query_scalar("SELECT 1 FROM claude_runs WHERE id = $1")
+            .bind(run_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(pg_err)?;
+        if exists.is_none() {
+            return Err(pg_not_found(&format!("claude_run {run_id}")));
+        }
+
+        let row = sqlx::query_as::<_, RunMetricsRow>(
+            "INSERT INTO run_metrics (
+                 run_id, duration_ms, stdout_bytes, input_tokens, output_tokens,
+                 cost_usd, recorded_at
+             ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+             ON CONFLICT (run_id) DO UPDATE SET
+                 duration_ms = EXCLUDED.duration_ms,
+                 stdout_bytes = EXCLUDED.stdout_bytes,
+                 input_tokens = EXCLUDED.input_tokens,
+                 output_tokens = EXCLUDED.output_tokens,
+                 cost_usd = EXCLUDED.cost_usd,
+                 recorded_at = EXCLUDED.recorded_at
+             RETURNING *",
+        )
+        .bind(run_id)
+        .bind(input.duration_ms)
+        .bind(input.stdout_bytes)
+        .bind(input.input_tokens)
+        .bind(input.output_tokens)
+        .bind(input.cost_usd)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        Ok(row.into())
+    }
+
+    pub(crate) async fn pg_summarize_run_metrics(
+        &self,
+        filter: &RunMetricsFilter,
+    ) -> Result<Vec<RunMetricsSummary>, DbError> {
+        let rows = sqlx::query_as::<_, SummaryRow>(
+            "SELECT r.action AS action,
+                    COUNT(*) AS runs,
+                    COALESCE(SUM(m.duration_ms), 0)::BIGINT AS total_duration_ms,
+                    COALESCE(SUM(m.stdout_bytes), 0)::BIGINT AS total_stdout_bytes,
+                    COALESCE(SUM(m.input_tokens), 0)::BIGINT AS input_tokens,
+                    COALESCE(SUM(m.output_tokens), 0)::BIGINT AS output_tokens,
+                    COALESCE(SUM(m.cost_usd), 0)::DOUBLE PRECISION AS cost_usd,
+                    COUNT(*) - COUNT(m.cost_usd) AS unpriced_runs
+             FROM run_metrics m
+             JOIN claude_runs r ON r.id = m.run_id
+             JOIN tasks t ON t.id = r.task_id
+             WHERE ($1::TEXT IS NULL OR t.project_id = $1)
+               AND ($2::TIMESTAMPTZ IS NULL OR m.recorded_at >= $2)
+             GROUP BY r.action
+             ORDER BY r.action",
+        )
+        .bind(&filter.project_id)
+        .bind(filter.since)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+}
@@ -0,0 +1,150 @@
+use chrono::{DateTime, Utc};
+
+use flowstate_core::runner::{PendingRunnerConfig, RunnerRecord, RunnerStatus};
+
+use super::super::{pg_err, pg_not_found, PostgresDatabase};
+use crate::DbError;
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct RunnerRow {
+    runner_id: String,
+    org_id: Option<String>,
+    backend_name: Option<String>,
+    capability: Option<String>,
+    poll_interval: Option<i64>,
+    max_concurrent: Option<i64>,
+    max_builds: Option<i64>,
+    active_count: Option<i64>,
+    active_builds: Option<i64>,
+    status: String,
+    pending_config: Option<String>,
+    benchmark: Option<String>,
+    first_seen_at: DateTime<Utc>,
+    last_seen_at: DateTime<Utc>,
+}
+
+impl From<RunnerRow> for RunnerRecord {
+    fn from(r: RunnerRow) -> Self {
+        let count = |n: Option<i64>| n.map(|n| n as usize);
+        RunnerRecord {
+            runner_id: r.runner_id,
+            org_id: r.org_id,
+            backend_name: r.backend_name,
+            capability: r.capability,
+            poll_interval: r.poll_interval.map(|n| n as u64),
+            max_concurrent: count(r.max_concurrent),
+            max_builds: count(r.max_builds),
+            active_count: count(r.active_count),
+            active_builds: count(r.active_builds),
+            status: RunnerStatus::parse_str(&r.status).unwrap_or(RunnerStatus::Active),
+            pending_config: r.pending_config.and_then(|s| serde_json::from_str(&s).ok()),
+            benchmark: r.benchmark.and_then(|s| serde_json::from_str(&s).ok()),
+            first_seen_at: r.first_seen_at,
+            last_seen_at: r.last_seen_at,
+        }
+    }
+}
+
+fn to_json<T: serde::Serialize>(value: Option<&T>) -> Result<Option<String>, DbError> {
+    value
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| DbError::Internal(e.to_string()))
+}
+
+impl PostgresDatabase {
+    pub(crate) async fn pg_upsert_runner(
+        &self,
+        runner: &RunnerRecord,
+    ) -> Result<RunnerRecord, DbError> {
+        let row = sqlx::query_as::<_, RunnerRow>(
+            "INSERT INTO runners (runner_id, org_id, backend_name, capability, poll_interval,
+                 max_concurrent, max_builds, active_count, active_builds, status,
+                 pending_config, benchmark, first_seen_at, last_seen_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+             ON CONFLICT (runner_id) DO UPDATE SET
+                 org_id = EXCLUDED.org_id,
+                 backend_name = EXCLUDED.backend_name,
+                 capability = EXCLUDED.capability,
+                 poll_interval = EXCLUDED.poll_interval,
+                 max_concurrent = EXCLUDED.max_concurrent,
+                 max_builds = EXCLUDED.max_builds,
+                 active_count = EXCLUDED.active_count,
+                 active_builds = EXCLUDED.active_builds,
+                 status = EXCLUDED.status,
+                 benchmark = EXCLUDED.benchmark,
+                 last_seen_at = EXCLUDED.last_seen_at
+             RETURNING *",
+        )
+        .bind(&runner.runner_id)
+        .bind(&runner.org_id)
+        .bind(&runner.backend_name)
+        .bind(&runner.capability)
+        .bind(runner.poll_interval.map(|n| n as i64))
+        .bind(runner.max_concurrent.map(|n| n as i64))
+        .bind(runner.max_builds.map(|n| n as i64))
+        .bind(runner.active_count.map(|n| n as i64))
+        .bind(runner.active_builds.map(|n| n as i64))
+        .bind(runner.status.as_str())
+        .bind(to_json(runner.pending_config.as_ref())?)
+        .bind(to_json(runner.benchmark.as_ref())?)
+        .bind(runner.first_seen_at)
+        .bind(runner.last_seen_at)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        Ok(row.into())
+    }
+
+    pub(crate) async fn pg_get_runner(&self, runner_id: &str) -> Result<RunnerRecord, DbError> {
+        let row = sqlx::query_as::<_, RunnerRow>("SELECT * FROM runners WHERE runner_id = $1")
+            .bind(runner_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(pg_err)?
+            .ok_or_else(|| pg_not_found(&format!("runner {runner_id}")))?;
+
+        Ok(row.into())
+    }
+
+    pub(crate) async fn pg_list_runners(&self) -> Result<Vec<RunnerRecord>, DbError> {
+        let rows = sqlx::query_as::<_, RunnerRow>("SELECT * FROM runners ORDER BY runner_id")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(pg_err)?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    pub(crate) async fn pg_set_runner_pending_config(
+        &self,
+        runner_id: &str,
+        config: Option<&PendingRunnerConfig>,
+    ) -> Result<(), DbError> {
+        let result = sqlx::query("UPDATE runners SET pending_config = $1 WHERE runner_id = $2")
+            .bind(to_json(config)?)
+            .bind(runner_id)
+            .execute(&self.pool)
+            .await
+            .map_err(pg_err)?;
+
+        if result.rows_affected() == 0 {
+            return Err(pg_not_found(&format!("runner {runner_id}")));
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn pg_delete_runner(&self, runner_id: &str) -> Result<(), DbError> {
+        let result = sqlx::query("DELETE FROM runners WHERE runner_id = $1")
+            .bind(runner_id)
+            .execute(&self.pool)
+            .await
+            .map_err(pg_err)?;
+
+        if result.rows_affected() == 0 {
+            return Err(pg_not_found(&format!("runner {runner_id}")));
+        }
+        Ok(())
+    }
+}
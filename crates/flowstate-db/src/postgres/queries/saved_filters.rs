@@ -0,0 +1,150 @@
+use chrono::{DateTime, Utc};
+
+use flowstate_core::saved_filter::{CreateSavedFilter, SavedFilter, UpdateSavedFilter};
+
+use super::super::{pg_err, pg_not_found, PostgresDatabase};
+use crate::DbError;
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct SavedFilterRow {
+    id: String,
+    project_id: String,
+    user_id: Option<String>,
+    name: String,
+    query: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<SavedFilterRow> for SavedFilter {
+    fn from(r: SavedFilterRow) -> Self {
+        SavedFilter {
+            id: r.id,
+            project_id: r.project_id,
+            user_id: r.user_id,
+            name: r.name,
+            query: serde_json::from_str(&r.query).unwrap_or_default(),
+            created_at: r.created_at,
+            updated_at: r.updated_at,
+        }
+    }
+}
+
+impl PostgresDatabase {
+    pub(crate) async fn pg_create_saved_filter(
+        &self,
+        input: &CreateSavedFilter,
+    ) -> Result<SavedFilter, DbError> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = Utc::now();
+        let query =
+            serde_json::to_string(&input.query).map_err(|e| DbError::Internal(e.to_string()))?;
+
+        sqlx::query(
+            "INSERT INTO saved_filters (id, project_id, user_id, name, query, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)",
+        )
+        .bind(&id)
+        .bind(&input.project_id)
+        .bind(&input.user_id)
+        .bind(&input.name)
+        .bind(query)
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        self.pg_get_saved_filter(&id).await
+    }
+
+    pub(crate) async fn pg_get_saved_filter(&self, id: &str) -> Result<SavedFilter, DbError> {
+        let row = sqlx::query_as::<_, SavedFilterRow>("SELECT * FROM saved_filters WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(pg_err)?
+            .ok_or_else(|| pg_not_found(&format!("saved filter {id}")))?;
+
+        Ok(row.into())
+    }
+
+    pub(crate) async fn pg_list_saved_filters(
+        &self,
+        project_id: &str,
+        user_id: Option<&str>,
+    ) -> Result<Vec<SavedFilter>, DbError> {
+        let rows = sqlx::query_as::<_, SavedFilterRow>(
+            "SELECT * FROM saved_filters
+             WHERE project_id = $1 AND ($2::TEXT IS NULL OR user_id IS NULL OR user_id = $2)
+             ORDER BY name, id",
+        )
+        .bind(project_id)
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    pub(crate) async fn pg_update_saved_filter(
+        &self,
+        id: &str,
+        update: &UpdateSavedFilter,
+    ) -> Result<SavedFilter, DbError> {
+        if update.name.is_none() && update.query.is_none() {
+            return self.pg_get_saved_filter(id).await;
+        }
+
+        let mut sets = Vec::new();
+        let mut binds: Vec<String> = Vec::new();
+
+        if let Some(ref name) = update.name {
+            binds.push(name.clone());
+            sets.push(format!("name = ${}", binds.len()));
+        }
+        if let Some(ref query) = update.query {
+            binds.push(serde_json::to_string(query).map_err(|e| DbError::Internal(e.to_string()))?);
+            sets.push(format!("query = ${}", binds.len()));
+        }
+
+        let sql = format!(
+            "UPDATE saved_filters SET {}, updated_at = ${} WHERE id = ${}",
+            sets.join(", "),
+            binds.len() + 1,
+            binds.len() + 2
+        );
+
+        let mut query = sqlx::query(&sql);
+        for bind in &binds {
+            query = query.bind(bind);
+        }
+        let result = query
+            .bind(Utc::now())
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(pg_err)?;
+
+        if result.rows_affected() == 0 {
+            return Err(pg_not_found(&format!("saved filter {id}")));
+        }
+
+        self.pg_get_saved_filter(id).await
+    }
+
+    pub(crate) async fn pg_delete_saved_filter(&self, id: &str) -> Result<(), DbError> {
+        let result = sqlx::query("DELETE FROM saved_filters WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(pg_err)?;
+
+        if result.rows_affected() == 0 {
+            return Err(pg_not_found(&format!("saved filter {id}")));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,763 @@
+use flowstate_core::notification::Notification;
+use flowstate_core::task_revision::TaskRevision;
+use flowstate_core::webhook::Webhook;
+
+use crate::snapshot::Snapshot;
+
+use super::super::{pg_err, PostgresDatabase};
+use super::approval_rules::ApprovalRuleRow;
+use super::attachments::AttachmentRow;
+use super::claude_runs::ClaudeRunRow;
+use super::custom_fields::{CustomFieldRow, TaskFieldValueRow};
+use super::epics::EpicRow;
+use super::feature_flags::FeatureFlagRow;
+use super::feedback_history::FeedbackEntryRow;
+use super::labels::{LabelRow, TaskLabelRow};
+use super::organizations::OrganizationRow;
+use super::projects::ProjectRow;
+use super::run_metrics::RunMetricsRow;
+use super::saved_filters::SavedFilterRow;
+use super::sprints::SprintRow;
+use super::subscriptions::SubscriptionRow;
+use super::task_imports::TaskImportRow;
+use super::task_links::TaskLinkRow;
+use super::task_prs::TaskPrRow;
+use super::task_revisions::TaskRevisionRow;
+use super::tasks::TaskRow;
+use super::users::UserRow;
+use super::watchers::{NotificationRow, TaskWatcherRow};
+use super::webhooks::WebhookRow;
+use crate::DbError;
+
+impl PostgresDatabase {
+    pub(crate) async fn pg_export_snapshot(&self) -> Result<Snapshot, DbError> {
+        // One read-only transaction so every table comes from the same
+        // snapshot; separate pool connections could each see a different one.
+        let mut tx = self.pool.begin().await.map_err(pg_err)?;
+        sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        let mut snapshot = Snapshot::new();
+
+        snapshot.organizations =
+            sqlx::query_as::<_, OrganizationRow>("SELECT * FROM organizations ORDER BY created_at")
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(pg_err)?
+                .into_iter()
+                .map(|r| r.into())
+                .collect();
+        snapshot.projects =
+            sqlx::query_as::<_, ProjectRow>("SELECT * FROM projects ORDER BY created_at")
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(pg_err)?
+                .into_iter()
+                .map(|r| r.into())
+                .collect();
+        snapshot.sprints =
+            sqlx::query_as::<_, SprintRow>("SELECT * FROM sprints ORDER BY created_at")
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(pg_err)?
+                .into_iter()
+                .map(|r| r.into())
+                .collect();
+        snapshot.epics = sqlx::query_as::<_, EpicRow>("SELECT * FROM epics ORDER BY created_at")
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(pg_err)?
+            .into_iter()
+            .map(|r| r.into())
+            .collect();
+        snapshot.labels = sqlx::query_as::<_, LabelRow>("SELECT * FROM labels ORDER BY created_at")
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(pg_err)?
+            .into_iter()
+            .map(|r| r.into())
+            .collect();
+        snapshot.users = sqlx::query_as::<_, UserRow>("SELECT * FROM users ORDER BY created_at")
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(pg_err)?
+            .into_iter()
+            .map(|r| r.into())
+            .collect();
+        snapshot.saved_filters =
+            sqlx::query_as::<_, SavedFilterRow>("SELECT * FROM saved_filters ORDER BY created_at")
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(pg_err)?
+                .into_iter()
+                .map(|r| r.into())
+                .collect();
+        snapshot.custom_fields =
+            sqlx::query_as::<_, CustomFieldRow>("SELECT * FROM custom_fields ORDER BY created_at")
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(pg_err)?
+                .into_iter()
+                .map(|r| r.into())
+                .collect();
+        snapshot.tasks = sqlx::query_as::<_, TaskRow>("SELECT * FROM tasks ORDER BY created_at")
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(pg_err)?
+            .into_iter()
+            .map(|r| r.into())
+            .collect();
+        snapshot.task_field_values = sqlx::query_as::<_, TaskFieldValueRow>(
+            "SELECT * FROM task_field_values ORDER BY task_id, field_id",
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(pg_err)?
+        .into_iter()
+        .map(|r| r.into())
+        .collect();
+        snapshot.task_labels = sqlx::query_as::<_, TaskLabelRow>(
+            "SELECT * FROM task_labels ORDER BY task_id, label_id",
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(pg_err)?
+        .into_iter()
+        .map(|r| r.into())
+        .collect();
+        snapshot.claude_runs =
+            sqlx::query_as::<_, ClaudeRunRow>("SELECT * FROM claude_runs ORDER BY started_at")
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(pg_err)?
+                .into_iter()
+                .map(|r| r.into())
+                .collect();
+        snapshot.run_metrics =
+            sqlx::query_as::<_, RunMetricsRow>("SELECT * FROM run_metrics ORDER BY recorded_at")
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(pg_err)?
+                .into_iter()
+                .map(|r| r.into())
+                .collect();
+        snapshot.task_links =
+            sqlx::query_as::<_, TaskLinkRow>("SELECT * FROM task_links ORDER BY created_at")
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(pg_err)?
+                .into_iter()
+                .map(|r| r.into())
+                .collect();
+        snapshot.task_prs =
+            sqlx::query_as::<_, TaskPrRow>("SELECT * FROM task_prs ORDER BY created_at")
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(pg_err)?
+                .into_iter()
+                .map(|r| r.into())
+                .collect();
+        snapshot.attachments =
+            sqlx::query_as::<_, AttachmentRow>("SELECT * FROM attachments ORDER BY created_at")
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(pg_err)?
+                .into_iter()
+                .map(|r| r.into())
+                .collect();
+        snapshot.task_revisions = sqlx::query_as::<_, TaskRevisionRow>(
+            "SELECT * FROM task_revisions ORDER BY created_at",
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(pg_err)?
+        .into_iter()
+        .map(TaskRevision::try_from)
+        .collect::<Result<_, _>>()?;
+        snapshot.feedback_history = sqlx::query_as::<_, FeedbackEntryRow>(
+            "SELECT * FROM feedback_history ORDER BY created_at",
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(pg_err)?
+        .into_iter()
+        .map(|r| r.into())
+        .collect();
+        snapshot.task_watchers =
+            sqlx::query_as::<_, TaskWatcherRow>("SELECT * FROM task_watchers ORDER BY created_at")
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(pg_err)?
+                .into_iter()
+                .map(|r| r.into())
+                .collect();
+        snapshot.notifications =
+            sqlx::query_as::<_, NotificationRow>("SELECT * FROM notifications ORDER BY created_at")
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(pg_err)?
+                .into_iter()
+                .map(Notification::try_from)
+                .collect::<Result<_, _>>()?;
+        snapshot.webhooks =
+            sqlx::query_as::<_, WebhookRow>("SELECT * FROM webhooks ORDER BY created_at")
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(pg_err)?
+                .into_iter()
+                .map(|r| Webhook::from(r).into())
+                .collect();
+        snapshot.feature_flags = sqlx::query_as::<_, FeatureFlagRow>(
+            "SELECT * FROM feature_flags ORDER BY key, project_id",
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(pg_err)?
+        .into_iter()
+        .map(|r| r.into())
+        .collect();
+        snapshot.task_imports =
+            sqlx::query_as::<_, TaskImportRow>("SELECT * FROM task_imports ORDER BY created_at")
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(pg_err)?
+                .into_iter()
+                .map(|r| r.into())
+                .collect();
+        snapshot.approval_rules = sqlx::query_as::<_, ApprovalRuleRow>(
+            "SELECT * FROM approval_rules ORDER BY project_id, phase",
+        )
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(pg_err)?
+        .into_iter()
+        .map(|r| r.into())
+        .collect();
+        snapshot.subscriptions =
+            sqlx::query_as::<_, SubscriptionRow>("SELECT * FROM subscriptions ORDER BY created_at")
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(pg_err)?
+                .into_iter()
+                .map(|r| r.into())
+                .collect();
+        tx.commit().await.map_err(pg_err)?;
+
+        Ok(snapshot)
+    }
+
+    pub(crate) async fn pg_import_snapshot(&self, snapshot: &Snapshot) -> Result<(), DbError> {
+        let mut tx = self.pool.begin().await.map_err(pg_err)?;
+
+        for o in &snapshot.organizations {
+            sqlx::query(
+                "INSERT INTO organizations (id, name, slug, created_at) VALUES ($1, $2, $3, $4)",
+            )
+            .bind(&o.id)
+            .bind(&o.name)
+            .bind(&o.slug)
+            .bind(o.created_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        for p in &snapshot.projects {
+            sqlx::query(
+                "INSERT INTO projects (
+                    id, name, slug, description, repo_url, repo_token,
+                    provider_type, skip_tls_verify, created_at, updated_at,
+                    max_concurrent_runs, claim_weight, run_window_start, run_window_end,
+                    run_window_offset, docs_in_repo, verify_followups, org_id,
+                    monthly_budget_usd, budget_override_until, ssh_key_path,
+                    fork_owner, repo_subpath, run_window_tz
+                 ) VALUES (
+                    $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17,
+                    $18, $19, $20, $21, $22, $23, $24
+                 )",
+            )
+            .bind(&p.id)
+            .bind(&p.name)
+            .bind(&p.slug)
+            .bind(&p.description)
+            .bind(&p.repo_url)
+            .bind(&p.repo_token)
+            .bind(p.provider_type.map(|t| t.as_str()))
+            .bind(p.skip_tls_verify)
+            .bind(p.created_at)
+            .bind(p.updated_at)
+            .bind(p.max_concurrent_runs)
+            .bind(p.claim_weight)
+            .bind(p.run_window.map(|w| w.start))
+            .bind(p.run_window.map(|w| w.end))
+            .bind(p.run_window.map(|w| w.utc_offset))
+            .bind(p.docs_in_repo)
+            .bind(p.verify_followups)
+            .bind(&p.org_id)
+            .bind(p.monthly_budget_usd)
+            .bind(p.budget_override_until)
+            .bind(&p.ssh_key_path)
+            .bind(&p.fork_owner)
+            .bind(&p.repo_subpath)
+            .bind(p.run_window.and_then(|w| w.time_zone_name()))
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        for s in &snapshot.sprints {
+            sqlx::query(
+                "INSERT INTO sprints (
+                    id, project_id, name, goal, starts_at, ends_at, status, archived,
+                    created_at, updated_at
+                 ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+            )
+            .bind(&s.id)
+            .bind(&s.project_id)
+            .bind(&s.name)
+            .bind(&s.goal)
+            .bind(s.starts_at)
+            .bind(s.ends_at)
+            .bind(s.status.as_str())
+            .bind(s.archived)
+            .bind(s.created_at)
+            .bind(s.updated_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        for e in &snapshot.epics {
+            sqlx::query(
+                "INSERT INTO epics (
+                    id, project_id, name, description, status, target_date,
+                    created_at, updated_at
+                 ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+            )
+            .bind(&e.id)
+            .bind(&e.project_id)
+            .bind(&e.name)
+            .bind(&e.description)
+            .bind(e.status.as_str())
+            .bind(e.target_date)
+            .bind(e.created_at)
+            .bind(e.updated_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        for l in &snapshot.labels {
+            sqlx::query(
+                "INSERT INTO labels (id, project_id, name, color, created_at)
+                 VALUES ($1, $2, $3, $4, $5)",
+            )
+            .bind(&l.id)
+            .bind(&l.project_id)
+            .bind(&l.name)
+            .bind(&l.color)
+            .bind(l.created_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        for u in &snapshot.users {
+            sqlx::query(
+                "INSERT INTO users (id, name, email, created_at, updated_at)
+                 VALUES ($1, $2, $3, $4, $5)",
+            )
+            .bind(&u.id)
+            .bind(&u.name)
+            .bind(&u.email)
+            .bind(u.created_at)
+            .bind(u.updated_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        for f in &snapshot.saved_filters {
+            let query =
+                serde_json::to_string(&f.query).map_err(|e| DbError::Internal(e.to_string()))?;
+            sqlx::query(
+                "INSERT INTO saved_filters (
+                    id, project_id, user_id, name, query, created_at, updated_at
+                 ) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            )
+            .bind(&f.id)
+            .bind(&f.project_id)
+            .bind(&f.user_id)
+            .bind(&f.name)
+            .bind(query)
+            .bind(f.created_at)
+            .bind(f.updated_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        for t in snapshot.tasks_parent_first() {
+            sqlx::query(
+                "INSERT INTO tasks (
+                    id, project_id, sprint_id, epic_id, assignee_id, parent_id, title, description, reviewer,
+                    status, priority, sort_order,
+                    research_status, spec_status, plan_status, verify_status,
+                    spec_approved_hash, research_approved_hash,
+                    research_feedback, spec_feedback, plan_feedback, verify_feedback,
+                    research_capability, design_capability, plan_capability,
+                    build_capability, verify_capability, due_at, archived,
+                    created_at, updated_at, task_type, autopilot
+                 ) VALUES (
+                    $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14,
+                    $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27,
+                    $28, $29, $30, $31, $32, $33
+                 )",
+            )
+            .bind(&t.id)
+            .bind(&t.project_id)
+            .bind(&t.sprint_id)
+            .bind(&t.epic_id)
+            .bind(&t.assignee_id)
+            .bind(&t.parent_id)
+            .bind(&t.title)
+            .bind(&t.description)
+            .bind(&t.reviewer)
+            .bind(t.status.as_str())
+            .bind(t.priority.as_str())
+            .bind(t.sort_order)
+            .bind(t.research_status.as_str())
+            .bind(t.spec_status.as_str())
+            .bind(t.plan_status.as_str())
+            .bind(t.verify_status.as_str())
+            .bind(&t.spec_approved_hash)
+            .bind(&t.research_approved_hash)
+            .bind(&t.research_feedback)
+            .bind(&t.spec_feedback)
+            .bind(&t.plan_feedback)
+            .bind(&t.verify_feedback)
+            .bind(t.research_capability.map(|c| c.as_str()))
+            .bind(t.design_capability.map(|c| c.as_str()))
+            .bind(t.plan_capability.map(|c| c.as_str()))
+            .bind(t.build_capability.map(|c| c.as_str()))
+            .bind(t.verify_capability.map(|c| c.as_str()))
+            .bind(t.due_at)
+            .bind(t.archived)
+            .bind(t.created_at)
+            .bind(t.updated_at)
+            .bind(t.task_type.as_str())
+            .bind(t.autopilot)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        for r in &snapshot.claude_runs {
+            sqlx::query(
+                "INSERT INTO claude_runs (
+                    id, task_id, action, status, error_message, exit_code,
+                    pr_url, pr_number, branch_name, progress_message, runner_id,
+                    started_at, finished_at, required_capability, priority, feedback,
+                    idempotency_key, run_window_start, run_window_end, run_window_offset,
+                    trace_context, run_window_tz
+                 ) VALUES (
+                    $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16,
+                    $17, $18, $19, $20, $21, $22
+                 )",
+            )
+            .bind(&r.id)
+            .bind(&r.task_id)
+            .bind(r.action.as_str())
+            .bind(r.status.as_str())
+            .bind(&r.error_message)
+            .bind(r.exit_code)
+            .bind(&r.pr_url)
+            .bind(r.pr_number)
+            .bind(&r.branch_name)
+            .bind(&r.progress_message)
+            .bind(&r.runner_id)
+            .bind(r.started_at)
+            .bind(r.finished_at)
+            .bind(&r.required_capability)
+            .bind(r.priority)
+            .bind(&r.feedback)
+            .bind(&r.idempotency_key)
+            .bind(r.run_window.map(|w| w.start))
+            .bind(r.run_window.map(|w| w.end))
+            .bind(r.run_window.map(|w| w.utc_offset))
+            .bind(&r.trace_context)
+            .bind(r.run_window.and_then(|w| w.time_zone_name()))
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        for m in &snapshot.run_metrics {
+            sqlx::query(
+                "INSERT INTO run_metrics (
+                    run_id, duration_ms, stdout_bytes, input_tokens, output_tokens,
+                    cost_usd, recorded_at
+                 ) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            )
+            .bind(&m.run_id)
+            .bind(m.duration_ms)
+            .bind(m.stdout_bytes)
+            .bind(m.input_tokens)
+            .bind(m.output_tokens)
+            .bind(m.cost_usd)
+            .bind(m.recorded_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        for f in &snapshot.custom_fields {
+            let options =
+                serde_json::to_string(&f.options).map_err(|e| DbError::Internal(e.to_string()))?;
+            sqlx::query(
+                "INSERT INTO custom_fields (
+                    id, project_id, name, field_type, options, created_at, updated_at
+                 ) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            )
+            .bind(&f.id)
+            .bind(&f.project_id)
+            .bind(&f.name)
+            .bind(f.field_type.as_str())
+            .bind(options)
+            .bind(f.created_at)
+            .bind(f.updated_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        for v in &snapshot.task_field_values {
+            sqlx::query(
+                "INSERT INTO task_field_values (task_id, field_id, value) VALUES ($1, $2, $3)",
+            )
+            .bind(&v.task_id)
+            .bind(&v.field_id)
+            .bind(&v.value)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        for tl in &snapshot.task_labels {
+            sqlx::query("INSERT INTO task_labels (task_id, label_id) VALUES ($1, $2)")
+                .bind(&tl.task_id)
+                .bind(&tl.label_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(pg_err)?;
+        }
+
+        for l in &snapshot.task_links {
+            sqlx::query(
+                "INSERT INTO task_links (id, source_task_id, target_task_id, link_type, created_at)
+                 VALUES ($1, $2, $3, $4, $5)",
+            )
+            .bind(&l.id)
+            .bind(&l.source_task_id)
+            .bind(&l.target_task_id)
+            .bind(l.link_type.as_str())
+            .bind(l.created_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        for pr in &snapshot.task_prs {
+            sqlx::query(
+                "INSERT INTO task_prs (
+                    id, task_id, claude_run_id, pr_url, pr_number, branch_name, state,
+                    checks_status, draft, created_at
+                 ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)",
+            )
+            .bind(&pr.id)
+            .bind(&pr.task_id)
+            .bind(&pr.claude_run_id)
+            .bind(&pr.pr_url)
+            .bind(pr.pr_number)
+            .bind(&pr.branch_name)
+            .bind(pr.state.as_str())
+            .bind(pr.checks_status.map(|s| s.as_str()))
+            .bind(pr.draft)
+            .bind(pr.created_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        for a in &snapshot.attachments {
+            sqlx::query(
+                "INSERT INTO attachments
+                     (id, task_id, filename, store_key, size_bytes, content_type, sha256, created_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+            )
+            .bind(&a.id)
+            .bind(&a.task_id)
+            .bind(&a.filename)
+            .bind(&a.store_key)
+            .bind(a.size_bytes)
+            .bind(&a.content_type)
+            .bind(&a.sha256)
+            .bind(a.created_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        for rev in &snapshot.task_revisions {
+            let changes = serde_json::to_string(&rev.changes)
+                .map_err(|e| DbError::Internal(e.to_string()))?;
+            sqlx::query(
+                "INSERT INTO task_revisions (id, task_id, actor, changes, created_at)
+                 VALUES ($1, $2, $3, $4, $5)",
+            )
+            .bind(&rev.id)
+            .bind(&rev.task_id)
+            .bind(&rev.actor)
+            .bind(changes)
+            .bind(rev.created_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        for f in &snapshot.feedback_history {
+            sqlx::query(
+                "INSERT INTO feedback_history (id, task_id, phase, feedback, author, created_at)
+                 VALUES ($1, $2, $3, $4, $5, $6)",
+            )
+            .bind(&f.id)
+            .bind(&f.task_id)
+            .bind(&f.phase)
+            .bind(&f.feedback)
+            .bind(&f.author)
+            .bind(f.created_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        for w in &snapshot.task_watchers {
+            sqlx::query(
+                "INSERT INTO task_watchers (task_id, user_id, created_at) VALUES ($1, $2, $3)",
+            )
+            .bind(&w.task_id)
+            .bind(&w.user_id)
+            .bind(w.created_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        for n in &snapshot.notifications {
+            let changes =
+                serde_json::to_string(&n.changes).map_err(|e| DbError::Internal(e.to_string()))?;
+            sqlx::query(
+                "INSERT INTO notifications (id, user_id, task_id, actor, changes, read, created_at)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            )
+            .bind(&n.id)
+            .bind(&n.user_id)
+            .bind(&n.task_id)
+            .bind(&n.actor)
+            .bind(changes)
+            .bind(n.read)
+            .bind(n.created_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        for w in &snapshot.webhooks {
+            let hook = &w.webhook;
+            let events = serde_json::to_string(&hook.events)
+                .map_err(|e| DbError::Internal(e.to_string()))?;
+            sqlx::query(
+                "INSERT INTO webhooks (
+                    id, url, secret, events, project_id, enabled, created_at, updated_at
+                 ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
+            )
+            .bind(&hook.id)
+            .bind(&hook.url)
+            .bind(&w.secret)
+            .bind(events)
+            .bind(&hook.project_id)
+            .bind(hook.enabled)
+            .bind(hook.created_at)
+            .bind(hook.updated_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        for f in &snapshot.feature_flags {
+            sqlx::query(
+                "INSERT INTO feature_flags (key, project_id, enabled, updated_at)
+                 VALUES ($1, $2, $3, $4)",
+            )
+            .bind(&f.key)
+            .bind(f.project_id.as_deref().unwrap_or(""))
+            .bind(f.enabled)
+            .bind(f.updated_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        for i in &snapshot.task_imports {
+            sqlx::query(
+                "INSERT INTO task_imports (project_id, source, task_id, created_at)
+                 VALUES ($1, $2, $3, $4)",
+            )
+            .bind(&i.project_id)
+            .bind(&i.source)
+            .bind(&i.task_id)
+            .bind(i.created_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        for r in &snapshot.approval_rules {
+            sqlx::query(
+                "INSERT INTO approval_rules (project_id, phase, policy, updated_at)
+                 VALUES ($1, $2, $3, $4)",
+            )
+            .bind(&r.project_id)
+            .bind(r.phase.as_str())
+            .bind(r.policy.as_str())
+            .bind(r.updated_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        for sub in &snapshot.subscriptions {
+            let events =
+                serde_json::to_string(&sub.events).map_err(|e| DbError::Internal(e.to_string()))?;
+            sqlx::query(
+                "INSERT INTO subscriptions (
+                    id, channel, target, events, project_id, user_id, created_at
+                 ) VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            )
+            .bind(&sub.id)
+            .bind(sub.channel.as_str())
+            .bind(&sub.target)
+            .bind(events)
+            .bind(&sub.project_id)
+            .bind(&sub.user_id)
+            .bind(sub.created_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        }
+
+        tx.commit().await.map_err(pg_err)?;
+        Ok(())
+    }
+}
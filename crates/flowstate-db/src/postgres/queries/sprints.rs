@@ -6,7 +6,7 @@ use super::super::{pg_err, pg_not_found, PostgresDatabase};
 use crate::DbError;
 
 #[derive(sqlx::FromRow)]
-struct SprintRow {
+pub(crate) struct SprintRow {
     id: String,
     project_id: String,
     name: String,
@@ -14,6 +14,7 @@ struct SprintRow {
     starts_at: Option<DateTime<Utc>>,
     ends_at: Option<DateTime<Utc>>,
     status: String,
+    archived: bool,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
 }
@@ -28,6 +29,7 @@ impl From<SprintRow> for Sprint {
             starts_at: r.starts_at,
             ends_at: r.ends_at,
             status: SprintStatus::parse_str(&r.status).unwrap_or(SprintStatus::Planned),
+            archived: r.archived,
             created_at: r.created_at,
             updated_at: r.updated_at,
         }
@@ -176,6 +178,41 @@ impl PostgresDatabase {
         self.pg_get_sprint(id).await
     }
 
+    pub(crate) async fn pg_archive_sprint(&self, id: &str) -> Result<Sprint, DbError> {
+        let mut tx = self.pool.begin().await.map_err(pg_err)?;
+        let now = Utc::now();
+        let result =
+            sqlx::query("UPDATE sprints SET archived = TRUE, updated_at = $2 WHERE id = $1")
+                .bind(id)
+                .bind(now)
+                .execute(&mut *tx)
+                .await
+                .map_err(pg_err)?;
+        if result.rows_affected() == 0 {
+            return Err(pg_not_found(&format!("sprint {id}")));
+        }
+        sqlx::query(
+            "WITH RECURSIVE tree(id) AS (
+                 SELECT id FROM tasks WHERE sprint_id = $1
+                 UNION SELECT t.id FROM tasks t JOIN tree ON t.parent_id = tree.id
+             )
+             UPDATE tasks SET archived = TRUE, updated_at = $2
+             WHERE id IN (SELECT id FROM tree)",
+        )
+        .bind(id)
+        .bind(now)
+        .execute(&mut *tx)
+        .await
+        .map_err(pg_err)?;
+        let row = sqlx::query_as::<_, SprintRow>("SELECT * FROM sprints WHERE id = $1")
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(pg_err)?;
+        tx.commit().await.map_err(pg_err)?;
+        Ok(row.into())
+    }
+
     pub(crate) async fn pg_delete_sprint(&self, id: &str) -> Result<(), DbError> {
         let result = sqlx::query("DELETE FROM sprints WHERE id = $1")
             .bind(id)
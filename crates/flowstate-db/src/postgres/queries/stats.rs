@@ -0,0 +1,91 @@
+use std::time::Instant;
+
+use chrono::Utc;
+
+use super::super::{pg_err, PostgresDatabase};
+use crate::stats::{
+    DbStats, MaintenanceReport, MaintenanceStep, PoolStats, TableCount, STATS_TABLES,
+};
+use crate::DbError;
+
+impl PostgresDatabase {
+    pub(crate) async fn pg_stats(&self) -> Result<DbStats, DbError> {
+        let mut tables = Vec::with_capacity(STATS_TABLES.len());
+        for name in STATS_TABLES {
+            let rows: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {name}"))
+                .fetch_one(&self.pool)
+                .await
+                .map_err(pg_err)?;
+            tables.push(TableCount {
+                name: name.to_string(),
+                rows,
+            });
+        }
+        let schema_version: i32 =
+            sqlx::query_scalar("SELECT COALESCE(MAX(version), 0) FROM schema_version")
+                .fetch_one(&self.pool)
+                .await
+                .map_err(pg_err)?;
+        Ok(DbStats {
+            backend: "postgres".into(),
+            schema_version: i64::from(schema_version),
+            tables,
+            size_bytes: None,
+            pool: Some(PoolStats {
+                size: self.pool.size(),
+                idle: self.pool.num_idle() as u32,
+                max_connections: self.pool.options().get_max_connections(),
+            }),
+        })
+    }
+
+    /// Refresh the planner's statistics and list indexes worth rebuilding.
+    /// Nothing is reindexed: `REINDEX` locks the table, so that is left to
+    /// the operator.
+    pub(crate) async fn pg_run_maintenance(&self) -> Result<MaintenanceReport, DbError> {
+        let started_at = Utc::now();
+        let timer = Instant::now();
+        let mut steps = Vec::new();
+
+        steps.push(match sqlx::query("ANALYZE").execute(&self.pool).await {
+            Ok(_) => MaintenanceStep::new("analyze", true, "planner statistics refreshed"),
+            Err(e) => MaintenanceStep::new("analyze", false, e.to_string()),
+        });
+
+        // Invalid indexes (left by a failed CREATE INDEX CONCURRENTLY), and
+        // indexes over 8 MiB that have grown past twice their table's size
+        let advisory: Result<Vec<String>, sqlx::Error> = sqlx::query_scalar(
+            "SELECT c.relname::text FROM pg_index i
+             JOIN pg_class c ON c.oid = i.indexrelid
+             JOIN pg_namespace n ON n.oid = c.relnamespace
+             WHERE n.nspname = current_schema()
+               AND (NOT i.indisvalid
+                    OR (pg_relation_size(i.indexrelid) > 8 * 1024 * 1024
+                        AND pg_relation_size(i.indexrelid) > 2 * pg_relation_size(i.indrelid)))
+             ORDER BY c.relname",
+        )
+        .fetch_all(&self.pool)
+        .await;
+        steps.push(match advisory {
+            Ok(indexes) if indexes.is_empty() => {
+                MaintenanceStep::new("reindex_advisory", true, "no indexes need rebuilding")
+            }
+            Ok(indexes) => MaintenanceStep::new(
+                "reindex_advisory",
+                false,
+                format!(
+                    "REINDEX INDEX CONCURRENTLY suggested for: {}",
+                    indexes.join(", ")
+                ),
+            ),
+            Err(e) => MaintenanceStep::new("reindex_advisory", false, e.to_string()),
+        });
+
+        Ok(MaintenanceReport {
+            backend: "postgres".into(),
+            started_at,
+            duration_ms: timer.elapsed().as_millis() as i64,
+            steps,
+        })
+    }
+}
@@ -0,0 +1,94 @@
+use chrono::{DateTime, Utc};
+
+use flowstate_core::subscription::{CreateSubscription, NotifyChannel, Subscription};
+
+use super::super::{pg_err, pg_not_found, PostgresDatabase};
+use crate::DbError;
+
+/// `events` is stored as a JSON array.
+#[derive(sqlx::FromRow)]
+pub(crate) struct SubscriptionRow {
+    id: String,
+    channel: String,
+    target: String,
+    events: String,
+    project_id: Option<String>,
+    user_id: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<SubscriptionRow> for Subscription {
+    fn from(r: SubscriptionRow) -> Self {
+        Subscription {
+            id: r.id,
+            channel: NotifyChannel::parse_str(&r.channel).unwrap_or(NotifyChannel::Webhook),
+            target: r.target,
+            events: serde_json::from_str(&r.events).unwrap_or_default(),
+            project_id: r.project_id,
+            user_id: r.user_id,
+            created_at: r.created_at,
+        }
+    }
+}
+
+impl PostgresDatabase {
+    pub(crate) async fn pg_create_subscription(
+        &self,
+        input: &CreateSubscription,
+    ) -> Result<Subscription, DbError> {
+        let events =
+            serde_json::to_string(&input.events).map_err(|e| DbError::Internal(e.to_string()))?;
+        let row = sqlx::query_as::<_, SubscriptionRow>(
+            "INSERT INTO subscriptions (id, channel, target, events, project_id, user_id, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7)
+             RETURNING *",
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(input.channel.as_str())
+        .bind(input.target.trim())
+        .bind(events)
+        .bind(&input.project_id)
+        .bind(&input.user_id)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        Ok(row.into())
+    }
+
+    pub(crate) async fn pg_get_subscription(&self, id: &str) -> Result<Subscription, DbError> {
+        let row = sqlx::query_as::<_, SubscriptionRow>("SELECT * FROM subscriptions WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(pg_err)?
+            .ok_or_else(|| pg_not_found(&format!("subscription {id}")))?;
+
+        Ok(row.into())
+    }
+
+    pub(crate) async fn pg_list_subscriptions(&self) -> Result<Vec<Subscription>, DbError> {
+        let rows = sqlx::query_as::<_, SubscriptionRow>(
+            "SELECT * FROM subscriptions ORDER BY created_at, id",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    pub(crate) async fn pg_delete_subscription(&self, id: &str) -> Result<(), DbError> {
+        let result = sqlx::query("DELETE FROM subscriptions WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(pg_err)?;
+
+        if result.rows_affected() == 0 {
+            return Err(pg_not_found(&format!("subscription {id}")));
+        }
+        Ok(())
+    }
+}
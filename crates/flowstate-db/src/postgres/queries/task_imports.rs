@@ -0,0 +1,60 @@
+use chrono::{DateTime, Utc};
+
+use super::super::{pg_err, PostgresDatabase};
+use crate::snapshot::TaskImport;
+use crate::DbError;
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct TaskImportRow {
+    project_id: String,
+    source: String,
+    task_id: String,
+    created_at: DateTime<Utc>,
+}
+
+impl From<TaskImportRow> for TaskImport {
+    fn from(r: TaskImportRow) -> Self {
+        TaskImport {
+            project_id: r.project_id,
+            source: r.source,
+            task_id: r.task_id,
+            created_at: r.created_at,
+        }
+    }
+}
+
+impl PostgresDatabase {
+    pub(crate) async fn pg_find_imported_task(
+        &self,
+        project_id: &str,
+        source: &str,
+    ) -> Result<Option<String>, DbError> {
+        sqlx::query_scalar("SELECT task_id FROM task_imports WHERE project_id = $1 AND source = $2")
+            .bind(project_id)
+            .bind(source)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(pg_err)
+    }
+
+    pub(crate) async fn pg_record_task_import(
+        &self,
+        project_id: &str,
+        source: &str,
+        task_id: &str,
+    ) -> Result<(), DbError> {
+        sqlx::query(
+            "INSERT INTO task_imports (project_id, source, task_id, created_at)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT DO NOTHING",
+        )
+        .bind(project_id)
+        .bind(source)
+        .bind(task_id)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await
+        .map_err(pg_err)?;
+        Ok(())
+    }
+}
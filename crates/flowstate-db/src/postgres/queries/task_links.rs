@@ -6,7 +6,7 @@ use super::super::{pg_err, pg_not_found, PostgresDatabase};
 use crate::DbError;
 
 #[derive(sqlx::FromRow)]
-struct TaskLinkRow {
+pub(crate) struct TaskLinkRow {
     id: String,
     source_task_id: String,
     target_task_id: String,
@@ -56,6 +56,16 @@ impl PostgresDatabase {
         Ok(row.into())
     }
 
+    pub(crate) async fn pg_get_task_link(&self, id: &str) -> Result<TaskLink, DbError> {
+        sqlx::query_as::<_, TaskLinkRow>("SELECT * FROM task_links WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(pg_err)?
+            .map(Into::into)
+            .ok_or_else(|| pg_not_found(&format!("task_link {id}")))
+    }
+
     pub(crate) async fn pg_list_task_links(&self, task_id: &str) -> Result<Vec<TaskLink>, DbError> {
         let rows = sqlx::query_as::<_, TaskLinkRow>(
             "SELECT * FROM task_links
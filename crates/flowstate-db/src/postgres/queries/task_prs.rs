@@ -1,18 +1,21 @@
 use chrono::{DateTime, Utc};
 
-use flowstate_core::task_pr::{CreateTaskPr, TaskPr};
+use flowstate_core::task_pr::{ChecksStatus, CreateTaskPr, PrState, TaskPr};
 
-use super::super::{pg_err, PostgresDatabase};
+use super::super::{pg_err, pg_not_found, PostgresDatabase};
 use crate::DbError;
 
 #[derive(sqlx::FromRow)]
-struct TaskPrRow {
+pub(crate) struct TaskPrRow {
     id: String,
     task_id: String,
     claude_run_id: Option<String>,
     pr_url: String,
     pr_number: i64,
     branch_name: String,
+    state: String,
+    checks_status: Option<String>,
+    draft: bool,
     created_at: DateTime<Utc>,
 }
 
@@ -25,6 +28,9 @@ impl From<TaskPrRow> for TaskPr {
             pr_url: r.pr_url,
             pr_number: r.pr_number,
             branch_name: r.branch_name,
+            state: PrState::parse_str(&r.state).unwrap_or_default(),
+            checks_status: r.checks_status.as_deref().and_then(ChecksStatus::parse_str),
+            draft: r.draft,
             created_at: r.created_at,
         }
     }
@@ -37,8 +43,8 @@ impl PostgresDatabase {
 
         // Use ON CONFLICT DO NOTHING for idempotent insert (unique on pr_url)
         sqlx::query(
-            "INSERT INTO task_prs (id, task_id, claude_run_id, pr_url, pr_number, branch_name, created_at)
-             VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "INSERT INTO task_prs (id, task_id, claude_run_id, pr_url, pr_number, branch_name, draft, created_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
              ON CONFLICT (pr_url) DO NOTHING",
         )
         .bind(&id)
@@ -47,6 +53,7 @@ impl PostgresDatabase {
         .bind(&input.pr_url)
         .bind(input.pr_number)
         .bind(&input.branch_name)
+        .bind(input.draft)
         .bind(now)
         .execute(&self.pool)
         .await
@@ -73,4 +80,91 @@ impl PostgresDatabase {
 
         Ok(rows.into_iter().map(|r| r.into()).collect())
     }
+
+    pub(crate) async fn pg_get_task_pr(&self, id: &str) -> Result<TaskPr, DbError> {
+        let row = sqlx::query_as::<_, TaskPrRow>("SELECT * FROM task_prs WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(pg_err)?
+            .ok_or_else(|| pg_not_found(&format!("task pr {id}")))?;
+
+        Ok(row.into())
+    }
+
+    pub(crate) async fn pg_get_task_pr_by_url(&self, pr_url: &str) -> Result<TaskPr, DbError> {
+        let row = sqlx::query_as::<_, TaskPrRow>("SELECT * FROM task_prs WHERE pr_url = $1")
+            .bind(pr_url)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(pg_err)?
+            .ok_or_else(|| pg_not_found(&format!("task pr {pr_url}")))?;
+
+        Ok(row.into())
+    }
+
+    pub(crate) async fn pg_set_task_pr_state(
+        &self,
+        id: &str,
+        state: PrState,
+    ) -> Result<TaskPr, DbError> {
+        let row = sqlx::query_as::<_, TaskPrRow>(
+            "UPDATE task_prs SET state = $1 WHERE id = $2 RETURNING *",
+        )
+        .bind(state.as_str())
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(pg_err)?
+        .ok_or_else(|| pg_not_found(&format!("task pr {id}")))?;
+
+        Ok(row.into())
+    }
+
+    pub(crate) async fn pg_set_task_pr_checks(
+        &self,
+        id: &str,
+        checks_status: Option<ChecksStatus>,
+    ) -> Result<TaskPr, DbError> {
+        let row = sqlx::query_as::<_, TaskPrRow>(
+            "UPDATE task_prs SET checks_status = $1 WHERE id = $2 RETURNING *",
+        )
+        .bind(checks_status.map(|s| s.as_str()))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(pg_err)?
+        .ok_or_else(|| pg_not_found(&format!("task pr {id}")))?;
+
+        Ok(row.into())
+    }
+
+    pub(crate) async fn pg_set_task_pr_draft(
+        &self,
+        id: &str,
+        draft: bool,
+    ) -> Result<TaskPr, DbError> {
+        let row = sqlx::query_as::<_, TaskPrRow>(
+            "UPDATE task_prs SET draft = $1 WHERE id = $2 RETURNING *",
+        )
+        .bind(draft)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(pg_err)?
+        .ok_or_else(|| pg_not_found(&format!("task pr {id}")))?;
+
+        Ok(row.into())
+    }
+
+    pub(crate) async fn pg_list_open_task_prs(&self) -> Result<Vec<TaskPr>, DbError> {
+        let rows = sqlx::query_as::<_, TaskPrRow>(
+            "SELECT * FROM task_prs WHERE state = 'open' ORDER BY created_at",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
 }
@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+
+use flowstate_core::task_revision::{FieldChange, TaskRevision};
+
+use super::super::{pg_err, PostgresDatabase};
+use crate::DbError;
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct TaskRevisionRow {
+    id: String,
+    task_id: String,
+    actor: String,
+    changes: String,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<TaskRevisionRow> for TaskRevision {
+    type Error = DbError;
+
+    fn try_from(r: TaskRevisionRow) -> Result<Self, DbError> {
+        let changes = serde_json::from_str(&r.changes)
+            .map_err(|e| DbError::Internal(format!("task revision {}: {e}", r.id)))?;
+        Ok(TaskRevision {
+            id: r.id,
+            task_id: r.task_id,
+            actor: r.actor,
+            changes,
+            created_at: r.created_at,
+        })
+    }
+}
+
+/// Record a revision through `executor`, so callers can include it in the
+/// same transaction as the task update it describes.
+pub(crate) async fn pg_insert_task_revision(
+    executor: impl sqlx::PgExecutor<'_>,
+    task_id: &str,
+    actor: &str,
+    changes: &[FieldChange],
+) -> Result<(), DbError> {
+    let changes_json =
+        serde_json::to_string(changes).map_err(|e| DbError::Internal(e.to_string()))?;
+    sqlx::query(
+        "INSERT INTO task_revisions (id, task_id, actor, changes, created_at)
+         VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(uuid::Uuid::new_v4().to_string())
+    .bind(task_id)
+    .bind(actor)
+    .bind(changes_json)
+    .bind(Utc::now())
+    .execute(executor)
+    .await
+    .map_err(pg_err)?;
+    Ok(())
+}
+
+impl PostgresDatabase {
+    pub(crate) async fn pg_list_task_revisions(
+        &self,
+        task_id: &str,
+    ) -> Result<Vec<TaskRevision>, DbError> {
+        let rows = sqlx::query_as::<_, TaskRevisionRow>(
+            "SELECT * FROM task_revisions WHERE task_id = $1 ORDER BY created_at DESC",
+        )
+        .bind(task_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        rows.into_iter().map(TaskRevision::try_from).collect()
+    }
+}
@@ -1,25 +1,35 @@
 use chrono::{DateTime, Utc};
-use sqlx::Row;
+use sqlx::{PgConnection, Row};
 
+use flowstate_core::feedback::new_rejections;
 use flowstate_core::runner::RunnerCapability;
 use flowstate_core::task::{
-    ApprovalStatus, CreateTask, Priority, Status, Task, TaskFilter, UpdateTask,
+    ApprovalStatus, CreateTask, Priority, Status, Task, TaskFilter, TaskType, UpdateTask,
 };
-
-use super::super::{pg_err, pg_not_found, PostgresDatabase};
+use flowstate_core::task_revision::diff_tasks;
+
+use super::super::{bind_values, pg_err, pg_not_found, PostgresDatabase};
+use super::custom_fields::pg_set_task_field_values_in;
+use super::feedback_history::pg_insert_feedback_entry;
+use super::task_revisions::pg_insert_task_revision;
+use super::watchers::pg_notify_watchers;
+use crate::query::{task_filter_query, Dialect};
 use crate::DbError;
 
 #[derive(sqlx::FromRow)]
-struct TaskRow {
+pub(crate) struct TaskRow {
     id: String,
     project_id: String,
     sprint_id: Option<String>,
+    epic_id: Option<String>,
+    assignee_id: Option<String>,
     parent_id: Option<String>,
     title: String,
     description: String,
     reviewer: String,
     status: String,
     priority: String,
+    task_type: String,
     sort_order: f64,
     research_status: String,
     spec_status: String,
@@ -36,6 +46,9 @@ struct TaskRow {
     plan_capability: Option<String>,
     build_capability: Option<String>,
     verify_capability: Option<String>,
+    due_at: Option<DateTime<Utc>>,
+    archived: bool,
+    autopilot: bool,
     created_at: DateTime<Utc>,
     updated_at: DateTime<Utc>,
 }
@@ -46,6 +59,8 @@ impl From<TaskRow> for Task {
             id: r.id,
             project_id: r.project_id,
             sprint_id: r.sprint_id,
+            epic_id: r.epic_id,
+            assignee_id: r.assignee_id,
             parent_id: r.parent_id,
             title: r.title,
             description: r.description,
@@ -64,6 +79,8 @@ impl From<TaskRow> for Task {
             verify_feedback: r.verify_feedback,
             status: Status::parse_str(&r.status).unwrap_or(Status::Todo),
             priority: Priority::parse_str(&r.priority).unwrap_or(Priority::Medium),
+            task_type: TaskType::parse_str(&r.task_type).unwrap_or_default(),
+            due_at: r.due_at,
             research_capability: r
                 .research_capability
                 .and_then(|s| RunnerCapability::parse_str(&s)),
@@ -80,61 +97,309 @@ impl From<TaskRow> for Task {
                 .verify_capability
                 .and_then(|s| RunnerCapability::parse_str(&s)),
             sort_order: r.sort_order,
+            archived: r.archived,
+            autopilot: r.autopilot,
             created_at: r.created_at,
             updated_at: r.updated_at,
         }
     }
 }
 
-impl PostgresDatabase {
-    pub(crate) async fn pg_create_task(&self, input: &CreateTask) -> Result<Task, DbError> {
-        let id = uuid::Uuid::new_v4().to_string();
-        let now = Utc::now();
+/// Insert a task on `conn`, which may be a transaction shared with other writes.
+async fn create_task_in(conn: &mut PgConnection, input: &CreateTask) -> Result<Task, DbError> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = Utc::now();
+
+    // Get next sort_order for this project+status
+    let max_order: f64 = sqlx::query_scalar(
+        "SELECT COALESCE(MAX(sort_order), 0) FROM tasks WHERE project_id = $1 AND status = $2",
+    )
+    .bind(&input.project_id)
+    .bind(input.status.as_str())
+    .fetch_one(&mut *conn)
+    .await
+    .unwrap_or(0.0);
+
+    sqlx::query(
+        "INSERT INTO tasks (
+             id, project_id, parent_id, title, description, reviewer, status, priority, sort_order, created_at, updated_at,
+             research_capability, design_capability, plan_capability, build_capability, verify_capability, due_at,
+             task_type
+         )
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18)",
+    )
+    .bind(&id)
+    .bind(&input.project_id)
+    .bind(&input.parent_id)
+    .bind(&input.title)
+    .bind(&input.description)
+    .bind(&input.reviewer)
+    .bind(input.status.as_str())
+    .bind(input.priority.as_str())
+    .bind(max_order + 1.0)
+    .bind(now)
+    .bind(now)
+    .bind(input.research_capability.map(|c| c.as_str().to_string()))
+    .bind(input.design_capability.map(|c| c.as_str().to_string()))
+    .bind(input.plan_capability.map(|c| c.as_str().to_string()))
+    .bind(input.build_capability.map(|c| c.as_str().to_string()))
+    .bind(input.verify_capability.map(|c| c.as_str().to_string()))
+    .bind(input.due_at)
+    .bind(input.task_type.as_str())
+    .execute(&mut *conn)
+    .await
+    .map_err(pg_err)?;
+
+    let row = sqlx::query_as::<_, TaskRow>("SELECT * FROM tasks WHERE id = $1")
+        .bind(&id)
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(pg_err)?;
 
-        // Get next sort_order for this project+status
-        let max_order: f64 = sqlx::query_scalar(
-            "SELECT COALESCE(MAX(sort_order), 0) FROM tasks WHERE project_id = $1 AND status = $2",
-        )
-        .bind(&input.project_id)
-        .bind(input.status.as_str())
-        .fetch_one(&self.pool)
+    Ok(row.into())
+}
+
+/// Apply `update` on `conn` and record the resulting revision, if any.
+async fn update_task_in(
+    conn: &mut PgConnection,
+    id: &str,
+    update: &UpdateTask,
+) -> Result<Task, DbError> {
+    let before: Task = sqlx::query_as::<_, TaskRow>("SELECT * FROM tasks WHERE id = $1 FOR UPDATE")
+        .bind(id)
+        .fetch_optional(&mut *conn)
         .await
-        .unwrap_or(0.0);
+        .map_err(pg_err)?
+        .ok_or_else(|| pg_not_found(&format!("task {id}")))?
+        .into();
+
+    let now = Utc::now();
+    let mut sets = vec![String::new()]; // placeholder for updated_at at index 0
+    let mut param_idx = 1usize;
+
+    // We'll track all string values and special typed values
+    enum ParamValue {
+        Str(String),
+        OptStr(Option<String>),
+        Float(f64),
+        Timestamp(DateTime<Utc>),
+        OptTimestamp(Option<DateTime<Utc>>),
+        Bool(bool),
+    }
+    let mut params: Vec<ParamValue> = Vec::new();
 
-        sqlx::query(
-            "INSERT INTO tasks (
-                 id, project_id, parent_id, title, description, reviewer, status, priority, sort_order, created_at, updated_at,
-                 research_capability, design_capability, plan_capability, build_capability, verify_capability
-             )
-             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)",
-        )
-        .bind(&id)
-        .bind(&input.project_id)
-        .bind(&input.parent_id)
-        .bind(&input.title)
-        .bind(&input.description)
-        .bind(&input.reviewer)
-        .bind(input.status.as_str())
-        .bind(input.priority.as_str())
-        .bind(max_order + 1.0)
-        .bind(now)
-        .bind(now)
-        .bind(input.research_capability.map(|c| c.as_str().to_string()))
-        .bind(input.design_capability.map(|c| c.as_str().to_string()))
-        .bind(input.plan_capability.map(|c| c.as_str().to_string()))
-        .bind(input.build_capability.map(|c| c.as_str().to_string()))
-        .bind(input.verify_capability.map(|c| c.as_str().to_string()))
-        .execute(&self.pool)
+    // updated_at is always first
+    sets[0] = format!("updated_at = ${param_idx}");
+    params.push(ParamValue::Timestamp(now));
+    param_idx += 1;
+
+    if let Some(ref title) = update.title {
+        sets.push(format!("title = ${param_idx}"));
+        params.push(ParamValue::Str(title.clone()));
+        param_idx += 1;
+    }
+    if let Some(ref description) = update.description {
+        sets.push(format!("description = ${param_idx}"));
+        params.push(ParamValue::Str(description.clone()));
+        param_idx += 1;
+    }
+    if let Some(status) = update.status {
+        sets.push(format!("status = ${param_idx}"));
+        params.push(ParamValue::Str(status.as_str().to_string()));
+        param_idx += 1;
+    }
+    if let Some(priority) = update.priority {
+        sets.push(format!("priority = ${param_idx}"));
+        params.push(ParamValue::Str(priority.as_str().to_string()));
+        param_idx += 1;
+    }
+    if let Some(task_type) = update.task_type {
+        sets.push(format!("task_type = ${param_idx}"));
+        params.push(ParamValue::Str(task_type.as_str().to_string()));
+        param_idx += 1;
+    }
+    if let Some(ref sprint_id) = update.sprint_id {
+        sets.push(format!("sprint_id = ${param_idx}"));
+        params.push(ParamValue::OptStr(sprint_id.clone()));
+        param_idx += 1;
+    }
+    if let Some(ref epic_id) = update.epic_id {
+        sets.push(format!("epic_id = ${param_idx}"));
+        params.push(ParamValue::OptStr(epic_id.clone()));
+        param_idx += 1;
+    }
+    if let Some(ref assignee_id) = update.assignee_id {
+        sets.push(format!("assignee_id = ${param_idx}"));
+        params.push(ParamValue::OptStr(assignee_id.clone()));
+        param_idx += 1;
+    }
+    if let Some(due_at) = update.due_at {
+        sets.push(format!("due_at = ${param_idx}"));
+        params.push(ParamValue::OptTimestamp(due_at));
+        param_idx += 1;
+    }
+    if let Some(sort_order) = update.sort_order {
+        sets.push(format!("sort_order = ${param_idx}"));
+        params.push(ParamValue::Float(sort_order));
+        param_idx += 1;
+    }
+    if let Some(ref parent_id) = update.parent_id {
+        sets.push(format!("parent_id = ${param_idx}"));
+        params.push(ParamValue::OptStr(parent_id.clone()));
+        param_idx += 1;
+    }
+    if let Some(ref reviewer) = update.reviewer {
+        sets.push(format!("reviewer = ${param_idx}"));
+        params.push(ParamValue::Str(reviewer.clone()));
+        param_idx += 1;
+    }
+    if let Some(research_status) = update.research_status {
+        sets.push(format!("research_status = ${param_idx}"));
+        params.push(ParamValue::Str(research_status.as_str().to_string()));
+        param_idx += 1;
+    }
+    if let Some(spec_status) = update.spec_status {
+        sets.push(format!("spec_status = ${param_idx}"));
+        params.push(ParamValue::Str(spec_status.as_str().to_string()));
+        param_idx += 1;
+    }
+    if let Some(plan_status) = update.plan_status {
+        sets.push(format!("plan_status = ${param_idx}"));
+        params.push(ParamValue::Str(plan_status.as_str().to_string()));
+        param_idx += 1;
+    }
+    if let Some(verify_status) = update.verify_status {
+        sets.push(format!("verify_status = ${param_idx}"));
+        params.push(ParamValue::Str(verify_status.as_str().to_string()));
+        param_idx += 1;
+    }
+    if let Some(ref hash) = update.spec_approved_hash {
+        sets.push(format!("spec_approved_hash = ${param_idx}"));
+        params.push(ParamValue::Str(hash.clone()));
+        param_idx += 1;
+    }
+    if let Some(ref hash) = update.research_approved_hash {
+        sets.push(format!("research_approved_hash = ${param_idx}"));
+        params.push(ParamValue::Str(hash.clone()));
+        param_idx += 1;
+    }
+    if let Some(ref feedback) = update.research_feedback {
+        sets.push(format!("research_feedback = ${param_idx}"));
+        params.push(ParamValue::Str(feedback.clone()));
+        param_idx += 1;
+    }
+    if let Some(ref feedback) = update.spec_feedback {
+        sets.push(format!("spec_feedback = ${param_idx}"));
+        params.push(ParamValue::Str(feedback.clone()));
+        param_idx += 1;
+    }
+    if let Some(ref feedback) = update.plan_feedback {
+        sets.push(format!("plan_feedback = ${param_idx}"));
+        params.push(ParamValue::Str(feedback.clone()));
+        param_idx += 1;
+    }
+    if let Some(ref feedback) = update.verify_feedback {
+        sets.push(format!("verify_feedback = ${param_idx}"));
+        params.push(ParamValue::Str(feedback.clone()));
+        param_idx += 1;
+    }
+    if let Some(cap) = &update.research_capability {
+        sets.push(format!("research_capability = ${param_idx}"));
+        params.push(ParamValue::OptStr(cap.map(|c| c.as_str().to_string())));
+        param_idx += 1;
+    }
+    if let Some(cap) = &update.design_capability {
+        sets.push(format!("design_capability = ${param_idx}"));
+        params.push(ParamValue::OptStr(cap.map(|c| c.as_str().to_string())));
+        param_idx += 1;
+    }
+    if let Some(cap) = &update.plan_capability {
+        sets.push(format!("plan_capability = ${param_idx}"));
+        params.push(ParamValue::OptStr(cap.map(|c| c.as_str().to_string())));
+        param_idx += 1;
+    }
+    if let Some(cap) = &update.build_capability {
+        sets.push(format!("build_capability = ${param_idx}"));
+        params.push(ParamValue::OptStr(cap.map(|c| c.as_str().to_string())));
+        param_idx += 1;
+    }
+    if let Some(cap) = &update.verify_capability {
+        sets.push(format!("verify_capability = ${param_idx}"));
+        params.push(ParamValue::OptStr(cap.map(|c| c.as_str().to_string())));
+        param_idx += 1;
+    }
+    if let Some(autopilot) = update.autopilot {
+        sets.push(format!("autopilot = ${param_idx}"));
+        params.push(ParamValue::Bool(autopilot));
+        param_idx += 1;
+    }
+
+    let id_param = param_idx;
+
+    let sql = format!(
+        "UPDATE tasks SET {} WHERE id = ${id_param}",
+        sets.join(", ")
+    );
+
+    let mut query = sqlx::query(&sql);
+    for p in &params {
+        match p {
+            ParamValue::Str(s) => query = query.bind(s),
+            ParamValue::OptStr(s) => query = query.bind(s),
+            ParamValue::Float(f) => query = query.bind(f),
+            ParamValue::Timestamp(t) => query = query.bind(t),
+            ParamValue::OptTimestamp(t) => query = query.bind(t),
+            ParamValue::Bool(b) => query = query.bind(b),
+        }
+    }
+    query = query.bind(id);
+
+    let result = query.execute(&mut *conn).await.map_err(pg_err)?;
+
+    if result.rows_affected() == 0 {
+        return Err(pg_not_found(&format!("task {id}")));
+    }
+
+    let after: Task = sqlx::query_as::<_, TaskRow>("SELECT * FROM tasks WHERE id = $1")
+        .bind(id)
+        .fetch_one(&mut *conn)
         .await
-        .map_err(pg_err)?;
+        .map_err(pg_err)?
+        .into();
+
+    let mut changes = diff_tasks(&before, &after);
+    changes.extend(
+        pg_set_task_field_values_in(&mut *conn, id, &after.project_id, &update.custom_fields)
+            .await?,
+    );
+    let actor = update.actor.as_deref().unwrap_or_default();
+    if !changes.is_empty() {
+        pg_insert_task_revision(&mut *conn, id, actor, &changes).await?;
+        pg_notify_watchers(&mut *conn, id, actor, &changes).await?;
+    }
+    for (phase, feedback) in new_rejections(&before, &after) {
+        pg_insert_feedback_entry(&mut *conn, id, phase, feedback, actor).await?;
+    }
+    Ok(after)
+}
 
-        let row = sqlx::query_as::<_, TaskRow>("SELECT * FROM tasks WHERE id = $1")
-            .bind(&id)
-            .fetch_one(&self.pool)
-            .await
-            .map_err(pg_err)?;
+impl PostgresDatabase {
+    pub(crate) async fn pg_create_task(&self, input: &CreateTask) -> Result<Task, DbError> {
+        let mut conn = self.pool.acquire().await.map_err(pg_err)?;
+        create_task_in(&mut conn, input).await
+    }
 
-        Ok(row.into())
+    pub(crate) async fn pg_bulk_create_tasks(
+        &self,
+        inputs: &[CreateTask],
+    ) -> Result<Vec<Task>, DbError> {
+        let mut tx = self.pool.begin().await.map_err(pg_err)?;
+        let mut tasks = Vec::with_capacity(inputs.len());
+        for input in inputs {
+            tasks.push(create_task_in(&mut tx, input).await?);
+        }
+        tx.commit().await.map_err(pg_err)?;
+        Ok(tasks)
     }
 
     pub(crate) async fn pg_get_task(&self, id: &str) -> Result<Task, DbError> {
@@ -149,70 +414,11 @@ impl PostgresDatabase {
     }
 
     pub(crate) async fn pg_list_tasks(&self, filter: &TaskFilter) -> Result<Vec<Task>, DbError> {
-        let mut sql = String::from("SELECT * FROM tasks WHERE 1=1");
-        let mut param_idx = 1usize;
-
-        // We'll collect bind values as trait objects
-        // Since sqlx doesn't easily support dynamic bind lists,
-        // we build the SQL string and use a manual approach.
-
-        // Collect filter values in order
-        struct StrParam(String);
-        let mut params: Vec<StrParam> = Vec::new();
-        let mut has_limit = false;
-        let mut limit_val: i64 = 0;
-
-        if let Some(ref project_id) = filter.project_id {
-            sql.push_str(&format!(" AND project_id = ${param_idx}"));
-            params.push(StrParam(project_id.clone()));
-            param_idx += 1;
-        }
-        if let Some(status) = filter.status {
-            sql.push_str(&format!(" AND status = ${param_idx}"));
-            params.push(StrParam(status.as_str().to_string()));
-            param_idx += 1;
-        }
-        if let Some(priority) = filter.priority {
-            sql.push_str(&format!(" AND priority = ${param_idx}"));
-            params.push(StrParam(priority.as_str().to_string()));
-            param_idx += 1;
-        }
-        if let Some(ref sprint_id) = filter.sprint_id {
-            sql.push_str(&format!(" AND sprint_id = ${param_idx}"));
-            params.push(StrParam(sprint_id.clone()));
-            param_idx += 1;
-        }
-        if let Some(ref parent_id_filter) = filter.parent_id {
-            match parent_id_filter {
-                None => {
-                    sql.push_str(" AND parent_id IS NULL");
-                }
-                Some(pid) => {
-                    sql.push_str(&format!(" AND parent_id = ${param_idx}"));
-                    params.push(StrParam(pid.clone()));
-                    param_idx += 1;
-                }
-            }
-        }
-
-        sql.push_str(" ORDER BY sort_order ASC");
-
-        if let Some(limit) = filter.limit {
-            sql.push_str(&format!(" LIMIT ${param_idx}"));
-            has_limit = true;
-            limit_val = limit;
-        }
-
-        // Build the query with sequential binds
-        let mut query = sqlx::query_as::<_, TaskRow>(&sql);
-        for p in &params {
-            query = query.bind(&p.0);
-        }
-        if has_limit {
-            query = query.bind(limit_val);
-        }
-
-        let rows = query.fetch_all(&self.pool).await.map_err(pg_err)?;
+        let query = task_filter_query(Dialect::Postgres, filter);
+        let rows = bind_values(sqlx::query_as::<_, TaskRow>(query.sql()), query.values())
+            .fetch_all(&self.pool)
+            .await
+            .map_err(pg_err)?;
         Ok(rows.into_iter().map(|r| r.into()).collect())
     }
 
@@ -233,165 +439,96 @@ impl PostgresDatabase {
         id: &str,
         update: &UpdateTask,
     ) -> Result<Task, DbError> {
-        let now = Utc::now();
-        let mut sets = vec![String::new()]; // placeholder for updated_at at index 0
-        let mut param_idx = 1usize;
-
-        // We'll track all string values and special typed values
-        enum ParamValue {
-            Str(String),
-            OptStr(Option<String>),
-            Float(f64),
-            Timestamp(DateTime<Utc>),
-        }
-        let mut params: Vec<ParamValue> = Vec::new();
-
-        // updated_at is always first
-        sets[0] = format!("updated_at = ${param_idx}");
-        params.push(ParamValue::Timestamp(now));
-        param_idx += 1;
+        let mut tx = self.pool.begin().await.map_err(pg_err)?;
+        let task = update_task_in(&mut tx, id, update).await?;
+        tx.commit().await.map_err(pg_err)?;
+        Ok(task)
+    }
 
-        if let Some(ref title) = update.title {
-            sets.push(format!("title = ${param_idx}"));
-            params.push(ParamValue::Str(title.clone()));
-            param_idx += 1;
-        }
-        if let Some(ref description) = update.description {
-            sets.push(format!("description = ${param_idx}"));
-            params.push(ParamValue::Str(description.clone()));
-            param_idx += 1;
-        }
-        if let Some(status) = update.status {
-            sets.push(format!("status = ${param_idx}"));
-            params.push(ParamValue::Str(status.as_str().to_string()));
-            param_idx += 1;
-        }
-        if let Some(priority) = update.priority {
-            sets.push(format!("priority = ${param_idx}"));
-            params.push(ParamValue::Str(priority.as_str().to_string()));
-            param_idx += 1;
-        }
-        if let Some(ref sprint_id) = update.sprint_id {
-            sets.push(format!("sprint_id = ${param_idx}"));
-            params.push(ParamValue::OptStr(sprint_id.clone()));
-            param_idx += 1;
-        }
-        if let Some(sort_order) = update.sort_order {
-            sets.push(format!("sort_order = ${param_idx}"));
-            params.push(ParamValue::Float(sort_order));
-            param_idx += 1;
-        }
-        if let Some(ref parent_id) = update.parent_id {
-            sets.push(format!("parent_id = ${param_idx}"));
-            params.push(ParamValue::OptStr(parent_id.clone()));
-            param_idx += 1;
-        }
-        if let Some(ref reviewer) = update.reviewer {
-            sets.push(format!("reviewer = ${param_idx}"));
-            params.push(ParamValue::Str(reviewer.clone()));
-            param_idx += 1;
-        }
-        if let Some(research_status) = update.research_status {
-            sets.push(format!("research_status = ${param_idx}"));
-            params.push(ParamValue::Str(research_status.as_str().to_string()));
-            param_idx += 1;
-        }
-        if let Some(spec_status) = update.spec_status {
-            sets.push(format!("spec_status = ${param_idx}"));
-            params.push(ParamValue::Str(spec_status.as_str().to_string()));
-            param_idx += 1;
-        }
-        if let Some(plan_status) = update.plan_status {
-            sets.push(format!("plan_status = ${param_idx}"));
-            params.push(ParamValue::Str(plan_status.as_str().to_string()));
-            param_idx += 1;
-        }
-        if let Some(verify_status) = update.verify_status {
-            sets.push(format!("verify_status = ${param_idx}"));
-            params.push(ParamValue::Str(verify_status.as_str().to_string()));
-            param_idx += 1;
-        }
-        if let Some(ref hash) = update.spec_approved_hash {
-            sets.push(format!("spec_approved_hash = ${param_idx}"));
-            params.push(ParamValue::Str(hash.clone()));
-            param_idx += 1;
-        }
-        if let Some(ref hash) = update.research_approved_hash {
-            sets.push(format!("research_approved_hash = ${param_idx}"));
-            params.push(ParamValue::Str(hash.clone()));
-            param_idx += 1;
-        }
-        if let Some(ref feedback) = update.research_feedback {
-            sets.push(format!("research_feedback = ${param_idx}"));
-            params.push(ParamValue::Str(feedback.clone()));
-            param_idx += 1;
-        }
-        if let Some(ref feedback) = update.spec_feedback {
-            sets.push(format!("spec_feedback = ${param_idx}"));
-            params.push(ParamValue::Str(feedback.clone()));
-            param_idx += 1;
-        }
-        if let Some(ref feedback) = update.plan_feedback {
-            sets.push(format!("plan_feedback = ${param_idx}"));
-            params.push(ParamValue::Str(feedback.clone()));
-            param_idx += 1;
-        }
-        if let Some(ref feedback) = update.verify_feedback {
-            sets.push(format!("verify_feedback = ${param_idx}"));
-            params.push(ParamValue::Str(feedback.clone()));
-            param_idx += 1;
-        }
-        if let Some(cap) = &update.research_capability {
-            sets.push(format!("research_capability = ${param_idx}"));
-            params.push(ParamValue::OptStr(cap.map(|c| c.as_str().to_string())));
-            param_idx += 1;
-        }
-        if let Some(cap) = &update.design_capability {
-            sets.push(format!("design_capability = ${param_idx}"));
-            params.push(ParamValue::OptStr(cap.map(|c| c.as_str().to_string())));
-            param_idx += 1;
-        }
-        if let Some(cap) = &update.plan_capability {
-            sets.push(format!("plan_capability = ${param_idx}"));
-            params.push(ParamValue::OptStr(cap.map(|c| c.as_str().to_string())));
-            param_idx += 1;
-        }
-        if let Some(cap) = &update.build_capability {
-            sets.push(format!("build_capability = ${param_idx}"));
-            params.push(ParamValue::OptStr(cap.map(|c| c.as_str().to_string())));
-            param_idx += 1;
-        }
-        if let Some(cap) = &update.verify_capability {
-            sets.push(format!("verify_capability = ${param_idx}"));
-            params.push(ParamValue::OptStr(cap.map(|c| c.as_str().to_string())));
-            param_idx += 1;
-        }
+    /// Apply the same update to every task in `ids`. All-or-nothing: a
+    /// missing id rolls back the whole batch.
+    pub(crate) async fn pg_bulk_update_tasks(
+        &self,
+        ids: &[String],
+        update: &UpdateTask,
+    ) -> Result<Vec<Task>, DbError> {
+        let mut tx = self.pool.begin().await.map_err(pg_err)?;
+        let mut tasks = Vec::with_capacity(ids.len());
+        for id in ids {
+            tasks.push(update_task_in(&mut tx, id, update).await?);
+        }
+        tx.commit().await.map_err(pg_err)?;
+        Ok(tasks)
+    }
 
-        let id_param = param_idx;
+    pub(crate) async fn pg_reorder_task(
+        &self,
+        id: &str,
+        after_id: Option<&str>,
+    ) -> Result<Task, DbError> {
+        let mut tx = self.pool.begin().await.map_err(pg_err)?;
 
-        let sql = format!(
-            "UPDATE tasks SET {} WHERE id = ${id_param}",
-            sets.join(", ")
-        );
+        let task: Task = sqlx::query_as::<_, TaskRow>("SELECT * FROM tasks WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(pg_err)?
+            .ok_or_else(|| pg_not_found(&format!("task {id}")))?
+            .into();
+
+        // Lock the whole column so concurrent reorders serialise.
+        let mut column: Vec<String> = sqlx::query_scalar(
+            "SELECT id FROM tasks WHERE project_id = $1 AND status = $2 AND id != $3
+             ORDER BY sort_order ASC, created_at ASC
+             FOR UPDATE",
+        )
+        .bind(&task.project_id)
+        .bind(task.status.as_str())
+        .bind(id)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(pg_err)?;
 
-        let mut query = sqlx::query(&sql);
-        for p in &params {
-            match p {
-                ParamValue::Str(s) => query = query.bind(s),
-                ParamValue::OptStr(s) => query = query.bind(s),
-                ParamValue::Float(f) => query = query.bind(f),
-                ParamValue::Timestamp(t) => query = query.bind(t),
+        let position = match after_id {
+            None => 0,
+            Some(after_id) => {
+                column
+                    .iter()
+                    .position(|other| other == after_id)
+                    .ok_or_else(|| {
+                        pg_not_found(&format!(
+                            "task {after_id} in column {}",
+                            task.status.as_str()
+                        ))
+                    })?
+                    + 1
             }
-        }
-        query = query.bind(id);
-
-        let result = query.execute(&self.pool).await.map_err(pg_err)?;
-
-        if result.rows_affected() == 0 {
-            return Err(pg_not_found(&format!("task {id}")));
-        }
+        };
+        column.insert(position, id.to_string());
+
+        for (index, task_id) in column.iter().enumerate() {
+            sqlx::query("UPDATE tasks SET sort_order = $1 WHERE id = $2")
+                .bind((index + 1) as f64)
+                .bind(task_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(pg_err)?;
+        }
+        sqlx::query("UPDATE tasks SET updated_at = $1 WHERE id = $2")
+            .bind(Utc::now())
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(pg_err)?;
 
-        self.pg_get_task(id).await
+        let task: Task = sqlx::query_as::<_, TaskRow>("SELECT * FROM tasks WHERE id = $1")
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(pg_err)?
+            .into();
+        tx.commit().await.map_err(pg_err)?;
+        Ok(task)
     }
 
     pub(crate) async fn pg_delete_task(&self, id: &str) -> Result<(), DbError> {
@@ -408,12 +545,33 @@ impl PostgresDatabase {
         Ok(())
     }
 
+    pub(crate) async fn pg_archive_task(&self, id: &str) -> Result<Task, DbError> {
+        let result = sqlx::query(
+            "WITH RECURSIVE tree(id) AS (
+                 SELECT id FROM tasks WHERE id = $1
+                 UNION SELECT t.id FROM tasks t JOIN tree ON t.parent_id = tree.id
+             )
+             UPDATE tasks SET archived = TRUE, updated_at = $2
+             WHERE id IN (SELECT id FROM tree)",
+        )
+        .bind(id)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await
+        .map_err(pg_err)?;
+        if result.rows_affected() == 0 {
+            return Err(pg_not_found(&format!("task {id}")));
+        }
+        self.pg_get_task(id).await
+    }
+
     pub(crate) async fn pg_count_tasks_by_status(
         &self,
         project_id: &str,
     ) -> Result<Vec<(String, i64)>, DbError> {
         let rows = sqlx::query(
-            "SELECT status, COUNT(*) as cnt FROM tasks WHERE project_id = $1 GROUP BY status",
+            "SELECT status, COUNT(*) as cnt FROM tasks
+             WHERE project_id = $1 AND NOT archived GROUP BY status",
         )
         .bind(project_id)
         .fetch_all(&self.pool)
@@ -0,0 +1,130 @@
+use chrono::{DateTime, Utc};
+
+use flowstate_core::user::{CreateUser, UpdateUser, User};
+
+use super::super::{pg_err, pg_not_found, PostgresDatabase};
+use crate::DbError;
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct UserRow {
+    id: String,
+    name: String,
+    email: String,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<UserRow> for User {
+    fn from(r: UserRow) -> Self {
+        User {
+            id: r.id,
+            name: r.name,
+            email: r.email,
+            created_at: r.created_at,
+            updated_at: r.updated_at,
+        }
+    }
+}
+
+impl PostgresDatabase {
+    pub(crate) async fn pg_create_user(&self, input: &CreateUser) -> Result<User, DbError> {
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = Utc::now();
+
+        sqlx::query(
+            "INSERT INTO users (id, name, email, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(&id)
+        .bind(&input.name)
+        .bind(&input.email)
+        .bind(now)
+        .bind(now)
+        .execute(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        self.pg_get_user(&id).await
+    }
+
+    pub(crate) async fn pg_get_user(&self, id: &str) -> Result<User, DbError> {
+        let row = sqlx::query_as::<_, UserRow>("SELECT * FROM users WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(pg_err)?
+            .ok_or_else(|| pg_not_found(&format!("user {id}")))?;
+
+        Ok(row.into())
+    }
+
+    pub(crate) async fn pg_list_users(&self) -> Result<Vec<User>, DbError> {
+        let rows = sqlx::query_as::<_, UserRow>("SELECT * FROM users ORDER BY name, id")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(pg_err)?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    pub(crate) async fn pg_update_user(
+        &self,
+        id: &str,
+        update: &UpdateUser,
+    ) -> Result<User, DbError> {
+        if update.name.is_none() && update.email.is_none() {
+            return self.pg_get_user(id).await;
+        }
+
+        let mut sets = Vec::new();
+        let mut binds: Vec<String> = Vec::new();
+
+        if let Some(ref name) = update.name {
+            binds.push(name.clone());
+            sets.push(format!("name = ${}", binds.len()));
+        }
+        if let Some(ref email) = update.email {
+            binds.push(email.clone());
+            sets.push(format!("email = ${}", binds.len()));
+        }
+
+        let sql = format!(
+            "UPDATE users SET {}, updated_at = ${} WHERE id = ${}",
+            sets.join(", "),
+            binds.len() + 1,
+            binds.len() + 2
+        );
+
+        let mut query = sqlx::query(&sql);
+        for bind in &binds {
+            query = query.bind(bind);
+        }
+        let result = query
+            .bind(Utc::now())
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(pg_err)?;
+
+        if result.rows_affected() == 0 {
+            return Err(pg_not_found(&format!("user {id}")));
+        }
+
+        self.pg_get_user(id).await
+    }
+
+    /// Delete a user; their tasks are unassigned by `ON DELETE SET NULL`.
+    pub(crate) async fn pg_delete_user(&self, id: &str) -> Result<(), DbError> {
+        let result = sqlx::query("DELETE FROM users WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(pg_err)?;
+
+        if result.rows_affected() == 0 {
+            return Err(pg_not_found(&format!("user {id}")));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgConnection;
+
+use flowstate_core::notification::{Notification, TaskWatcher};
+use flowstate_core::task_revision::FieldChange;
+use flowstate_core::user::User;
+
+use super::super::{pg_err, pg_not_found, PostgresDatabase};
+use super::users::UserRow;
+use crate::DbError;
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct TaskWatcherRow {
+    task_id: String,
+    user_id: String,
+    created_at: DateTime<Utc>,
+}
+
+impl From<TaskWatcherRow> for TaskWatcher {
+    fn from(r: TaskWatcherRow) -> Self {
+        TaskWatcher {
+            task_id: r.task_id,
+            user_id: r.user_id,
+            created_at: r.created_at,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct NotificationRow {
+    id: String,
+    user_id: String,
+    task_id: String,
+    actor: String,
+    changes: String,
+    read: bool,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<NotificationRow> for Notification {
+    type Error = DbError;
+
+    fn try_from(r: NotificationRow) -> Result<Self, DbError> {
+        let changes = serde_json::from_str(&r.changes)
+            .map_err(|e| DbError::Internal(format!("notification {}: {e}", r.id)))?;
+        Ok(Notification {
+            id: r.id,
+            user_id: r.user_id,
+            task_id: r.task_id,
+            actor: r.actor,
+            changes,
+            read: r.read,
+            created_at: r.created_at,
+        })
+    }
+}
+
+/// Give every watcher of `task_id` a notification for `changes`, on `conn`
+/// so it lands in the same transaction as the task revision.
+pub(crate) async fn pg_notify_watchers(
+    conn: &mut PgConnection,
+    task_id: &str,
+    actor: &str,
+    changes: &[FieldChange],
+) -> Result<(), DbError> {
+    let watchers: Vec<String> =
+        sqlx::query_scalar("SELECT user_id FROM task_watchers WHERE task_id = $1")
+            .bind(task_id)
+            .fetch_all(&mut *conn)
+            .await
+            .map_err(pg_err)?;
+    if watchers.is_empty() {
+        return Ok(());
+    }
+    let changes_json =
+        serde_json::to_string(changes).map_err(|e| DbError::Internal(e.to_string()))?;
+    let now = Utc::now();
+    for user_id in watchers {
+        sqlx::query(
+            "INSERT INTO notifications (id, user_id, task_id, actor, changes, read, created_at)
+             VALUES ($1, $2, $3, $4, $5, FALSE, $6)",
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(user_id)
+        .bind(task_id)
+        .bind(actor)
+        .bind(&changes_json)
+        .bind(now)
+        .execute(&mut *conn)
+        .await
+        .map_err(pg_err)?;
+    }
+    Ok(())
+}
+
+impl PostgresDatabase {
+    pub(crate) async fn pg_watch_task(&self, task_id: &str, user_id: &str) -> Result<(), DbError> {
+        sqlx::query(
+            "INSERT INTO task_watchers (task_id, user_id, created_at) VALUES ($1, $2, $3)
+             ON CONFLICT (task_id, user_id) DO NOTHING",
+        )
+        .bind(task_id)
+        .bind(user_id)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await
+        .map_err(pg_err)?;
+        Ok(())
+    }
+
+    pub(crate) async fn pg_unwatch_task(
+        &self,
+        task_id: &str,
+        user_id: &str,
+    ) -> Result<(), DbError> {
+        sqlx::query("DELETE FROM task_watchers WHERE task_id = $1 AND user_id = $2")
+            .bind(task_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(pg_err)?;
+        Ok(())
+    }
+
+    pub(crate) async fn pg_list_task_watchers(&self, task_id: &str) -> Result<Vec<User>, DbError> {
+        let rows = sqlx::query_as::<_, UserRow>(
+            "SELECT users.* FROM users
+             JOIN task_watchers ON task_watchers.user_id = users.id
+             WHERE task_watchers.task_id = $1
+             ORDER BY users.name",
+        )
+        .bind(task_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    pub(crate) async fn pg_list_notifications(
+        &self,
+        user_id: &str,
+        unread_only: bool,
+    ) -> Result<Vec<Notification>, DbError> {
+        let sql = if unread_only {
+            "SELECT * FROM notifications WHERE user_id = $1 AND NOT read
+             ORDER BY created_at DESC"
+        } else {
+            "SELECT * FROM notifications WHERE user_id = $1 ORDER BY created_at DESC"
+        };
+        let rows = sqlx::query_as::<_, NotificationRow>(sql)
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(pg_err)?;
+
+        rows.into_iter().map(Notification::try_from).collect()
+    }
+
+    pub(crate) async fn pg_get_notification(&self, id: &str) -> Result<Notification, DbError> {
+        sqlx::query_as::<_, NotificationRow>("SELECT * FROM notifications WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(pg_err)?
+            .ok_or_else(|| pg_not_found(&format!("notification {id}")))?
+            .try_into()
+    }
+
+    pub(crate) async fn pg_mark_notification_read(
+        &self,
+        id: &str,
+    ) -> Result<Notification, DbError> {
+        sqlx::query_as::<_, NotificationRow>(
+            "UPDATE notifications SET read = TRUE WHERE id = $1 RETURNING *",
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(pg_err)?
+        .ok_or_else(|| pg_not_found(&format!("notification {id}")))?
+        .try_into()
+    }
+}
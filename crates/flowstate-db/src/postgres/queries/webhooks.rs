@@ -0,0 +1,293 @@
+use chrono::{DateTime, Utc};
+
+use flowstate_core::webhook::{
+    CreateWebhook, DeliveryAttempt, DeliveryStatus, UpdateWebhook, Webhook, WebhookDelivery,
+    WebhookEvent,
+};
+
+use super::super::{pg_err, pg_not_found, PostgresDatabase};
+use crate::DbError;
+
+/// `events` is stored as a JSON array.
+#[derive(sqlx::FromRow)]
+pub(crate) struct WebhookRow {
+    id: String,
+    url: String,
+    secret: String,
+    events: String,
+    project_id: Option<String>,
+    enabled: bool,
+    created_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+}
+
+impl From<WebhookRow> for Webhook {
+    fn from(r: WebhookRow) -> Self {
+        Webhook {
+            id: r.id,
+            url: r.url,
+            secret: r.secret,
+            events: serde_json::from_str(&r.events).unwrap_or_default(),
+            project_id: r.project_id,
+            enabled: r.enabled,
+            created_at: r.created_at,
+            updated_at: r.updated_at,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct WebhookDeliveryRow {
+    id: String,
+    webhook_id: String,
+    event: String,
+    payload: String,
+    status: String,
+    attempts: i32,
+    next_attempt_at: DateTime<Utc>,
+    response_status: Option<i32>,
+    last_error: Option<String>,
+    created_at: DateTime<Utc>,
+    delivered_at: Option<DateTime<Utc>>,
+}
+
+impl From<WebhookDeliveryRow> for WebhookDelivery {
+    fn from(r: WebhookDeliveryRow) -> Self {
+        WebhookDelivery {
+            id: r.id,
+            webhook_id: r.webhook_id,
+            event: WebhookEvent::parse_str(&r.event).unwrap_or(WebhookEvent::TaskStatusChanged),
+            payload: r.payload,
+            status: DeliveryStatus::parse_str(&r.status).unwrap_or(DeliveryStatus::Pending),
+            attempts: r.attempts,
+            next_attempt_at: r.next_attempt_at,
+            response_status: r.response_status,
+            last_error: r.last_error,
+            created_at: r.created_at,
+            delivered_at: r.delivered_at,
+        }
+    }
+}
+
+impl PostgresDatabase {
+    pub(crate) async fn pg_create_webhook(
+        &self,
+        input: &CreateWebhook,
+    ) -> Result<Webhook, DbError> {
+        let now = Utc::now();
+        let events =
+            serde_json::to_string(&input.events).map_err(|e| DbError::Internal(e.to_string()))?;
+        let row = sqlx::query_as::<_, WebhookRow>(
+            "INSERT INTO webhooks (id, url, secret, events, project_id, enabled, created_at, updated_at)
+             VALUES ($1, $2, $3, $4, $5, TRUE, $6, $6)
+             RETURNING *",
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(&input.url)
+        .bind(&input.secret)
+        .bind(events)
+        .bind(&input.project_id)
+        .bind(now)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        Ok(row.into())
+    }
+
+    pub(crate) async fn pg_get_webhook(&self, id: &str) -> Result<Webhook, DbError> {
+        let row = sqlx::query_as::<_, WebhookRow>("SELECT * FROM webhooks WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(pg_err)?
+            .ok_or_else(|| pg_not_found(&format!("webhook {id}")))?;
+
+        Ok(row.into())
+    }
+
+    pub(crate) async fn pg_list_webhooks(&self) -> Result<Vec<Webhook>, DbError> {
+        let rows =
+            sqlx::query_as::<_, WebhookRow>("SELECT * FROM webhooks ORDER BY created_at, id")
+                .fetch_all(&self.pool)
+                .await
+                .map_err(pg_err)?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    pub(crate) async fn pg_update_webhook(
+        &self,
+        id: &str,
+        update: &UpdateWebhook,
+    ) -> Result<Webhook, DbError> {
+        if update.url.is_none()
+            && update.secret.is_none()
+            && update.events.is_none()
+            && update.project_id.is_none()
+            && update.enabled.is_none()
+        {
+            return self.pg_get_webhook(id).await;
+        }
+
+        let events = update
+            .events
+            .as_ref()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|e| DbError::Internal(e.to_string()))?;
+        let row = sqlx::query_as::<_, WebhookRow>(
+            "UPDATE webhooks SET
+                 url = COALESCE($1, url),
+                 secret = COALESCE($2, secret),
+                 events = COALESCE($3, events),
+                 project_id = CASE WHEN $4 THEN $5 ELSE project_id END,
+                 enabled = COALESCE($6, enabled),
+                 updated_at = $7
+             WHERE id = $8
+             RETURNING *",
+        )
+        .bind(&update.url)
+        .bind(&update.secret)
+        .bind(events)
+        .bind(update.project_id.is_some())
+        .bind(update.project_id.clone().flatten())
+        .bind(update.enabled)
+        .bind(Utc::now())
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(pg_err)?
+        .ok_or_else(|| pg_not_found(&format!("webhook {id}")))?;
+
+        Ok(row.into())
+    }
+
+    pub(crate) async fn pg_delete_webhook(&self, id: &str) -> Result<(), DbError> {
+        let result = sqlx::query("DELETE FROM webhooks WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(pg_err)?;
+
+        if result.rows_affected() == 0 {
+            return Err(pg_not_found(&format!("webhook {id}")));
+        }
+        Ok(())
+    }
+
+    pub(crate) async fn pg_enqueue_webhook_delivery(
+        &self,
+        webhook_id: &str,
+        event: WebhookEvent,
+        payload: &str,
+    ) -> Result<WebhookDelivery, DbError> {
+        let row = sqlx::query_as::<_, WebhookDeliveryRow>(
+            "INSERT INTO webhook_deliveries
+                 (id, webhook_id, event, payload, status, attempts, next_attempt_at, created_at)
+             VALUES ($1, $2, $3, $4, 'pending', 0, $5, $5)
+             RETURNING *",
+        )
+        .bind(uuid::Uuid::new_v4().to_string())
+        .bind(webhook_id)
+        .bind(event.as_str())
+        .bind(payload)
+        .bind(Utc::now())
+        .fetch_one(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        Ok(row.into())
+    }
+
+    /// `SKIP LOCKED` keeps concurrent workers on different servers from
+    /// claiming the same delivery.
+    pub(crate) async fn pg_claim_webhook_deliveries(
+        &self,
+        lease_until: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, DbError> {
+        let rows = sqlx::query_as::<_, WebhookDeliveryRow>(
+            "UPDATE webhook_deliveries SET next_attempt_at = $1
+             WHERE id IN (
+                 SELECT id FROM webhook_deliveries
+                 WHERE status = 'pending' AND next_attempt_at <= $2
+                 ORDER BY next_attempt_at, created_at
+                 LIMIT $3
+                 FOR UPDATE SKIP LOCKED
+             )
+             RETURNING *",
+        )
+        .bind(lease_until)
+        .bind(Utc::now())
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        let mut deliveries: Vec<WebhookDelivery> = rows.into_iter().map(|r| r.into()).collect();
+        deliveries.sort_by_key(|d| d.created_at);
+        Ok(deliveries)
+    }
+
+    pub(crate) async fn pg_record_webhook_attempt(
+        &self,
+        id: &str,
+        attempt: &DeliveryAttempt,
+    ) -> Result<WebhookDelivery, DbError> {
+        let delivered_at = (attempt.status == DeliveryStatus::Delivered).then(Utc::now);
+        let row = sqlx::query_as::<_, WebhookDeliveryRow>(
+            "UPDATE webhook_deliveries
+             SET status = $1, attempts = attempts + 1, response_status = $2,
+                 last_error = $3, next_attempt_at = $4, delivered_at = $5
+             WHERE id = $6
+             RETURNING *",
+        )
+        .bind(attempt.status.as_str())
+        .bind(attempt.response_status)
+        .bind(&attempt.error)
+        .bind(attempt.next_attempt_at)
+        .bind(delivered_at)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(pg_err)?
+        .ok_or_else(|| pg_not_found(&format!("webhook delivery {id}")))?;
+
+        Ok(row.into())
+    }
+
+    pub(crate) async fn pg_list_webhook_deliveries(
+        &self,
+        webhook_id: &str,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, DbError> {
+        let rows = sqlx::query_as::<_, WebhookDeliveryRow>(
+            "SELECT * FROM webhook_deliveries WHERE webhook_id = $1
+             ORDER BY created_at DESC, id LIMIT $2",
+        )
+        .bind(webhook_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        Ok(rows.into_iter().map(|r| r.into()).collect())
+    }
+
+    pub(crate) async fn pg_prune_webhook_deliveries(
+        &self,
+        before: DateTime<Utc>,
+    ) -> Result<u64, DbError> {
+        let result = sqlx::query(
+            "DELETE FROM webhook_deliveries
+             WHERE status IN ('delivered', 'failed') AND created_at < $1",
+        )
+        .bind(before)
+        .execute(&self.pool)
+        .await
+        .map_err(pg_err)?;
+
+        Ok(result.rows_affected())
+    }
+}
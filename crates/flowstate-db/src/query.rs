@@ -0,0 +1,306 @@
+//! Dynamic `SELECT` building shared by both backends, so a filter is turned
+//! into SQL in one place and each backend only binds the values.
+
+use chrono::{DateTime, Utc};
+
+use flowstate_core::task::TaskFilter;
+
+/// The placeholder and operator flavor to emit.
+#[cfg_attr(not(all(feature = "sqlite", feature = "postgres")), allow(dead_code))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Dialect {
+    Sqlite,
+    Postgres,
+}
+
+/// A value bound to a placeholder.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SqlValue {
+    Text(String),
+    Int(i64),
+    Bool(bool),
+    Timestamp(DateTime<Utc>),
+}
+
+impl From<&str> for SqlValue {
+    fn from(s: &str) -> Self {
+        SqlValue::Text(s.to_string())
+    }
+}
+
+impl From<String> for SqlValue {
+    fn from(s: String) -> Self {
+        SqlValue::Text(s)
+    }
+}
+
+impl From<&String> for SqlValue {
+    fn from(s: &String) -> Self {
+        SqlValue::Text(s.clone())
+    }
+}
+
+impl From<i64> for SqlValue {
+    fn from(n: i64) -> Self {
+        SqlValue::Int(n)
+    }
+}
+
+impl From<bool> for SqlValue {
+    fn from(b: bool) -> Self {
+        SqlValue::Bool(b)
+    }
+}
+
+impl From<DateTime<Utc>> for SqlValue {
+    fn from(t: DateTime<Utc>) -> Self {
+        SqlValue::Timestamp(t)
+    }
+}
+
+/// A `SELECT` whose `WHERE` clause is built up condition by condition, with
+/// numbered placeholders in the backend's syntax.
+#[derive(Debug, Clone)]
+pub(crate) struct SelectQuery {
+    dialect: Dialect,
+    sql: String,
+    values: Vec<SqlValue>,
+}
+
+impl SelectQuery {
+    /// Start from `select`, which must end just before the `WHERE` clause.
+    pub fn new(dialect: Dialect, select: &str) -> Self {
+        Self {
+            dialect,
+            sql: format!("{select} WHERE 1=1"),
+            values: Vec::new(),
+        }
+    }
+
+    /// Add `value` to the bound values and return its placeholder.
+    pub fn bind(&mut self, value: impl Into<SqlValue>) -> String {
+        self.values.push(value.into());
+        match self.dialect {
+            Dialect::Sqlite => format!("?{}", self.values.len()),
+            Dialect::Postgres => format!("${}", self.values.len()),
+        }
+    }
+
+    /// `AND` a condition that binds nothing.
+    pub fn and(&mut self, condition: &str) -> &mut Self {
+        self.sql.push_str(" AND ");
+        self.sql.push_str(condition);
+        self
+    }
+
+    /// `AND column <op> value`.
+    pub fn and_cmp(&mut self, column: &str, op: &str, value: impl Into<SqlValue>) -> &mut Self {
+        let p = self.bind(value);
+        self.and(&format!("{column} {op} {p}"))
+    }
+
+    /// `AND column = value`.
+    pub fn and_eq(&mut self, column: &str, value: impl Into<SqlValue>) -> &mut Self {
+        self.and_cmp(column, "=", value)
+    }
+
+    /// `AND column IN (...)`; nothing when `values` is empty.
+    pub fn and_in<V: Into<SqlValue>>(
+        &mut self,
+        column: &str,
+        values: impl IntoIterator<Item = V>,
+    ) -> &mut Self {
+        let placeholders: Vec<String> = values.into_iter().map(|v| self.bind(v)).collect();
+        if placeholders.is_empty() {
+            return self;
+        }
+        self.and(&format!("{column} IN ({})", placeholders.join(", ")))
+    }
+
+    /// `AND` a case-insensitive substring match of `text` on any of
+    /// `columns`.
+    pub fn and_contains_text(&mut self, columns: &[&str], text: &str) -> &mut Self {
+        let p = self.bind(crate::like_pattern(text));
+        // LIKE is case-insensitive for ASCII in SQLite
+        let op = match self.dialect {
+            Dialect::Sqlite => "LIKE",
+            Dialect::Postgres => "ILIKE",
+        };
+        let matches: Vec<String> = columns
+            .iter()
+            .map(|c| format!("{c} {op} {p} ESCAPE '\\'"))
+            .collect();
+        self.and(&format!("({})", matches.join(" OR ")))
+    }
+
+    /// Append a trailing clause such as `ORDER BY`.
+    pub fn push(&mut self, clause: &str) -> &mut Self {
+        self.sql.push(' ');
+        self.sql.push_str(clause);
+        self
+    }
+
+    /// `LIMIT value`.
+    pub fn limit(&mut self, limit: i64) -> &mut Self {
+        let p = self.bind(limit);
+        self.push(&format!("LIMIT {p}"))
+    }
+
+    pub fn sql(&self) -> &str {
+        &self.sql
+    }
+
+    pub fn values(&self) -> &[SqlValue] {
+        &self.values
+    }
+}
+
+/// The `SELECT * FROM tasks` query for `filter`, in sort order.
+pub(crate) fn task_filter_query(dialect: Dialect, filter: &TaskFilter) -> SelectQuery {
+    let mut q = SelectQuery::new(dialect, "SELECT * FROM tasks");
+
+    if let Some(ref project_id) = filter.project_id {
+        q.and_eq("project_id", project_id);
+    }
+    q.and_in("project_id", &filter.project_ids);
+    if let Some(status) = filter.status {
+        q.and_eq("status", status.as_str());
+    }
+    q.and_in("status", filter.statuses.iter().map(|s| s.as_str()));
+    if let Some(priority) = filter.priority {
+        q.and_eq("priority", priority.as_str());
+    }
+    if let Some(task_type) = filter.task_type {
+        q.and_eq("task_type", task_type.as_str());
+    }
+    if let Some(ref sprint_id) = filter.sprint_id {
+        q.and_eq("sprint_id", sprint_id);
+    }
+    if let Some(ref epic_id) = filter.epic_id {
+        q.and_eq("epic_id", epic_id);
+    }
+    if let Some(ref assignee_id) = filter.assignee_id {
+        q.and_eq("assignee_id", assignee_id);
+    }
+    q.and_in("assignee_id", &filter.assignee_ids);
+    if filter.unassigned {
+        q.and("assignee_id IS NULL");
+    }
+    if let Some(ref user_id) = filter.watched_by {
+        let p = q.bind(user_id);
+        q.and(&format!(
+            "id IN (SELECT task_id FROM task_watchers WHERE user_id = {p})"
+        ));
+    }
+    for label_id in &filter.label_ids {
+        let p = q.bind(label_id);
+        q.and(&format!(
+            "id IN (SELECT task_id FROM task_labels WHERE label_id = {p})"
+        ));
+    }
+    if let Some(ref text) = filter.text {
+        q.and_contains_text(&["title", "description"], text);
+    }
+    if let Some((ref field_id, ref value)) = filter.custom_field {
+        let field = q.bind(field_id);
+        let value = q.bind(value);
+        q.and(&format!(
+            "id IN (SELECT task_id FROM task_field_values WHERE field_id = {field} AND value = {value})"
+        ));
+    }
+    if let Some(due_after) = filter.due_after {
+        q.and_cmp("due_at", ">=", due_after);
+    }
+    if let Some(due_before) = filter.due_before {
+        q.and_cmp("due_at", "<", due_before);
+    }
+    if filter.overdue {
+        q.and_cmp("due_at", "<", Utc::now())
+            .and("status NOT IN ('done', 'cancelled')");
+    }
+    if let Some(created_after) = filter.created_after {
+        q.and_cmp("created_at", ">=", created_after);
+    }
+    if let Some(created_before) = filter.created_before {
+        q.and_cmp("created_at", "<", created_before);
+    }
+    if let Some(updated_after) = filter.updated_after {
+        q.and_cmp("updated_at", ">=", updated_after);
+    }
+    match filter.parent_id {
+        Some(None) => {
+            q.and("parent_id IS NULL");
+        }
+        Some(Some(ref pid)) => {
+            q.and_eq("parent_id", pid);
+        }
+        None => {}
+    }
+    q.and_eq("archived", filter.archived);
+
+    q.push("ORDER BY sort_order ASC");
+    if let Some(limit) = filter.limit {
+        q.limit(limit);
+    }
+    q
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flowstate_core::task::Status;
+
+    #[test]
+    fn placeholders_follow_the_dialect() {
+        let filter = TaskFilter {
+            project_id: Some("p1".into()),
+            statuses: vec![Status::Todo, Status::Build],
+            limit: Some(10),
+            ..Default::default()
+        };
+
+        let q = task_filter_query(Dialect::Sqlite, &filter);
+        assert_eq!(
+            q.sql(),
+            "SELECT * FROM tasks WHERE 1=1 AND project_id = ?1 AND status IN (?2, ?3) \
+             AND archived = ?4 ORDER BY sort_order ASC LIMIT ?5"
+        );
+        assert_eq!(
+            q.values(),
+            &[
+                SqlValue::Text("p1".into()),
+                SqlValue::Text("todo".into()),
+                SqlValue::Text("build".into()),
+                SqlValue::Bool(false),
+                SqlValue::Int(10),
+            ]
+        );
+
+        let q = task_filter_query(Dialect::Postgres, &filter);
+        assert_eq!(
+            q.sql(),
+            "SELECT * FROM tasks WHERE 1=1 AND project_id = $1 AND status IN ($2, $3) \
+             AND archived = $4 ORDER BY sort_order ASC LIMIT $5"
+        );
+    }
+
+    #[test]
+    fn text_match_reuses_one_placeholder() {
+        let filter = TaskFilter {
+            text: Some("50%".into()),
+            ..Default::default()
+        };
+        let q = task_filter_query(Dialect::Postgres, &filter);
+        assert!(q
+            .sql()
+            .contains("(title ILIKE $1 ESCAPE '\\' OR description ILIKE $1 ESCAPE '\\')"));
+        assert_eq!(q.values()[0], SqlValue::Text("%50\\%%".into()));
+    }
+
+    #[test]
+    fn empty_lists_add_no_condition() {
+        let q = task_filter_query(Dialect::Sqlite, &TaskFilter::default());
+        assert!(!q.sql().contains(" IN "));
+        assert_eq!(q.values(), &[SqlValue::Bool(false)]);
+    }
+}
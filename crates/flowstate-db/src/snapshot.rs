@@ -0,0 +1,335 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use flowstate_core::approval_rule::ApprovalRule;
+use flowstate_core::attachment::Attachment;
+use flowstate_core::claude_run::ClaudeRun;
+use flowstate_core::custom_field::{CustomField, TaskFieldValue};
+use flowstate_core::epic::Epic;
+use flowstate_core::feature_flag::FeatureFlag;
+use flowstate_core::feedback::FeedbackEntry;
+use flowstate_core::label::Label;
+use flowstate_core::notification::{Notification, TaskWatcher};
+use flowstate_core::organization::Organization;
+use flowstate_core::project::Project;
+use flowstate_core::run_metrics::RunMetrics;
+use flowstate_core::saved_filter::SavedFilter;
+use flowstate_core::sprint::Sprint;
+use flowstate_core::subscription::Subscription;
+use flowstate_core::task::Task;
+use flowstate_core::task_link::TaskLink;
+use flowstate_core::task_pr::TaskPr;
+use flowstate_core::task_revision::TaskRevision;
+use flowstate_core::user::User;
+use flowstate_core::webhook::Webhook;
+
+/// Format version written into every snapshot. Bump when the layout changes
+/// in a way older readers cannot handle.
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// Tables deliberately left out of snapshots. Every other table must have a
+/// matching `Snapshot` field of the same name.
+pub const EXCLUDED_TABLES: &[&str] = &[
+    // Migration bookkeeping; the target database tracks its own.
+    "schema_version",
+    // Per-deployment credentials.
+    "api_keys",
+    "api_key_projects",
+    // Runners re-register with the server they connect to.
+    "runners",
+    // Delivery history, not configuration.
+    "webhook_deliveries",
+    // Legacy tables that nothing reads or writes.
+    "commit_links",
+    "task_verifications",
+    "verification_profiles",
+    "verification_steps",
+    "verification_runs",
+    "verification_run_steps",
+];
+
+/// Backend-agnostic dump of every entity in the database.
+///
+/// Produced by `Database::export_snapshot` and consumed by
+/// `Database::import_snapshot`, which preserves IDs and timestamps so a
+/// snapshot taken from SQLite can be restored into Postgres (and vice versa).
+/// Each field holds one table; the tables in [`EXCLUDED_TABLES`] are left out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub format_version: u32,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub organizations: Vec<Organization>,
+    #[serde(default)]
+    pub projects: Vec<Project>,
+    #[serde(default)]
+    pub sprints: Vec<Sprint>,
+    #[serde(default)]
+    pub epics: Vec<Epic>,
+    #[serde(default)]
+    pub labels: Vec<Label>,
+    #[serde(default)]
+    pub users: Vec<User>,
+    #[serde(default)]
+    pub saved_filters: Vec<SavedFilter>,
+    #[serde(default)]
+    pub custom_fields: Vec<CustomField>,
+    #[serde(default)]
+    pub tasks: Vec<Task>,
+    #[serde(default)]
+    pub task_field_values: Vec<TaskFieldValue>,
+    #[serde(default)]
+    pub task_labels: Vec<TaskLabel>,
+    #[serde(default)]
+    pub claude_runs: Vec<ClaudeRun>,
+    #[serde(default)]
+    pub run_metrics: Vec<RunMetrics>,
+    #[serde(default)]
+    pub task_links: Vec<TaskLink>,
+    #[serde(default)]
+    pub task_prs: Vec<TaskPr>,
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+    #[serde(default)]
+    pub task_revisions: Vec<TaskRevision>,
+    #[serde(default)]
+    pub feedback_history: Vec<FeedbackEntry>,
+    #[serde(default)]
+    pub task_watchers: Vec<TaskWatcher>,
+    #[serde(default)]
+    pub notifications: Vec<Notification>,
+    #[serde(default)]
+    pub webhooks: Vec<WebhookRecord>,
+    #[serde(default)]
+    pub feature_flags: Vec<FeatureFlag>,
+    #[serde(default)]
+    pub task_imports: Vec<TaskImport>,
+    #[serde(default)]
+    pub approval_rules: Vec<ApprovalRule>,
+    #[serde(default)]
+    pub subscriptions: Vec<Subscription>,
+}
+
+/// A webhook together with its signing secret, which `Webhook` never
+/// serializes. The secret stays encrypted with the server key, so a restore
+/// needs the same `server.key` for deliveries to be signed correctly.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookRecord {
+    #[serde(flatten)]
+    pub webhook: Webhook,
+    pub secret: String,
+}
+
+impl From<Webhook> for WebhookRecord {
+    fn from(webhook: Webhook) -> Self {
+        let secret = webhook.secret.clone();
+        Self { webhook, secret }
+    }
+}
+
+/// Assignment of a label to a task.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskLabel {
+    pub task_id: String,
+    pub label_id: String,
+}
+
+/// Record of an external issue imported as a task. Import skips sources it
+/// has already seen, so these must survive a restore to avoid duplicates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskImport {
+    pub project_id: String,
+    pub source: String,
+    pub task_id: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Snapshot {
+    /// Create an empty snapshot stamped with the current format version.
+    pub fn new() -> Self {
+        Self {
+            format_version: SNAPSHOT_FORMAT_VERSION,
+            created_at: Utc::now(),
+            organizations: Vec::new(),
+            projects: Vec::new(),
+            sprints: Vec::new(),
+            epics: Vec::new(),
+            labels: Vec::new(),
+            users: Vec::new(),
+            saved_filters: Vec::new(),
+            custom_fields: Vec::new(),
+            tasks: Vec::new(),
+            task_field_values: Vec::new(),
+            task_labels: Vec::new(),
+            claude_runs: Vec::new(),
+            run_metrics: Vec::new(),
+            task_links: Vec::new(),
+            task_prs: Vec::new(),
+            attachments: Vec::new(),
+            task_revisions: Vec::new(),
+            feedback_history: Vec::new(),
+            task_watchers: Vec::new(),
+            notifications: Vec::new(),
+            webhooks: Vec::new(),
+            feature_flags: Vec::new(),
+            task_imports: Vec::new(),
+            approval_rules: Vec::new(),
+            subscriptions: Vec::new(),
+        }
+    }
+
+    /// Total number of entities across all tables.
+    pub fn entity_count(&self) -> usize {
+        self.organizations.len()
+            + self.projects.len()
+            + self.sprints.len()
+            + self.epics.len()
+            + self.labels.len()
+            + self.users.len()
+            + self.saved_filters.len()
+            + self.custom_fields.len()
+            + self.tasks.len()
+            + self.task_field_values.len()
+            + self.task_labels.len()
+            + self.claude_runs.len()
+            + self.run_metrics.len()
+            + self.task_links.len()
+            + self.task_prs.len()
+            + self.attachments.len()
+            + self.task_revisions.len()
+            + self.feedback_history.len()
+            + self.task_watchers.len()
+            + self.notifications.len()
+            + self.webhooks.len()
+            + self.feature_flags.len()
+            + self.task_imports.len()
+            + self.approval_rules.len()
+            + self.subscriptions.len()
+    }
+
+    /// Tasks ordered so that every parent precedes its children.
+    ///
+    /// Import inserts tasks in this order so the `parent_id` foreign key is
+    /// always satisfied, regardless of the order they were exported in.
+    pub fn tasks_parent_first(&self) -> Vec<&Task> {
+        let mut ordered: Vec<&Task> = Vec::with_capacity(self.tasks.len());
+        let mut placed: std::collections::HashSet<&str> = std::collections::HashSet::new();
+        let mut remaining: Vec<&Task> = self.tasks.iter().collect();
+
+        while !remaining.is_empty() {
+            let before = remaining.len();
+            remaining.retain(|t| {
+                let ready = match t.parent_id.as_deref() {
+                    None => true,
+                    Some(pid) => placed.contains(pid) || !self.tasks.iter().any(|p| p.id == pid),
+                };
+                if ready {
+                    placed.insert(t.id.as_str());
+                    ordered.push(t);
+                }
+                !ready
+            });
+            if remaining.len() == before {
+                // Cycle in parent_id: append the rest as-is and let the
+                // database report the constraint violation.
+                ordered.append(&mut remaining);
+            }
+        }
+        ordered
+    }
+}
+
+impl Default for Snapshot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flowstate_core::task::{ApprovalStatus, Priority, Status, TaskType};
+
+    fn task(id: &str, parent: Option<&str>) -> Task {
+        Task {
+            id: id.into(),
+            project_id: "p".into(),
+            sprint_id: None,
+            epic_id: None,
+            assignee_id: None,
+            parent_id: parent.map(String::from),
+            title: id.into(),
+            description: String::new(),
+            reviewer: String::new(),
+            research_status: ApprovalStatus::None,
+            spec_status: ApprovalStatus::None,
+            plan_status: ApprovalStatus::None,
+            verify_status: ApprovalStatus::None,
+            spec_approved_hash: String::new(),
+            research_approved_hash: String::new(),
+            research_feedback: String::new(),
+            spec_feedback: String::new(),
+            plan_feedback: String::new(),
+            verify_feedback: String::new(),
+            status: Status::Todo,
+            priority: Priority::Medium,
+            task_type: TaskType::Feature,
+            research_capability: None,
+            design_capability: None,
+            plan_capability: None,
+            build_capability: None,
+            verify_capability: None,
+            due_at: None,
+            sort_order: 0.0,
+            archived: false,
+            autopilot: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn tasks_parent_first_orders_children_after_parents() {
+        let mut snap = Snapshot::new();
+        snap.tasks = vec![
+            task("grandchild", Some("child")),
+            task("child", Some("root")),
+            task("root", None),
+        ];
+        let order: Vec<&str> = snap
+            .tasks_parent_first()
+            .iter()
+            .map(|t| t.id.as_str())
+            .collect();
+        assert_eq!(order, vec!["root", "child", "grandchild"]);
+    }
+
+    #[test]
+    fn snapshot_roundtrips_through_json() {
+        let mut snap = Snapshot::new();
+        snap.tasks.push(task("t1", None));
+        let json = serde_json::to_string(&snap).unwrap();
+        let parsed: Snapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.format_version, SNAPSHOT_FORMAT_VERSION);
+        assert_eq!(parsed.entity_count(), 1);
+    }
+
+    #[test]
+    fn webhook_secret_survives_serialization() {
+        let mut snap = Snapshot::new();
+        snap.webhooks.push(WebhookRecord::from(Webhook {
+            id: "w1".into(),
+            url: "https://example.com/hook".into(),
+            secret: "enc:v1:abc".into(),
+            events: Vec::new(),
+            project_id: None,
+            enabled: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }));
+        let json = serde_json::to_string(&snap).unwrap();
+        let parsed: Snapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.webhooks[0].secret, "enc:v1:abc");
+        assert_eq!(parsed.webhooks[0].webhook.url, "https://example.com/hook");
+    }
+}
@@ -1,9 +1,688 @@
+use std::path::Path;
+
 use rusqlite::Connection;
 
 use super::SqliteResultExt;
+use crate::migrate::{self, Migration, MigrationPlan};
 use crate::DbError;
 
+/// Versions 1-12 predate down migrations and are applied imperatively below
+/// (`up: None`); from v13 on each step is plain SQL with a matching revert.
+pub(crate) const MIGRATIONS: &[Migration] = &[
+    legacy(1, "project repo_url, task links, claude runs, attachments"),
+    legacy(2, "task spec_approved_hash"),
+    legacy(3, "claude_run pr_url and pr_number"),
+    legacy(4, "claude_run progress_message"),
+    legacy(5, "project repo_token"),
+    legacy(6, "task_prs"),
+    legacy(7, "attachment store_key"),
+    legacy(8, "five-phase workflow"),
+    legacy(9, "salvage statuses and runner_id"),
+    legacy(10, "claude_run required_capability"),
+    legacy(11, "project provider_type and skip_tls_verify"),
+    legacy(12, "task phase capabilities"),
+    Migration {
+        // Per-update diff history for tasks.
+        version: 13,
+        name: "task_revisions",
+        up: Some(
+            "CREATE TABLE IF NOT EXISTS task_revisions (
+                 id          TEXT PRIMARY KEY,
+                 task_id     TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+                 actor       TEXT NOT NULL DEFAULT '',
+                 changes     TEXT NOT NULL,
+                 created_at  TEXT NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS idx_task_revisions_task
+                 ON task_revisions(task_id, created_at);",
+        ),
+        down: Some("DROP TABLE IF EXISTS task_revisions;"),
+    },
+    Migration {
+        // Per-key network policy (JSON arrays, empty = unrestricted).
+        version: 14,
+        name: "api_key policy",
+        up: Some(
+            "ALTER TABLE api_keys ADD COLUMN allowed_cidrs TEXT NOT NULL DEFAULT '[]';
+             ALTER TABLE api_keys ADD COLUMN allowed_routes TEXT NOT NULL DEFAULT '[]';",
+        ),
+        down: Some(
+            "ALTER TABLE api_keys DROP COLUMN allowed_routes;
+             ALTER TABLE api_keys DROP COLUMN allowed_cidrs;",
+        ),
+    },
+    Migration {
+        // Runtime feature flags. project_id '' is the global scope.
+        version: 15,
+        name: "feature_flags",
+        up: Some(
+            "CREATE TABLE IF NOT EXISTS feature_flags (
+                 key         TEXT NOT NULL,
+                 project_id  TEXT NOT NULL DEFAULT '',
+                 enabled     INTEGER NOT NULL,
+                 updated_at  TEXT NOT NULL,
+                 PRIMARY KEY (key, project_id)
+             );",
+        ),
+        down: Some("DROP TABLE IF EXISTS feature_flags;"),
+    },
+    Migration {
+        // Per-run resource accounting reported by runners.
+        version: 16,
+        name: "run_metrics",
+        up: Some(
+            "CREATE TABLE IF NOT EXISTS run_metrics (
+                 run_id         TEXT PRIMARY KEY REFERENCES claude_runs(id) ON DELETE CASCADE,
+                 duration_ms    INTEGER NOT NULL,
+                 stdout_bytes   INTEGER NOT NULL,
+                 input_tokens   INTEGER,
+                 output_tokens  INTEGER,
+                 cost_usd       REAL,
+                 recorded_at    TEXT NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS idx_run_metrics_recorded
+                 ON run_metrics(recorded_at);",
+        ),
+        down: Some("DROP TABLE IF EXISTS run_metrics;"),
+    },
+    Migration {
+        // Scheduling weight for queued runs (higher claimed first).
+        version: 17,
+        name: "claude_run priority",
+        up: Some(
+            "ALTER TABLE claude_runs ADD COLUMN priority INTEGER NOT NULL DEFAULT 0;
+             CREATE INDEX IF NOT EXISTS idx_claude_runs_queue
+                 ON claude_runs(status, priority DESC, started_at);",
+        ),
+        down: Some(
+            "DROP INDEX IF EXISTS idx_claude_runs_queue;
+             ALTER TABLE claude_runs DROP COLUMN priority;",
+        ),
+    },
+    Migration {
+        // Per-project cap on simultaneously running runs (NULL = unlimited).
+        version: 18,
+        name: "project max_concurrent_runs",
+        up: Some("ALTER TABLE projects ADD COLUMN max_concurrent_runs INTEGER;"),
+        down: Some("ALTER TABLE projects DROP COLUMN max_concurrent_runs;"),
+    },
+    Migration {
+        // Per-project weight for fair run claiming across projects.
+        version: 19,
+        name: "project claim_weight",
+        up: Some("ALTER TABLE projects ADD COLUMN claim_weight INTEGER NOT NULL DEFAULT 1;"),
+        down: Some("ALTER TABLE projects DROP COLUMN claim_weight;"),
+    },
+    Migration {
+        // Epics, and the tasks that belong to them. tasks.epic_id has no
+        // foreign key so the column can be dropped again; delete_epic
+        // clears it instead.
+        version: 20,
+        name: "epics",
+        up: Some(
+            "CREATE TABLE IF NOT EXISTS epics (
+                 id           TEXT PRIMARY KEY,
+                 project_id   TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+                 name         TEXT NOT NULL,
+                 description  TEXT NOT NULL DEFAULT '',
+                 status       TEXT NOT NULL DEFAULT 'open'
+                                  CHECK(status IN ('open', 'in_progress', 'done', 'cancelled')),
+                 target_date  TEXT,
+                 created_at   TEXT NOT NULL,
+                 updated_at   TEXT NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS idx_epics_project ON epics(project_id);
+             ALTER TABLE tasks ADD COLUMN epic_id TEXT;
+             CREATE INDEX IF NOT EXISTS idx_tasks_epic ON tasks(epic_id);",
+        ),
+        down: Some(
+            "DROP INDEX IF EXISTS idx_tasks_epic;
+             ALTER TABLE tasks DROP COLUMN epic_id;
+             DROP TABLE IF EXISTS epics;",
+        ),
+    },
+    Migration {
+        // Reviewer feedback a distill run was queued to address.
+        version: 21,
+        name: "claude_run feedback",
+        up: Some("ALTER TABLE claude_runs ADD COLUMN feedback TEXT;"),
+        down: Some("ALTER TABLE claude_runs DROP COLUMN feedback;"),
+    },
+    Migration {
+        // Per-project custom task fields. `options` is a JSON array, only
+        // non-empty for enum fields.
+        version: 22,
+        name: "custom fields",
+        up: Some(
+            "CREATE TABLE IF NOT EXISTS custom_fields (
+                 id          TEXT PRIMARY KEY,
+                 project_id  TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+                 name        TEXT NOT NULL,
+                 field_type  TEXT NOT NULL
+                                 CHECK(field_type IN ('text', 'number', 'enum', 'date')),
+                 options     TEXT NOT NULL DEFAULT '[]',
+                 created_at  TEXT NOT NULL,
+                 updated_at  TEXT NOT NULL,
+                 UNIQUE(project_id, name)
+             );
+             CREATE TABLE IF NOT EXISTS task_field_values (
+                 task_id   TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+                 field_id  TEXT NOT NULL REFERENCES custom_fields(id) ON DELETE CASCADE,
+                 value     TEXT NOT NULL,
+                 PRIMARY KEY (task_id, field_id)
+             );
+             CREATE INDEX IF NOT EXISTS idx_task_field_values_field
+                 ON task_field_values(field_id, value);",
+        ),
+        down: Some(
+            "DROP TABLE IF EXISTS task_field_values;
+             DROP TABLE IF EXISTS custom_fields;",
+        ),
+    },
+    Migration {
+        // Every rejection's feedback, since the task only keeps the latest
+        // per phase.
+        version: 23,
+        name: "feedback history",
+        up: Some(
+            "CREATE TABLE IF NOT EXISTS feedback_history (
+                 id          TEXT PRIMARY KEY,
+                 task_id     TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+                 phase       TEXT NOT NULL
+                                 CHECK(phase IN ('research', 'design', 'plan', 'verify')),
+                 feedback    TEXT NOT NULL,
+                 author      TEXT NOT NULL DEFAULT '',
+                 created_at  TEXT NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS idx_feedback_history_task
+                 ON feedback_history(task_id, created_at);",
+        ),
+        down: Some("DROP TABLE IF EXISTS feedback_history;"),
+    },
+    Migration {
+        // Optional task deadline, for due-date and overdue queries.
+        version: 24,
+        name: "task due_at",
+        up: Some(
+            "ALTER TABLE tasks ADD COLUMN due_at TEXT;
+             CREATE INDEX IF NOT EXISTS idx_tasks_due ON tasks(due_at);",
+        ),
+        down: Some(
+            "DROP INDEX IF EXISTS idx_tasks_due;
+             ALTER TABLE tasks DROP COLUMN due_at;",
+        ),
+    },
+    Migration {
+        // Users tasks can be assigned to. As with epic_id, tasks.assignee_id
+        // has no foreign key so the column can be dropped again; delete_user
+        // clears it instead.
+        version: 25,
+        name: "users",
+        up: Some(
+            "CREATE TABLE IF NOT EXISTS users (
+                 id          TEXT PRIMARY KEY,
+                 name        TEXT NOT NULL,
+                 email       TEXT NOT NULL DEFAULT '',
+                 created_at  TEXT NOT NULL,
+                 updated_at  TEXT NOT NULL
+             );
+             CREATE UNIQUE INDEX IF NOT EXISTS idx_users_email
+                 ON users(email) WHERE email != '';
+             ALTER TABLE tasks ADD COLUMN assignee_id TEXT;
+             CREATE INDEX IF NOT EXISTS idx_tasks_assignee ON tasks(assignee_id);",
+        ),
+        down: Some(
+            "DROP INDEX IF EXISTS idx_tasks_assignee;
+             ALTER TABLE tasks DROP COLUMN assignee_id;
+             DROP TABLE IF EXISTS users;",
+        ),
+    },
+    Migration {
+        // Named task queries; `query` is a JSON-encoded FilterQuery.
+        version: 26,
+        name: "saved filters",
+        up: Some(
+            "CREATE TABLE IF NOT EXISTS saved_filters (
+                 id          TEXT PRIMARY KEY,
+                 project_id  TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+                 user_id     TEXT REFERENCES users(id) ON DELETE CASCADE,
+                 name        TEXT NOT NULL,
+                 query       TEXT NOT NULL DEFAULT '{}',
+                 created_at  TEXT NOT NULL,
+                 updated_at  TEXT NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS idx_saved_filters_project
+                 ON saved_filters(project_id, user_id);",
+        ),
+        down: Some("DROP TABLE IF EXISTS saved_filters;"),
+    },
+    Migration {
+        // Attachment metadata computed on upload.
+        version: 27,
+        name: "attachment content type and checksum",
+        up: Some(
+            "ALTER TABLE attachments ADD COLUMN content_type TEXT NOT NULL DEFAULT '';
+             ALTER TABLE attachments ADD COLUMN sha256 TEXT NOT NULL DEFAULT '';",
+        ),
+        down: Some(
+            "ALTER TABLE attachments DROP COLUMN sha256;
+             ALTER TABLE attachments DROP COLUMN content_type;",
+        ),
+    },
+    Migration {
+        // Task subscriptions, and the per-watcher copies of each task
+        // revision that update_task records for them.
+        version: 28,
+        name: "task watchers and notifications",
+        up: Some(
+            "CREATE TABLE IF NOT EXISTS task_watchers (
+                 task_id     TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+                 user_id     TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                 created_at  TEXT NOT NULL,
+                 PRIMARY KEY (task_id, user_id)
+             );
+             CREATE INDEX IF NOT EXISTS idx_task_watchers_user ON task_watchers(user_id);
+             CREATE TABLE IF NOT EXISTS notifications (
+                 id          TEXT PRIMARY KEY,
+                 user_id     TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                 task_id     TEXT NOT NULL REFERENCES tasks(id) ON DELETE CASCADE,
+                 actor       TEXT NOT NULL DEFAULT '',
+                 changes     TEXT NOT NULL DEFAULT '[]',
+                 read        INTEGER NOT NULL DEFAULT 0,
+                 created_at  TEXT NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS idx_notifications_user
+                 ON notifications(user_id, read);",
+        ),
+        down: Some(
+            "DROP TABLE IF EXISTS notifications;
+             DROP TABLE IF EXISTS task_watchers;",
+        ),
+    },
+    Migration {
+        // Client-supplied keys that make run creation safe to retry.
+        version: 29,
+        name: "claude run idempotency keys",
+        up: Some(
+            "ALTER TABLE claude_runs ADD COLUMN idempotency_key TEXT;
+             CREATE UNIQUE INDEX IF NOT EXISTS idx_claude_runs_idempotency
+                 ON claude_runs(task_id, idempotency_key)
+                 WHERE idempotency_key IS NOT NULL;",
+        ),
+        down: Some(
+            "DROP INDEX IF EXISTS idx_claude_runs_idempotency;
+             ALTER TABLE claude_runs DROP COLUMN idempotency_key;",
+        ),
+    },
+    Migration {
+        // Archived sprints and tasks drop off the board but are kept.
+        version: 30,
+        name: "archived sprints and tasks",
+        up: Some(
+            "ALTER TABLE tasks ADD COLUMN archived INTEGER NOT NULL DEFAULT 0;
+             ALTER TABLE sprints ADD COLUMN archived INTEGER NOT NULL DEFAULT 0;",
+        ),
+        down: Some(
+            "ALTER TABLE tasks DROP COLUMN archived;
+             ALTER TABLE sprints DROP COLUMN archived;",
+        ),
+    },
+    Migration {
+        // Daily claim windows: minutes after local midnight plus the UTC
+        // offset, so the claim query can test them without parsing.
+        version: 31,
+        name: "run windows",
+        up: Some(
+            "ALTER TABLE projects ADD COLUMN run_window_start INTEGER;
+             ALTER TABLE projects ADD COLUMN run_window_end INTEGER;
+             ALTER TABLE projects ADD COLUMN run_window_offset INTEGER;
+             ALTER TABLE claude_runs ADD COLUMN run_window_start INTEGER;
+             ALTER TABLE claude_runs ADD COLUMN run_window_end INTEGER;
+             ALTER TABLE claude_runs ADD COLUMN run_window_offset INTEGER;",
+        ),
+        down: Some(
+            "ALTER TABLE projects DROP COLUMN run_window_start;
+             ALTER TABLE projects DROP COLUMN run_window_end;
+             ALTER TABLE projects DROP COLUMN run_window_offset;
+             ALTER TABLE claude_runs DROP COLUMN run_window_start;
+             ALTER TABLE claude_runs DROP COLUMN run_window_end;
+             ALTER TABLE claude_runs DROP COLUMN run_window_offset;",
+        ),
+    },
+    Migration {
+        // Content-addressed attachments share a store key; counting the
+        // rows that point at one tells when its blob can go.
+        version: 32,
+        name: "attachment store key index",
+        up: Some("CREATE INDEX IF NOT EXISTS idx_attachments_store_key ON attachments(store_key);"),
+        down: Some("DROP INDEX IF EXISTS idx_attachments_store_key;"),
+    },
+    Migration {
+        version: 33,
+        name: "project docs in repo",
+        up: Some("ALTER TABLE projects ADD COLUMN docs_in_repo INTEGER NOT NULL DEFAULT 0;"),
+        down: Some("ALTER TABLE projects DROP COLUMN docs_in_repo;"),
+    },
+    Migration {
+        version: 34,
+        name: "task type",
+        up: Some("ALTER TABLE tasks ADD COLUMN task_type TEXT NOT NULL DEFAULT 'feature';"),
+        down: Some("ALTER TABLE tasks DROP COLUMN task_type;"),
+    },
+    Migration {
+        version: 35,
+        name: "project verify followups",
+        up: Some("ALTER TABLE projects ADD COLUMN verify_followups INTEGER NOT NULL DEFAULT 0;"),
+        down: Some("ALTER TABLE projects DROP COLUMN verify_followups;"),
+    },
+    Migration {
+        // Outbound webhooks and their delivery queue. events is a JSON array;
+        // empty means every event.
+        version: 36,
+        name: "webhooks",
+        up: Some(
+            "CREATE TABLE IF NOT EXISTS webhooks (
+                 id          TEXT PRIMARY KEY,
+                 url         TEXT NOT NULL,
+                 secret      TEXT NOT NULL,
+                 events      TEXT NOT NULL DEFAULT '[]',
+                 project_id  TEXT REFERENCES projects(id) ON DELETE CASCADE,
+                 enabled     INTEGER NOT NULL DEFAULT 1,
+                 created_at  TEXT NOT NULL,
+                 updated_at  TEXT NOT NULL
+             );
+             CREATE TABLE IF NOT EXISTS webhook_deliveries (
+                 id               TEXT PRIMARY KEY,
+                 webhook_id       TEXT NOT NULL REFERENCES webhooks(id) ON DELETE CASCADE,
+                 event            TEXT NOT NULL,
+                 payload          TEXT NOT NULL,
+                 status           TEXT NOT NULL,
+                 attempts         INTEGER NOT NULL DEFAULT 0,
+                 next_attempt_at  TEXT NOT NULL,
+                 response_status  INTEGER,
+                 last_error       TEXT,
+                 created_at       TEXT NOT NULL,
+                 delivered_at     TEXT
+             );
+             CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_due
+                 ON webhook_deliveries(status, next_attempt_at);
+             CREATE INDEX IF NOT EXISTS idx_webhook_deliveries_webhook
+                 ON webhook_deliveries(webhook_id, created_at);",
+        ),
+        down: Some(
+            "DROP TABLE IF EXISTS webhook_deliveries;
+             DROP TABLE IF EXISTS webhooks;",
+        ),
+    },
+    Migration {
+        // open, merged or closed; kept current by the GitHub webhook receiver.
+        version: 37,
+        name: "task_pr state",
+        up: Some("ALTER TABLE task_prs ADD COLUMN state TEXT NOT NULL DEFAULT 'open';"),
+        down: Some("ALTER TABLE task_prs DROP COLUMN state;"),
+    },
+    Migration {
+        // Projects an API key is restricted to; none means every project.
+        // project_id has no foreign key so deleting a project cannot widen
+        // a key that was scoped to it.
+        version: 38,
+        name: "api key project scopes",
+        up: Some(
+            "CREATE TABLE IF NOT EXISTS api_key_projects (
+                 api_key_id  TEXT NOT NULL REFERENCES api_keys(id) ON DELETE CASCADE,
+                 project_id  TEXT NOT NULL,
+                 PRIMARY KEY (api_key_id, project_id)
+             );",
+        ),
+        down: Some("DROP TABLE IF EXISTS api_key_projects;"),
+    },
+    Migration {
+        // Where imported tasks came from, so importing again skips them.
+        // task_id has no foreign key so a task deleted after import is not
+        // brought back by the next one.
+        version: 39,
+        name: "task imports",
+        up: Some(
+            "CREATE TABLE IF NOT EXISTS task_imports (
+                 project_id  TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+                 source      TEXT NOT NULL,
+                 task_id     TEXT NOT NULL,
+                 created_at  TEXT NOT NULL,
+                 PRIMARY KEY (project_id, source)
+             );",
+        ),
+        down: Some("DROP TABLE IF EXISTS task_imports;"),
+    },
+    Migration {
+        version: 40,
+        name: "task autopilot",
+        up: Some("ALTER TABLE tasks ADD COLUMN autopilot INTEGER NOT NULL DEFAULT 0;"),
+        down: Some("ALTER TABLE tasks DROP COLUMN autopilot;"),
+    },
+    Migration {
+        version: 41,
+        name: "approval rules",
+        up: Some(
+            "CREATE TABLE IF NOT EXISTS approval_rules (
+                 project_id  TEXT NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+                 phase       TEXT NOT NULL,
+                 policy      TEXT NOT NULL,
+                 updated_at  TEXT NOT NULL,
+                 PRIMARY KEY (project_id, phase)
+             );",
+        ),
+        down: Some("DROP TABLE IF EXISTS approval_rules;"),
+    },
+    Migration {
+        version: 42,
+        name: "notification subscriptions",
+        up: Some(
+            "CREATE TABLE IF NOT EXISTS subscriptions (
+                 id          TEXT PRIMARY KEY,
+                 channel     TEXT NOT NULL,
+                 target      TEXT NOT NULL DEFAULT '',
+                 events      TEXT NOT NULL DEFAULT '[]',
+                 project_id  TEXT REFERENCES projects(id) ON DELETE CASCADE,
+                 user_id     TEXT REFERENCES users(id) ON DELETE CASCADE,
+                 created_at  TEXT NOT NULL
+             );",
+        ),
+        down: Some("DROP TABLE IF EXISTS subscriptions;"),
+    },
+    Migration {
+        // Teams sharing the server. Projects and API keys without an org
+        // belong to the server as a whole.
+        version: 43,
+        name: "organizations",
+        up: Some(
+            "CREATE TABLE IF NOT EXISTS organizations (
+                 id          TEXT PRIMARY KEY,
+                 name        TEXT NOT NULL,
+                 slug        TEXT NOT NULL UNIQUE,
+                 created_at  TEXT NOT NULL
+             );
+             ALTER TABLE projects ADD COLUMN org_id TEXT;
+             CREATE INDEX IF NOT EXISTS idx_projects_org ON projects(org_id);
+             ALTER TABLE api_keys ADD COLUMN org_id TEXT;",
+        ),
+        down: Some(
+            "ALTER TABLE api_keys DROP COLUMN org_id;
+             DROP INDEX IF EXISTS idx_projects_org;
+             ALTER TABLE projects DROP COLUMN org_id;
+             DROP TABLE IF EXISTS organizations;",
+        ),
+    },
+    Migration {
+        // The runner registry, so drain commands and history outlive a
+        // server restart. Config and benchmark are JSON.
+        version: 44,
+        name: "runners",
+        up: Some(
+            "CREATE TABLE IF NOT EXISTS runners (
+                 runner_id       TEXT PRIMARY KEY,
+                 org_id          TEXT,
+                 backend_name    TEXT,
+                 capability      TEXT,
+                 poll_interval   INTEGER,
+                 max_concurrent  INTEGER,
+                 max_builds      INTEGER,
+                 active_count    INTEGER,
+                 active_builds   INTEGER,
+                 status          TEXT NOT NULL DEFAULT 'active',
+                 pending_config  TEXT,
+                 benchmark       TEXT,
+                 first_seen_at   TEXT NOT NULL,
+                 last_seen_at    TEXT NOT NULL
+             );",
+        ),
+        down: Some("DROP TABLE IF EXISTS runners;"),
+    },
+    Migration {
+        version: 45,
+        name: "project budgets",
+        up: Some(
+            "ALTER TABLE projects ADD COLUMN monthly_budget_usd REAL;
+             ALTER TABLE projects ADD COLUMN budget_override_until TEXT;",
+        ),
+        down: Some(
+            "ALTER TABLE projects DROP COLUMN budget_override_until;
+             ALTER TABLE projects DROP COLUMN monthly_budget_usd;",
+        ),
+    },
+    Migration {
+        version: 46,
+        name: "run trace context",
+        up: Some("ALTER TABLE claude_runs ADD COLUMN trace_context TEXT;"),
+        down: Some("ALTER TABLE claude_runs DROP COLUMN trace_context;"),
+    },
+    Migration {
+        version: 47,
+        name: "project ssh key path",
+        up: Some("ALTER TABLE projects ADD COLUMN ssh_key_path TEXT;"),
+        down: Some("ALTER TABLE projects DROP COLUMN ssh_key_path;"),
+    },
+    Migration {
+        // pending, success or failure; null until CI checks are seen.
+        version: 48,
+        name: "task_pr checks status",
+        up: Some("ALTER TABLE task_prs ADD COLUMN checks_status TEXT;"),
+        down: Some("ALTER TABLE task_prs DROP COLUMN checks_status;"),
+    },
+    Migration {
+        version: 49,
+        name: "task_pr draft",
+        up: Some("ALTER TABLE task_prs ADD COLUMN draft INTEGER NOT NULL DEFAULT 0;"),
+        down: Some("ALTER TABLE task_prs DROP COLUMN draft;"),
+    },
+    Migration {
+        // SQLite cannot alter a CHECK constraint, and rebuilding claude_runs
+        // would cascade into the tables referencing it, so the stored
+        // schema text is edited in place as the ALTER TABLE docs describe.
+        version: 50,
+        name: "revise action",
+        up: Some(
+            "PRAGMA writable_schema = ON;
+             UPDATE sqlite_master
+                SET sql = replace(sql, '''verify_distill''', '''verify_distill'', ''revise''')
+              WHERE type = 'table' AND name = 'claude_runs';
+             PRAGMA writable_schema = RESET;",
+        ),
+        down: Some(
+            "DELETE FROM claude_runs WHERE action = 'revise';
+             PRAGMA writable_schema = ON;
+             UPDATE sqlite_master
+                SET sql = replace(sql, '''verify_distill'', ''revise''', '''verify_distill''')
+              WHERE type = 'table' AND name = 'claude_runs';
+             PRAGMA writable_schema = RESET;",
+        ),
+    },
+    Migration {
+        version: 51,
+        name: "project fork owner",
+        up: Some("ALTER TABLE projects ADD COLUMN fork_owner TEXT;"),
+        down: Some("ALTER TABLE projects DROP COLUMN fork_owner;"),
+    },
+    Migration {
+        version: 52,
+        name: "project repo subpath",
+        up: Some("ALTER TABLE projects ADD COLUMN repo_subpath TEXT;"),
+        down: Some("ALTER TABLE projects DROP COLUMN repo_subpath;"),
+    },
+    Migration {
+        // IANA zone name of a run window; its current offset is looked up
+        // at claim time and takes the place of run_window_offset.
+        version: 53,
+        name: "run window time zones",
+        up: Some(
+            "ALTER TABLE projects ADD COLUMN run_window_tz TEXT;
+             ALTER TABLE claude_runs ADD COLUMN run_window_tz TEXT;",
+        ),
+        down: Some(
+            "ALTER TABLE projects DROP COLUMN run_window_tz;
+             ALTER TABLE claude_runs DROP COLUMN run_window_tz;",
+        ),
+    },
+];
+
+const fn legacy(version: i64, name: &'static str) -> Migration {
+    Migration {
+        version,
+        name,
+        up: None,
+        down: None,
+    }
+}
+
+/// Bring the schema up to the latest version.
 pub fn run(conn: &Connection) -> Result<(), DbError> {
+    let latest = MIGRATIONS.last().map_or(0, |m| m.version);
+    migrate_up(conn, latest)
+}
+
+/// Open the database at `path` without auto-migrating and move it to
+/// `target`, or only plan the move when `dry_run` is set.
+pub(crate) fn migrate_path(
+    path: &Path,
+    target: Option<i64>,
+    dry_run: bool,
+) -> Result<MigrationPlan, DbError> {
+    let conn = Connection::open(path).to_db()?;
+    conn.execute_batch(
+        "PRAGMA foreign_keys=ON;
+         PRAGMA busy_timeout=5000;",
+    )
+    .to_db()?;
+    let plan = migrate::plan("sqlite", MIGRATIONS, current_version(&conn), target)?;
+    if dry_run || plan.is_empty() {
+        return Ok(plan);
+    }
+    if plan.target > plan.current {
+        migrate_up(&conn, plan.target)?;
+    } else {
+        for step in &plan.steps {
+            let tx = conn.unchecked_transaction().to_db()?;
+            tx.execute_batch(step.sql.unwrap_or_default()).to_db()?;
+            tx.execute(
+                "DELETE FROM schema_version WHERE version = ?1",
+                [step.version],
+            )
+            .to_db()?;
+            tx.commit().to_db()?;
+        }
+    }
+    Ok(plan)
+}
+
+fn current_version(conn: &Connection) -> i64 {
+    conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM schema_version",
+        [],
+        |r| r.get(0),
+    )
+    .unwrap_or(0)
+}
+
+fn migrate_up(conn: &Connection, target: i64) -> Result<(), DbError> {
     // Original schema -- idempotent CREATE TABLE IF NOT EXISTS
     conn.execute_batch(
         "
@@ -162,15 +841,9 @@ pub fn run(conn: &Connection) -> Result<(), DbError> {
     )
     .to_db()?;
 
-    let current_version: i64 = conn
-        .query_row(
-            "SELECT COALESCE(MAX(version), 0) FROM schema_version",
-            [],
-            |r| r.get(0),
-        )
-        .unwrap_or(0);
+    let current_version = current_version(conn);
 
-    if current_version < 1 {
+    if current_version < 1 && target >= 1 {
         // v1: project repo_url, task new columns, task_links, claude_runs, attachments
         // Use a helper to check if column exists before ALTER TABLE
         let has_column = |table: &str, col: &str| -> bool {
@@ -241,7 +914,7 @@ pub fn run(conn: &Connection) -> Result<(), DbError> {
         .to_db()?;
     }
 
-    if current_version < 2 {
+    if current_version < 2 && target >= 2 {
         let has_column = |table: &str, col: &str| -> bool {
             conn.prepare(&format!("SELECT {col} FROM {table} LIMIT 0"))
                 .is_ok()
@@ -259,7 +932,7 @@ pub fn run(conn: &Connection) -> Result<(), DbError> {
         .to_db()?;
     }
 
-    if current_version < 3 {
+    if current_version < 3 && target >= 3 {
         let has_column = |table: &str, col: &str| -> bool {
             conn.prepare(&format!("SELECT {col} FROM {table} LIMIT 0"))
                 .is_ok()
@@ -279,7 +952,7 @@ pub fn run(conn: &Connection) -> Result<(), DbError> {
         .to_db()?;
     }
 
-    if current_version < 4 {
+    if current_version < 4 && target >= 4 {
         let has_column = |table: &str, col: &str| -> bool {
             conn.prepare(&format!("SELECT {col} FROM {table} LIMIT 0"))
                 .is_ok()
@@ -295,7 +968,7 @@ pub fn run(conn: &Connection) -> Result<(), DbError> {
         .to_db()?;
     }
 
-    if current_version < 5 {
+    if current_version < 5 && target >= 5 {
         let has_column = |table: &str, col: &str| -> bool {
             conn.prepare(&format!("SELECT {col} FROM {table} LIMIT 0"))
                 .is_ok()
@@ -311,7 +984,7 @@ pub fn run(conn: &Connection) -> Result<(), DbError> {
         .to_db()?;
     }
 
-    if current_version < 6 {
+    if current_version < 6 && target >= 6 {
         conn.execute_batch(
             "CREATE TABLE IF NOT EXISTS task_prs (
                 id            TEXT PRIMARY KEY,
@@ -333,7 +1006,7 @@ pub fn run(conn: &Connection) -> Result<(), DbError> {
         .to_db()?;
     }
 
-    if current_version < 7 {
+    if current_version < 7 && target >= 7 {
         // Rename disk_path -> store_key in attachments table
         let has_column = |table: &str, col: &str| -> bool {
             conn.prepare(&format!("SELECT {col} FROM {table} LIMIT 0"))
@@ -378,7 +1051,7 @@ pub fn run(conn: &Connection) -> Result<(), DbError> {
         .to_db()?;
     }
 
-    if current_version < 8 {
+    if current_version < 8 && target >= 8 {
         // v8: Five-phase workflow with review-distill support
 
         let has_column = |table: &str, col: &str| -> bool {
@@ -518,7 +1191,7 @@ pub fn run(conn: &Connection) -> Result<(), DbError> {
         .to_db()?;
     }
 
-    if current_version < 9 {
+    if current_version < 9 && target >= 9 {
         // v9: Salvage logic support -- add TimedOut/Salvaging status variants and runner_id column
 
         // Recreate claude_runs table with expanded status CHECK and runner_id column
@@ -567,7 +1240,7 @@ pub fn run(conn: &Connection) -> Result<(), DbError> {
         .to_db()?;
     }
 
-    if current_version < 10 {
+    if current_version < 10 && target >= 10 {
         // v10: Add required_capability column to claude_runs for capability-based routing.
         let has_column = |table: &str, col: &str| -> bool {
             conn.prepare(&format!("SELECT {col} FROM {table} LIMIT 0"))
@@ -584,7 +1257,7 @@ pub fn run(conn: &Connection) -> Result<(), DbError> {
         .to_db()?;
     }
 
-    if current_version < 11 {
+    if current_version < 11 && target >= 11 {
         // v11: Add provider_type and skip_tls_verify columns to projects
         let has_column = |table: &str, col: &str| -> bool {
             conn.prepare(&format!("SELECT {col} FROM {table} LIMIT 0"))
@@ -604,7 +1277,7 @@ pub fn run(conn: &Connection) -> Result<(), DbError> {
         .to_db()?;
     }
 
-    if current_version < 12 {
+    if current_version < 12 && target >= 12 {
         let has_column = |table: &str, col: &str| -> bool {
             conn.prepare(&format!("SELECT {col} FROM {table} LIMIT 0"))
                 .is_ok()
@@ -626,5 +1299,21 @@ pub fn run(conn: &Connection) -> Result<(), DbError> {
         .to_db()?;
     }
 
+    for m in MIGRATIONS
+        .iter()
+        .filter(|m| m.version > current_version && m.version <= target)
+    {
+        if let Some(up) = m.up {
+            let tx = conn.unchecked_transaction().to_db()?;
+            tx.execute_batch(up).to_db()?;
+            tx.execute(
+                "INSERT INTO schema_version (version, applied_at) VALUES (?1, datetime('now'))",
+                [m.version],
+            )
+            .to_db()?;
+            tx.commit().to_db()?;
+        }
+    }
+
     Ok(())
 }
@@ -6,6 +6,22 @@ use flowstate_core::api_key::ApiKey;
 use super::super::{SqliteDatabase, SqliteResultExt};
 use crate::DbError;
 
+/// Keys with their project scope gathered into a JSON array, like the other
+/// allowlists.
+const SELECT_API_KEYS: &str = "SELECT k.*,
+     (SELECT json_group_array(project_id ORDER BY project_id)
+      FROM api_key_projects WHERE api_key_id = k.id) AS allowed_projects
+     FROM api_keys k";
+
+/// An allowlist column. A corrupt one is an error, not an empty list, which
+/// would leave the key unrestricted.
+fn allowlist(row: &Row, column: &str) -> rusqlite::Result<Vec<String>> {
+    let json: String = row.get(column)?;
+    serde_json::from_str(&json).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
 fn row_to_api_key(row: &Row) -> rusqlite::Result<ApiKey> {
     Ok(ApiKey {
         id: row.get("id")?,
@@ -13,6 +29,10 @@ fn row_to_api_key(row: &Row) -> rusqlite::Result<ApiKey> {
         key_hash: row.get("key_hash")?,
         created_at: row.get("created_at")?,
         last_used_at: row.get("last_used_at")?,
+        allowed_cidrs: allowlist(row, "allowed_cidrs")?,
+        allowed_routes: allowlist(row, "allowed_routes")?,
+        allowed_projects: allowlist(row, "allowed_projects")?,
+        org_id: row.get("org_id")?,
     })
 }
 
@@ -27,7 +47,7 @@ impl SqliteDatabase {
             )
             .to_db()?;
             conn.query_row(
-                "SELECT * FROM api_keys WHERE id = ?1",
+                &format!("{SELECT_API_KEYS} WHERE k.id = ?1"),
                 params![id],
                 row_to_api_key,
             )
@@ -36,9 +56,9 @@ impl SqliteDatabase {
     }
 
     pub fn find_api_key_by_hash_sync(&self, key_hash: &str) -> Result<Option<ApiKey>, DbError> {
-        self.with_conn(|conn| {
+        self.with_read_conn(|conn| {
             let result = conn.query_row(
-                "SELECT * FROM api_keys WHERE key_hash = ?1",
+                &format!("{SELECT_API_KEYS} WHERE k.key_hash = ?1"),
                 params![key_hash],
                 row_to_api_key,
             );
@@ -63,7 +83,7 @@ impl SqliteDatabase {
     }
 
     pub fn has_api_keys_sync(&self) -> Result<bool, DbError> {
-        self.with_conn(|conn| {
+        self.with_read_conn(|conn| {
             let count: i64 = conn
                 .query_row("SELECT COUNT(*) FROM api_keys", [], |row| row.get(0))
                 .to_db()?;
@@ -72,9 +92,9 @@ impl SqliteDatabase {
     }
 
     pub fn list_api_keys_sync(&self) -> Result<Vec<ApiKey>, DbError> {
-        self.with_conn(|conn| {
+        self.with_read_conn(|conn| {
             let mut stmt = conn
-                .prepare("SELECT * FROM api_keys ORDER BY created_at DESC")
+                .prepare(&format!("{SELECT_API_KEYS} ORDER BY k.created_at DESC"))
                 .to_db()?;
             let keys = stmt
                 .query_map([], row_to_api_key)
@@ -85,6 +105,97 @@ impl SqliteDatabase {
         })
     }
 
+    pub fn set_api_key_policy_sync(
+        &self,
+        id: &str,
+        allowed_cidrs: &[String],
+        allowed_routes: &[String],
+    ) -> Result<ApiKey, DbError> {
+        let cidrs =
+            serde_json::to_string(allowed_cidrs).map_err(|e| DbError::Internal(e.to_string()))?;
+        let routes =
+            serde_json::to_string(allowed_routes).map_err(|e| DbError::Internal(e.to_string()))?;
+        self.with_conn(|conn| {
+            let changed = conn
+                .execute(
+                    "UPDATE api_keys SET allowed_cidrs = ?1, allowed_routes = ?2 WHERE id = ?3",
+                    params![cidrs, routes, id],
+                )
+                .to_db()?;
+            if changed == 0 {
+                return Err(DbError::NotFound(format!("api_key {id}")));
+            }
+            conn.query_row(
+                &format!("{SELECT_API_KEYS} WHERE k.id = ?1"),
+                params![id],
+                row_to_api_key,
+            )
+            .to_db()
+        })
+    }
+
+    pub fn set_api_key_projects_sync(
+        &self,
+        id: &str,
+        project_ids: &[String],
+    ) -> Result<ApiKey, DbError> {
+        self.with_conn(|conn| {
+            let tx = conn.unchecked_transaction().to_db()?;
+            let exists: i64 = tx
+                .query_row(
+                    "SELECT COUNT(*) FROM api_keys WHERE id = ?1",
+                    params![id],
+                    |row| row.get(0),
+                )
+                .to_db()?;
+            if exists == 0 {
+                return Err(DbError::NotFound(format!("api_key {id}")));
+            }
+            tx.execute(
+                "DELETE FROM api_key_projects WHERE api_key_id = ?1",
+                params![id],
+            )
+            .to_db()?;
+            for project_id in project_ids {
+                tx.execute(
+                    "INSERT OR IGNORE INTO api_key_projects (api_key_id, project_id)
+                     VALUES (?1, ?2)",
+                    params![id, project_id],
+                )
+                .to_db()?;
+            }
+            let key = tx
+                .query_row(
+                    &format!("{SELECT_API_KEYS} WHERE k.id = ?1"),
+                    params![id],
+                    row_to_api_key,
+                )
+                .to_db()?;
+            tx.commit().to_db()?;
+            Ok(key)
+        })
+    }
+
+    pub fn set_api_key_org_sync(&self, id: &str, org_id: Option<&str>) -> Result<ApiKey, DbError> {
+        self.with_conn(|conn| {
+            let changed = conn
+                .execute(
+                    "UPDATE api_keys SET org_id = ?1 WHERE id = ?2",
+                    params![org_id, id],
+                )
+                .to_db()?;
+            if changed == 0 {
+                return Err(DbError::NotFound(format!("api_key {id}")));
+            }
+            conn.query_row(
+                &format!("{SELECT_API_KEYS} WHERE k.id = ?1"),
+                params![id],
+                row_to_api_key,
+            )
+            .to_db()
+        })
+    }
+
     pub fn delete_api_key_sync(&self, id: &str) -> Result<(), DbError> {
         self.with_conn(|conn| {
             let changed = conn
@@ -137,4 +248,22 @@ mod tests {
         db.delete_api_key_sync(&key.id).unwrap();
         assert!(!db.has_api_keys_sync().unwrap());
     }
+
+    #[test]
+    fn corrupt_allowlist_rejects_the_key() {
+        let db = Db::open_in_memory().unwrap();
+        for column in ["allowed_cidrs", "allowed_routes"] {
+            let key = db.insert_api_key_sync(column, column).unwrap();
+            db.with_conn(|conn| {
+                conn.execute(
+                    &format!("UPDATE api_keys SET {column} = 'not json' WHERE id = ?1"),
+                    [&key.id],
+                )
+                .unwrap();
+                Ok(())
+            })
+            .unwrap();
+            assert!(db.find_api_key_by_hash_sync(column).is_err());
+        }
+    }
 }
@@ -0,0 +1,77 @@
+use chrono::Utc;
+use rusqlite::{params, Row};
+
+use flowstate_core::approval_rule::{ApprovalPhase, ApprovalPolicy, ApprovalRule};
+
+use super::super::{SqliteDatabase, SqliteResultExt};
+use crate::DbError;
+
+pub(crate) fn row_to_approval_rule(row: &Row) -> rusqlite::Result<ApprovalRule> {
+    let phase: String = row.get("phase")?;
+    let policy: String = row.get("policy")?;
+    Ok(ApprovalRule {
+        project_id: row.get("project_id")?,
+        phase: ApprovalPhase::parse_str(&phase).unwrap_or(ApprovalPhase::Research),
+        policy: ApprovalPolicy::parse_str(&policy).unwrap_or(ApprovalPolicy::Human),
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+impl SqliteDatabase {
+    pub fn list_approval_rules_sync(&self, project_id: &str) -> Result<Vec<ApprovalRule>, DbError> {
+        self.with_read_conn(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT * FROM approval_rules WHERE project_id = ?1 ORDER BY phase")
+                .to_db()?;
+            let rules = stmt
+                .query_map(params![project_id], row_to_approval_rule)
+                .to_db()?
+                .collect::<Result<Vec<_>, _>>()
+                .to_db()?;
+            Ok(rules)
+        })
+    }
+
+    pub fn set_approval_rule_sync(
+        &self,
+        project_id: &str,
+        phase: ApprovalPhase,
+        policy: ApprovalPolicy,
+    ) -> Result<ApprovalRule, DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO approval_rules (project_id, phase, policy, updated_at)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (project_id, phase)
+                 DO UPDATE SET policy = excluded.policy, updated_at = excluded.updated_at",
+                params![project_id, phase.as_str(), policy.as_str(), Utc::now()],
+            )
+            .to_db()?;
+            conn.query_row(
+                "SELECT * FROM approval_rules WHERE project_id = ?1 AND phase = ?2",
+                params![project_id, phase.as_str()],
+                row_to_approval_rule,
+            )
+            .to_db()
+        })
+    }
+
+    pub fn delete_approval_rule_sync(
+        &self,
+        project_id: &str,
+        phase: ApprovalPhase,
+    ) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            let changed = conn
+                .execute(
+                    "DELETE FROM approval_rules WHERE project_id = ?1 AND phase = ?2",
+                    params![project_id, phase.as_str()],
+                )
+                .to_db()?;
+            if changed == 0 {
+                return Err(DbError::NotFound(format!("approval_rule {phase}")));
+            }
+            Ok(())
+        })
+    }
+}
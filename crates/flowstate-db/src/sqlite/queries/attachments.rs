@@ -6,13 +6,15 @@ use flowstate_core::attachment::Attachment;
 use super::super::{SqliteDatabase, SqliteResultExt};
 use crate::DbError;
 
-fn row_to_attachment(row: &Row) -> rusqlite::Result<Attachment> {
+pub(crate) fn row_to_attachment(row: &Row) -> rusqlite::Result<Attachment> {
     Ok(Attachment {
         id: row.get("id")?,
         task_id: row.get("task_id")?,
         filename: row.get("filename")?,
         store_key: row.get("store_key")?,
         size_bytes: row.get("size_bytes")?,
+        content_type: row.get("content_type")?,
+        sha256: row.get("sha256")?,
         created_at: row.get("created_at")?,
     })
 }
@@ -24,14 +26,17 @@ impl SqliteDatabase {
         filename: &str,
         store_key: &str,
         size_bytes: i64,
+        content_type: &str,
+        sha256: &str,
     ) -> Result<Attachment, DbError> {
         self.with_conn(|conn| {
             let id = uuid::Uuid::new_v4().to_string();
             let now = Utc::now();
             conn.execute(
-                "INSERT INTO attachments (id, task_id, filename, store_key, size_bytes, created_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-                params![id, task_id, filename, store_key, size_bytes, now],
+                "INSERT INTO attachments
+                     (id, task_id, filename, store_key, size_bytes, content_type, sha256, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![id, task_id, filename, store_key, size_bytes, content_type, sha256, now],
             )
             .to_db()?;
             conn.query_row(
@@ -44,7 +49,7 @@ impl SqliteDatabase {
     }
 
     pub fn list_attachments_sync(&self, task_id: &str) -> Result<Vec<Attachment>, DbError> {
-        self.with_conn(|conn| {
+        self.with_read_conn(|conn| {
             let mut stmt = conn
                 .prepare("SELECT * FROM attachments WHERE task_id = ?1 ORDER BY created_at DESC")
                 .to_db()?;
@@ -58,7 +63,7 @@ impl SqliteDatabase {
     }
 
     pub fn get_attachment_sync(&self, id: &str) -> Result<Attachment, DbError> {
-        self.with_conn(|conn| {
+        self.with_read_conn(|conn| {
             conn.query_row(
                 "SELECT * FROM attachments WHERE id = ?1",
                 params![id],
@@ -92,4 +97,15 @@ impl SqliteDatabase {
             Ok(attachment)
         })
     }
+
+    pub fn count_attachment_refs_sync(&self, store_key: &str) -> Result<i64, DbError> {
+        self.with_read_conn(|conn| {
+            conn.query_row(
+                "SELECT COUNT(*) FROM attachments WHERE store_key = ?1",
+                params![store_key],
+                |row| row.get(0),
+            )
+            .to_db()
+        })
+    }
 }
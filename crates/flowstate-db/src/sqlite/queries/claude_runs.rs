@@ -1,12 +1,35 @@
 use chrono::{DateTime, Utc};
-use rusqlite::{params, Row};
+use rusqlite::{params, params_from_iter, OptionalExtension, Row};
 
 use flowstate_core::claude_run::{ClaudeAction, ClaudeRun, ClaudeRunStatus, CreateClaudeRun};
 
 use super::super::{SqliteDatabase, SqliteResultExt};
-use crate::DbError;
-
-fn row_to_claude_run(row: &Row) -> rusqlite::Result<ClaudeRun> {
+use crate::query::{Dialect, SelectQuery};
+use crate::{DbError, QueueDemand};
+
+/// Claim filter: skip runs whose project is already at its
+/// `max_concurrent_runs` cap.
+const UNDER_PROJECT_CAP: &str = "NOT EXISTS (
+    SELECT 1 FROM tasks t JOIN projects p ON p.id = t.project_id
+    WHERE t.id = claude_runs.task_id
+      AND p.max_concurrent_runs IS NOT NULL
+      AND p.max_concurrent_runs <= (
+          SELECT COUNT(*) FROM claude_runs r JOIN tasks rt ON rt.id = r.task_id
+          WHERE rt.project_id = p.id AND r.status = 'running'
+      )
+)";
+
+/// Claim ordering key: the run's project's running runs per unit of
+/// `claim_weight`, so equal-priority work is shared fairly across projects.
+const PROJECT_SHARE: &str = "(
+    SELECT CAST(COUNT(r.id) AS REAL) / MAX(p.claim_weight)
+    FROM tasks t JOIN projects p ON p.id = t.project_id
+    LEFT JOIN tasks rt ON rt.project_id = p.id
+    LEFT JOIN claude_runs r ON r.task_id = rt.id AND r.status = 'running'
+    WHERE t.id = claude_runs.task_id
+)";
+
+pub(crate) fn row_to_claude_run(row: &Row) -> rusqlite::Result<ClaudeRun> {
     let action_str: String = row.get("action")?;
     let status_str: String = row.get("status")?;
     Ok(ClaudeRun {
@@ -24,6 +47,16 @@ fn row_to_claude_run(row: &Row) -> rusqlite::Result<ClaudeRun> {
         started_at: row.get("started_at")?,
         finished_at: row.get("finished_at")?,
         required_capability: row.get("required_capability").unwrap_or(None),
+        priority: row.get("priority")?,
+        feedback: row.get("feedback")?,
+        idempotency_key: row.get("idempotency_key")?,
+        run_window: crate::run_window_from_columns(
+            row.get("run_window_start")?,
+            row.get("run_window_end")?,
+            row.get("run_window_offset")?,
+            row.get("run_window_tz")?,
+        ),
+        trace_context: row.get("trace_context")?,
     })
 }
 
@@ -32,23 +65,51 @@ impl SqliteDatabase {
         self.with_conn(|conn| {
             let id = uuid::Uuid::new_v4().to_string();
             let now = Utc::now();
+            // A key already used on this task leaves the insert a no-op, and
+            // the lookup below returns the earlier run.
             conn.execute(
-                "INSERT INTO claude_runs (id, task_id, action, status, started_at, required_capability)
-                 VALUES (?1, ?2, ?3, 'queued', ?4, ?5)",
-                params![id, input.task_id, input.action.as_str(), now, input.required_capability],
+                "INSERT INTO claude_runs (
+                     id, task_id, action, status, started_at, required_capability, priority,
+                     feedback, idempotency_key, run_window_start, run_window_end,
+                     run_window_offset, run_window_tz, trace_context
+                 ) VALUES (?1, ?2, ?3, 'queued', ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+                 ON CONFLICT (task_id, idempotency_key) WHERE idempotency_key IS NOT NULL
+                 DO NOTHING",
+                params![
+                    id,
+                    input.task_id,
+                    input.action.as_str(),
+                    now,
+                    input.required_capability,
+                    input.priority,
+                    input.feedback,
+                    input.idempotency_key,
+                    input.run_window.map(|w| w.start),
+                    input.run_window.map(|w| w.end),
+                    input.run_window.map(|w| w.utc_offset),
+                    input.run_window.and_then(|w| w.time_zone_name()),
+                    input.trace_context,
+                ],
             )
             .to_db()?;
-            conn.query_row(
-                "SELECT * FROM claude_runs WHERE id = ?1",
-                params![id],
-                row_to_claude_run,
-            )
+            match input.idempotency_key {
+                Some(ref key) => conn.query_row(
+                    "SELECT * FROM claude_runs WHERE task_id = ?1 AND idempotency_key = ?2",
+                    params![input.task_id, key],
+                    row_to_claude_run,
+                ),
+                None => conn.query_row(
+                    "SELECT * FROM claude_runs WHERE id = ?1",
+                    params![id],
+                    row_to_claude_run,
+                ),
+            }
             .map_err(|e| DbError::Internal(e.to_string()))
         })
     }
 
     pub fn get_claude_run_sync(&self, id: &str) -> Result<ClaudeRun, DbError> {
-        self.with_conn(|conn| {
+        self.with_read_conn(|conn| {
             conn.query_row(
                 "SELECT * FROM claude_runs WHERE id = ?1",
                 params![id],
@@ -64,7 +125,7 @@ impl SqliteDatabase {
     }
 
     pub fn list_claude_runs_for_task_sync(&self, task_id: &str) -> Result<Vec<ClaudeRun>, DbError> {
-        self.with_conn(|conn| {
+        self.with_read_conn(|conn| {
             let mut stmt = conn
                 .prepare("SELECT * FROM claude_runs WHERE task_id = ?1 ORDER BY started_at DESC")
                 .to_db()?;
@@ -114,7 +175,11 @@ impl SqliteDatabase {
         })
     }
 
-    /// Atomically claim the oldest queued run, setting it to Running.
+    /// Atomically claim a queued run, setting it to Running. Runs are taken
+    /// highest priority first; ties go to the project with the smallest
+    /// running share (see `PROJECT_SHARE`), then to the oldest run. Runs
+    /// whose project is at its `max_concurrent_runs` cap, or outside their
+    /// run window, are skipped.
     /// If `capabilities` is non-empty, only claim runs whose `required_capability`
     /// is NULL or matches one of the given values.
     /// Returns None if no matching queued runs exist.
@@ -122,69 +187,64 @@ impl SqliteDatabase {
         &self,
         capabilities: &[&str],
     ) -> Result<Option<ClaudeRun>, DbError> {
-        self.with_conn(|conn| {
-            let now = Utc::now();
-
-            if capabilities.is_empty() {
-                // No capability filter — claim any queued run
-                let result = conn.query_row(
-                    "UPDATE claude_runs
-                     SET status = 'running', started_at = ?1
-                     WHERE id = (
-                         SELECT id FROM claude_runs
-                         WHERE status = 'queued'
-                         ORDER BY started_at ASC
-                         LIMIT 1
-                     )
-                     RETURNING *",
-                    params![now],
-                    row_to_claude_run,
-                );
-
-                match result {
-                    Ok(run) => Ok(Some(run)),
-                    Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-                    Err(e) => Err(DbError::Internal(e.to_string())),
-                }
-            } else {
-                // Build dynamic IN clause for capabilities
-                let placeholders: Vec<String> = capabilities
-                    .iter()
-                    .enumerate()
-                    .map(|(i, _)| format!("?{}", i + 2))
-                    .collect();
-                let in_clause = placeholders.join(", ");
-
-                let sql = format!(
-                    "UPDATE claude_runs
-                     SET status = 'running', started_at = ?1
-                     WHERE id = (
-                         SELECT id FROM claude_runs
-                         WHERE status = 'queued'
-                           AND (required_capability IS NULL OR required_capability IN ({in_clause}))
-                         ORDER BY started_at ASC
-                         LIMIT 1
-                     )
-                     RETURNING *"
-                );
-
-                let mut stmt = conn.prepare(&sql).to_db()?;
-                let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
-                param_values.push(Box::new(now.to_rfc3339()));
-                for cap in capabilities {
-                    param_values.push(Box::new(cap.to_string()));
-                }
-                let param_refs: Vec<&dyn rusqlite::types::ToSql> =
-                    param_values.iter().map(|b| b.as_ref()).collect();
+        self.claim_next_claude_run_except_sync(capabilities, &[])
+    }
 
-                let result = stmt.query_row(param_refs.as_slice(), row_to_claude_run);
+    /// [`claim_next_claude_run_sync`](Self::claim_next_claude_run_sync),
+    /// leaving runs of the `skip` actions queued.
+    pub fn claim_next_claude_run_except_sync(
+        &self,
+        capabilities: &[&str],
+        skip: &[ClaudeAction],
+    ) -> Result<Option<ClaudeRun>, DbError> {
+        self.claim_next_claude_run_scoped_sync(capabilities, skip, &[])
+    }
 
-                match result {
-                    Ok(run) => Ok(Some(run)),
-                    Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-                    Err(e) => Err(DbError::Internal(e.to_string())),
-                }
+    /// [`claim_next_claude_run_except_sync`](Self::claim_next_claude_run_except_sync),
+    /// only claiming runs for tasks in `projects` (any project when empty).
+    pub fn claim_next_claude_run_scoped_sync(
+        &self,
+        capabilities: &[&str],
+        skip: &[ClaudeAction],
+        projects: &[String],
+    ) -> Result<Option<ClaudeRun>, DbError> {
+        self.with_conn(|conn| {
+            let now = Utc::now();
+            let zones = conn
+                .prepare(crate::RUN_WINDOW_ZONES_SQL)
+                .to_db()?
+                .query_map([], |row| row.get::<_, String>(0))
+                .to_db()?
+                .collect::<Result<Vec<_>, _>>()
+                .to_db()?;
+            let mut q = SelectQuery::new(Dialect::Sqlite, "SELECT id FROM claude_runs");
+            let started_at = q.bind(now);
+            let window = crate::in_run_window_sql(&mut q, now, &zones);
+            q.and("status = 'queued'");
+            if !capabilities.is_empty() {
+                let caps: Vec<String> = capabilities.iter().map(|c| q.bind(*c)).collect();
+                q.and(&format!(
+                    "(required_capability IS NULL OR required_capability IN ({}))",
+                    caps.join(", ")
+                ));
             }
+            q.and(UNDER_PROJECT_CAP)
+                .and(&window)
+                .and(&crate::skip_actions_sql(skip))
+                .and_in(crate::RUN_PROJECT_SQL, projects)
+                .push(&format!(
+                    "ORDER BY priority DESC, {PROJECT_SHARE} ASC, started_at ASC LIMIT 1"
+                ));
+
+            let sql = format!(
+                "UPDATE claude_runs SET status = 'running', started_at = {started_at}
+                 WHERE id = ({})
+                 RETURNING *",
+                q.sql()
+            );
+            conn.query_row(&sql, params_from_iter(q.values()), row_to_claude_run)
+                .optional()
+                .to_db()
         })
     }
 
@@ -231,7 +291,7 @@ impl SqliteDatabase {
         &self,
         older_than: DateTime<Utc>,
     ) -> Result<Vec<ClaudeRun>, DbError> {
-        self.with_conn(|conn| {
+        self.with_read_conn(|conn| {
             let mut stmt = conn
                 .prepare("SELECT * FROM claude_runs WHERE status = 'running' AND started_at < ?1")
                 .to_db()?;
@@ -250,7 +310,7 @@ impl SqliteDatabase {
         &self,
         older_than: DateTime<Utc>,
     ) -> Result<Vec<ClaudeRun>, DbError> {
-        self.with_conn(|conn| {
+        self.with_read_conn(|conn| {
             let mut stmt = conn
                 .prepare("SELECT * FROM claude_runs WHERE status = 'salvaging' AND started_at < ?1")
                 .to_db()?;
@@ -294,9 +354,67 @@ impl SqliteDatabase {
         })
     }
 
+    /// Atomically move a failed, timed-out or cancelled run back to Queued.
+    /// Returns Ok(None) if the run was not in those statuses.
+    pub fn requeue_claude_run_sync(&self, id: &str) -> Result<Option<ClaudeRun>, DbError> {
+        self.with_conn(|conn| {
+            let affected = conn
+                .execute(
+                    "UPDATE claude_runs
+                     SET status = 'queued', error_message = NULL, exit_code = NULL,
+                         progress_message = NULL, runner_id = NULL, finished_at = NULL,
+                         started_at = ?1
+                     WHERE id = ?2 AND status IN ('failed', 'timed_out', 'cancelled')",
+                    params![Utc::now(), id],
+                )
+                .to_db()?;
+
+            if affected == 0 {
+                return Ok(None);
+            }
+
+            conn.query_row(
+                "SELECT * FROM claude_runs WHERE id = ?1",
+                params![id],
+                row_to_claude_run,
+            )
+            .map(Some)
+            .map_err(|e| DbError::Internal(e.to_string()))
+        })
+    }
+
+    /// Set the priority of a queued run. Returns Ok(None) if the run is not
+    /// queued.
+    pub fn set_claude_run_priority_sync(
+        &self,
+        id: &str,
+        priority: i32,
+    ) -> Result<Option<ClaudeRun>, DbError> {
+        self.with_conn(|conn| {
+            let affected = conn
+                .execute(
+                    "UPDATE claude_runs SET priority = ?1 WHERE id = ?2 AND status = 'queued'",
+                    params![priority, id],
+                )
+                .to_db()?;
+
+            if affected == 0 {
+                return Ok(None);
+            }
+
+            conn.query_row(
+                "SELECT * FROM claude_runs WHERE id = ?1",
+                params![id],
+                row_to_claude_run,
+            )
+            .map(Some)
+            .map_err(|e| DbError::Internal(e.to_string()))
+        })
+    }
+
     /// Count runs in queued status.
     pub fn count_queued_runs_sync(&self) -> Result<i64, DbError> {
-        self.with_conn(|conn| {
+        self.with_read_conn(|conn| {
             conn.query_row(
                 "SELECT COUNT(*) FROM claude_runs WHERE status = 'queued'",
                 [],
@@ -306,6 +424,76 @@ impl SqliteDatabase {
         })
     }
 
+    pub fn queued_run_demand_sync(&self) -> Result<Vec<QueueDemand>, DbError> {
+        self.with_read_conn(|conn| {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT required_capability, COUNT(*), MIN(started_at)
+                     FROM claude_runs WHERE status = 'queued'
+                     GROUP BY required_capability
+                     ORDER BY COUNT(*) DESC, required_capability",
+                )
+                .to_db()?;
+            let demand = stmt
+                .query_map([], |row| {
+                    Ok(QueueDemand {
+                        capability: row.get(0)?,
+                        queued: row.get(1)?,
+                        oldest_queued_at: row.get(2)?,
+                    })
+                })
+                .to_db()?
+                .collect::<Result<Vec<_>, _>>()
+                .to_db()?;
+            Ok(demand)
+        })
+    }
+
+    /// Count runs finished since `since`, as `(finished, failed)`.
+    pub fn count_finished_runs_sync(&self, since: DateTime<Utc>) -> Result<(i64, i64), DbError> {
+        self.with_read_conn(|conn| {
+            conn.query_row(
+                "SELECT COUNT(*),
+                        COALESCE(SUM(CASE WHEN status IN ('failed', 'timed_out') THEN 1 ELSE 0 END), 0)
+                 FROM claude_runs
+                 WHERE status IN ('completed', 'failed', 'timed_out') AND finished_at >= ?1",
+                params![since],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|e| DbError::Internal(e.to_string()))
+        })
+    }
+
+    pub fn delete_runs_older_than_sync(
+        &self,
+        cutoff: DateTime<Utc>,
+    ) -> Result<Vec<String>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn
+                .prepare(
+                    "DELETE FROM claude_runs
+                     WHERE status IN ('completed', 'failed', 'cancelled', 'timed_out')
+                       AND COALESCE(finished_at, started_at) < ?1
+                       AND EXISTS (
+                           SELECT 1 FROM claude_runs newer
+                           WHERE newer.task_id = claude_runs.task_id
+                             AND newer.action = claude_runs.action
+                             AND (newer.started_at > claude_runs.started_at
+                                  OR (newer.started_at = claude_runs.started_at
+                                      AND newer.id > claude_runs.id))
+                       )
+                     RETURNING id",
+                )
+                .to_db()?;
+            let ids = stmt
+                .query_map(params![cutoff], |row| row.get(0))
+                .to_db()?
+                .collect::<Result<Vec<String>, _>>()
+                .to_db()?;
+            Ok(ids)
+        })
+    }
+
     /// Set runner_id on a claude run (at claim time).
     pub fn set_claude_run_runner_sync(&self, id: &str, runner_id: &str) -> Result<(), DbError> {
         self.with_conn(|conn| {
@@ -324,7 +512,7 @@ mod tests {
     use crate::Db;
     use flowstate_core::claude_run::{ClaudeAction, ClaudeRunStatus, CreateClaudeRun};
     use flowstate_core::project::CreateProject;
-    use flowstate_core::task::{CreateTask, Priority, Status};
+    use flowstate_core::task::{CreateTask, Priority, Status, TaskType};
 
     fn setup() -> (Db, String) {
         let db = Db::open_in_memory().unwrap();
@@ -343,6 +531,7 @@ mod tests {
                 description: String::new(),
                 status: Status::Todo,
                 priority: Priority::Medium,
+                task_type: TaskType::Feature,
                 parent_id: None,
                 reviewer: String::new(),
                 research_capability: None,
@@ -350,6 +539,7 @@ mod tests {
                 plan_capability: None,
                 build_capability: None,
                 verify_capability: None,
+                due_at: None,
             })
             .unwrap();
         (db, task.id)
@@ -364,6 +554,11 @@ mod tests {
                 task_id: task_id.clone(),
                 action: ClaudeAction::Design,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .unwrap();
         assert_eq!(run.status, ClaudeRunStatus::Queued);
@@ -399,6 +594,11 @@ mod tests {
                 task_id: task_id.clone(),
                 action: ClaudeAction::Design,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .unwrap();
         let _run2 = db
@@ -406,6 +606,11 @@ mod tests {
                 task_id: task_id.clone(),
                 action: ClaudeAction::Plan,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .unwrap();
 
@@ -431,6 +636,11 @@ mod tests {
                 task_id,
                 action: ClaudeAction::Build,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .unwrap();
 
@@ -461,6 +671,11 @@ mod tests {
                 task_id: task_id.clone(),
                 action: ClaudeAction::Build,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .unwrap();
         let updated = db
@@ -481,6 +696,11 @@ mod tests {
                 task_id: task_id.clone(),
                 action: ClaudeAction::Build,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .unwrap();
         let _ = db.claim_next_claude_run_sync(&[]).unwrap(); // claim run2 to set it Running
@@ -501,6 +721,11 @@ mod tests {
                 task_id: task_id.clone(),
                 action: ClaudeAction::Build,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .unwrap();
         let _claimed = db.claim_next_claude_run_sync(&[]).unwrap().unwrap();
@@ -532,6 +757,11 @@ mod tests {
                 task_id: task_id.clone(),
                 action: ClaudeAction::Build,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .unwrap();
 
@@ -572,6 +802,11 @@ mod tests {
                 task_id: task_id.clone(),
                 action: ClaudeAction::Research,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .unwrap();
         }
@@ -601,6 +836,11 @@ mod tests {
                 task_id,
                 action: ClaudeAction::Build,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .unwrap();
         assert!(run.runner_id.is_none());
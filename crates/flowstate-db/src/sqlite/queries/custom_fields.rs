@@ -0,0 +1,216 @@
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde_json::Value;
+
+use flowstate_core::custom_field::{
+    CreateCustomField, CustomField, CustomFieldType, TaskFieldValue, UpdateCustomField,
+};
+use flowstate_core::task_revision::FieldChange;
+
+use super::super::{SqliteDatabase, SqliteResultExt};
+use crate::DbError;
+
+pub(crate) fn row_to_custom_field(row: &Row) -> rusqlite::Result<CustomField> {
+    let field_type: String = row.get("field_type")?;
+    let options: String = row.get("options")?;
+    Ok(CustomField {
+        id: row.get("id")?,
+        project_id: row.get("project_id")?,
+        name: row.get("name")?,
+        field_type: CustomFieldType::parse_str(&field_type).unwrap_or(CustomFieldType::Text),
+        options: serde_json::from_str(&options).unwrap_or_default(),
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+pub(crate) fn row_to_task_field_value(row: &Row) -> rusqlite::Result<TaskFieldValue> {
+    Ok(TaskFieldValue {
+        task_id: row.get("task_id")?,
+        field_id: row.get("field_id")?,
+        value: row.get("value")?,
+    })
+}
+
+/// Write `values` (field id → value, `None` clears) for a task of
+/// `project_id`, returning one revision change per value that moved.
+pub(crate) fn set_task_field_values_in(
+    conn: &Connection,
+    task_id: &str,
+    project_id: &str,
+    values: &BTreeMap<String, Option<String>>,
+) -> Result<Vec<FieldChange>, DbError> {
+    let mut changes = Vec::new();
+    for (field_id, value) in values {
+        let name: String = conn
+            .query_row(
+                "SELECT name FROM custom_fields WHERE id = ?1 AND project_id = ?2",
+                params![field_id, project_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .to_db()?
+            .ok_or_else(|| DbError::NotFound(format!("custom field {field_id}")))?;
+        let old: Option<String> = conn
+            .query_row(
+                "SELECT value FROM task_field_values WHERE task_id = ?1 AND field_id = ?2",
+                params![task_id, field_id],
+                |row| row.get(0),
+            )
+            .optional()
+            .to_db()?;
+        if old == *value {
+            continue;
+        }
+        match value {
+            Some(value) => conn.execute(
+                "INSERT INTO task_field_values (task_id, field_id, value) VALUES (?1, ?2, ?3)
+                 ON CONFLICT(task_id, field_id) DO UPDATE SET value = excluded.value",
+                params![task_id, field_id, value],
+            ),
+            None => conn.execute(
+                "DELETE FROM task_field_values WHERE task_id = ?1 AND field_id = ?2",
+                params![task_id, field_id],
+            ),
+        }
+        .to_db()?;
+        changes.push(FieldChange {
+            field: format!("custom_fields.{name}"),
+            old: old.map_or(Value::Null, Value::String),
+            new: value.clone().map_or(Value::Null, Value::String),
+        });
+    }
+    Ok(changes)
+}
+
+impl SqliteDatabase {
+    pub fn create_custom_field_sync(
+        &self,
+        input: &CreateCustomField,
+    ) -> Result<CustomField, DbError> {
+        self.with_conn(|conn| {
+            let id = uuid::Uuid::new_v4().to_string();
+            let now = Utc::now();
+            let options = serde_json::to_string(&input.options)
+                .map_err(|e| DbError::Internal(e.to_string()))?;
+            conn.execute(
+                "INSERT INTO custom_fields (id, project_id, name, field_type, options, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![id, input.project_id, input.name, input.field_type.as_str(), options, now, now],
+            )
+            .to_db()?;
+            conn.query_row(
+                "SELECT * FROM custom_fields WHERE id = ?1",
+                params![id],
+                row_to_custom_field,
+            )
+            .to_db()
+        })
+    }
+
+    pub fn get_custom_field_sync(&self, id: &str) -> Result<CustomField, DbError> {
+        self.with_read_conn(|conn| {
+            conn.query_row(
+                "SELECT * FROM custom_fields WHERE id = ?1",
+                params![id],
+                row_to_custom_field,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    DbError::NotFound(format!("custom field {id}"))
+                }
+                other => DbError::Internal(other.to_string()),
+            })
+        })
+    }
+
+    pub fn list_custom_fields_sync(&self, project_id: &str) -> Result<Vec<CustomField>, DbError> {
+        self.with_read_conn(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT * FROM custom_fields WHERE project_id = ?1 ORDER BY name")
+                .to_db()?;
+            let fields = stmt
+                .query_map(params![project_id], row_to_custom_field)
+                .to_db()?
+                .collect::<Result<Vec<_>, _>>()
+                .to_db()?;
+            Ok(fields)
+        })
+    }
+
+    pub fn update_custom_field_sync(
+        &self,
+        id: &str,
+        update: &UpdateCustomField,
+    ) -> Result<CustomField, DbError> {
+        self.with_conn(|conn| {
+            let mut sets = Vec::new();
+            let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+            if let Some(ref name) = update.name {
+                sets.push("name = ?");
+                values.push(Box::new(name.clone()));
+            }
+            if let Some(ref options) = update.options {
+                let options =
+                    serde_json::to_string(options).map_err(|e| DbError::Internal(e.to_string()))?;
+                sets.push("options = ?");
+                values.push(Box::new(options));
+            }
+
+            if !sets.is_empty() {
+                sets.push("updated_at = ?");
+                values.push(Box::new(Utc::now()));
+                values.push(Box::new(id.to_string()));
+
+                let sql = format!("UPDATE custom_fields SET {} WHERE id = ?", sets.join(", "));
+                let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+                conn.execute(&sql, params.as_slice()).to_db()?;
+            }
+
+            conn.query_row(
+                "SELECT * FROM custom_fields WHERE id = ?1",
+                params![id],
+                row_to_custom_field,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    DbError::NotFound(format!("custom field {id}"))
+                }
+                other => DbError::Internal(other.to_string()),
+            })
+        })
+    }
+
+    /// Delete a field; its values go with it via `ON DELETE CASCADE`.
+    pub fn delete_custom_field_sync(&self, id: &str) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            let changed = conn
+                .execute("DELETE FROM custom_fields WHERE id = ?1", params![id])
+                .to_db()?;
+            if changed == 0 {
+                return Err(DbError::NotFound(format!("custom field {id}")));
+            }
+            Ok(())
+        })
+    }
+
+    pub fn list_task_field_values_sync(
+        &self,
+        task_id: &str,
+    ) -> Result<Vec<TaskFieldValue>, DbError> {
+        self.with_read_conn(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT * FROM task_field_values WHERE task_id = ?1 ORDER BY field_id")
+                .to_db()?;
+            let values = stmt
+                .query_map(params![task_id], row_to_task_field_value)
+                .to_db()?
+                .collect::<Result<Vec<_>, _>>()
+                .to_db()?;
+            Ok(values)
+        })
+    }
+}
@@ -0,0 +1,144 @@
+use chrono::Utc;
+use rusqlite::{params, Row};
+
+use flowstate_core::epic::{CreateEpic, Epic, EpicStatus, UpdateEpic};
+
+use super::super::{SqliteDatabase, SqliteResultExt};
+use crate::DbError;
+
+pub(crate) fn row_to_epic(row: &Row) -> rusqlite::Result<Epic> {
+    let status_str: String = row.get("status")?;
+    Ok(Epic {
+        id: row.get("id")?,
+        project_id: row.get("project_id")?,
+        name: row.get("name")?,
+        description: row.get("description")?,
+        status: EpicStatus::parse_str(&status_str).unwrap_or(EpicStatus::Open),
+        target_date: row.get("target_date")?,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+impl SqliteDatabase {
+    pub fn create_epic_sync(&self, input: &CreateEpic) -> Result<Epic, DbError> {
+        self.with_conn(|conn| {
+            let id = uuid::Uuid::new_v4().to_string();
+            let now = Utc::now();
+            conn.execute(
+                "INSERT INTO epics (id, project_id, name, description, status, target_date, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![id, input.project_id, input.name, input.description, "open", input.target_date, now, now],
+            )
+            .to_db()?;
+            conn.query_row("SELECT * FROM epics WHERE id = ?1", params![id], row_to_epic)
+                .to_db()
+        })
+    }
+
+    pub fn get_epic_sync(&self, id: &str) -> Result<Epic, DbError> {
+        self.with_read_conn(|conn| {
+            conn.query_row(
+                "SELECT * FROM epics WHERE id = ?1",
+                params![id],
+                row_to_epic,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => DbError::NotFound(format!("epic {id}")),
+                other => DbError::Internal(other.to_string()),
+            })
+        })
+    }
+
+    pub fn list_epics_sync(&self, project_id: &str) -> Result<Vec<Epic>, DbError> {
+        self.with_read_conn(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT * FROM epics WHERE project_id = ?1 ORDER BY created_at DESC")
+                .to_db()?;
+            let epics = stmt
+                .query_map(params![project_id], row_to_epic)
+                .to_db()?
+                .collect::<Result<Vec<_>, _>>()
+                .to_db()?;
+            Ok(epics)
+        })
+    }
+
+    pub fn update_epic_sync(&self, id: &str, update: &UpdateEpic) -> Result<Epic, DbError> {
+        self.with_conn(|conn| {
+            let mut sets = Vec::new();
+            let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+            if let Some(ref name) = update.name {
+                sets.push("name = ?");
+                values.push(Box::new(name.clone()));
+            }
+            if let Some(ref description) = update.description {
+                sets.push("description = ?");
+                values.push(Box::new(description.clone()));
+            }
+            if let Some(ref status) = update.status {
+                sets.push("status = ?");
+                values.push(Box::new(status.as_str().to_string()));
+            }
+            if let Some(ref target_date) = update.target_date {
+                sets.push("target_date = ?");
+                values.push(Box::new(*target_date));
+            }
+
+            if sets.is_empty() {
+                return conn
+                    .query_row(
+                        "SELECT * FROM epics WHERE id = ?1",
+                        params![id],
+                        row_to_epic,
+                    )
+                    .map_err(|e| match e {
+                        rusqlite::Error::QueryReturnedNoRows => {
+                            DbError::NotFound(format!("epic {id}"))
+                        }
+                        other => DbError::Internal(other.to_string()),
+                    });
+            }
+
+            sets.push("updated_at = ?");
+            values.push(Box::new(Utc::now()));
+            values.push(Box::new(id.to_string()));
+
+            let sql = format!("UPDATE epics SET {} WHERE id = ?", sets.join(", "));
+            let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+            let changed = conn.execute(&sql, params.as_slice()).to_db()?;
+            if changed == 0 {
+                return Err(DbError::NotFound(format!("epic {id}")));
+            }
+
+            conn.query_row(
+                "SELECT * FROM epics WHERE id = ?1",
+                params![id],
+                row_to_epic,
+            )
+            .map_err(|e| DbError::Internal(e.to_string()))
+        })
+    }
+
+    /// Delete an epic and detach its tasks. SQLite's `tasks.epic_id` has no
+    /// foreign key (see migration 20), so the detach happens here.
+    pub fn delete_epic_sync(&self, id: &str) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            let tx = conn.unchecked_transaction().to_db()?;
+            tx.execute(
+                "UPDATE tasks SET epic_id = NULL WHERE epic_id = ?1",
+                params![id],
+            )
+            .to_db()?;
+            let changed = tx
+                .execute("DELETE FROM epics WHERE id = ?1", params![id])
+                .to_db()?;
+            if changed == 0 {
+                return Err(DbError::NotFound(format!("epic {id}")));
+            }
+            tx.commit().to_db()?;
+            Ok(())
+        })
+    }
+}
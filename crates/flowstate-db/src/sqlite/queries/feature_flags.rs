@@ -0,0 +1,77 @@
+use chrono::Utc;
+use rusqlite::{params, Row};
+
+use flowstate_core::feature_flag::FeatureFlag;
+
+use super::super::{SqliteDatabase, SqliteResultExt};
+use crate::DbError;
+
+pub(crate) fn row_to_feature_flag(row: &Row) -> rusqlite::Result<FeatureFlag> {
+    let project_id: String = row.get("project_id")?;
+    Ok(FeatureFlag {
+        key: row.get("key")?,
+        project_id: (!project_id.is_empty()).then_some(project_id),
+        enabled: row.get::<_, i32>("enabled")? != 0,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+impl SqliteDatabase {
+    pub fn list_feature_flags_sync(&self) -> Result<Vec<FeatureFlag>, DbError> {
+        self.with_read_conn(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT * FROM feature_flags ORDER BY key, project_id")
+                .to_db()?;
+            let flags = stmt
+                .query_map([], row_to_feature_flag)
+                .to_db()?
+                .collect::<Result<Vec<_>, _>>()
+                .to_db()?;
+            Ok(flags)
+        })
+    }
+
+    pub fn set_feature_flag_sync(
+        &self,
+        key: &str,
+        project_id: Option<&str>,
+        enabled: bool,
+    ) -> Result<FeatureFlag, DbError> {
+        let scope = project_id.unwrap_or("");
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO feature_flags (key, project_id, enabled, updated_at)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (key, project_id)
+                 DO UPDATE SET enabled = excluded.enabled, updated_at = excluded.updated_at",
+                params![key, scope, enabled as i32, Utc::now()],
+            )
+            .to_db()?;
+            conn.query_row(
+                "SELECT * FROM feature_flags WHERE key = ?1 AND project_id = ?2",
+                params![key, scope],
+                row_to_feature_flag,
+            )
+            .to_db()
+        })
+    }
+
+    pub fn delete_feature_flag_sync(
+        &self,
+        key: &str,
+        project_id: Option<&str>,
+    ) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            let changed = conn
+                .execute(
+                    "DELETE FROM feature_flags WHERE key = ?1 AND project_id = ?2",
+                    params![key, project_id.unwrap_or("")],
+                )
+                .to_db()?;
+            if changed == 0 {
+                return Err(DbError::NotFound(format!("feature_flag {key}")));
+            }
+            Ok(())
+        })
+    }
+}
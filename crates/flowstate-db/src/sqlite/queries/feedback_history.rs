@@ -0,0 +1,88 @@
+use chrono::Utc;
+use rusqlite::{params, Connection, Row};
+
+use flowstate_core::feedback::FeedbackEntry;
+
+use super::super::{SqliteDatabase, SqliteResultExt};
+use crate::DbError;
+
+pub(crate) fn row_to_feedback_entry(row: &Row) -> rusqlite::Result<FeedbackEntry> {
+    Ok(FeedbackEntry {
+        id: row.get("id")?,
+        task_id: row.get("task_id")?,
+        phase: row.get("phase")?,
+        feedback: row.get("feedback")?,
+        author: row.get("author")?,
+        created_at: row.get("created_at")?,
+    })
+}
+
+/// Record a feedback round on an open connection, so it lands in the same
+/// transaction as the rejection it belongs to.
+pub(crate) fn insert_feedback_entry(
+    conn: &Connection,
+    task_id: &str,
+    phase: &str,
+    feedback: &str,
+    author: &str,
+) -> Result<(), DbError> {
+    conn.execute(
+        "INSERT INTO feedback_history (id, task_id, phase, feedback, author, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            uuid::Uuid::new_v4().to_string(),
+            task_id,
+            phase,
+            feedback,
+            author,
+            Utc::now(),
+        ],
+    )
+    .to_db()?;
+    Ok(())
+}
+
+impl SqliteDatabase {
+    pub fn add_feedback_entry_sync(
+        &self,
+        task_id: &str,
+        phase: &str,
+        feedback: &str,
+        author: &str,
+    ) -> Result<FeedbackEntry, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(
+                "INSERT INTO feedback_history (id, task_id, phase, feedback, author, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                 RETURNING *",
+                params![
+                    uuid::Uuid::new_v4().to_string(),
+                    task_id,
+                    phase,
+                    feedback,
+                    author,
+                    Utc::now(),
+                ],
+                row_to_feedback_entry,
+            )
+            .to_db()
+        })
+    }
+
+    pub fn list_feedback_history_sync(&self, task_id: &str) -> Result<Vec<FeedbackEntry>, DbError> {
+        self.with_read_conn(|conn| {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT * FROM feedback_history WHERE task_id = ?1
+                     ORDER BY created_at DESC, rowid DESC",
+                )
+                .to_db()?;
+            let entries = stmt
+                .query_map(params![task_id], row_to_feedback_entry)
+                .to_db()?
+                .collect::<Result<Vec<_>, _>>()
+                .to_db()?;
+            Ok(entries)
+        })
+    }
+}
@@ -0,0 +1,85 @@
+use chrono::Utc;
+use rusqlite::{params, Row};
+
+use flowstate_core::label::Label;
+
+use super::super::{SqliteDatabase, SqliteResultExt};
+use crate::snapshot::TaskLabel;
+use crate::DbError;
+
+pub(crate) fn row_to_label(row: &Row) -> rusqlite::Result<Label> {
+    Ok(Label {
+        id: row.get("id")?,
+        project_id: row.get("project_id")?,
+        name: row.get("name")?,
+        color: row.get("color")?,
+        created_at: row.get("created_at")?,
+    })
+}
+
+pub(crate) fn row_to_task_label(row: &Row) -> rusqlite::Result<TaskLabel> {
+    Ok(TaskLabel {
+        task_id: row.get("task_id")?,
+        label_id: row.get("label_id")?,
+    })
+}
+
+impl SqliteDatabase {
+    pub fn ensure_label_sync(
+        &self,
+        project_id: &str,
+        name: &str,
+        color: &str,
+    ) -> Result<Label, DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT OR IGNORE INTO labels (id, project_id, name, color, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    uuid::Uuid::new_v4().to_string(),
+                    project_id,
+                    name,
+                    color,
+                    Utc::now(),
+                ],
+            )
+            .to_db()?;
+            conn.query_row(
+                "SELECT * FROM labels WHERE project_id = ?1 AND name = ?2",
+                params![project_id, name],
+                row_to_label,
+            )
+            .to_db()
+        })
+    }
+
+    pub fn add_task_label_sync(&self, task_id: &str, label_id: &str) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT OR IGNORE INTO task_labels (task_id, label_id) VALUES (?1, ?2)",
+                params![task_id, label_id],
+            )
+            .to_db()?;
+            Ok(())
+        })
+    }
+
+    pub fn list_task_labels_sync(&self, task_id: &str) -> Result<Vec<Label>, DbError> {
+        self.with_read_conn(|conn| {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT labels.* FROM labels
+                     JOIN task_labels ON task_labels.label_id = labels.id
+                     WHERE task_labels.task_id = ?1
+                     ORDER BY labels.name",
+                )
+                .to_db()?;
+            let labels = stmt
+                .query_map(params![task_id], row_to_label)
+                .to_db()?
+                .collect::<Result<Vec<_>, _>>()
+                .to_db()?;
+            Ok(labels)
+        })
+    }
+}
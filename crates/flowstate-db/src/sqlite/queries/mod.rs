@@ -1,8 +1,26 @@
 pub mod api_keys;
+pub mod approval_rules;
 pub mod attachments;
 pub mod claude_runs;
+pub mod custom_fields;
+pub mod epics;
+pub mod feature_flags;
+pub mod feedback_history;
+pub mod labels;
+pub mod organizations;
 pub mod projects;
+pub mod run_metrics;
+pub mod runners;
+pub mod saved_filters;
+pub mod snapshot;
 pub mod sprints;
+pub mod stats;
+pub mod subscriptions;
+pub mod task_imports;
 pub mod task_links;
 pub mod task_prs;
+pub mod task_revisions;
 pub mod tasks;
+pub mod users;
+pub mod watchers;
+pub mod webhooks;
@@ -0,0 +1,129 @@
+use chrono::Utc;
+use rusqlite::{params, Row};
+
+use flowstate_core::organization::{CreateOrganization, Organization};
+use flowstate_core::project::Project;
+
+use super::super::{SqliteDatabase, SqliteResultExt};
+use super::projects::row_to_project;
+use crate::DbError;
+
+pub(crate) fn row_to_organization(row: &Row) -> rusqlite::Result<Organization> {
+    Ok(Organization {
+        id: row.get("id")?,
+        name: row.get("name")?,
+        slug: row.get("slug")?,
+        created_at: row.get("created_at")?,
+    })
+}
+
+impl SqliteDatabase {
+    pub fn create_organization_sync(
+        &self,
+        input: &CreateOrganization,
+    ) -> Result<Organization, DbError> {
+        self.with_conn(|conn| {
+            let id = uuid::Uuid::new_v4().to_string();
+            conn.execute(
+                "INSERT INTO organizations (id, name, slug, created_at) VALUES (?1, ?2, ?3, ?4)",
+                params![id, input.name.trim(), input.slug, Utc::now()],
+            )
+            .to_db()?;
+            conn.query_row(
+                "SELECT * FROM organizations WHERE id = ?1",
+                params![id],
+                row_to_organization,
+            )
+            .to_db()
+        })
+    }
+
+    pub fn get_organization_sync(&self, id: &str) -> Result<Organization, DbError> {
+        self.with_read_conn(|conn| {
+            conn.query_row(
+                "SELECT * FROM organizations WHERE id = ?1",
+                params![id],
+                row_to_organization,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    DbError::NotFound(format!("organization {id}"))
+                }
+                other => DbError::Internal(other.to_string()),
+            })
+        })
+    }
+
+    pub fn list_organizations_sync(&self) -> Result<Vec<Organization>, DbError> {
+        self.with_read_conn(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT * FROM organizations ORDER BY name")
+                .to_db()?;
+            let orgs = stmt
+                .query_map([], row_to_organization)
+                .to_db()?
+                .collect::<Result<Vec<_>, _>>()
+                .to_db()?;
+            Ok(orgs)
+        })
+    }
+
+    pub fn delete_organization_sync(&self, id: &str) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            let tx = conn.unchecked_transaction().to_db()?;
+            tx.execute("DELETE FROM api_keys WHERE org_id = ?1", params![id])
+                .to_db()?;
+            tx.execute(
+                "UPDATE projects SET org_id = NULL WHERE org_id = ?1",
+                params![id],
+            )
+            .to_db()?;
+            let changed = tx
+                .execute("DELETE FROM organizations WHERE id = ?1", params![id])
+                .to_db()?;
+            if changed == 0 {
+                return Err(DbError::NotFound(format!("organization {id}")));
+            }
+            tx.commit().to_db()?;
+            Ok(())
+        })
+    }
+
+    pub fn list_organization_project_ids_sync(&self, org_id: &str) -> Result<Vec<String>, DbError> {
+        self.with_read_conn(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT id FROM projects WHERE org_id = ?1 ORDER BY id")
+                .to_db()?;
+            let ids = stmt
+                .query_map(params![org_id], |row| row.get(0))
+                .to_db()?
+                .collect::<Result<Vec<String>, _>>()
+                .to_db()?;
+            Ok(ids)
+        })
+    }
+
+    pub fn set_project_org_sync(
+        &self,
+        project_id: &str,
+        org_id: Option<&str>,
+    ) -> Result<Project, DbError> {
+        self.with_conn(|conn| {
+            let changed = conn
+                .execute(
+                    "UPDATE projects SET org_id = ?1, updated_at = ?2 WHERE id = ?3",
+                    params![org_id, Utc::now(), project_id],
+                )
+                .to_db()?;
+            if changed == 0 {
+                return Err(DbError::NotFound(format!("project {project_id}")));
+            }
+            conn.query_row(
+                "SELECT * FROM projects WHERE id = ?1",
+                params![project_id],
+                row_to_project,
+            )
+            .to_db()
+        })
+    }
+}
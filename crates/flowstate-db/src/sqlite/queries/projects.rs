@@ -1,17 +1,20 @@
 use chrono::Utc;
 use rusqlite::{params, Row};
 
+use flowstate_core::dashboard::ProjectDashboard;
 use flowstate_core::project::{CreateProject, Project, ProviderType, UpdateProject};
 
 use super::super::{SqliteDatabase, SqliteResultExt};
-use crate::DbError;
+use crate::{dashboard_from_rows, DashboardRow, DbError};
 
-fn row_to_project(row: &Row) -> rusqlite::Result<Project> {
+pub(crate) fn row_to_project(row: &Row) -> rusqlite::Result<Project> {
     let provider_type_str: Option<String> = row.get("provider_type")?;
     let provider_type = provider_type_str
         .as_deref()
         .and_then(ProviderType::parse_str);
     let skip_tls_verify: i32 = row.get("skip_tls_verify")?;
+    let docs_in_repo: i32 = row.get("docs_in_repo")?;
+    let verify_followups: i32 = row.get("verify_followups")?;
     Ok(Project {
         id: row.get("id")?,
         name: row.get("name")?,
@@ -21,6 +24,22 @@ fn row_to_project(row: &Row) -> rusqlite::Result<Project> {
         repo_token: row.get("repo_token")?,
         provider_type,
         skip_tls_verify: skip_tls_verify != 0,
+        max_concurrent_runs: row.get("max_concurrent_runs")?,
+        claim_weight: row.get("claim_weight")?,
+        run_window: crate::run_window_from_columns(
+            row.get("run_window_start")?,
+            row.get("run_window_end")?,
+            row.get("run_window_offset")?,
+            row.get("run_window_tz")?,
+        ),
+        docs_in_repo: docs_in_repo != 0,
+        verify_followups: verify_followups != 0,
+        org_id: row.get("org_id")?,
+        monthly_budget_usd: row.get("monthly_budget_usd")?,
+        budget_override_until: row.get("budget_override_until")?,
+        ssh_key_path: row.get("ssh_key_path")?,
+        fork_owner: row.get("fork_owner")?,
+        repo_subpath: row.get("repo_subpath")?,
         created_at: row.get("created_at")?,
         updated_at: row.get("updated_at")?,
     })
@@ -49,7 +68,7 @@ impl SqliteDatabase {
     }
 
     pub fn get_project_sync(&self, id: &str) -> Result<Project, DbError> {
-        self.with_conn(|conn| {
+        self.with_read_conn(|conn| {
             conn.query_row(
                 "SELECT * FROM projects WHERE id = ?1",
                 params![id],
@@ -63,7 +82,7 @@ impl SqliteDatabase {
     }
 
     pub fn get_project_by_slug_sync(&self, slug: &str) -> Result<Project, DbError> {
-        self.with_conn(|conn| {
+        self.with_read_conn(|conn| {
             conn.query_row(
                 "SELECT * FROM projects WHERE slug = ?1",
                 params![slug],
@@ -79,7 +98,7 @@ impl SqliteDatabase {
     }
 
     pub fn list_projects_sync(&self) -> Result<Vec<Project>, DbError> {
-        self.with_conn(|conn| {
+        self.with_read_conn(|conn| {
             let mut stmt = conn
                 .prepare("SELECT * FROM projects ORDER BY name")
                 .to_db()?;
@@ -125,6 +144,52 @@ impl SqliteDatabase {
                 sets.push("skip_tls_verify = ?");
                 values.push(Box::new(if skip_tls_verify { 1i32 } else { 0i32 }));
             }
+            if let Some(max_concurrent_runs) = update.max_concurrent_runs {
+                sets.push("max_concurrent_runs = ?");
+                values.push(Box::new(max_concurrent_runs));
+            }
+            if let Some(claim_weight) = update.claim_weight {
+                sets.push("claim_weight = ?");
+                values.push(Box::new(claim_weight));
+            }
+            if let Some(run_window) = update.run_window {
+                sets.push(
+                    "run_window_start = ?, run_window_end = ?, run_window_offset = ?, \
+                     run_window_tz = ?",
+                );
+                values.push(Box::new(run_window.map(|w| w.start)));
+                values.push(Box::new(run_window.map(|w| w.end)));
+                values.push(Box::new(run_window.map(|w| w.utc_offset)));
+                values.push(Box::new(run_window.and_then(|w| w.time_zone_name())));
+            }
+            if let Some(docs_in_repo) = update.docs_in_repo {
+                sets.push("docs_in_repo = ?");
+                values.push(Box::new(if docs_in_repo { 1i32 } else { 0i32 }));
+            }
+            if let Some(verify_followups) = update.verify_followups {
+                sets.push("verify_followups = ?");
+                values.push(Box::new(if verify_followups { 1i32 } else { 0i32 }));
+            }
+            if let Some(monthly_budget_usd) = update.monthly_budget_usd {
+                sets.push("monthly_budget_usd = ?");
+                values.push(Box::new(monthly_budget_usd));
+            }
+            if let Some(budget_override_until) = update.budget_override_until {
+                sets.push("budget_override_until = ?");
+                values.push(Box::new(budget_override_until));
+            }
+            if let Some(ssh_key_path) = &update.ssh_key_path {
+                sets.push("ssh_key_path = ?");
+                values.push(Box::new(ssh_key_path.clone()));
+            }
+            if let Some(fork_owner) = &update.fork_owner {
+                sets.push("fork_owner = ?");
+                values.push(Box::new(fork_owner.clone()));
+            }
+            if let Some(repo_subpath) = &update.repo_subpath {
+                sets.push("repo_subpath = ?");
+                values.push(Box::new(repo_subpath.clone()));
+            }
 
             if sets.is_empty() {
                 return conn
@@ -172,6 +237,49 @@ impl SqliteDatabase {
             Ok(())
         })
     }
+
+    pub fn project_dashboard_sync(&self, project_id: &str) -> Result<ProjectDashboard, DbError> {
+        self.with_read_conn(|conn| {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT 'status', status, '', '', COUNT(*), 0
+                     FROM tasks WHERE project_id = ?1 AND archived = 0
+                     GROUP BY status
+                     UNION ALL
+                     SELECT 'priority', priority, '', '', COUNT(*), 0
+                     FROM tasks WHERE project_id = ?1 AND archived = 0
+                     GROUP BY priority
+                     UNION ALL
+                     SELECT 'run', r.action, '', r.status, COUNT(*), 0
+                     FROM claude_runs r JOIN tasks t ON t.id = r.task_id
+                     WHERE t.project_id = ?1
+                       AND r.status IN ('completed', 'failed', 'timed_out', 'cancelled')
+                     GROUP BY r.action, r.status
+                     UNION ALL
+                     SELECT 'sprint', s.id, s.name, s.status, COUNT(t.id),
+                            COALESCE(SUM(CASE WHEN t.status = 'done' THEN 1 ELSE 0 END), 0)
+                     FROM sprints s LEFT JOIN tasks t ON t.sprint_id = s.id
+                     WHERE s.project_id = ?1 AND s.archived = 0
+                     GROUP BY s.id, s.name, s.status",
+                )
+                .to_db()?;
+            let rows = stmt
+                .query_map(params![project_id], |row| {
+                    Ok(DashboardRow {
+                        kind: row.get(0)?,
+                        key: row.get(1)?,
+                        name: row.get(2)?,
+                        state: row.get(3)?,
+                        total: row.get(4)?,
+                        done: row.get(5)?,
+                    })
+                })
+                .to_db()?
+                .collect::<Result<Vec<_>, _>>()
+                .to_db()?;
+            Ok(dashboard_from_rows(project_id, rows))
+        })
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,124 @@
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension, Row};
+
+use flowstate_core::run_metrics::{
+    RecordRunMetrics, RunMetrics, RunMetricsFilter, RunMetricsSummary,
+};
+
+use super::super::{SqliteDatabase, SqliteResultExt};
+use crate::DbError;
+
+pub(crate) fn row_to_run_metrics(row: &Row) -> rusqlite::Result<RunMetrics> {
+    Ok(RunMetrics {
+        run_id: row.get("run_id")?,
+        duration_ms: row.get("duration_ms")?,
+        stdout_bytes: row.get("stdout_bytes")?,
+        input_tokens: row.get("input_tokens")?,
+        output_tokens: row.get("output_tokens")?,
+        cost_usd: row.get("cost_usd")?,
+        recorded_at: row.get("recorded_at")?,
+    })
+}
+
+fn row_to_summary(row: &Row) -> rusqlite::Result<RunMetricsSummary> {
+    let runs: i64 = row.get(1)?;
+    let total_duration_ms: i64 = row.get(2)?;
+    Ok(RunMetricsSummary {
+        action: row.get(0)?,
+        runs,
+        total_duration_ms,
+        avg_duration_ms: if runs > 0 {
+            total_duration_ms / runs
+        } else {
+            0
+        },
+        total_stdout_bytes: row.get(3)?,
+        input_tokens: row.get(4)?,
+        output_tokens: row.get(5)?,
+        cost_usd: row.get(6)?,
+        unpriced_runs: row.get(7)?,
+    })
+}
+
+impl SqliteDatabase {
+    pub fn record_run_metrics_sync(
+        &self,
+        run_id: &str,
+        input: &RecordRunMetrics,
+    ) -> Result<RunMetrics, DbError> {
+        self.with_conn(|conn| {
+            let exists = conn
+                .query_row(
+                    "SELECT 1 FROM claude_runs WHERE id = ?1",
+                    params![run_id],
+                    |_| Ok(()),
+                )
+                .optional()
+                .to_db()?;
+            if exists.is_none() {
+                return Err(DbError::NotFound(format!("claude_run {run_id}")));
+            }
+            conn.execute(
+                "INSERT INTO run_metrics (
+                     run_id, duration_ms, stdout_bytes, input_tokens, output_tokens,
+                     cost_usd, recorded_at
+                 ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT (run_id) DO UPDATE SET
+                     duration_ms = excluded.duration_ms,
+                     stdout_bytes = excluded.stdout_bytes,
+                     input_tokens = excluded.input_tokens,
+                     output_tokens = excluded.output_tokens,
+                     cost_usd = excluded.cost_usd,
+                     recorded_at = excluded.recorded_at",
+                params![
+                    run_id,
+                    input.duration_ms,
+                    input.stdout_bytes,
+                    input.input_tokens,
+                    input.output_tokens,
+                    input.cost_usd,
+                    Utc::now(),
+                ],
+            )
+            .to_db()?;
+            conn.query_row(
+                "SELECT * FROM run_metrics WHERE run_id = ?1",
+                params![run_id],
+                row_to_run_metrics,
+            )
+            .to_db()
+        })
+    }
+
+    pub fn summarize_run_metrics_sync(
+        &self,
+        filter: &RunMetricsFilter,
+    ) -> Result<Vec<RunMetricsSummary>, DbError> {
+        self.with_read_conn(|conn| {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT r.action, COUNT(*),
+                            COALESCE(SUM(m.duration_ms), 0),
+                            COALESCE(SUM(m.stdout_bytes), 0),
+                            COALESCE(SUM(m.input_tokens), 0),
+                            COALESCE(SUM(m.output_tokens), 0),
+                            COALESCE(SUM(m.cost_usd), 0.0),
+                            COUNT(*) - COUNT(m.cost_usd)
+                     FROM run_metrics m
+                     JOIN claude_runs r ON r.id = m.run_id
+                     JOIN tasks t ON t.id = r.task_id
+                     WHERE (?1 IS NULL OR t.project_id = ?1)
+                       AND (?2 IS NULL OR m.recorded_at >= ?2)
+                     GROUP BY r.action
+                     ORDER BY r.action",
+                )
+                .to_db()?;
+            let rows = stmt
+                .query_map(params![filter.project_id, filter.since], row_to_summary)
+                .to_db()?
+                .collect::<Result<Vec<_>, _>>()
+                .to_db()?;
+            Ok(rows)
+        })
+    }
+}
@@ -0,0 +1,155 @@
+use rusqlite::{params, Row};
+
+use flowstate_core::runner::{PendingRunnerConfig, RunnerRecord, RunnerStatus};
+
+use super::super::{SqliteDatabase, SqliteResultExt};
+use crate::DbError;
+
+fn row_to_runner(row: &Row) -> rusqlite::Result<RunnerRecord> {
+    let status: String = row.get("status")?;
+    let pending_config: Option<String> = row.get("pending_config")?;
+    let benchmark: Option<String> = row.get("benchmark")?;
+    let count = |col: &str| -> rusqlite::Result<Option<usize>> {
+        Ok(row.get::<_, Option<i64>>(col)?.map(|n| n as usize))
+    };
+    Ok(RunnerRecord {
+        runner_id: row.get("runner_id")?,
+        org_id: row.get("org_id")?,
+        backend_name: row.get("backend_name")?,
+        capability: row.get("capability")?,
+        poll_interval: row
+            .get::<_, Option<i64>>("poll_interval")?
+            .map(|n| n as u64),
+        max_concurrent: count("max_concurrent")?,
+        max_builds: count("max_builds")?,
+        active_count: count("active_count")?,
+        active_builds: count("active_builds")?,
+        status: RunnerStatus::parse_str(&status).unwrap_or(RunnerStatus::Active),
+        pending_config: pending_config.and_then(|s| serde_json::from_str(&s).ok()),
+        benchmark: benchmark.and_then(|s| serde_json::from_str(&s).ok()),
+        first_seen_at: row.get("first_seen_at")?,
+        last_seen_at: row.get("last_seen_at")?,
+    })
+}
+
+fn to_json<T: serde::Serialize>(value: Option<&T>) -> Result<Option<String>, DbError> {
+    value
+        .map(serde_json::to_string)
+        .transpose()
+        .map_err(|e| DbError::Internal(e.to_string()))
+}
+
+impl SqliteDatabase {
+    pub fn upsert_runner_sync(&self, runner: &RunnerRecord) -> Result<RunnerRecord, DbError> {
+        let pending_config = to_json(runner.pending_config.as_ref())?;
+        let benchmark = to_json(runner.benchmark.as_ref())?;
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT INTO runners (runner_id, org_id, backend_name, capability, poll_interval,
+                     max_concurrent, max_builds, active_count, active_builds, status,
+                     pending_config, benchmark, first_seen_at, last_seen_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+                 ON CONFLICT (runner_id) DO UPDATE SET
+                     org_id = excluded.org_id,
+                     backend_name = excluded.backend_name,
+                     capability = excluded.capability,
+                     poll_interval = excluded.poll_interval,
+                     max_concurrent = excluded.max_concurrent,
+                     max_builds = excluded.max_builds,
+                     active_count = excluded.active_count,
+                     active_builds = excluded.active_builds,
+                     status = excluded.status,
+                     benchmark = excluded.benchmark,
+                     last_seen_at = excluded.last_seen_at",
+                params![
+                    runner.runner_id,
+                    runner.org_id,
+                    runner.backend_name,
+                    runner.capability,
+                    runner.poll_interval.map(|n| n as i64),
+                    runner.max_concurrent.map(|n| n as i64),
+                    runner.max_builds.map(|n| n as i64),
+                    runner.active_count.map(|n| n as i64),
+                    runner.active_builds.map(|n| n as i64),
+                    runner.status.as_str(),
+                    pending_config,
+                    benchmark,
+                    runner.first_seen_at,
+                    runner.last_seen_at,
+                ],
+            )
+            .to_db()?;
+            conn.query_row(
+                "SELECT * FROM runners WHERE runner_id = ?1",
+                params![runner.runner_id],
+                row_to_runner,
+            )
+            .to_db()
+        })
+    }
+
+    pub fn get_runner_sync(&self, runner_id: &str) -> Result<RunnerRecord, DbError> {
+        self.with_read_conn(|conn| {
+            conn.query_row(
+                "SELECT * FROM runners WHERE runner_id = ?1",
+                params![runner_id],
+                row_to_runner,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    DbError::NotFound(format!("runner {runner_id}"))
+                }
+                other => DbError::Internal(other.to_string()),
+            })
+        })
+    }
+
+    pub fn list_runners_sync(&self) -> Result<Vec<RunnerRecord>, DbError> {
+        self.with_read_conn(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT * FROM runners ORDER BY runner_id")
+                .to_db()?;
+            let runners = stmt
+                .query_map([], row_to_runner)
+                .to_db()?
+                .collect::<Result<Vec<_>, _>>()
+                .to_db()?;
+            Ok(runners)
+        })
+    }
+
+    pub fn set_runner_pending_config_sync(
+        &self,
+        runner_id: &str,
+        config: Option<&PendingRunnerConfig>,
+    ) -> Result<(), DbError> {
+        let config = to_json(config)?;
+        self.with_conn(|conn| {
+            let changed = conn
+                .execute(
+                    "UPDATE runners SET pending_config = ?1 WHERE runner_id = ?2",
+                    params![config, runner_id],
+                )
+                .to_db()?;
+            if changed == 0 {
+                return Err(DbError::NotFound(format!("runner {runner_id}")));
+            }
+            Ok(())
+        })
+    }
+
+    pub fn delete_runner_sync(&self, runner_id: &str) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            let changed = conn
+                .execute(
+                    "DELETE FROM runners WHERE runner_id = ?1",
+                    params![runner_id],
+                )
+                .to_db()?;
+            if changed == 0 {
+                return Err(DbError::NotFound(format!("runner {runner_id}")));
+            }
+            Ok(())
+        })
+    }
+}
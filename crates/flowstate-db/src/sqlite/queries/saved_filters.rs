@@ -0,0 +1,140 @@
+use chrono::Utc;
+use rusqlite::{params, Row};
+
+use flowstate_core::saved_filter::{CreateSavedFilter, SavedFilter, UpdateSavedFilter};
+
+use super::super::{SqliteDatabase, SqliteResultExt};
+use crate::DbError;
+
+pub(crate) fn row_to_saved_filter(row: &Row) -> rusqlite::Result<SavedFilter> {
+    let query: String = row.get("query")?;
+    Ok(SavedFilter {
+        id: row.get("id")?,
+        project_id: row.get("project_id")?,
+        user_id: row.get("user_id")?,
+        name: row.get("name")?,
+        query: serde_json::from_str(&query).unwrap_or_default(),
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+impl SqliteDatabase {
+    pub fn create_saved_filter_sync(
+        &self,
+        input: &CreateSavedFilter,
+    ) -> Result<SavedFilter, DbError> {
+        self.with_conn(|conn| {
+            let id = uuid::Uuid::new_v4().to_string();
+            let now = Utc::now();
+            let query = serde_json::to_string(&input.query)
+                .map_err(|e| DbError::Internal(e.to_string()))?;
+            conn.execute(
+                "INSERT INTO saved_filters (id, project_id, user_id, name, query, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![id, input.project_id, input.user_id, input.name, query, now, now],
+            )
+            .to_db()?;
+            conn.query_row(
+                "SELECT * FROM saved_filters WHERE id = ?1",
+                params![id],
+                row_to_saved_filter,
+            )
+            .to_db()
+        })
+    }
+
+    pub fn get_saved_filter_sync(&self, id: &str) -> Result<SavedFilter, DbError> {
+        self.with_read_conn(|conn| {
+            conn.query_row(
+                "SELECT * FROM saved_filters WHERE id = ?1",
+                params![id],
+                row_to_saved_filter,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    DbError::NotFound(format!("saved filter {id}"))
+                }
+                other => DbError::Internal(other.to_string()),
+            })
+        })
+    }
+
+    pub fn list_saved_filters_sync(
+        &self,
+        project_id: &str,
+        user_id: Option<&str>,
+    ) -> Result<Vec<SavedFilter>, DbError> {
+        self.with_read_conn(|conn| {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT * FROM saved_filters
+                     WHERE project_id = ?1 AND (?2 IS NULL OR user_id IS NULL OR user_id = ?2)
+                     ORDER BY name, id",
+                )
+                .to_db()?;
+            let filters = stmt
+                .query_map(params![project_id, user_id], row_to_saved_filter)
+                .to_db()?
+                .collect::<Result<Vec<_>, _>>()
+                .to_db()?;
+            Ok(filters)
+        })
+    }
+
+    pub fn update_saved_filter_sync(
+        &self,
+        id: &str,
+        update: &UpdateSavedFilter,
+    ) -> Result<SavedFilter, DbError> {
+        self.with_conn(|conn| {
+            let mut sets = Vec::new();
+            let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+            if let Some(ref name) = update.name {
+                sets.push("name = ?");
+                values.push(Box::new(name.clone()));
+            }
+            if let Some(ref query) = update.query {
+                let query =
+                    serde_json::to_string(query).map_err(|e| DbError::Internal(e.to_string()))?;
+                sets.push("query = ?");
+                values.push(Box::new(query));
+            }
+
+            if !sets.is_empty() {
+                sets.push("updated_at = ?");
+                values.push(Box::new(Utc::now()));
+                values.push(Box::new(id.to_string()));
+
+                let sql = format!("UPDATE saved_filters SET {} WHERE id = ?", sets.join(", "));
+                let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+                conn.execute(&sql, params.as_slice()).to_db()?;
+            }
+
+            conn.query_row(
+                "SELECT * FROM saved_filters WHERE id = ?1",
+                params![id],
+                row_to_saved_filter,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    DbError::NotFound(format!("saved filter {id}"))
+                }
+                other => DbError::Internal(other.to_string()),
+            })
+        })
+    }
+
+    pub fn delete_saved_filter_sync(&self, id: &str) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            let changed = conn
+                .execute("DELETE FROM saved_filters WHERE id = ?1", params![id])
+                .to_db()?;
+            if changed == 0 {
+                return Err(DbError::NotFound(format!("saved filter {id}")));
+            }
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,646 @@
+use rusqlite::{params, Connection, Row};
+
+use crate::snapshot::Snapshot;
+
+use super::super::{SqliteDatabase, SqliteResultExt};
+use super::approval_rules::row_to_approval_rule;
+use super::attachments::row_to_attachment;
+use super::claude_runs::row_to_claude_run;
+use super::custom_fields::{row_to_custom_field, row_to_task_field_value};
+use super::epics::row_to_epic;
+use super::feature_flags::row_to_feature_flag;
+use super::feedback_history::row_to_feedback_entry;
+use super::labels::{row_to_label, row_to_task_label};
+use super::organizations::row_to_organization;
+use super::projects::row_to_project;
+use super::run_metrics::row_to_run_metrics;
+use super::saved_filters::row_to_saved_filter;
+use super::sprints::row_to_sprint;
+use super::subscriptions::row_to_subscription;
+use super::task_imports::row_to_task_import;
+use super::task_links::row_to_task_link;
+use super::task_prs::row_to_task_pr;
+use super::task_revisions::row_to_task_revision;
+use super::tasks::row_to_task;
+use super::users::row_to_user;
+use super::watchers::{row_to_notification, row_to_task_watcher};
+use super::webhooks::row_to_webhook;
+use crate::DbError;
+
+fn select_all<T>(
+    conn: &Connection,
+    sql: &str,
+    f: fn(&Row) -> rusqlite::Result<T>,
+) -> Result<Vec<T>, DbError> {
+    let mut stmt = conn.prepare(sql).to_db()?;
+    let rows = stmt
+        .query_map([], f)
+        .to_db()?
+        .collect::<Result<Vec<_>, _>>()
+        .to_db()?;
+    Ok(rows)
+}
+
+impl SqliteDatabase {
+    pub fn export_snapshot_sync(&self) -> Result<Snapshot, DbError> {
+        self.with_read_conn(|conn| {
+            // One read transaction so every table comes from the same snapshot.
+            let tx = conn.unchecked_transaction().to_db()?;
+            let mut snapshot = Snapshot::new();
+            snapshot.organizations = select_all(
+                &tx,
+                "SELECT * FROM organizations ORDER BY created_at",
+                row_to_organization,
+            )?;
+            snapshot.projects = select_all(
+                &tx,
+                "SELECT * FROM projects ORDER BY created_at",
+                row_to_project,
+            )?;
+            snapshot.sprints = select_all(
+                &tx,
+                "SELECT * FROM sprints ORDER BY created_at",
+                row_to_sprint,
+            )?;
+            snapshot.epics =
+                select_all(&tx, "SELECT * FROM epics ORDER BY created_at", row_to_epic)?;
+            snapshot.labels = select_all(
+                &tx,
+                "SELECT * FROM labels ORDER BY created_at",
+                row_to_label,
+            )?;
+            snapshot.users =
+                select_all(&tx, "SELECT * FROM users ORDER BY created_at", row_to_user)?;
+            snapshot.saved_filters = select_all(
+                &tx,
+                "SELECT * FROM saved_filters ORDER BY created_at",
+                row_to_saved_filter,
+            )?;
+            snapshot.custom_fields = select_all(
+                &tx,
+                "SELECT * FROM custom_fields ORDER BY created_at",
+                row_to_custom_field,
+            )?;
+            snapshot.tasks =
+                select_all(&tx, "SELECT * FROM tasks ORDER BY created_at", row_to_task)?;
+            snapshot.task_field_values = select_all(
+                &tx,
+                "SELECT * FROM task_field_values ORDER BY task_id, field_id",
+                row_to_task_field_value,
+            )?;
+            snapshot.task_labels = select_all(
+                &tx,
+                "SELECT * FROM task_labels ORDER BY task_id, label_id",
+                row_to_task_label,
+            )?;
+            snapshot.claude_runs = select_all(
+                &tx,
+                "SELECT * FROM claude_runs ORDER BY started_at",
+                row_to_claude_run,
+            )?;
+            snapshot.run_metrics = select_all(
+                &tx,
+                "SELECT * FROM run_metrics ORDER BY recorded_at",
+                row_to_run_metrics,
+            )?;
+            snapshot.task_links = select_all(
+                &tx,
+                "SELECT * FROM task_links ORDER BY created_at",
+                row_to_task_link,
+            )?;
+            snapshot.task_prs = select_all(
+                &tx,
+                "SELECT * FROM task_prs ORDER BY created_at",
+                row_to_task_pr,
+            )?;
+            snapshot.attachments = select_all(
+                &tx,
+                "SELECT * FROM attachments ORDER BY created_at",
+                row_to_attachment,
+            )?;
+            snapshot.task_revisions = select_all(
+                &tx,
+                "SELECT * FROM task_revisions ORDER BY created_at",
+                row_to_task_revision,
+            )?;
+            snapshot.feedback_history = select_all(
+                &tx,
+                "SELECT * FROM feedback_history ORDER BY created_at",
+                row_to_feedback_entry,
+            )?;
+            snapshot.task_watchers = select_all(
+                &tx,
+                "SELECT * FROM task_watchers ORDER BY created_at",
+                row_to_task_watcher,
+            )?;
+            snapshot.notifications = select_all(
+                &tx,
+                "SELECT * FROM notifications ORDER BY created_at",
+                row_to_notification,
+            )?;
+            snapshot.webhooks = select_all(
+                &tx,
+                "SELECT * FROM webhooks ORDER BY created_at",
+                row_to_webhook,
+            )?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+            snapshot.feature_flags = select_all(
+                &tx,
+                "SELECT * FROM feature_flags ORDER BY key, project_id",
+                row_to_feature_flag,
+            )?;
+            snapshot.task_imports = select_all(
+                &tx,
+                "SELECT * FROM task_imports ORDER BY created_at",
+                row_to_task_import,
+            )?;
+            snapshot.approval_rules = select_all(
+                &tx,
+                "SELECT * FROM approval_rules ORDER BY project_id, phase",
+                row_to_approval_rule,
+            )?;
+            snapshot.subscriptions = select_all(
+                &tx,
+                "SELECT * FROM subscriptions ORDER BY created_at",
+                row_to_subscription,
+            )?;
+            Ok(snapshot)
+        })
+    }
+
+    pub fn import_snapshot_sync(&self, snapshot: &Snapshot) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            let tx = conn.unchecked_transaction().to_db()?;
+
+            for o in &snapshot.organizations {
+                tx.execute(
+                    "INSERT INTO organizations (id, name, slug, created_at)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![o.id, o.name, o.slug, o.created_at],
+                )
+                .to_db()?;
+            }
+
+            for p in &snapshot.projects {
+                tx.execute(
+                    "INSERT INTO projects (
+                        id, name, slug, description, repo_url, repo_token,
+                        provider_type, skip_tls_verify, created_at, updated_at,
+                        max_concurrent_runs, claim_weight, run_window_start, run_window_end,
+                        run_window_offset, docs_in_repo, verify_followups, org_id,
+                        monthly_budget_usd, budget_override_until, ssh_key_path,
+                        fork_owner, repo_subpath, run_window_tz
+                     ) VALUES (
+                        ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17,
+                        ?18, ?19, ?20, ?21, ?22, ?23, ?24
+                     )",
+                    params![
+                        p.id,
+                        p.name,
+                        p.slug,
+                        p.description,
+                        p.repo_url,
+                        p.repo_token,
+                        p.provider_type.map(|t| t.as_str()),
+                        p.skip_tls_verify as i32,
+                        p.created_at,
+                        p.updated_at,
+                        p.max_concurrent_runs,
+                        p.claim_weight,
+                        p.run_window.map(|w| w.start),
+                        p.run_window.map(|w| w.end),
+                        p.run_window.map(|w| w.utc_offset),
+                        p.docs_in_repo as i32,
+                        p.verify_followups as i32,
+                        p.org_id,
+                        p.monthly_budget_usd,
+                        p.budget_override_until,
+                        p.ssh_key_path,
+                        p.fork_owner,
+                        p.repo_subpath,
+                        p.run_window.and_then(|w| w.time_zone_name()),
+                    ],
+                )
+                .to_db()?;
+            }
+
+            for s in &snapshot.sprints {
+                tx.execute(
+                    "INSERT INTO sprints (
+                        id, project_id, name, goal, starts_at, ends_at, status, archived,
+                        created_at, updated_at
+                     ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                    params![
+                        s.id,
+                        s.project_id,
+                        s.name,
+                        s.goal,
+                        s.starts_at,
+                        s.ends_at,
+                        s.status.as_str(),
+                        s.archived,
+                        s.created_at,
+                        s.updated_at,
+                    ],
+                )
+                .to_db()?;
+            }
+
+            for e in &snapshot.epics {
+                tx.execute(
+                    "INSERT INTO epics (
+                        id, project_id, name, description, status, target_date,
+                        created_at, updated_at
+                     ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        e.id,
+                        e.project_id,
+                        e.name,
+                        e.description,
+                        e.status.as_str(),
+                        e.target_date,
+                        e.created_at,
+                        e.updated_at,
+                    ],
+                )
+                .to_db()?;
+            }
+
+            for l in &snapshot.labels {
+                tx.execute(
+                    "INSERT INTO labels (id, project_id, name, color, created_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![l.id, l.project_id, l.name, l.color, l.created_at],
+                )
+                .to_db()?;
+            }
+
+            for u in &snapshot.users {
+                tx.execute(
+                    "INSERT INTO users (id, name, email, created_at, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![u.id, u.name, u.email, u.created_at, u.updated_at],
+                )
+                .to_db()?;
+            }
+
+            for f in &snapshot.saved_filters {
+                let query = serde_json::to_string(&f.query)
+                    .map_err(|e| DbError::Internal(e.to_string()))?;
+                tx.execute(
+                    "INSERT INTO saved_filters (
+                        id, project_id, user_id, name, query, created_at, updated_at
+                     ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        f.id,
+                        f.project_id,
+                        f.user_id,
+                        f.name,
+                        query,
+                        f.created_at,
+                        f.updated_at,
+                    ],
+                )
+                .to_db()?;
+            }
+
+            for t in snapshot.tasks_parent_first() {
+                tx.execute(
+                    "INSERT INTO tasks (
+                        id, project_id, sprint_id, epic_id, assignee_id, parent_id, title, description, reviewer,
+                        status, priority, sort_order,
+                        research_status, spec_status, plan_status, verify_status,
+                        spec_approved_hash, research_approved_hash,
+                        research_feedback, spec_feedback, plan_feedback, verify_feedback,
+                        research_capability, design_capability, plan_capability,
+                        build_capability, verify_capability, due_at, archived,
+                        created_at, updated_at, task_type, autopilot
+                     ) VALUES (
+                        ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14,
+                        ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27,
+                        ?28, ?29, ?30, ?31, ?32, ?33
+                     )",
+                    params![
+                        t.id,
+                        t.project_id,
+                        t.sprint_id,
+                        t.epic_id,
+                        t.assignee_id,
+                        t.parent_id,
+                        t.title,
+                        t.description,
+                        t.reviewer,
+                        t.status.as_str(),
+                        t.priority.as_str(),
+                        t.sort_order,
+                        t.research_status.as_str(),
+                        t.spec_status.as_str(),
+                        t.plan_status.as_str(),
+                        t.verify_status.as_str(),
+                        t.spec_approved_hash,
+                        t.research_approved_hash,
+                        t.research_feedback,
+                        t.spec_feedback,
+                        t.plan_feedback,
+                        t.verify_feedback,
+                        t.research_capability.map(|c| c.as_str()),
+                        t.design_capability.map(|c| c.as_str()),
+                        t.plan_capability.map(|c| c.as_str()),
+                        t.build_capability.map(|c| c.as_str()),
+                        t.verify_capability.map(|c| c.as_str()),
+                        t.due_at,
+                        t.archived,
+                        t.created_at,
+                        t.updated_at,
+                        t.task_type.as_str(),
+                        t.autopilot,
+                    ],
+                )
+                .to_db()?;
+            }
+
+            for r in &snapshot.claude_runs {
+                tx.execute(
+                    "INSERT INTO claude_runs (
+                        id, task_id, action, status, error_message, exit_code,
+                        pr_url, pr_number, branch_name, progress_message, runner_id,
+                        started_at, finished_at, required_capability, priority, feedback,
+                        idempotency_key, run_window_start, run_window_end, run_window_offset,
+                        trace_context, run_window_tz
+                     ) VALUES (
+                        ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16,
+                        ?17, ?18, ?19, ?20, ?21, ?22
+                     )",
+                    params![
+                        r.id,
+                        r.task_id,
+                        r.action.as_str(),
+                        r.status.as_str(),
+                        r.error_message,
+                        r.exit_code,
+                        r.pr_url,
+                        r.pr_number,
+                        r.branch_name,
+                        r.progress_message,
+                        r.runner_id,
+                        r.started_at,
+                        r.finished_at,
+                        r.required_capability,
+                        r.priority,
+                        r.feedback,
+                        r.idempotency_key,
+                        r.run_window.map(|w| w.start),
+                        r.run_window.map(|w| w.end),
+                        r.run_window.map(|w| w.utc_offset),
+                        r.trace_context,
+                        r.run_window.and_then(|w| w.time_zone_name()),
+                    ],
+                )
+                .to_db()?;
+            }
+
+            for m in &snapshot.run_metrics {
+                tx.execute(
+                    "INSERT INTO run_metrics (
+                        run_id, duration_ms, stdout_bytes, input_tokens, output_tokens,
+                        cost_usd, recorded_at
+                     ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        m.run_id,
+                        m.duration_ms,
+                        m.stdout_bytes,
+                        m.input_tokens,
+                        m.output_tokens,
+                        m.cost_usd,
+                        m.recorded_at,
+                    ],
+                )
+                .to_db()?;
+            }
+
+            for f in &snapshot.custom_fields {
+                let options = serde_json::to_string(&f.options)
+                    .map_err(|e| DbError::Internal(e.to_string()))?;
+                tx.execute(
+                    "INSERT INTO custom_fields (
+                        id, project_id, name, field_type, options, created_at, updated_at
+                     ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        f.id,
+                        f.project_id,
+                        f.name,
+                        f.field_type.as_str(),
+                        options,
+                        f.created_at,
+                        f.updated_at,
+                    ],
+                )
+                .to_db()?;
+            }
+
+            for v in &snapshot.task_field_values {
+                tx.execute(
+                    "INSERT INTO task_field_values (task_id, field_id, value) VALUES (?1, ?2, ?3)",
+                    params![v.task_id, v.field_id, v.value],
+                )
+                .to_db()?;
+            }
+
+            for tl in &snapshot.task_labels {
+                tx.execute(
+                    "INSERT INTO task_labels (task_id, label_id) VALUES (?1, ?2)",
+                    params![tl.task_id, tl.label_id],
+                )
+                .to_db()?;
+            }
+
+            for l in &snapshot.task_links {
+                tx.execute(
+                    "INSERT INTO task_links (id, source_task_id, target_task_id, link_type, created_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![
+                        l.id,
+                        l.source_task_id,
+                        l.target_task_id,
+                        l.link_type.as_str(),
+                        l.created_at,
+                    ],
+                )
+                .to_db()?;
+            }
+
+            for pr in &snapshot.task_prs {
+                tx.execute(
+                    "INSERT INTO task_prs (
+                        id, task_id, claude_run_id, pr_url, pr_number, branch_name, state,
+                        checks_status, draft, created_at
+                     ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                    params![
+                        pr.id,
+                        pr.task_id,
+                        pr.claude_run_id,
+                        pr.pr_url,
+                        pr.pr_number,
+                        pr.branch_name,
+                        pr.state.as_str(),
+                        pr.checks_status.map(|s| s.as_str()),
+                        pr.draft,
+                        pr.created_at,
+                    ],
+                )
+                .to_db()?;
+            }
+
+            for a in &snapshot.attachments {
+                tx.execute(
+                    "INSERT INTO attachments
+                         (id, task_id, filename, store_key, size_bytes, content_type, sha256,
+                          created_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        a.id,
+                        a.task_id,
+                        a.filename,
+                        a.store_key,
+                        a.size_bytes,
+                        a.content_type,
+                        a.sha256,
+                        a.created_at,
+                    ],
+                )
+                .to_db()?;
+            }
+
+            for rev in &snapshot.task_revisions {
+                let changes = serde_json::to_string(&rev.changes)
+                    .map_err(|e| DbError::Internal(e.to_string()))?;
+                tx.execute(
+                    "INSERT INTO task_revisions (id, task_id, actor, changes, created_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![rev.id, rev.task_id, rev.actor, changes, rev.created_at],
+                )
+                .to_db()?;
+            }
+
+            for f in &snapshot.feedback_history {
+                tx.execute(
+                    "INSERT INTO feedback_history (id, task_id, phase, feedback, author, created_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![f.id, f.task_id, f.phase, f.feedback, f.author, f.created_at],
+                )
+                .to_db()?;
+            }
+
+            for w in &snapshot.task_watchers {
+                tx.execute(
+                    "INSERT INTO task_watchers (task_id, user_id, created_at) VALUES (?1, ?2, ?3)",
+                    params![w.task_id, w.user_id, w.created_at],
+                )
+                .to_db()?;
+            }
+
+            for n in &snapshot.notifications {
+                let changes = serde_json::to_string(&n.changes)
+                    .map_err(|e| DbError::Internal(e.to_string()))?;
+                tx.execute(
+                    "INSERT INTO notifications (id, user_id, task_id, actor, changes, read, created_at)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        n.id,
+                        n.user_id,
+                        n.task_id,
+                        n.actor,
+                        changes,
+                        n.read,
+                        n.created_at,
+                    ],
+                )
+                .to_db()?;
+            }
+
+            for w in &snapshot.webhooks {
+                let hook = &w.webhook;
+                let events = serde_json::to_string(&hook.events)
+                    .map_err(|e| DbError::Internal(e.to_string()))?;
+                tx.execute(
+                    "INSERT INTO webhooks (
+                        id, url, secret, events, project_id, enabled, created_at, updated_at
+                     ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        hook.id,
+                        hook.url,
+                        w.secret,
+                        events,
+                        hook.project_id,
+                        hook.enabled as i32,
+                        hook.created_at,
+                        hook.updated_at,
+                    ],
+                )
+                .to_db()?;
+            }
+
+            for f in &snapshot.feature_flags {
+                tx.execute(
+                    "INSERT INTO feature_flags (key, project_id, enabled, updated_at)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![
+                        f.key,
+                        f.project_id.as_deref().unwrap_or(""),
+                        f.enabled as i32,
+                        f.updated_at,
+                    ],
+                )
+                .to_db()?;
+            }
+
+            for i in &snapshot.task_imports {
+                tx.execute(
+                    "INSERT INTO task_imports (project_id, source, task_id, created_at)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![i.project_id, i.source, i.task_id, i.created_at],
+                )
+                .to_db()?;
+            }
+
+            for r in &snapshot.approval_rules {
+                tx.execute(
+                    "INSERT INTO approval_rules (project_id, phase, policy, updated_at)
+                     VALUES (?1, ?2, ?3, ?4)",
+                    params![
+                        r.project_id,
+                        r.phase.as_str(),
+                        r.policy.as_str(),
+                        r.updated_at,
+                    ],
+                )
+                .to_db()?;
+            }
+
+            for sub in &snapshot.subscriptions {
+                let events = serde_json::to_string(&sub.events)
+                    .map_err(|e| DbError::Internal(e.to_string()))?;
+                tx.execute(
+                    "INSERT INTO subscriptions (
+                        id, channel, target, events, project_id, user_id, created_at
+                     ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        sub.id,
+                        sub.channel.as_str(),
+                        sub.target,
+                        events,
+                        sub.project_id,
+                        sub.user_id,
+                        sub.created_at,
+                    ],
+                )
+                .to_db()?;
+            }
+
+            tx.commit().to_db()?;
+            Ok(())
+        })
+    }
+}
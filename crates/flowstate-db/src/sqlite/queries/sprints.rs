@@ -6,7 +6,7 @@ use flowstate_core::sprint::{CreateSprint, Sprint, SprintStatus, UpdateSprint};
 use super::super::{SqliteDatabase, SqliteResultExt};
 use crate::DbError;
 
-fn row_to_sprint(row: &Row) -> rusqlite::Result<Sprint> {
+pub(crate) fn row_to_sprint(row: &Row) -> rusqlite::Result<Sprint> {
     let status_str: String = row.get("status")?;
     Ok(Sprint {
         id: row.get("id")?,
@@ -16,6 +16,7 @@ fn row_to_sprint(row: &Row) -> rusqlite::Result<Sprint> {
         starts_at: row.get("starts_at")?,
         ends_at: row.get("ends_at")?,
         status: SprintStatus::parse_str(&status_str).unwrap_or(SprintStatus::Planned),
+        archived: row.get("archived")?,
         created_at: row.get("created_at")?,
         updated_at: row.get("updated_at")?,
     })
@@ -42,7 +43,7 @@ impl SqliteDatabase {
     }
 
     pub fn get_sprint_sync(&self, id: &str) -> Result<Sprint, DbError> {
-        self.with_conn(|conn| {
+        self.with_read_conn(|conn| {
             conn.query_row(
                 "SELECT * FROM sprints WHERE id = ?1",
                 params![id],
@@ -56,7 +57,7 @@ impl SqliteDatabase {
     }
 
     pub fn list_sprints_sync(&self, project_id: &str) -> Result<Vec<Sprint>, DbError> {
-        self.with_conn(|conn| {
+        self.with_read_conn(|conn| {
             let mut stmt = conn
                 .prepare("SELECT * FROM sprints WHERE project_id = ?1 ORDER BY created_at DESC")
                 .to_db()?;
@@ -130,6 +131,42 @@ impl SqliteDatabase {
         })
     }
 
+    /// Archive the sprint, its tasks and their subtasks in one transaction.
+    pub fn archive_sprint_sync(&self, id: &str) -> Result<Sprint, DbError> {
+        self.with_conn(|conn| {
+            let tx = conn.unchecked_transaction().to_db()?;
+            let now = Utc::now();
+            let changed = tx
+                .execute(
+                    "UPDATE sprints SET archived = 1, updated_at = ?2 WHERE id = ?1",
+                    params![id, now],
+                )
+                .to_db()?;
+            if changed == 0 {
+                return Err(DbError::NotFound(format!("sprint {id}")));
+            }
+            tx.execute(
+                "WITH RECURSIVE tree(id) AS (
+                     SELECT id FROM tasks WHERE sprint_id = ?1
+                     UNION SELECT t.id FROM tasks t JOIN tree ON t.parent_id = tree.id
+                 )
+                 UPDATE tasks SET archived = 1, updated_at = ?2
+                 WHERE id IN (SELECT id FROM tree)",
+                params![id, now],
+            )
+            .to_db()?;
+            let sprint = tx
+                .query_row(
+                    "SELECT * FROM sprints WHERE id = ?1",
+                    params![id],
+                    row_to_sprint,
+                )
+                .to_db()?;
+            tx.commit().to_db()?;
+            Ok(sprint)
+        })
+    }
+
     pub fn delete_sprint_sync(&self, id: &str) -> Result<(), DbError> {
         self.with_conn(|conn| {
             let changed = conn
@@ -0,0 +1,126 @@
+use std::time::Instant;
+
+use chrono::Utc;
+use rusqlite::Connection;
+
+use super::super::{SqliteDatabase, SqliteResultExt};
+use crate::stats::{DbStats, MaintenanceReport, MaintenanceStep, TableCount, STATS_TABLES};
+use crate::DbError;
+
+impl SqliteDatabase {
+    pub fn stats_sync(&self) -> Result<DbStats, DbError> {
+        self.with_read_conn(|conn| {
+            let mut tables = Vec::with_capacity(STATS_TABLES.len());
+            for name in STATS_TABLES {
+                let rows: i64 = conn
+                    .query_row(&format!("SELECT COUNT(*) FROM {name}"), [], |row| {
+                        row.get(0)
+                    })
+                    .to_db()?;
+                tables.push(TableCount {
+                    name: name.to_string(),
+                    rows,
+                });
+            }
+            let schema_version: i64 = conn
+                .query_row(
+                    "SELECT COALESCE(MAX(version), 0) FROM schema_version",
+                    [],
+                    |row| row.get(0),
+                )
+                .to_db()?;
+            // page_count * page_size is the main file's size, excluding the WAL
+            let size_bytes: i64 = conn
+                .query_row(
+                    "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+                    [],
+                    |row| row.get(0),
+                )
+                .to_db()?;
+            Ok(DbStats {
+                backend: "sqlite".into(),
+                schema_version,
+                tables,
+                size_bytes: Some(size_bytes),
+                pool: None,
+            })
+        })
+    }
+
+    /// Checkpoint and truncate the WAL, release free pages, refresh the
+    /// planner's statistics and check integrity. Runs on the writer, so
+    /// writes wait until it finishes.
+    pub fn run_maintenance_sync(&self) -> Result<MaintenanceReport, DbError> {
+        let started_at = Utc::now();
+        let timer = Instant::now();
+        let steps = self.with_conn(|conn| {
+            Ok(vec![
+                run_step("wal_checkpoint", || wal_checkpoint(conn)),
+                run_step("vacuum", || vacuum(conn)),
+                run_step("optimize", || {
+                    conn.execute_batch("PRAGMA optimize;")?;
+                    Ok((true, "planner statistics refreshed".into()))
+                }),
+                run_step("integrity_check", || integrity_check(conn)),
+            ])
+        })?;
+        Ok(MaintenanceReport {
+            backend: "sqlite".into(),
+            started_at,
+            duration_ms: timer.elapsed().as_millis() as i64,
+            steps,
+        })
+    }
+}
+
+/// Run one maintenance step, recording an error as a failed step.
+fn run_step(name: &str, f: impl FnOnce() -> rusqlite::Result<(bool, String)>) -> MaintenanceStep {
+    match f() {
+        Ok((ok, detail)) => MaintenanceStep::new(name, ok, detail),
+        Err(e) => MaintenanceStep::new(name, false, e.to_string()),
+    }
+}
+
+fn wal_checkpoint(conn: &Connection) -> rusqlite::Result<(bool, String)> {
+    let (busy, log, checkpointed): (i64, i64, i64) =
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?;
+    if log < 0 {
+        return Ok((true, "not in WAL mode".into()));
+    }
+    // busy means a reader kept the checkpoint from finishing
+    Ok((
+        busy == 0,
+        format!("{checkpointed} of {log} WAL pages checkpointed"),
+    ))
+}
+
+fn vacuum(conn: &Connection) -> rusqlite::Result<(bool, String)> {
+    let freelist = || conn.query_row("PRAGMA freelist_count", [], |row| row.get::<_, i64>(0));
+    let before = freelist()?;
+    let auto_vacuum: i64 = conn.query_row("PRAGMA auto_vacuum", [], |row| row.get(0))?;
+    // 2 is INCREMENTAL. Switching an existing database to it takes one full
+    // VACUUM; later passes only release the free pages.
+    let detail = if auto_vacuum == 2 {
+        conn.execute_batch("PRAGMA incremental_vacuum;")?;
+        format!("{} free pages released", before - freelist()?)
+    } else {
+        conn.execute_batch("PRAGMA auto_vacuum = INCREMENTAL; VACUUM;")?;
+        format!("switched to incremental auto-vacuum, {before} free pages released")
+    };
+    Ok((true, detail))
+}
+
+fn integrity_check(conn: &Connection) -> rusqlite::Result<(bool, String)> {
+    // At most 20 problems; the first few are enough to act on
+    let mut stmt = conn.prepare("PRAGMA integrity_check(20)")?;
+    let problems = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    if problems == ["ok"] {
+        Ok((true, "ok".into()))
+    } else {
+        Ok((false, problems.join("; ")))
+    }
+}
@@ -0,0 +1,96 @@
+use chrono::Utc;
+use rusqlite::{params, Row};
+
+use flowstate_core::subscription::{CreateSubscription, NotifyChannel, Subscription};
+
+use super::super::{SqliteDatabase, SqliteResultExt};
+use crate::DbError;
+
+pub(crate) fn row_to_subscription(row: &Row) -> rusqlite::Result<Subscription> {
+    let channel: String = row.get("channel")?;
+    let events: String = row.get("events")?;
+    Ok(Subscription {
+        id: row.get("id")?,
+        channel: NotifyChannel::parse_str(&channel).unwrap_or(NotifyChannel::Webhook),
+        target: row.get("target")?,
+        events: serde_json::from_str(&events).unwrap_or_default(),
+        project_id: row.get("project_id")?,
+        user_id: row.get("user_id")?,
+        created_at: row.get("created_at")?,
+    })
+}
+
+impl SqliteDatabase {
+    pub fn create_subscription_sync(
+        &self,
+        input: &CreateSubscription,
+    ) -> Result<Subscription, DbError> {
+        self.with_conn(|conn| {
+            let id = uuid::Uuid::new_v4().to_string();
+            let events = serde_json::to_string(&input.events)
+                .map_err(|e| DbError::Internal(e.to_string()))?;
+            conn.execute(
+                "INSERT INTO subscriptions (id, channel, target, events, project_id, user_id, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    id,
+                    input.channel.as_str(),
+                    input.target.trim(),
+                    events,
+                    input.project_id,
+                    input.user_id,
+                    Utc::now()
+                ],
+            )
+            .to_db()?;
+            conn.query_row(
+                "SELECT * FROM subscriptions WHERE id = ?1",
+                params![id],
+                row_to_subscription,
+            )
+            .to_db()
+        })
+    }
+
+    pub fn get_subscription_sync(&self, id: &str) -> Result<Subscription, DbError> {
+        self.with_read_conn(|conn| {
+            conn.query_row(
+                "SELECT * FROM subscriptions WHERE id = ?1",
+                params![id],
+                row_to_subscription,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    DbError::NotFound(format!("subscription {id}"))
+                }
+                other => DbError::Internal(other.to_string()),
+            })
+        })
+    }
+
+    pub fn list_subscriptions_sync(&self) -> Result<Vec<Subscription>, DbError> {
+        self.with_read_conn(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT * FROM subscriptions ORDER BY created_at, id")
+                .to_db()?;
+            let subscriptions = stmt
+                .query_map([], row_to_subscription)
+                .to_db()?
+                .collect::<Result<Vec<_>, _>>()
+                .to_db()?;
+            Ok(subscriptions)
+        })
+    }
+
+    pub fn delete_subscription_sync(&self, id: &str) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            let changed = conn
+                .execute("DELETE FROM subscriptions WHERE id = ?1", params![id])
+                .to_db()?;
+            if changed == 0 {
+                return Err(DbError::NotFound(format!("subscription {id}")));
+            }
+            Ok(())
+        })
+    }
+}
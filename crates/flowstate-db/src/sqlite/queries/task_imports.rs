@@ -0,0 +1,50 @@
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension, Row};
+
+use super::super::{SqliteDatabase, SqliteResultExt};
+use crate::snapshot::TaskImport;
+use crate::DbError;
+
+pub(crate) fn row_to_task_import(row: &Row) -> rusqlite::Result<TaskImport> {
+    Ok(TaskImport {
+        project_id: row.get("project_id")?,
+        source: row.get("source")?,
+        task_id: row.get("task_id")?,
+        created_at: row.get("created_at")?,
+    })
+}
+
+impl SqliteDatabase {
+    pub fn find_imported_task_sync(
+        &self,
+        project_id: &str,
+        source: &str,
+    ) -> Result<Option<String>, DbError> {
+        self.with_read_conn(|conn| {
+            conn.query_row(
+                "SELECT task_id FROM task_imports WHERE project_id = ?1 AND source = ?2",
+                params![project_id, source],
+                |row| row.get(0),
+            )
+            .optional()
+            .to_db()
+        })
+    }
+
+    pub fn record_task_import_sync(
+        &self,
+        project_id: &str,
+        source: &str,
+        task_id: &str,
+    ) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT OR IGNORE INTO task_imports (project_id, source, task_id, created_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![project_id, source, task_id, Utc::now()],
+            )
+            .to_db()?;
+            Ok(())
+        })
+    }
+}
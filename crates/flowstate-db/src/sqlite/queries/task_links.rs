@@ -1,12 +1,12 @@
 use chrono::Utc;
-use rusqlite::{params, Row};
+use rusqlite::{params, OptionalExtension, Row};
 
 use flowstate_core::task_link::{CreateTaskLink, LinkType, TaskLink};
 
 use super::super::{SqliteDatabase, SqliteResultExt};
 use crate::DbError;
 
-fn row_to_task_link(row: &Row) -> rusqlite::Result<TaskLink> {
+pub(crate) fn row_to_task_link(row: &Row) -> rusqlite::Result<TaskLink> {
     let link_type_str: String = row.get("link_type")?;
     Ok(TaskLink {
         id: row.get("id")?,
@@ -43,8 +43,21 @@ impl SqliteDatabase {
         })
     }
 
+    pub fn get_task_link_sync(&self, id: &str) -> Result<TaskLink, DbError> {
+        self.with_read_conn(|conn| {
+            conn.query_row(
+                "SELECT * FROM task_links WHERE id = ?1",
+                params![id],
+                row_to_task_link,
+            )
+            .optional()
+            .to_db()?
+            .ok_or_else(|| DbError::NotFound(format!("task_link {id}")))
+        })
+    }
+
     pub fn list_task_links_sync(&self, task_id: &str) -> Result<Vec<TaskLink>, DbError> {
-        self.with_conn(|conn| {
+        self.with_read_conn(|conn| {
             let mut stmt = conn
                 .prepare(
                     "SELECT * FROM task_links
@@ -78,7 +91,7 @@ impl SqliteDatabase {
 mod tests {
     use crate::Db;
     use flowstate_core::project::CreateProject;
-    use flowstate_core::task::{CreateTask, Priority, Status};
+    use flowstate_core::task::{CreateTask, Priority, Status, TaskType};
     use flowstate_core::task_link::{CreateTaskLink, LinkType};
 
     #[test]
@@ -100,6 +113,7 @@ mod tests {
                 description: String::new(),
                 status: Status::Todo,
                 priority: Priority::Medium,
+                task_type: TaskType::Feature,
                 parent_id: None,
                 reviewer: String::new(),
                 research_capability: None,
@@ -107,6 +121,7 @@ mod tests {
                 plan_capability: None,
                 build_capability: None,
                 verify_capability: None,
+                due_at: None,
             })
             .unwrap();
         let t2 = db
@@ -116,6 +131,7 @@ mod tests {
                 description: String::new(),
                 status: Status::Todo,
                 priority: Priority::Medium,
+                task_type: TaskType::Feature,
                 parent_id: None,
                 reviewer: String::new(),
                 research_capability: None,
@@ -123,6 +139,7 @@ mod tests {
                 plan_capability: None,
                 build_capability: None,
                 verify_capability: None,
+                due_at: None,
             })
             .unwrap();
 
@@ -1,12 +1,12 @@
 use chrono::Utc;
 use rusqlite::{params, Row};
 
-use flowstate_core::task_pr::{CreateTaskPr, TaskPr};
+use flowstate_core::task_pr::{ChecksStatus, CreateTaskPr, PrState, TaskPr};
 
 use super::super::{SqliteDatabase, SqliteResultExt};
 use crate::DbError;
 
-fn row_to_task_pr(row: &Row) -> rusqlite::Result<TaskPr> {
+pub(crate) fn row_to_task_pr(row: &Row) -> rusqlite::Result<TaskPr> {
     Ok(TaskPr {
         id: row.get("id")?,
         task_id: row.get("task_id")?,
@@ -14,6 +14,11 @@ fn row_to_task_pr(row: &Row) -> rusqlite::Result<TaskPr> {
         pr_url: row.get("pr_url")?,
         pr_number: row.get("pr_number")?,
         branch_name: row.get("branch_name")?,
+        state: PrState::parse_str(&row.get::<_, String>("state")?).unwrap_or_default(),
+        checks_status: row
+            .get::<_, Option<String>>("checks_status")?
+            .and_then(|s| ChecksStatus::parse_str(&s)),
+        draft: row.get("draft")?,
         created_at: row.get("created_at")?,
     })
 }
@@ -24,8 +29,8 @@ impl SqliteDatabase {
             let id = uuid::Uuid::new_v4().to_string();
             let now = Utc::now();
             conn.execute(
-                "INSERT OR IGNORE INTO task_prs (id, task_id, claude_run_id, pr_url, pr_number, branch_name, created_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                "INSERT OR IGNORE INTO task_prs (id, task_id, claude_run_id, pr_url, pr_number, branch_name, draft, created_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
                 params![
                     id,
                     input.task_id,
@@ -33,6 +38,7 @@ impl SqliteDatabase {
                     input.pr_url,
                     input.pr_number,
                     input.branch_name,
+                    input.draft,
                     now,
                 ],
             )
@@ -47,7 +53,7 @@ impl SqliteDatabase {
     }
 
     pub fn list_task_prs_sync(&self, task_id: &str) -> Result<Vec<TaskPr>, DbError> {
-        self.with_conn(|conn| {
+        self.with_read_conn(|conn| {
             let mut stmt = conn
                 .prepare("SELECT * FROM task_prs WHERE task_id = ?1 ORDER BY created_at DESC")
                 .to_db()?;
@@ -59,6 +65,96 @@ impl SqliteDatabase {
             Ok(prs)
         })
     }
+
+    pub fn get_task_pr_sync(&self, id: &str) -> Result<TaskPr, DbError> {
+        self.with_read_conn(|conn| {
+            conn.query_row(
+                "SELECT * FROM task_prs WHERE id = ?1",
+                params![id],
+                row_to_task_pr,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => DbError::NotFound(format!("task pr {id}")),
+                other => DbError::Internal(other.to_string()),
+            })
+        })
+    }
+
+    pub fn get_task_pr_by_url_sync(&self, pr_url: &str) -> Result<TaskPr, DbError> {
+        self.with_read_conn(|conn| {
+            conn.query_row(
+                "SELECT * FROM task_prs WHERE pr_url = ?1",
+                params![pr_url],
+                row_to_task_pr,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    DbError::NotFound(format!("task pr {pr_url}"))
+                }
+                other => DbError::Internal(other.to_string()),
+            })
+        })
+    }
+
+    pub fn set_task_pr_state_sync(&self, id: &str, state: PrState) -> Result<TaskPr, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(
+                "UPDATE task_prs SET state = ?1 WHERE id = ?2 RETURNING *",
+                params![state.as_str(), id],
+                row_to_task_pr,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => DbError::NotFound(format!("task pr {id}")),
+                other => DbError::Internal(other.to_string()),
+            })
+        })
+    }
+
+    pub fn set_task_pr_checks_sync(
+        &self,
+        id: &str,
+        checks_status: Option<ChecksStatus>,
+    ) -> Result<TaskPr, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(
+                "UPDATE task_prs SET checks_status = ?1 WHERE id = ?2 RETURNING *",
+                params![checks_status.map(|s| s.as_str()), id],
+                row_to_task_pr,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => DbError::NotFound(format!("task pr {id}")),
+                other => DbError::Internal(other.to_string()),
+            })
+        })
+    }
+
+    pub fn set_task_pr_draft_sync(&self, id: &str, draft: bool) -> Result<TaskPr, DbError> {
+        self.with_conn(|conn| {
+            conn.query_row(
+                "UPDATE task_prs SET draft = ?1 WHERE id = ?2 RETURNING *",
+                params![draft, id],
+                row_to_task_pr,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => DbError::NotFound(format!("task pr {id}")),
+                other => DbError::Internal(other.to_string()),
+            })
+        })
+    }
+
+    pub fn list_open_task_prs_sync(&self) -> Result<Vec<TaskPr>, DbError> {
+        self.with_read_conn(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT * FROM task_prs WHERE state = 'open' ORDER BY created_at")
+                .to_db()?;
+            let prs = stmt
+                .query_map([], row_to_task_pr)
+                .to_db()?
+                .collect::<Result<Vec<_>, _>>()
+                .to_db()?;
+            Ok(prs)
+        })
+    }
 }
 
 #[cfg(test)]
@@ -66,8 +162,8 @@ mod tests {
     use crate::Db;
     use flowstate_core::claude_run::{ClaudeAction, CreateClaudeRun};
     use flowstate_core::project::CreateProject;
-    use flowstate_core::task::{CreateTask, Priority, Status};
-    use flowstate_core::task_pr::CreateTaskPr;
+    use flowstate_core::task::{CreateTask, Priority, Status, TaskType};
+    use flowstate_core::task_pr::{ChecksStatus, CreateTaskPr, PrState};
     use rusqlite::params;
 
     fn setup_db() -> (Db, String, String) {
@@ -87,6 +183,7 @@ mod tests {
                 description: String::new(),
                 status: Status::Todo,
                 priority: Priority::Medium,
+                task_type: TaskType::Feature,
                 parent_id: None,
                 reviewer: String::new(),
                 research_capability: None,
@@ -94,6 +191,7 @@ mod tests {
                 plan_capability: None,
                 build_capability: None,
                 verify_capability: None,
+                due_at: None,
             })
             .unwrap();
         (db, project.id, task.id)
@@ -107,6 +205,11 @@ mod tests {
                 task_id: task_id.clone(),
                 action: ClaudeAction::Build,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .unwrap();
 
@@ -117,6 +220,7 @@ mod tests {
                 pr_url: "https://github.com/owner/repo/pull/42".into(),
                 pr_number: 42,
                 branch_name: "flowstate/my-feature".into(),
+                draft: false,
             })
             .unwrap();
 
@@ -129,6 +233,86 @@ mod tests {
         let prs = db.list_task_prs_sync(&task_id).unwrap();
         assert_eq!(prs.len(), 1);
         assert_eq!(prs[0].id, pr.id);
+        assert_eq!(prs[0].state, PrState::Open);
+    }
+
+    #[test]
+    fn test_find_by_url_and_set_state() {
+        let (db, _project_id, task_id) = setup_db();
+        let url = "https://github.com/owner/repo/pull/3";
+        let pr = db
+            .create_task_pr_sync(&CreateTaskPr {
+                task_id,
+                claude_run_id: None,
+                pr_url: url.into(),
+                pr_number: 3,
+                branch_name: "flowstate/feat".into(),
+                draft: false,
+            })
+            .unwrap();
+
+        assert_eq!(db.get_task_pr_by_url_sync(url).unwrap().id, pr.id);
+        assert!(matches!(
+            db.get_task_pr_by_url_sync("https://github.com/owner/repo/pull/4"),
+            Err(crate::DbError::NotFound(_))
+        ));
+
+        let merged = db.set_task_pr_state_sync(&pr.id, PrState::Merged).unwrap();
+        assert_eq!(merged.state, PrState::Merged);
+        assert_eq!(
+            db.get_task_pr_by_url_sync(url).unwrap().state,
+            PrState::Merged
+        );
+    }
+
+    #[test]
+    fn test_checks_status_and_open_prs() {
+        let (db, _project_id, task_id) = setup_db();
+        let create = |n: i64| {
+            db.create_task_pr_sync(&CreateTaskPr {
+                task_id: task_id.clone(),
+                claude_run_id: None,
+                pr_url: format!("https://github.com/owner/repo/pull/{n}"),
+                pr_number: n,
+                branch_name: format!("flowstate/feat-{n}"),
+                draft: false,
+            })
+            .unwrap()
+        };
+        let (open, merged) = (create(1), create(2));
+        assert_eq!(open.checks_status, None);
+        db.set_task_pr_state_sync(&merged.id, PrState::Merged)
+            .unwrap();
+
+        let pr = db
+            .set_task_pr_checks_sync(&open.id, Some(ChecksStatus::Pending))
+            .unwrap();
+        assert_eq!(pr.checks_status, Some(ChecksStatus::Pending));
+        let pr = db.set_task_pr_checks_sync(&open.id, None).unwrap();
+        assert_eq!(pr.checks_status, None);
+
+        let prs = db.list_open_task_prs_sync().unwrap();
+        assert_eq!(prs.len(), 1);
+        assert_eq!(prs[0].id, open.id);
+    }
+
+    #[test]
+    fn test_draft_prs() {
+        let (db, _project_id, task_id) = setup_db();
+        let pr = db
+            .create_task_pr_sync(&CreateTaskPr {
+                task_id,
+                claude_run_id: None,
+                pr_url: "https://github.com/owner/repo/pull/6".into(),
+                pr_number: 6,
+                branch_name: "flowstate/feat".into(),
+                draft: true,
+            })
+            .unwrap();
+        assert!(pr.draft);
+        let ready = db.set_task_pr_draft_sync(&pr.id, false).unwrap();
+        assert!(!ready.draft);
+        assert!(db.set_task_pr_draft_sync("missing", false).is_err());
     }
 
     #[test]
@@ -142,6 +326,7 @@ mod tests {
                 pr_url: "https://github.com/owner/repo/pull/1".into(),
                 pr_number: 1,
                 branch_name: "flowstate/feat-1".into(),
+                draft: false,
             })
             .unwrap();
 
@@ -152,6 +337,7 @@ mod tests {
                 pr_url: "https://github.com/owner/repo/pull/1".into(),
                 pr_number: 1,
                 branch_name: "flowstate/feat-1".into(),
+                draft: false,
             })
             .unwrap();
 
@@ -170,6 +356,7 @@ mod tests {
             pr_url: "https://github.com/owner/repo/pull/10".into(),
             pr_number: 10,
             branch_name: "flowstate/feat-a".into(),
+            draft: false,
         })
         .unwrap();
 
@@ -179,6 +366,7 @@ mod tests {
             pr_url: "https://github.com/owner/repo/pull/11".into(),
             pr_number: 11,
             branch_name: "flowstate/feat-b".into(),
+            draft: false,
         })
         .unwrap();
 
@@ -196,6 +384,7 @@ mod tests {
             pr_url: "https://github.com/owner/repo/pull/5".into(),
             pr_number: 5,
             branch_name: "flowstate/feat".into(),
+            draft: false,
         })
         .unwrap();
 
@@ -212,6 +401,11 @@ mod tests {
                 task_id: task_id.clone(),
                 action: ClaudeAction::Build,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .unwrap();
 
@@ -222,6 +416,7 @@ mod tests {
                 pr_url: "https://github.com/owner/repo/pull/7".into(),
                 pr_number: 7,
                 branch_name: "flowstate/feat".into(),
+                draft: false,
             })
             .unwrap();
 
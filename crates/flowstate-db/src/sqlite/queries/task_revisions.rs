@@ -0,0 +1,65 @@
+use chrono::Utc;
+use rusqlite::{params, Connection, Row};
+
+use flowstate_core::task_revision::{FieldChange, TaskRevision};
+
+use super::super::{SqliteDatabase, SqliteResultExt};
+use crate::DbError;
+
+pub(crate) fn row_to_task_revision(row: &Row) -> rusqlite::Result<TaskRevision> {
+    let changes_json: String = row.get("changes")?;
+    let changes = serde_json::from_str(&changes_json).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+    Ok(TaskRevision {
+        id: row.get("id")?,
+        task_id: row.get("task_id")?,
+        actor: row.get("actor")?,
+        changes,
+        created_at: row.get("created_at")?,
+    })
+}
+
+/// Record a revision on an open connection, so callers can include it in the
+/// same transaction as the task update it describes.
+pub(crate) fn insert_task_revision(
+    conn: &Connection,
+    task_id: &str,
+    actor: &str,
+    changes: &[FieldChange],
+) -> Result<(), DbError> {
+    let changes_json =
+        serde_json::to_string(changes).map_err(|e| DbError::Internal(e.to_string()))?;
+    conn.execute(
+        "INSERT INTO task_revisions (id, task_id, actor, changes, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            uuid::Uuid::new_v4().to_string(),
+            task_id,
+            actor,
+            changes_json,
+            Utc::now(),
+        ],
+    )
+    .to_db()?;
+    Ok(())
+}
+
+impl SqliteDatabase {
+    pub fn list_task_revisions_sync(&self, task_id: &str) -> Result<Vec<TaskRevision>, DbError> {
+        self.with_read_conn(|conn| {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT * FROM task_revisions WHERE task_id = ?1
+                     ORDER BY created_at DESC, rowid DESC",
+                )
+                .to_db()?;
+            let revisions = stmt
+                .query_map(params![task_id], row_to_task_revision)
+                .to_db()?
+                .collect::<Result<Vec<_>, _>>()
+                .to_db()?;
+            Ok(revisions)
+        })
+    }
+}
@@ -1,17 +1,25 @@
 use chrono::Utc;
-use rusqlite::{params, Row};
+use rusqlite::{params, params_from_iter, Connection, Row};
 
+use flowstate_core::feedback::new_rejections;
 use flowstate_core::runner::RunnerCapability;
 use flowstate_core::task::{
-    ApprovalStatus, CreateTask, Priority, Status, Task, TaskFilter, UpdateTask,
+    ApprovalStatus, CreateTask, Priority, Status, Task, TaskFilter, TaskType, UpdateTask,
 };
+use flowstate_core::task_revision::diff_tasks;
 
 use super::super::{SqliteDatabase, SqliteResultExt};
+use super::custom_fields::set_task_field_values_in;
+use super::feedback_history::insert_feedback_entry;
+use super::task_revisions::insert_task_revision;
+use super::watchers::notify_watchers;
+use crate::query::{task_filter_query, Dialect};
 use crate::DbError;
 
-fn row_to_task(row: &Row) -> rusqlite::Result<Task> {
+pub(crate) fn row_to_task(row: &Row) -> rusqlite::Result<Task> {
     let status_str: String = row.get("status")?;
     let priority_str: String = row.get("priority")?;
+    let task_type_str: String = row.get("task_type")?;
     let spec_status_str: String = row.get("spec_status")?;
     let plan_status_str: String = row.get("plan_status")?;
     let research_status_str: String = row.get("research_status")?;
@@ -25,6 +33,8 @@ fn row_to_task(row: &Row) -> rusqlite::Result<Task> {
         id: row.get("id")?,
         project_id: row.get("project_id")?,
         sprint_id: row.get("sprint_id")?,
+        epic_id: row.get("epic_id")?,
+        assignee_id: row.get("assignee_id")?,
         parent_id: row.get("parent_id")?,
         title: row.get("title")?,
         description: row.get("description")?,
@@ -43,73 +53,268 @@ fn row_to_task(row: &Row) -> rusqlite::Result<Task> {
         verify_feedback: row.get("verify_feedback")?,
         status: Status::parse_str(&status_str).unwrap_or(Status::Todo),
         priority: Priority::parse_str(&priority_str).unwrap_or(Priority::Medium),
+        task_type: TaskType::parse_str(&task_type_str).unwrap_or_default(),
+        due_at: row.get("due_at")?,
         research_capability: research_cap_str.and_then(|s| RunnerCapability::parse_str(&s)),
         design_capability: design_cap_str.and_then(|s| RunnerCapability::parse_str(&s)),
         plan_capability: plan_cap_str.and_then(|s| RunnerCapability::parse_str(&s)),
         build_capability: build_cap_str.and_then(|s| RunnerCapability::parse_str(&s)),
         verify_capability: verify_cap_str.and_then(|s| RunnerCapability::parse_str(&s)),
         sort_order: row.get("sort_order")?,
+        archived: row.get("archived")?,
+        autopilot: row.get("autopilot")?,
         created_at: row.get("created_at")?,
         updated_at: row.get("updated_at")?,
     })
 }
 
+/// Insert a task on `conn`, which may be a transaction shared with other writes.
+fn create_task_in(conn: &Connection, input: &CreateTask) -> Result<Task, DbError> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let now = Utc::now();
+
+    // Get next sort_order for this project+status
+    let max_order: f64 = conn
+        .query_row(
+            "SELECT COALESCE(MAX(sort_order), 0) FROM tasks
+             WHERE project_id = ?1 AND status = ?2",
+            params![input.project_id, input.status.as_str()],
+            |row| row.get(0),
+        )
+        .unwrap_or(0.0);
+
+    conn.execute(
+        "INSERT INTO tasks (
+            id, project_id, parent_id, title, description, reviewer, status, priority, sort_order, created_at, updated_at,
+            research_capability, design_capability, plan_capability, build_capability, verify_capability, due_at,
+            task_type
+         )
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)",
+        params![
+            id,
+            input.project_id,
+            input.parent_id,
+            input.title,
+            input.description,
+            input.reviewer,
+            input.status.as_str(),
+            input.priority.as_str(),
+            max_order + 1.0,
+            now,
+            now,
+            input.research_capability.map(|c| c.as_str().to_string()),
+            input.design_capability.map(|c| c.as_str().to_string()),
+            input.plan_capability.map(|c| c.as_str().to_string()),
+            input.build_capability.map(|c| c.as_str().to_string()),
+            input.verify_capability.map(|c| c.as_str().to_string()),
+            input.due_at,
+            input.task_type.as_str(),
+        ],
+    )
+    .to_db()?;
+
+    let task = conn
+        .query_row(
+            "SELECT * FROM tasks WHERE id = ?1",
+            params![id],
+            row_to_task,
+        )
+        .to_db()?;
+    Ok(task)
+}
+
+/// Apply `update` on `conn` and record the resulting revision, if any.
+fn update_task_in(conn: &Connection, id: &str, update: &UpdateTask) -> Result<Task, DbError> {
+    let before = conn
+        .query_row(
+            "SELECT * FROM tasks WHERE id = ?1",
+            params![id],
+            row_to_task,
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => DbError::NotFound(format!("task {id}")),
+            other => DbError::Internal(other.to_string()),
+        })?;
+
+    let now = Utc::now();
+    let mut sets = vec!["updated_at = ?1".to_string()];
+    let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> = vec![Box::new(now)];
+
+    if let Some(ref title) = update.title {
+        param_values.push(Box::new(title.clone()));
+        sets.push(format!("title = ?{}", param_values.len()));
+    }
+    if let Some(ref description) = update.description {
+        param_values.push(Box::new(description.clone()));
+        sets.push(format!("description = ?{}", param_values.len()));
+    }
+    if let Some(status) = update.status {
+        param_values.push(Box::new(status.as_str().to_string()));
+        sets.push(format!("status = ?{}", param_values.len()));
+    }
+    if let Some(priority) = update.priority {
+        param_values.push(Box::new(priority.as_str().to_string()));
+        sets.push(format!("priority = ?{}", param_values.len()));
+    }
+    if let Some(task_type) = update.task_type {
+        param_values.push(Box::new(task_type.as_str().to_string()));
+        sets.push(format!("task_type = ?{}", param_values.len()));
+    }
+    if let Some(ref sprint_id) = update.sprint_id {
+        param_values.push(Box::new(sprint_id.clone()));
+        sets.push(format!("sprint_id = ?{}", param_values.len()));
+    }
+    if let Some(ref epic_id) = update.epic_id {
+        param_values.push(Box::new(epic_id.clone()));
+        sets.push(format!("epic_id = ?{}", param_values.len()));
+    }
+    if let Some(ref assignee_id) = update.assignee_id {
+        param_values.push(Box::new(assignee_id.clone()));
+        sets.push(format!("assignee_id = ?{}", param_values.len()));
+    }
+    if let Some(due_at) = update.due_at {
+        param_values.push(Box::new(due_at));
+        sets.push(format!("due_at = ?{}", param_values.len()));
+    }
+    if let Some(sort_order) = update.sort_order {
+        param_values.push(Box::new(sort_order));
+        sets.push(format!("sort_order = ?{}", param_values.len()));
+    }
+    if let Some(ref parent_id) = update.parent_id {
+        param_values.push(Box::new(parent_id.clone()));
+        sets.push(format!("parent_id = ?{}", param_values.len()));
+    }
+    if let Some(ref reviewer) = update.reviewer {
+        param_values.push(Box::new(reviewer.clone()));
+        sets.push(format!("reviewer = ?{}", param_values.len()));
+    }
+    if let Some(research_status) = update.research_status {
+        param_values.push(Box::new(research_status.as_str().to_string()));
+        sets.push(format!("research_status = ?{}", param_values.len()));
+    }
+    if let Some(spec_status) = update.spec_status {
+        param_values.push(Box::new(spec_status.as_str().to_string()));
+        sets.push(format!("spec_status = ?{}", param_values.len()));
+    }
+    if let Some(plan_status) = update.plan_status {
+        param_values.push(Box::new(plan_status.as_str().to_string()));
+        sets.push(format!("plan_status = ?{}", param_values.len()));
+    }
+    if let Some(verify_status) = update.verify_status {
+        param_values.push(Box::new(verify_status.as_str().to_string()));
+        sets.push(format!("verify_status = ?{}", param_values.len()));
+    }
+    if let Some(ref hash) = update.spec_approved_hash {
+        param_values.push(Box::new(hash.clone()));
+        sets.push(format!("spec_approved_hash = ?{}", param_values.len()));
+    }
+    if let Some(ref hash) = update.research_approved_hash {
+        param_values.push(Box::new(hash.clone()));
+        sets.push(format!("research_approved_hash = ?{}", param_values.len()));
+    }
+    if let Some(ref feedback) = update.research_feedback {
+        param_values.push(Box::new(feedback.clone()));
+        sets.push(format!("research_feedback = ?{}", param_values.len()));
+    }
+    if let Some(ref feedback) = update.spec_feedback {
+        param_values.push(Box::new(feedback.clone()));
+        sets.push(format!("spec_feedback = ?{}", param_values.len()));
+    }
+    if let Some(ref feedback) = update.plan_feedback {
+        param_values.push(Box::new(feedback.clone()));
+        sets.push(format!("plan_feedback = ?{}", param_values.len()));
+    }
+    if let Some(ref feedback) = update.verify_feedback {
+        param_values.push(Box::new(feedback.clone()));
+        sets.push(format!("verify_feedback = ?{}", param_values.len()));
+    }
+    if let Some(cap) = &update.research_capability {
+        param_values.push(Box::new(cap.map(|c| c.as_str().to_string())));
+        sets.push(format!("research_capability = ?{}", param_values.len()));
+    }
+    if let Some(cap) = &update.design_capability {
+        param_values.push(Box::new(cap.map(|c| c.as_str().to_string())));
+        sets.push(format!("design_capability = ?{}", param_values.len()));
+    }
+    if let Some(cap) = &update.plan_capability {
+        param_values.push(Box::new(cap.map(|c| c.as_str().to_string())));
+        sets.push(format!("plan_capability = ?{}", param_values.len()));
+    }
+    if let Some(cap) = &update.build_capability {
+        param_values.push(Box::new(cap.map(|c| c.as_str().to_string())));
+        sets.push(format!("build_capability = ?{}", param_values.len()));
+    }
+    if let Some(cap) = &update.verify_capability {
+        param_values.push(Box::new(cap.map(|c| c.as_str().to_string())));
+        sets.push(format!("verify_capability = ?{}", param_values.len()));
+    }
+    if let Some(autopilot) = update.autopilot {
+        param_values.push(Box::new(autopilot));
+        sets.push(format!("autopilot = ?{}", param_values.len()));
+    }
+
+    param_values.push(Box::new(id.to_string()));
+    let id_param = param_values.len();
+
+    let sql = format!(
+        "UPDATE tasks SET {} WHERE id = ?{}",
+        sets.join(", "),
+        id_param
+    );
+
+    let params_ref: Vec<&dyn rusqlite::types::ToSql> =
+        param_values.iter().map(|p| p.as_ref()).collect();
+
+    let changed = conn.execute(&sql, params_ref.as_slice()).to_db()?;
+    if changed == 0 {
+        return Err(DbError::NotFound(format!("task {id}")));
+    }
+
+    let after = conn
+        .query_row(
+            "SELECT * FROM tasks WHERE id = ?1",
+            params![id],
+            row_to_task,
+        )
+        .map_err(|e| DbError::Internal(e.to_string()))?;
+
+    let mut changes = diff_tasks(&before, &after);
+    changes.extend(set_task_field_values_in(
+        conn,
+        id,
+        &after.project_id,
+        &update.custom_fields,
+    )?);
+    let actor = update.actor.as_deref().unwrap_or_default();
+    if !changes.is_empty() {
+        insert_task_revision(conn, id, actor, &changes)?;
+        notify_watchers(conn, id, actor, &changes)?;
+    }
+    for (phase, feedback) in new_rejections(&before, &after) {
+        insert_feedback_entry(conn, id, phase, feedback, actor)?;
+    }
+    Ok(after)
+}
+
 impl SqliteDatabase {
     pub fn create_task_sync(&self, input: &CreateTask) -> Result<Task, DbError> {
-        self.with_conn(|conn| {
-            let id = uuid::Uuid::new_v4().to_string();
-            let now = Utc::now();
-
-            // Get next sort_order for this project+status
-            let max_order: f64 = conn
-                .query_row(
-                    "SELECT COALESCE(MAX(sort_order), 0) FROM tasks
-                     WHERE project_id = ?1 AND status = ?2",
-                    params![input.project_id, input.status.as_str()],
-                    |row| row.get(0),
-                )
-                .unwrap_or(0.0);
-
-            conn.execute(
-                "INSERT INTO tasks (
-                    id, project_id, parent_id, title, description, reviewer, status, priority, sort_order, created_at, updated_at,
-                    research_capability, design_capability, plan_capability, build_capability, verify_capability
-                 )
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)",
-                params![
-                    id,
-                    input.project_id,
-                    input.parent_id,
-                    input.title,
-                    input.description,
-                    input.reviewer,
-                    input.status.as_str(),
-                    input.priority.as_str(),
-                    max_order + 1.0,
-                    now,
-                    now,
-                    input.research_capability.map(|c| c.as_str().to_string()),
-                    input.design_capability.map(|c| c.as_str().to_string()),
-                    input.plan_capability.map(|c| c.as_str().to_string()),
-                    input.build_capability.map(|c| c.as_str().to_string()),
-                    input.verify_capability.map(|c| c.as_str().to_string()),
-                ],
-            )
-            .to_db()?;
+        self.with_conn(|conn| create_task_in(conn, input))
+    }
 
-            let task = conn
-                .query_row(
-                    "SELECT * FROM tasks WHERE id = ?1",
-                    params![id],
-                    row_to_task,
-                )
-                .to_db()?;
-            Ok(task)
+    pub fn bulk_create_tasks_sync(&self, inputs: &[CreateTask]) -> Result<Vec<Task>, DbError> {
+        self.with_conn(|conn| {
+            let tx = conn.unchecked_transaction().to_db()?;
+            let tasks = inputs
+                .iter()
+                .map(|input| create_task_in(&tx, input))
+                .collect::<Result<Vec<_>, _>>()?;
+            tx.commit().to_db()?;
+            Ok(tasks)
         })
     }
 
     pub fn get_task_sync(&self, id: &str) -> Result<Task, DbError> {
-        self.with_conn(|conn| {
+        self.with_read_conn(|conn| {
             conn.query_row(
                 "SELECT * FROM tasks WHERE id = ?1",
                 params![id],
@@ -123,51 +328,11 @@ impl SqliteDatabase {
     }
 
     pub fn list_tasks_sync(&self, filter: &TaskFilter) -> Result<Vec<Task>, DbError> {
-        self.with_conn(|conn| {
-            let mut sql = String::from("SELECT * FROM tasks WHERE 1=1");
-            let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> = Vec::new();
-
-            if let Some(ref project_id) = filter.project_id {
-                param_values.push(Box::new(project_id.clone()));
-                sql.push_str(&format!(" AND project_id = ?{}", param_values.len()));
-            }
-            if let Some(status) = filter.status {
-                param_values.push(Box::new(status.as_str().to_string()));
-                sql.push_str(&format!(" AND status = ?{}", param_values.len()));
-            }
-            if let Some(priority) = filter.priority {
-                param_values.push(Box::new(priority.as_str().to_string()));
-                sql.push_str(&format!(" AND priority = ?{}", param_values.len()));
-            }
-            if let Some(ref sprint_id) = filter.sprint_id {
-                param_values.push(Box::new(sprint_id.clone()));
-                sql.push_str(&format!(" AND sprint_id = ?{}", param_values.len()));
-            }
-            if let Some(ref parent_id_filter) = filter.parent_id {
-                match parent_id_filter {
-                    None => {
-                        sql.push_str(" AND parent_id IS NULL");
-                    }
-                    Some(pid) => {
-                        param_values.push(Box::new(pid.clone()));
-                        sql.push_str(&format!(" AND parent_id = ?{}", param_values.len()));
-                    }
-                }
-            }
-
-            sql.push_str(" ORDER BY sort_order ASC");
-
-            if let Some(limit) = filter.limit {
-                param_values.push(Box::new(limit));
-                sql.push_str(&format!(" LIMIT ?{}", param_values.len()));
-            }
-
-            let params_ref: Vec<&dyn rusqlite::types::ToSql> =
-                param_values.iter().map(|p| p.as_ref()).collect();
-
-            let mut stmt = conn.prepare(&sql).to_db()?;
+        self.with_read_conn(|conn| {
+            let query = task_filter_query(Dialect::Sqlite, filter);
+            let mut stmt = conn.prepare(query.sql()).to_db()?;
             let tasks = stmt
-                .query_map(params_ref.as_slice(), row_to_task)
+                .query_map(params_from_iter(query.values()), row_to_task)
                 .to_db()?
                 .collect::<Result<Vec<_>, _>>()
                 .to_db()?;
@@ -176,7 +341,7 @@ impl SqliteDatabase {
     }
 
     pub fn list_child_tasks_sync(&self, parent_id: &str) -> Result<Vec<Task>, DbError> {
-        self.with_conn(|conn| {
+        self.with_read_conn(|conn| {
             let mut stmt = conn
                 .prepare("SELECT * FROM tasks WHERE parent_id = ?1 ORDER BY sort_order ASC")
                 .to_db()?;
@@ -191,126 +356,102 @@ impl SqliteDatabase {
 
     pub fn update_task_sync(&self, id: &str, update: &UpdateTask) -> Result<Task, DbError> {
         self.with_conn(|conn| {
-            let now = Utc::now();
-            let mut sets = vec!["updated_at = ?1".to_string()];
-            let mut param_values: Vec<Box<dyn rusqlite::types::ToSql>> = vec![Box::new(now)];
-
-            if let Some(ref title) = update.title {
-                param_values.push(Box::new(title.clone()));
-                sets.push(format!("title = ?{}", param_values.len()));
-            }
-            if let Some(ref description) = update.description {
-                param_values.push(Box::new(description.clone()));
-                sets.push(format!("description = ?{}", param_values.len()));
-            }
-            if let Some(status) = update.status {
-                param_values.push(Box::new(status.as_str().to_string()));
-                sets.push(format!("status = ?{}", param_values.len()));
-            }
-            if let Some(priority) = update.priority {
-                param_values.push(Box::new(priority.as_str().to_string()));
-                sets.push(format!("priority = ?{}", param_values.len()));
-            }
-            if let Some(ref sprint_id) = update.sprint_id {
-                param_values.push(Box::new(sprint_id.clone()));
-                sets.push(format!("sprint_id = ?{}", param_values.len()));
-            }
-            if let Some(sort_order) = update.sort_order {
-                param_values.push(Box::new(sort_order));
-                sets.push(format!("sort_order = ?{}", param_values.len()));
-            }
-            if let Some(ref parent_id) = update.parent_id {
-                param_values.push(Box::new(parent_id.clone()));
-                sets.push(format!("parent_id = ?{}", param_values.len()));
-            }
-            if let Some(ref reviewer) = update.reviewer {
-                param_values.push(Box::new(reviewer.clone()));
-                sets.push(format!("reviewer = ?{}", param_values.len()));
-            }
-            if let Some(research_status) = update.research_status {
-                param_values.push(Box::new(research_status.as_str().to_string()));
-                sets.push(format!("research_status = ?{}", param_values.len()));
-            }
-            if let Some(spec_status) = update.spec_status {
-                param_values.push(Box::new(spec_status.as_str().to_string()));
-                sets.push(format!("spec_status = ?{}", param_values.len()));
-            }
-            if let Some(plan_status) = update.plan_status {
-                param_values.push(Box::new(plan_status.as_str().to_string()));
-                sets.push(format!("plan_status = ?{}", param_values.len()));
-            }
-            if let Some(verify_status) = update.verify_status {
-                param_values.push(Box::new(verify_status.as_str().to_string()));
-                sets.push(format!("verify_status = ?{}", param_values.len()));
-            }
-            if let Some(ref hash) = update.spec_approved_hash {
-                param_values.push(Box::new(hash.clone()));
-                sets.push(format!("spec_approved_hash = ?{}", param_values.len()));
-            }
-            if let Some(ref hash) = update.research_approved_hash {
-                param_values.push(Box::new(hash.clone()));
-                sets.push(format!("research_approved_hash = ?{}", param_values.len()));
-            }
-            if let Some(ref feedback) = update.research_feedback {
-                param_values.push(Box::new(feedback.clone()));
-                sets.push(format!("research_feedback = ?{}", param_values.len()));
-            }
-            if let Some(ref feedback) = update.spec_feedback {
-                param_values.push(Box::new(feedback.clone()));
-                sets.push(format!("spec_feedback = ?{}", param_values.len()));
-            }
-            if let Some(ref feedback) = update.plan_feedback {
-                param_values.push(Box::new(feedback.clone()));
-                sets.push(format!("plan_feedback = ?{}", param_values.len()));
-            }
-            if let Some(ref feedback) = update.verify_feedback {
-                param_values.push(Box::new(feedback.clone()));
-                sets.push(format!("verify_feedback = ?{}", param_values.len()));
-            }
-            if let Some(cap) = &update.research_capability {
-                param_values.push(Box::new(cap.map(|c| c.as_str().to_string())));
-                sets.push(format!("research_capability = ?{}", param_values.len()));
-            }
-            if let Some(cap) = &update.design_capability {
-                param_values.push(Box::new(cap.map(|c| c.as_str().to_string())));
-                sets.push(format!("design_capability = ?{}", param_values.len()));
-            }
-            if let Some(cap) = &update.plan_capability {
-                param_values.push(Box::new(cap.map(|c| c.as_str().to_string())));
-                sets.push(format!("plan_capability = ?{}", param_values.len()));
-            }
-            if let Some(cap) = &update.build_capability {
-                param_values.push(Box::new(cap.map(|c| c.as_str().to_string())));
-                sets.push(format!("build_capability = ?{}", param_values.len()));
-            }
-            if let Some(cap) = &update.verify_capability {
-                param_values.push(Box::new(cap.map(|c| c.as_str().to_string())));
-                sets.push(format!("verify_capability = ?{}", param_values.len()));
-            }
+            let tx = conn.unchecked_transaction().to_db()?;
+            let task = update_task_in(&tx, id, update)?;
+            tx.commit().to_db()?;
+            Ok(task)
+        })
+    }
 
-            param_values.push(Box::new(id.to_string()));
-            let id_param = param_values.len();
+    /// Apply the same update to every task in `ids`. All-or-nothing: a
+    /// missing id rolls back the whole batch.
+    pub fn bulk_update_tasks_sync(
+        &self,
+        ids: &[String],
+        update: &UpdateTask,
+    ) -> Result<Vec<Task>, DbError> {
+        self.with_conn(|conn| {
+            let tx = conn.unchecked_transaction().to_db()?;
+            let tasks = ids
+                .iter()
+                .map(|id| update_task_in(&tx, id, update))
+                .collect::<Result<Vec<_>, _>>()?;
+            tx.commit().to_db()?;
+            Ok(tasks)
+        })
+    }
 
-            let sql = format!(
-                "UPDATE tasks SET {} WHERE id = ?{}",
-                sets.join(", "),
-                id_param
-            );
+    /// Move `id` to directly after `after_id` (or to the top when `None`)
+    /// within its project+status column, renumbering the whole column so
+    /// the order is dense and unambiguous.
+    pub fn reorder_task_sync(&self, id: &str, after_id: Option<&str>) -> Result<Task, DbError> {
+        self.with_conn(|conn| {
+            let tx = conn.unchecked_transaction().to_db()?;
+            let task = tx
+                .query_row(
+                    "SELECT * FROM tasks WHERE id = ?1",
+                    params![id],
+                    row_to_task,
+                )
+                .map_err(|e| match e {
+                    rusqlite::Error::QueryReturnedNoRows => DbError::NotFound(format!("task {id}")),
+                    other => DbError::Internal(other.to_string()),
+                })?;
 
-            let params_ref: Vec<&dyn rusqlite::types::ToSql> =
-                param_values.iter().map(|p| p.as_ref()).collect();
+            let mut stmt = tx
+                .prepare(
+                    "SELECT id FROM tasks WHERE project_id = ?1 AND status = ?2 AND id != ?3
+                     ORDER BY sort_order ASC, created_at ASC",
+                )
+                .to_db()?;
+            let mut column = stmt
+                .query_map(params![task.project_id, task.status.as_str(), id], |row| {
+                    row.get::<_, String>(0)
+                })
+                .to_db()?
+                .collect::<Result<Vec<_>, _>>()
+                .to_db()?;
+            drop(stmt);
+
+            let position = match after_id {
+                None => 0,
+                Some(after_id) => {
+                    column
+                        .iter()
+                        .position(|other| other == after_id)
+                        .ok_or_else(|| {
+                            DbError::NotFound(format!(
+                                "task {after_id} in column {}",
+                                task.status.as_str()
+                            ))
+                        })?
+                        + 1
+                }
+            };
+            column.insert(position, id.to_string());
 
-            let changed = conn.execute(&sql, params_ref.as_slice()).to_db()?;
-            if changed == 0 {
-                return Err(DbError::NotFound(format!("task {id}")));
+            for (index, task_id) in column.iter().enumerate() {
+                tx.execute(
+                    "UPDATE tasks SET sort_order = ?1 WHERE id = ?2",
+                    params![(index + 1) as f64, task_id],
+                )
+                .to_db()?;
             }
-
-            conn.query_row(
-                "SELECT * FROM tasks WHERE id = ?1",
-                params![id],
-                row_to_task,
+            tx.execute(
+                "UPDATE tasks SET updated_at = ?1 WHERE id = ?2",
+                params![Utc::now(), id],
             )
-            .map_err(|e| DbError::Internal(e.to_string()))
+            .to_db()?;
+
+            let task = tx
+                .query_row(
+                    "SELECT * FROM tasks WHERE id = ?1",
+                    params![id],
+                    row_to_task,
+                )
+                .to_db()?;
+            tx.commit().to_db()?;
+            Ok(task)
         })
     }
 
@@ -326,15 +467,41 @@ impl SqliteDatabase {
         })
     }
 
+    /// Archive `id` and every task below it in the subtask tree.
+    pub fn archive_task_sync(&self, id: &str) -> Result<Task, DbError> {
+        self.with_conn(|conn| {
+            let changed = conn
+                .execute(
+                    "WITH RECURSIVE tree(id) AS (
+                         SELECT id FROM tasks WHERE id = ?1
+                         UNION SELECT t.id FROM tasks t JOIN tree ON t.parent_id = tree.id
+                     )
+                     UPDATE tasks SET archived = 1, updated_at = ?2
+                     WHERE id IN (SELECT id FROM tree)",
+                    params![id, Utc::now()],
+                )
+                .to_db()?;
+            if changed == 0 {
+                return Err(DbError::NotFound(format!("task {id}")));
+            }
+            conn.query_row(
+                "SELECT * FROM tasks WHERE id = ?1",
+                params![id],
+                row_to_task,
+            )
+            .to_db()
+        })
+    }
+
     pub fn count_tasks_by_status_sync(
         &self,
         project_id: &str,
     ) -> Result<Vec<(String, i64)>, DbError> {
-        self.with_conn(|conn| {
+        self.with_read_conn(|conn| {
             let mut stmt = conn
                 .prepare(
                     "SELECT status, COUNT(*) as cnt FROM tasks
-                     WHERE project_id = ?1 GROUP BY status",
+                     WHERE project_id = ?1 AND archived = 0 GROUP BY status",
                 )
                 .to_db()?;
             let counts = stmt
@@ -352,7 +519,7 @@ impl SqliteDatabase {
 #[cfg(test)]
 mod tests {
     use flowstate_core::project::CreateProject;
-    use flowstate_core::task::{CreateTask, Priority, Status, TaskFilter, UpdateTask};
+    use flowstate_core::task::{CreateTask, Priority, Status, TaskFilter, TaskType, UpdateTask};
 
     use crate::Db;
 
@@ -380,6 +547,7 @@ mod tests {
                 description: "Do something".into(),
                 status: Status::Todo,
                 priority: Priority::High,
+                task_type: TaskType::Feature,
                 parent_id: None,
                 reviewer: String::new(),
                 research_capability: None,
@@ -387,6 +555,7 @@ mod tests {
                 plan_capability: None,
                 build_capability: None,
                 verify_capability: None,
+                due_at: None,
             })
             .unwrap();
 
@@ -423,6 +592,7 @@ mod tests {
                 description: String::new(),
                 status: if i < 3 { Status::Todo } else { Status::Done },
                 priority: Priority::Medium,
+                task_type: TaskType::Feature,
                 parent_id: None,
                 reviewer: String::new(),
                 research_capability: None,
@@ -430,6 +600,7 @@ mod tests {
                 plan_capability: None,
                 build_capability: None,
                 verify_capability: None,
+                due_at: None,
             })
             .unwrap();
         }
@@ -472,6 +643,7 @@ mod tests {
                 description: String::new(),
                 status: Status::Todo,
                 priority: Priority::Medium,
+                task_type: TaskType::Feature,
                 parent_id: None,
                 reviewer: String::new(),
                 research_capability: None,
@@ -479,6 +651,7 @@ mod tests {
                 plan_capability: None,
                 build_capability: None,
                 verify_capability: None,
+                due_at: None,
             })
             .unwrap();
 
@@ -489,6 +662,7 @@ mod tests {
                 description: String::new(),
                 status: Status::Todo,
                 priority: Priority::Medium,
+                task_type: TaskType::Feature,
                 parent_id: None,
                 reviewer: String::new(),
                 research_capability: None,
@@ -496,6 +670,7 @@ mod tests {
                 plan_capability: None,
                 build_capability: None,
                 verify_capability: None,
+                due_at: None,
             })
             .unwrap();
 
@@ -512,6 +687,7 @@ mod tests {
             description: String::new(),
             status: Status::Todo,
             priority: Priority::Medium,
+            task_type: TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -519,6 +695,7 @@ mod tests {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .unwrap();
         db.create_task_sync(&CreateTask {
@@ -527,6 +704,7 @@ mod tests {
             description: String::new(),
             status: Status::Todo,
             priority: Priority::Medium,
+            task_type: TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -534,6 +712,7 @@ mod tests {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .unwrap();
         db.create_task_sync(&CreateTask {
@@ -542,6 +721,7 @@ mod tests {
             description: String::new(),
             status: Status::Done,
             priority: Priority::Medium,
+            task_type: TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -549,6 +729,7 @@ mod tests {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .unwrap();
 
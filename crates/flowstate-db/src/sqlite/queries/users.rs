@@ -0,0 +1,123 @@
+use chrono::Utc;
+use rusqlite::{params, Row};
+
+use flowstate_core::user::{CreateUser, UpdateUser, User};
+
+use super::super::{SqliteDatabase, SqliteResultExt};
+use crate::DbError;
+
+pub(crate) fn row_to_user(row: &Row) -> rusqlite::Result<User> {
+    Ok(User {
+        id: row.get("id")?,
+        name: row.get("name")?,
+        email: row.get("email")?,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+impl SqliteDatabase {
+    pub fn create_user_sync(&self, input: &CreateUser) -> Result<User, DbError> {
+        self.with_conn(|conn| {
+            let id = uuid::Uuid::new_v4().to_string();
+            let now = Utc::now();
+            conn.execute(
+                "INSERT INTO users (id, name, email, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![id, input.name, input.email, now, now],
+            )
+            .to_db()?;
+            conn.query_row(
+                "SELECT * FROM users WHERE id = ?1",
+                params![id],
+                row_to_user,
+            )
+            .to_db()
+        })
+    }
+
+    pub fn get_user_sync(&self, id: &str) -> Result<User, DbError> {
+        self.with_read_conn(|conn| {
+            conn.query_row(
+                "SELECT * FROM users WHERE id = ?1",
+                params![id],
+                row_to_user,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => DbError::NotFound(format!("user {id}")),
+                other => DbError::Internal(other.to_string()),
+            })
+        })
+    }
+
+    pub fn list_users_sync(&self) -> Result<Vec<User>, DbError> {
+        self.with_read_conn(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT * FROM users ORDER BY name, id")
+                .to_db()?;
+            let users = stmt
+                .query_map([], row_to_user)
+                .to_db()?
+                .collect::<Result<Vec<_>, _>>()
+                .to_db()?;
+            Ok(users)
+        })
+    }
+
+    pub fn update_user_sync(&self, id: &str, update: &UpdateUser) -> Result<User, DbError> {
+        self.with_conn(|conn| {
+            let mut sets = Vec::new();
+            let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+            if let Some(ref name) = update.name {
+                sets.push("name = ?");
+                values.push(Box::new(name.clone()));
+            }
+            if let Some(ref email) = update.email {
+                sets.push("email = ?");
+                values.push(Box::new(email.clone()));
+            }
+
+            if !sets.is_empty() {
+                sets.push("updated_at = ?");
+                values.push(Box::new(Utc::now()));
+                values.push(Box::new(id.to_string()));
+
+                let sql = format!("UPDATE users SET {} WHERE id = ?", sets.join(", "));
+                let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+                conn.execute(&sql, params.as_slice()).to_db()?;
+            }
+
+            conn.query_row(
+                "SELECT * FROM users WHERE id = ?1",
+                params![id],
+                row_to_user,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => DbError::NotFound(format!("user {id}")),
+                other => DbError::Internal(other.to_string()),
+            })
+        })
+    }
+
+    /// Delete a user and unassign their tasks. SQLite's `tasks.assignee_id`
+    /// has no foreign key (see migration 25), so the unassign happens here.
+    pub fn delete_user_sync(&self, id: &str) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            let tx = conn.unchecked_transaction().to_db()?;
+            tx.execute(
+                "UPDATE tasks SET assignee_id = NULL WHERE assignee_id = ?1",
+                params![id],
+            )
+            .to_db()?;
+            let changed = tx
+                .execute("DELETE FROM users WHERE id = ?1", params![id])
+                .to_db()?;
+            if changed == 0 {
+                return Err(DbError::NotFound(format!("user {id}")));
+            }
+            tx.commit().to_db()?;
+            Ok(())
+        })
+    }
+}
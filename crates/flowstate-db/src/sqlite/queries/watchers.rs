@@ -0,0 +1,173 @@
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+
+use flowstate_core::notification::{Notification, TaskWatcher};
+use flowstate_core::task_revision::FieldChange;
+use flowstate_core::user::User;
+
+use super::super::{SqliteDatabase, SqliteResultExt};
+use super::users::row_to_user;
+use crate::DbError;
+
+pub(crate) fn row_to_task_watcher(row: &Row) -> rusqlite::Result<TaskWatcher> {
+    Ok(TaskWatcher {
+        task_id: row.get("task_id")?,
+        user_id: row.get("user_id")?,
+        created_at: row.get("created_at")?,
+    })
+}
+
+pub(crate) fn row_to_notification(row: &Row) -> rusqlite::Result<Notification> {
+    let changes_json: String = row.get("changes")?;
+    let changes = serde_json::from_str(&changes_json).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+    Ok(Notification {
+        id: row.get("id")?,
+        user_id: row.get("user_id")?,
+        task_id: row.get("task_id")?,
+        actor: row.get("actor")?,
+        changes,
+        read: row.get("read")?,
+        created_at: row.get("created_at")?,
+    })
+}
+
+/// Give every watcher of `task_id` a notification for `changes`, on an open
+/// connection so it lands in the same transaction as the task revision.
+pub(crate) fn notify_watchers(
+    conn: &Connection,
+    task_id: &str,
+    actor: &str,
+    changes: &[FieldChange],
+) -> Result<(), DbError> {
+    let watchers = conn
+        .prepare("SELECT user_id FROM task_watchers WHERE task_id = ?1")
+        .to_db()?
+        .query_map(params![task_id], |row| row.get::<_, String>(0))
+        .to_db()?
+        .collect::<Result<Vec<_>, _>>()
+        .to_db()?;
+    if watchers.is_empty() {
+        return Ok(());
+    }
+    let changes_json =
+        serde_json::to_string(changes).map_err(|e| DbError::Internal(e.to_string()))?;
+    let now = Utc::now();
+    for user_id in watchers {
+        conn.execute(
+            "INSERT INTO notifications (id, user_id, task_id, actor, changes, read, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, 0, ?6)",
+            params![
+                uuid::Uuid::new_v4().to_string(),
+                user_id,
+                task_id,
+                actor,
+                changes_json,
+                now,
+            ],
+        )
+        .to_db()?;
+    }
+    Ok(())
+}
+
+impl SqliteDatabase {
+    pub fn watch_task_sync(&self, task_id: &str, user_id: &str) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "INSERT OR IGNORE INTO task_watchers (task_id, user_id, created_at)
+                 VALUES (?1, ?2, ?3)",
+                params![task_id, user_id, Utc::now()],
+            )
+            .to_db()?;
+            Ok(())
+        })
+    }
+
+    pub fn unwatch_task_sync(&self, task_id: &str, user_id: &str) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            conn.execute(
+                "DELETE FROM task_watchers WHERE task_id = ?1 AND user_id = ?2",
+                params![task_id, user_id],
+            )
+            .to_db()?;
+            Ok(())
+        })
+    }
+
+    pub fn list_task_watchers_sync(&self, task_id: &str) -> Result<Vec<User>, DbError> {
+        self.with_read_conn(|conn| {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT users.* FROM users
+                     JOIN task_watchers ON task_watchers.user_id = users.id
+                     WHERE task_watchers.task_id = ?1
+                     ORDER BY users.name",
+                )
+                .to_db()?;
+            let users = stmt
+                .query_map(params![task_id], row_to_user)
+                .to_db()?
+                .collect::<Result<Vec<_>, _>>()
+                .to_db()?;
+            Ok(users)
+        })
+    }
+
+    pub fn list_notifications_sync(
+        &self,
+        user_id: &str,
+        unread_only: bool,
+    ) -> Result<Vec<Notification>, DbError> {
+        self.with_read_conn(|conn| {
+            let sql = if unread_only {
+                "SELECT * FROM notifications WHERE user_id = ?1 AND read = 0
+                 ORDER BY created_at DESC, rowid DESC"
+            } else {
+                "SELECT * FROM notifications WHERE user_id = ?1
+                 ORDER BY created_at DESC, rowid DESC"
+            };
+            let mut stmt = conn.prepare(sql).to_db()?;
+            let notifications = stmt
+                .query_map(params![user_id], row_to_notification)
+                .to_db()?
+                .collect::<Result<Vec<_>, _>>()
+                .to_db()?;
+            Ok(notifications)
+        })
+    }
+
+    pub fn get_notification_sync(&self, id: &str) -> Result<Notification, DbError> {
+        self.with_read_conn(|conn| {
+            conn.query_row(
+                "SELECT * FROM notifications WHERE id = ?1",
+                params![id],
+                row_to_notification,
+            )
+            .optional()
+            .to_db()?
+            .ok_or_else(|| DbError::NotFound(format!("notification {id}")))
+        })
+    }
+
+    pub fn mark_notification_read_sync(&self, id: &str) -> Result<Notification, DbError> {
+        self.with_conn(|conn| {
+            let changed = conn
+                .execute(
+                    "UPDATE notifications SET read = 1 WHERE id = ?1",
+                    params![id],
+                )
+                .to_db()?;
+            if changed == 0 {
+                return Err(DbError::NotFound(format!("notification {id}")));
+            }
+            conn.query_row(
+                "SELECT * FROM notifications WHERE id = ?1",
+                params![id],
+                row_to_notification,
+            )
+            .to_db()
+        })
+    }
+}
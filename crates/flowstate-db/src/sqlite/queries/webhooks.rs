@@ -0,0 +1,273 @@
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Row};
+
+use flowstate_core::webhook::{
+    CreateWebhook, DeliveryAttempt, DeliveryStatus, UpdateWebhook, Webhook, WebhookDelivery,
+    WebhookEvent,
+};
+
+use super::super::{SqliteDatabase, SqliteResultExt};
+use crate::DbError;
+
+pub(crate) fn row_to_webhook(row: &Row) -> rusqlite::Result<Webhook> {
+    let events: String = row.get("events")?;
+    Ok(Webhook {
+        id: row.get("id")?,
+        url: row.get("url")?,
+        secret: row.get("secret")?,
+        events: serde_json::from_str(&events).unwrap_or_default(),
+        project_id: row.get("project_id")?,
+        enabled: row.get::<_, i32>("enabled")? != 0,
+        created_at: row.get("created_at")?,
+        updated_at: row.get("updated_at")?,
+    })
+}
+
+fn row_to_delivery(row: &Row) -> rusqlite::Result<WebhookDelivery> {
+    let event: String = row.get("event")?;
+    let status: String = row.get("status")?;
+    Ok(WebhookDelivery {
+        id: row.get("id")?,
+        webhook_id: row.get("webhook_id")?,
+        event: WebhookEvent::parse_str(&event).unwrap_or(WebhookEvent::TaskStatusChanged),
+        payload: row.get("payload")?,
+        status: DeliveryStatus::parse_str(&status).unwrap_or(DeliveryStatus::Pending),
+        attempts: row.get("attempts")?,
+        next_attempt_at: row.get("next_attempt_at")?,
+        response_status: row.get("response_status")?,
+        last_error: row.get("last_error")?,
+        created_at: row.get("created_at")?,
+        delivered_at: row.get("delivered_at")?,
+    })
+}
+
+impl SqliteDatabase {
+    pub fn create_webhook_sync(&self, input: &CreateWebhook) -> Result<Webhook, DbError> {
+        self.with_conn(|conn| {
+            let id = uuid::Uuid::new_v4().to_string();
+            let now = Utc::now();
+            let events = serde_json::to_string(&input.events)
+                .map_err(|e| DbError::Internal(e.to_string()))?;
+            conn.execute(
+                "INSERT INTO webhooks (id, url, secret, events, project_id, enabled, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, 1, ?6, ?7)",
+                params![id, input.url, input.secret, events, input.project_id, now, now],
+            )
+            .to_db()?;
+            conn.query_row(
+                "SELECT * FROM webhooks WHERE id = ?1",
+                params![id],
+                row_to_webhook,
+            )
+            .to_db()
+        })
+    }
+
+    pub fn get_webhook_sync(&self, id: &str) -> Result<Webhook, DbError> {
+        self.with_read_conn(|conn| {
+            conn.query_row(
+                "SELECT * FROM webhooks WHERE id = ?1",
+                params![id],
+                row_to_webhook,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => DbError::NotFound(format!("webhook {id}")),
+                other => DbError::Internal(other.to_string()),
+            })
+        })
+    }
+
+    pub fn list_webhooks_sync(&self) -> Result<Vec<Webhook>, DbError> {
+        self.with_read_conn(|conn| {
+            let mut stmt = conn
+                .prepare("SELECT * FROM webhooks ORDER BY created_at, id")
+                .to_db()?;
+            let webhooks = stmt
+                .query_map([], row_to_webhook)
+                .to_db()?
+                .collect::<Result<Vec<_>, _>>()
+                .to_db()?;
+            Ok(webhooks)
+        })
+    }
+
+    pub fn update_webhook_sync(
+        &self,
+        id: &str,
+        update: &UpdateWebhook,
+    ) -> Result<Webhook, DbError> {
+        self.with_conn(|conn| {
+            let mut sets = Vec::new();
+            let mut values: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+
+            if let Some(ref url) = update.url {
+                sets.push("url = ?");
+                values.push(Box::new(url.clone()));
+            }
+            if let Some(ref secret) = update.secret {
+                sets.push("secret = ?");
+                values.push(Box::new(secret.clone()));
+            }
+            if let Some(ref events) = update.events {
+                let events =
+                    serde_json::to_string(events).map_err(|e| DbError::Internal(e.to_string()))?;
+                sets.push("events = ?");
+                values.push(Box::new(events));
+            }
+            if let Some(ref project_id) = update.project_id {
+                sets.push("project_id = ?");
+                values.push(Box::new(project_id.clone()));
+            }
+            if let Some(enabled) = update.enabled {
+                sets.push("enabled = ?");
+                values.push(Box::new(enabled as i32));
+            }
+
+            if !sets.is_empty() {
+                sets.push("updated_at = ?");
+                values.push(Box::new(Utc::now()));
+                values.push(Box::new(id.to_string()));
+
+                let sql = format!("UPDATE webhooks SET {} WHERE id = ?", sets.join(", "));
+                let params: Vec<&dyn rusqlite::ToSql> = values.iter().map(|v| v.as_ref()).collect();
+                conn.execute(&sql, params.as_slice()).to_db()?;
+            }
+
+            conn.query_row(
+                "SELECT * FROM webhooks WHERE id = ?1",
+                params![id],
+                row_to_webhook,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => DbError::NotFound(format!("webhook {id}")),
+                other => DbError::Internal(other.to_string()),
+            })
+        })
+    }
+
+    pub fn delete_webhook_sync(&self, id: &str) -> Result<(), DbError> {
+        self.with_conn(|conn| {
+            let changed = conn
+                .execute("DELETE FROM webhooks WHERE id = ?1", params![id])
+                .to_db()?;
+            if changed == 0 {
+                return Err(DbError::NotFound(format!("webhook {id}")));
+            }
+            Ok(())
+        })
+    }
+
+    pub fn enqueue_webhook_delivery_sync(
+        &self,
+        webhook_id: &str,
+        event: WebhookEvent,
+        payload: &str,
+    ) -> Result<WebhookDelivery, DbError> {
+        self.with_conn(|conn| {
+            let id = uuid::Uuid::new_v4().to_string();
+            let now = Utc::now();
+            conn.query_row(
+                "INSERT INTO webhook_deliveries
+                     (id, webhook_id, event, payload, status, attempts, next_attempt_at, created_at)
+                 VALUES (?1, ?2, ?3, ?4, 'pending', 0, ?5, ?5)
+                 RETURNING *",
+                params![id, webhook_id, event.as_str(), payload, now],
+                row_to_delivery,
+            )
+            .to_db()
+        })
+    }
+
+    pub fn claim_webhook_deliveries_sync(
+        &self,
+        lease_until: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, DbError> {
+        self.with_conn(|conn| {
+            let mut stmt = conn
+                .prepare(
+                    "UPDATE webhook_deliveries SET next_attempt_at = ?1
+                     WHERE id IN (
+                         SELECT id FROM webhook_deliveries
+                         WHERE status = 'pending' AND next_attempt_at <= ?2
+                         ORDER BY next_attempt_at, created_at LIMIT ?3
+                     )
+                     RETURNING *",
+                )
+                .to_db()?;
+            let mut deliveries = stmt
+                .query_map(params![lease_until, Utc::now(), limit], row_to_delivery)
+                .to_db()?
+                .collect::<Result<Vec<_>, _>>()
+                .to_db()?;
+            deliveries.sort_by_key(|d| d.created_at);
+            Ok(deliveries)
+        })
+    }
+
+    pub fn record_webhook_attempt_sync(
+        &self,
+        id: &str,
+        attempt: &DeliveryAttempt,
+    ) -> Result<WebhookDelivery, DbError> {
+        self.with_conn(|conn| {
+            let delivered_at = (attempt.status == DeliveryStatus::Delivered).then(Utc::now);
+            conn.query_row(
+                "UPDATE webhook_deliveries
+                 SET status = ?1, attempts = attempts + 1, response_status = ?2,
+                     last_error = ?3, next_attempt_at = ?4, delivered_at = ?5
+                 WHERE id = ?6
+                 RETURNING *",
+                params![
+                    attempt.status.as_str(),
+                    attempt.response_status,
+                    attempt.error,
+                    attempt.next_attempt_at,
+                    delivered_at,
+                    id
+                ],
+                row_to_delivery,
+            )
+            .map_err(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => {
+                    DbError::NotFound(format!("webhook delivery {id}"))
+                }
+                other => DbError::Internal(other.to_string()),
+            })
+        })
+    }
+
+    pub fn list_webhook_deliveries_sync(
+        &self,
+        webhook_id: &str,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, DbError> {
+        self.with_read_conn(|conn| {
+            let mut stmt = conn
+                .prepare(
+                    "SELECT * FROM webhook_deliveries WHERE webhook_id = ?1
+                     ORDER BY created_at DESC, id LIMIT ?2",
+                )
+                .to_db()?;
+            let deliveries = stmt
+                .query_map(params![webhook_id, limit], row_to_delivery)
+                .to_db()?
+                .collect::<Result<Vec<_>, _>>()
+                .to_db()?;
+            Ok(deliveries)
+        })
+    }
+
+    pub fn prune_webhook_deliveries_sync(&self, before: DateTime<Utc>) -> Result<u64, DbError> {
+        self.with_conn(|conn| {
+            let removed = conn
+                .execute(
+                    "DELETE FROM webhook_deliveries
+                     WHERE status IN ('delivered', 'failed') AND created_at < ?1",
+                    params![before],
+                )
+                .to_db()?;
+            Ok(removed as u64)
+        })
+    }
+}
@@ -0,0 +1,117 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Tables whose row counts are reported by `Database::stats`.
+pub const STATS_TABLES: &[&str] = &[
+    "projects",
+    "sprints",
+    "epics",
+    "users",
+    "saved_filters",
+    "custom_fields",
+    "tasks",
+    "task_field_values",
+    "claude_runs",
+    "task_links",
+    "task_prs",
+    "attachments",
+    "task_revisions",
+    "feedback_history",
+    "task_watchers",
+    "notifications",
+    "api_keys",
+    "feature_flags",
+    "run_metrics",
+    "webhooks",
+    "webhook_deliveries",
+];
+
+/// Health and size information for the backing database.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct DbStats {
+    /// "sqlite" or "postgres".
+    pub backend: String,
+    /// Highest migration version applied.
+    pub schema_version: i64,
+    pub tables: Vec<TableCount>,
+    /// On-disk size of the main database file (SQLite only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<i64>,
+    /// Connection pool state (Postgres only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool: Option<PoolStats>,
+}
+
+impl DbStats {
+    /// Total number of rows across every counted table.
+    pub fn total_rows(&self) -> i64 {
+        self.tables.iter().map(|t| t.rows).sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct TableCount {
+    pub name: String,
+    pub rows: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct PoolStats {
+    /// Open connections, idle or in use.
+    pub size: u32,
+    pub idle: u32,
+    pub max_connections: u32,
+}
+
+/// Outcome of one `Database::run_maintenance` pass.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MaintenanceReport {
+    /// "sqlite" or "postgres".
+    pub backend: String,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: i64,
+    /// Each task in the order it ran.
+    pub steps: Vec<MaintenanceStep>,
+}
+
+impl MaintenanceReport {
+    /// Whether every step succeeded and found nothing to act on.
+    pub fn ok(&self) -> bool {
+        self.steps.iter().all(|s| s.ok)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct MaintenanceStep {
+    /// e.g. "wal_checkpoint", "integrity_check", "analyze".
+    pub name: String,
+    pub ok: bool,
+    /// What the step did or found, or its error.
+    pub detail: String,
+}
+
+impl MaintenanceStep {
+    pub fn new(name: &str, ok: bool, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            ok,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Queued runs needing one capability tier, from `Database::queued_run_demand`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct QueueDemand {
+    /// The runs' `required_capability`; `None` for runs any runner may claim.
+    pub capability: Option<String>,
+    pub queued: i64,
+    /// When the longest-waiting of these runs was queued.
+    pub oldest_queued_at: DateTime<Utc>,
+}
@@ -3,14 +3,30 @@
 // Each public async function accepts `&dyn Database` so that the same logic
 // can be exercised against both the SQLite and Postgres backends.
 
+use flowstate_core::approval_rule::{ApprovalPhase, ApprovalPolicy};
 use flowstate_core::claude_run::{ClaudeAction, ClaudeRunStatus, CreateClaudeRun};
+use flowstate_core::custom_field::{CreateCustomField, CustomFieldType, UpdateCustomField};
+use flowstate_core::epic::{CreateEpic, EpicStatus, UpdateEpic};
+use flowstate_core::organization::CreateOrganization;
 use flowstate_core::project::{CreateProject, UpdateProject};
-use flowstate_core::runner::RunnerCapability;
+use flowstate_core::run_metrics::{RecordRunMetrics, RunMetricsFilter};
+use flowstate_core::runner::{
+    PendingRunnerConfig, RunnerBenchmark, RunnerCapability, RunnerRecord, RunnerStatus,
+};
+use flowstate_core::saved_filter::{CreateSavedFilter, FilterQuery, UpdateSavedFilter};
 use flowstate_core::sprint::{CreateSprint, SprintStatus, UpdateSprint};
-use flowstate_core::task::{CreateTask, Priority, Status, TaskFilter, UpdateTask};
+use flowstate_core::subscription::{CreateSubscription, NotifyChannel, NotifyEvent};
+use flowstate_core::task::{
+    ApprovalStatus, CreateTask, Priority, Status, Task, TaskFilter, TaskType, UpdateTask,
+};
 use flowstate_core::task_link::{CreateTaskLink, LinkType};
-use flowstate_core::task_pr::CreateTaskPr;
+use flowstate_core::task_pr::{ChecksStatus, CreateTaskPr, PrState};
+use flowstate_core::user::{CreateUser, UpdateUser};
+use flowstate_core::webhook::{
+    CreateWebhook, DeliveryAttempt, DeliveryStatus, UpdateWebhook, WebhookEvent,
+};
 use flowstate_db::Database;
+use std::sync::Arc;
 
 // ---------------------------------------------------------------------------
 // Helpers
@@ -32,6 +48,7 @@ fn make_task(project_id: &str, title: &str) -> CreateTask {
         description: String::new(),
         status: Status::Todo,
         priority: Priority::Medium,
+        task_type: TaskType::Feature,
         parent_id: None,
         reviewer: String::new(),
         research_capability: None,
@@ -39,6 +56,7 @@ fn make_task(project_id: &str, title: &str) -> CreateTask {
         plan_capability: None,
         build_capability: None,
         verify_capability: None,
+        due_at: None,
     }
 }
 
@@ -112,6 +130,7 @@ pub async fn test_task_crud(db: &dyn Database) {
             description: "do something".into(),
             status: Status::Todo,
             priority: Priority::Medium,
+            task_type: TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: Some(RunnerCapability::Light),
@@ -119,6 +138,7 @@ pub async fn test_task_crud(db: &dyn Database) {
             plan_capability: None,
             build_capability: Some(RunnerCapability::Heavy),
             verify_capability: None,
+            due_at: None,
         })
         .await
         .unwrap();
@@ -141,8 +161,10 @@ pub async fn test_task_crud(db: &dyn Database) {
             &UpdateTask {
                 status: Some(Status::Build),
                 priority: Some(Priority::High),
+                task_type: Some(TaskType::Bug),
                 research_capability: Some(None), // unset
                 build_capability: Some(Some(RunnerCapability::Standard)), // change
+                autopilot: Some(true),
                 ..Default::default()
             },
         )
@@ -150,8 +172,10 @@ pub async fn test_task_crud(db: &dyn Database) {
         .unwrap();
     assert_eq!(updated.status, Status::Build);
     assert_eq!(updated.priority, Priority::High);
+    assert_eq!(updated.task_type, TaskType::Bug);
     assert_eq!(updated.research_capability, None);
     assert_eq!(updated.build_capability, Some(RunnerCapability::Standard));
+    assert!(!task.autopilot && updated.autopilot);
 
     // list with filter
     let tasks = db
@@ -196,6 +220,11 @@ pub async fn test_task_filtering(db: &dyn Database) {
             } else {
                 Priority::Medium
             },
+            task_type: if i == 4 {
+                TaskType::Spike
+            } else {
+                TaskType::Feature
+            },
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -203,6 +232,7 @@ pub async fn test_task_filtering(db: &dyn Database) {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .await
         .unwrap();
@@ -250,6 +280,18 @@ pub async fn test_task_filtering(db: &dyn Database) {
         .unwrap();
     assert_eq!(high.len(), 1);
 
+    // filter by type
+    let spikes = db
+        .list_tasks(&TaskFilter {
+            project_id: Some(project.id.clone()),
+            task_type: Some(TaskType::Spike),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(spikes.len(), 1);
+    assert_eq!(spikes[0].title, "Task 4");
+
     // limit
     let limited = db
         .list_tasks(&TaskFilter {
@@ -262,6 +304,132 @@ pub async fn test_task_filtering(db: &dyn Database) {
     assert_eq!(limited.len(), 2);
 }
 
+/// Test the list and range filters: any-of statuses and assignees, due and
+/// creation date ranges, and text combined with them.
+pub async fn test_task_filter_lists_and_ranges(db: &dyn Database) {
+    let project = db
+        .create_project(&make_project("task-filter-ranges"))
+        .await
+        .unwrap();
+    let alice = db
+        .create_user(&CreateUser {
+            name: "Alice".into(),
+            email: String::new(),
+        })
+        .await
+        .unwrap();
+    let bob = db
+        .create_user(&CreateUser {
+            name: "Bob".into(),
+            email: String::new(),
+        })
+        .await
+        .unwrap();
+
+    let now = chrono::Utc::now();
+    let day = chrono::Duration::days(1);
+    let mut tasks = Vec::new();
+    for (title, status, assignee, due_at) in [
+        ("Login page", Status::Todo, Some(&alice.id), Some(now + day)),
+        (
+            "Login API",
+            Status::Build,
+            Some(&bob.id),
+            Some(now + day * 3),
+        ),
+        ("Docs", Status::Verify, None, Some(now + day * 10)),
+        ("Cleanup", Status::Done, Some(&alice.id), None),
+    ] {
+        let mut input = make_task(&project.id, title);
+        input.status = status;
+        input.due_at = due_at;
+        let task = db.create_task(&input).await.unwrap();
+        if let Some(user_id) = assignee {
+            db.update_task(
+                &task.id,
+                &UpdateTask {
+                    assignee_id: Some(Some(user_id.clone())),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        }
+        tasks.push(task);
+    }
+    let base = || TaskFilter {
+        project_id: Some(project.id.clone()),
+        ..Default::default()
+    };
+    let titles = |found: Vec<Task>| found.into_iter().map(|t| t.title).collect::<Vec<_>>();
+
+    let found = db
+        .list_tasks(&TaskFilter {
+            statuses: vec![Status::Todo, Status::Verify],
+            ..base()
+        })
+        .await
+        .unwrap();
+    assert_eq!(titles(found), vec!["Login page", "Docs"]);
+
+    let found = db
+        .list_tasks(&TaskFilter {
+            assignee_ids: vec![alice.id.clone(), bob.id.clone()],
+            ..base()
+        })
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 3);
+
+    // Due in [now + 2 days, now + 5 days)
+    let found = db
+        .list_tasks(&TaskFilter {
+            due_after: Some(now + day * 2),
+            due_before: Some(now + day * 5),
+            ..base()
+        })
+        .await
+        .unwrap();
+    assert_eq!(titles(found), vec!["Login API"]);
+
+    // Text and lists combine with AND
+    let found = db
+        .list_tasks(&TaskFilter {
+            text: Some("login".into()),
+            statuses: vec![Status::Build, Status::Done],
+            ..base()
+        })
+        .await
+        .unwrap();
+    assert_eq!(titles(found), vec!["Login API"]);
+
+    let created = tasks.iter().map(|t| t.created_at).min().unwrap();
+    let found = db
+        .list_tasks(&TaskFilter {
+            created_after: Some(created),
+            ..base()
+        })
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 4);
+    let found = db
+        .list_tasks(&TaskFilter {
+            created_before: Some(created),
+            ..base()
+        })
+        .await
+        .unwrap();
+    assert!(found.is_empty());
+    let found = db
+        .list_tasks(&TaskFilter {
+            updated_after: Some(now + day),
+            ..base()
+        })
+        .await
+        .unwrap();
+    assert!(found.is_empty());
+}
+
 /// Test that sort_order auto-increments when creating tasks.
 pub async fn test_task_sort_order(db: &dyn Database) {
     let project = db
@@ -299,6 +467,7 @@ pub async fn test_count_by_status(db: &dyn Database) {
         description: String::new(),
         status: Status::Todo,
         priority: Priority::Medium,
+        task_type: TaskType::Feature,
         parent_id: None,
         reviewer: String::new(),
         research_capability: None,
@@ -306,6 +475,7 @@ pub async fn test_count_by_status(db: &dyn Database) {
         plan_capability: None,
         build_capability: None,
         verify_capability: None,
+        due_at: None,
     })
     .await
     .unwrap();
@@ -315,6 +485,7 @@ pub async fn test_count_by_status(db: &dyn Database) {
         description: String::new(),
         status: Status::Todo,
         priority: Priority::Medium,
+        task_type: TaskType::Feature,
         parent_id: None,
         reviewer: String::new(),
         research_capability: None,
@@ -322,6 +493,7 @@ pub async fn test_count_by_status(db: &dyn Database) {
         plan_capability: None,
         build_capability: None,
         verify_capability: None,
+        due_at: None,
     })
     .await
     .unwrap();
@@ -331,6 +503,7 @@ pub async fn test_count_by_status(db: &dyn Database) {
         description: String::new(),
         status: Status::Done,
         priority: Priority::Medium,
+        task_type: TaskType::Feature,
         parent_id: None,
         reviewer: String::new(),
         research_capability: None,
@@ -338,6 +511,7 @@ pub async fn test_count_by_status(db: &dyn Database) {
         plan_capability: None,
         build_capability: None,
         verify_capability: None,
+        due_at: None,
     })
     .await
     .unwrap();
@@ -349,6 +523,142 @@ pub async fn test_count_by_status(db: &dyn Database) {
     assert_eq!(done_count, Some(1));
 }
 
+/// Test the dashboard aggregates: task counts, run outcomes and sprint progress.
+pub async fn test_project_dashboard(db: &dyn Database) {
+    let project = db.create_project(&make_project("dashboard")).await.unwrap();
+    let other = db
+        .create_project(&make_project("dashboard-other"))
+        .await
+        .unwrap();
+    db.create_task(&make_task(&other.id, "Elsewhere"))
+        .await
+        .unwrap();
+
+    let sprint = db
+        .create_sprint(&CreateSprint {
+            project_id: project.id.clone(),
+            name: "Sprint 1".into(),
+            goal: String::new(),
+            starts_at: None,
+            ends_at: None,
+        })
+        .await
+        .unwrap();
+
+    let mut tasks = Vec::new();
+    for (title, status, priority) in [
+        ("A", Status::Todo, Priority::High),
+        ("B", Status::Build, Priority::High),
+        ("C", Status::Done, Priority::Low),
+    ] {
+        let mut input = make_task(&project.id, title);
+        input.status = status;
+        input.priority = priority;
+        let task = db.create_task(&input).await.unwrap();
+        db.update_task(
+            &task.id,
+            &UpdateTask {
+                sprint_id: Some(Some(sprint.id.clone())),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        tasks.push(task);
+    }
+
+    for (action, status) in [
+        (ClaudeAction::Build, Some(ClaudeRunStatus::Completed)),
+        (ClaudeAction::Build, Some(ClaudeRunStatus::Failed)),
+        (ClaudeAction::Build, Some(ClaudeRunStatus::Completed)),
+        (ClaudeAction::Research, Some(ClaudeRunStatus::Cancelled)),
+        (ClaudeAction::Research, None),
+    ] {
+        let run = db
+            .create_claude_run(&CreateClaudeRun {
+                task_id: tasks[1].id.clone(),
+                action,
+                required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
+            })
+            .await
+            .unwrap();
+        if let Some(status) = status {
+            db.update_claude_run_status(&run.id, status, None, None)
+                .await
+                .unwrap();
+        }
+    }
+
+    let dashboard = db.project_dashboard(&project.id).await.unwrap();
+    assert_eq!(dashboard.project_id, project.id);
+    assert_eq!(dashboard.total_tasks(), 3);
+    assert_eq!(dashboard.tasks_by_status.len(), Status::ALL.len());
+    let count_of = |status| {
+        dashboard
+            .tasks_by_status
+            .iter()
+            .find(|c| c.status == status)
+            .unwrap()
+            .count
+    };
+    assert_eq!(count_of(Status::Todo), 1);
+    assert_eq!(count_of(Status::Build), 1);
+    assert_eq!(count_of(Status::Verify), 0);
+    let priorities: Vec<_> = dashboard
+        .tasks_by_priority
+        .iter()
+        .map(|c| (c.priority, c.count))
+        .collect();
+    assert_eq!(
+        priorities,
+        vec![
+            (Priority::Urgent, 0),
+            (Priority::High, 2),
+            (Priority::Medium, 0),
+            (Priority::Low, 1),
+            (Priority::None, 0),
+        ]
+    );
+
+    // Runs are ordered by action; the queued research run is not counted
+    assert_eq!(dashboard.runs.len(), 2);
+    let build = dashboard.runs[0];
+    assert_eq!(build.action, Some(ClaudeAction::Build));
+    assert_eq!((build.completed, build.failed), (2, 1));
+    let research = dashboard.runs[1];
+    assert_eq!(research.action, Some(ClaudeAction::Research));
+    assert_eq!((research.completed, research.cancelled), (0, 1));
+    assert_eq!(research.success_rate(), None);
+
+    assert_eq!(dashboard.sprints.len(), 1);
+    let progress = &dashboard.sprints[0];
+    assert_eq!(progress.sprint_id, sprint.id);
+    assert_eq!(progress.name, "Sprint 1");
+    assert_eq!(progress.status, SprintStatus::Planned);
+    assert_eq!((progress.total_tasks, progress.done_tasks), (3, 1));
+
+    // Archived tasks leave the counts but still make up sprint progress
+    db.archive_task(&tasks[2].id).await.unwrap();
+    let dashboard = db.project_dashboard(&project.id).await.unwrap();
+    assert_eq!(dashboard.total_tasks(), 2);
+    assert_eq!(dashboard.sprints[0].done_tasks, 1);
+
+    // A project with nothing in it still reports every status and priority
+    let empty = db
+        .create_project(&make_project("dashboard-empty"))
+        .await
+        .unwrap();
+    let dashboard = db.project_dashboard(&empty.id).await.unwrap();
+    assert_eq!(dashboard.total_tasks(), 0);
+    assert_eq!(dashboard.tasks_by_priority.len(), Priority::ALL.len());
+    assert!(dashboard.runs.is_empty());
+    assert!(dashboard.sprints.is_empty());
+}
 /// Test parent/child task relationships via list_child_tasks.
 pub async fn test_child_tasks(db: &dyn Database) {
     let project = db
@@ -368,6 +678,7 @@ pub async fn test_child_tasks(db: &dyn Database) {
             description: String::new(),
             status: Status::Todo,
             priority: Priority::Medium,
+            task_type: TaskType::Feature,
             parent_id: Some(parent.id.clone()),
             reviewer: String::new(),
             research_capability: None,
@@ -375,6 +686,7 @@ pub async fn test_child_tasks(db: &dyn Database) {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .await
         .unwrap();
@@ -387,6 +699,7 @@ pub async fn test_child_tasks(db: &dyn Database) {
             description: String::new(),
             status: Status::Todo,
             priority: Priority::Medium,
+            task_type: TaskType::Feature,
             parent_id: Some(parent.id.clone()),
             reviewer: String::new(),
             research_capability: None,
@@ -394,6 +707,7 @@ pub async fn test_child_tasks(db: &dyn Database) {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .await
         .unwrap();
@@ -425,6 +739,197 @@ pub async fn test_child_tasks(db: &dyn Database) {
 // Claude run tests
 // ---------------------------------------------------------------------------
 
+/// Creating a run with a key already used on the task returns the earlier
+/// run instead of queueing a duplicate.
+pub async fn test_claude_run_idempotency(db: &dyn Database) {
+    let project = db
+        .create_project(&make_project("run-idempotency"))
+        .await
+        .unwrap();
+    let task = db
+        .create_task(&make_task(&project.id, "Retried task"))
+        .await
+        .unwrap();
+    let input = CreateClaudeRun {
+        task_id: task.id.clone(),
+        action: ClaudeAction::Build,
+        required_capability: None,
+        priority: 0,
+        feedback: None,
+        idempotency_key: Some("tui-retry-1".into()),
+        run_window: None,
+        trace_context: None,
+    };
+
+    let first = db.create_claude_run(&input).await.unwrap();
+    let again = db.create_claude_run(&input).await.unwrap();
+    assert_eq!(again.id, first.id);
+    assert_eq!(first.idempotency_key.as_deref(), Some("tui-retry-1"));
+
+    // Unkeyed runs never collide with each other.
+    let unkeyed = CreateClaudeRun {
+        idempotency_key: None,
+        ..input
+    };
+    let a = db.create_claude_run(&unkeyed).await.unwrap();
+    let b = db.create_claude_run(&unkeyed).await.unwrap();
+    assert_ne!(a.id, b.id);
+
+    let runs = db.list_claude_runs_for_task(&task.id).await.unwrap();
+    assert_eq!(runs.len(), 3);
+}
+
+/// Finished-run counts ignore queued and cancelled runs and runs that
+/// finished before the cutoff.
+pub async fn test_count_finished_runs(db: &dyn Database) {
+    let project = db
+        .create_project(&make_project("finished-runs"))
+        .await
+        .unwrap();
+    let task = db
+        .create_task(&make_task(&project.id, "Finished runs"))
+        .await
+        .unwrap();
+    let before = chrono::Utc::now() - chrono::Duration::seconds(1);
+
+    for status in [
+        Some(ClaudeRunStatus::Completed),
+        Some(ClaudeRunStatus::Failed),
+        Some(ClaudeRunStatus::TimedOut),
+        Some(ClaudeRunStatus::Cancelled),
+        None,
+    ] {
+        let run = db
+            .create_claude_run(&CreateClaudeRun {
+                task_id: task.id.clone(),
+                action: ClaudeAction::Build,
+                required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
+            })
+            .await
+            .unwrap();
+        if let Some(status) = status {
+            db.update_claude_run_status(&run.id, status, None, None)
+                .await
+                .unwrap();
+        }
+    }
+
+    assert_eq!(db.count_finished_runs(before).await.unwrap(), (3, 2));
+    let later = chrono::Utc::now() + chrono::Duration::minutes(1);
+    assert_eq!(db.count_finished_runs(later).await.unwrap(), (0, 0));
+}
+
+/// Queue demand groups queued runs by required capability, most first.
+pub async fn test_queued_run_demand(db: &dyn Database) {
+    assert!(db.queued_run_demand().await.unwrap().is_empty());
+    let project = db
+        .create_project(&make_project("queue-demand"))
+        .await
+        .unwrap();
+    let task = db
+        .create_task(&make_task(&project.id, "Queue demand"))
+        .await
+        .unwrap();
+    let before = chrono::Utc::now() - chrono::Duration::seconds(1);
+
+    let mut runs = Vec::new();
+    for capability in [Some("heavy"), None, Some("heavy"), Some("light")] {
+        let run = db
+            .create_claude_run(&CreateClaudeRun {
+                task_id: task.id.clone(),
+                action: ClaudeAction::Build,
+                required_capability: capability.map(str::to_string),
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
+            })
+            .await
+            .unwrap();
+        runs.push(run);
+    }
+    // Only queued runs count
+    db.update_claude_run_status(&runs[3].id, ClaudeRunStatus::Completed, None, None)
+        .await
+        .unwrap();
+
+    let demand = db.queued_run_demand().await.unwrap();
+    let counts: Vec<_> = demand
+        .iter()
+        .map(|d| (d.capability.as_deref(), d.queued))
+        .collect();
+    assert_eq!(counts, vec![(Some("heavy"), 2), (None, 1)]);
+    assert_eq!(
+        demand[0].oldest_queued_at.timestamp_millis(),
+        runs[0].started_at.timestamp_millis()
+    );
+    assert!(demand.iter().all(|d| d.oldest_queued_at > before));
+}
+
+pub async fn test_delete_runs_older_than(db: &dyn Database) {
+    let project = db
+        .create_project(&make_project("run-retention"))
+        .await
+        .unwrap();
+    let task = db
+        .create_task(&make_task(&project.id, "Retained runs"))
+        .await
+        .unwrap();
+
+    let mut runs = Vec::new();
+    for (action, status) in [
+        (ClaudeAction::Research, Some(ClaudeRunStatus::Completed)),
+        (ClaudeAction::Research, Some(ClaudeRunStatus::Failed)),
+        (ClaudeAction::Research, Some(ClaudeRunStatus::Completed)),
+        (ClaudeAction::Build, Some(ClaudeRunStatus::Completed)),
+        (ClaudeAction::Design, None),
+        (ClaudeAction::Design, None),
+    ] {
+        let run = db
+            .create_claude_run(&CreateClaudeRun {
+                task_id: task.id.clone(),
+                action,
+                required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
+            })
+            .await
+            .unwrap();
+        if let Some(status) = status {
+            db.update_claude_run_status(&run.id, status, None, None)
+                .await
+                .unwrap();
+        }
+        runs.push(run);
+    }
+
+    // Nothing has ended before an hour ago
+    let past = chrono::Utc::now() - chrono::Duration::hours(1);
+    assert!(db.delete_runs_older_than(past).await.unwrap().is_empty());
+
+    // The latest research run, the only build and the queued runs survive
+    let future = chrono::Utc::now() + chrono::Duration::hours(1);
+    let mut deleted = db.delete_runs_older_than(future).await.unwrap();
+    deleted.sort();
+    let mut expected = vec![runs[0].id.clone(), runs[1].id.clone()];
+    expected.sort();
+    assert_eq!(deleted, expected);
+
+    let left = db.list_claude_runs_for_task(&task.id).await.unwrap();
+    assert_eq!(left.len(), 4);
+    assert!(left.iter().all(|r| !deleted.contains(&r.id)));
+    assert!(db.delete_runs_older_than(future).await.unwrap().is_empty());
+}
+
 /// Test the full claude run lifecycle: create -> claim -> running -> completed.
 pub async fn test_claude_run_lifecycle(db: &dyn Database) {
     let project = db
@@ -442,6 +947,11 @@ pub async fn test_claude_run_lifecycle(db: &dyn Database) {
             task_id: task.id.clone(),
             action: ClaudeAction::Build,
             required_capability: None,
+            priority: 0,
+            feedback: None,
+            idempotency_key: None,
+            run_window: None,
+            trace_context: Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".into()),
         })
         .await
         .unwrap();
@@ -464,6 +974,7 @@ pub async fn test_claude_run_lifecycle(db: &dyn Database) {
     let claimed = db.claim_next_claude_run(&[]).await.unwrap().unwrap();
     assert_eq!(claimed.id, run.id);
     assert_eq!(claimed.status, ClaudeRunStatus::Running);
+    assert_eq!(claimed.trace_context, run.trace_context);
 
     // Set runner_id
     db.set_claude_run_runner(&run.id, "runner-42")
@@ -514,6 +1025,11 @@ pub async fn test_claude_run_lifecycle(db: &dyn Database) {
             task_id: task.id.clone(),
             action: ClaudeAction::Plan,
             required_capability: None,
+            priority: 0,
+            feedback: None,
+            idempotency_key: None,
+            run_window: None,
+            trace_context: None,
         })
         .await
         .unwrap();
@@ -530,6 +1046,25 @@ pub async fn test_claude_run_lifecycle(db: &dyn Database) {
     assert!(failed.finished_at.is_some());
     assert_eq!(failed.error_message.as_deref(), Some("segfault"));
     assert_eq!(failed.exit_code, Some(139));
+
+    // Revise runs are accepted alongside the phase actions
+    let revise = db
+        .create_claude_run(&CreateClaudeRun {
+            task_id: task.id.clone(),
+            action: ClaudeAction::Revise,
+            required_capability: None,
+            priority: 0,
+            feedback: None,
+            idempotency_key: None,
+            run_window: None,
+            trace_context: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        db.get_claude_run(&revise.id).await.unwrap().action,
+        ClaudeAction::Revise
+    );
 }
 
 /// Test that claim_next_claude_run returns None when no queued runs exist.
@@ -538,135 +1073,725 @@ pub async fn test_claim_empty(db: &dyn Database) {
     assert!(result.is_none());
 }
 
-/// Test find_stale_running_runs and timeout_claude_run.
-pub async fn test_stale_runs(db: &dyn Database) {
+/// Test that claims take the highest-priority queued run first, then the oldest.
+pub async fn test_claim_respects_priority(db: &dyn Database) {
     let project = db
-        .create_project(&make_project("stale-runs"))
+        .create_project(&make_project("claim-priority"))
         .await
         .unwrap();
     let task = db
-        .create_task(&make_task(&project.id, "Stale task"))
-        .await
-        .unwrap();
-
-    // Create and claim a run to set it Running
-    let run = db
-        .create_claude_run(&CreateClaudeRun {
-            task_id: task.id.clone(),
-            action: ClaudeAction::Build,
-            required_capability: None,
-        })
+        .create_task(&make_task(&project.id, "Priority task"))
         .await
         .unwrap();
-    let _claimed = db.claim_next_claude_run(&[]).await.unwrap().unwrap();
 
-    // With a threshold in the future, the run should be considered stale
-    let future = chrono::Utc::now() + chrono::Duration::hours(1);
-    let stale = db.find_stale_running_runs(future).await.unwrap();
-    assert_eq!(stale.len(), 1);
-    assert_eq!(stale[0].id, run.id);
+    let mut ids = Vec::new();
+    for (action, priority) in [
+        (ClaudeAction::Research, 0),
+        (ClaudeAction::Build, 40),
+        (ClaudeAction::Plan, 40),
+    ] {
+        let run = db
+            .create_claude_run(&CreateClaudeRun {
+                task_id: task.id.clone(),
+                action,
+                required_capability: None,
+                priority,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(run.priority, priority);
+        ids.push(run.id);
+    }
 
-    // With a threshold in the past, no runs should match
-    let past = chrono::Utc::now() - chrono::Duration::hours(1);
-    let not_stale = db.find_stale_running_runs(past).await.unwrap();
-    assert!(not_stale.is_empty());
+    let first = db.claim_next_claude_run(&[]).await.unwrap().unwrap();
+    assert_eq!(first.id, ids[1]);
+    let second = db.claim_next_claude_run(&[]).await.unwrap().unwrap();
+    assert_eq!(second.id, ids[2]);
+    let third = db.claim_next_claude_run(&[]).await.unwrap().unwrap();
+    assert_eq!(third.id, ids[0]);
+}
 
-    // Timeout the running run
-    let timed = db
-        .timeout_claude_run(&run.id, "watchdog timeout")
+/// Test that a scoped claim only takes runs from the given projects.
+pub async fn test_claim_scoped_to_projects(db: &dyn Database) {
+    let mine = db
+        .create_project(&make_project("claim-mine"))
         .await
         .unwrap();
-    assert!(timed.is_some());
-    let timed = timed.unwrap();
-    assert_eq!(timed.status, ClaudeRunStatus::TimedOut);
-    assert_eq!(timed.error_message.as_deref(), Some("watchdog timeout"));
-    assert!(timed.finished_at.is_some());
-
-    // Timeout on an already-timed-out run returns None
-    let again = db
-        .timeout_claude_run(&run.id, "second timeout")
+    let other = db
+        .create_project(&make_project("claim-other"))
         .await
         .unwrap();
-    assert!(again.is_none());
+    let mut runs = Vec::new();
+    for (project, priority) in [(&mine, 0), (&other, 50)] {
+        let task = db
+            .create_task(&make_task(&project.id, "Scoped task"))
+            .await
+            .unwrap();
+        let run = db
+            .create_claude_run(&CreateClaudeRun {
+                task_id: task.id.clone(),
+                action: ClaudeAction::Build,
+                required_capability: None,
+                priority,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
+            })
+            .await
+            .unwrap();
+        runs.push(run.id);
+    }
 
-    // After timing out, find_stale_running_runs should return empty
-    let stale_after = db.find_stale_running_runs(future).await.unwrap();
-    assert!(stale_after.is_empty());
+    // Project ids are bound, not spliced into the SQL
+    let hostile = ["x') OR ('1'='1".to_string()];
+    assert!(db
+        .claim_next_claude_run_scoped(&[], &[], &hostile)
+        .await
+        .unwrap()
+        .is_none());
 
-    // Test find_stale_salvaging_runs
-    let run2 = db
-        .create_claude_run(&CreateClaudeRun {
-            task_id: task.id.clone(),
-            action: ClaudeAction::Build,
-            required_capability: None,
-        })
+    let scope = [mine.id.clone()];
+    let claimed = db
+        .claim_next_claude_run_scoped(&["light", "standard"], &[], &scope)
         .await
+        .unwrap()
         .unwrap();
-    let _claimed2 = db.claim_next_claude_run(&[]).await.unwrap().unwrap();
-    // Transition to Salvaging
-    db.update_claude_run_status(&run2.id, ClaudeRunStatus::Salvaging, None, None)
+    assert_eq!(claimed.id, runs[0]);
+    assert!(db
+        .claim_next_claude_run_scoped(&[], &[], &scope)
         .await
-        .unwrap();
+        .unwrap()
+        .is_none());
+    let rest = db.claim_next_claude_run(&[]).await.unwrap().unwrap();
+    assert_eq!(rest.id, runs[1]);
+}
 
-    let salvaging = db.find_stale_salvaging_runs(future).await.unwrap();
-    assert_eq!(salvaging.len(), 1);
-    assert_eq!(salvaging[0].id, run2.id);
+/// Test that a claim can leave runs of some actions for another runner.
+pub async fn test_claim_skips_actions(db: &dyn Database) {
+    let project = db
+        .create_project(&make_project("claim-skip"))
+        .await
+        .unwrap();
+    let task = db
+        .create_task(&make_task(&project.id, "Skip task"))
+        .await
+        .unwrap();
+    let mut ids = Vec::new();
+    for (action, priority) in [(ClaudeAction::PlanDistill, 40), (ClaudeAction::Build, 0)] {
+        let run = db
+            .create_claude_run(&CreateClaudeRun {
+                task_id: task.id.clone(),
+                action,
+                required_capability: None,
+                priority,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
+            })
+            .await
+            .unwrap();
+        ids.push(run.id);
+    }
 
-    // Timeout the salvaging run too
-    let timed2 = db
-        .timeout_claude_run(&run2.id, "salvage timeout")
+    let skip = [ClaudeAction::PlanDistill, ClaudeAction::DesignDistill];
+    let first = db
+        .claim_next_claude_run_except(&[], &skip)
         .await
+        .unwrap()
         .unwrap();
-    assert!(timed2.is_some());
-    assert_eq!(timed2.unwrap().status, ClaudeRunStatus::TimedOut);
+    assert_eq!(first.id, ids[1]);
+    assert!(db
+        .claim_next_claude_run_except(&["light"], &skip)
+        .await
+        .unwrap()
+        .is_none());
+    let second = db.claim_next_claude_run(&[]).await.unwrap().unwrap();
+    assert_eq!(second.id, ids[0]);
 }
 
-// ---------------------------------------------------------------------------
-// Task link tests
-// ---------------------------------------------------------------------------
+/// Test that claims skip runs whose project is at its concurrency cap.
+pub async fn test_claim_respects_run_window(db: &dyn Database) {
+    use chrono::Timelike;
+    use flowstate_core::RunWindow;
 
-/// Test task link CRUD: create, list, delete.
-pub async fn test_task_links(db: &dyn Database) {
-    let project = db
-        .create_project(&make_project("task-links"))
+    let now = chrono::Utc::now();
+    let minute = (now.hour() * 60 + now.minute()) as i32;
+    let at = |offset: i32| (minute + offset).rem_euclid(24 * 60);
+    let closed = RunWindow::new(at(60), at(120), 0).unwrap();
+    let open = RunWindow::new(at(-60), at(60), 0).unwrap();
+
+    let night = db
+        .create_project(&make_project("window-night"))
         .await
         .unwrap();
-    let t1 = db
-        .create_task(&make_task(&project.id, "Source"))
+    let night = db
+        .update_project(
+            &night.id,
+            &UpdateProject {
+                run_window: Some(Some(closed)),
+                ..Default::default()
+            },
+        )
         .await
         .unwrap();
-    let t2 = db
-        .create_task(&make_task(&project.id, "Target"))
+    assert_eq!(night.run_window, Some(closed));
+    let anytime = db
+        .create_project(&make_project("window-anytime"))
         .await
         .unwrap();
-
-    let link = db
-        .create_task_link(&CreateTaskLink {
-            source_task_id: t1.id.clone(),
-            target_task_id: t2.id.clone(),
-            link_type: LinkType::Blocks,
-        })
+    let night_task = db
+        .create_task(&make_task(&night.id, "Night task"))
+        .await
+        .unwrap();
+    let anytime_task = db
+        .create_task(&make_task(&anytime.id, "Anytime task"))
         .await
         .unwrap();
-    assert_eq!(link.source_task_id, t1.id);
-    assert_eq!(link.target_task_id, t2.id);
-    assert_eq!(link.link_type, LinkType::Blocks);
 
-    // list from source side
-    let from_source = db.list_task_links(&t1.id).await.unwrap();
-    assert_eq!(from_source.len(), 1);
+    let mut queue = Vec::new();
+    for (task_id, priority, run_window) in [
+        (&night_task.id, 10, None),
+        (&night_task.id, 5, Some(open)),
+        (&anytime_task.id, 0, None),
+    ] {
+        let run = db
+            .create_claude_run(&CreateClaudeRun {
+                task_id: task_id.clone(),
+                action: ClaudeAction::Research,
+                required_capability: None,
+                priority,
+                feedback: None,
+                idempotency_key: None,
+                run_window,
+                trace_context: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(run.run_window, run_window);
+        queue.push(run.id);
+    }
 
-    // list from target side
-    let from_target = db.list_task_links(&t2.id).await.unwrap();
-    assert_eq!(from_target.len(), 1);
+    // The run's own open window replaces its project's closed one; the
+    // other night run is held despite its priority
+    let first = db.claim_next_claude_run(&[]).await.unwrap().unwrap();
+    assert_eq!(first.id, queue[1]);
+    let second = db.claim_next_claude_run(&[]).await.unwrap().unwrap();
+    assert_eq!(second.id, queue[2]);
+    assert!(db.claim_next_claude_run(&[]).await.unwrap().is_none());
 
-    // delete
+    let night = db
+        .update_project(
+            &night.id,
+            &UpdateProject {
+                run_window: Some(None),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(night.run_window, None);
+    let third = db.claim_next_claude_run(&[]).await.unwrap().unwrap();
+    assert_eq!(third.id, queue[0]);
+}
+
+/// Test that windows in a time zone are checked at the zone's current
+/// offset, next to windows with a fixed offset.
+pub async fn test_claim_follows_run_window_time_zone(db: &dyn Database) {
+    use chrono::Timelike;
+    use chrono_tz::{America::New_York, Europe::Berlin};
+    use flowstate_core::run_window::zone_offset;
+    use flowstate_core::RunWindow;
+
+    let now = chrono::Utc::now();
+    let utc_minute = (now.hour() * 60 + now.minute()) as i32;
+    let local = |tz, offset: i32| (utc_minute + zone_offset(tz, now) + offset).rem_euclid(24 * 60);
+    let closed = RunWindow::new(local(Berlin, 60), local(Berlin, 120), 0)
+        .unwrap()
+        .with_time_zone(Berlin);
+    let open = RunWindow::new(local(New_York, -20), local(New_York, 20), 0)
+        .unwrap()
+        .with_time_zone(New_York);
+    // Berlin's clock time read as UTC, which is never open now
+    let fixed = RunWindow::new(local(Berlin, -20), local(Berlin, 20), 0).unwrap();
+
+    let berlin = db
+        .create_project(&make_project("window-berlin"))
+        .await
+        .unwrap();
+    let berlin = db
+        .update_project(
+            &berlin.id,
+            &UpdateProject {
+                run_window: Some(Some(closed)),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(berlin.run_window, Some(closed));
+    let anytime = db
+        .create_project(&make_project("window-utc"))
+        .await
+        .unwrap();
+    let berlin_task = db
+        .create_task(&make_task(&berlin.id, "Berlin task"))
+        .await
+        .unwrap();
+    let anytime_task = db
+        .create_task(&make_task(&anytime.id, "UTC task"))
+        .await
+        .unwrap();
+
+    let mut queue = Vec::new();
+    for (task_id, priority, run_window) in [
+        (&berlin_task.id, 10, None),
+        (&anytime_task.id, 10, Some(fixed)),
+        (&berlin_task.id, 0, Some(open)),
+    ] {
+        let run = db
+            .create_claude_run(&CreateClaudeRun {
+                task_id: task_id.clone(),
+                action: ClaudeAction::Research,
+                required_capability: None,
+                priority,
+                feedback: None,
+                idempotency_key: None,
+                run_window,
+                trace_context: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(run.run_window, run_window);
+        queue.push(run.id);
+    }
+
+    let first = db.claim_next_claude_run(&[]).await.unwrap().unwrap();
+    assert_eq!(first.id, queue[2]);
+    assert!(db.claim_next_claude_run(&[]).await.unwrap().is_none());
+}
+
+pub async fn test_claim_respects_project_cap(db: &dyn Database) {
+    let capped = db
+        .create_project(&make_project("claim-capped"))
+        .await
+        .unwrap();
+    let capped = db
+        .update_project(
+            &capped.id,
+            &UpdateProject {
+                max_concurrent_runs: Some(Some(1)),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(capped.max_concurrent_runs, Some(1));
+    let open = db
+        .create_project(&make_project("claim-open"))
+        .await
+        .unwrap();
+    let capped_task = db
+        .create_task(&make_task(&capped.id, "Capped task"))
+        .await
+        .unwrap();
+    let open_task = db
+        .create_task(&make_task(&open.id, "Open task"))
+        .await
+        .unwrap();
+
+    let mut queue = Vec::new();
+    for (task_id, priority) in [
+        (&capped_task.id, 10),
+        (&capped_task.id, 10),
+        (&open_task.id, 0),
+    ] {
+        let run = db
+            .create_claude_run(&CreateClaudeRun {
+                task_id: task_id.clone(),
+                action: ClaudeAction::Research,
+                required_capability: None,
+                priority,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
+            })
+            .await
+            .unwrap();
+        queue.push(run.id);
+    }
+
+    // The second capped run outranks the open one but must wait its turn
+    let first = db.claim_next_claude_run(&[]).await.unwrap().unwrap();
+    assert_eq!(first.id, queue[0]);
+    let second = db.claim_next_claude_run(&[]).await.unwrap().unwrap();
+    assert_eq!(second.id, queue[2]);
+    assert!(db.claim_next_claude_run(&[]).await.unwrap().is_none());
+
+    db.update_claude_run_status(&first.id, ClaudeRunStatus::Completed, None, Some(0))
+        .await
+        .unwrap();
+    let third = db.claim_next_claude_run(&["light"]).await.unwrap().unwrap();
+    assert_eq!(third.id, queue[1]);
+}
+
+/// Test that claims racing for a project capped at one run let only one through.
+pub async fn test_concurrent_claims_respect_project_cap(db: Arc<dyn Database>) {
+    let project = db
+        .create_project(&make_project("claim-race"))
+        .await
+        .unwrap();
+    db.update_project(
+        &project.id,
+        &UpdateProject {
+            max_concurrent_runs: Some(Some(1)),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let task = db
+        .create_task(&make_task(&project.id, "Raced task"))
+        .await
+        .unwrap();
+
+    const CLAIMERS: usize = 8;
+    for _ in 0..CLAIMERS {
+        db.create_claude_run(&CreateClaudeRun {
+            task_id: task.id.clone(),
+            action: ClaudeAction::Research,
+            required_capability: None,
+            priority: 0,
+            feedback: None,
+            idempotency_key: None,
+            run_window: None,
+            trace_context: None,
+        })
+        .await
+        .unwrap();
+    }
+
+    let handles: Vec<_> = (0..CLAIMERS)
+        .map(|_| {
+            let db = db.clone();
+            tokio::spawn(async move { db.claim_next_claude_run(&[]).await.unwrap() })
+        })
+        .collect();
+    let mut claimed = 0;
+    for handle in handles {
+        if handle.await.unwrap().is_some() {
+            claimed += 1;
+        }
+    }
+    assert_eq!(claimed, 1);
+
+    let running = db
+        .list_claude_runs_for_task(&task.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .filter(|r| r.status == ClaudeRunStatus::Running)
+        .count();
+    assert_eq!(running, 1);
+}
+
+/// Test that equal-priority claims are shared across projects by weight
+/// rather than handed out strictly oldest first.
+pub async fn test_claim_fair_across_projects(db: &dyn Database) {
+    let busy = db
+        .create_project(&make_project("claim-busy"))
+        .await
+        .unwrap();
+    let busy = db
+        .update_project(
+            &busy.id,
+            &UpdateProject {
+                claim_weight: Some(3),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(busy.claim_weight, 3);
+    let quiet = db
+        .create_project(&make_project("claim-quiet"))
+        .await
+        .unwrap();
+    assert_eq!(quiet.claim_weight, 1);
+    let busy_task = db
+        .create_task(&make_task(&busy.id, "Busy task"))
+        .await
+        .unwrap();
+    let quiet_task = db
+        .create_task(&make_task(&quiet.id, "Quiet task"))
+        .await
+        .unwrap();
+
+    // The busy project enqueues a backlog before the quiet one enqueues anything
+    let mut busy_runs = Vec::new();
+    for _ in 0..4 {
+        let run = db
+            .create_claude_run(&CreateClaudeRun {
+                task_id: busy_task.id.clone(),
+                action: ClaudeAction::Research,
+                required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
+            })
+            .await
+            .unwrap();
+        busy_runs.push(run.id);
+    }
+    let mut quiet_runs = Vec::new();
+    for _ in 0..2 {
+        let run = db
+            .create_claude_run(&CreateClaudeRun {
+                task_id: quiet_task.id.clone(),
+                action: ClaudeAction::Research,
+                required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
+            })
+            .await
+            .unwrap();
+        quiet_runs.push(run.id);
+    }
+
+    let mut claimed = Vec::new();
+    for _ in 0..4 {
+        claimed.push(db.claim_next_claude_run(&[]).await.unwrap().unwrap().id);
+    }
+    // busy share 0/3 (oldest wins), then 1/3 vs 0/1, then 1/3 vs 1/1, then 2/3 vs 1/1
+    assert_eq!(
+        claimed,
+        vec![
+            busy_runs[0].clone(),
+            quiet_runs[0].clone(),
+            busy_runs[1].clone(),
+            busy_runs[2].clone(),
+        ]
+    );
+}
+
+/// Test find_stale_running_runs and timeout_claude_run.
+pub async fn test_stale_runs(db: &dyn Database) {
+    let project = db
+        .create_project(&make_project("stale-runs"))
+        .await
+        .unwrap();
+    let task = db
+        .create_task(&make_task(&project.id, "Stale task"))
+        .await
+        .unwrap();
+
+    // Create and claim a run to set it Running
+    let run = db
+        .create_claude_run(&CreateClaudeRun {
+            task_id: task.id.clone(),
+            action: ClaudeAction::Build,
+            required_capability: None,
+            priority: 0,
+            feedback: None,
+            idempotency_key: None,
+            run_window: None,
+            trace_context: None,
+        })
+        .await
+        .unwrap();
+    let _claimed = db.claim_next_claude_run(&[]).await.unwrap().unwrap();
+
+    // With a threshold in the future, the run should be considered stale
+    let future = chrono::Utc::now() + chrono::Duration::hours(1);
+    let stale = db.find_stale_running_runs(future).await.unwrap();
+    assert_eq!(stale.len(), 1);
+    assert_eq!(stale[0].id, run.id);
+
+    // With a threshold in the past, no runs should match
+    let past = chrono::Utc::now() - chrono::Duration::hours(1);
+    let not_stale = db.find_stale_running_runs(past).await.unwrap();
+    assert!(not_stale.is_empty());
+
+    // Timeout the running run
+    let timed = db
+        .timeout_claude_run(&run.id, "watchdog timeout")
+        .await
+        .unwrap();
+    assert!(timed.is_some());
+    let timed = timed.unwrap();
+    assert_eq!(timed.status, ClaudeRunStatus::TimedOut);
+    assert_eq!(timed.error_message.as_deref(), Some("watchdog timeout"));
+    assert!(timed.finished_at.is_some());
+
+    // Timeout on an already-timed-out run returns None
+    let again = db
+        .timeout_claude_run(&run.id, "second timeout")
+        .await
+        .unwrap();
+    assert!(again.is_none());
+
+    // After timing out, find_stale_running_runs should return empty
+    let stale_after = db.find_stale_running_runs(future).await.unwrap();
+    assert!(stale_after.is_empty());
+
+    // Test find_stale_salvaging_runs
+    let run2 = db
+        .create_claude_run(&CreateClaudeRun {
+            task_id: task.id.clone(),
+            action: ClaudeAction::Build,
+            required_capability: None,
+            priority: 0,
+            feedback: None,
+            idempotency_key: None,
+            run_window: None,
+            trace_context: None,
+        })
+        .await
+        .unwrap();
+    let _claimed2 = db.claim_next_claude_run(&[]).await.unwrap().unwrap();
+    // Transition to Salvaging
+    db.update_claude_run_status(&run2.id, ClaudeRunStatus::Salvaging, None, None)
+        .await
+        .unwrap();
+
+    let salvaging = db.find_stale_salvaging_runs(future).await.unwrap();
+    assert_eq!(salvaging.len(), 1);
+    assert_eq!(salvaging[0].id, run2.id);
+
+    // Timeout the salvaging run too
+    let timed2 = db
+        .timeout_claude_run(&run2.id, "salvage timeout")
+        .await
+        .unwrap();
+    assert!(timed2.is_some());
+    assert_eq!(timed2.unwrap().status, ClaudeRunStatus::TimedOut);
+}
+
+// ---------------------------------------------------------------------------
+// Task link tests
+// ---------------------------------------------------------------------------
+
+/// Test requeue_claude_run and set_claude_run_priority.
+pub async fn test_requeue_and_priority(db: &dyn Database) {
+    let project = db
+        .create_project(&make_project("requeue-runs"))
+        .await
+        .unwrap();
+    let task = db
+        .create_task(&make_task(&project.id, "Requeue task"))
+        .await
+        .unwrap();
+    let run = db
+        .create_claude_run(&CreateClaudeRun {
+            task_id: task.id.clone(),
+            action: ClaudeAction::Build,
+            required_capability: None,
+            priority: 0,
+            feedback: None,
+            idempotency_key: None,
+            run_window: None,
+            trace_context: None,
+        })
+        .await
+        .unwrap();
+
+    // Queued runs can be reprioritized but not requeued
+    let bumped = db.set_claude_run_priority(&run.id, 5).await.unwrap();
+    assert_eq!(bumped.unwrap().priority, 5);
+    assert!(db.requeue_claude_run(&run.id).await.unwrap().is_none());
+
+    let claimed = db.claim_next_claude_run(&[]).await.unwrap().unwrap();
+    assert_eq!(claimed.id, run.id);
+    db.update_claude_run_status(&run.id, ClaudeRunStatus::Failed, Some("boom"), Some(1))
+        .await
+        .unwrap();
+    assert!(db
+        .set_claude_run_priority(&run.id, 1)
+        .await
+        .unwrap()
+        .is_none());
+
+    let requeued = db.requeue_claude_run(&run.id).await.unwrap().unwrap();
+    assert_eq!(requeued.status, ClaudeRunStatus::Queued);
+    assert_eq!(requeued.priority, 5);
+    assert!(requeued.error_message.is_none());
+    assert!(requeued.exit_code.is_none());
+    assert!(requeued.finished_at.is_none());
+    assert!(requeued.runner_id.is_none());
+    assert_eq!(
+        db.claim_next_claude_run(&[]).await.unwrap().unwrap().id,
+        run.id
+    );
+
+    assert!(db.requeue_claude_run("missing").await.unwrap().is_none());
+    assert!(db
+        .set_claude_run_priority("missing", 1)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+/// Test task link CRUD: create, list, delete.
+pub async fn test_task_links(db: &dyn Database) {
+    let project = db
+        .create_project(&make_project("task-links"))
+        .await
+        .unwrap();
+    let t1 = db
+        .create_task(&make_task(&project.id, "Source"))
+        .await
+        .unwrap();
+    let t2 = db
+        .create_task(&make_task(&project.id, "Target"))
+        .await
+        .unwrap();
+
+    let link = db
+        .create_task_link(&CreateTaskLink {
+            source_task_id: t1.id.clone(),
+            target_task_id: t2.id.clone(),
+            link_type: LinkType::Blocks,
+        })
+        .await
+        .unwrap();
+    assert_eq!(link.source_task_id, t1.id);
+    assert_eq!(link.target_task_id, t2.id);
+    assert_eq!(link.link_type, LinkType::Blocks);
+
+    // list from source side
+    let from_source = db.list_task_links(&t1.id).await.unwrap();
+    assert_eq!(from_source.len(), 1);
+
+    // list from target side
+    let from_target = db.list_task_links(&t2.id).await.unwrap();
+    assert_eq!(from_target.len(), 1);
+    assert_eq!(
+        db.get_task_link(&link.id).await.unwrap().source_task_id,
+        t1.id
+    );
+
+    // delete
     db.delete_task_link(&link.id).await.unwrap();
     let after_delete = db.list_task_links(&t1.id).await.unwrap();
     assert!(after_delete.is_empty());
 
     // delete non-existent should error
     assert!(db.delete_task_link(&link.id).await.is_err());
+    assert!(matches!(
+        db.get_task_link(&link.id).await,
+        Err(flowstate_db::DbError::NotFound(_))
+    ));
 }
 
 // ---------------------------------------------------------------------------
@@ -687,6 +1812,11 @@ pub async fn test_task_prs(db: &dyn Database) {
             task_id: task.id.clone(),
             action: ClaudeAction::Build,
             required_capability: None,
+            priority: 0,
+            feedback: None,
+            idempotency_key: None,
+            run_window: None,
+            trace_context: None,
         })
         .await
         .unwrap();
@@ -698,6 +1828,7 @@ pub async fn test_task_prs(db: &dyn Database) {
             pr_url: "https://github.com/owner/repo/pull/42".into(),
             pr_number: 42,
             branch_name: "flowstate/my-feature".into(),
+            draft: false,
         })
         .await
         .unwrap();
@@ -719,6 +1850,7 @@ pub async fn test_task_prs(db: &dyn Database) {
         pr_url: "https://github.com/owner/repo/pull/43".into(),
         pr_number: 43,
         branch_name: "flowstate/second-feature".into(),
+        draft: false,
     })
     .await
     .unwrap();
@@ -733,19 +1865,57 @@ pub async fn test_task_prs(db: &dyn Database) {
             pr_url: "https://github.com/owner/repo/pull/42".into(),
             pr_number: 42,
             branch_name: "flowstate/my-feature".into(),
+            draft: false,
         })
         .await
         .unwrap();
     assert_eq!(dup.id, pr.id);
     let prs = db.list_task_prs(&task.id).await.unwrap();
     assert_eq!(prs.len(), 2);
-}
+
+    // Lookup by URL and state changes
+    assert_eq!(pr.state, PrState::Open);
+    let found = db.get_task_pr_by_url(&pr.pr_url).await.unwrap();
+    assert_eq!(found.id, pr.id);
+    assert_eq!(db.get_task_pr(&pr.id).await.unwrap().pr_url, pr.pr_url);
+    assert!(matches!(
+        db.get_task_pr("missing").await,
+        Err(flowstate_db::DbError::NotFound(_))
+    ));
+    assert!(db
+        .get_task_pr_by_url("https://github.com/owner/repo/pull/99")
+        .await
+        .is_err());
+    assert_eq!(pr.checks_status, None);
+    let checked = db
+        .set_task_pr_checks(&pr.id, Some(ChecksStatus::Failure))
+        .await
+        .unwrap();
+    assert_eq!(checked.checks_status, Some(ChecksStatus::Failure));
+    assert!(!pr.draft);
+    assert!(db.set_task_pr_draft(&pr.id, true).await.unwrap().draft);
+    assert!(!db.set_task_pr_draft(&pr.id, false).await.unwrap().draft);
+    assert_eq!(db.list_open_task_prs().await.unwrap().len(), 2);
+    let merged = db.set_task_pr_state(&pr.id, PrState::Merged).await.unwrap();
+    assert_eq!(merged.state, PrState::Merged);
+    assert_eq!(
+        db.get_task_pr_by_url(&pr.pr_url).await.unwrap().state,
+        PrState::Merged
+    );
+    let open = db.list_open_task_prs().await.unwrap();
+    assert_eq!(open.len(), 1);
+    assert_ne!(open[0].id, pr.id);
+    assert!(db
+        .set_task_pr_state("missing", PrState::Closed)
+        .await
+        .is_err());
+}
 
 // ---------------------------------------------------------------------------
-// Attachment tests
-// ---------------------------------------------------------------------------
+// Attachment tests
+// ---------------------------------------------------------------------------
 
-/// Test attachment CRUD: create, list, get, delete.
+/// Test attachment CRUD: create, list, get, delete, and shared-blob refcounts.
 pub async fn test_attachments(db: &dyn Database) {
     let project = db
         .create_project(&make_project("attachments"))
@@ -757,13 +1927,22 @@ pub async fn test_attachments(db: &dyn Database) {
         .unwrap();
 
     let att = db
-        .create_attachment(&task.id, "screenshot.png", "s3://bucket/key", 12345)
+        .create_attachment(
+            &task.id,
+            "screenshot.png",
+            "s3://bucket/key",
+            12345,
+            "image/png",
+            "abc123",
+        )
         .await
         .unwrap();
     assert_eq!(att.task_id, task.id);
     assert_eq!(att.filename, "screenshot.png");
     assert_eq!(att.store_key, "s3://bucket/key");
     assert_eq!(att.size_bytes, 12345);
+    assert_eq!(att.content_type, "image/png");
+    assert_eq!(att.sha256, "abc123");
 
     // get
     let fetched = db.get_attachment(&att.id).await.unwrap();
@@ -774,9 +1953,16 @@ pub async fn test_attachments(db: &dyn Database) {
     assert_eq!(list.len(), 1);
 
     // create another
-    db.create_attachment(&task.id, "log.txt", "s3://bucket/key2", 100)
-        .await
-        .unwrap();
+    db.create_attachment(
+        &task.id,
+        "log.txt",
+        "s3://bucket/key2",
+        100,
+        "text/plain",
+        "",
+    )
+    .await
+    .unwrap();
     let list = db.list_attachments(&task.id).await.unwrap();
     assert_eq!(list.len(), 2);
 
@@ -793,6 +1979,25 @@ pub async fn test_attachments(db: &dyn Database) {
 
     // delete non-existent should error
     assert!(db.delete_attachment(&att.id).await.is_err());
+
+    // attachments sharing a blob are counted, including across tasks
+    let other = db
+        .create_task(&make_task(&project.id, "Other"))
+        .await
+        .unwrap();
+    let blob = "attachments/sha256/abc123";
+    let first = db
+        .create_attachment(&task.id, "a.png", blob, 5, "image/png", "abc123")
+        .await
+        .unwrap();
+    db.create_attachment(&other.id, "b.png", blob, 5, "image/png", "abc123")
+        .await
+        .unwrap();
+    assert_eq!(db.count_attachment_refs(blob).await.unwrap(), 2);
+    db.delete_attachment(&first.id).await.unwrap();
+    assert_eq!(db.count_attachment_refs(blob).await.unwrap(), 1);
+    db.delete_task(&other.id).await.unwrap();
+    assert_eq!(db.count_attachment_refs(blob).await.unwrap(), 0);
 }
 
 // ---------------------------------------------------------------------------
@@ -847,6 +2052,355 @@ pub async fn test_api_keys(db: &dyn Database) {
     assert!(db.delete_api_key(&key.id).await.is_err());
 }
 
+/// Test per-key CIDR/route policy: defaults, set, clear, missing key.
+pub async fn test_api_key_policy(db: &dyn Database) {
+    let key = db.insert_api_key("runner", "hash_policy").await.unwrap();
+    assert!(key.allowed_cidrs.is_empty());
+    assert!(key.allowed_routes.is_empty());
+
+    let cidrs = vec!["10.0.0.0/24".to_string(), "fd00::/8".to_string()];
+    let routes = vec!["/api/claude-runs".to_string()];
+    let updated = db
+        .set_api_key_policy(&key.id, &cidrs, &routes)
+        .await
+        .unwrap();
+    assert_eq!(updated.allowed_cidrs, cidrs);
+    assert_eq!(updated.allowed_routes, routes);
+
+    let found = db
+        .find_api_key_by_hash("hash_policy")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.allowed_cidrs, cidrs);
+
+    let cleared = db.set_api_key_policy(&key.id, &[], &[]).await.unwrap();
+    assert!(cleared.allowed_cidrs.is_empty());
+    assert!(cleared.allowed_routes.is_empty());
+
+    assert!(db
+        .set_api_key_policy("nonexistent", &cidrs, &routes)
+        .await
+        .is_err());
+}
+
+/// Test per-key project scopes: set, replace, survive project deletion,
+/// clear, missing key.
+pub async fn test_api_key_projects(db: &dyn Database) {
+    let a = db.create_project(&make_project("scope-a")).await.unwrap();
+    let b = db.create_project(&make_project("scope-b")).await.unwrap();
+    let key = db.insert_api_key("team", "hash_scope").await.unwrap();
+    assert!(key.allowed_projects.is_empty());
+
+    let mut both = vec![a.id.clone(), b.id.clone()];
+    both.sort();
+    let updated = db.set_api_key_projects(&key.id, &both).await.unwrap();
+    assert_eq!(updated.allowed_projects, both);
+
+    let replaced = db
+        .set_api_key_projects(&key.id, std::slice::from_ref(&a.id))
+        .await
+        .unwrap();
+    assert_eq!(replaced.allowed_projects, vec![a.id.clone()]);
+
+    // Deleting the project must not turn the key into an unscoped one
+    db.delete_project(&a.id).await.unwrap();
+    let found = db
+        .find_api_key_by_hash("hash_scope")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(found.allowed_projects, vec![a.id.clone()]);
+
+    let cleared = db.set_api_key_projects(&key.id, &[]).await.unwrap();
+    assert!(cleared.allowed_projects.is_empty());
+
+    assert!(db.set_api_key_projects("nonexistent", &both).await.is_err());
+}
+
+// ---------------------------------------------------------------------------
+// Feature flag tests
+// ---------------------------------------------------------------------------
+
+/// Test feature flag upsert, scoping and removal.
+pub async fn test_feature_flags(db: &dyn Database) {
+    assert!(db.list_feature_flags().await.unwrap().is_empty());
+
+    let global = db.set_feature_flag("salvage", None, false).await.unwrap();
+    assert_eq!(global.project_id, None);
+    assert!(!global.enabled);
+
+    let scoped = db
+        .set_feature_flag("salvage", Some("proj-1"), true)
+        .await
+        .unwrap();
+    assert_eq!(scoped.project_id.as_deref(), Some("proj-1"));
+
+    // Upsert replaces rather than duplicating
+    let flipped = db.set_feature_flag("salvage", None, true).await.unwrap();
+    assert!(flipped.enabled);
+    assert_eq!(db.list_feature_flags().await.unwrap().len(), 2);
+
+    db.delete_feature_flag("salvage", Some("proj-1"))
+        .await
+        .unwrap();
+    let remaining = db.list_feature_flags().await.unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].project_id, None);
+
+    assert!(db
+        .delete_feature_flag("salvage", Some("proj-1"))
+        .await
+        .is_err());
+}
+
+// ---------------------------------------------------------------------------
+// Webhook tests
+// ---------------------------------------------------------------------------
+
+/// Test webhook CRUD and the delivery queue: claiming leases deliveries,
+/// recorded attempts reschedule or settle them, and pruning keeps pending ones.
+pub async fn test_webhooks(db: &dyn Database) {
+    let project = db.create_project(&make_project("hooks")).await.unwrap();
+    let hook = db
+        .create_webhook(&CreateWebhook {
+            url: "https://example.com/hook".into(),
+            secret: "sealed".into(),
+            events: vec![WebhookEvent::RunFinished],
+            project_id: Some(project.id.clone()),
+        })
+        .await
+        .unwrap();
+    assert!(hook.enabled);
+    assert_eq!(hook.secret, "sealed");
+    assert_eq!(hook.events, vec![WebhookEvent::RunFinished]);
+    assert_eq!(db.get_webhook(&hook.id).await.unwrap().url, hook.url);
+
+    let updated = db
+        .update_webhook(
+            &hook.id,
+            &UpdateWebhook {
+                events: Some(vec![]),
+                project_id: Some(None),
+                enabled: Some(false),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert!(updated.events.is_empty());
+    assert_eq!(updated.project_id, None);
+    assert!(!updated.enabled);
+    assert_eq!(updated.secret, "sealed");
+    let unchanged = db
+        .update_webhook(&hook.id, &UpdateWebhook::default())
+        .await
+        .unwrap();
+    assert!(!unchanged.enabled);
+    assert_eq!(db.list_webhooks().await.unwrap().len(), 1);
+
+    let first = db
+        .enqueue_webhook_delivery(&hook.id, WebhookEvent::TaskApproved, "{\"n\":1}")
+        .await
+        .unwrap();
+    assert_eq!(first.status, DeliveryStatus::Pending);
+    assert_eq!(first.attempts, 0);
+    let second = db
+        .enqueue_webhook_delivery(&hook.id, WebhookEvent::RunFinished, "{\"n\":2}")
+        .await
+        .unwrap();
+
+    // Claimed deliveries are leased and not handed out again
+    let lease = chrono::Utc::now() + chrono::Duration::minutes(5);
+    let claimed = db.claim_webhook_deliveries(lease, 1).await.unwrap();
+    assert_eq!(claimed.len(), 1);
+    assert_eq!(claimed[0].id, first.id);
+    let claimed = db.claim_webhook_deliveries(lease, 10).await.unwrap();
+    assert_eq!(claimed.len(), 1);
+    assert_eq!(claimed[0].id, second.id);
+    assert!(db
+        .claim_webhook_deliveries(lease, 10)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let delivered = db
+        .record_webhook_attempt(
+            &first.id,
+            &DeliveryAttempt {
+                status: DeliveryStatus::Delivered,
+                response_status: Some(200),
+                error: None,
+                next_attempt_at: chrono::Utc::now(),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(delivered.status, DeliveryStatus::Delivered);
+    assert_eq!(delivered.attempts, 1);
+    assert!(delivered.delivered_at.is_some());
+
+    // A failed attempt with a retry in the past is due again
+    let retry = db
+        .record_webhook_attempt(
+            &second.id,
+            &DeliveryAttempt {
+                status: DeliveryStatus::Pending,
+                response_status: Some(502),
+                error: Some("bad gateway".into()),
+                next_attempt_at: chrono::Utc::now() - chrono::Duration::seconds(1),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(retry.attempts, 1);
+    assert_eq!(retry.last_error.as_deref(), Some("bad gateway"));
+    assert_eq!(retry.delivered_at, None);
+    let claimed = db.claim_webhook_deliveries(lease, 10).await.unwrap();
+    assert_eq!(claimed.len(), 1);
+    assert_eq!(claimed[0].id, second.id);
+
+    let listed = db.list_webhook_deliveries(&hook.id, 10).await.unwrap();
+    assert_eq!(listed.len(), 2);
+    assert_eq!(listed[0].id, second.id);
+
+    let pruned = db
+        .prune_webhook_deliveries(chrono::Utc::now() + chrono::Duration::seconds(1))
+        .await
+        .unwrap();
+    assert_eq!(pruned, 1);
+    let listed = db.list_webhook_deliveries(&hook.id, 10).await.unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].id, second.id);
+
+    db.delete_webhook(&hook.id).await.unwrap();
+    assert!(db.get_webhook(&hook.id).await.is_err());
+    assert!(db
+        .list_webhook_deliveries(&hook.id, 10)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(db.delete_webhook(&hook.id).await.is_err());
+}
+
+// ---------------------------------------------------------------------------
+// Run metrics tests
+// ---------------------------------------------------------------------------
+
+/// Test recording run metrics and aggregating them per action and project.
+pub async fn test_run_metrics(db: &dyn Database) {
+    let p1 = db.create_project(&make_project("metrics-a")).await.unwrap();
+    let p2 = db.create_project(&make_project("metrics-b")).await.unwrap();
+    let t1 = db.create_task(&make_task(&p1.id, "T1")).await.unwrap();
+    let t2 = db.create_task(&make_task(&p2.id, "T2")).await.unwrap();
+
+    let mut runs = Vec::new();
+    for (task_id, action) in [
+        (&t1.id, ClaudeAction::Research),
+        (&t1.id, ClaudeAction::Research),
+        (&t1.id, ClaudeAction::Build),
+        (&t2.id, ClaudeAction::Research),
+    ] {
+        let run = db
+            .create_claude_run(&CreateClaudeRun {
+                task_id: task_id.clone(),
+                action,
+                required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
+            })
+            .await
+            .unwrap();
+        runs.push(run.id);
+    }
+
+    let first = db
+        .record_run_metrics(
+            &runs[0],
+            &RecordRunMetrics {
+                duration_ms: 1_000,
+                stdout_bytes: 10,
+                input_tokens: Some(100),
+                output_tokens: Some(50),
+                cost_usd: Some(0.25),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(first.duration_ms, 1_000);
+    assert_eq!(first.input_tokens, Some(100));
+
+    // A second report for the same run replaces the first
+    db.record_run_metrics(
+        &runs[0],
+        &RecordRunMetrics {
+            duration_ms: 2_000,
+            stdout_bytes: 20,
+            input_tokens: Some(200),
+            output_tokens: Some(80),
+            cost_usd: Some(0.5),
+        },
+    )
+    .await
+    .unwrap();
+    for (run_id, duration_ms) in [(&runs[1], 4_000), (&runs[2], 9_000), (&runs[3], 500)] {
+        db.record_run_metrics(
+            run_id,
+            &RecordRunMetrics {
+                duration_ms,
+                stdout_bytes: 5,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    }
+
+    let all = db
+        .summarize_run_metrics(&RunMetricsFilter::default())
+        .await
+        .unwrap();
+    assert_eq!(all.len(), 2);
+    let research = all.iter().find(|s| s.action == "research").unwrap();
+    assert_eq!(research.runs, 3);
+    assert_eq!(research.total_duration_ms, 6_500);
+    assert_eq!(research.avg_duration_ms, 2_166);
+    assert_eq!(research.total_stdout_bytes, 30);
+    assert_eq!(research.input_tokens, 200);
+    assert_eq!(research.output_tokens, 80);
+    assert!((research.cost_usd - 0.5).abs() < 1e-9);
+    assert_eq!(research.unpriced_runs, 2);
+
+    let scoped = db
+        .summarize_run_metrics(&RunMetricsFilter {
+            project_id: Some(p1.id.clone()),
+            since: None,
+        })
+        .await
+        .unwrap();
+    let research = scoped.iter().find(|s| s.action == "research").unwrap();
+    assert_eq!(research.runs, 2);
+    assert_eq!(scoped.iter().find(|s| s.action == "build").unwrap().runs, 1);
+
+    let future = db
+        .summarize_run_metrics(&RunMetricsFilter {
+            project_id: None,
+            since: Some(chrono::Utc::now() + chrono::Duration::hours(1)),
+        })
+        .await
+        .unwrap();
+    assert!(future.is_empty());
+
+    assert!(matches!(
+        db.record_run_metrics("nonexistent", &RecordRunMetrics::default())
+            .await,
+        Err(flowstate_db::DbError::NotFound(_))
+    ));
+}
+
 // ---------------------------------------------------------------------------
 // Sprint tests
 // ---------------------------------------------------------------------------
@@ -951,6 +2505,7 @@ pub async fn test_subtask_workflow(db: &dyn Database) {
             description: "Big feature".into(),
             status: Status::Build,
             priority: Priority::High,
+            task_type: TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -958,6 +2513,7 @@ pub async fn test_subtask_workflow(db: &dyn Database) {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .await
         .unwrap();
@@ -983,6 +2539,7 @@ pub async fn test_subtask_workflow(db: &dyn Database) {
             description: String::new(),
             status: Status::Todo,
             priority: Priority::High,
+            task_type: TaskType::Feature,
             parent_id: Some(parent.id.clone()),
             reviewer: String::new(),
             research_capability: None,
@@ -990,6 +2547,7 @@ pub async fn test_subtask_workflow(db: &dyn Database) {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .await
         .unwrap();
@@ -1015,6 +2573,94 @@ pub async fn test_subtask_workflow(db: &dyn Database) {
     assert_eq!(sprint_tasks[0].id, parent.id);
 }
 
+/// Archiving a sprint archives its tasks and their subtasks; archived tasks
+/// leave default lists and status counts but can still be listed.
+pub async fn test_archive_sprint_and_tasks(db: &dyn Database) {
+    let project = db.create_project(&make_project("archive")).await.unwrap();
+    let sprint = db
+        .create_sprint(&CreateSprint {
+            project_id: project.id.clone(),
+            name: "Done sprint".into(),
+            goal: String::new(),
+            starts_at: None,
+            ends_at: None,
+        })
+        .await
+        .unwrap();
+    assert!(!sprint.archived);
+
+    let in_sprint = db
+        .create_task(&make_task(&project.id, "In sprint"))
+        .await
+        .unwrap();
+    db.update_task(
+        &in_sprint.id,
+        &UpdateTask {
+            sprint_id: Some(Some(sprint.id.clone())),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let child = db
+        .create_task(&CreateTask {
+            parent_id: Some(in_sprint.id.clone()),
+            ..make_task(&project.id, "Child")
+        })
+        .await
+        .unwrap();
+    let loose = db
+        .create_task(&make_task(&project.id, "Loose"))
+        .await
+        .unwrap();
+    let other = db
+        .create_task(&make_task(&project.id, "Other"))
+        .await
+        .unwrap();
+
+    let archived_sprint = db.archive_sprint(&sprint.id).await.unwrap();
+    assert!(archived_sprint.archived);
+    assert!(db.get_task(&in_sprint.id).await.unwrap().archived);
+    assert!(db.get_task(&child.id).await.unwrap().archived);
+    assert!(!db.get_task(&loose.id).await.unwrap().archived);
+
+    let archived_task = db.archive_task(&loose.id).await.unwrap();
+    assert!(archived_task.archived);
+
+    let project_filter = TaskFilter {
+        project_id: Some(project.id.clone()),
+        ..Default::default()
+    };
+    let visible = db.list_tasks(&project_filter).await.unwrap();
+    assert_eq!(visible.len(), 1);
+    assert_eq!(visible[0].id, other.id);
+    let archived = db
+        .list_tasks(&TaskFilter {
+            archived: true,
+            ..project_filter
+        })
+        .await
+        .unwrap();
+    assert_eq!(archived.len(), 3);
+    let counted: i64 = db
+        .count_tasks_by_status(&project.id)
+        .await
+        .unwrap()
+        .iter()
+        .map(|(_, n)| n)
+        .sum();
+    assert_eq!(counted, 1);
+
+    assert!(matches!(
+        db.archive_task("missing").await,
+        Err(flowstate_db::DbError::NotFound(_))
+    ));
+    assert!(matches!(
+        db.archive_sprint("missing").await,
+        Err(flowstate_db::DbError::NotFound(_))
+    ));
+}
+
 // ---------------------------------------------------------------------------
 // Edge case tests
 // ---------------------------------------------------------------------------
@@ -1054,6 +2700,7 @@ pub async fn test_list_tasks_combined_filters(db: &dyn Database) {
             description: String::new(),
             status: Status::Todo,
             priority: Priority::High,
+            task_type: TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -1061,6 +2708,7 @@ pub async fn test_list_tasks_combined_filters(db: &dyn Database) {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .await
         .unwrap();
@@ -1072,6 +2720,7 @@ pub async fn test_list_tasks_combined_filters(db: &dyn Database) {
             description: String::new(),
             status: Status::Todo,
             priority: Priority::Medium,
+            task_type: TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -1079,6 +2728,7 @@ pub async fn test_list_tasks_combined_filters(db: &dyn Database) {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .await
         .unwrap();
@@ -1103,10 +2753,14 @@ pub async fn test_list_tasks_combined_filters(db: &dyn Database) {
 
 /// Test update_project with all fields set at once.
 pub async fn test_update_project_all_fields(db: &dyn Database) {
+    use chrono::Timelike;
+
     let project = db
         .create_project(&make_project("update-all"))
         .await
         .unwrap();
+    // Whole seconds, so both backends return it unchanged
+    let until = chrono::Utc::now().with_nanosecond(0).unwrap() + chrono::Duration::days(7);
 
     let updated = db
         .update_project(
@@ -1116,6 +2770,13 @@ pub async fn test_update_project_all_fields(db: &dyn Database) {
                 description: Some("New desc".into()),
                 repo_url: Some("https://new-url.com".into()),
                 repo_token: Some("tok_123".into()),
+                docs_in_repo: Some(true),
+                verify_followups: Some(true),
+                monthly_budget_usd: Some(Some(25.5)),
+                budget_override_until: Some(Some(until)),
+                ssh_key_path: Some(Some("/etc/flowstate/deploy_key".into())),
+                fork_owner: Some(Some("flowstate-bot".into())),
+                repo_subpath: Some(Some("packages/web".into())),
                 ..Default::default()
             },
         )
@@ -1124,6 +2785,39 @@ pub async fn test_update_project_all_fields(db: &dyn Database) {
     assert_eq!(updated.name, "New Name");
     assert_eq!(updated.description, "New desc");
     assert_eq!(updated.repo_url, "https://new-url.com");
+    assert!(!project.docs_in_repo);
+    assert!(updated.docs_in_repo);
+    assert!(!project.verify_followups);
+    assert!(updated.verify_followups);
+    assert_eq!(project.monthly_budget_usd, None);
+    assert_eq!(updated.monthly_budget_usd, Some(25.5));
+    assert_eq!(updated.budget_override_until, Some(until));
+    assert_eq!(
+        updated.ssh_key_path.as_deref(),
+        Some("/etc/flowstate/deploy_key")
+    );
+    assert_eq!(updated.fork_owner.as_deref(), Some("flowstate-bot"));
+    assert_eq!(updated.repo_subpath.as_deref(), Some("packages/web"));
+
+    let cleared = db
+        .update_project(
+            &project.id,
+            &UpdateProject {
+                monthly_budget_usd: Some(None),
+                budget_override_until: Some(None),
+                ssh_key_path: Some(None),
+                fork_owner: Some(None),
+                repo_subpath: Some(None),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(cleared.monthly_budget_usd, None);
+    assert_eq!(cleared.budget_override_until, None);
+    assert_eq!(cleared.ssh_key_path, None);
+    assert_eq!(cleared.fork_owner, None);
+    assert_eq!(cleared.repo_subpath, None);
 }
 
 /// Test update_project with default (no-op) returns project unchanged.
@@ -1219,3 +2913,1526 @@ pub async fn test_update_sprint_no_changes(db: &dyn Database) {
     assert_eq!(updated.goal, "Goal");
     assert_eq!(updated.status, SprintStatus::Planned);
 }
+
+// ---------------------------------------------------------------------------
+// Backup / restore tests
+// ---------------------------------------------------------------------------
+
+/// Export a populated database, wipe it, import the snapshot, and verify
+/// every entity comes back with its original ID.
+pub async fn test_snapshot_roundtrip(db: &dyn Database) {
+    let project = db.create_project(&make_project("snap")).await.unwrap();
+    db.update_project(
+        &project.id,
+        &UpdateProject {
+            docs_in_repo: Some(true),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let sprint = db
+        .create_sprint(&CreateSprint {
+            project_id: project.id.clone(),
+            name: "S1".into(),
+            goal: String::new(),
+            starts_at: None,
+            ends_at: None,
+        })
+        .await
+        .unwrap();
+    let parent = db
+        .create_task(&make_task(&project.id, "Parent"))
+        .await
+        .unwrap();
+    let child = db
+        .create_task(&CreateTask {
+            parent_id: Some(parent.id.clone()),
+            task_type: TaskType::Chore,
+            ..make_task(&project.id, "Child")
+        })
+        .await
+        .unwrap();
+    db.update_task(
+        &parent.id,
+        &UpdateTask {
+            sprint_id: Some(Some(sprint.id.clone())),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let run = db
+        .create_claude_run(&CreateClaudeRun {
+            task_id: parent.id.clone(),
+            action: ClaudeAction::Research,
+            required_capability: None,
+            priority: 0,
+            feedback: None,
+            idempotency_key: None,
+            run_window: None,
+            trace_context: None,
+        })
+        .await
+        .unwrap();
+    db.record_run_metrics(
+        &run.id,
+        &RecordRunMetrics {
+            duration_ms: 1_000,
+            cost_usd: Some(0.25),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    db.create_task_link(&CreateTaskLink {
+        source_task_id: parent.id.clone(),
+        target_task_id: child.id.clone(),
+        link_type: LinkType::Blocks,
+    })
+    .await
+    .unwrap();
+    db.create_task_pr(&CreateTaskPr {
+        task_id: parent.id.clone(),
+        claude_run_id: Some(run.id.clone()),
+        pr_url: "https://example.com/pr/1".into(),
+        pr_number: 1,
+        branch_name: "snap-branch".into(),
+        draft: false,
+    })
+    .await
+    .unwrap();
+    db.create_attachment(&parent.id, "a.txt", "tasks/a.txt", 3, "text/plain", "")
+        .await
+        .unwrap();
+    let hook = db
+        .create_webhook(&CreateWebhook {
+            url: "https://example.com/snap".into(),
+            secret: "sealed".into(),
+            events: vec![WebhookEvent::RunFinished],
+            project_id: Some(project.id.clone()),
+        })
+        .await
+        .unwrap();
+    db.set_feature_flag("salvage", Some(&project.id), false)
+        .await
+        .unwrap();
+    db.record_task_import(
+        &project.id,
+        "https://github.com/acme/app/issues/7",
+        &parent.id,
+    )
+    .await
+    .unwrap();
+    db.set_approval_rule(&project.id, ApprovalPhase::Plan, ApprovalPolicy::Reviewer)
+        .await
+        .unwrap();
+    let label = db
+        .ensure_label(&project.id, "backend", "#336699")
+        .await
+        .unwrap();
+    db.add_task_label(&child.id, &label.id).await.unwrap();
+    let subscription = db
+        .create_subscription(&CreateSubscription {
+            channel: NotifyChannel::Slack,
+            target: "https://hooks.slack.com/services/snap".into(),
+            events: vec![NotifyEvent::RunFailed],
+            project_id: Some(project.id.clone()),
+            user_id: None,
+        })
+        .await
+        .unwrap();
+
+    let snapshot = db.export_snapshot().await.unwrap();
+    assert_eq!(snapshot.projects.len(), 1);
+    assert_eq!(snapshot.sprints.len(), 1);
+    assert_eq!(snapshot.tasks.len(), 2);
+    assert_eq!(snapshot.claude_runs.len(), 1);
+    assert_eq!(snapshot.run_metrics.len(), 1);
+    assert_eq!(snapshot.task_links.len(), 1);
+    assert_eq!(snapshot.task_prs.len(), 1);
+    assert_eq!(snapshot.attachments.len(), 1);
+    assert_eq!(snapshot.task_revisions.len(), 1);
+    assert_eq!(snapshot.webhooks.len(), 1);
+    assert_eq!(snapshot.feature_flags.len(), 1);
+    assert_eq!(snapshot.task_imports.len(), 1);
+    assert_eq!(snapshot.approval_rules.len(), 1);
+    assert_eq!(snapshot.subscriptions.len(), 1);
+    assert_eq!(snapshot.labels.len(), 1);
+    assert_eq!(snapshot.task_labels.len(), 1);
+
+    // Importing over existing rows must fail atomically.
+    assert!(db.import_snapshot(&snapshot).await.is_err());
+
+    db.delete_project(&project.id).await.unwrap();
+    db.delete_feature_flag("salvage", Some(&project.id))
+        .await
+        .unwrap();
+    assert!(db.list_projects().await.unwrap().is_empty());
+
+    db.import_snapshot(&snapshot).await.unwrap();
+    assert!(db.get_project(&project.id).await.unwrap().docs_in_repo);
+    let restored_parent = db.get_task(&parent.id).await.unwrap();
+    assert_eq!(
+        restored_parent.sprint_id.as_deref(),
+        Some(sprint.id.as_str())
+    );
+    let restored_child = db.get_task(&child.id).await.unwrap();
+    assert_eq!(
+        restored_child.parent_id.as_deref(),
+        Some(parent.id.as_str())
+    );
+    assert_eq!(restored_child.task_type, TaskType::Chore);
+    let restored_run = db.get_claude_run(&run.id).await.unwrap();
+    assert_eq!(restored_run.action, ClaudeAction::Research);
+    // Cost history survives, so monthly budgets carry over
+    let spent = db
+        .summarize_run_metrics(&RunMetricsFilter {
+            project_id: Some(project.id.clone()),
+            since: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(spent.len(), 1);
+    assert_eq!(spent[0].cost_usd, 0.25);
+    assert_eq!(db.list_task_links(&parent.id).await.unwrap().len(), 1);
+    assert_eq!(db.list_task_prs(&parent.id).await.unwrap().len(), 1);
+    assert_eq!(db.list_attachments(&parent.id).await.unwrap().len(), 1);
+    assert_eq!(db.list_task_revisions(&parent.id).await.unwrap().len(), 1);
+    let restored_hook = db.get_webhook(&hook.id).await.unwrap();
+    assert_eq!(restored_hook.secret, "sealed");
+    assert_eq!(restored_hook.events, vec![WebhookEvent::RunFinished]);
+    let flags = db.list_feature_flags().await.unwrap();
+    assert_eq!(flags.len(), 1);
+    assert!(!flags[0].enabled);
+    assert_eq!(flags[0].project_id.as_deref(), Some(project.id.as_str()));
+    // Already-imported issues stay deduplicated
+    assert_eq!(
+        db.find_imported_task(&project.id, "https://github.com/acme/app/issues/7")
+            .await
+            .unwrap()
+            .as_deref(),
+        Some(parent.id.as_str())
+    );
+    let rules = db.list_approval_rules(&project.id).await.unwrap();
+    assert_eq!(rules.len(), 1);
+    assert_eq!(rules[0].phase, ApprovalPhase::Plan);
+    assert_eq!(rules[0].policy, ApprovalPolicy::Reviewer);
+    let restored_sub = db.get_subscription(&subscription.id).await.unwrap();
+    assert_eq!(restored_sub.channel, NotifyChannel::Slack);
+    assert_eq!(restored_sub.events, vec![NotifyEvent::RunFailed]);
+    assert_eq!(
+        restored_sub.project_id.as_deref(),
+        Some(project.id.as_str())
+    );
+    let restored_labels = db.list_task_labels(&child.id).await.unwrap();
+    assert_eq!(restored_labels.len(), 1);
+    assert_eq!(restored_labels[0].id, label.id);
+    assert_eq!(restored_labels[0].color, "#336699");
+
+    let again = db.export_snapshot().await.unwrap();
+    assert_eq!(again.entity_count(), snapshot.entity_count());
+}
+
+/// Test that `update_task` records a field-level revision per effective change.
+pub async fn test_task_revisions(db: &dyn Database) {
+    let project = db.create_project(&make_project("revs")).await.unwrap();
+    let task = db
+        .create_task(&make_task(&project.id, "Tracked"))
+        .await
+        .unwrap();
+    assert!(db.list_task_revisions(&task.id).await.unwrap().is_empty());
+
+    db.update_task(
+        &task.id,
+        &UpdateTask {
+            status: Some(Status::Build),
+            priority: Some(Priority::High),
+            actor: Some("key:alice".into()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    // Setting a field to its current value is not a change.
+    db.update_task(
+        &task.id,
+        &UpdateTask {
+            title: Some("Tracked".into()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    db.update_task(
+        &task.id,
+        &UpdateTask {
+            description: Some("new body".into()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+
+    let revisions = db.list_task_revisions(&task.id).await.unwrap();
+    assert_eq!(revisions.len(), 2);
+
+    // Newest first
+    assert_eq!(revisions[0].actor, "");
+    assert_eq!(revisions[0].changes.len(), 1);
+    assert_eq!(revisions[0].changes[0].field, "description");
+    assert_eq!(revisions[0].changes[0].new, "new body");
+
+    assert_eq!(revisions[1].actor, "key:alice");
+    let fields: Vec<&str> = revisions[1]
+        .changes
+        .iter()
+        .map(|c| c.field.as_str())
+        .collect();
+    assert_eq!(fields, vec!["priority", "status"]);
+    assert_eq!(revisions[1].changes[1].old, "todo");
+    assert_eq!(revisions[1].changes[1].new, "build");
+
+    // Revisions go away with the task.
+    db.delete_task(&task.id).await.unwrap();
+    assert!(db.list_task_revisions(&task.id).await.unwrap().is_empty());
+}
+
+/// Test bulk create/update: all-or-nothing semantics in a single transaction.
+pub async fn test_bulk_task_ops(db: &dyn Database) {
+    let project = db.create_project(&make_project("bulk")).await.unwrap();
+    let created = db
+        .bulk_create_tasks(&[
+            make_task(&project.id, "A"),
+            make_task(&project.id, "B"),
+            make_task(&project.id, "C"),
+        ])
+        .await
+        .unwrap();
+    assert_eq!(created.len(), 3);
+    // sort_order keeps increasing within the batch
+    assert!(created[0].sort_order < created[1].sort_order);
+    assert!(created[1].sort_order < created[2].sort_order);
+
+    let ids: Vec<String> = created[..2].iter().map(|t| t.id.clone()).collect();
+    let updated = db
+        .bulk_update_tasks(
+            &ids,
+            &UpdateTask {
+                status: Some(Status::Build),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(updated.len(), 2);
+    assert!(updated.iter().all(|t| t.status == Status::Build));
+    assert_eq!(
+        db.get_task(&created[2].id).await.unwrap().status,
+        Status::Todo
+    );
+
+    // A missing id rolls back the whole batch.
+    let err = db
+        .bulk_update_tasks(
+            &[created[2].id.clone(), "does-not-exist".into()],
+            &UpdateTask {
+                priority: Some(Priority::Urgent),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap_err();
+    assert!(matches!(err, flowstate_db::DbError::NotFound(_)));
+    assert_eq!(
+        db.get_task(&created[2].id).await.unwrap().priority,
+        Priority::Medium
+    );
+
+    // A bad input rolls back the creates that preceded it.
+    let before = db
+        .list_tasks(&TaskFilter {
+            project_id: Some(project.id.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+        .len();
+    assert!(db
+        .bulk_create_tasks(&[
+            make_task(&project.id, "D"),
+            make_task("no-such-project", "E"),
+        ])
+        .await
+        .is_err());
+    let after = db
+        .list_tasks(&TaskFilter {
+            project_id: Some(project.id.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+        .len();
+    assert_eq!(before, after);
+}
+
+/// Test reorder_task: move after a sibling, move to top, dense renumbering,
+/// column isolation, and errors for unknown ids.
+pub async fn test_reorder_task(db: &dyn Database) {
+    let project = db.create_project(&make_project("reorder")).await.unwrap();
+    let a = db.create_task(&make_task(&project.id, "A")).await.unwrap();
+    let b = db.create_task(&make_task(&project.id, "B")).await.unwrap();
+    let c = db.create_task(&make_task(&project.id, "C")).await.unwrap();
+    let mut other = make_task(&project.id, "Other column");
+    other.status = Status::Research;
+    let other = db.create_task(&other).await.unwrap();
+
+    let column = |tasks: Vec<Task>| -> Vec<String> {
+        tasks
+            .into_iter()
+            .filter(|t| t.status == Status::Todo)
+            .map(|t| t.title)
+            .collect()
+    };
+    let filter = TaskFilter {
+        project_id: Some(project.id.clone()),
+        ..Default::default()
+    };
+
+    // Move A after C: B, C, A
+    let moved = db.reorder_task(&a.id, Some(&c.id)).await.unwrap();
+    assert_eq!(moved.sort_order, 3.0);
+    assert_eq!(
+        column(db.list_tasks(&filter).await.unwrap()),
+        vec!["B", "C", "A"]
+    );
+
+    // Move A to the top: A, B, C
+    db.reorder_task(&a.id, None).await.unwrap();
+    let tasks = db.list_tasks(&filter).await.unwrap();
+    assert_eq!(column(tasks.clone()), vec!["A", "B", "C"]);
+    let orders: Vec<f64> = tasks
+        .iter()
+        .filter(|t| t.status == Status::Todo)
+        .map(|t| t.sort_order)
+        .collect();
+    assert_eq!(orders, vec![1.0, 2.0, 3.0]);
+
+    // Tasks in other columns are untouched.
+    assert_eq!(
+        db.get_task(&other.id).await.unwrap().sort_order,
+        other.sort_order
+    );
+
+    // after_id must be in the same column.
+    let err = db.reorder_task(&b.id, Some(&other.id)).await.unwrap_err();
+    assert!(matches!(err, flowstate_db::DbError::NotFound(_)));
+    let err = db.reorder_task("does-not-exist", None).await.unwrap_err();
+    assert!(matches!(err, flowstate_db::DbError::NotFound(_)));
+    // Failed moves leave the order unchanged.
+    assert_eq!(
+        column(db.list_tasks(&filter).await.unwrap()),
+        vec!["A", "B", "C"]
+    );
+}
+
+/// Test that stats counts rows per table and reports the schema version.
+pub async fn test_stats(db: &dyn Database) {
+    let project = db.create_project(&make_project("stats")).await.unwrap();
+    db.create_task(&make_task(&project.id, "Counted"))
+        .await
+        .unwrap();
+
+    let stats = db.stats().await.unwrap();
+    assert!(stats.schema_version > 0);
+    let rows = |name: &str| {
+        stats
+            .tables
+            .iter()
+            .find(|t| t.name == name)
+            .map(|t| t.rows)
+            .unwrap()
+    };
+    assert_eq!(rows("projects"), 1);
+    assert_eq!(rows("tasks"), 1);
+    assert_eq!(rows("claude_runs"), 0);
+    assert_eq!(stats.total_rows(), 2);
+    match stats.backend.as_str() {
+        "sqlite" => assert!(stats.size_bytes.unwrap() > 0),
+        "postgres" => assert!(stats.pool.unwrap().max_connections > 0),
+        other => panic!("unexpected backend {other}"),
+    }
+}
+
+pub async fn test_run_maintenance(db: &dyn Database) {
+    let project = db
+        .create_project(&make_project("maintenance"))
+        .await
+        .unwrap();
+    for i in 0..20 {
+        let task = db
+            .create_task(&make_task(&project.id, &format!("Churn {i}")))
+            .await
+            .unwrap();
+        db.delete_task(&task.id).await.unwrap();
+    }
+    db.create_task(&make_task(&project.id, "Kept"))
+        .await
+        .unwrap();
+
+    let report = db.run_maintenance().await.unwrap();
+    assert!(report.ok(), "{report:?}");
+    let names: Vec<&str> = report.steps.iter().map(|s| s.name.as_str()).collect();
+    match report.backend.as_str() {
+        "sqlite" => assert_eq!(
+            names,
+            ["wal_checkpoint", "vacuum", "optimize", "integrity_check"]
+        ),
+        "postgres" => assert_eq!(names, ["analyze", "reindex_advisory"]),
+        other => panic!("unexpected backend {other}"),
+    }
+
+    // A second pass finds nothing new and the data is intact
+    assert!(db.run_maintenance().await.unwrap().ok());
+    let tasks = db
+        .list_tasks(&TaskFilter {
+            project_id: Some(project.id.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(tasks.len(), 1);
+}
+
+/// Epic CRUD, the epic_id task filter, and detaching tasks on delete.
+pub async fn test_epic_crud(db: &dyn Database) {
+    let project = db.create_project(&make_project("epic-crud")).await.unwrap();
+
+    let epic = db
+        .create_epic(&CreateEpic {
+            project_id: project.id.clone(),
+            name: "Billing".into(),
+            description: "Invoices and payments".into(),
+            target_date: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(epic.status, EpicStatus::Open);
+    assert_eq!(db.get_epic(&epic.id).await.unwrap().name, "Billing");
+    assert_eq!(db.list_epics(&project.id).await.unwrap().len(), 1);
+
+    let target = chrono::Utc::now() + chrono::Duration::days(30);
+    let updated = db
+        .update_epic(
+            &epic.id,
+            &UpdateEpic {
+                status: Some(EpicStatus::InProgress),
+                target_date: Some(Some(target)),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(updated.status, EpicStatus::InProgress);
+    assert_eq!(
+        updated.target_date.map(|d| d.timestamp()),
+        Some(target.timestamp())
+    );
+
+    let t1 = db
+        .create_task(&make_task(&project.id, "In Epic"))
+        .await
+        .unwrap();
+    db.update_task(
+        &t1.id,
+        &UpdateTask {
+            epic_id: Some(Some(epic.id.clone())),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    db.create_task(&make_task(&project.id, "No Epic"))
+        .await
+        .unwrap();
+
+    let filtered = db
+        .list_tasks(&TaskFilter {
+            project_id: Some(project.id.clone()),
+            epic_id: Some(epic.id.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(filtered.len(), 1);
+    assert_eq!(filtered[0].title, "In Epic");
+
+    db.delete_epic(&epic.id).await.unwrap();
+    assert!(db.get_epic(&epic.id).await.is_err());
+    assert!(db.delete_epic(&epic.id).await.is_err());
+    assert_eq!(db.get_task(&t1.id).await.unwrap().epic_id, None);
+}
+
+/// Custom field CRUD, writing values through `update_task`, the value
+/// filter, revisions, and cascading deletes.
+pub async fn test_custom_field_crud(db: &dyn Database) {
+    let project = db
+        .create_project(&make_project("custom-fields"))
+        .await
+        .unwrap();
+
+    let severity = db
+        .create_custom_field(&CreateCustomField {
+            project_id: project.id.clone(),
+            name: "severity".into(),
+            field_type: CustomFieldType::Enum,
+            options: vec!["low".into(), "high".into()],
+        })
+        .await
+        .unwrap();
+    assert_eq!(severity.options, vec!["low", "high"]);
+    let customer = db
+        .create_custom_field(&CreateCustomField {
+            project_id: project.id.clone(),
+            name: "customer".into(),
+            field_type: CustomFieldType::Text,
+            options: Vec::new(),
+        })
+        .await
+        .unwrap();
+    let names: Vec<_> = db
+        .list_custom_fields(&project.id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|f| f.name)
+        .collect();
+    assert_eq!(names, vec!["customer", "severity"]);
+
+    let updated = db
+        .update_custom_field(
+            &severity.id,
+            &UpdateCustomField {
+                options: Some(vec!["low".into(), "high".into(), "critical".into()]),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(updated.options.len(), 3);
+    assert_eq!(updated.field_type, CustomFieldType::Enum);
+    assert_eq!(
+        db.get_custom_field(&severity.id)
+            .await
+            .unwrap()
+            .options
+            .len(),
+        3
+    );
+
+    let t1 = db
+        .create_task(&make_task(&project.id, "Outage"))
+        .await
+        .unwrap();
+    let t2 = db
+        .create_task(&make_task(&project.id, "Typo"))
+        .await
+        .unwrap();
+    let set = |values: &[(&str, Option<&str>)]| UpdateTask {
+        custom_fields: values
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.map(String::from)))
+            .collect(),
+        ..Default::default()
+    };
+    db.update_task(
+        &t1.id,
+        &set(&[
+            (&severity.id, Some("critical")),
+            (&customer.id, Some("Acme")),
+        ]),
+    )
+    .await
+    .unwrap();
+    db.update_task(&t2.id, &set(&[(&severity.id, Some("low"))]))
+        .await
+        .unwrap();
+    assert_eq!(db.list_task_field_values(&t1.id).await.unwrap().len(), 2);
+
+    let critical = db
+        .list_tasks(&TaskFilter {
+            project_id: Some(project.id.clone()),
+            custom_field: Some((severity.id.clone(), "critical".into())),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(critical.len(), 1);
+    assert_eq!(critical[0].title, "Outage");
+
+    let history = db.list_task_revisions(&t1.id).await.unwrap();
+    assert!(history[0]
+        .changes
+        .iter()
+        .any(|c| c.field == "custom_fields.severity" && c.new == "critical"));
+
+    // Clearing a value removes it; a field from another project is rejected.
+    db.update_task(&t1.id, &set(&[(&customer.id, None)]))
+        .await
+        .unwrap();
+    assert_eq!(db.list_task_field_values(&t1.id).await.unwrap().len(), 1);
+    let other = db
+        .create_project(&make_project("custom-fields-other"))
+        .await
+        .unwrap();
+    let t3 = db
+        .create_task(&make_task(&other.id, "Elsewhere"))
+        .await
+        .unwrap();
+    assert!(db
+        .update_task(&t3.id, &set(&[(&severity.id, Some("low"))]))
+        .await
+        .is_err());
+
+    db.delete_custom_field(&severity.id).await.unwrap();
+    assert!(db.get_custom_field(&severity.id).await.is_err());
+    assert!(db.delete_custom_field(&severity.id).await.is_err());
+    assert!(db.list_task_field_values(&t1.id).await.unwrap().is_empty());
+    assert!(db.list_task_field_values(&t2.id).await.unwrap().is_empty());
+}
+
+/// Every rejection with feedback is kept, not just the latest one.
+pub async fn test_feedback_history(db: &dyn Database) {
+    let project = db.create_project(&make_project("fbh")).await.unwrap();
+    let task = db
+        .create_task(&make_task(&project.id, "Reviewed"))
+        .await
+        .unwrap();
+
+    let reject = |feedback: &str, actor: &str| UpdateTask {
+        spec_status: Some(ApprovalStatus::Rejected),
+        spec_feedback: Some(feedback.into()),
+        actor: Some(actor.into()),
+        ..Default::default()
+    };
+    db.update_task(&task.id, &reject("cover migrations", "key:alice"))
+        .await
+        .unwrap();
+    // Same feedback again is not a new round.
+    db.update_task(&task.id, &reject("cover migrations", "key:alice"))
+        .await
+        .unwrap();
+    // Feedback on a phase that isn't rejected is not recorded.
+    db.update_task(
+        &task.id,
+        &UpdateTask {
+            plan_feedback: Some("just a note".into()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    db.update_task(&task.id, &reject("and rollback", "key:bob"))
+        .await
+        .unwrap();
+
+    let history = db.list_feedback_history(&task.id).await.unwrap();
+    assert_eq!(history.len(), 2);
+    // Newest first
+    assert_eq!(history[0].feedback, "and rollback");
+    assert_eq!(history[0].author, "key:bob");
+    assert_eq!(history[0].phase, "design");
+    assert_eq!(history[1].feedback, "cover migrations");
+    assert_eq!(history[1].author, "key:alice");
+
+    // Feedback from elsewhere, such as a PR review, is recorded as given
+    let entry = db
+        .add_feedback_entry(&task.id, "verify", "handle the empty case", "github:carol")
+        .await
+        .unwrap();
+    assert_eq!(entry.phase, "verify");
+    let history = db.list_feedback_history(&task.id).await.unwrap();
+    assert_eq!(history.len(), 3);
+    assert_eq!(history[0].id, entry.id);
+    assert_eq!(history[0].author, "github:carol");
+
+    db.delete_task(&task.id).await.unwrap();
+    assert!(db.list_feedback_history(&task.id).await.unwrap().is_empty());
+}
+
+/// Due dates round-trip, can be cleared, and drive the due/overdue filters.
+pub async fn test_task_due_dates(db: &dyn Database) {
+    let project = db.create_project(&make_project("due")).await.unwrap();
+    let now = chrono::Utc::now();
+    let yesterday = now - chrono::Duration::days(1);
+    let next_week = now + chrono::Duration::days(7);
+
+    let late = db
+        .create_task(&CreateTask {
+            due_at: Some(yesterday),
+            ..make_task(&project.id, "Late")
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        late.due_at.map(|d| d.timestamp_micros()),
+        Some(yesterday.timestamp_micros())
+    );
+    let upcoming = db
+        .create_task(&make_task(&project.id, "Upcoming"))
+        .await
+        .unwrap();
+    assert!(upcoming.due_at.is_none());
+    let upcoming = db
+        .update_task(
+            &upcoming.id,
+            &UpdateTask {
+                due_at: Some(Some(next_week)),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert!(upcoming.due_at.is_some());
+    let finished = db
+        .create_task(&CreateTask {
+            due_at: Some(yesterday),
+            status: Status::Done,
+            ..make_task(&project.id, "Finished")
+        })
+        .await
+        .unwrap();
+
+    let titles = |tasks: Vec<Task>| -> Vec<String> {
+        let mut titles: Vec<String> = tasks.into_iter().map(|t| t.title).collect();
+        titles.sort();
+        titles
+    };
+    let overdue = db
+        .list_tasks(&TaskFilter {
+            project_id: Some(project.id.clone()),
+            overdue: true,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(titles(overdue), vec!["Late"]);
+
+    let due_soon = db
+        .list_tasks(&TaskFilter {
+            project_id: Some(project.id.clone()),
+            due_before: Some(now),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(titles(due_soon), vec!["Finished", "Late"]);
+
+    // Clearing the due date takes the task out of both filters.
+    let late = db
+        .update_task(
+            &late.id,
+            &UpdateTask {
+                due_at: Some(None),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert!(late.due_at.is_none());
+    let overdue = db
+        .list_tasks(&TaskFilter {
+            project_id: Some(project.id.clone()),
+            overdue: true,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert!(overdue.is_empty());
+    assert!(finished.due_at.is_some());
+}
+
+/// User CRUD, assigning tasks, the assignee filter, and unassigning on delete.
+pub async fn test_users_and_assignment(db: &dyn Database) {
+    let project = db.create_project(&make_project("assignees")).await.unwrap();
+
+    let alice = db
+        .create_user(&CreateUser {
+            name: "Alice".into(),
+            email: "alice@example.com".into(),
+        })
+        .await
+        .unwrap();
+    let bob = db
+        .create_user(&CreateUser {
+            name: "Bob".into(),
+            email: String::new(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(db.get_user(&alice.id).await.unwrap(), alice);
+    let names: Vec<String> = db
+        .list_users()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|u| u.name)
+        .collect();
+    assert_eq!(names, vec!["Alice", "Bob"]);
+
+    let renamed = db
+        .update_user(
+            &bob.id,
+            &UpdateUser {
+                name: Some("Bobby".into()),
+                email: Some("bob@example.com".into()),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(renamed.name, "Bobby");
+    assert_eq!(renamed.email, "bob@example.com");
+    assert!(db
+        .update_user("missing", &UpdateUser::default())
+        .await
+        .is_err());
+
+    let t1 = db
+        .create_task(&make_task(&project.id, "Alice's"))
+        .await
+        .unwrap();
+    assert_eq!(t1.assignee_id, None);
+    let assigned = db
+        .update_task(
+            &t1.id,
+            &UpdateTask {
+                assignee_id: Some(Some(alice.id.clone())),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(assigned.assignee_id.as_deref(), Some(alice.id.as_str()));
+    db.create_task(&make_task(&project.id, "Nobody's"))
+        .await
+        .unwrap();
+
+    let filtered = db
+        .list_tasks(&TaskFilter {
+            project_id: Some(project.id.clone()),
+            assignee_id: Some(alice.id.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(filtered.len(), 1);
+    assert_eq!(filtered[0].title, "Alice's");
+
+    db.delete_user(&alice.id).await.unwrap();
+    assert!(db.get_user(&alice.id).await.is_err());
+    assert!(db.delete_user(&alice.id).await.is_err());
+    assert_eq!(db.get_task(&t1.id).await.unwrap().assignee_id, None);
+}
+
+/// Saved filter CRUD, per-user visibility, and running a filter's query
+/// (text, unassigned and label conditions included).
+pub async fn test_saved_filters(db: &dyn Database) {
+    let project = db
+        .create_project(&make_project("saved-filters"))
+        .await
+        .unwrap();
+    let alice = db
+        .create_user(&CreateUser {
+            name: "Alice".into(),
+            email: String::new(),
+        })
+        .await
+        .unwrap();
+    let bob = db
+        .create_user(&CreateUser {
+            name: "Bob".into(),
+            email: String::new(),
+        })
+        .await
+        .unwrap();
+
+    let urgent = db
+        .create_task(&CreateTask {
+            priority: Priority::Urgent,
+            task_type: TaskType::Feature,
+            description: "Login fails with 100% CPU".into(),
+            ..make_task(&project.id, "Crash on login")
+        })
+        .await
+        .unwrap();
+    let assigned = db
+        .create_task(&CreateTask {
+            priority: Priority::Urgent,
+            task_type: TaskType::Feature,
+            ..make_task(&project.id, "Login page copy")
+        })
+        .await
+        .unwrap();
+    db.update_task(
+        &assigned.id,
+        &UpdateTask {
+            assignee_id: Some(Some(alice.id.clone())),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    db.create_task(&make_task(&project.id, "Refactor settings"))
+        .await
+        .unwrap();
+
+    let shared = db
+        .create_saved_filter(&CreateSavedFilter {
+            project_id: project.id.clone(),
+            user_id: None,
+            name: "Urgent unassigned".into(),
+            query: FilterQuery {
+                priority: Some(Priority::Urgent),
+                unassigned: true,
+                ..Default::default()
+            },
+        })
+        .await
+        .unwrap();
+    let mine = db
+        .create_saved_filter(&CreateSavedFilter {
+            project_id: project.id.clone(),
+            user_id: Some(alice.id.clone()),
+            name: "Login".into(),
+            query: FilterQuery {
+                text: Some("LOGIN".into()),
+                ..Default::default()
+            },
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        db.get_saved_filter(&shared.id).await.unwrap().query,
+        shared.query
+    );
+
+    let names = |filters: Vec<flowstate_core::saved_filter::SavedFilter>| {
+        filters.into_iter().map(|f| f.name).collect::<Vec<_>>()
+    };
+    assert_eq!(
+        names(db.list_saved_filters(&project.id, None).await.unwrap()),
+        vec!["Login", "Urgent unassigned"]
+    );
+    assert_eq!(
+        names(
+            db.list_saved_filters(&project.id, Some(&bob.id))
+                .await
+                .unwrap()
+        ),
+        vec!["Urgent unassigned"]
+    );
+    assert_eq!(
+        db.list_saved_filters(&project.id, Some(&alice.id))
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+
+    // Titles of the tasks a query matches, sorted
+    async fn run(db: &dyn Database, project_id: &str, query: &FilterQuery) -> Vec<String> {
+        let tasks = db.list_tasks(&query.to_task_filter(project_id)).await;
+        let mut titles: Vec<String> = tasks.unwrap().into_iter().map(|t| t.title).collect();
+        titles.sort();
+        titles
+    }
+    assert_eq!(
+        run(db, &project.id, &shared.query).await,
+        vec!["Crash on login"]
+    );
+    assert_eq!(
+        run(db, &project.id, &mine.query).await,
+        vec!["Crash on login", "Login page copy"]
+    );
+    // LIKE wildcards in the text match literally
+    let percent = FilterQuery {
+        text: Some("100%".into()),
+        ..Default::default()
+    };
+    assert_eq!(run(db, &project.id, &percent).await, vec!["Crash on login"]);
+    let underscore = FilterQuery {
+        text: Some("log_n".into()),
+        ..Default::default()
+    };
+    assert!(run(db, &project.id, &underscore).await.is_empty());
+    // no task carries this label
+    let labelled = FilterQuery {
+        labels: vec!["missing-label".into()],
+        ..Default::default()
+    };
+    assert!(run(db, &project.id, &labelled).await.is_empty());
+
+    let updated = db
+        .update_saved_filter(
+            &mine.id,
+            &UpdateSavedFilter {
+                name: Some("Login bugs".into()),
+                query: Some(FilterQuery {
+                    text: Some("login".into()),
+                    unassigned: true,
+                    ..Default::default()
+                }),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(updated.name, "Login bugs");
+    assert_eq!(
+        run(db, &project.id, &updated.query).await,
+        vec![urgent.title]
+    );
+
+    // a user's own filters go with them
+    db.delete_user(&alice.id).await.unwrap();
+    assert!(db.get_saved_filter(&mine.id).await.is_err());
+    db.delete_saved_filter(&shared.id).await.unwrap();
+    assert!(db.delete_saved_filter(&shared.id).await.is_err());
+    assert!(db
+        .list_saved_filters(&project.id, None)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+/// Watching a task delivers its updates as notifications, and the
+/// watched-by filter finds the tasks a user follows.
+pub async fn test_task_watchers(db: &dyn Database) {
+    let project = db.create_project(&make_project("watchers")).await.unwrap();
+    let alice = db
+        .create_user(&CreateUser {
+            name: "Alice".into(),
+            email: String::new(),
+        })
+        .await
+        .unwrap();
+    let watched = db
+        .create_task(&make_task(&project.id, "Watched"))
+        .await
+        .unwrap();
+    let other = db
+        .create_task(&make_task(&project.id, "Other"))
+        .await
+        .unwrap();
+
+    db.watch_task(&watched.id, &alice.id).await.unwrap();
+    db.watch_task(&watched.id, &alice.id).await.unwrap();
+    let watchers = db.list_task_watchers(&watched.id).await.unwrap();
+    assert_eq!(watchers.len(), 1);
+    assert_eq!(watchers[0].id, alice.id);
+
+    let filtered = db
+        .list_tasks(&TaskFilter {
+            project_id: Some(project.id.clone()),
+            watched_by: Some(alice.id.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(filtered.len(), 1);
+    assert_eq!(filtered[0].id, watched.id);
+
+    for id in [&watched.id, &other.id] {
+        db.update_task(
+            id,
+            &UpdateTask {
+                status: Some(Status::Research),
+                actor: Some("key:ci".into()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    }
+    let notifications = db.list_notifications(&alice.id, false).await.unwrap();
+    assert_eq!(notifications.len(), 1);
+    let n = &notifications[0];
+    assert_eq!(n.task_id, watched.id);
+    assert_eq!(n.actor, "key:ci");
+    assert_eq!(n.changes[0].field, "status");
+    assert!(!n.read);
+
+    assert_eq!(
+        db.get_notification(&n.id).await.unwrap().task_id,
+        watched.id
+    );
+    assert!(matches!(
+        db.get_notification("missing").await,
+        Err(flowstate_db::DbError::NotFound(_))
+    ));
+    let read = db.mark_notification_read(&n.id).await.unwrap();
+    assert!(read.read);
+    assert!(db
+        .list_notifications(&alice.id, true)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(db.mark_notification_read("missing").await.is_err());
+
+    db.unwatch_task(&watched.id, &alice.id).await.unwrap();
+    assert!(db.list_task_watchers(&watched.id).await.unwrap().is_empty());
+    db.update_task(
+        &watched.id,
+        &UpdateTask {
+            status: Some(Status::Design),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        db.list_notifications(&alice.id, false).await.unwrap().len(),
+        1
+    );
+}
+
+/// Labels are created once per project name and attach to tasks
+/// idempotently; recorded imports are found again by their source.
+pub async fn test_labels_and_task_imports(db: &dyn Database) {
+    let project = db.create_project(&make_project("imports")).await.unwrap();
+    let other = db
+        .create_project(&make_project("imports-other"))
+        .await
+        .unwrap();
+    let task = db
+        .create_task(&make_task(&project.id, "Imported"))
+        .await
+        .unwrap();
+
+    let bug = db
+        .ensure_label(&project.id, "bug", "#d73a4a")
+        .await
+        .unwrap();
+    let again = db
+        .ensure_label(&project.id, "bug", "#000000")
+        .await
+        .unwrap();
+    assert_eq!(again.id, bug.id);
+    assert_eq!(again.color, "#d73a4a");
+    let docs = db
+        .ensure_label(&project.id, "docs", "#0075ca")
+        .await
+        .unwrap();
+    let elsewhere = db.ensure_label(&other.id, "bug", "#d73a4a").await.unwrap();
+    assert_ne!(elsewhere.id, bug.id);
+
+    db.add_task_label(&task.id, &docs.id).await.unwrap();
+    db.add_task_label(&task.id, &bug.id).await.unwrap();
+    db.add_task_label(&task.id, &bug.id).await.unwrap();
+    let labels = db.list_task_labels(&task.id).await.unwrap();
+    assert_eq!(
+        labels.iter().map(|l| l.name.as_str()).collect::<Vec<_>>(),
+        vec!["bug", "docs"]
+    );
+    let filtered = db
+        .list_tasks(&TaskFilter {
+            project_id: Some(project.id.clone()),
+            label_ids: vec![bug.id.clone()],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_eq!(filtered.len(), 1);
+
+    let source = "https://github.com/acme/app/issues/1";
+    assert_eq!(
+        db.find_imported_task(&project.id, source).await.unwrap(),
+        None
+    );
+    db.record_task_import(&project.id, source, &task.id)
+        .await
+        .unwrap();
+    db.record_task_import(&project.id, source, "someone-else")
+        .await
+        .unwrap();
+    assert_eq!(
+        db.find_imported_task(&project.id, source).await.unwrap(),
+        Some(task.id.clone())
+    );
+    assert_eq!(
+        db.find_imported_task(&other.id, source).await.unwrap(),
+        None
+    );
+
+    // The record outlives the task, so a deleted task stays deleted
+    db.delete_task(&task.id).await.unwrap();
+    assert_eq!(
+        db.find_imported_task(&project.id, source).await.unwrap(),
+        Some(task.id.clone())
+    );
+}
+
+/// Approval rules are one per project and phase: setting one again replaces
+/// its policy, and deleting it falls back to no rule.
+pub async fn test_approval_rules(db: &dyn Database) {
+    let project = db
+        .create_project(&make_project("approval-rules"))
+        .await
+        .unwrap();
+    let other = db
+        .create_project(&make_project("approval-rules-other"))
+        .await
+        .unwrap();
+    assert!(db
+        .list_approval_rules(&project.id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    db.set_approval_rule(&project.id, ApprovalPhase::Research, ApprovalPolicy::Auto)
+        .await
+        .unwrap();
+    db.set_approval_rule(&project.id, ApprovalPhase::Plan, ApprovalPolicy::Human)
+        .await
+        .unwrap();
+    let rule = db
+        .set_approval_rule(&project.id, ApprovalPhase::Plan, ApprovalPolicy::Reviewer)
+        .await
+        .unwrap();
+    assert_eq!(rule.policy, ApprovalPolicy::Reviewer);
+    db.set_approval_rule(&other.id, ApprovalPhase::Verify, ApprovalPolicy::Auto)
+        .await
+        .unwrap();
+
+    let rules = db.list_approval_rules(&project.id).await.unwrap();
+    let got: Vec<_> = rules.iter().map(|r| (r.phase, r.policy)).collect();
+    assert_eq!(
+        got,
+        vec![
+            (ApprovalPhase::Plan, ApprovalPolicy::Reviewer),
+            (ApprovalPhase::Research, ApprovalPolicy::Auto),
+        ]
+    );
+
+    db.delete_approval_rule(&project.id, ApprovalPhase::Plan)
+        .await
+        .unwrap();
+    assert!(matches!(
+        db.delete_approval_rule(&project.id, ApprovalPhase::Plan)
+            .await,
+        Err(flowstate_db::DbError::NotFound(_))
+    ));
+    assert_eq!(db.list_approval_rules(&project.id).await.unwrap().len(), 1);
+    assert_eq!(db.list_approval_rules(&other.id).await.unwrap().len(), 1);
+}
+
+pub async fn test_subscriptions(db: &dyn Database) {
+    let project = db
+        .create_project(&make_project("subscriptions"))
+        .await
+        .unwrap();
+    let user = db
+        .create_user(&CreateUser {
+            name: "Alice".into(),
+            email: "alice@example.com".into(),
+        })
+        .await
+        .unwrap();
+    assert!(db.list_subscriptions().await.unwrap().is_empty());
+
+    let slack = db
+        .create_subscription(&CreateSubscription {
+            channel: NotifyChannel::Slack,
+            target: " https://hooks.slack.com/services/x ".into(),
+            events: vec![NotifyEvent::RunFailed, NotifyEvent::PrOpened],
+            project_id: Some(project.id.clone()),
+            user_id: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(slack.target, "https://hooks.slack.com/services/x");
+    assert_eq!(
+        slack.events,
+        vec![NotifyEvent::RunFailed, NotifyEvent::PrOpened]
+    );
+    let email = db
+        .create_subscription(&CreateSubscription {
+            channel: NotifyChannel::Email,
+            target: String::new(),
+            events: vec![],
+            project_id: None,
+            user_id: Some(user.id.clone()),
+        })
+        .await
+        .unwrap();
+
+    let got = db.get_subscription(&email.id).await.unwrap();
+    assert_eq!(got.channel, NotifyChannel::Email);
+    assert_eq!(got.user_id.as_deref(), Some(user.id.as_str()));
+    assert!(got.events.is_empty());
+    let listed = db.list_subscriptions().await.unwrap();
+    assert_eq!(listed.len(), 2);
+
+    // Deleting the user takes their subscriptions with them
+    db.delete_user(&user.id).await.unwrap();
+    assert!(matches!(
+        db.get_subscription(&email.id).await,
+        Err(flowstate_db::DbError::NotFound(_))
+    ));
+    db.delete_subscription(&slack.id).await.unwrap();
+    assert!(matches!(
+        db.delete_subscription(&slack.id).await,
+        Err(flowstate_db::DbError::NotFound(_))
+    ));
+    assert!(db.list_subscriptions().await.unwrap().is_empty());
+}
+
+pub async fn test_organizations(db: &dyn Database) {
+    let org = db
+        .create_organization(&CreateOrganization {
+            name: " Acme ".into(),
+            slug: "acme".into(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(org.name, "Acme");
+    assert_eq!(db.get_organization(&org.id).await.unwrap(), org);
+    assert!(db
+        .create_organization(&CreateOrganization {
+            name: "Acme again".into(),
+            slug: "acme".into(),
+        })
+        .await
+        .is_err());
+
+    let owned = db.create_project(&make_project("org-owned")).await.unwrap();
+    let shared = db
+        .create_project(&make_project("org-shared"))
+        .await
+        .unwrap();
+    assert!(owned.org_id.is_none());
+    let owned = db.set_project_org(&owned.id, Some(&org.id)).await.unwrap();
+    assert_eq!(owned.org_id.as_deref(), Some(org.id.as_str()));
+    assert_eq!(
+        db.list_organization_project_ids(&org.id).await.unwrap(),
+        vec![owned.id.clone()]
+    );
+    assert!(db.get_project(&shared.id).await.unwrap().org_id.is_none());
+    assert!(matches!(
+        db.set_project_org("missing", Some(&org.id)).await,
+        Err(flowstate_db::DbError::NotFound(_))
+    ));
+
+    let key = db.insert_api_key("acme-ci", "hash_org").await.unwrap();
+    assert!(key.org_id.is_none());
+    let key = db.set_api_key_org(&key.id, Some(&org.id)).await.unwrap();
+    assert_eq!(key.org_id.as_deref(), Some(org.id.as_str()));
+    let found = db.find_api_key_by_hash("hash_org").await.unwrap().unwrap();
+    assert_eq!(found.org_id.as_deref(), Some(org.id.as_str()));
+
+    let snapshot = db.export_snapshot().await.unwrap();
+    assert_eq!(snapshot.organizations, vec![org.clone()]);
+    let exported = snapshot.projects.iter().find(|p| p.id == owned.id).unwrap();
+    assert_eq!(exported.org_id.as_deref(), Some(org.id.as_str()));
+
+    // Deleting the organization revokes its keys and releases its projects
+    db.delete_organization(&org.id).await.unwrap();
+    assert!(db.list_organizations().await.unwrap().is_empty());
+    assert!(db.find_api_key_by_hash("hash_org").await.unwrap().is_none());
+    assert!(db.get_project(&owned.id).await.unwrap().org_id.is_none());
+    assert!(matches!(
+        db.delete_organization(&org.id).await,
+        Err(flowstate_db::DbError::NotFound(_))
+    ));
+}
+
+pub async fn test_runners(db: &dyn Database) {
+    let first_seen = chrono::Utc::now() - chrono::Duration::minutes(10);
+    let mut runner = RunnerRecord {
+        runner_id: "runner-b".into(),
+        org_id: None,
+        backend_name: Some("claude-cli".into()),
+        capability: Some("heavy".into()),
+        poll_interval: Some(5),
+        max_concurrent: Some(4),
+        max_builds: Some(1),
+        active_count: Some(0),
+        active_builds: Some(0),
+        status: RunnerStatus::Active,
+        pending_config: None,
+        benchmark: Some(RunnerBenchmark {
+            compile_secs: Some(1.5),
+            tokens_per_sec: None,
+        }),
+        first_seen_at: first_seen,
+        last_seen_at: first_seen,
+    };
+    let stored = db.upsert_runner(&runner).await.unwrap();
+    assert_eq!(stored.capability.as_deref(), Some("heavy"));
+    assert_eq!(stored.benchmark, runner.benchmark);
+    assert_eq!(db.get_runner("runner-b").await.unwrap(), stored);
+
+    let drain = PendingRunnerConfig {
+        poll_interval: None,
+        drain: Some(true),
+    };
+    db.set_runner_pending_config("runner-b", Some(&drain))
+        .await
+        .unwrap();
+
+    // A heartbeat updates the runner but keeps when it was first seen and
+    // the config still waiting for it
+    runner.status = RunnerStatus::Draining;
+    runner.active_count = Some(2);
+    runner.first_seen_at = chrono::Utc::now();
+    runner.last_seen_at = chrono::Utc::now();
+    let updated = db.upsert_runner(&runner).await.unwrap();
+    assert_eq!(updated.status, RunnerStatus::Draining);
+    assert_eq!(updated.active_count, Some(2));
+    assert_eq!(updated.first_seen_at, stored.first_seen_at);
+    assert!(updated.last_seen_at > stored.last_seen_at);
+    assert_eq!(updated.pending_config, Some(drain));
+
+    db.set_runner_pending_config("runner-b", None)
+        .await
+        .unwrap();
+    assert!(db
+        .get_runner("runner-b")
+        .await
+        .unwrap()
+        .pending_config
+        .is_none());
+    assert!(matches!(
+        db.set_runner_pending_config("missing", None).await,
+        Err(flowstate_db::DbError::NotFound(_))
+    ));
+    assert!(matches!(
+        db.get_runner("missing").await,
+        Err(flowstate_db::DbError::NotFound(_))
+    ));
+
+    db.upsert_runner(&RunnerRecord {
+        runner_id: "runner-a".into(),
+        org_id: Some("org-1".into()),
+        benchmark: None,
+        ..runner
+    })
+    .await
+    .unwrap();
+    let ids: Vec<_> = db
+        .list_runners()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| r.runner_id)
+        .collect();
+    assert_eq!(ids, vec!["runner-a", "runner-b"]);
+
+    db.delete_runner("runner-a").await.unwrap();
+    assert_eq!(db.list_runners().await.unwrap().len(), 1);
+    assert!(matches!(
+        db.delete_runner("runner-a").await,
+        Err(flowstate_db::DbError::NotFound(_))
+    ));
+}
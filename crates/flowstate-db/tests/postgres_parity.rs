@@ -29,6 +29,16 @@ async fn make_db() -> Arc<dyn Database> {
     let cleanup_pool = sqlx::PgPool::connect(&url).await.unwrap();
     sqlx::query(
         "TRUNCATE
+            runners,
+            subscriptions,
+            approval_rules,
+            task_imports,
+            run_metrics,
+            notifications,
+            task_watchers,
+            saved_filters,
+            task_revisions,
+            feedback_history,
             task_prs,
             attachments,
             task_links,
@@ -40,11 +50,19 @@ async fn make_db() -> Arc<dyn Database> {
             verification_steps,
             verification_profiles,
             commit_links,
+            task_field_values,
+            custom_fields,
             tasks,
             sprints,
+            epics,
+            users,
             labels,
             projects,
-            api_keys
+            api_keys,
+            feature_flags,
+            webhook_deliveries,
+            webhooks,
+            organizations
          CASCADE",
     )
     .execute(&cleanup_pool)
@@ -118,6 +136,13 @@ async fn stale_runs() {
     common::test_stale_runs(&*db).await;
 }
 
+#[tokio::test]
+#[ignore]
+async fn requeue_and_priority() {
+    let db = make_db().await;
+    common::test_requeue_and_priority(&*db).await;
+}
+
 #[tokio::test]
 #[ignore]
 async fn task_links() {
@@ -208,3 +233,269 @@ async fn update_sprint_no_changes() {
     let db = make_db().await;
     common::test_update_sprint_no_changes(&*db).await;
 }
+
+#[tokio::test]
+#[ignore]
+async fn snapshot_roundtrip() {
+    let db = make_db().await;
+    common::test_snapshot_roundtrip(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn task_revisions() {
+    let db = make_db().await;
+    common::test_task_revisions(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn bulk_task_ops() {
+    let db = make_db().await;
+    common::test_bulk_task_ops(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn api_key_policy() {
+    let db = make_db().await;
+    common::test_api_key_policy(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn api_key_projects() {
+    let db = make_db().await;
+    common::test_api_key_projects(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn reorder_task() {
+    let db = make_db().await;
+    common::test_reorder_task(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn feature_flags() {
+    let db = make_db().await;
+    common::test_feature_flags(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn webhooks() {
+    let db = make_db().await;
+    common::test_webhooks(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn run_metrics() {
+    let db = make_db().await;
+    common::test_run_metrics(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn claim_respects_priority() {
+    let db = make_db().await;
+    common::test_claim_respects_priority(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn claim_skips_actions() {
+    let db = make_db().await;
+    common::test_claim_skips_actions(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn claim_scoped_to_projects() {
+    let db = make_db().await;
+    common::test_claim_scoped_to_projects(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn claim_respects_project_cap() {
+    let db = make_db().await;
+    common::test_claim_respects_project_cap(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn concurrent_claims_respect_project_cap() {
+    let db = make_db().await;
+    common::test_concurrent_claims_respect_project_cap(db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn claim_fair_across_projects() {
+    let db = make_db().await;
+    common::test_claim_fair_across_projects(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn stats() {
+    let db = make_db().await;
+    common::test_stats(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn epic_crud() {
+    let db = make_db().await;
+    common::test_epic_crud(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn custom_field_crud() {
+    let db = make_db().await;
+    common::test_custom_field_crud(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn feedback_history() {
+    let db = make_db().await;
+    common::test_feedback_history(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn task_due_dates() {
+    let db = make_db().await;
+    common::test_task_due_dates(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn users_and_assignment() {
+    let db = make_db().await;
+    common::test_users_and_assignment(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn saved_filters() {
+    let db = make_db().await;
+    common::test_saved_filters(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn task_watchers() {
+    let db = make_db().await;
+    common::test_task_watchers(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn claude_run_idempotency() {
+    let db = make_db().await;
+    common::test_claude_run_idempotency(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn queued_run_demand() {
+    let db = make_db().await;
+    common::test_queued_run_demand(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn count_finished_runs() {
+    let db = make_db().await;
+    common::test_count_finished_runs(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn archive_sprint_and_tasks() {
+    let db = make_db().await;
+    common::test_archive_sprint_and_tasks(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn claim_respects_run_window() {
+    let db = make_db().await;
+    common::test_claim_respects_run_window(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn claim_follows_run_window_time_zone() {
+    let db = make_db().await;
+    common::test_claim_follows_run_window_time_zone(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn run_maintenance() {
+    let db = make_db().await;
+    common::test_run_maintenance(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn delete_runs_older_than() {
+    let db = make_db().await;
+    common::test_delete_runs_older_than(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn project_dashboard() {
+    let db = make_db().await;
+    common::test_project_dashboard(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn task_filter_lists_and_ranges() {
+    let db = make_db().await;
+    common::test_task_filter_lists_and_ranges(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn labels_and_task_imports() {
+    let db = make_db().await;
+    common::test_labels_and_task_imports(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn approval_rules() {
+    let db = make_db().await;
+    common::test_approval_rules(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn subscriptions() {
+    let db = make_db().await;
+    common::test_subscriptions(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn organizations() {
+    let db = make_db().await;
+    common::test_organizations(&*db).await;
+}
+
+#[tokio::test]
+#[ignore]
+async fn runners() {
+    let db = make_db().await;
+    common::test_runners(&*db).await;
+}
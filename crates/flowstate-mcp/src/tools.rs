@@ -1,4 +1,4 @@
-use flowstate_core::task::{CreateTask, Priority, Status, TaskFilter, UpdateTask};
+use flowstate_core::task::{CreateTask, Priority, Status, TaskFilter, TaskType, UpdateTask};
 use flowstate_service::HttpService;
 use serde_json::json;
 
@@ -9,12 +9,14 @@ pub fn tool_definitions() -> Vec<ToolDefinition> {
     vec![
         ToolDefinition {
             name: "list_tasks".into(),
-            description: "List tasks, optionally filtered by project_id and/or status.".into(),
+            description: "List tasks, optionally filtered by project_id, status and/or task_type."
+                .into(),
             input_schema: json!({
                 "type": "object",
                 "properties": {
                     "project_id": { "type": "string", "description": "Filter by project ID" },
-                    "status": { "type": "string", "description": "Filter by status (todo, research, design, plan, build, verify, done)" }
+                    "status": { "type": "string", "description": "Filter by status (todo, research, design, plan, build, verify, done)" },
+                    "task_type": { "type": "string", "description": "Filter by type (feature, bug, chore, spike)" }
                 }
             }),
         },
@@ -40,7 +42,8 @@ pub fn tool_definitions() -> Vec<ToolDefinition> {
                     "description": { "type": "string" },
                     "parent_id": { "type": "string", "description": "Optional parent task ID for subtasks" },
                     "status": { "type": "string", "description": "Initial status (default: todo)" },
-                    "priority": { "type": "string", "description": "Priority (urgent, high, medium, low, none; default: medium)" }
+                    "priority": { "type": "string", "description": "Priority (urgent, high, medium, low, none; default: medium)" },
+                    "task_type": { "type": "string", "description": "Type (feature, bug, chore, spike; default: feature). Spikes are never built." }
                 },
                 "required": ["project_id", "title"]
             }),
@@ -55,7 +58,8 @@ pub fn tool_definitions() -> Vec<ToolDefinition> {
                     "title": { "type": "string" },
                     "description": { "type": "string" },
                     "status": { "type": "string" },
-                    "priority": { "type": "string" }
+                    "priority": { "type": "string" },
+                    "task_type": { "type": "string" }
                 },
                 "required": ["task_id"]
             }),
@@ -142,6 +146,10 @@ async fn handle_list_tasks(service: &HttpService, args: &serde_json::Value) -> T
             .get("status")
             .and_then(|v| v.as_str())
             .and_then(Status::parse_str),
+        task_type: args
+            .get("task_type")
+            .and_then(|v| v.as_str())
+            .and_then(TaskType::parse_str),
         ..Default::default()
     };
     match flowstate_service::TaskService::list_tasks(service, &filter).await {
@@ -191,6 +199,11 @@ async fn handle_create_task(service: &HttpService, args: &serde_json::Value) ->
         .and_then(|v| v.as_str())
         .and_then(Priority::parse_str)
         .unwrap_or(Priority::Medium);
+    let task_type = args
+        .get("task_type")
+        .and_then(|v| v.as_str())
+        .and_then(TaskType::parse_str)
+        .unwrap_or_default();
     let parent_id = args
         .get("parent_id")
         .and_then(|v| v.as_str())
@@ -202,6 +215,7 @@ async fn handle_create_task(service: &HttpService, args: &serde_json::Value) ->
         description,
         status,
         priority,
+        task_type,
         parent_id,
         reviewer: String::new(),
         research_capability: None,
@@ -209,6 +223,7 @@ async fn handle_create_task(service: &HttpService, args: &serde_json::Value) ->
         plan_capability: None,
         build_capability: None,
         verify_capability: None,
+        due_at: None,
     };
     match flowstate_service::TaskService::create_task(service, &input).await {
         Ok(task) => match serde_json::to_string_pretty(&task) {
@@ -238,6 +253,10 @@ async fn handle_update_task(service: &HttpService, args: &serde_json::Value) ->
             .get("priority")
             .and_then(|v| v.as_str())
             .and_then(Priority::parse_str),
+        task_type: args
+            .get("task_type")
+            .and_then(|v| v.as_str())
+            .and_then(TaskType::parse_str),
         ..Default::default()
     };
     match flowstate_service::TaskService::update_task(service, task_id, &update).await {
@@ -1,3 +1,5 @@
+use flowstate_core::feedback::FeedbackEntry;
+use flowstate_core::task::TaskType;
 use serde::{Deserialize, Serialize};
 
 /// Information about a child task, for inclusion in prompts.
@@ -23,13 +25,21 @@ pub struct PromptContext {
     pub task_id: String,
     pub project_name: String,
     pub repo_url: String,
+    /// Package directory the task is confined to in a monorepo.
+    pub repo_subpath: Option<String>,
     pub task_title: String,
     pub task_description: String,
+    pub task_type: TaskType,
     pub spec_content: Option<String>,
     pub plan_content: Option<String>,
     pub research_content: Option<String>,
     pub verification_content: Option<String>,
+    /// Reviewer feedback a distill run addresses; for a revise run, the
+    /// unresolved review comments on the task's pull request.
     pub distill_feedback: Option<String>,
+    /// Earlier rejection feedback on the document being distilled, oldest
+    /// first, excluding `distill_feedback` itself.
+    pub feedback_history: Vec<FeedbackEntry>,
     pub reviewer_notes: Vec<(String, String)>,
     pub child_tasks: Vec<ChildTaskInfo>,
     pub parent_context: Option<ParentContext>,
@@ -43,6 +53,13 @@ impl PromptContext {
         if !self.repo_url.is_empty() {
             prompt.push_str(&format!("Repository: {}\n\n", self.repo_url));
         }
+        if let Some(ref subpath) = self.repo_subpath {
+            prompt.push_str(&format!(
+                "Package directory: `{subpath}`. This project is one package in a larger \
+                 repository. Keep your changes inside `{subpath}/`; the rest of the \
+                 repository is there for reference only.\n\n"
+            ));
+        }
 
         if let Some(ref parent) = self.parent_context {
             prompt.push_str(&format!("# Parent Task: {}\n\n", parent.title));
@@ -67,6 +84,9 @@ impl PromptContext {
                 self.task_title, self.task_id
             ));
         }
+        if self.task_type != TaskType::Feature {
+            prompt.push_str(&format!("**Type:** {}\n\n", self.task_type.display_name()));
+        }
         prompt.push_str(&format!("## Description\n\n{}\n\n", self.task_description));
 
         if let Some(ref research) = self.research_content {
@@ -126,13 +146,16 @@ mod tests {
             task_id: String::new(),
             project_name: "TestProject".into(),
             repo_url: String::new(),
+            repo_subpath: None,
             task_title: "Test Task".into(),
             task_description: "Do the thing".into(),
+            task_type: TaskType::Feature,
             spec_content: None,
             plan_content: None,
             research_content: None,
             verification_content: None,
             distill_feedback: None,
+            feedback_history: vec![],
             reviewer_notes: vec![],
             child_tasks: vec![],
             parent_context: None,
@@ -164,6 +187,20 @@ mod tests {
         assert!(out.contains("https://github.com/test/repo"));
     }
 
+    #[test]
+    fn preamble_with_repo_subpath() {
+        let mut ctx = minimal_ctx();
+        let mut out = String::new();
+        ctx.append_preamble(&mut out);
+        assert!(!out.contains("Package directory"));
+
+        ctx.repo_subpath = Some("packages/web".into());
+        let mut out = String::new();
+        ctx.append_preamble(&mut out);
+        assert!(out.contains("Package directory: `packages/web`"));
+        assert!(out.contains("inside `packages/web/`"));
+    }
+
     #[test]
     fn preamble_with_spec_and_plan() {
         let mut ctx = minimal_ctx();
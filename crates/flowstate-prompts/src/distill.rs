@@ -1,5 +1,13 @@
-/// Append review-distill instructions to the prompt.
-pub fn append_instructions(prompt: &mut String, phase: &str, feedback: &str) {
+use flowstate_core::feedback::FeedbackEntry;
+
+/// Append review-distill instructions to the prompt. `history` holds earlier
+/// rounds of feedback on the same document, oldest first.
+pub fn append_instructions(
+    prompt: &mut String,
+    phase: &str,
+    feedback: &str,
+    history: &[FeedbackEntry],
+) {
     prompt.push_str("## Instructions — Review & Distill\n\n");
     prompt.push_str(&format!(
         "The previous {phase} output has been reviewed and feedback was provided. \
@@ -8,6 +16,26 @@ pub fn append_instructions(prompt: &mut String, phase: &str, feedback: &str) {
     prompt.push_str("### Reviewer Feedback\n\n");
     prompt.push_str(feedback);
     prompt.push_str("\n\n");
+    if !history.is_empty() {
+        prompt.push_str("### Earlier Review Rounds\n\n");
+        prompt.push_str(
+            "The document was rejected before. Earlier feedback, oldest first; \
+             make sure the revision does not regress on any of it:\n\n",
+        );
+        for entry in history {
+            let author = if entry.author.is_empty() {
+                "unknown"
+            } else {
+                &entry.author
+            };
+            prompt.push_str(&format!(
+                "- {} ({author}): {}\n",
+                entry.created_at.format("%Y-%m-%d"),
+                entry.feedback
+            ));
+        }
+        prompt.push('\n');
+    }
     prompt.push_str(&format!(
         "Revise the {phase} document to address ALL feedback points. \
          Maintain everything that was correct in the original while fixing \
@@ -47,7 +75,7 @@ mod tests {
         ];
         for (phase, expected_file) in cases {
             let mut prompt = String::new();
-            append_instructions(&mut prompt, phase, "feedback");
+            append_instructions(&mut prompt, phase, "feedback", &[]);
             assert!(
                 prompt.contains(expected_file),
                 "phase '{phase}' should produce file '{expected_file}', got: {prompt}"
@@ -58,7 +86,7 @@ mod tests {
     #[test]
     fn distill_instructions_research() {
         let mut out = String::new();
-        append_instructions(&mut out, "research", "fix typos");
+        append_instructions(&mut out, "research", "fix typos", &[]);
         assert!(out.contains("Review & Distill"));
         assert!(out.contains("research"));
         assert!(out.contains("fix typos"));
@@ -68,7 +96,7 @@ mod tests {
     #[test]
     fn distill_instructions_design() {
         let mut out = String::new();
-        append_instructions(&mut out, "design", "revise API");
+        append_instructions(&mut out, "design", "revise API", &[]);
         assert!(out.contains("Review & Distill"));
         assert!(out.contains("design"));
         assert!(out.contains("SPECIFICATION.md"));
@@ -77,7 +105,7 @@ mod tests {
     #[test]
     fn distill_instructions_plan() {
         let mut out = String::new();
-        append_instructions(&mut out, "plan", "add phases");
+        append_instructions(&mut out, "plan", "add phases", &[]);
         assert!(out.contains("Review & Distill"));
         assert!(out.contains("plan"));
         assert!(out.contains("PLAN.md"));
@@ -86,7 +114,7 @@ mod tests {
     #[test]
     fn distill_instructions_verify() {
         let mut out = String::new();
-        append_instructions(&mut out, "verification", "check edge cases");
+        append_instructions(&mut out, "verification", "check edge cases", &[]);
         assert!(out.contains("Review & Distill"));
         assert!(out.contains("verification"));
         assert!(out.contains("VERIFICATION.md"));
@@ -95,7 +123,7 @@ mod tests {
     #[test]
     fn distill_instructions_unknown_phase() {
         let mut out = String::new();
-        append_instructions(&mut out, "foobar", "some feedback");
+        append_instructions(&mut out, "foobar", "some feedback", &[]);
         assert!(out.contains("Review & Distill"));
         assert!(out.contains("foobar"));
         assert!(out.contains("OUTPUT.md"));
@@ -104,7 +132,7 @@ mod tests {
     #[test]
     fn distill_instructions_empty_feedback() {
         let mut out = String::new();
-        append_instructions(&mut out, "research", "");
+        append_instructions(&mut out, "research", "", &[]);
         assert!(out.contains("Review & Distill"));
         assert!(out.contains("research"));
         assert!(out.contains("RESEARCH.md"));
@@ -4,6 +4,8 @@ pub mod design;
 pub mod distill;
 pub mod plan;
 pub mod research;
+pub mod revise;
+pub mod task_type;
 pub mod verify;
 
 pub use context::{ChildTaskInfo, ParentContext, PromptContext};
@@ -15,6 +17,7 @@ pub fn assemble_prompt(ctx: &PromptContext, action: ClaudeAction) -> String {
     ctx.append_preamble(&mut prompt);
 
     let feedback = ctx.distill_feedback.as_deref().unwrap_or("(no feedback)");
+    let history = &ctx.feedback_history;
 
     match action {
         ClaudeAction::Research => research::append_instructions(&mut prompt),
@@ -28,18 +31,20 @@ pub fn assemble_prompt(ctx: &PromptContext, action: ClaudeAction) -> String {
                 prompt.push_str(content);
                 prompt.push_str("\n\n");
             }
-            distill::append_instructions(&mut prompt, "research", feedback);
+            distill::append_instructions(&mut prompt, "research", feedback, history);
         }
         ClaudeAction::DesignDistill => {
-            distill::append_instructions(&mut prompt, "design", feedback);
+            distill::append_instructions(&mut prompt, "design", feedback, history);
         }
         ClaudeAction::PlanDistill => {
-            distill::append_instructions(&mut prompt, "plan", feedback);
+            distill::append_instructions(&mut prompt, "plan", feedback, history);
         }
         ClaudeAction::VerifyDistill => {
-            distill::append_instructions(&mut prompt, "verification", feedback);
+            distill::append_instructions(&mut prompt, "verification", feedback, history);
         }
+        ClaudeAction::Revise => revise::append_instructions(&mut prompt, feedback),
     }
+    task_type::append_instructions(&mut prompt, ctx.task_type, action);
 
     prompt
 }
@@ -47,19 +52,24 @@ pub fn assemble_prompt(ctx: &PromptContext, action: ClaudeAction) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use flowstate_core::feedback::FeedbackEntry;
+    use flowstate_core::task::TaskType;
 
     fn minimal_ctx() -> PromptContext {
         PromptContext {
             task_id: String::new(),
             project_name: "TestProject".into(),
             repo_url: String::new(),
+            repo_subpath: None,
             task_title: "Test Task".into(),
             task_description: "Do the thing".into(),
+            task_type: TaskType::Feature,
             spec_content: None,
             plan_content: None,
             research_content: None,
             verification_content: None,
             distill_feedback: None,
+            feedback_history: vec![],
             reviewer_notes: vec![],
             child_tasks: vec![],
             parent_context: None,
@@ -96,6 +106,15 @@ mod tests {
         assert!(out.contains("Implement the changes"));
     }
 
+    #[test]
+    fn assemble_prompt_bug_build() {
+        let mut ctx = minimal_ctx();
+        ctx.task_type = TaskType::Bug;
+        let out = assemble_prompt(&ctx, ClaudeAction::Build);
+        assert!(out.contains("**Type:** Bug"));
+        assert!(out.find("Implement the changes") < out.find("### Task Type: Bug"));
+    }
+
     #[test]
     fn assemble_prompt_verify() {
         let ctx = minimal_ctx();
@@ -141,6 +160,38 @@ mod tests {
         assert!(out.contains("verification"));
     }
 
+    #[test]
+    fn assemble_prompt_revise() {
+        let mut ctx = minimal_ctx();
+        ctx.plan_content = Some("Phase 1: add the endpoint".into());
+        ctx.distill_feedback = Some("- (bob): handle the empty case".into());
+        let out = assemble_prompt(&ctx, ClaudeAction::Revise);
+        assert!(out.contains("Address Review Comments"));
+        assert!(out.contains("handle the empty case"));
+        assert!(out.contains("Phase 1: add the endpoint"));
+    }
+
+    #[test]
+    fn distill_prompt_includes_earlier_rounds() {
+        let mut ctx = minimal_ctx();
+        ctx.distill_feedback = Some("and rollback".into());
+        let out = assemble_prompt(&ctx, ClaudeAction::PlanDistill);
+        assert!(!out.contains("Earlier Review Rounds"));
+
+        ctx.feedback_history = vec![FeedbackEntry {
+            id: "f1".into(),
+            task_id: "t1".into(),
+            phase: "plan".into(),
+            feedback: "cover migrations".into(),
+            author: "key:alice".into(),
+            created_at: Default::default(),
+        }];
+        let out = assemble_prompt(&ctx, ClaudeAction::PlanDistill);
+        assert!(out.contains("### Earlier Review Rounds"));
+        assert!(out.contains("- 1970-01-01 (key:alice): cover migrations"));
+        assert!(out.find("and rollback") < out.find("cover migrations"));
+    }
+
     #[test]
     fn design_prompt_includes_research_notes() {
         let mut ctx = minimal_ctx();
@@ -0,0 +1,40 @@
+/// Append review-response instructions to the prompt. `comments` is the
+/// unresolved review feedback on the task's pull request, already rendered
+/// as Markdown.
+pub fn append_instructions(prompt: &mut String, comments: &str) {
+    prompt.push_str("## Instructions — Address Review Comments\n\n");
+    prompt.push_str(
+        "The changes for this task are already implemented on the current branch \
+         and open as a pull request. Reviewers left the comments below. Your task \
+         is to update the code on this branch to address them.\n\n",
+    );
+    prompt.push_str("### Review Comments\n\n");
+    prompt.push_str(comments);
+    prompt.push_str("\n\n");
+    prompt.push_str(
+        "Follow these guidelines:\n\
+         - Address every comment. Comments with a file and line refer to the code \
+           at that location.\n\
+         - Keep the change focused on the review; do not rework parts nobody \
+           commented on.\n\
+         - Where you disagree with a comment or cannot act on it, leave the code \
+           as it is and explain why in `REVIEW_RESPONSE.md`.\n\
+         - Run the project's test suite and fix any failures before finishing.\n\
+         - Do not commit or push; the system does that after you finish.\n",
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn revise_instructions_content() {
+        let mut out = String::new();
+        append_instructions(&mut out, "- `src/lib.rs:12` (alice): rename this");
+        assert!(out.contains("Address Review Comments"));
+        assert!(out.contains("### Review Comments"));
+        assert!(out.contains("rename this"));
+        assert!(out.contains("Do not commit or push"));
+    }
+}
@@ -0,0 +1,102 @@
+use flowstate_core::claude_run::ClaudeAction;
+use flowstate_core::task::TaskType;
+
+/// Append the instructions specific to the task's type, after the phase's
+/// own instructions. Features get none; distill runs get their phase's.
+pub fn append_instructions(prompt: &mut String, task_type: TaskType, action: ClaudeAction) {
+    let guidance = match (task_type, action) {
+        (TaskType::Bug, ClaudeAction::Research | ClaudeAction::ResearchDistill) => {
+            "This task is a bug report. Before anything else, find a reliable way \
+             to reproduce it: the inputs, configuration and steps that trigger it, \
+             and the expected versus actual behaviour. Then trace it to its root \
+             cause and note when it was likely introduced. Include a \
+             **Reproduction** section with the exact steps.\n"
+        }
+        (TaskType::Bug, ClaudeAction::Design | ClaudeAction::DesignDistill) => {
+            "This task is a bug fix. The specification must state the root cause, \
+             the smallest change that fixes it, and the regression test that will \
+             prove it. Do not widen the scope into unrelated refactoring.\n"
+        }
+        (TaskType::Bug, ClaudeAction::Plan | ClaudeAction::PlanDistill) => {
+            "This task is a bug fix. The first work phase MUST add a regression \
+             test that reproduces the bug and fails against the current code; \
+             later phases make it pass.\n"
+        }
+        (TaskType::Bug, ClaudeAction::Build) => {
+            "This task is a bug fix. Work in this order:\n\
+             1. Write a regression test that reproduces the bug and run it to \
+             confirm it fails.\n\
+             2. Fix the root cause with the smallest change that makes the test pass.\n\
+             3. Run the full test suite.\n\
+             Keep the regression test in the final change.\n"
+        }
+        (TaskType::Bug, ClaudeAction::Revise) => {
+            "This task is a bug fix. Keep the regression test that reproduces the \
+             bug, and make sure it still passes after addressing the review.\n"
+        }
+        (TaskType::Bug, ClaudeAction::Verify | ClaudeAction::VerifyDistill) => {
+            "This task is a bug fix. Confirm that a regression test reproducing the \
+             bug was added, that it passes, and that it exercises the root cause \
+             rather than a symptom. A fix without a regression test is a FAIL.\n"
+        }
+        (TaskType::Chore, ClaudeAction::Build | ClaudeAction::Revise) => {
+            "This task is a chore: maintenance with no intended change in behaviour. \
+             Existing tests should pass without modification; if one has to change, \
+             explain why.\n"
+        }
+        (TaskType::Chore, ClaudeAction::Verify | ClaudeAction::VerifyDistill) => {
+            "This task is a chore. Check that behaviour is unchanged: existing tests \
+             pass and were not weakened to make them pass.\n"
+        }
+        (TaskType::Chore, _) => {
+            "This task is a chore: maintenance with no intended change in behaviour. \
+             Keep this document short and focused on what changes and how to tell \
+             nothing broke.\n"
+        }
+        (TaskType::Spike, _) => {
+            "This task is a spike: a time-boxed investigation that will not be \
+             built. Its deliverable is knowledge: findings, the options considered \
+             with their trade-offs, a recommendation, and the follow-up tasks that \
+             would implement it. Prototype only as far as needed to answer the \
+             open questions.\n"
+        }
+        (TaskType::Feature, _) => return,
+    };
+    prompt.push_str(&format!(
+        "\n### Task Type: {}\n\n",
+        task_type.display_name()
+    ));
+    prompt.push_str(guidance);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(task_type: TaskType, action: ClaudeAction) -> String {
+        let mut out = String::new();
+        append_instructions(&mut out, task_type, action);
+        out
+    }
+
+    #[test]
+    fn features_get_no_extra_instructions() {
+        assert!(render(TaskType::Feature, ClaudeAction::Build).is_empty());
+    }
+
+    #[test]
+    fn bug_builds_start_with_a_failing_regression_test() {
+        let out = render(TaskType::Bug, ClaudeAction::Build);
+        assert!(out.contains("### Task Type: Bug"));
+        assert!(out.contains("regression test"));
+        assert!(out.find("confirm it fails") < out.find("Fix the root cause"));
+        assert!(render(TaskType::Bug, ClaudeAction::PlanDistill).contains("MUST add a regression"));
+    }
+
+    #[test]
+    fn spikes_ask_for_findings() {
+        let out = render(TaskType::Spike, ClaudeAction::Plan);
+        assert!(out.contains("will not be built"));
+        assert!(out.contains("follow-up tasks"));
+    }
+}
@@ -3,10 +3,11 @@ use std::time::Duration;
 
 use anyhow::{bail, Context, Result};
 use async_trait::async_trait;
+use serde::Deserialize;
 use tokio::process::Command;
-use tracing::info;
+use tracing::{info, warn};
 
-use super::{AgentBackend, AgentOutput, McpEnv};
+use super::{AgentBackend, AgentOutput, McpEnv, TokenUsage};
 use crate::process;
 
 /// Claude CLI backend — wraps the `claude` command-line tool.
@@ -23,6 +24,47 @@ pub struct ClaudeCliBackend {
     pub model: Option<String>,
 }
 
+/// The object `claude -p --output-format json` prints when it finishes.
+#[derive(Deserialize)]
+struct CliResult {
+    #[serde(default)]
+    result: String,
+    total_cost_usd: Option<f64>,
+    usage: Option<CliUsage>,
+}
+
+#[derive(Deserialize)]
+struct CliUsage {
+    #[serde(default)]
+    input_tokens: i64,
+    #[serde(default)]
+    cache_creation_input_tokens: i64,
+    #[serde(default)]
+    cache_read_input_tokens: i64,
+    #[serde(default)]
+    output_tokens: i64,
+}
+
+/// The answer text and usage in a JSON result. Cache reads and writes are
+/// counted as input tokens, since they are billed as input.
+fn parse_result(stdout: &str) -> Option<(String, TokenUsage)> {
+    let result: CliResult = serde_json::from_str(stdout.trim()).ok()?;
+    let usage = result.usage.map_or(
+        TokenUsage {
+            cost_usd: result.total_cost_usd,
+            ..TokenUsage::default()
+        },
+        |u| TokenUsage {
+            input_tokens: u.input_tokens
+                + u.cache_creation_input_tokens
+                + u.cache_read_input_tokens,
+            output_tokens: u.output_tokens,
+            cost_usd: result.total_cost_usd,
+        },
+    );
+    Some((result.result, usage))
+}
+
 #[async_trait]
 impl AgentBackend for ClaudeCliBackend {
     fn name(&self) -> &str {
@@ -98,7 +140,7 @@ impl AgentBackend for ClaudeCliBackend {
         cmd.arg("-p")
             .arg(prompt)
             .arg("--output-format")
-            .arg("text")
+            .arg("json")
             .arg("--dangerously-skip-permissions")
             .current_dir(work_dir);
 
@@ -119,7 +161,17 @@ impl AgentBackend for ClaudeCliBackend {
             cmd.arg("--mcp-config").arg(&mcp_config);
         }
 
-        process::run_managed_with_timeout(&mut cmd, work_dir, timeout, kill_grace).await
+        let mut output =
+            process::run_managed_with_timeout(&mut cmd, work_dir, timeout, kill_grace).await?;
+        match parse_result(&output.stdout) {
+            Some((text, usage)) => {
+                output.stdout = text;
+                output.usage = Some(usage);
+                process::save_output(work_dir, &output.stdout);
+            }
+            None => warn!("claude: output is not a JSON result, so no usage was recorded"),
+        }
+        Ok(output)
     }
 }
 
@@ -198,6 +250,30 @@ mod tests {
         assert_eq!(b.model_hint(), Some("claude-opus-4-6"));
     }
 
+    /// A result printed by `claude -p --output-format json`.
+    const JSON_RESULT: &str = r#"{"type":"result","subtype":"success","is_error":false,"duration_ms":48211,"duration_api_ms":45102,"num_turns":7,"result":"Wrote .flowstate-output/research.md","session_id":"8f0c2d4e-5b1a-4c7e-9e43-2a6f1d9b7c10","total_cost_usd":0.2143915,"usage":{"input_tokens":38,"cache_creation_input_tokens":21334,"cache_read_input_tokens":152840,"output_tokens":2917,"server_tool_use":{"web_search_requests":0},"service_tier":"standard"}}
+"#;
+
+    #[test]
+    fn parses_json_result() {
+        let (text, usage) = parse_result(JSON_RESULT).unwrap();
+        assert_eq!(text, "Wrote .flowstate-output/research.md");
+        assert_eq!(
+            usage,
+            TokenUsage {
+                input_tokens: 38 + 21334 + 152840,
+                output_tokens: 2917,
+                cost_usd: Some(0.2143915),
+            }
+        );
+    }
+
+    #[test]
+    fn plain_text_is_not_a_result() {
+        assert!(parse_result("Wrote .flowstate-output/research.md\n").is_none());
+        assert!(parse_result("").is_none());
+    }
+
     #[test]
     fn name_with_both_overrides() {
         let b = ClaudeCliBackend {
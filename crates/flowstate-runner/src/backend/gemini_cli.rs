@@ -1,12 +1,14 @@
+use std::collections::HashMap;
 use std::path::Path;
 use std::time::Duration;
 
 use anyhow::{bail, Context, Result};
 use async_trait::async_trait;
+use serde::Deserialize;
 use tokio::process::Command;
-use tracing::info;
+use tracing::{info, warn};
 
-use super::{AgentBackend, AgentOutput};
+use super::{AgentBackend, AgentOutput, TokenUsage};
 use crate::process;
 
 /// Gemini CLI backend — wraps the `gemini` command-line tool (`@google/gemini-cli`).
@@ -59,6 +61,52 @@ impl GeminiCliBackend {
     }
 }
 
+/// The object `gemini -p --output-format json` prints when it finishes.
+#[derive(Deserialize)]
+struct CliResult {
+    #[serde(default)]
+    response: String,
+    stats: Option<CliStats>,
+}
+
+#[derive(Deserialize)]
+struct CliStats {
+    #[serde(default)]
+    models: HashMap<String, ModelStats>,
+}
+
+#[derive(Deserialize)]
+struct ModelStats {
+    tokens: ModelTokens,
+}
+
+#[derive(Deserialize)]
+struct ModelTokens {
+    #[serde(default)]
+    prompt: i64,
+    #[serde(default)]
+    candidates: i64,
+    #[serde(default)]
+    thoughts: i64,
+}
+
+/// The answer text and usage in a JSON result, summed over every model the
+/// run used. Thinking tokens are billed as output, so they count as output.
+/// The CLI reports no cost.
+fn parse_result(stdout: &str) -> Option<(String, TokenUsage)> {
+    let result: CliResult = serde_json::from_str(stdout.trim()).ok()?;
+    let mut usage = TokenUsage::default();
+    for model in result
+        .stats
+        .into_iter()
+        .flat_map(|s| s.models.into_values())
+    {
+        usage.input_tokens += model.tokens.prompt;
+        usage.output_tokens += model.tokens.candidates + model.tokens.thoughts;
+    }
+    Some((result.response, usage))
+}
+
 #[async_trait]
 impl AgentBackend for GeminiCliBackend {
     fn name(&self) -> &str {
@@ -122,7 +170,7 @@ impl AgentBackend for GeminiCliBackend {
         cmd.arg("-p")
             .arg(prompt)
             .arg("--output-format")
-            .arg("text")
+            .arg("json")
             .arg("--yolo")
             .current_dir(work_dir);
 
@@ -132,7 +180,17 @@ impl AgentBackend for GeminiCliBackend {
 
         self.apply_env_async(&mut cmd, repo_token);
 
-        process::run_managed_with_timeout(&mut cmd, work_dir, timeout, kill_grace).await
+        let mut output =
+            process::run_managed_with_timeout(&mut cmd, work_dir, timeout, kill_grace).await?;
+        match parse_result(&output.stdout) {
+            Some((text, usage)) => {
+                output.stdout = text;
+                output.usage = Some(usage);
+                process::save_output(work_dir, &output.stdout);
+            }
+            None => warn!("gemini: output is not a JSON result, so no usage was recorded"),
+        }
+        Ok(output)
     }
 }
 
@@ -207,6 +265,41 @@ mod tests {
         assert_eq!(backend_full().model_hint(), Some("gemini-2.5-flash"));
     }
 
+    /// A result printed by `gemini -p --output-format json`.
+    const JSON_RESULT: &str = r#"{
+  "response": "Wrote .flowstate-output/plan.md",
+  "stats": {
+    "models": {
+      "gemini-2.5-pro": {
+        "api": { "totalRequests": 4, "totalErrors": 0, "totalLatencyMs": 31870 },
+        "tokens": { "prompt": 18423, "candidates": 1204, "total": 21050, "cached": 9120, "thoughts": 1423, "tool": 0 }
+      },
+      "gemini-2.5-flash": {
+        "api": { "totalRequests": 1, "totalErrors": 0, "totalLatencyMs": 1402 },
+        "tokens": { "prompt": 912, "candidates": 37, "total": 1001, "cached": 0, "thoughts": 52, "tool": 0 }
+      }
+    },
+    "tools": { "totalCalls": 6, "totalSuccess": 6, "totalFail": 0, "totalDurationMs": 2210 },
+    "files": { "totalLinesAdded": 48, "totalLinesRemoved": 0 }
+  }
+}
+"#;
+
+    #[test]
+    fn parses_json_result() {
+        let (text, usage) = parse_result(JSON_RESULT).unwrap();
+        assert_eq!(text, "Wrote .flowstate-output/plan.md");
+        assert_eq!(
+            usage,
+            TokenUsage {
+                input_tokens: 18423 + 912,
+                output_tokens: 1204 + 1423 + 37 + 52,
+                cost_usd: None,
+            }
+        );
+        assert!(parse_result("Wrote .flowstate-output/plan.md").is_none());
+    }
+
     #[test]
     fn test_apply_env_no_config() {
         let backend = backend_minimal();
@@ -0,0 +1,145 @@
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use flowstate_core::run_metrics::RecordRunMetrics;
+
+use super::{AgentBackend, AgentOutput, McpEnv, TokenUsage};
+
+/// Wraps a backend for the lifetime of one run, totalling stdout size and
+/// token usage across every invocation (a build runs the agent several times).
+pub struct MeteredBackend<'a> {
+    inner: &'a dyn AgentBackend,
+    totals: Mutex<Totals>,
+}
+
+#[derive(Default)]
+struct Totals {
+    stdout_bytes: i64,
+    usage: Option<TokenUsage>,
+}
+
+impl<'a> MeteredBackend<'a> {
+    pub fn new(inner: &'a dyn AgentBackend) -> Self {
+        Self {
+            inner,
+            totals: Mutex::new(Totals::default()),
+        }
+    }
+
+    /// Metrics for everything observed so far, with `elapsed` as the duration.
+    pub fn metrics(&self, elapsed: Duration) -> RecordRunMetrics {
+        let totals = self.totals.lock().unwrap();
+        RecordRunMetrics {
+            duration_ms: elapsed.as_millis() as i64,
+            stdout_bytes: totals.stdout_bytes,
+            input_tokens: totals.usage.map(|u| u.input_tokens),
+            output_tokens: totals.usage.map(|u| u.output_tokens),
+            cost_usd: totals.usage.and_then(|u| u.cost_usd),
+        }
+    }
+
+    fn observe(&self, output: &AgentOutput) {
+        let mut totals = self.totals.lock().unwrap();
+        totals.stdout_bytes += output.stdout.len() as i64;
+        if let Some(usage) = output.usage {
+            let sum = totals.usage.get_or_insert_with(TokenUsage::default);
+            sum.input_tokens += usage.input_tokens;
+            sum.output_tokens += usage.output_tokens;
+            if let Some(cost) = usage.cost_usd {
+                sum.cost_usd = Some(sum.cost_usd.unwrap_or(0.0) + cost);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl AgentBackend for MeteredBackend<'_> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn model_hint(&self) -> Option<&str> {
+        self.inner.model_hint()
+    }
+
+    fn supports_mcp(&self) -> bool {
+        self.inner.supports_mcp()
+    }
+
+    async fn preflight_check(&self) -> Result<()> {
+        self.inner.preflight_check().await
+    }
+
+    async fn run(
+        &self,
+        prompt: &str,
+        work_dir: &Path,
+        timeout: Duration,
+        kill_grace: Duration,
+        repo_token: Option<&str>,
+        mcp_env: Option<&McpEnv>,
+    ) -> Result<AgentOutput> {
+        let output = self
+            .inner
+            .run(prompt, work_dir, timeout, kill_grace, repo_token, mcp_env)
+            .await?;
+        self.observe(&output);
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockBackend;
+
+    async fn run_once(backend: &dyn AgentBackend, dir: &Path) {
+        backend
+            .run(
+                "prompt",
+                dir,
+                Duration::from_secs(60),
+                Duration::from_secs(5),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn totals_stdout_across_runs() {
+        let mock = MockBackend::success("hello");
+        let metered = MeteredBackend::new(&mock);
+        let tmp = tempfile::tempdir().unwrap();
+        run_once(&metered, tmp.path()).await;
+        run_once(&metered, tmp.path()).await;
+
+        let m = metered.metrics(Duration::from_millis(1234));
+        assert_eq!(m.duration_ms, 1234);
+        assert_eq!(m.stdout_bytes, 10);
+        assert_eq!(m.input_tokens, None);
+        assert_eq!(m.cost_usd, None);
+    }
+
+    #[tokio::test]
+    async fn sums_reported_usage() {
+        let mock = MockBackend::success("").with_usage(TokenUsage {
+            input_tokens: 100,
+            output_tokens: 20,
+            cost_usd: Some(0.5),
+        });
+        let metered = MeteredBackend::new(&mock);
+        let tmp = tempfile::tempdir().unwrap();
+        run_once(&metered, tmp.path()).await;
+        run_once(&metered, tmp.path()).await;
+
+        let m = metered.metrics(Duration::ZERO);
+        assert_eq!(m.input_tokens, Some(200));
+        assert_eq!(m.output_tokens, Some(40));
+        assert_eq!(m.cost_usd, Some(1.0));
+    }
+}
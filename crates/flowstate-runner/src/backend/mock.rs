@@ -4,7 +4,7 @@ use std::time::Duration;
 use anyhow::Result;
 use async_trait::async_trait;
 
-use super::{AgentBackend, AgentOutput};
+use super::{AgentBackend, AgentOutput, TokenUsage};
 
 /// A mock backend for testing that writes specified files into the workspace
 /// and returns a preconfigured output.
@@ -24,6 +24,7 @@ impl MockBackend {
                 stdout: stdout.to_string(),
                 stderr: String::new(),
                 exit_code: 0,
+                usage: None,
             },
             files: Vec::new(),
         }
@@ -37,11 +38,18 @@ impl MockBackend {
                 stdout: String::new(),
                 stderr: stderr.to_string(),
                 exit_code,
+                usage: None,
             },
             files: Vec::new(),
         }
     }
 
+    /// Report `usage` with the output, as a metering backend would.
+    pub fn with_usage(mut self, usage: TokenUsage) -> Self {
+        self.output.usage = Some(usage);
+        self
+    }
+
     /// Add files to write into the workspace before returning.
     pub fn with_files(mut self, files: Vec<(&str, &str)>) -> Self {
         self.files = files
@@ -1,6 +1,8 @@
 pub mod claude_cli;
 pub mod gemini_cli;
+pub mod metered;
 pub mod mock;
+pub mod ollama;
 pub mod opencode;
 
 use std::path::{Path, PathBuf};
@@ -16,6 +18,16 @@ pub struct AgentOutput {
     pub stdout: String,
     pub stderr: String,
     pub exit_code: i32,
+    /// Token usage, for backends whose output reports it.
+    pub usage: Option<TokenUsage>,
+}
+
+/// Tokens consumed by one agent invocation.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TokenUsage {
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub cost_usd: Option<f64>,
 }
 
 /// MCP server configuration passed to backends that support it.
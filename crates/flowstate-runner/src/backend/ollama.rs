@@ -0,0 +1,370 @@
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+use tokio::io::AsyncWriteExt;
+use tracing::info;
+
+use super::{AgentBackend, AgentOutput, McpEnv, TokenUsage};
+use crate::process;
+
+/// Ollama backend — sends the prompt to a locally served model through the
+/// OpenAI-compatible API that both Ollama and vLLM expose.
+///
+/// The model only answers in text: it has no tools, so it cannot explore
+/// the workspace or edit files. Its answer becomes the run's stdout, which
+/// the executor takes as the document when no file was written. That suits
+/// research, design, plan and verify runs, not builds.
+pub struct OllamaBackend {
+    /// Server root, e.g. "http://127.0.0.1:11434" for Ollama or
+    /// "http://127.0.0.1:8000" for vLLM
+    pub base_url: String,
+    /// Model name as the server lists it, e.g. "qwen2.5-coder:32b"
+    pub model: String,
+    /// Bearer token, for a vLLM server started with `--api-key`
+    pub api_key: Option<String>,
+}
+
+/// `GET /v1/models`.
+#[derive(Deserialize)]
+struct ModelList {
+    data: Vec<ModelEntry>,
+}
+
+#[derive(Deserialize)]
+struct ModelEntry {
+    id: String,
+}
+
+/// One `data:` event of a streamed chat completion.
+#[derive(Deserialize)]
+struct StreamChunk {
+    #[serde(default)]
+    choices: Vec<StreamChoice>,
+    usage: Option<Usage>,
+}
+
+#[derive(Deserialize)]
+struct StreamChoice {
+    #[serde(default)]
+    delta: Delta,
+}
+
+#[derive(Deserialize, Default)]
+struct Delta {
+    content: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Usage {
+    prompt_tokens: i64,
+    completion_tokens: i64,
+}
+
+impl OllamaBackend {
+    /// `path` under the server's OpenAI-compatible API. A base URL that
+    /// already ends in `/v1` is accepted too.
+    fn endpoint(&self, path: &str) -> String {
+        let base = self.base_url.trim_end_matches('/');
+        let base = base.strip_suffix("/v1").unwrap_or(base);
+        format!("{base}/v1/{path}")
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let request = reqwest::Client::new().request(method, self.endpoint(path));
+        match self.api_key {
+            Some(ref key) => request.bearer_auth(key),
+            None => request,
+        }
+    }
+
+    /// Stream a chat completion for `prompt`, appending the answer to the
+    /// live log as it arrives.
+    async fn complete(&self, prompt: &str, work_dir: &Path) -> Result<AgentOutput> {
+        let body = json!({
+            "model": self.model,
+            "messages": [{"role": "user", "content": prompt}],
+            "stream": true,
+            "stream_options": {"include_usage": true},
+        });
+        let mut response = self
+            .request(reqwest::Method::POST, "chat/completions")
+            .json(&body)
+            .send()
+            .await
+            .with_context(|| format!("POST {}", self.endpoint("chat/completions")))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let detail = response.text().await.unwrap_or_default();
+            return Ok(AgentOutput {
+                success: false,
+                stdout: String::new(),
+                stderr: format!("{status}: {}", detail.trim()),
+                exit_code: 1,
+                usage: None,
+            });
+        }
+
+        let mut live_log = process::open_live_log(work_dir).await;
+        let mut answer = String::new();
+        let mut usage = None;
+        let mut pending = Vec::new();
+        while let Some(bytes) = response
+            .chunk()
+            .await
+            .context("reading completion stream")?
+        {
+            pending.extend_from_slice(&bytes);
+            while let Some(end) = pending.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = pending.drain(..=end).collect();
+                let Some(chunk) = parse_event(&String::from_utf8_lossy(&line)) else {
+                    continue;
+                };
+                for content in chunk.choices.into_iter().filter_map(|c| c.delta.content) {
+                    if let Some(log) = live_log.as_mut() {
+                        let _ = log.write_all(content.as_bytes()).await;
+                    }
+                    answer.push_str(&content);
+                }
+                if let Some(u) = chunk.usage {
+                    usage = Some(TokenUsage {
+                        input_tokens: u.prompt_tokens,
+                        output_tokens: u.completion_tokens,
+                        cost_usd: None,
+                    });
+                }
+            }
+        }
+
+        process::save_output(work_dir, &answer);
+        Ok(AgentOutput {
+            success: true,
+            stdout: answer,
+            stderr: String::new(),
+            exit_code: 0,
+            usage,
+        })
+    }
+}
+
+/// Parse one line of the event stream; `None` for blank lines, comments,
+/// the closing `[DONE]` and anything unreadable.
+fn parse_event(line: &str) -> Option<StreamChunk> {
+    let data = line.trim().strip_prefix("data:")?.trim();
+    if data == "[DONE]" {
+        return None;
+    }
+    serde_json::from_str(data).ok()
+}
+
+/// Whether `model` is among the server's `models`. Ollama lists a model
+/// with its tag, so an untagged name matches `:latest`.
+fn model_available(models: &[String], model: &str) -> bool {
+    models
+        .iter()
+        .any(|m| m == model || (!model.contains(':') && *m == format!("{model}:latest")))
+}
+
+#[async_trait]
+impl AgentBackend for OllamaBackend {
+    fn name(&self) -> &str {
+        "ollama"
+    }
+
+    fn model_hint(&self) -> Option<&str> {
+        Some(&self.model)
+    }
+
+    async fn preflight_check(&self) -> Result<()> {
+        let url = self.endpoint("models");
+        let response = self
+            .request(reqwest::Method::GET, "models")
+            .send()
+            .await
+            .with_context(|| format!("cannot reach {url}; is `ollama serve` running?"))?;
+        if !response.status().is_success() {
+            bail!("GET {url} returned {}", response.status());
+        }
+        let models: Vec<String> = response
+            .json::<ModelList>()
+            .await
+            .with_context(|| format!("parsing model list from {url}"))?
+            .data
+            .into_iter()
+            .map(|m| m.id)
+            .collect();
+
+        if !model_available(&models, &self.model) {
+            bail!(
+                "model {} is not available at {}. Pull it: ollama pull {}\n\
+                 Available: {}",
+                self.model,
+                self.base_url,
+                self.model,
+                if models.is_empty() {
+                    "(none)".to_string()
+                } else {
+                    models.join(", ")
+                }
+            );
+        }
+        info!("ollama: {} available at {}", self.model, self.base_url);
+
+        Ok(())
+    }
+
+    async fn run(
+        &self,
+        prompt: &str,
+        work_dir: &Path,
+        timeout: Duration,
+        _kill_grace: Duration,
+        _repo_token: Option<&str>,
+        _mcp_env: Option<&McpEnv>,
+    ) -> Result<AgentOutput> {
+        tokio::time::timeout(timeout, self.complete(prompt, work_dir))
+            .await
+            .map_err(|_| anyhow::anyhow!("request timed out after {timeout:?}"))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::{get, post};
+    use axum::Router;
+
+    fn backend(base_url: &str) -> OllamaBackend {
+        OllamaBackend {
+            base_url: base_url.to_string(),
+            model: "qwen2.5-coder".to_string(),
+            api_key: None,
+        }
+    }
+
+    /// Serve a fake OpenAI-compatible API listing `models` and streaming
+    /// `events` for every completion; returns its base URL.
+    async fn serve(models: &'static [&'static str], events: &'static str) -> String {
+        let app = Router::new()
+            .route(
+                "/v1/models",
+                get(move || async move {
+                    axum::Json(json!({
+                        "object": "list",
+                        "data": models.iter().map(|id| json!({"id": id})).collect::<Vec<_>>(),
+                    }))
+                }),
+            )
+            .route(
+                "/v1/chat/completions",
+                post(move || async move { ([("content-type", "text/event-stream")], events) }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{addr}")
+    }
+
+    #[test]
+    fn test_name_and_model_hint() {
+        let b = backend("http://127.0.0.1:11434");
+        assert_eq!(b.name(), "ollama");
+        assert_eq!(b.model_hint(), Some("qwen2.5-coder"));
+    }
+
+    #[test]
+    fn test_endpoint() {
+        assert_eq!(
+            backend("http://127.0.0.1:11434").endpoint("models"),
+            "http://127.0.0.1:11434/v1/models"
+        );
+        assert_eq!(
+            backend("http://gpu:8000/v1/").endpoint("chat/completions"),
+            "http://gpu:8000/v1/chat/completions"
+        );
+    }
+
+    #[test]
+    fn test_model_available() {
+        let models = vec!["qwen2.5-coder:latest".to_string(), "llama3:8b".to_string()];
+        assert!(model_available(&models, "qwen2.5-coder"));
+        assert!(model_available(&models, "qwen2.5-coder:latest"));
+        assert!(model_available(&models, "llama3:8b"));
+        assert!(!model_available(&models, "llama3"));
+        assert!(!model_available(&models, "qwen2.5-coder:32b"));
+    }
+
+    #[test]
+    fn test_parse_event() {
+        let chunk = parse_event(r#"data: {"choices":[{"delta":{"content":"Hi"}}]}"#).unwrap();
+        assert_eq!(chunk.choices[0].delta.content.as_deref(), Some("Hi"));
+        assert!(parse_event("data: [DONE]").is_none());
+        assert!(parse_event("").is_none());
+        assert!(parse_event(": keep-alive").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_preflight_checks_the_model_is_pulled() {
+        let url = serve(&["qwen2.5-coder:latest"], "").await;
+        backend(&url).preflight_check().await.unwrap();
+
+        let mut missing = backend(&url);
+        missing.model = "llama3".into();
+        let err = missing.preflight_check().await.unwrap_err().to_string();
+        assert!(err.contains("ollama pull llama3"), "{err}");
+        assert!(err.contains("qwen2.5-coder:latest"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_preflight_unreachable_server() {
+        let err = backend("http://127.0.0.1:1")
+            .preflight_check()
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("ollama serve"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_run_streams_the_answer() {
+        let events = concat!(
+            "data: {\"choices\":[{\"delta\":{\"role\":\"assistant\",\"content\":\"# Research\"}}]}\n\n",
+            "data: {\"choices\":[{\"delta\":{\"content\":\"\\n\\nFindings.\"}}]}\n\n",
+            "data: {\"choices\":[],\"usage\":{\"prompt_tokens\":12,\"completion_tokens\":5}}\n\n",
+            "data: [DONE]\n\n",
+        );
+        let url = serve(&["qwen2.5-coder:latest"], events).await;
+        let tmp = tempfile::tempdir().unwrap();
+
+        let output = backend(&url)
+            .run(
+                "Research this",
+                tmp.path(),
+                Duration::from_secs(10),
+                Duration::from_secs(1),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(output.success);
+        assert_eq!(output.stdout, "# Research\n\nFindings.");
+        assert_eq!(
+            output.usage,
+            Some(TokenUsage {
+                input_tokens: 12,
+                output_tokens: 5,
+                cost_usd: None,
+            })
+        );
+        let live = std::fs::read_to_string(process::live_log_path(tmp.path())).unwrap();
+        assert_eq!(live, "# Research\n\nFindings.");
+        let saved =
+            std::fs::read_to_string(tmp.path().join(".flowstate-output/output.txt")).unwrap();
+        assert_eq!(saved, output.stdout);
+    }
+}
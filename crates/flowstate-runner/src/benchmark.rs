@@ -0,0 +1,202 @@
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use flowstate_core::runner::RunnerBenchmark;
+use tracing::{info, warn};
+
+use crate::backend::{AgentBackend, AgentOutput};
+
+/// Longest either measurement may take before it is abandoned.
+const BENCHMARK_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Prompt whose answer is long enough to time generation but needs no tools
+/// or thought.
+const BACKEND_PROMPT: &str = "Write the integers from 1 to 400 in words, one per line \
+     (\"one\", \"two\", ...). Output only the list. Do not use any tools or read any files.";
+
+const SAMPLE_MANIFEST: &str = r#"[package]
+name = "flowstate-benchmark"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+"#;
+
+/// Enough generic code to keep the compiler busy for a few seconds without
+/// pulling in dependencies.
+const SAMPLE_MAIN: &str = r#"use std::collections::{BTreeMap, HashMap};
+use std::fmt::Debug;
+
+trait Shape: Debug {
+    fn area(&self) -> f64;
+    fn scale(&self, by: f64) -> Box<dyn Shape>;
+}
+
+macro_rules! shapes {
+    ($($name:ident($($field:ident),*) => $area:expr;)*) => {$(
+        #[derive(Debug, Clone, PartialEq)]
+        struct $name { $($field: f64),* }
+        impl Shape for $name {
+            fn area(&self) -> f64 { let $name { $($field),* } = self; $area }
+            fn scale(&self, by: f64) -> Box<dyn Shape> {
+                Box::new($name { $($field: self.$field * by),* })
+            }
+        }
+    )*};
+}
+
+shapes! {
+    Square(side) => side * side;
+    Rect(width, height) => width * height;
+    Circle(radius) => std::f64::consts::PI * radius * radius;
+    Triangle(base, height) => base * height / 2.0;
+    Ellipse(a, b) => std::f64::consts::PI * a * b;
+    Trapezoid(a, b, height) => (a + b) * height / 2.0;
+}
+
+fn summarize<K: Ord + Clone + Debug, V: Into<f64> + Copy>(items: &[(K, V)]) -> BTreeMap<K, f64> {
+    let mut out = BTreeMap::new();
+    for (k, v) in items {
+        *out.entry(k.clone()).or_insert(0.0) += (*v).into();
+    }
+    out
+}
+
+fn main() {
+    let shapes: Vec<Box<dyn Shape>> = vec![
+        Box::new(Square { side: 2.0 }),
+        Box::new(Rect { width: 2.0, height: 3.0 }),
+        Box::new(Circle { radius: 1.0 }),
+        Box::new(Triangle { base: 3.0, height: 4.0 }),
+        Box::new(Ellipse { a: 1.0, b: 2.0 }),
+        Box::new(Trapezoid { a: 1.0, b: 2.0, height: 3.0 }),
+    ];
+    let scaled: Vec<_> = shapes.iter().map(|s| s.scale(1.5)).collect();
+    let mut by_kind: HashMap<String, f64> = HashMap::new();
+    for s in shapes.iter().chain(&scaled) {
+        let kind = format!("{s:?}").split(' ').next().unwrap_or("").to_string();
+        *by_kind.entry(kind).or_default() += s.area();
+    }
+    let pairs: Vec<(String, f32)> = by_kind.iter().map(|(k, v)| (k.clone(), *v as f32)).collect();
+    println!("{:?}", summarize(&pairs));
+}
+"#;
+
+/// Measure this machine and backend. Each measurement that fails is logged
+/// and left out rather than failing startup.
+pub async fn run(backend: &dyn AgentBackend) -> RunnerBenchmark {
+    let dir = std::env::temp_dir().join(format!("flowstate-benchmark-{}", uuid::Uuid::new_v4()));
+    let benchmark = RunnerBenchmark {
+        compile_secs: match time_compile(&dir).await {
+            Ok(secs) => Some(secs),
+            Err(e) => {
+                warn!("benchmark: sample build failed: {e}");
+                None
+            }
+        },
+        tokens_per_sec: match time_backend(backend, &dir).await {
+            Ok(rate) => rate,
+            Err(e) => {
+                warn!("benchmark: {} prompt failed: {e}", backend.name());
+                None
+            }
+        },
+    };
+    let _ = tokio::fs::remove_dir_all(&dir).await;
+    info!(
+        "benchmark: compile {:?}s, {:?} tokens/s, class {}",
+        benchmark.compile_secs,
+        benchmark.tokens_per_sec,
+        benchmark.performance_class()
+    );
+    benchmark
+}
+
+/// Seconds for a clean `cargo build` of the sample project.
+async fn time_compile(dir: &Path) -> anyhow::Result<f64> {
+    let project = dir.join("sample");
+    tokio::fs::create_dir_all(project.join("src")).await?;
+    tokio::fs::write(project.join("Cargo.toml"), SAMPLE_MANIFEST).await?;
+    tokio::fs::write(project.join("src/main.rs"), SAMPLE_MAIN).await?;
+
+    let started = Instant::now();
+    let build = tokio::process::Command::new("cargo")
+        .args(["build", "--offline", "--quiet"])
+        .current_dir(&project)
+        .env("CARGO_TARGET_DIR", dir.join("target"))
+        .kill_on_drop(true)
+        .output();
+    let output = tokio::time::timeout(BENCHMARK_TIMEOUT, build)
+        .await
+        .map_err(|_| anyhow::anyhow!("timed out"))??;
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(started.elapsed().as_secs_f64())
+}
+
+async fn time_backend(backend: &dyn AgentBackend, dir: &Path) -> anyhow::Result<Option<f64>> {
+    let work_dir = dir.join("prompt");
+    tokio::fs::create_dir_all(&work_dir).await?;
+    let started = Instant::now();
+    let output = backend
+        .run(
+            BACKEND_PROMPT,
+            &work_dir,
+            BENCHMARK_TIMEOUT,
+            Duration::from_secs(10),
+            None,
+            None,
+        )
+        .await?;
+    if !output.success {
+        anyhow::bail!("exit code {}", output.exit_code);
+    }
+    Ok(tokens_per_sec(&output, started.elapsed()))
+}
+
+/// Output tokens per second, from the backend's reported usage or, without
+/// it, estimated at four characters a token.
+fn tokens_per_sec(output: &AgentOutput, elapsed: Duration) -> Option<f64> {
+    let tokens = match output.usage {
+        Some(usage) if usage.output_tokens > 0 => usage.output_tokens as f64,
+        _ => output.stdout.trim().chars().count() as f64 / 4.0,
+    };
+    let secs = elapsed.as_secs_f64();
+    (tokens > 0.0 && secs > 0.0).then(|| tokens / secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::TokenUsage;
+
+    #[test]
+    fn tokens_per_sec_prefers_reported_usage() {
+        let mut output = AgentOutput {
+            success: true,
+            stdout: "x".repeat(400),
+            stderr: String::new(),
+            exit_code: 0,
+            usage: None,
+        };
+        assert_eq!(tokens_per_sec(&output, Duration::from_secs(2)), Some(50.0));
+        output.usage = Some(TokenUsage {
+            input_tokens: 10,
+            output_tokens: 300,
+            cost_usd: None,
+        });
+        assert_eq!(tokens_per_sec(&output, Duration::from_secs(2)), Some(150.0));
+        output.usage = None;
+        output.stdout.clear();
+        assert_eq!(tokens_per_sec(&output, Duration::from_secs(2)), None);
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn sample_project_builds() {
+        // Needs cargo on PATH; the sample has no dependencies to fetch
+        let dir = tempfile::tempdir().unwrap();
+        assert!(time_compile(dir.path()).await.unwrap() > 0.0);
+    }
+}
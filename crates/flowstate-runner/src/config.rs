@@ -1,13 +1,17 @@
+use std::net::IpAddr;
 use std::path::PathBuf;
 use std::time::Duration;
 
-use anyhow::{bail, Result};
-use clap::Parser;
+use anyhow::{bail, Context, Result};
+use clap::{Args, Parser, Subcommand};
 use flowstate_core::claude_run::ClaudeAction;
 use flowstate_core::runner::RunnerCapability;
+use flowstate_core::TaskLinks;
+use flowstate_service::HttpService;
 
 use crate::backend::claude_cli::ClaudeCliBackend;
 use crate::backend::gemini_cli::GeminiCliBackend;
+use crate::backend::ollama::OllamaBackend;
 use crate::backend::opencode::OpenCodeBackend;
 use crate::backend::{AgentBackend, McpEnv};
 
@@ -26,18 +30,80 @@ pub struct RunnerConfig {
     #[arg(long, env = "FLOWSTATE_API_KEY")]
     pub api_key: Option<String>,
 
+    /// Client certificate (PEM) presented to a server that enforces runner
+    /// mTLS. Issue one with `flowstate-server enroll-runner`.
+    #[arg(long, env = "FLOWSTATE_CLIENT_CERT", requires = "client_key")]
+    pub client_cert: Option<PathBuf>,
+
+    /// Private key (PEM) for `--client-cert`
+    #[arg(long, env = "FLOWSTATE_CLIENT_KEY", requires = "client_cert")]
+    pub client_key: Option<PathBuf>,
+
+    /// Additional CA certificate (PEM) to trust for the server's TLS certificate
+    #[arg(long, env = "FLOWSTATE_SERVER_CA")]
+    pub server_ca: Option<PathBuf>,
+
+    /// Fork into the background before starting (not needed under systemd)
+    #[arg(long, requires = "log_file")]
+    pub daemon: bool,
+
+    /// With --daemon, write the background process's PID to this file
+    #[arg(long, requires = "daemon")]
+    pub pid_file: Option<PathBuf>,
+
+    /// Append logs to this file instead of writing them to stderr
+    #[arg(long, env = "FLOWSTATE_LOG_FILE")]
+    pub log_file: Option<PathBuf>,
+
     /// Poll interval in seconds
     #[arg(long, default_value = "5")]
     pub poll_interval: u64,
 
+    /// How long to keep retrying writes the server refuses for maintenance
+    /// (seconds), so runs in flight can still report. 0 fails them at once.
+    #[arg(long, env = "FLOWSTATE_MAINTENANCE_WAIT", default_value = "3600")]
+    pub maintenance_wait: u64,
+
+    /// Seconds the server may hold an empty claim open waiting for work
+    /// (capped at 25 server-side); 0 falls back to plain polling
+    #[arg(long, env = "FLOWSTATE_CLAIM_WAIT", default_value = "20")]
+    pub claim_wait: u64,
+
     /// Root directory for workspaces
     #[arg(long, env = "FLOWSTATE_WORKSPACE_ROOT")]
     pub workspace_root: Option<PathBuf>,
 
+    /// How often to sweep the workspace root for orphaned workspaces
+    /// (seconds). 0 disables the janitor.
+    #[arg(long, env = "FLOWSTATE_JANITOR_INTERVAL", default_value = "600")]
+    pub janitor_interval: u64,
+
+    /// Workspaces modified more recently than this are never removed (seconds).
+    #[arg(long, env = "FLOWSTATE_JANITOR_MIN_AGE", default_value = "3600")]
+    pub janitor_min_age: u64,
+
+    /// Log orphaned workspaces instead of removing them.
+    #[arg(long, env = "FLOWSTATE_JANITOR_DRY_RUN")]
+    pub janitor_dry_run: bool,
+
+    /// File recording in-flight runs for crash recovery
+    /// (default: `.inflight.json` in the workspace root)
+    #[arg(long, env = "FLOWSTATE_STATE_FILE")]
+    pub state_file: Option<PathBuf>,
+
     /// Port for the health check endpoint
-    #[arg(long, default_value = "3711")]
+    #[arg(long, env = "FLOWSTATE_HEALTH_PORT", default_value = "3711")]
     pub health_port: u16,
 
+    /// Address the health endpoint binds to. Use 0.0.0.0 to let fleet
+    /// monitoring scrape the runner directly (pair with --health-token).
+    #[arg(long, env = "FLOWSTATE_HEALTH_BIND", default_value = "127.0.0.1")]
+    pub health_bind: IpAddr,
+
+    /// Bearer token required to read the health endpoint. Unset leaves it open.
+    #[arg(long, env = "FLOWSTATE_HEALTH_TOKEN")]
+    pub health_token: Option<String>,
+
     /// Timeout for research/design/plan/verify actions (seconds).
     /// Research tasks can be open-ended, so this defaults to 30 minutes.
     #[arg(long, env = "FLOWSTATE_LIGHT_TIMEOUT", default_value = "1800")]
@@ -64,11 +130,16 @@ pub struct RunnerConfig {
     #[arg(long, env = "FLOWSTATE_MAX_BUILDS", default_value = "1")]
     pub max_builds: usize,
 
+    /// How many of a plan's validation commands a verify run executes at once
+    #[arg(long, env = "FLOWSTATE_VERIFY_PARALLELISM", default_value = "4")]
+    pub verify_parallelism: usize,
+
     /// Seconds to wait for in-progress runs during graceful shutdown before force-killing.
     #[arg(long, env = "FLOWSTATE_SHUTDOWN_TIMEOUT", default_value = "120")]
     pub shutdown_timeout: u64,
 
-    /// Which agentic backend to use: "claude-cli" (default), "gemini-cli", or "opencode"
+    /// Which agentic backend to use: "claude-cli" (default), "gemini-cli", "opencode",
+    /// or "ollama"
     #[arg(long, env = "FLOWSTATE_AGENT_BACKEND", default_value = "claude-cli")]
     pub agent_backend: String,
 
@@ -77,6 +148,17 @@ pub struct RunnerConfig {
     #[arg(long, env = "FLOWSTATE_RUNNER_CAPABILITY", default_value = "heavy")]
     pub runner_capability: String,
 
+    /// Id the runner registers under. Defaults to the host name, or a
+    /// random UUID without one.
+    #[arg(long, env = "FLOWSTATE_RUNNER_ID")]
+    pub runner_id: Option<String>,
+
+    /// Benchmark the runner at startup (a sample build and a short backend
+    /// prompt) and report the results, so the server can steer
+    /// time-sensitive runs to faster runners
+    #[arg(long, env = "FLOWSTATE_BENCHMARK")]
+    pub benchmark: bool,
+
     /// For claude-cli backend: override ANTHROPIC_BASE_URL
     /// (enables using vLLM, Ollama, OpenRouter with Anthropic-compatible API)
     #[arg(long, env = "FLOWSTATE_ANTHROPIC_BASE_URL")]
@@ -111,6 +193,17 @@ pub struct RunnerConfig {
     #[arg(long, env = "FLOWSTATE_MCP_SERVER_PATH")]
     pub mcp_server_path: Option<PathBuf>,
 
+    /// Link to a task in PR bodies; `:id` is replaced with the task id. Use
+    /// an https redirect for hosts that only make web links clickable.
+    #[arg(long, env = "FLOWSTATE_TASK_LINK", default_value = flowstate_core::deep_link::DEFAULT_TASK_LINK)]
+    pub task_link: String,
+
+    /// Open Build PRs as drafts on providers that support them. The server
+    /// marks a draft ready for review once the task's verify phase is
+    /// approved.
+    #[arg(long, env = "FLOWSTATE_DRAFT_PRS")]
+    pub draft_prs: bool,
+
     /// For gemini-cli backend: Gemini API key
     #[arg(long, env = "FLOWSTATE_GEMINI_API_KEY")]
     pub gemini_api_key: Option<String>,
@@ -126,6 +219,50 @@ pub struct RunnerConfig {
     /// For gemini-cli backend: Google Cloud location (for Vertex AI)
     #[arg(long, env = "FLOWSTATE_GEMINI_GCP_LOCATION")]
     pub gemini_gcp_location: Option<String>,
+
+    /// For ollama backend: server URL (Ollama, or vLLM's OpenAI-compatible server)
+    #[arg(
+        long,
+        env = "FLOWSTATE_OLLAMA_URL",
+        default_value = "http://127.0.0.1:11434"
+    )]
+    pub ollama_url: String,
+
+    /// For ollama backend: model name (e.g., "qwen2.5-coder:32b"); required
+    #[arg(long, env = "FLOWSTATE_OLLAMA_MODEL")]
+    pub ollama_model: Option<String>,
+
+    /// For ollama backend: API key, for a vLLM server started with --api-key
+    #[arg(long, env = "FLOWSTATE_OLLAMA_API_KEY")]
+    pub ollama_api_key: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<RunnerCommand>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum RunnerCommand {
+    /// Write a systemd unit that starts this runner on boot
+    InstallService(InstallServiceArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct InstallServiceArgs {
+    /// Install a user unit (~/.config/systemd/user) instead of a system unit
+    #[arg(long)]
+    pub user: bool,
+
+    /// Where to write the unit; "-" prints it to stdout
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+
+    /// Environment file the unit loads (default: the runner credentials file)
+    #[arg(long)]
+    pub env_file: Option<PathBuf>,
+
+    /// Arguments passed to the runner by the unit, after `--`
+    #[arg(last = true)]
+    pub args: Vec<String>,
 }
 
 /// Dynamic runtime configuration that can be updated by the server.
@@ -164,21 +301,70 @@ impl RunnerConfig {
                 self.max_concurrent
             );
         }
+        if self.verify_parallelism < 1 {
+            bail!(
+                "--verify-parallelism must be >= 1, got {}",
+                self.verify_parallelism
+            );
+        }
+        TaskLinks::new(&self.task_link).context("--task-link")?;
+        // Builds need an agent that edits files; a local model only answers
+        if self.agent_backend == "ollama" && self.capability().ok() == Some(RunnerCapability::Heavy)
+        {
+            bail!("the ollama backend cannot build; set --runner-capability to light or standard");
+        }
         Ok(())
     }
 
+    /// Links to tasks, from `--task-link`; the default if it is invalid,
+    /// which `validate` reports.
+    pub fn task_links(&self) -> TaskLinks {
+        TaskLinks::new(&self.task_link).unwrap_or_default()
+    }
+
+    /// Attach the configured client certificate and server CA to `svc`.
+    pub fn apply_tls(&self, svc: HttpService) -> Result<HttpService> {
+        if self.client_cert.is_none() && self.server_ca.is_none() {
+            return Ok(svc);
+        }
+        if self.server_url.starts_with("unix:") {
+            bail!("--client-cert/--server-ca cannot be used with a unix: server URL");
+        }
+        let identity = match (&self.client_cert, &self.client_key) {
+            (Some(cert), Some(key)) => {
+                let mut pem = std::fs::read(cert)
+                    .with_context(|| format!("failed to read {}", cert.display()))?;
+                pem.extend(
+                    std::fs::read(key)
+                        .with_context(|| format!("failed to read {}", key.display()))?,
+                );
+                Some(pem)
+            }
+            _ => None,
+        };
+        let ca = match &self.server_ca {
+            Some(path) => Some(
+                std::fs::read(path)
+                    .with_context(|| format!("failed to read {}", path.display()))?,
+            ),
+            None => None,
+        };
+        Ok(svc.with_tls(identity.as_deref(), ca.as_deref())?)
+    }
+
     /// Return the appropriate timeout duration for a given action type.
     pub fn timeout_for_action(&self, action: ClaudeAction) -> Duration {
         let secs = match action {
-            ClaudeAction::Build => self.build_timeout,
+            ClaudeAction::Build | ClaudeAction::Revise => self.build_timeout,
             _ => self.light_timeout,
         };
         Duration::from_secs(secs)
     }
 
     /// Returns true if the given action is a Build action (requires the build lock).
+    /// A revise run builds on an existing PR branch, so it counts as one.
     pub fn is_build_action(action: ClaudeAction) -> bool {
-        matches!(action, ClaudeAction::Build)
+        matches!(action, ClaudeAction::Build | ClaudeAction::Revise)
     }
 
     /// Build the appropriate AgentBackend from configuration.
@@ -201,8 +387,18 @@ impl RunnerConfig {
                 api_key: self.opencode_api_key.clone(),
                 base_url: self.opencode_base_url.clone(),
             })),
+            "ollama" => Ok(Box::new(OllamaBackend {
+                base_url: self.ollama_url.clone(),
+                model: self
+                    .ollama_model
+                    .clone()
+                    .context("--ollama-model is required for the ollama backend")?,
+                api_key: self.ollama_api_key.clone(),
+            })),
             other => {
-                bail!("unknown agent backend: {other}. Supported: claude-cli, gemini-cli, opencode")
+                bail!(
+                    "unknown agent backend: {other}. Supported: claude-cli, gemini-cli, opencode, ollama"
+                )
             }
         }
     }
@@ -230,23 +426,41 @@ impl RunnerConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::net::Ipv4Addr;
 
     fn test_config() -> RunnerConfig {
         RunnerConfig {
             server_url: "http://localhost:3710".into(),
             api_key: None,
+            client_cert: None,
+            client_key: None,
+            server_ca: None,
+            daemon: false,
+            pid_file: None,
+            log_file: None,
             poll_interval: 5,
+            maintenance_wait: 3600,
+            claim_wait: 0,
             workspace_root: None,
+            janitor_interval: 600,
+            janitor_min_age: 3600,
+            janitor_dry_run: false,
+            state_file: None,
             health_port: 3711,
+            health_bind: IpAddr::V4(Ipv4Addr::LOCALHOST),
+            health_token: None,
             light_timeout: 1800,
             build_timeout: 3600,
             kill_grace_period: 10,
             activity_timeout: 900,
             max_concurrent: 5,
             max_builds: 1,
+            verify_parallelism: 4,
             shutdown_timeout: 120,
             agent_backend: "claude-cli".into(),
             runner_capability: "heavy".into(),
+            runner_id: None,
+            benchmark: false,
             anthropic_base_url: None,
             anthropic_auth_token: None,
             anthropic_model: None,
@@ -255,10 +469,16 @@ mod tests {
             opencode_model: None,
             opencode_api_key: None,
             opencode_base_url: None,
+            task_link: flowstate_core::deep_link::DEFAULT_TASK_LINK.into(),
+            draft_prs: false,
             gemini_api_key: None,
             gemini_model: None,
             gemini_gcp_project: None,
             gemini_gcp_location: None,
+            ollama_url: "http://127.0.0.1:11434".into(),
+            ollama_model: None,
+            ollama_api_key: None,
+            command: None,
         }
     }
 
@@ -267,6 +487,20 @@ mod tests {
         assert!(test_config().validate().is_ok());
     }
 
+    #[test]
+    fn test_validate_task_link_needs_placeholder() {
+        let mut cfg = test_config();
+        cfg.task_link = "https://tasks.example.com/t/".into();
+        let err = cfg.validate().unwrap_err();
+        assert!(err.to_string().contains("task-link"), "{err}");
+        cfg.task_link = "https://tasks.example.com/t/:id".into();
+        assert!(cfg.validate().is_ok());
+        assert_eq!(
+            cfg.task_links().task_url("t1"),
+            "https://tasks.example.com/t/t1"
+        );
+    }
+
     #[test]
     fn test_validate_max_concurrent_zero() {
         let mut cfg = test_config();
@@ -340,6 +574,7 @@ mod tests {
         assert!(!RunnerConfig::is_build_action(ClaudeAction::DesignDistill));
         assert!(!RunnerConfig::is_build_action(ClaudeAction::PlanDistill));
         assert!(!RunnerConfig::is_build_action(ClaudeAction::VerifyDistill));
+        assert!(RunnerConfig::is_build_action(ClaudeAction::Revise));
     }
 
     #[test]
@@ -408,6 +643,29 @@ mod tests {
         assert_eq!(backend.model_hint(), Some("gemini-2.5-pro"));
     }
 
+    #[test]
+    fn test_build_backend_ollama() {
+        let mut cfg = test_config();
+        cfg.agent_backend = "ollama".into();
+        let err = cfg.build_backend().err().unwrap();
+        assert!(err.to_string().contains("--ollama-model"), "{err}");
+
+        cfg.ollama_model = Some("qwen2.5-coder:32b".into());
+        let backend = cfg.build_backend().unwrap();
+        assert_eq!(backend.name(), "ollama");
+        assert_eq!(backend.model_hint(), Some("qwen2.5-coder:32b"));
+    }
+
+    #[test]
+    fn test_validate_ollama_cannot_build() {
+        let mut cfg = test_config();
+        cfg.agent_backend = "ollama".into();
+        let err = cfg.validate().unwrap_err();
+        assert!(err.to_string().contains("runner-capability"), "{err}");
+        cfg.runner_capability = "standard".into();
+        assert!(cfg.validate().is_ok());
+    }
+
     #[test]
     fn test_build_backend_unknown() {
         let mut cfg = test_config();
@@ -0,0 +1,208 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use tracing::debug;
+
+use crate::config::{InstallServiceArgs, RunnerConfig};
+
+/// Extra time systemd allows past `--shutdown-timeout` before it SIGKILLs
+/// the runner, so the runner's own drain timeout always fires first.
+const STOP_TIMEOUT_SLACK_SECS: u64 = 30;
+
+/// Detach from the terminal and continue in the background, writing the
+/// new PID to `pid_file` if given. Must run before the tokio runtime starts.
+#[cfg(target_os = "linux")]
+pub fn daemonize(pid_file: Option<&Path>) -> Result<()> {
+    nix::unistd::daemon(true, false).context("failed to daemonize")?;
+    if let Some(path) = pid_file {
+        std::fs::write(path, format!("{}\n", std::process::id()))
+            .with_context(|| format!("failed to write pid file {}", path.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn daemonize(_pid_file: Option<&Path>) -> Result<()> {
+    bail!("--daemon is only supported on Linux")
+}
+
+/// Send a state update (e.g. `READY=1`) to systemd. A no-op unless the
+/// runner was started by a `Type=notify` unit (`NOTIFY_SOCKET` is set).
+pub fn notify(state: &str) {
+    let Some(socket) = std::env::var_os("NOTIFY_SOCKET") else {
+        return;
+    };
+    if let Err(e) = send_notify(Path::new(&socket), state) {
+        debug!("sd_notify {state:?} failed: {e}");
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn send_notify(socket: &Path, state: &str) -> std::io::Result<()> {
+    use std::os::linux::net::SocketAddrExt;
+    use std::os::unix::ffi::OsStrExt;
+    use std::os::unix::net::{SocketAddr, UnixDatagram};
+
+    let sock = UnixDatagram::unbound()?;
+    let bytes = socket.as_os_str().as_bytes();
+    // A leading '@' names a socket in the abstract namespace.
+    let addr = match bytes.strip_prefix(b"@") {
+        Some(name) => SocketAddr::from_abstract_name(name)?,
+        None => SocketAddr::from_pathname(socket)?,
+    };
+    sock.send_to_addr(state.as_bytes(), &addr)?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn send_notify(socket: &Path, state: &str) -> std::io::Result<()> {
+    std::os::unix::net::UnixDatagram::unbound()?.send_to(state.as_bytes(), socket)?;
+    Ok(())
+}
+
+/// Resolve when the runner is asked to stop: ctrl-c or SIGTERM (what
+/// systemd and `kill` send).
+pub async fn shutdown_signal() {
+    let mut sigterm = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+        .expect("failed to install SIGTERM handler");
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = sigterm.recv() => {}
+    }
+}
+
+/// Write (or print, for `--output -`) a systemd unit that runs this binary
+/// with `args.args`.
+pub fn install_service(config: &RunnerConfig, args: &InstallServiceArgs) -> Result<()> {
+    let exe = std::env::current_exe().context("failed to locate the runner binary")?;
+    let env_file = args.env_file.clone().unwrap_or_else(default_env_file);
+    let unit = render_unit(&exe, &args.args, &env_file, config, args.user);
+
+    let output = match &args.output {
+        Some(path) if path.as_os_str() == "-" => {
+            std::io::stdout().write_all(unit.as_bytes())?;
+            return Ok(());
+        }
+        Some(path) => path.clone(),
+        None => default_unit_path(args.user)?,
+    };
+    if let Some(parent) = output.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&output, unit)
+        .with_context(|| format!("failed to write {}", output.display()))?;
+
+    let scope = if args.user { " --user" } else { "" };
+    eprintln!("Wrote {}", output.display());
+    eprintln!("Enable it with:");
+    eprintln!("  systemctl{scope} daemon-reload");
+    eprintln!("  systemctl{scope} enable --now flowstate-runner");
+    Ok(())
+}
+
+/// Render the unit file. `Type=notify` pairs with the `READY=1` sent once
+/// the poll loop starts; `KillMode=mixed` delivers SIGTERM to the runner
+/// alone so in-flight agent processes are drained rather than killed.
+pub fn render_unit(
+    exe: &Path,
+    args: &[String],
+    env_file: &Path,
+    config: &RunnerConfig,
+    user: bool,
+) -> String {
+    let mut exec_start = exe.display().to_string();
+    for arg in args {
+        exec_start.push(' ');
+        exec_start.push_str(&quote_arg(arg));
+    }
+    format!(
+        "[Unit]
+Description=Flowstate runner
+After=network-online.target
+Wants=network-online.target
+
+[Service]
+Type=notify
+NotifyAccess=main
+ExecStart={exec_start}
+EnvironmentFile=-{env_file}
+Restart=on-failure
+RestartSec=5
+KillMode=mixed
+TimeoutStopSec={stop_timeout}
+
+[Install]
+WantedBy={wanted_by}
+",
+        env_file = env_file.display(),
+        stop_timeout = config.shutdown_timeout + STOP_TIMEOUT_SLACK_SECS,
+        wanted_by = if user {
+            "default.target"
+        } else {
+            "multi-user.target"
+        },
+    )
+}
+
+/// Quote an `ExecStart` argument if it contains whitespace or quotes.
+fn quote_arg(arg: &str) -> String {
+    if arg.is_empty() || arg.contains(|c: char| c.is_whitespace() || c == '"' || c == '\\') {
+        format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        arg.to_string()
+    }
+}
+
+fn default_env_file() -> PathBuf {
+    let home = std::env::var_os("HOME")
+        .map(PathBuf::from)
+        .unwrap_or_default();
+    home.join(".local/share/flowstate/runner/credentials/runner.env")
+}
+
+fn default_unit_path(user: bool) -> Result<PathBuf> {
+    if !user {
+        return Ok(PathBuf::from(
+            "/etc/systemd/system/flowstate-runner.service",
+        ));
+    }
+    let Some(home) = std::env::var_os("HOME") else {
+        bail!("HOME is not set; pass --output");
+    };
+    Ok(PathBuf::from(home).join(".config/systemd/user/flowstate-runner.service"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn unit_runs_binary_with_args() {
+        let config = RunnerConfig::parse_from(["flowstate-runner", "--shutdown-timeout", "300"]);
+        let unit = render_unit(
+            Path::new("/usr/bin/flowstate-runner"),
+            &["--max-builds".into(), "2".into(), "two words".into()],
+            Path::new("/etc/flowstate/runner.env"),
+            &config,
+            false,
+        );
+        assert!(unit.contains("ExecStart=/usr/bin/flowstate-runner --max-builds 2 \"two words\"\n"));
+        assert!(unit.contains("EnvironmentFile=-/etc/flowstate/runner.env\n"));
+        assert!(unit.contains("Type=notify\n"));
+        assert!(unit.contains("TimeoutStopSec=330\n"));
+        assert!(unit.contains("WantedBy=multi-user.target\n"));
+    }
+
+    #[test]
+    fn notify_sends_datagram() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("notify.sock");
+        let listener = std::os::unix::net::UnixDatagram::bind(&path).unwrap();
+        send_notify(&path, "READY=1").unwrap();
+        let mut buf = [0u8; 16];
+        let n = listener.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"READY=1");
+    }
+}
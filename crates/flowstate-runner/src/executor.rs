@@ -11,9 +11,17 @@ use tracing::{info, warn};
 
 use crate::backend::{AgentBackend, McpEnv};
 use crate::config::RunnerConfig;
+use crate::followups;
 use crate::pipeline;
+use crate::plan_parser;
+use crate::repo_docs;
+use crate::repo_provider;
+use crate::revise;
 use crate::workspace;
 
+/// How many earlier feedback rounds a distill prompt includes.
+const FEEDBACK_HISTORY_ROUNDS: usize = 5;
+
 /// Dispatch a claimed run to the appropriate handler.
 /// Each run gets a fresh workspace directory keyed by run ID,
 /// which is cleaned up after the run completes (success or failure).
@@ -53,13 +61,38 @@ pub async fn dispatch(
         }
         ClaudeAction::Build => {
             pipeline::execute(
+                service,
+                run,
+                task,
+                project,
+                &ws_dir,
+                timeout,
+                kill_grace,
+                backend,
+                mcp_env,
+                &config.task_links(),
+                config.draft_prs,
+            )
+            .await
+        }
+        ClaudeAction::Revise => {
+            revise::execute(
                 service, run, task, project, &ws_dir, timeout, kill_grace, backend, mcp_env,
             )
             .await
         }
         ClaudeAction::Verify | ClaudeAction::VerifyDistill => {
             execute_verify(
-                service, run, task, project, &ws_dir, timeout, kill_grace, backend, mcp_env,
+                service,
+                run,
+                task,
+                project,
+                &ws_dir,
+                timeout,
+                kill_grace,
+                backend,
+                mcp_env,
+                config.verify_parallelism,
             )
             .await
         }
@@ -73,9 +106,13 @@ pub async fn dispatch(
 
 /// Resolve a per-run workspace directory.
 pub fn resolve_workspace_dir(workspace_root: &Option<PathBuf>, run_id: &str) -> PathBuf {
-    resolve_workspace_dir_from(
+    resolve_workspace_root(workspace_root).join(run_id)
+}
+
+/// Resolve the directory holding every per-run workspace.
+pub fn resolve_workspace_root(workspace_root: &Option<PathBuf>) -> PathBuf {
+    resolve_workspace_root_from(
         workspace_root,
-        run_id,
         std::env::var("XDG_DATA_HOME").ok(),
         std::env::var_os("HOME").map(PathBuf::from),
     )
@@ -86,19 +123,24 @@ pub fn resolve_workspace_dir_from(
     run_id: &str,
     xdg_data_home: Option<String>,
     home: Option<PathBuf>,
+) -> PathBuf {
+    resolve_workspace_root_from(workspace_root, xdg_data_home, home).join(run_id)
+}
+
+fn resolve_workspace_root_from(
+    workspace_root: &Option<PathBuf>,
+    xdg_data_home: Option<String>,
+    home: Option<PathBuf>,
 ) -> PathBuf {
     match workspace_root {
-        Some(root) => root.join(run_id),
+        Some(root) => root.clone(),
         None => {
             if let Some(xdg) = xdg_data_home {
-                PathBuf::from(xdg)
-                    .join("flowstate")
-                    .join("workspaces")
-                    .join(run_id)
+                PathBuf::from(xdg).join("flowstate").join("workspaces")
             } else if let Some(home) = home {
-                home.join(".local/share/flowstate/workspaces").join(run_id)
+                home.join(".local/share/flowstate/workspaces")
             } else {
-                PathBuf::from(".").join("flowstate/workspaces").join(run_id)
+                PathBuf::from(".").join("flowstate/workspaces")
             }
         }
     }
@@ -133,11 +175,13 @@ async fn execute_research(
         &project.repo_url,
         token.as_deref(),
         project.skip_tls_verify,
+        project.ssh_key_path.as_deref(),
+        project.subpath(),
     )
     .await?;
 
     progress(service, &run.id, "Assembling prompt...").await;
-    let ctx = build_prompt_context(service, task, project, run.action).await;
+    let ctx = build_prompt_context(service, task, project, run).await;
     let prompt = flowstate_prompts::assemble_prompt(&ctx, run.action);
 
     save_prompt(&run.id, &prompt)?;
@@ -197,11 +241,13 @@ async fn execute_design(
         &project.repo_url,
         token.as_deref(),
         project.skip_tls_verify,
+        project.ssh_key_path.as_deref(),
+        project.subpath(),
     )
     .await?;
 
     progress(service, &run.id, "Assembling prompt...").await;
-    let ctx = build_prompt_context(service, task, project, run.action).await;
+    let ctx = build_prompt_context(service, task, project, run).await;
     let prompt = flowstate_prompts::assemble_prompt(&ctx, run.action);
 
     save_prompt(&run.id, &prompt)?;
@@ -260,11 +306,13 @@ async fn execute_plan(
         &project.repo_url,
         token.as_deref(),
         project.skip_tls_verify,
+        project.ssh_key_path.as_deref(),
+        project.subpath(),
     )
     .await?;
 
     progress(service, &run.id, "Assembling prompt...").await;
-    let ctx = build_prompt_context(service, task, project, run.action).await;
+    let ctx = build_prompt_context(service, task, project, run).await;
     let prompt = flowstate_prompts::assemble_prompt(&ctx, run.action);
 
     save_prompt(&run.id, &prompt)?;
@@ -306,6 +354,7 @@ async fn execute_plan(
                     description: def.description.clone(),
                     status: flowstate_core::task::Status::Todo,
                     priority: task.priority,
+                    task_type: task.task_type,
                     parent_id: Some(task.id.clone()),
                     reviewer: task.reviewer.clone(),
                     research_capability: None,
@@ -313,6 +362,7 @@ async fn execute_plan(
                     plan_capability: None,
                     build_capability: def.build_capability,
                     verify_capability: None,
+                    due_at: None,
                 };
                 match service.create_task(&create).await {
                     Ok(child) => info!("created subtask '{}' ({})", child.title, child.id),
@@ -341,6 +391,7 @@ async fn execute_verify(
     kill_grace: Duration,
     backend: &dyn AgentBackend,
     mcp_env: Option<&McpEnv>,
+    verify_parallelism: usize,
 ) -> Result<()> {
     // Clone repo so the agent can explore the codebase
     progress(service, &run.id, "Cloning repository...").await;
@@ -350,6 +401,8 @@ async fn execute_verify(
         &project.repo_url,
         token.as_deref(),
         project.skip_tls_verify,
+        project.ssh_key_path.as_deref(),
+        project.subpath(),
     )
     .await?;
 
@@ -362,6 +415,7 @@ async fn execute_verify(
                 && r.status == flowstate_core::claude_run::ClaudeRunStatus::Completed
         })
         .and_then(|r| r.branch_name.clone());
+    let mut checked_out = None;
     if let Some(ref branch) = build_branch {
         progress(service, &run.id, "Checking out feature branch...").await;
         let status = tokio::process::Command::new("git")
@@ -369,14 +423,34 @@ async fn execute_verify(
             .current_dir(ws_dir)
             .status()
             .await?;
-        if !status.success() {
+        if status.success() {
+            checked_out = Some(branch.as_str());
+        } else {
             warn!("failed to checkout branch {branch}, continuing on default branch");
         }
     }
 
+    let checks = run_plan_checks(service, run, task, ws_dir, verify_parallelism).await;
+    if let (true, Some(checks)) = (project.verify_followups, &checks) {
+        if !checks.steps.iter().all(|s| s.passed()) {
+            progress(service, &run.id, "Creating follow-up tasks...").await;
+            if let Err(e) = followups::create_followups(service, task, checks).await {
+                warn!("failed to create follow-up tasks for {}: {e}", task.id);
+            }
+        }
+    }
+
     progress(service, &run.id, "Assembling prompt...").await;
-    let ctx = build_prompt_context(service, task, project, run.action).await;
-    let prompt = flowstate_prompts::assemble_prompt(&ctx, run.action);
+    let ctx = build_prompt_context(service, task, project, run).await;
+    let mut prompt = flowstate_prompts::assemble_prompt(&ctx, run.action);
+    if let Some(checks) = &checks {
+        prompt.push_str(&format!(
+            "\n\n## Automated Check Results\n\nThe runner has already run the plan's \
+             validation commands against this branch. Use these results rather than \
+             re-running the same commands.\n\n{}",
+            checks.summary()
+        ));
+    }
 
     save_prompt(&run.id, &prompt)?;
 
@@ -388,8 +462,12 @@ async fn execute_verify(
     if output.success {
         progress(service, &run.id, "Reading output...").await;
         let verification_file = ws_dir.join("VERIFICATION.md");
-        let content =
+        let mut content =
             std::fs::read_to_string(&verification_file).unwrap_or_else(|_| output.stdout.clone());
+        if let Some(checks) = &checks {
+            content.push_str("\n\n## Automated Checks\n\n");
+            content.push_str(&checks.summary());
+        }
 
         progress(service, &run.id, "Writing verification to server...").await;
         service
@@ -397,6 +475,15 @@ async fn execute_verify(
             .await
             .map_err(|e| anyhow::anyhow!("{e}"))?;
 
+        if let (true, Some(branch)) = (project.docs_in_repo, checked_out) {
+            progress(service, &run.id, "Committing task documents...").await;
+            if let Err(e) =
+                push_task_docs(service, task, project, token.clone(), ws_dir, branch).await
+            {
+                warn!("failed to commit task documents to {branch}: {e}");
+            }
+        }
+
         let update = flowstate_core::task::UpdateTask {
             verify_status: Some(flowstate_core::task::ApprovalStatus::Pending),
             ..Default::default()
@@ -415,12 +502,80 @@ async fn execute_verify(
     Ok(())
 }
 
-async fn build_prompt_context(
+/// Refresh the task's documents on its build branch so the verification
+/// report sits next to the spec and plan, then push. Only the docs directory
+/// is committed; anything else the verify agent left behind stays out.
+async fn push_task_docs(
+    service: &HttpService,
+    task: &Task,
+    project: &Project,
+    token: Option<String>,
+    ws_dir: &Path,
+    branch: &str,
+) -> Result<()> {
+    let dir = repo_docs::mirror_task_docs(service, task, ws_dir).await?;
+    let message = format!("docs: verification for {} [flowstate]", task.title);
+    if !workspace::commit_paths(ws_dir, &[&dir], &message).await? {
+        return Ok(());
+    }
+    let provider = repo_provider::provider_for_url(
+        &project.repo_url,
+        token,
+        project.provider_type,
+        project.skip_tls_verify,
+    )
+    .map_err(|e| anyhow::anyhow!("unsupported repo provider: {e}"))?;
+    provider
+        .push_branch(ws_dir, branch)
+        .await
+        .map_err(|e| anyhow::anyhow!("push failed: {e}"))
+}
+
+/// Run the validation commands from the task's plan as parallel sub-processes
+/// (checks are independent: build, tests, lint, docs). Returns `None` when
+/// the plan lists none. The aggregated result is saved next to the prompt.
+async fn run_plan_checks(
+    service: &HttpService,
+    run: &ClaudeRun,
+    task: &Task,
+    ws_dir: &Path,
+    parallelism: usize,
+) -> Option<flowstate_verify::runner::RunResult> {
+    let plan = service.read_task_plan(&task.id).await.ok()?;
+    let steps = plan_parser::extract_validation_commands(&plan);
+    if steps.is_empty() {
+        return None;
+    }
+
+    let message = format!(
+        "Running {} checks ({parallelism} at a time)...",
+        steps.len()
+    );
+    progress(service, &run.id, &message).await;
+    let result = flowstate_verify::Runner::new()
+        .execute_parallel(&steps, ws_dir, parallelism)
+        .await;
+    let passed = result.steps.iter().filter(|s| s.passed()).count();
+    info!("checks finished: {passed}/{} passed", result.steps.len());
+
+    match serde_json::to_string_pretty(&result) {
+        Ok(json) => {
+            if let Err(e) = save_run_file(&run.id, "checks.json", &json) {
+                warn!("failed to save check results: {e}");
+            }
+        }
+        Err(e) => warn!("failed to serialize check results: {e}"),
+    }
+    Some(result)
+}
+
+pub(crate) async fn build_prompt_context(
     service: &HttpService,
     task: &Task,
     project: &Project,
-    action: ClaudeAction,
+    run: &ClaudeRun,
 ) -> PromptContext {
+    let action = run.action;
     // Fetch research content for downstream phases
     let research_content = if matches!(
         action,
@@ -442,6 +597,7 @@ async fn build_prompt_context(
         action,
         ClaudeAction::Plan
             | ClaudeAction::Build
+            | ClaudeAction::Revise
             | ClaudeAction::Verify
             | ClaudeAction::PlanDistill
             | ClaudeAction::VerifyDistill
@@ -453,7 +609,10 @@ async fn build_prompt_context(
 
     let plan_content = if matches!(
         action,
-        ClaudeAction::Build | ClaudeAction::Verify | ClaudeAction::VerifyDistill
+        ClaudeAction::Build
+            | ClaudeAction::Revise
+            | ClaudeAction::Verify
+            | ClaudeAction::VerifyDistill
     ) {
         service.read_task_plan(&task.id).await.ok()
     } else {
@@ -466,12 +625,40 @@ async fn build_prompt_context(
         None
     };
 
+    // Feedback queued with the run wins over the task's, which a later
+    // review may already have overwritten.
     let distill_feedback = match action {
         ClaudeAction::ResearchDistill => Some(task.research_feedback.clone()),
         ClaudeAction::DesignDistill => Some(task.spec_feedback.clone()),
         ClaudeAction::PlanDistill => Some(task.plan_feedback.clone()),
         ClaudeAction::VerifyDistill => Some(task.verify_feedback.clone()),
         _ => None,
+    }
+    .map(|feedback| run.feedback.clone().unwrap_or(feedback));
+
+    // Earlier rounds of feedback on the document being distilled, so a
+    // revision doesn't undo what a previous review asked for.
+    let distill_phase = match action {
+        ClaudeAction::ResearchDistill => Some("research"),
+        ClaudeAction::DesignDistill => Some("design"),
+        ClaudeAction::PlanDistill => Some("plan"),
+        ClaudeAction::VerifyDistill => Some("verify"),
+        _ => None,
+    };
+    let feedback_history = match distill_phase {
+        Some(phase) => {
+            let mut history: Vec<_> = service
+                .list_feedback_history(&task.id)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|e| e.phase == phase && Some(&e.feedback) != distill_feedback.as_ref())
+                .take(FEEDBACK_HISTORY_ROUNDS)
+                .collect();
+            history.reverse();
+            history
+        }
+        None => Vec::new(),
     };
 
     // Collect reviewer notes from approved prior phases for forward propagation.
@@ -522,13 +709,16 @@ async fn build_prompt_context(
         task_id: task.id.clone(),
         project_name: project.name.clone(),
         repo_url: project.repo_url.clone(),
+        repo_subpath: project.subpath().map(str::to_string),
         task_title: task.title.clone(),
         task_description: task.description.clone(),
+        task_type: task.task_type,
         research_content,
         spec_content,
         plan_content,
         verification_content,
         distill_feedback,
+        feedback_history,
         reviewer_notes,
         child_tasks,
         parent_context: None,
@@ -536,7 +726,12 @@ async fn build_prompt_context(
     }
 }
 
-fn save_prompt(run_id: &str, prompt: &str) -> Result<()> {
+pub(crate) fn save_prompt(run_id: &str, prompt: &str) -> Result<()> {
+    save_run_file(run_id, "prompt.md", prompt)
+}
+
+/// Write `name` into the run's local data directory.
+fn save_run_file(run_id: &str, name: &str, contents: &str) -> Result<()> {
     let data_dir = if let Ok(xdg) = std::env::var("XDG_DATA_HOME") {
         PathBuf::from(xdg).join("flowstate")
     } else if let Some(home) = std::env::var_os("HOME") {
@@ -546,7 +741,7 @@ fn save_prompt(run_id: &str, prompt: &str) -> Result<()> {
     };
     let run_dir = data_dir.join("claude_runs").join(run_id);
     std::fs::create_dir_all(&run_dir)?;
-    std::fs::write(run_dir.join("prompt.md"), prompt)?;
+    std::fs::write(run_dir.join(name), contents)?;
     Ok(())
 }
 
@@ -0,0 +1,118 @@
+use anyhow::Result;
+use flowstate_core::task::{CreateTask, Status, Task, TaskType};
+use flowstate_core::task_link::{CreateTaskLink, LinkType};
+use flowstate_service::{HttpService, TaskService};
+use flowstate_verify::runner::{FailureCluster, RunResult};
+use tracing::info;
+
+/// Longest error signature kept in a follow-up's title.
+const TITLE_SIGNATURE_LIMIT: usize = 80;
+
+/// Open one bug task per cluster of failed checks, each linked as blocking
+/// `task`. A cluster whose follow-up is already open is skipped, so
+/// re-verifying the same failure does not pile up duplicates. Returns how
+/// many tasks were created.
+pub async fn create_followups(
+    service: &HttpService,
+    task: &Task,
+    checks: &RunResult,
+) -> Result<usize> {
+    let mut open_titles = Vec::new();
+    for link in service.list_task_links(&task.id).await? {
+        if link.link_type != LinkType::Blocks || link.target_task_id != task.id {
+            continue;
+        }
+        let blocker = service.get_task(&link.source_task_id).await?;
+        if !matches!(blocker.status, Status::Done | Status::Cancelled) {
+            open_titles.push(blocker.title);
+        }
+    }
+
+    let mut created = 0;
+    for cluster in checks.failure_clusters() {
+        let title = followup_title(&cluster);
+        if open_titles.contains(&title) {
+            continue;
+        }
+        let followup = service
+            .create_task(&CreateTask {
+                project_id: task.project_id.clone(),
+                title,
+                description: followup_description(&task.title, &task.id, &cluster),
+                status: Status::Todo,
+                priority: task.priority,
+                task_type: TaskType::Bug,
+                parent_id: None,
+                reviewer: task.reviewer.clone(),
+                research_capability: None,
+                design_capability: None,
+                plan_capability: None,
+                build_capability: None,
+                verify_capability: None,
+                due_at: None,
+            })
+            .await?;
+        service
+            .create_task_link(&CreateTaskLink {
+                source_task_id: followup.id.clone(),
+                target_task_id: task.id.clone(),
+                link_type: LinkType::Blocks,
+            })
+            .await?;
+        info!("created follow-up '{}' ({})", followup.title, followup.id);
+        created += 1;
+    }
+    Ok(created)
+}
+
+fn followup_title(cluster: &FailureCluster<'_>) -> String {
+    let signature = match cluster.signature.char_indices().nth(TITLE_SIGNATURE_LIMIT) {
+        Some((end, _)) => format!("{}...", &cluster.signature[..end]),
+        None => cluster.signature.clone(),
+    };
+    format!("Fix failing check: {signature}")
+}
+
+fn followup_description(title: &str, task_id: &str, cluster: &FailureCluster<'_>) -> String {
+    let steps: Vec<_> = cluster.steps.iter().map(|s| s.step_name.as_str()).collect();
+    format!(
+        "Verification of \"{}\" ({}) failed: {}.\n\n\
+         The original task cannot be moved to Done until this task is done \
+         or cancelled.\n\n## Evidence\n\n{}\n",
+        title,
+        task_id,
+        steps.join(", "),
+        cluster.evidence()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use flowstate_verify::runner::{RunStatus, StepResult};
+
+    #[test]
+    fn followup_carries_signature_and_evidence() {
+        let checks = RunResult {
+            status: RunStatus::Failed,
+            steps: vec![StepResult {
+                step_name: "test".into(),
+                command: "cargo test".into(),
+                exit_code: Some(101),
+                stdout: format!("thread 'main' panicked at {}\n", "x".repeat(100)),
+                stderr: String::new(),
+                started_at: Utc::now(),
+                finished_at: Utc::now(),
+            }],
+        };
+        let clusters = checks.failure_clusters();
+        let title = followup_title(&clusters[0]);
+        assert!(title.starts_with("Fix failing check: thread 'main' panicked at xx"));
+        assert!(title.ends_with("..."));
+
+        let description = followup_description("Add login", "task-1", &clusters[0]);
+        assert!(description.starts_with("Verification of \"Add login\" (task-1) failed: test."));
+        assert!(description.contains("`cargo test` (exit 101)"));
+    }
+}
@@ -0,0 +1,263 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::{routing::get, Json, Router};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::json;
+use tokio::net::TcpListener;
+use tokio::sync::Mutex;
+
+use crate::backend::AgentBackend;
+use crate::config::RunnerConfig;
+use crate::executor;
+use crate::run_tracker::{ActiveRunSnapshot, RunTracker};
+
+/// How long a backend preflight result is reused before a scrape re-runs it.
+const PREFLIGHT_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Clone)]
+pub struct HealthState {
+    tracker: Arc<RwLock<RunTracker>>,
+    config: Arc<RunnerConfig>,
+    runner_id: String,
+    backend: Arc<dyn AgentBackend>,
+    capability: String,
+    preflight: Arc<Mutex<Option<PreflightStatus>>>,
+}
+
+impl HealthState {
+    pub fn new(
+        tracker: Arc<RwLock<RunTracker>>,
+        config: Arc<RunnerConfig>,
+        runner_id: String,
+        backend: Arc<dyn AgentBackend>,
+        capability: String,
+    ) -> Self {
+        Self {
+            tracker,
+            config,
+            runner_id,
+            backend,
+            capability,
+            preflight: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct HealthResponse {
+    /// "ok", or "degraded" while the backend preflight check is failing.
+    status: &'static str,
+    role: &'static str,
+    runner_id: String,
+    backend: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
+    capability: String,
+    capacity: CapacityInfo,
+    active_runs: Vec<ActiveRunSnapshot>,
+    last_claim_at: Option<DateTime<Utc>>,
+    /// Free space on the filesystem holding the workspace root.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disk: Option<DiskInfo>,
+    preflight: PreflightStatus,
+}
+
+#[derive(Serialize)]
+struct CapacityInfo {
+    max_concurrent: usize,
+    max_builds: usize,
+    active_total: usize,
+    active_builds: usize,
+    available: usize,
+}
+
+#[derive(Serialize)]
+struct DiskInfo {
+    path: PathBuf,
+    available_bytes: u64,
+    total_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct PreflightStatus {
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    checked_at: DateTime<Utc>,
+}
+
+/// The `/health` router, requiring `Authorization: Bearer <token>` when
+/// `--health-token` is set.
+pub fn router(state: HealthState) -> Router {
+    Router::new()
+        .route("/health", get(health_handler))
+        .layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .with_state(state)
+}
+
+pub async fn serve(listener: TcpListener, state: HealthState) -> anyhow::Result<()> {
+    axum::serve(listener, router(state)).await?;
+    Ok(())
+}
+
+async fn require_token(State(state): State<HealthState>, request: Request, next: Next) -> Response {
+    let Some(expected) = state.config.health_token.as_deref() else {
+        return next.run(request).await;
+    };
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    match token {
+        Some(token) if constant_time_eq(token, expected) => next.run(request).await,
+        _ => (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({ "error": "missing or invalid health token" })),
+        )
+            .into_response(),
+    }
+}
+
+/// Constant-time string comparison to prevent timing attacks.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.as_bytes()
+        .iter()
+        .zip(b.as_bytes())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+        == 0
+}
+
+async fn health_handler(State(state): State<HealthState>) -> Json<HealthResponse> {
+    let preflight = preflight_status(&state).await;
+    let root = executor::resolve_workspace_root(&state.config.workspace_root);
+
+    let tracker = state.tracker.read().unwrap();
+    let active_total = tracker.active_count();
+    let active_builds = tracker.active_build_count();
+    let active_runs = tracker.snapshot();
+    let last_claim_at = tracker.last_claim_at();
+    drop(tracker);
+
+    Json(HealthResponse {
+        status: if preflight.ok { "ok" } else { "degraded" },
+        role: "runner",
+        runner_id: state.runner_id.clone(),
+        backend: state.backend.name().to_string(),
+        model: state.backend.model_hint().map(str::to_string),
+        capability: state.capability.clone(),
+        capacity: CapacityInfo {
+            max_concurrent: state.config.max_concurrent,
+            max_builds: state.config.max_builds,
+            active_total,
+            active_builds,
+            available: state.config.max_concurrent.saturating_sub(active_total),
+        },
+        active_runs,
+        last_claim_at,
+        disk: disk_info(&root),
+        preflight,
+    })
+}
+
+/// The cached backend preflight result, re-checked once it is older than
+/// [`PREFLIGHT_TTL`] so frequent scrapes don't spawn the agent CLI each time.
+async fn preflight_status(state: &HealthState) -> PreflightStatus {
+    let mut cached = state.preflight.lock().await;
+    if let Some(status) = cached.as_ref() {
+        if (Utc::now() - status.checked_at)
+            .to_std()
+            .unwrap_or_default()
+            < PREFLIGHT_TTL
+        {
+            return status.clone();
+        }
+    }
+    let error = state
+        .backend
+        .preflight_check()
+        .await
+        .err()
+        .map(|e| e.to_string());
+    let status = PreflightStatus {
+        ok: error.is_none(),
+        error,
+        checked_at: Utc::now(),
+    };
+    *cached = Some(status.clone());
+    status
+}
+
+/// Space on the filesystem holding `path`, measured at its nearest existing
+/// ancestor since the workspace root is created lazily.
+// statvfs field widths vary by platform, hence the casts.
+#[allow(clippy::unnecessary_cast)]
+fn disk_info(path: &Path) -> Option<DiskInfo> {
+    let existing = path.ancestors().find(|p| p.exists())?;
+    let stat = nix::sys::statvfs::statvfs(existing).ok()?;
+    let fragment = stat.fragment_size() as u64;
+    Some(DiskInfo {
+        path: existing.to_path_buf(),
+        available_bytes: stat.blocks_available() as u64 * fragment,
+        total_bytes: stat.blocks() as u64 * fragment,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockBackend;
+    use clap::Parser;
+
+    async fn spawn(args: &[&str]) -> (String, Arc<RwLock<RunTracker>>) {
+        let config = RunnerConfig::parse_from(std::iter::once(&"flowstate-runner").chain(args));
+        let tracker = Arc::new(RwLock::new(RunTracker::new()));
+        let state = HealthState::new(
+            tracker.clone(),
+            Arc::new(config),
+            "runner-1".into(),
+            Arc::new(MockBackend::success("")),
+            "heavy".into(),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/health", listener.local_addr().unwrap());
+        tokio::spawn(serve(listener, state));
+        (url, tracker)
+    }
+
+    #[tokio::test]
+    async fn reports_diagnostics() {
+        let (url, tracker) = spawn(&[]).await;
+        let body: serde_json::Value = reqwest::get(&url).await.unwrap().json().await.unwrap();
+        assert_eq!(body["status"], "ok");
+        assert_eq!(body["preflight"]["ok"], true);
+        assert!(body["last_claim_at"].is_null());
+        assert!(body["disk"]["total_bytes"].as_u64().unwrap() > 0);
+
+        tracker.write().unwrap().record_claim();
+        let body: serde_json::Value = reqwest::get(&url).await.unwrap().json().await.unwrap();
+        assert!(body["last_claim_at"].is_string());
+    }
+
+    #[tokio::test]
+    async fn token_required_when_configured() {
+        let (url, _) = spawn(&["--health-token", "s3cret"]).await;
+        let client = reqwest::Client::new();
+        let resp = client.get(&url).send().await.unwrap();
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        let resp = client.get(&url).bearer_auth("wrong").send().await.unwrap();
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+        let resp = client.get(&url).bearer_auth("s3cret").send().await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,259 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use flowstate_core::claude_run::ClaudeRunStatus;
+use flowstate_service::{ServiceError, TaskService};
+use tracing::{info, warn};
+
+/// What the janitor does with one workspace directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    /// The run is finished, or unknown to a server that would show it; the
+    /// directory is orphaned.
+    Remove,
+    /// The run is still tracked locally, too recent, or still live on the server.
+    Keep,
+    /// The server could not be asked, or answered 404 to a key that may
+    /// not see the run; leave the directory for the next sweep.
+    Unreachable,
+}
+
+/// Result of one sweep over the workspace root.
+#[derive(Debug, Default)]
+pub struct SweepReport {
+    /// Directories removed (or that would have been, in dry-run mode).
+    pub removed: Vec<PathBuf>,
+    pub kept: usize,
+    pub unreachable: usize,
+}
+
+/// Scan `root` for run workspaces left behind by crashed or killed runs and
+/// remove those whose run the server reports as finished. A run the server
+/// does not know is only removed when `unscoped` says the key sees every
+/// run; a project-scoped key gets the same 404 for runs outside its scope.
+///
+/// Only directories named by a run id are considered, so anything else kept
+/// under the root is left alone. Those named after a run in `active`, or
+/// modified less than `min_age` ago, are never touched. With `dry_run` set,
+/// orphans are only logged and reported.
+pub async fn sweep(
+    service: &dyn TaskService,
+    root: &Path,
+    active: &HashSet<String>,
+    min_age: Duration,
+    unscoped: bool,
+    dry_run: bool,
+) -> std::io::Result<SweepReport> {
+    let mut report = SweepReport::default();
+    let mut entries = match tokio::fs::read_dir(root).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(report),
+        Err(e) => return Err(e),
+    };
+
+    while let Some(entry) = entries.next_entry().await? {
+        let meta = entry.metadata().await?;
+        if !meta.is_dir() {
+            continue;
+        }
+        let Some(run_id) = entry.file_name().to_str().map(String::from) else {
+            continue;
+        };
+        if uuid::Uuid::parse_str(&run_id).is_err() {
+            continue;
+        }
+        let age = meta
+            .modified()
+            .ok()
+            .and_then(|m| SystemTime::now().duration_since(m).ok())
+            .unwrap_or_default();
+
+        let verdict = if active.contains(&run_id) || age < min_age {
+            Verdict::Keep
+        } else {
+            classify(
+                service.get_claude_run(&run_id).await.map(|r| r.status),
+                unscoped,
+            )
+        };
+
+        match verdict {
+            Verdict::Keep => report.kept += 1,
+            Verdict::Unreachable => report.unreachable += 1,
+            Verdict::Remove => {
+                let path = entry.path();
+                if dry_run {
+                    info!("janitor (dry run): would remove {}", path.display());
+                } else {
+                    info!("janitor: removing orphaned workspace {}", path.display());
+                    if let Err(e) = tokio::fs::remove_dir_all(&path).await {
+                        warn!("janitor: failed to remove {}: {e}", path.display());
+                        continue;
+                    }
+                }
+                report.removed.push(path);
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Decide a workspace's fate from the server's view of its run, as seen by a
+/// key that reaches every project when `unscoped` is set.
+pub fn classify(status: Result<ClaudeRunStatus, ServiceError>, unscoped: bool) -> Verdict {
+    match status {
+        Ok(
+            ClaudeRunStatus::Completed
+            | ClaudeRunStatus::Failed
+            | ClaudeRunStatus::Cancelled
+            | ClaudeRunStatus::TimedOut,
+        ) => Verdict::Remove,
+        Ok(ClaudeRunStatus::Queued | ClaudeRunStatus::Running | ClaudeRunStatus::Salvaging) => {
+            Verdict::Keep
+        }
+        Err(ServiceError::NotFound(_)) if unscoped => Verdict::Remove,
+        Err(_) => Verdict::Unreachable,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flowstate_core::claude_run::{ClaudeAction, CreateClaudeRun};
+    use flowstate_core::project::CreateProject;
+    use flowstate_core::task::{CreateTask, Priority, Status, TaskType};
+    use flowstate_service::HttpService;
+
+    #[test]
+    fn classify_by_status() {
+        assert_eq!(
+            classify(Ok(ClaudeRunStatus::Completed), false),
+            Verdict::Remove
+        );
+        assert_eq!(
+            classify(Ok(ClaudeRunStatus::TimedOut), false),
+            Verdict::Remove
+        );
+        assert_eq!(classify(Ok(ClaudeRunStatus::Running), true), Verdict::Keep);
+        assert_eq!(
+            classify(Ok(ClaudeRunStatus::Salvaging), true),
+            Verdict::Keep
+        );
+        assert_eq!(
+            classify(Err(ServiceError::NotFound("run".into())), true),
+            Verdict::Remove
+        );
+        // A scoped key's 404 may hide a live run
+        assert_eq!(
+            classify(Err(ServiceError::NotFound("run".into())), false),
+            Verdict::Unreachable
+        );
+        assert_eq!(
+            classify(
+                Err(ServiceError::Internal("connection refused".into())),
+                true
+            ),
+            Verdict::Unreachable
+        );
+    }
+
+    #[tokio::test]
+    async fn sweep_removes_only_orphans() {
+        let server = flowstate_server::test_helpers::spawn_test_server().await;
+        let svc = HttpService::new(&server.base_url);
+        let project = svc
+            .create_project(&CreateProject {
+                name: "Janitor".into(),
+                slug: "janitor".into(),
+                description: String::new(),
+                repo_url: String::new(),
+            })
+            .await
+            .unwrap();
+        let task = svc
+            .create_task(&CreateTask {
+                project_id: project.id.clone(),
+                title: "T".into(),
+                description: String::new(),
+                status: Status::Todo,
+                priority: Priority::Medium,
+                task_type: TaskType::Feature,
+                parent_id: None,
+                reviewer: String::new(),
+                research_capability: None,
+                design_capability: None,
+                plan_capability: None,
+                build_capability: None,
+                verify_capability: None,
+                due_at: None,
+            })
+            .await
+            .unwrap();
+        let queued = svc
+            .create_claude_run(&CreateClaudeRun {
+                task_id: task.id.clone(),
+                action: ClaudeAction::Research,
+                required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
+            })
+            .await
+            .unwrap();
+
+        let tmp = tempfile::tempdir().unwrap();
+        let gone = tmp.path().join(uuid::Uuid::new_v4().to_string());
+        let local = uuid::Uuid::new_v4().to_string();
+        let unrelated = tmp.path().join("my-notes");
+        for dir in [
+            &tmp.path().join(&queued.id),
+            &gone,
+            &tmp.path().join(&local),
+            &unrelated,
+        ] {
+            std::fs::create_dir(dir).unwrap();
+        }
+        let active = HashSet::from([local]);
+
+        let report = sweep(&svc, tmp.path(), &active, Duration::ZERO, true, true)
+            .await
+            .unwrap();
+        assert_eq!(report.removed, vec![gone.clone()]);
+        assert_eq!(report.kept, 2);
+        assert!(gone.exists(), "dry run deletes nothing");
+
+        assert_eq!(svc.key_projects().await.unwrap(), None);
+        // A scoped key cannot tell a deleted run from one it may not see
+        let report = sweep(&svc, tmp.path(), &active, Duration::ZERO, false, false)
+            .await
+            .unwrap();
+        assert!(report.removed.is_empty());
+        assert_eq!(report.unreachable, 1);
+        assert!(gone.exists());
+
+        let report = sweep(&svc, tmp.path(), &active, Duration::ZERO, true, false)
+            .await
+            .unwrap();
+        assert_eq!(report.removed.len(), 1);
+        assert!(!gone.exists());
+        assert!(tmp.path().join(&queued.id).exists());
+        assert!(unrelated.exists(), "directories not named by a run id stay");
+
+        // Fresh directories are left alone regardless of run state
+        let report = sweep(
+            &svc,
+            tmp.path(),
+            &HashSet::new(),
+            Duration::from_secs(3600),
+            true,
+            false,
+        )
+        .await
+        .unwrap();
+        assert!(report.removed.is_empty());
+    }
+}
@@ -1,11 +1,20 @@
 pub mod backend;
+pub mod benchmark;
 pub mod config;
+pub mod daemon;
 pub mod executor;
+pub mod followups;
+pub mod health;
+pub mod janitor;
+pub mod log_stream;
 pub mod pipeline;
 pub mod plan_parser;
 pub mod preflight;
 pub mod process;
+pub mod recovery;
+pub mod repo_docs;
 pub mod repo_provider;
+pub mod revise;
 pub mod run_tracker;
 pub mod salvage;
 pub mod subtask_parser;
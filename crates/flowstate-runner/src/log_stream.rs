@@ -0,0 +1,138 @@
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use flowstate_service::HttpService;
+use tracing::debug;
+
+/// How often new agent output is sent to the server.
+const SHIP_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Most output sent in one request; a backlog goes out over several.
+const MAX_CHUNK: u64 = 64 * 1024;
+
+/// Reads what has been appended to a file since the last read.
+pub struct LogTail {
+    path: PathBuf,
+    offset: u64,
+}
+
+impl LogTail {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            offset: 0,
+        }
+    }
+
+    /// Text appended since the last call, up to `MAX_CHUNK` bytes, or `None`
+    /// if there is nothing new. A UTF-8 character split across writes is
+    /// held back until the rest of it arrives. A file that has shrunk, e.g.
+    /// because the workspace was recreated, is read again from the start.
+    pub fn read_new(&mut self) -> std::io::Result<Option<String>> {
+        let mut file = match std::fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let len = file.metadata()?.len();
+        if len < self.offset {
+            self.offset = 0;
+        }
+        if len == self.offset {
+            return Ok(None);
+        }
+        file.seek(SeekFrom::Start(self.offset))?;
+        let mut bytes = Vec::new();
+        file.take(MAX_CHUNK).read_to_end(&mut bytes)?;
+
+        let text = match std::str::from_utf8(&bytes) {
+            Ok(text) => text.to_string(),
+            // Incomplete character at the end: send up to it
+            Err(e) if e.error_len().is_none() => {
+                bytes.truncate(e.valid_up_to());
+                String::from_utf8(bytes).unwrap()
+            }
+            Err(_) => String::from_utf8_lossy(&bytes).into_owned(),
+        };
+        if text.is_empty() {
+            return Ok(None);
+        }
+        self.offset += text.len() as u64;
+        Ok(Some(text))
+    }
+}
+
+/// Send agent output written to `path` to the run's live log on the server
+/// until cancelled. Output the server refuses or cannot be reached for is
+/// dropped; the live log is best effort.
+pub async fn ship_log(service: &HttpService, run_id: &str, path: &Path) {
+    let mut tail = LogTail::new(path);
+    let mut interval = tokio::time::interval(SHIP_INTERVAL);
+    loop {
+        interval.tick().await;
+        loop {
+            let text = match tail.read_new() {
+                Ok(Some(text)) => text,
+                Ok(None) => break,
+                Err(e) => {
+                    debug!("reading {}: {e}", path.display());
+                    break;
+                }
+            };
+            if let Err(e) = service.append_claude_run_log(run_id, &text).await {
+                debug!("shipping log for run {run_id}: {e}");
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn append(path: &Path, bytes: &[u8]) {
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap()
+            .write_all(bytes)
+            .unwrap();
+    }
+
+    #[test]
+    fn reads_only_what_was_appended() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("live.log");
+        let mut tail = LogTail::new(&path);
+        assert_eq!(tail.read_new().unwrap(), None);
+
+        append(&path, b"one\n");
+        assert_eq!(tail.read_new().unwrap().as_deref(), Some("one\n"));
+        assert_eq!(tail.read_new().unwrap(), None);
+
+        // "é" arrives in two writes
+        append(&path, b"two \xc3");
+        assert_eq!(tail.read_new().unwrap().as_deref(), Some("two "));
+        append(&path, b"\xa9\n");
+        assert_eq!(tail.read_new().unwrap().as_deref(), Some("é\n"));
+
+        // Recreated file
+        std::fs::write(&path, b"new\n").unwrap();
+        assert_eq!(tail.read_new().unwrap().as_deref(), Some("new\n"));
+    }
+
+    #[test]
+    fn large_backlogs_come_in_chunks() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("live.log");
+        append(&path, &vec![b'x'; MAX_CHUNK as usize + 10]);
+        let mut tail = LogTail::new(&path);
+        assert_eq!(tail.read_new().unwrap().unwrap().len(), MAX_CHUNK as usize);
+        assert_eq!(tail.read_new().unwrap().unwrap().len(), 10);
+        assert_eq!(tail.read_new().unwrap(), None);
+    }
+}
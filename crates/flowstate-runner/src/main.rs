@@ -4,37 +4,72 @@ use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
 
 use anyhow::Result;
-use axum::extract::State;
-use axum::{routing::get, Json, Router};
 use clap::Parser;
-use flowstate_core::claude_run::ClaudeRun;
+use flowstate_core::claude_run::{ClaudeAction, ClaudeRun};
 use flowstate_core::project::Project;
 use flowstate_core::task::Task;
+use flowstate_runner::backend::metered::MeteredBackend;
 use flowstate_runner::backend::AgentBackend;
-use flowstate_runner::config::{RunnerConfig, RuntimeConfig};
-use flowstate_runner::run_tracker::{
-    ActiveRun, ActiveRunSnapshot, RunOutcome, RunResult, RunTracker,
+use flowstate_runner::config::{RunnerCommand, RunnerConfig, RuntimeConfig};
+use flowstate_runner::health::{self, HealthState};
+use flowstate_runner::recovery::{self, InFlightRun, RunJournal};
+use flowstate_runner::run_tracker::{ActiveRun, RunOutcome, RunResult, RunTracker};
+use flowstate_runner::{
+    benchmark, daemon, executor, janitor, log_stream, preflight, process, salvage,
 };
-use flowstate_runner::{executor, preflight, salvage};
+use flowstate_service::telemetry::{self, Telemetry};
 use flowstate_service::{HttpService, RunnerUtilization, TaskService};
-use serde::Serialize;
 use tokio::net::TcpListener;
 use tokio::sync::Semaphore;
 use tokio::task::JoinSet;
 use tracing::{error, info, warn, Instrument};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
-        )
-        .init();
-
+fn main() -> Result<()> {
     let config = RunnerConfig::parse();
+    if let Some(RunnerCommand::InstallService(args)) = &config.command {
+        return daemon::install_service(&config, args);
+    }
     config.validate()?;
 
+    // Fork before the tokio runtime (and its threads) exist.
+    if config.daemon {
+        daemon::daemonize(config.pid_file.as_deref())?;
+    }
+
+    let filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info"));
+    let log = match &config.log_file {
+        Some(path) => {
+            let file = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?;
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(std::sync::Mutex::new(file))
+                .boxed()
+        }
+        None => tracing_subscriber::fmt::layer().boxed(),
+    };
+    // Held until main returns so buffered spans are flushed.
+    let telemetry =
+        Telemetry::from_env("flowstate-runner").map_err(|e| anyhow::anyhow!("telemetry: {e}"))?;
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(log)
+        .with(telemetry.as_ref().map(|t| t.layer()))
+        .init();
+
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?
+        .block_on(run(config))
+}
+
+async fn run(config: RunnerConfig) -> Result<()> {
     // Build agent backend from configuration
     let backend = config.build_backend()?;
     let capability = config.capability()?;
@@ -55,22 +90,34 @@ async fn main() -> Result<()> {
         config.max_concurrent, config.max_builds, config.shutdown_timeout
     );
 
-    // Generate runner ID from HOSTNAME env var or UUID
-    let runner_id = std::env::var("HOSTNAME")
-        .or_else(|_| std::env::var("HOST"))
-        .unwrap_or_else(|_| uuid::Uuid::new_v4().to_string());
+    // Runner ID from config, else the HOSTNAME env var, else a UUID
+    let runner_id = config
+        .runner_id
+        .clone()
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .or_else(|| std::env::var("HOST").ok())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
     info!("runner id: {runner_id}");
 
-    let mut svc = match &config.api_key {
+    let svc = match &config.api_key {
         Some(key) => HttpService::with_api_key(&config.server_url, key.clone()),
         None => HttpService::new(&config.server_url),
     };
+    let mut svc = config.apply_tls(svc)?;
     svc.set_runner_id(runner_id.clone());
+    svc.set_maintenance_wait(Duration::from_secs(config.maintenance_wait));
     let service = Arc::new(svc);
 
     // Run preflight checks
     preflight::run_all(&service, backend.as_ref()).await?;
 
+    let benchmark = if config.benchmark {
+        info!("benchmarking runner...");
+        Some(benchmark::run(backend.as_ref()).await)
+    } else {
+        None
+    };
+
     // Register with the server
     if let Err(e) = service
         .register_runner(&runner_id, backend.name(), capability.as_str())
@@ -96,28 +143,45 @@ async fn main() -> Result<()> {
     let shutdown = Arc::new(AtomicBool::new(false));
     let shutdown_flag = shutdown.clone();
     tokio::spawn(async move {
-        tokio::signal::ctrl_c().await.ok();
+        daemon::shutdown_signal().await;
         info!("received shutdown signal, draining active runs...");
+        daemon::notify("STOPPING=1");
         shutdown_flag.store(true, Ordering::SeqCst);
     });
 
     // Start health endpoint in background
-    let health_state = HealthState {
-        tracker: tracker.clone(),
-        config: config.clone(),
-        runner_id: runner_id.clone(),
-        backend_name: backend.name().to_string(),
-        model_hint: backend.model_hint().map(|s| s.to_string()),
-        capability: capability.as_str().to_string(),
-    };
-    let health_port = config.health_port;
+    let health_state = HealthState::new(
+        tracker.clone(),
+        config.clone(),
+        runner_id.clone(),
+        backend.clone(),
+        capability.as_str().to_string(),
+    );
+    let health_addr = SocketAddr::new(config.health_bind, config.health_port);
+    let health_listener = TcpListener::bind(health_addr).await?;
     tokio::spawn(async move {
-        if let Err(e) = run_health_server(health_port, health_state).await {
+        if let Err(e) = health::serve(health_listener, health_state).await {
             error!("health server failed: {e}");
         }
     });
 
-    info!("health endpoint: http://127.0.0.1:{health_port}/health");
+    info!("health endpoint: http://{health_addr}/health");
+    if !config.health_bind.is_loopback() && config.health_token.is_none() {
+        warn!("health endpoint is reachable off-host without --health-token");
+    }
+
+    if config.janitor_interval > 0 {
+        let svc = service.clone();
+        let trk = tracker.clone();
+        let cfg = config.clone();
+        tokio::spawn(async move { janitor_loop(&svc, &trk, &cfg).await });
+    }
+    daemon::notify("READY=1");
+
+    // Reconcile runs a previous process left in flight before taking new work
+    let journal = Arc::new(RunJournal::open(&recovery::journal_path(&config)));
+    recovery::recover(&service, &journal, &runner_id, &config).await;
+
     info!("entering poll loop (interval: {}s)", config.poll_interval);
 
     // JoinSet for concurrent run tasks
@@ -149,6 +213,7 @@ async fn main() -> Result<()> {
                 active_count,
                 active_builds,
                 status: drain_status,
+                benchmark,
             }
         };
 
@@ -187,6 +252,7 @@ async fn main() -> Result<()> {
                     active_count: 0,
                     active_builds: 0,
                     status: Some("drained".to_string()),
+                    benchmark,
                 };
                 let _ = service
                     .register_runner_with_utilization(
@@ -225,10 +291,15 @@ async fn main() -> Result<()> {
             }
         }
 
-        // C. Claim loop: while we have capacity, claim work
+        // C. Claim loop: while we have capacity, claim work. An empty claim
+        // is held open by the server until work is queued (or claim_wait).
+        let mut idle_since = None;
         while total_semaphore.available_permits() > 0 {
-            match service.claim_claude_run().await {
+            let claim_started = Instant::now();
+            match service.claim_claude_run_wait(config.claim_wait).await {
                 Ok(Some(run)) => {
+                    tracker.write().unwrap().record_claim();
+                    journal.insert(InFlightRun::new(&run));
                     info!(
                         run_id = %run.id,
                         action = %run.action,
@@ -248,6 +319,7 @@ async fn main() -> Result<()> {
                         let _ = service
                             .update_claude_run_status(&run.id, "queued", None, None)
                             .await;
+                        journal.remove(&run.id);
                         break;
                     }
 
@@ -260,6 +332,7 @@ async fn main() -> Result<()> {
                             let _ = service
                                 .update_claude_run_status(&run.id, "failed", Some(&msg), None)
                                 .await;
+                            journal.remove(&run.id);
                             continue;
                         }
                     };
@@ -272,6 +345,7 @@ async fn main() -> Result<()> {
                             let _ = service
                                 .update_claude_run_status(&run.id, "failed", Some(&msg), None)
                                 .await;
+                            journal.remove(&run.id);
                             continue;
                         }
                     };
@@ -283,9 +357,16 @@ async fn main() -> Result<()> {
                     let bs = build_semaphore.clone();
                     let trk = tracker.clone();
                     let be = backend.clone();
-                    join_set.spawn(execute_run(svc, run, task, project, cfg, ts, bs, trk, be));
+                    let jnl = journal.clone();
+                    join_set.spawn(execute_run(
+                        svc, run, task, project, cfg, ts, bs, trk, be, jnl,
+                    ));
+                }
+                Ok(None) => {
+                    // no work available
+                    idle_since = Some(claim_started);
+                    break;
                 }
-                Ok(None) => break, // no work available
                 Err(e) => {
                     error!("claim failed: {e}");
                     break;
@@ -293,9 +374,11 @@ async fn main() -> Result<()> {
             }
         }
 
-        // D. Sleep (use dynamic poll_interval from RuntimeConfig)
-        let poll_interval = runtime_config.read().unwrap().poll_interval;
-        tokio::time::sleep(Duration::from_secs(poll_interval)).await;
+        // D. Sleep (use dynamic poll_interval from RuntimeConfig), less any
+        // time the empty claim already spent waiting on the server.
+        let poll_interval = Duration::from_secs(runtime_config.read().unwrap().poll_interval);
+        let waited = idle_since.map(|t| t.elapsed()).unwrap_or_default();
+        tokio::time::sleep(poll_interval.saturating_sub(waited)).await;
     }
 
     // Graceful shutdown: wait for active runs to complete
@@ -305,6 +388,9 @@ async fn main() -> Result<()> {
             "waiting up to {}s for {} active run(s) to complete",
             config.shutdown_timeout, active_count
         );
+        daemon::notify(&format!(
+            "STATUS=waiting for {active_count} run(s) to finish"
+        ));
 
         let drain_result =
             tokio::time::timeout(Duration::from_secs(config.shutdown_timeout), async {
@@ -368,6 +454,7 @@ async fn execute_run(
     build_semaphore: Arc<Semaphore>,
     tracker: Arc<RwLock<RunTracker>>,
     backend: Arc<dyn AgentBackend>,
+    journal: Arc<RunJournal>,
 ) -> RunResult {
     let run_id = run.id.clone();
     let task_id = run.task_id.clone();
@@ -378,6 +465,10 @@ async fn execute_run(
         task_id = %run.task_id,
         action = %run.action,
     );
+    // Continue the trace of the request that queued the run
+    if let Some(traceparent) = &run.trace_context {
+        telemetry::set_parent(&span, traceparent);
+    }
 
     async move {
         // Acquire total permit (should succeed immediately since we checked availability)
@@ -407,6 +498,17 @@ async fn execute_run(
             heartbeat_loop(&heartbeat_service, &heartbeat_run_id).await;
         });
 
+        // Ship agent output to the server's live log as it is written
+        let log_service = service.clone();
+        let log_run_id = run_id.clone();
+        let log_path = process::live_log_path(&executor::resolve_workspace_dir(
+            &config.workspace_root,
+            &run_id,
+        ));
+        let log_shipper = tokio::spawn(async move {
+            log_stream::ship_log(&log_service, &log_run_id, &log_path).await;
+        });
+
         // Build MCP env if configured and backend supports it
         let mcp_env = if backend.supports_mcp() {
             config.build_mcp_env()
@@ -414,7 +516,9 @@ async fn execute_run(
             None
         };
 
-        // Execute with timeout
+        // Execute with timeout, metering every backend invocation
+        let metered = MeteredBackend::new(backend.as_ref());
+        let started = Instant::now();
         let result = tokio::time::timeout(
             timeout,
             executor::dispatch(
@@ -423,14 +527,16 @@ async fn execute_run(
                 &task,
                 &project,
                 &config,
-                backend.as_ref(),
+                &metered,
                 mcp_env.as_ref(),
             ),
         )
         .await;
+        let metrics = metered.metrics(started.elapsed());
 
-        // Stop heartbeat
+        // Stop heartbeat and log shipping
         heartbeat.abort();
+        log_shipper.abort();
 
         let outcome = match result {
             Ok(Ok(())) => {
@@ -458,8 +564,9 @@ async fn execute_run(
                     )
                     .await;
 
-                // Attempt salvage for Build actions (under the same build permit)
-                if RunnerConfig::is_build_action(action) {
+                // Attempt salvage for Build actions (under the same build permit);
+                // salvage opens a fresh PR, so a revise run's work is not salvaged
+                if action == ClaudeAction::Build {
                     let ws_dir = executor::resolve_workspace_dir(&config.workspace_root, &run_id);
                     let outcome =
                         salvage::attempt_salvage(&service, &run, &task, &project, &ws_dir, &config)
@@ -478,6 +585,9 @@ async fn execute_run(
                         salvage::SalvageOutcome::SalvageError { error } => {
                             error!("salvage error: {error}");
                         }
+                        salvage::SalvageOutcome::Skipped { reason } => {
+                            info!("salvage skipped: {reason}");
+                        }
                     }
 
                     // Clean up workspace after salvage
@@ -488,8 +598,13 @@ async fn execute_run(
             }
         };
 
-        // Unregister from tracker
+        if let Err(e) = service.record_run_metrics(&run_id, &metrics).await {
+            warn!("failed to record run metrics: {e}");
+        }
+
+        // Unregister from tracker and the crash-recovery journal
         tracker.write().unwrap().remove(&run_id);
+        journal.remove(&run_id);
 
         RunResult {
             run_id,
@@ -537,6 +652,40 @@ fn log_run_outcome(result: &RunResult) {
     }
 }
 
+/// Periodically remove workspaces left behind by crashed runs.
+async fn janitor_loop(service: &HttpService, tracker: &RwLock<RunTracker>, config: &RunnerConfig) {
+    let root = executor::resolve_workspace_root(&config.workspace_root);
+    let min_age = Duration::from_secs(config.janitor_min_age);
+    let mut interval = tokio::time::interval(Duration::from_secs(config.janitor_interval));
+    loop {
+        interval.tick().await;
+        let active = tracker.read().unwrap().run_ids();
+        // Runs a scoped key cannot see look deleted, so only an unscoped key
+        // may remove workspaces of runs the server does not know.
+        let unscoped = matches!(service.key_projects().await, Ok(None));
+        match janitor::sweep(
+            service,
+            &root,
+            &active,
+            min_age,
+            unscoped,
+            config.janitor_dry_run,
+        )
+        .await
+        {
+            Ok(report) if !report.removed.is_empty() || report.unreachable > 0 => info!(
+                removed = report.removed.len(),
+                kept = report.kept,
+                unreachable = report.unreachable,
+                dry_run = config.janitor_dry_run,
+                "janitor sweep finished"
+            ),
+            Ok(_) => {}
+            Err(e) => warn!("janitor sweep of {} failed: {e}", root.display()),
+        }
+    }
+}
+
 async fn heartbeat_loop(service: &HttpService, run_id: &str) {
     let mut interval = tokio::time::interval(Duration::from_secs(30));
     loop {
@@ -546,73 +695,3 @@ async fn heartbeat_loop(service: &HttpService, run_id: &str) {
             .await;
     }
 }
-
-// --- Health endpoint ---
-
-#[derive(Clone)]
-struct HealthState {
-    tracker: Arc<RwLock<RunTracker>>,
-    config: Arc<RunnerConfig>,
-    runner_id: String,
-    backend_name: String,
-    model_hint: Option<String>,
-    capability: String,
-}
-
-#[derive(Serialize)]
-struct HealthResponse {
-    status: &'static str,
-    role: &'static str,
-    runner_id: String,
-    backend: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    model: Option<String>,
-    capability: String,
-    capacity: CapacityInfo,
-    active_runs: Vec<ActiveRunSnapshot>,
-}
-
-#[derive(Serialize)]
-struct CapacityInfo {
-    max_concurrent: usize,
-    max_builds: usize,
-    active_total: usize,
-    active_builds: usize,
-    available: usize,
-}
-
-async fn health_handler(State(state): State<HealthState>) -> Json<HealthResponse> {
-    let tracker = state.tracker.read().unwrap();
-    let active_total = tracker.active_count();
-    let active_builds = tracker.active_build_count();
-    let active_runs = tracker.snapshot();
-    drop(tracker);
-
-    Json(HealthResponse {
-        status: "ok",
-        role: "runner",
-        runner_id: state.runner_id.clone(),
-        backend: state.backend_name.clone(),
-        model: state.model_hint.clone(),
-        capability: state.capability.clone(),
-        capacity: CapacityInfo {
-            max_concurrent: state.config.max_concurrent,
-            max_builds: state.config.max_builds,
-            active_total,
-            active_builds,
-            available: state.config.max_concurrent.saturating_sub(active_total),
-        },
-        active_runs,
-    })
-}
-
-async fn run_health_server(port: u16, state: HealthState) -> Result<()> {
-    let app = Router::new()
-        .route("/health", get(health_handler))
-        .with_state(state);
-
-    let addr = SocketAddr::from(([127, 0, 0, 1], port));
-    let listener = TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
-    Ok(())
-}
@@ -5,14 +5,16 @@ use anyhow::{bail, Result};
 use flowstate_core::claude_run::{ClaudeAction, ClaudeRun};
 use flowstate_core::project::Project;
 use flowstate_core::task::Task;
+use flowstate_core::TaskLinks;
 use flowstate_prompts::{ChildTaskInfo, ParentContext, PromptContext};
 use flowstate_service::{HttpService, TaskService};
 use flowstate_verify::Runner as VerifyRunner;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 use crate::backend::{AgentBackend, McpEnv};
 use crate::plan_parser;
-use crate::repo_provider::{self, ProviderError};
+use crate::repo_docs;
+use crate::repo_provider::{self, ProviderError, RepoProvider};
 use crate::workspace;
 
 /// Execute the full build pipeline for an approved task.
@@ -27,9 +29,14 @@ pub async fn execute(
     kill_grace: Duration,
     backend: &dyn AgentBackend,
     mcp_env: Option<&McpEnv>,
+    task_links: &TaskLinks,
+    draft_pr: bool,
 ) -> Result<()> {
     // 1. Validate prerequisites
     //    Subtasks inherit approvals from their parent task.
+    if !task.task_type.is_buildable() {
+        bail!("{} tasks are not built", task.task_type.as_str());
+    }
     let is_subtask = task.is_subtask();
     if is_subtask {
         let parent_id = task.parent_id.as_deref().unwrap();
@@ -74,6 +81,7 @@ pub async fn execute(
         .check_auth(&project.repo_url)
         .await
         .map_err(|e| anyhow::anyhow!("repo auth check failed: {e}"))?;
+    let fork = fork_owner(project.fork_owner.as_deref(), provider.as_ref())?;
 
     // 4. Clone repo to fresh workspace
     progress(service, &run.id, "Cloning repository...").await;
@@ -82,9 +90,17 @@ pub async fn execute(
         &project.repo_url,
         token.as_deref(),
         project.skip_tls_verify,
+        project.ssh_key_path.as_deref(),
+        project.subpath(),
     )
     .await?;
 
+    // 4b. Set up the fork early; GitHub creates forks asynchronously
+    if let Some(owner) = fork {
+        progress(service, &run.id, "Preparing fork...").await;
+        prepare_fork(provider.as_ref(), project, owner, ws_dir, token.as_deref()).await?;
+    }
+
     // 5. Detect default branch (fresh clone is already on it)
     let default_branch = workspace::detect_default_branch(ws_dir).await?;
 
@@ -148,17 +164,20 @@ pub async fn execute(
         task_id: task.id.clone(),
         project_name: project.name.clone(),
         repo_url: project.repo_url.clone(),
+        repo_subpath: project.subpath().map(str::to_string),
         task_title: task.title.clone(),
         task_description: task.description.clone(),
+        task_type: task.task_type,
         spec_content,
         plan_content: plan_content.clone(),
         research_content: None,
         verification_content: None,
         distill_feedback: None,
+        feedback_history: vec![],
         reviewer_notes: vec![],
         child_tasks,
         parent_context,
-        file_allowlist,
+        file_allowlist: file_allowlist.clone(),
     };
 
     let prompt = flowstate_prompts::assemble_prompt(&ctx, ClaudeAction::Build);
@@ -253,28 +272,96 @@ pub async fn execute(
         }
     }
 
-    // 14. Tests pass -> commit
+    // 14. Tests pass -> commit, with the task's documents alongside the
+    //     code when the project keeps them in the repo
+    let docs_dir = if project.docs_in_repo {
+        progress(service, &run.id, "Copying task documents into the repo...").await;
+        match repo_docs::mirror_task_docs(service, task, ws_dir).await {
+            Ok(dir) => Some(dir),
+            Err(e) => {
+                warn!("failed to copy task documents into the repo: {e}");
+                None
+            }
+        }
+    } else {
+        None
+    };
     progress(service, &run.id, "Committing changes...").await;
     let commit_msg = format!("feat: {} [flowstate]", task.title);
     workspace::add_and_commit(ws_dir, &commit_msg).await?;
 
+    // 14b. Compare the diff against the plan's declared files, and against
+    //      the package directory of a monorepo project; findings must be
+    //      acknowledged before the task can move to Done
+    progress(service, &run.id, "Checking diff against plan scope...").await;
+    let mut scope = if is_subtask {
+        file_allowlist
+    } else {
+        plan_content
+            .as_deref()
+            .map(plan_parser::extract_file_scope)
+            .unwrap_or_default()
+    };
+    let docs_dir: Vec<String> = docs_dir.into_iter().collect();
+    scope.extend(docs_dir.iter().cloned());
+    match workspace::changed_files(ws_dir, &default_branch).await {
+        Ok(changes) => {
+            let mut findings = flowstate_core::scope::check_scope(&scope, &changes);
+            if let Some(subpath) = project.subpath() {
+                findings.extend(flowstate_core::scope::check_subpath(
+                    subpath, &docs_dir, &changes,
+                ));
+            }
+            if !findings.is_empty() {
+                warn!("{} scope findings need review", findings.len());
+            }
+            if let Err(e) = service.write_task_scope_findings(&task.id, &findings).await {
+                warn!("failed to record scope findings: {e}");
+            }
+        }
+        Err(e) => warn!("failed to diff against {default_branch}: {e}"),
+    }
+
     // 15. Push branch
     progress(service, &run.id, "Pushing branch...").await;
-    provider
-        .push_branch(ws_dir, &branch_name)
-        .await
-        .map_err(|e| anyhow::anyhow!("push failed: {e}"))?;
+    match fork {
+        Some(_) => provider.push_branch_to_fork(ws_dir, &branch_name).await,
+        None => provider.push_branch(ws_dir, &branch_name).await,
+    }
+    .map_err(|e| anyhow::anyhow!("push failed: {e}"))?;
 
-    // 16. Open PR
+    // 16. Open PR, as a draft until verify is approved when asked to
     progress(service, &run.id, "Opening pull request...").await;
-    let pr_body = format!(
-        "## Task\n\n{}\n\n## Description\n\n{}\n\n---\nGenerated by flowstate runner",
-        task.title, task.description
-    );
-    let pr = provider
-        .open_pull_request(ws_dir, &branch_name, &task.title, &pr_body, &default_branch)
-        .await
-        .map_err(|e: ProviderError| anyhow::anyhow!("PR creation failed: {e}"))?;
+    let pr_body = pr_body(task, task_links, None);
+    let draft = draft_pr && provider.supports_draft_prs();
+    if draft_pr && !draft {
+        warn!(
+            "{} cannot open draft PRs; opening a ready one",
+            provider.name()
+        );
+    }
+    let pr = if let Some(owner) = fork {
+        provider
+            .open_fork_pull_request(
+                &project.repo_url,
+                owner,
+                &branch_name,
+                &task.title,
+                &pr_body,
+                &default_branch,
+                draft,
+            )
+            .await
+    } else if draft {
+        provider
+            .open_draft_pull_request(ws_dir, &branch_name, &task.title, &pr_body, &default_branch)
+            .await
+    } else {
+        provider
+            .open_pull_request(ws_dir, &branch_name, &task.title, &pr_body, &default_branch)
+            .await
+    }
+    .map_err(|e: ProviderError| anyhow::anyhow!("PR creation failed: {e}"))?;
 
     // 17. Update claude_run with PR info
     service
@@ -295,6 +382,7 @@ pub async fn execute(
         pr_url: pr.url.clone(),
         pr_number: pr.number as i64,
         branch_name: pr.branch.clone(),
+        draft,
     };
     if let Err(e) = service.create_task_pr(&create_pr).await {
         tracing::warn!("failed to link PR to task: {e}");
@@ -320,7 +408,59 @@ async fn progress(service: &HttpService, run_id: &str, message: &str) {
     let _ = service.update_claude_run_progress(run_id, message).await;
 }
 
-fn slugify(title: &str) -> String {
+/// The account whose fork the project pushes to, or `None` to push to the
+/// repository itself. Fails when `provider` cannot work through a fork.
+pub(crate) fn fork_owner<'a>(
+    fork_owner: Option<&'a str>,
+    provider: &dyn RepoProvider,
+) -> Result<Option<&'a str>> {
+    let Some(owner) = fork_owner.filter(|o| !o.is_empty()) else {
+        return Ok(None);
+    };
+    if !provider.supports_forks() {
+        bail!(
+            "{} cannot push to a fork; clear fork_owner on the project",
+            provider.name()
+        );
+    }
+    Ok(Some(owner))
+}
+
+/// Make sure `owner`'s fork of the project exists and point the
+/// workspace's fork remote at it.
+pub(crate) async fn prepare_fork(
+    provider: &dyn RepoProvider,
+    project: &Project,
+    owner: &str,
+    ws_dir: &Path,
+    token: Option<&str>,
+) -> Result<()> {
+    let fork_url = provider
+        .ensure_fork(&project.repo_url, owner)
+        .await
+        .map_err(|e| anyhow::anyhow!("fork setup failed: {e}"))?;
+    workspace::set_fork_remote(ws_dir, &fork_url, token).await
+}
+
+/// Pull request description with a link back to the task; `note` goes
+/// above the footer.
+pub(crate) fn pr_body(task: &Task, task_links: &TaskLinks, note: Option<&str>) -> String {
+    format_pr_body(
+        &task.title,
+        &task.description,
+        &task_links.task_url(&task.id),
+        note,
+    )
+}
+
+fn format_pr_body(title: &str, description: &str, task_url: &str, note: Option<&str>) -> String {
+    let note = note.map(|n| format!("{n}\n\n")).unwrap_or_default();
+    format!(
+        "## Task\n\n{title}\n\nOpen in flowstate: {task_url}\n\n## Description\n\n{description}\n\n---\n{note}Generated by flowstate runner"
+    )
+}
+
+pub(crate) fn slugify(title: &str) -> String {
     title
         .to_lowercase()
         .chars()
@@ -335,10 +475,54 @@ fn slugify(title: &str) -> String {
         .collect()
 }
 
+fn save_run_prompt(run_id: &str, prompt: &str) -> Result<()> {
+    let data_dir = if let Ok(xdg) = std::env::var("XDG_DATA_HOME") {
+        PathBuf::from(xdg).join("flowstate")
+    } else if let Some(home) = std::env::var_os("HOME") {
+        PathBuf::from(home).join(".local/share/flowstate")
+    } else {
+        PathBuf::from(".").join("flowstate")
+    };
+    let run_dir = data_dir.join("claude_runs").join(run_id);
+    std::fs::create_dir_all(&run_dir)?;
+    std::fs::write(run_dir.join("prompt.md"), prompt)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn fork_owner_needs_fork_support() {
+        let mock = repo_provider::mock::MockRepoProvider::new();
+        assert_eq!(fork_owner(None, &mock).unwrap(), None);
+        assert_eq!(fork_owner(Some(""), &mock).unwrap(), None);
+        assert_eq!(fork_owner(Some("bot"), &mock).unwrap(), Some("bot"));
+
+        let gitea = repo_provider::gitea::GiteaProvider::new(
+            "https://git.example.com/org/repo.git",
+            Some("tok".into()),
+            false,
+        )
+        .unwrap();
+        assert_eq!(fork_owner(None, &gitea).unwrap(), None);
+        let err = fork_owner(Some("bot"), &gitea).unwrap_err().to_string();
+        assert!(err.contains("cannot push to a fork"), "{err}");
+    }
+
+    #[test]
+    fn pr_body_links_to_the_task() {
+        let url = TaskLinks::default().task_url("t-1");
+        let body = format_pr_body("Fix login", "Users cannot log in", &url, None);
+        assert!(body.contains("Open in flowstate: flowstate://task/t-1"));
+        assert!(body.contains("Users cannot log in"));
+        assert!(body.ends_with("---\nGenerated by flowstate runner"));
+
+        let body = format_pr_body("Fix login", "", &url, Some("**Note:** salvaged"));
+        assert!(body.ends_with("**Note:** salvaged\n\nGenerated by flowstate runner"));
+    }
+
     #[test]
     fn slugify_simple() {
         assert_eq!(slugify("My Task"), "my-task");
@@ -402,17 +586,3 @@ mod tests {
         assert_eq!(content, prompt);
     }
 }
-
-fn save_run_prompt(run_id: &str, prompt: &str) -> Result<()> {
-    let data_dir = if let Ok(xdg) = std::env::var("XDG_DATA_HOME") {
-        PathBuf::from(xdg).join("flowstate")
-    } else if let Some(home) = std::env::var_os("HOME") {
-        PathBuf::from(home).join(".local/share/flowstate")
-    } else {
-        PathBuf::from(".").join("flowstate")
-    };
-    let run_dir = data_dir.join("claude_runs").join(run_id);
-    std::fs::create_dir_all(&run_dir)?;
-    std::fs::write(run_dir.join("prompt.md"), prompt)?;
-    Ok(())
-}
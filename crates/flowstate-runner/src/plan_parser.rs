@@ -78,6 +78,73 @@ pub fn extract_validation_commands(plan_content: &str) -> Vec<VerificationStep>
     steps
 }
 
+/// Parse a PLAN.md for the files it declares in its
+/// "### 1. Directories and Files" section.
+///
+/// Takes each backticked path, or the first cell of a table row or the
+/// text of a bullet when it has none. Entries may be directories.
+pub fn extract_file_scope(plan_content: &str) -> Vec<String> {
+    let mut paths: Vec<String> = Vec::new();
+    let mut in_files_section = false;
+
+    for line in plan_content.lines() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with("### 1.") || trimmed.starts_with("## 1.") {
+            in_files_section = true;
+            continue;
+        }
+        if trimmed.starts_with("### ") || trimmed.starts_with("## ") {
+            in_files_section = false;
+            continue;
+        }
+        if !in_files_section {
+            continue;
+        }
+
+        let mut found = false;
+        let mut rest = trimmed;
+        while let Some(start) = rest.find('`') {
+            let after = &rest[start + 1..];
+            let Some(end) = after.find('`') else { break };
+            let path = after[..end].trim();
+            if looks_like_path(path) {
+                paths.push(path.to_string());
+                found = true;
+            }
+            rest = &after[end + 1..];
+        }
+        if found {
+            continue;
+        }
+
+        let bare = if let Some(row) = trimmed.strip_prefix('|') {
+            row.split('|').next().unwrap_or("")
+        } else if let Some(item) = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+        {
+            item.split_whitespace().next().unwrap_or("")
+        } else {
+            ""
+        };
+        let bare = bare.trim().trim_matches('*');
+        if looks_like_path(bare) {
+            paths.push(bare.to_string());
+        }
+    }
+
+    paths.dedup();
+    paths
+}
+
+fn looks_like_path(s: &str) -> bool {
+    !s.is_empty()
+        && !s.contains(char::is_whitespace)
+        && !s.chars().all(|c| c == '-' || c == ':')
+        && (s.contains('/') || s.contains('.'))
+}
+
 fn flush_code_block(content: &str, steps: &mut Vec<VerificationStep>, index: &mut i32) {
     for line in content.lines() {
         let trimmed = line.trim();
@@ -223,6 +290,37 @@ cargo test
         assert_eq!(steps[0].command, "cargo test");
     }
 
+    #[test]
+    fn test_extract_file_scope() {
+        let plan = r#"
+### 1. Directories and Files
+
+| Path | Change |
+|------|--------|
+| `crates/core/src/scope.rs` | NEW |
+| crates/core/src/lib.rs | MODIFIED |
+
+- `docs/server.md` (MODIFIED) and `docs/runner.md`
+- crates/runner/src/ — NEW directory
+- Nothing else
+
+### 2. Work Phases
+
+- `src/elsewhere.rs`
+"#;
+        assert_eq!(
+            extract_file_scope(plan),
+            [
+                "crates/core/src/scope.rs",
+                "crates/core/src/lib.rs",
+                "docs/server.md",
+                "docs/runner.md",
+                "crates/runner/src/",
+            ]
+        );
+        assert!(extract_file_scope("# Plan\n\nNo files section.").is_empty());
+    }
+
     #[test]
     fn test_non_command_content_ignored() {
         let plan = r#"
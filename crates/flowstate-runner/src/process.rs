@@ -1,93 +1,16 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::time::Duration;
 
 use anyhow::{Context, Result};
 use nix::sys::signal::{kill, Signal};
 use nix::unistd::Pid;
-use tokio::io::AsyncReadExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::process::{ChildStderr, ChildStdout, Command};
 use tracing::{info, warn};
 
 use crate::backend::AgentOutput;
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[tokio::test]
-    async fn run_managed_success() {
-        let tmp = tempfile::tempdir().unwrap();
-        let mut cmd = Command::new("echo");
-        cmd.arg("hello world");
-        let output = run_managed_with_timeout(
-            &mut cmd,
-            tmp.path(),
-            Duration::from_secs(10),
-            Duration::from_secs(2),
-        )
-        .await
-        .unwrap();
-        assert!(output.success);
-        assert!(output.stdout.contains("hello world"));
-        assert_eq!(output.exit_code, 0);
-    }
-
-    #[tokio::test]
-    async fn run_managed_failure() {
-        let tmp = tempfile::tempdir().unwrap();
-        let mut cmd = Command::new("false");
-        let output = run_managed_with_timeout(
-            &mut cmd,
-            tmp.path(),
-            Duration::from_secs(10),
-            Duration::from_secs(2),
-        )
-        .await
-        .unwrap();
-        assert!(!output.success);
-        assert_ne!(output.exit_code, 0);
-    }
-
-    #[tokio::test]
-    async fn run_managed_timeout() {
-        let tmp = tempfile::tempdir().unwrap();
-        let mut cmd = Command::new("sleep");
-        cmd.arg("60");
-        let result = run_managed_with_timeout(
-            &mut cmd,
-            tmp.path(),
-            Duration::from_millis(100),
-            Duration::from_millis(100),
-        )
-        .await;
-        assert!(result.is_err());
-        let err = result.unwrap_err().to_string();
-        assert!(err.contains("timed out"));
-    }
-
-    #[tokio::test]
-    async fn output_saved_to_file() {
-        let tmp = tempfile::tempdir().unwrap();
-        let mut cmd = Command::new("echo");
-        cmd.arg("saved output");
-        let output = run_managed_with_timeout(
-            &mut cmd,
-            tmp.path(),
-            Duration::from_secs(10),
-            Duration::from_secs(2),
-        )
-        .await
-        .unwrap();
-        assert!(output.success);
-
-        let output_file = tmp.path().join(".flowstate-output/output.txt");
-        assert!(output_file.exists());
-        let content = std::fs::read_to_string(&output_file).unwrap();
-        assert!(content.contains("saved output"));
-    }
-}
-
 /// A child process managed within its own process group.
 /// Enables killing the entire process tree (including any orphaned children
 /// that inherit pipe file descriptors).
@@ -125,6 +48,33 @@ impl ManagedChild {
     }
 }
 
+/// Agent stdout as it is produced, appended across every agent invocation in
+/// the workspace so the runner can ship it to the server while the run goes.
+pub fn live_log_path(work_dir: &Path) -> PathBuf {
+    work_dir.join(".flowstate-output").join("live.log")
+}
+
+/// Open [`live_log_path`] for appending; `None`, after a warning, if it
+/// cannot be opened.
+pub async fn open_live_log(work_dir: &Path) -> Option<tokio::fs::File> {
+    let live_log_file = live_log_path(work_dir);
+    let _ = std::fs::create_dir_all(work_dir.join(".flowstate-output"));
+    tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&live_log_file)
+        .await
+        .inspect_err(|e| warn!("opening {}: {e}", live_log_file.display()))
+        .ok()
+}
+
+/// Save an agent's full stdout to `work_dir/.flowstate-output/output.txt`.
+pub fn save_output(work_dir: &Path, stdout: &str) {
+    let run_dir = work_dir.join(".flowstate-output");
+    let _ = std::fs::create_dir_all(&run_dir);
+    let _ = std::fs::write(run_dir.join("output.txt"), stdout);
+}
+
 /// Spawn a command in a new process group via setsid.
 /// Returns the managed child and its stdout/stderr handles.
 pub fn spawn_managed(cmd: &mut Command) -> Result<(ManagedChild, ChildStdout, ChildStderr)> {
@@ -147,7 +97,8 @@ pub fn spawn_managed(cmd: &mut Command) -> Result<(ManagedChild, ChildStdout, Ch
 
 /// Run a managed process with timeout.
 /// Captures stdout/stderr and returns AgentOutput.
-/// Saves output to `work_dir/.flowstate-output/output.txt`.
+/// Saves output to `work_dir/.flowstate-output/output.txt`, and appends
+/// stdout to [`live_log_path`] as it arrives.
 pub async fn run_managed_with_timeout(
     cmd: &mut Command,
     work_dir: &Path,
@@ -156,19 +107,32 @@ pub async fn run_managed_with_timeout(
 ) -> Result<AgentOutput> {
     cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
 
+    let mut live_log = open_live_log(work_dir).await;
+
     let (mut managed, mut stdout, mut stderr) = spawn_managed(cmd)?;
 
     let result = tokio::time::timeout(timeout_duration, async {
         let mut stdout_bytes = Vec::new();
         let mut stderr_bytes = Vec::new();
 
-        let (stdout_res, stderr_res, status) = tokio::try_join!(
-            async { stdout.read_to_end(&mut stdout_bytes).await },
+        let ((), stderr_res, status) = tokio::try_join!(
+            async {
+                let mut buf = [0u8; 8192];
+                loop {
+                    let n = stdout.read(&mut buf).await?;
+                    if n == 0 {
+                        return Ok(());
+                    }
+                    stdout_bytes.extend_from_slice(&buf[..n]);
+                    if let Some(log) = live_log.as_mut() {
+                        let _ = log.write_all(&buf[..n]).await;
+                    }
+                }
+            },
             async { stderr.read_to_end(&mut stderr_bytes).await },
             managed.child.wait()
         )?;
 
-        let _ = stdout_res;
         let _ = stderr_res;
         Ok::<_, anyhow::Error>((stdout_bytes, stderr_bytes, status))
     })
@@ -180,16 +144,14 @@ pub async fn run_managed_with_timeout(
             let stderr_str = String::from_utf8_lossy(&stderr_bytes).to_string();
             let exit_code = status.code().unwrap_or(-1);
 
-            // Save output
-            let run_dir = work_dir.join(".flowstate-output");
-            let _ = std::fs::create_dir_all(&run_dir);
-            let _ = std::fs::write(run_dir.join("output.txt"), &stdout_str);
+            save_output(work_dir, &stdout_str);
 
             Ok(AgentOutput {
                 success: status.success(),
                 stdout: stdout_str,
                 stderr: stderr_str,
                 exit_code,
+                usage: None,
             })
         }
         Ok(Err(e)) => Err(e),
@@ -207,3 +169,99 @@ pub async fn run_managed_with_timeout(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn run_managed_success() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut cmd = Command::new("echo");
+        cmd.arg("hello world");
+        let output = run_managed_with_timeout(
+            &mut cmd,
+            tmp.path(),
+            Duration::from_secs(10),
+            Duration::from_secs(2),
+        )
+        .await
+        .unwrap();
+        assert!(output.success);
+        assert!(output.stdout.contains("hello world"));
+        assert_eq!(output.exit_code, 0);
+    }
+
+    #[tokio::test]
+    async fn run_managed_failure() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut cmd = Command::new("false");
+        let output = run_managed_with_timeout(
+            &mut cmd,
+            tmp.path(),
+            Duration::from_secs(10),
+            Duration::from_secs(2),
+        )
+        .await
+        .unwrap();
+        assert!(!output.success);
+        assert_ne!(output.exit_code, 0);
+    }
+
+    #[tokio::test]
+    async fn run_managed_timeout() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut cmd = Command::new("sleep");
+        cmd.arg("60");
+        let result = run_managed_with_timeout(
+            &mut cmd,
+            tmp.path(),
+            Duration::from_millis(100),
+            Duration::from_millis(100),
+        )
+        .await;
+        assert!(result.is_err());
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("timed out"));
+    }
+
+    #[tokio::test]
+    async fn output_saved_to_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut cmd = Command::new("echo");
+        cmd.arg("saved output");
+        let output = run_managed_with_timeout(
+            &mut cmd,
+            tmp.path(),
+            Duration::from_secs(10),
+            Duration::from_secs(2),
+        )
+        .await
+        .unwrap();
+        assert!(output.success);
+
+        let output_file = tmp.path().join(".flowstate-output/output.txt");
+        assert!(output_file.exists());
+        let content = std::fs::read_to_string(&output_file).unwrap();
+        assert!(content.contains("saved output"));
+    }
+
+    #[tokio::test]
+    async fn live_log_accumulates_across_invocations() {
+        let tmp = tempfile::tempdir().unwrap();
+        for word in ["first", "second"] {
+            let mut cmd = Command::new("echo");
+            cmd.arg(word);
+            run_managed_with_timeout(
+                &mut cmd,
+                tmp.path(),
+                Duration::from_secs(10),
+                Duration::from_secs(2),
+            )
+            .await
+            .unwrap();
+        }
+        let live = std::fs::read_to_string(live_log_path(tmp.path())).unwrap();
+        assert_eq!(live, "first\nsecond\n");
+    }
+}
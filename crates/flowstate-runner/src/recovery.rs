@@ -0,0 +1,345 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use flowstate_core::claude_run::{ClaudeAction, ClaudeRun, ClaudeRunStatus};
+use flowstate_service::{HttpService, ServiceError, TaskService};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::config::RunnerConfig;
+use crate::executor;
+use crate::salvage::{self, SalvageOutcome};
+
+/// Reason recorded on runs that were in flight when the runner died.
+const INTERRUPTED: &str = "runner restarted while the run was in progress";
+
+/// Where the journal lives: `--state-file`, or `.inflight.json` in the
+/// workspace root.
+pub fn journal_path(config: &RunnerConfig) -> PathBuf {
+    config.state_file.clone().unwrap_or_else(|| {
+        executor::resolve_workspace_root(&config.workspace_root).join(".inflight.json")
+    })
+}
+
+/// A claimed run, recorded before it starts executing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InFlightRun {
+    pub run_id: String,
+    pub task_id: String,
+    pub action: ClaudeAction,
+    pub claimed_at: DateTime<Utc>,
+}
+
+impl InFlightRun {
+    pub fn new(run: &ClaudeRun) -> Self {
+        Self {
+            run_id: run.id.clone(),
+            task_id: run.task_id.clone(),
+            action: run.action,
+            claimed_at: Utc::now(),
+        }
+    }
+}
+
+/// On-disk record of the runs this runner is executing. Every change is
+/// written through (via a temp file and rename), so after a crash the file
+/// names exactly the runs that never finished.
+pub struct RunJournal {
+    path: PathBuf,
+    runs: Mutex<BTreeMap<String, InFlightRun>>,
+}
+
+impl RunJournal {
+    /// Load the journal at `path`. A missing file is an empty journal; an
+    /// unreadable one is logged and replaced, since it only holds hints.
+    pub fn open(path: &Path) -> Self {
+        let runs = match std::fs::read(path) {
+            Ok(bytes) => match serde_json::from_slice::<Vec<InFlightRun>>(&bytes) {
+                Ok(runs) => runs.into_iter().map(|r| (r.run_id.clone(), r)).collect(),
+                Err(e) => {
+                    warn!("ignoring unreadable run journal {}: {e}", path.display());
+                    BTreeMap::new()
+                }
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => {
+                warn!("failed to read run journal {}: {e}", path.display());
+                BTreeMap::new()
+            }
+        };
+        Self {
+            path: path.to_path_buf(),
+            runs: Mutex::new(runs),
+        }
+    }
+
+    /// Runs recorded as in flight, oldest claim first.
+    pub fn runs(&self) -> Vec<InFlightRun> {
+        let mut runs: Vec<_> = self.runs.lock().unwrap().values().cloned().collect();
+        runs.sort_by_key(|r| r.claimed_at);
+        runs
+    }
+
+    pub fn insert(&self, run: InFlightRun) {
+        let mut runs = self.runs.lock().unwrap();
+        runs.insert(run.run_id.clone(), run);
+        self.persist(&runs);
+    }
+
+    pub fn remove(&self, run_id: &str) {
+        let mut runs = self.runs.lock().unwrap();
+        if runs.remove(run_id).is_some() {
+            self.persist(&runs);
+        }
+    }
+
+    fn persist(&self, runs: &BTreeMap<String, InFlightRun>) {
+        if let Err(e) = self.write(runs) {
+            warn!("failed to write run journal {}: {e}", self.path.display());
+        }
+    }
+
+    fn write(&self, runs: &BTreeMap<String, InFlightRun>) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let list: Vec<&InFlightRun> = runs.values().collect();
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(&list)?)?;
+        std::fs::rename(&tmp, &self.path)
+    }
+}
+
+/// What to do with a journalled run after a restart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reconcile {
+    /// The server already finished the run (or reassigned it); just forget it.
+    Forget,
+    /// Mark the run failed with [`INTERRUPTED`].
+    Fail,
+    /// Try to salvage the build workspace, failing the run if that's refused.
+    Salvage,
+    /// The server could not be asked; keep the entry for the next restart.
+    Retry,
+}
+
+/// Decide a journalled run's fate from the server's view of it. Only runs
+/// still Running under this runner's id are touched, so a run the watchdog
+/// already timed out (or another runner has since claimed) is left alone.
+pub fn classify(
+    run: Result<&ClaudeRun, &ServiceError>,
+    runner_id: &str,
+    workspace_exists: bool,
+) -> Reconcile {
+    match run {
+        Ok(run)
+            if run.status == ClaudeRunStatus::Running
+                && run.runner_id.as_deref() == Some(runner_id) =>
+        {
+            if run.action == ClaudeAction::Build && workspace_exists {
+                Reconcile::Salvage
+            } else {
+                Reconcile::Fail
+            }
+        }
+        Ok(_) | Err(ServiceError::NotFound(_)) => Reconcile::Forget,
+        Err(_) => Reconcile::Retry,
+    }
+}
+
+/// Reconcile every run left in the journal by a previous process with the
+/// server, then clean up its workspace. Runs this before claiming new work.
+pub async fn recover(
+    service: &HttpService,
+    journal: &RunJournal,
+    runner_id: &str,
+    config: &RunnerConfig,
+) {
+    for entry in journal.runs() {
+        let ws_dir = executor::resolve_workspace_dir(&config.workspace_root, &entry.run_id);
+        let run = service.get_claude_run(&entry.run_id).await;
+        let action = classify(run.as_ref(), runner_id, ws_dir.exists());
+        info!(run_id = %entry.run_id, action = ?action, "recovering interrupted run");
+
+        match (action, &run) {
+            (Reconcile::Retry, _) => continue,
+            (Reconcile::Fail, _) => fail(service, &entry.run_id).await,
+            (Reconcile::Salvage, Ok(run)) => salvage_run(service, run, &ws_dir, config).await,
+            _ => {}
+        }
+        executor::cleanup_workspace(&ws_dir);
+        journal.remove(&entry.run_id);
+    }
+}
+
+async fn fail(service: &HttpService, run_id: &str) {
+    if let Err(e) = service
+        .update_claude_run_status(run_id, "failed", Some(INTERRUPTED), None)
+        .await
+    {
+        warn!("failed to mark interrupted run {run_id} failed: {e}");
+    }
+}
+
+async fn salvage_run(service: &HttpService, run: &ClaudeRun, ws_dir: &Path, config: &RunnerConfig) {
+    let task = match service.get_task(&run.task_id).await {
+        Ok(task) => task,
+        Err(_) => return fail(service, &run.id).await,
+    };
+    let project = match service.get_project(&task.project_id).await {
+        Ok(project) => project,
+        Err(_) => return fail(service, &run.id).await,
+    };
+    match salvage::attempt_salvage(service, run, &task, &project, ws_dir, config).await {
+        SalvageOutcome::PrCut { pr_url, .. } => info!("salvaged interrupted run: {pr_url}"),
+        SalvageOutcome::Skipped { reason } => {
+            info!("salvage skipped ({reason}), failing interrupted run");
+            fail(service, &run.id).await;
+        }
+        // attempt_salvage already marked the run failed
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use flowstate_core::claude_run::CreateClaudeRun;
+    use flowstate_core::project::CreateProject;
+    use flowstate_core::task::{CreateTask, Priority, Status, TaskType};
+
+    fn run(status: ClaudeRunStatus, action: ClaudeAction, runner: Option<&str>) -> ClaudeRun {
+        ClaudeRun {
+            id: "run-1".into(),
+            task_id: "task-1".into(),
+            action,
+            status,
+            error_message: None,
+            exit_code: None,
+            pr_url: None,
+            pr_number: None,
+            branch_name: None,
+            progress_message: None,
+            runner_id: runner.map(String::from),
+            started_at: Utc::now(),
+            finished_at: None,
+            required_capability: None,
+            priority: 0,
+            feedback: None,
+            idempotency_key: None,
+            run_window: None,
+            trace_context: None,
+        }
+    }
+
+    #[test]
+    fn classify_by_server_state() {
+        let mine = run(ClaudeRunStatus::Running, ClaudeAction::Research, Some("r1"));
+        assert_eq!(classify(Ok(&mine), "r1", false), Reconcile::Fail);
+
+        let build = run(ClaudeRunStatus::Running, ClaudeAction::Build, Some("r1"));
+        assert_eq!(classify(Ok(&build), "r1", true), Reconcile::Salvage);
+        assert_eq!(classify(Ok(&build), "r1", false), Reconcile::Fail);
+
+        let reassigned = run(ClaudeRunStatus::Running, ClaudeAction::Research, Some("r2"));
+        assert_eq!(classify(Ok(&reassigned), "r1", false), Reconcile::Forget);
+        let timed_out = run(ClaudeRunStatus::TimedOut, ClaudeAction::Build, Some("r1"));
+        assert_eq!(classify(Ok(&timed_out), "r1", true), Reconcile::Forget);
+
+        let gone = ServiceError::NotFound("run".into());
+        assert_eq!(classify(Err(&gone), "r1", false), Reconcile::Forget);
+        let down = ServiceError::Internal("connection refused".into());
+        assert_eq!(classify(Err(&down), "r1", false), Reconcile::Retry);
+    }
+
+    #[test]
+    fn journal_survives_reopen() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("state/inflight.json");
+        let journal = RunJournal::open(&path);
+        let entry = InFlightRun::new(&run(ClaudeRunStatus::Running, ClaudeAction::Plan, None));
+        journal.insert(entry.clone());
+
+        assert_eq!(RunJournal::open(&path).runs(), vec![entry]);
+        journal.remove("run-1");
+        assert!(RunJournal::open(&path).runs().is_empty());
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(RunJournal::open(&path).runs().is_empty());
+    }
+
+    #[tokio::test]
+    async fn recover_fails_interrupted_runs() {
+        let server = flowstate_server::test_helpers::spawn_test_server().await;
+        let mut svc = HttpService::new(&server.base_url);
+        svc.set_runner_id("runner-1".into());
+        let project = svc
+            .create_project(&CreateProject {
+                name: "Recovery".into(),
+                slug: "recovery".into(),
+                description: String::new(),
+                repo_url: String::new(),
+            })
+            .await
+            .unwrap();
+        let task = svc
+            .create_task(&CreateTask {
+                project_id: project.id.clone(),
+                title: "T".into(),
+                description: String::new(),
+                status: Status::Todo,
+                priority: Priority::Medium,
+                task_type: TaskType::Feature,
+                parent_id: None,
+                reviewer: String::new(),
+                research_capability: None,
+                design_capability: None,
+                plan_capability: None,
+                build_capability: None,
+                verify_capability: None,
+                due_at: None,
+            })
+            .await
+            .unwrap();
+        svc.create_claude_run(&CreateClaudeRun {
+            task_id: task.id.clone(),
+            action: ClaudeAction::Research,
+            required_capability: None,
+            priority: 0,
+            feedback: None,
+            idempotency_key: None,
+            run_window: None,
+            trace_context: None,
+        })
+        .await
+        .unwrap();
+        let claimed = svc.claim_claude_run().await.unwrap().unwrap();
+
+        let tmp = tempfile::tempdir().unwrap();
+        let journal = RunJournal::open(&tmp.path().join("inflight.json"));
+        journal.insert(InFlightRun::new(&claimed));
+        journal.insert(InFlightRun::new(&run(
+            ClaudeRunStatus::Running,
+            ClaudeAction::Plan,
+            None,
+        )));
+        let workspace_root = tmp.path().join("workspaces");
+        let config = RunnerConfig::parse_from([
+            "flowstate-runner",
+            "--workspace-root",
+            workspace_root.to_str().unwrap(),
+        ]);
+        std::fs::create_dir_all(workspace_root.join(&claimed.id)).unwrap();
+
+        recover(&svc, &journal, "runner-1", &config).await;
+
+        let run = svc.get_claude_run(&claimed.id).await.unwrap();
+        assert_eq!(run.status, ClaudeRunStatus::Failed);
+        assert_eq!(run.error_message.as_deref(), Some(INTERRUPTED));
+        assert!(!workspace_root.join(&claimed.id).exists());
+        assert!(journal.runs().is_empty());
+    }
+}
@@ -0,0 +1,88 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use flowstate_core::task::Task;
+use flowstate_service::HttpService;
+
+use crate::pipeline::slugify;
+
+/// Repository directory a task's documents are mirrored into, relative to
+/// the repo root. Falls back to the task id when the title has no usable
+/// characters.
+pub fn docs_dir(task: &Task) -> String {
+    let slug = slugify(&task.title);
+    let slug = if slug.is_empty() {
+        task.id.as_str()
+    } else {
+        &slug
+    };
+    format!("docs/flowstate/{slug}")
+}
+
+/// Copy the task's spec, plan and verification report from the server into
+/// [`docs_dir`] in the workspace. The server stays the source of truth;
+/// documents it doesn't have yet are skipped. Returns the directory written.
+pub async fn mirror_task_docs(service: &HttpService, task: &Task, ws_dir: &Path) -> Result<String> {
+    let spec = service.read_task_spec(&task.id).await.ok();
+    let plan = service.read_task_plan(&task.id).await.ok();
+    let verification = service.read_task_verification(&task.id).await.ok();
+
+    let dir = docs_dir(task);
+    write_docs(
+        &ws_dir.join(&dir),
+        &[
+            ("specification.md", spec.as_deref()),
+            ("plan.md", plan.as_deref()),
+            ("verification.md", verification.as_deref()),
+        ],
+    )?;
+    Ok(dir)
+}
+
+fn write_docs(dir: &Path, docs: &[(&str, Option<&str>)]) -> Result<()> {
+    let present: Vec<_> = docs
+        .iter()
+        .filter_map(|(name, content)| Some((name, (*content)?)))
+        .filter(|(_, content)| !content.trim().is_empty())
+        .collect();
+    if present.is_empty() {
+        return Ok(());
+    }
+    std::fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    for (name, content) in present {
+        let path = dir.join(name);
+        std::fs::write(&path, content).with_context(|| format!("writing {}", path.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_only_documents_with_content() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().join("docs/flowstate/add-login");
+        write_docs(
+            &dir,
+            &[
+                ("specification.md", Some("# Spec")),
+                ("plan.md", Some("  \n")),
+                ("verification.md", None),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(dir.join("specification.md")).unwrap(),
+            "# Spec"
+        );
+        assert!(!dir.join("plan.md").exists());
+        assert!(!dir.join("verification.md").exists());
+
+        let empty = tmp.path().join("docs/flowstate/empty");
+        write_docs(&empty, &[("plan.md", None)]).unwrap();
+        assert!(!empty.exists());
+    }
+}
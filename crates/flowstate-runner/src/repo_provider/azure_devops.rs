@@ -0,0 +1,576 @@
+use std::path::Path;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::process::Command;
+use tracing::info;
+
+use super::{PrComment, ProviderError, PullRequest, RepoProvider};
+
+const API_VERSION: &str = "7.1";
+
+/// Azure Repos rejects pull request descriptions longer than this.
+const MAX_DESCRIPTION_CHARS: usize = 4000;
+
+#[derive(Debug)]
+pub struct AzureDevOpsProvider {
+    /// Organization URL (e.g. "https://dev.azure.com/myorg").
+    org_url: String,
+    /// Project parsed from the repo URL, still percent-encoded.
+    project: String,
+    /// Repository name parsed from the repo URL, still percent-encoded.
+    repo: String,
+    /// Personal access token, sent as the password of HTTP basic auth.
+    token: String,
+    /// HTTP client (configured for TLS).
+    client: reqwest::Client,
+    /// Whether to skip TLS verification for git commands.
+    skip_tls_verify: bool,
+}
+
+impl AzureDevOpsProvider {
+    pub fn new(
+        repo_url: &str,
+        token: Option<String>,
+        skip_tls_verify: bool,
+    ) -> Result<Self, ProviderError> {
+        let token = token.ok_or_else(|| {
+            ProviderError::AuthFailed("Azure DevOps requires a personal access token".into())
+        })?;
+
+        let (org_url, project, repo) = parse_azure_devops_url(repo_url)?;
+
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(skip_tls_verify)
+            .user_agent("flowstate-runner")
+            .build()
+            .map_err(|e| ProviderError::Other(format!("HTTP client init: {e}")))?;
+
+        Ok(Self {
+            org_url,
+            project,
+            repo,
+            token,
+            client,
+            skip_tls_verify,
+        })
+    }
+
+    /// URL of `path` under the repository's Git API, with the API version set.
+    fn api_url(&self, path: &str) -> String {
+        let separator = if path.contains('?') { '&' } else { '?' };
+        format!(
+            "{}/{}/_apis/git/repositories/{}{path}{separator}api-version={API_VERSION}",
+            self.org_url, self.project, self.repo
+        )
+    }
+
+    async fn api_get(&self, path: &str) -> Result<reqwest::Response, ProviderError> {
+        self.client
+            .get(self.api_url(path))
+            .basic_auth("", Some(&self.token))
+            .send()
+            .await
+            .map_err(|e| ProviderError::Other(format!("HTTP request failed: {e}")))
+    }
+
+    async fn api_post<T: Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        body: &T,
+    ) -> Result<reqwest::Response, ProviderError> {
+        self.client
+            .post(self.api_url(path))
+            .basic_auth("", Some(&self.token))
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| ProviderError::Other(format!("HTTP request failed: {e}")))
+    }
+}
+
+/// Whether `url` points at Azure Repos (dev.azure.com or a legacy
+/// visualstudio.com organization).
+pub fn is_azure_devops_url(url: &str) -> bool {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_ascii_lowercase))
+        .is_some_and(|host| host == "dev.azure.com" || host.ends_with(".visualstudio.com"))
+}
+
+/// Parse an Azure Repos URL into (organization URL, project, repo).
+///
+/// Supports:
+///   https://dev.azure.com/org/project/_git/repo
+///   https://org@dev.azure.com/org/project/_git/repo
+///   https://org.visualstudio.com/project/_git/repo
+///   https://org.visualstudio.com/DefaultCollection/project/_git/repo
+fn parse_azure_devops_url(url: &str) -> Result<(String, String, String), ProviderError> {
+    let parsed =
+        url::Url::parse(url).map_err(|e| ProviderError::Other(format!("invalid URL: {e}")))?;
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| ProviderError::Other("URL has no host".into()))?
+        .to_ascii_lowercase();
+
+    let segments: Vec<&str> = parsed
+        .path_segments()
+        .ok_or_else(|| ProviderError::Other("URL has no path".into()))?
+        .filter(|s| !s.is_empty())
+        .collect();
+    let git = segments
+        .iter()
+        .position(|s| *s == "_git")
+        .filter(|&i| i > 0 && i + 1 < segments.len())
+        .ok_or_else(|| {
+            ProviderError::Other("URL must contain <project>/_git/<repo> path segments".into())
+        })?;
+    let project = segments[git - 1].to_string();
+    let repo = segments[git + 1]
+        .strip_suffix(".git")
+        .unwrap_or(segments[git + 1])
+        .to_string();
+
+    let org_url = if host == "dev.azure.com" {
+        if git < 2 {
+            return Err(ProviderError::Other(
+                "dev.azure.com URL must start with the organization".into(),
+            ));
+        }
+        format!("https://dev.azure.com/{}", segments[git - 2])
+    } else if host.ends_with(".visualstudio.com") {
+        format!("https://{host}")
+    } else {
+        return Err(ProviderError::Unsupported(format!(
+            "{host} is not an Azure DevOps host"
+        )));
+    };
+
+    Ok((org_url, project, repo))
+}
+
+/// Trim a description to the length Azure Repos accepts.
+fn truncate_description(body: &str) -> String {
+    if body.chars().count() <= MAX_DESCRIPTION_CHARS {
+        return body.to_string();
+    }
+    let marker = "\n\n…(truncated)";
+    let keep = MAX_DESCRIPTION_CHARS - marker.chars().count();
+    let mut out: String = body.chars().take(keep).collect();
+    out.push_str(marker);
+    out
+}
+
+/// Comment ids are only unique within their thread, so the thread id goes
+/// in the upper 32 bits.
+fn comment_id(thread_id: u64, comment_id: u64) -> u64 {
+    (thread_id << 32) | (comment_id & 0xffff_ffff)
+}
+
+fn to_pr_comment(thread_id: u64, context: Option<&ThreadContext>, c: AdoComment) -> PrComment {
+    PrComment {
+        id: comment_id(thread_id, c.id),
+        body: c.content.unwrap_or_default(),
+        author: c.author.unique_name.unwrap_or(c.author.display_name),
+        path: context.map(|ctx| ctx.file_path.trim_start_matches('/').to_string()),
+        line: context.and_then(|ctx| ctx.right_file_start.as_ref().map(|p| p.line)),
+        created_at: c.published_date,
+        updated_at: c.last_updated_date,
+    }
+}
+
+#[async_trait]
+impl RepoProvider for AzureDevOpsProvider {
+    fn name(&self) -> &str {
+        "azure_devops"
+    }
+
+    fn supports_url(&self, repo_url: &str) -> bool {
+        is_azure_devops_url(repo_url)
+    }
+
+    async fn preflight(&self) -> Result<(), ProviderError> {
+        // No external CLI needed for Azure DevOps — we use reqwest directly.
+        Ok(())
+    }
+
+    async fn check_auth(&self, _repo_url: &str) -> Result<(), ProviderError> {
+        let resp = self.api_get("").await?;
+        // A rejected PAT gets a 203 sign-in page rather than a 401
+        if resp.status() != reqwest::StatusCode::OK {
+            return Err(ProviderError::AuthFailed(format!(
+                "cannot access Azure DevOps repo {}/{} (status {})",
+                self.project,
+                self.repo,
+                resp.status()
+            )));
+        }
+
+        info!(
+            "azure devops: authenticated and repo {}/{} accessible",
+            self.project, self.repo
+        );
+        Ok(())
+    }
+
+    async fn push_branch(&self, work_dir: &Path, branch: &str) -> Result<(), ProviderError> {
+        let mut cmd = Command::new("git");
+        cmd.args(["push", "-u", "origin", branch]);
+        cmd.current_dir(work_dir);
+        if self.skip_tls_verify {
+            cmd.env("GIT_SSL_NO_VERIFY", "true");
+        }
+
+        let output = cmd
+            .output()
+            .await
+            .map_err(|e| ProviderError::PushFailed(format!("git push: {e}")))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(ProviderError::PushFailed(format!(
+                "git push failed: {stderr}"
+            )));
+        }
+
+        info!("pushed branch {branch} to origin");
+        Ok(())
+    }
+
+    async fn open_pull_request(
+        &self,
+        _work_dir: &Path,
+        branch: &str,
+        title: &str,
+        body: &str,
+        base: &str,
+    ) -> Result<PullRequest, ProviderError> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct CreatePr<'a> {
+            source_ref_name: String,
+            target_ref_name: String,
+            title: &'a str,
+            description: String,
+        }
+
+        let req_body = CreatePr {
+            source_ref_name: format!("refs/heads/{branch}"),
+            target_ref_name: format!("refs/heads/{base}"),
+            title,
+            description: truncate_description(body),
+        };
+
+        let resp = self.api_post("/pullrequests", &req_body).await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ProviderError::PrFailed(format!(
+                "Azure DevOps PR creation failed (status {status}): {text}"
+            )));
+        }
+
+        let pr: AdoPullRequest = resp
+            .json()
+            .await
+            .map_err(|e| ProviderError::PrFailed(format!("parse PR response: {e}")))?;
+        let url = format!(
+            "{}/pullrequest/{}",
+            pr.repository.web_url, pr.pull_request_id
+        );
+
+        info!("opened PR #{}: {url}", pr.pull_request_id);
+
+        Ok(PullRequest {
+            number: pr.pull_request_id,
+            url,
+            branch: branch.to_string(),
+        })
+    }
+
+    async fn list_pr_comments(
+        &self,
+        _repo_url: &str,
+        pr_number: u64,
+    ) -> Result<Vec<PrComment>, ProviderError> {
+        let resp = self
+            .api_get(&format!("/pullRequests/{pr_number}/threads"))
+            .await?;
+
+        if !resp.status().is_success() {
+            return Err(ProviderError::Other(format!(
+                "list PR threads failed (status {})",
+                resp.status()
+            )));
+        }
+
+        let threads: AdoList<AdoThread> = resp
+            .json()
+            .await
+            .map_err(|e| ProviderError::Other(format!("parse threads response: {e}")))?;
+
+        let comments = threads
+            .value
+            .into_iter()
+            .filter(|t| !t.is_deleted)
+            .flat_map(|t| {
+                let thread_id = t.id;
+                let context = t.thread_context;
+                t.comments
+                    .into_iter()
+                    // System comments record votes and pushes, not reviewer text
+                    .filter(|c| !c.is_deleted && c.comment_type.as_deref() != Some("system"))
+                    .map(move |c| to_pr_comment(thread_id, context.as_ref(), c))
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        Ok(comments)
+    }
+
+    async fn create_pr_comment(
+        &self,
+        _repo_url: &str,
+        pr_number: u64,
+        body: &str,
+    ) -> Result<PrComment, ProviderError> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct NewComment<'a> {
+            parent_comment_id: u64,
+            content: &'a str,
+            comment_type: &'a str,
+        }
+
+        #[derive(Serialize)]
+        struct NewThread<'a> {
+            comments: [NewComment<'a>; 1],
+            status: &'a str,
+        }
+
+        let resp = self
+            .api_post(
+                &format!("/pullRequests/{pr_number}/threads"),
+                &NewThread {
+                    comments: [NewComment {
+                        parent_comment_id: 0,
+                        content: body,
+                        comment_type: "text",
+                    }],
+                    status: "active",
+                },
+            )
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ProviderError::Other(format!(
+                "create comment failed (status {status}): {text}"
+            )));
+        }
+
+        let thread: AdoThread = resp
+            .json()
+            .await
+            .map_err(|e| ProviderError::Other(format!("parse thread response: {e}")))?;
+        let thread_id = thread.id;
+        let context = thread.thread_context;
+        thread
+            .comments
+            .into_iter()
+            .next()
+            .map(|c| to_pr_comment(thread_id, context.as_ref(), c))
+            .ok_or_else(|| ProviderError::Other("created thread has no comment".into()))
+    }
+}
+
+// Azure DevOps API response structs
+
+#[derive(Deserialize)]
+struct AdoList<T> {
+    value: Vec<T>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AdoPullRequest {
+    pull_request_id: u64,
+    repository: AdoRepository,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AdoRepository {
+    web_url: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AdoThread {
+    id: u64,
+    #[serde(default)]
+    comments: Vec<AdoComment>,
+    thread_context: Option<ThreadContext>,
+    #[serde(default)]
+    is_deleted: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ThreadContext {
+    file_path: String,
+    right_file_start: Option<FilePosition>,
+}
+
+#[derive(Deserialize)]
+struct FilePosition {
+    line: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AdoComment {
+    id: u64,
+    content: Option<String>,
+    author: AdoIdentity,
+    published_date: String,
+    last_updated_date: Option<String>,
+    comment_type: Option<String>,
+    #[serde(default)]
+    is_deleted: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AdoIdentity {
+    display_name: String,
+    unique_name: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_dev_azure_url() {
+        let (org, project, repo) =
+            parse_azure_devops_url("https://dev.azure.com/acme/Web%20App/_git/frontend").unwrap();
+        assert_eq!(org, "https://dev.azure.com/acme");
+        assert_eq!(project, "Web%20App");
+        assert_eq!(repo, "frontend");
+
+        // Clone URLs copied from the portal carry the organization as a user
+        let (org, project, repo) =
+            parse_azure_devops_url("https://acme@dev.azure.com/acme/web/_git/frontend.git")
+                .unwrap();
+        assert_eq!(org, "https://dev.azure.com/acme");
+        assert_eq!(project, "web");
+        assert_eq!(repo, "frontend");
+    }
+
+    #[test]
+    fn parse_visualstudio_url() {
+        let (org, project, repo) =
+            parse_azure_devops_url("https://acme.visualstudio.com/web/_git/frontend").unwrap();
+        assert_eq!(org, "https://acme.visualstudio.com");
+        assert_eq!(project, "web");
+        assert_eq!(repo, "frontend");
+
+        let (org, project, _) = parse_azure_devops_url(
+            "https://acme.visualstudio.com/DefaultCollection/web/_git/frontend",
+        )
+        .unwrap();
+        assert_eq!(org, "https://acme.visualstudio.com");
+        assert_eq!(project, "web");
+    }
+
+    #[test]
+    fn parse_rejects_other_urls() {
+        assert!(parse_azure_devops_url("https://dev.azure.com/acme/web").is_err());
+        assert!(parse_azure_devops_url("https://dev.azure.com/web/_git/frontend").is_err());
+        assert!(matches!(
+            parse_azure_devops_url("https://github.com/acme/web/_git/frontend"),
+            Err(ProviderError::Unsupported(_))
+        ));
+        assert!(parse_azure_devops_url("not-a-url").is_err());
+    }
+
+    #[test]
+    fn detects_azure_hosts() {
+        assert!(is_azure_devops_url(
+            "https://dev.azure.com/acme/web/_git/frontend"
+        ));
+        assert!(is_azure_devops_url(
+            "https://acme.visualstudio.com/web/_git/frontend"
+        ));
+        assert!(!is_azure_devops_url("https://github.com/acme/web"));
+        assert!(!is_azure_devops_url(
+            "https://evilvisualstudio.com/x/_git/y"
+        ));
+    }
+
+    #[test]
+    fn requires_token() {
+        let err =
+            AzureDevOpsProvider::new("https://dev.azure.com/acme/web/_git/frontend", None, false)
+                .unwrap_err();
+        assert!(matches!(err, ProviderError::AuthFailed(_)));
+    }
+
+    #[test]
+    fn api_url_formatting() {
+        let provider = AzureDevOpsProvider::new(
+            "https://dev.azure.com/acme/web/_git/frontend",
+            Some("pat".into()),
+            false,
+        )
+        .unwrap();
+        assert_eq!(provider.name(), "azure_devops");
+        assert_eq!(
+            provider.api_url("/pullrequests"),
+            "https://dev.azure.com/acme/web/_apis/git/repositories/frontend/pullrequests?api-version=7.1"
+        );
+        assert_eq!(
+            provider.api_url(""),
+            "https://dev.azure.com/acme/web/_apis/git/repositories/frontend?api-version=7.1"
+        );
+    }
+
+    #[test]
+    fn long_descriptions_are_truncated() {
+        assert_eq!(truncate_description("short"), "short");
+        let long = "é".repeat(MAX_DESCRIPTION_CHARS + 10);
+        let out = truncate_description(&long);
+        assert_eq!(out.chars().count(), MAX_DESCRIPTION_CHARS);
+        assert!(out.ends_with("(truncated)"));
+    }
+
+    #[test]
+    fn threads_map_to_comments() {
+        let json = r#"{"value": [
+            {"id": 7, "isDeleted": false,
+             "threadContext": {"filePath": "/src/main.rs", "rightFileStart": {"line": 12, "offset": 1}},
+             "comments": [
+                {"id": 1, "content": "Use a constant here", "commentType": "text",
+                 "author": {"displayName": "Ana", "uniqueName": "ana@acme.com"},
+                 "publishedDate": "2026-01-02T03:04:05Z", "lastUpdatedDate": "2026-01-02T03:04:05Z"},
+                {"id": 2, "content": "Voted approve", "commentType": "system",
+                 "author": {"displayName": "Ana"}, "publishedDate": "2026-01-02T03:05:00Z"}
+             ]}
+        ]}"#;
+        let threads: AdoList<AdoThread> = serde_json::from_str(json).unwrap();
+        let thread = threads.value.into_iter().next().unwrap();
+        let comment = to_pr_comment(
+            thread.id,
+            thread.thread_context.as_ref(),
+            thread.comments.into_iter().next().unwrap(),
+        );
+        assert_eq!(comment.id, comment_id(7, 1));
+        assert_ne!(comment.id, comment_id(8, 1));
+        assert_eq!(comment.author, "ana@acme.com");
+        assert_eq!(comment.path.as_deref(), Some("src/main.rs"));
+        assert_eq!(comment.line, Some(12));
+    }
+}
@@ -5,6 +5,8 @@ use serde::{Deserialize, Serialize};
 use tokio::process::Command;
 use tracing::info;
 
+use flowstate_core::task_pr::{self, ChecksStatus, MergeStrategy, PrCheck};
+
 use super::{PrComment, PrReview, ProviderError, PullRequest, RepoProvider, ReviewState};
 
 #[derive(Debug)]
@@ -76,6 +78,71 @@ impl GiteaProvider {
             .await
             .map_err(|e| ProviderError::Other(format!("HTTP request failed: {e}")))
     }
+
+    async fn api_patch<T: Serialize + Send + Sync>(
+        &self,
+        path: &str,
+        body: &T,
+    ) -> Result<reqwest::Response, ProviderError> {
+        self.client
+            .patch(self.api_url(path))
+            .header("Authorization", format!("token {}", self.token))
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| ProviderError::Other(format!("HTTP request failed: {e}")))
+    }
+
+    async fn create_pr(
+        &self,
+        branch: &str,
+        title: &str,
+        body: &str,
+        base: &str,
+    ) -> Result<PullRequest, ProviderError> {
+        #[derive(Serialize)]
+        struct CreatePr<'a> {
+            title: &'a str,
+            body: &'a str,
+            head: &'a str,
+            base: &'a str,
+        }
+
+        let req_body = CreatePr {
+            title,
+            body,
+            head: branch,
+            base,
+        };
+
+        let resp = self
+            .api_post(
+                &format!("/repos/{}/{}/pulls", self.owner, self.repo),
+                &req_body,
+            )
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ProviderError::PrFailed(format!(
+                "Gitea PR creation failed (status {status}): {text}"
+            )));
+        }
+
+        let pr: GiteaPr = resp
+            .json()
+            .await
+            .map_err(|e| ProviderError::PrFailed(format!("parse PR response: {e}")))?;
+
+        info!("opened PR #{}: {}", pr.number, pr.html_url);
+
+        Ok(PullRequest {
+            number: pr.number,
+            url: pr.html_url,
+            branch: branch.to_string(),
+        })
+    }
 }
 
 /// Parse a Gitea repo URL into (base_url, owner, repo).
@@ -194,48 +261,64 @@ impl RepoProvider for GiteaProvider {
         body: &str,
         base: &str,
     ) -> Result<PullRequest, ProviderError> {
+        self.create_pr(branch, title, body, base).await
+    }
+
+    fn supports_draft_prs(&self) -> bool {
+        true
+    }
+
+    /// Gitea has no draft flag; a `WIP:` title prefix keeps the pull
+    /// request from being merged until it is removed.
+    async fn open_draft_pull_request(
+        &self,
+        _work_dir: &Path,
+        branch: &str,
+        title: &str,
+        body: &str,
+        base: &str,
+    ) -> Result<PullRequest, ProviderError> {
+        let title = format!("{}{title}", task_pr::WIP_PREFIX);
+        self.create_pr(branch, &title, body, base).await
+    }
+
+    async fn mark_pull_request_ready(
+        &self,
+        _repo_url: &str,
+        pr_number: u64,
+    ) -> Result<(), ProviderError> {
         #[derive(Serialize)]
-        struct CreatePr<'a> {
+        struct TitleBody<'a> {
             title: &'a str,
-            body: &'a str,
-            head: &'a str,
-            base: &'a str,
         }
 
-        let req_body = CreatePr {
-            title,
-            body,
-            head: branch,
-            base,
+        let path = format!("/repos/{}/{}/pulls/{pr_number}", self.owner, self.repo);
+        let resp = self.api_get(&path).await?;
+        if !resp.status().is_success() {
+            return Err(ProviderError::Other(format!(
+                "get PR failed (status {})",
+                resp.status()
+            )));
+        }
+        let pr: GiteaPrTitle = resp
+            .json()
+            .await
+            .map_err(|e| ProviderError::Other(format!("parse PR: {e}")))?;
+        let Some(title) = task_pr::strip_wip_prefix(&pr.title) else {
+            return Ok(());
         };
 
-        let resp = self
-            .api_post(
-                &format!("/repos/{}/{}/pulls", self.owner, self.repo),
-                &req_body,
-            )
-            .await?;
-
+        let resp = self.api_patch(&path, &TitleBody { title }).await?;
         if !resp.status().is_success() {
             let status = resp.status();
             let text = resp.text().await.unwrap_or_default();
-            return Err(ProviderError::PrFailed(format!(
-                "Gitea PR creation failed (status {status}): {text}"
+            return Err(ProviderError::Other(format!(
+                "mark PR ready failed (status {status}): {text}"
             )));
         }
 
-        let pr: GiteaPr = resp
-            .json()
-            .await
-            .map_err(|e| ProviderError::PrFailed(format!("parse PR response: {e}")))?;
-
-        info!("opened PR #{}: {}", pr.number, pr.html_url);
-
-        Ok(PullRequest {
-            number: pr.number,
-            url: pr.html_url,
-            branch: branch.to_string(),
-        })
+        info!("marked PR #{pr_number} ready for review");
+        Ok(())
     }
 
     async fn get_pr_diff(&self, _repo_url: &str, pr_number: u64) -> Result<String, ProviderError> {
@@ -343,6 +426,122 @@ impl RepoProvider for GiteaProvider {
         })
     }
 
+    async fn merge_pull_request(
+        &self,
+        _repo_url: &str,
+        pr_number: u64,
+        strategy: MergeStrategy,
+    ) -> Result<(), ProviderError> {
+        #[derive(Serialize)]
+        struct MergeBody<'a> {
+            #[serde(rename = "Do")]
+            action: &'a str,
+        }
+
+        let resp = self
+            .api_post(
+                &format!(
+                    "/repos/{}/{}/pulls/{pr_number}/merge",
+                    self.owner, self.repo
+                ),
+                &MergeBody {
+                    action: strategy.as_str(),
+                },
+            )
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ProviderError::MergeFailed(format!(
+                "Gitea merge failed (status {status}): {text}"
+            )));
+        }
+
+        info!("merged PR #{pr_number} ({})", strategy.as_str());
+        Ok(())
+    }
+
+    async fn close_pull_request(
+        &self,
+        _repo_url: &str,
+        pr_number: u64,
+    ) -> Result<(), ProviderError> {
+        #[derive(Serialize)]
+        struct StateBody<'a> {
+            state: &'a str,
+        }
+
+        let resp = self
+            .api_patch(
+                &format!("/repos/{}/{}/pulls/{pr_number}", self.owner, self.repo),
+                &StateBody { state: "closed" },
+            )
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            return Err(ProviderError::Other(format!(
+                "close PR failed (status {status}): {text}"
+            )));
+        }
+
+        info!("closed PR #{pr_number}");
+        Ok(())
+    }
+
+    async fn get_pr_checks(
+        &self,
+        _repo_url: &str,
+        pr_number: u64,
+    ) -> Result<Vec<PrCheck>, ProviderError> {
+        let resp = self
+            .api_get(&format!(
+                "/repos/{}/{}/pulls/{pr_number}",
+                self.owner, self.repo
+            ))
+            .await?;
+        if !resp.status().is_success() {
+            return Err(ProviderError::Other(format!(
+                "get PR failed (status {})",
+                resp.status()
+            )));
+        }
+        let pr: GiteaPrHead = resp
+            .json()
+            .await
+            .map_err(|e| ProviderError::Other(format!("parse PR: {e}")))?;
+
+        // Gitea Actions and external CI both report commit statuses
+        let resp = self
+            .api_get(&format!(
+                "/repos/{}/{}/commits/{}/status",
+                self.owner, self.repo, pr.head.sha
+            ))
+            .await?;
+        if !resp.status().is_success() {
+            return Err(ProviderError::Other(format!(
+                "get commit status failed (status {})",
+                resp.status()
+            )));
+        }
+        let combined: GiteaCombinedStatus = resp
+            .json()
+            .await
+            .map_err(|e| ProviderError::Other(format!("parse commit status: {e}")))?;
+
+        Ok(combined
+            .statuses
+            .into_iter()
+            .map(|s| PrCheck {
+                status: ChecksStatus::from_commit_state(&s.status),
+                name: s.context,
+                url: s.target_url.filter(|u| !u.is_empty()),
+            })
+            .collect())
+    }
+
     async fn list_pr_reviews(
         &self,
         _repo_url: &str,
@@ -482,6 +681,34 @@ struct GiteaReview {
     submitted_at: Option<String>,
 }
 
+#[derive(Deserialize)]
+struct GiteaPrTitle {
+    title: String,
+}
+
+#[derive(Deserialize)]
+struct GiteaPrHead {
+    head: GiteaBranch,
+}
+
+#[derive(Deserialize)]
+struct GiteaBranch {
+    sha: String,
+}
+
+#[derive(Deserialize)]
+struct GiteaCombinedStatus {
+    #[serde(default)]
+    statuses: Vec<GiteaCommitStatus>,
+}
+
+#[derive(Deserialize)]
+struct GiteaCommitStatus {
+    context: String,
+    status: String,
+    target_url: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
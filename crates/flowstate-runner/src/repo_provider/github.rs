@@ -5,8 +5,32 @@ use serde::Deserialize;
 use tokio::process::Command;
 use tracing::info;
 
+use flowstate_core::task_pr::{ChecksStatus, MergeStrategy, PrCheck};
+
 use super::{PrComment, PrReview, ProviderError, PullRequest, RepoProvider, ReviewState};
 
+/// Where `gh pr create` finds the head branch of a pull request.
+enum PrSource<'a> {
+    /// Pushed to the workspace's `origin`.
+    Workspace(&'a Path),
+    /// Pushed to `fork_owner`'s fork of `repo`, given as `owner/name`.
+    Fork { repo: String, fork_owner: &'a str },
+}
+
+/// The parts of `POST /repos/{owner}/{repo}/forks` the runner uses.
+#[derive(Deserialize)]
+struct ForkResponse {
+    full_name: String,
+    clone_url: String,
+    ssh_url: String,
+    owner: ForkOwner,
+}
+
+#[derive(Deserialize)]
+struct ForkOwner {
+    login: String,
+}
+
 pub struct GitHubProvider {
     /// Optional PAT used to authenticate `gh` CLI calls via GH_TOKEN env var.
     /// When set, this overrides any local `gh auth` session on the host.
@@ -27,7 +51,8 @@ impl GitHubProvider {
 
     /// Parse "owner/repo" from a GitHub URL.
     fn parse_owner_repo(repo_url: &str) -> Result<(String, String), ProviderError> {
-        let url = repo_url.strip_suffix(".git").unwrap_or(repo_url);
+        let repo_url = super::https_repo_url(repo_url)?;
+        let url = repo_url.strip_suffix(".git").unwrap_or(&repo_url);
         let parts: Vec<&str> = url.trim_end_matches('/').rsplitn(3, '/').collect();
         if parts.len() < 2 {
             return Err(ProviderError::Other("invalid GitHub URL".into()));
@@ -54,6 +79,91 @@ impl GitHubProvider {
 
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
     }
+
+    /// Push `branch` to `remote` with the token already injected into the
+    /// remote URL by `workspace::ensure_repo` / `workspace::set_fork_remote`.
+    async fn push(&self, work_dir: &Path, remote: &str, branch: &str) -> Result<(), ProviderError> {
+        let output = Command::new("git")
+            .args(["push", "-u", remote, branch])
+            .current_dir(work_dir)
+            .output()
+            .await
+            .map_err(|e| ProviderError::PushFailed(format!("git push: {e}")))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(ProviderError::PushFailed(format!(
+                "git push failed: {stderr}"
+            )));
+        }
+
+        info!("pushed branch {branch} to {remote}");
+        Ok(())
+    }
+
+    /// Open a PR with `gh pr create`, as a draft when `draft`.
+    async fn create_pr(
+        &self,
+        source: PrSource<'_>,
+        branch: &str,
+        title: &str,
+        body: &str,
+        base: &str,
+        draft: bool,
+    ) -> Result<PullRequest, ProviderError> {
+        let mut cmd = Command::new("gh");
+        cmd.args([
+            "pr", "create", "--title", title, "--body", body, "--base", base,
+        ]);
+        match source {
+            PrSource::Workspace(work_dir) => {
+                cmd.args(["--head", branch]).current_dir(work_dir);
+            }
+            // Naming the repository keeps gh from guessing between the
+            // workspace's origin and fork remotes
+            PrSource::Fork { repo, fork_owner } => {
+                cmd.args(["--head", &format!("{fork_owner}:{branch}"), "--repo", &repo]);
+            }
+        }
+        if draft {
+            cmd.arg("--draft");
+        }
+        self.apply_token(&mut cmd);
+
+        let output = cmd
+            .output()
+            .await
+            .map_err(|e| ProviderError::PrFailed(format!("gh pr create: {e}")))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(ProviderError::PrFailed(format!(
+                "gh pr create failed: {stderr}"
+            )));
+        }
+
+        // gh pr create prints the PR URL to stdout, e.g.:
+        // https://github.com/owner/repo/pull/42
+        let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let number = url
+            .rsplit('/')
+            .next()
+            .and_then(|s| s.parse::<u64>().ok())
+            .ok_or_else(|| {
+                ProviderError::PrFailed(format!("could not parse PR number from: {url}"))
+            })?;
+
+        info!(
+            "opened {}PR #{number}: {url}",
+            if draft { "draft " } else { "" }
+        );
+
+        Ok(PullRequest {
+            number,
+            url,
+            branch: branch.to_string(),
+        })
+    }
 }
 
 #[async_trait]
@@ -128,6 +238,7 @@ impl RepoProvider for GitHubProvider {
         }
 
         // Check repo access
+        let repo_url = &super::https_repo_url(repo_url)?;
         let mut view_cmd = Command::new("gh");
         view_cmd.args(["repo", "view", repo_url, "--json", "nameWithOwner"]);
         self.apply_token(&mut view_cmd);
@@ -155,71 +266,146 @@ impl RepoProvider for GitHubProvider {
     }
 
     async fn push_branch(&self, work_dir: &Path, branch: &str) -> Result<(), ProviderError> {
-        // git push uses the token already injected into the remote URL
-        // by workspace::ensure_repo / inject_token
-        let output = Command::new("git")
-            .args(["push", "-u", "origin", branch])
-            .current_dir(work_dir)
-            .output()
-            .await
-            .map_err(|e| ProviderError::PushFailed(format!("git push: {e}")))?;
+        self.push(work_dir, "origin", branch).await
+    }
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(ProviderError::PushFailed(format!(
-                "git push failed: {stderr}"
+    async fn open_pull_request(
+        &self,
+        work_dir: &Path,
+        branch: &str,
+        title: &str,
+        body: &str,
+        base: &str,
+    ) -> Result<PullRequest, ProviderError> {
+        self.create_pr(
+            PrSource::Workspace(work_dir),
+            branch,
+            title,
+            body,
+            base,
+            false,
+        )
+        .await
+    }
+
+    fn supports_draft_prs(&self) -> bool {
+        true
+    }
+
+    async fn open_draft_pull_request(
+        &self,
+        work_dir: &Path,
+        branch: &str,
+        title: &str,
+        body: &str,
+        base: &str,
+    ) -> Result<PullRequest, ProviderError> {
+        self.create_pr(
+            PrSource::Workspace(work_dir),
+            branch,
+            title,
+            body,
+            base,
+            true,
+        )
+        .await
+    }
+
+    fn supports_forks(&self) -> bool {
+        true
+    }
+
+    async fn ensure_fork(&self, repo_url: &str, owner: &str) -> Result<String, ProviderError> {
+        let (upstream, repo) = Self::parse_owner_repo(repo_url)?;
+        if owner.eq_ignore_ascii_case(&upstream) {
+            return Err(ProviderError::Other(format!(
+                "{owner} owns {upstream}/{repo} and cannot fork it"
             )));
         }
 
-        info!("pushed branch {branch} to origin");
-        Ok(())
+        // Forking into the token's own account takes no organization; an
+        // existing fork is returned as it is
+        let viewer = self.gh_api(&["user", "--jq", ".login"]).await?;
+        let endpoint = format!("repos/{upstream}/{repo}/forks");
+        let organization = format!("organization={owner}");
+        let mut args = vec!["-X", "POST", endpoint.as_str()];
+        if !owner.eq_ignore_ascii_case(viewer.trim()) {
+            args.extend(["-f", organization.as_str()]);
+        }
+        let json = self.gh_api(&args).await?;
+        let fork: ForkResponse = serde_json::from_str(&json)
+            .map_err(|e| ProviderError::Other(format!("parse fork: {e}")))?;
+        if !fork.owner.login.eq_ignore_ascii_case(owner) {
+            return Err(ProviderError::Other(format!(
+                "fork landed in {}, not {owner}",
+                fork.full_name
+            )));
+        }
+
+        info!("using fork {}", fork.full_name);
+        Ok(if super::is_ssh_url(repo_url) {
+            fork.ssh_url
+        } else {
+            fork.clone_url
+        })
     }
 
-    async fn open_pull_request(
+    async fn push_branch_to_fork(
         &self,
         work_dir: &Path,
         branch: &str,
+    ) -> Result<(), ProviderError> {
+        self.push(work_dir, super::FORK_REMOTE, branch).await
+    }
+
+    async fn open_fork_pull_request(
+        &self,
+        repo_url: &str,
+        fork_owner: &str,
+        branch: &str,
         title: &str,
         body: &str,
         base: &str,
+        draft: bool,
     ) -> Result<PullRequest, ProviderError> {
+        let (owner, repo) = Self::parse_owner_repo(repo_url)?;
+        let source = PrSource::Fork {
+            repo: format!("{owner}/{repo}"),
+            fork_owner,
+        };
+        self.create_pr(source, branch, title, body, base, draft)
+            .await
+    }
+
+    async fn mark_pull_request_ready(
+        &self,
+        repo_url: &str,
+        pr_number: u64,
+    ) -> Result<(), ProviderError> {
+        let (owner, repo) = Self::parse_owner_repo(repo_url)?;
         let mut cmd = Command::new("gh");
         cmd.args([
-            "pr", "create", "--title", title, "--body", body, "--head", branch, "--base", base,
+            "pr",
+            "ready",
+            &pr_number.to_string(),
+            "--repo",
+            &format!("{owner}/{repo}"),
         ]);
-        cmd.current_dir(work_dir);
         self.apply_token(&mut cmd);
 
         let output = cmd
             .output()
             .await
-            .map_err(|e| ProviderError::PrFailed(format!("gh pr create: {e}")))?;
-
+            .map_err(|e| ProviderError::Other(format!("gh pr ready: {e}")))?;
         if !output.status.success() {
             let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(ProviderError::PrFailed(format!(
-                "gh pr create failed: {stderr}"
+            return Err(ProviderError::Other(format!(
+                "gh pr ready failed: {stderr}"
             )));
         }
 
-        // gh pr create prints the PR URL to stdout, e.g.:
-        // https://github.com/owner/repo/pull/42
-        let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        let number = url
-            .rsplit('/')
-            .next()
-            .and_then(|s| s.parse::<u64>().ok())
-            .ok_or_else(|| {
-                ProviderError::PrFailed(format!("could not parse PR number from: {url}"))
-            })?;
-
-        info!("opened PR #{number}: {url}");
-
-        Ok(PullRequest {
-            number,
-            url,
-            branch: branch.to_string(),
-        })
+        info!("marked PR #{pr_number} ready for review");
+        Ok(())
     }
 
     async fn get_pr_diff(&self, repo_url: &str, pr_number: u64) -> Result<String, ProviderError> {
@@ -324,6 +510,90 @@ impl RepoProvider for GitHubProvider {
         })
     }
 
+    async fn merge_pull_request(
+        &self,
+        repo_url: &str,
+        pr_number: u64,
+        strategy: MergeStrategy,
+    ) -> Result<(), ProviderError> {
+        let (owner, repo) = Self::parse_owner_repo(repo_url)?;
+        self.gh_api(&[
+            "-X",
+            "PUT",
+            &format!("repos/{owner}/{repo}/pulls/{pr_number}/merge"),
+            "-f",
+            &format!("merge_method={}", strategy.as_str()),
+        ])
+        .await
+        .map_err(|e| ProviderError::MergeFailed(e.to_string()))?;
+
+        info!("merged PR #{pr_number} ({})", strategy.as_str());
+        Ok(())
+    }
+
+    async fn close_pull_request(
+        &self,
+        repo_url: &str,
+        pr_number: u64,
+    ) -> Result<(), ProviderError> {
+        let (owner, repo) = Self::parse_owner_repo(repo_url)?;
+        self.gh_api(&[
+            "-X",
+            "PATCH",
+            &format!("repos/{owner}/{repo}/pulls/{pr_number}"),
+            "-f",
+            "state=closed",
+        ])
+        .await?;
+
+        info!("closed PR #{pr_number}");
+        Ok(())
+    }
+
+    async fn get_pr_checks(
+        &self,
+        repo_url: &str,
+        pr_number: u64,
+    ) -> Result<Vec<PrCheck>, ProviderError> {
+        let (owner, repo) = Self::parse_owner_repo(repo_url)?;
+        let sha = self
+            .gh_api(&[
+                &format!("repos/{owner}/{repo}/pulls/{pr_number}"),
+                "--jq",
+                ".head.sha",
+            ])
+            .await?;
+        let sha = sha.trim();
+
+        // Checks from GitHub Actions and apps, then legacy commit statuses
+        let runs: GhCheckRuns = serde_json::from_str(
+            &self
+                .gh_api(&[&format!(
+                    "repos/{owner}/{repo}/commits/{sha}/check-runs?per_page=100"
+                )])
+                .await?,
+        )
+        .map_err(|e| ProviderError::Other(format!("parse check runs: {e}")))?;
+        let combined: GhCombinedStatus = serde_json::from_str(
+            &self
+                .gh_api(&[&format!("repos/{owner}/{repo}/commits/{sha}/status")])
+                .await?,
+        )
+        .map_err(|e| ProviderError::Other(format!("parse commit status: {e}")))?;
+
+        let runs = runs.check_runs.into_iter().map(|r| PrCheck {
+            status: ChecksStatus::from_check_run(&r.status, r.conclusion.as_deref()),
+            name: r.name,
+            url: r.html_url,
+        });
+        let statuses = combined.statuses.into_iter().map(|s| PrCheck {
+            status: ChecksStatus::from_commit_state(&s.state),
+            name: s.context,
+            url: s.target_url,
+        });
+        Ok(runs.chain(statuses).collect())
+    }
+
     async fn list_pr_reviews(
         &self,
         repo_url: &str,
@@ -467,6 +737,31 @@ struct GhReview {
     submitted_at: Option<String>,
 }
 
+#[derive(Deserialize)]
+struct GhCheckRuns {
+    check_runs: Vec<GhCheckRun>,
+}
+
+#[derive(Deserialize)]
+struct GhCheckRun {
+    name: String,
+    status: String,
+    conclusion: Option<String>,
+    html_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GhCombinedStatus {
+    statuses: Vec<GhCommitStatus>,
+}
+
+#[derive(Deserialize)]
+struct GhCommitStatus {
+    context: String,
+    state: String,
+    target_url: Option<String>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -487,6 +782,14 @@ mod tests {
         assert_eq!(repo, "repo");
     }
 
+    #[test]
+    fn parse_owner_repo_ssh() {
+        let (owner, repo) =
+            GitHubProvider::parse_owner_repo("git@github.com:user/repo.git").unwrap();
+        assert_eq!(owner, "user");
+        assert_eq!(repo, "repo");
+    }
+
     #[test]
     fn parse_owner_repo_with_trailing_slash() {
         let (owner, repo) =
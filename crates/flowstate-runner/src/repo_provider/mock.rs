@@ -2,6 +2,7 @@ use std::path::Path;
 use std::sync::atomic::{AtomicU64, Ordering};
 
 use async_trait::async_trait;
+use flowstate_core::task_pr::{MergeStrategy, PrCheck};
 
 use super::{ProviderError, PullRequest, RepoProvider};
 
@@ -11,6 +12,7 @@ pub struct MockRepoProvider {
     pr_counter: AtomicU64,
     push_fail: bool,
     pr_fail: bool,
+    checks: Vec<PrCheck>,
 }
 
 impl Default for MockRepoProvider {
@@ -25,6 +27,7 @@ impl MockRepoProvider {
             pr_counter: AtomicU64::new(1),
             push_fail: false,
             pr_fail: false,
+            checks: Vec::new(),
         }
     }
 
@@ -37,6 +40,12 @@ impl MockRepoProvider {
         self.pr_fail = true;
         self
     }
+
+    /// Report `checks` for every pull request.
+    pub fn with_checks(mut self, checks: Vec<PrCheck>) -> Self {
+        self.checks = checks;
+        self
+    }
 }
 
 #[async_trait]
@@ -82,4 +91,86 @@ impl RepoProvider for MockRepoProvider {
             branch: branch.to_string(),
         })
     }
+
+    fn supports_draft_prs(&self) -> bool {
+        true
+    }
+
+    async fn open_draft_pull_request(
+        &self,
+        work_dir: &Path,
+        branch: &str,
+        title: &str,
+        body: &str,
+        base: &str,
+    ) -> Result<PullRequest, ProviderError> {
+        self.open_pull_request(work_dir, branch, title, body, base)
+            .await
+    }
+
+    fn supports_forks(&self) -> bool {
+        true
+    }
+
+    async fn ensure_fork(&self, _repo_url: &str, owner: &str) -> Result<String, ProviderError> {
+        Ok(format!("https://github.com/{owner}/repo.git"))
+    }
+
+    async fn push_branch_to_fork(
+        &self,
+        work_dir: &Path,
+        branch: &str,
+    ) -> Result<(), ProviderError> {
+        self.push_branch(work_dir, branch).await
+    }
+
+    async fn open_fork_pull_request(
+        &self,
+        _repo_url: &str,
+        _fork_owner: &str,
+        branch: &str,
+        title: &str,
+        body: &str,
+        base: &str,
+        _draft: bool,
+    ) -> Result<PullRequest, ProviderError> {
+        self.open_pull_request(Path::new("."), branch, title, body, base)
+            .await
+    }
+
+    async fn mark_pull_request_ready(
+        &self,
+        _repo_url: &str,
+        _pr_number: u64,
+    ) -> Result<(), ProviderError> {
+        Ok(())
+    }
+
+    async fn merge_pull_request(
+        &self,
+        _repo_url: &str,
+        _pr_number: u64,
+        _strategy: MergeStrategy,
+    ) -> Result<(), ProviderError> {
+        if self.pr_fail {
+            return Err(ProviderError::MergeFailed("mock merge failure".into()));
+        }
+        Ok(())
+    }
+
+    async fn close_pull_request(
+        &self,
+        _repo_url: &str,
+        _pr_number: u64,
+    ) -> Result<(), ProviderError> {
+        Ok(())
+    }
+
+    async fn get_pr_checks(
+        &self,
+        _repo_url: &str,
+        _pr_number: u64,
+    ) -> Result<Vec<PrCheck>, ProviderError> {
+        Ok(self.checks.clone())
+    }
 }
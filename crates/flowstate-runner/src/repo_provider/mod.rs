@@ -1,3 +1,4 @@
+pub mod azure_devops;
 pub mod gitea;
 pub mod github;
 pub mod mock;
@@ -6,6 +7,7 @@ use std::path::Path;
 
 use async_trait::async_trait;
 use flowstate_core::project::ProviderType;
+use flowstate_core::task_pr::{MergeStrategy, PrCheck};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
@@ -20,6 +22,9 @@ pub enum ProviderError {
     #[error("pr creation failed: {0}")]
     PrFailed(String),
 
+    #[error("pr merge failed: {0}")]
+    MergeFailed(String),
+
     #[error("unsupported repo URL: {0}")]
     Unsupported(String),
 
@@ -30,6 +35,10 @@ pub enum ProviderError {
     Other(String),
 }
 
+/// The workspace remote that points at the project's fork, alongside
+/// `origin` for the upstream repository.
+pub const FORK_REMOTE: &str = "fork";
+
 #[derive(Debug, Clone)]
 pub struct PullRequest {
     pub number: u64,
@@ -93,6 +102,84 @@ pub trait RepoProvider: Send + Sync {
         base: &str,
     ) -> Result<PullRequest, ProviderError>;
 
+    /// Whether this provider can open draft pull requests.
+    fn supports_draft_prs(&self) -> bool {
+        false
+    }
+
+    /// Open a pull request as a draft, not yet ready for review.
+    async fn open_draft_pull_request(
+        &self,
+        _work_dir: &Path,
+        _branch: &str,
+        _title: &str,
+        _body: &str,
+        _base: &str,
+    ) -> Result<PullRequest, ProviderError> {
+        Err(ProviderError::NotSupported(format!(
+            "{} does not support open_draft_pull_request",
+            self.name()
+        )))
+    }
+
+    /// Whether this provider can push to a fork and open pull requests from it.
+    fn supports_forks(&self) -> bool {
+        false
+    }
+
+    /// Fork `repo_url` into `owner`, a user or organization, unless that
+    /// fork already exists, and return the URL to push to it. The URL is an
+    /// SSH remote when `repo_url` is.
+    async fn ensure_fork(&self, _repo_url: &str, _owner: &str) -> Result<String, ProviderError> {
+        Err(ProviderError::NotSupported(format!(
+            "{} does not support ensure_fork",
+            self.name()
+        )))
+    }
+
+    /// Push `branch` to the workspace's [`FORK_REMOTE`].
+    async fn push_branch_to_fork(
+        &self,
+        _work_dir: &Path,
+        _branch: &str,
+    ) -> Result<(), ProviderError> {
+        Err(ProviderError::NotSupported(format!(
+            "{} does not support push_branch_to_fork",
+            self.name()
+        )))
+    }
+
+    /// Open a pull request on `repo_url` from `branch` on `fork_owner`'s
+    /// fork, as a draft when `draft`.
+    #[allow(clippy::too_many_arguments)]
+    async fn open_fork_pull_request(
+        &self,
+        _repo_url: &str,
+        _fork_owner: &str,
+        _branch: &str,
+        _title: &str,
+        _body: &str,
+        _base: &str,
+        _draft: bool,
+    ) -> Result<PullRequest, ProviderError> {
+        Err(ProviderError::NotSupported(format!(
+            "{} does not support open_fork_pull_request",
+            self.name()
+        )))
+    }
+
+    /// Mark a draft pull request ready for review.
+    async fn mark_pull_request_ready(
+        &self,
+        _repo_url: &str,
+        _pr_number: u64,
+    ) -> Result<(), ProviderError> {
+        Err(ProviderError::NotSupported(format!(
+            "{} does not support mark_pull_request_ready",
+            self.name()
+        )))
+    }
+
     /// Get the diff for a pull request as a unified diff string.
     async fn get_pr_diff(&self, _repo_url: &str, _pr_number: u64) -> Result<String, ProviderError> {
         Err(ProviderError::NotSupported(format!(
@@ -138,6 +225,43 @@ pub trait RepoProvider: Send + Sync {
         )))
     }
 
+    /// Merge a pull request into its base branch.
+    async fn merge_pull_request(
+        &self,
+        _repo_url: &str,
+        _pr_number: u64,
+        _strategy: MergeStrategy,
+    ) -> Result<(), ProviderError> {
+        Err(ProviderError::NotSupported(format!(
+            "{} does not support merge_pull_request",
+            self.name()
+        )))
+    }
+
+    /// Close a pull request without merging it.
+    async fn close_pull_request(
+        &self,
+        _repo_url: &str,
+        _pr_number: u64,
+    ) -> Result<(), ProviderError> {
+        Err(ProviderError::NotSupported(format!(
+            "{} does not support close_pull_request",
+            self.name()
+        )))
+    }
+
+    /// The CI checks reported on a pull request's head commit.
+    async fn get_pr_checks(
+        &self,
+        _repo_url: &str,
+        _pr_number: u64,
+    ) -> Result<Vec<PrCheck>, ProviderError> {
+        Err(ProviderError::NotSupported(format!(
+            "{} does not support get_pr_checks",
+            self.name()
+        )))
+    }
+
     /// Create a review on a pull request.
     async fn create_pr_review(
         &self,
@@ -153,22 +277,80 @@ pub trait RepoProvider: Send + Sync {
     }
 }
 
+/// Whether `url` is an SSH remote, either `ssh://[user@]host[:port]/path`
+/// or the scp-like `[user@]host:path`.
+pub fn is_ssh_url(url: &str) -> bool {
+    if url.starts_with("ssh://") {
+        return true;
+    }
+    if url.contains("://") {
+        return false;
+    }
+    url.split_once(':')
+        .is_some_and(|(host, path)| !host.is_empty() && !host.contains('/') && !path.is_empty())
+}
+
+/// The HTTPS URL of the repository behind `repo_url`, for reaching the
+/// provider's API. An SSH remote maps to the same host and path, except on
+/// Azure DevOps, whose SSH paths are laid out differently. Other URLs are
+/// returned unchanged.
+pub fn https_repo_url(repo_url: &str) -> Result<String, ProviderError> {
+    if !is_ssh_url(repo_url) {
+        return Ok(repo_url.to_string());
+    }
+    let invalid = || ProviderError::Other(format!("invalid SSH repository URL: {repo_url}"));
+    let (authority, path) = match repo_url.strip_prefix("ssh://") {
+        Some(rest) => rest.split_once('/').ok_or_else(invalid)?,
+        None => repo_url.split_once(':').ok_or_else(invalid)?,
+    };
+    let host = authority.rsplit('@').next().unwrap_or(authority);
+    // The SSH port says nothing about where the web server listens
+    let host = if repo_url.starts_with("ssh://") {
+        host.split(':').next().unwrap_or(host)
+    } else {
+        host
+    };
+    let path = path.trim_matches('/');
+    if host.is_empty() || path.is_empty() {
+        return Err(invalid());
+    }
+
+    if host == "ssh.dev.azure.com" || host == "vs-ssh.visualstudio.com" {
+        let parts: Vec<&str> = path.split('/').collect();
+        let ["v3", org, project, repo] = parts.as_slice() else {
+            return Err(invalid());
+        };
+        let repo = repo.strip_suffix(".git").unwrap_or(repo);
+        return Ok(if host == "ssh.dev.azure.com" {
+            format!("https://dev.azure.com/{org}/{project}/_git/{repo}")
+        } else {
+            format!("https://{org}.visualstudio.com/{project}/_git/{repo}")
+        });
+    }
+    Ok(format!("https://{host}/{path}"))
+}
+
 /// Build a provider for the given project configuration.
 ///
 /// Resolution order:
 /// 1. If `provider_type` is explicitly set, use that provider.
 /// 2. If `repo_url` contains "github.com", use GitHub.
-/// 3. Otherwise, return Unsupported.
+/// 3. If `repo_url` is on dev.azure.com or visualstudio.com, use Azure DevOps.
+/// 4. Otherwise, return Unsupported.
+///
+/// An SSH `repo_url` is resolved with [`https_repo_url`] first: git clones
+/// and pushes over SSH, while the provider's API is still reached over HTTPS.
 pub fn provider_for_url(
     repo_url: &str,
     token: Option<String>,
     provider_type: Option<ProviderType>,
     skip_tls_verify: bool,
 ) -> Result<Box<dyn RepoProvider>, ProviderError> {
+    let repo_url = &https_repo_url(repo_url)?;
     // Enforce HTTPS
     if !repo_url.starts_with("https://") {
         return Err(ProviderError::Other(
-            "only HTTPS repository URLs are supported".into(),
+            "only HTTPS and SSH repository URLs are supported".into(),
         ));
     }
 
@@ -179,12 +361,24 @@ pub fn provider_for_url(
             token,
             skip_tls_verify,
         )?)),
+        Some(ProviderType::AzureDevops) => Ok(Box::new(azure_devops::AzureDevOpsProvider::new(
+            repo_url,
+            token,
+            skip_tls_verify,
+        )?)),
         None => {
             // Auto-detect from URL
-            let gh = github::GitHubProvider::new(token);
+            let gh = github::GitHubProvider::new(token.clone());
             if gh.supports_url(repo_url) {
                 return Ok(Box::new(gh));
             }
+            if azure_devops::is_azure_devops_url(repo_url) {
+                return Ok(Box::new(azure_devops::AzureDevOpsProvider::new(
+                    repo_url,
+                    token,
+                    skip_tls_verify,
+                )?));
+            }
             Err(ProviderError::Unsupported(format!(
                 "cannot auto-detect provider for {repo_url}; set provider_type on the project",
             )))
@@ -251,6 +445,87 @@ mod tests {
         assert_eq!(provider.name(), "gitea");
     }
 
+    #[test]
+    fn factory_auto_detects_azure_devops() {
+        let provider = provider_for_url(
+            "https://dev.azure.com/acme/web/_git/frontend",
+            Some("pat".into()),
+            None,
+            false,
+        )
+        .unwrap();
+        assert_eq!(provider.name(), "azure_devops");
+
+        let provider = provider_for_url(
+            "https://acme.visualstudio.com/web/_git/frontend",
+            Some("pat".into()),
+            Some(ProviderType::AzureDevops),
+            false,
+        )
+        .unwrap();
+        assert_eq!(provider.name(), "azure_devops");
+    }
+
+    #[test]
+    fn ssh_urls_are_recognized() {
+        assert!(is_ssh_url("git@github.com:user/repo.git"));
+        assert!(is_ssh_url("ssh://git@gitea.example.com:2222/user/repo.git"));
+        assert!(!is_ssh_url("https://github.com/user/repo"));
+        assert!(!is_ssh_url("http://gitea.example.com/user/repo"));
+        assert!(!is_ssh_url("/srv/git/repo.git"));
+        assert!(!is_ssh_url(""));
+    }
+
+    #[test]
+    fn ssh_urls_map_to_https() {
+        assert_eq!(
+            https_repo_url("git@github.com:user/repo.git").unwrap(),
+            "https://github.com/user/repo.git"
+        );
+        assert_eq!(
+            https_repo_url("ssh://git@gitea.example.com:2222/user/repo.git").unwrap(),
+            "https://gitea.example.com/user/repo.git"
+        );
+        assert_eq!(
+            https_repo_url("git@ssh.dev.azure.com:v3/acme/web/frontend").unwrap(),
+            "https://dev.azure.com/acme/web/_git/frontend"
+        );
+        assert_eq!(
+            https_repo_url("acme@vs-ssh.visualstudio.com:v3/acme/web/frontend").unwrap(),
+            "https://acme.visualstudio.com/web/_git/frontend"
+        );
+        assert_eq!(
+            https_repo_url("https://github.com/user/repo").unwrap(),
+            "https://github.com/user/repo"
+        );
+        assert!(https_repo_url("git@ssh.dev.azure.com:acme/web").is_err());
+        assert!(https_repo_url("ssh://git@github.com").is_err());
+    }
+
+    #[test]
+    fn factory_accepts_ssh_urls() {
+        let provider = provider_for_url("git@github.com:user/repo.git", None, None, false).unwrap();
+        assert_eq!(provider.name(), "github");
+
+        let provider = provider_for_url(
+            "git@ssh.dev.azure.com:v3/acme/web/frontend",
+            Some("pat".into()),
+            None,
+            false,
+        )
+        .unwrap();
+        assert_eq!(provider.name(), "azure_devops");
+
+        let provider = provider_for_url(
+            "ssh://git@gitea.example.com:2222/user/repo.git",
+            Some("token".into()),
+            Some(ProviderType::Gitea),
+            false,
+        )
+        .unwrap();
+        assert_eq!(provider.name(), "gitea");
+    }
+
     #[test]
     fn factory_rejects_http() {
         let result = provider_for_url("http://insecure.example.com/user/repo", None, None, false);
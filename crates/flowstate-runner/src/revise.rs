@@ -0,0 +1,393 @@
+//! Revise runs: answer review comments on a task's open pull request.
+//!
+//! The runner reads the PR's reviews that arrived since the last revise,
+//! checks out the PR branch, lets the agent address them and pushes the
+//! result to the same branch, so the PR updates in place.
+
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use flowstate_core::claude_run::{ClaudeAction, ClaudeRun, ClaudeRunStatus};
+use flowstate_core::project::Project;
+use flowstate_core::task::Task;
+use flowstate_core::task_pr::PrState;
+use flowstate_service::{HttpService, TaskService};
+use flowstate_verify::Runner as VerifyRunner;
+use tracing::{info, warn};
+
+use crate::backend::{AgentBackend, McpEnv};
+use crate::executor;
+use crate::pipeline;
+use crate::plan_parser;
+use crate::repo_provider::{self, PrReview, ReviewState, FORK_REMOTE};
+use crate::workspace;
+
+/// Where the agent explains comments it did not act on. It is posted on
+/// the PR rather than committed.
+const RESPONSE_FILE: &str = "REVIEW_RESPONSE.md";
+
+/// Address the unresolved review comments on the task's open PR and push
+/// the changes to its branch.
+#[allow(clippy::too_many_arguments)]
+pub async fn execute(
+    service: &HttpService,
+    run: &ClaudeRun,
+    task: &Task,
+    project: &Project,
+    ws_dir: &Path,
+    timeout: Duration,
+    kill_grace: Duration,
+    backend: &dyn AgentBackend,
+    mcp_env: Option<&McpEnv>,
+) -> Result<()> {
+    // 1. The PR to revise: the newest open one
+    let task_pr = service
+        .list_task_prs(&task.id)
+        .await
+        .map_err(|e| anyhow::anyhow!("{e}"))?
+        .into_iter()
+        .filter(|pr| pr.state == PrState::Open)
+        .max_by_key(|pr| pr.created_at);
+    let Some(task_pr) = task_pr else {
+        bail!("task has no open pull request to revise");
+    };
+    let pr_number = task_pr.pr_number as u64;
+
+    let token = service.get_repo_token(&project.id).await.ok();
+    progress(service, &run.id, "Checking repo auth...").await;
+    let provider = repo_provider::provider_for_url(
+        &project.repo_url,
+        token.clone(),
+        project.provider_type,
+        project.skip_tls_verify,
+    )
+    .map_err(|e| anyhow::anyhow!("unsupported repo provider: {e}"))?;
+    provider
+        .preflight()
+        .await
+        .map_err(|e| anyhow::anyhow!("provider preflight: {e}"))?;
+    provider
+        .check_auth(&project.repo_url)
+        .await
+        .map_err(|e| anyhow::anyhow!("repo auth check failed: {e}"))?;
+    let fork = pipeline::fork_owner(project.fork_owner.as_deref(), provider.as_ref())?;
+
+    // 2. Reviews not yet answered by an earlier revise run
+    progress(service, &run.id, "Reading review comments...").await;
+    let reviews = provider
+        .list_pr_reviews(&project.repo_url, pr_number)
+        .await
+        .map_err(|e| anyhow::anyhow!("listing reviews on PR #{pr_number}: {e}"))?;
+    let since = service
+        .list_claude_runs(&task.id)
+        .await
+        .unwrap_or_default()
+        .iter()
+        .filter(|r| r.action == ClaudeAction::Revise && r.status == ClaudeRunStatus::Completed)
+        .map(|r| r.started_at)
+        .max();
+    let unresolved = unresolved_reviews(&reviews, since);
+    if unresolved.is_empty() {
+        bail!("no unresolved review comments on PR #{pr_number}");
+    }
+    info!("{} unresolved reviews on PR #{pr_number}", unresolved.len());
+
+    // 3. Check out the PR branch
+    progress(service, &run.id, "Cloning repository...").await;
+    workspace::ensure_repo(
+        ws_dir,
+        &project.repo_url,
+        token.as_deref(),
+        project.skip_tls_verify,
+        project.ssh_key_path.as_deref(),
+        project.subpath(),
+    )
+    .await?;
+    if let Some(owner) = fork {
+        progress(service, &run.id, "Fetching fork...").await;
+        pipeline::prepare_fork(provider.as_ref(), project, owner, ws_dir, token.as_deref()).await?;
+        workspace::fetch_remote(ws_dir, FORK_REMOTE, project.skip_tls_verify).await?;
+    }
+    progress(service, &run.id, "Checking out pull request branch...").await;
+    workspace::checkout_branch(ws_dir, &task_pr.branch_name).await?;
+    let base_commit = workspace::head_commit(ws_dir).await?;
+
+    // 4. Run the agent on the review comments
+    progress(service, &run.id, "Assembling prompt...").await;
+    let mut ctx = executor::build_prompt_context(service, task, project, run).await;
+    ctx.distill_feedback = Some(render_reviews(&unresolved));
+    let prompt = flowstate_prompts::assemble_prompt(&ctx, ClaudeAction::Revise);
+    executor::save_prompt(&run.id, &prompt)?;
+
+    progress(service, &run.id, &format!("Running {}...", backend.name())).await;
+    let output = backend
+        .run(
+            &prompt,
+            ws_dir,
+            timeout,
+            kill_grace,
+            token.as_deref(),
+            mcp_env,
+        )
+        .await?;
+    if !output.success {
+        let msg = if output.stderr.is_empty() {
+            format!("agent exited with code {}", output.exit_code)
+        } else {
+            output.stderr.clone()
+        };
+        service
+            .update_claude_run_status(&run.id, "failed", Some(&msg), Some(output.exit_code))
+            .await
+            .map_err(|e| anyhow::anyhow!("{e}"))?;
+        return Ok(());
+    }
+
+    let response_path = ws_dir.join(RESPONSE_FILE);
+    let response = std::fs::read_to_string(&response_path).ok();
+    if response.is_some() {
+        std::fs::remove_file(&response_path)?;
+    }
+
+    // 5. Run the plan's validation commands; nothing is pushed on failure
+    let plan = service.read_task_plan(&task.id).await.ok();
+    let steps = plan
+        .as_deref()
+        .map(plan_parser::extract_validation_commands)
+        .unwrap_or_default();
+    if !steps.is_empty() {
+        progress(service, &run.id, "Running validation tests...").await;
+        let result = VerifyRunner::new().execute(&steps, ws_dir).await;
+        if !matches!(result.status, flowstate_verify::runner::RunStatus::Passed) {
+            let mut error_msg = String::from("Validation failed:\n");
+            for step in result.steps.iter().filter(|s| s.exit_code != Some(0)) {
+                error_msg.push_str(&format!(
+                    "\n--- {} (exit {}) ---\n{}\n{}\n",
+                    step.step_name,
+                    step.exit_code
+                        .map_or("timeout".to_string(), |c| c.to_string()),
+                    step.stdout,
+                    step.stderr,
+                ));
+            }
+            service
+                .update_claude_run_status(&run.id, "failed", Some(&error_msg), Some(1))
+                .await
+                .map_err(|e| anyhow::anyhow!("{e}"))?;
+            return Ok(());
+        }
+    }
+
+    // 6. Commit and push to the PR branch
+    progress(service, &run.id, "Committing changes...").await;
+    let message = format!("fix: address review on {} [flowstate]", task.title);
+    workspace::add_and_commit(ws_dir, &message).await?;
+    let head = workspace::head_commit(ws_dir).await?;
+    let changed = head != base_commit;
+    if changed {
+        progress(service, &run.id, "Pushing branch...").await;
+        match fork {
+            Some(_) => {
+                provider
+                    .push_branch_to_fork(ws_dir, &task_pr.branch_name)
+                    .await
+            }
+            None => provider.push_branch(ws_dir, &task_pr.branch_name).await,
+        }
+        .map_err(|e| anyhow::anyhow!("push failed: {e}"))?;
+    } else {
+        warn!("revise made no changes to {}", task_pr.branch_name);
+    }
+
+    service
+        .update_claude_run_pr(
+            &run.id,
+            Some(&task_pr.pr_url),
+            Some(task_pr.pr_number),
+            Some(&task_pr.branch_name),
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+
+    // 7. Tell the reviewers; not every provider takes PR comments
+    let comment = response_comment(changed.then_some(head.as_str()), response.as_deref());
+    if let Err(e) = provider
+        .create_pr_comment(&project.repo_url, pr_number, &comment)
+        .await
+    {
+        warn!("failed to comment on PR #{pr_number}: {e}");
+    }
+
+    // 8. Back to Verify for another look
+    let update = flowstate_core::task::UpdateTask {
+        status: Some(flowstate_core::task::Status::Verify),
+        ..Default::default()
+    };
+    service
+        .update_task(&task.id, &update)
+        .await
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+
+    service
+        .update_claude_run_status(&run.id, "completed", None, Some(output.exit_code))
+        .await
+        .map_err(|e| anyhow::anyhow!("{e}"))?;
+    info!("revise complete for PR #{pr_number} at {}", task_pr.pr_url);
+    Ok(())
+}
+
+async fn progress(service: &HttpService, run_id: &str, message: &str) {
+    info!("{message}");
+    let _ = service.update_claude_run_progress(run_id, message).await;
+}
+
+/// Reviews asking for changes or leaving comments, submitted after `since`
+/// (every one when `None`). Approvals, dismissed and pending reviews, and
+/// reviews with nothing to say, are left out.
+pub fn unresolved_reviews(reviews: &[PrReview], since: Option<DateTime<Utc>>) -> Vec<&PrReview> {
+    reviews
+        .iter()
+        .filter(|r| {
+            matches!(
+                r.state,
+                ReviewState::ChangesRequested | ReviewState::Commented
+            )
+        })
+        .filter(|r| !r.body.trim().is_empty() || !r.comments.is_empty())
+        .filter(|r| {
+            let submitted = r
+                .submitted_at
+                .as_deref()
+                .and_then(|at| DateTime::parse_from_rfc3339(at).ok());
+            match (since, submitted) {
+                (Some(since), Some(submitted)) => submitted > since,
+                _ => true,
+            }
+        })
+        .collect()
+}
+
+/// Render reviews as Markdown for the prompt: each review's summary, then
+/// its line comments prefixed with `path:line`.
+pub fn render_reviews(reviews: &[&PrReview]) -> String {
+    let mut out = String::new();
+    for review in reviews {
+        let verdict = match review.state {
+            ReviewState::ChangesRequested => "changes requested",
+            _ => "commented",
+        };
+        out.push_str(&format!("#### {} ({verdict})\n\n", review.author));
+        if !review.body.trim().is_empty() {
+            out.push_str(review.body.trim());
+            out.push_str("\n\n");
+        }
+        for comment in &review.comments {
+            let location = match (&comment.path, comment.line) {
+                (Some(path), Some(line)) => format!("`{path}:{line}`: "),
+                (Some(path), None) => format!("`{path}`: "),
+                _ => String::new(),
+            };
+            let body = comment.body.trim().replace('\n', "\n  ");
+            out.push_str(&format!("- {location}{body}\n"));
+        }
+        if !review.comments.is_empty() {
+            out.push('\n');
+        }
+    }
+    out.trim_end().to_string()
+}
+
+/// The PR comment a revise run leaves: the commit that answers the review,
+/// followed by the agent's notes on comments it did not act on.
+fn response_comment(commit: Option<&str>, response: Option<&str>) -> String {
+    let mut out = match commit {
+        Some(sha) => format!("Addressed review comments in {sha}."),
+        None => "Reviewed the comments; no code changes were needed.".to_string(),
+    };
+    if let Some(response) = response.map(str::trim).filter(|r| !r.is_empty()) {
+        out.push_str("\n\n");
+        out.push_str(response);
+    }
+    out.push_str("\n\n---\nGenerated by flowstate runner");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repo_provider::PrComment;
+
+    fn review(state: ReviewState, body: &str, submitted_at: &str) -> PrReview {
+        PrReview {
+            id: 1,
+            author: "alice".into(),
+            state,
+            body: body.into(),
+            comments: vec![],
+            submitted_at: Some(submitted_at.into()),
+        }
+    }
+
+    #[test]
+    fn unresolved_reviews_skip_approvals_and_answered_ones() {
+        let reviews = vec![
+            review(ReviewState::Approved, "LGTM", "2026-03-02T10:00:00Z"),
+            review(ReviewState::ChangesRequested, "old", "2026-03-01T10:00:00Z"),
+            review(ReviewState::ChangesRequested, "new", "2026-03-02T10:00:00Z"),
+            review(ReviewState::Commented, "", "2026-03-02T11:00:00Z"),
+            review(ReviewState::Dismissed, "gone", "2026-03-02T12:00:00Z"),
+        ];
+        let bodies = |since| -> Vec<String> {
+            unresolved_reviews(&reviews, since)
+                .iter()
+                .map(|r| r.body.clone())
+                .collect()
+        };
+
+        assert_eq!(bodies(None), ["old", "new"]);
+        let since = "2026-03-01T12:00:00Z".parse().unwrap();
+        assert_eq!(bodies(Some(since)), ["new"]);
+    }
+
+    #[test]
+    fn render_reviews_places_line_comments() {
+        let mut r = review(ReviewState::ChangesRequested, "A few things.", "");
+        r.comments = vec![
+            PrComment {
+                id: 2,
+                body: "Rename this.\nIt shadows a builtin.".into(),
+                author: "alice".into(),
+                path: Some("src/lib.rs".into()),
+                line: Some(12),
+                created_at: String::new(),
+                updated_at: None,
+            },
+            PrComment {
+                id: 3,
+                body: "Add a test".into(),
+                author: "alice".into(),
+                path: None,
+                line: None,
+                created_at: String::new(),
+                updated_at: None,
+            },
+        ];
+        let out = render_reviews(&[&r]);
+        assert_eq!(
+            out,
+            "#### alice (changes requested)\n\nA few things.\n\n\
+             - `src/lib.rs:12`: Rename this.\n  It shadows a builtin.\n\
+             - Add a test"
+        );
+    }
+
+    #[test]
+    fn response_comment_names_the_commit() {
+        let out = response_comment(Some("abc123"), Some("Kept the name: it is public API.\n"));
+        assert!(out.starts_with("Addressed review comments in abc123.\n\nKept the name"));
+        let out = response_comment(None, None);
+        assert!(out.starts_with("Reviewed the comments; no code changes"));
+    }
+}
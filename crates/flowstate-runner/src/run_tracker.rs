@@ -1,12 +1,16 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::Instant;
 
+use chrono::{DateTime, Utc};
 use flowstate_core::claude_run::ClaudeAction;
 use serde::Serialize;
 
+use crate::config::RunnerConfig;
+
 /// Tracks active runs for health reporting and capacity management.
 pub struct RunTracker {
     active: HashMap<String, ActiveRun>,
+    last_claim_at: Option<DateTime<Utc>>,
 }
 
 /// An in-progress run tracked by the runner.
@@ -52,9 +56,20 @@ impl RunTracker {
     pub fn new() -> Self {
         Self {
             active: HashMap::new(),
+            last_claim_at: None,
         }
     }
 
+    /// Note that a run was just claimed from the server.
+    pub fn record_claim(&mut self) {
+        self.last_claim_at = Some(Utc::now());
+    }
+
+    /// When this runner last claimed a run, if ever.
+    pub fn last_claim_at(&self) -> Option<DateTime<Utc>> {
+        self.last_claim_at
+    }
+
     pub fn insert(&mut self, run: ActiveRun) {
         self.active.insert(run.run_id.clone(), run);
     }
@@ -63,6 +78,11 @@ impl RunTracker {
         self.active.remove(run_id);
     }
 
+    /// IDs of every run currently executing on this runner.
+    pub fn run_ids(&self) -> HashSet<String> {
+        self.active.keys().cloned().collect()
+    }
+
     pub fn active_count(&self) -> usize {
         self.active.len()
     }
@@ -70,7 +90,7 @@ impl RunTracker {
     pub fn active_build_count(&self) -> usize {
         self.active
             .values()
-            .filter(|r| r.action == ClaudeAction::Build)
+            .filter(|r| RunnerConfig::is_build_action(r.action))
             .count()
     }
 
@@ -115,6 +135,10 @@ mod tests {
         tracker.insert(make_run("r2", "t2", ClaudeAction::Research));
         assert_eq!(tracker.active_count(), 2);
         assert_eq!(tracker.active_build_count(), 1);
+        assert_eq!(
+            tracker.run_ids(),
+            HashSet::from(["r1".to_string(), "r2".to_string()])
+        );
     }
 
     #[test]
@@ -151,6 +175,14 @@ mod tests {
         assert_eq!(tracker.active_build_count(), 1);
     }
 
+    #[test]
+    fn test_run_tracker_records_claims() {
+        let mut tracker = RunTracker::new();
+        assert!(tracker.last_claim_at().is_none());
+        tracker.record_claim();
+        assert!(tracker.last_claim_at().is_some());
+    }
+
     #[test]
     fn test_run_tracker_default() {
         let tracker = RunTracker::default();
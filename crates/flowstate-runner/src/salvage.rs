@@ -21,6 +21,8 @@ pub enum SalvageOutcome {
     ValidationFailed { error: String },
     /// Salvage process itself failed.
     SalvageError { error: String },
+    /// The server refused the move to Salvaging (e.g. the `salvage` flag is off).
+    Skipped { reason: String },
 }
 
 /// Attempt to salvage a timed-out Build run.
@@ -39,13 +41,18 @@ pub async fn attempt_salvage(
     task: &Task,
     project: &Project,
     ws_dir: &Path,
-    _config: &RunnerConfig,
+    config: &RunnerConfig,
 ) -> SalvageOutcome {
     // 1. Mark run as Salvaging
     info!("salvage: starting salvage attempt for run {}", run.id);
-    let _ = service
+    if let Err(e) = service
         .update_claude_run_status(&run.id, "salvaging", None, None)
-        .await;
+        .await
+    {
+        return SalvageOutcome::Skipped {
+            reason: e.to_string(),
+        };
+    }
     let _ = service
         .update_claude_run_progress(&run.id, "salvage: assessing workspace...")
         .await;
@@ -225,12 +232,21 @@ pub async fn attempt_salvage(
         .update_claude_run_progress(&run.id, "salvage: pushing branch...")
         .await;
 
-    if let Err(e) = provider.push_branch(ws_dir, &branch_name).await {
+    // The build already set up the fork remote when the project uses one
+    let fork = project.fork_owner.as_deref().filter(|o| !o.is_empty());
+    let (pushed, remote) = match fork {
+        Some(_) => (
+            provider.push_branch_to_fork(ws_dir, &branch_name).await,
+            repo_provider::FORK_REMOTE,
+        ),
+        None => (provider.push_branch(ws_dir, &branch_name).await, "origin"),
+    };
+    if let Err(e) = pushed {
         // Try force-with-lease in case branch partially pushed before timeout
         warn!("salvage: push failed ({e}), trying force-with-lease...");
         let force_result = run_git_command(
             ws_dir,
-            &["push", "-u", "origin", &branch_name, "--force-with-lease"],
+            &["push", "-u", remote, &branch_name, "--force-with-lease"],
             project.skip_tls_verify,
         )
         .await;
@@ -259,15 +275,33 @@ pub async fn attempt_salvage(
         .await
         .unwrap_or_else(|_| "main".to_string());
 
-    let pr_body = format!(
-        "## Task\n\n{}\n\n## Description\n\n{}\n\n---\n**Note:** This PR was salvaged from a timed-out build run.\n\nGenerated by flowstate runner",
-        task.title, task.description
+    let pr_body = crate::pipeline::pr_body(
+        task,
+        &config.task_links(),
+        Some("**Note:** This PR was salvaged from a timed-out build run."),
     );
 
-    match provider
-        .open_pull_request(ws_dir, &branch_name, &task.title, &pr_body, &default_branch)
-        .await
-    {
+    let pr = match fork {
+        Some(owner) => {
+            provider
+                .open_fork_pull_request(
+                    &project.repo_url,
+                    owner,
+                    &branch_name,
+                    &task.title,
+                    &pr_body,
+                    &default_branch,
+                    false,
+                )
+                .await
+        }
+        None => {
+            provider
+                .open_pull_request(ws_dir, &branch_name, &task.title, &pr_body, &default_branch)
+                .await
+        }
+    };
+    match pr {
         Ok(pr) => {
             info!("salvage: PR #{} created at {}", pr.number, pr.url);
 
@@ -288,6 +322,7 @@ pub async fn attempt_salvage(
                 pr_url: pr.url.clone(),
                 pr_number: pr.number as i64,
                 branch_name: pr.branch.clone(),
+                draft: false,
             };
             let _ = service.create_task_pr(&create_pr).await;
 
@@ -70,18 +70,35 @@ fn test_config(workspace_root: PathBuf) -> RunnerConfig {
     RunnerConfig {
         server_url: String::new(),
         api_key: None,
+        client_cert: None,
+        client_key: None,
+        server_ca: None,
+        daemon: false,
+        pid_file: None,
+        log_file: None,
         poll_interval: 5,
+        maintenance_wait: 0,
+        claim_wait: 0,
         workspace_root: Some(workspace_root),
+        janitor_interval: 0,
+        janitor_min_age: 3600,
+        janitor_dry_run: false,
+        state_file: None,
         health_port: 0,
+        health_bind: std::net::IpAddr::V4(std::net::Ipv4Addr::LOCALHOST),
+        health_token: None,
         light_timeout: 30,
         build_timeout: 60,
         kill_grace_period: 2,
         activity_timeout: 900,
         max_concurrent: 1,
         max_builds: 1,
+        verify_parallelism: 4,
         shutdown_timeout: 10,
         agent_backend: "mock".into(),
         runner_capability: "heavy".into(),
+        runner_id: None,
+        benchmark: false,
         anthropic_base_url: None,
         anthropic_auth_token: None,
         anthropic_model: None,
@@ -90,10 +107,16 @@ fn test_config(workspace_root: PathBuf) -> RunnerConfig {
         opencode_model: None,
         opencode_api_key: None,
         opencode_base_url: None,
+        ollama_url: "http://127.0.0.1:11434".into(),
+        ollama_model: None,
+        ollama_api_key: None,
+        task_link: flowstate_core::deep_link::DEFAULT_TASK_LINK.into(),
+        draft_prs: false,
         gemini_api_key: None,
         gemini_model: None,
         gemini_gcp_project: None,
         gemini_gcp_location: None,
+        command: None,
     }
 }
 
@@ -124,6 +147,7 @@ async fn setup_run(
             description: "A test task".into(),
             status: flowstate_core::task::Status::Todo,
             priority: flowstate_core::task::Priority::Medium,
+            task_type: flowstate_core::task::TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -131,6 +155,7 @@ async fn setup_run(
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .await
         .unwrap();
@@ -419,3 +444,37 @@ async fn dispatch_research_no_file_uses_stdout() {
     let research = svc.read_task_research(&task.id).await.unwrap();
     assert_eq!(research, "stdout research content");
 }
+
+#[tokio::test]
+async fn dispatch_verify_runs_plan_checks() {
+    let tmp = tempfile::tempdir().unwrap();
+    let repo_url = create_test_repo(tmp.path());
+    let url = spawn_server().await;
+    let mut svc = HttpService::new(&url);
+    let (project, task, run) = setup_run(&mut svc, &repo_url, "research").await;
+
+    let plan = "# Plan\n\n### 4. Validation Steps\n\n```\nsh -c 'echo ok'\nsh -c 'exit 3'\n```\n";
+    svc.write_task_plan(&task.id, plan).await.unwrap();
+
+    // Dispatch the claimed run as a verify run; prerequisites are the
+    // server's concern, not the executor's
+    let run = flowstate_core::claude_run::ClaudeRun {
+        action: flowstate_core::claude_run::ClaudeAction::Verify,
+        ..run
+    };
+    let config = test_config(tmp.path().join("workspaces"));
+    let backend = MockBackend::success("verify output")
+        .with_files(vec![("VERIFICATION.md", "# Verification\n\nLooks good.")]);
+    executor::dispatch(&svc, &run, &task, &project, &config, &backend, None)
+        .await
+        .unwrap();
+
+    let updated = svc.get_claude_run(&run.id).await.unwrap();
+    assert_eq!(updated.status, ClaudeRunStatus::Completed);
+
+    let report = svc.read_task_verification(&task.id).await.unwrap();
+    assert!(report.contains("Looks good."));
+    assert!(report.contains("## Automated Checks"));
+    assert!(report.contains("`sh -c 'echo ok'` | passed"));
+    assert!(report.contains("`sh -c 'exit 3'` | failed (exit 3)"));
+}
@@ -97,7 +97,7 @@ async fn ensure_repo_clones_local_repo() {
     let ws = tempfile::tempdir().unwrap();
     let ws_path = ws.path().join("clone");
 
-    flowstate_runner::workspace::ensure_repo(&ws_path, &repo_url, None, false)
+    flowstate_runner::workspace::ensure_repo(&ws_path, &repo_url, None, false, None, None)
         .await
         .unwrap();
 
@@ -112,12 +112,12 @@ async fn ensure_repo_fetches_existing_clone() {
     let ws_path = ws.path().join("clone");
 
     // First clone
-    flowstate_runner::workspace::ensure_repo(&ws_path, &repo_url, None, false)
+    flowstate_runner::workspace::ensure_repo(&ws_path, &repo_url, None, false, None, None)
         .await
         .unwrap();
 
     // Second call should fetch (not fail)
-    flowstate_runner::workspace::ensure_repo(&ws_path, &repo_url, None, false)
+    flowstate_runner::workspace::ensure_repo(&ws_path, &repo_url, None, false, None, None)
         .await
         .unwrap();
 
@@ -127,7 +127,8 @@ async fn ensure_repo_fetches_existing_clone() {
 #[tokio::test]
 async fn ensure_repo_empty_url_fails() {
     let ws = tempfile::tempdir().unwrap();
-    let result = flowstate_runner::workspace::ensure_repo(ws.path(), "", None, false).await;
+    let result =
+        flowstate_runner::workspace::ensure_repo(ws.path(), "", None, false, None, None).await;
     assert!(result.is_err());
     let msg = result.unwrap_err().to_string();
     assert!(msg.contains("no repo_url"));
@@ -141,7 +142,7 @@ async fn create_branch_switches_to_new_branch() {
     let ws = tempfile::tempdir().unwrap();
     let ws_path = ws.path().join("clone");
 
-    flowstate_runner::workspace::ensure_repo(&ws_path, &repo_url, None, false)
+    flowstate_runner::workspace::ensure_repo(&ws_path, &repo_url, None, false, None, None)
         .await
         .unwrap();
 
@@ -159,7 +160,7 @@ async fn create_branch_recreates_existing_branch() {
     let ws = tempfile::tempdir().unwrap();
     let ws_path = ws.path().join("clone");
 
-    flowstate_runner::workspace::ensure_repo(&ws_path, &repo_url, None, false)
+    flowstate_runner::workspace::ensure_repo(&ws_path, &repo_url, None, false, None, None)
         .await
         .unwrap();
 
@@ -193,7 +194,7 @@ async fn add_and_commit_with_changes() {
     let ws = tempfile::tempdir().unwrap();
     let ws_path = ws.path().join("clone");
 
-    flowstate_runner::workspace::ensure_repo(&ws_path, &repo_url, None, false)
+    flowstate_runner::workspace::ensure_repo(&ws_path, &repo_url, None, false, None, None)
         .await
         .unwrap();
 
@@ -232,7 +233,7 @@ async fn add_and_commit_no_changes_succeeds() {
     let ws = tempfile::tempdir().unwrap();
     let ws_path = ws.path().join("clone");
 
-    flowstate_runner::workspace::ensure_repo(&ws_path, &repo_url, None, false)
+    flowstate_runner::workspace::ensure_repo(&ws_path, &repo_url, None, false, None, None)
         .await
         .unwrap();
 
@@ -272,7 +273,7 @@ async fn detect_default_branch_returns_main_or_master() {
     let ws = tempfile::tempdir().unwrap();
     let ws_path = ws.path().join("clone");
 
-    flowstate_runner::workspace::ensure_repo(&ws_path, &repo_url, None, false)
+    flowstate_runner::workspace::ensure_repo(&ws_path, &repo_url, None, false, None, None)
         .await
         .unwrap();
 
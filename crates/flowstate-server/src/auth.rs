@@ -1,17 +1,24 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
 use std::sync::Arc;
 
 use axum::{
-    extract::{Request, State},
-    http::StatusCode,
+    extract::{ConnectInfo, FromRequestParts, MatchedPath, Query, RawPathParams, Request, State},
+    http::{request::Parts, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
     Json,
 };
-use serde_json::json;
+use flowstate_core::api_key::ApiKey;
+use flowstate_service::TaskService;
+use ipnet::IpNet;
+use serde_json::{json, Value};
 use sha2::{Digest, Sha256};
 
 use flowstate_db::Database;
 
+use crate::listen::PeerInfo;
+use crate::oidc::{self, Oidc};
 use crate::routes::AppState;
 
 /// Authentication configuration.
@@ -20,6 +27,107 @@ pub struct AuthConfig {
     pub env_key_hash: Option<String>,
     /// Database handle for DB-backed API keys.
     pub db: Arc<dyn Database>,
+    /// OpenID provider for user sign-in, when `FLOWSTATE_OIDC_ISSUER` is set.
+    pub oidc: Option<Arc<Oidc>>,
+}
+
+/// Identity of the caller, inserted into request extensions by
+/// [`auth_middleware`] so handlers can record who made a change.
+///
+/// `key:<name>` for DB-backed keys, `env-key` for `FLOWSTATE_API_KEY`,
+/// `user:<email>` for OIDC users, and empty when authentication is disabled.
+/// `X-Runner-Id` is unauthenticated, so a runner id it names is appended as
+/// `<label> (runner:<id>)` and only stands alone when authentication is off.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Caller(pub String);
+
+impl Caller {
+    fn resolve(request: &Request, key_label: Option<String>) -> Self {
+        let runner = request
+            .headers()
+            .get("x-runner-id")
+            .and_then(|v| v.to_str().ok())
+            .filter(|v| !v.is_empty());
+        match (runner, key_label) {
+            (Some(id), Some(label)) => Caller(format!("{label} (runner:{id})")),
+            (Some(id), None) => Caller(format!("runner:{id}")),
+            (None, Some(label)) => Caller(label),
+            (None, None) => Caller::default(),
+        }
+    }
+}
+
+/// Projects the caller may read and change, inserted into request
+/// extensions by [`auth_middleware`]. Unrestricted unless the request was
+/// made with a DB key scoped to specific projects or to an organization.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectScope {
+    projects: Option<Vec<String>>,
+    org_id: Option<String>,
+}
+
+impl ProjectScope {
+    /// The scope of `key`. An organization key reaches the organization's
+    /// projects, narrowed further by the key's own project list.
+    async fn load(db: &dyn Database, key: &ApiKey) -> Result<Self, flowstate_db::DbError> {
+        let projects = match &key.org_id {
+            Some(org_id) => {
+                let mut ids = db.list_organization_project_ids(org_id).await?;
+                if !key.allowed_projects.is_empty() {
+                    ids.retain(|id| key.allowed_projects.contains(id));
+                }
+                Some(ids)
+            }
+            None if key.allowed_projects.is_empty() => None,
+            None => Some(key.allowed_projects.clone()),
+        };
+        Ok(ProjectScope {
+            projects,
+            org_id: key.org_id.clone(),
+        })
+    }
+
+    /// The allowed project ids, or `None` when every project is allowed.
+    /// An organization that owns no projects yet allows none.
+    pub fn projects(&self) -> Option<&[String]> {
+        self.projects.as_deref()
+    }
+
+    /// The caller's organization, or `None` for a server-wide caller.
+    pub fn org_id(&self) -> Option<&str> {
+        self.org_id.as_deref()
+    }
+
+    pub fn allows(&self, project_id: &str) -> bool {
+        self.projects
+            .as_ref()
+            .is_none_or(|ids| ids.iter().any(|id| id == project_id))
+    }
+
+    /// Refuse a project outside the scope as if it did not exist, so a
+    /// scoped key cannot probe for other teams' projects.
+    pub fn check(&self, project_id: &str) -> Result<(), (StatusCode, Json<Value>)> {
+        if self.allows(project_id) {
+            Ok(())
+        } else {
+            Err(out_of_scope())
+        }
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for ProjectScope {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts.extensions.get::<Self>().cloned().unwrap_or_default())
+    }
+}
+
+fn out_of_scope() -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::NOT_FOUND,
+        Json(json!({ "error": "not found: outside this API key's projects" })),
+    )
 }
 
 /// SHA-256 hash a raw key, returning the hex-encoded digest.
@@ -46,15 +154,20 @@ pub fn generate_api_key() -> String {
 /// Axum middleware that enforces authentication.
 ///
 /// If `auth` is `None` in the AppState, all requests pass through (open access).
-/// Otherwise, requires a valid `Authorization: Bearer <token>` header.
+/// Otherwise, requires a valid `Authorization: Bearer <token>` header, or
+/// with OIDC configured, a session cookie or a bearer JWT from the provider.
 pub async fn auth_middleware(
     State(state): State<AppState>,
-    request: Request,
+    mut request: Request,
     next: Next,
 ) -> Response {
     let auth = match &state.auth {
         Some(auth) => auth,
-        None => return next.run(request).await,
+        None => {
+            let caller = Caller::resolve(&request, None);
+            request.extensions_mut().insert(caller);
+            return next.run(request).await;
+        }
     };
 
     // Extract bearer token
@@ -64,6 +177,26 @@ pub async fn auth_middleware(
         .and_then(|v| v.to_str().ok())
         .and_then(|v| v.strip_prefix("Bearer "));
 
+    if let Some(oidc) = &auth.oidc {
+        let identity = match token {
+            // API keys never contain dots; JWTs always have two
+            Some(t) if t.matches('.').count() == 2 => oidc.verify_token(t).await.ok(),
+            Some(_) => None,
+            None => request
+                .headers()
+                .get("cookie")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| oidc::cookie_value(v, oidc::SESSION_COOKIE))
+                .and_then(|v| oidc.session(v)),
+        };
+        if let Some(identity) = identity {
+            let caller = Caller::resolve(&request, Some(identity.label()));
+            request.extensions_mut().insert(caller);
+            request.extensions_mut().insert(identity);
+            return next.run(request).await;
+        }
+    }
+
     let token = match token {
         Some(t) => t,
         None => {
@@ -80,6 +213,8 @@ pub async fn auth_middleware(
     // Check env key (constant-time comparison via hash equality)
     if let Some(ref env_hash) = auth.env_key_hash {
         if constant_time_eq(&token_hash, env_hash) {
+            let caller = Caller::resolve(&request, Some("env-key".into()));
+            request.extensions_mut().insert(caller);
             return next.run(request).await;
         }
     }
@@ -89,12 +224,33 @@ pub async fn auth_middleware(
     let hash_for_db = token_hash.clone();
     match db.find_api_key_by_hash(&hash_for_db).await {
         Ok(Some(api_key)) => {
+            if let Err(reason) = check_key_policy(&api_key, &request) {
+                return (StatusCode::FORBIDDEN, Json(json!({ "error": reason }))).into_response();
+            }
             // Fire-and-forget: update last_used_at
             let db2 = db.clone();
             let key_id = api_key.id.clone();
             tokio::spawn(async move {
                 let _ = db2.touch_api_key(&key_id).await;
             });
+            let label = if api_key.name.is_empty() {
+                format!("key:{}", api_key.id)
+            } else {
+                format!("key:{}", api_key.name)
+            };
+            let scope = match ProjectScope::load(db.as_ref(), &api_key).await {
+                Ok(scope) => scope,
+                Err(e) => {
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(json!({ "error": e.to_string() })),
+                    )
+                        .into_response();
+                }
+            };
+            let caller = Caller::resolve(&request, Some(label));
+            request.extensions_mut().insert(caller);
+            request.extensions_mut().insert(scope);
             return next.run(request).await;
         }
         Ok(None) => {}
@@ -108,6 +264,249 @@ pub async fn auth_middleware(
         .into_response()
 }
 
+/// Enforce a DB key's CIDR and route allowlists against `request`.
+///
+/// The address checked is the socket peer, so behind a reverse proxy the
+/// allowlist must name the proxy. Connections without an IP address (Unix
+/// sockets) never satisfy a CIDR allowlist.
+fn check_key_policy(key: &ApiKey, request: &Request) -> Result<(), &'static str> {
+    if !key.allowed_cidrs.is_empty() {
+        let ip = request
+            .extensions()
+            .get::<ConnectInfo<PeerInfo>>()
+            .and_then(|info| info.0.remote_addr)
+            .map(|addr| addr.ip().to_canonical());
+        let allowed = ip.is_some_and(|ip| key.allowed_cidrs.iter().any(|c| cidr_contains(c, ip)));
+        if !allowed {
+            return Err("API key not permitted from this address");
+        }
+    }
+    if !key.allows_route(request.uri().path()) {
+        return Err("API key not permitted for this route");
+    }
+    Ok(())
+}
+
+/// Match `ip` against a CIDR block or a bare address. Unparseable entries
+/// match nothing; [`validate_key_policy`] rejects them up front.
+fn cidr_contains(cidr: &str, ip: IpAddr) -> bool {
+    match cidr.parse::<IpNet>() {
+        Ok(net) => net.contains(&ip),
+        Err(_) => cidr.parse::<IpAddr>().is_ok_and(|addr| addr == ip),
+    }
+}
+
+/// Check allowlist entries before they are stored on a key.
+pub fn validate_key_policy(cidrs: &[String], routes: &[String]) -> Result<(), String> {
+    for cidr in cidrs {
+        if cidr.parse::<IpNet>().is_err() && cidr.parse::<IpAddr>().is_err() {
+            return Err(format!("invalid CIDR or address: {cidr}"));
+        }
+    }
+    for route in routes {
+        if !route.starts_with('/') {
+            return Err(format!("route prefix must start with '/': {route}"));
+        }
+    }
+    Ok(())
+}
+
+/// Path prefixes acting on the whole server rather than on projects, closed
+/// to organization and project-scoped keys.
+const SERVER_WIDE_ROUTES: &[&str] = &[
+    "/admin/",
+    "/api/infra/gpu",
+    "/api/infra/db",
+    "/api/infra/storage",
+    "/api/infra/watchdog",
+];
+
+/// Path prefixes for users, which belong to no organization and so are
+/// closed to organization keys.
+const USER_ROUTES: &[&str] = &["/api/users"];
+
+/// Axum middleware refusing project, task and run routes whose resource lies
+/// outside the caller's [`ProjectScope`], server-wide routes to any scoped
+/// key, and user routes to organization keys. Routes that list or create
+/// work check the scope themselves.
+///
+/// Lookups that fail are passed through so the handler reports them as
+/// usual.
+pub async fn project_scope_middleware(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+    matched: Option<MatchedPath>,
+    params: RawPathParams,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    let server_wide = SERVER_WIDE_ROUTES.iter().any(|p| path.starts_with(p));
+    if scope.org_id().is_some() && (server_wide || USER_ROUTES.iter().any(|p| path.starts_with(p)))
+    {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({ "error": "organization keys cannot call server-wide routes" })),
+        )
+            .into_response();
+    }
+    if scope.projects().is_some() && server_wide {
+        return out_of_scope().into_response();
+    }
+    if scope.projects().is_none() {
+        return next.run(request).await;
+    }
+    let Some(route) = matched else {
+        return next.run(request).await;
+    };
+    let route = route.as_str();
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.to_string())
+    };
+
+    let project_id = if route.starts_with("/api/projects/by-slug/") {
+        match param("slug") {
+            Some(slug) => state
+                .service
+                .get_project_by_slug(&slug)
+                .await
+                .ok()
+                .map(|p| p.id),
+            None => None,
+        }
+    } else if route.starts_with("/api/projects/") {
+        param("id")
+    } else if route.starts_with("/api/tasks/") {
+        match param("id").or_else(|| param("task_id")) {
+            Some(id) => state.service.get_task(&id).await.ok().map(|t| t.project_id),
+            None => None,
+        }
+    } else if route.starts_with("/api/claude-runs/") {
+        match param("id") {
+            Some(id) => match state.service.get_claude_run(&id).await {
+                Ok(run) => state
+                    .service
+                    .get_task(&run.task_id)
+                    .await
+                    .ok()
+                    .map(|t| t.project_id),
+                Err(_) => None,
+            },
+            None => None,
+        }
+    } else if route.starts_with("/api/sprints/") {
+        match param("id") {
+            Some(id) => state
+                .service
+                .get_sprint(&id)
+                .await
+                .ok()
+                .map(|s| s.project_id),
+            None => None,
+        }
+    } else if route.starts_with("/api/epics/") {
+        match param("id") {
+            Some(id) => state.service.get_epic(&id).await.ok().map(|e| e.project_id),
+            None => None,
+        }
+    } else if route.starts_with("/api/custom-fields/") {
+        match param("id") {
+            Some(id) => state
+                .service
+                .get_custom_field(&id)
+                .await
+                .ok()
+                .map(|f| f.project_id),
+            None => None,
+        }
+    } else if route.starts_with("/api/saved-filters/") {
+        match param("id") {
+            Some(id) => state
+                .service
+                .get_saved_filter(&id)
+                .await
+                .ok()
+                .map(|f| f.project_id),
+            None => None,
+        }
+    } else {
+        None
+    };
+    if project_id.is_some_and(|id| !scope.allows(&id)) {
+        return out_of_scope().into_response();
+    }
+
+    // List routes name their projects in the query string
+    let query = Query::<HashMap<String, String>>::try_from_uri(request.uri())
+        .map(|Query(q)| q)
+        .unwrap_or_default();
+    let outside = query.get("project_id").is_some_and(|id| !scope.allows(id))
+        || query
+            .get("project_ids")
+            .is_some_and(|ids| ids.split(',').any(|id| !id.is_empty() && !scope.allows(id)));
+    if outside {
+        return out_of_scope().into_response();
+    }
+    next.run(request).await
+}
+
+/// Common name of the verified runner client certificate, inserted into
+/// request extensions by [`runner_cert_middleware`] when runner mTLS is on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunnerCert(pub String);
+
+/// Axum middleware for runner-facing routes when runner mTLS is enabled.
+///
+/// Requires the connection to have presented a client certificate that was
+/// verified against the runner CA during the TLS handshake, issued to the
+/// runner named in `X-Runner-Id`. This is checked in addition to the bearer
+/// key, not instead of it.
+pub async fn runner_cert_middleware(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if !state.runner_mtls {
+        return next.run(request).await;
+    }
+    let client_cn = request
+        .extensions()
+        .get::<ConnectInfo<PeerInfo>>()
+        .and_then(|info| info.0.client_cert_cn.clone());
+    let Some(cn) = client_cn else {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({ "error": "runner client certificate required" })),
+        )
+            .into_response();
+    };
+    let claimed = request
+        .headers()
+        .get("x-runner-id")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    if claimed != cn {
+        return runner_cert_mismatch(&cn, claimed).into_response();
+    }
+    tracing::debug!("runner client certificate: {cn}");
+    request.extensions_mut().insert(RunnerCert(cn));
+    next.run(request).await
+}
+
+/// 403 for a runner presenting `cn`'s certificate while claiming to be
+/// `claimed`.
+pub fn runner_cert_mismatch(cn: &str, claimed: &str) -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::FORBIDDEN,
+        Json(json!({
+            "error": format!("client certificate is for runner {cn:?}, not {claimed:?}")
+        })),
+    )
+}
+
 /// Constant-time string comparison to prevent timing attacks.
 fn constant_time_eq(a: &str, b: &str) -> bool {
     if a.len() != b.len() {
@@ -122,27 +521,35 @@ fn constant_time_eq(a: &str, b: &str) -> bool {
 
 /// Build an `Option<AuthConfig>` from env + DB state.
 ///
-/// Returns `None` (open access) when neither `FLOWSTATE_API_KEY` is set
-/// nor any DB-backed keys exist.
-pub async fn build_auth_config(db: Arc<dyn Database>) -> Option<Arc<AuthConfig>> {
+/// Returns `None` (open access) when `FLOWSTATE_API_KEY` is unset, no
+/// DB-backed keys exist and OIDC is not configured.
+pub async fn build_auth_config(
+    db: Arc<dyn Database>,
+    oidc: Option<Arc<Oidc>>,
+) -> Option<Arc<AuthConfig>> {
     let env_key = std::env::var("FLOWSTATE_API_KEY").ok();
-    build_auth_config_with_key(db, env_key.as_deref()).await
+    build_auth_config_with_key(db, env_key.as_deref(), oidc).await
 }
 
 /// Build auth config from an explicit key value (testable without env mutation).
 pub async fn build_auth_config_with_key(
     db: Arc<dyn Database>,
     env_key: Option<&str>,
+    oidc: Option<Arc<Oidc>>,
 ) -> Option<Arc<AuthConfig>> {
     let env_key_hash = env_key.filter(|k| !k.is_empty()).map(sha256_hex);
 
     let has_db_keys = db.has_api_keys().await.unwrap_or(false);
 
-    if env_key_hash.is_none() && !has_db_keys {
+    if env_key_hash.is_none() && !has_db_keys && oidc.is_none() {
         return None;
     }
 
-    Some(Arc::new(AuthConfig { env_key_hash, db }))
+    Some(Arc::new(AuthConfig {
+        env_key_hash,
+        db,
+        oidc,
+    }))
 }
 
 #[cfg(test)]
@@ -215,7 +622,7 @@ mod tests {
     #[tokio::test]
     async fn build_auth_config_no_keys() {
         let db = Arc::new(flowstate_db::SqliteDatabase::open_in_memory().unwrap());
-        let config = build_auth_config_with_key(db, None).await;
+        let config = build_auth_config_with_key(db, None, None).await;
         // No env key, no DB keys → open access
         assert!(config.is_none());
     }
@@ -223,12 +630,40 @@ mod tests {
     #[tokio::test]
     async fn build_auth_config_env_key() {
         let db = Arc::new(flowstate_db::SqliteDatabase::open_in_memory().unwrap());
-        let config = build_auth_config_with_key(db, Some("test-key-for-auth")).await;
+        let config = build_auth_config_with_key(db, Some("test-key-for-auth"), None).await;
         assert!(config.is_some());
         let auth = config.unwrap();
         assert!(auth.env_key_hash.is_some());
     }
 
+    #[test]
+    fn caller_keeps_authenticated_label_with_runner_header() {
+        let request = |runner: Option<&str>| {
+            let mut builder = axum::http::Request::builder().uri("/api/tasks/t");
+            if let Some(id) = runner {
+                builder = builder.header("x-runner-id", id);
+            }
+            builder.body(axum::body::Body::empty()).unwrap()
+        };
+
+        assert_eq!(
+            Caller::resolve(&request(Some("runner-1")), Some("key:ci".into())),
+            Caller("key:ci (runner:runner-1)".into())
+        );
+        assert_eq!(
+            Caller::resolve(&request(Some("runner-1")), None),
+            Caller("runner:runner-1".into())
+        );
+        assert_eq!(
+            Caller::resolve(&request(None), Some("user:a@example.com".into())),
+            Caller("user:a@example.com".into())
+        );
+        assert_eq!(
+            Caller::resolve(&request(Some("")), Some("env-key".into())),
+            Caller("env-key".into())
+        );
+    }
+
     #[tokio::test]
     async fn auth_middleware_no_config_passes_all() {
         use crate::test_helpers::test_router;
@@ -332,4 +767,380 @@ mod tests {
             .unwrap();
         assert_eq!(resp.status(), StatusCode::OK);
     }
+
+    fn policy_key(cidrs: &[&str], routes: &[&str]) -> ApiKey {
+        ApiKey {
+            id: "k1".into(),
+            name: "runner".into(),
+            key_hash: String::new(),
+            created_at: String::new(),
+            last_used_at: None,
+            allowed_cidrs: cidrs.iter().map(|c| c.to_string()).collect(),
+            allowed_routes: routes.iter().map(|r| r.to_string()).collect(),
+            allowed_projects: vec![],
+            org_id: None,
+        }
+    }
+
+    fn request_from(path: &str, remote: Option<&str>) -> Request {
+        let mut request = Request::builder()
+            .uri(path)
+            .body(axum::body::Body::empty())
+            .unwrap();
+        request.extensions_mut().insert(ConnectInfo(PeerInfo {
+            remote_addr: remote.map(|r| r.parse().unwrap()),
+            client_cert_cn: None,
+        }));
+        request
+    }
+
+    #[test]
+    fn cidr_contains_blocks_and_addresses() {
+        let ip: IpAddr = "10.1.2.3".parse().unwrap();
+        assert!(cidr_contains("10.1.2.0/24", ip));
+        assert!(!cidr_contains("10.1.3.0/24", ip));
+        assert!(cidr_contains("10.1.2.3", ip));
+        assert!(!cidr_contains("garbage", ip));
+    }
+
+    #[test]
+    fn key_policy_checks_address() {
+        let key = policy_key(&["10.0.0.0/8"], &[]);
+        assert!(
+            check_key_policy(&key, &request_from("/api/projects", Some("10.2.3.4:5000"))).is_ok()
+        );
+        assert!(check_key_policy(
+            &key,
+            &request_from("/api/projects", Some("192.168.1.1:5000"))
+        )
+        .is_err());
+        // IPv4-mapped IPv6 peers (dual-stack listeners) match IPv4 blocks.
+        assert!(check_key_policy(
+            &key,
+            &request_from("/api/projects", Some("[::ffff:10.0.0.1]:5000"))
+        )
+        .is_ok());
+        // No peer address (e.g. Unix socket) fails closed.
+        assert!(check_key_policy(&key, &request_from("/api/projects", None)).is_err());
+    }
+
+    #[test]
+    fn key_policy_checks_route() {
+        let key = policy_key(&[], &["/api/claude-runs", "/api/runners"]);
+        assert!(check_key_policy(&key, &request_from("/api/claude-runs/claim", None)).is_ok());
+        assert_eq!(
+            check_key_policy(&key, &request_from("/api/projects", None)),
+            Err("API key not permitted for this route")
+        );
+    }
+
+    async fn call(
+        app: &axum::Router,
+        method: &str,
+        uri: &str,
+        key: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        use tower::ServiceExt;
+
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {key}"))
+            .header("content-type", "application/json")
+            .body(match body {
+                Some(body) => axum::body::Body::from(body.to_string()),
+                None => axum::body::Body::empty(),
+            })
+            .unwrap();
+        let resp = app.clone().oneshot(request).await.unwrap();
+        let status = resp.status();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    #[tokio::test]
+    async fn scoped_key_only_reaches_its_projects() {
+        use crate::test_helpers::test_state_with_auth;
+
+        let (state, admin) = test_state_with_auth().await;
+        let app = crate::routes::build_router(state.clone());
+        let mut projects = Vec::new();
+        let mut tasks = Vec::new();
+        for slug in ["team-a", "team-b"] {
+            let (_, project) = call(
+                &app,
+                "POST",
+                "/api/projects",
+                &admin,
+                Some(json!({ "name": slug, "slug": slug })),
+            )
+            .await;
+            let project_id = project["id"].as_str().unwrap().to_string();
+            let (_, task) = call(
+                &app,
+                "POST",
+                "/api/tasks",
+                &admin,
+                Some(json!({
+                    "project_id": project_id,
+                    "title": slug,
+                    "status": "todo",
+                    "priority": "medium",
+                })),
+            )
+            .await;
+            projects.push(project_id);
+            tasks.push(task["id"].as_str().unwrap().to_string());
+        }
+        let key = state
+            .db
+            .insert_api_key("team-a", &sha256_hex("fs_team_a"))
+            .await
+            .unwrap();
+        state
+            .db
+            .set_api_key_projects(&key.id, &projects[..1])
+            .await
+            .unwrap();
+        let team = "fs_team_a";
+
+        let (status, listed) = call(&app, "GET", "/api/projects", team, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(listed.as_array().unwrap().len(), 1);
+        let (_, listed) = call(&app, "GET", "/api/tasks", team, None).await;
+        assert_eq!(listed[0]["id"], tasks[0].as_str());
+        assert_eq!(listed.as_array().unwrap().len(), 1);
+
+        let uri = |task: &str| format!("/api/tasks/{task}/spec");
+        assert_eq!(
+            call(&app, "GET", &uri(&tasks[0]), team, None).await.0,
+            StatusCode::OK
+        );
+        assert_eq!(
+            call(&app, "GET", &uri(&tasks[1]), team, None).await.0,
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            call(
+                &app,
+                "GET",
+                &format!("/api/projects/{}", projects[1]),
+                team,
+                None
+            )
+            .await
+            .0,
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            call(&app, "GET", "/api/projects/by-slug/team-b", team, None)
+                .await
+                .0,
+            StatusCode::NOT_FOUND
+        );
+        let create = json!({
+            "project_id": projects[1],
+            "title": "sneaky",
+            "status": "todo",
+            "priority": "medium",
+        });
+        assert_eq!(
+            call(&app, "POST", "/api/tasks", team, Some(create)).await.0,
+            StatusCode::NOT_FOUND
+        );
+        let (status, _) = call(
+            &app,
+            "GET",
+            &format!("/api/tasks?project_id={}", projects[1]),
+            team,
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        // A run in the other project is left for someone else
+        let (status, _) = call(
+            &app,
+            "POST",
+            &format!("/api/tasks/{}/claude-runs", tasks[1]),
+            &admin,
+            Some(json!({ "action": "research" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(
+            call(&app, "POST", "/api/claude-runs/claim", team, None)
+                .await
+                .0,
+            StatusCode::NO_CONTENT
+        );
+        assert_eq!(
+            call(&app, "GET", &uri(&tasks[1]), &admin, None).await.0,
+            StatusCode::OK
+        );
+
+        // Server-wide routes would reach every project
+        for (method, uri, body) in [
+            (
+                "PATCH",
+                "/admin/flags",
+                json!({ "key": "salvage", "enabled": false }),
+            ),
+            ("PUT", "/admin/maintenance", json!({ "enabled": true })),
+            (
+                "POST",
+                "/admin/webhooks",
+                json!({ "url": "https://hooks.example.com/all", "events": ["task.created"] }),
+            ),
+        ] {
+            assert_eq!(
+                call(&app, method, uri, team, Some(body)).await.0,
+                StatusCode::NOT_FOUND,
+                "{method} {uri}"
+            );
+        }
+        let (_, maintenance) = call(&app, "GET", "/admin/maintenance", &admin, None).await;
+        assert_eq!(maintenance["enabled"], false);
+        let (_, hooks) = call(&app, "GET", "/admin/webhooks", &admin, None).await;
+        assert_eq!(hooks.as_array().unwrap().len(), 0);
+    }
+
+    #[tokio::test]
+    async fn scoped_key_cannot_reach_links_or_notifications_elsewhere() {
+        use crate::test_helpers::test_state_with_auth;
+
+        let (state, admin) = test_state_with_auth().await;
+        let app = crate::routes::build_router(state.clone());
+        let (_, user) = call(
+            &app,
+            "POST",
+            "/api/users",
+            &admin,
+            Some(json!({ "name": "Alice", "email": "" })),
+        )
+        .await;
+        let user_id = user["id"].as_str().unwrap().to_string();
+        let mut projects = Vec::new();
+        for slug in ["team-a", "team-b"] {
+            let (_, project) = call(
+                &app,
+                "POST",
+                "/api/projects",
+                &admin,
+                Some(json!({ "name": slug, "slug": slug })),
+            )
+            .await;
+            projects.push(project["id"].as_str().unwrap().to_string());
+        }
+        // One watched task in team-a and two in team-b, each with a
+        // notification for Alice
+        let mut tasks = Vec::new();
+        for project_id in [&projects[0], &projects[1], &projects[1]] {
+            let (_, task) = call(
+                &app,
+                "POST",
+                "/api/tasks",
+                &admin,
+                Some(json!({
+                    "project_id": project_id,
+                    "title": "Watched",
+                    "status": "todo",
+                    "priority": "medium",
+                })),
+            )
+            .await;
+            let task_id = task["id"].as_str().unwrap().to_string();
+            let watch = json!({ "user_id": user_id });
+            let uri = format!("/api/tasks/{task_id}/watch");
+            call(&app, "POST", &uri, &admin, Some(watch)).await;
+            let update = json!({ "priority": "urgent" });
+            let uri = format!("/api/tasks/{task_id}");
+            call(&app, "PUT", &uri, &admin, Some(update)).await;
+            tasks.push(task_id);
+        }
+        let (status, link) = call(
+            &app,
+            "POST",
+            "/api/task-links",
+            &admin,
+            Some(json!({
+                "source_task_id": tasks[1],
+                "target_task_id": tasks[2],
+                "link_type": "blocks",
+            })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let link_uri = format!("/api/task-links/{}", link["id"].as_str().unwrap());
+
+        let key = state
+            .db
+            .insert_api_key("team-a", &sha256_hex("fs_team_a"))
+            .await
+            .unwrap();
+        state
+            .db
+            .set_api_key_projects(&key.id, &projects[..1])
+            .await
+            .unwrap();
+        let team = "fs_team_a";
+
+        assert_eq!(
+            call(&app, "DELETE", &link_uri, team, None).await.0,
+            StatusCode::NOT_FOUND
+        );
+        let (_, links) = call(
+            &app,
+            "GET",
+            &format!("/api/tasks/{}/links", tasks[1]),
+            &admin,
+            None,
+        )
+        .await;
+        assert_eq!(links.as_array().unwrap().len(), 1);
+
+        let list = format!("/api/notifications?user_id={user_id}");
+        let (_, all) = call(&app, "GET", &list, &admin, None).await;
+        assert_eq!(all.as_array().unwrap().len(), 3);
+        let (status, visible) = call(&app, "GET", &list, team, None).await;
+        assert_eq!(status, StatusCode::OK);
+        let visible = visible.as_array().unwrap();
+        assert_eq!(visible.len(), 1);
+        assert_eq!(visible[0]["task_id"], tasks[0].as_str());
+
+        let other = all
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|n| n["task_id"] != tasks[0].as_str())
+            .unwrap();
+        let read_uri = |n: &Value| format!("/api/notifications/{}/read", n["id"].as_str().unwrap());
+        assert_eq!(
+            call(&app, "POST", &read_uri(other), team, None).await.0,
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            call(&app, "POST", &read_uri(&visible[0]), team, None)
+                .await
+                .0,
+            StatusCode::OK
+        );
+        let (_, unread) = call(&app, "GET", &format!("{list}&unread=true"), &admin, None).await;
+        assert_eq!(unread.as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn validate_key_policy_rejects_bad_entries() {
+        assert!(
+            validate_key_policy(&["10.0.0.0/8".into(), "::1".into()], &["/api".into()]).is_ok()
+        );
+        assert!(validate_key_policy(&["10.0.0.0/33".into()], &[]).is_err());
+        assert!(validate_key_policy(&[], &["api/tasks".into()]).is_err());
+    }
 }
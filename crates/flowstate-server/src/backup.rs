@@ -0,0 +1,147 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use flowstate_db::snapshot::SNAPSHOT_FORMAT_VERSION;
+use flowstate_db::{Database, Snapshot};
+
+/// Export every entity from `db` into a pretty-printed JSON archive at `path`.
+///
+/// The archive is written to a temporary file beside `path` and renamed over
+/// it, so a crash mid-write leaves the previous backup intact.
+///
+/// Returns the snapshot that was written so callers can report counts.
+pub async fn write_backup(db: &dyn Database, path: &Path) -> Result<Snapshot> {
+    let snapshot = db
+        .export_snapshot()
+        .await
+        .context("failed to export database snapshot")?;
+    let json = serde_json::to_vec_pretty(&snapshot)?;
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let written = std::fs::File::create(&tmp).and_then(|mut file| {
+        file.write_all(&json)?;
+        file.sync_all()
+    });
+    if let Err(e) = written.and_then(|()| std::fs::rename(&tmp, path)) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e).with_context(|| format!("failed to write {}", path.display()));
+    }
+    Ok(snapshot)
+}
+
+/// Read a JSON archive produced by [`write_backup`] and import it into `db`.
+///
+/// The target database must be empty (no projects); restoring into a
+/// populated database would collide on primary keys.
+pub async fn restore_backup(db: &dyn Database, path: &Path) -> Result<Snapshot> {
+    let data = std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let snapshot: Snapshot =
+        serde_json::from_slice(&data).context("backup file is not a valid flowstate snapshot")?;
+    if snapshot.format_version > SNAPSHOT_FORMAT_VERSION {
+        bail!(
+            "backup format version {} is newer than supported version {}",
+            snapshot.format_version,
+            SNAPSHOT_FORMAT_VERSION
+        );
+    }
+    if !db.list_projects().await?.is_empty() {
+        bail!("refusing to restore into a non-empty database");
+    }
+    db.import_snapshot(&snapshot)
+        .await
+        .context("failed to import snapshot")?;
+    Ok(snapshot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flowstate_core::project::CreateProject;
+
+    fn make_project(slug: &str) -> CreateProject {
+        CreateProject {
+            name: slug.into(),
+            slug: slug.into(),
+            description: String::new(),
+            repo_url: String::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn backup_then_restore_into_fresh_db() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("nested/backup.json");
+
+        let src = flowstate_db::SqliteDatabase::open_in_memory().unwrap();
+        let project = src.create_project(&make_project("bk")).await.unwrap();
+        let written = write_backup(&src, &path).await.unwrap();
+        assert_eq!(written.projects.len(), 1);
+        assert!(path.exists());
+
+        let dst = flowstate_db::SqliteDatabase::open_in_memory().unwrap();
+        let restored = restore_backup(&dst, &path).await.unwrap();
+        assert_eq!(restored.entity_count(), 1);
+        assert_eq!(dst.get_project(&project.id).await.unwrap().slug, "bk");
+    }
+
+    #[tokio::test]
+    async fn backup_replaces_the_previous_one_whole() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("backup.json");
+        std::fs::write(&path, b"previous backup").unwrap();
+
+        let db = flowstate_db::SqliteDatabase::open_in_memory().unwrap();
+        db.create_project(&make_project("bk")).await.unwrap();
+        write_backup(&db, &path).await.unwrap();
+        let snapshot: Snapshot = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(snapshot.projects.len(), 1);
+        assert_eq!(
+            std::fs::read_dir(tmp.path()).unwrap().count(),
+            1,
+            "no temporary file is left behind"
+        );
+    }
+
+    #[tokio::test]
+    async fn restore_refuses_non_empty_db() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("backup.json");
+
+        let db = flowstate_db::SqliteDatabase::open_in_memory().unwrap();
+        db.create_project(&make_project("existing")).await.unwrap();
+        write_backup(&db, &path).await.unwrap();
+
+        let err = restore_backup(&db, &path).await.unwrap_err();
+        assert!(err.to_string().contains("non-empty"));
+    }
+
+    #[tokio::test]
+    async fn restore_rejects_newer_format() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("future.json");
+        let mut snap = Snapshot::new();
+        snap.format_version = SNAPSHOT_FORMAT_VERSION + 1;
+        std::fs::write(&path, serde_json::to_vec(&snap).unwrap()).unwrap();
+
+        let db = flowstate_db::SqliteDatabase::open_in_memory().unwrap();
+        let err = restore_backup(&db, &path).await.unwrap_err();
+        assert!(err.to_string().contains("newer"));
+    }
+
+    #[tokio::test]
+    async fn restore_rejects_garbage() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("garbage.json");
+        std::fs::write(&path, b"not json").unwrap();
+
+        let db = flowstate_db::SqliteDatabase::open_in_memory().unwrap();
+        assert!(restore_backup(&db, &path).await.is_err());
+    }
+}
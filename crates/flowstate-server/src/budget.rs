@@ -0,0 +1,170 @@
+//! Monthly cost budgets for projects.
+//!
+//! A project's spend is the `cost_usd` its runners report in run metrics,
+//! summed over the current calendar month in UTC. Once the spend reaches
+//! `monthly_budget_usd`, no further Build runs are queued for the project
+//! until the month turns or `budget_override_until` lets them through.
+//! Other actions are cheap enough to keep running.
+//!
+//! A run whose backend reported no cost cannot be counted, so while the
+//! month has any, the spend is unknown and Build runs are refused as if the
+//! budget were spent. An override lets them through.
+
+use chrono::{DateTime, Datelike, TimeZone, Utc};
+use flowstate_core::project::Project;
+use flowstate_core::run_metrics::RunMetricsFilter;
+use flowstate_db::{Database, DbError};
+use serde::Serialize;
+
+/// Share of the budget at which a `budget_warning` is first sent. A second
+/// one follows when the budget is used up.
+pub const WARN_FRACTION: f64 = 0.8;
+
+/// Where a project stands against its budget this month.
+#[derive(Debug, Clone, PartialEq, Serialize, utoipa::ToSchema)]
+pub struct BudgetStatus {
+    pub project_id: String,
+    /// `None` when the project has no budget.
+    pub monthly_budget_usd: Option<f64>,
+    pub spent_usd: f64,
+    /// Runs this month that reported no cost and are missing from
+    /// `spent_usd`. Any at all block builds on a project with a budget.
+    pub unpriced_runs: i64,
+    pub remaining_usd: Option<f64>,
+    pub period_start: DateTime<Utc>,
+    pub period_end: DateTime<Utc>,
+    pub override_until: Option<DateTime<Utc>>,
+    /// Whether Build runs are refused right now.
+    pub builds_blocked: bool,
+}
+
+/// Midnight UTC on the first day of `now`'s month.
+pub fn month_start(now: DateTime<Utc>) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(now.year(), now.month(), 1, 0, 0, 0)
+        .single()
+        .expect("the first of a month is a valid date")
+}
+
+/// Midnight UTC on the first day of the month after `now`'s.
+pub fn next_month_start(now: DateTime<Utc>) -> DateTime<Utc> {
+    let (year, month) = if now.month() == 12 {
+        (now.year() + 1, 1)
+    } else {
+        (now.year(), now.month() + 1)
+    };
+    Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0)
+        .single()
+        .expect("the first of a month is a valid date")
+}
+
+/// Cost reported by `project_id`'s runs recorded at or after `since`, and
+/// how many of those runs reported none.
+pub async fn spend_since(
+    db: &dyn Database,
+    project_id: &str,
+    since: DateTime<Utc>,
+) -> Result<(f64, i64), DbError> {
+    let filter = RunMetricsFilter {
+        project_id: Some(project_id.to_string()),
+        since: Some(since),
+    };
+    let summaries = db.summarize_run_metrics(&filter).await?;
+    Ok((
+        summaries.iter().map(|s| s.cost_usd).sum(),
+        summaries.iter().map(|s| s.unpriced_runs).sum(),
+    ))
+}
+
+/// `project`'s spend and budget for the month containing `now`.
+pub async fn status(
+    db: &dyn Database,
+    project: &Project,
+    now: DateTime<Utc>,
+) -> Result<BudgetStatus, DbError> {
+    let period_start = month_start(now);
+    let (spent_usd, unpriced_runs) = spend_since(db, &project.id, period_start).await?;
+    let overridden = project
+        .budget_override_until
+        .is_some_and(|until| until > now);
+    let builds_blocked = !overridden
+        && project
+            .monthly_budget_usd
+            .is_some_and(|budget| spent_usd >= budget || unpriced_runs > 0);
+    Ok(BudgetStatus {
+        project_id: project.id.clone(),
+        monthly_budget_usd: project.monthly_budget_usd,
+        spent_usd,
+        unpriced_runs,
+        remaining_usd: project
+            .monthly_budget_usd
+            .map(|budget| (budget - spent_usd).max(0.0)),
+        period_start,
+        period_end: next_month_start(now),
+        override_until: project.budget_override_until,
+        builds_blocked,
+    })
+}
+
+impl BudgetStatus {
+    /// Why Build runs are refused, for the error returned to the caller.
+    pub fn blocked_reason(&self) -> String {
+        let budget = self.monthly_budget_usd.unwrap_or_default();
+        let until = self.period_end.to_rfc3339();
+        if self.unpriced_runs > 0 && self.spent_usd < budget {
+            format!(
+                "{} run(s) this month reported no cost, so spend against the ${budget:.2} \
+                 monthly budget is unknown; no builds until {until} unless an override is set",
+                self.unpriced_runs
+            )
+        } else {
+            format!(
+                "project has spent ${:.2} of its ${budget:.2} monthly budget; no builds until {until}",
+                self.spent_usd
+            )
+        }
+    }
+}
+
+/// The share of `budget` that spend passed on its way from `before` to
+/// `after`: `1.0` when it was used up, [`WARN_FRACTION`] when it came
+/// close, `None` when it crossed neither.
+pub fn crossed_threshold(budget: f64, before: f64, after: f64) -> Option<f64> {
+    [1.0, WARN_FRACTION]
+        .into_iter()
+        .find(|fraction| before < budget * fraction && after >= budget * fraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn month_bounds_wrap_the_year() {
+        let now = Utc.with_ymd_and_hms(2026, 12, 31, 23, 59, 59).unwrap();
+        assert_eq!(
+            month_start(now),
+            Utc.with_ymd_and_hms(2026, 12, 1, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            next_month_start(now),
+            Utc.with_ymd_and_hms(2027, 1, 1, 0, 0, 0).unwrap()
+        );
+        let now = Utc.with_ymd_and_hms(2026, 2, 1, 0, 0, 0).unwrap();
+        assert_eq!(month_start(now), now);
+        assert_eq!(
+            next_month_start(now),
+            Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn thresholds_fire_once_each() {
+        assert_eq!(crossed_threshold(100.0, 10.0, 50.0), None);
+        assert_eq!(crossed_threshold(100.0, 70.0, 85.0), Some(WARN_FRACTION));
+        assert_eq!(crossed_threshold(100.0, 80.0, 90.0), None);
+        assert_eq!(crossed_threshold(100.0, 90.0, 100.0), Some(1.0));
+        // A single expensive run can cross both; the larger one is reported
+        assert_eq!(crossed_threshold(100.0, 10.0, 120.0), Some(1.0));
+        assert_eq!(crossed_threshold(100.0, 120.0, 130.0), None);
+    }
+}
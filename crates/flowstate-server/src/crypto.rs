@@ -40,6 +40,14 @@ fn load_or_generate_key_at(path: &std::path::Path) -> Key<Aes256Gcm> {
     key
 }
 
+/// Whether objects are encrypted before they reach the store, read from
+/// `FLOWSTATE_STORE_ENCRYPT`.
+pub(crate) fn store_encryption_from_env() -> bool {
+    std::env::var("FLOWSTATE_STORE_ENCRYPT")
+        .map(|v| matches!(v.as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
 /// Encrypt plaintext. Returns a base64 string containing nonce + ciphertext.
 pub fn encrypt(key: &Key<Aes256Gcm>, plaintext: &str) -> Result<String, String> {
     let cipher = Aes256Gcm::new(key);
@@ -76,6 +84,14 @@ pub fn decrypt(key: &Key<Aes256Gcm>, encoded: &str) -> Result<String, String> {
     String::from_utf8(plaintext).map_err(|e| format!("utf8: {e}"))
 }
 
+/// Directory holding the server's local secrets (`~/.config/flowstate`).
+pub(crate) fn config_dir() -> PathBuf {
+    key_file_path()
+        .parent()
+        .map(PathBuf::from)
+        .unwrap_or_default()
+}
+
 fn key_file_path() -> PathBuf {
     key_file_path_from(
         std::env::var("XDG_CONFIG_HOME").ok(),
@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use tokio::time::Instant;
+use tracing::{error, info, warn};
+
+use crate::routes::AppState;
+use crate::shutdown;
+
+/// Hours between passes when `FLOWSTATE_DB_MAINTENANCE_HOURS` is unset.
+const DEFAULT_INTERVAL_HOURS: u64 = 24;
+
+/// Delay before the first pass, so a server that restarts more often than
+/// the interval still gets maintained.
+const FIRST_PASS_DELAY: Duration = Duration::from_secs(10 * 60);
+
+/// Time between maintenance passes, read from
+/// `FLOWSTATE_DB_MAINTENANCE_HOURS`; `0` turns maintenance off.
+pub fn interval_from_env() -> Option<Duration> {
+    let hours = std::env::var("FLOWSTATE_DB_MAINTENANCE_HOURS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_INTERVAL_HOURS);
+    (hours > 0).then(|| Duration::from_secs(hours * 60 * 60))
+}
+
+/// Background task that runs `Database::run_maintenance` every `interval`,
+/// logs each step and keeps the latest report for `/api/status`.
+pub async fn run_db_maintenance(state: AppState, interval: Duration) {
+    let first = Instant::now() + FIRST_PASS_DELAY.min(interval);
+    let mut ticker = tokio::time::interval_at(first, interval);
+    while shutdown::next_tick(&mut ticker, &state.shutdown).await {
+        run_once(&state).await;
+    }
+}
+
+async fn run_once(state: &AppState) {
+    let report = match state.db.run_maintenance().await {
+        Ok(report) => report,
+        Err(e) => {
+            error!("db maintenance error: {e}");
+            return;
+        }
+    };
+    for step in &report.steps {
+        if step.ok {
+            info!("db maintenance: {}: {}", step.name, step.detail);
+        } else {
+            warn!(
+                "db maintenance: {} needs attention: {}",
+                step.name, step.detail
+            );
+        }
+    }
+    info!("db maintenance finished in {}ms", report.duration_ms);
+    *state.db_maintenance.lock().unwrap() = Some(report);
+}
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use axum::{
+    body::Body,
+    extract::{Query, Request},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use flowstate_core::DisplayZone;
+use serde_json::{json, Map, Value};
+
+/// Axum middleware for the `?tz=` query parameter. Timestamps are stored and
+/// returned in UTC; when a request names a zone, every timestamp field of a
+/// JSON response (`*_at`, `last_seen`) is rewritten with that zone's offset
+/// and gains a `<field>_relative` sibling such as `"3h ago"`.
+pub async fn display_time_middleware(request: Request, next: Next) -> Response {
+    let zone = match Query::<HashMap<String, String>>::try_from_uri(request.uri())
+        .ok()
+        .and_then(|Query(params)| params.get("tz").cloned())
+    {
+        Some(tz) => match DisplayZone::parse_str(&tz) {
+            Ok(zone) => zone,
+            Err(e) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(json!({ "error": e.to_string() })),
+                )
+                    .into_response()
+            }
+        },
+        None => return next.run(request).await,
+    };
+
+    let response = next.run(request).await;
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    if !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({ "error": e.to_string() })),
+            )
+                .into_response()
+        }
+    };
+    let Ok(mut value) = serde_json::from_slice::<Value>(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+    localize(&mut value, &zone, Utc::now());
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(value.to_string()))
+}
+
+fn is_timestamp_key(key: &str) -> bool {
+    key.ends_with("_at") || key == "last_seen"
+}
+
+/// Rewrite the timestamps in `value` into `zone`, adding relative times.
+fn localize(value: &mut Value, zone: &DisplayZone, now: DateTime<Utc>) {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                localize(item, zone, now);
+            }
+        }
+        Value::Object(map) => localize_object(map, zone, now),
+        _ => {}
+    }
+}
+
+fn localize_object(map: &mut Map<String, Value>, zone: &DisplayZone, now: DateTime<Utc>) {
+    let mut relative = Vec::new();
+    for (key, value) in map.iter_mut() {
+        let at = match value {
+            Value::String(s) if is_timestamp_key(key) => DateTime::parse_from_rfc3339(s).ok(),
+            _ => None,
+        };
+        match at {
+            Some(at) => {
+                let at = at.with_timezone(&Utc);
+                *value = Value::String(zone.to_rfc3339(at));
+                relative.push((format!("{key}_relative"), zone.relative(at, now)));
+            }
+            None => localize(value, zone, now),
+        }
+    }
+    for (key, text) in relative {
+        map.insert(key, Value::String(text));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::test_router;
+    use axum::http::Method;
+    use chrono::TimeZone;
+    use tower::ServiceExt;
+
+    #[test]
+    fn localizes_nested_timestamps() {
+        let now = Utc.with_ymd_and_hms(2026, 10, 16, 12, 0, 0).unwrap();
+        let zone = DisplayZone::parse_str("+02:00").unwrap();
+        let mut value = json!([{
+            "title": "not_at",
+            "created_at": "2026-10-16T09:00:00+00:00",
+            "due_at": null,
+            "runner": { "last_seen": "2026-10-16T11:55:00Z" },
+        }]);
+        localize(&mut value, &zone, now);
+        assert_eq!(value[0]["title"], "not_at");
+        assert_eq!(value[0]["created_at"], "2026-10-16T11:00:00+02:00");
+        assert_eq!(value[0]["created_at_relative"], "3h ago");
+        assert_eq!(value[0]["due_at"], Value::Null);
+        assert!(value[0].get("due_at_relative").is_none());
+        assert_eq!(value[0]["runner"]["last_seen_relative"], "5m ago");
+    }
+
+    async fn send(
+        app: &axum::Router,
+        method: Method,
+        uri: &str,
+        body: Value,
+    ) -> (StatusCode, Value) {
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = resp.status();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn timestamps_are_stored_in_utc_and_shown_in_tz() {
+        let app = test_router().await;
+        let (_, project) = send(
+            &app,
+            Method::POST,
+            "/api/projects",
+            json!({ "name": "Test", "slug": "test" }),
+        )
+        .await;
+        let (status, task) = send(
+            &app,
+            Method::POST,
+            "/api/tasks",
+            json!({
+                "project_id": project["id"],
+                "title": "Task",
+                "status": "todo",
+                "priority": "medium",
+                "due_at": "2030-01-01T12:00:00+02:00",
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(task["due_at"], "2030-01-01T10:00:00Z");
+        assert!(task.get("due_at_relative").is_none());
+
+        let id = task["id"].as_str().unwrap();
+        let (status, task) = send(
+            &app,
+            Method::GET,
+            &format!("/api/tasks/{id}?tz=Asia/Tokyo"),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(task["due_at"], "2030-01-01T19:00:00+09:00");
+        assert_eq!(task["due_at_relative"], "2030-01-01");
+        assert_eq!(task["created_at_relative"], "just now");
+    }
+
+    #[tokio::test]
+    async fn unknown_tz_is_rejected() {
+        let app = test_router().await;
+        let (status, body) = send(
+            &app,
+            Method::GET,
+            "/api/projects?tz=Mars/Olympus",
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("Mars/Olympus"));
+    }
+}
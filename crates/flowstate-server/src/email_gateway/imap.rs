@@ -0,0 +1,256 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use rustls::pki_types::ServerName;
+use rustls::{ClientConfig, RootCertStore};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio_rustls::client::TlsStream;
+use tokio_rustls::TlsConnector;
+
+/// How long connecting and each command may take before the poll gives up.
+const IO_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A minimal IMAP4rev1 client: just enough to read unseen messages from
+/// one mailbox and mark them seen.
+pub(super) struct ImapSession<S> {
+    stream: BufReader<S>,
+    next_tag: u32,
+}
+
+/// What the server sent for one command: its untagged lines, with any
+/// literals taken out and kept in order.
+#[derive(Debug, Default)]
+struct Response {
+    lines: Vec<String>,
+    literals: Vec<Vec<u8>>,
+}
+
+/// Open an implicit-TLS (port 993 style) connection to `host`.
+pub(super) async fn connect(host: &str, port: u16) -> Result<ImapSession<TlsStream<TcpStream>>> {
+    let roots = RootCertStore::from_iter(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    let config =
+        ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()?
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+    let name = ServerName::try_from(host.to_string())
+        .with_context(|| format!("invalid IMAP host {host}"))?;
+    let connect = async {
+        let tcp = TcpStream::connect((host, port)).await?;
+        TlsConnector::from(Arc::new(config))
+            .connect(name, tcp)
+            .await
+    };
+    let tls = tokio::time::timeout(IO_TIMEOUT, connect)
+        .await
+        .context("timed out connecting")?
+        .with_context(|| format!("connect to {host}:{port}"))?;
+    ImapSession::open(tls).await
+}
+
+impl<S: AsyncRead + AsyncWrite + Unpin> ImapSession<S> {
+    /// Wrap a connected stream and read the server greeting.
+    pub(super) async fn open(stream: S) -> Result<Self> {
+        let mut session = Self {
+            stream: BufReader::new(stream),
+            next_tag: 1,
+        };
+        let (greeting, _) = session.read_line().await?;
+        if !greeting.starts_with("* OK") && !greeting.starts_with("* PREAUTH") {
+            bail!("unexpected IMAP greeting: {greeting}");
+        }
+        Ok(session)
+    }
+
+    pub(super) async fn login(&mut self, username: &str, password: &str) -> Result<()> {
+        self.command(&format!("LOGIN {} {}", quote(username), quote(password)))
+            .await
+            .context("LOGIN")?;
+        Ok(())
+    }
+
+    pub(super) async fn select(&mut self, mailbox: &str) -> Result<()> {
+        self.command(&format!("SELECT {}", quote(mailbox)))
+            .await
+            .with_context(|| format!("SELECT {mailbox}"))?;
+        Ok(())
+    }
+
+    /// UIDs of the messages in the selected mailbox without `\Seen`.
+    pub(super) async fn search_unseen(&mut self) -> Result<Vec<u32>> {
+        let response = self.command("UID SEARCH UNSEEN").await?;
+        Ok(response
+            .lines
+            .iter()
+            .filter_map(|line| line.strip_prefix("* SEARCH"))
+            .flat_map(|uids| uids.split_whitespace().filter_map(|u| u.parse().ok()))
+            .collect())
+    }
+
+    /// The raw RFC 822 message. `BODY.PEEK` leaves it unseen, so a message
+    /// that fails to file is tried again on the next poll.
+    pub(super) async fn fetch(&mut self, uid: u32) -> Result<Vec<u8>> {
+        let response = self
+            .command(&format!("UID FETCH {uid} BODY.PEEK[]"))
+            .await?;
+        response
+            .literals
+            .into_iter()
+            .next()
+            .with_context(|| format!("message {uid} not found"))
+    }
+
+    pub(super) async fn mark_seen(&mut self, uid: u32) -> Result<()> {
+        self.command(&format!("UID STORE {uid} +FLAGS.SILENT (\\Seen)"))
+            .await?;
+        Ok(())
+    }
+
+    pub(super) async fn logout(&mut self) -> Result<()> {
+        self.command("LOGOUT").await?;
+        Ok(())
+    }
+
+    /// Send one tagged command and read up to its completion, failing unless
+    /// the server answers `OK`.
+    async fn command(&mut self, command: &str) -> Result<Response> {
+        let tag = format!("a{}", self.next_tag);
+        self.next_tag += 1;
+        let exchange = async {
+            let stream = self.stream.get_mut();
+            stream
+                .write_all(format!("{tag} {command}\r\n").as_bytes())
+                .await?;
+            stream.flush().await?;
+
+            let mut response = Response::default();
+            loop {
+                let (line, literals) = self.read_line().await?;
+                response.literals.extend(literals);
+                let Some(status) = line.strip_prefix(&format!("{tag} ")) else {
+                    response.lines.push(line);
+                    continue;
+                };
+                if !status.starts_with("OK") {
+                    bail!("{status}");
+                }
+                return Ok(response);
+            }
+        };
+        tokio::time::timeout(IO_TIMEOUT, exchange)
+            .await
+            .context("timed out waiting for the IMAP server")?
+    }
+
+    /// Read one logical response line. A line ending in `{n}` is followed by
+    /// an n-byte literal and then the rest of the line; literals are returned
+    /// separately and the line keeps only its text.
+    async fn read_line(&mut self) -> Result<(String, Vec<Vec<u8>>)> {
+        let mut text = String::new();
+        let mut literals = Vec::new();
+        loop {
+            let mut raw = Vec::new();
+            if self.stream.read_until(b'\n', &mut raw).await? == 0 {
+                bail!("IMAP server closed the connection");
+            }
+            let part = String::from_utf8_lossy(&raw);
+            let part = part.trim_end_matches(['\r', '\n']);
+            text.push_str(part);
+            let Some(len) = literal_len(part) else {
+                return Ok((text, literals));
+            };
+            let mut literal = vec![0; len];
+            self.stream.read_exact(&mut literal).await?;
+            literals.push(literal);
+        }
+    }
+}
+
+/// The length announced by a trailing `{n}`, if the line ends in one.
+fn literal_len(line: &str) -> Option<usize> {
+    let open = line.strip_suffix('}')?.rfind('{')?;
+    line[open + 1..line.len() - 1].parse().ok()
+}
+
+/// An IMAP quoted string.
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::duplex;
+
+    /// Play the server side of a session: answer each expected command with
+    /// its canned reply.
+    async fn serve(server: tokio::io::DuplexStream, script: Vec<(&'static str, String)>) {
+        let mut server = BufReader::new(server);
+        server
+            .get_mut()
+            .write_all(b"* OK IMAP4rev1 ready\r\n")
+            .await
+            .unwrap();
+        for (expected, reply) in script {
+            let mut line = String::new();
+            server.read_line(&mut line).await.unwrap();
+            assert_eq!(line.trim_end(), expected);
+            server.get_mut().write_all(reply.as_bytes()).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn reads_unseen_messages_and_marks_them_seen() {
+        let message = "From: a@example.com\r\nSubject: Hi\r\n\r\nBody {3}\r\n";
+        let script = vec![
+            (
+                "a1 LOGIN \"user\" \"p\\\"w\"",
+                "a1 OK logged in\r\n".to_string(),
+            ),
+            (
+                "a2 SELECT \"INBOX\"",
+                "* 2 EXISTS\r\na2 OK [READ-WRITE] done\r\n".to_string(),
+            ),
+            (
+                "a3 UID SEARCH UNSEEN",
+                "* SEARCH 7 9\r\na3 OK done\r\n".to_string(),
+            ),
+            (
+                "a4 UID FETCH 7 BODY.PEEK[]",
+                format!(
+                    "* 1 FETCH (UID 7 BODY[] {{{}}}\r\n{message})\r\na4 OK done\r\n",
+                    message.len()
+                ),
+            ),
+            (
+                "a5 UID STORE 7 +FLAGS.SILENT (\\Seen)",
+                "a5 OK done\r\n".to_string(),
+            ),
+            (
+                "a6 UID FETCH 8 BODY.PEEK[]",
+                "a6 NO no such message\r\n".to_string(),
+            ),
+        ];
+        let (client, server) = duplex(4096);
+        let server = tokio::spawn(serve(server, script));
+
+        let mut session = ImapSession::open(client).await.unwrap();
+        session.login("user", "p\"w").await.unwrap();
+        session.select("INBOX").await.unwrap();
+        assert_eq!(session.search_unseen().await.unwrap(), vec![7, 9]);
+        assert_eq!(session.fetch(7).await.unwrap(), message.as_bytes());
+        session.mark_seen(7).await.unwrap();
+        let err = session.fetch(8).await.unwrap_err();
+        assert_eq!(err.to_string(), "NO no such message");
+        server.await.unwrap();
+    }
+
+    #[test]
+    fn literal_lengths() {
+        assert_eq!(literal_len("* 1 FETCH (BODY[] {42}"), Some(42));
+        assert_eq!(literal_len("* OK done"), None);
+        assert_eq!(literal_len("* OK {x}"), None);
+    }
+}
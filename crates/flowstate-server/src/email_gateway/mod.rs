@@ -0,0 +1,491 @@
+mod imap;
+
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use bytes::Bytes;
+use flowstate_core::task::{CreateTask, Priority, Status, Task, TaskType};
+use flowstate_service::{ServiceError, TaskService};
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use mail_parser::{MessageParser, MimeHeaders};
+use tracing::{error, info, warn};
+
+use crate::routes::AppState;
+use crate::shutdown;
+
+/// Seconds between mailbox polls when `FLOWSTATE_EMAIL_POLL_SECS` is unset.
+const DEFAULT_POLL_SECS: u64 = 60;
+
+/// Title for an email sent without a subject.
+const NO_SUBJECT: &str = "(no subject)";
+
+/// Settings for the email-in gateway, which files emails sent to a mailbox
+/// as tasks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailGatewayConfig {
+    pub imap_host: String,
+    pub imap_port: u16,
+    pub username: String,
+    pub password: String,
+    /// Mailbox polled for new mail.
+    pub mailbox: String,
+    /// Slug of the project new tasks are filed in.
+    pub project: String,
+    pub allowlist: SenderAllowlist,
+    pub poll_interval: Duration,
+    /// Where confirmations are sent from; without one, senders get no reply.
+    pub smtp: Option<SmtpConfig>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    /// The gateway's own address, which confirmations come from.
+    pub from: String,
+}
+
+impl EmailGatewayConfig {
+    /// Returns `Ok(None)` when `FLOWSTATE_EMAIL_IMAP_HOST` is unset. With it
+    /// set, missing credentials, project or allowlist are a configuration
+    /// error rather than a gateway that quietly files nothing, or anything.
+    pub fn from_env() -> Result<Option<Self>> {
+        Self::from_getter(|key| std::env::var(key).ok())
+    }
+
+    /// Build from an arbitrary variable-lookup function (testable without env mutation).
+    pub fn from_getter(get: impl Fn(&str) -> Option<String>) -> Result<Option<Self>> {
+        let get = |key: &str| get(key).filter(|v| !v.trim().is_empty());
+        let Some(imap_host) = get("FLOWSTATE_EMAIL_IMAP_HOST") else {
+            return Ok(None);
+        };
+        let required = |key: &str| {
+            get(key).with_context(|| format!("{key} is required with FLOWSTATE_EMAIL_IMAP_HOST"))
+        };
+        let port = |key: &str, default: u16| match get(key) {
+            Some(v) => v.parse::<u16>().with_context(|| format!("{key}: {v:?}")),
+            None => Ok(default),
+        };
+        let username = required("FLOWSTATE_EMAIL_USERNAME")?;
+        let allowlist = SenderAllowlist::parse(&required("FLOWSTATE_EMAIL_ALLOWLIST")?);
+        if allowlist.is_empty() {
+            bail!("FLOWSTATE_EMAIL_ALLOWLIST lists no senders");
+        }
+        let poll_secs = match get("FLOWSTATE_EMAIL_POLL_SECS") {
+            Some(v) => v
+                .parse::<u64>()
+                .ok()
+                .filter(|secs| *secs > 0)
+                .with_context(|| format!("FLOWSTATE_EMAIL_POLL_SECS: {v:?}"))?,
+            None => DEFAULT_POLL_SECS,
+        };
+        let smtp = match get("FLOWSTATE_EMAIL_SMTP_HOST") {
+            Some(host) => Some(SmtpConfig {
+                host,
+                port: port("FLOWSTATE_EMAIL_SMTP_PORT", 465)?,
+                from: get("FLOWSTATE_EMAIL_ADDRESS").unwrap_or_else(|| username.clone()),
+            }),
+            None => None,
+        };
+        Ok(Some(Self {
+            imap_port: port("FLOWSTATE_EMAIL_IMAP_PORT", 993)?,
+            imap_host,
+            password: required("FLOWSTATE_EMAIL_PASSWORD")?,
+            username,
+            mailbox: get("FLOWSTATE_EMAIL_MAILBOX").unwrap_or_else(|| "INBOX".into()),
+            project: required("FLOWSTATE_EMAIL_PROJECT")?,
+            allowlist,
+            poll_interval: Duration::from_secs(poll_secs),
+            smtp,
+        }))
+    }
+}
+
+/// Senders whose email becomes tasks: full addresses, or `@domain` for
+/// everyone at a domain. Matching ignores case.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SenderAllowlist {
+    addresses: Vec<String>,
+    domains: Vec<String>,
+}
+
+impl SenderAllowlist {
+    /// Parse a comma-separated list such as `alice@example.com,@corp.example`.
+    pub fn parse(list: &str) -> Self {
+        let mut allowlist = Self::default();
+        for entry in list.split(',').map(|e| e.trim().to_lowercase()) {
+            if let Some(domain) = entry.strip_prefix('@') {
+                if !domain.is_empty() {
+                    allowlist.domains.push(domain.to_string());
+                }
+            } else if entry.contains('@') {
+                allowlist.addresses.push(entry);
+            }
+        }
+        allowlist
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.addresses.is_empty() && self.domains.is_empty()
+    }
+
+    pub fn allows(&self, address: &str) -> bool {
+        let address = address.trim().to_lowercase();
+        if self.addresses.contains(&address) {
+            return true;
+        }
+        match address.rsplit_once('@') {
+            Some((_, domain)) => self.domains.iter().any(|d| d == domain),
+            None => false,
+        }
+    }
+}
+
+/// The parts of an email that become a task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InboundEmail {
+    /// The sender's address, from `From`.
+    pub from: String,
+    pub subject: String,
+    pub body: String,
+    pub message_id: Option<String>,
+    pub attachments: Vec<InboundAttachment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InboundAttachment {
+    pub filename: String,
+    pub content_type: String,
+    pub data: Bytes,
+}
+
+impl InboundEmail {
+    /// Parse a raw RFC 822 message. `None` when it has no sender address.
+    pub fn parse(raw: &[u8]) -> Option<Self> {
+        let message = MessageParser::default().parse(raw)?;
+        let from = message.from()?.first()?.address()?.to_string();
+        let attachments = message
+            .attachments()
+            .enumerate()
+            .map(|(i, part)| {
+                let filename = part
+                    .attachment_name()
+                    .map(|name| name.replace(['/', '\\'], "_"))
+                    .filter(|name| !name.trim().is_empty())
+                    .unwrap_or_else(|| format!("attachment-{}", i + 1));
+                let content_type = match part.content_type() {
+                    Some(ct) => match ct.subtype() {
+                        Some(sub) => format!("{}/{sub}", ct.ctype()),
+                        None => ct.ctype().to_string(),
+                    },
+                    None => flowstate_core::attachment::guess_content_type(&filename).into(),
+                };
+                InboundAttachment {
+                    filename,
+                    content_type,
+                    data: Bytes::copy_from_slice(part.contents()),
+                }
+            })
+            .collect();
+        Some(Self {
+            from,
+            subject: message.subject().unwrap_or_default().trim().to_string(),
+            body: message
+                .body_text(0)
+                .map(|body| body.trim().to_string())
+                .unwrap_or_default(),
+            message_id: message.message_id().map(str::to_string),
+            attachments,
+        })
+    }
+
+    fn title(&self) -> &str {
+        if self.subject.is_empty() {
+            NO_SUBJECT
+        } else {
+            &self.subject
+        }
+    }
+
+    fn description(&self) -> String {
+        let sent_by = format!("Filed by email from {}.", self.from);
+        if self.body.is_empty() {
+            sent_by
+        } else {
+            format!("{}\n\n---\n{sent_by}", self.body)
+        }
+    }
+}
+
+/// Create a Todo task for `email` in `project_id` and attach its
+/// attachments. An attachment that fails to store is logged and skipped,
+/// since the task itself already exists.
+pub async fn file_email(
+    state: &AppState,
+    project_id: &str,
+    email: &InboundEmail,
+) -> Result<Task, ServiceError> {
+    let task = state
+        .service
+        .create_task(&CreateTask {
+            project_id: project_id.to_string(),
+            title: email.title().to_string(),
+            description: email.description(),
+            status: Status::Todo,
+            priority: Priority::Medium,
+            task_type: TaskType::default(),
+            parent_id: None,
+            reviewer: String::new(),
+            due_at: None,
+            research_capability: None,
+            design_capability: None,
+            plan_capability: None,
+            build_capability: None,
+            verify_capability: None,
+        })
+        .await?;
+    crate::routes::tasks::publish_task(state, &task);
+    for attachment in &email.attachments {
+        if let Err(e) = crate::routes::tasks::attach_bytes(
+            state,
+            &task.id,
+            &attachment.filename,
+            &attachment.content_type,
+            attachment.data.clone(),
+        )
+        .await
+        {
+            warn!(
+                "email gateway: attaching {} to {}: {e}",
+                attachment.filename, task.id
+            );
+        }
+    }
+    Ok(task)
+}
+
+/// Background task that polls the mailbox every `poll_interval` and files
+/// new mail from allowed senders as tasks.
+pub async fn run_email_gateway(state: AppState, config: EmailGatewayConfig) {
+    let mut ticker = tokio::time::interval(config.poll_interval);
+    while shutdown::next_tick(&mut ticker, &state.shutdown).await {
+        match poll_once(&state, &config).await {
+            Ok(0) => {}
+            Ok(filed) => info!("email gateway: filed {filed} task(s)"),
+            Err(e) => error!("email gateway error: {e:#}"),
+        }
+    }
+}
+
+/// Read the unseen messages in the mailbox once. Messages are marked seen
+/// once filed or rejected; one that fails to file stays unseen and is tried
+/// again on the next poll. Returns how many tasks were created.
+async fn poll_once(state: &AppState, config: &EmailGatewayConfig) -> Result<usize> {
+    let project = state
+        .service
+        .get_project_by_slug(&config.project)
+        .await
+        .with_context(|| format!("project {}", config.project))?;
+    let mut session = imap::connect(&config.imap_host, config.imap_port).await?;
+    session.login(&config.username, &config.password).await?;
+    session.select(&config.mailbox).await?;
+
+    let mut filed = 0;
+    for uid in session.search_unseen().await? {
+        let raw = session.fetch(uid).await?;
+        let Some(email) = InboundEmail::parse(&raw) else {
+            warn!("email gateway: message {uid} has no sender, skipping");
+            session.mark_seen(uid).await?;
+            continue;
+        };
+        if !config.allowlist.allows(&email.from) {
+            warn!(
+                "email gateway: ignoring message from {}: not on the allowlist",
+                email.from
+            );
+            session.mark_seen(uid).await?;
+            continue;
+        }
+        let task = match file_email(state, &project.id, &email).await {
+            Ok(task) => task,
+            Err(e) => {
+                warn!("email gateway: filing message from {}: {e}", email.from);
+                continue;
+            }
+        };
+        session.mark_seen(uid).await?;
+        info!(
+            "email gateway: filed '{}' ({}) from {}",
+            task.title, task.id, email.from
+        );
+        filed += 1;
+        if let Some(smtp) = &config.smtp {
+            let link = state.task_links.task_url(&task.id);
+            if let Err(e) = send_confirmation(config, smtp, &email, &task, &link).await {
+                warn!("email gateway: confirming to {}: {e:#}", email.from);
+            }
+        }
+    }
+    let _ = session.logout().await;
+    Ok(filed)
+}
+
+/// Reply to the sender with the task their email became.
+async fn send_confirmation(
+    config: &EmailGatewayConfig,
+    smtp: &SmtpConfig,
+    email: &InboundEmail,
+    task: &Task,
+    link: &str,
+) -> Result<()> {
+    let mut message = Message::builder()
+        .from(smtp.from.parse()?)
+        .to(email.from.parse()?)
+        .subject(format!("Re: {}", email.title()))
+        .header(ContentType::TEXT_PLAIN);
+    if let Some(id) = &email.message_id {
+        message = message
+            .in_reply_to(format!("<{id}>"))
+            .references(format!("<{id}>"));
+    }
+    let message = message.body(confirmation_body(task, link))?;
+    let transport = AsyncSmtpTransport::<Tokio1Executor>::relay(&smtp.host)?
+        .port(smtp.port)
+        .credentials(Credentials::new(
+            config.username.clone(),
+            config.password.clone(),
+        ))
+        .build();
+    transport.send(message).await?;
+    Ok(())
+}
+
+fn confirmation_body(task: &Task, link: &str) -> String {
+    format!(
+        "Your email has been filed as task \"{}\" ({}).\n\n{link}\n",
+        task.title, task.id
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    const RAW: &str = "From: Alice <Alice@Example.com>\r\n\
+        To: tasks@flowstate.example\r\n\
+        Subject: Login page is broken\r\n\
+        Message-ID: <abc@example.com>\r\n\
+        MIME-Version: 1.0\r\n\
+        Content-Type: multipart/mixed; boundary=\"b\"\r\n\
+        \r\n\
+        --b\r\n\
+        Content-Type: text/plain\r\n\
+        \r\n\
+        The button does nothing.\r\n\
+        --b\r\n\
+        Content-Type: image/png\r\n\
+        Content-Disposition: attachment; filename=\"screen.png\"\r\n\
+        Content-Transfer-Encoding: base64\r\n\
+        \r\n\
+        iVBORw0KGgo=\r\n\
+        --b--\r\n";
+
+    fn vars(pairs: &[(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+        let map: HashMap<_, _> = pairs.iter().copied().collect();
+        move |key| map.get(key).map(|v| v.to_string())
+    }
+
+    #[test]
+    fn config_needs_credentials_project_and_allowlist() {
+        assert_eq!(EmailGatewayConfig::from_getter(vars(&[])).unwrap(), None);
+
+        let base = [
+            ("FLOWSTATE_EMAIL_IMAP_HOST", "imap.example.com"),
+            ("FLOWSTATE_EMAIL_USERNAME", "tasks@example.com"),
+            ("FLOWSTATE_EMAIL_PASSWORD", "secret"),
+            ("FLOWSTATE_EMAIL_PROJECT", "web"),
+            ("FLOWSTATE_EMAIL_ALLOWLIST", "@example.com"),
+        ];
+        let config = EmailGatewayConfig::from_getter(vars(&base))
+            .unwrap()
+            .unwrap();
+        assert_eq!(config.imap_port, 993);
+        assert_eq!(config.mailbox, "INBOX");
+        assert_eq!(config.poll_interval, Duration::from_secs(60));
+        assert_eq!(config.smtp, None);
+
+        let mut with_smtp = base.to_vec();
+        with_smtp.push(("FLOWSTATE_EMAIL_SMTP_HOST", "smtp.example.com"));
+        let config = EmailGatewayConfig::from_getter(vars(&with_smtp))
+            .unwrap()
+            .unwrap();
+        let smtp = config.smtp.unwrap();
+        assert_eq!((smtp.port, smtp.from.as_str()), (465, "tasks@example.com"));
+
+        let no_allowlist: Vec<_> = base
+            .iter()
+            .copied()
+            .filter(|(k, _)| *k != "FLOWSTATE_EMAIL_ALLOWLIST")
+            .collect();
+        let err = EmailGatewayConfig::from_getter(vars(&no_allowlist)).unwrap_err();
+        assert!(err.to_string().contains("FLOWSTATE_EMAIL_ALLOWLIST"));
+    }
+
+    #[test]
+    fn allowlist_matches_addresses_and_domains() {
+        let allowlist = SenderAllowlist::parse("Bob@Other.org, @example.com, junk");
+        assert!(allowlist.allows("bob@other.org"));
+        assert!(allowlist.allows("alice@EXAMPLE.com"));
+        assert!(!allowlist.allows("eve@other.org"));
+        assert!(!allowlist.allows("eve@notexample.com"));
+        assert!(SenderAllowlist::parse("junk, @").is_empty());
+    }
+
+    #[test]
+    fn parses_sender_subject_body_and_attachments() {
+        let email = InboundEmail::parse(RAW.as_bytes()).unwrap();
+        assert_eq!(email.from, "Alice@Example.com");
+        assert_eq!(email.subject, "Login page is broken");
+        assert_eq!(email.body, "The button does nothing.");
+        assert_eq!(email.message_id.as_deref(), Some("abc@example.com"));
+        assert_eq!(email.attachments.len(), 1);
+        assert_eq!(email.attachments[0].filename, "screen.png");
+        assert_eq!(email.attachments[0].content_type, "image/png");
+        assert_eq!(email.attachments[0].data.as_ref(), b"\x89PNG\r\n\x1a\n");
+        assert!(email
+            .description()
+            .ends_with("---\nFiled by email from Alice@Example.com."));
+    }
+
+    #[tokio::test]
+    async fn filing_creates_a_task_with_attachments() {
+        let state = crate::test_helpers::test_state().await;
+        let project = state
+            .service
+            .create_project(&flowstate_core::project::CreateProject {
+                name: "Web".into(),
+                slug: "web".into(),
+                description: String::new(),
+                repo_url: String::new(),
+            })
+            .await
+            .unwrap();
+        let email = InboundEmail::parse(RAW.as_bytes()).unwrap();
+
+        let task = file_email(&state, &project.id, &email).await.unwrap();
+        assert_eq!(task.title, "Login page is broken");
+        assert_eq!(task.status, Status::Todo);
+        assert!(task.description.starts_with("The button does nothing."));
+        let attachments = state.db.list_attachments(&task.id).await.unwrap();
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments[0].filename, "screen.png");
+        let stored = state.store.get(&attachments[0].store_key).await.unwrap();
+        assert_eq!(stored.as_ref(), b"\x89PNG\r\n\x1a\n");
+
+        let body = confirmation_body(&task, "https://flowstate.example/t/1");
+        assert!(body.contains(&task.id));
+    }
+}
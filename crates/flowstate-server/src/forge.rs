@@ -0,0 +1,355 @@
+//! Pull request calls the server makes itself, on the repository host, with
+//! the project's repo token.
+//!
+//! Runners talk to the host through their own providers while they work on
+//! a task; these are the few things flowstate does with a PR once the work
+//! is done: watching its CI checks, and merging or closing it.
+
+use std::time::Duration;
+
+use flowstate_core::project::{Project, ProviderType};
+use flowstate_core::task_pr::{self, ChecksStatus, MergeStrategy, PrCheck};
+use reqwest::{Method, StatusCode};
+use serde_json::{json, Value};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, thiserror::Error)]
+pub enum ForgeError {
+    #[error("{0}")]
+    Unsupported(String),
+    /// The host understood the request and declined it, e.g. a merge
+    /// conflict or a failing required check.
+    #[error("{0}")]
+    Refused(String),
+    #[error("{0}")]
+    Failed(String),
+}
+
+/// Host, scheme, owner and repo of an HTTPS or `git@host:owner/repo` URL.
+fn repo_parts(repo_url: &str) -> Option<(String, String, String, String)> {
+    let url = repo_url.trim().trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+    let (scheme, rest) = match url.split_once("://") {
+        Some((scheme, rest)) => (scheme, rest.to_string()),
+        None => ("https", url.strip_prefix("git@")?.replacen(':', "/", 1)),
+    };
+    let mut parts = rest.split('/');
+    let host = parts.next().filter(|h| !h.is_empty())?;
+    let host = host.rsplit('@').next().unwrap_or(host);
+    let (owner, repo) = (parts.next()?, parts.next()?);
+    if owner.is_empty() || repo.is_empty() || parts.next().is_some() {
+        return None;
+    }
+    Some((scheme.into(), host.into(), owner.into(), repo.into()))
+}
+
+/// The REST endpoint of the GitHub repository at `repo_url`: on
+/// api.github.com for github.com, and under `/api/v3` on the same host for
+/// GitHub Enterprise Server.
+pub fn github_repo_endpoint(repo_url: &str) -> Option<String> {
+    let (scheme, host, owner, repo) = repo_parts(repo_url)?;
+    let api = if host == "github.com" {
+        "https://api.github.com".to_string()
+    } else {
+        format!("{scheme}://{host}/api/v3")
+    };
+    Some(format!("{api}/repos/{owner}/{repo}"))
+}
+
+/// The GraphQL endpoint of the GitHub host of `repo_url`, for the few
+/// operations REST lacks.
+pub fn github_graphql_endpoint(repo_url: &str) -> Option<String> {
+    let (scheme, host, _, _) = repo_parts(repo_url)?;
+    Some(if host == "github.com" {
+        "https://api.github.com/graphql".to_string()
+    } else {
+        format!("{scheme}://{host}/api/graphql")
+    })
+}
+
+/// The REST endpoint of the Gitea repository at `repo_url`.
+pub fn gitea_repo_endpoint(repo_url: &str) -> Option<String> {
+    let (scheme, host, owner, repo) = repo_parts(repo_url)?;
+    Some(format!("{scheme}://{host}/api/v1/repos/{owner}/{repo}"))
+}
+
+/// Merge pull request `pr_number` of the project's repository.
+pub async fn merge_pull_request(
+    project: &Project,
+    token: &str,
+    pr_number: i64,
+    strategy: MergeStrategy,
+) -> Result<(), ForgeError> {
+    match project.provider_type.unwrap_or_default() {
+        ProviderType::Github => {
+            let endpoint = endpoint(project, github_repo_endpoint)?;
+            let body = json!({ "merge_method": strategy.as_str() });
+            let url = format!("{endpoint}/pulls/{pr_number}/merge");
+            send(project, token, Method::PUT, &url, Some(body))
+                .await
+                .map(drop)
+        }
+        ProviderType::Gitea => {
+            let endpoint = endpoint(project, gitea_repo_endpoint)?;
+            let body = json!({ "Do": strategy.as_str() });
+            let url = format!("{endpoint}/pulls/{pr_number}/merge");
+            send(project, token, Method::POST, &url, Some(body))
+                .await
+                .map(drop)
+        }
+        other => Err(unsupported(other)),
+    }
+}
+
+/// Close pull request `pr_number` of the project's repository without
+/// merging it.
+pub async fn close_pull_request(
+    project: &Project,
+    token: &str,
+    pr_number: i64,
+) -> Result<(), ForgeError> {
+    let endpoint = match project.provider_type.unwrap_or_default() {
+        ProviderType::Github => endpoint(project, github_repo_endpoint)?,
+        ProviderType::Gitea => endpoint(project, gitea_repo_endpoint)?,
+        other => return Err(unsupported(other)),
+    };
+    let url = format!("{endpoint}/pulls/{pr_number}");
+    let body = json!({ "state": "closed" });
+    send(project, token, Method::PATCH, &url, Some(body))
+        .await
+        .map(drop)
+}
+
+/// Mark draft pull request `pr_number` ready for review. On Gitea, which
+/// has no drafts, this drops the `WIP:` title prefix.
+pub async fn mark_pull_request_ready(
+    project: &Project,
+    token: &str,
+    pr_number: i64,
+) -> Result<(), ForgeError> {
+    match project.provider_type.unwrap_or_default() {
+        ProviderType::Github => {
+            let graphql = endpoint(project, github_graphql_endpoint)?;
+            let endpoint = endpoint(project, github_repo_endpoint)?;
+            let pr = send(
+                project,
+                token,
+                Method::GET,
+                &format!("{endpoint}/pulls/{pr_number}"),
+                None,
+            )
+            .await?;
+            if pr["draft"] != Value::Bool(true) {
+                return Ok(());
+            }
+            let Some(node_id) = pr["node_id"].as_str() else {
+                return Err(ForgeError::Failed("pull request has no node id".into()));
+            };
+            let body = json!({
+                "query": "mutation($id: ID!) { markPullRequestReadyForReview(input: {pullRequestId: $id}) { clientMutationId } }",
+                "variables": { "id": node_id },
+            });
+            let resp = send(project, token, Method::POST, &graphql, Some(body)).await?;
+            // GraphQL reports failures in the body of a 200
+            match resp["errors"][0]["message"].as_str() {
+                Some(message) => Err(ForgeError::Refused(message.to_string())),
+                None => Ok(()),
+            }
+        }
+        ProviderType::Gitea => {
+            let endpoint = endpoint(project, gitea_repo_endpoint)?;
+            let url = format!("{endpoint}/pulls/{pr_number}");
+            let pr = send(project, token, Method::GET, &url, None).await?;
+            let title = pr["title"].as_str().unwrap_or_default();
+            let Some(title) = task_pr::strip_wip_prefix(title) else {
+                return Ok(());
+            };
+            let body = json!({ "title": title });
+            send(project, token, Method::PATCH, &url, Some(body))
+                .await
+                .map(drop)
+        }
+        other => Err(unsupported(other)),
+    }
+}
+
+/// The CI checks on the head commit of pull request `pr_number`: check
+/// runs and commit statuses on GitHub, commit statuses on Gitea.
+pub async fn get_pr_checks(
+    project: &Project,
+    token: &str,
+    pr_number: i64,
+) -> Result<Vec<PrCheck>, ForgeError> {
+    let provider = project.provider_type.unwrap_or_default();
+    let endpoint = match provider {
+        ProviderType::Github => endpoint(project, github_repo_endpoint)?,
+        ProviderType::Gitea => endpoint(project, gitea_repo_endpoint)?,
+        other => return Err(unsupported(other)),
+    };
+    let pr = send(
+        project,
+        token,
+        Method::GET,
+        &format!("{endpoint}/pulls/{pr_number}"),
+        None,
+    )
+    .await?;
+    let Some(sha) = pr["head"]["sha"].as_str() else {
+        return Err(ForgeError::Failed("pull request has no head commit".into()));
+    };
+    let combined = send(
+        project,
+        token,
+        Method::GET,
+        &format!("{endpoint}/commits/{sha}/status"),
+        None,
+    )
+    .await?;
+    // GitHub names a status's result `state`, Gitea `status`
+    let mut checks: Vec<PrCheck> = combined["statuses"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|s| PrCheck {
+            name: s["context"].as_str().unwrap_or_default().to_string(),
+            status: ChecksStatus::from_commit_state(
+                s["state"]
+                    .as_str()
+                    .or(s["status"].as_str())
+                    .unwrap_or_default(),
+            ),
+            url: s["target_url"]
+                .as_str()
+                .filter(|u| !u.is_empty())
+                .map(str::to_string),
+        })
+        .collect();
+    if provider == ProviderType::Github {
+        let runs = send(
+            project,
+            token,
+            Method::GET,
+            &format!("{endpoint}/commits/{sha}/check-runs?per_page=100"),
+            None,
+        )
+        .await?;
+        checks.extend(
+            runs["check_runs"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|r| PrCheck {
+                    name: r["name"].as_str().unwrap_or_default().to_string(),
+                    status: ChecksStatus::from_check_run(
+                        r["status"].as_str().unwrap_or_default(),
+                        r["conclusion"].as_str(),
+                    ),
+                    url: r["html_url"].as_str().map(str::to_string),
+                }),
+        );
+    }
+    Ok(checks)
+}
+
+fn endpoint(project: &Project, parse: fn(&str) -> Option<String>) -> Result<String, ForgeError> {
+    parse(&project.repo_url).ok_or_else(|| {
+        ForgeError::Unsupported(format!(
+            "repo_url is not a {} repository",
+            project.provider_type.unwrap_or_default().as_str()
+        ))
+    })
+}
+
+fn unsupported(provider: ProviderType) -> ForgeError {
+    ForgeError::Unsupported(format!(
+        "flowstate cannot manage pull requests on {}",
+        provider.as_str()
+    ))
+}
+
+async fn send(
+    project: &Project,
+    token: &str,
+    method: Method,
+    url: &str,
+    body: Option<Value>,
+) -> Result<Value, ForgeError> {
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent("flowstate")
+        .danger_accept_invalid_certs(project.skip_tls_verify)
+        .build()
+        .map_err(|e| ForgeError::Failed(e.to_string()))?;
+    let mut request = client.request(method, url);
+    if let Some(body) = body {
+        request = request.json(&body);
+    }
+    let request = match project.provider_type.unwrap_or_default() {
+        ProviderType::Gitea => request.header("authorization", format!("token {token}")),
+        _ => request
+            .bearer_auth(token)
+            .header("accept", "application/vnd.github+json")
+            .header("x-github-api-version", "2022-11-28"),
+    };
+    let resp = request
+        .send()
+        .await
+        .map_err(|e| ForgeError::Failed(e.to_string()))?;
+    let status = resp.status();
+    if status.is_success() {
+        // Merges and closes answer with bodies nothing here reads
+        return Ok(resp.json().await.unwrap_or(Value::Null));
+    }
+    let message = resp
+        .json::<Value>()
+        .await
+        .ok()
+        .and_then(|v| v["message"].as_str().map(str::to_string))
+        .unwrap_or_else(|| status.to_string());
+    match status {
+        StatusCode::METHOD_NOT_ALLOWED
+        | StatusCode::CONFLICT
+        | StatusCode::UNPROCESSABLE_ENTITY => Err(ForgeError::Refused(message)),
+        _ => Err(ForgeError::Failed(format!("{status}: {message}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn github_endpoints() {
+        assert_eq!(
+            github_repo_endpoint("https://github.com/acme/app.git").as_deref(),
+            Some("https://api.github.com/repos/acme/app")
+        );
+        assert_eq!(
+            github_repo_endpoint("git@ghe.example.com:acme/app.git").as_deref(),
+            Some("https://ghe.example.com/api/v3/repos/acme/app")
+        );
+        assert_eq!(github_repo_endpoint("https://github.com/acme"), None);
+        assert_eq!(
+            github_graphql_endpoint("https://github.com/acme/app").as_deref(),
+            Some("https://api.github.com/graphql")
+        );
+        assert_eq!(
+            github_graphql_endpoint("http://ghe.local:8080/acme/app").as_deref(),
+            Some("http://ghe.local:8080/api/graphql")
+        );
+    }
+
+    #[test]
+    fn gitea_endpoints() {
+        assert_eq!(
+            gitea_repo_endpoint("https://git.example.com/acme/app.git").as_deref(),
+            Some("https://git.example.com/api/v1/repos/acme/app")
+        );
+        assert_eq!(
+            gitea_repo_endpoint("http://user@localhost:3000/acme/app/").as_deref(),
+            Some("http://localhost:3000/api/v1/repos/acme/app")
+        );
+        assert_eq!(gitea_repo_endpoint("https://git.example.com/a/b/c"), None);
+    }
+}
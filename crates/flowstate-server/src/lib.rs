@@ -1,31 +1,71 @@
 pub mod auth;
+pub mod backup;
+pub mod budget;
 pub mod crypto;
+pub mod db_maintenance;
+pub mod display_time;
+pub mod email_gateway;
+pub mod forge;
+pub mod listen;
+pub mod notifier;
+pub mod oidc;
+pub mod orchestrator;
 pub mod pod_manager;
+pub mod pr_checks;
+pub mod project_config;
+pub mod rate_limit;
+pub mod request_span;
+pub mod retention;
 #[cfg(any(test, feature = "test-helpers"))]
 pub mod routes;
 #[cfg(not(any(test, feature = "test-helpers")))]
 mod routes;
+pub mod runner_pki;
+pub mod seed;
+pub mod server_config;
+pub mod shutdown;
+pub mod store_gc;
+pub mod tls;
 pub mod watchdog;
+pub mod webhooks;
 
 #[cfg(any(test, feature = "test-helpers"))]
 pub mod test_helpers;
 
 use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use anyhow::Result;
 use flowstate_db::Database;
 use flowstate_service::LocalService;
-use tokio::net::TcpListener;
 
 use auth::AuthConfig;
+use axum::extract::connect_info::Connected;
+use axum::serve::IncomingStream;
+use listen::PeerInfo;
 use routes::{AppState, InnerAppState};
 
-pub async fn serve(
-    listener: TcpListener,
+/// Run the server on `listener` until it fails or is asked to stop.
+///
+/// On SIGTERM or ctrl-c it stops accepting connections, gives in-flight
+/// requests up to `FLOWSTATE_DRAIN_TIMEOUT_SECS` to finish, and then waits as
+/// long again for background tasks to end their current pass.
+///
+/// `runner_mtls` makes runner-facing routes reject connections that did not
+/// present a verified client certificate; it only has an effect when the
+/// listener terminates TLS with client verification enabled.
+pub async fn serve<L>(
+    listener: L,
     db: Arc<dyn Database>,
     auth: Option<Arc<AuthConfig>>,
-) -> Result<()> {
+    runner_mtls: bool,
+) -> Result<()>
+where
+    L: axum::serve::Listener,
+    L::Addr: std::fmt::Debug,
+    for<'a> PeerInfo: Connected<IncomingStream<'a, L>>,
+{
     let encryption_key = crypto::load_or_generate_key();
     let store_config = flowstate_store::StoreConfig::from_env();
     if store_config.is_s3() {
@@ -34,6 +74,14 @@ pub async fn serve(
             store_config.endpoint_url.as_deref().unwrap_or("?"),
             store_config.bucket.as_deref().unwrap_or("?"),
         );
+    } else if let Some(gcs) = &store_config.gcs {
+        tracing::info!("storage backend: gcs (bucket={})", gcs.bucket);
+    } else if let Some(azure) = &store_config.azure {
+        tracing::info!(
+            "storage backend: azure (account={}, container={})",
+            azure.account,
+            azure.container,
+        );
     } else {
         tracing::info!(
             "storage backend: local ({})",
@@ -43,9 +91,29 @@ pub async fn serve(
                 .unwrap_or(&flowstate_db::data_dir().to_string_lossy()),
         );
     }
-    let store = flowstate_store::create_store(&store_config)
+    let mut store = flowstate_store::create_store(&store_config)
         .map_err(|e| anyhow::anyhow!("failed to create object store: {e}"))?;
+    if crypto::store_encryption_from_env() {
+        tracing::info!("store encryption: enabled");
+        store = Arc::new(flowstate_store::EncryptedStore::new(store, &encryption_key));
+    }
+    // Always wrapped, so output compressed earlier still reads once the
+    // toggle is turned off
+    let compress_run_output = run_output_compression_from_env();
+    tracing::info!(
+        "run output compression: {}",
+        if compress_run_output {
+            "enabled"
+        } else {
+            "disabled"
+        }
+    );
+    store = Arc::new(flowstate_store::CompressedStore::new(
+        store,
+        compress_run_output,
+    ));
     let service = LocalService::new(db.clone());
+    let retention_store = store.clone();
 
     // Check for pod manager configuration
     let pod_manager_state = pod_manager::PodManagerConfig::from_env().map(|pm_config| {
@@ -53,9 +121,21 @@ pub async fn serve(
         let state = Arc::new(tokio::sync::Mutex::new(pod_manager::PodManagerState::new(
             pod_id,
         )));
-        (pm_config, state)
+        let api: Arc<dyn pod_manager::RunPodApi> =
+            Arc::new(pod_manager::RunPodClient::new(&pm_config.api_key));
+        (pm_config, state, api)
     });
 
+    let task_links = routes::notifications::task_links_from_env()
+        .map_err(|e| anyhow::anyhow!("FLOWSTATE_TASK_LINK: {e}"))?;
+    let email_gateway = email_gateway::EmailGatewayConfig::from_env()?;
+    let rate_limits = rate_limit::RateLimitConfig::from_env()?;
+    let notifier = notifier::Notifier::from_env()?;
+    let watchdog_config = Arc::new(std::sync::RwLock::new(watchdog::WatchdogConfig::from_env()?));
+    if notifier.supports(flowstate_core::subscription::NotifyChannel::Email) {
+        tracing::info!("notification email: enabled");
+    }
+
     let state: AppState = Arc::new(InnerAppState {
         service,
         db: db.clone(),
@@ -63,27 +143,112 @@ pub async fn serve(
         runners: std::sync::Mutex::new(HashMap::new()),
         encryption_key,
         store,
-        pod_manager: pod_manager_state.as_ref().map(|(_, s)| s.clone()),
+        pod_manager: pod_manager_state.as_ref().map(|(_, s, _)| s.clone()),
+        pod_api: pod_manager_state.as_ref().map(|(_, _, api)| api.clone()),
+        watchdog: watchdog_config.clone(),
+        runner_mtls,
+        maintenance: AtomicBool::new(routes::admin::maintenance_from_env()),
+        status_page: routes::status::StatusPage::new(routes::status::StatusExposure::from_env()),
+        db_maintenance: std::sync::Mutex::new(None),
+        task_links,
+        notifier,
+        events: Default::default(),
+        run_logs: Default::default(),
+        github_webhook_secret: routes::github::secret_from_env(),
+        rate_limiter: rate_limit::RateLimiter::new(rate_limits),
+        shutdown: Default::default(),
     });
 
     let app = routes::build_router(state.clone());
+    let shutdown = state.shutdown.clone();
+    let mut background = Vec::new();
 
-    // Launch the watchdog background task (scans every 60 seconds)
+    // Launch the retention task if configured (scans hourly)
+    if let Some(retention) = retention::retention_from_env() {
+        let retention_db = db.clone();
+        let stop = shutdown.clone();
+        background.push(tokio::spawn(async move {
+            retention::run_retention(retention_db, retention_store, retention, 3600, stop).await;
+        }));
+    }
+
+    // Launch scheduled store garbage collection if configured
+    if let Some(interval) = store_gc::interval_from_env() {
+        let gc_db = db.clone();
+        let gc_store = state.store.clone();
+        let stop = shutdown.clone();
+        background.push(tokio::spawn(async move {
+            store_gc::run_store_gc(gc_db, gc_store, interval, stop).await;
+        }));
+    }
+
+    // Launch the watchdog background task
     let watchdog_db = db;
-    tokio::spawn(async move {
-        watchdog::run_watchdog(watchdog_db, 60).await;
-    });
+    let stop = shutdown.clone();
+    background.push(tokio::spawn(async move {
+        watchdog::run_watchdog(watchdog_db, watchdog_config, stop).await;
+    }));
+
+    // Launch the webhook delivery worker
+    let webhook_db = state.db.clone();
+    let webhook_key = state.encryption_key;
+    let stop = shutdown.clone();
+    background.push(tokio::spawn(async move {
+        webhooks::run_webhook_worker(webhook_db, webhook_key, stop).await;
+    }));
+
+    // Launch scheduled database maintenance unless turned off
+    if let Some(interval) = db_maintenance::interval_from_env() {
+        let maintenance_state = state.clone();
+        background.push(tokio::spawn(async move {
+            db_maintenance::run_db_maintenance(maintenance_state, interval).await;
+        }));
+    }
+
+    // Launch PR checks polling unless turned off
+    if let Some(interval) = pr_checks::interval_from_env() {
+        let checks_state = state.clone();
+        background.push(tokio::spawn(async move {
+            pr_checks::run_pr_checks(checks_state, interval).await;
+        }));
+    }
+
+    // Launch the email-in gateway if configured
+    if let Some(config) = email_gateway {
+        tracing::info!(
+            "email gateway enabled ({}@{}, project {})",
+            config.mailbox,
+            config.imap_host,
+            config.project
+        );
+        let gateway_state = state.clone();
+        background.push(tokio::spawn(async move {
+            email_gateway::run_email_gateway(gateway_state, config).await;
+        }));
+    }
 
     // Launch the pod manager background task if configured
-    if let Some((pm_config, pm_state)) = pod_manager_state {
+    if let Some((pm_config, pm_state, api)) = pod_manager_state {
         let pm_app_state = state;
-        let api = Arc::new(pod_manager::RunPodClient::new(&pm_config.api_key));
         tracing::info!("pod manager enabled (pod_id={:?})", pm_config.pod_id);
-        tokio::spawn(async move {
+        background.push(tokio::spawn(async move {
             pod_manager::run_pod_manager(pm_app_state, pm_config, pm_state, api).await;
-        });
+        }));
     }
 
-    axum::serve(listener, app).await?;
+    let drain = shutdown::drain_timeout_from_env();
+    shutdown::serve_until(listener, app, shutdown::signal(), shutdown.clone(), drain).await?;
+    // A server that failed rather than being stopped still stops its tasks
+    shutdown.cancel();
+    shutdown::join_background(background, drain).await;
+    tracing::info!("shutdown complete");
     Ok(())
 }
+
+/// Whether run output is compressed before it is stored, read from
+/// `FLOWSTATE_COMPRESS_RUN_OUTPUT`. On unless set to `0`, `false` or `no`.
+fn run_output_compression_from_env() -> bool {
+    std::env::var("FLOWSTATE_COMPRESS_RUN_OUTPUT")
+        .map(|v| !matches!(v.as_str(), "0" | "false" | "no"))
+        .unwrap_or(true)
+}
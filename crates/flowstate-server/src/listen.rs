@@ -0,0 +1,139 @@
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use axum::extract::connect_info::Connected;
+use axum::serve::IncomingStream;
+
+/// Where the server accepts connections, parsed from `FLOWSTATE_BIND`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BindTarget {
+    /// IP address + `FLOWSTATE_PORT`.
+    Tcp(SocketAddr),
+    /// `unix:/path/to.sock` — no TCP port is opened at all.
+    Unix(PathBuf),
+}
+
+impl BindTarget {
+    pub fn parse(bind: &str, port: u16) -> Result<Self> {
+        if let Some(path) = bind.strip_prefix("unix:") {
+            if path.is_empty() {
+                bail!("FLOWSTATE_BIND=unix: requires a socket path");
+            }
+            if !cfg!(unix) {
+                bail!("unix domain sockets are not supported on this platform");
+            }
+            return Ok(Self::Unix(PathBuf::from(path)));
+        }
+        let ip = bind
+            .parse()
+            .with_context(|| format!("invalid FLOWSTATE_BIND address: {bind}"))?;
+        Ok(Self::Tcp(SocketAddr::new(ip, port)))
+    }
+}
+
+/// Per-connection metadata made available to handlers and middleware as
+/// `ConnectInfo<PeerInfo>`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PeerInfo {
+    /// Remote address, when the transport has one (not for Unix sockets).
+    pub remote_addr: Option<SocketAddr>,
+    /// Common name of the client certificate verified during the TLS
+    /// handshake, if the client presented one.
+    pub client_cert_cn: Option<String>,
+}
+
+impl Connected<IncomingStream<'_, tokio::net::TcpListener>> for PeerInfo {
+    fn connect_info(stream: IncomingStream<'_, tokio::net::TcpListener>) -> Self {
+        Self {
+            remote_addr: Some(*stream.remote_addr()),
+            client_cert_cn: None,
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Connected<IncomingStream<'_, tokio::net::UnixListener>> for PeerInfo {
+    fn connect_info(_stream: IncomingStream<'_, tokio::net::UnixListener>) -> Self {
+        Self::default()
+    }
+}
+
+/// Bind a Unix domain socket at `path`, replacing a stale socket left behind
+/// by a previous run. The socket is restricted to the owning user.
+#[cfg(unix)]
+pub fn bind_unix(path: &Path) -> Result<tokio::net::UnixListener> {
+    use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+    if let Ok(meta) = std::fs::symlink_metadata(path) {
+        if !meta.file_type().is_socket() {
+            bail!("{} exists and is not a socket", path.display());
+        }
+        std::fs::remove_file(path)
+            .with_context(|| format!("failed to remove stale socket {}", path.display()))?;
+    }
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+    let listener = tokio::net::UnixListener::bind(path)
+        .with_context(|| format!("failed to bind {}", path.display()))?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_ip_uses_port() {
+        let target = BindTarget::parse("127.0.0.1", 4000).unwrap();
+        assert_eq!(target, BindTarget::Tcp("127.0.0.1:4000".parse().unwrap()));
+    }
+
+    #[test]
+    fn parse_unix_path() {
+        let target = BindTarget::parse("unix:/run/flowstate.sock", 3710).unwrap();
+        assert_eq!(
+            target,
+            BindTarget::Unix(PathBuf::from("/run/flowstate.sock"))
+        );
+    }
+
+    #[test]
+    fn parse_rejects_empty_unix_path() {
+        assert!(BindTarget::parse("unix:", 3710).is_err());
+    }
+
+    #[test]
+    fn parse_rejects_garbage() {
+        assert!(BindTarget::parse("not-an-ip", 3710).is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn bind_unix_replaces_stale_socket_and_restricts_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("run/flowstate.sock");
+        drop(bind_unix(&path).unwrap());
+        assert!(path.exists(), "stale socket should be left behind");
+
+        let _listener = bind_unix(&path).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn bind_unix_refuses_regular_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("not-a-socket");
+        std::fs::write(&path, b"data").unwrap();
+        let err = bind_unix(&path).unwrap_err();
+        assert!(err.to_string().contains("not a socket"));
+    }
+}
@@ -1,16 +1,29 @@
-use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
+use flowstate_db::migrate::{Direction, MigrationPlan};
 use flowstate_db::Database;
+use flowstate_service::telemetry::Telemetry;
 use tokio::net::TcpListener;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
 
 use flowstate_server::auth;
+use flowstate_server::listen::BindTarget;
+use flowstate_server::oidc::{Oidc, OidcConfig};
+use flowstate_server::project_config;
+use flowstate_server::runner_pki::{self, RunnerCa, RunnerCaPaths};
+use flowstate_server::server_config::ServerConfigFile;
+use flowstate_server::tls::{self, TlsConfig, TlsListener};
 
 #[derive(Parser)]
 #[command(name = "flowstate-server")]
 struct Cli {
+    /// Server configuration file (TOML); environment variables override it
+    #[arg(long, global = true, env = "FLOWSTATE_CONFIG")]
+    config: Option<PathBuf>,
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -22,38 +35,180 @@ enum Commands {
         /// Human-readable name for the key
         #[arg(long, default_value = "")]
         name: String,
+        /// Only accept the key from this CIDR block or address (repeatable)
+        #[arg(long = "allow-cidr")]
+        allow_cidrs: Vec<String>,
+        /// Only accept the key on routes under this path prefix (repeatable)
+        #[arg(long = "allow-route")]
+        allow_routes: Vec<String>,
+        /// Only let the key see and change this project, by id or slug (repeatable)
+        #[arg(long = "project")]
+        projects: Vec<String>,
+        /// Make the key belong to this organization, by id or slug
+        #[arg(long)]
+        org: Option<String>,
     },
     /// List all API keys (metadata only, no secrets)
     ListKeys,
+    /// Replace an API key's CIDR, route and project allowlists (no flags clears them)
+    SetKeyPolicy {
+        /// The API key ID to update
+        id: String,
+        /// Only accept the key from this CIDR block or address (repeatable)
+        #[arg(long = "allow-cidr")]
+        allow_cidrs: Vec<String>,
+        /// Only accept the key on routes under this path prefix (repeatable)
+        #[arg(long = "allow-route")]
+        allow_routes: Vec<String>,
+        /// Only let the key see and change this project, by id or slug (repeatable)
+        #[arg(long = "project")]
+        projects: Vec<String>,
+    },
     /// Revoke (delete) an API key by ID
     RevokeKey {
         /// The API key ID to revoke
         id: String,
     },
+    /// Export all data to a portable JSON archive (works with any backend)
+    Backup {
+        /// Destination file for the archive
+        path: PathBuf,
+    },
+    /// Import a JSON archive produced by `backup` into an empty database
+    Restore {
+        /// Archive file to import
+        path: PathBuf,
+    },
+    /// Delete stored objects whose task or run no longer exists
+    Gc {
+        /// Report what would be deleted without deleting it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Fill the database with generated projects, tasks and runs for demos and load testing
+    Seed {
+        /// Number of projects to create
+        #[arg(long, default_value_t = 3)]
+        projects: usize,
+        /// Tasks per project
+        #[arg(long, default_value_t = 50)]
+        tasks: usize,
+        /// Finished runs per project
+        #[arg(long, default_value_t = 20)]
+        runs: usize,
+        /// Random seed, for the same names, statuses and outcomes on every run
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+    /// Issue a client certificate for a runner (creates the runner CA on first use)
+    EnrollRunner {
+        /// Runner name, recorded as the certificate common name
+        name: String,
+        /// Directory to write `<name>.pem` and `<name>.key` into
+        #[arg(long, default_value = ".")]
+        out: PathBuf,
+        /// Certificate validity in days
+        #[arg(long, default_value_t = 365)]
+        days: u32,
+    },
+    /// Reconcile projects and sprints with a YAML configuration file
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Migrate the schema to a version (default: latest), rolling back if it is older
+    Migrate {
+        /// Target schema version
+        #[arg(long)]
+        to: Option<i64>,
+        /// Print the pending SQL without applying it
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Show what `apply` would change, without changing anything
+    Plan {
+        /// Configuration file
+        #[arg(short = 'f', long = "file")]
+        file: PathBuf,
+    },
+    /// Show the changes, then make them
+    Apply {
+        /// Configuration file
+        #[arg(short = 'f', long = "file")]
+        file: PathBuf,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    tracing_subscriber::fmt()
-        .with_env_filter(
+    let cli = Cli::parse();
+    if let Some(path) = &cli.config {
+        let file = ServerConfigFile::load(path)?;
+        for var in file.apply_to_env() {
+            eprintln!("{var} is set; using it over {}", path.display());
+        }
+    }
+
+    // After the config file, which can set the OTLP endpoint. Held until
+    // main returns so buffered spans are flushed.
+    let telemetry =
+        Telemetry::from_env("flowstate-server").map_err(|e| anyhow::anyhow!("telemetry: {e}"))?;
+    tracing_subscriber::registry()
+        .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
         )
+        .with(tracing_subscriber::fmt::layer())
+        .with(telemetry.as_ref().map(|t| t.layer()))
         .init();
-
-    let cli = Cli::parse();
     let config = flowstate_db::DbConfig::from_env();
+
+    // Handled before opening the database, which would migrate it to latest.
+    if let Some(Commands::Migrate { to, dry_run }) = cli.command {
+        let plan = flowstate_db::migrate::migrate(&config, to, dry_run).await?;
+        print_migration_plan(&plan, dry_run);
+        return Ok(());
+    }
+
     let db: Arc<dyn Database> = flowstate_db::open_database(&config).await?;
 
     match cli.command {
-        Some(Commands::Keygen { name }) => {
+        Some(Commands::Keygen {
+            name,
+            allow_cidrs,
+            allow_routes,
+            projects,
+            org,
+        }) => {
+            auth::validate_key_policy(&allow_cidrs, &allow_routes).map_err(anyhow::Error::msg)?;
+            let projects = resolve_projects(&*db, &projects).await?;
+            let org_id = match org {
+                Some(org) => Some(resolve_org(&*db, &org).await?),
+                None => None,
+            };
             let raw_key = auth::generate_api_key();
             let hash = auth::sha256_hex(&raw_key);
-            let api_key = db.insert_api_key(&name, &hash).await?;
+            let mut api_key = db.insert_api_key(&name, &hash).await?;
+            if !allow_cidrs.is_empty() || !allow_routes.is_empty() {
+                api_key = db
+                    .set_api_key_policy(&api_key.id, &allow_cidrs, &allow_routes)
+                    .await?;
+            }
+            if !projects.is_empty() {
+                api_key = db.set_api_key_projects(&api_key.id, &projects).await?;
+            }
+            if let Some(org_id) = org_id {
+                api_key = db.set_api_key_org(&api_key.id, Some(&org_id)).await?;
+            }
             eprintln!("Created API key (id: {})", api_key.id);
             if !name.is_empty() {
                 eprintln!("  name: {name}");
             }
+            print_key_policy(&api_key);
             // Print the raw key to stdout so it can be captured
             println!("{raw_key}");
             eprintln!("\nSave this key — it cannot be retrieved again.");
@@ -72,13 +227,132 @@ async fn main() -> Result<()> {
                         key.created_at,
                         key.last_used_at.as_deref().unwrap_or("never"),
                     );
+                    if !key.allowed_cidrs.is_empty() {
+                        println!("    allowed from: {}", key.allowed_cidrs.join(", "));
+                    }
+                    if !key.allowed_routes.is_empty() {
+                        println!("    allowed routes: {}", key.allowed_routes.join(", "));
+                    }
+                    if !key.allowed_projects.is_empty() {
+                        println!("    allowed projects: {}", key.allowed_projects.join(", "));
+                    }
+                    if let Some(org_id) = &key.org_id {
+                        println!("    organization: {org_id}");
+                    }
                 }
             }
         }
+        Some(Commands::SetKeyPolicy {
+            id,
+            allow_cidrs,
+            allow_routes,
+            projects,
+        }) => {
+            auth::validate_key_policy(&allow_cidrs, &allow_routes).map_err(anyhow::Error::msg)?;
+            let projects = resolve_projects(&*db, &projects).await?;
+            db.set_api_key_policy(&id, &allow_cidrs, &allow_routes)
+                .await?;
+            let key = db.set_api_key_projects(&id, &projects).await?;
+            eprintln!("Updated policy for API key {id}");
+            print_key_policy(&key);
+        }
         Some(Commands::RevokeKey { id }) => {
             db.delete_api_key(&id).await?;
             eprintln!("Revoked API key {id}");
         }
+        Some(Commands::Backup { path }) => {
+            let snapshot = flowstate_server::backup::write_backup(&*db, &path).await?;
+            eprintln!(
+                "Wrote {} records ({} projects, {} tasks, {} runs) to {}",
+                snapshot.entity_count(),
+                snapshot.projects.len(),
+                snapshot.tasks.len(),
+                snapshot.claude_runs.len(),
+                path.display()
+            );
+        }
+        Some(Commands::Restore { path }) => {
+            let snapshot = flowstate_server::backup::restore_backup(&*db, &path).await?;
+            eprintln!(
+                "Restored {} records ({} projects, {} tasks, {} runs) from {}",
+                snapshot.entity_count(),
+                snapshot.projects.len(),
+                snapshot.tasks.len(),
+                snapshot.claude_runs.len(),
+                path.display()
+            );
+        }
+        Some(Commands::Gc { dry_run }) => {
+            let store = flowstate_store::create_store(&flowstate_store::StoreConfig::from_env())
+                .map_err(|e| anyhow::anyhow!("failed to create object store: {e}"))?;
+            let report =
+                flowstate_server::store_gc::collect_garbage(&*db, &*store, dry_run).await?;
+            for key in &report.orphaned_keys {
+                println!("{key}");
+            }
+            eprintln!(
+                "{} {} orphaned objects ({} bytes) of {} scanned",
+                if dry_run { "Would delete" } else { "Deleted" },
+                report.orphaned_keys.len(),
+                report.reclaimed_bytes,
+                report.scanned_keys
+            );
+        }
+        Some(Commands::Seed {
+            projects,
+            tasks,
+            runs,
+            seed,
+        }) => {
+            let opts = flowstate_server::seed::SeedOptions {
+                projects,
+                tasks,
+                runs,
+                seed,
+            };
+            let summary = flowstate_server::seed::seed(&*db, &opts).await?;
+            eprintln!(
+                "Seeded {} projects, {} sprints, {} tasks and {} runs",
+                summary.projects, summary.sprints, summary.tasks, summary.runs
+            );
+        }
+        Some(Commands::EnrollRunner { name, out, days }) => {
+            let paths = RunnerCaPaths::from_env();
+            let ca = RunnerCa::load_or_create(&paths)?;
+            let issued = ca.issue(&name, days)?;
+            let cert_path = out.join(format!("{name}.pem"));
+            let key_path = out.join(format!("{name}.key"));
+            runner_pki::write_file(&cert_path, &issued.cert_pem, 0o644)?;
+            runner_pki::write_file(&key_path, &issued.key_pem, 0o600)?;
+            eprintln!("Issued runner certificate for {name} (valid {days} days)");
+            eprintln!("  cert: {}", cert_path.display());
+            eprintln!("  key:  {}", key_path.display());
+            eprintln!("  CA:   {}", paths.cert_path.display());
+        }
+        Some(Commands::Config { action }) => {
+            let (path, apply) = match action {
+                ConfigAction::Plan { file } => (file, false),
+                ConfigAction::Apply { file } => (file, true),
+            };
+            let yaml = std::fs::read_to_string(&path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            let file = project_config::ConfigFile::parse(&yaml)
+                .with_context(|| format!("invalid configuration in {}", path.display()))?;
+            let plan = project_config::plan(&*db, &file).await?;
+            if plan.is_empty() {
+                eprintln!("No changes; the configuration matches {}", path.display());
+                return Ok(());
+            }
+            print!("{plan}");
+            let count = plan.changes.len();
+            if apply {
+                project_config::apply(&*db, plan).await?;
+                eprintln!("Applied {count} change(s)");
+            } else {
+                eprintln!("{count} change(s); run `config apply` to make them");
+            }
+        }
+        Some(Commands::Migrate { .. }) => unreachable!("handled before opening the database"),
         None => {
             // Default: start server
             let bind = std::env::var("FLOWSTATE_BIND").unwrap_or_else(|_| "0.0.0.0".into());
@@ -87,21 +361,153 @@ async fn main() -> Result<()> {
                 .and_then(|p| p.parse().ok())
                 .unwrap_or(3710);
 
-            let addr = SocketAddr::new(bind.parse()?, port);
+            let target = BindTarget::parse(&bind, port)?;
+            let mut tls_config = TlsConfig::from_env()?;
+            let runner_mtls = runner_pki::mtls_enabled_from_env();
+            if runner_mtls {
+                let Some(tls_config) = tls_config.as_mut() else {
+                    anyhow::bail!(
+                        "FLOWSTATE_RUNNER_MTLS requires FLOWSTATE_TLS_CERT and FLOWSTATE_TLS_KEY"
+                    );
+                };
+                let ca_path = RunnerCaPaths::from_env().cert_path;
+                if !ca_path.exists() {
+                    anyhow::bail!(
+                        "runner CA {} not found; run `flowstate-server enroll-runner` first",
+                        ca_path.display()
+                    );
+                }
+                tls_config.client_ca_path = Some(ca_path);
+            }
+            let tls = match tls_config {
+                Some(config) => Some((config.load()?, config)),
+                None => None,
+            };
+            if tls.is_some() && matches!(target, BindTarget::Unix(_)) {
+                anyhow::bail!(
+                    "FLOWSTATE_TLS_CERT/FLOWSTATE_TLS_KEY cannot be combined with a unix: bind"
+                );
+            }
 
-            let auth = auth::build_auth_config(db.clone()).await;
+            let oidc = match OidcConfig::from_env()? {
+                Some(config) => {
+                    let key = flowstate_server::crypto::load_or_generate_key();
+                    let oidc = Oidc::discover(config, key.as_slice()).await?;
+                    eprintln!("OIDC login enabled");
+                    Some(Arc::new(oidc))
+                }
+                None => None,
+            };
+            let auth = auth::build_auth_config(db.clone(), oidc).await;
             if auth.is_some() {
                 eprintln!("authentication enabled");
             } else {
-                eprintln!("authentication disabled (no FLOWSTATE_API_KEY or DB keys)");
+                eprintln!("authentication disabled (no FLOWSTATE_API_KEY, DB keys or OIDC)");
             }
 
-            let listener = TcpListener::bind(addr).await?;
-            eprintln!("flowstate-server listening on http://{addr}");
-
-            flowstate_server::serve(listener, db, auth).await?;
+            match target {
+                BindTarget::Tcp(addr) => {
+                    let listener = TcpListener::bind(addr).await?;
+                    if let Some((tls, tls_config)) = tls {
+                        let listener = TlsListener::new(listener, tls)?;
+                        listener.reload_on_change(tls_config, tls::RELOAD_INTERVAL);
+                        eprintln!("flowstate-server listening on https://{addr}");
+                        if runner_mtls {
+                            eprintln!("runner client certificates required");
+                        }
+                        flowstate_server::serve(listener, db, auth, runner_mtls).await?;
+                    } else {
+                        eprintln!("flowstate-server listening on http://{addr}");
+                        flowstate_server::serve(listener, db, auth, false).await?;
+                    }
+                }
+                #[cfg(unix)]
+                BindTarget::Unix(path) => {
+                    let listener = flowstate_server::listen::bind_unix(&path)?;
+                    eprintln!("flowstate-server listening on unix:{}", path.display());
+                    flowstate_server::serve(listener, db, auth, false).await?;
+                }
+                #[cfg(not(unix))]
+                BindTarget::Unix(_) => unreachable!("rejected by BindTarget::parse"),
+            }
         }
     }
 
     Ok(())
 }
+
+/// Summarise a migration plan on stderr; for dry runs also print each step's
+/// SQL to stdout so it can be reviewed or piped to a file.
+fn print_migration_plan(plan: &MigrationPlan, dry_run: bool) {
+    if plan.is_empty() {
+        eprintln!(
+            "{} schema is at v{}; nothing to do",
+            plan.backend, plan.current
+        );
+        return;
+    }
+    eprintln!(
+        "{} {} schema v{} -> v{}",
+        if dry_run { "Would migrate" } else { "Migrated" },
+        plan.backend,
+        plan.current,
+        plan.target
+    );
+    for step in &plan.steps {
+        let direction = match step.direction {
+            Direction::Up => "up",
+            Direction::Down => "down",
+        };
+        eprintln!("  {direction:<4} v{} {}", step.version, step.name);
+        if dry_run {
+            println!("-- v{} {} ({direction})", step.version, step.name);
+            println!(
+                "{}",
+                step.sql.unwrap_or("-- applied in code, no SQL to show")
+            );
+        }
+    }
+}
+
+/// Print a key's allowlists, if it has any, beneath its summary line.
+fn print_key_policy(key: &flowstate_core::api_key::ApiKey) {
+    if !key.allowed_cidrs.is_empty() {
+        eprintln!("  allowed from: {}", key.allowed_cidrs.join(", "));
+    }
+    if !key.allowed_routes.is_empty() {
+        eprintln!("  allowed routes: {}", key.allowed_routes.join(", "));
+    }
+    if !key.allowed_projects.is_empty() {
+        eprintln!("  allowed projects: {}", key.allowed_projects.join(", "));
+    }
+    if let Some(org_id) = &key.org_id {
+        eprintln!("  organization: {org_id}");
+    }
+}
+
+/// Turn `--project` values (ids or slugs) into project ids, failing on any
+/// that name no project.
+async fn resolve_projects(db: &dyn Database, projects: &[String]) -> anyhow::Result<Vec<String>> {
+    let mut ids = Vec::with_capacity(projects.len());
+    for project in projects {
+        let found = match db.get_project(project).await {
+            Ok(p) => p,
+            Err(_) => db
+                .get_project_by_slug(project)
+                .await
+                .map_err(|_| anyhow::anyhow!("no project with id or slug: {project}"))?,
+        };
+        ids.push(found.id);
+    }
+    Ok(ids)
+}
+
+/// Turn an `--org` value (id or slug) into an organization id.
+async fn resolve_org(db: &dyn Database, org: &str) -> anyhow::Result<String> {
+    db.list_organizations()
+        .await?
+        .into_iter()
+        .find(|o| o.id == org || o.slug == org)
+        .map(|o| o.id)
+        .ok_or_else(|| anyhow::anyhow!("no organization with id or slug: {org}"))
+}
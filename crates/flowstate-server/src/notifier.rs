@@ -0,0 +1,829 @@
+//! Notifications sent to people outside the app: email, Slack and plain
+//! HTTP webhooks.
+//!
+//! Subscriptions pick the events they want, optionally for one project and
+//! for the tasks one user is involved in. Each event is rendered once into
+//! a [`Notice`] and handed to the [`Channel`] the subscription names.
+//! Sending happens in the background and is tried once; failures are
+//! logged and never fail the request that caused the event. Use a
+//! [webhook](crate::webhooks) for retried, signed delivery.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use flowstate_core::approval_rule::{self, ApprovalPhase};
+use flowstate_core::claude_run::{ClaudeRun, ClaudeRunStatus};
+use flowstate_core::run_metrics::RunMetrics;
+use flowstate_core::subscription::{NotifyChannel, NotifyEvent, Subscription};
+use flowstate_core::task::{ApprovalStatus, Task};
+use flowstate_core::task_pr::TaskPr;
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde_json::{json, Value};
+use tracing::{debug, warn};
+
+use crate::budget;
+use crate::routes::AppState;
+
+/// Time allowed for one channel to take one notice.
+const SEND_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// One event, rendered for people to read.
+#[derive(Debug, Clone)]
+pub struct Notice {
+    pub event: NotifyEvent,
+    pub project_id: String,
+    pub subject: String,
+    pub body: String,
+    /// Deep link to the task, in the `FLOWSTATE_TASK_LINK` format.
+    pub link: String,
+    /// The records behind the event, for machine receivers.
+    pub data: Value,
+}
+
+/// A way of delivering notices, such as email or a chat webhook.
+#[async_trait]
+pub trait Channel: Send + Sync {
+    /// Deliver `notice` to `target`, an address or URL from a subscription.
+    async fn send(&self, target: &str, notice: &Notice) -> Result<()>;
+}
+
+/// The channels the server can send on. Email is only available with SMTP
+/// configured.
+#[derive(Clone, Default)]
+pub struct Notifier {
+    channels: HashMap<NotifyChannel, Arc<dyn Channel>>,
+}
+
+impl Notifier {
+    /// Slack and webhooks always, and email when
+    /// `FLOWSTATE_NOTIFY_SMTP_HOST` is set.
+    pub fn from_env() -> Result<Self> {
+        Self::from_getter(|key| std::env::var(key).ok())
+    }
+
+    /// Build from an arbitrary variable-lookup function (testable without env mutation).
+    pub fn from_getter(get: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(SEND_TIMEOUT)
+            .build()
+            .context("building notification http client")?;
+        let mut notifier = Self::default()
+            .with_channel(
+                NotifyChannel::Slack,
+                Arc::new(SlackChannel {
+                    client: client.clone(),
+                }),
+            )
+            .with_channel(NotifyChannel::Webhook, Arc::new(WebhookChannel { client }));
+        if let Some(smtp) = SmtpChannel::from_getter(get)? {
+            notifier = notifier.with_channel(NotifyChannel::Email, Arc::new(smtp));
+        }
+        Ok(notifier)
+    }
+
+    /// Send `kind` notifications through `channel`, replacing any earlier one.
+    pub fn with_channel(mut self, kind: NotifyChannel, channel: Arc<dyn Channel>) -> Self {
+        self.channels.insert(kind, channel);
+        self
+    }
+
+    pub fn supports(&self, kind: NotifyChannel) -> bool {
+        self.channels.contains_key(&kind)
+    }
+}
+
+/// Email over SMTP with implicit TLS.
+pub struct SmtpChannel {
+    host: String,
+    port: u16,
+    credentials: Option<Credentials>,
+    from: String,
+}
+
+impl SmtpChannel {
+    /// Returns `Ok(None)` when `FLOWSTATE_NOTIFY_SMTP_HOST` is unset.
+    fn from_getter(get: impl Fn(&str) -> Option<String>) -> Result<Option<Self>> {
+        let get = |key: &str| get(key).filter(|v| !v.trim().is_empty());
+        let Some(host) = get("FLOWSTATE_NOTIFY_SMTP_HOST") else {
+            return Ok(None);
+        };
+        let port = match get("FLOWSTATE_NOTIFY_SMTP_PORT") {
+            Some(v) => v
+                .parse::<u16>()
+                .with_context(|| format!("FLOWSTATE_NOTIFY_SMTP_PORT: {v:?}"))?,
+            None => 465,
+        };
+        let username = get("FLOWSTATE_NOTIFY_SMTP_USERNAME");
+        let from = get("FLOWSTATE_NOTIFY_FROM")
+            .or_else(|| username.clone())
+            .context("FLOWSTATE_NOTIFY_FROM is required with FLOWSTATE_NOTIFY_SMTP_HOST")?;
+        let credentials = username.map(|user| {
+            Credentials::new(
+                user,
+                get("FLOWSTATE_NOTIFY_SMTP_PASSWORD").unwrap_or_default(),
+            )
+        });
+        Ok(Some(Self {
+            host,
+            port,
+            credentials,
+            from,
+        }))
+    }
+}
+
+#[async_trait]
+impl Channel for SmtpChannel {
+    async fn send(&self, target: &str, notice: &Notice) -> Result<()> {
+        let message = Message::builder()
+            .from(self.from.parse()?)
+            .to(target.parse()?)
+            .subject(&notice.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(format!("{}\n\n{}\n", notice.body, notice.link))?;
+        let mut transport =
+            AsyncSmtpTransport::<Tokio1Executor>::relay(&self.host)?.port(self.port);
+        if let Some(credentials) = &self.credentials {
+            transport = transport.credentials(credentials.clone());
+        }
+        transport.build().send(message).await?;
+        Ok(())
+    }
+}
+
+/// Messages posted to a Slack incoming webhook.
+pub struct SlackChannel {
+    client: reqwest::Client,
+}
+
+#[async_trait]
+impl Channel for SlackChannel {
+    async fn send(&self, target: &str, notice: &Notice) -> Result<()> {
+        self.client
+            .post(target)
+            .json(&json!({ "text": slack_text(notice) }))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// `*subject*`, the body and a link, in Slack's mrkdwn.
+fn slack_text(notice: &Notice) -> String {
+    let escape = |s: &str| {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    };
+    format!(
+        "*{}*\n{}\n<{}|Open task>",
+        escape(&notice.subject),
+        escape(&notice.body),
+        notice.link
+    )
+}
+
+/// A JSON body POSTed to any URL: `{"event", "project_id", "occurred_at",
+/// "subject", "text", "link", "data"}`.
+pub struct WebhookChannel {
+    client: reqwest::Client,
+}
+
+#[async_trait]
+impl Channel for WebhookChannel {
+    async fn send(&self, target: &str, notice: &Notice) -> Result<()> {
+        self.client
+            .post(target)
+            .header("x-flowstate-event", notice.event.as_str())
+            .json(&json!({
+                "event": notice.event,
+                "project_id": notice.project_id,
+                "occurred_at": Utc::now(),
+                "subject": notice.subject,
+                "text": notice.body,
+                "link": notice.link,
+                "data": notice.data,
+            }))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Send `notice` about `task` to every subscription that wants it, in the
+/// background.
+async fn notify(state: &AppState, task: &Task, notice: Notice) {
+    let subscriptions = match state.db.list_subscriptions().await {
+        Ok(subscriptions) => subscriptions,
+        Err(e) => {
+            warn!("notifier: listing subscriptions for {}: {e}", notice.event);
+            return;
+        }
+    };
+    let mut sends = Vec::new();
+    let mut watchers = None;
+    for subscription in subscriptions
+        .iter()
+        .filter(|s| s.wants(notice.event, &notice.project_id))
+    {
+        let Some(channel) = state.notifier.channels.get(&subscription.channel) else {
+            debug!(
+                "notifier: {} channel not configured, skipping subscription {}",
+                subscription.channel, subscription.id
+            );
+            continue;
+        };
+        if let Some(target) = resolve_target(state, subscription, task, &mut watchers).await {
+            sends.push((subscription.id.clone(), channel.clone(), target));
+        }
+    }
+    if sends.is_empty() {
+        return;
+    }
+    tokio::spawn(async move {
+        for (id, channel, target) in sends {
+            match tokio::time::timeout(SEND_TIMEOUT, channel.send(&target, &notice)).await {
+                Ok(Ok(())) => debug!("notifier: sent {} for subscription {id}", notice.event),
+                Ok(Err(e)) => warn!(
+                    "notifier: sending {} for subscription {id}: {e:#}",
+                    notice.event
+                ),
+                Err(_) => warn!(
+                    "notifier: sending {} for subscription {id}: timed out",
+                    notice.event
+                ),
+            }
+        }
+    });
+}
+
+/// Where `subscription` sends a notice about `task`, or `None` when its
+/// user has nothing to do with the task or no address to send to.
+/// `watchers` caches the task's watchers across subscriptions.
+async fn resolve_target(
+    state: &AppState,
+    subscription: &Subscription,
+    task: &Task,
+    watchers: &mut Option<Vec<String>>,
+) -> Option<String> {
+    let Some(user_id) = &subscription.user_id else {
+        return Some(subscription.target.clone());
+    };
+    let user = match state.db.get_user(user_id).await {
+        Ok(user) => user,
+        Err(e) => {
+            warn!(
+                "notifier: loading user of subscription {}: {e}",
+                subscription.id
+            );
+            return None;
+        }
+    };
+    if watchers.is_none() {
+        let ids = match state.db.list_task_watchers(&task.id).await {
+            Ok(users) => users.into_iter().map(|u| u.id).collect(),
+            Err(e) => {
+                warn!("notifier: loading watchers of task {}: {e}", task.id);
+                Vec::new()
+            }
+        };
+        *watchers = Some(ids);
+    }
+    let involved = task.assignee_id.as_deref() == Some(user.id.as_str())
+        || watchers.as_deref().unwrap_or_default().contains(&user.id)
+        || approval_rule::is_reviewer(&task.reviewer, &user.name)
+        || (!user.email.is_empty() && approval_rule::is_reviewer(&task.reviewer, &user.email));
+    if !involved {
+        return None;
+    }
+    let target = if subscription.target.is_empty() && subscription.channel == NotifyChannel::Email {
+        user.email
+    } else {
+        subscription.target.clone()
+    };
+    (!target.is_empty()).then_some(target)
+}
+
+/// Send `approval_pending` for each phase that became ready for review
+/// between `before` and `after`.
+pub(crate) async fn task_changed(state: &AppState, before: &Task, after: &Task) {
+    for phase in ApprovalPhase::ALL {
+        if phase.status(after) != ApprovalStatus::Pending
+            || phase.status(before) == ApprovalStatus::Pending
+        {
+            continue;
+        }
+        let notice = Notice {
+            event: NotifyEvent::ApprovalPending,
+            project_id: after.project_id.clone(),
+            subject: format!("{}: {phase} awaiting approval", after.title),
+            body: format!(
+                "The {phase} for task \"{}\" ({}) is ready for review.",
+                after.title, after.id
+            ),
+            link: state.task_links.task_url(&after.id),
+            data: json!({ "task": after, "phase": phase }),
+        };
+        notify(state, after, notice).await;
+    }
+}
+
+/// Send `run_failed` for a run that has just failed or timed out.
+pub(crate) async fn run_finished(state: &AppState, run: &ClaudeRun) {
+    let outcome = match run.status {
+        ClaudeRunStatus::Failed => "failed",
+        ClaudeRunStatus::TimedOut => "timed out",
+        _ => return,
+    };
+    let task = match state.db.get_task(&run.task_id).await {
+        Ok(task) => task,
+        Err(e) => {
+            warn!("notifier: loading task of run {}: {e}", run.id);
+            return;
+        }
+    };
+    let mut body = format!(
+        "The {} run {} for task \"{}\" ({}) {outcome}.",
+        run.action, run.id, task.title, task.id
+    );
+    if let Some(error) = &run.error_message {
+        body.push_str(&format!("\n\n{error}"));
+    }
+    let notice = Notice {
+        event: NotifyEvent::RunFailed,
+        project_id: task.project_id.clone(),
+        subject: format!("{}: {} run {outcome}", task.title, run.action),
+        body,
+        link: state.task_links.task_url(&task.id),
+        data: json!({ "run": run, "task": task }),
+    };
+    notify(state, &task, notice).await;
+}
+
+/// Send `pr_opened` for a pull request just recorded against its task.
+pub(crate) async fn pr_opened(state: &AppState, pr: &TaskPr) {
+    let task = match state.db.get_task(&pr.task_id).await {
+        Ok(task) => task,
+        Err(e) => {
+            warn!("notifier: loading task of pull request {}: {e}", pr.pr_url);
+            return;
+        }
+    };
+    let notice = Notice {
+        event: NotifyEvent::PrOpened,
+        project_id: task.project_id.clone(),
+        subject: format!("{}: pull request #{} opened", task.title, pr.pr_number),
+        body: format!(
+            "Pull request #{} was opened for task \"{}\" ({}):\n{}",
+            pr.pr_number, task.title, task.id, pr.pr_url
+        ),
+        link: state.task_links.task_url(&task.id),
+        data: json!({ "pr": pr, "task": task }),
+    };
+    notify(state, &task, notice).await;
+}
+
+/// Send `budget_warning` when the cost in `metrics` carried its project's
+/// spend this month past [`budget::WARN_FRACTION`] of the budget, or past
+/// all of it.
+pub(crate) async fn run_cost_recorded(state: &AppState, metrics: &RunMetrics) {
+    let Some(cost) = metrics.cost_usd.filter(|c| *c > 0.0) else {
+        return;
+    };
+    let task = match state.db.get_claude_run(&metrics.run_id).await {
+        Ok(run) => state.db.get_task(&run.task_id).await,
+        Err(e) => Err(e),
+    };
+    let task = match task {
+        Ok(task) => task,
+        Err(e) => {
+            warn!("notifier: loading task of run {}: {e}", metrics.run_id);
+            return;
+        }
+    };
+    let project = match state.db.get_project(&task.project_id).await {
+        Ok(project) => project,
+        Err(e) => {
+            warn!("notifier: loading project {}: {e}", task.project_id);
+            return;
+        }
+    };
+    let Some(limit) = project.monthly_budget_usd else {
+        return;
+    };
+    let status = match budget::status(&*state.db, &project, Utc::now()).await {
+        Ok(status) => status,
+        Err(e) => {
+            warn!("notifier: budget of project {}: {e}", project.id);
+            return;
+        }
+    };
+    let Some(fraction) =
+        budget::crossed_threshold(limit, status.spent_usd - cost, status.spent_usd)
+    else {
+        return;
+    };
+    let (subject, consequence) = if fraction >= 1.0 {
+        (
+            format!("{}: monthly budget spent", project.name),
+            "New Build runs are refused until the month ends or an override is set.",
+        )
+    } else {
+        (
+            format!(
+                "{}: {:.0}% of monthly budget spent",
+                project.name,
+                status.spent_usd / limit * 100.0
+            ),
+            "New Build runs will be refused once the budget is spent.",
+        )
+    };
+    let notice = Notice {
+        event: NotifyEvent::BudgetWarning,
+        project_id: project.id.clone(),
+        subject,
+        body: format!(
+            "Project \"{}\" has spent ${:.2} of its ${limit:.2} budget this month, \
+             most recently on run {} for task \"{}\" ({}). {consequence}",
+            project.name, status.spent_usd, metrics.run_id, task.title, task.id
+        ),
+        link: state.task_links.task_url(&task.id),
+        data: json!({ "budget": status, "run_id": metrics.run_id, "task": task }),
+    };
+    notify(state, &task, notice).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use flowstate_core::claude_run::{ClaudeAction, CreateClaudeRun};
+    use flowstate_core::project::CreateProject;
+    use flowstate_core::subscription::CreateSubscription;
+    use flowstate_core::task::{CreateTask, Priority, Status, TaskType};
+    use flowstate_core::user::CreateUser;
+    use tokio::sync::mpsc;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::routes::build_router;
+    use crate::test_helpers::test_state;
+
+    /// Records every notice instead of sending it.
+    struct Recorder(
+        NotifyChannel,
+        mpsc::UnboundedSender<(NotifyChannel, String, Notice)>,
+    );
+
+    #[async_trait]
+    impl Channel for Recorder {
+        async fn send(&self, target: &str, notice: &Notice) -> Result<()> {
+            let _ = self.1.send((self.0, target.to_string(), notice.clone()));
+            Ok(())
+        }
+    }
+
+    fn request(method: &str, uri: &str, body: Value) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    #[test]
+    fn smtp_needs_a_sender() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |key: &str| {
+                vars.iter()
+                    .find(|(k, _)| *k == key)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+        assert!(SmtpChannel::from_getter(env(&[])).unwrap().is_none());
+        assert!(SmtpChannel::from_getter(env(&[(
+            "FLOWSTATE_NOTIFY_SMTP_HOST",
+            "smtp.example.com"
+        )]))
+        .is_err());
+        let smtp = SmtpChannel::from_getter(env(&[
+            ("FLOWSTATE_NOTIFY_SMTP_HOST", "smtp.example.com"),
+            ("FLOWSTATE_NOTIFY_SMTP_USERNAME", "bot@example.com"),
+        ]))
+        .unwrap()
+        .unwrap();
+        assert_eq!((smtp.port, smtp.from.as_str()), (465, "bot@example.com"));
+        assert!(smtp.credentials.is_some());
+        let notifier = Notifier::from_getter(env(&[])).unwrap();
+        assert!(notifier.supports(NotifyChannel::Slack));
+        assert!(!notifier.supports(NotifyChannel::Email));
+    }
+
+    #[test]
+    fn slack_text_is_escaped() {
+        let notice = Notice {
+            event: NotifyEvent::RunFailed,
+            project_id: "p1".into(),
+            subject: "Fix <b> & co".into(),
+            body: "a > b".into(),
+            link: "https://flowstate.example.com/tasks/t1".into(),
+            data: Value::Null,
+        };
+        assert_eq!(
+            slack_text(&notice),
+            "*Fix &lt;b&gt; &amp; co*\na &gt; b\n<https://flowstate.example.com/tasks/t1|Open task>"
+        );
+    }
+
+    #[tokio::test]
+    async fn subscriptions_hear_about_their_events() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut state = Arc::try_unwrap(test_state().await)
+            .ok()
+            .expect("fresh state has one owner");
+        for kind in NotifyChannel::ALL {
+            state.notifier = state
+                .notifier
+                .with_channel(*kind, Arc::new(Recorder(*kind, tx.clone())));
+        }
+        let state = Arc::new(state);
+        let app = build_router(state.clone());
+
+        let project = state
+            .db
+            .create_project(&CreateProject {
+                name: "Notify".into(),
+                slug: "notify".into(),
+                description: String::new(),
+                repo_url: String::new(),
+            })
+            .await
+            .unwrap();
+        let user = |name: &str| CreateUser {
+            name: name.into(),
+            email: format!("{}@example.com", name.to_lowercase()),
+        };
+        let alice = state.db.create_user(&user("Alice")).await.unwrap();
+        let bob = state.db.create_user(&user("Bob")).await.unwrap();
+        let task = state
+            .db
+            .create_task(&CreateTask {
+                project_id: project.id.clone(),
+                title: "Ship it".into(),
+                description: String::new(),
+                status: Status::Research,
+                priority: Priority::Medium,
+                task_type: TaskType::Feature,
+                parent_id: None,
+                reviewer: "alice".into(),
+                due_at: None,
+                research_capability: None,
+                design_capability: None,
+                plan_capability: None,
+                build_capability: None,
+                verify_capability: None,
+            })
+            .await
+            .unwrap();
+        let subscribe = |channel, target: &str, events, user_id: Option<&str>| CreateSubscription {
+            channel,
+            target: target.into(),
+            events,
+            project_id: Some(project.id.clone()),
+            user_id: user_id.map(String::from),
+        };
+        for input in [
+            subscribe(
+                NotifyChannel::Slack,
+                "https://hooks.slack.com/services/x",
+                vec![NotifyEvent::RunFailed],
+                None,
+            ),
+            subscribe(NotifyChannel::Email, "", vec![], Some(&alice.id)),
+            // Bob has nothing to do with the task
+            subscribe(NotifyChannel::Email, "", vec![], Some(&bob.id)),
+        ] {
+            state.db.create_subscription(&input).await.unwrap();
+        }
+
+        let resp = app
+            .clone()
+            .oneshot(request(
+                "PUT",
+                &format!("/api/tasks/{}", task.id),
+                json!({ "research_status": "pending" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let run = state
+            .db
+            .create_claude_run(&CreateClaudeRun {
+                task_id: task.id.clone(),
+                action: ClaudeAction::Research,
+                required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
+            })
+            .await
+            .unwrap();
+        state.db.claim_next_claude_run(&[]).await.unwrap().unwrap();
+        let resp = app
+            .clone()
+            .oneshot(request(
+                "PUT",
+                &format!("/api/claude-runs/{}/status", run.id),
+                json!({ "status": "failed", "error_message": "boom" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // Recording the same PR twice notifies once
+        for _ in 0..2 {
+            let resp = app
+                .clone()
+                .oneshot(request(
+                    "POST",
+                    &format!("/api/tasks/{}/prs", task.id),
+                    json!({
+                        "pr_url": "https://github.com/acme/app/pull/9",
+                        "pr_number": 9,
+                        "branch_name": "flowstate/ship-it",
+                    }),
+                ))
+                .await
+                .unwrap();
+            assert_eq!(resp.status(), StatusCode::CREATED);
+        }
+
+        let mut got = Vec::new();
+        while got.len() < 4 {
+            let next = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+                .await
+                .expect("notification sent")
+                .unwrap();
+            got.push(next);
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(rx.try_recv().is_err(), "nothing else is sent");
+
+        let mut sent: Vec<_> = got
+            .iter()
+            .map(|(kind, target, notice)| (notice.event, *kind, target.as_str()))
+            .collect();
+        sent.sort_by_key(|(event, kind, _)| (event.as_str(), kind.as_str()));
+        assert_eq!(
+            sent,
+            vec![
+                (
+                    NotifyEvent::ApprovalPending,
+                    NotifyChannel::Email,
+                    "alice@example.com"
+                ),
+                (
+                    NotifyEvent::PrOpened,
+                    NotifyChannel::Email,
+                    "alice@example.com"
+                ),
+                (
+                    NotifyEvent::RunFailed,
+                    NotifyChannel::Email,
+                    "alice@example.com"
+                ),
+                (
+                    NotifyEvent::RunFailed,
+                    NotifyChannel::Slack,
+                    "https://hooks.slack.com/services/x"
+                ),
+            ]
+        );
+        let failed = &got
+            .iter()
+            .find(|(_, _, n)| n.event == NotifyEvent::RunFailed)
+            .unwrap()
+            .2;
+        assert_eq!(failed.subject, "Ship it: research run failed");
+        assert!(failed.body.ends_with("\n\nboom"));
+    }
+
+    #[tokio::test]
+    async fn budget_warnings_fire_as_spend_crosses_thresholds() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut state = Arc::try_unwrap(test_state().await)
+            .ok()
+            .expect("fresh state has one owner");
+        state.notifier = state.notifier.with_channel(
+            NotifyChannel::Webhook,
+            Arc::new(Recorder(NotifyChannel::Webhook, tx)),
+        );
+        let state = Arc::new(state);
+        let app = build_router(state.clone());
+
+        let project = state
+            .db
+            .create_project(&CreateProject {
+                name: "Budget".into(),
+                slug: "budget".into(),
+                description: String::new(),
+                repo_url: String::new(),
+            })
+            .await
+            .unwrap();
+        state
+            .db
+            .update_project(
+                &project.id,
+                &flowstate_core::project::UpdateProject {
+                    monthly_budget_usd: Some(Some(10.0)),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let task = state
+            .db
+            .create_task(&CreateTask {
+                project_id: project.id.clone(),
+                title: "Spend".into(),
+                description: String::new(),
+                status: Status::Todo,
+                priority: Priority::Medium,
+                task_type: TaskType::Feature,
+                parent_id: None,
+                reviewer: String::new(),
+                due_at: None,
+                research_capability: None,
+                design_capability: None,
+                plan_capability: None,
+                build_capability: None,
+                verify_capability: None,
+            })
+            .await
+            .unwrap();
+        state
+            .db
+            .create_subscription(&CreateSubscription {
+                channel: NotifyChannel::Webhook,
+                target: "https://hooks.example.com/budget".into(),
+                events: vec![NotifyEvent::BudgetWarning],
+                project_id: Some(project.id.clone()),
+                user_id: None,
+            })
+            .await
+            .unwrap();
+
+        let mut subjects = Vec::new();
+        for cost in [5.0, 4.0, 0.5, 1.0] {
+            let run = state
+                .db
+                .create_claude_run(&CreateClaudeRun {
+                    task_id: task.id.clone(),
+                    action: ClaudeAction::Research,
+                    required_capability: None,
+                    priority: 0,
+                    feedback: None,
+                    idempotency_key: None,
+                    run_window: None,
+                    trace_context: None,
+                })
+                .await
+                .unwrap();
+            let resp = app
+                .clone()
+                .oneshot(request(
+                    "POST",
+                    &format!("/api/claude-runs/{}/metrics", run.id),
+                    json!({ "duration_ms": 1000, "stdout_bytes": 10, "cost_usd": cost }),
+                ))
+                .await
+                .unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+            if let Ok(Some((_, _, notice))) =
+                tokio::time::timeout(Duration::from_millis(200), rx.recv()).await
+            {
+                subjects.push(notice.subject);
+            }
+        }
+        assert_eq!(
+            subjects,
+            vec![
+                "Budget: 90% of monthly budget spent",
+                "Budget: monthly budget spent"
+            ]
+        );
+    }
+}
@@ -0,0 +1,724 @@
+//! OpenID Connect login for people, alongside API keys for machines.
+//!
+//! A browser signs in with the authorization code flow (with PKCE) and gets
+//! a session cookie; tools such as the TUI may instead send a token from the
+//! provider as `Authorization: Bearer <jwt>`. Sessions are signed cookies
+//! rather than database rows, so any replica can check them and signing out
+//! only forgets the cookie.
+
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use jsonwebtoken::jwk::{AlgorithmParameters, Jwk, JwkSet};
+use jsonwebtoken::{DecodingKey, Validation};
+use rand::Rng;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::sync::{Mutex, RwLock};
+
+/// Cookie holding a signed-in user's session.
+pub const SESSION_COOKIE: &str = "flowstate_session";
+
+/// Cookie carrying a login's state, nonce and PKCE verifier from
+/// `/auth/login` to `/auth/callback`.
+pub const LOGIN_COOKIE: &str = "flowstate_login";
+
+/// How long a login may take at the provider before the callback is refused.
+const LOGIN_TTL: Duration = Duration::from_secs(600);
+
+/// Default session lifetime when `FLOWSTATE_OIDC_SESSION_HOURS` is unset.
+const DEFAULT_SESSION_HOURS: u64 = 12;
+
+/// Minimum time between key set refetches triggered by unknown key ids.
+const JWKS_REFETCH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// OpenID provider settings, read from `FLOWSTATE_OIDC_*`.
+#[derive(Debug, Clone)]
+pub struct OidcConfig {
+    /// Issuer URL; `/.well-known/openid-configuration` is fetched from it.
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: String,
+    /// This server's `/auth/callback` as registered with the provider.
+    pub redirect_url: String,
+    pub session_ttl: Duration,
+}
+
+impl OidcConfig {
+    /// Returns `Ok(None)` when `FLOWSTATE_OIDC_ISSUER` is unset. Once it is
+    /// set, the client id, secret and redirect URL are required.
+    pub fn from_env() -> Result<Option<Self>> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        Self::from_values(
+            var("FLOWSTATE_OIDC_ISSUER"),
+            var("FLOWSTATE_OIDC_CLIENT_ID"),
+            var("FLOWSTATE_OIDC_CLIENT_SECRET"),
+            var("FLOWSTATE_OIDC_REDIRECT_URL"),
+            var("FLOWSTATE_OIDC_SESSION_HOURS"),
+        )
+    }
+
+    fn from_values(
+        issuer: Option<String>,
+        client_id: Option<String>,
+        client_secret: Option<String>,
+        redirect_url: Option<String>,
+        session_hours: Option<String>,
+    ) -> Result<Option<Self>> {
+        let Some(issuer) = issuer else {
+            return Ok(None);
+        };
+        let (Some(client_id), Some(client_secret), Some(redirect_url)) =
+            (client_id, client_secret, redirect_url)
+        else {
+            bail!(
+                "FLOWSTATE_OIDC_ISSUER requires FLOWSTATE_OIDC_CLIENT_ID, \
+                 FLOWSTATE_OIDC_CLIENT_SECRET and FLOWSTATE_OIDC_REDIRECT_URL"
+            );
+        };
+        let hours = match session_hours {
+            Some(h) => h
+                .parse::<u64>()
+                .ok()
+                .filter(|h| *h > 0)
+                .context("FLOWSTATE_OIDC_SESSION_HOURS must be a positive number of hours")?,
+            None => DEFAULT_SESSION_HOURS,
+        };
+        Ok(Some(Self {
+            issuer: issuer.trim_end_matches('/').to_string(),
+            client_id,
+            client_secret,
+            redirect_url,
+            session_ttl: Duration::from_secs(hours * 3600),
+        }))
+    }
+}
+
+/// The parts of the provider's discovery document the login flow uses.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProviderMetadata {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub jwks_uri: String,
+}
+
+/// Who signed in, as the provider's token describes them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct Identity {
+    pub sub: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl Identity {
+    /// The caller label recorded on changes: `user:<email>`, or
+    /// `user:<sub>` when the provider shares no email.
+    pub fn label(&self) -> String {
+        format!("user:{}", self.email.as_deref().unwrap_or(&self.sub))
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum OidcError {
+    #[error("login expired or was not started here")]
+    InvalidLogin,
+    #[error("identity provider: {0}")]
+    Provider(String),
+    #[error("invalid token: {0}")]
+    InvalidToken(String),
+}
+
+/// A started login, carried in [`LOGIN_COOKIE`].
+#[derive(Debug, Serialize, Deserialize)]
+struct PendingLogin {
+    state: String,
+    nonce: String,
+    verifier: String,
+    return_to: String,
+    exp: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Session {
+    #[serde(flatten)]
+    identity: Identity,
+    exp: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdClaims {
+    #[serde(flatten)]
+    identity: Identity,
+    #[serde(default)]
+    nonce: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+/// A configured provider: discovery metadata, its signing keys, and the key
+/// this server signs cookies with.
+pub struct Oidc {
+    config: OidcConfig,
+    metadata: ProviderMetadata,
+    jwks: RwLock<JwkSet>,
+    /// When the key set was last refetched for an unknown key id.
+    jwks_refetched: Mutex<Option<Instant>>,
+    http: reqwest::Client,
+    cookie_key: [u8; 32],
+}
+
+impl Oidc {
+    /// Fetch the provider's discovery document and keys. Cookies are signed
+    /// with a key derived from `server_key`, so sessions survive restarts
+    /// and are valid on every replica sharing it.
+    pub async fn discover(config: OidcConfig, server_key: &[u8]) -> Result<Self> {
+        let http = reqwest::Client::new();
+        let url = format!("{}/.well-known/openid-configuration", config.issuer);
+        let metadata: ProviderMetadata = http
+            .get(&url)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .with_context(|| format!("failed to fetch {url}"))?
+            .json()
+            .await
+            .with_context(|| format!("invalid discovery document at {url}"))?;
+        if metadata.issuer.trim_end_matches('/') != config.issuer {
+            bail!(
+                "provider reports issuer {} but FLOWSTATE_OIDC_ISSUER is {}",
+                metadata.issuer,
+                config.issuer
+            );
+        }
+        let jwks = fetch_jwks(&http, &metadata.jwks_uri).await?;
+        Ok(Self::new(config, metadata, jwks, server_key))
+    }
+
+    pub fn new(
+        config: OidcConfig,
+        metadata: ProviderMetadata,
+        jwks: JwkSet,
+        server_key: &[u8],
+    ) -> Self {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(server_key).expect("HMAC takes keys of any length");
+        mac.update(b"flowstate oidc cookies");
+        Self {
+            config,
+            metadata,
+            jwks: RwLock::new(jwks),
+            jwks_refetched: Mutex::new(None),
+            http: reqwest::Client::new(),
+            cookie_key: mac.finalize().into_bytes().into(),
+        }
+    }
+
+    /// Whether cookies should be marked `Secure`: only when the callback is
+    /// served over HTTPS, so plain-HTTP development setups still work.
+    fn secure(&self) -> bool {
+        self.config.redirect_url.starts_with("https://")
+    }
+
+    /// Start a login. Returns the provider URL to send the browser to and
+    /// the `Set-Cookie` value remembering the login until the callback.
+    pub fn login(&self, return_to: &str) -> (String, String) {
+        let pending = PendingLogin {
+            state: random_token(),
+            nonce: random_token(),
+            verifier: random_token(),
+            return_to: return_to.to_string(),
+            exp: Utc::now().timestamp() + LOGIN_TTL.as_secs() as i64,
+        };
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(pending.verifier.as_bytes()));
+        let separator = if self.metadata.authorization_endpoint.contains('?') {
+            '&'
+        } else {
+            '?'
+        };
+        let url = format!(
+            "{}{separator}response_type=code&scope=openid%20email%20profile&client_id={}\
+             &redirect_uri={}&state={}&nonce={}&code_challenge={challenge}\
+             &code_challenge_method=S256",
+            self.metadata.authorization_endpoint,
+            encode(&self.config.client_id),
+            encode(&self.config.redirect_url),
+            pending.state,
+            pending.nonce,
+        );
+        let cookie = self.cookie(LOGIN_COOKIE, &self.sign(&pending), LOGIN_TTL, "/auth");
+        (url, cookie)
+    }
+
+    /// Finish a login from the callback's `code` and `state` and the value
+    /// of [`LOGIN_COOKIE`]. Returns who signed in and where they were
+    /// headed.
+    pub async fn finish_login(
+        &self,
+        code: &str,
+        state: &str,
+        login_cookie: &str,
+    ) -> Result<(Identity, String), OidcError> {
+        let pending: PendingLogin = self.verify(login_cookie).ok_or(OidcError::InvalidLogin)?;
+        if pending.exp < Utc::now().timestamp() || pending.state != state {
+            return Err(OidcError::InvalidLogin);
+        }
+        let response = self
+            .http
+            .post(&self.metadata.token_endpoint)
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", &self.config.redirect_url),
+                ("client_id", &self.config.client_id),
+                ("client_secret", &self.config.client_secret),
+                ("code_verifier", &pending.verifier),
+            ])
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| OidcError::Provider(e.to_string()))?;
+        let tokens: TokenResponse = response
+            .json()
+            .await
+            .map_err(|e| OidcError::Provider(e.to_string()))?;
+        let claims = self.verify_jwt(&tokens.id_token).await?;
+        if claims.nonce.as_deref() != Some(pending.nonce.as_str()) {
+            return Err(OidcError::InvalidToken("nonce mismatch".into()));
+        }
+        Ok((claims.identity, pending.return_to))
+    }
+
+    /// Check a bearer token issued by the provider for this client.
+    pub async fn verify_token(&self, token: &str) -> Result<Identity, OidcError> {
+        self.verify_jwt(token).await.map(|claims| claims.identity)
+    }
+
+    /// The `Set-Cookie` value starting a session for `identity`.
+    pub fn session_cookie(&self, identity: &Identity) -> String {
+        let session = Session {
+            identity: identity.clone(),
+            exp: Utc::now().timestamp() + self.config.session_ttl.as_secs() as i64,
+        };
+        self.cookie(
+            SESSION_COOKIE,
+            &self.sign(&session),
+            self.config.session_ttl,
+            "/",
+        )
+    }
+
+    /// The `Set-Cookie` value ending a session.
+    pub fn clear_session_cookie(&self) -> String {
+        self.cookie(SESSION_COOKIE, "", Duration::ZERO, "/")
+    }
+
+    /// The identity behind a [`SESSION_COOKIE`] value, if it is genuine and
+    /// unexpired.
+    pub fn session(&self, value: &str) -> Option<Identity> {
+        let session: Session = self.verify(value)?;
+        (session.exp >= Utc::now().timestamp()).then_some(session.identity)
+    }
+
+    fn cookie(&self, name: &str, value: &str, max_age: Duration, path: &str) -> String {
+        let secure = if self.secure() { "; Secure" } else { "" };
+        format!(
+            "{name}={value}; Path={path}; Max-Age={}; HttpOnly; SameSite=Lax{secure}",
+            max_age.as_secs()
+        )
+    }
+
+    /// `base64(json).base64(hmac)`.
+    fn sign<T: Serialize>(&self, value: &T) -> String {
+        let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(value).expect("serializable"));
+        let mut mac = self.mac();
+        mac.update(payload.as_bytes());
+        let tag = URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes());
+        format!("{payload}.{tag}")
+    }
+
+    fn verify<T: DeserializeOwned>(&self, signed: &str) -> Option<T> {
+        let (payload, tag) = signed.split_once('.')?;
+        let tag = URL_SAFE_NO_PAD.decode(tag).ok()?;
+        let mut mac = self.mac();
+        mac.update(payload.as_bytes());
+        mac.verify_slice(&tag).ok()?;
+        serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).ok()?).ok()
+    }
+
+    fn mac(&self) -> Hmac<Sha256> {
+        Hmac::<Sha256>::new_from_slice(&self.cookie_key).expect("HMAC takes keys of any length")
+    }
+
+    /// Verify a JWT's signature against the provider's keys, and its issuer,
+    /// audience and expiry. An unknown key id refetches the key set, in case
+    /// the provider rotated its keys; see [`Self::refetch_jwk`].
+    async fn verify_jwt(&self, token: &str) -> Result<IdClaims, OidcError> {
+        let invalid = |e: jsonwebtoken::errors::Error| OidcError::InvalidToken(e.to_string());
+        let header = jsonwebtoken::decode_header(token).map_err(invalid)?;
+        let kid = header
+            .kid
+            .ok_or_else(|| OidcError::InvalidToken("no key id".into()))?;
+        let cached = self.jwks.read().await.find(&kid).cloned();
+        let jwk = match cached {
+            Some(jwk) => Some(jwk),
+            None => self.refetch_jwk(&kid).await?,
+        };
+        let jwk = jwk.ok_or_else(|| OidcError::InvalidToken(format!("unknown key id {kid}")))?;
+        // A shared secret in a published key set would let anyone mint tokens
+        if matches!(jwk.algorithm, AlgorithmParameters::OctetKey(_)) {
+            return Err(OidcError::InvalidToken(
+                "symmetric keys are not accepted".into(),
+            ));
+        }
+        let key = DecodingKey::from_jwk(&jwk).map_err(invalid)?;
+        let mut validation = Validation::new(header.alg);
+        validation.set_audience(&[&self.config.client_id]);
+        validation.set_issuer(&[&self.metadata.issuer]);
+        jsonwebtoken::decode::<IdClaims>(token, &key, &validation)
+            .map(|data| data.claims)
+            .map_err(invalid)
+    }
+
+    /// Refetch the key set looking for `kid`. Tokens are checked before the
+    /// caller is authenticated, so refetches are limited to one per
+    /// [`JWKS_REFETCH_INTERVAL`]; a failed fetch keeps the cached set.
+    async fn refetch_jwk(&self, kid: &str) -> Result<Option<Jwk>, OidcError> {
+        let mut refetched = self.jwks_refetched.lock().await;
+        // Another request may have refetched while this one waited
+        let cached = self.jwks.read().await.find(kid).cloned();
+        if cached.is_some() {
+            return Ok(cached);
+        }
+        if refetched.is_some_and(|at| at.elapsed() < JWKS_REFETCH_INTERVAL) {
+            return Ok(None);
+        }
+        *refetched = Some(Instant::now());
+        let fresh = fetch_jwks(&self.http, &self.metadata.jwks_uri)
+            .await
+            .map_err(|e| OidcError::Provider(e.to_string()))?;
+        let jwk = fresh.find(kid).cloned();
+        *self.jwks.write().await = fresh;
+        Ok(jwk)
+    }
+}
+
+async fn fetch_jwks(http: &reqwest::Client, url: &str) -> Result<JwkSet> {
+    http.get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .with_context(|| format!("failed to fetch {url}"))?
+        .json()
+        .await
+        .with_context(|| format!("invalid key set at {url}"))
+}
+
+/// 32 random bytes, URL-safe.
+fn random_token() -> String {
+    let bytes: [u8; 32] = rand::thread_rng().gen();
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn encode(value: &str) -> String {
+    percent_encoding::utf8_percent_encode(value, percent_encoding::NON_ALPHANUMERIC).to_string()
+}
+
+/// The value of cookie `name` in a `Cookie` header.
+pub fn cookie_value<'a>(header: &'a str, name: &str) -> Option<&'a str> {
+    header.split(';').find_map(|pair| {
+        let (key, value) = pair.trim().split_once('=')?;
+        (key == name).then_some(value)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> OidcConfig {
+        OidcConfig::from_values(
+            Some("https://idp.example/".into()),
+            Some("flowstate".into()),
+            Some("secret".into()),
+            Some("https://flowstate.example/auth/callback".into()),
+            None,
+        )
+        .unwrap()
+        .unwrap()
+    }
+
+    fn oidc() -> Oidc {
+        let metadata = ProviderMetadata {
+            issuer: "https://idp.example".into(),
+            authorization_endpoint: "https://idp.example/authorize".into(),
+            token_endpoint: "https://idp.example/token".into(),
+            jwks_uri: "https://idp.example/jwks".into(),
+        };
+        Oidc::new(config(), metadata, JwkSet { keys: vec![] }, b"server key")
+    }
+
+    #[test]
+    fn config_requires_client_settings() {
+        assert!(OidcConfig::from_values(None, None, None, None, None)
+            .unwrap()
+            .is_none());
+        assert!(
+            OidcConfig::from_values(Some("https://idp".into()), None, None, None, None).is_err()
+        );
+        let config = config();
+        assert_eq!(config.issuer, "https://idp.example");
+        assert_eq!(config.session_ttl, Duration::from_secs(12 * 3600));
+    }
+
+    #[test]
+    fn session_cookie_round_trips_and_rejects_tampering() {
+        let oidc = oidc();
+        let alice = Identity {
+            sub: "123".into(),
+            email: Some("alice@example.com".into()),
+            name: None,
+        };
+        let cookie = oidc.session_cookie(&alice);
+        assert!(cookie.contains("HttpOnly") && cookie.contains("Secure"));
+        let value = cookie_value(cookie.split(';').next().unwrap(), SESSION_COOKIE).unwrap();
+        assert_eq!(oidc.session(value), Some(alice));
+        assert_eq!(oidc.session(&value.replace('.', ".x")), None);
+
+        let expired = oidc.sign(&Session {
+            identity: Identity {
+                sub: "123".into(),
+                email: None,
+                name: None,
+            },
+            exp: Utc::now().timestamp() - 1,
+        });
+        assert_eq!(oidc.session(&expired), None);
+    }
+
+    #[test]
+    fn login_url_carries_pkce_and_state() {
+        let oidc = oidc();
+        let (url, cookie) = oidc.login("/board");
+        assert!(url.starts_with("https://idp.example/authorize?response_type=code"));
+        assert!(url.contains("code_challenge_method=S256"));
+        let value = cookie_value(cookie.split(';').next().unwrap(), LOGIN_COOKIE).unwrap();
+        let pending: PendingLogin = oidc.verify(value).unwrap();
+        assert!(url.contains(&format!("state={}", pending.state)));
+        assert_eq!(pending.return_to, "/board");
+    }
+
+    #[test]
+    fn identity_label_prefers_email() {
+        let mut identity = Identity {
+            sub: "123".into(),
+            email: None,
+            name: None,
+        };
+        assert_eq!(identity.label(), "user:123");
+        identity.email = Some("a@b.c".into());
+        assert_eq!(identity.label(), "user:a@b.c");
+    }
+
+    #[tokio::test]
+    async fn verifies_provider_tokens_for_this_client() {
+        let provider = fake_provider::FakeProvider::start().await;
+        let oidc = Oidc::discover(provider.config(), b"server key")
+            .await
+            .unwrap();
+
+        let identity = oidc
+            .verify_token(&provider.token("flowstate"))
+            .await
+            .unwrap();
+        assert_eq!(identity.label(), "user:alice@example.com");
+
+        let other_client = provider.token("someone-else");
+        assert!(oidc.verify_token(&other_client).await.is_err());
+        let mut forged = provider.token("flowstate");
+        forged.push('x');
+        assert!(oidc.verify_token(&forged).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn unknown_key_ids_refetch_at_most_once_a_minute() {
+        let provider = fake_provider::FakeProvider::start().await;
+        let mut oidc = Oidc::discover(provider.config(), b"server key")
+            .await
+            .unwrap();
+        assert_eq!(provider.jwks_fetches(), 1);
+
+        for _ in 0..3 {
+            let err = oidc
+                .verify_token(&provider.token_with_kid("rotated"))
+                .await
+                .unwrap_err();
+            assert!(matches!(err, OidcError::InvalidToken(_)), "{err}");
+        }
+        assert_eq!(provider.jwks_fetches(), 2);
+
+        // A failed refetch keeps the keys already cached
+        *oidc.jwks_refetched.get_mut() = None;
+        oidc.metadata.jwks_uri = "http://127.0.0.1:1/jwks".into();
+        assert!(matches!(
+            oidc.verify_token(&provider.token_with_kid("rotated")).await,
+            Err(OidcError::Provider(_))
+        ));
+        oidc.verify_token(&provider.token("flowstate"))
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn cookie_value_finds_named_cookie() {
+        let header = "a=1; flowstate_session=abc.def; b=2";
+        assert_eq!(cookie_value(header, SESSION_COOKIE), Some("abc.def"));
+        assert_eq!(cookie_value(header, "missing"), None);
+    }
+}
+
+/// A stand-in identity provider for tests: an RSA signing key and the
+/// discovery document, key set and token endpoint serving it.
+#[cfg(test)]
+pub(crate) mod fake_provider {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use axum::routing::{get, post};
+    use axum::{Form, Json, Router};
+    use jsonwebtoken::{EncodingKey, Header};
+    use openssl::rsa::Rsa;
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    pub struct FakeProvider {
+        pub issuer: String,
+        encoding_key: EncodingKey,
+        /// Nonce the next token from the token endpoint carries.
+        pub nonce: Arc<Mutex<String>>,
+        jwks_fetches: Arc<AtomicUsize>,
+    }
+
+    impl FakeProvider {
+        pub async fn start() -> Self {
+            let rsa = Rsa::generate(2048).unwrap();
+            let jwks = json!({ "keys": [{
+                "kty": "RSA",
+                "kid": "test-key",
+                "alg": "RS256",
+                "use": "sig",
+                "n": URL_SAFE_NO_PAD.encode(rsa.n().to_vec()),
+                "e": URL_SAFE_NO_PAD.encode(rsa.e().to_vec()),
+            }]});
+            let encoding_key =
+                EncodingKey::from_rsa_pem(&rsa.private_key_to_pem().unwrap()).unwrap();
+
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let issuer = format!("http://{}", listener.local_addr().unwrap());
+            let nonce = Arc::new(Mutex::new(String::new()));
+            let jwks_fetches = Arc::new(AtomicUsize::new(0));
+            let discovery = json!({
+                "issuer": issuer,
+                "authorization_endpoint": format!("{issuer}/authorize"),
+                "token_endpoint": format!("{issuer}/token"),
+                "jwks_uri": format!("{issuer}/jwks"),
+            });
+            let provider = Self {
+                issuer,
+                encoding_key,
+                nonce: nonce.clone(),
+                jwks_fetches: jwks_fetches.clone(),
+            };
+            let token_key = provider.encoding_key.clone();
+            let token_issuer = provider.issuer.clone();
+            let app = Router::new()
+                .route(
+                    "/.well-known/openid-configuration",
+                    get(move || async move { Json(discovery) }),
+                )
+                .route(
+                    "/jwks",
+                    get(move || async move {
+                        jwks_fetches.fetch_add(1, Ordering::SeqCst);
+                        Json(jwks)
+                    }),
+                )
+                .route(
+                    "/token",
+                    post(move |Form(form): Form<Vec<(String, String)>>| async move {
+                        assert!(form.iter().any(|(k, _)| k == "code_verifier"));
+                        let nonce = nonce.lock().unwrap().clone();
+                        let id_token = sign(
+                            &token_key,
+                            "test-key",
+                            &token_issuer,
+                            "flowstate",
+                            Some(&nonce),
+                        );
+                        Json(json!({ "id_token": id_token, "token_type": "Bearer" }))
+                    }),
+                );
+            tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+            provider
+        }
+
+        pub fn config(&self) -> OidcConfig {
+            OidcConfig::from_values(
+                Some(self.issuer.clone()),
+                Some("flowstate".into()),
+                Some("secret".into()),
+                Some("http://flowstate.test/auth/callback".into()),
+                None,
+            )
+            .unwrap()
+            .unwrap()
+        }
+
+        pub fn token(&self, audience: &str) -> String {
+            sign(&self.encoding_key, "test-key", &self.issuer, audience, None)
+        }
+
+        /// A token naming a key id the key set does not have.
+        pub fn token_with_kid(&self, kid: &str) -> String {
+            sign(&self.encoding_key, kid, &self.issuer, "flowstate", None)
+        }
+
+        /// How many times the key set has been served.
+        pub fn jwks_fetches(&self) -> usize {
+            self.jwks_fetches.load(Ordering::SeqCst)
+        }
+    }
+
+    fn sign(
+        key: &EncodingKey,
+        kid: &str,
+        issuer: &str,
+        audience: &str,
+        nonce: Option<&str>,
+    ) -> String {
+        let mut header = Header::new(jsonwebtoken::Algorithm::RS256);
+        header.kid = Some(kid.into());
+        let mut claims: Value = json!({
+            "iss": issuer,
+            "aud": audience,
+            "sub": "user-1",
+            "email": "alice@example.com",
+            "exp": Utc::now().timestamp() + 300,
+        });
+        if let Some(nonce) = nonce {
+            claims["nonce"] = json!(nonce);
+        }
+        jsonwebtoken::encode(&header, &claims, key).unwrap()
+    }
+}
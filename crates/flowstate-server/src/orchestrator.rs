@@ -0,0 +1,371 @@
+//! Server-side approval when a run completes, and autopilot.
+//!
+//! The phase a completed run produced is approved on the spot when the
+//! project's approval rules say `auto` for it, or when the task is on
+//! autopilot and no rule asks for a person. Approving has the same side
+//! effects as a reviewer doing it. Autopilot then queues the next phase's
+//! run, carrying the task through research → design → plan → build →
+//! verify; a revise run answering PR review goes back to verify. Nothing happens once the task has moved on without it: a phase
+//! already approved or rejected, or the task done or cancelled.
+
+use flowstate_core::approval_rule::{self, ApprovalPhase, ApprovalPolicy};
+use flowstate_core::claude_run::{ClaudeAction, ClaudeRun};
+use flowstate_core::feature_flag::AUTOPILOT;
+use flowstate_core::task::{ApprovalStatus, Status, TaskType, UpdateTask};
+use tracing::{debug, info, warn};
+
+use crate::routes::claude_runs::{self, QueueOptions};
+use crate::routes::{admin, tasks, AppState};
+
+/// Recorded as the actor of the approvals autopilot makes.
+const AUTOPILOT_ACTOR: &str = "autopilot";
+/// Recorded as the actor of approvals made by an `auto` approval rule.
+const RULE_ACTOR: &str = "rule:auto";
+
+/// What happens once a run completes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    /// The phase the run produced an artifact for, to approve first.
+    pub approve: Option<ApprovalPhase>,
+    /// The run autopilot queues next, if the pipeline continues.
+    pub queue: Option<ClaudeAction>,
+}
+
+/// The transition out of a completed `action`. A distill revises its
+/// phase's artifact, so it moves on exactly as the phase's own run does.
+pub fn next_step(action: ClaudeAction, task_type: TaskType) -> Step {
+    let (approve, queue) = match action {
+        ClaudeAction::Research | ClaudeAction::ResearchDistill => {
+            (Some(ApprovalPhase::Research), Some(ClaudeAction::Design))
+        }
+        ClaudeAction::Design | ClaudeAction::DesignDistill => {
+            (Some(ApprovalPhase::Spec), Some(ClaudeAction::Plan))
+        }
+        // Spikes end at an approved plan
+        ClaudeAction::Plan | ClaudeAction::PlanDistill => (
+            Some(ApprovalPhase::Plan),
+            task_type.is_buildable().then_some(ClaudeAction::Build),
+        ),
+        ClaudeAction::Build | ClaudeAction::Revise => (None, Some(ClaudeAction::Verify)),
+        ClaudeAction::Verify | ClaudeAction::VerifyDistill => (Some(ApprovalPhase::Verify), None),
+    };
+    Step { approve, queue }
+}
+
+/// Whether `phase` is approved without a person. An `auto` rule always
+/// approves; a `human` or `reviewer` rule never does. With no rule,
+/// autopilot approves every phase but verification.
+pub fn approves_automatically(
+    policy: Option<ApprovalPolicy>,
+    phase: ApprovalPhase,
+    autopilot: bool,
+) -> bool {
+    match policy {
+        Some(ApprovalPolicy::Auto) => true,
+        Some(ApprovalPolicy::Human | ApprovalPolicy::Reviewer) => false,
+        None => autopilot && phase != ApprovalPhase::Verify,
+    }
+}
+
+/// Apply the project's approval rules and autopilot to `run`'s task after
+/// the run completed. Autopilot needs the task's `autopilot` setting and
+/// the `autopilot` flag for its project. Failures are logged and never fail
+/// the status report that finished the run.
+pub(crate) async fn run_completed(state: &AppState, run: &ClaudeRun) {
+    let task = match state.db.get_task(&run.task_id).await {
+        Ok(task) => task,
+        Err(e) => {
+            warn!("approvals: loading task of run {}: {e}", run.id);
+            return;
+        }
+    };
+    if matches!(task.status, Status::Done | Status::Cancelled) {
+        return;
+    }
+    let autopilot =
+        task.autopilot && admin::flag_enabled(state, AUTOPILOT, Some(&task.project_id)).await;
+
+    let step = next_step(run.action, task.task_type);
+    if let Some(phase) = step.approve {
+        // Only an artifact still waiting for review; a reviewer got there first otherwise
+        if phase.status(&task) != ApprovalStatus::Pending {
+            debug!("approvals: {phase} of task {} is not pending", task.id);
+            return;
+        }
+        let rules = match state.db.list_approval_rules(&task.project_id).await {
+            Ok(rules) => rules,
+            Err(e) => {
+                warn!(
+                    "approvals: loading rules of project {}: {e}",
+                    task.project_id
+                );
+                return;
+            }
+        };
+        let policy = approval_rule::policy_for(&rules, phase);
+        if !approves_automatically(policy, phase, autopilot) {
+            return;
+        }
+        let actor = if policy == Some(ApprovalPolicy::Auto) {
+            RULE_ACTOR
+        } else {
+            AUTOPILOT_ACTOR
+        };
+        let mut update = UpdateTask {
+            actor: Some(actor.to_string()),
+            ..Default::default()
+        };
+        phase.set_status(&mut update, ApprovalStatus::Approved);
+        if let Err((_, body)) = tasks::apply_task_update(state, &task.id, update).await {
+            warn!(
+                "approvals: approving {phase} of task {}: {}",
+                task.id, body.0
+            );
+            return;
+        }
+    }
+    if !autopilot {
+        return;
+    }
+    let Some(next) = step.queue else {
+        return;
+    };
+    // Keyed on the finished run, so a repeated report cannot queue twice
+    let options = QueueOptions {
+        idempotency_key: Some(format!("{AUTOPILOT_ACTOR}:{}", run.id)),
+        ..Default::default()
+    };
+    match claude_runs::queue_run(state, &task.id, next, options).await {
+        Ok(queued) => info!(
+            "autopilot: queued {next} run {} for task {}",
+            queued.id, task.id
+        ),
+        Err((_, body)) => warn!(
+            "autopilot: queueing {next} for task {}: {}",
+            task.id, body.0
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use flowstate_core::claude_run::{ClaudeRunStatus, CreateClaudeRun};
+    use flowstate_core::project::CreateProject;
+    use flowstate_core::task::{CreateTask, Priority};
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::routes::build_router;
+    use crate::test_helpers::test_state;
+
+    #[test]
+    fn steps_follow_the_workflow() {
+        let step = next_step(ClaudeAction::Research, TaskType::Feature);
+        assert_eq!(step.approve, Some(ApprovalPhase::Research));
+        assert_eq!(step.queue, Some(ClaudeAction::Design));
+        assert_eq!(
+            next_step(ClaudeAction::DesignDistill, TaskType::Feature),
+            Step {
+                approve: Some(ApprovalPhase::Spec),
+                queue: Some(ClaudeAction::Plan),
+            }
+        );
+        assert_eq!(
+            next_step(ClaudeAction::Plan, TaskType::Bug).queue,
+            Some(ClaudeAction::Build)
+        );
+        assert_eq!(next_step(ClaudeAction::Plan, TaskType::Spike).queue, None);
+        assert_eq!(
+            next_step(ClaudeAction::Build, TaskType::Feature),
+            Step {
+                approve: None,
+                queue: Some(ClaudeAction::Verify),
+            }
+        );
+        assert_eq!(
+            next_step(ClaudeAction::Revise, TaskType::Bug).queue,
+            Some(ClaudeAction::Verify)
+        );
+        assert_eq!(
+            next_step(ClaudeAction::Verify, TaskType::Feature),
+            Step {
+                approve: Some(ApprovalPhase::Verify),
+                queue: None,
+            }
+        );
+    }
+
+    #[test]
+    fn rules_decide_before_autopilot() {
+        use ApprovalPolicy::*;
+        let plan = ApprovalPhase::Plan;
+        assert!(approves_automatically(Some(Auto), plan, false));
+        assert!(!approves_automatically(Some(Human), plan, true));
+        assert!(!approves_automatically(Some(Reviewer), plan, true));
+        assert!(approves_automatically(None, plan, true));
+        assert!(!approves_automatically(None, plan, false));
+        assert!(!approves_automatically(None, ApprovalPhase::Verify, true));
+        assert!(approves_automatically(
+            Some(Auto),
+            ApprovalPhase::Verify,
+            false
+        ));
+    }
+
+    /// Queue and claim a research run on a new task, leaving its research
+    /// pending review as a runner does. Returns the task and run ids.
+    async fn research_done(state: &AppState, slug: &str, autopilot: bool) -> (String, String) {
+        let project = state
+            .db
+            .create_project(&CreateProject {
+                name: slug.into(),
+                slug: slug.into(),
+                description: String::new(),
+                repo_url: String::new(),
+            })
+            .await
+            .unwrap();
+        let task = state
+            .db
+            .create_task(&CreateTask {
+                project_id: project.id,
+                title: "Autopiloted".into(),
+                description: String::new(),
+                status: Status::Research,
+                priority: Priority::Medium,
+                task_type: TaskType::Feature,
+                parent_id: None,
+                reviewer: String::new(),
+                due_at: None,
+                research_capability: None,
+                design_capability: None,
+                plan_capability: None,
+                build_capability: None,
+                verify_capability: None,
+            })
+            .await
+            .unwrap();
+        state
+            .db
+            .update_task(
+                &task.id,
+                &UpdateTask {
+                    autopilot: Some(autopilot),
+                    research_status: Some(ApprovalStatus::Pending),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let run = state
+            .db
+            .create_claude_run(&CreateClaudeRun {
+                task_id: task.id.clone(),
+                action: ClaudeAction::Research,
+                required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
+            })
+            .await
+            .unwrap();
+        state.db.claim_next_claude_run(&[]).await.unwrap().unwrap();
+        (task.id, run.id)
+    }
+
+    fn complete(run_id: &str) -> Request<Body> {
+        Request::builder()
+            .method(Method::PUT)
+            .uri(format!("/api/claude-runs/{run_id}/status"))
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"status": "completed"}"#))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn completed_research_approves_and_queues_design() {
+        let state = test_state().await;
+        let app = build_router(state.clone());
+        let (task_id, run_id) = research_done(&state, "auto", true).await;
+
+        let resp = app.clone().oneshot(complete(&run_id)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let task = state.db.get_task(&task_id).await.unwrap();
+        assert_eq!(task.research_status, ApprovalStatus::Approved);
+        assert_eq!(task.status, Status::Design);
+        let runs = state.db.list_claude_runs_for_task(&task_id).await.unwrap();
+        let design: Vec<_> = runs
+            .iter()
+            .filter(|r| r.action == ClaudeAction::Design)
+            .collect();
+        assert_eq!(design.len(), 1);
+        assert_eq!(design[0].status, ClaudeRunStatus::Queued);
+
+        // A repeated report queues nothing more
+        app.oneshot(complete(&run_id)).await.unwrap();
+        let runs = state.db.list_claude_runs_for_task(&task_id).await.unwrap();
+        assert_eq!(runs.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn manual_tasks_and_disabled_projects_wait_for_review() {
+        let state = test_state().await;
+        let app = build_router(state.clone());
+        let (manual, run_id) = research_done(&state, "manual", false).await;
+        app.clone().oneshot(complete(&run_id)).await.unwrap();
+
+        let (flagged, run_id) = research_done(&state, "flagged", true).await;
+        let project_id = state.db.get_task(&flagged).await.unwrap().project_id;
+        state
+            .db
+            .set_feature_flag(AUTOPILOT, Some(&project_id), false)
+            .await
+            .unwrap();
+        app.oneshot(complete(&run_id)).await.unwrap();
+
+        for task_id in [manual, flagged] {
+            let task = state.db.get_task(&task_id).await.unwrap();
+            assert_eq!(task.research_status, ApprovalStatus::Pending);
+            let runs = state.db.list_claude_runs_for_task(&task_id).await.unwrap();
+            assert_eq!(runs.len(), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn approval_rules_override_autopilot() {
+        let state = test_state().await;
+        let app = build_router(state.clone());
+
+        // An auto rule approves on a manual task, which still queues nothing
+        let (manual, run_id) = research_done(&state, "ruled", false).await;
+        let project_id = state.db.get_task(&manual).await.unwrap().project_id;
+        state
+            .db
+            .set_approval_rule(&project_id, ApprovalPhase::Research, ApprovalPolicy::Auto)
+            .await
+            .unwrap();
+        app.clone().oneshot(complete(&run_id)).await.unwrap();
+        let task = state.db.get_task(&manual).await.unwrap();
+        assert_eq!(task.research_status, ApprovalStatus::Approved);
+        let runs = state.db.list_claude_runs_for_task(&manual).await.unwrap();
+        assert_eq!(runs.len(), 1);
+
+        // A human rule stops autopilot at the phase
+        let (piloted, run_id) = research_done(&state, "held", true).await;
+        let project_id = state.db.get_task(&piloted).await.unwrap().project_id;
+        state
+            .db
+            .set_approval_rule(&project_id, ApprovalPhase::Research, ApprovalPolicy::Human)
+            .await
+            .unwrap();
+        app.oneshot(complete(&run_id)).await.unwrap();
+        let task = state.db.get_task(&piloted).await.unwrap();
+        assert_eq!(task.research_status, ApprovalStatus::Pending);
+        let runs = state.db.list_claude_runs_for_task(&piloted).await.unwrap();
+        assert_eq!(runs.len(), 1);
+    }
+}
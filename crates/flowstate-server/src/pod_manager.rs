@@ -1,11 +1,15 @@
 use std::sync::Arc;
 use std::time::Instant;
 
+use chrono::{DateTime, Utc};
+use flowstate_core::feature_flag::AUTOSCALING;
+use flowstate_core::runner::RunnerCapability;
+use flowstate_db::QueueDemand;
 use serde::Serialize;
 use tokio::sync::Mutex as TokioMutex;
 use tracing::{error, info, warn};
 
-use crate::routes::{AppState, PendingConfig, RunnerStatus};
+use crate::routes::{admin, queue_runner_config, AppState, PendingConfig, RunnerStatus};
 
 // ---------------------------------------------------------------------------
 // RunPod API trait (for testability)
@@ -18,6 +22,9 @@ pub trait RunPodApi: Send + Sync {
     async fn start_pod(&self, pod_id: &str) -> Result<(), PodApiError>;
     async fn stop_pod(&self, pod_id: &str) -> Result<(), PodApiError>;
     async fn create_pod(&self, config: &PodCreateRequest) -> Result<String, PodApiError>;
+    /// Make a cheap authenticated call, to check the API is reachable and
+    /// accepts the key.
+    async fn ping(&self) -> Result<(), PodApiError>;
 }
 
 #[derive(Debug)]
@@ -73,6 +80,19 @@ pub struct PodManagerConfig {
     /// Assembled from `FLOWSTATE_RUNPOD_POD_*` env vars on the server.
     pub pod_env: Vec<(String, String)>,
     pub ts_authkey: Option<String>,
+    /// Fewest pods kept up, even with an empty queue.
+    pub min_pods: usize,
+    /// Most pods up at once, counting the primary pod.
+    pub max_pods: usize,
+    /// Queued runs each pod is expected to take on.
+    pub runs_per_pod: i64,
+    /// A claimable run queued for longer than this asks for one more pod.
+    pub queue_age_threshold_secs: u64,
+    pub scale_up_cooldown_secs: u64,
+    pub scale_down_cooldown_secs: u64,
+    /// Tier the pods' runners advertise; only runs they could claim count
+    /// as demand.
+    pub pod_capability: RunnerCapability,
 }
 
 impl PodManagerConfig {
@@ -126,6 +146,35 @@ impl PodManagerConfig {
                 .unwrap_or_else(|_| "COMMUNITY".into()),
             pod_env,
             ts_authkey,
+            min_pods: std::env::var("FLOWSTATE_RUNPOD_MIN_PODS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+            max_pods: std::env::var("FLOWSTATE_RUNPOD_MAX_PODS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(1),
+            runs_per_pod: std::env::var("FLOWSTATE_RUNPOD_RUNS_PER_POD")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(2),
+            queue_age_threshold_secs: std::env::var("FLOWSTATE_RUNPOD_QUEUE_AGE_THRESHOLD")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(600),
+            scale_up_cooldown_secs: std::env::var("FLOWSTATE_RUNPOD_SCALE_UP_COOLDOWN")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(120),
+            scale_down_cooldown_secs: std::env::var("FLOWSTATE_RUNPOD_SCALE_DOWN_COOLDOWN")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(600),
+            // Runners default to the heavy tier
+            pod_capability: std::env::var("FLOWSTATE_RUNPOD_POD_CAPABILITY")
+                .ok()
+                .and_then(|s| RunnerCapability::parse_str(&s))
+                .unwrap_or(RunnerCapability::Heavy),
         })
     }
 
@@ -221,10 +270,27 @@ pub struct PodManagerState {
     /// When a drain was requested.
     #[serde(skip)]
     pub drain_requested_at: Option<Instant>,
+    /// Pods started beside the primary one when the queue calls for more.
+    pub scale_pods: Vec<ScalePod>,
+    /// When a scale-out pod was last started or drained, for cooldowns.
+    #[serde(skip)]
+    pub last_scaled_at: Option<Instant>,
+}
+
+/// A pod the autoscaler started beside the primary one. Stopped pods are
+/// kept and restarted before new ones are created.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct ScalePod {
+    pub pod_id: String,
+    /// Id the pod's runner registers under, set through `FLOWSTATE_RUNNER_ID`.
+    pub runner_id: String,
+    pub status: PodStatus,
+    #[serde(skip)]
+    pub drain_requested_at: Option<Instant>,
 }
 
 /// Pod lifecycle status from the pod manager's perspective.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, utoipa::ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum PodStatus {
     Unknown,
@@ -245,10 +311,64 @@ impl PodManagerState {
             day_start: Instant::now(),
             cost_capped: false,
             drain_requested_at: None,
+            scale_pods: Vec::new(),
+            last_scaled_at: None,
         }
     }
 }
 
+/// How many pods the queue calls for: enough for the runs a pod could claim
+/// at `runs_per_pod` each, plus one when the longest-waiting of them has
+/// waited past `queue_age_threshold_secs`, kept within `min_pods..=max_pods`.
+pub fn desired_pods(
+    config: &PodManagerConfig,
+    demand: &[QueueDemand],
+    now: DateTime<Utc>,
+) -> usize {
+    let tiers = config.pod_capability.handled_tiers();
+    let claimable: Vec<&QueueDemand> = demand
+        .iter()
+        .filter(|d| match d.capability.as_deref() {
+            None => true,
+            Some(c) => RunnerCapability::parse_str(c).is_some_and(|c| tiers.contains(&c)),
+        })
+        .collect();
+    let queued: i64 = claimable.iter().map(|d| d.queued).sum();
+    let mut pods = (queued.max(0) as usize).div_ceil(config.runs_per_pod.max(1) as usize);
+    if claimable
+        .iter()
+        .any(|d| (now - d.oldest_queued_at).num_seconds() > config.queue_age_threshold_secs as i64)
+    {
+        pods += 1;
+    }
+    pods.clamp(config.min_pods, config.max_pods.max(config.min_pods))
+}
+
+/// The pod manager's view of a RunPod `desiredStatus`.
+fn pod_status(runpod_status: &str) -> PodStatus {
+    match runpod_status {
+        "RUNNING" => PodStatus::Running,
+        "EXITED" | "STOPPED" | "TERMINATED" => PodStatus::Stopped,
+        "CREATED" | "STARTING" => PodStatus::Starting,
+        _ => PodStatus::Unknown,
+    }
+}
+
+/// Cents a pod costs over one decision tick.
+fn tick_cost_cents(cost_per_hr: f64, config: &PodManagerConfig) -> u64 {
+    (cost_per_hr * 100.0 * config.scan_interval_secs as f64 / 3600.0) as u64
+}
+
+/// Whether `runner_id` last reported itself drained.
+fn runner_drained(state: &AppState, runner_id: &str) -> bool {
+    state
+        .runners
+        .lock()
+        .unwrap()
+        .get(runner_id)
+        .is_some_and(|r| r.status == RunnerStatus::Drained)
+}
+
 // ---------------------------------------------------------------------------
 // Pod Manager Loop
 // ---------------------------------------------------------------------------
@@ -268,7 +388,10 @@ pub async fn run_pod_manager(
     );
 
     loop {
-        tokio::time::sleep(interval).await;
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = state.shutdown.cancelled() => break,
+        }
 
         if let Err(e) = pod_manager_tick(&state, &config, &pod_state, api.as_ref()).await {
             error!("pod manager tick error: {e}");
@@ -289,6 +412,11 @@ pub async fn pod_manager_tick(
         .count_queued_runs()
         .await
         .map_err(|e| format!("count_queued_runs: {e}"))?;
+    let demand = state
+        .db
+        .queued_run_demand()
+        .await
+        .map_err(|e| format!("queued_run_demand: {e}"))?;
 
     let mut ps = pod_state.lock().await;
 
@@ -304,12 +432,7 @@ pub async fn pod_manager_tick(
     if let Some(ref pod_id) = ps.pod_id {
         match api.get_pod(pod_id).await {
             Ok(info) => {
-                let new_status = match info.status.as_str() {
-                    "RUNNING" => PodStatus::Running,
-                    "EXITED" | "STOPPED" | "TERMINATED" => PodStatus::Stopped,
-                    "CREATED" | "STARTING" => PodStatus::Starting,
-                    _ => PodStatus::Unknown,
-                };
+                let new_status = pod_status(&info.status);
                 // Don't override Draining/Drained status from our side
                 if ps.pod_status != PodStatus::Draining && ps.pod_status != PodStatus::Drained {
                     ps.pod_status = new_status;
@@ -317,9 +440,7 @@ pub async fn pod_manager_tick(
 
                 // Accumulate cost
                 if let Some(cost_per_hr) = info.cost_per_hr {
-                    let cost_per_tick =
-                        (cost_per_hr * 100.0 * config.scan_interval_secs as f64 / 3600.0) as u64;
-                    ps.daily_cost_cents += cost_per_tick;
+                    ps.daily_cost_cents += tick_cost_cents(cost_per_hr, config);
                 }
             }
             Err(e) => {
@@ -327,9 +448,24 @@ pub async fn pod_manager_tick(
             }
         }
     }
+    let mut scale_cost_cents = 0;
+    for pod in ps.scale_pods.iter_mut() {
+        match api.get_pod(&pod.pod_id).await {
+            Ok(info) => {
+                if pod.status != PodStatus::Draining {
+                    pod.status = pod_status(&info.status);
+                }
+                if pod.status != PodStatus::Stopped {
+                    scale_cost_cents += info.cost_per_hr.map_or(0, |c| tick_cost_cents(c, config));
+                }
+            }
+            Err(e) => warn!("pod manager: failed to get pod {} status: {e}", pod.pod_id),
+        }
+    }
+    ps.daily_cost_cents += scale_cost_cents;
 
     // 3. Find the RunPod runner in the runners map
-    let runner_id = find_runpod_runner(&state.runners);
+    let runner_id = find_runpod_runner(&state.runners, &ps.scale_pods);
 
     // 4. Decision logic
 
@@ -341,17 +477,28 @@ pub async fn pod_manager_tick(
         );
         ps.cost_capped = true;
         if let Some(ref rid) = runner_id {
-            set_runner_drain(&state.runners, rid);
+            set_runner_drain(state, rid).await;
         }
         ps.pod_status = PodStatus::Draining;
         ps.drain_requested_at = Some(Instant::now());
+        for pod in ps.scale_pods.iter_mut() {
+            if matches!(pod.status, PodStatus::Starting | PodStatus::Running) {
+                set_runner_drain(state, &pod.runner_id).await;
+                pod.status = PodStatus::Draining;
+                pod.drain_requested_at = Some(Instant::now());
+            }
+        }
         return Ok(());
     }
 
     match ps.pod_status {
         PodStatus::Stopped | PodStatus::Unknown => {
-            // SPIN UP: if queued >= threshold and not cost-capped
-            if queue_depth >= config.queue_threshold && !ps.cost_capped {
+            // SPIN UP: if queued >= threshold (or pods are kept warm), not
+            // cost-capped, and autoscaling is on
+            if (queue_depth >= config.queue_threshold || config.min_pods > 0)
+                && !ps.cost_capped
+                && admin::flag_enabled(state, AUTOSCALING, None).await
+            {
                 info!(
                     "pod manager: queue_depth={queue_depth} >= threshold={}, spinning up",
                     config.queue_threshold
@@ -400,10 +547,13 @@ pub async fn pod_manager_tick(
                 .map(|t| t.elapsed().as_secs())
                 .unwrap_or(config.idle_timeout_secs + 1);
 
-            if queue_depth <= config.spindown_threshold && idle_secs > config.idle_timeout_secs {
+            if queue_depth <= config.spindown_threshold
+                && idle_secs > config.idle_timeout_secs
+                && config.min_pods == 0
+            {
                 info!("pod manager: idle for {idle_secs}s, draining");
                 if let Some(ref rid) = runner_id {
-                    set_runner_drain(&state.runners, rid);
+                    set_runner_drain(state, rid).await;
                 }
                 ps.pod_status = PodStatus::Draining;
                 ps.drain_requested_at = Some(Instant::now());
@@ -456,31 +606,172 @@ pub async fn pod_manager_tick(
         }
     }
 
+    autoscale(state, config, &mut ps, api, &demand).await;
+
     Ok(())
 }
 
+/// Start or drain scale-out pods so that, with the primary pod, as many
+/// are up as the queue calls for. At most one pod is started or drained
+/// per cooldown window, and a pod is only stopped once its runner reports
+/// drained (or the drain times out).
+async fn autoscale(
+    state: &AppState,
+    config: &PodManagerConfig,
+    ps: &mut PodManagerState,
+    api: &dyn RunPodApi,
+    demand: &[QueueDemand],
+) {
+    for pod in ps
+        .scale_pods
+        .iter_mut()
+        .filter(|p| p.status == PodStatus::Draining)
+    {
+        let drained = runner_drained(state, &pod.runner_id);
+        let timed_out = pod
+            .drain_requested_at
+            .is_some_and(|t| t.elapsed().as_secs() > config.drain_timeout_secs);
+        if !drained && !timed_out {
+            continue;
+        }
+        if drained {
+            info!(
+                "pod manager: runner {} drained, stopping pod {}",
+                pod.runner_id, pod.pod_id
+            );
+        } else {
+            warn!(
+                "pod manager: drain timeout, force stopping pod {}",
+                pod.pod_id
+            );
+        }
+        if let Err(e) = api.stop_pod(&pod.pod_id).await {
+            error!("pod manager: failed to stop pod {}: {e}", pod.pod_id);
+            continue;
+        }
+        pod.status = PodStatus::Stopped;
+        pod.drain_requested_at = None;
+    }
+
+    if config.max_pods <= 1 {
+        return;
+    }
+    // Scale-out pods only join a primary pod that is up
+    let wanted = if matches!(ps.pod_status, PodStatus::Starting | PodStatus::Running) {
+        desired_pods(config, demand, Utc::now()).saturating_sub(1)
+    } else {
+        0
+    };
+    let up = ps
+        .scale_pods
+        .iter()
+        .filter(|p| matches!(p.status, PodStatus::Starting | PodStatus::Running))
+        .count();
+    let cooled_down = |secs: u64| {
+        ps.last_scaled_at
+            .is_none_or(|t| t.elapsed().as_secs() >= secs)
+    };
+
+    if up < wanted {
+        if ps.cost_capped
+            || !cooled_down(config.scale_up_cooldown_secs)
+            || !admin::flag_enabled(state, AUTOSCALING, None).await
+        {
+            return;
+        }
+        info!("pod manager: {} pods wanted, scaling up", wanted + 1);
+        scale_up(config, ps, api).await;
+    } else if up > wanted && cooled_down(config.scale_down_cooldown_secs) {
+        // Drain the newest running pod; it stops once its runner is drained
+        let Some(pod) = ps
+            .scale_pods
+            .iter_mut()
+            .rev()
+            .find(|p| p.status == PodStatus::Running)
+        else {
+            return;
+        };
+        info!(
+            "pod manager: {} pods wanted, draining pod {}",
+            wanted + 1,
+            pod.pod_id
+        );
+        set_runner_drain(state, &pod.runner_id).await;
+        pod.status = PodStatus::Draining;
+        pod.drain_requested_at = Some(Instant::now());
+        ps.last_scaled_at = Some(Instant::now());
+    }
+}
+
+/// Restart a stopped scale-out pod, or create a new one when none is
+/// stopped.
+async fn scale_up(config: &PodManagerConfig, ps: &mut PodManagerState, api: &dyn RunPodApi) {
+    if let Some(pod) = ps
+        .scale_pods
+        .iter_mut()
+        .find(|p| p.status == PodStatus::Stopped)
+    {
+        if let Err(e) = api.start_pod(&pod.pod_id).await {
+            error!("pod manager: failed to start pod {}: {e}", pod.pod_id);
+            return;
+        }
+        pod.status = PodStatus::Starting;
+    } else {
+        // The primary pod is the first
+        let runner_id = format!("flowstate-gpu-{}", ps.scale_pods.len() + 2);
+        let mut env_vars = config.pod_env.clone();
+        env_vars.push(("FLOWSTATE_RUNNER_ID".into(), runner_id.clone()));
+        let req = PodCreateRequest {
+            name: runner_id.clone(),
+            image: config.template_image.clone(),
+            gpu_type: config.gpu_type.clone(),
+            gpu_count: config.gpu_count,
+            cloud_type: config.cloud_type.clone(),
+            network_volume_id: config.network_volume.clone(),
+            env_vars,
+        };
+        match api.create_pod(&req).await {
+            Ok(pod_id) => {
+                info!("pod manager: created pod {pod_id} for runner {runner_id}");
+                ps.scale_pods.push(ScalePod {
+                    pod_id,
+                    runner_id,
+                    status: PodStatus::Starting,
+                    drain_requested_at: None,
+                });
+            }
+            Err(e) => {
+                error!("pod manager: failed to create pod: {e}");
+                return;
+            }
+        }
+    }
+    ps.last_scaled_at = Some(Instant::now());
+}
+
 /// Find a runner that looks like a RunPod runner (heuristic: check for known naming patterns).
-/// For now, returns the first runner whose runner_id is not "unknown".
+/// For now, returns the first runner that does not belong to a scale-out pod.
 fn find_runpod_runner(
     runners: &std::sync::Mutex<std::collections::HashMap<String, crate::routes::RunnerInfo>>,
+    scale_pods: &[ScalePod],
 ) -> Option<String> {
     let runners = runners.lock().unwrap();
     // In practice, the RunPod runner will be the only runner or the one with
     // a specific naming convention. For now, return the first registered runner.
-    runners.keys().next().cloned()
+    runners
+        .keys()
+        .find(|id| !scale_pods.iter().any(|p| &p.runner_id == *id))
+        .cloned()
 }
 
 /// Set drain pending_config on a specific runner.
-fn set_runner_drain(
-    runners: &std::sync::Mutex<std::collections::HashMap<String, crate::routes::RunnerInfo>>,
-    runner_id: &str,
-) {
-    let mut runners = runners.lock().unwrap();
-    if let Some(info) = runners.get_mut(runner_id) {
-        info.pending_config = Some(PendingConfig {
-            poll_interval: None,
-            drain: Some(true),
-        });
+async fn set_runner_drain(state: &AppState, runner_id: &str) {
+    let drain = PendingConfig {
+        poll_interval: None,
+        drain: Some(true),
+    };
+    if let Err(e) = queue_runner_config(state, runner_id, drain).await {
+        warn!("pod manager: failed to record drain for runner {runner_id}: {e}");
     }
 }
 
@@ -586,6 +877,14 @@ impl RunPodApi for RunPodClient {
 
         Ok(pod.id)
     }
+
+    async fn ping(&self) -> Result<(), PodApiError> {
+        self.inner
+            .list_pods()
+            .await
+            .map(|_| ())
+            .map_err(|e| PodApiError(e.to_string()))
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -598,6 +897,7 @@ mod tests {
     use crate::routes::{InnerAppState, RunnerInfo, RunnerStatus};
     use flowstate_service::LocalService;
     use std::collections::HashMap;
+    use std::sync::atomic::AtomicBool;
     use std::sync::Arc;
 
     /// Mock RunPod API for testing.
@@ -605,6 +905,7 @@ mod tests {
         pod_status: std::sync::Mutex<String>,
         started: std::sync::Mutex<bool>,
         stopped: std::sync::Mutex<bool>,
+        created: std::sync::Mutex<Vec<PodCreateRequest>>,
     }
 
     impl MockRunPodApi {
@@ -613,6 +914,7 @@ mod tests {
                 pod_status: std::sync::Mutex::new(status.into()),
                 started: std::sync::Mutex::new(false),
                 stopped: std::sync::Mutex::new(false),
+                created: std::sync::Mutex::new(Vec::new()),
             }
         }
     }
@@ -640,9 +942,14 @@ mod tests {
             Ok(())
         }
 
-        async fn create_pod(&self, _config: &PodCreateRequest) -> Result<String, PodApiError> {
+        async fn create_pod(&self, config: &PodCreateRequest) -> Result<String, PodApiError> {
+            self.created.lock().unwrap().push(config.clone());
             Ok("new-pod-123".into())
         }
+
+        async fn ping(&self) -> Result<(), PodApiError> {
+            Ok(())
+        }
     }
 
     fn test_config() -> PodManagerConfig {
@@ -662,6 +969,13 @@ mod tests {
             cloud_type: "COMMUNITY".into(),
             pod_env: vec![],
             ts_authkey: None,
+            min_pods: 0,
+            max_pods: 1,
+            runs_per_pod: 2,
+            queue_age_threshold_secs: 600,
+            scale_up_cooldown_secs: 120,
+            scale_down_cooldown_secs: 600,
+            pod_capability: RunnerCapability::Heavy,
         }
     }
 
@@ -681,6 +995,10 @@ mod tests {
                     .to_string_lossy()
                     .to_string(),
             ),
+            gcs: None,
+            azure: None,
+            durability: flowstate_store::Durability::Atomic,
+            multipart: flowstate_store::MultipartConfig::default(),
         };
         let store = flowstate_store::create_store(&store_config).unwrap();
         use aes_gcm::KeyInit;
@@ -693,6 +1011,21 @@ mod tests {
             encryption_key: key,
             store,
             pod_manager: None,
+            pod_api: None,
+            watchdog: Default::default(),
+            runner_mtls: false,
+            maintenance: AtomicBool::new(false),
+            status_page: crate::routes::status::StatusPage::new(
+                crate::routes::status::StatusExposure::Off,
+            ),
+            db_maintenance: std::sync::Mutex::new(None),
+            task_links: flowstate_core::TaskLinks::default(),
+            notifier: Default::default(),
+            events: Default::default(),
+            run_logs: Default::default(),
+            github_webhook_secret: None,
+            rate_limiter: Default::default(),
+            shutdown: Default::default(),
         })
     }
 
@@ -728,6 +1061,7 @@ mod tests {
                 description: String::new(),
                 status: flowstate_core::task::Status::Todo,
                 priority: flowstate_core::task::Priority::Medium,
+                task_type: flowstate_core::task::TaskType::Feature,
                 parent_id: None,
                 reviewer: String::new(),
                 research_capability: None,
@@ -735,6 +1069,7 @@ mod tests {
                 plan_capability: None,
                 build_capability: None,
                 verify_capability: None,
+                due_at: None,
             })
             .await
             .unwrap();
@@ -744,6 +1079,11 @@ mod tests {
                 task_id: task.id,
                 action: flowstate_core::claude_run::ClaudeAction::Research,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .await
             .unwrap();
@@ -758,6 +1098,72 @@ mod tests {
         assert_eq!(ps.pod_status, PodStatus::Starting);
     }
 
+    #[tokio::test]
+    async fn test_no_spin_up_when_autoscaling_disabled() {
+        let state = test_state().await;
+        let config = test_config();
+        let api = Arc::new(MockRunPodApi::new("EXITED"));
+        let pod_state = Arc::new(TokioMutex::new(PodManagerState::new(Some("pod-1".into()))));
+        pod_state.lock().await.pod_status = PodStatus::Stopped;
+
+        let project = state
+            .db
+            .create_project(&flowstate_core::project::CreateProject {
+                name: "P".into(),
+                slug: "p".into(),
+                description: String::new(),
+                repo_url: String::new(),
+            })
+            .await
+            .unwrap();
+        let task = state
+            .db
+            .create_task(&flowstate_core::task::CreateTask {
+                project_id: project.id,
+                title: "T".into(),
+                description: String::new(),
+                status: flowstate_core::task::Status::Todo,
+                priority: flowstate_core::task::Priority::Medium,
+                task_type: flowstate_core::task::TaskType::Feature,
+                parent_id: None,
+                reviewer: String::new(),
+                research_capability: None,
+                design_capability: None,
+                plan_capability: None,
+                build_capability: None,
+                verify_capability: None,
+                due_at: None,
+            })
+            .await
+            .unwrap();
+        state
+            .db
+            .create_claude_run(&flowstate_core::claude_run::CreateClaudeRun {
+                task_id: task.id,
+                action: flowstate_core::claude_run::ClaudeAction::Research,
+                required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
+            })
+            .await
+            .unwrap();
+        state
+            .db
+            .set_feature_flag(AUTOSCALING, None, false)
+            .await
+            .unwrap();
+
+        pod_manager_tick(&state, &config, &pod_state, api.as_ref())
+            .await
+            .unwrap();
+
+        assert!(!*api.started.lock().unwrap());
+        assert_eq!(pod_state.lock().await.pod_status, PodStatus::Stopped);
+    }
+
     #[tokio::test]
     async fn test_no_spin_up_when_queue_empty() {
         let state = test_state().await;
@@ -810,6 +1216,8 @@ mod tests {
                     active_builds: None,
                     status: RunnerStatus::Active,
                     pending_config: None,
+                    benchmark: None,
+                    org_id: None,
                 },
             );
         }
@@ -858,6 +1266,8 @@ mod tests {
                     active_builds: None,
                     status: RunnerStatus::Drained,
                     pending_config: None,
+                    benchmark: None,
+                    org_id: None,
                 },
             );
         }
@@ -924,6 +1334,7 @@ mod tests {
                 description: String::new(),
                 status: flowstate_core::task::Status::Todo,
                 priority: flowstate_core::task::Priority::Medium,
+                task_type: flowstate_core::task::TaskType::Feature,
                 parent_id: None,
                 reviewer: String::new(),
                 research_capability: None,
@@ -931,6 +1342,7 @@ mod tests {
                 plan_capability: None,
                 build_capability: None,
                 verify_capability: None,
+                due_at: None,
             })
             .await
             .unwrap();
@@ -940,6 +1352,11 @@ mod tests {
                 task_id: task.id,
                 action: flowstate_core::claude_run::ClaudeAction::Research,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .await
             .unwrap();
@@ -986,6 +1403,7 @@ mod tests {
                 description: String::new(),
                 status: flowstate_core::task::Status::Todo,
                 priority: flowstate_core::task::Priority::Medium,
+                task_type: flowstate_core::task::TaskType::Feature,
                 parent_id: None,
                 reviewer: String::new(),
                 research_capability: None,
@@ -993,6 +1411,7 @@ mod tests {
                 plan_capability: None,
                 build_capability: None,
                 verify_capability: None,
+                due_at: None,
             })
             .await
             .unwrap();
@@ -1002,6 +1421,11 @@ mod tests {
                 task_id: task.id,
                 action: flowstate_core::claude_run::ClaudeAction::Research,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .await
             .unwrap();
@@ -1068,6 +1492,7 @@ mod tests {
                 description: String::new(),
                 status: flowstate_core::task::Status::Todo,
                 priority: flowstate_core::task::Priority::Medium,
+                task_type: flowstate_core::task::TaskType::Feature,
                 parent_id: None,
                 reviewer: String::new(),
                 research_capability: None,
@@ -1075,6 +1500,7 @@ mod tests {
                 plan_capability: None,
                 build_capability: None,
                 verify_capability: None,
+                due_at: None,
             })
             .await
             .unwrap();
@@ -1084,6 +1510,11 @@ mod tests {
                 task_id: task.id,
                 action: flowstate_core::claude_run::ClaudeAction::Research,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .await
             .unwrap();
@@ -1144,6 +1575,9 @@ mod tests {
             async fn create_pod(&self, _config: &PodCreateRequest) -> Result<String, PodApiError> {
                 Ok("x".into())
             }
+            async fn ping(&self) -> Result<(), PodApiError> {
+                Err(PodApiError("connection refused".into()))
+            }
         }
 
         let api = Arc::new(FailGetPodApi);
@@ -1245,6 +1679,8 @@ mod tests {
                     active_builds: None,
                     status: RunnerStatus::Active,
                     pending_config: None,
+                    benchmark: None,
+                    org_id: None,
                 },
             );
         }
@@ -1257,4 +1693,213 @@ mod tests {
         assert!(ps.cost_capped);
         assert_eq!(ps.pod_status, PodStatus::Draining);
     }
+
+    fn demand(capability: Option<&str>, queued: i64, waited_secs: i64) -> QueueDemand {
+        QueueDemand {
+            capability: capability.map(str::to_string),
+            queued,
+            oldest_queued_at: Utc::now() - chrono::Duration::seconds(waited_secs),
+        }
+    }
+
+    fn runner(runner_id: &str, status: RunnerStatus) -> RunnerInfo {
+        RunnerInfo {
+            runner_id: runner_id.into(),
+            last_seen: chrono::Utc::now(),
+            backend_name: None,
+            capability: None,
+            capabilities: vec![],
+            poll_interval: None,
+            max_concurrent: None,
+            max_builds: None,
+            active_count: None,
+            active_builds: None,
+            status,
+            pending_config: None,
+            benchmark: None,
+            org_id: None,
+        }
+    }
+
+    async fn queue_runs(state: &AppState, count: usize) {
+        let project = state
+            .db
+            .create_project(&flowstate_core::project::CreateProject {
+                name: "P".into(),
+                slug: "p".into(),
+                description: String::new(),
+                repo_url: String::new(),
+            })
+            .await
+            .unwrap();
+        let task = state
+            .db
+            .create_task(&flowstate_core::task::CreateTask {
+                project_id: project.id,
+                title: "T".into(),
+                description: String::new(),
+                status: flowstate_core::task::Status::Todo,
+                priority: flowstate_core::task::Priority::Medium,
+                task_type: flowstate_core::task::TaskType::Feature,
+                parent_id: None,
+                reviewer: String::new(),
+                research_capability: None,
+                design_capability: None,
+                plan_capability: None,
+                build_capability: None,
+                verify_capability: None,
+                due_at: None,
+            })
+            .await
+            .unwrap();
+        for _ in 0..count {
+            state
+                .db
+                .create_claude_run(&flowstate_core::claude_run::CreateClaudeRun {
+                    task_id: task.id.clone(),
+                    action: flowstate_core::claude_run::ClaudeAction::Build,
+                    required_capability: Some("heavy".into()),
+                    priority: 0,
+                    feedback: None,
+                    idempotency_key: None,
+                    run_window: None,
+                    trace_context: None,
+                })
+                .await
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_desired_pods_follows_claimable_demand() {
+        let mut config = test_config();
+        config.max_pods = 4;
+        config.pod_capability = RunnerCapability::Standard;
+        let now = Utc::now();
+
+        assert_eq!(desired_pods(&config, &[], now), 0);
+        // Heavy runs are beyond a standard pod; unknown tiers are never claimed
+        let queue = [
+            demand(Some("heavy"), 9, 0),
+            demand(Some("light"), 2, 0),
+            demand(None, 1, 0),
+            demand(Some("gpu"), 5, 0),
+        ];
+        assert_eq!(desired_pods(&config, &queue, now), 2);
+        // A long wait asks for one more pod, up to the maximum
+        let queue = [demand(Some("standard"), 3, 700)];
+        assert_eq!(desired_pods(&config, &queue, now), 3);
+        let queue = [demand(None, 20, 700)];
+        assert_eq!(desired_pods(&config, &queue, now), 4);
+
+        config.min_pods = 1;
+        assert_eq!(desired_pods(&config, &[], now), 1);
+    }
+
+    #[tokio::test]
+    async fn test_scale_up_adds_pods_after_cooldown() {
+        let state = test_state().await;
+        let mut config = test_config();
+        config.max_pods = 3;
+        config.pod_env = vec![("FLOWSTATE_SERVER_URL".into(), "http://server".into())];
+        let api = Arc::new(MockRunPodApi::new("RUNNING"));
+        let pod_state = Arc::new(TokioMutex::new(PodManagerState::new(Some("pod-1".into()))));
+        pod_state.lock().await.pod_status = PodStatus::Running;
+        queue_runs(&state, 5).await;
+
+        pod_manager_tick(&state, &config, &pod_state, api.as_ref())
+            .await
+            .unwrap();
+        {
+            let created = api.created.lock().unwrap();
+            assert_eq!(created.len(), 1);
+            assert_eq!(created[0].name, "flowstate-gpu-2");
+            assert!(created[0]
+                .env_vars
+                .contains(&("FLOWSTATE_RUNNER_ID".into(), "flowstate-gpu-2".into())));
+            assert_eq!(created[0].env_vars.len(), 2);
+        }
+        assert_eq!(pod_state.lock().await.scale_pods.len(), 1);
+
+        // Still cooling down: three pods are wanted but no more start yet
+        pod_manager_tick(&state, &config, &pod_state, api.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(api.created.lock().unwrap().len(), 1);
+
+        pod_state.lock().await.last_scaled_at = None;
+        pod_manager_tick(&state, &config, &pod_state, api.as_ref())
+            .await
+            .unwrap();
+        let ps = pod_state.lock().await;
+        assert_eq!(ps.scale_pods.len(), 2);
+        assert_eq!(ps.scale_pods[1].runner_id, "flowstate-gpu-3");
+    }
+
+    #[tokio::test]
+    async fn test_scale_down_stops_pod_only_once_drained() {
+        let state = test_state().await;
+        let mut config = test_config();
+        config.max_pods = 3;
+        let api = Arc::new(MockRunPodApi::new("RUNNING"));
+        let pod_state = Arc::new(TokioMutex::new(PodManagerState::new(Some("pod-1".into()))));
+        {
+            let mut ps = pod_state.lock().await;
+            ps.pod_status = PodStatus::Running;
+            ps.last_work_seen = Some(Instant::now());
+            ps.scale_pods.push(ScalePod {
+                pod_id: "pod-2".into(),
+                runner_id: "flowstate-gpu-2".into(),
+                status: PodStatus::Running,
+                drain_requested_at: None,
+            });
+        }
+        state.runners.lock().unwrap().insert(
+            "flowstate-gpu-2".into(),
+            runner("flowstate-gpu-2", RunnerStatus::Active),
+        );
+
+        // The queue is empty: the scale-out pod's runner is told to drain
+        pod_manager_tick(&state, &config, &pod_state, api.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(
+            pod_state.lock().await.scale_pods[0].status,
+            PodStatus::Draining
+        );
+        let pending = state.runners.lock().unwrap()["flowstate-gpu-2"]
+            .pending_config
+            .clone();
+        assert_eq!(pending.and_then(|p| p.drain), Some(true));
+        assert!(!*api.stopped.lock().unwrap());
+
+        // Still working through its runs
+        state
+            .runners
+            .lock()
+            .unwrap()
+            .get_mut("flowstate-gpu-2")
+            .unwrap()
+            .status = RunnerStatus::Draining;
+        pod_manager_tick(&state, &config, &pod_state, api.as_ref())
+            .await
+            .unwrap();
+        assert!(!*api.stopped.lock().unwrap());
+
+        state
+            .runners
+            .lock()
+            .unwrap()
+            .get_mut("flowstate-gpu-2")
+            .unwrap()
+            .status = RunnerStatus::Drained;
+        pod_manager_tick(&state, &config, &pod_state, api.as_ref())
+            .await
+            .unwrap();
+        assert!(*api.stopped.lock().unwrap());
+        let ps = pod_state.lock().await;
+        assert_eq!(ps.scale_pods[0].status, PodStatus::Stopped);
+        // The primary pod stays up; its runner is not the scale-out one
+        assert_eq!(ps.pod_status, PodStatus::Running);
+    }
 }
@@ -0,0 +1,99 @@
+//! Keeps the CI checks status of open task PRs current, so a task's verify
+//! phase is only approved once its PR's checks have passed.
+//!
+//! Open PRs of tasks in Verify are polled on an interval; the GitHub webhook
+//! receiver also asks for a refresh as soon as a check suite completes.
+
+use std::time::Duration;
+
+use flowstate_core::task::Status;
+use flowstate_core::task_pr::{ChecksStatus, PrCheck, TaskPr};
+use flowstate_service::TaskService;
+use tracing::{error, info, warn};
+
+use crate::forge::{self, ForgeError};
+use crate::routes::AppState;
+use crate::{crypto, shutdown};
+
+/// Seconds between polls when `FLOWSTATE_PR_CHECKS_SECS` is unset.
+const DEFAULT_INTERVAL_SECS: u64 = 120;
+
+/// Time between polls, read from `FLOWSTATE_PR_CHECKS_SECS`; `0` turns
+/// polling off, leaving checks to the GitHub webhook.
+pub fn interval_from_env() -> Option<Duration> {
+    let secs = std::env::var("FLOWSTATE_PR_CHECKS_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_INTERVAL_SECS);
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
+/// Background task that runs [`poll`] every `interval`.
+pub async fn run_pr_checks(state: AppState, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    while shutdown::next_tick(&mut ticker, &state.shutdown).await {
+        poll(&state).await;
+    }
+}
+
+/// Refresh the checks of every open PR whose task is waiting in Verify.
+/// PRs of projects without a repo token are skipped.
+pub async fn poll(state: &AppState) {
+    let prs = match state.db.list_open_task_prs().await {
+        Ok(prs) => prs,
+        Err(e) => {
+            error!("pr checks: {e}");
+            return;
+        }
+    };
+    for task_pr in prs {
+        match state.service.get_task(&task_pr.task_id).await {
+            Ok(task) if task.status == Status::Verify => {}
+            _ => continue,
+        }
+        match refresh(state, &task_pr).await {
+            Ok(_) | Err(ForgeError::Unsupported(_)) => {}
+            Err(e) => warn!("pr checks: {}: {e}", task_pr.pr_url),
+        }
+    }
+}
+
+/// Fetch the checks of `task_pr` from the repository host and store their
+/// combined status.
+pub async fn refresh(
+    state: &AppState,
+    task_pr: &TaskPr,
+) -> Result<(TaskPr, Vec<PrCheck>), ForgeError> {
+    let task = state
+        .service
+        .get_task(&task_pr.task_id)
+        .await
+        .map_err(|e| ForgeError::Failed(e.to_string()))?;
+    let project = state
+        .service
+        .get_project(&task.project_id)
+        .await
+        .map_err(|e| ForgeError::Failed(e.to_string()))?;
+    let Some(encrypted) = project.repo_token.as_deref() else {
+        return Err(ForgeError::Unsupported("project has no repo token".into()));
+    };
+    let token = crypto::decrypt(&state.encryption_key, encrypted)
+        .map_err(|e| ForgeError::Failed(format!("decrypt: {e}")))?;
+
+    let checks = forge::get_pr_checks(&project, &token, task_pr.pr_number).await?;
+    let status = ChecksStatus::combine(&checks);
+    if status == task_pr.checks_status {
+        return Ok((task_pr.clone(), checks));
+    }
+    let updated = state
+        .db
+        .set_task_pr_checks(&task_pr.id, status)
+        .await
+        .map_err(|e| ForgeError::Failed(e.to_string()))?;
+    info!(
+        "checks on {} are {}",
+        task_pr.pr_url,
+        status.map_or("gone", |s| s.as_str())
+    );
+    Ok((updated, checks))
+}
@@ -0,0 +1,510 @@
+use std::fmt;
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use flowstate_core::project::{CreateProject, Project, ProviderType, UpdateProject};
+use flowstate_core::run_window::RunWindow;
+use flowstate_core::sprint::{CreateSprint, Sprint, SprintStatus, UpdateSprint};
+use flowstate_db::{Database, DbError};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::json;
+
+/// A project configuration file: the projects to create or bring in line,
+/// with their sprints. Fields left out are left as they are; nothing the
+/// file does not mention is changed or deleted.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    #[serde(default)]
+    pub projects: Vec<ProjectSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectSpec {
+    /// Identifies the project; a slug not in the database creates one.
+    pub slug: String,
+    /// Required to create the project.
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub repo_url: Option<String>,
+    pub provider_type: Option<ProviderType>,
+    pub skip_tls_verify: Option<bool>,
+    /// `null` lifts the cap.
+    #[serde(default, deserialize_with = "present")]
+    pub max_concurrent_runs: Option<Option<i32>>,
+    pub claim_weight: Option<i32>,
+    /// `null` clears the window.
+    #[serde(default, deserialize_with = "present")]
+    pub run_window: Option<Option<RunWindow>>,
+    pub docs_in_repo: Option<bool>,
+    pub verify_followups: Option<bool>,
+    /// `null` removes the budget.
+    #[serde(default, deserialize_with = "present")]
+    pub monthly_budget_usd: Option<Option<f64>>,
+    /// `null` goes back to the runner's own SSH configuration.
+    #[serde(default, deserialize_with = "present")]
+    pub ssh_key_path: Option<Option<String>>,
+    /// `null` pushes to `repo_url` itself again.
+    #[serde(default, deserialize_with = "present")]
+    pub fork_owner: Option<Option<String>>,
+    /// `null` gives runs the whole repository again.
+    #[serde(default, deserialize_with = "present")]
+    pub repo_subpath: Option<Option<String>>,
+    #[serde(default)]
+    pub sprints: Vec<SprintSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SprintSpec {
+    /// Identifies the sprint within its project.
+    pub name: String,
+    pub goal: Option<String>,
+    pub status: Option<SprintStatus>,
+    #[serde(default, deserialize_with = "present")]
+    pub starts_at: Option<Option<DateTime<Utc>>>,
+    #[serde(default, deserialize_with = "present")]
+    pub ends_at: Option<Option<DateTime<Utc>>>,
+}
+
+/// Tell an explicit `null` (`Some(None)`) from a missing field (`None`).
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+impl ConfigFile {
+    pub fn parse(yaml: &str) -> Result<Self> {
+        let file: Self = serde_yaml::from_str(yaml)?;
+        for (i, project) in file.projects.iter().enumerate() {
+            if file.projects[..i].iter().any(|p| p.slug == project.slug) {
+                bail!("project {} is listed twice", project.slug);
+            }
+            let limits = UpdateProject {
+                max_concurrent_runs: project.max_concurrent_runs,
+                claim_weight: project.claim_weight,
+                monthly_budget_usd: project.monthly_budget_usd,
+                ..Default::default()
+            };
+            if let Err(e) = limits.validate() {
+                bail!("project {}: {e}", project.slug);
+            }
+            for (j, sprint) in project.sprints.iter().enumerate() {
+                if project.sprints[..j].iter().any(|s| s.name == sprint.name) {
+                    bail!("sprint {} is listed twice in {}", sprint.name, project.slug);
+                }
+            }
+        }
+        Ok(file)
+    }
+}
+
+/// What `apply` would change, in the order it changes it.
+#[derive(Debug, Default)]
+pub struct Plan {
+    pub changes: Vec<Change>,
+}
+
+#[derive(Debug)]
+pub struct Change {
+    pub kind: ChangeKind,
+    /// `project <slug>` or `sprint <slug>/<name>`.
+    pub target: String,
+    pub fields: Vec<FieldChange>,
+    action: Action,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Create,
+    Update,
+}
+
+/// One field's value before and after, rendered as JSON. `from` is `None`
+/// for fields of something being created.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldChange {
+    pub name: &'static str,
+    pub from: Option<String>,
+    pub to: String,
+}
+
+#[derive(Debug)]
+enum Action {
+    CreateProject(CreateProject, UpdateProject),
+    UpdateProject(String, UpdateProject),
+    /// The project is found by slug when applied, as it may be created by
+    /// an earlier change.
+    CreateSprint(String, CreateSprint, UpdateSprint),
+    UpdateSprint(String, UpdateSprint),
+}
+
+impl Plan {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            let sign = match change.kind {
+                ChangeKind::Create => '+',
+                ChangeKind::Update => '~',
+            };
+            writeln!(f, "{sign} {}", change.target)?;
+            for field in &change.fields {
+                match &field.from {
+                    Some(from) => writeln!(f, "    {}: {from} -> {}", field.name, field.to)?,
+                    None => writeln!(f, "    {}: {}", field.name, field.to)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Collects the fields that differ between the database and the file.
+#[derive(Default)]
+struct Diff(Vec<FieldChange>);
+
+impl Diff {
+    /// Record `name` if the file sets it to something other than `current`
+    /// (`None` when creating), returning the value to write.
+    fn field<T: Serialize + PartialEq + Clone>(
+        &mut self,
+        name: &'static str,
+        current: Option<&T>,
+        wanted: &Option<T>,
+    ) -> Option<T> {
+        let wanted = wanted.as_ref()?;
+        if current == Some(wanted) {
+            return None;
+        }
+        self.0.push(FieldChange {
+            name,
+            from: current.map(|v| json!(v).to_string()),
+            to: json!(wanted).to_string(),
+        });
+        Some(wanted.clone())
+    }
+}
+
+/// Compare the file with the database and work out the changes, without
+/// making any.
+pub async fn plan(db: &dyn Database, file: &ConfigFile) -> Result<Plan> {
+    let mut plan = Plan::default();
+    for spec in &file.projects {
+        let existing = match db.get_project_by_slug(&spec.slug).await {
+            Ok(project) => Some(project),
+            Err(DbError::NotFound(_)) => None,
+            Err(e) => return Err(e.into()),
+        };
+        plan.changes.extend(plan_project(spec, existing.as_ref())?);
+        let sprints = match &existing {
+            Some(project) => db.list_sprints(&project.id).await?,
+            None => Vec::new(),
+        };
+        for sprint in &spec.sprints {
+            let current = sprints.iter().find(|s| s.name == sprint.name);
+            plan.changes
+                .extend(plan_sprint(&spec.slug, sprint, current));
+        }
+    }
+    Ok(plan)
+}
+
+fn plan_project(spec: &ProjectSpec, existing: Option<&Project>) -> Result<Option<Change>> {
+    let mut diff = Diff::default();
+    let name = diff.field("name", existing.map(|p| &p.name), &spec.name);
+    let description = diff.field(
+        "description",
+        existing.map(|p| &p.description),
+        &spec.description,
+    );
+    let repo_url = diff.field("repo_url", existing.map(|p| &p.repo_url), &spec.repo_url);
+    let update = UpdateProject {
+        provider_type: diff.field(
+            "provider_type",
+            existing.map(|p| p.provider_type.as_ref().unwrap_or(&ProviderType::Github)),
+            &spec.provider_type,
+        ),
+        skip_tls_verify: diff.field(
+            "skip_tls_verify",
+            existing.map(|p| &p.skip_tls_verify),
+            &spec.skip_tls_verify,
+        ),
+        max_concurrent_runs: diff.field(
+            "max_concurrent_runs",
+            existing.map(|p| &p.max_concurrent_runs),
+            &spec.max_concurrent_runs,
+        ),
+        claim_weight: diff.field(
+            "claim_weight",
+            existing.map(|p| &p.claim_weight),
+            &spec.claim_weight,
+        ),
+        run_window: diff.field(
+            "run_window",
+            existing.map(|p| &p.run_window),
+            &spec.run_window,
+        ),
+        docs_in_repo: diff.field(
+            "docs_in_repo",
+            existing.map(|p| &p.docs_in_repo),
+            &spec.docs_in_repo,
+        ),
+        verify_followups: diff.field(
+            "verify_followups",
+            existing.map(|p| &p.verify_followups),
+            &spec.verify_followups,
+        ),
+        monthly_budget_usd: diff.field(
+            "monthly_budget_usd",
+            existing.map(|p| &p.monthly_budget_usd),
+            &spec.monthly_budget_usd,
+        ),
+        ssh_key_path: diff.field(
+            "ssh_key_path",
+            existing.map(|p| &p.ssh_key_path),
+            &spec.ssh_key_path,
+        ),
+        fork_owner: diff.field(
+            "fork_owner",
+            existing.map(|p| &p.fork_owner),
+            &spec.fork_owner,
+        ),
+        repo_subpath: diff.field(
+            "repo_subpath",
+            existing.map(|p| &p.repo_subpath),
+            &spec.repo_subpath,
+        ),
+        ..Default::default()
+    };
+
+    let target = format!("project {}", spec.slug);
+    let (kind, action) = match existing {
+        Some(project) => {
+            if diff.0.is_empty() {
+                return Ok(None);
+            }
+            let update = UpdateProject {
+                name,
+                description,
+                repo_url,
+                ..update
+            };
+            (
+                ChangeKind::Update,
+                Action::UpdateProject(project.id.clone(), update),
+            )
+        }
+        None => {
+            let Some(name) = name else {
+                bail!("{target} does not exist yet and needs a name to be created");
+            };
+            let create = CreateProject {
+                name,
+                slug: spec.slug.clone(),
+                description: description.unwrap_or_default(),
+                repo_url: repo_url.unwrap_or_default(),
+            };
+            (ChangeKind::Create, Action::CreateProject(create, update))
+        }
+    };
+    Ok(Some(Change {
+        kind,
+        target,
+        fields: diff.0,
+        action,
+    }))
+}
+
+fn plan_sprint(slug: &str, spec: &SprintSpec, existing: Option<&Sprint>) -> Option<Change> {
+    let mut diff = Diff::default();
+    let goal = diff.field("goal", existing.map(|s| &s.goal), &spec.goal);
+    let status = diff.field("status", existing.map(|s| &s.status), &spec.status);
+    let starts_at = diff.field("starts_at", existing.map(|s| &s.starts_at), &spec.starts_at);
+    let ends_at = diff.field("ends_at", existing.map(|s| &s.ends_at), &spec.ends_at);
+
+    let target = format!("sprint {slug}/{}", spec.name);
+    let (kind, action) = match existing {
+        Some(sprint) => {
+            if diff.0.is_empty() {
+                return None;
+            }
+            let update = UpdateSprint {
+                goal,
+                status,
+                starts_at,
+                ends_at,
+                ..Default::default()
+            };
+            (
+                ChangeKind::Update,
+                Action::UpdateSprint(sprint.id.clone(), update),
+            )
+        }
+        None => {
+            let create = CreateSprint {
+                project_id: String::new(),
+                name: spec.name.clone(),
+                goal: goal.unwrap_or_default(),
+                starts_at: starts_at.flatten(),
+                ends_at: ends_at.flatten(),
+            };
+            let update = UpdateSprint {
+                status,
+                ..Default::default()
+            };
+            (
+                ChangeKind::Create,
+                Action::CreateSprint(slug.to_string(), create, update),
+            )
+        }
+    };
+    Some(Change {
+        kind,
+        target,
+        fields: diff.0,
+        action,
+    })
+}
+
+/// Make the changes in `plan`, in order. A failure stops the apply part way;
+/// planning again shows what is left.
+pub async fn apply(db: &dyn Database, plan: Plan) -> Result<()> {
+    for change in plan.changes {
+        let target = change.target;
+        let applied = async {
+            match change.action {
+                Action::CreateProject(create, update) => {
+                    let project = db.create_project(&create).await?;
+                    db.update_project(&project.id, &update).await?;
+                }
+                Action::UpdateProject(id, update) => {
+                    db.update_project(&id, &update).await?;
+                }
+                Action::CreateSprint(slug, mut create, update) => {
+                    create.project_id = db.get_project_by_slug(&slug).await?.id;
+                    let sprint = db.create_sprint(&create).await?;
+                    if update.status.is_some() {
+                        db.update_sprint(&sprint.id, &update).await?;
+                    }
+                }
+                Action::UpdateSprint(id, update) => {
+                    db.update_sprint(&id, &update).await?;
+                }
+            }
+            Ok::<_, DbError>(())
+        };
+        applied
+            .await
+            .with_context(|| format!("applying {target}"))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flowstate_db::SqliteDatabase;
+
+    const CONFIG: &str = r#"
+projects:
+  - slug: web
+    name: Web App
+    repo_url: https://github.com/acme/web
+    max_concurrent_runs: 2
+    run_window: "20:00-06:00"
+    docs_in_repo: true
+    sprints:
+      - name: Sprint 1
+        goal: Ship login
+        status: active
+        starts_at: 2026-10-01T00:00:00Z
+"#;
+
+    #[test]
+    fn unknown_sections_and_duplicates_are_rejected() {
+        let err = ConfigFile::parse("projects: []\nwebhooks: []\n").unwrap_err();
+        assert!(err.to_string().contains("unknown field `webhooks`"));
+        let err = ConfigFile::parse("projects:\n  - slug: a\n  - slug: a\n").unwrap_err();
+        assert_eq!(err.to_string(), "project a is listed twice");
+    }
+
+    #[test]
+    fn out_of_range_limits_are_rejected() {
+        for (field, value) in [
+            ("claim_weight", "0"),
+            ("claim_weight", "-1"),
+            ("max_concurrent_runs", "0"),
+            ("monthly_budget_usd", "-5.0"),
+        ] {
+            let err = ConfigFile::parse(&format!("projects:\n  - slug: a\n    {field}: {value}\n"))
+                .unwrap_err();
+            assert!(err.to_string().starts_with("project a: "), "{err}");
+            assert!(err.to_string().contains(field), "{err}");
+        }
+        ConfigFile::parse(
+            "projects:\n  - slug: a\n    claim_weight: 1\n    max_concurrent_runs: null\n",
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn plan_then_apply_converges() {
+        let db = SqliteDatabase::open_in_memory().unwrap();
+        let file = ConfigFile::parse(CONFIG).unwrap();
+
+        let first = plan(&db, &file).await.unwrap();
+        assert_eq!(
+            first.to_string(),
+            "+ project web\n    \
+                 name: \"Web App\"\n    \
+                 repo_url: \"https://github.com/acme/web\"\n    \
+                 max_concurrent_runs: 2\n    \
+                 run_window: \"20:00-06:00+00:00\"\n    \
+                 docs_in_repo: true\n\
+             + sprint web/Sprint 1\n    \
+                 goal: \"Ship login\"\n    \
+                 status: \"active\"\n    \
+                 starts_at: \"2026-10-01T00:00:00Z\"\n"
+        );
+        apply(&db, first).await.unwrap();
+
+        let project = db.get_project_by_slug("web").await.unwrap();
+        assert_eq!(project.max_concurrent_runs, Some(2));
+        assert!(project.docs_in_repo);
+        let sprints = db.list_sprints(&project.id).await.unwrap();
+        assert_eq!(sprints[0].status, SprintStatus::Active);
+        assert!(plan(&db, &file).await.unwrap().is_empty());
+
+        // Lift the cap and leave everything else alone
+        let file = ConfigFile::parse(
+            "projects:\n  - slug: web\n    max_concurrent_runs: null\n    claim_weight: 1\n",
+        )
+        .unwrap();
+        let second = plan(&db, &file).await.unwrap();
+        assert_eq!(
+            second.to_string(),
+            "~ project web\n    max_concurrent_runs: 2 -> null\n"
+        );
+        apply(&db, second).await.unwrap();
+        let project = db.get_project_by_slug("web").await.unwrap();
+        assert_eq!(project.max_concurrent_runs, None);
+        assert_eq!(project.name, "Web App");
+    }
+
+    #[tokio::test]
+    async fn new_projects_need_a_name() {
+        let db = SqliteDatabase::open_in_memory().unwrap();
+        let file = ConfigFile::parse("projects:\n  - slug: api\n").unwrap();
+        let err = plan(&db, &file).await.unwrap_err();
+        assert!(err.to_string().contains("needs a name"));
+    }
+}
@@ -0,0 +1,314 @@
+//! Token-bucket rate limiting per API key and per client address, so one
+//! runaway polling client cannot starve the rest.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+
+use crate::auth::sha256_hex;
+use crate::listen::PeerInfo;
+use crate::routes::AppState;
+
+/// Buckets kept before idle ones are dropped.
+const PRUNE_THRESHOLD: usize = 4096;
+
+/// Hard cap on buckets per kind of client. Keys are counted before they are
+/// authenticated, so a client sending a new random key with every request
+/// would otherwise grow the map without bound.
+const MAX_BUCKETS: usize = 4 * PRUNE_THRESHOLD;
+
+/// Routes never limited, so load balancer health checks keep working.
+const EXEMPT_PATHS: &[&str] = &["/api/health", "/api/health/live", "/api/health/ready"];
+
+/// A sustained rate and the burst allowed above it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limit {
+    pub per_minute: u32,
+    pub burst: u32,
+}
+
+impl Limit {
+    fn refill_per_sec(&self) -> f64 {
+        f64::from(self.per_minute) / 60.0
+    }
+}
+
+/// Limits read from `FLOWSTATE_RATE_LIMIT_*`. Both are off by default.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RateLimitConfig {
+    /// Per bearer token, however many addresses it is used from.
+    pub per_key: Option<Limit>,
+    /// Per client address, whatever key it sends.
+    pub per_ip: Option<Limit>,
+}
+
+impl RateLimitConfig {
+    /// `FLOWSTATE_RATE_LIMIT_KEY` and `FLOWSTATE_RATE_LIMIT_IP` are requests
+    /// per minute (unset or `0` for no limit). `FLOWSTATE_RATE_LIMIT_BURST`
+    /// is how many requests a client may make at once, defaulting to the
+    /// per-minute figure.
+    pub fn from_env() -> Result<Self> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        Self::from_values(
+            var("FLOWSTATE_RATE_LIMIT_KEY").as_deref(),
+            var("FLOWSTATE_RATE_LIMIT_IP").as_deref(),
+            var("FLOWSTATE_RATE_LIMIT_BURST").as_deref(),
+        )
+    }
+
+    fn from_values(key: Option<&str>, ip: Option<&str>, burst: Option<&str>) -> Result<Self> {
+        let burst = burst
+            .map(|b| b.parse::<u32>().ok().filter(|b| *b > 0))
+            .map(|b| b.context("FLOWSTATE_RATE_LIMIT_BURST must be a positive number"))
+            .transpose()?;
+        let limit = |value: Option<&str>, name: &str| -> Result<Option<Limit>> {
+            let Some(value) = value else {
+                return Ok(None);
+            };
+            let per_minute: u32 = value
+                .parse()
+                .with_context(|| format!("{name} must be a number of requests per minute"))?;
+            Ok((per_minute > 0).then(|| Limit {
+                per_minute,
+                burst: burst.unwrap_or(per_minute),
+            }))
+        };
+        Ok(Self {
+            per_key: limit(key, "FLOWSTATE_RATE_LIMIT_KEY")?,
+            per_ip: limit(ip, "FLOWSTATE_RATE_LIMIT_IP")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Buckets for one kind of client.
+struct Buckets<K> {
+    limit: Limit,
+    buckets: Mutex<HashMap<K, Bucket>>,
+}
+
+impl<K: Hash + Eq> Buckets<K> {
+    fn new(limit: Limit) -> Self {
+        Self {
+            limit,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Take a token for `client`, or return the seconds until one is
+    /// available.
+    fn take(&self, client: K, now: Instant) -> Result<(), u64> {
+        let rate = self.limit.refill_per_sec();
+        let capacity = f64::from(self.limit.burst);
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= PRUNE_THRESHOLD {
+            // A bucket that has refilled is the same as no bucket
+            buckets.retain(|_, b| {
+                b.tokens + now.duration_since(b.updated).as_secs_f64() * rate < capacity
+            });
+        }
+        if buckets.len() >= MAX_BUCKETS && !buckets.contains_key(&client) {
+            // Keep the most recently used half. A dropped client starts over
+            // with a full bucket, so this only ever errs towards allowing.
+            let mut updated: Vec<Instant> = buckets.values().map(|b| b.updated).collect();
+            let (_, median, _) = updated.select_nth_unstable(MAX_BUCKETS / 2);
+            let cutoff = *median;
+            buckets.retain(|_, b| b.updated > cutoff);
+        }
+        let bucket = buckets.entry(client).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(capacity);
+        bucket.updated = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(((1.0 - bucket.tokens) / rate).ceil().max(1.0) as u64)
+        }
+    }
+}
+
+/// Per-key and per-address buckets for every request to the server.
+#[derive(Default)]
+pub struct RateLimiter {
+    per_key: Option<Buckets<String>>,
+    per_ip: Option<Buckets<IpAddr>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            per_key: config.per_key.map(Buckets::new),
+            per_ip: config.per_ip.map(Buckets::new),
+        }
+    }
+
+    /// Count a request, or return the seconds the client should wait. Keys
+    /// are tracked by hash so the limiter never holds them in the clear.
+    fn check(&self, token: Option<&str>, ip: Option<IpAddr>, now: Instant) -> Result<(), u64> {
+        if let (Some(buckets), Some(ip)) = (&self.per_ip, ip) {
+            buckets.take(ip, now)?;
+        }
+        if let (Some(buckets), Some(token)) = (&self.per_key, token) {
+            buckets.take(sha256_hex(token), now)?;
+        }
+        Ok(())
+    }
+}
+
+/// Axum middleware answering `429 Too Many Requests`, with `Retry-After`,
+/// once a client exceeds its limit. Runs before authentication, so
+/// requests with a wrong key count too.
+///
+/// The address limited is the socket peer, so behind a reverse proxy every
+/// client shares the proxy's budget. Unix-socket clients are only limited
+/// per key.
+pub async fn rate_limit_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if EXEMPT_PATHS.contains(&request.uri().path()) {
+        return next.run(request).await;
+    }
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    let ip = request
+        .extensions()
+        .get::<ConnectInfo<PeerInfo>>()
+        .and_then(|info| info.0.remote_addr)
+        .map(|addr| addr.ip().to_canonical());
+    if let Err(retry_after) = state.rate_limiter.check(token, ip, Instant::now()) {
+        let mut resp = (
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(json!({ "error": "too many requests" })),
+        )
+            .into_response();
+        resp.headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+        return resp;
+    }
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use axum::body::Body;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::routes::build_router;
+    use crate::test_helpers::test_state;
+
+    fn limit(per_minute: u32, burst: u32) -> Limit {
+        Limit { per_minute, burst }
+    }
+
+    #[test]
+    fn config_from_values() {
+        assert_eq!(
+            RateLimitConfig::from_values(None, None, None).unwrap(),
+            RateLimitConfig::default()
+        );
+        let config = RateLimitConfig::from_values(Some("120"), Some("0"), None).unwrap();
+        assert_eq!(config.per_key, Some(limit(120, 120)));
+        assert_eq!(config.per_ip, None);
+        let config = RateLimitConfig::from_values(None, Some("600"), Some("20")).unwrap();
+        assert_eq!(config.per_ip, Some(limit(600, 20)));
+        assert!(RateLimitConfig::from_values(Some("lots"), None, None).is_err());
+        assert!(RateLimitConfig::from_values(Some("60"), None, Some("0")).is_err());
+    }
+
+    #[test]
+    fn bucket_allows_burst_then_refills() {
+        let buckets = Buckets::new(limit(60, 3));
+        let start = Instant::now();
+        for _ in 0..3 {
+            assert_eq!(buckets.take("a", start), Ok(()));
+        }
+        assert_eq!(buckets.take("a", start), Err(1));
+        // Other clients have their own bucket
+        assert_eq!(buckets.take("b", start), Ok(()));
+        // One token per second at 60 a minute
+        assert_eq!(buckets.take("a", start + Duration::from_secs(1)), Ok(()));
+        assert_eq!(buckets.take("a", start + Duration::from_secs(1)), Err(1));
+    }
+
+    #[test]
+    fn retry_after_reflects_slow_refill() {
+        let buckets = Buckets::new(limit(2, 1));
+        let start = Instant::now();
+        assert_eq!(buckets.take("a", start), Ok(()));
+        assert_eq!(buckets.take("a", start), Err(30));
+    }
+
+    #[test]
+    fn bucket_count_is_capped() {
+        // Slow enough that no bucket refills and gets pruned on its own
+        let buckets = Buckets::new(limit(1, 2));
+        let start = Instant::now();
+        for i in 0..MAX_BUCKETS + 10 {
+            let now = start + Duration::from_millis(i as u64);
+            assert_eq!(buckets.take(format!("random-{i}"), now), Ok(()));
+        }
+        let kept = buckets.buckets.lock().unwrap();
+        assert!(kept.len() <= MAX_BUCKETS);
+        // The newest clients keep their partly drained buckets
+        assert!(kept.contains_key(&format!("random-{}", MAX_BUCKETS + 9)));
+    }
+
+    #[tokio::test]
+    async fn limited_key_gets_429_with_retry_after() {
+        let mut state = Arc::try_unwrap(test_state().await)
+            .ok()
+            .expect("fresh state has one owner");
+        state.rate_limiter = RateLimiter::new(RateLimitConfig {
+            per_key: Some(limit(60, 2)),
+            per_ip: None,
+        });
+        let app = build_router(Arc::new(state));
+        let request = |key: &str| {
+            Request::get("/api/projects")
+                .header("authorization", format!("Bearer {key}"))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        for _ in 0..2 {
+            let resp = app.clone().oneshot(request("a")).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+        }
+        let resp = app.clone().oneshot(request("a")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(resp.headers()[header::RETRY_AFTER], "1");
+
+        let resp = app.clone().oneshot(request("b")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
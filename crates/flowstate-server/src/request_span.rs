@@ -0,0 +1,32 @@
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use flowstate_service::telemetry;
+use tracing::Instrument;
+
+/// Axum middleware wrapping each request in an `http.request` span. A
+/// `traceparent` header makes the span part of the caller's trace, which is
+/// how a runner's calls show up under the run they serve.
+pub async fn request_span_middleware(request: Request, next: Next) -> Response {
+    let method = request.method().clone();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+    let span = tracing::info_span!(
+        "http.request",
+        otel.name = %format!("{method} {route}"),
+        otel.kind = "server",
+        http.request.method = %method,
+        http.route = %route,
+        http.response.status_code = tracing::field::Empty,
+    );
+    telemetry::set_parent_from_headers(&span, request.headers());
+
+    let response = next.run(request).instrument(span.clone()).await;
+    span.record("http.response.status_code", response.status().as_u16());
+    response
+}
@@ -0,0 +1,177 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use flowstate_db::Database;
+use flowstate_store::ObjectStore;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+use crate::shutdown;
+
+/// How long finished runs are kept, read from `FLOWSTATE_RUN_RETENTION_DAYS`.
+/// Unset or `0` keeps runs forever.
+pub fn retention_from_env() -> Option<chrono::Duration> {
+    std::env::var("FLOWSTATE_RUN_RETENTION_DAYS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .filter(|days| *days > 0)
+        .map(chrono::Duration::days)
+}
+
+/// Background task that deletes finished runs older than `retention`,
+/// together with their stored prompts and output. Runs beside the watchdog
+/// and scans every `scan_interval_secs`.
+pub async fn run_retention(
+    db: Arc<dyn Database>,
+    store: Arc<dyn ObjectStore>,
+    retention: chrono::Duration,
+    scan_interval_secs: u64,
+    shutdown: CancellationToken,
+) {
+    let mut ticker = tokio::time::interval(Duration::from_secs(scan_interval_secs));
+    while shutdown::next_tick(&mut ticker, &shutdown).await {
+        if let Err(e) = prune_runs(&*db, &*store, retention).await {
+            error!("retention error: {e}");
+        }
+    }
+}
+
+/// Delete runs that ended more than `retention` ago and their objects.
+/// Returns how many runs were deleted.
+async fn prune_runs(
+    db: &dyn Database,
+    store: &dyn ObjectStore,
+    retention: chrono::Duration,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let deleted = db.delete_runs_older_than(Utc::now() - retention).await?;
+    for run_id in &deleted {
+        // A missing or undeletable object only leaves garbage behind; keep
+        // going so one bad key doesn't hold up the rest.
+        let keys = match store
+            .list(&flowstate_store::claude_run_prefix(run_id))
+            .await
+        {
+            Ok(keys) => keys,
+            Err(e) => {
+                warn!("retention: listing objects of run {run_id}: {e}");
+                continue;
+            }
+        };
+        for key in keys {
+            if let Err(e) = store.delete(&key).await {
+                warn!("retention: deleting {key}: {e}");
+            }
+        }
+    }
+    if !deleted.is_empty() {
+        info!(
+            "retention: deleted {} runs older than {} days",
+            deleted.len(),
+            retention.num_days()
+        );
+    }
+    Ok(deleted.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use flowstate_core::claude_run::{ClaudeAction, ClaudeRunStatus, CreateClaudeRun};
+    use flowstate_core::project::CreateProject;
+    use flowstate_core::task::{CreateTask, Priority, Status, TaskType};
+    use flowstate_store::StoreConfig;
+
+    #[tokio::test]
+    async fn prune_deletes_old_runs_and_their_objects() {
+        let db = Arc::new(flowstate_db::SqliteDatabase::open_in_memory().unwrap());
+        let tmp = tempfile::tempdir().unwrap();
+        let store = flowstate_store::create_store(&StoreConfig {
+            endpoint_url: None,
+            region: None,
+            bucket: None,
+            access_key_id: None,
+            secret_access_key: None,
+            local_data_dir: Some(tmp.path().to_string_lossy().to_string()),
+            gcs: None,
+            azure: None,
+            durability: flowstate_store::Durability::Atomic,
+            multipart: flowstate_store::MultipartConfig::default(),
+        })
+        .unwrap();
+
+        let project = db
+            .create_project(&CreateProject {
+                name: "Retention".into(),
+                slug: "retention".into(),
+                description: String::new(),
+                repo_url: String::new(),
+            })
+            .await
+            .unwrap();
+        let task = db
+            .create_task(&CreateTask {
+                project_id: project.id.clone(),
+                title: "Retained".into(),
+                description: String::new(),
+                status: Status::Todo,
+                priority: Priority::Medium,
+                task_type: TaskType::Feature,
+                parent_id: None,
+                reviewer: String::new(),
+                research_capability: None,
+                design_capability: None,
+                plan_capability: None,
+                build_capability: None,
+                verify_capability: None,
+                due_at: None,
+            })
+            .await
+            .unwrap();
+
+        let mut runs = Vec::new();
+        for _ in 0..2 {
+            let run = db
+                .create_claude_run(&CreateClaudeRun {
+                    task_id: task.id.clone(),
+                    action: ClaudeAction::Research,
+                    required_capability: None,
+                    priority: 0,
+                    feedback: None,
+                    idempotency_key: None,
+                    run_window: None,
+                    trace_context: None,
+                })
+                .await
+                .unwrap();
+            db.update_claude_run_status(&run.id, ClaudeRunStatus::Completed, None, Some(0))
+                .await
+                .unwrap();
+            store
+                .put(
+                    &flowstate_store::claude_run_output_key(&run.id),
+                    Bytes::from("output"),
+                )
+                .await
+                .unwrap();
+            runs.push(run);
+        }
+
+        // Nothing is old enough yet
+        let week = chrono::Duration::days(7);
+        assert_eq!(prune_runs(&*db, &*store, week).await.unwrap(), 0);
+
+        // A negative window puts the cutoff in the future
+        let pruned = prune_runs(&*db, &*store, chrono::Duration::hours(-1))
+            .await
+            .unwrap();
+        assert_eq!(pruned, 1);
+        let old_output = flowstate_store::claude_run_output_key(&runs[0].id);
+        let kept_output = flowstate_store::claude_run_output_key(&runs[1].id);
+        assert!(!store.exists(&old_output).await.unwrap());
+        assert!(store.exists(&kept_output).await.unwrap());
+        assert!(db.get_claude_run(&runs[0].id).await.is_err());
+        assert!(db.get_claude_run(&runs[1].id).await.is_ok());
+    }
+}
@@ -0,0 +1,381 @@
+use std::sync::atomic::Ordering;
+
+use axum::{
+    extract::{Request, State},
+    http::{header, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use flowstate_core::feature_flag::{self, FeatureFlag};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::{info, warn};
+
+use super::openapi::ErrorBody;
+use super::AppState;
+
+/// Seconds clients are told to wait before retrying during maintenance.
+const MAINTENANCE_RETRY_AFTER_SECS: u64 = 60;
+
+/// Writes that stay allowed in maintenance mode: runner heartbeats, and
+/// claims (which the handler answers with "no work").
+const MAINTENANCE_EXEMPT_PATHS: &[&str] = &["/api/runners/register", "/api/claude-runs/claim"];
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/admin/flags", get(list_flags).patch(set_flag))
+        .route(
+            "/admin/maintenance",
+            get(get_maintenance).put(set_maintenance),
+        )
+}
+
+/// Whether the server should start in maintenance mode, read from
+/// `FLOWSTATE_MAINTENANCE`.
+pub(crate) fn maintenance_from_env() -> bool {
+    std::env::var("FLOWSTATE_MAINTENANCE")
+        .map(|v| matches!(v.as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+/// Axum middleware that rejects mutating requests with 503 while the server
+/// is in maintenance mode. Reads, `/admin` routes and runner heartbeats pass
+/// through so operators can inspect state and turn maintenance back off.
+pub async fn maintenance_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let read_only = matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    );
+    let path = request.uri().path();
+    if !state.maintenance.load(Ordering::Relaxed)
+        || read_only
+        || path.starts_with("/admin/")
+        || MAINTENANCE_EXEMPT_PATHS.contains(&path)
+    {
+        return next.run(request).await;
+    }
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [(
+            header::RETRY_AFTER,
+            MAINTENANCE_RETRY_AFTER_SECS.to_string(),
+        )],
+        Json(json!({ "error": "server is in maintenance mode" })),
+    )
+        .into_response()
+}
+
+#[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
+struct MaintenanceState {
+    enabled: bool,
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/maintenance",
+    tag = "admin",
+    responses((status = 200, body = MaintenanceState))
+)]
+async fn get_maintenance(State(state): State<AppState>) -> Json<MaintenanceState> {
+    Json(MaintenanceState {
+        enabled: state.maintenance.load(Ordering::Relaxed),
+    })
+}
+
+/// Turn maintenance mode on or off. While it is on, writes outside
+/// `/admin` are refused with 503.
+#[utoipa::path(
+    put,
+    path = "/admin/maintenance",
+    tag = "admin",
+    request_body = MaintenanceState,
+    responses((status = 200, body = MaintenanceState))
+)]
+async fn set_maintenance(
+    State(state): State<AppState>,
+    Json(input): Json<MaintenanceState>,
+) -> Json<MaintenanceState> {
+    let was = state.maintenance.swap(input.enabled, Ordering::Relaxed);
+    if was != input.enabled {
+        info!(
+            "maintenance mode {}",
+            if input.enabled { "enabled" } else { "disabled" }
+        );
+    }
+    Json(input)
+}
+
+/// Whether `key` is enabled for `project_id`, falling back to the global
+/// override and then the default. A database error is logged and treated
+/// as the default so a flaky read never changes behavior.
+pub(crate) async fn flag_enabled(state: &AppState, key: &str, project_id: Option<&str>) -> bool {
+    match state.db.list_feature_flags().await {
+        Ok(flags) => feature_flag::resolve(&flags, key, project_id),
+        Err(e) => {
+            warn!("failed to read feature flags: {e}");
+            true
+        }
+    }
+}
+
+#[derive(Serialize, utoipa::ToSchema)]
+struct FlagsResponse {
+    /// Every known flag resolved at global scope.
+    known: Vec<KnownFlag>,
+    /// Stored overrides, global and per-project.
+    overrides: Vec<FeatureFlag>,
+}
+
+#[derive(Serialize, utoipa::ToSchema)]
+struct KnownFlag {
+    key: &'static str,
+    enabled: bool,
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/flags",
+    tag = "admin",
+    responses((status = 200, body = FlagsResponse))
+)]
+async fn list_flags(
+    State(state): State<AppState>,
+) -> Result<Json<FlagsResponse>, (StatusCode, Json<Value>)> {
+    let overrides = state.db.list_feature_flags().await.map_err(to_error)?;
+    let known = feature_flag::KNOWN_FLAGS
+        .iter()
+        .map(|key| KnownFlag {
+            key,
+            enabled: feature_flag::resolve(&overrides, key, None),
+        })
+        .collect();
+    Ok(Json(FlagsResponse { known, overrides }))
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+struct SetFlagInput {
+    key: String,
+    #[serde(default)]
+    project_id: Option<String>,
+    /// `null` removes the override so the flag falls back to the next scope.
+    enabled: Option<bool>,
+}
+
+/// Set a flag override, or remove it with `"enabled": null`.
+#[utoipa::path(
+    patch,
+    path = "/admin/flags",
+    tag = "admin",
+    request_body = SetFlagInput,
+    responses(
+        (status = 200, body = FeatureFlag),
+        (status = 400, description = "Unknown flag", body = ErrorBody),
+        (status = 404, description = "Unknown project", body = ErrorBody)
+    )
+)]
+async fn set_flag(
+    State(state): State<AppState>,
+    Json(input): Json<SetFlagInput>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    if !feature_flag::is_known_flag(&input.key) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("unknown flag: {}", input.key)})),
+        ));
+    }
+    if let Some(ref project_id) = input.project_id {
+        state.db.get_project(project_id).await.map_err(to_error)?;
+    }
+
+    match input.enabled {
+        Some(enabled) => {
+            let flag = state
+                .db
+                .set_feature_flag(&input.key, input.project_id.as_deref(), enabled)
+                .await
+                .map_err(to_error)?;
+            Ok(Json(json!(flag)))
+        }
+        None => {
+            state
+                .db
+                .delete_feature_flag(&input.key, input.project_id.as_deref())
+                .await
+                .map_err(to_error)?;
+            Ok(Json(
+                json!({"key": input.key, "project_id": input.project_id}),
+            ))
+        }
+    }
+}
+
+fn to_error(e: flowstate_db::DbError) -> (StatusCode, Json<Value>) {
+    let status = match &e {
+        flowstate_db::DbError::NotFound(_) => StatusCode::NOT_FOUND,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(json!({ "error": e.to_string() })))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::test_router;
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    async fn patch(app: &axum::Router, body: Value) -> (StatusCode, Value) {
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::PATCH)
+                    .uri("/admin/flags")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = resp.status();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    async fn get_flags(app: &axum::Router) -> Value {
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/admin/flags")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    fn known(flags: &Value, key: &str) -> bool {
+        flags["known"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|f| f["key"] == key)
+            .unwrap()["enabled"]
+            .as_bool()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn set_and_clear_global_flag() {
+        let app = test_router().await;
+        assert!(known(&get_flags(&app).await, "salvage"));
+
+        let (status, flag) = patch(&app, json!({"key": "salvage", "enabled": false})).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(flag["enabled"], false);
+        assert!(!known(&get_flags(&app).await, "salvage"));
+
+        let (status, _) = patch(&app, json!({"key": "salvage", "enabled": null})).await;
+        assert_eq!(status, StatusCode::OK);
+        let flags = get_flags(&app).await;
+        assert!(known(&flags, "salvage"));
+        assert!(flags["overrides"].as_array().unwrap().is_empty());
+    }
+
+    async fn send(app: &axum::Router, method: Method, uri: &str, body: Value) -> StatusCode {
+        app.clone()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn maintenance_blocks_writes_but_not_reads() {
+        let app = test_router().await;
+        let project = json!({"name": "P", "slug": "p"});
+        assert_eq!(
+            send(
+                &app,
+                Method::PUT,
+                "/admin/maintenance",
+                json!({"enabled": true})
+            )
+            .await,
+            StatusCode::OK
+        );
+
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/api/projects")
+                    .header("content-type", "application/json")
+                    .body(Body::from(project.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(resp.headers()["retry-after"], "60");
+
+        assert_eq!(
+            send(&app, Method::GET, "/api/projects", Value::Null).await,
+            StatusCode::OK
+        );
+        // Claims report "no work" instead of failing
+        assert_eq!(
+            send(&app, Method::POST, "/api/claude-runs/claim", Value::Null).await,
+            StatusCode::NO_CONTENT
+        );
+
+        send(
+            &app,
+            Method::PUT,
+            "/admin/maintenance",
+            json!({"enabled": false}),
+        )
+        .await;
+        assert_eq!(
+            send(&app, Method::POST, "/api/projects", project).await,
+            StatusCode::CREATED
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_flag_and_project() {
+        let app = test_router().await;
+        let (status, _) = patch(&app, json!({"key": "warp_drive", "enabled": true})).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = patch(
+            &app,
+            json!({"key": "salvage", "project_id": "nope", "enabled": true}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,295 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, put},
+    Json, Router,
+};
+use flowstate_core::approval_rule::{self, ApprovalPhase, ApprovalPolicy, ApprovalRule};
+use flowstate_core::task::{ApprovalStatus, Task, UpdateTask};
+use flowstate_service::TaskService;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::openapi::ErrorBody;
+use super::AppState;
+
+type ApiError = (StatusCode, Json<Value>);
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/projects/{id}/approval-rules",
+            get(list_approval_rules),
+        )
+        .route(
+            "/api/projects/{id}/approval-rules/{phase}",
+            put(set_approval_rule).delete(delete_approval_rule),
+        )
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+struct SetApprovalRule {
+    policy: ApprovalPolicy,
+}
+
+fn parse_phase(phase: &str) -> Result<ApprovalPhase, ApiError> {
+    ApprovalPhase::parse_str(phase).ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": format!("invalid phase: {phase} (expected research, spec, plan or verify)")
+            })),
+        )
+    })
+}
+
+/// GET /api/projects/{id}/approval-rules — the project's rule for each
+/// phase that has one.
+#[utoipa::path(
+    get,
+    path = "/api/projects/{id}/approval-rules",
+    tag = "projects",
+    responses(
+        (status = 200, body = [ApprovalRule]),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn list_approval_rules(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let project = state.service.get_project(&id).await.map_err(to_error)?;
+    state
+        .db
+        .list_approval_rules(&project.id)
+        .await
+        .map(|rules| Json(json!(rules)))
+        .map_err(|e| to_error(e.into()))
+}
+
+/// PUT /api/projects/{id}/approval-rules/{phase} — set how the project
+/// approves `phase`, replacing any earlier rule for it.
+#[utoipa::path(
+    put,
+    path = "/api/projects/{id}/approval-rules/{phase}",
+    tag = "projects",
+    request_body = SetApprovalRule,
+    responses(
+        (status = 200, body = ApprovalRule),
+        (status = 400, body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn set_approval_rule(
+    State(state): State<AppState>,
+    Path((id, phase)): Path<(String, String)>,
+    Json(input): Json<SetApprovalRule>,
+) -> Result<Json<Value>, ApiError> {
+    let phase = parse_phase(&phase)?;
+    let project = state.service.get_project(&id).await.map_err(to_error)?;
+    state
+        .db
+        .set_approval_rule(&project.id, phase, input.policy)
+        .await
+        .map(|rule| Json(json!(rule)))
+        .map_err(|e| to_error(e.into()))
+}
+
+/// DELETE /api/projects/{id}/approval-rules/{phase} — go back to approving
+/// `phase` by hand.
+#[utoipa::path(
+    delete,
+    path = "/api/projects/{id}/approval-rules/{phase}",
+    tag = "projects",
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 400, body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn delete_approval_rule(
+    State(state): State<AppState>,
+    Path((id, phase)): Path<(String, String)>,
+) -> Result<StatusCode, ApiError> {
+    let phase = parse_phase(&phase)?;
+    state
+        .db
+        .delete_approval_rule(&id, phase)
+        .await
+        .map(|()| StatusCode::NO_CONTENT)
+        .map_err(|e| to_error(e.into()))
+}
+
+/// Refuse an update that approves a phase the project reserves for the
+/// task's reviewer, unless `update.actor` is that reviewer.
+pub(crate) async fn check_reviewer(
+    state: &AppState,
+    task: &Task,
+    update: &UpdateTask,
+) -> Result<(), ApiError> {
+    let approving: Vec<_> = ApprovalPhase::ALL
+        .iter()
+        .filter(|phase| {
+            phase.update_status(update) == Some(ApprovalStatus::Approved)
+                && phase.status(task) != ApprovalStatus::Approved
+        })
+        .collect();
+    if approving.is_empty() {
+        return Ok(());
+    }
+    let rules = state
+        .db
+        .list_approval_rules(&task.project_id)
+        .await
+        .map_err(|e| to_error(e.into()))?;
+    for phase in approving {
+        if approval_rule::policy_for(&rules, *phase) != Some(ApprovalPolicy::Reviewer) {
+            continue;
+        }
+        let caller = update.actor.as_deref().unwrap_or_default();
+        if !approval_rule::is_reviewer(&task.reviewer, caller) {
+            return Err((
+                StatusCode::FORBIDDEN,
+                Json(json!({
+                    "error": format!("only the task's reviewer may approve its {phase}")
+                })),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn to_error(e: flowstate_service::ServiceError) -> ApiError {
+    let (status, msg) = match &e {
+        flowstate_service::ServiceError::NotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
+        flowstate_service::ServiceError::InvalidInput(_) => {
+            (StatusCode::BAD_REQUEST, e.to_string())
+        }
+        flowstate_service::ServiceError::Internal(_) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        }
+    };
+    (status, Json(json!({ "error": msg })))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::Request;
+    use flowstate_core::project::CreateProject;
+    use flowstate_core::task::{CreateTask, Priority, Status, TaskType};
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::routes::build_router;
+    use crate::test_helpers::test_state;
+
+    fn send(method: &str, uri: &str, body: Value) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    async fn body_json(resp: axum::response::Response) -> Value {
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn rules_round_trip_and_reviewer_rule_guards_approval() {
+        let state = test_state().await;
+        let project = state
+            .db
+            .create_project(&CreateProject {
+                name: "Rules".into(),
+                slug: "rules".into(),
+                description: String::new(),
+                repo_url: String::new(),
+            })
+            .await
+            .unwrap();
+        let app = build_router(state.clone());
+        let rules = format!("/api/projects/{}/approval-rules", project.id);
+
+        let resp = app
+            .clone()
+            .oneshot(send(
+                "PUT",
+                &format!("{rules}/design"),
+                json!({ "policy": "reviewer" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(body_json(resp).await["phase"], "spec");
+        let resp = app
+            .clone()
+            .oneshot(send(
+                "PUT",
+                &format!("{rules}/build"),
+                json!({ "policy": "auto" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let resp = app
+            .clone()
+            .oneshot(send("GET", &rules, Value::Null))
+            .await
+            .unwrap();
+        let listed = body_json(resp).await;
+        assert_eq!(listed.as_array().unwrap().len(), 1);
+        assert_eq!(listed[0]["policy"], "reviewer");
+
+        // Without a caller identity nobody can prove to be the reviewer
+        let task = state
+            .db
+            .create_task(&CreateTask {
+                project_id: project.id.clone(),
+                title: "Guarded".into(),
+                description: String::new(),
+                status: Status::Design,
+                priority: Priority::Medium,
+                task_type: TaskType::Feature,
+                parent_id: None,
+                reviewer: "alice".into(),
+                due_at: None,
+                research_capability: None,
+                design_capability: None,
+                plan_capability: None,
+                build_capability: None,
+                verify_capability: None,
+            })
+            .await
+            .unwrap();
+        let approve = || {
+            send(
+                "PUT",
+                &format!("/api/tasks/{}", task.id),
+                json!({ "spec_status": "approved" }),
+            )
+        };
+        let resp = app.clone().oneshot(approve()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let resp = app
+            .clone()
+            .oneshot(send("DELETE", &format!("{rules}/spec"), Value::Null))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        let resp = app
+            .clone()
+            .oneshot(send("DELETE", &format!("{rules}/spec"), Value::Null))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+        let resp = app.oneshot(approve()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
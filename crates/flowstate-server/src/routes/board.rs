@@ -0,0 +1,170 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use flowstate_core::board::ProjectLane;
+use flowstate_service::TaskService;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use utoipa::IntoParams;
+
+use super::AppState;
+use crate::auth::ProjectScope;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/api/board", get(rollup_board))
+}
+
+#[derive(Deserialize, IntoParams)]
+struct BoardQuery {
+    /// Comma-separated project ids; every project when absent.
+    project_ids: Option<String>,
+}
+
+/// Tasks of several projects at once, one swimlane per project.
+#[utoipa::path(
+    get,
+    path = "/api/board",
+    tag = "tasks",
+    params(BoardQuery),
+    responses((status = 200, body = [ProjectLane]))
+)]
+async fn rollup_board(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+    Query(q): Query<BoardQuery>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let mut project_ids: Vec<String> = q
+        .project_ids
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .filter(|id| !id.is_empty())
+        .map(str::to_string)
+        .collect();
+    if let Some(allowed) = scope.projects() {
+        if project_ids.is_empty() {
+            project_ids = allowed.to_vec();
+        }
+        if project_ids.is_empty() {
+            return Ok(Json(json!([])));
+        }
+    }
+    state
+        .service
+        .rollup_board(&project_ids)
+        .await
+        .map(|lanes| Json(json!(lanes)))
+        .map_err(to_error)
+}
+
+fn to_error(e: flowstate_service::ServiceError) -> (StatusCode, Json<Value>) {
+    let (status, msg) = match &e {
+        flowstate_service::ServiceError::NotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
+        flowstate_service::ServiceError::InvalidInput(_) => {
+            (StatusCode::BAD_REQUEST, e.to_string())
+        }
+        flowstate_service::ServiceError::Internal(_) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        }
+    };
+    (status, Json(json!({ "error": msg })))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::test_router;
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    async fn send(
+        app: &axum::Router,
+        method: Method,
+        uri: &str,
+        body: Value,
+    ) -> (StatusCode, Value) {
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = resp.status();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    #[tokio::test]
+    async fn board_has_a_lane_per_project() {
+        let app = test_router().await;
+        let mut ids = Vec::new();
+        for slug in ["api", "web", "infra"] {
+            let (_, project) = send(
+                &app,
+                Method::POST,
+                "/api/projects",
+                json!({ "name": slug, "slug": slug }),
+            )
+            .await;
+            let id = project["id"].as_str().unwrap().to_string();
+            let (_, task) = send(
+                &app,
+                Method::POST,
+                "/api/tasks",
+                json!({ "project_id": id, "title": format!("{slug} task"), "status": "todo", "priority": "medium" }),
+            )
+            .await;
+            // subtasks stay on their parent's own board
+            send(
+                &app,
+                Method::POST,
+                "/api/tasks",
+                json!({ "project_id": id, "title": "child", "status": "todo", "priority": "medium", "parent_id": task["id"] }),
+            )
+            .await;
+            ids.push(id);
+        }
+
+        let (status, lanes) = send(
+            &app,
+            Method::GET,
+            &format!("/api/board?project_ids={},{}", ids[2], ids[0]),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let lanes = lanes.as_array().unwrap();
+        assert_eq!(lanes.len(), 2);
+        assert_eq!(lanes[0]["project"]["slug"], "infra");
+        assert_eq!(lanes[1]["project"]["slug"], "api");
+        assert_eq!(lanes[1]["tasks"].as_array().unwrap().len(), 1);
+        assert_eq!(lanes[1]["tasks"][0]["title"], "api task");
+
+        let (_, lanes) = send(&app, Method::GET, "/api/board", Value::Null).await;
+        assert_eq!(lanes.as_array().unwrap().len(), 3);
+
+        let (status, _) = send(
+            &app,
+            Method::GET,
+            "/api/board?project_ids=missing",
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -1,17 +1,29 @@
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::{HeaderMap, StatusCode},
-    routing::{get, post, put},
-    Json, Router,
+    routing::{get, patch, post, put},
+    Extension, Json, Router,
 };
-use chrono::Utc;
-use flowstate_core::claude_run::{ClaudeAction, ClaudeRunStatus, CreateClaudeRun};
-use flowstate_core::runner::RunnerCapability;
-use flowstate_service::TaskService;
+use chrono::{DateTime, Utc};
+use flowstate_core::claude_run::{ClaudeAction, ClaudeRun, ClaudeRunStatus, CreateClaudeRun};
+use flowstate_core::feature_flag::{COST_ROUTING, SALVAGE};
+use flowstate_core::run_metrics::{RecordRunMetrics, RunMetrics};
+use flowstate_core::runner::{RunnerBenchmark, RunnerCapability};
+use flowstate_core::task_pr::PrState;
+use flowstate_core::RunWindow;
+use flowstate_service::{telemetry, RegisterResponse, TaskService};
 use serde::Deserialize;
 use serde_json::{json, Value};
 
-use super::{AppState, RunnerInfo};
+use super::events::ServerEvent;
+use super::openapi::ErrorBody;
+use super::{admin, run_logs, AppState, RunnerInfo};
+use crate::auth::{runner_cert_mismatch, ProjectScope, RunnerCert};
+use crate::{budget, notifier, orchestrator, webhooks};
 
 pub fn routes() -> Router<AppState> {
     Router::new()
@@ -19,8 +31,20 @@ pub fn routes() -> Router<AppState> {
             "/api/tasks/{task_id}/claude-runs",
             get(list_claude_runs).post(trigger_claude_run),
         )
-        .route("/api/claude-runs/claim", post(claim_claude_run))
         .route("/api/claude-runs/{id}", get(get_claude_run))
+        .route("/api/claude-runs/{id}/output", get(get_claude_run_output))
+        .route("/api/claude-runs/{id}/requeue", post(requeue_claude_run))
+        .route(
+            "/api/claude-runs/{id}/priority",
+            patch(set_claude_run_priority),
+        )
+}
+
+/// Routes only runners call. These get the client-certificate check when
+/// runner mTLS is enabled.
+pub fn runner_routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/claude-runs/claim", post(claim_claude_run))
         .route(
             "/api/claude-runs/{id}/status",
             put(update_claude_run_status),
@@ -29,15 +53,75 @@ pub fn routes() -> Router<AppState> {
             "/api/claude-runs/{id}/progress",
             put(update_claude_run_progress),
         )
-        .route("/api/claude-runs/{id}/output", get(get_claude_run_output))
+        .route(
+            "/api/claude-runs/{id}/metrics",
+            post(record_claude_run_metrics),
+        )
         .route("/api/runners/register", post(register_runner))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 struct TriggerInput {
     action: String,
     #[serde(default)]
     required_capability: Option<String>,
+    /// Overrides the scheduling weight derived from the task's priority.
+    #[serde(default)]
+    priority: Option<i32>,
+    /// Run a cost-routed action on a heavy runner instead of a light one.
+    #[serde(default)]
+    escalate: bool,
+    /// Client-chosen key that makes the trigger safe to retry: a second
+    /// trigger with the same key returns the run the first one queued.
+    #[serde(default)]
+    idempotency_key: Option<String>,
+    /// Daily window the run may start in, in place of the project's.
+    #[serde(default)]
+    run_window: Option<RunWindow>,
+}
+
+/// Actions that `cost_routing` keeps on light runners: their output is
+/// reviewed or condensed later, so a cheap model is usually good enough.
+fn is_cost_routed(action: ClaudeAction) -> bool {
+    matches!(
+        action,
+        ClaudeAction::Research
+            | ClaudeAction::ResearchDistill
+            | ClaudeAction::DesignDistill
+            | ClaudeAction::PlanDistill
+            | ClaudeAction::VerifyDistill
+    )
+}
+
+/// Pick the capability tier for a new run. An explicit `requested` tier
+/// always wins. With cost routing on, research and distill runs go to light
+/// runners and escalate to heavy on request or when the task's previous run
+/// of that action failed; research still honors the task's own override.
+/// Otherwise the task's per-phase override, then the action default, applies.
+fn route_capability(
+    action: ClaudeAction,
+    task: &flowstate_core::task::Task,
+    requested: Option<RunnerCapability>,
+    cost_routing: bool,
+    escalate: bool,
+    previous_failed: bool,
+) -> RunnerCapability {
+    if let Some(cap) = requested {
+        return cap;
+    }
+    if cost_routing && is_cost_routed(action) {
+        if escalate || previous_failed {
+            return RunnerCapability::Heavy;
+        }
+        if action == ClaudeAction::Research {
+            if let Some(cap) = task.research_capability {
+                return cap;
+            }
+        }
+        return RunnerCapability::Light;
+    }
+    task.capability_for_action(action)
+        .unwrap_or_else(|| RunnerCapability::default_for_action(action))
 }
 
 /// Validate that prerequisites are met for triggering a Claude run
@@ -45,7 +129,8 @@ struct TriggerInput {
 ///
 /// `has_completed_build`: whether any ClaudeRun with action=Build
 ///     and status=Completed exists for this task
-/// `has_prs`: whether any TaskPrs are linked to this task
+/// `has_prs`: whether any TaskPrs are linked to this task; for Revise,
+///     whether any of them is still open
 ///
 /// Returns Ok(()) if the action can proceed, or Err with a human-readable message.
 pub(crate) fn validate_action_prerequisites(
@@ -97,6 +182,19 @@ pub(crate) fn validate_action_prerequisites(
         return Err("cannot distill plan: plan artifact must exist first".to_string());
     }
 
+    // Spikes end at an approved plan
+    if matches!(
+        action,
+        ClaudeAction::Build | ClaudeAction::Verify | ClaudeAction::Revise
+    ) && !task.task_type.is_buildable()
+    {
+        return Err(format!(
+            "cannot {}: {} tasks are not built",
+            action.as_str(),
+            task.task_type.as_str()
+        ));
+    }
+
     // Both spec and plan must be approved before building
     if action == ClaudeAction::Build {
         if task.spec_status != flowstate_core::task::ApprovalStatus::Approved {
@@ -120,6 +218,11 @@ pub(crate) fn validate_action_prerequisites(
         );
     }
 
+    // Revise: pushes to the branch of an open PR
+    if action == ClaudeAction::Revise && !has_prs {
+        return Err("cannot revise: the task has no open pull request".to_string());
+    }
+
     // VerifyDistill: verify artifact must exist
     if action == ClaudeAction::VerifyDistill
         && task.verify_status == flowstate_core::task::ApprovalStatus::None
@@ -132,6 +235,19 @@ pub(crate) fn validate_action_prerequisites(
     Ok(())
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/tasks/{task_id}/claude-runs",
+    tag = "runs",
+    request_body = TriggerInput,
+    responses(
+        (status = 201, body = ClaudeRun),
+        (status = 200, description = "A run with the same `idempotency_key` already exists", body = ClaudeRun),
+        (status = 400, description = "Unknown action, or the task is not ready for it", body = ErrorBody),
+        (status = 402, description = "A Build or Revise for a project that has spent its monthly budget", body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
 async fn trigger_claude_run(
     State(state): State<AppState>,
     Path(task_id): Path<String>,
@@ -139,67 +255,199 @@ async fn trigger_claude_run(
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
     let action = ClaudeAction::parse_str(&input.action).ok_or_else(|| {
         to_error(flowstate_service::ServiceError::InvalidInput(format!(
-            "invalid action: {} (expected research, design, plan, build, verify, research_distill, design_distill, plan_distill, or verify_distill)",
+            "invalid action: {} (expected research, design, plan, build, verify, research_distill, design_distill, plan_distill, verify_distill, or revise)",
             input.action
         )))
     })?;
-
-    let task = state.service.get_task(&task_id).await.map_err(to_error)?;
-
-    // For Verify, look up build/PR status
-    let (has_completed_build, has_prs) = if action == ClaudeAction::Verify {
+    if let Some(key) = &input.idempotency_key {
         let runs = state
             .service
             .list_claude_runs(&task_id)
             .await
             .map_err(to_error)?;
-        let prs = state
+        if let Some(run) = runs
+            .into_iter()
+            .find(|r| r.idempotency_key.as_ref() == Some(key))
+        {
+            return Ok((StatusCode::OK, Json(json!(run))));
+        }
+    }
+    let options = QueueOptions {
+        required_capability: input
+            .required_capability
+            .and_then(|c| RunnerCapability::parse_str(&c)),
+        priority: input.priority,
+        escalate: input.escalate,
+        feedback: None,
+        idempotency_key: input.idempotency_key,
+        run_window: input.run_window,
+    };
+    let run = queue_run(&state, &task_id, action, options).await?;
+    Ok((StatusCode::CREATED, Json(json!(run))))
+}
+
+/// How to queue a run, beyond its task and action.
+#[derive(Debug, Default)]
+pub(crate) struct QueueOptions {
+    pub required_capability: Option<RunnerCapability>,
+    pub priority: Option<i32>,
+    pub escalate: bool,
+    pub feedback: Option<String>,
+    pub idempotency_key: Option<String>,
+    pub run_window: Option<RunWindow>,
+}
+
+/// Check `action`'s prerequisites against the task and queue the run,
+/// picking its runner capability and priority.
+pub(crate) async fn queue_run(
+    state: &AppState,
+    task_id: &str,
+    action: ClaudeAction,
+    options: QueueOptions,
+) -> Result<ClaudeRun, (StatusCode, Json<Value>)> {
+    let task = state.service.get_task(task_id).await.map_err(to_error)?;
+
+    let cost_routing = is_cost_routed(action)
+        && admin::flag_enabled(state, COST_ROUTING, Some(&task.project_id)).await;
+    let runs = if action == ClaudeAction::Verify || cost_routing {
+        state
             .service
-            .list_task_prs(&task_id)
+            .list_claude_runs(task_id)
             .await
-            .map_err(to_error)?;
-        (
-            runs.iter()
-                .any(|r| r.action == ClaudeAction::Build && r.status == ClaudeRunStatus::Completed),
-            !prs.is_empty(),
-        )
+            .map_err(to_error)?
     } else {
-        (false, false)
+        Vec::new()
+    };
+
+    // For Verify, look up build/PR status; for Revise, the open PRs
+    let (has_completed_build, has_prs) = match action {
+        ClaudeAction::Verify => {
+            let prs = state
+                .service
+                .list_task_prs(task_id)
+                .await
+                .map_err(to_error)?;
+            (
+                runs.iter().any(|r| {
+                    r.action == ClaudeAction::Build && r.status == ClaudeRunStatus::Completed
+                }),
+                !prs.is_empty(),
+            )
+        }
+        ClaudeAction::Revise => {
+            let prs = state
+                .service
+                .list_task_prs(task_id)
+                .await
+                .map_err(to_error)?;
+            (false, prs.iter().any(|pr| pr.state == PrState::Open))
+        }
+        _ => (false, false),
     };
 
     validate_action_prerequisites(action, &task, has_completed_build, has_prs)
         .map_err(|msg| to_error(flowstate_service::ServiceError::InvalidInput(msg)))?;
 
-    let cap = input
-        .required_capability
-        .and_then(|c| RunnerCapability::parse_str(&c))
-        .or_else(|| task.capability_for_action(action))
-        .unwrap_or_else(|| RunnerCapability::default_for_action(action));
+    if matches!(action, ClaudeAction::Build | ClaudeAction::Revise) {
+        let project = state
+            .service
+            .get_project(&task.project_id)
+            .await
+            .map_err(to_error)?;
+        let budget = budget::status(&*state.db, &project, Utc::now())
+            .await
+            .map_err(|e| to_error(e.into()))?;
+        if budget.builds_blocked {
+            return Err((
+                StatusCode::PAYMENT_REQUIRED,
+                Json(json!({ "error": budget.blocked_reason() })),
+            ));
+        }
+    }
+
+    let previous_failed = runs
+        .iter()
+        .filter(|r| r.action == action)
+        .max_by_key(|r| r.started_at)
+        .is_some_and(|r| {
+            matches!(
+                r.status,
+                ClaudeRunStatus::Failed | ClaudeRunStatus::TimedOut
+            )
+        });
+    let cap = route_capability(
+        action,
+        &task,
+        options.required_capability,
+        cost_routing,
+        options.escalate,
+        previous_failed,
+    );
     let required_capability = Some(cap.as_str().to_string());
     let create = CreateClaudeRun {
-        task_id: task_id.clone(),
+        task_id: task_id.to_string(),
         action,
         required_capability,
+        priority: options
+            .priority
+            .unwrap_or_else(|| task.priority.run_weight()),
+        feedback: options.feedback,
+        idempotency_key: options.idempotency_key,
+        run_window: options.run_window,
+        trace_context: telemetry::current_traceparent(),
     };
+
+    // Runners pick this up by claiming; creating it wakes any claim that is
+    // waiting for work. No tokio::spawn here.
     let run = state
         .service
         .create_claude_run(&create)
         .await
         .map_err(to_error)?;
+    state
+        .events
+        .publish(ServerEvent::RunUpdated { run: run.clone() });
+    Ok(run)
+}
 
-    // The runner will pick this up via polling — no tokio::spawn here.
+/// Longest a claim may be held open waiting for work. Kept under the 30s
+/// window in which `/api/status` still reports a runner as connected.
+const MAX_CLAIM_WAIT_SECS: u64 = 25;
 
-    Ok((StatusCode::CREATED, Json(json!(run))))
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+struct ClaimQuery {
+    /// Seconds to wait for a run to be queued before returning 204.
+    #[serde(default)]
+    wait: u64,
 }
 
-/// Claim the oldest queued run, atomically setting it to Running.
-/// Returns 204 if no queued runs exist.
+/// Claim the highest-priority (then oldest) queued run, atomically setting it to Running.
+/// Returns 204 if no queued runs exist, after waiting up to `?wait=` seconds
+/// for one to be queued.
 /// Also records the runner heartbeat via X-Runner-Id header.
 /// If the runner is registered, uses its capability tiers for filtering.
+/// A key scoped to projects only claims runs from those projects.
+#[utoipa::path(
+    post,
+    path = "/api/claude-runs/claim",
+    tag = "runners",
+    params(ClaimQuery, ("X-Runner-Id" = Option<String>, Header, description = "The claiming runner")),
+    responses(
+        (status = 200, description = "The claimed run, now running", body = ClaudeRun),
+        (status = 204, description = "No work")
+    )
+)]
 async fn claim_claude_run(
     State(state): State<AppState>,
+    scope: ProjectScope,
+    Query(query): Query<ClaimQuery>,
     headers: HeaderMap,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    // An organization that owns no projects has nothing to claim
+    if scope.projects().is_some_and(|ids| ids.is_empty()) {
+        return Ok((StatusCode::NO_CONTENT, Json(json!(null))));
+    }
+
     // Record runner heartbeat
     let runner_id = headers
         .get("X-Runner-Id")
@@ -207,13 +455,18 @@ async fn claim_claude_run(
         .unwrap_or("unknown")
         .to_string();
 
-    // Look up registered capabilities for this runner
-    let capabilities: Vec<String> = {
+    // Look up registered capabilities for this runner, and which actions it
+    // should leave for a faster one
+    let (capabilities, skip) = {
         let runners = state.runners.lock().unwrap();
-        runners
+        let capabilities: Vec<String> = runners
             .get(&runner_id)
             .map(|info| info.capabilities.clone())
-            .unwrap_or_default()
+            .unwrap_or_default();
+        (
+            capabilities,
+            deferred_actions(&runners, &runner_id, Utc::now()),
+        )
     };
 
     // Update last_seen (preserve existing registration info)
@@ -235,27 +488,104 @@ async fn claim_claude_run(
                 active_builds: None,
                 status: super::RunnerStatus::Active,
                 pending_config: None,
+                benchmark: None,
+                org_id: scope.org_id().map(str::to_string),
             });
     }
 
     let cap_refs: Vec<&str> = capabilities.iter().map(|s| s.as_str()).collect();
-    let result = state
-        .db
-        .claim_next_claude_run(&cap_refs)
-        .await
-        .map_err(|e| to_error(flowstate_service::ServiceError::Internal(e.to_string())))?;
+    let deadline =
+        tokio::time::Instant::now() + Duration::from_secs(query.wait.min(MAX_CLAIM_WAIT_SECS));
+    let work = state.db.work_available();
+
+    loop {
+        // Hand out no work while in maintenance mode
+        if state.maintenance.load(Ordering::Relaxed) {
+            return Ok((StatusCode::NO_CONTENT, Json(json!(null))));
+        }
+
+        // Register for the wake-up before claiming so a run queued in
+        // between isn't missed.
+        let queued = work.notified();
+        tokio::pin!(queued);
+        queued.as_mut().enable();
 
-    match result {
-        Some(run) => {
+        let result = state
+            .db
+            .claim_next_claude_run_scoped(&cap_refs, &skip, scope.projects().unwrap_or_default())
+            .await
+            .map_err(|e| to_error(flowstate_service::ServiceError::Internal(e.to_string())))?;
+
+        if let Some(run) = result {
             // Record which runner claimed this run
             let _ = state.db.set_claude_run_runner(&run.id, &runner_id).await;
-            Ok((StatusCode::OK, Json(json!(run))))
+            if let Some(traceparent) = &run.trace_context {
+                // Mark the hand-off in the run's own trace, and tie this
+                // claim (the runner's poll) to it
+                let assigned = tracing::info_span!(
+                    "run.assign",
+                    run.id = %run.id,
+                    run.action = run.action.as_str(),
+                    runner.id = %runner_id,
+                );
+                telemetry::set_parent(&assigned, traceparent);
+                telemetry::add_link(&tracing::Span::current(), traceparent);
+            }
+            state
+                .events
+                .publish(ServerEvent::RunUpdated { run: run.clone() });
+            return Ok((StatusCode::OK, Json(json!(run))));
+        }
+
+        // Nothing claimable; wait for a run to be queued (which another
+        // runner may win, hence the loop) or give up at the deadline.
+        if tokio::time::timeout_at(deadline, queued).await.is_err() {
+            return Ok((StatusCode::NO_CONTENT, Json(json!(null))));
         }
-        None => Ok((StatusCode::NO_CONTENT, Json(json!(null)))),
     }
 }
 
-#[derive(Debug, Deserialize)]
+/// Time-sensitive actions a runner leaves queued because a faster runner
+/// could take them now: one that is connected and active, scored a better
+/// performance class in its startup benchmark, handles every tier this one
+/// does and has a free slot. A runner without a benchmark never defers and
+/// is never deferred to.
+fn deferred_actions(
+    runners: &HashMap<String, RunnerInfo>,
+    runner_id: &str,
+    now: DateTime<Utc>,
+) -> Vec<ClaudeAction> {
+    let Some(me) = runners.get(runner_id) else {
+        return vec![];
+    };
+    let Some(class) = me.benchmark.map(|b| b.performance_class()) else {
+        return vec![];
+    };
+    let faster_runner_free = runners.values().any(|other| {
+        other.runner_id != runner_id
+            && other.org_id == me.org_id
+            && other.status == super::RunnerStatus::Active
+            && now - other.last_seen < chrono::Duration::seconds(30)
+            && other
+                .benchmark
+                .is_some_and(|b| b.performance_class() > class)
+            && me
+                .capabilities
+                .iter()
+                .all(|c| other.capabilities.contains(c))
+            && matches!((other.active_count, other.max_concurrent), (Some(a), Some(m)) if a < m)
+    });
+    if !faster_runner_free {
+        return vec![];
+    }
+    ClaudeAction::ALL
+        .iter()
+        .copied()
+        .filter(ClaudeAction::is_time_sensitive)
+        .collect()
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 struct RegisterRunnerInput {
     runner_id: String,
     #[serde(default)]
@@ -274,15 +604,35 @@ struct RegisterRunnerInput {
     active_builds: Option<usize>,
     #[serde(default)]
     status: Option<String>,
+    #[serde(default)]
+    benchmark: Option<RunnerBenchmark>,
 }
 
 /// Register a runner with the server, recording its capabilities.
 /// Also serves as a heartbeat: runner calls this at the top of each poll iteration.
 /// Returns any pending config changes for the runner.
+#[utoipa::path(
+    post,
+    path = "/api/runners/register",
+    tag = "runners",
+    request_body = RegisterRunnerInput,
+    responses(
+        (status = 200, body = RegisterResponse),
+        (status = 409, body = ErrorBody)
+    )
+)]
 async fn register_runner(
     State(state): State<AppState>,
+    scope: ProjectScope,
+    cert: Option<Extension<RunnerCert>>,
     Json(input): Json<RegisterRunnerInput>,
-) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+) -> Result<Json<RegisterResponse>, (StatusCode, Json<Value>)> {
+    if let Some(Extension(RunnerCert(cn))) = &cert {
+        if *cn != input.runner_id {
+            return Err(runner_cert_mismatch(cn, &input.runner_id));
+        }
+    }
+
     // Parse capability and compute handled tiers
     let capabilities: Vec<String> = input
         .capability
@@ -300,47 +650,105 @@ async fn register_runner(
     let runner_status = input
         .status
         .as_deref()
-        .map(|s| match s {
-            "draining" => super::RunnerStatus::Draining,
-            "drained" => super::RunnerStatus::Drained,
-            _ => super::RunnerStatus::Active,
-        })
+        .and_then(super::RunnerStatus::parse_str)
         .unwrap_or(super::RunnerStatus::Active);
 
-    // Extract pending config to return, then clear it
-    let pending_config = {
-        let mut runners = state.runners.lock().unwrap();
-        let existing_pending = runners
-            .get(&input.runner_id)
-            .and_then(|r| r.pending_config.clone());
-
-        let info = RunnerInfo {
-            runner_id: input.runner_id.clone(),
-            last_seen: Utc::now(),
-            backend_name: input.backend_name.clone(),
-            capability: input.capability.clone(),
-            capabilities,
-            poll_interval: input.poll_interval,
-            max_concurrent: input.max_concurrent,
-            max_builds: input.max_builds,
-            active_count: input.active_count,
-            active_builds: input.active_builds,
-            status: runner_status,
-            pending_config: None, // cleared after delivery
-        };
-
-        runners.insert(input.runner_id.clone(), info);
-        existing_pending
+    // What the server already knows about the runner: from memory, or from
+    // the registry when the server restarted since its last heartbeat
+    let known = state
+        .runners
+        .lock()
+        .unwrap()
+        .get(&input.runner_id)
+        .map(|r| (r.org_id.clone(), r.pending_config.clone(), r.benchmark));
+    let known = match known {
+        Some(known) => Some(known),
+        None => match state.db.get_runner(&input.runner_id).await {
+            Ok(r) => Some((r.org_id, r.pending_config, r.benchmark)),
+            Err(flowstate_db::DbError::NotFound(_)) => None,
+            Err(e) => {
+                return Err((
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({"error": e.to_string()})),
+                ))
+            }
+        },
     };
+    let (known_org, pending_config, known_benchmark) = match known {
+        Some((org_id, pending, benchmark)) => (Some(org_id), pending, benchmark),
+        None => (None, None, None),
+    };
+    // Runner ids are server-wide; one organization cannot take over
+    // another's runner
+    if known_org.is_some_and(|org| org.as_deref() != scope.org_id()) {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({
+                "error": format!("runner id {} is in use", input.runner_id)
+            })),
+        ));
+    }
+    // A runner benchmarks once at startup; keep its results across
+    // heartbeats that leave them out.
+    let benchmark = input.benchmark.or(known_benchmark);
+
+    let now = Utc::now();
+    let info = RunnerInfo {
+        runner_id: input.runner_id.clone(),
+        last_seen: now,
+        backend_name: input.backend_name.clone(),
+        capability: input.capability.clone(),
+        capabilities,
+        poll_interval: input.poll_interval,
+        max_concurrent: input.max_concurrent,
+        max_builds: input.max_builds,
+        active_count: input.active_count,
+        active_builds: input.active_builds,
+        status: runner_status,
+        pending_config: None, // cleared after delivery
+        benchmark,
+        org_id: scope.org_id().map(str::to_string),
+    };
+    let record = info.to_record();
+    state
+        .runners
+        .lock()
+        .unwrap()
+        .insert(input.runner_id.clone(), info);
+
+    // The registry keeps the runner across restarts; a heartbeat that fails
+    // to land there still reaches the runner
+    if let Err(e) = state.db.upsert_runner(&record).await {
+        tracing::warn!("failed to record runner {}: {e}", input.runner_id);
+    }
+    if pending_config.is_some() {
+        if let Err(e) = state
+            .db
+            .set_runner_pending_config(&input.runner_id, None)
+            .await
+        {
+            tracing::warn!(
+                "failed to clear pending config of runner {}: {e}",
+                input.runner_id
+            );
+        }
+    }
 
-    Ok(Json(json!({
-        "status": "registered",
-        "runner_id": input.runner_id,
-        "pending_config": pending_config,
-    })))
+    state.events.publish(ServerEvent::RunnerHeartbeat {
+        runner_id: input.runner_id.clone(),
+        status: runner_status,
+        active_count: input.active_count,
+        max_concurrent: input.max_concurrent,
+    });
+
+    Ok(Json(RegisterResponse {
+        status: "registered".into(),
+        runner_id: input.runner_id,
+        pending_config,
+    }))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 struct UpdateStatusInput {
     status: String,
     #[serde(default)]
@@ -355,6 +763,17 @@ struct UpdateStatusInput {
     branch_name: Option<String>,
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/claude-runs/{id}/status",
+    tag = "runners",
+    request_body = UpdateStatusInput,
+    responses(
+        (status = 200, body = ClaudeRun),
+        (status = 400, body = ErrorBody),
+        (status = 409, description = "Salvage is disabled for the project", body = ErrorBody)
+    )
+)]
 async fn update_claude_run_status(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -367,7 +786,29 @@ async fn update_claude_run_status(
         )))
     })?;
 
-    let run = state
+    if status == ClaudeRunStatus::Salvaging {
+        let run = state.service.get_claude_run(&id).await.map_err(to_error)?;
+        let task = state
+            .service
+            .get_task(&run.task_id)
+            .await
+            .map_err(to_error)?;
+        if !admin::flag_enabled(&state, SALVAGE, Some(&task.project_id)).await {
+            return Err((
+                StatusCode::CONFLICT,
+                Json(json!({"error": "salvage is disabled for this project"})),
+            ));
+        }
+    }
+
+    // Webhooks hear about a run finishing once, not on every later report
+    let was_finished = state
+        .db
+        .get_claude_run(&id)
+        .await
+        .is_ok_and(|run| run_logs::is_finished(run.status));
+
+    let mut run = state
         .db
         .update_claude_run_status(&id, status, input.error_message.as_deref(), input.exit_code)
         .await
@@ -375,7 +816,7 @@ async fn update_claude_run_status(
 
     // Update PR info if provided
     if input.pr_url.is_some() || input.pr_number.is_some() || input.branch_name.is_some() {
-        let run = state
+        run = state
             .db
             .update_claude_run_pr(
                 &id,
@@ -385,17 +826,36 @@ async fn update_claude_run_status(
             )
             .await
             .map_err(|e| to_error(flowstate_service::ServiceError::Internal(e.to_string())))?;
-        return Ok(Json(json!(run)));
     }
 
+    if run_logs::is_finished(run.status) {
+        state.run_logs.finish(&id, run.status);
+        if !was_finished {
+            webhooks::run_finished(&state, &run).await;
+            notifier::run_finished(&state, &run).await;
+            if run.status == ClaudeRunStatus::Completed {
+                orchestrator::run_completed(&state, &run).await;
+            }
+        }
+    }
+    state
+        .events
+        .publish(ServerEvent::RunUpdated { run: run.clone() });
     Ok(Json(json!(run)))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 struct ProgressInput {
     message: String,
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/claude-runs/{id}/progress",
+    tag = "runners",
+    request_body = ProgressInput,
+    responses((status = 204, description = "Recorded"))
+)]
 async fn update_claude_run_progress(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -409,6 +869,36 @@ async fn update_claude_run_progress(
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/claude-runs/{id}/metrics",
+    tag = "runners",
+    request_body = RecordRunMetrics,
+    responses(
+        (status = 200, body = RunMetrics),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn record_claude_run_metrics(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(input): Json<RecordRunMetrics>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let metrics = state
+        .db
+        .record_run_metrics(&id, &input)
+        .await
+        .map_err(|e| to_error(e.into()))?;
+    notifier::run_cost_recorded(&state, &metrics).await;
+    Ok(Json(json!(metrics)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{task_id}/claude-runs",
+    tag = "runs",
+    responses((status = 200, body = [ClaudeRun]))
+)]
 async fn list_claude_runs(
     State(state): State<AppState>,
     Path(task_id): Path<String>,
@@ -421,6 +911,15 @@ async fn list_claude_runs(
         .map_err(to_error)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/claude-runs/{id}",
+    tag = "runs",
+    responses(
+        (status = 200, body = ClaudeRun),
+        (status = 404, body = ErrorBody)
+    )
+)]
 async fn get_claude_run(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -433,6 +932,15 @@ async fn get_claude_run(
         .map_err(to_error)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/claude-runs/{id}/output",
+    tag = "runs",
+    responses(
+        (status = 200, description = "The run's output", body = String, content_type = "text/plain"),
+        (status = 404, description = "Unknown run, or no output yet", body = ErrorBody)
+    )
+)]
 async fn get_claude_run_output(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -455,6 +963,86 @@ async fn get_claude_run_output(
     }
 }
 
+/// POST /api/claude-runs/{id}/requeue — put a failed, timed-out or
+/// cancelled run back in the queue, keeping its action, feedback and
+/// priority. It queues behind runs of the same priority.
+#[utoipa::path(
+    post,
+    path = "/api/claude-runs/{id}/requeue",
+    tag = "runs",
+    responses(
+        (status = 200, body = ClaudeRun),
+        (status = 404, body = ErrorBody),
+        (status = 409, description = "The run has not failed, timed out or been cancelled", body = ErrorBody)
+    )
+)]
+async fn requeue_claude_run(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let current = state.service.get_claude_run(&id).await.map_err(to_error)?;
+    let run = state
+        .db
+        .requeue_claude_run(&id)
+        .await
+        .map_err(|e| to_error(e.into()))?
+        .ok_or_else(|| {
+            (
+                StatusCode::CONFLICT,
+                Json(json!({
+                    "error": format!("cannot requeue a {} run", current.status.as_str())
+                })),
+            )
+        })?;
+    state
+        .events
+        .publish(ServerEvent::RunUpdated { run: run.clone() });
+    Ok(Json(json!(run)))
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+struct PriorityInput {
+    priority: i32,
+}
+
+/// PATCH /api/claude-runs/{id}/priority — move a queued run up or down
+/// the queue. Higher priorities are claimed first.
+#[utoipa::path(
+    patch,
+    path = "/api/claude-runs/{id}/priority",
+    tag = "runs",
+    request_body = PriorityInput,
+    responses(
+        (status = 200, body = ClaudeRun),
+        (status = 404, body = ErrorBody),
+        (status = 409, description = "The run is no longer queued", body = ErrorBody)
+    )
+)]
+async fn set_claude_run_priority(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(input): Json<PriorityInput>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let current = state.service.get_claude_run(&id).await.map_err(to_error)?;
+    let run = state
+        .db
+        .set_claude_run_priority(&id, input.priority)
+        .await
+        .map_err(|e| to_error(e.into()))?
+        .ok_or_else(|| {
+            (
+                StatusCode::CONFLICT,
+                Json(json!({
+                    "error": format!("cannot reprioritize a {} run", current.status.as_str())
+                })),
+            )
+        })?;
+    state
+        .events
+        .publish(ServerEvent::RunUpdated { run: run.clone() });
+    Ok(Json(json!(run)))
+}
+
 fn to_error(e: flowstate_service::ServiceError) -> (StatusCode, Json<Value>) {
     let (status, msg) = match &e {
         flowstate_service::ServiceError::NotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
@@ -471,13 +1059,15 @@ fn to_error(e: flowstate_service::ServiceError) -> (StatusCode, Json<Value>) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use flowstate_core::task::{ApprovalStatus, Priority, Status, Task};
+    use flowstate_core::task::{ApprovalStatus, Priority, Status, Task, TaskType};
 
     fn make_test_task() -> Task {
         Task {
             id: "task-1".into(),
             project_id: "proj-1".into(),
             sprint_id: None,
+            epic_id: None,
+            assignee_id: None,
             parent_id: None,
             title: "Test".into(),
             description: String::new(),
@@ -487,6 +1077,7 @@ mod tests {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
             research_status: ApprovalStatus::None,
             spec_status: ApprovalStatus::None,
             plan_status: ApprovalStatus::None,
@@ -499,7 +1090,10 @@ mod tests {
             verify_feedback: String::new(),
             status: Status::Todo,
             priority: Priority::Medium,
+            task_type: TaskType::Feature,
             sort_order: 1.0,
+            archived: false,
+            autopilot: false,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         }
@@ -583,6 +1177,19 @@ mod tests {
         assert!(validate_action_prerequisites(ClaudeAction::Build, &task, false, false).is_ok());
     }
 
+    #[test]
+    fn test_prerequisites_spikes_are_not_built() {
+        let mut task = make_test_task();
+        task.task_type = TaskType::Spike;
+        task.spec_status = ApprovalStatus::Approved;
+        task.plan_status = ApprovalStatus::Approved;
+        let err =
+            validate_action_prerequisites(ClaudeAction::Build, &task, false, false).unwrap_err();
+        assert!(err.contains("spike tasks are not built"));
+        assert!(validate_action_prerequisites(ClaudeAction::Verify, &task, true, false).is_err());
+        assert!(validate_action_prerequisites(ClaudeAction::Plan, &task, false, false).is_ok());
+    }
+
     #[test]
     fn test_prerequisites_verify_needs_build_or_pr() {
         let task = make_test_task();
@@ -594,6 +1201,69 @@ mod tests {
         assert!(validate_action_prerequisites(ClaudeAction::Verify, &task, false, true).is_ok());
     }
 
+    #[test]
+    fn route_capability_prefers_light_for_cost_routed_actions() {
+        use RunnerCapability::{Heavy, Light, Standard};
+        let mut task = make_test_task();
+        task.design_capability = Some(Heavy);
+
+        let route = |action, task: &Task, requested, routing, escalate, failed| {
+            route_capability(action, task, requested, routing, escalate, failed)
+        };
+        // Distill ignores the phase override and stays light
+        assert_eq!(
+            route(ClaudeAction::DesignDistill, &task, None, true, false, false),
+            Light
+        );
+        // ...unless escalated, the last attempt failed, or a tier is requested
+        assert_eq!(
+            route(ClaudeAction::DesignDistill, &task, None, true, true, false),
+            Heavy
+        );
+        assert_eq!(
+            route(ClaudeAction::DesignDistill, &task, None, true, false, true),
+            Heavy
+        );
+        assert_eq!(
+            route(
+                ClaudeAction::DesignDistill,
+                &task,
+                Some(Standard),
+                true,
+                false,
+                true
+            ),
+            Standard
+        );
+        // Routing off: the old override/default chain
+        assert_eq!(
+            route(
+                ClaudeAction::DesignDistill,
+                &task,
+                None,
+                false,
+                false,
+                false
+            ),
+            Heavy
+        );
+        // Non-routed actions are untouched
+        assert_eq!(
+            route(ClaudeAction::Design, &task, None, true, true, true),
+            Heavy
+        );
+        assert_eq!(
+            route(ClaudeAction::Build, &task, None, true, false, false),
+            Heavy
+        );
+        // Research honors the task's own research override
+        task.research_capability = Some(Standard);
+        assert_eq!(
+            route(ClaudeAction::Research, &task, None, true, false, false),
+            Standard
+        );
+    }
+
     #[test]
     fn test_prerequisites_verify_distill_needs_verification() {
         let mut task = make_test_task();
@@ -607,6 +1277,66 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_prerequisites_revise_needs_open_pr() {
+        let task = make_test_task();
+        let err =
+            validate_action_prerequisites(ClaudeAction::Revise, &task, false, false).unwrap_err();
+        assert!(err.contains("no open pull request"));
+        assert!(validate_action_prerequisites(ClaudeAction::Revise, &task, false, true).is_ok());
+    }
+
+    fn benchmarked_runner(id: &str, tokens_per_sec: Option<f64>, active: usize) -> RunnerInfo {
+        RunnerInfo {
+            runner_id: id.into(),
+            last_seen: Utc::now(),
+            backend_name: None,
+            capability: Some("standard".into()),
+            capabilities: vec!["light".into(), "standard".into()],
+            poll_interval: None,
+            max_concurrent: Some(2),
+            max_builds: Some(1),
+            active_count: Some(active),
+            active_builds: Some(0),
+            status: super::super::RunnerStatus::Active,
+            pending_config: None,
+            benchmark: tokens_per_sec.map(|rate| RunnerBenchmark {
+                compile_secs: None,
+                tokens_per_sec: Some(rate),
+            }),
+            org_id: None,
+        }
+    }
+
+    #[test]
+    fn slow_runner_defers_distills_to_a_free_faster_one() {
+        let now = Utc::now();
+        let mut runners: HashMap<String, RunnerInfo> = HashMap::new();
+        runners.insert("slow".into(), benchmarked_runner("slow", Some(5.0), 0));
+        assert!(deferred_actions(&runners, "slow", now).is_empty());
+
+        runners.insert("fast".into(), benchmarked_runner("fast", Some(80.0), 1));
+        let skip = deferred_actions(&runners, "slow", now);
+        assert!(skip.contains(&ClaudeAction::PlanDistill));
+        assert!(!skip.contains(&ClaudeAction::Build));
+        assert!(deferred_actions(&runners, "fast", now).is_empty());
+
+        // Not when the faster runner is full, stale or lacks a tier
+        runners.get_mut("fast").unwrap().active_count = Some(2);
+        assert!(deferred_actions(&runners, "slow", now).is_empty());
+        runners.get_mut("fast").unwrap().active_count = Some(0);
+        runners.get_mut("fast").unwrap().last_seen = now - chrono::Duration::seconds(60);
+        assert!(deferred_actions(&runners, "slow", now).is_empty());
+        runners.get_mut("fast").unwrap().last_seen = now;
+        runners.get_mut("fast").unwrap().capabilities = vec!["light".into()];
+        assert!(deferred_actions(&runners, "slow", now).is_empty());
+
+        // Nor for a runner that never benchmarked
+        runners.insert("fast".into(), benchmarked_runner("fast", Some(80.0), 0));
+        runners.get_mut("slow").unwrap().benchmark = None;
+        assert!(deferred_actions(&runners, "slow", now).is_empty());
+    }
+
     // ---- Integration tests ----
 
     use axum::body::Body;
@@ -688,6 +1418,48 @@ mod tests {
         assert_eq!(resp.status(), AxumStatusCode::NO_CONTENT);
     }
 
+    #[tokio::test]
+    async fn claim_claude_run_waits_for_work() {
+        let app = test_router().await;
+        let project_id = create_project(&app).await;
+        let task_id = create_task(&app, &project_id).await;
+
+        let claim = tokio::spawn(
+            app.clone().oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/api/claude-runs/claim?wait=20")
+                    .header("X-Runner-Id", "test-runner")
+                    .body(Body::empty())
+                    .unwrap(),
+            ),
+        );
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        assert!(!claim.is_finished());
+
+        let body = serde_json::to_string(&json!({"action": "research"})).unwrap();
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(format!("/api/tasks/{task_id}/claude-runs"))
+                    .header("content-type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), AxumStatusCode::CREATED);
+
+        let resp = tokio::time::timeout(std::time::Duration::from_secs(5), claim)
+            .await
+            .expect("claim should wake when a run is queued")
+            .unwrap()
+            .unwrap();
+        assert_eq!(resp.status(), AxumStatusCode::OK);
+    }
+
     #[tokio::test]
     async fn claim_claude_run_success() {
         let app = test_router().await;
@@ -731,6 +1503,186 @@ mod tests {
         assert_eq!(run["status"], "running");
     }
 
+    #[tokio::test]
+    async fn trigger_derives_priority_from_task() {
+        let app = test_router().await;
+        let project_id = create_project(&app).await;
+        let task_id = create_task(&app, &project_id).await;
+
+        for (body, expected) in [
+            (json!({"action": "research"}), 20),
+            (json!({"action": "research", "priority": 5}), 5),
+        ] {
+            let resp = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method(Method::POST)
+                        .uri(format!("/api/tasks/{task_id}/claude-runs"))
+                        .header("content-type", "application/json")
+                        .body(Body::from(body.to_string()))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(resp.status(), AxumStatusCode::CREATED);
+            let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let run: Value = serde_json::from_slice(&bytes).unwrap();
+            assert_eq!(run["priority"], expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn builds_stop_when_the_budget_is_spent() {
+        let app = test_router().await;
+        let send = |method: Method, uri: String, body: Value| {
+            let app = app.clone();
+            async move {
+                let resp = app
+                    .oneshot(
+                        Request::builder()
+                            .method(method)
+                            .uri(uri)
+                            .header("content-type", "application/json")
+                            .body(Body::from(body.to_string()))
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                let status = resp.status();
+                let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (status, serde_json::from_slice::<Value>(&bytes).unwrap())
+            }
+        };
+        let project_id = create_project(&app).await;
+        let task_id = create_task(&app, &project_id).await;
+        let (status, _) = send(
+            Method::PUT,
+            format!("/api/projects/{project_id}"),
+            json!({"monthly_budget_usd": 1.0}),
+        )
+        .await;
+        assert_eq!(status, AxumStatusCode::OK);
+        send(
+            Method::PUT,
+            format!("/api/tasks/{task_id}"),
+            json!({"spec_status": "approved", "plan_status": "approved"}),
+        )
+        .await;
+
+        let runs = format!("/api/tasks/{task_id}/claude-runs");
+        let (_, run) = send(Method::POST, runs.clone(), json!({"action": "research"})).await;
+        let (status, _) = send(
+            Method::POST,
+            format!("/api/claude-runs/{}/metrics", run["id"].as_str().unwrap()),
+            json!({"duration_ms": 1000, "stdout_bytes": 10, "cost_usd": 1.5}),
+        )
+        .await;
+        assert_eq!(status, AxumStatusCode::OK);
+
+        let (status, _) = send(Method::POST, runs.clone(), json!({"action": "build"})).await;
+        assert_eq!(status, AxumStatusCode::PAYMENT_REQUIRED);
+        // Cheaper actions still run
+        let (status, _) = send(Method::POST, runs.clone(), json!({"action": "research"})).await;
+        assert_eq!(status, AxumStatusCode::CREATED);
+
+        let budget_override = format!("/api/projects/{project_id}/budget/override");
+        let (status, budget) = send(Method::PUT, budget_override.clone(), json!({})).await;
+        assert_eq!(status, AxumStatusCode::OK);
+        assert_eq!(budget["spent_usd"], 1.5);
+        assert_eq!(budget["remaining_usd"], 0.0);
+        assert_eq!(budget["builds_blocked"], false);
+        assert_eq!(budget["override_until"], budget["period_end"]);
+        let (status, _) = send(Method::POST, runs.clone(), json!({"action": "build"})).await;
+        assert_eq!(status, AxumStatusCode::CREATED);
+
+        let (status, budget) = send(Method::DELETE, budget_override, Value::Null).await;
+        assert_eq!(status, AxumStatusCode::OK);
+        assert_eq!(budget["builds_blocked"], true);
+        let (status, _) = send(
+            Method::PUT,
+            format!("/api/projects/{project_id}/budget/override"),
+            json!({"until": "2020-01-01T00:00:00Z"}),
+        )
+        .await;
+        assert_eq!(status, AxumStatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn builds_stop_when_a_run_reports_no_cost() {
+        let app = test_router().await;
+        let send = |method: Method, uri: String, body: Value| {
+            let app = app.clone();
+            async move {
+                let resp = app
+                    .oneshot(
+                        Request::builder()
+                            .method(method)
+                            .uri(uri)
+                            .header("content-type", "application/json")
+                            .body(Body::from(body.to_string()))
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                let status = resp.status();
+                let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (status, serde_json::from_slice::<Value>(&bytes).unwrap())
+            }
+        };
+        let project_id = create_project(&app).await;
+        let task_id = create_task(&app, &project_id).await;
+        send(
+            Method::PUT,
+            format!("/api/projects/{project_id}"),
+            json!({"monthly_budget_usd": 100.0}),
+        )
+        .await;
+        send(
+            Method::PUT,
+            format!("/api/tasks/{task_id}"),
+            json!({"spec_status": "approved", "plan_status": "approved"}),
+        )
+        .await;
+
+        let runs = format!("/api/tasks/{task_id}/claude-runs");
+        let (_, run) = send(Method::POST, runs.clone(), json!({"action": "research"})).await;
+        send(
+            Method::POST,
+            format!("/api/claude-runs/{}/metrics", run["id"].as_str().unwrap()),
+            json!({"duration_ms": 1000, "stdout_bytes": 10, "input_tokens": 5000}),
+        )
+        .await;
+
+        let (status, body) = send(Method::POST, runs.clone(), json!({"action": "build"})).await;
+        assert_eq!(status, AxumStatusCode::PAYMENT_REQUIRED);
+        assert!(body["error"].as_str().unwrap().contains("reported no cost"));
+        let (_, budget) = send(
+            Method::GET,
+            format!("/api/projects/{project_id}/budget"),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(budget["spent_usd"], 0.0);
+        assert_eq!(budget["unpriced_runs"], 1);
+        assert_eq!(budget["builds_blocked"], true);
+
+        send(
+            Method::PUT,
+            format!("/api/projects/{project_id}/budget/override"),
+            json!({}),
+        )
+        .await;
+        let (status, _) = send(Method::POST, runs, json!({"action": "build"})).await;
+        assert_eq!(status, AxumStatusCode::CREATED);
+    }
+
     #[tokio::test]
     async fn get_claude_run_by_id() {
         let app = test_router().await;
@@ -842,6 +1794,112 @@ mod tests {
         assert_eq!(run["status"], "completed");
     }
 
+    #[tokio::test]
+    async fn failed_research_escalates_to_heavy() {
+        let app = test_router().await;
+        let project_id = create_project(&app).await;
+        let task_id = create_task(&app, &project_id).await;
+
+        async fn send(app: &axum::Router, method: Method, uri: &str, body: Value) -> Value {
+            let resp = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method(method)
+                        .uri(uri)
+                        .header("content-type", "application/json")
+                        .header("X-Runner-Id", "test-runner")
+                        .body(Body::from(body.to_string()))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null)
+        }
+
+        let trigger_uri = format!("/api/tasks/{task_id}/claude-runs");
+        let first = send(
+            &app,
+            Method::POST,
+            &trigger_uri,
+            json!({"action": "research"}),
+        )
+        .await;
+        assert_eq!(first["required_capability"], "light");
+
+        send(&app, Method::POST, "/api/claude-runs/claim", json!(null)).await;
+        let status_uri = format!("/api/claude-runs/{}/status", first["id"].as_str().unwrap());
+        send(&app, Method::PUT, &status_uri, json!({"status": "failed"})).await;
+
+        let retry = send(
+            &app,
+            Method::POST,
+            &trigger_uri,
+            json!({"action": "research"}),
+        )
+        .await;
+        assert_eq!(retry["required_capability"], "heavy");
+    }
+
+    #[tokio::test]
+    async fn salvaging_rejected_when_salvage_flag_off() {
+        let app = test_router().await;
+        let project_id = create_project(&app).await;
+        let task_id = create_task(&app, &project_id).await;
+
+        let body = serde_json::to_string(&json!({"action": "research"})).unwrap();
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(format!("/api/tasks/{task_id}/claude-runs"))
+                    .header("content-type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let created: Value = serde_json::from_slice(&bytes).unwrap();
+        let run_id = created["id"].as_str().unwrap();
+
+        let body = json!({"key": "salvage", "project_id": project_id, "enabled": false});
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::PATCH)
+                    .uri("/admin/flags")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), AxumStatusCode::OK);
+
+        let body = json!({"status": "salvaging"});
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::PUT)
+                    .uri(format!("/api/claude-runs/{run_id}/status"))
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), AxumStatusCode::CONFLICT);
+    }
+
     #[tokio::test]
     async fn update_claude_run_progress_flow() {
         let app = test_router().await;
@@ -996,6 +2054,45 @@ mod tests {
         assert_eq!(result["runner_id"], "cap-runner-1");
     }
 
+    #[tokio::test]
+    async fn register_keeps_benchmark_across_heartbeats() {
+        let app = test_router().await;
+        for body in [
+            json!({"runner_id": "bench-1", "benchmark": {"compile_secs": 1.5, "tokens_per_sec": 64.0}}),
+            json!({"runner_id": "bench-1", "active_count": 0}),
+        ] {
+            let resp = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method(Method::POST)
+                        .uri("/api/runners/register")
+                        .header("content-type", "application/json")
+                        .body(Body::from(serde_json::to_string(&body).unwrap()))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(resp.status(), AxumStatusCode::OK);
+        }
+
+        let resp = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/infra/runners")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let runners: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(runners[0]["benchmark"]["compile_secs"], 1.5);
+        assert_eq!(runners[0]["performance_class"], "fast");
+    }
+
     #[tokio::test]
     async fn get_claude_run_output_missing() {
         let app = test_router().await;
@@ -1085,4 +2182,147 @@ mod tests {
         let list: Value = serde_json::from_slice(&bytes).unwrap();
         assert_eq!(list.as_array().unwrap().len(), 1);
     }
+
+    #[tokio::test]
+    async fn revise_needs_an_open_pr() {
+        let app = test_router().await;
+        let project_id = create_project(&app).await;
+        let task_id = create_task(&app, &project_id).await;
+        let post = |uri: String, body: Value| {
+            app.clone().oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+        };
+        let revise = || {
+            post(
+                format!("/api/tasks/{task_id}/claude-runs"),
+                json!({"action": "revise"}),
+            )
+        };
+
+        let resp = revise().await.unwrap();
+        assert_eq!(resp.status(), AxumStatusCode::BAD_REQUEST);
+
+        let resp = post(
+            format!("/api/tasks/{task_id}/prs"),
+            json!({
+                "pr_url": "https://github.com/acme/app/pull/7",
+                "pr_number": 7,
+                "branch_name": "flowstate/run-task",
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.status(), AxumStatusCode::CREATED);
+        let resp = revise().await.unwrap();
+        assert_eq!(resp.status(), AxumStatusCode::CREATED);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let run: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(run["action"], "revise");
+        assert_eq!(run["required_capability"], "heavy");
+    }
+
+    #[tokio::test]
+    async fn requeue_and_reprioritize_runs() {
+        let app = test_router().await;
+        let project_id = create_project(&app).await;
+        let task_id = create_task(&app, &project_id).await;
+        let send = |method: Method, uri: String, body: Value| {
+            app.clone().oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .header("X-Runner-Id", "test-runner")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+        };
+        let json_of = |resp: axum::response::Response| async move {
+            let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice::<Value>(&bytes).unwrap()
+        };
+
+        let resp = send(
+            Method::POST,
+            format!("/api/tasks/{task_id}/claude-runs"),
+            json!({"action": "research"}),
+        )
+        .await
+        .unwrap();
+        let run_id = json_of(resp).await["id"].as_str().unwrap().to_string();
+
+        let resp = send(
+            Method::PATCH,
+            format!("/api/claude-runs/{run_id}/priority"),
+            json!({"priority": 7}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.status(), AxumStatusCode::OK);
+        assert_eq!(json_of(resp).await["priority"], 7);
+
+        // A queued run cannot be requeued
+        let resp = send(
+            Method::POST,
+            format!("/api/claude-runs/{run_id}/requeue"),
+            Value::Null,
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.status(), AxumStatusCode::CONFLICT);
+
+        send(Method::POST, "/api/claude-runs/claim".into(), Value::Null)
+            .await
+            .unwrap();
+        let resp = send(
+            Method::PUT,
+            format!("/api/claude-runs/{run_id}/status"),
+            json!({"status": "failed", "error_message": "boom"}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.status(), AxumStatusCode::OK);
+
+        // ...nor can a failed one be reprioritized
+        let resp = send(
+            Method::PATCH,
+            format!("/api/claude-runs/{run_id}/priority"),
+            json!({"priority": 1}),
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.status(), AxumStatusCode::CONFLICT);
+
+        let resp = send(
+            Method::POST,
+            format!("/api/claude-runs/{run_id}/requeue"),
+            Value::Null,
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.status(), AxumStatusCode::OK);
+        let run = json_of(resp).await;
+        assert_eq!(run["status"], "queued");
+        assert_eq!(run["priority"], 7);
+        assert!(run["error_message"].is_null());
+
+        let resp = send(
+            Method::POST,
+            "/api/claude-runs/missing/requeue".into(),
+            Value::Null,
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.status(), AxumStatusCode::NOT_FOUND);
+    }
 }
@@ -0,0 +1,315 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get, post, put},
+    Json, Router,
+};
+use flowstate_core::custom_field::{CreateCustomField, CustomField, UpdateCustomField};
+use flowstate_service::TaskService;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::openapi::ErrorBody;
+use super::AppState;
+use crate::auth::ProjectScope;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/custom-fields", post(create_custom_field))
+        .route("/api/custom-fields", get(list_custom_fields))
+        .route("/api/custom-fields/{id}", get(get_custom_field))
+        .route("/api/custom-fields/{id}", put(update_custom_field))
+        .route("/api/custom-fields/{id}", delete(delete_custom_field))
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+struct ListCustomFieldsQuery {
+    project_id: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/custom-fields",
+    tag = "custom fields",
+    request_body = CreateCustomField,
+    responses(
+        (status = 201, body = CustomField),
+        (status = 400, body = ErrorBody)
+    )
+)]
+async fn create_custom_field(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+    Json(input): Json<CreateCustomField>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    scope.check(&input.project_id)?;
+    state
+        .service
+        .create_custom_field(&input)
+        .await
+        .map(|f| (StatusCode::CREATED, Json(json!(f))))
+        .map_err(to_error)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/custom-fields/{id}",
+    tag = "custom fields",
+    responses(
+        (status = 200, body = CustomField),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn get_custom_field(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    state
+        .service
+        .get_custom_field(&id)
+        .await
+        .map(|f| Json(json!(f)))
+        .map_err(to_error)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/custom-fields",
+    tag = "custom fields",
+    params(ListCustomFieldsQuery),
+    responses((status = 200, body = [CustomField]))
+)]
+async fn list_custom_fields(
+    State(state): State<AppState>,
+    Query(q): Query<ListCustomFieldsQuery>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    state
+        .service
+        .list_custom_fields(&q.project_id)
+        .await
+        .map(|f| Json(json!(f)))
+        .map_err(to_error)
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/custom-fields/{id}",
+    tag = "custom fields",
+    request_body = UpdateCustomField,
+    responses(
+        (status = 200, body = CustomField),
+        (status = 400, body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn update_custom_field(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(update): Json<UpdateCustomField>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    state
+        .service
+        .update_custom_field(&id, &update)
+        .await
+        .map(|f| Json(json!(f)))
+        .map_err(to_error)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/custom-fields/{id}",
+    tag = "custom fields",
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn delete_custom_field(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<Value>)> {
+    state
+        .service
+        .delete_custom_field(&id)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(to_error)
+}
+
+fn to_error(e: flowstate_service::ServiceError) -> (StatusCode, Json<Value>) {
+    let (status, msg) = match &e {
+        flowstate_service::ServiceError::NotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
+        flowstate_service::ServiceError::InvalidInput(_) => {
+            (StatusCode::BAD_REQUEST, e.to_string())
+        }
+        flowstate_service::ServiceError::Internal(_) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        }
+    };
+    (status, Json(json!({ "error": msg })))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::test_router;
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    async fn send(
+        app: &axum::Router,
+        method: Method,
+        uri: &str,
+        body: Value,
+    ) -> (StatusCode, Value) {
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = resp.status();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    #[tokio::test]
+    async fn custom_fields_validate_and_filter() {
+        let app = test_router().await;
+        let (_, project) = send(
+            &app,
+            Method::POST,
+            "/api/projects",
+            json!({ "name": "Fields", "slug": "fields" }),
+        )
+        .await;
+        let project_id = project["id"].as_str().unwrap();
+
+        let (status, _) = send(
+            &app,
+            Method::POST,
+            "/api/custom-fields",
+            json!({ "project_id": project_id, "name": "severity", "type": "enum" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, severity) = send(
+            &app,
+            Method::POST,
+            "/api/custom-fields",
+            json!({
+                "project_id": project_id,
+                "name": "severity",
+                "type": "enum",
+                "options": ["low", "high"]
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let severity_id = severity["id"].as_str().unwrap();
+        let (status, _) = send(
+            &app,
+            Method::POST,
+            "/api/custom-fields",
+            json!({ "project_id": project_id, "name": "severity", "type": "text" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (_, due) = send(
+            &app,
+            Method::POST,
+            "/api/custom-fields",
+            json!({ "project_id": project_id, "name": "due", "type": "date" }),
+        )
+        .await;
+        let due_id = due["id"].as_str().unwrap();
+
+        let (_, task) = send(
+            &app,
+            Method::POST,
+            "/api/tasks",
+            json!({ "project_id": project_id, "title": "Crash on save", "status": "todo", "priority": "high" }),
+        )
+        .await;
+        let task_id = task["id"].as_str().unwrap();
+
+        let (status, _) = send(
+            &app,
+            Method::PUT,
+            &format!("/api/tasks/{task_id}"),
+            json!({ "custom_fields": { severity_id: "urgent" } }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = send(
+            &app,
+            Method::PUT,
+            &format!("/api/tasks/{task_id}"),
+            json!({ "custom_fields": { severity_id: "high", due_id: "2026-1-5" } }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, values) = send(
+            &app,
+            Method::GET,
+            &format!("/api/tasks/{task_id}/fields"),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let due_value = values
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|v| v["field_id"] == due_id)
+            .unwrap();
+        assert_eq!(due_value["value"], "2026-01-05");
+
+        let (_, tasks) = send(
+            &app,
+            Method::GET,
+            &format!("/api/tasks?project_id={project_id}&field_id={severity_id}&field_value=high"),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(tasks.as_array().unwrap().len(), 1);
+        let (_, tasks) = send(
+            &app,
+            Method::GET,
+            &format!("/api/tasks?project_id={project_id}&field_id={severity_id}&field_value=low"),
+            Value::Null,
+        )
+        .await;
+        assert!(tasks.as_array().unwrap().is_empty());
+
+        let (status, _) = send(
+            &app,
+            Method::DELETE,
+            &format!("/api/custom-fields/{severity_id}"),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (_, values) = send(
+            &app,
+            Method::GET,
+            &format!("/api/tasks/{task_id}/fields"),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(values.as_array().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,294 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get, post, put},
+    Json, Router,
+};
+use flowstate_core::epic::{CreateEpic, Epic, UpdateEpic};
+use flowstate_service::TaskService;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::openapi::ErrorBody;
+use super::AppState;
+use crate::auth::ProjectScope;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/epics", post(create_epic))
+        .route("/api/epics", get(list_epics))
+        .route("/api/epics/{id}", get(get_epic))
+        .route("/api/epics/{id}", put(update_epic))
+        .route("/api/epics/{id}", delete(delete_epic))
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+struct ListEpicsQuery {
+    project_id: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/epics",
+    tag = "epics",
+    request_body = CreateEpic,
+    responses(
+        (status = 201, body = Epic),
+        (status = 400, body = ErrorBody)
+    )
+)]
+async fn create_epic(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+    Json(input): Json<CreateEpic>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    scope.check(&input.project_id)?;
+    state
+        .service
+        .create_epic(&input)
+        .await
+        .map(|e| (StatusCode::CREATED, Json(json!(e))))
+        .map_err(to_error)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/epics/{id}",
+    tag = "epics",
+    responses(
+        (status = 200, body = Epic),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn get_epic(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    state
+        .service
+        .get_epic(&id)
+        .await
+        .map(|e| Json(json!(e)))
+        .map_err(to_error)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/epics",
+    tag = "epics",
+    params(ListEpicsQuery),
+    responses((status = 200, body = [Epic]))
+)]
+async fn list_epics(
+    State(state): State<AppState>,
+    Query(q): Query<ListEpicsQuery>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    state
+        .service
+        .list_epics(&q.project_id)
+        .await
+        .map(|e| Json(json!(e)))
+        .map_err(to_error)
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/epics/{id}",
+    tag = "epics",
+    request_body = UpdateEpic,
+    responses(
+        (status = 200, body = Epic),
+        (status = 400, body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn update_epic(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(update): Json<UpdateEpic>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    state
+        .service
+        .update_epic(&id, &update)
+        .await
+        .map(|e| Json(json!(e)))
+        .map_err(to_error)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/epics/{id}",
+    tag = "epics",
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn delete_epic(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<Value>)> {
+    state
+        .service
+        .delete_epic(&id)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(to_error)
+}
+
+fn to_error(e: flowstate_service::ServiceError) -> (StatusCode, Json<Value>) {
+    let (status, msg) = match &e {
+        flowstate_service::ServiceError::NotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
+        flowstate_service::ServiceError::InvalidInput(_) => {
+            (StatusCode::BAD_REQUEST, e.to_string())
+        }
+        flowstate_service::ServiceError::Internal(_) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        }
+    };
+    (status, Json(json!({ "error": msg })))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::test_router;
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    async fn send(
+        app: &axum::Router,
+        method: Method,
+        uri: &str,
+        body: Value,
+    ) -> (StatusCode, Value) {
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = resp.status();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    #[tokio::test]
+    async fn epic_groups_tasks() {
+        let app = test_router().await;
+        let (_, project) = send(
+            &app,
+            Method::POST,
+            "/api/projects",
+            json!({ "name": "Epics", "slug": "epics" }),
+        )
+        .await;
+        let (_, other) = send(
+            &app,
+            Method::POST,
+            "/api/projects",
+            json!({ "name": "Other", "slug": "other" }),
+        )
+        .await;
+        let project_id = project["id"].as_str().unwrap();
+
+        let (status, epic) = send(
+            &app,
+            Method::POST,
+            "/api/epics",
+            json!({
+                "project_id": project_id,
+                "name": "Onboarding",
+                "target_date": "2026-12-01T00:00:00Z"
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(epic["status"], "open");
+        let epic_id = epic["id"].as_str().unwrap();
+
+        let (_, task) = send(
+            &app,
+            Method::POST,
+            "/api/tasks",
+            json!({ "project_id": project_id, "title": "Signup form", "status": "todo", "priority": "medium" }),
+        )
+        .await;
+        let task_id = task["id"].as_str().unwrap();
+        let (status, task) = send(
+            &app,
+            Method::PUT,
+            &format!("/api/tasks/{task_id}"),
+            json!({ "epic_id": epic_id }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(task["epic_id"], epic_id);
+
+        // A task from another project cannot join the epic
+        let (_, foreign) = send(
+            &app,
+            Method::POST,
+            "/api/tasks",
+            json!({ "project_id": other["id"], "title": "Elsewhere", "status": "todo", "priority": "medium" }),
+        )
+        .await;
+        let (status, _) = send(
+            &app,
+            Method::PUT,
+            &format!("/api/tasks/{}", foreign["id"].as_str().unwrap()),
+            json!({ "epic_id": epic_id }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, tasks) = send(
+            &app,
+            Method::GET,
+            &format!("/api/tasks?epic_id={epic_id}"),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(tasks.as_array().unwrap().len(), 1);
+
+        let (status, epic) = send(
+            &app,
+            Method::PUT,
+            &format!("/api/epics/{epic_id}"),
+            json!({ "status": "done" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(epic["status"], "done");
+
+        let (status, _) = send(
+            &app,
+            Method::DELETE,
+            &format!("/api/epics/{epic_id}"),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (_, task) = send(
+            &app,
+            Method::GET,
+            &format!("/api/tasks/{task_id}"),
+            Value::Null,
+        )
+        .await;
+        assert!(task["epic_id"].is_null());
+    }
+}
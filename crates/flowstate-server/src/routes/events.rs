@@ -0,0 +1,319 @@
+use std::convert::Infallible;
+
+use axum::{
+    extract::State,
+    response::sse::{Event, KeepAlive, Sse},
+    routing::get,
+    Router,
+};
+use flowstate_core::claude_run::ClaudeRun;
+use flowstate_core::task::Task;
+use flowstate_service::TaskService;
+use futures_util::{Stream, StreamExt};
+use serde::Serialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use super::{AppState, RunnerStatus};
+use crate::auth::ProjectScope;
+
+/// Events a subscriber may fall behind by before it misses some and is told
+/// to resync.
+const CHANNEL_CAPACITY: usize = 1024;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/api/events", get(events))
+}
+
+/// A change pushed to `/api/events` subscribers. The SSE event name is the
+/// `type` tag and the data is the whole event as JSON.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerEvent {
+    /// A task was created or changed; carries the task as it now is.
+    TaskUpdated {
+        task: Task,
+    },
+    TaskDeleted {
+        task_id: String,
+    },
+    /// A run was queued, claimed, or changed status.
+    RunUpdated {
+        run: ClaudeRun,
+    },
+    RunnerHeartbeat {
+        runner_id: String,
+        status: RunnerStatus,
+        active_count: Option<usize>,
+        max_concurrent: Option<usize>,
+    },
+}
+
+impl ServerEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            ServerEvent::TaskUpdated { .. } => "task_updated",
+            ServerEvent::TaskDeleted { .. } => "task_deleted",
+            ServerEvent::RunUpdated { .. } => "run_updated",
+            ServerEvent::RunnerHeartbeat { .. } => "runner_heartbeat",
+        }
+    }
+
+    /// Whether a caller limited to `scope` may see this event. Deletions
+    /// carry only an id and go to everyone.
+    async fn visible(&self, state: &AppState, scope: &ProjectScope) -> bool {
+        if scope.projects().is_none() {
+            return true;
+        }
+        match self {
+            ServerEvent::TaskUpdated { task } => scope.allows(&task.project_id),
+            ServerEvent::TaskDeleted { .. } => true,
+            ServerEvent::RunUpdated { run } => state
+                .service
+                .get_task(&run.task_id)
+                .await
+                .is_ok_and(|task| scope.allows(&task.project_id)),
+            ServerEvent::RunnerHeartbeat { runner_id, .. } => state
+                .runners
+                .lock()
+                .unwrap()
+                .get(runner_id)
+                .is_some_and(|r| r.org_id.as_deref() == scope.org_id()),
+        }
+    }
+}
+
+/// Fans server events out to every open `/api/events` stream. Publishing
+/// never blocks and is a no-op while nobody is listening.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<ServerEvent>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self {
+            sender: broadcast::channel(CHANNEL_CAPACITY).0,
+        }
+    }
+}
+
+impl EventBus {
+    pub fn publish(&self, event: ServerEvent) {
+        // Err only means there are no subscribers
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<ServerEvent> {
+        self.sender.subscribe()
+    }
+}
+
+/// Stream server events as they happen. A subscriber that falls too far
+/// behind gets a `resync` event in place of the ones it missed and should
+/// refetch whatever it shows.
+#[utoipa::path(
+    get,
+    path = "/api/events",
+    tag = "events",
+    responses((
+        status = 200,
+        description = "Server-sent events, one per change",
+        body = ServerEvent,
+        content_type = "text/event-stream"
+    ))
+)]
+async fn events(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    // Ends on shutdown so an idle subscriber does not hold up draining
+    let shutdown = state.shutdown.clone().cancelled_owned();
+    let rx = state.events.subscribe();
+    let stream =
+        futures_util::stream::unfold((rx, state, scope), |(mut rx, state, scope)| async move {
+            let event = loop {
+                match rx.recv().await {
+                    Ok(event) if !event.visible(&state, &scope).await => continue,
+                    Ok(event) => {
+                        break Event::default()
+                            .event(event.name())
+                            .json_data(&event)
+                            .unwrap_or_else(|_| Event::default().event("resync").data("{}"))
+                    }
+                    Err(RecvError::Lagged(missed)) => {
+                        break Event::default()
+                            .event("resync")
+                            .data(format!("{{\"missed\":{missed}}}"))
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            };
+            Some((Ok(event), (rx, state, scope)))
+        })
+        .take_until(shutdown);
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::test_router;
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use futures_util::StreamExt;
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    async fn send(app: &axum::Router, method: Method, uri: &str, body: Value) -> Value {
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null)
+    }
+
+    /// Read SSE frames until `count` events have arrived, skipping keep-alives.
+    async fn next_events(
+        body: &mut (impl futures_util::Stream<Item = Result<axum::body::Bytes, axum::Error>> + Unpin),
+        count: usize,
+    ) -> Vec<(String, Value)> {
+        let mut text = String::new();
+        let mut events = Vec::new();
+        while events.len() < count {
+            let chunk = tokio::time::timeout(std::time::Duration::from_secs(5), body.next())
+                .await
+                .expect("timed out waiting for an event")
+                .unwrap()
+                .unwrap();
+            text.push_str(std::str::from_utf8(&chunk).unwrap());
+            while let Some(end) = text.find("\n\n") {
+                let frame: String = text.drain(..end + 2).collect();
+                let name = frame.lines().find_map(|l| l.strip_prefix("event: "));
+                let data = frame.lines().find_map(|l| l.strip_prefix("data: "));
+                if let (Some(name), Some(data)) = (name, data) {
+                    events.push((name.to_string(), serde_json::from_str(data).unwrap()));
+                }
+            }
+        }
+        events
+    }
+
+    #[tokio::test]
+    async fn streams_task_run_and_runner_events() {
+        let app = test_router().await;
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/events")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers()["content-type"].to_str().unwrap(),
+            "text/event-stream"
+        );
+        let mut body = resp.into_body().into_data_stream();
+
+        let project = send(
+            &app,
+            Method::POST,
+            "/api/projects",
+            json!({"name": "Events", "slug": "events"}),
+        )
+        .await;
+        let task = send(
+            &app,
+            Method::POST,
+            "/api/tasks",
+            json!({
+                "project_id": project["id"],
+                "title": "Watch me",
+                "status": "todo",
+                "priority": "medium",
+            }),
+        )
+        .await;
+        let task_id = task["id"].as_str().unwrap();
+        send(
+            &app,
+            Method::PUT,
+            &format!("/api/tasks/{task_id}"),
+            json!({"title": "Watched"}),
+        )
+        .await;
+        send(
+            &app,
+            Method::POST,
+            &format!("/api/tasks/{task_id}/claude-runs"),
+            json!({"action": "research"}),
+        )
+        .await;
+        send(
+            &app,
+            Method::POST,
+            "/api/runners/register",
+            json!({"runner_id": "r1", "active_count": 0, "max_concurrent": 2}),
+        )
+        .await;
+        send(
+            &app,
+            Method::DELETE,
+            &format!("/api/tasks/{task_id}"),
+            json!(null),
+        )
+        .await;
+
+        let events = next_events(&mut body, 5).await;
+        let names: Vec<&str> = events.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "task_updated",
+                "task_updated",
+                "run_updated",
+                "runner_heartbeat",
+                "task_deleted"
+            ]
+        );
+        assert_eq!(events[1].1["task"]["title"], "Watched");
+        assert_eq!(events[2].1["run"]["status"], "queued");
+        assert_eq!(events[3].1["runner_id"], "r1");
+        assert_eq!(events[3].1["max_concurrent"], 2);
+        assert_eq!(events[4].1["task_id"], task_id);
+    }
+
+    #[tokio::test]
+    async fn stream_ends_on_shutdown() {
+        let state = crate::test_helpers::test_state().await;
+        let app = crate::routes::build_router(state.clone());
+        let resp = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/events")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let mut body = resp.into_body().into_data_stream();
+        state.shutdown.cancel();
+        let end = tokio::time::timeout(std::time::Duration::from_secs(5), body.next())
+            .await
+            .expect("stream did not end");
+        assert!(end.is_none());
+    }
+}
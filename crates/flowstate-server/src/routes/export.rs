@@ -0,0 +1,390 @@
+use std::collections::HashMap;
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::{DateTime, Utc};
+use flowstate_core::task::{Priority, Status, TaskFilter, TaskType};
+use flowstate_service::TaskService;
+use futures_util::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::openapi::ErrorBody;
+use super::AppState;
+
+type ApiError = (StatusCode, Json<Value>);
+
+/// CSV column names, in [`ExportRow`] field order.
+const CSV_HEADER: &str = "id,title,status,priority,task_type,sprint,epic,assignee,parent_id,\
+due_at,archived,created_at,updated_at,pr_urls\r\n";
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/api/projects/{id}/export", get(export_project))
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+struct ExportQuery {
+    /// `csv` (the default) or `json`.
+    #[serde(default)]
+    format: ExportFormat,
+}
+
+/// One task as exported, with its sprint, epic and assignee by name.
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct ExportRow {
+    pub id: String,
+    pub title: String,
+    pub status: Status,
+    pub priority: Priority,
+    pub task_type: TaskType,
+    pub sprint: Option<String>,
+    pub epic: Option<String>,
+    pub assignee: Option<String>,
+    pub parent_id: Option<String>,
+    pub due_at: Option<DateTime<Utc>>,
+    pub archived: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Pull requests opened for the task.
+    pub pr_urls: Vec<String>,
+}
+
+impl ExportRow {
+    fn to_csv(&self) -> String {
+        let time = |t: &DateTime<Utc>| t.to_rfc3339();
+        let fields = [
+            self.id.clone(),
+            self.title.clone(),
+            self.status.as_str().to_string(),
+            self.priority.as_str().to_string(),
+            self.task_type.as_str().to_string(),
+            self.sprint.clone().unwrap_or_default(),
+            self.epic.clone().unwrap_or_default(),
+            self.assignee.clone().unwrap_or_default(),
+            self.parent_id.clone().unwrap_or_default(),
+            self.due_at.as_ref().map(time).unwrap_or_default(),
+            self.archived.to_string(),
+            time(&self.created_at),
+            time(&self.updated_at),
+            self.pr_urls.join(" "),
+        ];
+        let mut line = fields
+            .iter()
+            .map(|f| csv_field(f))
+            .collect::<Vec<_>>()
+            .join(",");
+        line.push_str("\r\n");
+        line
+    }
+}
+
+/// Quote a CSV field when it needs it. Text a spreadsheet would read as a
+/// formula gets a leading `'`, so an exported title cannot run one.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// GET /api/projects/{id}/export — every task in the project, archived
+/// ones included, as CSV or a JSON array of [`ExportRow`]s. Rows are
+/// written as they are read, so large projects start downloading at once.
+#[utoipa::path(
+    get,
+    path = "/api/projects/{id}/export",
+    tag = "projects",
+    params(ExportQuery),
+    responses(
+        (status = 200, description = "`text/csv` with a header row, or `application/json`", body = [ExportRow]),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn export_project(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(q): Query<ExportQuery>,
+) -> Result<Response, ApiError> {
+    let project = state.service.get_project(&id).await.map_err(to_error)?;
+    let mut tasks = Vec::new();
+    for archived in [false, true] {
+        tasks.extend(
+            state
+                .service
+                .list_tasks(&TaskFilter {
+                    project_id: Some(project.id.clone()),
+                    archived,
+                    ..Default::default()
+                })
+                .await
+                .map_err(to_error)?,
+        );
+    }
+    let sprints: HashMap<_, _> = state
+        .service
+        .list_sprints(&project.id)
+        .await
+        .map_err(to_error)?
+        .into_iter()
+        .map(|s| (s.id, s.name))
+        .collect();
+    let epics: HashMap<_, _> = state
+        .service
+        .list_epics(&project.id)
+        .await
+        .map_err(to_error)?
+        .into_iter()
+        .map(|e| (e.id, e.name))
+        .collect();
+    let users: HashMap<_, _> = state
+        .service
+        .list_users()
+        .await
+        .map_err(to_error)?
+        .into_iter()
+        .map(|u| (u.id, u.name))
+        .collect();
+
+    let format = q.format;
+    let rows = stream::iter(tasks).enumerate().then(move |(i, task)| {
+        let state = state.clone();
+        let name = |names: &HashMap<String, String>, id: &Option<String>| {
+            id.as_ref().and_then(|id| names.get(id).cloned())
+        };
+        let mut row = ExportRow {
+            sprint: name(&sprints, &task.sprint_id),
+            epic: name(&epics, &task.epic_id),
+            assignee: name(&users, &task.assignee_id),
+            id: task.id,
+            title: task.title,
+            status: task.status,
+            priority: task.priority,
+            task_type: task.task_type,
+            parent_id: task.parent_id,
+            due_at: task.due_at,
+            archived: task.archived,
+            created_at: task.created_at,
+            updated_at: task.updated_at,
+            pr_urls: Vec::new(),
+        };
+        async move {
+            row.pr_urls = state
+                .service
+                .list_task_prs(&row.id)
+                .await
+                .map_err(std::io::Error::other)?
+                .into_iter()
+                .map(|pr| pr.pr_url)
+                .collect();
+            let text = match format {
+                ExportFormat::Csv => row.to_csv(),
+                ExportFormat::Json => {
+                    let sep = if i == 0 { "\n" } else { ",\n" };
+                    format!("{sep}{}", json!(row))
+                }
+            };
+            Ok::<_, std::io::Error>(Bytes::from(text))
+        }
+    });
+    let (head, tail, content_type, ext) = match format {
+        ExportFormat::Csv => (CSV_HEADER, "", "text/csv; charset=utf-8", "csv"),
+        ExportFormat::Json => ("[", "\n]\n", "application/json", "json"),
+    };
+    let body = stream::once(async move { Ok(Bytes::from_static(head.as_bytes())) })
+        .chain(rows)
+        .chain(stream::once(async move {
+            Ok(Bytes::from_static(tail.as_bytes()))
+        }));
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}-tasks.{ext}\"", project.slug),
+            ),
+        ],
+        Body::from_stream(body),
+    )
+        .into_response())
+}
+
+fn to_error(e: flowstate_service::ServiceError) -> ApiError {
+    let (status, msg) = match &e {
+        flowstate_service::ServiceError::NotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
+        flowstate_service::ServiceError::InvalidInput(_) => {
+            (StatusCode::BAD_REQUEST, e.to_string())
+        }
+        flowstate_service::ServiceError::Internal(_) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        }
+    };
+    (status, Json(json!({ "error": msg })))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::Request;
+    use flowstate_core::project::CreateProject;
+    use flowstate_core::sprint::CreateSprint;
+    use flowstate_core::task::CreateTask;
+    use flowstate_core::task_pr::CreateTaskPr;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::routes::build_router;
+    use crate::test_helpers::test_state;
+
+    #[test]
+    fn csv_fields_are_quoted_and_defused() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a, b"), "\"a, b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("=SUM(A1:A2)"), "'=SUM(A1:A2)");
+        assert_eq!(csv_field("line\nbreak"), "\"line\nbreak\"");
+    }
+
+    #[tokio::test]
+    async fn exports_tasks_as_csv_and_json() {
+        let state = test_state().await;
+        let project = state
+            .db
+            .create_project(&CreateProject {
+                name: "Export".into(),
+                slug: "export".into(),
+                description: String::new(),
+                repo_url: String::new(),
+            })
+            .await
+            .unwrap();
+        let sprint = state
+            .db
+            .create_sprint(&CreateSprint {
+                project_id: project.id.clone(),
+                name: "Sprint 1".into(),
+                goal: String::new(),
+                starts_at: None,
+                ends_at: None,
+            })
+            .await
+            .unwrap();
+        let make = |title: &str| CreateTask {
+            project_id: project.id.clone(),
+            title: title.into(),
+            description: String::new(),
+            status: Status::Todo,
+            priority: Priority::High,
+            task_type: TaskType::Bug,
+            parent_id: None,
+            reviewer: String::new(),
+            due_at: None,
+            research_capability: None,
+            design_capability: None,
+            plan_capability: None,
+            build_capability: None,
+            verify_capability: None,
+        };
+        let task = state
+            .db
+            .create_task(&make("Fix login, again"))
+            .await
+            .unwrap();
+        state
+            .db
+            .update_task(
+                &task.id,
+                &flowstate_core::task::UpdateTask {
+                    sprint_id: Some(Some(sprint.id.clone())),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        state
+            .db
+            .create_task_pr(&CreateTaskPr {
+                task_id: task.id.clone(),
+                claude_run_id: None,
+                pr_url: "https://github.com/acme/app/pull/7".into(),
+                pr_number: 7,
+                branch_name: "fix-login".into(),
+                draft: false,
+            })
+            .await
+            .unwrap();
+        let old = state.db.create_task(&make("Old")).await.unwrap();
+        state.db.archive_task(&old.id).await.unwrap();
+        let app = build_router(state);
+        let export = |format: &str| {
+            Request::get(format!(
+                "/api/projects/{}/export?format={format}",
+                project.id
+            ))
+            .body(Body::empty())
+            .unwrap()
+        };
+
+        let resp = app.clone().oneshot(export("csv")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(
+            resp.headers()[header::CONTENT_DISPOSITION],
+            "attachment; filename=\"export-tasks.csv\""
+        );
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let csv = String::from_utf8(body.to_vec()).unwrap();
+        let lines: Vec<_> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(format!("{}\r\n", lines[0]), CSV_HEADER);
+        assert!(lines[1].starts_with(&format!(
+            "{},\"Fix login, again\",todo,high,bug,Sprint 1,",
+            task.id
+        )));
+        assert!(lines[1].ends_with(",https://github.com/acme/app/pull/7"));
+        assert!(lines[2].contains(",Old,") && lines[2].contains(",true,"));
+
+        let resp = app.clone().oneshot(export("json")).await.unwrap();
+        assert_eq!(resp.headers()[header::CONTENT_TYPE], "application/json");
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let rows: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(rows.as_array().unwrap().len(), 2);
+        assert_eq!(rows[0]["sprint"], "Sprint 1");
+        assert_eq!(
+            rows[0]["pr_urls"],
+            json!(["https://github.com/acme/app/pull/7"])
+        );
+        assert_eq!(rows[1]["archived"], true);
+
+        let resp = app
+            .oneshot(
+                Request::get("/api/projects/missing/export")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+}
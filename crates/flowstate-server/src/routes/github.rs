@@ -0,0 +1,639 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+    Json, Router,
+};
+use bytes::Bytes;
+use flowstate_core::task::{Status, Task, UpdateTask};
+use flowstate_core::task_pr::{PrState, TaskPr};
+use flowstate_service::TaskService;
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::Sha256;
+use tracing::{info, warn};
+
+use super::openapi::ErrorBody;
+use super::tasks::publish_task;
+use super::{scope_findings, task_links, AppState};
+use crate::{notifier, pr_checks, webhooks};
+
+/// Revision history author for changes made on GitHub's word.
+const ACTOR: &str = "github";
+
+/// Feedback phase review comments are filed under. Reviews of a PR review
+/// the build, which is what the verify distill revises against.
+const REVIEW_PHASE: &str = "verify";
+
+type ApiError = (StatusCode, Json<Value>);
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/integrations/github/webhook", post(github_webhook))
+}
+
+/// The webhook secret, read from `FLOWSTATE_GITHUB_WEBHOOK_SECRET`.
+pub(crate) fn secret_from_env() -> Option<String> {
+    std::env::var("FLOWSTATE_GITHUB_WEBHOOK_SECRET")
+        .ok()
+        .filter(|s| !s.is_empty())
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequest {
+    html_url: String,
+    #[serde(default)]
+    merged: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequestEvent {
+    action: String,
+    pull_request: PullRequest,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReviewComment {
+    body: String,
+    user: User,
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    line: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReviewCommentEvent {
+    action: String,
+    comment: ReviewComment,
+    pull_request: PullRequest,
+}
+
+#[derive(Debug, Deserialize)]
+struct Review {
+    #[serde(default)]
+    body: Option<String>,
+    user: User,
+}
+
+#[derive(Debug, Deserialize)]
+struct Repository {
+    html_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct CheckPullRequest {
+    number: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Checks {
+    #[serde(default)]
+    pull_requests: Vec<CheckPullRequest>,
+}
+
+/// A `check_suite` or `check_run` delivery; only the PRs checked matter.
+#[derive(Debug, Deserialize)]
+struct ChecksEvent {
+    action: String,
+    #[serde(alias = "check_run")]
+    check_suite: Checks,
+    repository: Repository,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReviewEvent {
+    action: String,
+    review: Review,
+    pull_request: PullRequest,
+}
+
+/// Whether `header` (`sha256=<hex>`, from `X-Hub-Signature-256`) is the
+/// HMAC-SHA256 of `body` under `secret`. Compared in constant time.
+fn signature_valid(secret: &str, body: &[u8], header: Option<&str>) -> bool {
+    let Some(hex) = header.and_then(|h| h.strip_prefix("sha256=")) else {
+        return false;
+    };
+    if hex.len() != 64 || !hex.is_ascii() {
+        return false;
+    }
+    let Ok(expected) = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+        .collect::<Result<Vec<u8>, _>>()
+    else {
+        return false;
+    };
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// Receive a GitHub webhook delivery. Pull requests not linked to a task
+/// and events this server has no use for are acknowledged and ignored, so
+/// GitHub does not report them as failed.
+#[utoipa::path(
+    post,
+    path = "/integrations/github/webhook",
+    tag = "integrations",
+    security(()),
+    params(
+        ("X-GitHub-Event" = String, Header, description = "`pull_request`, `pull_request_review`, `pull_request_review_comment`, `check_suite`, `check_run` or `ping`"),
+        ("X-Hub-Signature-256" = String, Header, description = "`sha256=<hex>` HMAC of the body under the webhook secret")
+    ),
+    request_body(content = Object, description = "GitHub's event payload"),
+    responses(
+        (status = 200, body = Object),
+        (status = 401, description = "Missing or invalid signature", body = ErrorBody),
+        (status = 404, description = "No webhook secret is configured", body = ErrorBody)
+    )
+)]
+async fn github_webhook(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<Value>, ApiError> {
+    let Some(secret) = state.github_webhook_secret.as_deref() else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({"error": "GitHub webhook receiver is not configured"})),
+        ));
+    };
+    let signature = headers
+        .get("x-hub-signature-256")
+        .and_then(|v| v.to_str().ok());
+    if !signature_valid(secret, &body, signature) {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            Json(json!({"error": "invalid signature"})),
+        ));
+    }
+
+    let event = headers
+        .get("x-github-event")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    match event {
+        "ping" => Ok(Json(json!({"ok": true}))),
+        "pull_request" => pull_request(&state, parse(&body)?).await,
+        "check_suite" | "check_run" => checks_completed(&state, parse(&body)?).await,
+        "pull_request_review_comment" => {
+            let event: ReviewCommentEvent = parse(&body)?;
+            if event.action != "created" {
+                return Ok(ignored("comment not created"));
+            }
+            let comment = event.comment;
+            let feedback = match (comment.path, comment.line) {
+                (Some(path), Some(line)) => format!("{path}:{line}: {}", comment.body),
+                (Some(path), None) => format!("{path}: {}", comment.body),
+                _ => comment.body,
+            };
+            record_review(&state, &event.pull_request, &feedback, &comment.user).await
+        }
+        "pull_request_review" => {
+            let event: ReviewEvent = parse(&body)?;
+            match event.review.body.filter(|b| !b.trim().is_empty()) {
+                Some(body) if event.action == "submitted" => {
+                    record_review(&state, &event.pull_request, &body, &event.review.user).await
+                }
+                _ => Ok(ignored("review has no summary")),
+            }
+        }
+        _ => Ok(ignored("unhandled event")),
+    }
+}
+
+fn parse<T: serde::de::DeserializeOwned>(body: &[u8]) -> Result<T, ApiError> {
+    serde_json::from_slice(body).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": format!("invalid payload: {e}")})),
+        )
+    })
+}
+
+fn ignored(reason: &str) -> Json<Value> {
+    Json(json!({"ignored": reason}))
+}
+
+/// The task PR at `pr_url`, or `None` when no task links to it.
+async fn linked_pr(state: &AppState, pr_url: &str) -> Result<Option<TaskPr>, ApiError> {
+    match state.db.get_task_pr_by_url(pr_url).await {
+        Ok(task_pr) => Ok(Some(task_pr)),
+        Err(flowstate_db::DbError::NotFound(_)) => Ok(None),
+        Err(e) => Err(internal(e)),
+    }
+}
+
+fn internal(e: impl std::fmt::Display) -> ApiError {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({"error": e.to_string()})),
+    )
+}
+
+/// Track a linked PR being merged, closed or reopened.
+async fn pull_request(state: &AppState, event: PullRequestEvent) -> Result<Json<Value>, ApiError> {
+    let pr_state = match event.action.as_str() {
+        "closed" if event.pull_request.merged => PrState::Merged,
+        "closed" => PrState::Closed,
+        "reopened" => PrState::Open,
+        _ => return Ok(ignored("unhandled action")),
+    };
+    let Some(task_pr) = linked_pr(state, &event.pull_request.html_url).await? else {
+        return Ok(ignored("not a linked pull request"));
+    };
+    let (task_pr, task) = apply_pr_state(state, &task_pr, pr_state, ACTOR).await?;
+    Ok(Json(json!({"pr": task_pr, "task": task})))
+}
+
+/// Refresh the CI checks of the linked PRs a completed check suite or run
+/// was for.
+async fn checks_completed(state: &AppState, event: ChecksEvent) -> Result<Json<Value>, ApiError> {
+    if event.action != "completed" {
+        return Ok(ignored("checks not completed"));
+    }
+    let mut refreshed = Vec::new();
+    for pr in &event.check_suite.pull_requests {
+        let url = format!("{}/pull/{}", event.repository.html_url, pr.number);
+        let Some(task_pr) = linked_pr(state, &url).await? else {
+            continue;
+        };
+        match pr_checks::refresh(state, &task_pr).await {
+            Ok((task_pr, _)) => refreshed.push(task_pr),
+            Err(e) => warn!("checks for {url}: {e}"),
+        }
+    }
+    if refreshed.is_empty() {
+        return Ok(ignored("no linked pull request refreshed"));
+    }
+    Ok(Json(json!({"prs": refreshed})))
+}
+
+/// Record a task PR's new state and move its task along. A merge moves the
+/// task to Done, unless the usual Done gates refuse. Closing without a
+/// merge sends a task in Verify back to Build when none of its other PRs
+/// is still open. `actor` is credited with the status change.
+pub(crate) async fn apply_pr_state(
+    state: &AppState,
+    task_pr: &TaskPr,
+    pr_state: PrState,
+    actor: &str,
+) -> Result<(TaskPr, Task), ApiError> {
+    let task_pr = state
+        .db
+        .set_task_pr_state(&task_pr.id, pr_state)
+        .await
+        .map_err(internal)?;
+    let task = state
+        .service
+        .get_task(&task_pr.task_id)
+        .await
+        .map_err(internal)?;
+
+    let target = match pr_state {
+        PrState::Merged if !matches!(task.status, Status::Done | Status::Cancelled) => {
+            let gates = match scope_findings::check_done_gate(state, &task.id).await {
+                Ok(()) => task_links::check_blockers(state, &task.id).await,
+                Err(e) => Err(e),
+            };
+            match gates {
+                Ok(()) => Some(Status::Done),
+                Err((_, Json(reason))) => {
+                    info!(
+                        "PR {} merged but task {} stays {}: {}",
+                        task_pr.pr_url,
+                        task.id,
+                        task.status.as_str(),
+                        reason["error"]
+                    );
+                    None
+                }
+            }
+        }
+        PrState::Closed if task.status == Status::Verify => {
+            let others_open = state
+                .db
+                .list_task_prs(&task.id)
+                .await
+                .map_err(internal)?
+                .iter()
+                .any(|pr| pr.id != task_pr.id && pr.state == PrState::Open);
+            (!others_open).then_some(Status::Build)
+        }
+        _ => None,
+    };
+
+    let task = match target {
+        Some(status) => update_status(state, &task, status, actor).await?,
+        None => task,
+    };
+    Ok((task_pr, task))
+}
+
+async fn update_status(
+    state: &AppState,
+    task: &Task,
+    status: Status,
+    actor: &str,
+) -> Result<Task, ApiError> {
+    let update = UpdateTask {
+        status: Some(status),
+        actor: Some(actor.into()),
+        ..Default::default()
+    };
+    let updated = state
+        .service
+        .update_task(&task.id, &update)
+        .await
+        .map_err(internal)?;
+    publish_task(state, &updated);
+    webhooks::task_changed(state, task, &updated).await;
+    notifier::task_changed(state, task, &updated).await;
+    Ok(updated)
+}
+
+/// File a review comment on a linked PR as feedback on its task.
+async fn record_review(
+    state: &AppState,
+    pr: &PullRequest,
+    feedback: &str,
+    user: &User,
+) -> Result<Json<Value>, ApiError> {
+    let Some(task_pr) = linked_pr(state, &pr.html_url).await? else {
+        return Ok(ignored("not a linked pull request"));
+    };
+    let entry = state
+        .db
+        .add_feedback_entry(
+            &task_pr.task_id,
+            REVIEW_PHASE,
+            feedback,
+            &format!("github:{}", user.login),
+        )
+        .await
+        .map_err(|e| {
+            warn!("github: recording review on {}: {e}", pr.html_url);
+            internal(e)
+        })?;
+    Ok(Json(json!({"feedback": entry})))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::test_state;
+    use axum::body::Body;
+    use axum::http::Request;
+    use flowstate_core::task_pr::CreateTaskPr;
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    const SECRET: &str = "github-secret";
+    const PR_URL: &str = "https://github.com/acme/app/pull/7";
+
+    async fn app_with_task(status: &str) -> (axum::Router, AppState, String) {
+        let mut inner = Arc::try_unwrap(test_state().await).ok().unwrap();
+        inner.github_webhook_secret = Some(SECRET.into());
+        let state = Arc::new(inner);
+        let app = crate::routes::build_router(state.clone());
+
+        let project = state
+            .db
+            .create_project(&flowstate_core::project::CreateProject {
+                name: "App".into(),
+                slug: "app".into(),
+                description: String::new(),
+                repo_url: "https://github.com/acme/app".into(),
+            })
+            .await
+            .unwrap();
+        let task = state
+            .db
+            .create_task(&flowstate_core::task::CreateTask {
+                project_id: project.id,
+                title: "Add export".into(),
+                description: String::new(),
+                status: Status::parse_str(status).unwrap(),
+                priority: flowstate_core::task::Priority::Medium,
+                task_type: flowstate_core::task::TaskType::Feature,
+                parent_id: None,
+                reviewer: String::new(),
+                research_capability: None,
+                design_capability: None,
+                plan_capability: None,
+                build_capability: None,
+                verify_capability: None,
+                due_at: None,
+            })
+            .await
+            .unwrap();
+        state
+            .db
+            .create_task_pr(&CreateTaskPr {
+                task_id: task.id.clone(),
+                claude_run_id: None,
+                pr_url: PR_URL.into(),
+                pr_number: 7,
+                branch_name: "flowstate/add-export".into(),
+                draft: false,
+            })
+            .await
+            .unwrap();
+        (app, state, task.id)
+    }
+
+    async fn deliver(app: &axum::Router, event: &str, payload: Value) -> (StatusCode, Value) {
+        let body = payload.to_string();
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/integrations/github/webhook")
+                    .header("x-github-event", event)
+                    .header(
+                        "x-hub-signature-256",
+                        webhooks::sign(SECRET, body.as_bytes()),
+                    )
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = resp.status();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    fn closed(merged: bool) -> Value {
+        json!({
+            "action": "closed",
+            "pull_request": {"html_url": PR_URL, "merged": merged},
+        })
+    }
+
+    #[test]
+    fn signatures() {
+        let sig = webhooks::sign("k", b"body");
+        assert!(signature_valid("k", b"body", Some(&sig)));
+        assert!(!signature_valid("k", b"other", Some(&sig)));
+        assert!(!signature_valid("other", b"body", Some(&sig)));
+        assert!(!signature_valid("k", b"body", None));
+        assert!(!signature_valid("k", b"body", Some("sha256=zz")));
+        assert!(!signature_valid(
+            "k",
+            b"body",
+            Some(&sig.replace("sha256=", "sha1="))
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_bad_signatures_and_unconfigured_receivers() {
+        let (app, _, _) = app_with_task("verify").await;
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/integrations/github/webhook")
+                    .header("x-github-event", "ping")
+                    .header("x-hub-signature-256", webhooks::sign("wrong", b"{}"))
+                    .body(Body::from("{}"))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let (status, _) = deliver(&app, "ping", json!({"zen": "hi"})).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let unconfigured = crate::test_helpers::test_router().await;
+        let (status, _) = deliver(&unconfigured, "ping", json!({})).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn merged_pr_finishes_the_task() {
+        let (app, state, task_id) = app_with_task("verify").await;
+        let (status, body) = deliver(&app, "pull_request", closed(true)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["pr"]["state"], "merged");
+        assert_eq!(body["task"]["status"], "done");
+
+        let revisions = state.db.list_task_revisions(&task_id).await.unwrap();
+        assert_eq!(revisions[0].actor, ACTOR);
+
+        // Unlinked PRs are acknowledged and left alone
+        let (status, body) = deliver(
+            &app,
+            "pull_request",
+            json!({
+                "action": "closed",
+                "pull_request": {"html_url": "https://github.com/acme/app/pull/8", "merged": true},
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["ignored"], "not a linked pull request");
+    }
+
+    #[tokio::test]
+    async fn closed_pr_sends_the_task_back_to_build() {
+        let (app, state, task_id) = app_with_task("verify").await;
+        let (_, body) = deliver(&app, "pull_request", closed(false)).await;
+        assert_eq!(body["pr"]["state"], "closed");
+        assert_eq!(body["task"]["status"], "build");
+
+        let (_, body) = deliver(
+            &app,
+            "pull_request",
+            json!({"action": "reopened", "pull_request": {"html_url": PR_URL}}),
+        )
+        .await;
+        assert_eq!(body["pr"]["state"], "open");
+        let task = state.db.get_task(&task_id).await.unwrap();
+        assert_eq!(task.status, Status::Build);
+    }
+
+    #[tokio::test]
+    async fn check_deliveries_need_a_repo_token() {
+        let (app, _, _) = app_with_task("verify").await;
+        let suite = |action: &str| {
+            json!({
+                "action": action,
+                "check_suite": {"pull_requests": [{"number": 7}, {"number": 8}]},
+                "repository": {"html_url": "https://github.com/acme/app"},
+            })
+        };
+        let (status, body) = deliver(&app, "check_suite", suite("requested")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["ignored"], "checks not completed");
+
+        // PR 7 is linked, but the project has no token to read checks with
+        let (status, body) = deliver(&app, "check_suite", suite("completed")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["ignored"], "no linked pull request refreshed");
+    }
+
+    #[tokio::test]
+    async fn review_comments_become_feedback() {
+        let (app, state, task_id) = app_with_task("verify").await;
+        let (status, _) = deliver(
+            &app,
+            "pull_request_review_comment",
+            json!({
+                "action": "created",
+                "comment": {
+                    "body": "Handle an empty export",
+                    "user": {"login": "octocat"},
+                    "path": "src/export.rs",
+                    "line": 12,
+                },
+                "pull_request": {"html_url": PR_URL},
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        deliver(
+            &app,
+            "pull_request_review",
+            json!({
+                "action": "submitted",
+                "review": {"body": "Close, a few nits", "user": {"login": "hubot"}},
+                "pull_request": {"html_url": PR_URL},
+            }),
+        )
+        .await;
+        // Approvals without a summary have nothing to keep
+        deliver(
+            &app,
+            "pull_request_review",
+            json!({
+                "action": "submitted",
+                "review": {"body": null, "user": {"login": "hubot"}},
+                "pull_request": {"html_url": PR_URL},
+            }),
+        )
+        .await;
+
+        let history = state.db.list_feedback_history(&task_id).await.unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].phase, "verify");
+        assert_eq!(history[1].author, "github:octocat");
+        assert_eq!(
+            history[1].feedback,
+            "src/export.rs:12: Handle an empty export"
+        );
+        assert_eq!(history[0].feedback, "Close, a few nits");
+    }
+}
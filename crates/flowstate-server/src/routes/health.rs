@@ -1,12 +1,28 @@
-use axum::{extract::State, routing::get, Json, Router};
+use std::future::Future;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
 use chrono::Utc;
+use flowstate_service::{RunnerStatus, StuckRun, SystemStatus};
+use serde::Serialize;
 use serde_json::{json, Value};
 
 use super::AppState;
 
+/// How long a readiness check may take before it counts as failed.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Key probed in the object store. It need not exist; the lookup only has
+/// to succeed.
+const STORE_PROBE_KEY: &str = "health/ready-probe";
+
 /// Public routes (no auth required).
 pub fn routes() -> Router<AppState> {
-    Router::new().route("/api/health", get(health))
+    Router::new()
+        .route("/api/health", get(health))
+        .route("/api/health/live", get(live))
+        .route("/api/health/ready", get(ready))
 }
 
 /// Protected routes (auth required).
@@ -14,17 +30,201 @@ pub fn protected_routes() -> Router<AppState> {
     Router::new().route("/api/status", get(system_status))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/health",
+    tag = "system",
+    security(()),
+    responses((status = 200, description = "`{\"status\": \"ok\"}`", body = Object))
+)]
 async fn health() -> Json<Value> {
     Json(json!({ "status": "ok" }))
 }
 
+/// Liveness: the process is up and serving requests. Dependencies are not
+/// checked, so an outage elsewhere does not get the server restarted.
+#[utoipa::path(
+    get,
+    path = "/api/health/live",
+    tag = "system",
+    security(()),
+    responses((status = 200, description = "`{\"status\": \"ok\"}`", body = Object))
+)]
+async fn live() -> Json<Value> {
+    Json(json!({ "status": "ok" }))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    Error,
+    /// No answer within five seconds.
+    Timeout,
+}
+
+/// One dependency's answer to a readiness check.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct DependencyCheck {
+    /// `database`, `store` or `pod_manager`.
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub latency_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct Readiness {
+    /// `ok`, `unavailable` when a dependency failed, or `shutting_down`.
+    pub status: &'static str,
+    /// Empty while shutting down.
+    pub checks: Vec<DependencyCheck>,
+}
+
+/// Readiness: whether the server can do useful work. Checks the database,
+/// the object store and, when the pod manager is configured, the RunPod
+/// API, all at once. Answers 503 if any fails or the server is shutting
+/// down. Failure details are logged rather than returned, since the route
+/// needs no key.
+#[utoipa::path(
+    get,
+    path = "/api/health/ready",
+    tag = "system",
+    security(()),
+    responses(
+        (status = 200, body = Readiness),
+        (status = 503, description = "A dependency failed, or the server is shutting down", body = Readiness)
+    )
+)]
+async fn ready(State(state): State<AppState>) -> (StatusCode, Json<Readiness>) {
+    if state.shutdown.is_cancelled() {
+        let readiness = Readiness {
+            status: "shutting_down",
+            checks: Vec::new(),
+        };
+        return (StatusCode::SERVICE_UNAVAILABLE, Json(readiness));
+    }
+    let (database, store, pod_manager) = tokio::join!(
+        check("database", async {
+            state.db.count_queued_runs().await.map(|_| ())
+        }),
+        check("store", async {
+            state.store.exists(STORE_PROBE_KEY).await.map(|_| ())
+        }),
+        async {
+            match &state.pod_api {
+                Some(api) => Some(check("pod_manager", api.ping()).await),
+                None => None,
+            }
+        },
+    );
+    let checks: Vec<_> = [Some(database), Some(store), pod_manager]
+        .into_iter()
+        .flatten()
+        .collect();
+    if checks.iter().all(|c| c.status == CheckStatus::Ok) {
+        let readiness = Readiness {
+            status: "ok",
+            checks,
+        };
+        (StatusCode::OK, Json(readiness))
+    } else {
+        let readiness = Readiness {
+            status: "unavailable",
+            checks,
+        };
+        (StatusCode::SERVICE_UNAVAILABLE, Json(readiness))
+    }
+}
+
+/// Run one dependency check under [`CHECK_TIMEOUT`], logging why it failed.
+async fn check<E: std::fmt::Display>(
+    name: &'static str,
+    probe: impl Future<Output = Result<(), E>>,
+) -> DependencyCheck {
+    let started = Instant::now();
+    let status = match tokio::time::timeout(CHECK_TIMEOUT, probe).await {
+        Ok(Ok(())) => CheckStatus::Ok,
+        Ok(Err(e)) => {
+            tracing::warn!("readiness check {name} failed: {e}");
+            CheckStatus::Error
+        }
+        Err(_) => {
+            tracing::warn!("readiness check {name} timed out");
+            CheckStatus::Timeout
+        }
+    };
+    DependencyCheck {
+        name,
+        status,
+        latency_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/status",
+    tag = "system",
+    responses((status = 200, body = SystemStatus))
+)]
+async fn system_status(State(state): State<AppState>) -> Json<SystemStatus> {
+    let now = Utc::now();
+    let stale_threshold = chrono::Duration::minutes(5);
+    let connected_threshold = chrono::Duration::seconds(30);
+
+    let runners: Vec<RunnerStatus> = {
+        let mut runners_lock = state.runners.lock().unwrap();
+
+        // Prune runners not seen in 5 minutes
+        runners_lock.retain(|_, info| now - info.last_seen < stale_threshold);
+
+        runners_lock
+            .values()
+            .map(|info| RunnerStatus {
+                runner_id: info.runner_id.clone(),
+                last_seen: info.last_seen.to_rfc3339(),
+                connected: now - info.last_seen < connected_threshold,
+            })
+            .collect()
+    };
+
+    // Find runs that may be stuck (running for more than 15 minutes)
+    let stuck_threshold = now - chrono::Duration::minutes(15);
+    let stuck_runs: Vec<StuckRun> = state
+        .db
+        .find_stale_running_runs(stuck_threshold)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|run| StuckRun {
+            running_for_seconds: (now - run.started_at).num_seconds(),
+            started_at: run.started_at.to_rfc3339(),
+            id: run.id,
+            task_id: run.task_id,
+            action: run.action,
+            status: run.status,
+            runner_id: run.runner_id,
+        })
+        .collect();
+
+    let db_maintenance = state.db_maintenance.lock().unwrap().clone();
+
+    Json(SystemStatus {
+        server: "ok".into(),
+        maintenance: state.maintenance.load(Ordering::Relaxed),
+        runners,
+        stuck_runs,
+        db_maintenance,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use axum::body::Body;
     use axum::http::{Request, StatusCode};
     use tower::ServiceExt;
 
-    use crate::test_helpers::test_router;
+    use crate::routes::build_router;
+    use crate::test_helpers::{test_router, test_state};
 
     #[tokio::test]
     async fn health_returns_ok() {
@@ -46,6 +246,41 @@ mod tests {
         assert_eq!(json["status"], "ok");
     }
 
+    #[tokio::test]
+    async fn readiness_checks_dependencies() {
+        let state = test_state().await;
+        let app = build_router(state.clone());
+        let get = |uri: &'static str| {
+            app.clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        };
+
+        let resp = get("/api/health/live").await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = get("/api/health/ready").await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["status"], "ok");
+        let names: Vec<_> = json["checks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| (c["name"].as_str().unwrap(), c["status"].as_str().unwrap()))
+            .collect();
+        assert_eq!(names, vec![("database", "ok"), ("store", "ok")]);
+
+        // Taken out of rotation while draining, though still alive
+        state.shutdown.cancel();
+        let resp = get("/api/health/ready").await.unwrap();
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let resp = get("/api/health/live").await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
     #[tokio::test]
     async fn system_status_with_registered_runner() {
         use axum::http::Method;
@@ -113,58 +348,6 @@ mod tests {
         assert_eq!(json["server"], "ok");
         assert_eq!(json["runners"].as_array().unwrap().len(), 0);
         assert_eq!(json["stuck_runs"].as_array().unwrap().len(), 0);
+        assert!(json["db_maintenance"].is_null());
     }
 }
-
-async fn system_status(State(state): State<AppState>) -> Json<Value> {
-    let now = Utc::now();
-    let stale_threshold = chrono::Duration::minutes(5);
-    let connected_threshold = chrono::Duration::seconds(30);
-
-    let runners: Vec<Value> = {
-        let mut runners_lock = state.runners.lock().unwrap();
-
-        // Prune runners not seen in 5 minutes
-        runners_lock.retain(|_, info| now - info.last_seen < stale_threshold);
-
-        runners_lock
-            .values()
-            .map(|info| {
-                let connected = now - info.last_seen < connected_threshold;
-                json!({
-                    "runner_id": info.runner_id,
-                    "last_seen": info.last_seen.to_rfc3339(),
-                    "connected": connected,
-                })
-            })
-            .collect()
-    };
-
-    // Find runs that may be stuck (running for more than 15 minutes)
-    let stuck_threshold = now - chrono::Duration::minutes(15);
-    let stuck_runs: Vec<Value> = state
-        .db
-        .find_stale_running_runs(stuck_threshold)
-        .await
-        .unwrap_or_default()
-        .iter()
-        .map(|run| {
-            let running_for = (now - run.started_at).num_seconds();
-            json!({
-                "id": run.id,
-                "task_id": run.task_id,
-                "action": run.action.as_str(),
-                "status": run.status.as_str(),
-                "started_at": run.started_at.to_rfc3339(),
-                "running_for_seconds": running_for,
-                "runner_id": run.runner_id,
-            })
-        })
-        .collect();
-
-    Json(json!({
-        "server": "ok",
-        "runners": runners,
-        "stuck_runs": stuck_runs,
-    }))
-}
@@ -0,0 +1,517 @@
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::post,
+    Extension, Json, Router,
+};
+use flowstate_core::project::ProviderType;
+use flowstate_core::task::{CreateTask, Priority, Status, Task, TaskType, UpdateTask};
+use flowstate_service::TaskService;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::info;
+
+use super::openapi::ErrorBody;
+use super::tasks::publish_task;
+use super::AppState;
+use crate::auth::Caller;
+use crate::{crypto, forge};
+
+/// Issues fetched per request; GitHub's maximum.
+const PAGE_SIZE: usize = 100;
+
+/// Color for labels GitHub reports without one.
+const DEFAULT_LABEL_COLOR: &str = "#808080";
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+type ApiError = (StatusCode, Json<Value>);
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route(
+        "/api/projects/{id}/import/github-issues",
+        post(import_github_issues),
+    )
+}
+
+#[derive(Debug, Deserialize)]
+struct Issue {
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    html_url: String,
+    #[serde(default)]
+    labels: Vec<IssueLabel>,
+    #[serde(default)]
+    assignees: Vec<GitHubUser>,
+    /// Present when the "issue" is a pull request.
+    #[serde(default)]
+    pull_request: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssueLabel {
+    name: String,
+    #[serde(default)]
+    color: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitHubUser {
+    login: String,
+}
+
+/// What an import did.
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct ImportReport {
+    /// Tasks created for issues not imported before.
+    pub created: Vec<Task>,
+    /// Issues skipped because an earlier import already created their task.
+    pub skipped: usize,
+    /// GitHub logins assigned to imported issues that match no user.
+    pub unmatched_assignees: Vec<String>,
+}
+
+/// The issues endpoint of the GitHub repository at `repo_url`. Accepts
+/// HTTPS and `git@host:owner/repo` URLs.
+fn issues_endpoint(repo_url: &str) -> Option<String> {
+    forge::github_repo_endpoint(repo_url).map(|e| format!("{e}/issues"))
+}
+
+/// Every open issue in the repository, oldest first, pull requests
+/// excluded.
+async fn fetch_open_issues(endpoint: &str, token: &str) -> Result<Vec<Issue>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent("flowstate")
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut issues = Vec::new();
+    for page in 1.. {
+        let batch: Vec<Issue> = client
+            .get(endpoint)
+            .bearer_auth(token)
+            .header("accept", "application/vnd.github+json")
+            .header("x-github-api-version", "2022-11-28")
+            .query(&[
+                ("state", "open"),
+                ("sort", "created"),
+                ("direction", "asc"),
+                ("per_page", &PAGE_SIZE.to_string()),
+                ("page", &page.to_string()),
+            ])
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| e.to_string())?
+            .json()
+            .await
+            .map_err(|e| format!("unexpected response: {e}"))?;
+        let last = batch.len() < PAGE_SIZE;
+        issues.extend(batch.into_iter().filter(|i| i.pull_request.is_none()));
+        if last {
+            break;
+        }
+    }
+    Ok(issues)
+}
+
+fn task_description(issue: &Issue) -> String {
+    match issue.body.as_deref().map(str::trim) {
+        Some(body) if !body.is_empty() => {
+            format!("{body}\n\nImported from {}", issue.html_url)
+        }
+        _ => format!("Imported from {}", issue.html_url),
+    }
+}
+
+/// POST /api/projects/{id}/import/github-issues — create a task for every
+/// open issue in the project's GitHub repository, using its repo token.
+///
+/// Issues carry their labels over (created in the project as needed, and a
+/// `bug` label makes the task a bug) and their first assignee whose login
+/// matches a user's name. Issues imported before are skipped, even if
+/// their task has since been deleted, so the import can be run again to
+/// pick up new issues.
+#[utoipa::path(
+    post,
+    path = "/api/projects/{id}/import/github-issues",
+    tag = "projects",
+    responses(
+        (status = 200, body = ImportReport),
+        (status = 400, description = "The project has no GitHub repository or repo token", body = ErrorBody),
+        (status = 404, body = ErrorBody),
+        (status = 502, description = "GitHub refused or failed the request", body = ErrorBody)
+    )
+)]
+async fn import_github_issues(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    caller: Option<Extension<Caller>>,
+) -> Result<Json<Value>, ApiError> {
+    let project = state.service.get_project(&id).await.map_err(to_error)?;
+    if project.provider_type.unwrap_or_default() != ProviderType::Github {
+        return Err(bad_request("project is not hosted on GitHub"));
+    }
+    let Some(endpoint) = issues_endpoint(&project.repo_url) else {
+        return Err(bad_request("project repo_url is not a GitHub repository"));
+    };
+    let Some(encrypted) = project.repo_token.as_deref() else {
+        return Err(bad_request("project has no repo token"));
+    };
+    let token = crypto::decrypt(&state.encryption_key, encrypted).map_err(|e| {
+        to_error(flowstate_service::ServiceError::Internal(format!(
+            "decrypt: {e}"
+        )))
+    })?;
+
+    let issues = fetch_open_issues(&endpoint, &token).await.map_err(|e| {
+        (
+            StatusCode::BAD_GATEWAY,
+            Json(json!({ "error": format!("GitHub: {e}") })),
+        )
+    })?;
+    let users = state.service.list_users().await.map_err(to_error)?;
+    let actor = caller.map(|Extension(Caller(c))| c);
+
+    let mut report = ImportReport {
+        created: Vec::new(),
+        skipped: 0,
+        unmatched_assignees: Vec::new(),
+    };
+    for issue in issues {
+        if state
+            .db
+            .find_imported_task(&project.id, &issue.html_url)
+            .await
+            .map_err(db_error)?
+            .is_some()
+        {
+            report.skipped += 1;
+            continue;
+        }
+
+        let is_bug = issue
+            .labels
+            .iter()
+            .any(|l| l.name.eq_ignore_ascii_case("bug"));
+        let task = state
+            .service
+            .create_task(&CreateTask {
+                project_id: project.id.clone(),
+                title: issue.title.clone(),
+                description: task_description(&issue),
+                status: Status::Todo,
+                priority: Priority::Medium,
+                task_type: if is_bug {
+                    TaskType::Bug
+                } else {
+                    TaskType::Feature
+                },
+                parent_id: None,
+                reviewer: String::new(),
+                due_at: None,
+                research_capability: None,
+                design_capability: None,
+                plan_capability: None,
+                build_capability: None,
+                verify_capability: None,
+            })
+            .await
+            .map_err(to_error)?;
+        state
+            .db
+            .record_task_import(&project.id, &issue.html_url, &task.id)
+            .await
+            .map_err(db_error)?;
+
+        for label in &issue.labels {
+            let color = label
+                .color
+                .as_deref()
+                .filter(|c| !c.is_empty())
+                .map(|c| format!("#{c}"))
+                .unwrap_or_else(|| DEFAULT_LABEL_COLOR.to_string());
+            let label = state
+                .db
+                .ensure_label(&project.id, &label.name, &color)
+                .await
+                .map_err(db_error)?;
+            state
+                .db
+                .add_task_label(&task.id, &label.id)
+                .await
+                .map_err(db_error)?;
+        }
+
+        let mut assignee = None;
+        for login in issue.assignees.iter().map(|a| &a.login) {
+            match users.iter().find(|u| u.name.eq_ignore_ascii_case(login)) {
+                Some(user) if assignee.is_none() => assignee = Some(user.id.clone()),
+                Some(_) => {}
+                None if !report.unmatched_assignees.contains(login) => {
+                    report.unmatched_assignees.push(login.clone())
+                }
+                None => {}
+            }
+        }
+        let task = match assignee {
+            Some(user_id) => state
+                .service
+                .update_task(
+                    &task.id,
+                    &UpdateTask {
+                        assignee_id: Some(Some(user_id)),
+                        actor: actor.clone(),
+                        ..Default::default()
+                    },
+                )
+                .await
+                .map_err(to_error)?,
+            None => task,
+        };
+        publish_task(&state, &task);
+        report.created.push(task);
+    }
+
+    info!(
+        "imported {} GitHub issues into project {} ({} already imported)",
+        report.created.len(),
+        project.slug,
+        report.skipped
+    );
+    Ok(Json(json!(report)))
+}
+
+fn bad_request(msg: &str) -> ApiError {
+    (StatusCode::BAD_REQUEST, Json(json!({ "error": msg })))
+}
+
+fn db_error(e: flowstate_db::DbError) -> ApiError {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({ "error": e.to_string() })),
+    )
+}
+
+fn to_error(e: flowstate_service::ServiceError) -> ApiError {
+    let (status, msg) = match &e {
+        flowstate_service::ServiceError::NotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
+        flowstate_service::ServiceError::InvalidInput(_) => {
+            (StatusCode::BAD_REQUEST, e.to_string())
+        }
+        flowstate_service::ServiceError::Internal(_) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        }
+    };
+    (status, Json(json!({ "error": msg })))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use axum::body::Body;
+    use axum::extract::{Query, Request};
+    use axum::http::{HeaderMap, Method};
+    use axum::routing::get;
+    use flowstate_core::user::CreateUser;
+    use std::collections::HashMap;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::routes::build_router;
+    use crate::test_helpers::test_state;
+
+    #[test]
+    fn issues_endpoint_from_repo_url() {
+        assert_eq!(
+            issues_endpoint("https://github.com/acme/app.git").as_deref(),
+            Some("https://api.github.com/repos/acme/app/issues")
+        );
+        assert_eq!(
+            issues_endpoint("git@github.com:acme/app.git").as_deref(),
+            Some("https://api.github.com/repos/acme/app/issues")
+        );
+        assert_eq!(
+            issues_endpoint("https://git.example.com/acme/app/").as_deref(),
+            Some("https://git.example.com/api/v3/repos/acme/app/issues")
+        );
+        assert_eq!(issues_endpoint(""), None);
+        assert_eq!(issues_endpoint("https://github.com/acme"), None);
+    }
+
+    /// A GitHub stand-in serving one open issue, one pull request and, on a
+    /// second import, a new issue. Records the Authorization headers seen.
+    async fn fake_github(issues: Arc<Mutex<Vec<Value>>>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let auth = Arc::new(Mutex::new(Vec::new()));
+        let seen = auth.clone();
+        let app = axum::Router::new().route(
+            "/api/v3/repos/acme/app/issues",
+            get(
+                move |headers: HeaderMap, Query(q): Query<HashMap<String, String>>| {
+                    let issues = issues.clone();
+                    let seen = seen.clone();
+                    async move {
+                        seen.lock()
+                            .unwrap()
+                            .push(headers["authorization"].to_str().unwrap().to_string());
+                        assert_eq!(q["state"], "open");
+                        let page = if q["page"] == "1" {
+                            issues.lock().unwrap().clone()
+                        } else {
+                            Vec::new()
+                        };
+                        Json(page)
+                    }
+                },
+            ),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/acme/app", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (url, auth)
+    }
+
+    fn issue(number: u64, title: &str, labels: Value, assignees: &[&str]) -> Value {
+        json!({
+            "number": number,
+            "title": title,
+            "body": "Steps to reproduce",
+            "html_url": format!("https://github.com/acme/app/issues/{number}"),
+            "labels": labels,
+            "assignees": assignees.iter().map(|l| json!({"login": l})).collect::<Vec<_>>(),
+        })
+    }
+
+    #[tokio::test]
+    async fn imports_open_issues_once() {
+        let issues = Arc::new(Mutex::new(vec![
+            issue(
+                1,
+                "Crash on save",
+                json!([{"name": "bug", "color": "d73a4a"}, {"name": "ui"}]),
+                &["Octocat", "stranger"],
+            ),
+            json!({
+                "number": 2,
+                "title": "A pull request",
+                "html_url": "https://github.com/acme/app/pull/2",
+                "pull_request": {},
+            }),
+        ]));
+        let (repo_url, auth) = fake_github(issues.clone()).await;
+        let state = test_state().await;
+        let project = state
+            .db
+            .create_project(&flowstate_core::project::CreateProject {
+                name: "App".into(),
+                slug: "app".into(),
+                description: String::new(),
+                repo_url,
+            })
+            .await
+            .unwrap();
+        state
+            .db
+            .update_project(
+                &project.id,
+                &flowstate_core::project::UpdateProject {
+                    repo_token: Some(crypto::encrypt(&state.encryption_key, "ghp_test").unwrap()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let octocat = state
+            .db
+            .create_user(&CreateUser {
+                name: "octocat".into(),
+                email: String::new(),
+            })
+            .await
+            .unwrap();
+        let app = build_router(state.clone());
+        let import = || {
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("/api/projects/{}/import/github-issues", project.id))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let resp = app.clone().oneshot(import()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let report: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(report["created"].as_array().unwrap().len(), 1);
+        assert_eq!(report["skipped"], 0);
+        assert_eq!(report["unmatched_assignees"], json!(["stranger"]));
+        let task = &report["created"][0];
+        assert_eq!(task["title"], "Crash on save");
+        assert_eq!(task["task_type"], "bug");
+        assert_eq!(task["assignee_id"], json!(octocat.id));
+        assert!(task["description"]
+            .as_str()
+            .unwrap()
+            .ends_with("Imported from https://github.com/acme/app/issues/1"));
+        let labels = state
+            .db
+            .list_task_labels(task["id"].as_str().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(
+            labels
+                .iter()
+                .map(|l| (l.name.as_str(), l.color.as_str()))
+                .collect::<Vec<_>>(),
+            vec![("bug", "#d73a4a"), ("ui", DEFAULT_LABEL_COLOR)]
+        );
+        assert_eq!(auth.lock().unwrap()[0], "Bearer ghp_test");
+
+        issues
+            .lock()
+            .unwrap()
+            .push(issue(3, "Add dark mode", json!([]), &[]));
+        let resp = app.clone().oneshot(import()).await.unwrap();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let report: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(report["skipped"], 1);
+        let created = report["created"].as_array().unwrap();
+        assert_eq!(created.len(), 1);
+        assert_eq!(created[0]["title"], "Add dark mode");
+        assert_eq!(created[0]["task_type"], "feature");
+    }
+
+    #[tokio::test]
+    async fn import_requires_repo_token() {
+        let state = test_state().await;
+        let project = state
+            .db
+            .create_project(&flowstate_core::project::CreateProject {
+                name: "App".into(),
+                slug: "app".into(),
+                description: String::new(),
+                repo_url: "https://github.com/acme/app".into(),
+            })
+            .await
+            .unwrap();
+        let resp = build_router(state)
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(format!("/api/projects/{}/import/github-issues", project.id))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+}
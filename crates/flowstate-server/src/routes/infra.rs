@@ -1,14 +1,22 @@
+use std::collections::{BTreeMap, HashSet};
+
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
-    routing::{get, post, put},
+    routing::{get, patch, post, put},
     Json, Router,
 };
+use chrono::{DateTime, Utc};
+use flowstate_core::runner::{PerformanceClass, RunnerBenchmark, RunnerRecord};
+use flowstate_db::DbStats;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
-use super::{AppState, PendingConfig, RunnerStatus};
-use crate::pod_manager::PodStatus;
+use super::openapi::ErrorBody;
+use super::{queue_runner_config, AppState, PendingConfig, RunnerStatus};
+use crate::auth::ProjectScope;
+use crate::pod_manager::{PodStatus, ScalePod};
+use crate::watchdog::{WatchdogConfig, WatchdogPatch};
 
 pub fn routes() -> Router<AppState> {
     Router::new()
@@ -17,9 +25,18 @@ pub fn routes() -> Router<AppState> {
         .route("/api/infra/gpu/stop", post(gpu_stop))
         .route("/api/infra/runners", get(list_runners))
         .route("/api/infra/runners/{id}/config", put(set_runner_config))
+        .route("/api/runners", get(list_registered_runners))
+        .route("/api/runners/{id}/drain", post(drain_runner))
+        .route("/api/runners/{id}/config", patch(patch_runner_config))
+        .route("/api/infra/db", get(db_stats))
+        .route("/api/infra/storage", get(storage_usage))
+        .route(
+            "/api/infra/watchdog",
+            get(get_watchdog).patch(patch_watchdog),
+        )
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, utoipa::ToSchema)]
 struct GpuStatusResponse {
     enabled: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -30,9 +47,18 @@ struct GpuStatusResponse {
     daily_cost_cents: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     cost_capped: Option<bool>,
+    /// Pods the autoscaler started beside the primary one.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    scale_pods: Vec<ScalePod>,
     queue_depth: i64,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/infra/gpu-status",
+    tag = "infra",
+    responses((status = 200, body = GpuStatusResponse))
+)]
 async fn gpu_status(
     State(state): State<AppState>,
 ) -> Result<Json<GpuStatusResponse>, (StatusCode, Json<Value>)> {
@@ -52,6 +78,7 @@ async fn gpu_status(
                 pod_status: Some(ps.pod_status.clone()),
                 daily_cost_cents: Some(ps.daily_cost_cents),
                 cost_capped: Some(ps.cost_capped),
+                scale_pods: ps.scale_pods.clone(),
                 queue_depth,
             }))
         }
@@ -61,11 +88,21 @@ async fn gpu_status(
             pod_status: None,
             daily_cost_cents: None,
             cost_capped: None,
+            scale_pods: Vec::new(),
             queue_depth,
         })),
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/infra/gpu/start",
+    tag = "infra",
+    responses(
+        (status = 200, description = "`{\"status\": \"start_requested\"}` or `already_running`", body = Object),
+        (status = 404, description = "No pod manager is configured", body = ErrorBody)
+    )
+)]
 async fn gpu_start(
     State(state): State<AppState>,
 ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
@@ -91,6 +128,15 @@ async fn gpu_start(
     Ok(Json(json!({"status": "start_requested"})))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/infra/gpu/stop",
+    tag = "infra",
+    responses(
+        (status = 200, description = "`{\"status\": \"drain_requested\"}` or `already_stopped`", body = Object),
+        (status = 404, description = "No pod manager is configured", body = ErrorBody)
+    )
+)]
 async fn gpu_stop(State(state): State<AppState>) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
     let pm = state.pod_manager.as_ref().ok_or_else(|| {
         (
@@ -105,23 +151,31 @@ async fn gpu_stop(State(state): State<AppState>) -> Result<Json<Value>, (StatusC
     }
 
     // Set drain on all runners
-    {
-        let mut runners = state.runners.lock().unwrap();
-        for info in runners.values_mut() {
-            info.pending_config = Some(PendingConfig {
-                poll_interval: None,
-                drain: Some(true),
-            });
+    let runner_ids: Vec<String> = state.runners.lock().unwrap().keys().cloned().collect();
+    for runner_id in runner_ids {
+        let drain = PendingConfig {
+            poll_interval: None,
+            drain: Some(true),
+        };
+        if let Err(e) = queue_runner_config(&state, &runner_id, drain).await {
+            tracing::warn!("failed to record drain for runner {runner_id}: {e}");
         }
     }
 
+    let now = std::time::Instant::now();
     ps.pod_status = PodStatus::Draining;
-    ps.drain_requested_at = Some(std::time::Instant::now());
+    ps.drain_requested_at = Some(now);
+    for pod in ps.scale_pods.iter_mut() {
+        if matches!(pod.status, PodStatus::Starting | PodStatus::Running) {
+            pod.status = PodStatus::Draining;
+            pod.drain_requested_at = Some(now);
+        }
+    }
 
     Ok(Json(json!({"status": "drain_requested"})))
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, utoipa::ToSchema)]
 struct RunnerInfoResponse {
     runner_id: String,
     last_seen: String,
@@ -135,37 +189,87 @@ struct RunnerInfoResponse {
     status: RunnerStatus,
     saturation_pct: Option<f64>,
     has_pending_config: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    benchmark: Option<RunnerBenchmark>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    performance_class: Option<PerformanceClass>,
+}
+
+impl From<RunnerRecord> for RunnerInfoResponse {
+    fn from(r: RunnerRecord) -> Self {
+        let saturation_pct = match (r.active_count, r.max_concurrent) {
+            (Some(active), Some(max)) if max > 0 => Some(active as f64 / max as f64 * 100.0),
+            _ => None,
+        };
+        RunnerInfoResponse {
+            runner_id: r.runner_id,
+            last_seen: r.last_seen_at.to_rfc3339(),
+            backend_name: r.backend_name,
+            capability: r.capability,
+            poll_interval: r.poll_interval,
+            max_concurrent: r.max_concurrent,
+            max_builds: r.max_builds,
+            active_count: r.active_count,
+            active_builds: r.active_builds,
+            status: r.status,
+            saturation_pct,
+            has_pending_config: r.pending_config.is_some(),
+            benchmark: r.benchmark,
+            performance_class: r.benchmark.map(|b| b.performance_class()),
+        }
+    }
 }
 
-async fn list_runners(State(state): State<AppState>) -> Json<Vec<RunnerInfoResponse>> {
-    let runners = state.runners.lock().unwrap();
-    let list: Vec<RunnerInfoResponse> = runners
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+struct ListRunnersQuery {
+    /// Also list runners from the registry that have not checked in since
+    /// the server started.
+    #[serde(default)]
+    all: bool,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/infra/runners",
+    tag = "infra",
+    params(ListRunnersQuery),
+    responses(
+        (status = 200, body = [RunnerInfoResponse]),
+        (status = 500, body = ErrorBody)
+    )
+)]
+async fn list_runners(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+    Query(query): Query<ListRunnersQuery>,
+) -> Result<Json<Vec<RunnerInfoResponse>>, (StatusCode, Json<Value>)> {
+    let visible = |org_id: Option<&str>| scope.org_id().is_none() || org_id == scope.org_id();
+    let mut list: Vec<RunnerRecord> = state
+        .runners
+        .lock()
+        .unwrap()
         .values()
-        .map(|r| {
-            let saturation_pct = match (r.active_count, r.max_concurrent) {
-                (Some(active), Some(max)) if max > 0 => Some(active as f64 / max as f64 * 100.0),
-                _ => None,
-            };
-            RunnerInfoResponse {
-                runner_id: r.runner_id.clone(),
-                last_seen: r.last_seen.to_rfc3339(),
-                backend_name: r.backend_name.clone(),
-                capability: r.capability.clone(),
-                poll_interval: r.poll_interval,
-                max_concurrent: r.max_concurrent,
-                max_builds: r.max_builds,
-                active_count: r.active_count,
-                active_builds: r.active_builds,
-                status: r.status.clone(),
-                saturation_pct,
-                has_pending_config: r.pending_config.is_some(),
-            }
-        })
+        .filter(|r| visible(r.org_id.as_deref()))
+        .map(|r| r.to_record())
         .collect();
-    Json(list)
+    if query.all {
+        let registry = state.db.list_runners().await.map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": e.to_string()})),
+            )
+        })?;
+        let live: HashSet<String> = list.iter().map(|r| r.runner_id.clone()).collect();
+        list.extend(
+            registry
+                .into_iter()
+                .filter(|r| !live.contains(&r.runner_id) && visible(r.org_id.as_deref())),
+        );
+    }
+    Ok(Json(list.into_iter().map(Into::into).collect()))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 struct SetRunnerConfigInput {
     #[serde(default)]
     poll_interval: Option<u64>,
@@ -173,28 +277,280 @@ struct SetRunnerConfigInput {
     drain: Option<bool>,
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/infra/runners/{id}/config",
+    tag = "infra",
+    request_body = SetRunnerConfigInput,
+    responses(
+        (status = 200, description = "Delivered with the runner's next heartbeat", body = Object),
+        (status = 404, body = ErrorBody)
+    )
+)]
 async fn set_runner_config(
     State(state): State<AppState>,
+    scope: ProjectScope,
     Path(id): Path<String>,
     Json(input): Json<SetRunnerConfigInput>,
 ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
-    let mut runners = state.runners.lock().unwrap();
-    let info = runners.get_mut(&id).ok_or_else(|| {
+    find_runner(&state, &scope, &id).await?;
+
+    let config = PendingConfig {
+        poll_interval: input.poll_interval,
+        drain: input.drain,
+    };
+    queue_runner_config(&state, &id, config)
+        .await
+        .map_err(internal_error)?;
+
+    Ok(Json(json!({
+        "status": "pending_config_set",
+        "runner_id": id,
+    })))
+}
+
+fn internal_error(e: flowstate_db::DbError) -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({"error": e.to_string()})),
+    )
+}
+
+/// Look a runner up in the registry, which also holds runners that have not
+/// checked in since a restart; their config waits there for the next
+/// heartbeat. Runners of other organizations are not found.
+async fn find_runner(
+    state: &AppState,
+    scope: &ProjectScope,
+    id: &str,
+) -> Result<RunnerRecord, (StatusCode, Json<Value>)> {
+    let not_found = || {
         (
             StatusCode::NOT_FOUND,
             Json(json!({"error": format!("runner {id} not found")})),
         )
+    };
+    let runner = state.db.get_runner(id).await.map_err(|e| match e {
+        flowstate_db::DbError::NotFound(_) => not_found(),
+        e => internal_error(e),
     })?;
+    if scope.org_id().is_some() && runner.org_id.as_deref() != scope.org_id() {
+        return Err(not_found());
+    }
+    Ok(runner)
+}
+
+/// Merge `patch` into whatever config is already waiting for the runner and
+/// return the runner as it now stands.
+async fn update_pending_config(
+    state: &AppState,
+    scope: &ProjectScope,
+    id: &str,
+    patch: PendingConfig,
+) -> Result<RunnerRecord, (StatusCode, Json<Value>)> {
+    let runner = find_runner(state, scope, id).await?;
+    let pending = runner.pending_config.unwrap_or_default();
+    let config = PendingConfig {
+        poll_interval: patch.poll_interval.or(pending.poll_interval),
+        drain: patch.drain.or(pending.drain),
+    };
+    queue_runner_config(state, id, config.clone())
+        .await
+        .map_err(internal_error)?;
+    Ok(RunnerRecord {
+        pending_config: Some(config),
+        ..runner
+    })
+}
 
-    info.pending_config = Some(PendingConfig {
+#[utoipa::path(
+    get,
+    path = "/api/runners",
+    tag = "infra",
+    responses(
+        (status = 200, description = "Every runner in the registry, online or not", body = [RunnerRecord]),
+        (status = 500, body = ErrorBody)
+    )
+)]
+async fn list_registered_runners(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+) -> Result<Json<Vec<RunnerRecord>>, (StatusCode, Json<Value>)> {
+    let runners = state.db.list_runners().await.map_err(internal_error)?;
+    Ok(Json(
+        runners
+            .into_iter()
+            .filter(|r| scope.org_id().is_none() || r.org_id.as_deref() == scope.org_id())
+            .collect(),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/runners/{id}/drain",
+    tag = "infra",
+    responses(
+        (status = 200, description = "Drain delivered with the runner's next heartbeat", body = RunnerRecord),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn drain_runner(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+    Path(id): Path<String>,
+) -> Result<Json<RunnerRecord>, (StatusCode, Json<Value>)> {
+    let drain = PendingConfig {
+        poll_interval: None,
+        drain: Some(true),
+    };
+    update_pending_config(&state, &scope, &id, drain)
+        .await
+        .map(Json)
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/runners/{id}/config",
+    tag = "infra",
+    request_body = SetRunnerConfigInput,
+    responses(
+        (status = 200, description = "Merged into the config waiting for the runner's next heartbeat", body = RunnerRecord),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn patch_runner_config(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+    Path(id): Path<String>,
+    Json(input): Json<SetRunnerConfigInput>,
+) -> Result<Json<RunnerRecord>, (StatusCode, Json<Value>)> {
+    if input.poll_interval == Some(0) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "poll_interval must be at least 1 second"})),
+        ));
+    }
+    let patch = PendingConfig {
         poll_interval: input.poll_interval,
         drain: input.drain,
-    });
+    };
+    update_pending_config(&state, &scope, &id, patch)
+        .await
+        .map(Json)
+}
 
-    Ok(Json(json!({
-        "status": "pending_config_set",
-        "runner_id": id,
-    })))
+#[utoipa::path(
+    get,
+    path = "/api/infra/db",
+    tag = "infra",
+    responses((status = 200, body = DbStats))
+)]
+async fn db_stats(
+    State(state): State<AppState>,
+) -> Result<Json<DbStats>, (StatusCode, Json<Value>)> {
+    state.db.stats().await.map(Json).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": e.to_string()})),
+        )
+    })
+}
+
+#[derive(Serialize, Default, utoipa::ToSchema)]
+struct StorageUsage {
+    objects: usize,
+    bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_modified: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, utoipa::ToSchema)]
+struct StorageUsageResponse {
+    #[serde(flatten)]
+    total: StorageUsage,
+    /// Usage per top-level prefix, such as `tasks/` or `claude_runs/`.
+    prefixes: BTreeMap<String, StorageUsage>,
+}
+
+impl StorageUsage {
+    fn add(&mut self, object: &flowstate_store::ObjectMeta) {
+        self.objects += 1;
+        self.bytes += object.size;
+        self.last_modified = self.last_modified.max(object.last_modified);
+    }
+}
+
+/// Object counts and bytes in the store, from one listing rather than
+/// reading any object.
+#[utoipa::path(
+    get,
+    path = "/api/infra/storage",
+    tag = "infra",
+    responses((status = 200, body = StorageUsageResponse))
+)]
+async fn storage_usage(
+    State(state): State<AppState>,
+) -> Result<Json<StorageUsageResponse>, (StatusCode, Json<Value>)> {
+    let objects = state.store.list_with_meta("").await.map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({"error": e.to_string()})),
+        )
+    })?;
+    let mut total = StorageUsage::default();
+    let mut prefixes: BTreeMap<String, StorageUsage> = BTreeMap::new();
+    for object in &objects {
+        let prefix = match object.key.split_once('/') {
+            Some((top, _)) => format!("{top}/"),
+            None => String::new(),
+        };
+        prefixes.entry(prefix).or_default().add(object);
+        total.add(object);
+    }
+    Ok(Json(StorageUsageResponse { total, prefixes }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/infra/watchdog",
+    tag = "infra",
+    responses((status = 200, body = WatchdogConfig))
+)]
+async fn get_watchdog(State(state): State<AppState>) -> Json<WatchdogConfig> {
+    Json(state.watchdog.read().unwrap().clone())
+}
+
+/// Change the watchdog's scan interval or timeouts until the server
+/// restarts. Only unscoped keys may. The interval applies after the next
+/// scan, the timeouts on it.
+#[utoipa::path(
+    patch,
+    path = "/api/infra/watchdog",
+    tag = "infra",
+    request_body = WatchdogPatch,
+    responses(
+        (status = 200, body = WatchdogConfig),
+        (status = 400, body = ErrorBody),
+        (status = 403, body = ErrorBody)
+    )
+)]
+async fn patch_watchdog(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+    Json(patch): Json<WatchdogPatch>,
+) -> Result<Json<WatchdogConfig>, (StatusCode, Json<Value>)> {
+    if scope.projects().is_some() {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(json!({"error": "project-scoped keys cannot change the watchdog"})),
+        ));
+    }
+    let mut config = state.watchdog.write().unwrap();
+    config
+        .apply(patch)
+        .map_err(|e| (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))))?;
+    tracing::info!("watchdog configuration changed: {:?}", *config);
+    Ok(Json(config.clone()))
 }
 
 #[cfg(test)]
@@ -227,6 +583,89 @@ mod tests {
         assert_eq!(v["enabled"], false);
     }
 
+    #[tokio::test]
+    async fn db_stats_reports_sqlite() {
+        let app = test_router().await;
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/infra/db")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), AxumStatusCode::OK);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let v: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(v["backend"], "sqlite");
+        assert!(v["schema_version"].as_i64().unwrap() > 0);
+        assert!(!v["tables"].as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn storage_usage_groups_by_prefix() {
+        let app = test_router().await;
+        let send = |method: Method, uri: String, body: Value| {
+            let app = app.clone();
+            async move {
+                let resp = app
+                    .oneshot(
+                        Request::builder()
+                            .method(method)
+                            .uri(uri)
+                            .header("content-type", "application/json")
+                            .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                serde_json::from_slice::<Value>(&bytes).unwrap_or(Value::Null)
+            }
+        };
+
+        let empty = send(Method::GET, "/api/infra/storage".into(), Value::Null).await;
+        assert_eq!(empty["objects"], 0);
+        assert!(empty["prefixes"].as_object().unwrap().is_empty());
+
+        let project = send(
+            Method::POST,
+            "/api/projects".into(),
+            serde_json::json!({"name": "P", "slug": "p"}),
+        )
+        .await;
+        let task = send(
+            Method::POST,
+            "/api/tasks".into(),
+            serde_json::json!({
+                "project_id": project["id"],
+                "title": "T",
+                "status": "todo",
+                "priority": "medium"
+            }),
+        )
+        .await;
+        let id = task["id"].as_str().unwrap();
+        send(
+            Method::PUT,
+            format!("/api/tasks/{id}/spec"),
+            Value::String("# spec".into()),
+        )
+        .await;
+
+        let usage = send(Method::GET, "/api/infra/storage".into(), Value::Null).await;
+        assert_eq!(usage["objects"], 1);
+        assert_eq!(usage["prefixes"]["tasks/"]["objects"], 1);
+        assert!(usage["prefixes"]["tasks/"]["bytes"].as_u64().unwrap() >= 6);
+        assert!(usage["last_modified"].is_string());
+    }
+
     #[tokio::test]
     async fn list_runners_empty() {
         let app = test_router().await;
@@ -292,6 +731,59 @@ mod tests {
         assert_eq!(v[0]["runner_id"], "test-runner-1");
     }
 
+    #[tokio::test]
+    async fn watchdog_config_can_be_patched() {
+        let app = test_router().await;
+        let patch = |body: Value| {
+            app.clone().oneshot(
+                Request::builder()
+                    .method(Method::PATCH)
+                    .uri("/api/infra/watchdog")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+        };
+
+        let resp = patch(serde_json::json!({
+            "interval_secs": 30,
+            "action_timeout_mins": { "research": 20, "build": 180 },
+        }))
+        .await
+        .unwrap();
+        assert_eq!(resp.status(), AxumStatusCode::OK);
+        let resp = patch(serde_json::json!({ "action_timeout_mins": { "deploy": 5 } }))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), AxumStatusCode::BAD_REQUEST);
+        let resp = patch(serde_json::json!({ "salvage_timeout_mins": 0 }))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), AxumStatusCode::BAD_REQUEST);
+
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/infra/watchdog")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["interval_secs"], 30);
+        assert_eq!(json["running_timeout_mins"], 90);
+        assert_eq!(json["salvage_timeout_mins"], 30);
+        assert_eq!(
+            json["action_timeout_mins"],
+            serde_json::json!({ "build": 180, "research": 20 })
+        );
+    }
+
     #[tokio::test]
     async fn set_runner_config_unknown_runner() {
         let app = test_router().await;
@@ -548,4 +1040,112 @@ mod tests {
             .unwrap();
         assert_eq!(resp.status(), AxumStatusCode::NOT_FOUND);
     }
+
+    #[tokio::test]
+    async fn pending_drain_survives_a_restart() {
+        let state = crate::test_helpers::test_state().await;
+        let app = crate::routes::build_router(state.clone());
+        let send = |method: Method, uri: &str, body: Value| {
+            let req = Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap();
+            let app = app.clone();
+            async move {
+                let resp = app.oneshot(req).await.unwrap();
+                let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                serde_json::from_slice::<Value>(&bytes).unwrap_or(Value::Null)
+            }
+        };
+        let register = serde_json::json!({"runner_id": "persisted", "capability": "light"});
+
+        send(Method::POST, "/api/runners/register", register.clone()).await;
+        state.runners.lock().unwrap().clear();
+
+        // The registry still knows the runner while it is offline
+        let live = send(Method::GET, "/api/infra/runners", Value::Null).await;
+        assert!(live.as_array().unwrap().is_empty());
+        let all = send(Method::GET, "/api/infra/runners?all=true", Value::Null).await;
+        assert_eq!(all[0]["runner_id"], "persisted");
+        let set = send(
+            Method::PUT,
+            "/api/infra/runners/persisted/config",
+            serde_json::json!({"drain": true}),
+        )
+        .await;
+        assert_eq!(set["status"], "pending_config_set");
+
+        // ...and hands over the drain once it checks in, exactly once
+        let resp = send(Method::POST, "/api/runners/register", register.clone()).await;
+        assert_eq!(resp["pending_config"]["drain"], true);
+        state.runners.lock().unwrap().clear();
+        let resp = send(Method::POST, "/api/runners/register", register).await;
+        assert!(resp["pending_config"].is_null());
+    }
+
+    #[tokio::test]
+    async fn drain_and_patch_merge_into_pending_config() {
+        let app = test_router().await;
+        let send = |method: Method, uri: &str, body: Value| {
+            let req = Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap();
+            let app = app.clone();
+            async move {
+                let resp = app.oneshot(req).await.unwrap();
+                let status = resp.status();
+                let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                (
+                    status,
+                    serde_json::from_slice::<Value>(&bytes).unwrap_or(Value::Null),
+                )
+            }
+        };
+        let register = serde_json::json!({"runner_id": "admin-runner", "capability": "light"});
+        send(Method::POST, "/api/runners/register", register.clone()).await;
+
+        let (status, runners) = send(Method::GET, "/api/runners", Value::Null).await;
+        assert_eq!(status, AxumStatusCode::OK);
+        assert_eq!(runners[0]["runner_id"], "admin-runner");
+        assert!(runners[0]["pending_config"].is_null());
+
+        let (status, runner) = send(
+            Method::PATCH,
+            "/api/runners/admin-runner/config",
+            serde_json::json!({"poll_interval": 30}),
+        )
+        .await;
+        assert_eq!(status, AxumStatusCode::OK);
+        assert_eq!(runner["pending_config"]["poll_interval"], 30);
+
+        // Draining keeps the poll interval already waiting for the runner
+        let (status, runner) =
+            send(Method::POST, "/api/runners/admin-runner/drain", Value::Null).await;
+        assert_eq!(status, AxumStatusCode::OK);
+        assert_eq!(runner["pending_config"]["poll_interval"], 30);
+        assert_eq!(runner["pending_config"]["drain"], true);
+
+        let (status, _) = send(
+            Method::PATCH,
+            "/api/runners/admin-runner/config",
+            serde_json::json!({"poll_interval": 0}),
+        )
+        .await;
+        assert_eq!(status, AxumStatusCode::BAD_REQUEST);
+        let (status, _) = send(Method::POST, "/api/runners/missing/drain", Value::Null).await;
+        assert_eq!(status, AxumStatusCode::NOT_FOUND);
+
+        let (_, resp) = send(Method::POST, "/api/runners/register", register).await;
+        assert_eq!(resp["pending_config"]["poll_interval"], 30);
+        assert_eq!(resp["pending_config"]["drain"], true);
+    }
 }
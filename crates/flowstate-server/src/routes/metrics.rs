@@ -0,0 +1,146 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use flowstate_core::run_metrics::{RunMetricsFilter, RunMetricsSummary};
+use serde_json::{json, Value};
+
+use super::AppState;
+use crate::auth::ProjectScope;
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/metrics/runs", get(run_metrics))
+}
+
+/// Per-action duration, output size, token and cost totals, optionally
+/// narrowed to one project (`project_id`) or a window (`since`, RFC 3339).
+#[utoipa::path(
+    get,
+    path = "/metrics/runs",
+    tag = "runs",
+    params(RunMetricsFilter),
+    responses((status = 200, body = [RunMetricsSummary]))
+)]
+async fn run_metrics(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+    Query(filter): Query<RunMetricsFilter>,
+) -> Result<Json<Vec<RunMetricsSummary>>, (StatusCode, Json<Value>)> {
+    if scope.projects().is_some() && filter.project_id.is_none() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({"error": "a project-scoped key must give a project_id"})),
+        ));
+    }
+    state
+        .db
+        .summarize_run_metrics(&filter)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": e.to_string()})),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::test_router;
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    async fn send(
+        app: &axum::Router,
+        method: Method,
+        uri: &str,
+        body: Value,
+    ) -> (StatusCode, Value) {
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = resp.status();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    #[tokio::test]
+    async fn record_and_aggregate_run_metrics() {
+        let app = test_router().await;
+        let (_, project) = send(
+            &app,
+            Method::POST,
+            "/api/projects",
+            json!({"name": "M", "slug": "m"}),
+        )
+        .await;
+        let project_id = project["id"].as_str().unwrap();
+        let (_, task) = send(
+            &app,
+            Method::POST,
+            "/api/tasks",
+            json!({"project_id": project_id, "title": "T", "status": "todo", "priority": "medium"}),
+        )
+        .await;
+        let task_id = task["id"].as_str().unwrap();
+        let (_, run) = send(
+            &app,
+            Method::POST,
+            &format!("/api/tasks/{task_id}/claude-runs"),
+            json!({"action": "research"}),
+        )
+        .await;
+        let run_id = run["id"].as_str().unwrap();
+
+        let (status, recorded) = send(
+            &app,
+            Method::POST,
+            &format!("/api/claude-runs/{run_id}/metrics"),
+            json!({"duration_ms": 1500, "stdout_bytes": 42, "input_tokens": 10}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(recorded["stdout_bytes"], 42);
+
+        let (status, summary) = send(
+            &app,
+            Method::GET,
+            &format!("/metrics/runs?project_id={project_id}"),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(summary[0]["action"], "research");
+        assert_eq!(summary[0]["runs"], 1);
+        assert_eq!(summary[0]["total_duration_ms"], 1500);
+        assert_eq!(summary[0]["input_tokens"], 10);
+
+        let (status, _) = send(
+            &app,
+            Method::POST,
+            "/api/claude-runs/missing/metrics",
+            json!({"duration_ms": 1, "stdout_bytes": 0}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -1,43 +1,61 @@
+pub mod admin;
+pub mod approval_rules;
+pub mod board;
 pub mod claude_runs;
+pub mod custom_fields;
+pub mod epics;
+pub mod events;
+pub mod export;
+pub mod github;
 pub mod health;
+pub mod imports;
 pub mod infra;
+pub mod metrics;
+pub mod notifications;
+pub mod oidc;
+pub mod openapi;
+pub mod orgs;
 pub mod projects;
+pub mod run_logs;
+pub mod saved_filters;
+pub mod scope_findings;
 pub mod sprints;
+pub mod status;
+pub mod store;
+pub mod subscriptions;
+pub mod task_clone;
 pub mod task_links;
 pub mod task_prs;
 pub mod tasks;
+pub mod users;
+pub mod webhooks;
 
 use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use aes_gcm::{Aes256Gcm, Key};
 use axum::{middleware, Router};
 use chrono::{DateTime, Utc};
-use flowstate_db::Database;
+use flowstate_core::runner::{RunnerBenchmark, RunnerRecord};
+use flowstate_core::TaskLinks;
+use flowstate_db::{Database, MaintenanceReport};
 use flowstate_service::LocalService;
 use flowstate_store::ObjectStore;
-use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
 
-use crate::auth::{auth_middleware, AuthConfig};
-use crate::pod_manager::PodManagerState;
+use crate::auth::{auth_middleware, project_scope_middleware, runner_cert_middleware, AuthConfig};
+use crate::display_time::display_time_middleware;
+use crate::pod_manager::{PodManagerState, RunPodApi};
+use crate::rate_limit::{rate_limit_middleware, RateLimiter};
+use crate::request_span::request_span_middleware;
+use crate::watchdog::WatchdogConfig;
 
-/// Pending configuration changes to be delivered to a runner via registration response.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PendingConfig {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub poll_interval: Option<u64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub drain: Option<bool>,
-}
+/// Pending configuration changes to be delivered to a runner via registration
+/// response. The client's type, so the two cannot disagree on the wire.
+pub use flowstate_service::PendingConfigResponse as PendingConfig;
 
-/// Runner lifecycle status.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub enum RunnerStatus {
-    Active,
-    Draining,
-    Drained,
-}
+pub use flowstate_core::runner::RunnerStatus;
 
 pub struct RunnerInfo {
     pub runner_id: String,
@@ -60,6 +78,50 @@ pub struct RunnerInfo {
     pub status: RunnerStatus,
     /// Pending configuration to deliver on next registration heartbeat.
     pub pending_config: Option<PendingConfig>,
+    /// Startup benchmark results, when the runner ran one.
+    pub benchmark: Option<RunnerBenchmark>,
+    /// Organization of the key the runner registered with; `None` for a
+    /// server-wide runner.
+    pub org_id: Option<String>,
+}
+
+impl RunnerInfo {
+    /// The registry row for this heartbeat. The database keeps the runner's
+    /// original `first_seen_at` and its pending config.
+    pub fn to_record(&self) -> RunnerRecord {
+        RunnerRecord {
+            runner_id: self.runner_id.clone(),
+            org_id: self.org_id.clone(),
+            backend_name: self.backend_name.clone(),
+            capability: self.capability.clone(),
+            poll_interval: self.poll_interval,
+            max_concurrent: self.max_concurrent,
+            max_builds: self.max_builds,
+            active_count: self.active_count,
+            active_builds: self.active_builds,
+            status: self.status,
+            pending_config: self.pending_config.clone(),
+            benchmark: self.benchmark,
+            first_seen_at: self.last_seen,
+            last_seen_at: self.last_seen,
+        }
+    }
+}
+
+/// Queue `config` for a runner's next heartbeat. It is kept in the registry
+/// too, so a runner that re-registers after a server restart still gets it.
+pub async fn queue_runner_config(
+    state: &InnerAppState,
+    runner_id: &str,
+    config: PendingConfig,
+) -> Result<(), flowstate_db::DbError> {
+    if let Some(info) = state.runners.lock().unwrap().get_mut(runner_id) {
+        info.pending_config = Some(config.clone());
+    }
+    state
+        .db
+        .set_runner_pending_config(runner_id, Some(&config))
+        .await
 }
 
 pub struct InnerAppState {
@@ -70,26 +132,103 @@ pub struct InnerAppState {
     pub encryption_key: Key<Aes256Gcm>,
     pub store: Arc<dyn ObjectStore>,
     pub pod_manager: Option<Arc<tokio::sync::Mutex<PodManagerState>>>,
+    /// The RunPod API the pod manager drives, checked by `/api/health/ready`.
+    pub pod_api: Option<Arc<dyn RunPodApi>>,
+    /// Scan interval and timeouts the watchdog reads on each scan.
+    pub watchdog: Arc<std::sync::RwLock<WatchdogConfig>>,
+    /// Require a verified client certificate on runner-facing routes.
+    pub runner_mtls: bool,
+    /// Pause run claiming and reject writes; see [`admin::maintenance_middleware`].
+    pub maintenance: AtomicBool,
+    /// Exposure and rate limiting for the public `/status` endpoint.
+    pub status_page: status::StatusPage,
+    /// Latest scheduled database maintenance report, shown by `/api/status`.
+    pub db_maintenance: std::sync::Mutex<Option<MaintenanceReport>>,
+    /// Deep links to tasks, attached to notifications.
+    pub task_links: TaskLinks,
+    /// Email, Slack and webhook channels for notification subscriptions.
+    pub notifier: crate::notifier::Notifier,
+    /// Live updates for `/api/events` subscribers.
+    pub events: events::EventBus,
+    /// Live output of running runs, for `/api/claude-runs/{id}/logs/stream`.
+    pub run_logs: run_logs::RunLogs,
+    /// Shared secret GitHub signs webhook deliveries with; the receiver is
+    /// off without one.
+    pub github_webhook_secret: Option<String>,
+    /// Per-key and per-address request budgets; unlimited by default.
+    pub rate_limiter: RateLimiter,
+    /// Cancelled when the server begins shutting down; background loops and
+    /// open event streams end on it.
+    pub shutdown: CancellationToken,
 }
 
 pub type AppState = Arc<InnerAppState>;
 
 pub fn build_router(state: AppState) -> Router {
-    let public = Router::new().merge(health::routes());
+    let public = Router::new()
+        .merge(health::routes())
+        .merge(status::routes())
+        .merge(store::routes())
+        .merge(github::routes())
+        .merge(oidc::routes())
+        .merge(openapi::routes());
 
     let protected = Router::new()
         .merge(projects::routes())
+        .merge(imports::routes())
+        .merge(export::routes())
+        .merge(approval_rules::routes())
         .merge(tasks::routes())
+        .merge(board::routes())
         .merge(sprints::routes())
+        .merge(epics::routes())
+        .merge(events::routes())
+        .merge(users::routes())
+        .merge(saved_filters::routes())
+        .merge(notifications::routes())
+        .merge(subscriptions::routes())
+        .merge(custom_fields::routes())
+        .merge(task_clone::routes())
         .merge(task_links::routes())
         .merge(task_prs::routes())
+        .merge(scope_findings::routes())
         .merge(claude_runs::routes())
+        .merge(run_logs::routes())
+        .merge(
+            claude_runs::runner_routes()
+                .merge(run_logs::runner_routes())
+                .route_layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    runner_cert_middleware,
+                )),
+        )
         .merge(infra::routes())
+        .merge(admin::routes())
+        .merge(orgs::routes())
+        .merge(webhooks::routes())
+        .merge(metrics::routes())
         .merge(health::protected_routes())
+        .merge(oidc::protected_routes())
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            project_scope_middleware,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            admin::maintenance_middleware,
+        ))
         .route_layer(middleware::from_fn_with_state(
             state.clone(),
             auth_middleware,
         ));
 
-    public.merge(protected).with_state(state)
+    public
+        .merge(protected)
+        .layer(middleware::from_fn(display_time_middleware))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            rate_limit_middleware,
+        ))
+        .layer(middleware::from_fn(request_span_middleware))
+        .with_state(state)
 }
@@ -0,0 +1,357 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::Utc;
+use flowstate_core::notification::TaskWatcher;
+use flowstate_core::{DisplayZone, FlowstateError, Notification, TaskLinks};
+use flowstate_service::TaskService;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::openapi::ErrorBody;
+use super::AppState;
+use crate::auth::ProjectScope;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/tasks/{id}/watch",
+            post(watch_task).delete(unwatch_task),
+        )
+        .route("/api/tasks/{id}/watchers", get(list_task_watchers))
+        .route("/api/notifications", get(list_notifications))
+        .route("/api/notifications/{id}/read", post(mark_notification_read))
+}
+
+/// Template for the task links attached to notifications and PR bodies,
+/// read from `FLOWSTATE_TASK_LINK`; unset means `flowstate://task/:id`.
+pub(crate) fn task_links_from_env() -> Result<TaskLinks, FlowstateError> {
+    match std::env::var("FLOWSTATE_TASK_LINK") {
+        Ok(template) if !template.is_empty() => TaskLinks::new(&template),
+        _ => Ok(TaskLinks::default()),
+    }
+}
+
+/// A notification with a `link` that opens its task and when it was
+/// created relative to now; `?tz=` localizes the latter.
+fn with_link(links: &TaskLinks, notification: &Notification) -> Value {
+    let mut value = json!(notification);
+    value["link"] = json!(links.task_url(&notification.task_id));
+    value["created_at_relative"] =
+        json!(DisplayZone::Utc.relative(notification.created_at, Utc::now()));
+    value
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema, utoipa::IntoParams)]
+struct WatchRequest {
+    user_id: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/tasks/{id}/watch",
+    tag = "notifications",
+    request_body = WatchRequest,
+    responses(
+        (status = 200, description = "The task's watchers", body = [TaskWatcher]),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn watch_task(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(input): Json<WatchRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    state
+        .service
+        .watch_task(&id, &input.user_id)
+        .await
+        .map(|w| Json(json!(w)))
+        .map_err(to_error)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/tasks/{id}/watch",
+    tag = "notifications",
+    params(WatchRequest),
+    responses(
+        (status = 204, description = "No longer watching"),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn unwatch_task(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<WatchRequest>,
+) -> Result<StatusCode, (StatusCode, Json<Value>)> {
+    state
+        .service
+        .unwatch_task(&id, &query.user_id)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(to_error)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{id}/watchers",
+    tag = "notifications",
+    responses(
+        (status = 200, body = [TaskWatcher]),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn list_task_watchers(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    state
+        .service
+        .list_task_watchers(&id)
+        .await
+        .map(|w| Json(json!(w)))
+        .map_err(to_error)
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+struct NotificationQuery {
+    user_id: String,
+    /// Only notifications not yet marked read.
+    #[serde(default)]
+    unread: bool,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/notifications",
+    tag = "notifications",
+    params(NotificationQuery),
+    responses((status = 200, description = "Each notification also has a `link` to its task and a `created_at_relative` time. A scoped key only sees notifications on its projects' tasks.", body = [Notification]))
+)]
+async fn list_notifications(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+    Query(query): Query<NotificationQuery>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let mut notifications = state
+        .service
+        .list_notifications(&query.user_id, query.unread)
+        .await
+        .map_err(to_error)?;
+    if scope.projects().is_some() {
+        let mut visible = Vec::with_capacity(notifications.len());
+        for n in notifications {
+            if in_scope(&state, &scope, &n).await {
+                visible.push(n);
+            }
+        }
+        notifications = visible;
+    }
+    Ok(Json(Value::Array(
+        notifications
+            .iter()
+            .map(|n| with_link(&state.task_links, n))
+            .collect(),
+    )))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/notifications/{id}/read",
+    tag = "notifications",
+    responses(
+        (status = 200, description = "Each notification also has a `link` to its task and a `created_at_relative` time", body = Notification),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn mark_notification_read(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    if scope.projects().is_some() {
+        let n = state
+            .db
+            .get_notification(&id)
+            .await
+            .map_err(|e| to_error(e.into()))?;
+        let task = state.service.get_task(&n.task_id).await.map_err(to_error)?;
+        scope.check(&task.project_id)?;
+    }
+    state
+        .service
+        .mark_notification_read(&id)
+        .await
+        .map(|n| Json(with_link(&state.task_links, &n)))
+        .map_err(to_error)
+}
+
+/// Whether the notification's task lies in the caller's scope.
+async fn in_scope(state: &AppState, scope: &ProjectScope, n: &Notification) -> bool {
+    match state.service.get_task(&n.task_id).await {
+        Ok(task) => scope.allows(&task.project_id),
+        Err(_) => false,
+    }
+}
+
+fn to_error(e: flowstate_service::ServiceError) -> (StatusCode, Json<Value>) {
+    let (status, msg) = match &e {
+        flowstate_service::ServiceError::NotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
+        flowstate_service::ServiceError::InvalidInput(_) => {
+            (StatusCode::BAD_REQUEST, e.to_string())
+        }
+        flowstate_service::ServiceError::Internal(_) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        }
+    };
+    (status, Json(json!({ "error": msg })))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::test_router;
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    async fn send(
+        app: &axum::Router,
+        method: Method,
+        uri: &str,
+        body: Value,
+    ) -> (StatusCode, Value) {
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = resp.status();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    #[tokio::test]
+    async fn watcher_is_notified_of_task_updates() {
+        let app = test_router().await;
+        let (_, user) = send(
+            &app,
+            Method::POST,
+            "/api/users",
+            json!({ "name": "Alice", "email": "" }),
+        )
+        .await;
+        let user_id = user["id"].as_str().unwrap();
+        let (_, project) = send(
+            &app,
+            Method::POST,
+            "/api/projects",
+            json!({ "name": "Watchers", "slug": "watchers" }),
+        )
+        .await;
+        let (_, task) = send(
+            &app,
+            Method::POST,
+            "/api/tasks",
+            json!({ "project_id": project["id"], "title": "Flaky test", "status": "todo", "priority": "medium" }),
+        )
+        .await;
+        let task_id = task["id"].as_str().unwrap();
+
+        let (status, _) = send(
+            &app,
+            Method::POST,
+            &format!("/api/tasks/{task_id}/watch"),
+            json!({ "user_id": "nobody" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, watchers) = send(
+            &app,
+            Method::POST,
+            &format!("/api/tasks/{task_id}/watch"),
+            json!({ "user_id": user_id }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(watchers[0]["id"], user_id);
+
+        let (status, tasks) = send(
+            &app,
+            Method::GET,
+            &format!("/api/tasks?watched_by={user_id}"),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(tasks.as_array().unwrap().len(), 1);
+
+        send(
+            &app,
+            Method::PUT,
+            &format!("/api/tasks/{task_id}"),
+            json!({ "priority": "urgent" }),
+        )
+        .await;
+        let (status, notifications) = send(
+            &app,
+            Method::GET,
+            &format!("/api/notifications?user_id={user_id}&unread=true"),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let notifications = notifications.as_array().unwrap();
+        assert_eq!(notifications.len(), 1);
+        assert_eq!(notifications[0]["changes"][0]["field"], "priority");
+        assert_eq!(
+            notifications[0]["link"],
+            format!("flowstate://task/{task_id}")
+        );
+        assert_eq!(notifications[0]["created_at_relative"], "just now");
+
+        let notification_id = notifications[0]["id"].as_str().unwrap();
+        let (status, read) = send(
+            &app,
+            Method::POST,
+            &format!("/api/notifications/{notification_id}/read"),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(read["read"], true);
+
+        let (status, _) = send(
+            &app,
+            Method::DELETE,
+            &format!("/api/tasks/{task_id}/watch?user_id={user_id}"),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (_, watchers) = send(
+            &app,
+            Method::GET,
+            &format!("/api/tasks/{task_id}/watchers"),
+            Value::Null,
+        )
+        .await;
+        assert!(watchers.as_array().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,291 @@
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use utoipa::{IntoParams, ToSchema};
+
+use super::openapi::ErrorBody;
+use super::AppState;
+use crate::auth::{Caller, ProjectScope};
+use crate::oidc::{cookie_value, Identity, Oidc, LOGIN_COOKIE};
+
+type ApiError = (StatusCode, Json<Value>);
+
+/// Public routes (no auth required): the login flow itself.
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/auth/login", get(login))
+        .route("/auth/callback", get(callback))
+        .route("/auth/logout", post(logout))
+}
+
+/// Protected routes (auth required).
+pub fn protected_routes() -> Router<AppState> {
+    Router::new().route("/auth/me", get(me))
+}
+
+fn provider(state: &AppState) -> Result<&Oidc, ApiError> {
+    state
+        .auth
+        .as_ref()
+        .and_then(|auth| auth.oidc.as_deref())
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({ "error": "OIDC login is not configured" })),
+            )
+        })
+}
+
+fn redirect(location: &str, cookie: &str) -> Response {
+    (
+        StatusCode::FOUND,
+        [(header::LOCATION, location), (header::SET_COOKIE, cookie)],
+    )
+        .into_response()
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+struct LoginQuery {
+    /// Path on this server to return to after signing in. Defaults to `/`.
+    redirect: Option<String>,
+}
+
+/// Send the browser to the identity provider to sign in.
+#[utoipa::path(
+    get,
+    path = "/auth/login",
+    tag = "auth",
+    security(()),
+    params(LoginQuery),
+    responses(
+        (status = 302, description = "Redirect to the identity provider"),
+        (status = 404, description = "OIDC is not configured", body = ErrorBody)
+    )
+)]
+async fn login(
+    State(state): State<AppState>,
+    Query(q): Query<LoginQuery>,
+) -> Result<Response, ApiError> {
+    let oidc = provider(&state)?;
+    // Only paths on this server, so the login cannot be used as an open redirect
+    let return_to = q
+        .redirect
+        .filter(|r| r.starts_with('/') && !r.starts_with("//") && !r.starts_with("/\\"))
+        .unwrap_or_else(|| "/".into());
+    let (url, cookie) = oidc.login(&return_to);
+    Ok(redirect(&url, &cookie))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+struct CallbackQuery {
+    code: Option<String>,
+    state: Option<String>,
+    /// Set by the provider when sign-in failed or was declined.
+    error: Option<String>,
+}
+
+/// Where the identity provider returns the browser. Starts a session and
+/// redirects to the page the login began from.
+#[utoipa::path(
+    get,
+    path = "/auth/callback",
+    tag = "auth",
+    security(()),
+    params(CallbackQuery),
+    responses(
+        (status = 302, description = "Signed in; the session cookie is set"),
+        (status = 400, description = "The login failed or expired", body = ErrorBody),
+        (status = 404, description = "OIDC is not configured", body = ErrorBody)
+    )
+)]
+async fn callback(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(q): Query<CallbackQuery>,
+) -> Result<Response, ApiError> {
+    let oidc = provider(&state)?;
+    let bad_request = |msg: String| (StatusCode::BAD_REQUEST, Json(json!({ "error": msg })));
+    if let Some(error) = q.error {
+        return Err(bad_request(format!("identity provider: {error}")));
+    }
+    let (Some(code), Some(login_state)) = (q.code, q.state) else {
+        return Err(bad_request("missing code or state".into()));
+    };
+    let pending = headers
+        .get(header::COOKIE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| cookie_value(v, LOGIN_COOKIE))
+        .ok_or_else(|| bad_request("login expired or was not started here".into()))?;
+    let (identity, return_to) = oidc
+        .finish_login(&code, &login_state, pending)
+        .await
+        .map_err(|e| bad_request(e.to_string()))?;
+    tracing::info!("signed in {}", identity.label());
+    Ok(redirect(&return_to, &oidc.session_cookie(&identity)))
+}
+
+/// End the browser's session. Tokens the provider issued stay valid until
+/// they expire.
+#[utoipa::path(
+    post,
+    path = "/auth/logout",
+    tag = "auth",
+    security(()),
+    responses(
+        (status = 204, description = "The session cookie is cleared"),
+        (status = 404, description = "OIDC is not configured", body = ErrorBody)
+    )
+)]
+async fn logout(State(state): State<AppState>) -> Result<Response, ApiError> {
+    let oidc = provider(&state)?;
+    Ok((
+        StatusCode::NO_CONTENT,
+        [(header::SET_COOKIE, oidc.clear_session_cookie())],
+    )
+        .into_response())
+}
+
+/// Who the server takes the caller to be.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Me {
+    /// The label recorded on changes, e.g. `user:alice@example.com` or
+    /// `key:ci`.
+    pub caller: String,
+    /// The signed-in user, when authenticated through OIDC.
+    pub user: Option<Identity>,
+    /// Projects the caller's key is limited to; `null` when it reaches
+    /// every project.
+    pub projects: Option<Vec<String>>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/auth/me",
+    tag = "auth",
+    responses((status = 200, body = Me))
+)]
+async fn me(
+    caller: Option<Extension<Caller>>,
+    user: Option<Extension<Identity>>,
+    scope: ProjectScope,
+) -> Json<Me> {
+    Json(Me {
+        caller: caller.map(|c| c.0 .0).unwrap_or_default(),
+        user: user.map(|u| u.0),
+        projects: scope.projects().map(<[String]>::to_vec),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::auth::AuthConfig;
+    use crate::oidc::fake_provider::FakeProvider;
+    use crate::oidc::SESSION_COOKIE;
+    use crate::routes::build_router;
+    use crate::test_helpers::test_state;
+
+    fn query_param<'a>(url: &'a str, name: &str) -> &'a str {
+        url.split(['?', '&'])
+            .find_map(|pair| pair.strip_prefix(&format!("{name}=")))
+            .unwrap()
+    }
+
+    /// `name=value` of the cookie a response sets.
+    fn set_cookie(resp: &Response) -> String {
+        let cookie = resp.headers()[header::SET_COOKIE].to_str().unwrap();
+        cookie.split(';').next().unwrap().to_string()
+    }
+
+    async fn get(app: &Router, uri: &str, headers: &[(header::HeaderName, &str)]) -> Response {
+        let mut req = Request::get(uri);
+        for (name, value) in headers {
+            req = req.header(name, *value);
+        }
+        app.clone()
+            .oneshot(req.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn browser_login_starts_a_session() {
+        let provider = FakeProvider::start().await;
+        let oidc = Oidc::discover(provider.config(), b"server key")
+            .await
+            .unwrap();
+        let mut state = Arc::try_unwrap(test_state().await)
+            .ok()
+            .expect("fresh state has one owner");
+        state.auth = Some(Arc::new(AuthConfig {
+            env_key_hash: None,
+            db: state.db.clone(),
+            oidc: Some(Arc::new(oidc)),
+        }));
+        let app = build_router(Arc::new(state));
+
+        let resp = get(&app, "/auth/me", &[]).await;
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let resp = get(&app, "/auth/login?redirect=/board", &[]).await;
+        assert_eq!(resp.status(), StatusCode::FOUND);
+        let location = resp.headers()[header::LOCATION].to_str().unwrap();
+        assert!(location.starts_with(&format!("{}/authorize?", provider.issuer)));
+        *provider.nonce.lock().unwrap() = query_param(location, "nonce").to_string();
+        let login_state = query_param(location, "state").to_string();
+        let login_cookie = set_cookie(&resp);
+
+        let resp = get(
+            &app,
+            &format!("/auth/callback?code=abc&state={login_state}"),
+            &[(header::COOKIE, &login_cookie)],
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::FOUND);
+        assert_eq!(resp.headers()[header::LOCATION], "/board");
+        let session = set_cookie(&resp);
+        assert!(session.starts_with(SESSION_COOKIE));
+
+        let resp = get(&app, "/auth/me", &[(header::COOKIE, &session)]).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let me: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(me["caller"], "user:alice@example.com");
+        assert_eq!(me["user"]["sub"], "user-1");
+        assert_eq!(me["projects"], Value::Null);
+
+        let bearer = format!("Bearer {}", provider.token("flowstate"));
+        let resp = get(&app, "/auth/me", &[(header::AUTHORIZATION, &bearer)]).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // A callback without the cookie from its own login is refused
+        let resp = get(
+            &app,
+            &format!("/auth/callback?code=abc&state={login_state}"),
+            &[],
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn login_is_off_without_oidc() {
+        let app = build_router(test_state().await);
+        let resp = get(&app, "/auth/login", &[]).await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+}
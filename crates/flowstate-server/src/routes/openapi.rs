@@ -0,0 +1,315 @@
+use axum::Router;
+use serde::Serialize;
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme};
+use utoipa::{Modify, OpenApi, ToSchema};
+use utoipa_swagger_ui::SwaggerUi;
+
+use super::*;
+
+/// Where the specification is served.
+const SPEC_PATH: &str = "/openapi.json";
+
+/// Where Swagger UI is served.
+const DOCS_PATH: &str = "/docs";
+
+/// Serves the specification and a Swagger UI for it. Both are public, like
+/// `/api/health`; requests made from the UI still need an API key.
+pub fn routes() -> Router<AppState> {
+    SwaggerUi::new(DOCS_PATH)
+        .url(SPEC_PATH, ApiDoc::openapi())
+        .into()
+}
+
+/// The body of every error response.
+#[derive(Serialize, ToSchema)]
+pub struct ErrorBody {
+    pub error: String,
+}
+
+/// Registers the bearer scheme that every operation requires unless it
+/// says otherwise.
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+    }
+}
+
+/// The HTTP API. Request and response schemas are derived from the same
+/// types `HttpService` sends and receives, so the two describe one wire
+/// format.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "flowstate",
+        description = "Task pipeline server. Send an API key, or with OIDC configured a token from the identity provider, as `Authorization: Bearer <key>`."
+    ),
+    modifiers(&BearerAuth),
+    security(("api_key" = [])),
+    paths(
+        health::health,
+        health::live,
+        health::ready,
+        health::system_status,
+        status::public_status,
+        store::presigned_get,
+        store::presigned_put,
+        github::github_webhook,
+        oidc::login,
+        oidc::callback,
+        oidc::logout,
+        oidc::me,
+        projects::list_projects,
+        projects::get_project,
+        projects::get_project_by_slug,
+        projects::project_dashboard,
+        projects::create_project,
+        projects::update_project,
+        projects::delete_project,
+        projects::set_repo_token,
+        projects::get_repo_token,
+        projects::get_budget,
+        projects::set_budget_override,
+        projects::clear_budget_override,
+        imports::import_github_issues,
+        export::export_project,
+        approval_rules::list_approval_rules,
+        approval_rules::set_approval_rule,
+        approval_rules::delete_approval_rule,
+        tasks::list_tasks,
+        tasks::get_task,
+        tasks::create_task,
+        tasks::update_task,
+        tasks::bulk_create_tasks,
+        tasks::bulk_update_tasks,
+        tasks::reorder_task,
+        tasks::archive_task,
+        tasks::delete_task,
+        tasks::count_by_status,
+        tasks::list_children,
+        tasks::read_spec,
+        tasks::write_spec,
+        tasks::read_plan,
+        tasks::write_plan,
+        tasks::read_research,
+        tasks::write_research,
+        tasks::read_verification,
+        tasks::write_verification,
+        tasks::write_feedback,
+        tasks::feedback_history,
+        tasks::list_task_field_values,
+        tasks::list_attachments,
+        tasks::upload_attachment,
+        tasks::delete_attachment,
+        tasks::download_attachment,
+        tasks::download_attachment_by_id,
+        tasks::attachment_url,
+        tasks::task_history,
+        board::rollup_board,
+        sprints::create_sprint,
+        sprints::get_sprint,
+        sprints::list_sprints,
+        sprints::update_sprint,
+        sprints::delete_sprint,
+        sprints::archive_sprint,
+        sprints::start_sprint,
+        sprints::complete_sprint,
+        epics::create_epic,
+        epics::get_epic,
+        epics::list_epics,
+        epics::update_epic,
+        epics::delete_epic,
+        events::events,
+        users::create_user,
+        users::get_user,
+        users::list_users,
+        users::update_user,
+        users::delete_user,
+        saved_filters::create_saved_filter,
+        saved_filters::get_saved_filter,
+        saved_filters::list_saved_filters,
+        saved_filters::update_saved_filter,
+        saved_filters::delete_saved_filter,
+        saved_filters::saved_filter_tasks,
+        notifications::watch_task,
+        notifications::unwatch_task,
+        notifications::list_task_watchers,
+        notifications::list_notifications,
+        notifications::mark_notification_read,
+        subscriptions::list_subscriptions,
+        subscriptions::create_subscription,
+        subscriptions::get_subscription,
+        subscriptions::delete_subscription,
+        custom_fields::create_custom_field,
+        custom_fields::get_custom_field,
+        custom_fields::list_custom_fields,
+        custom_fields::update_custom_field,
+        custom_fields::delete_custom_field,
+        task_links::create_task_link,
+        task_links::list_task_links,
+        task_links::delete_task_link,
+        task_clone::clone_task,
+        task_prs::create_task_pr,
+        task_prs::list_task_prs,
+        task_prs::merge_task_pr,
+        task_prs::close_task_pr,
+        task_prs::get_task_pr_checks,
+        task_prs::ready_task_pr,
+        scope_findings::list_findings,
+        scope_findings::write_findings,
+        scope_findings::acknowledge_findings,
+        claude_runs::trigger_claude_run,
+        claude_runs::claim_claude_run,
+        claude_runs::register_runner,
+        claude_runs::update_claude_run_status,
+        claude_runs::update_claude_run_progress,
+        claude_runs::record_claude_run_metrics,
+        claude_runs::list_claude_runs,
+        claude_runs::get_claude_run,
+        claude_runs::get_claude_run_output,
+        claude_runs::requeue_claude_run,
+        claude_runs::set_claude_run_priority,
+        run_logs::append_run_log,
+        run_logs::stream_run_log,
+        infra::gpu_status,
+        infra::gpu_start,
+        infra::gpu_stop,
+        infra::list_runners,
+        infra::set_runner_config,
+        infra::list_registered_runners,
+        infra::drain_runner,
+        infra::patch_runner_config,
+        infra::db_stats,
+        infra::storage_usage,
+        infra::get_watchdog,
+        infra::patch_watchdog,
+        admin::get_maintenance,
+        admin::set_maintenance,
+        admin::list_flags,
+        admin::set_flag,
+        webhooks::list_webhooks,
+        webhooks::create_webhook,
+        webhooks::get_webhook,
+        webhooks::update_webhook,
+        webhooks::delete_webhook,
+        webhooks::list_deliveries,
+        orgs::list_orgs,
+        orgs::create_org,
+        orgs::get_org,
+        orgs::delete_org,
+        orgs::add_project,
+        orgs::remove_project,
+        orgs::create_org_key,
+        metrics::run_metrics,
+    )
+)]
+pub struct ApiDoc;
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use serde_json::Value;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::test_helpers::{test_router, test_state};
+
+    fn spec() -> Value {
+        serde_json::to_value(ApiDoc::openapi()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn serves_spec_and_docs() {
+        let app = test_router().await;
+        let resp = app
+            .clone()
+            .oneshot(Request::get(SPEC_PATH).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let served: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(served, spec());
+        assert!(served["paths"]["/api/tasks/{id}"]["put"].is_object());
+        assert!(served["components"]["schemas"]["Task"].is_object());
+
+        let resp = app
+            .oneshot(
+                Request::get(format!("{DOCS_PATH}/"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn path_parameters_are_declared() {
+        let spec = spec();
+        for (path, item) in spec["paths"].as_object().unwrap() {
+            let names: Vec<&str> = path
+                .split('/')
+                .filter_map(|s| s.strip_prefix('{')?.strip_suffix('}'))
+                .collect();
+            for (method, op) in item.as_object().unwrap() {
+                let declared: Vec<&str> = op["parameters"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter(|p| p["in"] == "path")
+                    .map(|p| p["name"].as_str().unwrap())
+                    .collect();
+                assert_eq!(declared, names, "{method} {path}");
+            }
+        }
+    }
+
+    /// Every documented operation reaches a handler, so the spec cannot
+    /// describe routes the server does not have.
+    #[tokio::test]
+    async fn every_operation_is_routed() {
+        // Tell unmatched paths apart from handlers' own 404s
+        let app = build_router(test_state().await).fallback(|| async { StatusCode::IM_A_TEAPOT });
+        let spec = spec();
+        let mut operations = 0;
+        for (path, item) in spec["paths"].as_object().unwrap() {
+            let uri: String = path
+                .split('/')
+                .map(|s| if s.starts_with('{') { "x" } else { s })
+                .collect::<Vec<_>>()
+                .join("/");
+            for method in item.as_object().unwrap().keys() {
+                let method = Method::from_bytes(method.to_uppercase().as_bytes()).unwrap();
+                let resp = app
+                    .clone()
+                    .oneshot(
+                        Request::builder()
+                            .method(method.clone())
+                            .uri(&uri)
+                            .body(Body::empty())
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                assert!(
+                    !matches!(
+                        resp.status(),
+                        StatusCode::IM_A_TEAPOT | StatusCode::METHOD_NOT_ALLOWED
+                    ),
+                    "{method} {path} is documented but not routed"
+                );
+                operations += 1;
+            }
+        }
+        assert!(operations > 100);
+    }
+}
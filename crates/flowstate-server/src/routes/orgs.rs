@@ -0,0 +1,547 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post, put},
+    Json, Router,
+};
+use flowstate_core::organization::{CreateOrganization, Organization};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::openapi::ErrorBody;
+use super::projects::redact_token;
+use super::AppState;
+use crate::auth::{self, ProjectScope};
+
+type ApiError = (StatusCode, Json<Value>);
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/admin/orgs", get(list_orgs).post(create_org))
+        .route("/admin/orgs/{id}", get(get_org).delete(delete_org))
+        .route(
+            "/admin/orgs/{id}/projects/{project_id}",
+            put(add_project).delete(remove_project),
+        )
+        .route("/admin/orgs/{id}/keys", post(create_org_key))
+}
+
+/// An organization with the projects it owns.
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+struct OrganizationDetail {
+    #[serde(flatten)]
+    org: Organization,
+    project_ids: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+struct CreateOrgKey {
+    #[serde(default)]
+    name: String,
+}
+
+/// A new organization key. `key` is not shown again.
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+struct OrgKey {
+    id: String,
+    name: String,
+    org_id: String,
+    key: String,
+}
+
+/// Managing organizations hands out access to projects, so it takes a key
+/// that already reaches every project.
+fn require_server_wide(scope: &ProjectScope) -> Result<(), ApiError> {
+    if scope.projects().is_some() {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(json!({ "error": "organizations are managed with a server-wide key" })),
+        ));
+    }
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/orgs",
+    tag = "organizations",
+    responses(
+        (status = 200, body = [Organization]),
+        (status = 403, body = ErrorBody)
+    )
+)]
+async fn list_orgs(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+) -> Result<Json<Value>, ApiError> {
+    require_server_wide(&scope)?;
+    let orgs = state.db.list_organizations().await.map_err(to_error)?;
+    Ok(Json(json!(orgs)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/admin/orgs",
+    tag = "organizations",
+    request_body = CreateOrganization,
+    responses(
+        (status = 201, body = Organization),
+        (status = 400, body = ErrorBody),
+        (status = 403, body = ErrorBody),
+        (status = 409, body = ErrorBody)
+    )
+)]
+async fn create_org(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+    Json(input): Json<CreateOrganization>,
+) -> Result<(StatusCode, Json<Value>), ApiError> {
+    require_server_wide(&scope)?;
+    input.validate().map_err(|e| bad_request(e.to_string()))?;
+    let orgs = state.db.list_organizations().await.map_err(to_error)?;
+    if orgs.iter().any(|o| o.slug == input.slug) {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({ "error": format!("organization slug {} is taken", input.slug) })),
+        ));
+    }
+    let org = state
+        .db
+        .create_organization(&input)
+        .await
+        .map_err(to_error)?;
+    Ok((StatusCode::CREATED, Json(json!(org))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/orgs/{id}",
+    tag = "organizations",
+    responses(
+        (status = 200, body = OrganizationDetail),
+        (status = 403, body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn get_org(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    require_server_wide(&scope)?;
+    let org = state.db.get_organization(&id).await.map_err(to_error)?;
+    let project_ids = state
+        .db
+        .list_organization_project_ids(&id)
+        .await
+        .map_err(to_error)?;
+    Ok(Json(json!(OrganizationDetail { org, project_ids })))
+}
+
+/// Delete an organization and revoke its keys. Refused while it still owns
+/// projects, so none is left behind by accident.
+#[utoipa::path(
+    delete,
+    path = "/admin/orgs/{id}",
+    tag = "organizations",
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 403, body = ErrorBody),
+        (status = 404, body = ErrorBody),
+        (status = 409, body = ErrorBody)
+    )
+)]
+async fn delete_org(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    require_server_wide(&scope)?;
+    state.db.get_organization(&id).await.map_err(to_error)?;
+    let projects = state
+        .db
+        .list_organization_project_ids(&id)
+        .await
+        .map_err(to_error)?;
+    if !projects.is_empty() {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({
+                "error": format!("organization still owns {} project(s)", projects.len())
+            })),
+        ));
+    }
+    state
+        .db
+        .delete_organization(&id)
+        .await
+        .map(|()| StatusCode::NO_CONTENT)
+        .map_err(to_error)
+}
+
+/// Hand a project to the organization, taking it from any other.
+#[utoipa::path(
+    put,
+    path = "/admin/orgs/{id}/projects/{project_id}",
+    tag = "organizations",
+    responses(
+        (status = 200, body = flowstate_core::Project),
+        (status = 403, body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn add_project(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+    Path((id, project_id)): Path<(String, String)>,
+) -> Result<Json<Value>, ApiError> {
+    require_server_wide(&scope)?;
+    state.db.get_organization(&id).await.map_err(to_error)?;
+    let project = state
+        .db
+        .set_project_org(&project_id, Some(&id))
+        .await
+        .map_err(to_error)?;
+    Ok(Json(redact_token(project)))
+}
+
+/// Take a project back from the organization; only server-wide keys reach
+/// it afterwards.
+#[utoipa::path(
+    delete,
+    path = "/admin/orgs/{id}/projects/{project_id}",
+    tag = "organizations",
+    responses(
+        (status = 204, description = "Removed"),
+        (status = 403, body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn remove_project(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+    Path((id, project_id)): Path<(String, String)>,
+) -> Result<StatusCode, ApiError> {
+    require_server_wide(&scope)?;
+    let project = state.db.get_project(&project_id).await.map_err(to_error)?;
+    if project.org_id.as_deref() != Some(id.as_str()) {
+        return Err(to_error(flowstate_db::DbError::NotFound(format!(
+            "project {project_id} in organization {id}"
+        ))));
+    }
+    state
+        .db
+        .set_project_org(&project_id, None)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(to_error)
+}
+
+/// Create an API key belonging to the organization. The response carries
+/// the plaintext key; it is not shown again.
+#[utoipa::path(
+    post,
+    path = "/admin/orgs/{id}/keys",
+    tag = "organizations",
+    request_body = CreateOrgKey,
+    responses(
+        (status = 201, body = OrgKey),
+        (status = 403, body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn create_org_key(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+    Path(id): Path<String>,
+    Json(input): Json<CreateOrgKey>,
+) -> Result<(StatusCode, Json<Value>), ApiError> {
+    require_server_wide(&scope)?;
+    let org = state.db.get_organization(&id).await.map_err(to_error)?;
+    let raw_key = auth::generate_api_key();
+    let key = state
+        .db
+        .insert_api_key(&input.name, &auth::sha256_hex(&raw_key))
+        .await
+        .map_err(to_error)?;
+    let key = state
+        .db
+        .set_api_key_org(&key.id, Some(&org.id))
+        .await
+        .map_err(to_error)?;
+    Ok((
+        StatusCode::CREATED,
+        Json(json!(OrgKey {
+            id: key.id,
+            name: key.name,
+            org_id: org.id,
+            key: raw_key,
+        })),
+    ))
+}
+
+fn bad_request(msg: String) -> ApiError {
+    (StatusCode::BAD_REQUEST, Json(json!({ "error": msg })))
+}
+
+fn to_error(e: flowstate_db::DbError) -> ApiError {
+    let status = match &e {
+        flowstate_db::DbError::NotFound(_) => StatusCode::NOT_FOUND,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(json!({ "error": e.to_string() })))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::routes::build_router;
+    use crate::test_helpers::test_state_with_auth;
+
+    async fn call(
+        app: &Router,
+        method: &str,
+        uri: &str,
+        key: &str,
+        body: Value,
+    ) -> (StatusCode, Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {key}"))
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let resp = app.clone().oneshot(request).await.unwrap();
+        let status = resp.status();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    #[tokio::test]
+    async fn org_keys_only_reach_their_organization() {
+        let (state, admin) = test_state_with_auth().await;
+        let app = build_router(state);
+
+        let mut keys = Vec::new();
+        for slug in ["acme", "globex"] {
+            let (status, org) = call(
+                &app,
+                "POST",
+                "/admin/orgs",
+                &admin,
+                json!({ "name": slug, "slug": slug }),
+            )
+            .await;
+            assert_eq!(status, StatusCode::CREATED);
+            let (status, key) = call(
+                &app,
+                "POST",
+                &format!("/admin/orgs/{}/keys", org["id"].as_str().unwrap()),
+                &admin,
+                json!({ "name": format!("{slug}-ci") }),
+            )
+            .await;
+            assert_eq!(status, StatusCode::CREATED);
+            keys.push((org, key["key"].as_str().unwrap().to_string()));
+        }
+        let (status, _) = call(
+            &app,
+            "POST",
+            "/admin/orgs",
+            &admin,
+            json!({ "name": "Acme again", "slug": "acme" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (acme, acme_key) = &keys[0];
+        let (_, globex_key) = &keys[1];
+
+        // An organization without projects sees none, and has nothing to claim
+        let (_, listed) = call(&app, "GET", "/api/tasks", acme_key, Value::Null).await;
+        assert_eq!(listed, json!([]));
+        let (status, _) = call(
+            &app,
+            "POST",
+            "/api/claude-runs/claim",
+            acme_key,
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        // Projects made with an organization's key belong to it
+        let (status, project) = call(
+            &app,
+            "POST",
+            "/api/projects",
+            acme_key,
+            json!({ "name": "Rockets", "slug": "rockets" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(project["org_id"], acme["id"]);
+        let project_id = project["id"].as_str().unwrap();
+        let (_, listed) = call(&app, "GET", "/api/projects", acme_key, Value::Null).await;
+        assert_eq!(listed.as_array().unwrap().len(), 1);
+        let (_, listed) = call(&app, "GET", "/api/projects", globex_key, Value::Null).await;
+        assert!(listed.as_array().unwrap().is_empty());
+        let uri = format!("/api/projects/{project_id}");
+        let (status, _) = call(&app, "GET", &uri, globex_key, Value::Null).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = call(
+            &app,
+            "GET",
+            &format!("/api/sprints?project_id={project_id}"),
+            globex_key,
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        // Runners belong to the organization of the key that registered them
+        let register = |id: &str| json!({ "runner_id": id, "capability": "standard" });
+        let (status, _) = call(
+            &app,
+            "POST",
+            "/api/runners/register",
+            acme_key,
+            register("acme-1"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = call(
+            &app,
+            "POST",
+            "/api/runners/register",
+            globex_key,
+            register("acme-1"),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (_, runners) = call(&app, "GET", "/api/infra/runners", globex_key, Value::Null).await;
+        assert_eq!(runners, json!([]));
+        let (_, runners) = call(&app, "GET", "/api/infra/runners", &admin, Value::Null).await;
+        assert_eq!(runners.as_array().unwrap().len(), 1);
+
+        // Server-wide routes are closed to organization keys
+        let (status, _) = call(&app, "GET", "/admin/orgs", acme_key, Value::Null).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = call(&app, "GET", "/api/infra/db", acme_key, Value::Null).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // Users are shared by every organization, so their routes are closed
+        // too; notifications only show the organization's own tasks
+        let (status, user) = call(
+            &app,
+            "POST",
+            "/api/users",
+            &admin,
+            json!({ "name": "Alice", "email": "" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let user_id = user["id"].as_str().unwrap();
+        for (method, uri) in [
+            ("GET", "/api/users".to_string()),
+            ("DELETE", format!("/api/users/{user_id}")),
+        ] {
+            let (status, _) = call(&app, method, &uri, acme_key, Value::Null).await;
+            assert_eq!(status, StatusCode::FORBIDDEN, "{method} {uri}");
+        }
+        let (_, outside) = call(
+            &app,
+            "POST",
+            "/api/projects",
+            &admin,
+            json!({ "name": "Shared", "slug": "shared" }),
+        )
+        .await;
+        let (_, task) = call(
+            &app,
+            "POST",
+            "/api/tasks",
+            &admin,
+            json!({
+                "project_id": outside["id"],
+                "title": "Outside",
+                "status": "todo",
+                "priority": "medium",
+            }),
+        )
+        .await;
+        let task_id = task["id"].as_str().unwrap();
+        let watch = json!({ "user_id": user_id });
+        call(
+            &app,
+            "POST",
+            &format!("/api/tasks/{task_id}/watch"),
+            &admin,
+            watch,
+        )
+        .await;
+        let update = json!({ "priority": "urgent" });
+        call(
+            &app,
+            "PUT",
+            &format!("/api/tasks/{task_id}"),
+            &admin,
+            update,
+        )
+        .await;
+        let list = format!("/api/notifications?user_id={user_id}");
+        let (_, notifications) = call(&app, "GET", &list, &admin, Value::Null).await;
+        assert_eq!(notifications.as_array().unwrap().len(), 1);
+        let (_, notifications) = call(&app, "GET", &list, acme_key, Value::Null).await;
+        assert_eq!(notifications, json!([]));
+
+        // Moving the project hands it to the other organization
+        let acme_id = acme["id"].as_str().unwrap();
+        let (status, _) = call(
+            &app,
+            "DELETE",
+            &format!("/admin/orgs/{acme_id}"),
+            &admin,
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (_, globex) = call(&app, "GET", "/admin/orgs", &admin, Value::Null).await;
+        let globex_id = globex[1]["id"].as_str().unwrap();
+        let (status, _) = call(
+            &app,
+            "PUT",
+            &format!("/admin/orgs/{globex_id}/projects/{project_id}"),
+            &admin,
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = call(&app, "GET", &uri, globex_key, Value::Null).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = call(&app, "GET", &uri, acme_key, Value::Null).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        // Deleting the organization revokes its keys
+        let (status, _) = call(
+            &app,
+            "DELETE",
+            &format!("/admin/orgs/{acme_id}"),
+            &admin,
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = call(&app, "GET", "/api/projects", acme_key, Value::Null).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+}
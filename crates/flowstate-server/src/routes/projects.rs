@@ -4,12 +4,17 @@ use axum::{
     routing::{get, put},
     Json, Router,
 };
+use chrono::{DateTime, Utc};
+use flowstate_core::dashboard::ProjectDashboard;
 use flowstate_core::project::{CreateProject, Project, UpdateProject};
 use flowstate_service::TaskService;
 use serde_json::{json, Value};
 
+use crate::auth::ProjectScope;
+use crate::budget::{self, BudgetStatus};
 use crate::crypto;
 
+use super::openapi::ErrorBody;
 use super::AppState;
 
 pub fn routes() -> Router<AppState> {
@@ -20,14 +25,20 @@ pub fn routes() -> Router<AppState> {
             get(get_project).put(update_project).delete(delete_project),
         )
         .route("/api/projects/by-slug/{slug}", get(get_project_by_slug))
+        .route("/api/projects/{id}/dashboard", get(project_dashboard))
         .route(
             "/api/projects/{id}/repo-token",
             put(set_repo_token).get(get_repo_token),
         )
+        .route("/api/projects/{id}/budget", get(get_budget))
+        .route(
+            "/api/projects/{id}/budget/override",
+            put(set_budget_override).delete(clear_budget_override),
+        )
 }
 
 /// Strip the encrypted token from project responses, replace with a boolean flag.
-fn redact_token(mut project: Project) -> Value {
+pub(crate) fn redact_token(mut project: Project) -> Value {
     let has_token = project.repo_token.is_some();
     project.repo_token = None;
     let mut val = json!(project);
@@ -39,17 +50,33 @@ fn redact_tokens(projects: Vec<Project>) -> Value {
     json!(projects.into_iter().map(redact_token).collect::<Vec<_>>())
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/projects",
+    tag = "projects",
+    responses((status = 200, body = [Project]))
+)]
 async fn list_projects(
     State(state): State<AppState>,
+    scope: ProjectScope,
 ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
     state
         .service
         .list_projects()
         .await
-        .map(|p| Json(redact_tokens(p)))
+        .map(|mut p| {
+            p.retain(|project| scope.allows(&project.id));
+            Json(redact_tokens(p))
+        })
         .map_err(to_error)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/projects/{id}",
+    tag = "projects",
+    responses((status = 200, body = Project), (status = 404, body = ErrorBody))
+)]
 async fn get_project(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -62,6 +89,12 @@ async fn get_project(
         .map_err(to_error)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/projects/by-slug/{slug}",
+    tag = "projects",
+    responses((status = 200, body = Project), (status = 404, body = ErrorBody))
+)]
 async fn get_project_by_slug(
     State(state): State<AppState>,
     Path(slug): Path<String>,
@@ -74,23 +107,69 @@ async fn get_project_by_slug(
         .map_err(to_error)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/projects/{id}/dashboard",
+    tag = "projects",
+    responses((status = 200, body = ProjectDashboard), (status = 404, body = ErrorBody))
+)]
+async fn project_dashboard(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    state
+        .service
+        .project_dashboard(&id)
+        .await
+        .map(|d| Json(json!(d)))
+        .map_err(to_error)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/projects",
+    tag = "projects",
+    request_body = CreateProject,
+    responses((status = 201, body = Project), (status = 400, body = ErrorBody))
+)]
 async fn create_project(
     State(state): State<AppState>,
+    scope: ProjectScope,
     Json(input): Json<CreateProject>,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
-    state
+    let mut project = state
         .service
         .create_project(&input)
         .await
-        .map(|p| (StatusCode::CREATED, Json(redact_token(p))))
-        .map_err(to_error)
+        .map_err(to_error)?;
+    // A project made with an organization's key belongs to the organization
+    if let Some(org_id) = scope.org_id() {
+        project = state
+            .db
+            .set_project_org(&project.id, Some(org_id))
+            .await
+            .map_err(|e| to_error(e.into()))?;
+    }
+    Ok((StatusCode::CREATED, Json(redact_token(project))))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/projects/{id}",
+    tag = "projects",
+    request_body = UpdateProject,
+    responses(
+        (status = 200, body = Project),
+        (status = 400, body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
 async fn update_project(
     State(state): State<AppState>,
     Path(id): Path<String>,
     Json(input): Json<UpdateProject>,
 ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    input.validate().map_err(|e| to_error(e.into()))?;
     state
         .service
         .update_project(&id, &input)
@@ -99,6 +178,12 @@ async fn update_project(
         .map_err(to_error)
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/projects/{id}",
+    tag = "projects",
+    responses((status = 204, description = "Deleted"), (status = 404, body = ErrorBody))
+)]
 async fn delete_project(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -111,12 +196,19 @@ async fn delete_project(
         .map_err(to_error)
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, utoipa::ToSchema)]
 struct SetRepoTokenInput {
     token: String,
 }
 
 /// PUT /api/projects/{id}/repo-token — encrypt and store.
+#[utoipa::path(
+    put,
+    path = "/api/projects/{id}/repo-token",
+    tag = "projects",
+    request_body = SetRepoTokenInput,
+    responses((status = 200, body = Object), (status = 404, body = ErrorBody))
+)]
 async fn set_repo_token(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -141,6 +233,15 @@ async fn set_repo_token(
 }
 
 /// GET /api/projects/{id}/repo-token — decrypt and return (for runner use).
+#[utoipa::path(
+    get,
+    path = "/api/projects/{id}/repo-token",
+    tag = "projects",
+    responses(
+        (status = 200, description = "`{\"token\": ...}`", body = Object),
+        (status = 404, description = "Unknown project, or no token set", body = ErrorBody)
+    )
+)]
 async fn get_repo_token(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -162,6 +263,94 @@ async fn get_repo_token(
     }
 }
 
+/// GET /api/projects/{id}/budget — this month's spend against the budget.
+#[utoipa::path(
+    get,
+    path = "/api/projects/{id}/budget",
+    tag = "projects",
+    responses((status = 200, body = BudgetStatus), (status = 404, body = ErrorBody))
+)]
+async fn get_budget(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<BudgetStatus>, (StatusCode, Json<Value>)> {
+    let project = state.service.get_project(&id).await.map_err(to_error)?;
+    budget::status(&*state.db, &project, Utc::now())
+        .await
+        .map(Json)
+        .map_err(|e| to_error(e.into()))
+}
+
+#[derive(serde::Deserialize, utoipa::ToSchema)]
+struct BudgetOverrideInput {
+    /// When the override lapses; the end of the current month by default.
+    #[serde(default)]
+    until: Option<DateTime<Utc>>,
+}
+
+/// PUT /api/projects/{id}/budget/override — let Builds through past the budget.
+#[utoipa::path(
+    put,
+    path = "/api/projects/{id}/budget/override",
+    tag = "projects",
+    request_body = BudgetOverrideInput,
+    responses(
+        (status = 200, body = BudgetStatus),
+        (status = 400, description = "`until` is in the past", body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn set_budget_override(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(input): Json<BudgetOverrideInput>,
+) -> Result<Json<BudgetStatus>, (StatusCode, Json<Value>)> {
+    let now = Utc::now();
+    let until = input.until.unwrap_or_else(|| budget::next_month_start(now));
+    if until <= now {
+        return Err(to_error(flowstate_service::ServiceError::InvalidInput(
+            "until must be in the future".into(),
+        )));
+    }
+    write_budget_override(&state, &id, Some(until)).await
+}
+
+/// DELETE /api/projects/{id}/budget/override — enforce the budget again.
+#[utoipa::path(
+    delete,
+    path = "/api/projects/{id}/budget/override",
+    tag = "projects",
+    responses((status = 200, body = BudgetStatus), (status = 404, body = ErrorBody))
+)]
+async fn clear_budget_override(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<BudgetStatus>, (StatusCode, Json<Value>)> {
+    write_budget_override(&state, &id, None).await
+}
+
+async fn write_budget_override(
+    state: &AppState,
+    id: &str,
+    until: Option<DateTime<Utc>>,
+) -> Result<Json<BudgetStatus>, (StatusCode, Json<Value>)> {
+    let project = state
+        .service
+        .update_project(
+            id,
+            &UpdateProject {
+                budget_override_until: Some(until),
+                ..Default::default()
+            },
+        )
+        .await
+        .map_err(to_error)?;
+    budget::status(&*state.db, &project, Utc::now())
+        .await
+        .map(Json)
+        .map_err(|e| to_error(e.into()))
+}
+
 fn to_error(e: flowstate_service::ServiceError) -> (StatusCode, Json<Value>) {
     let (status, msg) = match &e {
         flowstate_service::ServiceError::NotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
@@ -257,6 +446,21 @@ mod tests {
         let updated: Value = serde_json::from_slice(&bytes).unwrap();
         assert_eq!(updated["name"], "Renamed Project");
 
+        // A concurrency cap below 1 is rejected
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::PUT)
+                    .uri(format!("/api/projects/{id}"))
+                    .header("content-type", "application/json")
+                    .body(Body::from(json!({"max_concurrent_runs": 0}).to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
         // DELETE /api/projects/{id} → 204
         let resp = app
             .clone()
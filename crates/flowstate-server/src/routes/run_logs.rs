@@ -0,0 +1,403 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::sse::{Event, KeepAlive, Sse},
+    routing::{get, post},
+    Json, Router,
+};
+use flowstate_core::claude_run::ClaudeRunStatus;
+use flowstate_service::TaskService;
+use futures_util::{Stream, StreamExt};
+use serde_json::{json, Value};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use super::openapi::ErrorBody;
+use super::AppState;
+
+/// Most of a run's log kept for subscribers that join late. Older output is
+/// dropped from the front.
+const MAX_BACKLOG: usize = 256 * 1024;
+
+/// Chunks a subscriber may fall behind by before it skips some.
+const CHANNEL_CAPACITY: usize = 256;
+
+/// How often an open stream checks whether its run has finished without
+/// the runner saying so, e.g. because the watchdog timed it out.
+const STATUS_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/api/claude-runs/{id}/logs/stream", get(stream_run_log))
+}
+
+/// Routes only runners call; see [`super::claude_runs::runner_routes`].
+pub fn runner_routes() -> Router<AppState> {
+    Router::new().route("/api/claude-runs/{id}/logs", post(append_run_log))
+}
+
+#[derive(Debug, Clone)]
+enum LogEvent {
+    Chunk(Arc<str>),
+    End(ClaudeRunStatus),
+}
+
+struct RunLog {
+    backlog: String,
+    sender: broadcast::Sender<LogEvent>,
+}
+
+impl RunLog {
+    fn new() -> Self {
+        Self {
+            backlog: String::new(),
+            sender: broadcast::channel(CHANNEL_CAPACITY).0,
+        }
+    }
+}
+
+/// Live output of running runs, as pushed by their runners. Held in memory
+/// only: a log exists from its first chunk or subscriber until the run
+/// finishes.
+#[derive(Default)]
+pub struct RunLogs {
+    logs: Mutex<HashMap<String, RunLog>>,
+}
+
+impl RunLogs {
+    fn append(&self, run_id: &str, text: &str) {
+        let mut logs = self.logs.lock().unwrap();
+        let log = logs.entry(run_id.to_string()).or_insert_with(RunLog::new);
+        log.backlog.push_str(text);
+        if log.backlog.len() > MAX_BACKLOG {
+            let mut cut = log.backlog.len() - MAX_BACKLOG;
+            while !log.backlog.is_char_boundary(cut) {
+                cut += 1;
+            }
+            log.backlog.drain(..cut);
+        }
+        let _ = log.sender.send(LogEvent::Chunk(text.into()));
+    }
+
+    /// The log so far and a receiver for what follows.
+    fn subscribe(&self, run_id: &str) -> (String, broadcast::Receiver<LogEvent>) {
+        let mut logs = self.logs.lock().unwrap();
+        let log = logs.entry(run_id.to_string()).or_insert_with(RunLog::new);
+        (log.backlog.clone(), log.sender.subscribe())
+    }
+
+    /// Drop the run's log and end its open streams.
+    pub fn finish(&self, run_id: &str, status: ClaudeRunStatus) {
+        if let Some(log) = self.logs.lock().unwrap().remove(run_id) {
+            let _ = log.sender.send(LogEvent::End(status));
+        }
+    }
+}
+
+/// Whether a run in `status` will produce no more output.
+pub(crate) fn is_finished(status: ClaudeRunStatus) -> bool {
+    matches!(
+        status,
+        ClaudeRunStatus::Completed
+            | ClaudeRunStatus::Failed
+            | ClaudeRunStatus::Cancelled
+            | ClaudeRunStatus::TimedOut
+    )
+}
+
+/// Append a chunk of agent output to a run's live log. The body is the raw
+/// text. Refused once the run has finished.
+#[utoipa::path(
+    post,
+    path = "/api/claude-runs/{id}/logs",
+    tag = "runners",
+    request_body(content = String, content_type = "text/plain"),
+    responses(
+        (status = 204, description = "Appended"),
+        (status = 404, body = ErrorBody),
+        (status = 409, description = "The run has finished", body = ErrorBody)
+    )
+)]
+async fn append_run_log(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    text: String,
+) -> Result<StatusCode, (StatusCode, Json<Value>)> {
+    let run = state.service.get_claude_run(&id).await.map_err(to_error)?;
+    if is_finished(run.status) {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(json!({ "error": format!("run is {}", run.status) })),
+        ));
+    }
+    state.run_logs.append(&id, &text);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Stream a run's output as the runner pushes it: first what has been
+/// logged so far, then each new chunk, as `log` events with `{"text": ...}`.
+/// Ends with an `end` event carrying the run's final status.
+#[utoipa::path(
+    get,
+    path = "/api/claude-runs/{id}/logs/stream",
+    tag = "runs",
+    responses(
+        (status = 200, description = "Server-sent `log` and `end` events", content_type = "text/event-stream"),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn stream_run_log(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, (StatusCode, Json<Value>)> {
+    let run = state.service.get_claude_run(&id).await.map_err(to_error)?;
+    let (backlog, rx) = state.run_logs.subscribe(&id);
+    if is_finished(run.status) {
+        state.run_logs.finish(&id, run.status);
+    }
+
+    let first = (!backlog.is_empty()).then(|| log_event(&backlog));
+    let mut check = tokio::time::interval(STATUS_CHECK_INTERVAL);
+    check.reset();
+    let shutdown = state.shutdown.clone().cancelled_owned();
+    let stream = futures_util::stream::unfold(
+        (state, id, rx, check, first, false),
+        |(state, id, mut rx, mut check, first, done)| async move {
+            if done {
+                return None;
+            }
+            if let Some(event) = first {
+                return Some((Ok(event), (state, id, rx, check, None, false)));
+            }
+            loop {
+                tokio::select! {
+                    received = rx.recv() => match received {
+                        Ok(LogEvent::Chunk(text)) => {
+                            let event = log_event(&text);
+                            return Some((Ok(event), (state, id, rx, check, None, false)));
+                        }
+                        Ok(LogEvent::End(status)) => {
+                            let event = end_event(status);
+                            return Some((Ok(event), (state, id, rx, check, None, true)));
+                        }
+                        Err(RecvError::Lagged(missed)) => {
+                            let event = Event::default()
+                                .event("lagged")
+                                .json_data(json!({ "missed": missed }))
+                                .unwrap();
+                            return Some((Ok(event), (state, id, rx, check, None, false)));
+                        }
+                        Err(RecvError::Closed) => return None,
+                    },
+                    _ = check.tick() => {
+                        if let Ok(run) = state.service.get_claude_run(&id).await {
+                            if is_finished(run.status) {
+                                state.run_logs.finish(&id, run.status);
+                            }
+                        }
+                    }
+                }
+            }
+        },
+    )
+    .take_until(shutdown);
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+fn log_event(text: &str) -> Event {
+    Event::default()
+        .event("log")
+        .json_data(json!({ "text": text }))
+        .unwrap()
+}
+
+fn end_event(status: ClaudeRunStatus) -> Event {
+    Event::default()
+        .event("end")
+        .json_data(json!({ "status": status }))
+        .unwrap()
+}
+
+fn to_error(e: flowstate_service::ServiceError) -> (StatusCode, Json<Value>) {
+    let (status, msg) = match &e {
+        flowstate_service::ServiceError::NotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
+        flowstate_service::ServiceError::InvalidInput(_) => {
+            (StatusCode::BAD_REQUEST, e.to_string())
+        }
+        flowstate_service::ServiceError::Internal(_) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        }
+    };
+    (status, Json(json!({ "error": msg })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::test_router;
+    use axum::body::Body;
+    use axum::http::{Method, Request};
+    use futures_util::StreamExt;
+    use tower::ServiceExt;
+
+    async fn send(app: &Router, method: Method, uri: &str, body: String) -> (StatusCode, Value) {
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = resp.status();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    /// Queue a research run and claim it, returning its id.
+    async fn running_run(app: &Router) -> String {
+        let (_, project) = send(
+            app,
+            Method::POST,
+            "/api/projects",
+            json!({"name": "Logs", "slug": "logs"}).to_string(),
+        )
+        .await;
+        let (_, task) = send(
+            app,
+            Method::POST,
+            "/api/tasks",
+            json!({
+                "project_id": project["id"],
+                "title": "Noisy",
+                "status": "todo",
+                "priority": "medium",
+            })
+            .to_string(),
+        )
+        .await;
+        let task_id = task["id"].as_str().unwrap();
+        send(
+            app,
+            Method::POST,
+            &format!("/api/tasks/{task_id}/claude-runs"),
+            json!({"action": "research"}).to_string(),
+        )
+        .await;
+        let (_, run) = send(app, Method::POST, "/api/claude-runs/claim", String::new()).await;
+        run["id"].as_str().unwrap().to_string()
+    }
+
+    /// Read SSE events until the stream ends.
+    async fn read_all(app: &Router, run_id: &str) -> Vec<(String, Value)> {
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/claude-runs/{run_id}/logs/stream"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let mut body = resp.into_body().into_data_stream();
+        let mut text = String::new();
+        while let Some(chunk) = tokio::time::timeout(Duration::from_secs(5), body.next())
+            .await
+            .expect("stream did not end")
+        {
+            text.push_str(std::str::from_utf8(&chunk.unwrap()).unwrap());
+        }
+        text.split("\n\n")
+            .filter_map(|frame| {
+                let name = frame.lines().find_map(|l| l.strip_prefix("event: "))?;
+                let data = frame.lines().find_map(|l| l.strip_prefix("data: "))?;
+                Some((name.to_string(), serde_json::from_str(data).unwrap()))
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn streams_backlog_then_live_chunks_until_the_run_ends() {
+        let app = test_router().await;
+        let run_id = running_run(&app).await;
+        let logs = format!("/api/claude-runs/{run_id}/logs");
+
+        let (status, _) = send(&app, Method::POST, &logs, "cloning\r\n".into()).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        let reader = tokio::spawn({
+            let app = app.clone();
+            let run_id = run_id.clone();
+            async move { read_all(&app, &run_id).await }
+        });
+        // Let the reader subscribe before pushing more
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        send(&app, Method::POST, &logs, "thinking...\n".into()).await;
+        send(
+            &app,
+            Method::PUT,
+            &format!("/api/claude-runs/{run_id}/status"),
+            json!({"status": "completed", "exit_code": 0}).to_string(),
+        )
+        .await;
+
+        let events = reader.await.unwrap();
+        assert_eq!(
+            events,
+            [
+                ("log".to_string(), json!({"text": "cloning\r\n"})),
+                ("log".to_string(), json!({"text": "thinking...\n"})),
+                ("end".to_string(), json!({"status": "completed"})),
+            ]
+        );
+
+        // Finished: no more output is taken and a new stream ends at once
+        let (status, _) = send(&app, Method::POST, &logs, "late".into()).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(
+            read_all(&app, &run_id).await,
+            [("end".to_string(), json!({"status": "completed"}))]
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_runs_are_not_found() {
+        let app = test_router().await;
+        let (status, _) = send(&app, Method::POST, "/api/claude-runs/nope/logs", "x".into()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let resp = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/claude-runs/nope/logs/stream")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn backlog_keeps_the_tail() {
+        let logs = RunLogs::default();
+        logs.append("r", &"a".repeat(MAX_BACKLOG));
+        logs.append("r", "é-end");
+        let (backlog, _) = logs.subscribe("r");
+        assert_eq!(backlog.len(), MAX_BACKLOG);
+        assert!(backlog.ends_with("é-end"));
+    }
+}
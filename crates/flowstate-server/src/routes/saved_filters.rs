@@ -0,0 +1,314 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get, post, put},
+    Json, Router,
+};
+use flowstate_core::saved_filter::{CreateSavedFilter, SavedFilter, UpdateSavedFilter};
+use flowstate_core::task::Task;
+use flowstate_service::TaskService;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::openapi::ErrorBody;
+use super::AppState;
+use crate::auth::ProjectScope;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/saved-filters", post(create_saved_filter))
+        .route("/api/saved-filters", get(list_saved_filters))
+        .route("/api/saved-filters/{id}", get(get_saved_filter))
+        .route("/api/saved-filters/{id}", put(update_saved_filter))
+        .route("/api/saved-filters/{id}", delete(delete_saved_filter))
+        .route("/api/saved-filters/{id}/tasks", get(saved_filter_tasks))
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+struct ListSavedFiltersQuery {
+    project_id: String,
+    /// Shared filters plus this user's own; all filters when absent.
+    user_id: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/saved-filters",
+    tag = "saved filters",
+    request_body = CreateSavedFilter,
+    responses(
+        (status = 201, body = SavedFilter),
+        (status = 400, body = ErrorBody)
+    )
+)]
+async fn create_saved_filter(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+    Json(input): Json<CreateSavedFilter>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    scope.check(&input.project_id)?;
+    state
+        .service
+        .create_saved_filter(&input)
+        .await
+        .map(|f| (StatusCode::CREATED, Json(json!(f))))
+        .map_err(to_error)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/saved-filters/{id}",
+    tag = "saved filters",
+    responses(
+        (status = 200, body = SavedFilter),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn get_saved_filter(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    state
+        .service
+        .get_saved_filter(&id)
+        .await
+        .map(|f| Json(json!(f)))
+        .map_err(to_error)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/saved-filters",
+    tag = "saved filters",
+    params(ListSavedFiltersQuery),
+    responses((status = 200, body = [SavedFilter]))
+)]
+async fn list_saved_filters(
+    State(state): State<AppState>,
+    Query(q): Query<ListSavedFiltersQuery>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    state
+        .service
+        .list_saved_filters(&q.project_id, q.user_id.as_deref())
+        .await
+        .map(|f| Json(json!(f)))
+        .map_err(to_error)
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/saved-filters/{id}",
+    tag = "saved filters",
+    request_body = UpdateSavedFilter,
+    responses(
+        (status = 200, body = SavedFilter),
+        (status = 400, body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn update_saved_filter(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(update): Json<UpdateSavedFilter>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    state
+        .service
+        .update_saved_filter(&id, &update)
+        .await
+        .map(|f| Json(json!(f)))
+        .map_err(to_error)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/saved-filters/{id}",
+    tag = "saved filters",
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn delete_saved_filter(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<Value>)> {
+    state
+        .service
+        .delete_saved_filter(&id)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(to_error)
+}
+
+/// Run a saved filter's query in its project.
+#[utoipa::path(
+    get,
+    path = "/api/saved-filters/{id}/tasks",
+    tag = "saved filters",
+    responses(
+        (status = 200, body = [Task]),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn saved_filter_tasks(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let filter = state
+        .service
+        .get_saved_filter(&id)
+        .await
+        .map_err(to_error)?;
+    state
+        .service
+        .list_tasks(&filter.query.to_task_filter(&filter.project_id))
+        .await
+        .map(|t| Json(json!(t)))
+        .map_err(to_error)
+}
+
+fn to_error(e: flowstate_service::ServiceError) -> (StatusCode, Json<Value>) {
+    let (status, msg) = match &e {
+        flowstate_service::ServiceError::NotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
+        flowstate_service::ServiceError::InvalidInput(_) => {
+            (StatusCode::BAD_REQUEST, e.to_string())
+        }
+        flowstate_service::ServiceError::Internal(_) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        }
+    };
+    (status, Json(json!({ "error": msg })))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::test_router;
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    async fn send(
+        app: &axum::Router,
+        method: Method,
+        uri: &str,
+        body: Value,
+    ) -> (StatusCode, Value) {
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = resp.status();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    #[tokio::test]
+    async fn saved_filter_runs_its_query() {
+        let app = test_router().await;
+        let (_, project) = send(
+            &app,
+            Method::POST,
+            "/api/projects",
+            json!({ "name": "Views", "slug": "views" }),
+        )
+        .await;
+        let project_id = project["id"].as_str().unwrap();
+        for (title, priority) in [("Crash on save", "urgent"), ("Tidy docs", "low")] {
+            send(
+                &app,
+                Method::POST,
+                "/api/tasks",
+                json!({ "project_id": project_id, "title": title, "status": "todo", "priority": priority }),
+            )
+            .await;
+        }
+
+        let (status, filter) = send(
+            &app,
+            Method::POST,
+            "/api/saved-filters",
+            json!({
+                "project_id": project_id,
+                "name": "Urgent unassigned",
+                "query": { "priority": "urgent", "unassigned": true }
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let filter_id = filter["id"].as_str().unwrap();
+
+        // Names are unique per owner within a project
+        let (status, _) = send(
+            &app,
+            Method::POST,
+            "/api/saved-filters",
+            json!({ "project_id": project_id, "name": "Urgent unassigned" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = send(
+            &app,
+            Method::POST,
+            "/api/saved-filters",
+            json!({ "project_id": project_id, "name": "Mine", "user_id": "nobody" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, tasks) = send(
+            &app,
+            Method::GET,
+            &format!("/api/saved-filters/{filter_id}/tasks"),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let tasks = tasks.as_array().unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0]["title"], "Crash on save");
+
+        // The same conditions work as plain task query parameters
+        let (_, tasks) = send(
+            &app,
+            Method::GET,
+            &format!("/api/tasks?project_id={project_id}&unassigned=true&text=DOCS"),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(tasks.as_array().unwrap().len(), 1);
+        assert_eq!(tasks[0]["title"], "Tidy docs");
+
+        let (status, filters) = send(
+            &app,
+            Method::GET,
+            &format!("/api/saved-filters?project_id={project_id}"),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(filters.as_array().unwrap().len(), 1);
+
+        let (status, _) = send(
+            &app,
+            Method::DELETE,
+            &format!("/api/saved-filters/{filter_id}"),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+    }
+}
@@ -0,0 +1,327 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use bytes::Bytes;
+use chrono::Utc;
+use flowstate_core::ScopeFinding;
+use flowstate_service::TaskService;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::openapi::ErrorBody;
+use super::AppState;
+use crate::auth::Caller;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/tasks/{id}/scope-findings",
+            get(list_findings).put(write_findings),
+        )
+        .route(
+            "/api/tasks/{id}/scope-findings/acknowledge",
+            post(acknowledge_findings),
+        )
+}
+
+type ApiError = (StatusCode, Json<Value>);
+
+fn internal(e: impl std::fmt::Display) -> ApiError {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({ "error": e.to_string() })),
+    )
+}
+
+async fn load(state: &AppState, task_id: &str) -> Result<Vec<ScopeFinding>, ApiError> {
+    let key = flowstate_store::task_scope_findings_key(task_id);
+    match state.store.get_opt(&key).await.map_err(internal)? {
+        Some(data) => serde_json::from_slice(&data).map_err(internal),
+        None => Ok(Vec::new()),
+    }
+}
+
+async fn save(state: &AppState, task_id: &str, findings: &[ScopeFinding]) -> Result<(), ApiError> {
+    let key = flowstate_store::task_scope_findings_key(task_id);
+    let data = serde_json::to_vec(findings).map_err(internal)?;
+    state
+        .store
+        .put(&key, Bytes::from(data))
+        .await
+        .map_err(internal)
+}
+
+/// Refuse to move a task to Done while its build diff has findings no
+/// reviewer has acknowledged.
+pub(super) async fn check_done_gate(state: &AppState, task_id: &str) -> Result<(), ApiError> {
+    let pending = load(state, task_id)
+        .await?
+        .iter()
+        .filter(|f| !f.is_acknowledged())
+        .count();
+    if pending == 0 {
+        return Ok(());
+    }
+    Err((
+        StatusCode::CONFLICT,
+        Json(json!({
+            "error": format!(
+                "task {task_id} has {pending} unacknowledged scope finding(s); \
+                 acknowledge them before moving it to done"
+            )
+        })),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{id}/scope-findings",
+    tag = "tasks",
+    responses(
+        (status = 200, body = [ScopeFinding]),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn list_findings(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    state.service.get_task(&id).await.map_err(to_error)?;
+    load(&state, &id).await.map(|f| Json(json!(f)))
+}
+
+/// Replace a task's findings with those from its latest build. A finding
+/// the previous build also raised keeps its acknowledgment.
+#[utoipa::path(
+    put,
+    path = "/api/tasks/{id}/scope-findings",
+    tag = "tasks",
+    request_body = Vec<ScopeFinding>,
+    responses(
+        (status = 200, body = [ScopeFinding]),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn write_findings(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(mut findings): Json<Vec<ScopeFinding>>,
+) -> Result<Json<Value>, ApiError> {
+    state.service.get_task(&id).await.map_err(to_error)?;
+    let previous = load(&state, &id).await?;
+    for finding in &mut findings {
+        if let Some(old) = previous
+            .iter()
+            .find(|old| old.id == finding.id && old.is_acknowledged())
+        {
+            finding.acknowledged_by = old.acknowledged_by.clone();
+            finding.acknowledged_at = old.acknowledged_at;
+        }
+    }
+    save(&state, &id, &findings).await?;
+    Ok(Json(json!(findings)))
+}
+
+#[derive(Debug, Default, Deserialize, utoipa::ToSchema)]
+struct AcknowledgeRequest {
+    /// Findings to acknowledge; all of them when omitted.
+    ids: Option<Vec<String>>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/tasks/{id}/scope-findings/acknowledge",
+    tag = "tasks",
+    request_body(content = Option<AcknowledgeRequest>),
+    responses(
+        (status = 200, body = [ScopeFinding]),
+        (status = 404, description = "Unknown task or finding", body = ErrorBody)
+    )
+)]
+async fn acknowledge_findings(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    caller: Option<Extension<Caller>>,
+    body: Option<Json<AcknowledgeRequest>>,
+) -> Result<Json<Value>, ApiError> {
+    state.service.get_task(&id).await.map_err(to_error)?;
+    let Json(request) = body.unwrap_or_default();
+    let reviewer = caller.map(|Extension(Caller(c))| c);
+    let mut findings = load(&state, &id).await?;
+
+    if let Some(ids) = &request.ids {
+        if let Some(unknown) = ids.iter().find(|i| !findings.iter().any(|f| &f.id == *i)) {
+            return Err((
+                StatusCode::NOT_FOUND,
+                Json(json!({ "error": format!("scope finding {unknown} not found") })),
+            ));
+        }
+    }
+    let now = Utc::now();
+    for finding in &mut findings {
+        let selected = request
+            .ids
+            .as_ref()
+            .is_none_or(|ids| ids.contains(&finding.id));
+        if selected && !finding.is_acknowledged() {
+            finding.acknowledged_by = reviewer.clone();
+            finding.acknowledged_at = Some(now);
+        }
+    }
+    save(&state, &id, &findings).await?;
+    Ok(Json(json!(findings)))
+}
+
+fn to_error(e: flowstate_service::ServiceError) -> ApiError {
+    let (status, msg) = match &e {
+        flowstate_service::ServiceError::NotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
+        flowstate_service::ServiceError::InvalidInput(_) => {
+            (StatusCode::BAD_REQUEST, e.to_string())
+        }
+        flowstate_service::ServiceError::Internal(_) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        }
+    };
+    (status, Json(json!({ "error": msg })))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::test_router;
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    async fn send(
+        app: &axum::Router,
+        method: Method,
+        uri: &str,
+        body: Value,
+    ) -> (StatusCode, Value) {
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = resp.status();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    fn finding(kind: &str, path: &str) -> Value {
+        json!({
+            "id": format!("{kind}:{path}"),
+            "kind": kind,
+            "path": path,
+            "detail": "not in the plan",
+        })
+    }
+
+    #[tokio::test]
+    async fn findings_block_done_until_acknowledged() {
+        let app = test_router().await;
+        let (_, project) = send(
+            &app,
+            Method::POST,
+            "/api/projects",
+            json!({ "name": "Scope", "slug": "scope" }),
+        )
+        .await;
+        let (_, task) = send(
+            &app,
+            Method::POST,
+            "/api/tasks",
+            json!({ "project_id": project["id"], "title": "Build", "status": "verify", "priority": "medium" }),
+        )
+        .await;
+        let task_id = task["id"].as_str().unwrap();
+        let uri = format!("/api/tasks/{task_id}/scope-findings");
+
+        let (status, findings) = send(&app, Method::GET, &uri, Value::Null).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(findings.as_array().unwrap().is_empty());
+
+        let (status, _) = send(
+            &app,
+            Method::PUT,
+            &uri,
+            json!([
+                finding("new_dependency", "Cargo.lock"),
+                finding("ci_change", ".github/workflows/ci.yml"),
+            ]),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, body) = send(
+            &app,
+            Method::PUT,
+            &format!("/api/tasks/{task_id}"),
+            json!({ "status": "done" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert!(body["error"].as_str().unwrap().contains("2 unacknowledged"));
+
+        let (status, _) = send(
+            &app,
+            Method::POST,
+            &format!("{uri}/acknowledge"),
+            json!({ "ids": ["out_of_scope:nope"] }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, findings) = send(
+            &app,
+            Method::POST,
+            &format!("{uri}/acknowledge"),
+            json!({ "ids": ["new_dependency:Cargo.lock"] }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(findings[0]["acknowledged_at"].is_string());
+        assert!(findings[1]["acknowledged_at"].is_null());
+
+        // A rebuild raising the same finding keeps its acknowledgment.
+        let (_, findings) = send(
+            &app,
+            Method::PUT,
+            &uri,
+            json!([
+                finding("new_dependency", "Cargo.lock"),
+                finding("deleted_test", "tests/api.rs"),
+            ]),
+        )
+        .await;
+        assert!(findings[0]["acknowledged_at"].is_string());
+        assert!(findings[1]["acknowledged_at"].is_null());
+
+        let (status, _) = send(&app, Method::POST, &format!("{uri}/acknowledge"), json!({})).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, task) = send(
+            &app,
+            Method::PUT,
+            &format!("/api/tasks/{task_id}"),
+            json!({ "status": "done" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(task["status"], "done");
+    }
+}
@@ -4,12 +4,15 @@ use axum::{
     routing::{delete, get, post, put},
     Json, Router,
 };
-use flowstate_core::sprint::{CreateSprint, UpdateSprint};
+use flowstate_core::sprint::{CompleteSprint, CreateSprint, Sprint, SprintRollover, UpdateSprint};
 use flowstate_service::TaskService;
 use serde::Deserialize;
 use serde_json::{json, Value};
 
+use super::openapi::ErrorBody;
+use super::tasks::publish_task;
 use super::AppState;
+use crate::auth::ProjectScope;
 
 pub fn routes() -> Router<AppState> {
     Router::new()
@@ -18,17 +21,32 @@ pub fn routes() -> Router<AppState> {
         .route("/api/sprints/{id}", get(get_sprint))
         .route("/api/sprints/{id}", put(update_sprint))
         .route("/api/sprints/{id}", delete(delete_sprint))
+        .route("/api/sprints/{id}/archive", post(archive_sprint))
+        .route("/api/sprints/{id}/start", post(start_sprint))
+        .route("/api/sprints/{id}/complete", post(complete_sprint))
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, utoipa::IntoParams)]
 struct ListSprintsQuery {
     project_id: String,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/sprints",
+    tag = "sprints",
+    request_body = CreateSprint,
+    responses(
+        (status = 201, body = Sprint),
+        (status = 400, body = ErrorBody)
+    )
+)]
 async fn create_sprint(
     State(state): State<AppState>,
+    scope: ProjectScope,
     Json(input): Json<CreateSprint>,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    scope.check(&input.project_id)?;
     state
         .service
         .create_sprint(&input)
@@ -37,6 +55,15 @@ async fn create_sprint(
         .map_err(to_error)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/sprints/{id}",
+    tag = "sprints",
+    responses(
+        (status = 200, body = Sprint),
+        (status = 404, body = ErrorBody)
+    )
+)]
 async fn get_sprint(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -49,6 +76,13 @@ async fn get_sprint(
         .map_err(to_error)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/sprints",
+    tag = "sprints",
+    params(ListSprintsQuery),
+    responses((status = 200, body = [Sprint]))
+)]
 async fn list_sprints(
     State(state): State<AppState>,
     Query(q): Query<ListSprintsQuery>,
@@ -61,6 +95,17 @@ async fn list_sprints(
         .map_err(to_error)
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/sprints/{id}",
+    tag = "sprints",
+    request_body = UpdateSprint,
+    responses(
+        (status = 200, body = Sprint),
+        (status = 400, body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
 async fn update_sprint(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -74,6 +119,15 @@ async fn update_sprint(
         .map_err(to_error)
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/sprints/{id}",
+    tag = "sprints",
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 404, body = ErrorBody)
+    )
+)]
 async fn delete_sprint(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -86,6 +140,79 @@ async fn delete_sprint(
         .map_err(to_error)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/sprints/{id}/archive",
+    tag = "sprints",
+    responses(
+        (status = 200, body = Sprint),
+        (status = 400, description = "The sprint is not completed", body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn archive_sprint(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    state
+        .service
+        .archive_sprint(&id)
+        .await
+        .map(|s| Json(json!(s)))
+        .map_err(to_error)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/sprints/{id}/start",
+    tag = "sprints",
+    responses(
+        (status = 200, body = Sprint),
+        (status = 400, description = "The sprint is not planned, or another sprint of the project is active", body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn start_sprint(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    state
+        .service
+        .start_sprint(&id)
+        .await
+        .map(|s| Json(json!(s)))
+        .map_err(to_error)
+}
+
+/// Complete the active sprint. Its unfinished tasks move to `next_sprint_id`,
+/// or back to the backlog without one.
+#[utoipa::path(
+    post,
+    path = "/api/sprints/{id}/complete",
+    tag = "sprints",
+    request_body = CompleteSprint,
+    responses(
+        (status = 200, body = SprintRollover),
+        (status = 400, description = "The sprint is not active, or the next sprint is not an open sprint of the same project", body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn complete_sprint(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(input): Json<CompleteSprint>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let rollover = state
+        .service
+        .complete_sprint(&id, &input)
+        .await
+        .map_err(to_error)?;
+    for task in &rollover.rolled_over {
+        publish_task(&state, task);
+    }
+    Ok(Json(json!(rollover)))
+}
+
 fn to_error(e: flowstate_service::ServiceError) -> (StatusCode, Json<Value>) {
     let (status, msg) = match &e {
         flowstate_service::ServiceError::NotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
@@ -0,0 +1,294 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderValue, StatusCode},
+    response::{Html, IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use chrono::Utc;
+use serde_json::{json, Value};
+
+use super::openapi::ErrorBody;
+use super::AppState;
+use crate::listen::PeerInfo;
+
+/// Requests one client may make to `/status` per window.
+const STATUS_RATE_LIMIT: u32 = 30;
+const STATUS_RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Public route (no auth required), answered only when the status page is
+/// exposed.
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/status", get(public_status))
+}
+
+/// How much the unauthenticated `/status` endpoint reveals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusExposure {
+    /// `/status` answers 404.
+    Off,
+    /// Only the overall state: ok, degraded or maintenance.
+    Summary,
+    /// The overall state plus queue depth, runner count and failure rate.
+    Full,
+}
+
+impl StatusExposure {
+    pub fn parse_str(s: &str) -> Option<Self> {
+        match s {
+            "off" | "false" | "0" => Some(Self::Off),
+            "summary" => Some(Self::Summary),
+            "full" | "true" | "1" => Some(Self::Full),
+            _ => None,
+        }
+    }
+
+    /// Read `FLOWSTATE_STATUS_PAGE`; unset or unrecognized means off.
+    pub fn from_env() -> Self {
+        std::env::var("FLOWSTATE_STATUS_PAGE")
+            .ok()
+            .and_then(|v| Self::parse_str(&v))
+            .unwrap_or(Self::Off)
+    }
+}
+
+/// Exposure setting and per-client request counts for `/status`.
+pub struct StatusPage {
+    pub exposure: StatusExposure,
+    /// Start and request count of each client's current window, keyed by
+    /// peer address (`None` for Unix-socket clients).
+    windows: Mutex<HashMap<Option<IpAddr>, (Instant, u32)>>,
+}
+
+impl StatusPage {
+    pub fn new(exposure: StatusExposure) -> Self {
+        Self {
+            exposure,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Count a request from `peer`, or return the seconds until its window
+    /// resets when it is over the limit.
+    fn check_rate(&self, peer: Option<IpAddr>, now: Instant) -> Result<(), u64> {
+        let mut windows = self.windows.lock().unwrap();
+        windows.retain(|_, (start, _)| now.duration_since(*start) < STATUS_RATE_WINDOW);
+        let (start, count) = windows.entry(peer).or_insert((now, 0));
+        if *count >= STATUS_RATE_LIMIT {
+            let remaining = STATUS_RATE_WINDOW.saturating_sub(now.duration_since(*start));
+            return Err(remaining.as_secs().max(1));
+        }
+        *count += 1;
+        Ok(())
+    }
+}
+
+/// Health summary for dashboards: JSON by default, an HTML page when the
+/// client prefers `text/html`.
+#[utoipa::path(
+    get,
+    path = "/status",
+    tag = "system",
+    security(()),
+    responses(
+        (status = 200, description = "`status` is ok, degraded or maintenance; `FLOWSTATE_STATUS_PAGE=full` adds the figures behind it", body = Object),
+        (status = 404, description = "The status page is off"),
+        (status = 429, body = ErrorBody)
+    )
+)]
+async fn public_status(State(state): State<AppState>, request: Request) -> Response {
+    let exposure = state.status_page.exposure;
+    if exposure == StatusExposure::Off {
+        return StatusCode::NOT_FOUND.into_response();
+    }
+
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<PeerInfo>>()
+        .and_then(|info| info.0.remote_addr)
+        .map(|addr| addr.ip().to_canonical());
+    if let Err(retry_after) = state.status_page.check_rate(peer, Instant::now()) {
+        let mut resp = (
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(json!({ "error": "too many requests" })),
+        )
+            .into_response();
+        resp.headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+        return resp;
+    }
+
+    let wants_html = request
+        .headers()
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("text/html"));
+
+    let summary = summarize(&state).await;
+    let body = match exposure {
+        StatusExposure::Full => summary,
+        _ => json!({ "status": summary["status"] }),
+    };
+    if wants_html {
+        Html(render_html(&body)).into_response()
+    } else {
+        Json(body).into_response()
+    }
+}
+
+/// Overall state and the figures it is derived from. The server is
+/// degraded when runs are queued with no runner connected, or when at least
+/// half of the last day's (five or more) finished runs failed.
+async fn summarize(state: &AppState) -> Value {
+    let now = Utc::now();
+    let connected_threshold = chrono::Duration::seconds(30);
+    let runners = state
+        .runners
+        .lock()
+        .unwrap()
+        .values()
+        .filter(|info| now - info.last_seen < connected_threshold)
+        .count();
+    let queue_depth = state.db.count_queued_runs().await.unwrap_or(0);
+    let (finished, failed) = state
+        .db
+        .count_finished_runs(now - chrono::Duration::hours(24))
+        .await
+        .unwrap_or((0, 0));
+    let failure_rate = (finished > 0).then(|| failed as f64 / finished as f64);
+
+    let status = if state.maintenance.load(Ordering::Relaxed) {
+        "maintenance"
+    } else if (queue_depth > 0 && runners == 0)
+        || (finished >= 5 && failure_rate.is_some_and(|r| r >= 0.5))
+    {
+        "degraded"
+    } else {
+        "ok"
+    };
+
+    json!({
+        "status": status,
+        "queue_depth": queue_depth,
+        "runners": runners,
+        "finished_runs_24h": finished,
+        "failed_runs_24h": failed,
+        "failure_rate_24h": failure_rate,
+    })
+}
+
+fn render_html(body: &Value) -> String {
+    let status = body["status"].as_str().unwrap_or("unknown");
+    let color = match status {
+        "ok" => "#2e7d32",
+        "maintenance" => "#1565c0",
+        _ => "#e65100",
+    };
+    let mut rows = String::new();
+    if let Some(queue_depth) = body.get("queue_depth") {
+        let rate = body["failure_rate_24h"]
+            .as_f64()
+            .map(|r| format!("{:.0}%", r * 100.0))
+            .unwrap_or_else(|| "-".into());
+        rows = format!(
+            "<tr><td>Queued runs</td><td>{queue_depth}</td></tr>\
+             <tr><td>Connected runners</td><td>{}</td></tr>\
+             <tr><td>Failure rate (24h)</td><td>{rate} of {}</td></tr>",
+            body["runners"], body["finished_runs_24h"],
+        );
+    }
+    format!(
+        "<!DOCTYPE html>\
+         <html><head><meta charset=\"utf-8\"><meta http-equiv=\"refresh\" content=\"60\">\
+         <title>flowstate status</title>\
+         <style>body{{font-family:sans-serif;margin:1em}}td{{padding:2px 12px 2px 0}}</style>\
+         </head><body>\
+         <h1>flowstate: <span style=\"color:{color}\">{status}</span></h1>\
+         <table>{rows}</table>\
+         </body></html>"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use tower::ServiceExt;
+
+    use crate::test_helpers::test_router;
+
+    #[test]
+    fn exposure_parses_env_values() {
+        assert_eq!(StatusExposure::parse_str("off"), Some(StatusExposure::Off));
+        assert_eq!(
+            StatusExposure::parse_str("summary"),
+            Some(StatusExposure::Summary)
+        );
+        assert_eq!(
+            StatusExposure::parse_str("full"),
+            Some(StatusExposure::Full)
+        );
+        assert_eq!(StatusExposure::parse_str("everything"), None);
+    }
+
+    #[test]
+    fn rate_limit_is_per_client_and_resets() {
+        let page = StatusPage::new(StatusExposure::Full);
+        let a = Some("10.0.0.1".parse().unwrap());
+        let b = Some("10.0.0.2".parse().unwrap());
+        let start = Instant::now();
+        for _ in 0..STATUS_RATE_LIMIT {
+            assert!(page.check_rate(a, start).is_ok());
+        }
+        assert!(page.check_rate(a, start).is_err());
+        assert!(page.check_rate(b, start).is_ok());
+        assert!(page.check_rate(a, start + STATUS_RATE_WINDOW).is_ok());
+    }
+
+    #[tokio::test]
+    async fn status_reports_json_and_html() {
+        let app = test_router().await;
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/status")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["status"], "ok");
+        assert_eq!(json["queue_depth"], 0);
+        assert_eq!(json["runners"], 0);
+        assert!(json["failure_rate_24h"].is_null());
+
+        let resp = app
+            .oneshot(
+                Request::builder()
+                    .uri("/status")
+                    .header("accept", "text/html")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let html = String::from_utf8(body.to_vec()).unwrap();
+        assert!(html.contains("Queued runs"));
+    }
+}
@@ -0,0 +1,133 @@
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use flowstate_store::{PresignMethod, StoreError, PRESIGNED_PATH};
+use futures_util::{StreamExt, TryStreamExt};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::openapi::ErrorBody;
+use super::AppState;
+
+/// Public routes (no auth required): the signed token in the path is the
+/// credential. These serve the presigned URLs the local store hands out;
+/// with S3, presigned URLs point at the bucket instead.
+pub fn routes() -> Router<AppState> {
+    Router::new().route(
+        &format!("{PRESIGNED_PATH}{{token}}"),
+        get(presigned_get).put(presigned_put),
+    )
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+struct PresignedGetQuery {
+    /// Name to take the content type from, for keys without an extension.
+    filename: Option<String>,
+}
+
+/// Download an object through a presigned URL.
+#[utoipa::path(
+    get,
+    path = "/api/store/presigned/{token}",
+    tag = "attachments",
+    security(()),
+    params(PresignedGetQuery),
+    responses(
+        (status = 200, description = "The object", content_type = "application/octet-stream"),
+        (status = 403, description = "Invalid or expired token", body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn presigned_get(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+    Query(query): Query<PresignedGetQuery>,
+) -> Response {
+    let key = match state.store.verify_presigned(&token, PresignMethod::Get) {
+        Ok(key) => key,
+        Err(e) => return store_error(e),
+    };
+    let stream = match state.store.get_stream(&key).await {
+        Ok(stream) => stream,
+        Err(e) => return store_error(e),
+    };
+    let filename = query
+        .filename
+        .as_deref()
+        .unwrap_or_else(|| key.rsplit('/').next().unwrap_or(&key));
+    let content_type = flowstate_core::attachment::guess_content_type(filename);
+    (
+        [(header::CONTENT_TYPE, content_type)],
+        Body::from_stream(stream),
+    )
+        .into_response()
+}
+
+/// Upload an object through a presigned URL.
+#[utoipa::path(
+    put,
+    path = "/api/store/presigned/{token}",
+    tag = "attachments",
+    security(()),
+    request_body(content = Vec<u8>, content_type = "application/octet-stream"),
+    responses(
+        (status = 204, description = "Stored"),
+        (status = 403, description = "Invalid or expired token", body = ErrorBody)
+    )
+)]
+async fn presigned_put(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+    body: Body,
+) -> Response {
+    let key = match state.store.verify_presigned(&token, PresignMethod::Put) {
+        Ok(key) => key,
+        Err(e) => return store_error(e),
+    };
+    let stream = body
+        .into_data_stream()
+        .map_err(|e| StoreError::Internal(format!("read body: {e}")))
+        .boxed();
+    match state.store.put_stream(&key, stream).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => store_error(e),
+    }
+}
+
+fn store_error(e: StoreError) -> Response {
+    let status = match &e {
+        StoreError::InvalidToken(_) => StatusCode::FORBIDDEN,
+        StoreError::NotFound(_) => StatusCode::NOT_FOUND,
+        StoreError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(json!({ "error": e.to_string() }))).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    use crate::test_helpers::test_router_with_auth;
+
+    #[tokio::test]
+    async fn presigned_routes_skip_auth_and_check_the_token() {
+        let (app, _api_key) = test_router_with_auth().await;
+        let resp = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/store/presigned/not-a-token")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    }
+}
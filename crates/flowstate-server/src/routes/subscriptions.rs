@@ -0,0 +1,293 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use flowstate_core::subscription::{CreateSubscription, Subscription};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::openapi::ErrorBody;
+use super::AppState;
+use crate::auth::ProjectScope;
+
+type ApiError = (StatusCode, Json<Value>);
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/subscriptions",
+            get(list_subscriptions).post(create_subscription),
+        )
+        .route(
+            "/api/subscriptions/{id}",
+            get(get_subscription).delete(delete_subscription),
+        )
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+struct SubscriptionQuery {
+    /// Only this user's subscriptions.
+    user_id: Option<String>,
+    /// Only subscriptions scoped to this project.
+    project_id: Option<String>,
+}
+
+/// Whether a scoped key may see `subscription`. Subscriptions for every
+/// project belong to unscoped keys.
+fn in_scope(scope: &ProjectScope, subscription: &Subscription) -> bool {
+    match &subscription.project_id {
+        Some(project_id) => scope.allows(project_id),
+        None => scope.projects().is_none(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/subscriptions",
+    tag = "notifications",
+    params(SubscriptionQuery),
+    responses((status = 200, body = [Subscription]))
+)]
+async fn list_subscriptions(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+    Query(query): Query<SubscriptionQuery>,
+) -> Result<Json<Value>, ApiError> {
+    let subscriptions: Vec<_> = state
+        .db
+        .list_subscriptions()
+        .await
+        .map_err(to_error)?
+        .into_iter()
+        .filter(|s| in_scope(&scope, s))
+        .filter(|s| query.user_id.is_none() || s.user_id == query.user_id)
+        .filter(|s| query.project_id.is_none() || s.project_id == query.project_id)
+        .collect();
+    Ok(Json(json!(subscriptions)))
+}
+
+/// Subscribe a channel target to events. With a `user_id`, only tasks the
+/// user is assigned, reviewing or watching are notified, and an email
+/// subscription without a `target` goes to the user's address.
+#[utoipa::path(
+    post,
+    path = "/api/subscriptions",
+    tag = "notifications",
+    request_body = CreateSubscription,
+    responses(
+        (status = 201, body = Subscription),
+        (status = 400, body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn create_subscription(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+    Json(input): Json<CreateSubscription>,
+) -> Result<(StatusCode, Json<Value>), ApiError> {
+    input.validate().map_err(|e| bad_request(e.to_string()))?;
+    if !state.notifier.supports(input.channel) {
+        return Err(bad_request(format!(
+            "{} notifications are not configured on this server",
+            input.channel
+        )));
+    }
+    match &input.project_id {
+        Some(project_id) => {
+            scope.check(project_id)?;
+            state.db.get_project(project_id).await.map_err(to_error)?;
+        }
+        None => {
+            if scope.projects().is_some() {
+                return Err(bad_request(
+                    "a project-scoped key must give a project_id".into(),
+                ));
+            }
+        }
+    }
+    if let Some(user_id) = &input.user_id {
+        state.db.get_user(user_id).await.map_err(to_error)?;
+    }
+    let subscription = state
+        .db
+        .create_subscription(&input)
+        .await
+        .map_err(to_error)?;
+    Ok((StatusCode::CREATED, Json(json!(subscription))))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/subscriptions/{id}",
+    tag = "notifications",
+    responses(
+        (status = 200, body = Subscription),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn get_subscription(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, ApiError> {
+    let subscription = load(&state, &scope, &id).await?;
+    Ok(Json(json!(subscription)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/subscriptions/{id}",
+    tag = "notifications",
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn delete_subscription(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    load(&state, &scope, &id).await?;
+    state
+        .db
+        .delete_subscription(&id)
+        .await
+        .map(|()| StatusCode::NO_CONTENT)
+        .map_err(to_error)
+}
+
+/// The subscription, as not found when it lies outside the caller's scope.
+async fn load(state: &AppState, scope: &ProjectScope, id: &str) -> Result<Subscription, ApiError> {
+    let subscription = state.db.get_subscription(id).await.map_err(to_error)?;
+    if !in_scope(scope, &subscription) {
+        return Err(to_error(flowstate_db::DbError::NotFound(format!(
+            "subscription {id}"
+        ))));
+    }
+    Ok(subscription)
+}
+
+fn bad_request(msg: String) -> ApiError {
+    (StatusCode::BAD_REQUEST, Json(json!({ "error": msg })))
+}
+
+fn to_error(e: flowstate_db::DbError) -> ApiError {
+    let status = match &e {
+        flowstate_db::DbError::NotFound(_) => StatusCode::NOT_FOUND,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(json!({ "error": e.to_string() })))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::test_router;
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    fn send(method: Method, uri: &str, body: Value) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    async fn body_json(resp: axum::response::Response) -> Value {
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn subscription_crud() {
+        let app = test_router().await;
+
+        // Email needs SMTP, which the test server does not have
+        let resp = app
+            .clone()
+            .oneshot(send(
+                Method::POST,
+                "/api/subscriptions",
+                json!({ "channel": "email", "target": "ops@example.com" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let resp = app
+            .clone()
+            .oneshot(send(
+                Method::POST,
+                "/api/subscriptions",
+                json!({ "channel": "slack", "target": "not a url" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        let resp = app
+            .clone()
+            .oneshot(send(
+                Method::POST,
+                "/api/subscriptions",
+                json!({ "channel": "webhook", "target": "https://ci.example.com/hook", "user_id": "nobody" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let resp = app
+            .clone()
+            .oneshot(send(
+                Method::POST,
+                "/api/subscriptions",
+                json!({
+                    "channel": "slack",
+                    "target": "https://hooks.slack.com/services/x",
+                    "events": ["run_failed", "pr_opened"],
+                }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let created = body_json(resp).await;
+        assert_eq!(created["events"], json!(["run_failed", "pr_opened"]));
+        let id = created["id"].as_str().unwrap();
+
+        let resp = app
+            .clone()
+            .oneshot(send(Method::GET, "/api/subscriptions", Value::Null))
+            .await
+            .unwrap();
+        assert_eq!(body_json(resp).await.as_array().unwrap().len(), 1);
+        let resp = app
+            .clone()
+            .oneshot(send(
+                Method::GET,
+                "/api/subscriptions?user_id=someone",
+                Value::Null,
+            ))
+            .await
+            .unwrap();
+        assert!(body_json(resp).await.as_array().unwrap().is_empty());
+
+        let uri = format!("/api/subscriptions/{id}");
+        let resp = app
+            .clone()
+            .oneshot(send(Method::DELETE, &uri, Value::Null))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        let resp = app
+            .oneshot(send(Method::GET, &uri, Value::Null))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+}
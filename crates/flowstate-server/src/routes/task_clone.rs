@@ -0,0 +1,512 @@
+use std::collections::{HashMap, HashSet};
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::post,
+    Json, Router,
+};
+use flowstate_core::task::{ApprovalStatus, CreateTask, Status, Task, UpdateTask};
+use flowstate_core::task_link::CreateTaskLink;
+use flowstate_service::{ServiceError, TaskService};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::openapi::ErrorBody;
+use super::tasks::publish_task;
+use super::AppState;
+use crate::auth::ProjectScope;
+
+type ApiError = (StatusCode, Json<Value>);
+
+pub fn routes() -> Router<AppState> {
+    Router::new().route("/api/tasks/{id}/clone", post(clone_task))
+}
+
+/// Request body for `POST /api/tasks/{id}/clone`. Everything is optional;
+/// `{}` copies the task alone into its own project.
+#[derive(Debug, Default, Deserialize, utoipa::ToSchema)]
+pub struct CloneTaskRequest {
+    /// Project to create the copy in; the task's own when omitted.
+    pub project_id: Option<String>,
+    /// Title for the copy; the original's when omitted.
+    pub title: Option<String>,
+    /// Also copy the task's subtasks, and theirs, under the copy.
+    #[serde(default)]
+    pub subtasks: bool,
+    /// Also copy the links of every copied task. A link between two copied
+    /// tasks joins the copies; one to a task left behind still points at it.
+    #[serde(default)]
+    pub links: bool,
+    /// Also copy the stored research, spec and plan, which come back for
+    /// review rather than approved.
+    #[serde(default)]
+    pub documents: bool,
+}
+
+/// Duplicate a task as a fresh work item: same title, description, type,
+/// priority, reviewer, capabilities and labels, back in Todo with no
+/// approvals, runs or history. Sprint, epic and custom field values are
+/// kept only within the same project.
+#[utoipa::path(
+    post,
+    path = "/api/tasks/{id}/clone",
+    tag = "tasks",
+    request_body = CloneTaskRequest,
+    responses(
+        (status = 201, description = "The copy of the task", body = Task),
+        (status = 403, body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn clone_task(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    scope: ProjectScope,
+    Json(req): Json<CloneTaskRequest>,
+) -> Result<(StatusCode, Json<Value>), ApiError> {
+    let source = state.service.get_task(&id).await.map_err(to_error)?;
+    let project_id = req
+        .project_id
+        .clone()
+        .unwrap_or_else(|| source.project_id.clone());
+    scope.check(&project_id)?;
+    state
+        .service
+        .get_project(&project_id)
+        .await
+        .map_err(to_error)?;
+
+    // Copies by original id; subtasks follow their parent
+    let mut copies: HashMap<String, Task> = HashMap::new();
+    let root = copy_task(&state, &source, &project_id, None, req.title.clone(), &req).await?;
+    copies.insert(source.id.clone(), root.clone());
+    if req.subtasks {
+        let mut pending = vec![source.id.clone()];
+        while let Some(parent) = pending.pop() {
+            let new_parent = copies[&parent].id.clone();
+            let children = state
+                .service
+                .list_child_tasks(&parent)
+                .await
+                .map_err(to_error)?;
+            for child in children.iter().filter(|c| !c.archived) {
+                if copies.contains_key(&child.id) {
+                    continue;
+                }
+                let copy = copy_task(
+                    &state,
+                    child,
+                    &project_id,
+                    Some(new_parent.clone()),
+                    None,
+                    &req,
+                )
+                .await?;
+                copies.insert(child.id.clone(), copy);
+                pending.push(child.id.clone());
+            }
+        }
+    }
+
+    if req.links {
+        let mut seen = HashSet::new();
+        for original in copies.keys() {
+            let links = state
+                .service
+                .list_task_links(original)
+                .await
+                .map_err(to_error)?;
+            for link in links {
+                if !seen.insert(link.id.clone()) {
+                    continue;
+                }
+                let end = |id: &str| copies.get(id).map_or(id, |t| t.id.as_str()).to_string();
+                state
+                    .service
+                    .create_task_link(&CreateTaskLink {
+                        source_task_id: end(&link.source_task_id),
+                        target_task_id: end(&link.target_task_id),
+                        link_type: link.link_type,
+                    })
+                    .await
+                    .map_err(to_error)?;
+            }
+        }
+    }
+
+    for task in copies.values() {
+        publish_task(&state, task);
+    }
+    Ok((StatusCode::CREATED, Json(json!(root))))
+}
+
+/// Create one copy of `source` in `project_id` and carry over what the
+/// request asks for.
+async fn copy_task(
+    state: &AppState,
+    source: &Task,
+    project_id: &str,
+    parent_id: Option<String>,
+    title: Option<String>,
+    req: &CloneTaskRequest,
+) -> Result<Task, ApiError> {
+    let same_project = source.project_id == project_id;
+    let task = state
+        .service
+        .create_task(&CreateTask {
+            project_id: project_id.to_string(),
+            title: title.unwrap_or_else(|| source.title.clone()),
+            description: source.description.clone(),
+            status: Status::Todo,
+            priority: source.priority,
+            task_type: source.task_type,
+            parent_id,
+            reviewer: source.reviewer.clone(),
+            due_at: source.due_at,
+            research_capability: source.research_capability,
+            design_capability: source.design_capability,
+            plan_capability: source.plan_capability,
+            build_capability: source.build_capability,
+            verify_capability: source.verify_capability,
+        })
+        .await
+        .map_err(to_error)?;
+
+    for label in state
+        .db
+        .list_task_labels(&source.id)
+        .await
+        .map_err(db_error)?
+    {
+        let label = if same_project {
+            label
+        } else {
+            state
+                .db
+                .ensure_label(project_id, &label.name, &label.color)
+                .await
+                .map_err(db_error)?
+        };
+        state
+            .db
+            .add_task_label(&task.id, &label.id)
+            .await
+            .map_err(db_error)?;
+    }
+
+    let mut update = UpdateTask {
+        assignee_id: source.assignee_id.clone().map(Some),
+        ..Default::default()
+    };
+    let mut changed = update.assignee_id.is_some();
+    if same_project {
+        update.sprint_id = source.sprint_id.clone().map(Some);
+        update.epic_id = source.epic_id.clone().map(Some);
+        update.custom_fields = state
+            .db
+            .list_task_field_values(&source.id)
+            .await
+            .map_err(db_error)?
+            .into_iter()
+            .map(|v| (v.field_id, Some(v.value)))
+            .collect();
+        changed |= update.sprint_id.is_some()
+            || update.epic_id.is_some()
+            || !update.custom_fields.is_empty();
+    }
+    if req.documents {
+        let documents = [
+            (
+                flowstate_store::task_research_key as fn(&str) -> String,
+                &mut update.research_status,
+            ),
+            (flowstate_store::task_spec_key, &mut update.spec_status),
+            (flowstate_store::task_plan_key, &mut update.plan_status),
+        ];
+        for (key, status) in documents {
+            if copy_document(state, &key(&source.id), &key(&task.id)).await? {
+                *status = Some(ApprovalStatus::Pending);
+                changed = true;
+            }
+        }
+    }
+    if !changed {
+        return Ok(task);
+    }
+    state
+        .service
+        .update_task(&task.id, &update)
+        .await
+        .map_err(to_error)
+}
+
+/// Copy the object at `from` to `to`; whether there was a non-empty one.
+async fn copy_document(state: &AppState, from: &str, to: &str) -> Result<bool, ApiError> {
+    let data = state
+        .store
+        .get_opt(from)
+        .await
+        .map_err(|e| to_error(ServiceError::Internal(format!("read {from}: {e}"))))?;
+    match data {
+        Some(data) if !data.is_empty() => {
+            state
+                .store
+                .put(to, data)
+                .await
+                .map_err(|e| to_error(ServiceError::Internal(format!("write {to}: {e}"))))?;
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+fn db_error(e: flowstate_db::DbError) -> ApiError {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({ "error": e.to_string() })),
+    )
+}
+
+fn to_error(e: ServiceError) -> ApiError {
+    let (status, msg) = match &e {
+        ServiceError::NotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
+        ServiceError::InvalidInput(_) => (StatusCode::BAD_REQUEST, e.to_string()),
+        ServiceError::Internal(_) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    };
+    (status, Json(json!({ "error": msg })))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use flowstate_core::sprint::CreateSprint;
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    use crate::test_helpers::test_state;
+
+    async fn send(
+        app: &axum::Router,
+        method: Method,
+        uri: &str,
+        body: Value,
+    ) -> (StatusCode, Value) {
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = resp.status();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    async fn create(app: &axum::Router, uri: &str, body: Value) -> String {
+        let (status, v) = send(app, Method::POST, uri, body).await;
+        assert_eq!(status, StatusCode::CREATED, "{v}");
+        v["id"].as_str().unwrap().to_string()
+    }
+
+    fn task(project_id: &str, title: &str, parent_id: Option<&str>) -> Value {
+        json!({
+            "project_id": project_id,
+            "title": title,
+            "description": "Do it again",
+            "status": "build",
+            "priority": "high",
+            "task_type": "bug",
+            "parent_id": parent_id,
+        })
+    }
+
+    #[tokio::test]
+    async fn clones_a_task_tree_with_links_and_documents() {
+        let state = test_state().await;
+        let app = crate::routes::build_router(state.clone());
+        let project = create(&app, "/api/projects", json!({ "name": "A", "slug": "a" })).await;
+        let sprint = state
+            .db
+            .create_sprint(&CreateSprint {
+                project_id: project.clone(),
+                name: "Sprint 1".into(),
+                goal: String::new(),
+                starts_at: None,
+                ends_at: None,
+            })
+            .await
+            .unwrap();
+        let source = create(&app, "/api/tasks", task(&project, "Release", None)).await;
+        let child = create(&app, "/api/tasks", task(&project, "Tag", Some(&source))).await;
+        let other = create(&app, "/api/tasks", task(&project, "Announce", None)).await;
+        send(
+            &app,
+            Method::PUT,
+            &format!("/api/tasks/{source}"),
+            json!({ "sprint_id": sprint.id }),
+        )
+        .await;
+        let label = state
+            .db
+            .ensure_label(&project, "release", "#ff0000")
+            .await
+            .unwrap();
+        state.db.add_task_label(&source, &label.id).await.unwrap();
+        for (from, to, link_type) in [(&source, &other, "blocks"), (&child, &source, "relates_to")]
+        {
+            create(
+                &app,
+                "/api/task-links",
+                json!({ "source_task_id": from, "target_task_id": to, "link_type": link_type }),
+            )
+            .await;
+        }
+        state
+            .store
+            .put(
+                &flowstate_store::task_spec_key(&source),
+                "# Spec".as_bytes().to_vec().into(),
+            )
+            .await
+            .unwrap();
+
+        let (status, copy) = send(
+            &app,
+            Method::POST,
+            &format!("/api/tasks/{source}/clone"),
+            json!({ "subtasks": true, "links": true, "documents": true }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{copy}");
+        let copy_id = copy["id"].as_str().unwrap();
+        assert_ne!(copy_id, source);
+        assert_eq!(copy["title"], "Release");
+        assert_eq!(copy["status"], "todo");
+        assert_eq!(copy["priority"], "high");
+        assert_eq!(copy["sprint_id"], sprint.id.as_str());
+        assert_eq!(copy["spec_status"], "pending");
+        assert_eq!(copy["plan_status"], "none");
+        let spec = state
+            .store
+            .get_opt(&flowstate_store::task_spec_key(copy_id))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(&spec[..], b"# Spec");
+        let labels = state.db.list_task_labels(copy_id).await.unwrap();
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels[0].id, label.id);
+
+        let (_, children) = send(
+            &app,
+            Method::GET,
+            &format!("/api/tasks/{copy_id}/children"),
+            Value::Null,
+        )
+        .await;
+        let children = children.as_array().unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0]["title"], "Tag");
+        let child_copy = children[0]["id"].as_str().unwrap();
+
+        let (_, links) = send(
+            &app,
+            Method::GET,
+            &format!("/api/tasks/{copy_id}/links"),
+            Value::Null,
+        )
+        .await;
+        let mut ends: Vec<(String, String)> = links
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|l| {
+                (
+                    l["source_task_id"].as_str().unwrap().to_string(),
+                    l["target_task_id"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect();
+        ends.sort();
+        let mut expected = vec![
+            (copy_id.to_string(), other.clone()),
+            (child_copy.to_string(), copy_id.to_string()),
+        ];
+        expected.sort();
+        assert_eq!(ends, expected);
+        // The original keeps its own links only
+        let (_, links) = send(
+            &app,
+            Method::GET,
+            &format!("/api/tasks/{source}/links"),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(links.as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn clones_into_another_project() {
+        let state = test_state().await;
+        let app = crate::routes::build_router(state.clone());
+        let from = create(&app, "/api/projects", json!({ "name": "A", "slug": "a" })).await;
+        let to = create(&app, "/api/projects", json!({ "name": "B", "slug": "b" })).await;
+        let source = create(&app, "/api/tasks", task(&from, "Release", None)).await;
+        create(&app, "/api/tasks", task(&from, "Tag", Some(&source))).await;
+        let label = state
+            .db
+            .ensure_label(&from, "release", "#ff0000")
+            .await
+            .unwrap();
+        state.db.add_task_label(&source, &label.id).await.unwrap();
+
+        let (status, copy) = send(
+            &app,
+            Method::POST,
+            &format!("/api/tasks/{source}/clone"),
+            json!({ "project_id": to, "title": "Release 2" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{copy}");
+        let copy_id = copy["id"].as_str().unwrap();
+        assert_eq!(copy["project_id"], to.as_str());
+        assert_eq!(copy["title"], "Release 2");
+        let labels = state.db.list_task_labels(copy_id).await.unwrap();
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels[0].project_id, to);
+        assert_eq!(labels[0].name, "release");
+        // Subtasks only when asked for
+        let (_, children) = send(
+            &app,
+            Method::GET,
+            &format!("/api/tasks/{copy_id}/children"),
+            Value::Null,
+        )
+        .await;
+        assert!(children.as_array().unwrap().is_empty());
+
+        let (status, _) = send(
+            &app,
+            Method::POST,
+            &format!("/api/tasks/{source}/clone"),
+            json!({ "project_id": "missing" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = send(&app, Method::POST, "/api/tasks/missing/clone", json!({})).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -4,11 +4,14 @@ use axum::{
     routing::{delete, get, post},
     Json, Router,
 };
-use flowstate_core::task_link::CreateTaskLink;
+use flowstate_core::task::Status;
+use flowstate_core::task_link::{CreateTaskLink, LinkType, TaskLink};
 use flowstate_service::TaskService;
 use serde_json::{json, Value};
 
+use super::openapi::ErrorBody;
 use super::AppState;
+use crate::auth::ProjectScope;
 
 pub fn routes() -> Router<AppState> {
     Router::new()
@@ -17,10 +20,28 @@ pub fn routes() -> Router<AppState> {
         .route("/api/tasks/{task_id}/links", get(list_task_links))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/task-links",
+    tag = "tasks",
+    request_body = CreateTaskLink,
+    responses(
+        (status = 201, body = TaskLink),
+        (status = 400, body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
 async fn create_task_link(
     State(state): State<AppState>,
+    scope: ProjectScope,
     Json(input): Json<CreateTaskLink>,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    if scope.projects().is_some() {
+        for task_id in [&input.source_task_id, &input.target_task_id] {
+            let task = state.service.get_task(task_id).await.map_err(to_error)?;
+            scope.check(&task.project_id)?;
+        }
+    }
     state
         .service
         .create_task_link(&input)
@@ -29,6 +50,15 @@ async fn create_task_link(
         .map_err(to_error)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{task_id}/links",
+    tag = "tasks",
+    responses(
+        (status = 200, body = [TaskLink]),
+        (status = 404, body = ErrorBody)
+    )
+)]
 async fn list_task_links(
     State(state): State<AppState>,
     Path(task_id): Path<String>,
@@ -41,10 +71,33 @@ async fn list_task_links(
         .map_err(to_error)
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/task-links/{id}",
+    tag = "tasks",
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 404, body = ErrorBody)
+    )
+)]
 async fn delete_task_link(
     State(state): State<AppState>,
+    scope: ProjectScope,
     Path(id): Path<String>,
 ) -> Result<StatusCode, (StatusCode, Json<Value>)> {
+    if scope.projects().is_some() {
+        let link = state
+            .db
+            .get_task_link(&id)
+            .await
+            .map_err(|e| to_error(e.into()))?;
+        let task = state
+            .service
+            .get_task(&link.source_task_id)
+            .await
+            .map_err(to_error)?;
+        scope.check(&task.project_id)?;
+    }
     state
         .service
         .delete_task_link(&id)
@@ -53,6 +106,45 @@ async fn delete_task_link(
         .map_err(to_error)
 }
 
+/// Refuse to move a task to Done while a task that blocks it is still open.
+pub(super) async fn check_blockers(
+    state: &AppState,
+    task_id: &str,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    let links = state
+        .service
+        .list_task_links(task_id)
+        .await
+        .map_err(to_error)?;
+    let mut open = Vec::new();
+    for link in links
+        .iter()
+        .filter(|l| l.link_type == LinkType::Blocks && l.target_task_id == task_id)
+    {
+        let blocker = state
+            .service
+            .get_task(&link.source_task_id)
+            .await
+            .map_err(to_error)?;
+        if !matches!(blocker.status, Status::Done | Status::Cancelled) {
+            open.push(blocker.title);
+        }
+    }
+    if open.is_empty() {
+        return Ok(());
+    }
+    Err((
+        StatusCode::CONFLICT,
+        Json(json!({
+            "error": format!(
+                "task {task_id} is blocked by {} open task(s): {}",
+                open.len(),
+                open.join(", ")
+            )
+        })),
+    ))
+}
+
 fn to_error(e: flowstate_service::ServiceError) -> (StatusCode, Json<Value>) {
     let (status, msg) = match &e {
         flowstate_service::ServiceError::NotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
@@ -197,4 +289,57 @@ mod tests {
             .unwrap();
         assert_eq!(resp.status(), StatusCode::NO_CONTENT);
     }
+
+    async fn set_status(app: &Router, task_id: &str, status: &str) -> StatusCode {
+        app.clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::PUT)
+                    .uri(format!("/api/tasks/{task_id}"))
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        serde_json::to_vec(&serde_json::json!({ "status": status })).unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn open_blocker_keeps_task_from_done() {
+        let app = test_router().await;
+        let project_id = create_project(&app).await;
+        let blocker_id = create_task(&app, &project_id).await;
+        let task_id = create_task(&app, &project_id).await;
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/api/task-links")
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        serde_json::to_vec(&serde_json::json!({
+                            "source_task_id": blocker_id,
+                            "target_task_id": task_id,
+                            "link_type": "blocks"
+                        }))
+                        .unwrap(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::CREATED);
+
+        assert_eq!(
+            set_status(&app, &task_id, "done").await,
+            StatusCode::CONFLICT
+        );
+        // The blocker itself is not held back by the link.
+        assert_eq!(set_status(&app, &blocker_id, "done").await, StatusCode::OK);
+        assert_eq!(set_status(&app, &task_id, "done").await, StatusCode::OK);
+    }
 }
@@ -1,51 +1,102 @@
 use axum::{
     extract::{Path, State},
     http::StatusCode,
-    routing::get,
-    Json, Router,
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use flowstate_core::project::Project;
+use flowstate_core::task::{ApprovalStatus, Task, UpdateTask};
+use flowstate_core::task_pr::{
+    ChecksStatus, CreateTaskPr, MergeStrategy, PrCheck, PrState, TaskPr,
 };
-use flowstate_core::task_pr::CreateTaskPr;
 use flowstate_service::TaskService;
 use serde::Deserialize;
 use serde_json::{json, Value};
+use tracing::{info, warn};
 
+use super::github::apply_pr_state;
+use super::openapi::ErrorBody;
 use super::AppState;
+use crate::auth::{Caller, ProjectScope};
+use crate::forge::{self, ForgeError};
+use crate::{crypto, notifier, pr_checks};
+
+type ApiError = (StatusCode, Json<Value>);
+
+/// Credited with status changes when no caller is known.
+const ACTOR: &str = "flowstate";
 
 pub fn routes() -> Router<AppState> {
-    Router::new().route(
-        "/api/tasks/{task_id}/prs",
-        get(list_task_prs).post(create_task_pr),
-    )
+    Router::new()
+        .route(
+            "/api/tasks/{task_id}/prs",
+            get(list_task_prs).post(create_task_pr),
+        )
+        .route("/api/task-prs/{id}/merge", post(merge_task_pr))
+        .route("/api/task-prs/{id}/close", post(close_task_pr))
+        .route("/api/task-prs/{id}/checks", get(get_task_pr_checks))
+        .route("/api/task-prs/{id}/ready", post(ready_task_pr))
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, utoipa::ToSchema)]
 struct CreateTaskPrRequest {
     pub claude_run_id: Option<String>,
     pub pr_url: String,
     pub pr_number: i64,
     pub branch_name: String,
+    /// The PR was opened as a draft.
+    #[serde(default)]
+    pub draft: bool,
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/tasks/{task_id}/prs",
+    tag = "tasks",
+    request_body = CreateTaskPrRequest,
+    responses(
+        (status = 201, body = TaskPr),
+        (status = 404, body = ErrorBody)
+    )
+)]
 async fn create_task_pr(
     State(state): State<AppState>,
     Path(task_id): Path<String>,
     Json(body): Json<CreateTaskPrRequest>,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    // Recording a PR again returns the existing one and notifies nobody
+    let known = state
+        .service
+        .list_task_prs(&task_id)
+        .await
+        .map_err(to_error)?
+        .iter()
+        .any(|pr| pr.pr_url == body.pr_url);
     let input = CreateTaskPr {
         task_id,
         claude_run_id: body.claude_run_id,
         pr_url: body.pr_url,
         pr_number: body.pr_number,
         branch_name: body.branch_name,
+        draft: body.draft,
     };
-    state
+    let pr = state
         .service
         .create_task_pr(&input)
         .await
-        .map(|pr| (StatusCode::CREATED, Json(json!(pr))))
-        .map_err(to_error)
+        .map_err(to_error)?;
+    if !known {
+        notifier::pr_opened(&state, &pr).await;
+    }
+    Ok((StatusCode::CREATED, Json(json!(pr))))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{task_id}/prs",
+    tag = "tasks",
+    responses((status = 200, body = [TaskPr]))
+)]
 async fn list_task_prs(
     State(state): State<AppState>,
     Path(task_id): Path<String>,
@@ -58,6 +109,302 @@ async fn list_task_prs(
         .map_err(to_error)
 }
 
+#[derive(Deserialize, utoipa::ToSchema)]
+struct MergeTaskPrRequest {
+    #[serde(default)]
+    pub strategy: MergeStrategy,
+}
+
+/// PR `id` and its task, provided the caller may act on the project.
+async fn load_pr(
+    state: &AppState,
+    scope: &ProjectScope,
+    id: &str,
+) -> Result<(TaskPr, Task), ApiError> {
+    let task_pr = state.db.get_task_pr(id).await.map_err(|e| match e {
+        flowstate_db::DbError::NotFound(_) => error(StatusCode::NOT_FOUND, "task PR not found"),
+        e => error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    })?;
+    let task = state
+        .service
+        .get_task(&task_pr.task_id)
+        .await
+        .map_err(to_error)?;
+    scope.check(&task.project_id)?;
+    Ok((task_pr, task))
+}
+
+/// The open PR `id`, its task and the project's decrypted repo token,
+/// provided the caller may act on the project.
+async fn open_pr(
+    state: &AppState,
+    scope: &ProjectScope,
+    id: &str,
+) -> Result<(TaskPr, Task, Project, String), ApiError> {
+    let (task_pr, task) = load_pr(state, scope, id).await?;
+    if task_pr.state != PrState::Open {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            &format!("PR is already {}", task_pr.state.as_str()),
+        ));
+    }
+    let project = state
+        .service
+        .get_project(&task.project_id)
+        .await
+        .map_err(to_error)?;
+    let token = repo_token(state, &project)?;
+    Ok((task_pr, task, project, token))
+}
+
+/// The project's decrypted repo token.
+fn repo_token(state: &AppState, project: &Project) -> Result<String, ApiError> {
+    let Some(encrypted) = project.repo_token.as_deref() else {
+        return Err(error(StatusCode::BAD_REQUEST, "project has no repo token"));
+    };
+    crypto::decrypt(&state.encryption_key, encrypted)
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, &format!("decrypt: {e}")))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/task-prs/{id}/merge",
+    tag = "tasks",
+    params(("id" = String, Path, description = "Task PR id")),
+    request_body = MergeTaskPrRequest,
+    responses(
+        (status = 200, description = "The merged PR and its task"),
+        (status = 400, description = "PR not open, verify not approved or no repo token", body = ErrorBody),
+        (status = 404, body = ErrorBody),
+        (status = 409, description = "The repository host refused the merge", body = ErrorBody),
+        (status = 502, body = ErrorBody)
+    )
+)]
+async fn merge_task_pr(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    scope: ProjectScope,
+    caller: Option<Extension<Caller>>,
+    Json(body): Json<MergeTaskPrRequest>,
+) -> Result<Json<Value>, ApiError> {
+    let (task_pr, task, project, token) = open_pr(&state, &scope, &id).await?;
+    if task.verify_status != ApprovalStatus::Approved {
+        return Err(error(
+            StatusCode::BAD_REQUEST,
+            "task verification has not been approved",
+        ));
+    }
+    forge::merge_pull_request(&project, &token, task_pr.pr_number, body.strategy)
+        .await
+        .map_err(forge_error)?;
+    let actor = caller.map(|Extension(Caller(c))| c);
+    let (task_pr, task) = apply_pr_state(
+        &state,
+        &task_pr,
+        PrState::Merged,
+        actor.as_deref().unwrap_or(ACTOR),
+    )
+    .await?;
+    Ok(Json(json!({"pr": task_pr, "task": task})))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/task-prs/{id}/close",
+    tag = "tasks",
+    params(("id" = String, Path, description = "Task PR id")),
+    responses(
+        (status = 200, description = "The closed PR and its task"),
+        (status = 400, description = "PR not open or no repo token", body = ErrorBody),
+        (status = 404, body = ErrorBody),
+        (status = 409, description = "The repository host refused to close the PR", body = ErrorBody),
+        (status = 502, body = ErrorBody)
+    )
+)]
+async fn close_task_pr(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    scope: ProjectScope,
+    caller: Option<Extension<Caller>>,
+) -> Result<Json<Value>, ApiError> {
+    let (task_pr, _, project, token) = open_pr(&state, &scope, &id).await?;
+    forge::close_pull_request(&project, &token, task_pr.pr_number)
+        .await
+        .map_err(forge_error)?;
+    let actor = caller.map(|Extension(Caller(c))| c);
+    let (task_pr, task) = apply_pr_state(
+        &state,
+        &task_pr,
+        PrState::Closed,
+        actor.as_deref().unwrap_or(ACTOR),
+    )
+    .await?;
+    Ok(Json(json!({"pr": task_pr, "task": task})))
+}
+
+/// Mark a draft PR ready for review on the repository host.
+#[utoipa::path(
+    post,
+    path = "/api/task-prs/{id}/ready",
+    tag = "tasks",
+    params(("id" = String, Path, description = "Task PR id")),
+    responses(
+        (status = 200, body = TaskPr),
+        (status = 400, description = "PR not open, no repo token or unsupported provider", body = ErrorBody),
+        (status = 404, body = ErrorBody),
+        (status = 409, description = "The repository host refused", body = ErrorBody),
+        (status = 502, body = ErrorBody)
+    )
+)]
+async fn ready_task_pr(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    scope: ProjectScope,
+) -> Result<Json<Value>, ApiError> {
+    let (task_pr, _, project, token) = open_pr(&state, &scope, &id).await?;
+    forge::mark_pull_request_ready(&project, &token, task_pr.pr_number)
+        .await
+        .map_err(forge_error)?;
+    let task_pr = state
+        .db
+        .set_task_pr_draft(&task_pr.id, false)
+        .await
+        .map_err(|e| error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()))?;
+    Ok(Json(json!(task_pr)))
+}
+
+/// Once `after` has its verify phase approved, mark its open draft PRs
+/// ready for review. Failures are logged and leave the PR a draft, for
+/// `POST /api/task-prs/{id}/ready` to retry.
+pub(crate) async fn ready_drafts(state: &AppState, before: &Task, after: &Task) {
+    if after.verify_status != ApprovalStatus::Approved
+        || before.verify_status == ApprovalStatus::Approved
+    {
+        return;
+    }
+    let drafts: Vec<_> = match state.service.list_task_prs(&after.id).await {
+        Ok(prs) => prs
+            .into_iter()
+            .filter(|pr| pr.draft && pr.state == PrState::Open)
+            .collect(),
+        Err(e) => {
+            warn!("draft PRs of task {}: {e}", after.id);
+            return;
+        }
+    };
+    if drafts.is_empty() {
+        return;
+    }
+    let project = match state.service.get_project(&after.project_id).await {
+        Ok(project) => project,
+        Err(e) => {
+            warn!("draft PRs of task {}: {e}", after.id);
+            return;
+        }
+    };
+    let token = match repo_token(state, &project) {
+        Ok(token) => token,
+        Err((_, Json(reason))) => {
+            warn!("draft PRs of task {}: {}", after.id, reason["error"]);
+            return;
+        }
+    };
+    for pr in drafts {
+        let ready = match forge::mark_pull_request_ready(&project, &token, pr.pr_number).await {
+            Ok(()) => state.db.set_task_pr_draft(&pr.id, false).await.map(drop),
+            Err(e) => {
+                warn!("could not mark {} ready for review: {e}", pr.pr_url);
+                continue;
+            }
+        };
+        match ready {
+            Ok(()) => info!("marked {} ready for review", pr.pr_url),
+            Err(e) => warn!("{}: {e}", pr.pr_url),
+        }
+    }
+}
+
+#[derive(serde::Serialize, utoipa::ToSchema)]
+struct TaskPrChecks {
+    checks_status: Option<ChecksStatus>,
+    checks: Vec<PrCheck>,
+}
+
+/// Fetch a PR's CI checks from the repository host, storing their combined
+/// status on the task PR.
+#[utoipa::path(
+    get,
+    path = "/api/task-prs/{id}/checks",
+    tag = "tasks",
+    params(("id" = String, Path, description = "Task PR id")),
+    responses(
+        (status = 200, body = TaskPrChecks),
+        (status = 400, description = "No repo token or unsupported provider", body = ErrorBody),
+        (status = 404, body = ErrorBody),
+        (status = 502, body = ErrorBody)
+    )
+)]
+async fn get_task_pr_checks(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    scope: ProjectScope,
+) -> Result<Json<Value>, ApiError> {
+    let (task_pr, _) = load_pr(&state, &scope, &id).await?;
+    let (task_pr, checks) = pr_checks::refresh(&state, &task_pr)
+        .await
+        .map_err(forge_error)?;
+    Ok(Json(json!(TaskPrChecks {
+        checks_status: task_pr.checks_status,
+        checks,
+    })))
+}
+
+/// Refuse an update that approves a task's verify phase while the checks
+/// on one of its open PRs are pending or failing. PRs whose checks have
+/// not been seen do not hold approval up.
+pub(crate) async fn check_verify_gate(
+    state: &AppState,
+    task: &Task,
+    update: &UpdateTask,
+) -> Result<(), ApiError> {
+    if update.verify_status != Some(ApprovalStatus::Approved)
+        || task.verify_status == ApprovalStatus::Approved
+    {
+        return Ok(());
+    }
+    let prs = state
+        .service
+        .list_task_prs(&task.id)
+        .await
+        .map_err(to_error)?;
+    for pr in prs.iter().filter(|pr| pr.state == PrState::Open) {
+        if let Some(status @ (ChecksStatus::Pending | ChecksStatus::Failure)) = pr.checks_status {
+            return Err(error(
+                StatusCode::CONFLICT,
+                &format!(
+                    "checks on PR #{} are {}; approve verify once they pass",
+                    pr.pr_number,
+                    status.as_str()
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn forge_error(e: ForgeError) -> ApiError {
+    let status = match e {
+        ForgeError::Unsupported(_) => StatusCode::BAD_REQUEST,
+        ForgeError::Refused(_) => StatusCode::CONFLICT,
+        ForgeError::Failed(_) => StatusCode::BAD_GATEWAY,
+    };
+    error(status, &e.to_string())
+}
+
+fn error(status: StatusCode, msg: &str) -> ApiError {
+    (status, Json(json!({ "error": msg })))
+}
+
 fn to_error(e: flowstate_service::ServiceError) -> (StatusCode, Json<Value>) {
     let (status, msg) = match &e {
         flowstate_service::ServiceError::NotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
@@ -182,4 +529,299 @@ mod tests {
         assert_eq!(prs.as_array().unwrap().len(), 1);
         assert_eq!(prs[0]["pr_number"], 42);
     }
+
+    use std::sync::{Arc, Mutex};
+
+    /// A GitHub stand-in for PR 7, a draft. Merges record their method and
+    /// ready-for-review mutations their node id; its head commit has a
+    /// passing `lint` status and a `build` check run that reports whatever
+    /// `run` holds.
+    struct FakeGithub {
+        repo_url: String,
+        merges: Arc<Mutex<Vec<String>>>,
+        readied: Arc<Mutex<Vec<String>>>,
+        run: Arc<Mutex<serde_json::Value>>,
+    }
+
+    async fn fake_github() -> FakeGithub {
+        use axum::routing::put;
+        use serde_json::json;
+
+        let merges = Arc::new(Mutex::new(Vec::new()));
+        let readied = Arc::new(Mutex::new(Vec::new()));
+        let run = Arc::new(Mutex::new(json!({"status": "in_progress"})));
+        let (seen, ready, current) = (merges.clone(), readied.clone(), run.clone());
+        let app = axum::Router::new()
+            .route(
+                "/api/v3/repos/acme/app/pulls/7/merge",
+                put(move |axum::Json(body): axum::Json<serde_json::Value>| {
+                    let seen = seen.clone();
+                    async move {
+                        seen.lock()
+                            .unwrap()
+                            .push(body["merge_method"].as_str().unwrap().to_string());
+                        axum::Json(json!({"merged": true}))
+                    }
+                }),
+            )
+            .route(
+                "/api/v3/repos/acme/app/pulls/7",
+                axum::routing::get(|| async {
+                    axum::Json(json!({
+                        "head": {"sha": "abc123"},
+                        "draft": true,
+                        "node_id": "PR_7"
+                    }))
+                }),
+            )
+            .route(
+                "/api/graphql",
+                axum::routing::post(move |axum::Json(body): axum::Json<serde_json::Value>| {
+                    let ready = ready.clone();
+                    async move {
+                        ready
+                            .lock()
+                            .unwrap()
+                            .push(body["variables"]["id"].as_str().unwrap().to_string());
+                        axum::Json(json!({"data": {}}))
+                    }
+                }),
+            )
+            .route(
+                "/api/v3/repos/acme/app/commits/abc123/status",
+                axum::routing::get(|| async {
+                    axum::Json(json!({"statuses": [
+                        {"context": "lint", "state": "success", "target_url": null}
+                    ]}))
+                }),
+            )
+            .route(
+                "/api/v3/repos/acme/app/commits/abc123/check-runs",
+                axum::routing::get(move || {
+                    let current = current.clone();
+                    async move {
+                        let mut run = current.lock().unwrap().clone();
+                        run["name"] = json!("build");
+                        run["html_url"] = json!("https://github.com/acme/app/runs/1");
+                        axum::Json(json!({"check_runs": [run]}))
+                    }
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let repo_url = format!("http://{}/acme/app", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        FakeGithub {
+            repo_url,
+            merges,
+            readied,
+            run,
+        }
+    }
+
+    /// A project on `repo_url` with a repo token, and a task in Verify
+    /// linked to PR 7.
+    async fn task_with_pr(
+        repo_url: String,
+    ) -> (
+        crate::routes::AppState,
+        String,
+        flowstate_core::task_pr::TaskPr,
+    ) {
+        use flowstate_core::task_pr::CreateTaskPr;
+
+        let state = crate::test_helpers::test_state().await;
+        let project = state
+            .db
+            .create_project(&flowstate_core::project::CreateProject {
+                name: "App".into(),
+                slug: "app".into(),
+                description: String::new(),
+                repo_url,
+            })
+            .await
+            .unwrap();
+        state
+            .db
+            .update_project(
+                &project.id,
+                &flowstate_core::project::UpdateProject {
+                    repo_token: Some(
+                        crate::crypto::encrypt(&state.encryption_key, "ghp_test").unwrap(),
+                    ),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let task = state
+            .db
+            .create_task(&flowstate_core::task::CreateTask {
+                project_id: project.id,
+                title: "Add export".into(),
+                description: String::new(),
+                status: flowstate_core::task::Status::Verify,
+                priority: flowstate_core::task::Priority::Medium,
+                task_type: flowstate_core::task::TaskType::Feature,
+                parent_id: None,
+                reviewer: String::new(),
+                research_capability: None,
+                design_capability: None,
+                plan_capability: None,
+                build_capability: None,
+                verify_capability: None,
+                due_at: None,
+            })
+            .await
+            .unwrap();
+        let pr = state
+            .db
+            .create_task_pr(&CreateTaskPr {
+                task_id: task.id.clone(),
+                claude_run_id: None,
+                pr_url: "https://github.com/acme/app/pull/7".into(),
+                pr_number: 7,
+                branch_name: "flowstate/add-export".into(),
+                draft: false,
+            })
+            .await
+            .unwrap();
+        (state, task.id, pr)
+    }
+
+    async fn json_body(resp: axum::response::Response) -> serde_json::Value {
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn merge_requires_approved_verify() {
+        use flowstate_core::task::{ApprovalStatus, UpdateTask};
+
+        let github = fake_github().await;
+        let (state, task_id, pr) = task_with_pr(github.repo_url.clone()).await;
+        let app = crate::routes::build_router(state.clone());
+        let merge = |id: &str| {
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("/api/task-prs/{id}/merge"))
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"strategy":"squash"}"#))
+                .unwrap()
+        };
+
+        let resp = app.clone().oneshot(merge("missing")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let resp = app.clone().oneshot(merge(&pr.id)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+        assert!(github.merges.lock().unwrap().is_empty());
+
+        state
+            .db
+            .update_task(
+                &task_id,
+                &UpdateTask {
+                    verify_status: Some(ApprovalStatus::Approved),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        let resp = app.clone().oneshot(merge(&pr.id)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = json_body(resp).await;
+        assert_eq!(body["pr"]["state"], "merged");
+        assert_eq!(body["task"]["status"], "done");
+        assert_eq!(*github.merges.lock().unwrap(), ["squash"]);
+
+        // Already merged
+        let resp = app.oneshot(merge(&pr.id)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn checks_hold_verify_approval() {
+        let github = fake_github().await;
+        let (state, task_id, pr) = task_with_pr(github.repo_url.clone()).await;
+        let app = crate::routes::build_router(state);
+        let checks = || {
+            Request::builder()
+                .uri(format!("/api/task-prs/{}/checks", pr.id))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let approve = || {
+            Request::builder()
+                .method(Method::PUT)
+                .uri(format!("/api/tasks/{task_id}"))
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"verify_status":"approved"}"#))
+                .unwrap()
+        };
+
+        let resp = app.clone().oneshot(checks()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = json_body(resp).await;
+        assert_eq!(body["checks_status"], "pending");
+        assert_eq!(body["checks"].as_array().unwrap().len(), 2);
+        let resp = app.clone().oneshot(approve()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        *github.run.lock().unwrap() =
+            serde_json::json!({"status": "completed", "conclusion": "failure"});
+        let body = json_body(app.clone().oneshot(checks()).await.unwrap()).await;
+        assert_eq!(body["checks_status"], "failure");
+        let resp = app.clone().oneshot(approve()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::CONFLICT);
+
+        *github.run.lock().unwrap() =
+            serde_json::json!({"status": "completed", "conclusion": "success"});
+        let body = json_body(app.clone().oneshot(checks()).await.unwrap()).await;
+        assert_eq!(body["checks_status"], "success");
+        let resp = app.oneshot(approve()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn verify_approval_readies_draft_prs() {
+        let github = fake_github().await;
+        let (state, task_id, pr) = task_with_pr(github.repo_url.clone()).await;
+        state.db.set_task_pr_draft(&pr.id, true).await.unwrap();
+        let app = crate::routes::build_router(state.clone());
+        let ready = |id: &str| {
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("/api/task-prs/{id}/ready"))
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let resp = app.clone().oneshot(ready("missing")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::PUT)
+                    .uri(format!("/api/tasks/{task_id}"))
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"verify_status":"approved"}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(*github.readied.lock().unwrap(), ["PR_7"]);
+        let prs = state.db.list_task_prs(&task_id).await.unwrap();
+        assert!(!prs[0].draft);
+
+        // The manual retry
+        let resp = app.oneshot(ready(&pr.id)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(json_body(resp).await["draft"], false);
+        assert_eq!(github.readied.lock().unwrap().len(), 2);
+    }
 }
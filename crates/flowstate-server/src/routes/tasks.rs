@@ -1,21 +1,39 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
 use axum::{
     body::Body,
     extract::{Path, Query, State},
-    http::StatusCode,
-    response::Response,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     routing::get,
-    Json, Router,
+    Extension, Json, Router,
 };
 use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use flowstate_core::attachment::Attachment;
+use flowstate_core::claude_run::{ClaudeAction, ClaudeRun};
+use flowstate_core::custom_field::TaskFieldValue;
+use flowstate_core::feature_flag::AUTO_PIPELINE;
+use flowstate_core::feedback::FeedbackEntry;
 use flowstate_core::task::{
-    self, ApprovalStatus, CreateTask, Priority, Status, TaskFilter, UpdateTask,
+    self, ApprovalStatus, BulkUpdateTasks, CreateTask, Priority, ReorderTask, Status, Task,
+    TaskFeedback, TaskFilter, TaskType, UpdateTask,
 };
+use flowstate_core::task_revision::TaskRevision;
 use flowstate_service::TaskService;
+use futures_util::{StreamExt, TryStreamExt};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
 use serde::Deserialize;
 use serde_json::{json, Value};
 use sha2::{Digest, Sha256};
 
-use super::AppState;
+use super::claude_runs::{queue_run, validate_action_prerequisites, QueueOptions};
+use super::events::ServerEvent;
+use super::openapi::ErrorBody;
+use super::{admin, approval_rules, scope_findings, task_links, task_prs, AppState};
+use crate::auth::{Caller, ProjectScope};
+use crate::{notifier, webhooks};
 
 pub fn routes() -> Router<AppState> {
     Router::new()
@@ -24,8 +42,14 @@ pub fn routes() -> Router<AppState> {
             "/api/tasks/{id}",
             get(get_task).put(update_task).delete(delete_task),
         )
+        .route(
+            "/api/tasks/bulk",
+            axum::routing::post(bulk_create_tasks).patch(bulk_update_tasks),
+        )
         .route("/api/tasks/count-by-status", get(count_by_status))
         .route("/api/tasks/{id}/children", get(list_children))
+        .route("/api/tasks/{id}/reorder", axum::routing::post(reorder_task))
+        .route("/api/tasks/{id}/archive", axum::routing::post(archive_task))
         .route("/api/tasks/{id}/spec", get(read_spec).put(write_spec))
         .route("/api/tasks/{id}/plan", get(read_plan).put(write_plan))
         .route(
@@ -38,30 +62,142 @@ pub fn routes() -> Router<AppState> {
         )
         .route(
             "/api/tasks/{id}/feedback",
-            axum::routing::put(write_feedback),
+            get(feedback_history).put(write_feedback),
+        )
+        .route(
+            "/api/tasks/{id}/attachments",
+            get(list_attachments).post(upload_attachment),
+        )
+        .route(
+            "/api/tasks/{id}/attachments/{attachment_id}",
+            get(download_attachment).delete(delete_attachment),
+        )
+        .route(
+            "/api/attachments/{id}/download",
+            get(download_attachment_by_id),
+        )
+        .route(
+            "/api/tasks/{id}/attachments/{attachment_id}/url",
+            get(attachment_url),
         )
-        .route("/api/tasks/{id}/attachments", get(list_attachments))
+        .route("/api/tasks/{id}/history", get(task_history))
+        .route("/api/tasks/{id}/fields", get(list_task_field_values))
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
 struct TaskQuery {
     project_id: Option<String>,
+    /// Comma-separated project ids; tasks may be in any of them.
+    projects: Option<String>,
     status: Option<String>,
+    /// Comma-separated statuses; tasks may be in any of them.
+    statuses: Option<String>,
     priority: Option<String>,
+    task_type: Option<String>,
     sprint_id: Option<String>,
+    epic_id: Option<String>,
+    assignee_id: Option<String>,
+    /// Comma-separated user ids; tasks may be assigned to any of them.
+    assignees: Option<String>,
+    /// Only tasks without an assignee.
+    #[serde(default)]
+    unassigned: bool,
+    /// Only tasks this user is watching.
+    watched_by: Option<String>,
+    /// Comma-separated label ids; tasks must carry all of them.
+    labels: Option<String>,
+    /// Case-insensitive match against title and description.
+    text: Option<String>,
+    /// With `field_value`, only tasks whose custom field has that value.
+    field_id: Option<String>,
+    field_value: Option<String>,
+    /// RFC 3339; only tasks due at or after this instant.
+    due_after: Option<DateTime<Utc>>,
+    /// RFC 3339; only tasks due before this instant.
+    due_before: Option<DateTime<Utc>>,
+    /// Only open tasks whose due date has passed.
+    #[serde(default)]
+    overdue: bool,
+    /// RFC 3339; only tasks created in `[created_after, created_before)`.
+    created_after: Option<DateTime<Utc>>,
+    created_before: Option<DateTime<Utc>>,
+    /// RFC 3339; only tasks updated at or after this instant.
+    updated_after: Option<DateTime<Utc>>,
+    /// Only archived tasks; they are left out otherwise.
+    #[serde(default)]
+    archived: bool,
     limit: Option<i64>,
 }
 
+/// The non-empty items of a comma-separated query value.
+fn split_list(value: Option<&str>) -> impl Iterator<Item = &str> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .filter(|item| !item.is_empty())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/tasks",
+    tag = "tasks",
+    params(TaskQuery),
+    responses(
+        (status = 200, body = [Task]),
+        (status = 400, body = ErrorBody)
+    )
+)]
 async fn list_tasks(
     State(state): State<AppState>,
+    scope: ProjectScope,
     Query(q): Query<TaskQuery>,
 ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    if let Some(ref project_id) = q.project_id {
+        scope.check(project_id)?;
+    }
+    let mut project_ids: Vec<String> = split_list(q.projects.as_deref())
+        .map(str::to_string)
+        .collect();
+    if let Some(allowed) = scope.projects() {
+        if project_ids.is_empty() {
+            project_ids = allowed.to_vec();
+        } else {
+            project_ids.retain(|id| scope.allows(id));
+        }
+        if project_ids.is_empty() {
+            return Ok(Json(json!([])));
+        }
+    }
     let filter = TaskFilter {
         project_id: q.project_id,
+        project_ids,
         status: q.status.and_then(|s| Status::parse_str(&s)),
+        statuses: split_list(q.statuses.as_deref())
+            .filter_map(Status::parse_str)
+            .collect(),
         priority: q.priority.and_then(|p| Priority::parse_str(&p)),
+        task_type: q.task_type.and_then(|t| TaskType::parse_str(&t)),
         sprint_id: q.sprint_id,
+        epic_id: q.epic_id,
+        assignee_id: q.assignee_id,
+        assignee_ids: split_list(q.assignees.as_deref())
+            .map(str::to_string)
+            .collect(),
+        unassigned: q.unassigned,
+        watched_by: q.watched_by,
+        label_ids: split_list(q.labels.as_deref())
+            .map(str::to_string)
+            .collect(),
+        text: q.text.filter(|t| !t.is_empty()),
+        custom_field: q.field_id.zip(q.field_value),
+        due_after: q.due_after,
+        due_before: q.due_before,
+        overdue: q.overdue,
+        created_after: q.created_after,
+        created_before: q.created_before,
+        updated_after: q.updated_after,
         parent_id: None,
+        archived: q.archived,
         limit: q.limit,
     };
     state
@@ -72,6 +208,15 @@ async fn list_tasks(
         .map_err(to_error)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{id}",
+    tag = "tasks",
+    responses(
+        (status = 200, body = Task),
+        (status = 404, body = ErrorBody)
+    )
+)]
 async fn get_task(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -84,51 +229,126 @@ async fn get_task(
         .map_err(to_error)
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/tasks",
+    tag = "tasks",
+    request_body = CreateTask,
+    responses(
+        (status = 201, body = Task),
+        (status = 400, body = ErrorBody)
+    )
+)]
 async fn create_task(
     State(state): State<AppState>,
+    scope: ProjectScope,
     Json(input): Json<CreateTask>,
 ) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    scope.check(&input.project_id)?;
     state
         .service
         .create_task(&input)
         .await
+        .inspect(|t| publish_task(&state, t))
         .map(|t| (StatusCode::CREATED, Json(json!(t))))
         .map_err(to_error)
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/tasks/{id}",
+    tag = "tasks",
+    request_body = UpdateTask,
+    responses(
+        (status = 200, body = Task),
+        (status = 400, body = ErrorBody),
+        (status = 403, description = "The project's approval rules reserve a phase for the task's reviewer", body = ErrorBody),
+        (status = 404, body = ErrorBody),
+        (status = 409, description = "Moving to Done is refused while scope findings are unacknowledged or a blocking task is open", body = ErrorBody)
+    )
+)]
 async fn update_task(
     State(state): State<AppState>,
     Path(id): Path<String>,
+    caller: Option<Extension<Caller>>,
     Json(mut input): Json<UpdateTask>,
 ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    input.actor = caller.map(|Extension(Caller(c))| c);
+    let task = apply_task_update(&state, &id, input).await?;
+    Ok(Json(json!(task)))
+}
+
+/// Refuse an update that points `task` at another project's epic or at a
+/// user that does not exist.
+async fn check_references(
+    state: &AppState,
+    task: &task::Task,
+    input: &UpdateTask,
+) -> Result<(), (StatusCode, Json<Value>)> {
+    // Epics are project-scoped; a task can only join one of its own project's
+    if let Some(Some(epic_id)) = &input.epic_id {
+        let epic = state.service.get_epic(epic_id).await.map_err(to_error)?;
+        if epic.project_id != task.project_id {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": "epic belongs to a different project" })),
+            ));
+        }
+    }
+
+    if let Some(Some(assignee_id)) = &input.assignee_id {
+        state
+            .service
+            .get_user(assignee_id)
+            .await
+            .map_err(to_error)?;
+    }
+    Ok(())
+}
+
+/// Update a task with everything `PUT /api/tasks/{id}` does around the write:
+/// approval content hashes, board auto-advance, the Done gate, live events
+/// and webhooks.
+pub(crate) async fn apply_task_update(
+    state: &AppState,
+    id: &str,
+    mut input: UpdateTask,
+) -> Result<task::Task, (StatusCode, Json<Value>)> {
     // Fetch current task for status comparison and hash logic
-    let current_task = state.service.get_task(&id).await.map_err(to_error)?;
+    let current_task = state.service.get_task(id).await.map_err(to_error)?;
+    approval_rules::check_reviewer(state, &current_task, &input).await?;
+    task_prs::check_verify_gate(state, &current_task, &input).await?;
+
+    check_references(state, &current_task, &input).await?;
 
     // On spec approval, compute and store the spec content hash
     if input.spec_status == Some(ApprovalStatus::Approved) {
-        let key = flowstate_store::task_spec_key(&id);
+        let key = flowstate_store::task_spec_key(id);
         if let Ok(Some(data)) = state.store.get_opt(&key).await {
             let content = String::from_utf8_lossy(&data);
-            input.spec_approved_hash = Some(sha256_hex(&content));
+            input.spec_approved_hash = Some(sha256_hex(content.as_bytes()));
         }
     }
     // On research approval, compute and store the research content hash
     if input.research_status == Some(ApprovalStatus::Approved) {
-        let key = flowstate_store::task_research_key(&id);
+        let key = flowstate_store::task_research_key(id);
         if let Ok(Some(data)) = state.store.get_opt(&key).await {
             let content = String::from_utf8_lossy(&data);
-            input.research_approved_hash = Some(sha256_hex(&content));
+            input.research_approved_hash = Some(sha256_hex(content.as_bytes()));
         }
     }
 
     // Auto-advance board status on approval (forward-only, skip Cancelled)
-    if input.status.is_none() && current_task.status != Status::Cancelled {
+    if input.status.is_none()
+        && current_task.status != Status::Cancelled
+        && admin::flag_enabled(state, AUTO_PIPELINE, Some(&current_task.project_id)).await
+    {
         let target = if input.research_status == Some(ApprovalStatus::Approved) {
             task::status_after_approval("research")
         } else if input.spec_status == Some(ApprovalStatus::Approved) {
             task::status_after_approval("spec")
         } else if input.plan_status == Some(ApprovalStatus::Approved) {
-            task::status_after_approval("plan")
+            task::status_after_plan_approval(current_task.task_type)
         } else if input.verify_status == Some(ApprovalStatus::Approved) {
             task::status_after_approval("verify")
         } else {
@@ -142,14 +362,181 @@ async fn update_task(
         }
     }
 
+    if input.status == Some(Status::Done) && current_task.status != Status::Done {
+        scope_findings::check_done_gate(state, id).await?;
+        task_links::check_blockers(state, id).await?;
+    }
+
+    let task = state
+        .service
+        .update_task(id, &input)
+        .await
+        .map_err(to_error)?;
+    publish_task(state, &task);
+    webhooks::task_changed(state, &current_task, &task).await;
+    notifier::task_changed(state, &current_task, &task).await;
+    task_prs::ready_drafts(state, &current_task, &task).await;
+    Ok(task)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/tasks/bulk",
+    tag = "tasks",
+    request_body = Vec<CreateTask>,
+    responses(
+        (status = 201, body = [Task]),
+        (status = 400, body = ErrorBody)
+    )
+)]
+async fn bulk_create_tasks(
+    State(state): State<AppState>,
+    scope: ProjectScope,
+    Json(inputs): Json<Vec<CreateTask>>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    for input in &inputs {
+        scope.check(&input.project_id)?;
+    }
+    state
+        .service
+        .bulk_create_tasks(&inputs)
+        .await
+        .inspect(|tasks| tasks.iter().for_each(|t| publish_task(&state, t)))
+        .map(|t| (StatusCode::CREATED, Json(json!(t))))
+        .map_err(to_error)
+}
+
+/// Apply one update to many tasks atomically. Unlike the single-task PUT,
+/// approval side effects (content hashes, board auto-advance) are not applied,
+/// but moving to Done is still refused while scope findings are pending or
+/// a blocking task is open.
+#[utoipa::path(
+    patch,
+    path = "/api/tasks/bulk",
+    tag = "tasks",
+    request_body = BulkUpdateTasks,
+    responses(
+        (status = 200, body = [Task]),
+        (status = 400, body = ErrorBody),
+        (status = 403, description = "The project's approval rules reserve a phase for the task's reviewer", body = ErrorBody),
+        (status = 404, body = ErrorBody),
+        (status = 409, description = "Moving to Done is refused while scope findings are unacknowledged or a blocking task is open", body = ErrorBody)
+    )
+)]
+async fn bulk_update_tasks(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    scope: ProjectScope,
+    Json(mut input): Json<BulkUpdateTasks>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    input.update.actor = caller.map(|Extension(Caller(c))| c);
+    let sets_reference = matches!(input.update.epic_id, Some(Some(_)))
+        || matches!(input.update.assignee_id, Some(Some(_)));
+    if scope.projects().is_some() || sets_reference {
+        for id in &input.ids {
+            let task = state.service.get_task(id).await.map_err(to_error)?;
+            scope.check(&task.project_id)?;
+            check_references(&state, &task, &input.update).await?;
+        }
+    }
+    if input.update.status == Some(Status::Done) {
+        for id in &input.ids {
+            scope_findings::check_done_gate(&state, id).await?;
+            task_links::check_blockers(&state, id).await?;
+        }
+    }
+    // Earlier versions are only needed to tell webhooks what changed
+    let update = &input.update;
+    let mut before = HashMap::new();
+    if update.status.is_some()
+        || update.research_status.is_some()
+        || update.spec_status.is_some()
+        || update.plan_status.is_some()
+        || update.verify_status.is_some()
+    {
+        for id in &input.ids {
+            if let Ok(task) = state.service.get_task(id).await {
+                before.insert(id.clone(), task);
+            }
+        }
+    }
+    for task in before.values() {
+        approval_rules::check_reviewer(&state, task, update).await?;
+        task_prs::check_verify_gate(&state, task, update).await?;
+    }
+    let tasks = state
+        .service
+        .bulk_update_tasks(&input.ids, &input.update)
+        .await
+        .map_err(to_error)?;
+    for task in &tasks {
+        publish_task(&state, task);
+        if let Some(previous) = before.get(&task.id) {
+            webhooks::task_changed(&state, previous, task).await;
+            notifier::task_changed(&state, previous, task).await;
+            task_prs::ready_drafts(&state, previous, task).await;
+        }
+    }
+    Ok(Json(json!(tasks)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/tasks/{id}/reorder",
+    tag = "tasks",
+    request_body = ReorderTask,
+    responses(
+        (status = 200, body = Task),
+        (status = 400, body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn reorder_task(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(input): Json<ReorderTask>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    state
+        .service
+        .reorder_task(&id, input.after_id.as_deref())
+        .await
+        .inspect(|t| publish_task(&state, t))
+        .map(|t| Json(json!(t)))
+        .map_err(to_error)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/tasks/{id}/archive",
+    tag = "tasks",
+    responses(
+        (status = 200, body = Task),
+        (status = 400, description = "The task is not done or cancelled", body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn archive_task(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
     state
         .service
-        .update_task(&id, &input)
+        .archive_task(&id)
         .await
+        .inspect(|t| publish_task(&state, t))
         .map(|t| Json(json!(t)))
         .map_err(to_error)
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/tasks/{id}",
+    tag = "tasks",
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 404, body = ErrorBody)
+    )
+)]
 async fn delete_task(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -158,19 +545,40 @@ async fn delete_task(
         .service
         .delete_task(&id)
         .await
+        .inspect(|_| {
+            state.events.publish(ServerEvent::TaskDeleted {
+                task_id: id.clone(),
+            })
+        })
         .map(|_| StatusCode::NO_CONTENT)
         .map_err(to_error)
 }
 
-#[derive(Debug, Deserialize)]
+/// Tell `/api/events` subscribers that `task` was created or changed.
+pub(crate) fn publish_task(state: &AppState, task: &task::Task) {
+    state
+        .events
+        .publish(ServerEvent::TaskUpdated { task: task.clone() });
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
 struct CountQuery {
     project_id: String,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/tasks/count-by-status",
+    tag = "tasks",
+    params(CountQuery),
+    responses((status = 200, description = "`[status, count]` pairs", body = Object))
+)]
 async fn count_by_status(
     State(state): State<AppState>,
+    scope: ProjectScope,
     Query(q): Query<CountQuery>,
 ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    scope.check(&q.project_id)?;
     state
         .service
         .count_tasks_by_status(&q.project_id)
@@ -179,6 +587,12 @@ async fn count_by_status(
         .map_err(to_error)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{id}/children",
+    tag = "tasks",
+    responses((status = 200, body = [Task]))
+)]
 async fn list_children(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -191,6 +605,15 @@ async fn list_children(
         .map_err(to_error)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{id}/spec",
+    tag = "tasks",
+    responses(
+        (status = 200, description = "The task's spec; empty when there is none yet", body = String, content_type = "text/markdown"),
+        (status = 404, body = ErrorBody)
+    )
+)]
 async fn read_spec(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -216,6 +639,16 @@ async fn read_spec(
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/tasks/{id}/spec",
+    tag = "tasks",
+    request_body(content = String, content_type = "text/markdown"),
+    responses(
+        (status = 204, description = "Stored"),
+        (status = 404, body = ErrorBody)
+    )
+)]
 async fn write_spec(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -257,6 +690,15 @@ async fn write_spec(
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{id}/plan",
+    tag = "tasks",
+    responses(
+        (status = 200, description = "The task's plan; empty when there is none yet", body = String, content_type = "text/markdown"),
+        (status = 404, body = ErrorBody)
+    )
+)]
 async fn read_plan(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -281,6 +723,16 @@ async fn read_plan(
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/tasks/{id}/plan",
+    tag = "tasks",
+    request_body(content = String, content_type = "text/markdown"),
+    responses(
+        (status = 204, description = "Stored"),
+        (status = 404, body = ErrorBody)
+    )
+)]
 async fn write_plan(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -310,6 +762,15 @@ async fn write_plan(
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{id}/research",
+    tag = "tasks",
+    responses(
+        (status = 200, description = "The task's research; empty when there is none yet", body = String, content_type = "text/markdown"),
+        (status = 404, body = ErrorBody)
+    )
+)]
 async fn read_research(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -334,6 +795,16 @@ async fn read_research(
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/tasks/{id}/research",
+    tag = "tasks",
+    request_body(content = String, content_type = "text/markdown"),
+    responses(
+        (status = 204, description = "Stored"),
+        (status = 404, body = ErrorBody)
+    )
+)]
 async fn write_research(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -375,6 +846,15 @@ async fn write_research(
     Ok(StatusCode::NO_CONTENT)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{id}/verification",
+    tag = "tasks",
+    responses(
+        (status = 200, description = "The task's verification; empty when there is none yet", body = String, content_type = "text/markdown"),
+        (status = 404, body = ErrorBody)
+    )
+)]
 async fn read_verification(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -399,6 +879,16 @@ async fn read_verification(
     }
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/tasks/{id}/verification",
+    tag = "tasks",
+    request_body(content = String, content_type = "text/markdown"),
+    responses(
+        (status = 204, description = "Stored"),
+        (status = 404, body = ErrorBody)
+    )
+)]
 async fn write_verification(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -428,33 +918,64 @@ async fn write_verification(
     Ok(StatusCode::NO_CONTENT)
 }
 
-#[derive(Debug, Deserialize)]
-struct FeedbackInput {
-    phase: String,
-    feedback: String,
-}
-
+/// Record reviewer feedback for a phase. With `distill`, also reject the
+/// phase and queue its distill run, returning the run (201); otherwise 204.
+#[utoipa::path(
+    put,
+    path = "/api/tasks/{id}/feedback",
+    tag = "tasks",
+    request_body = TaskFeedback,
+    responses(
+        (status = 204, description = "Recorded"),
+        (status = 201, description = "Recorded, and the distill run queued", body = ClaudeRun),
+        (status = 400, description = "Unknown phase, or nothing to distill yet", body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
 async fn write_feedback(
     State(state): State<AppState>,
     Path(id): Path<String>,
-    Json(input): Json<FeedbackInput>,
-) -> Result<StatusCode, (StatusCode, Json<Value>)> {
-    let _task = state.service.get_task(&id).await.map_err(to_error)?;
-    let update = match input.phase.as_str() {
+    caller: Option<Extension<Caller>>,
+    Json(input): Json<TaskFeedback>,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    let task = state.service.get_task(&id).await.map_err(to_error)?;
+
+    // Check the distill can run before writing anything, so a refused
+    // request leaves the task untouched.
+    let distill = if input.distill {
+        let action = ClaudeAction::distill_for_phase(&input.phase).ok_or_else(|| {
+            to_error(flowstate_service::ServiceError::InvalidInput(format!(
+                "invalid phase: {}",
+                input.phase
+            )))
+        })?;
+        validate_action_prerequisites(action, &task, false, false)
+            .map_err(|msg| to_error(flowstate_service::ServiceError::InvalidInput(msg)))?;
+        Some(action)
+    } else {
+        None
+    };
+    let rejected = distill.map(|_| ApprovalStatus::Rejected);
+
+    let mut update = match input.phase.as_str() {
         "research" => UpdateTask {
-            research_feedback: Some(input.feedback),
+            research_feedback: Some(input.feedback.clone()),
+            research_status: rejected,
             ..Default::default()
         },
-        "design" => UpdateTask {
-            spec_feedback: Some(input.feedback),
+        "design" | "spec" => UpdateTask {
+            spec_feedback: Some(input.feedback.clone()),
+            spec_status: rejected,
             ..Default::default()
         },
         "plan" => UpdateTask {
-            plan_feedback: Some(input.feedback),
+            plan_feedback: Some(input.feedback.clone()),
+            plan_status: rejected,
             ..Default::default()
         },
         "verify" => UpdateTask {
-            verify_feedback: Some(input.feedback),
+            verify_feedback: Some(input.feedback.clone()),
+            verify_status: rejected,
             ..Default::default()
         },
         _ => {
@@ -463,14 +984,71 @@ async fn write_feedback(
             )))
         }
     };
+    update.actor = caller.map(|Extension(Caller(c))| c);
     state
         .service
         .update_task(&id, &update)
         .await
         .map_err(to_error)?;
-    Ok(StatusCode::NO_CONTENT)
+
+    let Some(action) = distill else {
+        return Ok(StatusCode::NO_CONTENT.into_response());
+    };
+    let options = QueueOptions {
+        feedback: Some(input.feedback),
+        ..Default::default()
+    };
+    let run = queue_run(&state, &id, action, options).await?;
+    Ok((StatusCode::CREATED, Json(json!(run))).into_response())
+}
+
+/// Every rejection's feedback on the task's documents, newest first.
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{id}/feedback",
+    tag = "tasks",
+    responses(
+        (status = 200, body = [FeedbackEntry]),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn feedback_history(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let _task = state.service.get_task(&id).await.map_err(to_error)?;
+    state
+        .service
+        .list_feedback_history(&id)
+        .await
+        .map(|h| Json(json!(h)))
+        .map_err(to_error)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{id}/fields",
+    tag = "tasks",
+    responses((status = 200, body = [TaskFieldValue]))
+)]
+async fn list_task_field_values(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    state
+        .service
+        .list_task_field_values(&id)
+        .await
+        .map(|v| Json(json!(v)))
+        .map_err(to_error)
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{id}/attachments",
+    tag = "attachments",
+    responses((status = 200, body = [Attachment]))
+)]
 async fn list_attachments(
     State(state): State<AppState>,
     Path(id): Path<String>,
@@ -483,9 +1061,505 @@ async fn list_attachments(
         .map_err(to_error)
 }
 
-fn sha256_hex(content: &str) -> String {
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+struct UploadQuery {
+    /// Required for raw uploads; multipart uploads default to the file
+    /// part's own name.
+    filename: Option<String>,
+}
+
+/// Stream an upload into the store as an attachment, either the raw request
+/// body or the first file part of a `multipart/form-data` body. The content
+/// type comes from the request (or part) header, or from the filename when
+/// the client sends none; the checksum is always computed here.
+///
+/// Bytes are stored once per checksum: the upload is staged, then moved to
+/// its content-addressed key, or dropped if that blob is already stored.
+#[utoipa::path(
+    post,
+    path = "/api/tasks/{id}/attachments",
+    tag = "attachments",
+    params(UploadQuery),
+    request_body(
+        content(
+            (Vec<u8> = "application/octet-stream"),
+            (Object = "multipart/form-data")
+        )
+    ),
+    responses(
+        (status = 201, body = Attachment),
+        (status = 400, body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn upload_attachment(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<UploadQuery>,
+    request: axum::extract::Request,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    let _task = state.service.get_task(&id).await.map_err(to_error)?;
+    let request_type = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+    if !request_type.starts_with("multipart/form-data") {
+        let filename = query.filename.unwrap_or_default();
+        let stream = request
+            .into_body()
+            .into_data_stream()
+            .map_err(|e| flowstate_store::StoreError::Internal(format!("read body: {e}")))
+            .boxed();
+        let attachment = store_upload(&state, &id, &filename, Some(&request_type), stream).await?;
+        return Ok((StatusCode::CREATED, Json(json!(attachment))));
+    }
+
+    let bad_request = |msg: String| to_error(flowstate_service::ServiceError::InvalidInput(msg));
+    let mut multipart =
+        <axum::extract::Multipart as axum::extract::FromRequest<_>>::from_request(request, &state)
+            .await
+            .map_err(|e| bad_request(e.body_text()))?;
+    let mut field = loop {
+        match multipart.next_field().await {
+            Ok(Some(field)) if field.file_name().is_some() => break field,
+            Ok(Some(_)) => continue,
+            Ok(None) => return Err(bad_request("no file part in multipart body".into())),
+            Err(e) => return Err(bad_request(e.body_text())),
+        }
+    };
+    let filename = query
+        .filename
+        .or_else(|| field.file_name().map(str::to_string))
+        .unwrap_or_default();
+    let part_type = field.content_type().map(str::to_string);
+
+    // The part borrows the body, so it is pumped into the store through a
+    // channel rather than handed over as a stream.
+    let (tx, rx) = tokio::sync::mpsc::channel(4);
+    let pump = async move {
+        loop {
+            let chunk = match field.chunk().await {
+                Ok(Some(chunk)) => Ok(chunk),
+                Ok(None) => break,
+                Err(e) => Err(flowstate_store::StoreError::Internal(format!(
+                    "read body: {e}"
+                ))),
+            };
+            let failed = chunk.is_err();
+            if tx.send(chunk).await.is_err() || failed {
+                break;
+            }
+        }
+    };
+    let stream = channel_stream(rx);
+    let (attachment, ()) = tokio::join!(
+        store_upload(&state, &id, &filename, part_type.as_deref(), stream),
+        pump
+    );
+    Ok((StatusCode::CREATED, Json(json!(attachment?))))
+}
+
+/// A store stream reading from `rx` until its sender is dropped.
+fn channel_stream(
+    rx: tokio::sync::mpsc::Receiver<Result<Bytes, flowstate_store::StoreError>>,
+) -> flowstate_store::ByteStream {
+    futures_util::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|chunk| (chunk, rx))
+    })
+    .boxed()
+}
+
+/// Store `stream` as an attachment named `filename` on task `id`, hashing
+/// and counting it on the way through.
+async fn store_upload(
+    state: &AppState,
+    id: &str,
+    filename: &str,
+    content_type: Option<&str>,
+    stream: flowstate_store::ByteStream,
+) -> Result<Attachment, (StatusCode, Json<Value>)> {
+    let filename = filename.trim();
+    if filename.is_empty() || filename.contains(['/', '\\']) {
+        return Err(to_error(flowstate_service::ServiceError::InvalidInput(
+            "filename must be a plain file name".into(),
+        )));
+    }
+    let content_type = content_type
+        .filter(|v| !v.is_empty() && *v != "application/octet-stream")
+        .unwrap_or_else(|| flowstate_core::attachment::guess_content_type(filename))
+        .to_string();
+    let upload_key = flowstate_store::attachment_upload_key(&uuid::Uuid::new_v4().to_string());
+
+    // Hash and count the body as it passes through to the store.
+    let digest = Arc::new(Mutex::new((Sha256::new(), 0i64)));
+    let tap = digest.clone();
+    let stream = stream
+        .inspect_ok(move |chunk| {
+            let mut tap = tap.lock().unwrap();
+            tap.0.update(chunk);
+            tap.1 += chunk.len() as i64;
+        })
+        .boxed();
+    let write_error = |e: flowstate_store::StoreError| {
+        to_error(flowstate_service::ServiceError::Internal(format!(
+            "write: {e}"
+        )))
+    };
+    state
+        .store
+        .put_stream(&upload_key, stream)
+        .await
+        .map_err(write_error)?;
+    let (hasher, size_bytes) = std::mem::take(&mut *digest.lock().unwrap());
+    let sha256 = format!("{:x}", hasher.finalize());
+    let key = flowstate_store::attachment_blob_key(&sha256);
+
+    // Record the reference before touching the blob, so a concurrent delete
+    // of the last other reference counts this one and keeps the blob.
+    let attachment = match state
+        .db
+        .create_attachment(id, filename, &key, size_bytes, &content_type, &sha256)
+        .await
+    {
+        Ok(attachment) => attachment,
+        Err(e) => {
+            let _ = state.store.delete(&upload_key).await;
+            return Err(to_error(e.into()));
+        }
+    };
+    let stored = match state.store.exists(&key).await {
+        Ok(true) => state.store.delete(&upload_key).await,
+        Ok(false) => state.store.rename(&upload_key, &key).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = stored {
+        let _ = state.db.delete_attachment(&attachment.id).await;
+        let _ = state.store.delete(&upload_key).await;
+        return Err(write_error(e));
+    }
+    Ok(attachment)
+}
+
+/// Attach bytes already in memory to a task, stored once per checksum like
+/// an upload. For callers other than the upload route, such as the email
+/// gateway.
+pub(crate) async fn attach_bytes(
+    state: &AppState,
+    task_id: &str,
+    filename: &str,
+    content_type: &str,
+    data: Bytes,
+) -> Result<flowstate_core::attachment::Attachment, flowstate_service::ServiceError> {
+    let sha256 = format!("{:x}", Sha256::digest(&data));
+    let key = flowstate_store::attachment_blob_key(&sha256);
+    let attachment = state
+        .db
+        .create_attachment(
+            task_id,
+            filename,
+            &key,
+            data.len() as i64,
+            content_type,
+            &sha256,
+        )
+        .await?;
+    let stored = match state.store.exists(&key).await {
+        Ok(true) => Ok(()),
+        Ok(false) => state.store.put(&key, data).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = stored {
+        let _ = state.db.delete_attachment(&attachment.id).await;
+        return Err(flowstate_service::ServiceError::Internal(format!(
+            "write: {e}"
+        )));
+    }
+    Ok(attachment)
+}
+
+/// Delete an attachment, and its bytes once no other attachment shares
+/// them.
+#[utoipa::path(
+    delete,
+    path = "/api/tasks/{id}/attachments/{attachment_id}",
+    tag = "attachments",
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 404, description = "Unknown task or attachment", body = ErrorBody)
+    )
+)]
+async fn delete_attachment(
+    State(state): State<AppState>,
+    Path((id, attachment_id)): Path<(String, String)>,
+) -> Result<StatusCode, (StatusCode, Json<Value>)> {
+    let attachment = task_attachment(&state, &id, &attachment_id).await?;
+    state
+        .db
+        .delete_attachment(&attachment.id)
+        .await
+        .map_err(|e| to_error(e.into()))?;
+    match state.db.count_attachment_refs(&attachment.store_key).await {
+        Ok(0) => {
+            if let Err(e) = state.store.delete(&attachment.store_key).await {
+                tracing::warn!("deleting attachment blob {}: {e}", attachment.store_key);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!(
+            "counting references to {}: {e}; keeping the blob",
+            attachment.store_key
+        ),
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Serve an attachment's bytes with its recorded content type; the ETag is
+/// the SHA-256 so clients can check what they received. A single-range
+/// `Range` header gets `206 Partial Content`, so large downloads can resume.
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{id}/attachments/{attachment_id}",
+    tag = "attachments",
+    responses(
+        (status = 200, description = "The attachment, with its SHA-256 as the ETag", content_type = "application/octet-stream"),
+        (status = 206, description = "The requested byte range", content_type = "application/octet-stream"),
+        (status = 404, description = "Unknown task or attachment", body = ErrorBody),
+        (status = 416, description = "The range lies outside the attachment")
+    )
+)]
+async fn download_attachment(
+    State(state): State<AppState>,
+    Path((id, attachment_id)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    let attachment = task_attachment(&state, &id, &attachment_id).await?;
+    serve_attachment(&state, &attachment, &headers).await
+}
+
+/// Download an attachment by id alone, for links that do not carry the
+/// task. Same responses as the task-scoped route.
+#[utoipa::path(
+    get,
+    path = "/api/attachments/{id}/download",
+    tag = "attachments",
+    responses(
+        (status = 200, description = "The attachment, with its SHA-256 as the ETag", content_type = "application/octet-stream"),
+        (status = 206, description = "The requested byte range", content_type = "application/octet-stream"),
+        (status = 404, description = "Unknown attachment", body = ErrorBody),
+        (status = 416, description = "The range lies outside the attachment")
+    )
+)]
+async fn download_attachment_by_id(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    scope: ProjectScope,
+    headers: HeaderMap,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    let attachment = state
+        .db
+        .get_attachment(&id)
+        .await
+        .map_err(|e| to_error(e.into()))?;
+    if scope.projects().is_some() {
+        let task = state
+            .service
+            .get_task(&attachment.task_id)
+            .await
+            .map_err(to_error)?;
+        scope.check(&task.project_id)?;
+    }
+    serve_attachment(&state, &attachment, &headers).await
+}
+
+async fn serve_attachment(
+    state: &AppState,
+    attachment: &Attachment,
+    headers: &HeaderMap,
+) -> Result<Response, (StatusCode, Json<Value>)> {
+    let size = attachment.size_bytes.max(0) as u64;
+    let range = match headers.get(header::RANGE).and_then(|v| v.to_str().ok()) {
+        Some(value) => match parse_range(value, size) {
+            Some(range) => range,
+            None => {
+                return Ok(Response::builder()
+                    .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                    .header(header::CONTENT_RANGE, format!("bytes */{size}"))
+                    .body(Body::empty())
+                    .unwrap())
+            }
+        },
+        None => None,
+    };
+    let read = match range {
+        Some((start, end)) => {
+            state
+                .store
+                .get_range_stream(&attachment.store_key, start, end - start + 1)
+                .await
+        }
+        None => state.store.get_stream(&attachment.store_key).await,
+    };
+    let stream = read.map_err(|e| {
+        to_error(flowstate_service::ServiceError::Internal(format!(
+            "read attachment: {e}"
+        )))
+    })?;
+
+    let content_type = if attachment.content_type.is_empty() {
+        "application/octet-stream"
+    } else {
+        &attachment.content_type
+    };
+    let mut response = Response::builder()
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::ACCEPT_RANGES, "bytes");
+    response = match range {
+        Some((start, end)) => response
+            .status(StatusCode::PARTIAL_CONTENT)
+            .header(header::CONTENT_RANGE, format!("bytes {start}-{end}/{size}"))
+            .header(header::CONTENT_LENGTH, end - start + 1),
+        None => response.header(header::CONTENT_LENGTH, size),
+    };
+    if !attachment.sha256.is_empty() {
+        response = response.header(header::ETAG, format!("\"{}\"", attachment.sha256));
+    }
+    Ok(response.body(Body::from_stream(stream)).unwrap())
+}
+
+/// The inclusive byte range a `Range` header asks for within `size` bytes:
+/// `None` when it cannot be satisfied, and `Some(None)` when the whole body
+/// should be served (another unit, or several ranges, which the whole body
+/// covers).
+fn parse_range(value: &str, size: u64) -> Option<Option<(u64, u64)>> {
+    let Some(spec) = value.trim().strip_prefix("bytes=") else {
+        return Some(None);
+    };
+    if spec.contains(',') {
+        return Some(None);
+    }
+    let (first, last) = spec.trim().split_once('-')?;
+    let (start, end) = match (first.trim(), last.trim()) {
+        ("", suffix) => {
+            let len: u64 = suffix.parse().ok()?;
+            if len == 0 {
+                return None;
+            }
+            (size.saturating_sub(len), size.checked_sub(1)?)
+        }
+        (first, "") => (first.parse().ok()?, size.checked_sub(1)?),
+        (first, last) => {
+            let start: u64 = first.parse().ok()?;
+            let end: u64 = last.parse().ok()?;
+            (start, end.min(size.checked_sub(1)?))
+        }
+    };
+    (start <= end && start < size).then_some(Some((start, end)))
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+struct AttachmentUrlQuery {
+    /// How long the URL stays valid; 15 minutes by default.
+    ttl_secs: Option<u64>,
+}
+
+/// A presigned URL for downloading an attachment without going through
+/// this server (with S3) or without credentials (with the local store,
+/// whose URLs are paths on this server).
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{id}/attachments/{attachment_id}/url",
+    tag = "attachments",
+    params(AttachmentUrlQuery),
+    responses(
+        (status = 200, description = "`{\"url\", \"expires_at\"}`", body = Object),
+        (status = 400, description = "`ttl_secs` out of range", body = ErrorBody),
+        (status = 404, description = "Unknown task or attachment", body = ErrorBody)
+    )
+)]
+async fn attachment_url(
+    State(state): State<AppState>,
+    Path((id, attachment_id)): Path<(String, String)>,
+    Query(query): Query<AttachmentUrlQuery>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let ttl = std::time::Duration::from_secs(query.ttl_secs.unwrap_or(15 * 60));
+    if ttl.is_zero() || ttl > flowstate_store::MAX_PRESIGN_TTL {
+        return Err(to_error(flowstate_service::ServiceError::InvalidInput(
+            format!(
+                "ttl_secs must be between 1 and {}",
+                flowstate_store::MAX_PRESIGN_TTL.as_secs()
+            ),
+        )));
+    }
+    let attachment = task_attachment(&state, &id, &attachment_id).await?;
+    let url = state
+        .store
+        .presign_get(&attachment.store_key, ttl)
+        .await
+        .map_err(|e| {
+            to_error(flowstate_service::ServiceError::Internal(format!(
+                "presign attachment: {e}"
+            )))
+        })?;
+    // Blob keys carry no extension, so this server's download route takes
+    // the content type from the filename instead.
+    let url = if url.starts_with(flowstate_store::PRESIGNED_PATH) {
+        format!(
+            "{url}?filename={}",
+            utf8_percent_encode(&attachment.filename, NON_ALPHANUMERIC)
+        )
+    } else {
+        url
+    };
+    let expires_at = Utc::now() + chrono::Duration::seconds(ttl.as_secs() as i64);
+    Ok(Json(json!({ "url": url, "expires_at": expires_at })))
+}
+
+/// Look up an attachment, treating one on a different task as missing.
+async fn task_attachment(
+    state: &AppState,
+    task_id: &str,
+    attachment_id: &str,
+) -> Result<flowstate_core::attachment::Attachment, (StatusCode, Json<Value>)> {
+    let attachment = state
+        .db
+        .get_attachment(attachment_id)
+        .await
+        .map_err(|e| to_error(e.into()))?;
+    if attachment.task_id != task_id {
+        return Err(to_error(flowstate_service::ServiceError::NotFound(
+            format!("attachment {attachment_id}"),
+        )));
+    }
+    Ok(attachment)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/tasks/{id}/history",
+    tag = "tasks",
+    responses(
+        (status = 200, body = [TaskRevision]),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn task_history(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let _task = state.service.get_task(&id).await.map_err(to_error)?;
+    state
+        .db
+        .list_task_revisions(&id)
+        .await
+        .map(|r| Json(json!(r)))
+        .map_err(|e| to_error(e.into()))
+}
+
+fn sha256_hex(content: impl AsRef<[u8]>) -> String {
     let mut h = Sha256::new();
-    h.update(content.as_bytes());
+    h.update(content.as_ref());
     format!("{:x}", h.finalize())
 }
 
@@ -1161,51 +2235,75 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn read_empty_content() {
+    async fn feedback_with_distill_queues_run() {
         let app = test_router().await;
         let project_id = create_project(&app).await;
         let task_id = create_task(&app, &project_id).await;
 
-        // Read spec that doesn't exist yet → empty body
+        let put_feedback = |phase: &str| {
+            let body = serde_json::to_string(&json!({
+                "phase": phase,
+                "feedback": "cite sources",
+                "distill": true,
+            }))
+            .unwrap();
+            Request::builder()
+                .method(Method::PUT)
+                .uri(format!("/api/tasks/{task_id}/feedback"))
+                .header("content-type", "application/json")
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        // No plan artifact yet → refused, task untouched
+        let resp = app.clone().oneshot(put_feedback("plan")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
         let resp = app
             .clone()
             .oneshot(
                 Request::builder()
-                    .uri(format!("/api/tasks/{task_id}/spec"))
-                    .body(Body::empty())
+                    .method(Method::PUT)
+                    .uri(format!("/api/tasks/{task_id}/research"))
+                    .body(Body::from("research findings"))
                     .unwrap(),
             )
             .await
             .unwrap();
-        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+
+        let resp = app.clone().oneshot(put_feedback("research")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::CREATED);
         let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        assert_eq!(std::str::from_utf8(&bytes).unwrap(), "");
+        let run: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(run["action"], "research_distill");
+        assert_eq!(run["feedback"], "cite sources");
 
-        // Same for plan
         let resp = app
             .clone()
             .oneshot(
                 Request::builder()
-                    .uri(format!("/api/tasks/{task_id}/plan"))
+                    .uri(format!("/api/tasks/{task_id}"))
                     .body(Body::empty())
                     .unwrap(),
             )
             .await
             .unwrap();
-        assert_eq!(resp.status(), StatusCode::OK);
         let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        assert_eq!(std::str::from_utf8(&bytes).unwrap(), "");
+        let task: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(task["research_status"], "rejected");
+        assert_eq!(task["research_feedback"], "cite sources");
+        assert_eq!(task["plan_feedback"], "");
 
-        // Same for research
+        // The rejection is kept in the task's feedback history
         let resp = app
-            .clone()
             .oneshot(
                 Request::builder()
-                    .uri(format!("/api/tasks/{task_id}/research"))
+                    .uri(format!("/api/tasks/{task_id}/feedback"))
                     .body(Body::empty())
                     .unwrap(),
             )
@@ -1215,13 +2313,74 @@ mod tests {
         let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
             .await
             .unwrap();
-        assert_eq!(std::str::from_utf8(&bytes).unwrap(), "");
+        let history: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(history.as_array().unwrap().len(), 1);
+        assert_eq!(history[0]["phase"], "research");
+        assert_eq!(history[0]["feedback"], "cite sources");
+    }
 
-        // Same for verification
-        let resp = app
-            .oneshot(
-                Request::builder()
-                    .uri(format!("/api/tasks/{task_id}/verification"))
+    #[tokio::test]
+    async fn read_empty_content() {
+        let app = test_router().await;
+        let project_id = create_project(&app).await;
+        let task_id = create_task(&app, &project_id).await;
+
+        // Read spec that doesn't exist yet → empty body
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/tasks/{task_id}/spec"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(std::str::from_utf8(&bytes).unwrap(), "");
+
+        // Same for plan
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/tasks/{task_id}/plan"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(std::str::from_utf8(&bytes).unwrap(), "");
+
+        // Same for research
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/tasks/{task_id}/research"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(std::str::from_utf8(&bytes).unwrap(), "");
+
+        // Same for verification
+        let resp = app
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/tasks/{task_id}/verification"))
                     .body(Body::empty())
                     .unwrap(),
             )
@@ -1256,4 +2415,610 @@ mod tests {
         let attachments: Value = serde_json::from_slice(&bytes).unwrap();
         assert_eq!(attachments.as_array().unwrap().len(), 0);
     }
+
+    #[tokio::test]
+    async fn upload_attachment_records_content_type_and_checksum() {
+        let app = test_router().await;
+        let project_id = create_project(&app).await;
+        let task_id = create_task(&app, &project_id).await;
+
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(format!(
+                        "/api/tasks/{task_id}/attachments?filename=notes.md"
+                    ))
+                    .body(Body::from("hello"))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let attachment: Value = serde_json::from_slice(&bytes).unwrap();
+        let sha = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+        assert_eq!(attachment["content_type"], "text/markdown");
+        assert_eq!(attachment["sha256"], sha);
+        assert_eq!(attachment["size_bytes"], 5);
+
+        let attachment_id = attachment["id"].as_str().unwrap();
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/tasks/{task_id}/attachments/{attachment_id}"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()["content-type"], "text/markdown");
+        assert_eq!(resp.headers()["etag"], format!("\"{sha}\""));
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&bytes[..], b"hello");
+
+        let resp = app
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(format!("/api/tasks/{task_id}/attachments?filename=a/b.txt"))
+                    .body(Body::from("x"))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn identical_attachments_share_a_blob_until_the_last_is_deleted() {
+        let app = test_router().await;
+        let project_id = create_project(&app).await;
+        let upload = |task_id: String| {
+            let app = app.clone();
+            async move {
+                let resp = app
+                    .oneshot(
+                        Request::builder()
+                            .method(Method::POST)
+                            .uri(format!("/api/tasks/{task_id}/attachments?filename=a.png"))
+                            .body(Body::from("same bytes"))
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                assert_eq!(resp.status(), StatusCode::CREATED);
+                let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                serde_json::from_slice::<Value>(&bytes).unwrap()
+            }
+        };
+        let send = |method: Method, uri: String| {
+            let app = app.clone();
+            async move {
+                app.oneshot(
+                    Request::builder()
+                        .method(method)
+                        .uri(uri)
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap()
+            }
+        };
+
+        let first_task = create_task(&app, &project_id).await;
+        let second_task = create_task(&app, &project_id).await;
+        let first = upload(first_task.clone()).await;
+        let second = upload(second_task.clone()).await;
+        let blob = format!("attachments/sha256/{}", first["sha256"].as_str().unwrap());
+        assert_eq!(first["store_key"], blob);
+        assert_eq!(second["store_key"], blob);
+
+        // A presigned URL reads the blob directly, so it shows when it goes.
+        let resp = send(
+            Method::GET,
+            format!(
+                "/api/tasks/{first_task}/attachments/{}/url",
+                first["id"].as_str().unwrap()
+            ),
+        )
+        .await;
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let presigned: Value = serde_json::from_slice(&bytes).unwrap();
+        let url = presigned["url"].as_str().unwrap().to_string();
+        assert!(url.ends_with("?filename=a%2Epng"));
+        let resp = send(Method::GET, url.clone()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()["content-type"], "image/png");
+
+        let first_uri = format!(
+            "/api/tasks/{first_task}/attachments/{}",
+            first["id"].as_str().unwrap()
+        );
+        let second_uri = format!(
+            "/api/tasks/{second_task}/attachments/{}",
+            second["id"].as_str().unwrap()
+        );
+        // Deleting through the wrong task is a 404
+        let resp = send(
+            Method::DELETE,
+            format!(
+                "/api/tasks/{second_task}/attachments/{}",
+                first["id"].as_str().unwrap()
+            ),
+        )
+        .await;
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let resp = send(Method::DELETE, first_uri.clone()).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            send(Method::GET, first_uri).await.status(),
+            StatusCode::NOT_FOUND
+        );
+        let resp = send(Method::GET, second_uri.clone()).await;
+        assert_eq!(resp.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&bytes[..], b"same bytes");
+        assert_eq!(
+            send(Method::GET, url.clone()).await.status(),
+            StatusCode::OK
+        );
+
+        let resp = send(Method::DELETE, second_uri).await;
+        assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+        assert_eq!(send(Method::GET, url).await.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn multipart_upload_and_ranged_download() {
+        let app = test_router().await;
+        let project_id = create_project(&app).await;
+        let task_id = create_task(&app, &project_id).await;
+
+        let body = "--XyZ\r\n\
+             Content-Disposition: form-data; name=\"note\"\r\n\r\n\
+             ignored\r\n\
+             --XyZ\r\n\
+             Content-Disposition: form-data; name=\"file\"; filename=\"digits.txt\"\r\n\
+             Content-Type: text/plain\r\n\r\n\
+             0123456789\r\n\
+             --XyZ--\r\n";
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(format!("/api/tasks/{task_id}/attachments"))
+                    .header("content-type", "multipart/form-data; boundary=XyZ")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let attachment: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(attachment["filename"], "digits.txt");
+        assert_eq!(attachment["content_type"], "text/plain");
+        assert_eq!(attachment["size_bytes"], 10);
+        let attachment_id = attachment["id"].as_str().unwrap();
+
+        let download = |range: &str| {
+            Request::builder()
+                .uri(format!("/api/attachments/{attachment_id}/download"))
+                .header("range", range)
+                .body(Body::empty())
+                .unwrap()
+        };
+        for (range, content_range, expected) in [
+            ("bytes=2-4", "bytes 2-4/10", "234"),
+            ("bytes=7-", "bytes 7-9/10", "789"),
+            ("bytes=-2", "bytes 8-9/10", "89"),
+            ("bytes=8-100", "bytes 8-9/10", "89"),
+        ] {
+            let resp = app.clone().oneshot(download(range)).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::PARTIAL_CONTENT, "{range}");
+            assert_eq!(resp.headers()["content-range"], content_range);
+            let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            assert_eq!(&bytes[..], expected.as_bytes());
+        }
+
+        let resp = app.clone().oneshot(download("bytes=10-")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(resp.headers()["content-range"], "bytes */10");
+
+        // Several ranges are answered with the whole body
+        let resp = app.oneshot(download("bytes=0-1,4-5")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()["accept-ranges"], "bytes");
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&bytes[..], b"0123456789");
+    }
+
+    #[tokio::test]
+    async fn attachment_url_serves_the_bytes_without_the_api() {
+        let app = test_router().await;
+        let project_id = create_project(&app).await;
+        let task_id = create_task(&app, &project_id).await;
+
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(format!("/api/tasks/{task_id}/attachments?filename=log.txt"))
+                    .body(Body::from("line one"))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let attachment: Value = serde_json::from_slice(&bytes).unwrap();
+        let attachment_id = attachment["id"].as_str().unwrap();
+
+        let url_of = |query: &str| {
+            Request::builder()
+                .uri(format!(
+                    "/api/tasks/{task_id}/attachments/{attachment_id}/url{query}"
+                ))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let resp = app.clone().oneshot(url_of("?ttl_secs=60")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let presigned: Value = serde_json::from_slice(&bytes).unwrap();
+        let url = presigned["url"].as_str().unwrap();
+        assert!(url.starts_with(flowstate_store::PRESIGNED_PATH));
+        assert!(presigned["expires_at"].is_string());
+
+        let resp = app
+            .clone()
+            .oneshot(Request::builder().uri(url).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        assert_eq!(resp.headers()["content-type"], "text/plain");
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&bytes[..], b"line one");
+
+        // The token only allows reading
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::PUT)
+                    .uri(url)
+                    .body(Body::from("overwritten"))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+
+        let resp = app.oneshot(url_of("?ttl_secs=0")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn task_history_records_updates_with_caller() {
+        let app = test_router().await;
+        let project_id = create_project(&app).await;
+        let task_id = create_task(&app, &project_id).await;
+
+        let body =
+            serde_json::to_string(&json!({ "status": "build", "actor": "spoofed" })).unwrap();
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::PUT)
+                    .uri(format!("/api/tasks/{task_id}"))
+                    .header("content-type", "application/json")
+                    .header("x-runner-id", "runner-1")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/tasks/{task_id}/history"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let history: Value = serde_json::from_slice(&bytes).unwrap();
+        let revisions = history.as_array().unwrap();
+        assert_eq!(revisions.len(), 1);
+        assert_eq!(revisions[0]["actor"], "runner:runner-1");
+        assert_eq!(revisions[0]["changes"][0]["field"], "status");
+        assert_eq!(revisions[0]["changes"][0]["old"], "todo");
+        assert_eq!(revisions[0]["changes"][0]["new"], "build");
+
+        let resp = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/tasks/nonexistent/history")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn bulk_create_and_update_endpoints() {
+        let app = test_router().await;
+        let project_id = create_project(&app).await;
+
+        let body = serde_json::to_string(&json!([
+            { "project_id": project_id, "title": "One", "status": "todo", "priority": "low" },
+            { "project_id": project_id, "title": "Two", "status": "todo", "priority": "low" },
+        ]))
+        .unwrap();
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/api/tasks/bulk")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let created: Value = serde_json::from_slice(&bytes).unwrap();
+        let ids: Vec<&str> = created
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids.len(), 2);
+
+        let body = serde_json::to_string(&json!({
+            "ids": ids,
+            "update": { "priority": "high" },
+        }))
+        .unwrap();
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::PATCH)
+                    .uri("/api/tasks/bulk")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let updated: Value = serde_json::from_slice(&bytes).unwrap();
+        assert!(updated
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|t| t["priority"] == "high"));
+
+        // Unknown id → 404, nothing applied
+        let body = serde_json::to_string(&json!({
+            "ids": [ids[0], "missing"],
+            "update": { "priority": "urgent" },
+        }))
+        .unwrap();
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::PATCH)
+                    .uri("/api/tasks/bulk")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let resp = app
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/tasks/{}", ids[0]))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let task: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(task["priority"], "high");
+    }
+
+    #[tokio::test]
+    async fn bulk_update_checks_epic_and_assignee() {
+        let app = test_router().await;
+        let project_id = create_project(&app).await;
+        let ids = [
+            create_task(&app, &project_id).await,
+            create_task(&app, &project_id).await,
+        ];
+        let send = |method: Method, uri: &str, body: Value| {
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                .unwrap()
+        };
+
+        let resp = app
+            .clone()
+            .oneshot(send(
+                Method::POST,
+                "/api/projects",
+                json!({ "name": "Other", "slug": "other" }),
+            ))
+            .await
+            .unwrap();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let other: Value = serde_json::from_slice(&bytes).unwrap();
+        let resp = app
+            .clone()
+            .oneshot(send(
+                Method::POST,
+                "/api/epics",
+                json!({ "project_id": other["id"], "name": "Foreign" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let epic: Value = serde_json::from_slice(&bytes).unwrap();
+
+        // Another project's epic → 400, nothing applied
+        let resp = app
+            .clone()
+            .oneshot(send(
+                Method::PATCH,
+                "/api/tasks/bulk",
+                json!({ "ids": ids, "update": { "epic_id": epic["id"] } }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        // Unknown assignee → 404
+        let resp = app
+            .clone()
+            .oneshot(send(
+                Method::PATCH,
+                "/api/tasks/bulk",
+                json!({ "ids": ids, "update": { "assignee_id": "nobody" } }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+
+        let resp = app
+            .oneshot(send(
+                Method::GET,
+                &format!("/api/tasks/{}", ids[0]),
+                Value::Null,
+            ))
+            .await
+            .unwrap();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let task: Value = serde_json::from_slice(&bytes).unwrap();
+        assert!(task["epic_id"].is_null());
+        assert!(task["assignee_id"].is_null());
+    }
+
+    #[tokio::test]
+    async fn reorder_task_endpoint() {
+        let app = test_router().await;
+        let project_id = create_project(&app).await;
+        let first = create_task(&app, &project_id).await;
+        let second = create_task(&app, &project_id).await;
+
+        let reorder = |id: &str, body: Value| {
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("/api/tasks/{id}/reorder"))
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        let resp = app
+            .clone()
+            .oneshot(reorder(&first, json!({ "after_id": second })))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let moved: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(moved["sort_order"], 2.0);
+
+        // Omitting after_id moves the task back to the top.
+        let resp = app
+            .clone()
+            .oneshot(reorder(&first, json!({})))
+            .await
+            .unwrap();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let moved: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(moved["sort_order"], 1.0);
+
+        let resp = app
+            .clone()
+            .oneshot(reorder(&first, json!({ "after_id": first })))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+
+        let resp = app
+            .oneshot(reorder(&first, json!({ "after_id": "missing" })))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
 }
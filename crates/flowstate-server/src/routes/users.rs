@@ -0,0 +1,273 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{delete, get, post, put},
+    Json, Router,
+};
+use flowstate_core::user::{CreateUser, UpdateUser, User};
+use flowstate_service::TaskService;
+use serde_json::{json, Value};
+
+use super::openapi::ErrorBody;
+use super::AppState;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/api/users", post(create_user))
+        .route("/api/users", get(list_users))
+        .route("/api/users/{id}", get(get_user))
+        .route("/api/users/{id}", put(update_user))
+        .route("/api/users/{id}", delete(delete_user))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/users",
+    tag = "users",
+    request_body = CreateUser,
+    responses(
+        (status = 201, body = User),
+        (status = 400, body = ErrorBody)
+    )
+)]
+async fn create_user(
+    State(state): State<AppState>,
+    Json(input): Json<CreateUser>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    state
+        .service
+        .create_user(&input)
+        .await
+        .map(|u| (StatusCode::CREATED, Json(json!(u))))
+        .map_err(to_error)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/users/{id}",
+    tag = "users",
+    responses(
+        (status = 200, body = User),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn get_user(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    state
+        .service
+        .get_user(&id)
+        .await
+        .map(|u| Json(json!(u)))
+        .map_err(to_error)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/users",
+    tag = "users",
+    responses((status = 200, body = [User]))
+)]
+async fn list_users(
+    State(state): State<AppState>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    state
+        .service
+        .list_users()
+        .await
+        .map(|u| Json(json!(u)))
+        .map_err(to_error)
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/users/{id}",
+    tag = "users",
+    request_body = UpdateUser,
+    responses(
+        (status = 200, body = User),
+        (status = 400, body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn update_user(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(update): Json<UpdateUser>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    state
+        .service
+        .update_user(&id, &update)
+        .await
+        .map(|u| Json(json!(u)))
+        .map_err(to_error)
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/users/{id}",
+    tag = "users",
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn delete_user(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<Value>)> {
+    state
+        .service
+        .delete_user(&id)
+        .await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(to_error)
+}
+
+fn to_error(e: flowstate_service::ServiceError) -> (StatusCode, Json<Value>) {
+    let (status, msg) = match &e {
+        flowstate_service::ServiceError::NotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
+        flowstate_service::ServiceError::InvalidInput(_) => {
+            (StatusCode::BAD_REQUEST, e.to_string())
+        }
+        flowstate_service::ServiceError::Internal(_) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+        }
+    };
+    (status, Json(json!({ "error": msg })))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::test_router;
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    async fn send(
+        app: &axum::Router,
+        method: Method,
+        uri: &str,
+        body: Value,
+    ) -> (StatusCode, Value) {
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from(serde_json::to_vec(&body).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = resp.status();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    #[tokio::test]
+    async fn assign_task_to_user() {
+        let app = test_router().await;
+        let (status, user) = send(
+            &app,
+            Method::POST,
+            "/api/users",
+            json!({ "name": "Alice", "email": "alice@example.com" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let user_id = user["id"].as_str().unwrap();
+
+        // Emails are unique among users that have one
+        let (status, _) = send(
+            &app,
+            Method::POST,
+            "/api/users",
+            json!({ "name": "Other Alice", "email": "alice@example.com" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = send(
+            &app,
+            Method::POST,
+            "/api/users",
+            json!({ "name": "", "email": "" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (_, project) = send(
+            &app,
+            Method::POST,
+            "/api/projects",
+            json!({ "name": "Assignees", "slug": "assignees" }),
+        )
+        .await;
+        let (_, task) = send(
+            &app,
+            Method::POST,
+            "/api/tasks",
+            json!({ "project_id": project["id"], "title": "Signup form", "status": "todo", "priority": "medium" }),
+        )
+        .await;
+        let task_id = task["id"].as_str().unwrap();
+
+        let (status, _) = send(
+            &app,
+            Method::PUT,
+            &format!("/api/tasks/{task_id}"),
+            json!({ "assignee_id": "nobody" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, task) = send(
+            &app,
+            Method::PUT,
+            &format!("/api/tasks/{task_id}"),
+            json!({ "assignee_id": user_id }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(task["assignee_id"], user_id);
+
+        let (status, tasks) = send(
+            &app,
+            Method::GET,
+            &format!("/api/tasks?assignee_id={user_id}"),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(tasks.as_array().unwrap().len(), 1);
+
+        let (status, users) = send(&app, Method::GET, "/api/users", Value::Null).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(users.as_array().unwrap().len(), 1);
+
+        let (status, _) = send(
+            &app,
+            Method::DELETE,
+            &format!("/api/users/{user_id}"),
+            Value::Null,
+        )
+        .await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (_, task) = send(
+            &app,
+            Method::GET,
+            &format!("/api/tasks/{task_id}"),
+            Value::Null,
+        )
+        .await;
+        assert!(task["assignee_id"].is_null());
+    }
+}
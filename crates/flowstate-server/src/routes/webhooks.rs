@@ -0,0 +1,449 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use flowstate_core::webhook::{
+    CreateWebhook, UpdateWebhook, Webhook, WebhookDelivery, WebhookEvent,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::openapi::ErrorBody;
+use super::AppState;
+use crate::crypto;
+
+/// Deliveries listed when `?limit=` is not given.
+const DEFAULT_DELIVERY_LIMIT: i64 = 50;
+
+pub fn routes() -> Router<AppState> {
+    Router::new()
+        .route("/admin/webhooks", get(list_webhooks).post(create_webhook))
+        .route(
+            "/admin/webhooks/{id}",
+            get(get_webhook)
+                .patch(update_webhook)
+                .delete(delete_webhook),
+        )
+        .route("/admin/webhooks/{id}/deliveries", get(list_deliveries))
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+struct CreateWebhookInput {
+    url: String,
+    /// Signing secret; one is generated when omitted.
+    #[serde(default)]
+    secret: Option<String>,
+    #[serde(default)]
+    events: Vec<WebhookEvent>,
+    #[serde(default)]
+    project_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+struct UpdateWebhookInput {
+    url: Option<String>,
+    secret: Option<String>,
+    events: Option<Vec<WebhookEvent>>,
+    /// `""` makes the hook fire for every project again.
+    project_id: Option<String>,
+    enabled: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+struct DeliveriesQuery {
+    limit: Option<i64>,
+}
+
+/// 32 random bytes, hex encoded.
+fn generate_secret() -> String {
+    use rand::Rng;
+    let bytes: [u8; 32] = rand::thread_rng().gen();
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn seal(state: &AppState, secret: &str) -> Result<String, (StatusCode, Json<Value>)> {
+    crypto::encrypt(&state.encryption_key, secret).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({ "error": e })),
+        )
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/webhooks",
+    tag = "webhooks",
+    responses((status = 200, body = [Webhook]))
+)]
+async fn list_webhooks(
+    State(state): State<AppState>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let webhooks = state.db.list_webhooks().await.map_err(to_error)?;
+    Ok(Json(json!(webhooks)))
+}
+
+/// Create a webhook. The response carries the plaintext `secret`; it is
+/// not shown again.
+#[utoipa::path(
+    post,
+    path = "/admin/webhooks",
+    tag = "webhooks",
+    request_body = CreateWebhookInput,
+    responses(
+        (status = 201, description = "The webhook, with its plaintext `secret`", body = Webhook),
+        (status = 400, body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn create_webhook(
+    State(state): State<AppState>,
+    Json(input): Json<CreateWebhookInput>,
+) -> Result<(StatusCode, Json<Value>), (StatusCode, Json<Value>)> {
+    if let Some(ref project_id) = input.project_id {
+        state.db.get_project(project_id).await.map_err(to_error)?;
+    }
+    let secret = input
+        .secret
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(generate_secret);
+    let create = CreateWebhook {
+        url: input.url,
+        secret: seal(&state, &secret)?,
+        events: input.events,
+        project_id: input.project_id,
+    };
+    create.validate().map_err(bad_request)?;
+
+    let webhook = state.db.create_webhook(&create).await.map_err(to_error)?;
+    let mut body = json!(webhook);
+    body["secret"] = json!(secret);
+    Ok((StatusCode::CREATED, Json(body)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/admin/webhooks/{id}",
+    tag = "webhooks",
+    responses(
+        (status = 200, body = Webhook),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn get_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let webhook = state.db.get_webhook(&id).await.map_err(to_error)?;
+    Ok(Json(json!(webhook)))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/admin/webhooks/{id}",
+    tag = "webhooks",
+    request_body = UpdateWebhookInput,
+    responses(
+        (status = 200, body = Webhook),
+        (status = 400, body = ErrorBody),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn update_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(input): Json<UpdateWebhookInput>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let project_id = input.project_id.map(|p| (!p.is_empty()).then_some(p));
+    if let Some(Some(ref project_id)) = project_id {
+        state.db.get_project(project_id).await.map_err(to_error)?;
+    }
+    let secret = match input.secret {
+        Some(ref secret) if !secret.is_empty() => Some(seal(&state, secret)?),
+        _ => None,
+    };
+    let update = UpdateWebhook {
+        url: input.url,
+        secret,
+        events: input.events,
+        project_id,
+        enabled: input.enabled,
+    };
+    update.validate().map_err(bad_request)?;
+
+    let webhook = state
+        .db
+        .update_webhook(&id, &update)
+        .await
+        .map_err(to_error)?;
+    Ok(Json(json!(webhook)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/admin/webhooks/{id}",
+    tag = "webhooks",
+    responses(
+        (status = 204, description = "Deleted"),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn delete_webhook(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, (StatusCode, Json<Value>)> {
+    state.db.delete_webhook(&id).await.map_err(to_error)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Recent deliveries for a webhook, newest first.
+#[utoipa::path(
+    get,
+    path = "/admin/webhooks/{id}/deliveries",
+    tag = "webhooks",
+    params(DeliveriesQuery),
+    responses(
+        (status = 200, body = [WebhookDelivery]),
+        (status = 404, body = ErrorBody)
+    )
+)]
+async fn list_deliveries(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<DeliveriesQuery>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    state.db.get_webhook(&id).await.map_err(to_error)?;
+    let limit = query.limit.unwrap_or(DEFAULT_DELIVERY_LIMIT).clamp(1, 500);
+    let deliveries = state
+        .db
+        .list_webhook_deliveries(&id, limit)
+        .await
+        .map_err(to_error)?;
+    Ok(Json(json!(deliveries)))
+}
+
+fn bad_request(e: flowstate_core::FlowstateError) -> (StatusCode, Json<Value>) {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(json!({ "error": e.to_string() })),
+    )
+}
+
+fn to_error(e: flowstate_db::DbError) -> (StatusCode, Json<Value>) {
+    let status = match &e {
+        flowstate_db::DbError::NotFound(_) => StatusCode::NOT_FOUND,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, Json(json!({ "error": e.to_string() })))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_helpers::test_router;
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    async fn send(
+        app: &axum::Router,
+        method: Method,
+        uri: &str,
+        body: Value,
+    ) -> (StatusCode, Value) {
+        let resp = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = resp.status();
+        let bytes = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    #[tokio::test]
+    async fn manage_webhooks() {
+        let app = test_router().await;
+        let (status, _) = send(
+            &app,
+            Method::POST,
+            "/admin/webhooks",
+            json!({"url": "ftp://example.com"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, created) = send(
+            &app,
+            Method::POST,
+            "/admin/webhooks",
+            json!({"url": "https://example.com/hook", "events": ["run_finished"]}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(created["secret"].as_str().unwrap().len(), 64);
+        assert_eq!(created["enabled"], true);
+        let id = created["id"].as_str().unwrap();
+
+        // The secret is only ever returned on creation
+        let (_, listed) = send(&app, Method::GET, "/admin/webhooks", json!(null)).await;
+        assert_eq!(listed.as_array().unwrap().len(), 1);
+        assert!(listed[0].get("secret").is_none());
+
+        let (status, updated) = send(
+            &app,
+            Method::PATCH,
+            &format!("/admin/webhooks/{id}"),
+            json!({"enabled": false, "events": []}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(updated["enabled"], false);
+        assert_eq!(updated["events"], json!([]));
+
+        let (status, deliveries) = send(
+            &app,
+            Method::GET,
+            &format!("/admin/webhooks/{id}/deliveries"),
+            json!(null),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(deliveries, json!([]));
+
+        let uri = format!("/admin/webhooks/{id}");
+        let (status, _) = send(&app, Method::DELETE, &uri, json!(null)).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = send(&app, Method::GET, &uri, json!(null)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn task_and_run_changes_queue_deliveries() {
+        let app = test_router().await;
+        let (_, project) = send(
+            &app,
+            Method::POST,
+            "/api/projects",
+            json!({"name": "Hooks", "slug": "hooks"}),
+        )
+        .await;
+        let (_, hook) = send(
+            &app,
+            Method::POST,
+            "/admin/webhooks",
+            json!({"url": "https://example.com/hook", "project_id": project["id"]}),
+        )
+        .await;
+        let hook_id = hook["id"].as_str().unwrap();
+        let (_, task) = send(
+            &app,
+            Method::POST,
+            "/api/tasks",
+            json!({
+                "project_id": project["id"],
+                "title": "Ship it",
+                "status": "todo",
+                "priority": "medium",
+            }),
+        )
+        .await;
+        let task_id = task["id"].as_str().unwrap();
+
+        // A title edit is not an event
+        send(
+            &app,
+            Method::PUT,
+            &format!("/api/tasks/{task_id}"),
+            json!({"title": "Ship it now"}),
+        )
+        .await;
+        send(
+            &app,
+            Method::PUT,
+            &format!("/api/tasks/{task_id}"),
+            json!({"status": "build"}),
+        )
+        .await;
+        send(
+            &app,
+            Method::PUT,
+            &format!("/api/tasks/{task_id}"),
+            json!({"spec_status": "approved"}),
+        )
+        .await;
+
+        let (_, deliveries) = send(
+            &app,
+            Method::GET,
+            &format!("/admin/webhooks/{hook_id}/deliveries"),
+            json!(null),
+        )
+        .await;
+        let events: Vec<&str> = deliveries
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|d| d["event"].as_str().unwrap())
+            .collect();
+        assert_eq!(events, ["task_approved", "task_status_changed"]);
+        let payload: Value =
+            serde_json::from_str(deliveries[1]["payload"].as_str().unwrap()).unwrap();
+        assert_eq!(payload["event"], "task_status_changed");
+        assert_eq!(payload["project_id"], project["id"]);
+        assert_eq!(payload["data"]["previous_status"], "todo");
+        assert_eq!(payload["data"]["task"]["status"], "build");
+        assert_eq!(deliveries[0]["status"], "pending");
+
+        // A finished run fires once, however often the runner reports it
+        let (_, run) = send(
+            &app,
+            Method::POST,
+            &format!("/api/tasks/{task_id}/claude-runs"),
+            json!({"action": "research"}),
+        )
+        .await;
+        let status_uri = format!("/api/claude-runs/{}/status", run["id"].as_str().unwrap());
+        for _ in 0..2 {
+            let (status, _) = send(
+                &app,
+                Method::PUT,
+                &status_uri,
+                json!({"status": "failed", "error_message": "boom"}),
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
+        }
+        let (_, deliveries) = send(
+            &app,
+            Method::GET,
+            &format!("/admin/webhooks/{hook_id}/deliveries"),
+            json!(null),
+        )
+        .await;
+        let runs: Vec<Value> = deliveries
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|d| d["event"] == "run_finished")
+            .map(|d| serde_json::from_str(d["payload"].as_str().unwrap()).unwrap())
+            .collect();
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0]["data"]["run"]["status"], "failed");
+        assert_eq!(runs[0]["data"]["task"]["id"], task_id);
+    }
+}
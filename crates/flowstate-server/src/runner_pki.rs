@@ -0,0 +1,279 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use openssl::asn1::Asn1Time;
+use openssl::bn::{BigNum, MsbOption};
+use openssl::ec::{EcGroup, EcKey};
+use openssl::hash::MessageDigest;
+use openssl::nid::Nid;
+use openssl::pkey::{PKey, Private};
+use openssl::x509::extension::{
+    AuthorityKeyIdentifier, BasicConstraints, ExtendedKeyUsage, KeyUsage, SubjectKeyIdentifier,
+};
+use openssl::x509::{X509Builder, X509Name, X509NameBuilder, X509NameRef, X509};
+
+/// Validity of the runner CA created on first enrollment.
+const CA_VALIDITY_DAYS: u32 = 3650;
+
+/// Locations of the CA that signs runner client certificates, read from
+/// `FLOWSTATE_RUNNER_CA_CERT` / `FLOWSTATE_RUNNER_CA_KEY`.
+///
+/// Defaults to `runner-ca.pem` / `runner-ca.key` next to the server
+/// encryption key in `~/.config/flowstate`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunnerCaPaths {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+impl RunnerCaPaths {
+    pub fn from_env() -> Self {
+        let dir = crate::crypto::config_dir();
+        Self {
+            cert_path: std::env::var_os("FLOWSTATE_RUNNER_CA_CERT")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| dir.join("runner-ca.pem")),
+            key_path: std::env::var_os("FLOWSTATE_RUNNER_CA_KEY")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| dir.join("runner-ca.key")),
+        }
+    }
+}
+
+/// Whether runner-facing routes require a client certificate, read from
+/// `FLOWSTATE_RUNNER_MTLS`.
+pub fn mtls_enabled_from_env() -> bool {
+    std::env::var("FLOWSTATE_RUNNER_MTLS")
+        .map(|v| matches!(v.as_str(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+/// A freshly issued runner certificate and its private key, both PEM.
+pub struct IssuedCert {
+    pub cert_pem: Vec<u8>,
+    pub key_pem: Vec<u8>,
+}
+
+/// Certificate authority used to enroll runners for mutual TLS.
+pub struct RunnerCa {
+    cert: X509,
+    key: PKey<Private>,
+}
+
+impl RunnerCa {
+    /// Load the CA from `paths`, creating a new self-signed one if neither
+    /// file exists yet. A half-present CA is an error rather than being
+    /// silently replaced, since that would orphan every enrolled runner.
+    pub fn load_or_create(paths: &RunnerCaPaths) -> Result<Self> {
+        match (paths.cert_path.exists(), paths.key_path.exists()) {
+            (true, true) => Self::load(paths),
+            (false, false) => {
+                let ca = Self::generate()?;
+                write_file(&paths.cert_path, &ca.cert.to_pem()?, 0o644)?;
+                write_file(&paths.key_path, &ca.key.private_key_to_pem_pkcs8()?, 0o600)?;
+                Ok(ca)
+            }
+            _ => bail!(
+                "runner CA is incomplete: expected both {} and {}",
+                paths.cert_path.display(),
+                paths.key_path.display()
+            ),
+        }
+    }
+
+    fn load(paths: &RunnerCaPaths) -> Result<Self> {
+        let cert = fs::read(&paths.cert_path)
+            .with_context(|| format!("failed to read {}", paths.cert_path.display()))?;
+        let key = fs::read(&paths.key_path)
+            .with_context(|| format!("failed to read {}", paths.key_path.display()))?;
+        Ok(Self {
+            cert: X509::from_pem(&cert).context("invalid runner CA certificate")?,
+            key: PKey::private_key_from_pem(&key).context("invalid runner CA key")?,
+        })
+    }
+
+    fn generate() -> Result<Self> {
+        let key = generate_key()?;
+        let name = common_name("flowstate runner CA")?;
+
+        let mut builder = cert_builder(&name, &name, &key, CA_VALIDITY_DAYS)?;
+        builder.append_extension(BasicConstraints::new().critical().ca().pathlen(0).build()?)?;
+        builder.append_extension(
+            KeyUsage::new()
+                .critical()
+                .key_cert_sign()
+                .crl_sign()
+                .build()?,
+        )?;
+        let ski = SubjectKeyIdentifier::new().build(&builder.x509v3_context(None, None))?;
+        builder.append_extension(ski)?;
+        builder.sign(&key, MessageDigest::sha256())?;
+
+        Ok(Self {
+            cert: builder.build(),
+            key,
+        })
+    }
+
+    /// PEM encoding of the CA certificate, for configuring verifiers.
+    pub fn cert_pem(&self) -> Result<Vec<u8>> {
+        Ok(self.cert.to_pem()?)
+    }
+
+    /// Issue a client certificate for runner `name`, valid for `days`.
+    pub fn issue(&self, name: &str, days: u32) -> Result<IssuedCert> {
+        if name.is_empty() {
+            bail!("runner name must not be empty");
+        }
+        let key = generate_key()?;
+
+        let subject = common_name(name)?;
+        let mut builder = cert_builder(&subject, self.cert.subject_name(), &key, days)?;
+        builder.append_extension(BasicConstraints::new().critical().build()?)?;
+        builder.append_extension(
+            KeyUsage::new()
+                .critical()
+                .digital_signature()
+                .key_agreement()
+                .build()?,
+        )?;
+        builder.append_extension(ExtendedKeyUsage::new().client_auth().build()?)?;
+        let aki = AuthorityKeyIdentifier::new()
+            .keyid(true)
+            .build(&builder.x509v3_context(Some(&self.cert), None))?;
+        builder.append_extension(aki)?;
+        builder.sign(&self.key, MessageDigest::sha256())?;
+
+        Ok(IssuedCert {
+            cert_pem: builder.build().to_pem()?,
+            key_pem: key.private_key_to_pem_pkcs8()?,
+        })
+    }
+}
+
+/// Extract the subject common name from a DER-encoded certificate.
+pub fn common_name_from_der(der: &[u8]) -> Option<String> {
+    let cert = X509::from_der(der).ok()?;
+    let entry = cert.subject_name().entries_by_nid(Nid::COMMONNAME).next()?;
+    entry.data().as_utf8().ok().map(|s| s.to_string())
+}
+
+fn generate_key() -> Result<PKey<Private>> {
+    let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)?;
+    Ok(PKey::from_ec_key(EcKey::generate(&group)?)?)
+}
+
+fn common_name(cn: &str) -> Result<X509Name> {
+    let mut name = X509NameBuilder::new()?;
+    name.append_entry_by_nid(Nid::COMMONNAME, cn)?;
+    Ok(name.build())
+}
+
+/// Start a v3 certificate with a random serial, valid from now for `days`.
+fn cert_builder(
+    subject: &X509NameRef,
+    issuer: &X509NameRef,
+    key: &PKey<Private>,
+    days: u32,
+) -> Result<X509Builder> {
+    let mut serial = BigNum::new()?;
+    serial.rand(127, MsbOption::MAYBE_ZERO, false)?;
+    let serial = serial.to_asn1_integer()?;
+    let not_before = Asn1Time::days_from_now(0)?;
+    let not_after = Asn1Time::days_from_now(days)?;
+
+    let mut builder = X509Builder::new()?;
+    builder.set_version(2)?;
+    builder.set_serial_number(&serial)?;
+    builder.set_subject_name(subject)?;
+    builder.set_issuer_name(issuer)?;
+    builder.set_pubkey(key)?;
+    builder.set_not_before(&not_before)?;
+    builder.set_not_after(&not_after)?;
+    Ok(builder)
+}
+
+/// Write `data` to `path`, creating parent directories and restricting the
+/// file to `mode` on Unix.
+pub fn write_file(path: &Path, data: &[u8], mode: u32) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)?;
+        }
+    }
+    fs::write(path, data).with_context(|| format!("failed to write {}", path.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(mode))?;
+    }
+    #[cfg(not(unix))]
+    let _ = mode;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_paths(dir: &Path) -> RunnerCaPaths {
+        RunnerCaPaths {
+            cert_path: dir.join("ca/runner-ca.pem"),
+            key_path: dir.join("ca/runner-ca.key"),
+        }
+    }
+
+    #[test]
+    fn load_or_create_is_stable() {
+        let tmp = tempfile::tempdir().unwrap();
+        let paths = temp_paths(tmp.path());
+        let first = RunnerCa::load_or_create(&paths).unwrap();
+        let second = RunnerCa::load_or_create(&paths).unwrap();
+        assert_eq!(first.cert_pem().unwrap(), second.cert_pem().unwrap());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn ca_key_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+        let tmp = tempfile::tempdir().unwrap();
+        let paths = temp_paths(tmp.path());
+        RunnerCa::load_or_create(&paths).unwrap();
+        let mode = fs::metadata(&paths.key_path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn load_or_create_rejects_partial_ca() {
+        let tmp = tempfile::tempdir().unwrap();
+        let paths = temp_paths(tmp.path());
+        RunnerCa::load_or_create(&paths).unwrap();
+        fs::remove_file(&paths.key_path).unwrap();
+        let err = RunnerCa::load_or_create(&paths).err().unwrap();
+        assert!(err.to_string().contains("incomplete"));
+    }
+
+    #[test]
+    fn issued_cert_is_signed_by_ca() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ca = RunnerCa::load_or_create(&temp_paths(tmp.path())).unwrap();
+        let issued = ca.issue("runner-a", 30).unwrap();
+
+        let cert = X509::from_pem(&issued.cert_pem).unwrap();
+        let ca_key = ca.cert.public_key().unwrap();
+        assert!(cert.verify(&ca_key).unwrap());
+        assert_eq!(
+            common_name_from_der(&cert.to_der().unwrap()).as_deref(),
+            Some("runner-a")
+        );
+        PKey::private_key_from_pem(&issued.key_pem).unwrap();
+    }
+
+    #[test]
+    fn issue_rejects_empty_name() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ca = RunnerCa::load_or_create(&temp_paths(tmp.path())).unwrap();
+        assert!(ca.issue("", 30).is_err());
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::HashSet;
+
+use anyhow::{Context, Result};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use flowstate_core::claude_run::{ClaudeAction, ClaudeRunStatus, CreateClaudeRun};
+use flowstate_core::project::CreateProject;
+use flowstate_core::run_metrics::RecordRunMetrics;
+use flowstate_core::sprint::{CreateSprint, SprintStatus, UpdateSprint};
+use flowstate_core::task::{CreateTask, Priority, Status, TaskType, UpdateTask};
+use flowstate_db::Database;
+
+/// How much fixture data `seed` generates.
+#[derive(Debug, Clone)]
+pub struct SeedOptions {
+    pub projects: usize,
+    /// Tasks per project.
+    pub tasks: usize,
+    /// Finished runs per project, spread over its tasks.
+    pub runs: usize,
+    /// Random seed; the same seed produces the same names, statuses and
+    /// run outcomes (ids and timestamps still differ).
+    pub seed: Option<u64>,
+}
+
+/// What `seed` created.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SeedSummary {
+    pub projects: usize,
+    pub sprints: usize,
+    pub tasks: usize,
+    pub runs: usize,
+}
+
+/// Sprints per project; tasks are split between them and the backlog.
+const SPRINTS_PER_PROJECT: usize = 3;
+
+const PRODUCTS: &[&str] = &[
+    "Atlas", "Beacon", "Cobalt", "Drift", "Ember", "Fjord", "Granite", "Harbor", "Ion", "Juniper",
+];
+
+const VERBS: &[&str] = &[
+    "Add", "Fix", "Refactor", "Document", "Speed up", "Test", "Remove", "Migrate", "Harden",
+];
+
+const SUBJECTS: &[&str] = &[
+    "login flow",
+    "search results pagination",
+    "CSV export",
+    "notification emails",
+    "billing webhook",
+    "settings page",
+    "API rate limiter",
+    "audit log",
+    "image uploads",
+    "dark mode",
+    "onboarding checklist",
+    "session expiry",
+    "mobile layout",
+    "retry logic",
+    "error reporting",
+];
+
+const DETAILS: &[&str] = &[
+    "Customers reported this during the last release.",
+    "Needed before the next milestone.",
+    "Small change; keep the public API stable.",
+    "See the linked discussion for the agreed approach.",
+    "Measure before and after.",
+];
+
+const ACTIONS: &[ClaudeAction] = &[
+    ClaudeAction::Research,
+    ClaudeAction::Design,
+    ClaudeAction::Plan,
+    ClaudeAction::Build,
+    ClaudeAction::Verify,
+];
+
+/// Fill `db` with generated projects, sprints, tasks and finished runs for
+/// demos and load testing. Project slugs never collide with existing ones,
+/// so seeding can be repeated.
+pub async fn seed(db: &dyn Database, opts: &SeedOptions) -> Result<SeedSummary> {
+    let mut rng = match opts.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let mut taken: HashSet<String> = db
+        .list_projects()
+        .await?
+        .into_iter()
+        .map(|p| p.slug)
+        .collect();
+    let mut summary = SeedSummary::default();
+
+    for n in 0..opts.projects {
+        let product = PRODUCTS[n % PRODUCTS.len()];
+        let base = product.to_lowercase();
+        let slug = (1..)
+            .map(|i| format!("{base}-{i}"))
+            .find(|s| !taken.contains(s))
+            .expect("unbounded range");
+        taken.insert(slug.clone());
+        let project = db
+            .create_project(&CreateProject {
+                name: format!("{product} {}", &slug[base.len() + 1..]),
+                slug,
+                description: format!("Generated demo project for {product}."),
+                repo_url: String::new(),
+            })
+            .await
+            .context("failed to create project")?;
+        summary.projects += 1;
+
+        let inputs: Vec<CreateTask> = (0..opts.tasks)
+            .map(|_| random_task(&mut rng, &project.id))
+            .collect();
+        let tasks = db
+            .bulk_create_tasks(&inputs)
+            .await
+            .context("failed to create tasks")?;
+        summary.tasks += tasks.len();
+
+        // The first sprint is done, the second running and the rest
+        // planned; the last quarter of the tasks stay in the backlog.
+        let planned = tasks.len() * 3 / 4;
+        let per_sprint = planned.div_ceil(SPRINTS_PER_PROJECT).max(1);
+        for (i, chunk) in tasks[..planned].chunks(per_sprint).enumerate() {
+            let sprint = db
+                .create_sprint(&CreateSprint {
+                    project_id: project.id.clone(),
+                    name: format!("Sprint {}", i + 1),
+                    goal: String::new(),
+                    starts_at: None,
+                    ends_at: None,
+                })
+                .await?;
+            let status = match i {
+                0 => SprintStatus::Completed,
+                1 => SprintStatus::Active,
+                _ => SprintStatus::Planned,
+            };
+            if status != SprintStatus::Planned {
+                db.update_sprint(
+                    &sprint.id,
+                    &UpdateSprint {
+                        status: Some(status),
+                        ..Default::default()
+                    },
+                )
+                .await?;
+            }
+            let ids: Vec<String> = chunk.iter().map(|t| t.id.clone()).collect();
+            db.bulk_update_tasks(
+                &ids,
+                &UpdateTask {
+                    sprint_id: Some(Some(sprint.id.clone())),
+                    ..Default::default()
+                },
+            )
+            .await?;
+            summary.sprints += 1;
+        }
+
+        if tasks.is_empty() {
+            continue;
+        }
+        for _ in 0..opts.runs {
+            let task = tasks.choose(&mut rng).expect("tasks is not empty");
+            let run = db
+                .create_claude_run(&CreateClaudeRun {
+                    task_id: task.id.clone(),
+                    action: *ACTIONS.choose(&mut rng).expect("ACTIONS is not empty"),
+                    required_capability: None,
+                    priority: 0,
+                    feedback: None,
+                    idempotency_key: None,
+                    run_window: None,
+                    trace_context: None,
+                })
+                .await
+                .context("failed to create run")?;
+            let (status, error, exit_code) = match rng.gen_range(0..20) {
+                0..=14 => (ClaudeRunStatus::Completed, None, Some(0)),
+                15..=17 => (ClaudeRunStatus::Failed, Some("build failed"), Some(1)),
+                18 => (ClaudeRunStatus::TimedOut, Some("timed out"), None),
+                _ => (ClaudeRunStatus::Cancelled, None, None),
+            };
+            db.update_claude_run_status(&run.id, status, error, exit_code)
+                .await?;
+            let input_tokens = rng.gen_range(2_000..60_000);
+            let output_tokens = rng.gen_range(500..12_000);
+            db.record_run_metrics(
+                &run.id,
+                &RecordRunMetrics {
+                    duration_ms: rng.gen_range(20_000..1_800_000),
+                    stdout_bytes: rng.gen_range(1_000..400_000),
+                    input_tokens: Some(input_tokens),
+                    output_tokens: Some(output_tokens),
+                    cost_usd: Some((input_tokens * 3 + output_tokens * 15) as f64 / 1_000_000.0),
+                },
+            )
+            .await?;
+            summary.runs += 1;
+        }
+    }
+    Ok(summary)
+}
+
+fn random_task(rng: &mut StdRng, project_id: &str) -> CreateTask {
+    let verb = VERBS.choose(rng).expect("VERBS is not empty");
+    let subject = SUBJECTS.choose(rng).expect("SUBJECTS is not empty");
+    let detail = DETAILS.choose(rng).expect("DETAILS is not empty");
+    // Weighted toward the early columns, like a real board
+    let status = match rng.gen_range(0..20) {
+        0..=6 => Status::Todo,
+        7..=8 => Status::Research,
+        9..=10 => Status::Design,
+        11 => Status::Plan,
+        12..=13 => Status::Build,
+        14 => Status::Verify,
+        15..=18 => Status::Done,
+        _ => Status::Cancelled,
+    };
+    CreateTask {
+        project_id: project_id.to_string(),
+        title: format!("{verb} {subject}"),
+        description: detail.to_string(),
+        status,
+        priority: *Priority::ALL
+            .choose(rng)
+            .expect("Priority::ALL is not empty"),
+        task_type: match rng.gen_range(0..10) {
+            0..=2 => TaskType::Bug,
+            3 => TaskType::Chore,
+            _ => TaskType::Feature,
+        },
+        parent_id: None,
+        reviewer: String::new(),
+        research_capability: None,
+        design_capability: None,
+        plan_capability: None,
+        build_capability: None,
+        verify_capability: None,
+        due_at: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flowstate_core::task::TaskFilter;
+
+    fn opts(seed: Option<u64>) -> SeedOptions {
+        SeedOptions {
+            projects: 2,
+            tasks: 8,
+            runs: 5,
+            seed,
+        }
+    }
+
+    async fn titles(db: &dyn Database) -> Vec<String> {
+        let mut titles: Vec<String> = db
+            .list_tasks(&TaskFilter::default())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| format!("{} {}", t.title, t.status))
+            .collect();
+        titles.sort();
+        titles
+    }
+
+    #[tokio::test]
+    async fn seed_creates_requested_counts() {
+        let db = flowstate_db::SqliteDatabase::open_in_memory().unwrap();
+        let summary = seed(&db, &opts(None)).await.unwrap();
+        assert_eq!(
+            summary,
+            SeedSummary {
+                projects: 2,
+                sprints: 6,
+                tasks: 16,
+                runs: 10,
+            }
+        );
+        let projects = db.list_projects().await.unwrap();
+        assert_eq!(projects.len(), 2);
+        assert_eq!(db.count_queued_runs().await.unwrap(), 0);
+
+        // Seeding again picks fresh slugs
+        seed(&db, &opts(None)).await.unwrap();
+        let mut slugs: Vec<_> = db
+            .list_projects()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|p| p.slug)
+            .collect();
+        slugs.sort();
+        assert_eq!(slugs, vec!["atlas-1", "atlas-2", "beacon-1", "beacon-2"]);
+    }
+
+    #[tokio::test]
+    async fn same_seed_generates_same_data() {
+        let a = flowstate_db::SqliteDatabase::open_in_memory().unwrap();
+        let b = flowstate_db::SqliteDatabase::open_in_memory().unwrap();
+        seed(&a, &opts(Some(7))).await.unwrap();
+        seed(&b, &opts(Some(7))).await.unwrap();
+        assert_eq!(titles(&a).await, titles(&b).await);
+    }
+}
@@ -0,0 +1,355 @@
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+/// Every setting a server configuration file may hold, by section: its
+/// key, and the environment variable it stands for.
+const SECTIONS: &[(&str, &[(&str, &str)])] = &[
+    (
+        "server",
+        &[
+            ("bind", "FLOWSTATE_BIND"),
+            ("port", "FLOWSTATE_PORT"),
+            ("drain_timeout_secs", "FLOWSTATE_DRAIN_TIMEOUT_SECS"),
+            ("maintenance", "FLOWSTATE_MAINTENANCE"),
+            ("status_page", "FLOWSTATE_STATUS_PAGE"),
+            ("task_link", "FLOWSTATE_TASK_LINK"),
+            ("pr_checks_secs", "FLOWSTATE_PR_CHECKS_SECS"),
+        ],
+    ),
+    (
+        "tls",
+        &[
+            ("cert", "FLOWSTATE_TLS_CERT"),
+            ("key", "FLOWSTATE_TLS_KEY"),
+            ("runner_mtls", "FLOWSTATE_RUNNER_MTLS"),
+            ("runner_ca_cert", "FLOWSTATE_RUNNER_CA_CERT"),
+            ("runner_ca_key", "FLOWSTATE_RUNNER_CA_KEY"),
+        ],
+    ),
+    (
+        "database",
+        &[
+            ("backend", "FLOWSTATE_DB_BACKEND"),
+            ("sqlite_path", "FLOWSTATE_SQLITE_PATH"),
+            ("url", "FLOWSTATE_DATABASE_URL"),
+            ("maintenance_hours", "FLOWSTATE_DB_MAINTENANCE_HOURS"),
+            ("run_retention_days", "FLOWSTATE_RUN_RETENTION_DAYS"),
+        ],
+    ),
+    (
+        "store",
+        &[
+            ("durability", "FLOWSTATE_STORE_DURABILITY"),
+            ("encrypt", "FLOWSTATE_STORE_ENCRYPT"),
+            ("compress_run_output", "FLOWSTATE_COMPRESS_RUN_OUTPUT"),
+            ("gc_hours", "FLOWSTATE_STORE_GC_HOURS"),
+            ("s3_endpoint", "FLOWSTATE_S3_ENDPOINT"),
+            ("s3_region", "FLOWSTATE_S3_REGION"),
+            ("s3_bucket", "FLOWSTATE_S3_BUCKET"),
+            ("s3_access_key_id", "FLOWSTATE_S3_ACCESS_KEY_ID"),
+            ("s3_secret_access_key", "FLOWSTATE_S3_SECRET_ACCESS_KEY"),
+            (
+                "s3_multipart_threshold_mb",
+                "FLOWSTATE_S3_MULTIPART_THRESHOLD_MB",
+            ),
+            ("s3_part_size_mb", "FLOWSTATE_S3_PART_SIZE_MB"),
+            ("s3_part_retries", "FLOWSTATE_S3_PART_RETRIES"),
+            ("gcs_bucket", "FLOWSTATE_GCS_BUCKET"),
+            ("gcs_endpoint", "FLOWSTATE_GCS_ENDPOINT"),
+            ("gcs_access_token", "FLOWSTATE_GCS_ACCESS_TOKEN"),
+            ("gcs_signer_email", "FLOWSTATE_GCS_SIGNER_EMAIL"),
+            ("azure_account", "FLOWSTATE_AZURE_ACCOUNT"),
+            ("azure_access_key", "FLOWSTATE_AZURE_ACCESS_KEY"),
+            ("azure_container", "FLOWSTATE_AZURE_CONTAINER"),
+            ("azure_endpoint", "FLOWSTATE_AZURE_ENDPOINT"),
+        ],
+    ),
+    (
+        "watchdog",
+        &[
+            ("interval_secs", "FLOWSTATE_WATCHDOG_INTERVAL_SECS"),
+            (
+                "running_timeout_mins",
+                "FLOWSTATE_WATCHDOG_RUNNING_TIMEOUT_MINS",
+            ),
+            (
+                "salvage_timeout_mins",
+                "FLOWSTATE_WATCHDOG_SALVAGE_TIMEOUT_MINS",
+            ),
+            ("action_timeout_mins", "FLOWSTATE_WATCHDOG_ACTION_TIMEOUTS"),
+        ],
+    ),
+    (
+        "auth",
+        &[
+            ("api_key", "FLOWSTATE_API_KEY"),
+            ("oidc_issuer", "FLOWSTATE_OIDC_ISSUER"),
+            ("oidc_client_id", "FLOWSTATE_OIDC_CLIENT_ID"),
+            ("oidc_client_secret", "FLOWSTATE_OIDC_CLIENT_SECRET"),
+            ("oidc_redirect_url", "FLOWSTATE_OIDC_REDIRECT_URL"),
+            ("oidc_session_hours", "FLOWSTATE_OIDC_SESSION_HOURS"),
+            ("github_webhook_secret", "FLOWSTATE_GITHUB_WEBHOOK_SECRET"),
+        ],
+    ),
+    (
+        "rate_limit",
+        &[
+            ("per_key", "FLOWSTATE_RATE_LIMIT_KEY"),
+            ("per_ip", "FLOWSTATE_RATE_LIMIT_IP"),
+            ("burst", "FLOWSTATE_RATE_LIMIT_BURST"),
+        ],
+    ),
+    (
+        "notifications",
+        &[
+            ("from", "FLOWSTATE_NOTIFY_FROM"),
+            ("smtp_host", "FLOWSTATE_NOTIFY_SMTP_HOST"),
+            ("smtp_port", "FLOWSTATE_NOTIFY_SMTP_PORT"),
+            ("smtp_username", "FLOWSTATE_NOTIFY_SMTP_USERNAME"),
+            ("smtp_password", "FLOWSTATE_NOTIFY_SMTP_PASSWORD"),
+        ],
+    ),
+    (
+        "email_gateway",
+        &[
+            ("address", "FLOWSTATE_EMAIL_ADDRESS"),
+            ("allowlist", "FLOWSTATE_EMAIL_ALLOWLIST"),
+            ("project", "FLOWSTATE_EMAIL_PROJECT"),
+            ("imap_host", "FLOWSTATE_EMAIL_IMAP_HOST"),
+            ("imap_port", "FLOWSTATE_EMAIL_IMAP_PORT"),
+            ("smtp_host", "FLOWSTATE_EMAIL_SMTP_HOST"),
+            ("smtp_port", "FLOWSTATE_EMAIL_SMTP_PORT"),
+            ("username", "FLOWSTATE_EMAIL_USERNAME"),
+            ("password", "FLOWSTATE_EMAIL_PASSWORD"),
+            ("mailbox", "FLOWSTATE_EMAIL_MAILBOX"),
+            ("poll_secs", "FLOWSTATE_EMAIL_POLL_SECS"),
+        ],
+    ),
+    (
+        "pod_manager",
+        &[
+            ("api_key", "FLOWSTATE_RUNPOD_API_KEY"),
+            ("pod_id", "FLOWSTATE_RUNPOD_POD_ID"),
+            ("template_image", "FLOWSTATE_RUNPOD_TEMPLATE_IMAGE"),
+            ("gpu_type", "FLOWSTATE_RUNPOD_GPU_TYPE"),
+            ("gpu_count", "FLOWSTATE_RUNPOD_GPU_COUNT"),
+            ("cloud_type", "FLOWSTATE_RUNPOD_CLOUD_TYPE"),
+            ("network_volume", "FLOWSTATE_RUNPOD_NETWORK_VOLUME"),
+            ("idle_timeout_secs", "FLOWSTATE_RUNPOD_IDLE_TIMEOUT"),
+            ("queue_threshold", "FLOWSTATE_RUNPOD_QUEUE_THRESHOLD"),
+            ("spindown_threshold", "FLOWSTATE_RUNPOD_SPINDOWN_THRESHOLD"),
+            ("scan_interval_secs", "FLOWSTATE_RUNPOD_SCAN_INTERVAL"),
+            ("max_daily_spend_cents", "FLOWSTATE_RUNPOD_MAX_DAILY_SPEND"),
+            ("drain_timeout_secs", "FLOWSTATE_RUNPOD_DRAIN_TIMEOUT"),
+            ("min_pods", "FLOWSTATE_RUNPOD_MIN_PODS"),
+            ("max_pods", "FLOWSTATE_RUNPOD_MAX_PODS"),
+            ("runs_per_pod", "FLOWSTATE_RUNPOD_RUNS_PER_POD"),
+            (
+                "queue_age_threshold_secs",
+                "FLOWSTATE_RUNPOD_QUEUE_AGE_THRESHOLD",
+            ),
+            (
+                "scale_up_cooldown_secs",
+                "FLOWSTATE_RUNPOD_SCALE_UP_COOLDOWN",
+            ),
+            (
+                "scale_down_cooldown_secs",
+                "FLOWSTATE_RUNPOD_SCALE_DOWN_COOLDOWN",
+            ),
+            ("ts_authkey", "FLOWSTATE_RUNPOD_TS_AUTHKEY"),
+            ("pod_server_ip", "FLOWSTATE_RUNPOD_POD_SERVER_IP"),
+            ("pod_server_url", "FLOWSTATE_RUNPOD_POD_SERVER_URL"),
+            ("pod_api_key", "FLOWSTATE_RUNPOD_POD_API_KEY"),
+            ("pod_capability", "FLOWSTATE_RUNPOD_POD_CAPABILITY"),
+            ("pod_backend", "FLOWSTATE_RUNPOD_POD_BACKEND"),
+            ("pod_max_concurrent", "FLOWSTATE_RUNPOD_POD_MAX_CONCURRENT"),
+            ("pod_max_builds", "FLOWSTATE_RUNPOD_POD_MAX_BUILDS"),
+            ("pod_vllm_model", "FLOWSTATE_RUNPOD_POD_VLLM_MODEL"),
+            (
+                "pod_vllm_max_model_len",
+                "FLOWSTATE_RUNPOD_POD_VLLM_MAX_MODEL_LEN",
+            ),
+            ("pod_hf_token", "FLOWSTATE_RUNPOD_POD_HF_TOKEN"),
+        ],
+    ),
+    (
+        "telemetry",
+        &[
+            ("otlp_endpoint", "FLOWSTATE_OTLP_ENDPOINT"),
+            ("sample_ratio", "FLOWSTATE_OTLP_SAMPLE_RATIO"),
+        ],
+    ),
+];
+
+/// A server configuration file (`flowstate-server --config flowstate.toml`).
+///
+/// Each setting stands for one of the `FLOWSTATE_*` environment variables,
+/// which the rest of the server goes on reading, so the file and the
+/// environment can be mixed and a variable that is set wins over the file.
+/// Unknown sections and keys are rejected rather than ignored, so a typo
+/// does not silently leave a default in place.
+#[derive(Debug, Default, PartialEq)]
+pub struct ServerConfigFile {
+    /// Environment variable names and values, by section and key.
+    pub vars: Vec<(&'static str, String)>,
+}
+
+impl ServerConfigFile {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("invalid configuration in {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let table: toml::Table = text.parse()?;
+        let mut vars = Vec::new();
+        for (section, values) in &table {
+            let Some((_, settings)) = SECTIONS.iter().find(|(name, _)| name == section) else {
+                bail!("unknown section [{section}]");
+            };
+            let toml::Value::Table(values) = values else {
+                bail!("{section} must be a [{section}] table");
+            };
+            for (key, value) in values {
+                let Some((_, var)) = settings.iter().find(|(k, _)| k == key) else {
+                    bail!("unknown setting {key} in [{section}]");
+                };
+                let value = env_value(value)
+                    .with_context(|| format!("invalid value for {key} in [{section}]"))?;
+                vars.push((*var, value));
+            }
+        }
+        Ok(Self { vars })
+    }
+
+    /// Set each variable the environment does not already have, returning
+    /// the names of those the environment kept.
+    ///
+    /// Call before anything reads the environment, and before spawning
+    /// threads that might.
+    pub fn apply_to_env(&self) -> Vec<&'static str> {
+        let mut overridden = Vec::new();
+        for (var, value) in &self.vars {
+            if std::env::var_os(var).is_some() {
+                overridden.push(*var);
+            } else {
+                std::env::set_var(var, value);
+            }
+        }
+        overridden
+    }
+}
+
+/// A TOML value as the string its environment variable would hold. Arrays
+/// become comma-separated lists, and tables lists of `key=value`.
+fn env_value(value: &toml::Value) -> Result<String> {
+    Ok(match value {
+        toml::Value::String(s) => s.clone(),
+        toml::Value::Integer(i) => i.to_string(),
+        toml::Value::Float(f) => f.to_string(),
+        toml::Value::Boolean(b) => b.to_string(),
+        toml::Value::Array(items) => items
+            .iter()
+            .map(|item| match item {
+                toml::Value::Array(_) | toml::Value::Table(_) => {
+                    bail!("lists may only hold strings, numbers and booleans")
+                }
+                other => env_value(other),
+            })
+            .collect::<Result<Vec<_>>>()?
+            .join(","),
+        toml::Value::Table(entries) => entries
+            .iter()
+            .map(|(key, item)| match item {
+                toml::Value::Array(_) | toml::Value::Table(_) => {
+                    bail!("tables may only hold strings, numbers and booleans")
+                }
+                other => Ok(format!("{key}={}", env_value(other)?)),
+            })
+            .collect::<Result<Vec<_>>>()?
+            .join(","),
+        toml::Value::Datetime(_) => bail!("expected a string, number, boolean, list or table"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_map_to_env_vars() {
+        let file = ServerConfigFile::parse(
+            r#"
+            [server]
+            bind = "127.0.0.1"
+            port = 8080
+
+            [store]
+            encrypt = true
+            s3_bucket = "flowstate"
+
+            [watchdog]
+            interval_secs = 30
+            action_timeout_mins = { research = 20, build = 240 }
+
+            [email_gateway]
+            allowlist = ["@example.com", "ops@example.org"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            file.vars,
+            vec![
+                (
+                    "FLOWSTATE_EMAIL_ALLOWLIST",
+                    "@example.com,ops@example.org".into()
+                ),
+                ("FLOWSTATE_BIND", "127.0.0.1".into()),
+                ("FLOWSTATE_PORT", "8080".into()),
+                ("FLOWSTATE_STORE_ENCRYPT", "true".into()),
+                ("FLOWSTATE_S3_BUCKET", "flowstate".into()),
+                (
+                    "FLOWSTATE_WATCHDOG_ACTION_TIMEOUTS",
+                    "build=240,research=20".into()
+                ),
+                ("FLOWSTATE_WATCHDOG_INTERVAL_SECS", "30".into()),
+            ]
+        );
+        assert_eq!(ServerConfigFile::parse("").unwrap(), Default::default());
+    }
+
+    #[test]
+    fn unknown_settings_are_rejected() {
+        let err = ServerConfigFile::parse("[server]\nprot = 8080").unwrap_err();
+        assert!(err.to_string().contains("unknown setting prot in [server]"));
+        let err = ServerConfigFile::parse("[sever]\nport = 8080").unwrap_err();
+        assert!(err.to_string().contains("unknown section [sever]"));
+        assert!(ServerConfigFile::parse("bind = \"0.0.0.0\"").is_err());
+        assert!(ServerConfigFile::parse("[server]\nport = { n = [1] }").is_err());
+        assert!(ServerConfigFile::parse("[server\nport = 1").is_err());
+    }
+
+    #[test]
+    fn environment_wins_over_the_file() {
+        // Variables only this test touches, so it can't race others
+        let file = ServerConfigFile {
+            vars: vec![
+                ("FLOWSTATE_TEST_CONFIG_SET", "from file".into()),
+                ("FLOWSTATE_TEST_CONFIG_UNSET", "from file".into()),
+            ],
+        };
+        std::env::set_var("FLOWSTATE_TEST_CONFIG_SET", "from env");
+        std::env::remove_var("FLOWSTATE_TEST_CONFIG_UNSET");
+        assert_eq!(file.apply_to_env(), vec!["FLOWSTATE_TEST_CONFIG_SET"]);
+        assert_eq!(
+            std::env::var("FLOWSTATE_TEST_CONFIG_SET").unwrap(),
+            "from env"
+        );
+        assert_eq!(
+            std::env::var("FLOWSTATE_TEST_CONFIG_UNSET").unwrap(),
+            "from file"
+        );
+    }
+}
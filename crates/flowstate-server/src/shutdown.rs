@@ -0,0 +1,214 @@
+//! Graceful shutdown: on SIGTERM or ctrl-c the server stops accepting
+//! connections, lets in-flight requests finish within a drain timeout, and
+//! waits for background tasks to stop between passes.
+
+use std::future::{Future, IntoFuture};
+use std::time::Duration;
+
+use axum::extract::connect_info::Connected;
+use axum::serve::IncomingStream;
+use axum::Router;
+use tokio::task::JoinHandle;
+use tokio::time::Interval;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+use crate::listen::PeerInfo;
+
+/// Drain timeout when `FLOWSTATE_DRAIN_TIMEOUT_SECS` is unset.
+const DEFAULT_DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long in-flight requests, and then background tasks, get to finish
+/// once shutdown begins, read from `FLOWSTATE_DRAIN_TIMEOUT_SECS`.
+pub fn drain_timeout_from_env() -> Duration {
+    std::env::var("FLOWSTATE_DRAIN_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_DRAIN_TIMEOUT)
+}
+
+/// Resolve when the server is asked to stop: ctrl-c or SIGTERM (what
+/// systemd, Docker and Kubernetes send).
+pub async fn signal() {
+    #[cfg(unix)]
+    {
+        let mut sigterm = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install SIGTERM handler");
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = sigterm.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+/// Wait for the next tick of a background loop. Returns `false` once
+/// shutdown has begun, so the loop stops between passes rather than in the
+/// middle of one.
+pub async fn next_tick(ticker: &mut Interval, shutdown: &CancellationToken) -> bool {
+    tokio::select! {
+        _ = ticker.tick() => true,
+        _ = shutdown.cancelled() => false,
+    }
+}
+
+/// Serve `app` until `signal` resolves, then cancel `shutdown`, stop
+/// accepting connections and wait up to `drain` for open ones to finish.
+/// Connections still open after that are dropped.
+pub async fn serve_until<L>(
+    listener: L,
+    app: Router,
+    signal: impl Future<Output = ()>,
+    shutdown: CancellationToken,
+    drain: Duration,
+) -> std::io::Result<()>
+where
+    L: axum::serve::Listener,
+    L::Addr: std::fmt::Debug,
+    for<'a> PeerInfo: Connected<IncomingStream<'a, L>>,
+{
+    let stopping = shutdown.clone();
+    let server = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<PeerInfo>(),
+    )
+    .with_graceful_shutdown(async move { stopping.cancelled().await })
+    .into_future();
+    let mut server = std::pin::pin!(server);
+    tokio::select! {
+        result = &mut server => return result,
+        _ = signal => {}
+    }
+
+    info!(
+        "shutting down: draining connections for up to {}s",
+        drain.as_secs()
+    );
+    shutdown.cancel();
+    match tokio::time::timeout(drain, server).await {
+        Ok(result) => result,
+        Err(_) => {
+            warn!("drain timeout reached; dropping open connections");
+            Ok(())
+        }
+    }
+}
+
+/// Wait up to `timeout` for background tasks to notice shutdown and
+/// return, then abort any still running.
+pub async fn join_background(tasks: Vec<JoinHandle<()>>, timeout: Duration) {
+    let aborts: Vec<_> = tasks.iter().map(|t| t.abort_handle()).collect();
+    if tokio::time::timeout(timeout, futures_util::future::join_all(tasks))
+        .await
+        .is_err()
+    {
+        warn!("background tasks did not stop in time; aborting them");
+        for abort in aborts {
+            abort.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use axum::routing::get;
+    use tokio::net::TcpListener;
+    use tokio::sync::oneshot;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn finishes_in_flight_requests_then_refuses_new_ones() {
+        let (started_tx, started_rx) = oneshot::channel::<()>();
+        let started_tx = Arc::new(std::sync::Mutex::new(Some(started_tx)));
+        let app = Router::new().route(
+            "/slow",
+            get(move || {
+                let started_tx = started_tx.clone();
+                async move {
+                    if let Some(tx) = started_tx.lock().unwrap().take() {
+                        let _ = tx.send(());
+                    }
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                    "done"
+                }
+            }),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (stop_tx, stop_rx) = oneshot::channel::<()>();
+        let server = tokio::spawn(serve_until(
+            listener,
+            app,
+            async move {
+                let _ = stop_rx.await;
+            },
+            CancellationToken::new(),
+            Duration::from_secs(5),
+        ));
+
+        let request = tokio::spawn(reqwest::get(format!("http://{addr}/slow")));
+        started_rx.await.unwrap();
+        stop_tx.send(()).unwrap();
+
+        let resp = request.await.unwrap().unwrap();
+        assert_eq!(resp.text().await.unwrap(), "done");
+        server.await.unwrap().unwrap();
+        assert!(reqwest::get(format!("http://{addr}/slow")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn drain_timeout_drops_stuck_connections() {
+        let app = Router::new().route("/stuck", get(std::future::pending::<()>));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (stop_tx, stop_rx) = oneshot::channel::<()>();
+        let server = tokio::spawn(serve_until(
+            listener,
+            app,
+            async move {
+                let _ = stop_rx.await;
+            },
+            CancellationToken::new(),
+            Duration::from_millis(100),
+        ));
+        let request = tokio::spawn(reqwest::get(format!("http://{addr}/stuck")));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        stop_tx.send(()).unwrap();
+
+        tokio::time::timeout(Duration::from_secs(5), server)
+            .await
+            .expect("server stopped after the drain timeout")
+            .unwrap()
+            .unwrap();
+        request.abort();
+    }
+
+    #[tokio::test]
+    async fn background_loops_stop_between_passes() {
+        let shutdown = CancellationToken::new();
+        let passes = Arc::new(AtomicUsize::new(0));
+        let task = {
+            let shutdown = shutdown.clone();
+            let passes = passes.clone();
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(Duration::from_millis(10));
+                while next_tick(&mut ticker, &shutdown).await {
+                    passes.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        shutdown.cancel();
+        join_background(vec![task], Duration::from_secs(1)).await;
+        let stopped_at = passes.load(Ordering::SeqCst);
+        assert!(stopped_at > 0);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(passes.load(Ordering::SeqCst), stopped_at);
+    }
+}
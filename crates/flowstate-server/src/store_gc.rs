@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use flowstate_db::{Database, DbError};
+use flowstate_store::{ObjectMeta, ObjectStore};
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+use crate::shutdown;
+
+/// Store prefixes whose objects belong to a database row, keyed by the id
+/// after the prefix.
+const OWNED_PREFIXES: &[(&str, Owner)] = &[("tasks/", Owner::Task), ("claude_runs/", Owner::Run)];
+
+/// Delay before the first scheduled pass, so objects written by requests
+/// still in flight at startup are not judged before their rows commit.
+const FIRST_PASS_DELAY: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Owner {
+    Task,
+    Run,
+}
+
+/// What a garbage collection pass found and removed.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GcReport {
+    /// Keys listed under the owned prefixes.
+    pub scanned_keys: usize,
+    /// Keys whose task or run no longer exists; deleted unless a dry run.
+    pub orphaned_keys: Vec<String>,
+    /// Bytes the orphaned keys took up in the store.
+    pub reclaimed_bytes: u64,
+}
+
+/// Time between scheduled passes, read from `FLOWSTATE_STORE_GC_HOURS`.
+/// Unset or `0` leaves collection to `flowstate-server gc`.
+pub fn interval_from_env() -> Option<Duration> {
+    std::env::var("FLOWSTATE_STORE_GC_HOURS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|hours| *hours > 0)
+        .map(|hours| Duration::from_secs(hours * 60 * 60))
+}
+
+/// Background task that runs [`collect_garbage`] every `interval`.
+pub async fn run_store_gc(
+    db: Arc<dyn Database>,
+    store: Arc<dyn ObjectStore>,
+    interval: Duration,
+    shutdown: CancellationToken,
+) {
+    let first = Instant::now() + FIRST_PASS_DELAY.min(interval);
+    let mut ticker = tokio::time::interval_at(first, interval);
+    while shutdown::next_tick(&mut ticker, &shutdown).await {
+        match collect_garbage(&*db, &*store, false).await {
+            Ok(report) if !report.orphaned_keys.is_empty() => info!(
+                "store gc: deleted {} orphaned objects ({} bytes) of {} scanned",
+                report.orphaned_keys.len(),
+                report.reclaimed_bytes,
+                report.scanned_keys
+            ),
+            Ok(_) => {}
+            Err(e) => error!("store gc error: {e}"),
+        }
+    }
+}
+
+/// Find objects under `tasks/` and `claude_runs/` whose task or run has
+/// been deleted, and attachment blobs no attachment points at any more, and
+/// delete them unless `dry_run`.
+///
+/// An owner is only judged missing when the database says so; lookup
+/// errors keep its objects. Objects that fail to delete are logged and left
+/// out of the report.
+pub async fn collect_garbage(
+    db: &dyn Database,
+    store: &dyn ObjectStore,
+    dry_run: bool,
+) -> anyhow::Result<GcReport> {
+    let mut report = GcReport::default();
+    let mut exists: HashMap<(Owner, String), bool> = HashMap::new();
+
+    for &(prefix, owner) in OWNED_PREFIXES {
+        let objects = store
+            .list_with_meta(prefix)
+            .await
+            .map_err(|e| anyhow::anyhow!("listing {prefix}: {e}"))?;
+        report.scanned_keys += objects.len();
+
+        for object in objects {
+            let key = &object.key;
+            let Some(id) = key[prefix.len()..]
+                .split('/')
+                .next()
+                .filter(|id| !id.is_empty())
+            else {
+                continue;
+            };
+            let owned = match exists.get(&(owner, id.to_string())) {
+                Some(owned) => *owned,
+                None => {
+                    let result = match owner {
+                        Owner::Task => db.get_task(id).await.map(|_| ()),
+                        Owner::Run => db.get_claude_run(id).await.map(|_| ()),
+                    };
+                    let owned = match result {
+                        Ok(()) => true,
+                        Err(DbError::NotFound(_)) => false,
+                        Err(e) => {
+                            warn!("store gc: looking up {id} for {key}: {e}");
+                            true
+                        }
+                    };
+                    exists.insert((owner, id.to_string()), owned);
+                    owned
+                }
+            };
+            if !owned {
+                remove(store, object, dry_run, &mut report).await;
+            }
+        }
+    }
+
+    // Shared blobs are garbage once nothing references them, e.g. after
+    // their last task was deleted.
+    let prefix = flowstate_store::ATTACHMENT_BLOB_PREFIX;
+    let blobs = store
+        .list_with_meta(prefix)
+        .await
+        .map_err(|e| anyhow::anyhow!("listing {prefix}: {e}"))?;
+    report.scanned_keys += blobs.len();
+    for blob in blobs {
+        match db.count_attachment_refs(&blob.key).await {
+            Ok(0) => remove(store, blob, dry_run, &mut report).await,
+            Ok(_) => {}
+            Err(e) => warn!("store gc: counting references to {}: {e}", blob.key),
+        }
+    }
+    Ok(report)
+}
+
+/// Delete an orphaned object unless `dry_run` and add it to the report.
+async fn remove(store: &dyn ObjectStore, object: ObjectMeta, dry_run: bool, report: &mut GcReport) {
+    if !dry_run {
+        if let Err(e) = store.delete(&object.key).await {
+            warn!("store gc: deleting {}: {e}", object.key);
+            return;
+        }
+    }
+    report.reclaimed_bytes += object.size;
+    report.orphaned_keys.push(object.key);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use flowstate_core::claude_run::{ClaudeAction, CreateClaudeRun};
+    use flowstate_core::project::CreateProject;
+    use flowstate_core::task::{CreateTask, Priority, Status, TaskType};
+    use flowstate_store::StoreConfig;
+
+    #[tokio::test]
+    async fn deletes_objects_nothing_refers_to() {
+        let db = Arc::new(flowstate_db::SqliteDatabase::open_in_memory().unwrap());
+        let tmp = tempfile::tempdir().unwrap();
+        let store = flowstate_store::create_store(&StoreConfig {
+            endpoint_url: None,
+            region: None,
+            bucket: None,
+            access_key_id: None,
+            secret_access_key: None,
+            local_data_dir: Some(tmp.path().to_string_lossy().to_string()),
+            gcs: None,
+            azure: None,
+            durability: flowstate_store::Durability::Atomic,
+            multipart: flowstate_store::MultipartConfig::default(),
+        })
+        .unwrap();
+
+        let project = db
+            .create_project(&CreateProject {
+                name: "Gc".into(),
+                slug: "gc".into(),
+                description: String::new(),
+                repo_url: String::new(),
+            })
+            .await
+            .unwrap();
+        let task = db
+            .create_task(&CreateTask {
+                project_id: project.id.clone(),
+                title: "Kept".into(),
+                description: String::new(),
+                status: Status::Todo,
+                priority: Priority::Medium,
+                task_type: TaskType::Feature,
+                parent_id: None,
+                reviewer: String::new(),
+                research_capability: None,
+                design_capability: None,
+                plan_capability: None,
+                build_capability: None,
+                verify_capability: None,
+                due_at: None,
+            })
+            .await
+            .unwrap();
+        let run = db
+            .create_claude_run(&CreateClaudeRun {
+                task_id: task.id.clone(),
+                action: ClaudeAction::Research,
+                required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
+            })
+            .await
+            .unwrap();
+
+        let shared = flowstate_store::attachment_blob_key("aaaa");
+        db.create_attachment(&task.id, "a.png", &shared, 5, "image/png", "aaaa")
+            .await
+            .unwrap();
+
+        let kept = [
+            flowstate_store::task_spec_key(&task.id),
+            flowstate_store::claude_run_output_key(&run.id),
+            shared,
+        ];
+        let orphaned = [
+            flowstate_store::task_spec_key("deleted-task"),
+            flowstate_store::task_attachment_key("deleted-task", "att", "a.png"),
+            flowstate_store::claude_run_prompt_key("deleted-run"),
+            flowstate_store::attachment_blob_key("bbbb"),
+        ];
+        for key in kept.iter().chain(&orphaned) {
+            store.put(key, Bytes::from("12345")).await.unwrap();
+        }
+        store.put("other/file", Bytes::from("x")).await.unwrap();
+
+        let dry = collect_garbage(&*db, &*store, true).await.unwrap();
+        assert_eq!(dry.scanned_keys, 7);
+        assert_eq!(dry.orphaned_keys.len(), 4);
+        assert_eq!(dry.reclaimed_bytes, 20);
+        assert!(store.exists(&orphaned[0]).await.unwrap());
+
+        let report = collect_garbage(&*db, &*store, false).await.unwrap();
+        assert_eq!(report, dry);
+        for key in &orphaned {
+            assert!(!store.exists(key).await.unwrap(), "{key} kept");
+        }
+        for key in &kept {
+            assert!(store.exists(key).await.unwrap(), "{key} deleted");
+        }
+        assert!(store.exists("other/file").await.unwrap());
+
+        let again = collect_garbage(&*db, &*store, false).await.unwrap();
+        assert!(again.orphaned_keys.is_empty());
+    }
+}
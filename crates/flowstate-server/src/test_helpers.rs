@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use aes_gcm::aead::OsRng;
@@ -9,10 +10,16 @@ use flowstate_store::StoreConfig;
 use tokio::net::TcpListener;
 
 use crate::auth::AuthConfig;
-use crate::routes::InnerAppState;
+use crate::routes::status::{StatusExposure, StatusPage};
+use crate::routes::{AppState, InnerAppState};
 
 /// Build a test router with in-memory SQLite, temp local store, random AES key, no auth.
 pub async fn test_router() -> Router {
+    crate::routes::build_router(test_state().await)
+}
+
+/// The state behind `test_router`, for tests that call server code directly.
+pub async fn test_state() -> AppState {
     let db = Arc::new(flowstate_db::SqliteDatabase::open_in_memory().unwrap());
     let service = LocalService::new(db.clone());
     let store_config = StoreConfig {
@@ -28,10 +35,14 @@ pub async fn test_router() -> Router {
                 .to_string_lossy()
                 .to_string(),
         ),
+        gcs: None,
+        azure: None,
+        durability: flowstate_store::Durability::Atomic,
+        multipart: flowstate_store::MultipartConfig::default(),
     };
     let store = flowstate_store::create_store(&store_config).unwrap();
     let key = Aes256Gcm::generate_key(OsRng);
-    let state = Arc::new(InnerAppState {
+    Arc::new(InnerAppState {
         service,
         db,
         auth: None,
@@ -39,8 +50,35 @@ pub async fn test_router() -> Router {
         encryption_key: key,
         store,
         pod_manager: None,
-    });
-    crate::routes::build_router(state)
+        pod_api: None,
+        watchdog: Default::default(),
+        runner_mtls: false,
+        maintenance: AtomicBool::new(false),
+        status_page: StatusPage::new(StatusExposure::Full),
+        db_maintenance: std::sync::Mutex::new(None),
+        task_links: flowstate_core::TaskLinks::default(),
+        notifier: crate::notifier::Notifier::from_getter(|_| None).unwrap(),
+        events: Default::default(),
+        run_logs: Default::default(),
+        github_webhook_secret: None,
+        rate_limiter: Default::default(),
+        shutdown: Default::default(),
+    })
+}
+
+/// `test_state` with auth enabled, returning the state and the env API key.
+/// Keys inserted into `state.db` are accepted as well.
+pub async fn test_state_with_auth() -> (AppState, String) {
+    let mut state = Arc::try_unwrap(test_state().await)
+        .ok()
+        .expect("fresh state has one owner");
+    let api_key = crate::auth::generate_api_key();
+    state.auth = Some(Arc::new(AuthConfig {
+        env_key_hash: Some(crate::auth::sha256_hex(&api_key)),
+        db: state.db.clone(),
+        oidc: None,
+    }));
+    (Arc::new(state), api_key)
 }
 
 /// Build a test router with auth enabled, returning (router, api_key).
@@ -60,6 +98,10 @@ pub async fn test_router_with_auth() -> (Router, String) {
                 .to_string_lossy()
                 .to_string(),
         ),
+        gcs: None,
+        azure: None,
+        durability: flowstate_store::Durability::Atomic,
+        multipart: flowstate_store::MultipartConfig::default(),
     };
     let store = flowstate_store::create_store(&store_config).unwrap();
     let key = Aes256Gcm::generate_key(OsRng);
@@ -67,6 +109,7 @@ pub async fn test_router_with_auth() -> (Router, String) {
     let auth = Arc::new(AuthConfig {
         env_key_hash: Some(crate::auth::sha256_hex(&api_key)),
         db: db.clone(),
+        oidc: None,
     });
     let state = Arc::new(InnerAppState {
         service,
@@ -76,6 +119,19 @@ pub async fn test_router_with_auth() -> (Router, String) {
         encryption_key: key,
         store,
         pod_manager: None,
+        pod_api: None,
+        watchdog: Default::default(),
+        runner_mtls: false,
+        maintenance: AtomicBool::new(false),
+        status_page: StatusPage::new(StatusExposure::Full),
+        db_maintenance: std::sync::Mutex::new(None),
+        task_links: flowstate_core::TaskLinks::default(),
+        notifier: crate::notifier::Notifier::from_getter(|_| None).unwrap(),
+        events: Default::default(),
+        run_logs: Default::default(),
+        github_webhook_secret: None,
+        rate_limiter: Default::default(),
+        shutdown: Default::default(),
     });
     let router = crate::routes::build_router(state);
     (router, api_key)
@@ -98,6 +154,10 @@ pub async fn test_router_with_pod_manager() -> Router {
                 .to_string_lossy()
                 .to_string(),
         ),
+        gcs: None,
+        azure: None,
+        durability: flowstate_store::Durability::Atomic,
+        multipart: flowstate_store::MultipartConfig::default(),
     };
     let store = flowstate_store::create_store(&store_config).unwrap();
     let key = Aes256Gcm::generate_key(OsRng);
@@ -110,6 +170,69 @@ pub async fn test_router_with_pod_manager() -> Router {
         encryption_key: key,
         store,
         pod_manager: Some(Arc::new(tokio::sync::Mutex::new(pod_state))),
+        pod_api: None,
+        watchdog: Default::default(),
+        runner_mtls: false,
+        maintenance: AtomicBool::new(false),
+        status_page: StatusPage::new(StatusExposure::Full),
+        db_maintenance: std::sync::Mutex::new(None),
+        task_links: flowstate_core::TaskLinks::default(),
+        notifier: crate::notifier::Notifier::from_getter(|_| None).unwrap(),
+        events: Default::default(),
+        run_logs: Default::default(),
+        github_webhook_secret: None,
+        rate_limiter: Default::default(),
+        shutdown: Default::default(),
+    });
+    crate::routes::build_router(state)
+}
+
+/// Build a test router with runner mTLS enforcement on, no auth.
+/// Serve it with `PeerInfo` connect info so the client certificate is visible.
+pub async fn test_router_with_runner_mtls() -> Router {
+    let db = Arc::new(flowstate_db::SqliteDatabase::open_in_memory().unwrap());
+    let service = LocalService::new(db.clone());
+    let store_config = StoreConfig {
+        endpoint_url: None,
+        region: None,
+        bucket: None,
+        access_key_id: None,
+        secret_access_key: None,
+        local_data_dir: Some(
+            tempfile::tempdir()
+                .unwrap()
+                .keep()
+                .to_string_lossy()
+                .to_string(),
+        ),
+        gcs: None,
+        azure: None,
+        durability: flowstate_store::Durability::Atomic,
+        multipart: flowstate_store::MultipartConfig::default(),
+    };
+    let store = flowstate_store::create_store(&store_config).unwrap();
+    let key = Aes256Gcm::generate_key(OsRng);
+    let state = Arc::new(InnerAppState {
+        service,
+        db,
+        auth: None,
+        runners: std::sync::Mutex::new(HashMap::new()),
+        encryption_key: key,
+        store,
+        pod_manager: None,
+        pod_api: None,
+        watchdog: Default::default(),
+        runner_mtls: true,
+        maintenance: AtomicBool::new(false),
+        status_page: StatusPage::new(StatusExposure::Full),
+        db_maintenance: std::sync::Mutex::new(None),
+        task_links: flowstate_core::TaskLinks::default(),
+        notifier: crate::notifier::Notifier::from_getter(|_| None).unwrap(),
+        events: Default::default(),
+        run_logs: Default::default(),
+        github_webhook_secret: None,
+        rate_limiter: Default::default(),
+        shutdown: Default::default(),
     });
     crate::routes::build_router(state)
 }
@@ -118,6 +241,7 @@ pub async fn test_router_with_pod_manager() -> Router {
 pub struct TestServer {
     pub base_url: String,
     _handle: tokio::task::JoinHandle<()>,
+    _dir: Option<tempfile::TempDir>,
 }
 
 /// Spawn an axum test server on a random port. Returns the TestServer
@@ -133,5 +257,25 @@ pub async fn spawn_test_server() -> TestServer {
     TestServer {
         base_url,
         _handle: handle,
+        _dir: None,
+    }
+}
+
+/// Spawn an axum test server on a Unix domain socket in a temp directory.
+/// The `base_url` has the form "unix:/tmp/.../flowstate.sock".
+#[cfg(unix)]
+pub async fn spawn_unix_test_server() -> TestServer {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("flowstate.sock");
+    let listener = crate::listen::bind_unix(&path).unwrap();
+    let base_url = format!("unix:{}", path.display());
+    let app = test_router().await;
+    let handle = tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    TestServer {
+        base_url,
+        _handle: handle,
+        _dir: Some(dir),
     }
 }
@@ -0,0 +1,509 @@
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{bail, Context, Result};
+use axum::extract::connect_info::Connected;
+use axum::serve::IncomingStream;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{RootCertStore, ServerConfig};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, Notify};
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+
+use crate::listen::PeerInfo;
+
+/// How long a client gets to complete the TLS handshake before the
+/// connection is dropped.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often certificate files are checked for changes.
+pub const RELOAD_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Certificate and key paths for native HTTPS, read from
+/// `FLOWSTATE_TLS_CERT` / `FLOWSTATE_TLS_KEY`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsConfig {
+    /// PEM file with the server certificate, followed by any intermediates.
+    pub cert_path: PathBuf,
+    /// PEM file with the private key (PKCS#8, PKCS#1 or SEC1).
+    pub key_path: PathBuf,
+    /// CA bundle used to verify client certificates. When set, clients may
+    /// present a certificate signed by it; which routes require one is
+    /// decided by the router, not the handshake.
+    pub client_ca_path: Option<PathBuf>,
+}
+
+impl TlsConfig {
+    /// Returns `Ok(None)` when neither variable is set. Setting only one of
+    /// them is a configuration error rather than a silent fallback to HTTP.
+    pub fn from_env() -> Result<Option<Self>> {
+        Self::from_values(
+            std::env::var("FLOWSTATE_TLS_CERT").ok(),
+            std::env::var("FLOWSTATE_TLS_KEY").ok(),
+        )
+    }
+
+    fn from_values(cert: Option<String>, key: Option<String>) -> Result<Option<Self>> {
+        match (cert, key) {
+            (None, None) => Ok(None),
+            (Some(cert), Some(key)) => Ok(Some(Self {
+                cert_path: cert.into(),
+                key_path: key.into(),
+                client_ca_path: None,
+            })),
+            _ => bail!("FLOWSTATE_TLS_CERT and FLOWSTATE_TLS_KEY must be set together"),
+        }
+    }
+
+    /// Load the certificate chain and key into a rustls server config.
+    pub fn load(&self) -> Result<Arc<ServerConfig>> {
+        let certs = load_certs(&self.cert_path)?;
+        let key = PrivateKeyDer::from_pem_file(&self.key_path)
+            .with_context(|| format!("failed to read private key {}", self.key_path.display()))?;
+
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let builder = ServerConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()?;
+        let builder = match &self.client_ca_path {
+            Some(ca_path) => {
+                let mut roots = RootCertStore::empty();
+                for cert in load_certs(ca_path)? {
+                    roots.add(cert).context("invalid client CA certificate")?;
+                }
+                let verifier = WebPkiClientVerifier::builder_with_provider(roots.into(), provider)
+                    .allow_unauthenticated()
+                    .build()?;
+                builder.with_client_cert_verifier(verifier)
+            }
+            None => builder.with_no_client_auth(),
+        };
+        let mut config = builder
+            .with_single_cert(certs, key)
+            .context("certificate and private key do not match")?;
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+        Ok(Arc::new(config))
+    }
+
+    /// Modification times of the files [`load`](Self::load) reads, to tell
+    /// when they have been replaced.
+    fn modified(&self) -> Vec<Option<SystemTime>> {
+        [
+            Some(&self.cert_path),
+            Some(&self.key_path),
+            self.client_ca_path.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+        .collect()
+    }
+}
+
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let certs = CertificateDer::pem_file_iter(path)
+        .with_context(|| format!("failed to read certificate {}", path.display()))?
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("invalid PEM in {}", path.display()))?;
+    if certs.is_empty() {
+        bail!("no certificates found in {}", path.display());
+    }
+    Ok(certs)
+}
+
+/// TCP listener that terminates TLS before handing connections to axum.
+///
+/// Handshakes run on their own tasks so a slow or stalled client cannot
+/// block the accept loop; completed streams are queued for `accept`.
+pub struct TlsListener {
+    rx: mpsc::Receiver<(TlsStream<TcpStream>, SocketAddr)>,
+    local_addr: SocketAddr,
+    /// Config new handshakes use, replaced when certificates are reloaded.
+    config: Arc<RwLock<Arc<ServerConfig>>>,
+}
+
+impl TlsListener {
+    pub fn new(listener: TcpListener, config: Arc<ServerConfig>) -> Result<Self> {
+        let local_addr = listener.local_addr()?;
+        let config = Arc::new(RwLock::new(config));
+        let (tx, rx) = mpsc::channel(64);
+
+        let current = config.clone();
+        tokio::spawn(async move {
+            loop {
+                let (stream, addr) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        tracing::warn!("accept error: {e}");
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        continue;
+                    }
+                };
+                if tx.is_closed() {
+                    break;
+                }
+                let acceptor = TlsAcceptor::from(current.read().unwrap().clone());
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                        Ok(Ok(tls)) => {
+                            let _ = tx.send((tls, addr)).await;
+                        }
+                        Ok(Err(e)) => tracing::debug!("TLS handshake with {addr} failed: {e}"),
+                        Err(_) => tracing::debug!("TLS handshake with {addr} timed out"),
+                    }
+                });
+            }
+        });
+
+        Ok(Self {
+            rx,
+            local_addr,
+            config,
+        })
+    }
+
+    /// Reload certificates from `tls` when its files change, checked every
+    /// `interval`, and on SIGHUP. Open connections keep the certificate
+    /// they were accepted with. A reload that fails, such as one that reads
+    /// a new certificate before its key has been written, is logged and
+    /// the current certificate kept.
+    pub fn reload_on_change(&self, tls: TlsConfig, interval: Duration) {
+        let config = self.config.clone();
+        let hangup = Arc::new(Notify::new());
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+            let hangup = hangup.clone();
+            tokio::spawn(async move {
+                let Ok(mut signals) = signal(SignalKind::hangup()) else {
+                    return;
+                };
+                while signals.recv().await.is_some() {
+                    hangup.notify_one();
+                }
+            });
+        }
+        tokio::spawn(async move {
+            let mut seen = tls.modified();
+            let mut ticks = tokio::time::interval(interval);
+            loop {
+                let forced = tokio::select! {
+                    _ = ticks.tick() => false,
+                    _ = hangup.notified() => true,
+                };
+                let modified = tls.modified();
+                if modified == seen && !forced {
+                    continue;
+                }
+                seen = modified;
+                match tls.load() {
+                    Ok(fresh) => {
+                        *config.write().unwrap() = fresh;
+                        tracing::info!("reloaded TLS certificate {}", tls.cert_path.display());
+                    }
+                    Err(e) => tracing::warn!("keeping the current TLS certificate: {e:#}"),
+                }
+            }
+        });
+    }
+}
+
+impl axum::serve::Listener for TlsListener {
+    type Io = TlsStream<TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        match self.rx.recv().await {
+            Some(conn) => conn,
+            // The accept task only exits once this receiver is dropped.
+            None => std::future::pending().await,
+        }
+    }
+
+    fn local_addr(&self) -> std::io::Result<Self::Addr> {
+        Ok(self.local_addr)
+    }
+}
+
+impl Connected<IncomingStream<'_, TlsListener>> for PeerInfo {
+    fn connect_info(stream: IncomingStream<'_, TlsListener>) -> Self {
+        let (_, session) = stream.io().get_ref();
+        // rustls only exposes peer certificates that passed verification.
+        let client_cert_cn = session
+            .peer_certificates()
+            .and_then(|chain| chain.first())
+            .and_then(|cert| crate::runner_pki::common_name_from_der(cert));
+        Self {
+            remote_addr: Some(*stream.remote_addr()),
+            client_cert_cn,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures")
+            .join(name)
+    }
+
+    fn fixture_config() -> TlsConfig {
+        TlsConfig {
+            cert_path: fixture("tls-cert.pem"),
+            key_path: fixture("tls-key.pem"),
+            client_ca_path: None,
+        }
+    }
+
+    #[test]
+    fn from_values_none_when_unset() {
+        assert_eq!(TlsConfig::from_values(None, None).unwrap(), None);
+    }
+
+    #[test]
+    fn from_values_requires_both() {
+        assert!(TlsConfig::from_values(Some("cert.pem".into()), None).is_err());
+        assert!(TlsConfig::from_values(None, Some("key.pem".into())).is_err());
+    }
+
+    #[test]
+    fn load_fixture_cert() {
+        fixture_config().load().unwrap();
+    }
+
+    #[test]
+    fn load_rejects_missing_file() {
+        let config = TlsConfig {
+            cert_path: fixture("does-not-exist.pem"),
+            key_path: fixture("tls-key.pem"),
+            client_ca_path: None,
+        };
+        assert!(config.load().is_err());
+    }
+
+    #[test]
+    fn load_rejects_key_as_cert() {
+        let config = TlsConfig {
+            cert_path: fixture("tls-key.pem"),
+            key_path: fixture("tls-key.pem"),
+            client_ca_path: None,
+        };
+        let err = config.load().unwrap_err();
+        assert!(err.to_string().contains("no certificates"));
+    }
+
+    #[tokio::test]
+    async fn serves_https() {
+        let tcp = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = tcp.local_addr().unwrap().port();
+        let listener = TlsListener::new(tcp, fixture_config().load().unwrap()).unwrap();
+        let app = crate::test_helpers::test_router().await;
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let ca = std::fs::read(fixture("tls-ca.pem")).unwrap();
+        let client = reqwest::Client::builder()
+            .add_root_certificate(reqwest::Certificate::from_pem(&ca).unwrap())
+            .build()
+            .unwrap();
+        let resp = client
+            .get(format!("https://localhost:{port}/api/health"))
+            .send()
+            .await
+            .unwrap();
+        assert!(resp.status().is_success());
+
+        // Plain HTTP against the TLS port must not be served.
+        let plain = reqwest::get(format!("http://localhost:{port}/api/health")).await;
+        assert!(plain.is_err());
+    }
+
+    #[tokio::test]
+    async fn reloads_certificate_when_files_change() {
+        let tmp = tempfile::tempdir().unwrap();
+        let config = TlsConfig {
+            cert_path: tmp.path().join("cert.pem"),
+            key_path: tmp.path().join("key.pem"),
+            client_ca_path: None,
+        };
+        std::fs::copy(fixture("tls-cert.pem"), &config.cert_path).unwrap();
+        std::fs::copy(fixture("tls-key.pem"), &config.key_path).unwrap();
+        let tcp = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let listener = TlsListener::new(tcp, config.load().unwrap()).unwrap();
+        listener.reload_on_change(config.clone(), Duration::from_millis(20));
+        let serving = || listener.config.read().unwrap().clone();
+        let original = serving();
+
+        let touch = |path: &Path, contents: &[u8]| {
+            std::fs::write(path, contents).unwrap();
+            let later = SystemTime::now() + Duration::from_secs(5);
+            std::fs::File::options()
+                .write(true)
+                .open(path)
+                .unwrap()
+                .set_modified(later)
+                .unwrap();
+        };
+
+        // A broken certificate is not swapped in
+        touch(&config.cert_path, b"not a certificate");
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(Arc::ptr_eq(&serving(), &original));
+
+        touch(
+            &config.cert_path,
+            &std::fs::read(fixture("tls-cert.pem")).unwrap(),
+        );
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(!Arc::ptr_eq(&serving(), &original));
+    }
+
+    #[tokio::test]
+    async fn runner_routes_require_client_cert() {
+        use crate::runner_pki::{RunnerCa, RunnerCaPaths};
+
+        let tmp = tempfile::tempdir().unwrap();
+        let ca_paths = RunnerCaPaths {
+            cert_path: tmp.path().join("runner-ca.pem"),
+            key_path: tmp.path().join("runner-ca.key"),
+        };
+        let ca = RunnerCa::load_or_create(&ca_paths).unwrap();
+        let issued = ca.issue("runner-1", 1).unwrap();
+
+        let mut config = fixture_config();
+        config.client_ca_path = Some(ca_paths.cert_path.clone());
+        let tcp = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = tcp.local_addr().unwrap().port();
+        let listener = TlsListener::new(tcp, config.load().unwrap()).unwrap();
+        let app = crate::test_helpers::test_router_with_runner_mtls().await;
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<PeerInfo>(),
+            )
+            .await
+            .unwrap();
+        });
+
+        let server_ca =
+            reqwest::Certificate::from_pem(&std::fs::read(fixture("tls-ca.pem")).unwrap()).unwrap();
+        let anonymous = reqwest::Client::builder()
+            .use_rustls_tls()
+            .add_root_certificate(server_ca.clone())
+            .build()
+            .unwrap();
+        let mut identity = issued.cert_pem.clone();
+        identity.extend_from_slice(&issued.key_pem);
+        let enrolled = reqwest::Client::builder()
+            .use_rustls_tls()
+            .add_root_certificate(server_ca)
+            .identity(reqwest::Identity::from_pem(&identity).unwrap())
+            .build()
+            .unwrap();
+
+        let base = format!("https://localhost:{port}");
+        let register = serde_json::json!({ "runner_id": "runner-1" });
+
+        // Non-runner routes stay reachable without a certificate.
+        let resp = anonymous
+            .get(format!("{base}/api/projects"))
+            .send()
+            .await
+            .unwrap();
+        assert!(resp.status().is_success());
+
+        let resp = anonymous
+            .post(format!("{base}/api/runners/register"))
+            .json(&register)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::FORBIDDEN);
+
+        let resp = enrolled
+            .post(format!("{base}/api/runners/register"))
+            .header("X-Runner-Id", "runner-1")
+            .json(&register)
+            .send()
+            .await
+            .unwrap();
+        assert!(resp.status().is_success());
+    }
+
+    #[tokio::test]
+    async fn runner_cert_must_match_runner_id() {
+        use crate::runner_pki::{RunnerCa, RunnerCaPaths};
+
+        let tmp = tempfile::tempdir().unwrap();
+        let ca_paths = RunnerCaPaths {
+            cert_path: tmp.path().join("runner-ca.pem"),
+            key_path: tmp.path().join("runner-ca.key"),
+        };
+        let ca = RunnerCa::load_or_create(&ca_paths).unwrap();
+        let issued = ca.issue("runner-1", 1).unwrap();
+
+        let mut config = fixture_config();
+        config.client_ca_path = Some(ca_paths.cert_path.clone());
+        let tcp = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = tcp.local_addr().unwrap().port();
+        let listener = TlsListener::new(tcp, config.load().unwrap()).unwrap();
+        let app = crate::test_helpers::test_router_with_runner_mtls().await;
+        tokio::spawn(async move {
+            axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<PeerInfo>(),
+            )
+            .await
+            .unwrap();
+        });
+
+        let server_ca =
+            reqwest::Certificate::from_pem(&std::fs::read(fixture("tls-ca.pem")).unwrap()).unwrap();
+        let mut identity = issued.cert_pem.clone();
+        identity.extend_from_slice(&issued.key_pem);
+        let enrolled = reqwest::Client::builder()
+            .use_rustls_tls()
+            .add_root_certificate(server_ca)
+            .identity(reqwest::Identity::from_pem(&identity).unwrap())
+            .build()
+            .unwrap();
+        let base = format!("https://localhost:{port}");
+        let send = |path: &str, runner_id: Option<&str>, body: serde_json::Value| {
+            let mut req = enrolled.post(format!("{base}{path}")).json(&body);
+            if let Some(id) = runner_id {
+                req = req.header("X-Runner-Id", id);
+            }
+            async move { req.send().await.unwrap().status() }
+        };
+
+        // Another runner's id, or none, in the header
+        let other = serde_json::json!({ "runner_id": "runner-2" });
+        let status = send("/api/runners/register", Some("runner-2"), other.clone()).await;
+        assert_eq!(status, reqwest::StatusCode::FORBIDDEN);
+        let status = send("/api/claude-runs/claim", None, serde_json::json!({})).await;
+        assert_eq!(status, reqwest::StatusCode::FORBIDDEN);
+        // The right header, but registering someone else
+        let status = send("/api/runners/register", Some("runner-1"), other).await;
+        assert_eq!(status, reqwest::StatusCode::FORBIDDEN);
+
+        let own = serde_json::json!({ "runner_id": "runner-1" });
+        let status = send("/api/runners/register", Some("runner-1"), own).await;
+        assert!(status.is_success());
+        let status = send(
+            "/api/claude-runs/claim",
+            Some("runner-1"),
+            serde_json::json!({}),
+        )
+        .await;
+        assert!(status.is_success());
+    }
+}
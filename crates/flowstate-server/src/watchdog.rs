@@ -1,39 +1,208 @@
-use std::sync::Arc;
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
-use chrono::Utc;
+use anyhow::{bail, Result};
+use chrono::{DateTime, Utc};
+use flowstate_core::claude_run::ClaudeAction;
 use flowstate_db::Database;
-use tracing::{error, warn};
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
 
-/// Background task that detects and transitions stuck runs.
-///
-/// Runs periodically and looks for ClaudeRuns in Running or Salvaging status
-/// whose started_at is older than the hard timeout.
+use crate::shutdown;
+
+/// How often the watchdog scans, and how long runs may go before it times
+/// them out.
 ///
-/// Hard timeout defaults:
-/// - Running: 90 minutes (1.5x the runner's default 60 min build timeout)
-/// - Salvaging: 30 minutes
+/// The server timeouts should always be LONGER than the runner's own
+/// (60 minutes for builds by default), since the runner handles its own
+/// timeout first. The server watchdog is defense-in-depth for when the
+/// runner crashes.
+#[derive(Debug, Clone, PartialEq, Serialize, utoipa::ToSchema)]
+pub struct WatchdogConfig {
+    /// Seconds between scans.
+    pub interval_secs: u64,
+    /// Minutes a run may stay `running` when its action has no limit of its
+    /// own.
+    pub running_timeout_mins: u64,
+    /// Limits for particular actions, in minutes, keyed by action name
+    /// (`research`, `build`, ...).
+    pub action_timeout_mins: BTreeMap<String, u64>,
+    /// Minutes a run may stay `salvaging`.
+    pub salvage_timeout_mins: u64,
+}
+
+impl Default for WatchdogConfig {
+    /// Scans every minute; 90 minutes running (1.5x the runner's default
+    /// build timeout) and 30 salvaging.
+    fn default() -> Self {
+        Self {
+            interval_secs: 60,
+            running_timeout_mins: 90,
+            action_timeout_mins: BTreeMap::new(),
+            salvage_timeout_mins: 30,
+        }
+    }
+}
+
+/// A change to the [`WatchdogConfig`]; fields left out stay as they are.
+#[derive(Debug, Default, Deserialize, utoipa::ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct WatchdogPatch {
+    pub interval_secs: Option<u64>,
+    pub running_timeout_mins: Option<u64>,
+    /// Merged into the per-action limits; `null` removes an action's limit.
+    pub action_timeout_mins: Option<BTreeMap<String, Option<u64>>>,
+    pub salvage_timeout_mins: Option<u64>,
+}
+
+impl WatchdogConfig {
+    /// Read from `FLOWSTATE_WATCHDOG_INTERVAL_SECS`,
+    /// `FLOWSTATE_WATCHDOG_RUNNING_TIMEOUT_MINS`,
+    /// `FLOWSTATE_WATCHDOG_SALVAGE_TIMEOUT_MINS` and
+    /// `FLOWSTATE_WATCHDOG_ACTION_TIMEOUTS` (`research=30,build=120`).
+    pub fn from_env() -> Result<Self> {
+        Self::from_getter(|key| std::env::var(key).ok())
+    }
+
+    pub fn from_getter(get: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let number = |key: &str| -> Result<Option<u64>> {
+            match get(key) {
+                Some(v) => match v.trim().parse() {
+                    Ok(n) => Ok(Some(n)),
+                    Err(_) => bail!("{key}: expected a whole number, got {v:?}"),
+                },
+                None => Ok(None),
+            }
+        };
+        let mut action_timeout_mins = BTreeMap::new();
+        if let Some(list) = get("FLOWSTATE_WATCHDOG_ACTION_TIMEOUTS") {
+            for entry in list.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+                let parsed = entry
+                    .split_once('=')
+                    .and_then(|(action, mins)| Some((action.trim(), mins.trim().parse().ok()?)));
+                let Some((action, mins)) = parsed else {
+                    bail!(
+                        "FLOWSTATE_WATCHDOG_ACTION_TIMEOUTS: expected action=minutes, got {entry:?}"
+                    );
+                };
+                action_timeout_mins.insert(action.to_string(), Some(mins));
+            }
+        }
+        let mut config = Self::default();
+        config
+            .apply(WatchdogPatch {
+                interval_secs: number("FLOWSTATE_WATCHDOG_INTERVAL_SECS")?,
+                running_timeout_mins: number("FLOWSTATE_WATCHDOG_RUNNING_TIMEOUT_MINS")?,
+                action_timeout_mins: Some(action_timeout_mins),
+                salvage_timeout_mins: number("FLOWSTATE_WATCHDOG_SALVAGE_TIMEOUT_MINS")?,
+            })
+            .map_err(|e| anyhow::anyhow!("watchdog configuration: {e}"))?;
+        Ok(config)
+    }
+
+    /// Apply `patch`, leaving the configuration unchanged if the result
+    /// would be invalid.
+    pub fn apply(&mut self, patch: WatchdogPatch) -> Result<(), String> {
+        let mut next = self.clone();
+        if let Some(secs) = patch.interval_secs {
+            next.interval_secs = secs;
+        }
+        if let Some(mins) = patch.running_timeout_mins {
+            next.running_timeout_mins = mins;
+        }
+        if let Some(mins) = patch.salvage_timeout_mins {
+            next.salvage_timeout_mins = mins;
+        }
+        for (action, mins) in patch.action_timeout_mins.unwrap_or_default() {
+            if ClaudeAction::parse_str(&action).is_none() {
+                return Err(format!("unknown action {action:?}"));
+            }
+            match mins {
+                Some(mins) => next.action_timeout_mins.insert(action, mins),
+                None => next.action_timeout_mins.remove(&action),
+            };
+        }
+        if next.interval_secs == 0 {
+            return Err("interval_secs must be at least 1".into());
+        }
+        if next.running_timeout_mins == 0 || next.salvage_timeout_mins == 0 {
+            return Err("timeouts must be at least 1 minute".into());
+        }
+        if let Some((action, _)) = next.action_timeout_mins.iter().find(|(_, m)| **m == 0) {
+            return Err(format!("the {action} timeout must be at least 1 minute"));
+        }
+        *self = next;
+        Ok(())
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_secs)
+    }
+
+    /// How long a run of `action` may stay `running`.
+    pub fn running_timeout(&self, action: ClaudeAction) -> chrono::Duration {
+        let mins = self
+            .action_timeout_mins
+            .get(action.as_str())
+            .copied()
+            .unwrap_or(self.running_timeout_mins);
+        chrono::Duration::minutes(mins as i64)
+    }
+
+    /// The shortest time any run may stay `running`.
+    fn shortest_running_timeout(&self) -> chrono::Duration {
+        let mins = self
+            .action_timeout_mins
+            .values()
+            .copied()
+            .chain([self.running_timeout_mins])
+            .min()
+            .unwrap_or(self.running_timeout_mins);
+        chrono::Duration::minutes(mins as i64)
+    }
+}
+
+/// Background task that detects and transitions stuck runs.
 ///
-/// The server timeout should always be LONGER than the runner timeout,
-/// since the runner handles its own timeout first. The server watchdog
-/// is defense-in-depth for when the runner crashes.
-pub async fn run_watchdog(db: Arc<dyn Database>, scan_interval_secs: u64) {
-    let mut ticker = tokio::time::interval(Duration::from_secs(scan_interval_secs));
-    loop {
-        ticker.tick().await;
-        if let Err(e) = check_stale_runs(&*db).await {
+/// Scans every `interval_secs` for ClaudeRuns in Running or Salvaging
+/// status whose started_at is older than the configured timeout. The
+/// configuration is read afresh on each scan, so changes made through
+/// `PATCH /api/infra/watchdog` apply from the next one.
+pub async fn run_watchdog(
+    db: Arc<dyn Database>,
+    config: Arc<RwLock<WatchdogConfig>>,
+    shutdown: CancellationToken,
+) {
+    let mut ticker = tokio::time::interval(config.read().unwrap().interval());
+    while shutdown::next_tick(&mut ticker, &shutdown).await {
+        let current = config.read().unwrap().clone();
+        if let Err(e) = check_stale_runs(&*db, &current, Utc::now()).await {
             error!("watchdog error: {e}");
         }
+        if current.interval() != ticker.period() {
+            info!("watchdog now scanning every {}s", current.interval_secs);
+            let period = current.interval();
+            ticker = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        }
     }
 }
 
-async fn check_stale_runs(db: &dyn Database) -> Result<(), Box<dyn std::error::Error>> {
-    // Hard timeout for runs stuck in Running: 90 minutes
-    let running_timeout = chrono::Duration::minutes(90);
-    let running_threshold = Utc::now() - running_timeout;
+async fn check_stale_runs(
+    db: &dyn Database,
+    config: &WatchdogConfig,
+    now: DateTime<Utc>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Fetch against the shortest limit, then hold each run to its action's
+    let running_threshold = now - config.shortest_running_timeout();
     let stale_running = db.find_stale_running_runs(running_threshold).await?;
 
     for run in stale_running {
+        let running_timeout = config.running_timeout(run.action);
+        if now - run.started_at < running_timeout {
+            continue;
+        }
         warn!(
             "watchdog: timing out stale run {} (action={}, started_at={})",
             run.id, run.action, run.started_at
@@ -48,9 +217,7 @@ async fn check_stale_runs(db: &dyn Database) -> Result<(), Box<dyn std::error::E
         .await?;
     }
 
-    // Hard timeout for runs stuck in Salvaging: 30 minutes
-    let salvage_timeout = chrono::Duration::minutes(30);
-    let salvage_threshold = Utc::now() - salvage_timeout;
+    let salvage_threshold = now - chrono::Duration::minutes(config.salvage_timeout_mins as i64);
     let stale_salvaging = db.find_stale_salvaging_runs(salvage_threshold).await?;
 
     for run in stale_salvaging {
@@ -74,14 +241,16 @@ mod tests {
     async fn check_stale_runs_empty_db() {
         let db = Arc::new(flowstate_db::SqliteDatabase::open_in_memory().unwrap());
         // Should complete without errors on an empty database
-        check_stale_runs(&*db).await.unwrap();
+        check_stale_runs(&*db, &WatchdogConfig::default(), Utc::now())
+            .await
+            .unwrap();
     }
 
     #[tokio::test]
     async fn check_recent_runs_untouched() {
         use flowstate_core::claude_run::{ClaudeAction, ClaudeRunStatus, CreateClaudeRun};
         use flowstate_core::project::CreateProject;
-        use flowstate_core::task::{CreateTask, Priority, Status};
+        use flowstate_core::task::{CreateTask, Priority, Status, TaskType};
 
         let db = Arc::new(flowstate_db::SqliteDatabase::open_in_memory().unwrap());
 
@@ -102,6 +271,7 @@ mod tests {
                 description: String::new(),
                 status: Status::Todo,
                 priority: Priority::Medium,
+                task_type: TaskType::Feature,
                 parent_id: None,
                 reviewer: String::new(),
                 research_capability: None,
@@ -109,6 +279,7 @@ mod tests {
                 plan_capability: None,
                 build_capability: None,
                 verify_capability: None,
+                due_at: None,
             })
             .await
             .unwrap();
@@ -118,6 +289,11 @@ mod tests {
                 task_id: task.id.clone(),
                 action: ClaudeAction::Research,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .await
             .unwrap();
@@ -126,7 +302,9 @@ mod tests {
         let _ = db.claim_next_claude_run(&[]).await.unwrap();
 
         // Run watchdog check — recent run should NOT be timed out
-        check_stale_runs(&*db).await.unwrap();
+        check_stale_runs(&*db, &WatchdogConfig::default(), Utc::now())
+            .await
+            .unwrap();
 
         // Verify the run is still running
         let updated = db.get_claude_run(&run.id).await.unwrap();
@@ -137,7 +315,7 @@ mod tests {
     async fn check_stale_running_run_timed_out() {
         use flowstate_core::claude_run::{ClaudeAction, ClaudeRunStatus, CreateClaudeRun};
         use flowstate_core::project::CreateProject;
-        use flowstate_core::task::{CreateTask, Priority, Status};
+        use flowstate_core::task::{CreateTask, Priority, Status, TaskType};
 
         let db = Arc::new(flowstate_db::SqliteDatabase::open_in_memory().unwrap());
 
@@ -158,6 +336,7 @@ mod tests {
                 description: String::new(),
                 status: Status::Todo,
                 priority: Priority::Medium,
+                task_type: TaskType::Feature,
                 parent_id: None,
                 reviewer: String::new(),
                 research_capability: None,
@@ -165,6 +344,7 @@ mod tests {
                 plan_capability: None,
                 build_capability: None,
                 verify_capability: None,
+                due_at: None,
             })
             .await
             .unwrap();
@@ -174,6 +354,11 @@ mod tests {
                 task_id: task.id.clone(),
                 action: ClaudeAction::Build,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .await
             .unwrap();
@@ -206,7 +391,7 @@ mod tests {
     async fn check_stale_salvaging_run_timed_out() {
         use flowstate_core::claude_run::{ClaudeAction, ClaudeRunStatus, CreateClaudeRun};
         use flowstate_core::project::CreateProject;
-        use flowstate_core::task::{CreateTask, Priority, Status};
+        use flowstate_core::task::{CreateTask, Priority, Status, TaskType};
 
         let db = Arc::new(flowstate_db::SqliteDatabase::open_in_memory().unwrap());
 
@@ -227,6 +412,7 @@ mod tests {
                 description: String::new(),
                 status: Status::Todo,
                 priority: Priority::Medium,
+                task_type: TaskType::Feature,
                 parent_id: None,
                 reviewer: String::new(),
                 research_capability: None,
@@ -234,6 +420,7 @@ mod tests {
                 plan_capability: None,
                 build_capability: None,
                 verify_capability: None,
+                due_at: None,
             })
             .await
             .unwrap();
@@ -243,6 +430,11 @@ mod tests {
                 task_id: task.id.clone(),
                 action: ClaudeAction::Build,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .await
             .unwrap();
@@ -282,7 +474,7 @@ mod tests {
     async fn check_stale_runs_times_out_old_running_and_salvaging() {
         use flowstate_core::claude_run::{ClaudeAction, ClaudeRunStatus, CreateClaudeRun};
         use flowstate_core::project::CreateProject;
-        use flowstate_core::task::{CreateTask, Priority, Status};
+        use flowstate_core::task::{CreateTask, Priority, Status, TaskType};
         use flowstate_db::Database;
 
         let db = Arc::new(flowstate_db::SqliteDatabase::open_in_memory().unwrap());
@@ -305,6 +497,7 @@ mod tests {
                 description: String::new(),
                 status: Status::Todo,
                 priority: Priority::Medium,
+                task_type: TaskType::Feature,
                 parent_id: None,
                 reviewer: String::new(),
                 research_capability: None,
@@ -312,6 +505,7 @@ mod tests {
                 plan_capability: None,
                 build_capability: None,
                 verify_capability: None,
+                due_at: None,
             })
             .await
             .unwrap();
@@ -322,6 +516,11 @@ mod tests {
                 task_id: task.id.clone(),
                 action: ClaudeAction::Build,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .await
             .unwrap();
@@ -333,6 +532,11 @@ mod tests {
                 task_id: task.id.clone(),
                 action: ClaudeAction::Build,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .await
             .unwrap();
@@ -376,7 +580,7 @@ mod tests {
     async fn timeout_already_timed_out_run_returns_none() {
         use flowstate_core::claude_run::{ClaudeAction, CreateClaudeRun};
         use flowstate_core::project::CreateProject;
-        use flowstate_core::task::{CreateTask, Priority, Status};
+        use flowstate_core::task::{CreateTask, Priority, Status, TaskType};
 
         let db = Arc::new(flowstate_db::SqliteDatabase::open_in_memory().unwrap());
 
@@ -397,6 +601,7 @@ mod tests {
                 description: String::new(),
                 status: Status::Todo,
                 priority: Priority::Medium,
+                task_type: TaskType::Feature,
                 parent_id: None,
                 reviewer: String::new(),
                 research_capability: None,
@@ -404,6 +609,7 @@ mod tests {
                 plan_capability: None,
                 build_capability: None,
                 verify_capability: None,
+                due_at: None,
             })
             .await
             .unwrap();
@@ -413,6 +619,11 @@ mod tests {
                 task_id: task.id.clone(),
                 action: ClaudeAction::Build,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .await
             .unwrap();
@@ -434,7 +645,7 @@ mod tests {
     async fn check_stale_runs_finds_no_stale() {
         use flowstate_core::claude_run::{ClaudeAction, CreateClaudeRun};
         use flowstate_core::project::CreateProject;
-        use flowstate_core::task::{CreateTask, Priority, Status};
+        use flowstate_core::task::{CreateTask, Priority, Status, TaskType};
 
         let db = Arc::new(flowstate_db::SqliteDatabase::open_in_memory().unwrap());
 
@@ -455,6 +666,7 @@ mod tests {
                 description: String::new(),
                 status: Status::Todo,
                 priority: Priority::Medium,
+                task_type: TaskType::Feature,
                 parent_id: None,
                 reviewer: String::new(),
                 research_capability: None,
@@ -462,6 +674,7 @@ mod tests {
                 plan_capability: None,
                 build_capability: None,
                 verify_capability: None,
+                due_at: None,
             })
             .await
             .unwrap();
@@ -472,11 +685,169 @@ mod tests {
                 task_id: task.id.clone(),
                 action: ClaudeAction::Research,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .await
             .unwrap();
 
         // Run watchdog check — queued runs are not stale
-        check_stale_runs(&*db).await.unwrap();
+        check_stale_runs(&*db, &WatchdogConfig::default(), Utc::now())
+            .await
+            .unwrap();
+    }
+
+    fn getter(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: Vec<(String, String)> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |key| vars.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone())
+    }
+
+    #[test]
+    fn config_from_env_values() {
+        assert_eq!(
+            WatchdogConfig::from_getter(getter(&[])).unwrap(),
+            WatchdogConfig::default()
+        );
+        let config = WatchdogConfig::from_getter(getter(&[
+            ("FLOWSTATE_WATCHDOG_INTERVAL_SECS", "15"),
+            (
+                "FLOWSTATE_WATCHDOG_ACTION_TIMEOUTS",
+                "research=20, build=240",
+            ),
+        ]))
+        .unwrap();
+        assert_eq!(config.interval_secs, 15);
+        assert_eq!(
+            config.running_timeout(ClaudeAction::Research),
+            chrono::Duration::minutes(20)
+        );
+        assert_eq!(
+            config.running_timeout(ClaudeAction::Plan),
+            chrono::Duration::minutes(90)
+        );
+        for bad in [
+            ("FLOWSTATE_WATCHDOG_INTERVAL_SECS", "0"),
+            ("FLOWSTATE_WATCHDOG_SALVAGE_TIMEOUT_MINS", "soon"),
+            ("FLOWSTATE_WATCHDOG_ACTION_TIMEOUTS", "deploy=10"),
+            ("FLOWSTATE_WATCHDOG_ACTION_TIMEOUTS", "build"),
+        ] {
+            assert!(
+                WatchdogConfig::from_getter(getter(&[bad])).is_err(),
+                "{bad:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn patch_is_all_or_nothing() {
+        let mut config = WatchdogConfig::default();
+        let err = config
+            .apply(WatchdogPatch {
+                running_timeout_mins: Some(120),
+                action_timeout_mins: Some([("build".to_string(), Some(0))].into()),
+                ..Default::default()
+            })
+            .unwrap_err();
+        assert!(err.contains("build"));
+        assert_eq!(config, WatchdogConfig::default());
+
+        config
+            .apply(WatchdogPatch {
+                action_timeout_mins: Some([("build".to_string(), Some(180))].into()),
+                ..Default::default()
+            })
+            .unwrap();
+        config
+            .apply(WatchdogPatch {
+                action_timeout_mins: Some([("build".to_string(), None)].into()),
+                salvage_timeout_mins: Some(45),
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(config.action_timeout_mins.is_empty());
+        assert_eq!(config.salvage_timeout_mins, 45);
+    }
+
+    #[tokio::test]
+    async fn check_stale_runs_uses_per_action_timeouts() {
+        use flowstate_core::claude_run::{ClaudeRunStatus, CreateClaudeRun};
+        use flowstate_core::project::CreateProject;
+        use flowstate_core::task::{CreateTask, Priority, Status, TaskType};
+
+        let db = Arc::new(flowstate_db::SqliteDatabase::open_in_memory().unwrap());
+        let project = db
+            .create_project(&CreateProject {
+                name: "WD Actions".into(),
+                slug: "wd-actions".into(),
+                description: String::new(),
+                repo_url: String::new(),
+            })
+            .await
+            .unwrap();
+        let task = db
+            .create_task(&CreateTask {
+                project_id: project.id.clone(),
+                title: "WD Actions Task".into(),
+                description: String::new(),
+                status: Status::Todo,
+                priority: Priority::Medium,
+                task_type: TaskType::Feature,
+                parent_id: None,
+                reviewer: String::new(),
+                research_capability: None,
+                design_capability: None,
+                plan_capability: None,
+                build_capability: None,
+                verify_capability: None,
+                due_at: None,
+            })
+            .await
+            .unwrap();
+        let mut runs = Vec::new();
+        for action in [ClaudeAction::Research, ClaudeAction::Build] {
+            let run = db
+                .create_claude_run(&CreateClaudeRun {
+                    task_id: task.id.clone(),
+                    action,
+                    required_capability: None,
+                    priority: 0,
+                    feedback: None,
+                    idempotency_key: None,
+                    run_window: None,
+                    trace_context: None,
+                })
+                .await
+                .unwrap();
+            db.claim_next_claude_run(&[]).await.unwrap().unwrap();
+            runs.push(run.id);
+        }
+
+        let mut config = WatchdogConfig::default();
+        config
+            .apply(WatchdogPatch {
+                action_timeout_mins: Some([("research".to_string(), Some(30))].into()),
+                ..Default::default()
+            })
+            .unwrap();
+
+        // An hour on, research is past its 30 minutes but build is within 90
+        let later = Utc::now() + chrono::Duration::minutes(60);
+        check_stale_runs(&*db, &config, later).await.unwrap();
+        let research = db.get_claude_run(&runs[0]).await.unwrap();
+        assert_eq!(research.status, ClaudeRunStatus::TimedOut);
+        assert!(research.error_message.unwrap().contains(">30min"));
+        let build = db.get_claude_run(&runs[1]).await.unwrap();
+        assert_eq!(build.status, ClaudeRunStatus::Running);
+
+        let later = Utc::now() + chrono::Duration::minutes(100);
+        check_stale_runs(&*db, &config, later).await.unwrap();
+        let build = db.get_claude_run(&runs[1]).await.unwrap();
+        assert_eq!(build.status, ClaudeRunStatus::TimedOut);
     }
 }
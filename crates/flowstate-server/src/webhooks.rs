@@ -0,0 +1,387 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use aes_gcm::{Aes256Gcm, Key};
+use chrono::Utc;
+use flowstate_core::claude_run::ClaudeRun;
+use flowstate_core::task::{ApprovalStatus, Task};
+use flowstate_core::webhook::{DeliveryAttempt, DeliveryStatus, WebhookDelivery, WebhookEvent};
+use flowstate_db::{Database, DbError};
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, warn};
+
+use crate::crypto;
+use crate::routes::AppState;
+use crate::shutdown;
+
+/// How often the worker looks for due deliveries.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Deliveries taken per poll.
+const BATCH_SIZE: i64 = 20;
+
+/// How long a claimed delivery is held before another worker may retry it,
+/// in case this one dies mid-send. Longer than `REQUEST_TIMEOUT`.
+const LEASE: chrono::Duration = chrono::Duration::minutes(2);
+
+/// Time allowed for a receiver to answer.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Attempts before a delivery is given up on and marked failed.
+pub const MAX_ATTEMPTS: i32 = 8;
+
+/// Delivered and failed deliveries are kept this long for inspection.
+const DELIVERY_RETENTION: chrono::Duration = chrono::Duration::days(7);
+
+/// Queue `event` for every enabled webhook that wants it. The body sent is
+/// `{"event", "project_id", "occurred_at", "data"}`. Failures are logged and
+/// never fail the request that caused the event.
+pub(crate) async fn fire(state: &AppState, event: WebhookEvent, project_id: &str, data: Value) {
+    let webhooks = match state.db.list_webhooks().await {
+        Ok(webhooks) => webhooks,
+        Err(e) => {
+            warn!("webhooks: listing hooks for {event}: {e}");
+            return;
+        }
+    };
+    let mut targets = webhooks.iter().filter(|w| w.wants(event, project_id));
+    let Some(first) = targets.next() else {
+        return;
+    };
+    let payload = json!({
+        "event": event,
+        "project_id": project_id,
+        "occurred_at": Utc::now(),
+        "data": data,
+    })
+    .to_string();
+    for webhook in std::iter::once(first).chain(targets) {
+        if let Err(e) = state
+            .db
+            .enqueue_webhook_delivery(&webhook.id, event, &payload)
+            .await
+        {
+            warn!("webhooks: queueing {event} for {}: {e}", webhook.id);
+        }
+    }
+}
+
+/// Fire `task_status_changed` and `task_approved` for whatever changed
+/// between `before` and `after`.
+pub(crate) async fn task_changed(state: &AppState, before: &Task, after: &Task) {
+    if before.status != after.status {
+        let data = json!({ "task": after, "previous_status": before.status });
+        fire(
+            state,
+            WebhookEvent::TaskStatusChanged,
+            &after.project_id,
+            data,
+        )
+        .await;
+    }
+    let stages = [
+        ("research", before.research_status, after.research_status),
+        ("spec", before.spec_status, after.spec_status),
+        ("plan", before.plan_status, after.plan_status),
+        ("verify", before.verify_status, after.verify_status),
+    ];
+    for (stage, was, now) in stages {
+        if now == ApprovalStatus::Approved && was != ApprovalStatus::Approved {
+            let data = json!({ "task": after, "stage": stage });
+            fire(state, WebhookEvent::TaskApproved, &after.project_id, data).await;
+        }
+    }
+}
+
+/// Fire `run_finished` for a run that has just ended.
+pub(crate) async fn run_finished(state: &AppState, run: &ClaudeRun) {
+    let task = match state.db.get_task(&run.task_id).await {
+        Ok(task) => task,
+        Err(e) => {
+            warn!("webhooks: loading task of run {}: {e}", run.id);
+            return;
+        }
+    };
+    let data = json!({ "run": run, "task": task });
+    fire(state, WebhookEvent::RunFinished, &task.project_id, data).await;
+}
+
+/// `sha256=<hex>` HMAC of `body` under `secret`, sent as
+/// `X-Flowstate-Signature`.
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(body);
+    let digest = mac.finalize().into_bytes();
+    let hex: String = digest.iter().map(|b| format!("{b:02x}")).collect();
+    format!("sha256={hex}")
+}
+
+/// Wait before retrying after `attempts` failures: 30s doubling each time,
+/// capped at an hour.
+fn retry_delay(attempts: i32) -> chrono::Duration {
+    let exponent = attempts.clamp(1, 8) - 1;
+    (chrono::Duration::seconds(30) * 2i32.pow(exponent as u32)).min(chrono::Duration::hours(1))
+}
+
+/// Background task that sends queued deliveries and prunes old ones.
+pub async fn run_webhook_worker(
+    db: Arc<dyn Database>,
+    key: Key<Aes256Gcm>,
+    shutdown: CancellationToken,
+) {
+    let client = match reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => {
+            error!("webhooks: building http client: {e}");
+            return;
+        }
+    };
+    let mut ticker = tokio::time::interval(POLL_INTERVAL);
+    let mut last_prune = tokio::time::Instant::now();
+    while shutdown::next_tick(&mut ticker, &shutdown).await {
+        // Keep draining while full batches come back
+        loop {
+            match deliver_due(&*db, &client, &key).await {
+                Ok(n) if n as i64 == BATCH_SIZE => continue,
+                Ok(_) => break,
+                Err(e) => {
+                    error!("webhooks: claiming deliveries: {e}");
+                    break;
+                }
+            }
+        }
+        if last_prune.elapsed() >= Duration::from_secs(3600) {
+            last_prune = tokio::time::Instant::now();
+            match db
+                .prune_webhook_deliveries(Utc::now() - DELIVERY_RETENTION)
+                .await
+            {
+                Ok(0) => {}
+                Ok(n) => debug!("webhooks: pruned {n} old deliveries"),
+                Err(e) => warn!("webhooks: pruning deliveries: {e}"),
+            }
+        }
+    }
+}
+
+/// Send every due delivery once and record how it went. Returns how many
+/// were attempted.
+pub async fn deliver_due(
+    db: &dyn Database,
+    client: &reqwest::Client,
+    key: &Key<Aes256Gcm>,
+) -> Result<usize, DbError> {
+    let due = db
+        .claim_webhook_deliveries(Utc::now() + LEASE, BATCH_SIZE)
+        .await?;
+    for delivery in &due {
+        let attempt = attempt(db, client, key, delivery).await;
+        if attempt.status == DeliveryStatus::Failed {
+            warn!(
+                "webhooks: giving up on delivery {} after {} attempts: {}",
+                delivery.id,
+                delivery.attempts + 1,
+                attempt.error.as_deref().unwrap_or("unknown error")
+            );
+        }
+        if let Err(e) = db.record_webhook_attempt(&delivery.id, &attempt).await {
+            warn!("webhooks: recording delivery {}: {e}", delivery.id);
+        }
+    }
+    Ok(due.len())
+}
+
+async fn attempt(
+    db: &dyn Database,
+    client: &reqwest::Client,
+    key: &Key<Aes256Gcm>,
+    delivery: &WebhookDelivery,
+) -> DeliveryAttempt {
+    let failed = |response_status: Option<i32>, error: String| {
+        let attempts = delivery.attempts + 1;
+        DeliveryAttempt {
+            status: if attempts >= MAX_ATTEMPTS {
+                DeliveryStatus::Failed
+            } else {
+                DeliveryStatus::Pending
+            },
+            response_status,
+            error: Some(error),
+            next_attempt_at: Utc::now() + retry_delay(attempts),
+        }
+    };
+
+    let webhook = match db.get_webhook(&delivery.webhook_id).await {
+        Ok(webhook) => webhook,
+        Err(e) => return failed(None, format!("loading webhook: {e}")),
+    };
+    if !webhook.enabled {
+        return DeliveryAttempt {
+            status: DeliveryStatus::Failed,
+            response_status: None,
+            error: Some("webhook disabled".into()),
+            next_attempt_at: Utc::now(),
+        };
+    }
+    let secret = match crypto::decrypt(key, &webhook.secret) {
+        Ok(secret) => secret,
+        Err(e) => return failed(None, format!("decrypting secret: {e}")),
+    };
+
+    let result = client
+        .post(&webhook.url)
+        .header("content-type", "application/json")
+        .header("x-flowstate-event", delivery.event.as_str())
+        .header("x-flowstate-delivery", &delivery.id)
+        .header(
+            "x-flowstate-signature",
+            sign(&secret, delivery.payload.as_bytes()),
+        )
+        .body(delivery.payload.clone())
+        .send()
+        .await;
+    match result {
+        Ok(resp) if resp.status().is_success() => DeliveryAttempt {
+            status: DeliveryStatus::Delivered,
+            response_status: Some(resp.status().as_u16().into()),
+            error: None,
+            next_attempt_at: Utc::now(),
+        },
+        Ok(resp) => failed(
+            Some(resp.status().as_u16().into()),
+            format!("receiver answered {}", resp.status()),
+        ),
+        Err(e) => failed(None, e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::post;
+    use flowstate_core::webhook::CreateWebhook;
+    use tokio::sync::mpsc;
+
+    /// Receiver answering every POST with `status`, forwarding what it got.
+    async fn receiver(
+        status: StatusCode,
+    ) -> (String, mpsc::UnboundedReceiver<(HeaderMap, String)>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let app = axum::Router::new().route(
+            "/hook",
+            post(move |headers: HeaderMap, body: String| {
+                let tx = tx.clone();
+                async move {
+                    let _ = tx.send((headers, body));
+                    status
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (url, rx)
+    }
+
+    async fn setup(url: &str) -> (Arc<dyn Database>, Key<Aes256Gcm>, String) {
+        let db: Arc<dyn Database> =
+            Arc::new(flowstate_db::SqliteDatabase::open_in_memory().unwrap());
+        let key = Key::<Aes256Gcm>::from([7u8; 32]);
+        let webhook = db
+            .create_webhook(&CreateWebhook {
+                url: url.into(),
+                secret: crypto::encrypt(&key, "s3cret").unwrap(),
+                events: vec![],
+                project_id: None,
+            })
+            .await
+            .unwrap();
+        (db, key, webhook.id)
+    }
+
+    #[test]
+    fn signature_is_hmac_sha256_hex() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn retries_back_off_to_an_hour() {
+        assert_eq!(retry_delay(1), chrono::Duration::seconds(30));
+        assert_eq!(retry_delay(3), chrono::Duration::seconds(120));
+        assert_eq!(retry_delay(MAX_ATTEMPTS), chrono::Duration::hours(1));
+    }
+
+    #[tokio::test]
+    async fn delivers_signed_payloads() {
+        let (url, mut received) = receiver(StatusCode::NO_CONTENT).await;
+        let (db, key, webhook_id) = setup(&url).await;
+        let queued = db
+            .enqueue_webhook_delivery(&webhook_id, WebhookEvent::RunFinished, "{\"x\":1}")
+            .await
+            .unwrap();
+
+        let client = reqwest::Client::new();
+        assert_eq!(deliver_due(&*db, &client, &key).await.unwrap(), 1);
+        let (headers, body) = received.recv().await.unwrap();
+        assert_eq!(body, "{\"x\":1}");
+        assert_eq!(headers["x-flowstate-event"], "run_finished");
+        assert_eq!(headers["x-flowstate-delivery"], queued.id.as_str());
+        assert_eq!(
+            headers["x-flowstate-signature"],
+            sign("s3cret", body.as_bytes()).as_str()
+        );
+
+        let delivery = &db.list_webhook_deliveries(&webhook_id, 10).await.unwrap()[0];
+        assert_eq!(delivery.status, DeliveryStatus::Delivered);
+        assert_eq!(delivery.response_status, Some(204));
+        assert_eq!(deliver_due(&*db, &client, &key).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn failed_deliveries_retry_then_give_up() {
+        let (url, _received) = receiver(StatusCode::BAD_GATEWAY).await;
+        let (db, key, webhook_id) = setup(&url).await;
+        let queued = db
+            .enqueue_webhook_delivery(&webhook_id, WebhookEvent::TaskApproved, "{}")
+            .await
+            .unwrap();
+
+        let client = reqwest::Client::new();
+        deliver_due(&*db, &client, &key).await.unwrap();
+        let delivery = &db.list_webhook_deliveries(&webhook_id, 10).await.unwrap()[0];
+        assert_eq!(delivery.status, DeliveryStatus::Pending);
+        assert_eq!(delivery.attempts, 1);
+        assert_eq!(delivery.response_status, Some(502));
+        assert!(delivery.next_attempt_at > Utc::now());
+        // Not due again until the backoff passes
+        assert_eq!(deliver_due(&*db, &client, &key).await.unwrap(), 0);
+
+        // On the last allowed attempt the delivery is marked failed
+        for _ in 1..MAX_ATTEMPTS - 1 {
+            db.record_webhook_attempt(
+                &queued.id,
+                &DeliveryAttempt {
+                    status: DeliveryStatus::Pending,
+                    response_status: Some(502),
+                    error: None,
+                    next_attempt_at: Utc::now() - chrono::Duration::seconds(1),
+                },
+            )
+            .await
+            .unwrap();
+        }
+        assert_eq!(deliver_due(&*db, &client, &key).await.unwrap(), 1);
+        let delivery = &db.list_webhook_deliveries(&webhook_id, 10).await.unwrap()[0];
+        assert_eq!(delivery.status, DeliveryStatus::Failed);
+        assert_eq!(delivery.attempts, MAX_ATTEMPTS);
+    }
+}
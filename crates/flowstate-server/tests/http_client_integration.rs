@@ -88,6 +88,7 @@ async fn task_crud_via_http() {
             description: "desc".into(),
             status: flowstate_core::task::Status::Todo,
             priority: flowstate_core::task::Priority::Medium,
+            task_type: flowstate_core::task::TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -95,6 +96,7 @@ async fn task_crud_via_http() {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .await
         .unwrap();
@@ -159,6 +161,7 @@ async fn task_list_with_filters_via_http() {
         description: String::new(),
         status: flowstate_core::task::Status::Todo,
         priority: flowstate_core::task::Priority::High,
+        task_type: flowstate_core::task::TaskType::Feature,
         parent_id: None,
         reviewer: String::new(),
         research_capability: None,
@@ -166,6 +169,7 @@ async fn task_list_with_filters_via_http() {
         plan_capability: None,
         build_capability: None,
         verify_capability: None,
+        due_at: None,
     })
     .await
     .unwrap();
@@ -176,6 +180,7 @@ async fn task_list_with_filters_via_http() {
         description: String::new(),
         status: flowstate_core::task::Status::Done,
         priority: flowstate_core::task::Priority::Low,
+        task_type: flowstate_core::task::TaskType::Feature,
         parent_id: None,
         reviewer: String::new(),
         research_capability: None,
@@ -183,6 +188,7 @@ async fn task_list_with_filters_via_http() {
         plan_capability: None,
         build_capability: None,
         verify_capability: None,
+        due_at: None,
     })
     .await
     .unwrap();
@@ -281,6 +287,7 @@ async fn task_links_via_http() {
             description: String::new(),
             status: flowstate_core::task::Status::Todo,
             priority: flowstate_core::task::Priority::Medium,
+            task_type: flowstate_core::task::TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -288,6 +295,7 @@ async fn task_links_via_http() {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .await
         .unwrap();
@@ -299,6 +307,7 @@ async fn task_links_via_http() {
             description: String::new(),
             status: flowstate_core::task::Status::Todo,
             priority: flowstate_core::task::Priority::Medium,
+            task_type: flowstate_core::task::TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -306,6 +315,7 @@ async fn task_links_via_http() {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .await
         .unwrap();
@@ -344,6 +354,7 @@ async fn task_prs_via_http() {
             description: String::new(),
             status: flowstate_core::task::Status::Todo,
             priority: flowstate_core::task::Priority::Medium,
+            task_type: flowstate_core::task::TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -351,6 +362,7 @@ async fn task_prs_via_http() {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .await
         .unwrap();
@@ -363,6 +375,7 @@ async fn task_prs_via_http() {
             pr_url: "https://github.com/org/repo/pull/42".into(),
             pr_number: 42,
             branch_name: "flowstate/test".into(),
+            draft: false,
         })
         .await
         .unwrap();
@@ -387,6 +400,7 @@ async fn claude_run_lifecycle_via_http() {
             description: String::new(),
             status: flowstate_core::task::Status::Todo,
             priority: flowstate_core::task::Priority::Medium,
+            task_type: flowstate_core::task::TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -394,6 +408,7 @@ async fn claude_run_lifecycle_via_http() {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .await
         .unwrap();
@@ -428,6 +443,7 @@ async fn spec_plan_research_verification_roundtrip() {
             description: String::new(),
             status: flowstate_core::task::Status::Todo,
             priority: flowstate_core::task::Priority::Medium,
+            task_type: flowstate_core::task::TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -435,6 +451,7 @@ async fn spec_plan_research_verification_roundtrip() {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .await
         .unwrap();
@@ -482,6 +499,7 @@ async fn runner_registration_and_claim() {
             description: String::new(),
             status: flowstate_core::task::Status::Todo,
             priority: flowstate_core::task::Priority::Medium,
+            task_type: flowstate_core::task::TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -489,6 +507,7 @@ async fn runner_registration_and_claim() {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .await
         .unwrap();
@@ -565,6 +584,7 @@ async fn update_claude_run_pr_via_http() {
             description: String::new(),
             status: flowstate_core::task::Status::Todo,
             priority: flowstate_core::task::Priority::Medium,
+            task_type: flowstate_core::task::TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -572,6 +592,7 @@ async fn update_claude_run_pr_via_http() {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .await
         .unwrap();
@@ -634,6 +655,7 @@ async fn error_responses_via_http() {
             description: String::new(),
             status: flowstate_core::task::Status::Todo,
             priority: flowstate_core::task::Priority::Medium,
+            task_type: flowstate_core::task::TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -641,6 +663,7 @@ async fn error_responses_via_http() {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .await
         .unwrap();
@@ -685,6 +708,7 @@ async fn list_attachments_via_http() {
             description: String::new(),
             status: flowstate_core::task::Status::Todo,
             priority: flowstate_core::task::Priority::Medium,
+            task_type: flowstate_core::task::TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -692,6 +716,7 @@ async fn list_attachments_via_http() {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .await
         .unwrap();
@@ -774,6 +799,7 @@ fn blocking_task_crud() {
             description: "desc".into(),
             status: flowstate_core::task::Status::Todo,
             priority: flowstate_core::task::Priority::Medium,
+            task_type: flowstate_core::task::TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -781,6 +807,7 @@ fn blocking_task_crud() {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .unwrap();
 
@@ -866,6 +893,7 @@ fn blocking_task_links_and_prs() {
             description: String::new(),
             status: flowstate_core::task::Status::Todo,
             priority: flowstate_core::task::Priority::Medium,
+            task_type: flowstate_core::task::TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -873,6 +901,7 @@ fn blocking_task_links_and_prs() {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .unwrap();
 
@@ -883,6 +912,7 @@ fn blocking_task_links_and_prs() {
             description: String::new(),
             status: flowstate_core::task::Status::Todo,
             priority: flowstate_core::task::Priority::Medium,
+            task_type: flowstate_core::task::TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -890,6 +920,7 @@ fn blocking_task_links_and_prs() {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .unwrap();
 
@@ -915,6 +946,7 @@ fn blocking_task_links_and_prs() {
             pr_url: "https://github.com/org/repo/pull/1".into(),
             pr_number: 1,
             branch_name: "flowstate/test".into(),
+            draft: false,
         })
         .unwrap();
     assert_eq!(pr.pr_number, 1);
@@ -936,6 +968,7 @@ fn blocking_claude_run_and_content() {
             description: String::new(),
             status: flowstate_core::task::Status::Todo,
             priority: flowstate_core::task::Priority::Medium,
+            task_type: flowstate_core::task::TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -943,6 +976,7 @@ fn blocking_claude_run_and_content() {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .unwrap();
 
@@ -1013,6 +1047,7 @@ fn blocking_claude_run_output_not_found() {
             description: String::new(),
             status: flowstate_core::task::Status::Todo,
             priority: flowstate_core::task::Priority::Medium,
+            task_type: flowstate_core::task::TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -1020,6 +1055,7 @@ fn blocking_claude_run_output_not_found() {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .unwrap();
 
@@ -1,10 +1,20 @@
 use flowstate_core::attachment::Attachment;
+use flowstate_core::board::ProjectLane;
 use flowstate_core::claude_run::{ClaudeRun, CreateClaudeRun};
+use flowstate_core::custom_field::{
+    CreateCustomField, CustomField, TaskFieldValue, UpdateCustomField,
+};
+use flowstate_core::dashboard::ProjectDashboard;
+use flowstate_core::epic::{CreateEpic, Epic, UpdateEpic};
+use flowstate_core::feedback::FeedbackEntry;
+use flowstate_core::notification::Notification;
 use flowstate_core::project::{CreateProject, Project, UpdateProject};
+use flowstate_core::saved_filter::{CreateSavedFilter, SavedFilter, UpdateSavedFilter};
 use flowstate_core::sprint::{CreateSprint, Sprint, UpdateSprint};
-use flowstate_core::task::{CreateTask, Task, TaskFilter, UpdateTask};
+use flowstate_core::task::{CreateTask, Task, TaskFeedback, TaskFilter, UpdateTask};
 use flowstate_core::task_link::{CreateTaskLink, TaskLink};
 use flowstate_core::task_pr::{CreateTaskPr, TaskPr};
+use flowstate_core::user::{CreateUser, UpdateUser, User};
 use tokio::runtime::Runtime;
 
 use crate::{HttpService, ServiceError, TaskService};
@@ -67,6 +77,10 @@ impl BlockingHttpService {
         self.rt.block_on(self.inner.delete_project(id))
     }
 
+    pub fn project_dashboard(&self, id: &str) -> Result<ProjectDashboard, ServiceError> {
+        self.rt.block_on(self.inner.project_dashboard(id))
+    }
+
     pub fn list_tasks(&self, filter: &TaskFilter) -> Result<Vec<Task>, ServiceError> {
         self.rt.block_on(self.inner.list_tasks(filter))
     }
@@ -83,10 +97,30 @@ impl BlockingHttpService {
         self.rt.block_on(self.inner.update_task(id, update))
     }
 
+    pub fn bulk_create_tasks(&self, inputs: &[CreateTask]) -> Result<Vec<Task>, ServiceError> {
+        self.rt.block_on(self.inner.bulk_create_tasks(inputs))
+    }
+
+    pub fn bulk_update_tasks(
+        &self,
+        ids: &[String],
+        update: &UpdateTask,
+    ) -> Result<Vec<Task>, ServiceError> {
+        self.rt.block_on(self.inner.bulk_update_tasks(ids, update))
+    }
+
+    pub fn reorder_task(&self, id: &str, after_id: Option<&str>) -> Result<Task, ServiceError> {
+        self.rt.block_on(self.inner.reorder_task(id, after_id))
+    }
+
     pub fn delete_task(&self, id: &str) -> Result<(), ServiceError> {
         self.rt.block_on(self.inner.delete_task(id))
     }
 
+    pub fn archive_task(&self, id: &str) -> Result<Task, ServiceError> {
+        self.rt.block_on(self.inner.archive_task(id))
+    }
+
     pub fn count_tasks_by_status(
         &self,
         project_id: &str,
@@ -95,10 +129,18 @@ impl BlockingHttpService {
             .block_on(self.inner.count_tasks_by_status(project_id))
     }
 
+    pub fn rollup_board(&self, project_ids: &[String]) -> Result<Vec<ProjectLane>, ServiceError> {
+        self.rt.block_on(self.inner.rollup_board(project_ids))
+    }
+
     pub fn list_child_tasks(&self, parent_id: &str) -> Result<Vec<Task>, ServiceError> {
         self.rt.block_on(self.inner.list_child_tasks(parent_id))
     }
 
+    pub fn list_feedback_history(&self, task_id: &str) -> Result<Vec<FeedbackEntry>, ServiceError> {
+        self.rt.block_on(self.inner.list_feedback_history(task_id))
+    }
+
     pub fn create_sprint(&self, input: &CreateSprint) -> Result<Sprint, ServiceError> {
         self.rt.block_on(self.inner.create_sprint(input))
     }
@@ -119,6 +161,141 @@ impl BlockingHttpService {
         self.rt.block_on(self.inner.delete_sprint(id))
     }
 
+    pub fn archive_sprint(&self, id: &str) -> Result<Sprint, ServiceError> {
+        self.rt.block_on(self.inner.archive_sprint(id))
+    }
+
+    pub fn create_epic(&self, input: &CreateEpic) -> Result<Epic, ServiceError> {
+        self.rt.block_on(self.inner.create_epic(input))
+    }
+
+    pub fn get_epic(&self, id: &str) -> Result<Epic, ServiceError> {
+        self.rt.block_on(self.inner.get_epic(id))
+    }
+
+    pub fn list_epics(&self, project_id: &str) -> Result<Vec<Epic>, ServiceError> {
+        self.rt.block_on(self.inner.list_epics(project_id))
+    }
+
+    pub fn update_epic(&self, id: &str, update: &UpdateEpic) -> Result<Epic, ServiceError> {
+        self.rt.block_on(self.inner.update_epic(id, update))
+    }
+
+    pub fn delete_epic(&self, id: &str) -> Result<(), ServiceError> {
+        self.rt.block_on(self.inner.delete_epic(id))
+    }
+
+    pub fn create_user(&self, input: &CreateUser) -> Result<User, ServiceError> {
+        self.rt.block_on(self.inner.create_user(input))
+    }
+
+    pub fn get_user(&self, id: &str) -> Result<User, ServiceError> {
+        self.rt.block_on(self.inner.get_user(id))
+    }
+
+    pub fn list_users(&self) -> Result<Vec<User>, ServiceError> {
+        self.rt.block_on(self.inner.list_users())
+    }
+
+    pub fn update_user(&self, id: &str, update: &UpdateUser) -> Result<User, ServiceError> {
+        self.rt.block_on(self.inner.update_user(id, update))
+    }
+
+    pub fn delete_user(&self, id: &str) -> Result<(), ServiceError> {
+        self.rt.block_on(self.inner.delete_user(id))
+    }
+
+    pub fn create_saved_filter(
+        &self,
+        input: &CreateSavedFilter,
+    ) -> Result<SavedFilter, ServiceError> {
+        self.rt.block_on(self.inner.create_saved_filter(input))
+    }
+
+    pub fn get_saved_filter(&self, id: &str) -> Result<SavedFilter, ServiceError> {
+        self.rt.block_on(self.inner.get_saved_filter(id))
+    }
+
+    pub fn list_saved_filters(
+        &self,
+        project_id: &str,
+        user_id: Option<&str>,
+    ) -> Result<Vec<SavedFilter>, ServiceError> {
+        self.rt
+            .block_on(self.inner.list_saved_filters(project_id, user_id))
+    }
+
+    pub fn update_saved_filter(
+        &self,
+        id: &str,
+        update: &UpdateSavedFilter,
+    ) -> Result<SavedFilter, ServiceError> {
+        self.rt.block_on(self.inner.update_saved_filter(id, update))
+    }
+
+    pub fn delete_saved_filter(&self, id: &str) -> Result<(), ServiceError> {
+        self.rt.block_on(self.inner.delete_saved_filter(id))
+    }
+
+    pub fn watch_task(&self, task_id: &str, user_id: &str) -> Result<Vec<User>, ServiceError> {
+        self.rt.block_on(self.inner.watch_task(task_id, user_id))
+    }
+
+    pub fn unwatch_task(&self, task_id: &str, user_id: &str) -> Result<(), ServiceError> {
+        self.rt.block_on(self.inner.unwatch_task(task_id, user_id))
+    }
+
+    pub fn list_task_watchers(&self, task_id: &str) -> Result<Vec<User>, ServiceError> {
+        self.rt.block_on(self.inner.list_task_watchers(task_id))
+    }
+
+    pub fn list_notifications(
+        &self,
+        user_id: &str,
+        unread_only: bool,
+    ) -> Result<Vec<Notification>, ServiceError> {
+        self.rt
+            .block_on(self.inner.list_notifications(user_id, unread_only))
+    }
+
+    pub fn mark_notification_read(&self, id: &str) -> Result<Notification, ServiceError> {
+        self.rt.block_on(self.inner.mark_notification_read(id))
+    }
+
+    pub fn create_custom_field(
+        &self,
+        input: &CreateCustomField,
+    ) -> Result<CustomField, ServiceError> {
+        self.rt.block_on(self.inner.create_custom_field(input))
+    }
+
+    pub fn get_custom_field(&self, id: &str) -> Result<CustomField, ServiceError> {
+        self.rt.block_on(self.inner.get_custom_field(id))
+    }
+
+    pub fn list_custom_fields(&self, project_id: &str) -> Result<Vec<CustomField>, ServiceError> {
+        self.rt.block_on(self.inner.list_custom_fields(project_id))
+    }
+
+    pub fn update_custom_field(
+        &self,
+        id: &str,
+        update: &UpdateCustomField,
+    ) -> Result<CustomField, ServiceError> {
+        self.rt.block_on(self.inner.update_custom_field(id, update))
+    }
+
+    pub fn delete_custom_field(&self, id: &str) -> Result<(), ServiceError> {
+        self.rt.block_on(self.inner.delete_custom_field(id))
+    }
+
+    pub fn list_task_field_values(
+        &self,
+        task_id: &str,
+    ) -> Result<Vec<TaskFieldValue>, ServiceError> {
+        self.rt.block_on(self.inner.list_task_field_values(task_id))
+    }
+
     pub fn create_task_link(&self, input: &CreateTaskLink) -> Result<TaskLink, ServiceError> {
         self.rt.block_on(self.inner.create_task_link(input))
     }
@@ -166,6 +343,26 @@ impl BlockingHttpService {
             .block_on(self.inner.trigger_claude_run(task_id, action))
     }
 
+    pub fn trigger_claude_run_idempotent(
+        &self,
+        task_id: &str,
+        action: &str,
+        key: &str,
+    ) -> Result<ClaudeRun, ServiceError> {
+        self.rt.block_on(
+            self.inner
+                .trigger_claude_run_idempotent(task_id, action, key),
+        )
+    }
+
+    pub fn submit_feedback(
+        &self,
+        task_id: &str,
+        input: &TaskFeedback,
+    ) -> Result<Option<ClaudeRun>, ServiceError> {
+        self.rt.block_on(self.inner.submit_feedback(task_id, input))
+    }
+
     pub fn get_claude_run_output(&self, run_id: &str) -> Result<String, ServiceError> {
         self.rt.block_on(self.inner.get_claude_run_output(run_id))
     }
@@ -222,6 +419,30 @@ impl BlockingHttpService {
     pub fn system_status(&self) -> Result<crate::SystemStatus, ServiceError> {
         self.rt.block_on(self.inner.system_status())
     }
+
+    pub fn db_stats(&self) -> Result<flowstate_db::DbStats, ServiceError> {
+        self.rt.block_on(self.inner.db_stats())
+    }
+
+    pub fn list_runners(&self) -> Result<Vec<flowstate_core::runner::RunnerRecord>, ServiceError> {
+        self.rt.block_on(self.inner.list_runners())
+    }
+
+    pub fn drain_runner(
+        &self,
+        runner_id: &str,
+    ) -> Result<flowstate_core::runner::RunnerRecord, ServiceError> {
+        self.rt.block_on(self.inner.drain_runner(runner_id))
+    }
+
+    pub fn update_runner_config(
+        &self,
+        runner_id: &str,
+        config: &crate::PendingConfigResponse,
+    ) -> Result<flowstate_core::runner::RunnerRecord, ServiceError> {
+        self.rt
+            .block_on(self.inner.update_runner_config(runner_id, config))
+    }
 }
 
 #[cfg(test)]
@@ -229,7 +450,7 @@ mod tests {
     use super::*;
     use flowstate_core::project::CreateProject;
     use flowstate_core::sprint::CreateSprint;
-    use flowstate_core::task::{CreateTask, Priority, Status, TaskFilter, UpdateTask};
+    use flowstate_core::task::{CreateTask, Priority, Status, TaskFilter, TaskType, UpdateTask};
     use flowstate_core::task_link::{CreateTaskLink, LinkType};
     use flowstate_core::task_pr::CreateTaskPr;
 
@@ -267,6 +488,7 @@ mod tests {
             description: "A test task".into(),
             status: Status::Todo,
             priority: Priority::Medium,
+            task_type: TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -274,6 +496,7 @@ mod tests {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         }
     }
 
@@ -427,6 +650,7 @@ mod tests {
                 description: String::new(),
                 status: Status::Todo,
                 priority: Priority::Medium,
+                task_type: TaskType::Feature,
                 parent_id: None,
                 reviewer: String::new(),
                 research_capability: None,
@@ -434,6 +658,7 @@ mod tests {
                 plan_capability: None,
                 build_capability: None,
                 verify_capability: None,
+                due_at: None,
             })
             .unwrap();
 
@@ -470,6 +695,7 @@ mod tests {
                 pr_url: "https://github.com/org/repo/pull/7".into(),
                 pr_number: 7,
                 branch_name: "flowstate/blocking-test".into(),
+                draft: false,
             })
             .unwrap();
         assert_eq!(pr.pr_number, 7);
@@ -493,6 +719,11 @@ mod tests {
                 task_id: task.id.clone(),
                 action: flowstate_core::claude_run::ClaudeAction::Research,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .unwrap();
         assert_eq!(run.task_id, task.id);
@@ -1,28 +1,70 @@
+use std::time::Duration;
+
 use async_trait::async_trait;
+use chrono::SecondsFormat;
 use flowstate_core::attachment::Attachment;
-use flowstate_core::claude_run::{ClaudeRun, CreateClaudeRun};
+use flowstate_core::board::ProjectLane;
+use flowstate_core::claude_run::{ClaudeAction, ClaudeRun, ClaudeRunStatus, CreateClaudeRun};
+use flowstate_core::custom_field::{
+    CreateCustomField, CustomField, TaskFieldValue, UpdateCustomField,
+};
+use flowstate_core::dashboard::ProjectDashboard;
+use flowstate_core::epic::{CreateEpic, Epic, UpdateEpic};
+use flowstate_core::feedback::FeedbackEntry;
+use flowstate_core::notification::Notification;
 use flowstate_core::project::{CreateProject, Project, UpdateProject};
-use flowstate_core::sprint::{CreateSprint, Sprint, UpdateSprint};
-use flowstate_core::task::{CreateTask, Task, TaskFilter, UpdateTask};
+use flowstate_core::run_metrics::{RecordRunMetrics, RunMetrics};
+use flowstate_core::runner::{RunnerBenchmark, RunnerRecord};
+use flowstate_core::saved_filter::{CreateSavedFilter, SavedFilter, UpdateSavedFilter};
+use flowstate_core::scope::ScopeFinding;
+use flowstate_core::sprint::{CompleteSprint, CreateSprint, Sprint, SprintRollover, UpdateSprint};
+use flowstate_core::task::{
+    BulkUpdateTasks, CreateTask, ReorderTask, Task, TaskFeedback, TaskFilter, UpdateTask,
+};
 use flowstate_core::task_link::{CreateTaskLink, TaskLink};
 use flowstate_core::task_pr::{CreateTaskPr, TaskPr};
-use reqwest::{Client, RequestBuilder, StatusCode};
+use flowstate_core::user::{CreateUser, UpdateUser, User};
+use flowstate_db::{DbStats, MaintenanceReport};
+use reqwest::{Certificate, Client, Identity, RequestBuilder, StatusCode};
+use tokio::time::Instant;
 
 use crate::{ServiceError, TaskService};
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct SystemStatus {
     pub server: String,
+    #[serde(default)]
+    pub maintenance: bool,
     pub runners: Vec<RunnerStatus>,
+    /// Runs that have been running for more than 15 minutes.
+    #[serde(default)]
+    pub stuck_runs: Vec<StuckRun>,
+    /// The last database maintenance pass, if one has run.
+    #[serde(default)]
+    pub db_maintenance: Option<MaintenanceReport>,
 }
 
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct RunnerStatus {
     pub runner_id: String,
     pub last_seen: String,
     pub connected: bool,
 }
 
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct StuckRun {
+    pub id: String,
+    pub task_id: String,
+    pub action: ClaudeAction,
+    pub status: ClaudeRunStatus,
+    pub started_at: String,
+    pub running_for_seconds: i64,
+    pub runner_id: Option<String>,
+}
+
 /// Runner utilization metrics sent during registration heartbeat.
 #[derive(Debug, Clone)]
 pub struct RunnerUtilization {
@@ -32,19 +74,16 @@ pub struct RunnerUtilization {
     pub active_count: usize,
     pub active_builds: usize,
     pub status: Option<String>,
+    /// Startup benchmark results, when the runner ran one.
+    pub benchmark: Option<RunnerBenchmark>,
 }
 
 /// Pending configuration changes from the server.
-#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
-pub struct PendingConfigResponse {
-    #[serde(default)]
-    pub poll_interval: Option<u64>,
-    #[serde(default)]
-    pub drain: Option<bool>,
-}
+pub use flowstate_core::runner::PendingRunnerConfig as PendingConfigResponse;
 
 /// Response from the register endpoint.
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct RegisterResponse {
     pub status: String,
     pub runner_id: String,
@@ -52,6 +91,37 @@ pub struct RegisterResponse {
     pub pending_config: Option<PendingConfigResponse>,
 }
 
+/// Resolve a server address into the URL prefix and client used for requests.
+///
+/// For `unix:<path>` addresses the client is pinned to the socket and the
+/// URL host is a placeholder, since no DNS or TCP connection takes place.
+fn build_client(base_url: &str) -> (String, Client) {
+    #[cfg(unix)]
+    if let Some(path) = base_url.strip_prefix("unix:") {
+        let client = Client::builder()
+            .unix_socket(path)
+            .build()
+            .expect("failed to build unix socket client");
+        return ("http://localhost".to_string(), client);
+    }
+    (base_url.trim_end_matches('/').to_string(), Client::new())
+}
+
+/// Percent-encode a free-form query string value (custom field values may
+/// contain spaces, `&` and the like; ids never do).
+fn encode_query_value(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for b in value.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(b as char)
+            }
+            _ => out.push_str(&format!("%{b:02X}")),
+        }
+    }
+    out
+}
+
 /// Async HTTP client implementation of TaskService.
 /// Connects to a running flowstate-server.
 pub struct HttpService {
@@ -59,44 +129,114 @@ pub struct HttpService {
     client: Client,
     api_key: Option<String>,
     runner_id: Option<String>,
+    maintenance_wait: Duration,
 }
 
 impl HttpService {
+    /// Create a client for `base_url`.
+    ///
+    /// `base_url` is either an `http(s)://` URL or `unix:/path/to.sock`, in
+    /// which case every request is sent over that Unix domain socket.
     pub fn new(base_url: &str) -> Self {
-        let base_url = base_url.trim_end_matches('/').to_string();
+        let (base_url, client) = build_client(base_url);
         Self {
             base_url,
-            client: Client::new(),
+            client,
             api_key: None,
             runner_id: None,
+            maintenance_wait: Duration::ZERO,
         }
     }
 
     pub fn with_api_key(base_url: &str, key: String) -> Self {
-        let base_url = base_url.trim_end_matches('/').to_string();
+        let (base_url, client) = build_client(base_url);
         Self {
             base_url,
-            client: Client::new(),
+            client,
             api_key: Some(key),
             runner_id: None,
+            maintenance_wait: Duration::ZERO,
+        }
+    }
+
+    /// Rebuild the HTTP client to present a client certificate and/or trust
+    /// an extra CA for the server certificate.
+    ///
+    /// `identity_pem` is a PEM certificate followed by its private key.
+    /// Only meaningful for `https://` addresses; the rebuilt client does not
+    /// keep a `unix:` socket binding.
+    pub fn with_tls(
+        mut self,
+        identity_pem: Option<&[u8]>,
+        ca_pem: Option<&[u8]>,
+    ) -> Result<Self, ServiceError> {
+        // Pin rustls: the identity format below is rustls-specific, and
+        // native-tls may also be compiled in through other dependencies.
+        let mut builder = Client::builder().use_rustls_tls();
+        if let Some(pem) = identity_pem {
+            let identity = Identity::from_pem(pem)
+                .map_err(|e| ServiceError::InvalidInput(format!("client certificate: {e}")))?;
+            builder = builder.identity(identity);
+        }
+        if let Some(pem) = ca_pem {
+            let ca = Certificate::from_pem(pem)
+                .map_err(|e| ServiceError::InvalidInput(format!("server CA: {e}")))?;
+            builder = builder.add_root_certificate(ca);
         }
+        self.client = builder
+            .build()
+            .map_err(|e| ServiceError::Internal(format!("failed to build client: {e}")))?;
+        Ok(self)
     }
 
     pub fn set_runner_id(&mut self, id: String) {
         self.runner_id = Some(id);
     }
 
+    /// Retry requests refused for server maintenance (503 with
+    /// `Retry-After`) for up to `max` in total, instead of failing at once.
+    /// Runners set this so writes for runs already in flight survive a
+    /// maintenance window.
+    pub fn set_maintenance_wait(&mut self, max: Duration) {
+        self.maintenance_wait = max;
+    }
+
     fn with_auth(&self, builder: RequestBuilder) -> RequestBuilder {
         let builder = match &self.api_key {
             Some(key) => builder.header("Authorization", format!("Bearer {key}")),
             None => builder,
         };
+        #[cfg(feature = "telemetry")]
+        let builder = crate::telemetry::inject(builder);
         match &self.runner_id {
             Some(id) => builder.header("X-Runner-Id", id.as_str()),
             None => builder,
         }
     }
 
+    /// Send `builder` with credentials, waiting out maintenance for up to
+    /// `maintenance_wait`.
+    async fn send(&self, builder: RequestBuilder) -> Result<reqwest::Response, ServiceError> {
+        let mut builder = self.with_auth(builder);
+        let deadline = Instant::now() + self.maintenance_wait;
+        loop {
+            let retry = builder.try_clone();
+            let resp = builder
+                .send()
+                .await
+                .map_err(|e| ServiceError::Internal(e.to_string()))?;
+            let (Some(retry), Some(wait)) = (retry, maintenance_retry_after(&resp)) else {
+                return Ok(resp);
+            };
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Ok(resp);
+            }
+            tokio::time::sleep(wait.min(remaining)).await;
+            builder = retry;
+        }
+    }
+
     /// Check if the server is reachable.
     /// Health endpoint is NOT authenticated.
     pub async fn health_check(&self) -> Result<(), ServiceError> {
@@ -121,21 +261,13 @@ impl HttpService {
         path: &str,
     ) -> Result<T, ServiceError> {
         let builder = self.client.get(format!("{}{path}", self.base_url));
-        let resp = self
-            .with_auth(builder)
-            .send()
-            .await
-            .map_err(|e| ServiceError::Internal(e.to_string()))?;
+        let resp = self.send(builder).await?;
         handle_response(resp).await
     }
 
     async fn get_text(&self, path: &str) -> Result<String, ServiceError> {
         let builder = self.client.get(format!("{}{path}", self.base_url));
-        let resp = self
-            .with_auth(builder)
-            .send()
-            .await
-            .map_err(|e| ServiceError::Internal(e.to_string()))?;
+        let resp = self.send(builder).await?;
         let status = resp.status();
         if status.is_success() {
             resp.text()
@@ -155,11 +287,7 @@ impl HttpService {
             .client
             .post(format!("{}{path}", self.base_url))
             .json(body);
-        let resp = self
-            .with_auth(builder)
-            .send()
-            .await
-            .map_err(|e| ServiceError::Internal(e.to_string()))?;
+        let resp = self.send(builder).await?;
         handle_response(resp).await
     }
 
@@ -172,11 +300,20 @@ impl HttpService {
             .client
             .put(format!("{}{path}", self.base_url))
             .json(body);
-        let resp = self
-            .with_auth(builder)
-            .send()
-            .await
-            .map_err(|e| ServiceError::Internal(e.to_string()))?;
+        let resp = self.send(builder).await?;
+        handle_response(resp).await
+    }
+
+    async fn patch_json<B: serde::Serialize, T: serde::de::DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> Result<T, ServiceError> {
+        let builder = self
+            .client
+            .patch(format!("{}{path}", self.base_url))
+            .json(body);
+        let resp = self.send(builder).await?;
         handle_response(resp).await
     }
 
@@ -186,11 +323,7 @@ impl HttpService {
             .put(format!("{}{path}", self.base_url))
             .header("Content-Type", "text/markdown")
             .body(body.to_string());
-        let resp = self
-            .with_auth(builder)
-            .send()
-            .await
-            .map_err(|e| ServiceError::Internal(e.to_string()))?;
+        let resp = self.send(builder).await?;
         if resp.status().is_success() {
             Ok(())
         } else {
@@ -200,11 +333,7 @@ impl HttpService {
 
     async fn delete_req(&self, path: &str) -> Result<(), ServiceError> {
         let builder = self.client.delete(format!("{}{path}", self.base_url));
-        let resp = self
-            .with_auth(builder)
-            .send()
-            .await
-            .map_err(|e| ServiceError::Internal(e.to_string()))?;
+        let resp = self.send(builder).await?;
         if resp.status().is_success() {
             Ok(())
         } else {
@@ -226,6 +355,22 @@ impl HttpService {
         .await
     }
 
+    /// Like [`trigger_claude_run`](Self::trigger_claude_run), but safe to
+    /// retry: the server returns the run an earlier call with the same `key`
+    /// queued instead of queueing another.
+    pub async fn trigger_claude_run_idempotent(
+        &self,
+        task_id: &str,
+        action: &str,
+        key: &str,
+    ) -> Result<ClaudeRun, ServiceError> {
+        self.post_json(
+            &format!("/api/tasks/{task_id}/claude-runs"),
+            &serde_json::json!({ "action": action, "idempotency_key": key }),
+        )
+        .await
+    }
+
     /// Trigger a run on any task with an optional capability requirement.
     /// Used by the runner to trigger follow-up phases on tasks it doesn't
     /// currently own (e.g., triggering Build on newly created subtasks).
@@ -243,6 +388,25 @@ impl HttpService {
             .await
     }
 
+    /// Record reviewer feedback for a phase. Returns the queued distill run
+    /// when `input.distill` is set.
+    pub async fn submit_feedback(
+        &self,
+        task_id: &str,
+        input: &TaskFeedback,
+    ) -> Result<Option<ClaudeRun>, ServiceError> {
+        let builder = self
+            .client
+            .put(format!("{}/api/tasks/{task_id}/feedback", self.base_url))
+            .json(input);
+        let resp = self.send(builder).await?;
+
+        if resp.status() == StatusCode::NO_CONTENT {
+            return Ok(None);
+        }
+        handle_response(resp).await.map(Some)
+    }
+
     pub async fn get_claude_run_output(&self, run_id: &str) -> Result<String, ServiceError> {
         self.get_text(&format!("/api/claude-runs/{run_id}/output"))
             .await
@@ -294,6 +458,35 @@ impl HttpService {
             .await
     }
 
+    pub async fn read_task_scope_findings(
+        &self,
+        task_id: &str,
+    ) -> Result<Vec<ScopeFinding>, ServiceError> {
+        self.get_json(&format!("/api/tasks/{task_id}/scope-findings"))
+            .await
+    }
+
+    /// Replace the task's scope findings with those of the latest build.
+    pub async fn write_task_scope_findings(
+        &self,
+        task_id: &str,
+        findings: &[ScopeFinding],
+    ) -> Result<Vec<ScopeFinding>, ServiceError> {
+        self.put_json(&format!("/api/tasks/{task_id}/scope-findings"), &findings)
+            .await
+    }
+
+    /// Projects the configured key is limited to, or `None` when it reaches
+    /// every project. A server too old to say is an error, not `None`.
+    pub async fn key_projects(&self) -> Result<Option<Vec<String>>, ServiceError> {
+        let me: serde_json::Value = self.get_json("/auth/me").await?;
+        let projects = me
+            .get("projects")
+            .ok_or_else(|| ServiceError::Internal("server does not report key scope".into()))?;
+        serde_json::from_value(projects.clone())
+            .map_err(|e| ServiceError::Internal(format!("decode key scope: {e}")))
+    }
+
     /// Register this runner with the server, advertising its capabilities.
     /// When called without utilization, performs a simple registration.
     pub async fn register_runner(
@@ -330,17 +523,15 @@ impl HttpService {
             if let Some(ref status) = util.status {
                 body["status"] = serde_json::json!(status);
             }
+            if let Some(benchmark) = util.benchmark {
+                body["benchmark"] = serde_json::json!(benchmark);
+            }
         }
 
         let builder = self
             .client
             .post(format!("{}/api/runners/register", self.base_url));
-        let resp = self
-            .with_auth(builder)
-            .json(&body)
-            .send()
-            .await
-            .map_err(|e| ServiceError::Internal(e.to_string()))?;
+        let resp = self.send(builder.json(&body)).await?;
 
         if resp.status().is_success() {
             resp.json::<RegisterResponse>()
@@ -354,14 +545,20 @@ impl HttpService {
     /// Claim the next queued claude run, atomically setting it to Running.
     /// Returns None if no queued runs exist (server returns 204).
     pub async fn claim_claude_run(&self) -> Result<Option<ClaudeRun>, ServiceError> {
+        self.claim_claude_run_wait(0).await
+    }
+
+    /// Like [`claim_claude_run`](Self::claim_claude_run), but the server holds
+    /// the request open for up to `wait_secs` until a run is queued.
+    pub async fn claim_claude_run_wait(
+        &self,
+        wait_secs: u64,
+    ) -> Result<Option<ClaudeRun>, ServiceError> {
         let builder = self
             .client
-            .post(format!("{}/api/claude-runs/claim", self.base_url));
-        let resp = self
-            .with_auth(builder)
-            .send()
-            .await
-            .map_err(|e| ServiceError::Internal(e.to_string()))?;
+            .post(format!("{}/api/claude-runs/claim", self.base_url))
+            .query(&[("wait", wait_secs)]);
+        let resp = self.send(builder).await?;
 
         if resp.status() == StatusCode::NO_CONTENT {
             return Ok(None);
@@ -406,11 +603,22 @@ impl HttpService {
             .client
             .put(format!("{}/api/claude-runs/{id}/progress", self.base_url))
             .json(&serde_json::json!({ "message": message }));
-        let resp = self
-            .with_auth(builder)
-            .send()
-            .await
-            .map_err(|e| ServiceError::Internal(e.to_string()))?;
+        let resp = self.send(builder).await?;
+        if resp.status().is_success() {
+            Ok(())
+        } else {
+            Err(parse_error(resp).await)
+        }
+    }
+
+    /// Append a chunk of agent output to a running run's live log.
+    pub async fn append_claude_run_log(&self, id: &str, text: &str) -> Result<(), ServiceError> {
+        let builder = self
+            .client
+            .post(format!("{}/api/claude-runs/{id}/logs", self.base_url))
+            .header("Content-Type", "text/plain; charset=utf-8")
+            .body(text.to_string());
+        let resp = self.send(builder).await?;
         if resp.status().is_success() {
             Ok(())
         } else {
@@ -418,6 +626,16 @@ impl HttpService {
         }
     }
 
+    /// Report duration, output size and token usage for a finished run.
+    pub async fn record_run_metrics(
+        &self,
+        id: &str,
+        metrics: &RecordRunMetrics,
+    ) -> Result<RunMetrics, ServiceError> {
+        self.post_json(&format!("/api/claude-runs/{id}/metrics"), metrics)
+            .await
+    }
+
     /// Set the repo token for a project (encrypted server-side).
     pub async fn set_repo_token(&self, project_id: &str, token: &str) -> Result<(), ServiceError> {
         let builder = self
@@ -427,11 +645,7 @@ impl HttpService {
                 self.base_url
             ))
             .json(&serde_json::json!({ "token": token }));
-        let resp = self
-            .with_auth(builder)
-            .send()
-            .await
-            .map_err(|e| ServiceError::Internal(e.to_string()))?;
+        let resp = self.send(builder).await?;
         if resp.status().is_success() {
             Ok(())
         } else {
@@ -455,6 +669,36 @@ impl HttpService {
         self.get_json("/api/status").await
     }
 
+    /// Fetch database row counts, schema version and size/pool figures.
+    pub async fn db_stats(&self) -> Result<DbStats, ServiceError> {
+        self.get_json("/api/infra/db").await
+    }
+
+    /// List every runner in the server's registry, including ones that are
+    /// currently offline.
+    pub async fn list_runners(&self) -> Result<Vec<RunnerRecord>, ServiceError> {
+        self.get_json("/api/runners").await
+    }
+
+    /// Tell a runner to stop claiming work on its next heartbeat.
+    pub async fn drain_runner(&self, runner_id: &str) -> Result<RunnerRecord, ServiceError> {
+        self.post_json(
+            &format!("/api/runners/{runner_id}/drain"),
+            &serde_json::json!({}),
+        )
+        .await
+    }
+
+    /// Merge `config` into the config waiting for a runner's next heartbeat.
+    pub async fn update_runner_config(
+        &self,
+        runner_id: &str,
+        config: &PendingConfigResponse,
+    ) -> Result<RunnerRecord, ServiceError> {
+        self.patch_json(&format!("/api/runners/{runner_id}/config"), config)
+            .await
+    }
+
     /// Update a claude run with PR info (url, number, branch).
     pub async fn update_claude_run_pr(
         &self,
@@ -476,6 +720,22 @@ impl HttpService {
     }
 }
 
+/// How long the server asked to wait, when `resp` refuses the request for
+/// maintenance.
+fn maintenance_retry_after(resp: &reqwest::Response) -> Option<Duration> {
+    if resp.status() != StatusCode::SERVICE_UNAVAILABLE {
+        return None;
+    }
+    let secs = resp
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .parse::<u64>()
+        .ok()?;
+    Some(Duration::from_secs(secs.max(1)))
+}
+
 async fn handle_response<T: serde::de::DeserializeOwned>(
     resp: reqwest::Response,
 ) -> Result<T, ServiceError> {
@@ -541,20 +801,82 @@ impl TaskService for HttpService {
         self.delete_req(&format!("/api/projects/{id}")).await
     }
 
+    async fn project_dashboard(&self, id: &str) -> Result<ProjectDashboard, ServiceError> {
+        self.get_json(&format!("/api/projects/{id}/dashboard"))
+            .await
+    }
+
     async fn list_tasks(&self, filter: &TaskFilter) -> Result<Vec<Task>, ServiceError> {
         let mut params = Vec::new();
         if let Some(ref pid) = filter.project_id {
             params.push(format!("project_id={pid}"));
         }
+        if !filter.project_ids.is_empty() {
+            let projects = filter.project_ids.join(",");
+            params.push(format!("projects={}", encode_query_value(&projects)));
+        }
         if let Some(status) = filter.status {
             params.push(format!("status={}", status.as_str()));
         }
+        if !filter.statuses.is_empty() {
+            let statuses: Vec<_> = filter.statuses.iter().map(|s| s.as_str()).collect();
+            params.push(format!("statuses={}", statuses.join(",")));
+        }
         if let Some(priority) = filter.priority {
             params.push(format!("priority={}", priority.as_str()));
         }
+        if let Some(task_type) = filter.task_type {
+            params.push(format!("task_type={}", task_type.as_str()));
+        }
         if let Some(ref sid) = filter.sprint_id {
             params.push(format!("sprint_id={sid}"));
         }
+        if let Some(ref eid) = filter.epic_id {
+            params.push(format!("epic_id={eid}"));
+        }
+        if let Some(ref aid) = filter.assignee_id {
+            params.push(format!("assignee_id={aid}"));
+        }
+        if !filter.assignee_ids.is_empty() {
+            let assignees = filter.assignee_ids.join(",");
+            params.push(format!("assignees={}", encode_query_value(&assignees)));
+        }
+        if filter.unassigned {
+            params.push("unassigned=true".to_string());
+        }
+        if let Some(ref uid) = filter.watched_by {
+            params.push(format!("watched_by={uid}"));
+        }
+        if !filter.label_ids.is_empty() {
+            let labels = filter.label_ids.join(",");
+            params.push(format!("labels={}", encode_query_value(&labels)));
+        }
+        if let Some(ref text) = filter.text {
+            params.push(format!("text={}", encode_query_value(text)));
+        }
+        if let Some((ref field_id, ref value)) = filter.custom_field {
+            params.push(format!("field_id={field_id}"));
+            params.push(format!("field_value={}", encode_query_value(value)));
+        }
+        let instants = [
+            ("due_after", filter.due_after),
+            ("due_before", filter.due_before),
+            ("created_after", filter.created_after),
+            ("created_before", filter.created_before),
+            ("updated_after", filter.updated_after),
+        ];
+        for (name, instant) in instants {
+            if let Some(instant) = instant {
+                let instant = instant.to_rfc3339_opts(SecondsFormat::AutoSi, true);
+                params.push(format!("{name}={}", encode_query_value(&instant)));
+            }
+        }
+        if filter.overdue {
+            params.push("overdue=true".to_string());
+        }
+        if filter.archived {
+            params.push("archived=true".to_string());
+        }
         if let Some(limit) = filter.limit {
             params.push(format!("limit={limit}"));
         }
@@ -578,10 +900,39 @@ impl TaskService for HttpService {
         self.put_json(&format!("/api/tasks/{id}"), update).await
     }
 
+    async fn bulk_create_tasks(&self, inputs: &[CreateTask]) -> Result<Vec<Task>, ServiceError> {
+        self.post_json("/api/tasks/bulk", &inputs).await
+    }
+
+    async fn bulk_update_tasks(
+        &self,
+        ids: &[String],
+        update: &UpdateTask,
+    ) -> Result<Vec<Task>, ServiceError> {
+        let body = BulkUpdateTasks {
+            ids: ids.to_vec(),
+            update: update.clone(),
+        };
+        self.patch_json("/api/tasks/bulk", &body).await
+    }
+
+    async fn reorder_task(&self, id: &str, after_id: Option<&str>) -> Result<Task, ServiceError> {
+        let body = ReorderTask {
+            after_id: after_id.map(str::to_string),
+        };
+        self.post_json(&format!("/api/tasks/{id}/reorder"), &body)
+            .await
+    }
+
     async fn delete_task(&self, id: &str) -> Result<(), ServiceError> {
         self.delete_req(&format!("/api/tasks/{id}")).await
     }
 
+    async fn archive_task(&self, id: &str) -> Result<Task, ServiceError> {
+        self.post_json(&format!("/api/tasks/{id}/archive"), &serde_json::json!({}))
+            .await
+    }
+
     async fn count_tasks_by_status(
         &self,
         project_id: &str,
@@ -592,11 +943,27 @@ impl TaskService for HttpService {
         .await
     }
 
+    async fn rollup_board(&self, project_ids: &[String]) -> Result<Vec<ProjectLane>, ServiceError> {
+        if project_ids.is_empty() {
+            return self.get_json("/api/board").await;
+        }
+        self.get_json(&format!("/api/board?project_ids={}", project_ids.join(",")))
+            .await
+    }
+
     async fn list_child_tasks(&self, parent_id: &str) -> Result<Vec<Task>, ServiceError> {
         self.get_json(&format!("/api/tasks/{parent_id}/children"))
             .await
     }
 
+    async fn list_feedback_history(
+        &self,
+        task_id: &str,
+    ) -> Result<Vec<FeedbackEntry>, ServiceError> {
+        self.get_json(&format!("/api/tasks/{task_id}/feedback"))
+            .await
+    }
+
     async fn create_sprint(&self, input: &CreateSprint) -> Result<Sprint, ServiceError> {
         self.post_json("/api/sprints", input).await
     }
@@ -618,6 +985,179 @@ impl TaskService for HttpService {
         self.delete_req(&format!("/api/sprints/{id}")).await
     }
 
+    async fn archive_sprint(&self, id: &str) -> Result<Sprint, ServiceError> {
+        self.post_json(
+            &format!("/api/sprints/{id}/archive"),
+            &serde_json::json!({}),
+        )
+        .await
+    }
+
+    async fn start_sprint(&self, id: &str) -> Result<Sprint, ServiceError> {
+        self.post_json(&format!("/api/sprints/{id}/start"), &serde_json::json!({}))
+            .await
+    }
+
+    async fn complete_sprint(
+        &self,
+        id: &str,
+        input: &CompleteSprint,
+    ) -> Result<SprintRollover, ServiceError> {
+        self.post_json(&format!("/api/sprints/{id}/complete"), input)
+            .await
+    }
+
+    async fn create_epic(&self, input: &CreateEpic) -> Result<Epic, ServiceError> {
+        self.post_json("/api/epics", input).await
+    }
+
+    async fn get_epic(&self, id: &str) -> Result<Epic, ServiceError> {
+        self.get_json(&format!("/api/epics/{id}")).await
+    }
+
+    async fn list_epics(&self, project_id: &str) -> Result<Vec<Epic>, ServiceError> {
+        self.get_json(&format!("/api/epics?project_id={project_id}"))
+            .await
+    }
+
+    async fn update_epic(&self, id: &str, update: &UpdateEpic) -> Result<Epic, ServiceError> {
+        self.put_json(&format!("/api/epics/{id}"), update).await
+    }
+
+    async fn delete_epic(&self, id: &str) -> Result<(), ServiceError> {
+        self.delete_req(&format!("/api/epics/{id}")).await
+    }
+
+    async fn create_user(&self, input: &CreateUser) -> Result<User, ServiceError> {
+        self.post_json("/api/users", input).await
+    }
+
+    async fn get_user(&self, id: &str) -> Result<User, ServiceError> {
+        self.get_json(&format!("/api/users/{id}")).await
+    }
+
+    async fn list_users(&self) -> Result<Vec<User>, ServiceError> {
+        self.get_json("/api/users").await
+    }
+
+    async fn update_user(&self, id: &str, update: &UpdateUser) -> Result<User, ServiceError> {
+        self.put_json(&format!("/api/users/{id}"), update).await
+    }
+
+    async fn delete_user(&self, id: &str) -> Result<(), ServiceError> {
+        self.delete_req(&format!("/api/users/{id}")).await
+    }
+
+    async fn create_saved_filter(
+        &self,
+        input: &CreateSavedFilter,
+    ) -> Result<SavedFilter, ServiceError> {
+        self.post_json("/api/saved-filters", input).await
+    }
+
+    async fn get_saved_filter(&self, id: &str) -> Result<SavedFilter, ServiceError> {
+        self.get_json(&format!("/api/saved-filters/{id}")).await
+    }
+
+    async fn list_saved_filters(
+        &self,
+        project_id: &str,
+        user_id: Option<&str>,
+    ) -> Result<Vec<SavedFilter>, ServiceError> {
+        let mut path = format!("/api/saved-filters?project_id={project_id}");
+        if let Some(user_id) = user_id {
+            path.push_str(&format!("&user_id={user_id}"));
+        }
+        self.get_json(&path).await
+    }
+
+    async fn update_saved_filter(
+        &self,
+        id: &str,
+        update: &UpdateSavedFilter,
+    ) -> Result<SavedFilter, ServiceError> {
+        self.put_json(&format!("/api/saved-filters/{id}"), update)
+            .await
+    }
+
+    async fn delete_saved_filter(&self, id: &str) -> Result<(), ServiceError> {
+        self.delete_req(&format!("/api/saved-filters/{id}")).await
+    }
+
+    async fn watch_task(&self, task_id: &str, user_id: &str) -> Result<Vec<User>, ServiceError> {
+        self.post_json(
+            &format!("/api/tasks/{task_id}/watch"),
+            &serde_json::json!({ "user_id": user_id }),
+        )
+        .await
+    }
+
+    async fn unwatch_task(&self, task_id: &str, user_id: &str) -> Result<(), ServiceError> {
+        self.delete_req(&format!("/api/tasks/{task_id}/watch?user_id={user_id}"))
+            .await
+    }
+
+    async fn list_task_watchers(&self, task_id: &str) -> Result<Vec<User>, ServiceError> {
+        self.get_json(&format!("/api/tasks/{task_id}/watchers"))
+            .await
+    }
+
+    async fn list_notifications(
+        &self,
+        user_id: &str,
+        unread_only: bool,
+    ) -> Result<Vec<Notification>, ServiceError> {
+        let mut path = format!("/api/notifications?user_id={user_id}");
+        if unread_only {
+            path.push_str("&unread=true");
+        }
+        self.get_json(&path).await
+    }
+
+    async fn mark_notification_read(&self, id: &str) -> Result<Notification, ServiceError> {
+        self.post_json(
+            &format!("/api/notifications/{id}/read"),
+            &serde_json::json!({}),
+        )
+        .await
+    }
+
+    async fn create_custom_field(
+        &self,
+        input: &CreateCustomField,
+    ) -> Result<CustomField, ServiceError> {
+        self.post_json("/api/custom-fields", input).await
+    }
+
+    async fn get_custom_field(&self, id: &str) -> Result<CustomField, ServiceError> {
+        self.get_json(&format!("/api/custom-fields/{id}")).await
+    }
+
+    async fn list_custom_fields(&self, project_id: &str) -> Result<Vec<CustomField>, ServiceError> {
+        self.get_json(&format!("/api/custom-fields?project_id={project_id}"))
+            .await
+    }
+
+    async fn update_custom_field(
+        &self,
+        id: &str,
+        update: &UpdateCustomField,
+    ) -> Result<CustomField, ServiceError> {
+        self.put_json(&format!("/api/custom-fields/{id}"), update)
+            .await
+    }
+
+    async fn delete_custom_field(&self, id: &str) -> Result<(), ServiceError> {
+        self.delete_req(&format!("/api/custom-fields/{id}")).await
+    }
+
+    async fn list_task_field_values(
+        &self,
+        task_id: &str,
+    ) -> Result<Vec<TaskFieldValue>, ServiceError> {
+        self.get_json(&format!("/api/tasks/{task_id}/fields")).await
+    }
+
     async fn create_task_link(&self, input: &CreateTaskLink) -> Result<TaskLink, ServiceError> {
         self.post_json("/api/task-links", input).await
     }
@@ -665,7 +1205,7 @@ mod tests {
     use flowstate_core::claude_run::{ClaudeAction, ClaudeRunStatus, CreateClaudeRun};
     use flowstate_core::project::CreateProject;
     use flowstate_core::sprint::CreateSprint;
-    use flowstate_core::task::{CreateTask, Priority, Status, TaskFilter, UpdateTask};
+    use flowstate_core::task::{CreateTask, Priority, Status, TaskFilter, TaskType, UpdateTask};
     use flowstate_core::task_link::{CreateTaskLink, LinkType};
     use flowstate_core::task_pr::CreateTaskPr;
 
@@ -693,6 +1233,7 @@ mod tests {
             description: "A test task".into(),
             status: Status::Todo,
             priority: Priority::Medium,
+            task_type: TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -700,6 +1241,7 @@ mod tests {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         }
     }
 
@@ -738,6 +1280,39 @@ mod tests {
         svc.health_check().await.unwrap();
     }
 
+    #[tokio::test]
+    async fn maintenance_wait_retries_refused_writes() {
+        let (mut svc, server) = setup().await;
+        let set_maintenance = |enabled: bool| {
+            let url = format!("{}/admin/maintenance", server.base_url);
+            async move {
+                reqwest::Client::new()
+                    .put(url)
+                    .json(&serde_json::json!({ "enabled": enabled }))
+                    .send()
+                    .await
+                    .unwrap();
+            }
+        };
+        set_maintenance(true).await;
+        let err = svc.create_project(&test_project()).await.unwrap_err();
+        assert!(matches!(err, ServiceError::Internal(msg) if msg.contains("maintenance")));
+
+        // The server asks for 60s; the wait is capped by the budget, and
+        // the retry lands once maintenance is over
+        svc.set_maintenance_wait(Duration::from_secs(1));
+        let lift = tokio::spawn({
+            let done = set_maintenance(false);
+            async move {
+                tokio::time::sleep(Duration::from_millis(300)).await;
+                done.await;
+            }
+        });
+        let project = svc.create_project(&test_project()).await.unwrap();
+        assert_eq!(project.slug, "test-project");
+        lift.await.unwrap();
+    }
+
     // ---- project CRUD ----
 
     #[tokio::test]
@@ -843,6 +1418,7 @@ mod tests {
             description: String::new(),
             status: Status::Todo,
             priority: Priority::High,
+            task_type: TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -850,6 +1426,7 @@ mod tests {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .await
         .unwrap();
@@ -860,6 +1437,7 @@ mod tests {
             description: String::new(),
             status: Status::Done,
             priority: Priority::Low,
+            task_type: TaskType::Bug,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -867,6 +1445,7 @@ mod tests {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .await
         .unwrap();
@@ -895,6 +1474,19 @@ mod tests {
         assert_eq!(high_only.len(), 1);
         assert_eq!(high_only[0].title, "Todo");
 
+        // Filter by type
+        let bugs = svc
+            .list_tasks(&TaskFilter {
+                project_id: Some(project.id.clone()),
+                task_type: Some(TaskType::Bug),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(bugs.len(), 1);
+        assert_eq!(bugs[0].title, "Done");
+        assert_eq!(bugs[0].task_type, TaskType::Bug);
+
         // Filter with limit
         let limited = svc
             .list_tasks(&TaskFilter {
@@ -945,6 +1537,65 @@ mod tests {
         assert_eq!(with_sprint.len(), 1);
     }
 
+    #[tokio::test]
+    async fn list_tasks_with_due_filters() {
+        let (svc, _server) = setup().await;
+        let project = svc.create_project(&test_project()).await.unwrap();
+        let now = chrono::Utc::now();
+
+        let create = |title: &str, status: Status, due_at| CreateTask {
+            title: title.into(),
+            status,
+            due_at,
+            ..test_task(&project.id)
+        };
+        svc.create_task(&create(
+            "Late",
+            Status::Build,
+            Some(now - chrono::Duration::days(2)),
+        ))
+        .await
+        .unwrap();
+        svc.create_task(&create(
+            "Finished",
+            Status::Done,
+            Some(now - chrono::Duration::days(2)),
+        ))
+        .await
+        .unwrap();
+        svc.create_task(&create(
+            "Soon",
+            Status::Todo,
+            Some(now + chrono::Duration::days(3)),
+        ))
+        .await
+        .unwrap();
+        svc.create_task(&create("Someday", Status::Todo, None))
+            .await
+            .unwrap();
+
+        let overdue = svc
+            .list_tasks(&TaskFilter {
+                project_id: Some(project.id.clone()),
+                overdue: true,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        let titles: Vec<&str> = overdue.iter().map(|t| t.title.as_str()).collect();
+        assert_eq!(titles, vec!["Late"]);
+
+        let due_this_week = svc
+            .list_tasks(&TaskFilter {
+                project_id: Some(project.id.clone()),
+                due_before: Some(now + chrono::Duration::days(7)),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(due_this_week.len(), 3);
+    }
+
     // ---- count tasks by status ----
 
     #[tokio::test]
@@ -962,6 +1613,20 @@ mod tests {
         assert_eq!(todo_count.unwrap().1, 1);
     }
 
+    #[tokio::test]
+    async fn project_dashboard_counts_tasks() {
+        let (svc, _server) = setup().await;
+        let project = svc.create_project(&test_project()).await.unwrap();
+        svc.create_task(&test_task(&project.id)).await.unwrap();
+
+        let dashboard = svc.project_dashboard(&project.id).await.unwrap();
+        assert_eq!(dashboard.project_id, project.id);
+        assert_eq!(dashboard.total_tasks(), 1);
+
+        let err = svc.project_dashboard("missing").await.unwrap_err();
+        assert!(matches!(err, ServiceError::NotFound(_)));
+    }
+
     // ---- child tasks ----
 
     #[tokio::test]
@@ -981,6 +1646,7 @@ mod tests {
             description: String::new(),
             status: Status::Todo,
             priority: Priority::Low,
+            task_type: TaskType::Feature,
             parent_id: Some(parent.id.clone()),
             reviewer: String::new(),
             research_capability: None,
@@ -988,6 +1654,7 @@ mod tests {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .await
         .unwrap();
@@ -1039,6 +1706,52 @@ mod tests {
         assert!(all.is_empty());
     }
 
+    #[tokio::test]
+    async fn sprint_start_and_complete() {
+        let (svc, _server) = setup().await;
+        let project = svc.create_project(&test_project()).await.unwrap();
+        let sprint = svc
+            .create_sprint(&CreateSprint {
+                project_id: project.id.clone(),
+                name: "Sprint 1".into(),
+                goal: String::new(),
+                starts_at: None,
+                ends_at: None,
+            })
+            .await
+            .unwrap();
+        let task = svc.create_task(&test_task(&project.id)).await.unwrap();
+        svc.update_task(
+            &task.id,
+            &UpdateTask {
+                sprint_id: Some(Some(sprint.id.clone())),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let err = svc
+            .complete_sprint(&sprint.id, &Default::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServiceError::InvalidInput(_)));
+        let started = svc.start_sprint(&sprint.id).await.unwrap();
+        assert_eq!(started.status, flowstate_core::sprint::SprintStatus::Active);
+
+        let rollover = svc
+            .complete_sprint(&sprint.id, &Default::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            rollover.sprint.status,
+            flowstate_core::sprint::SprintStatus::Completed
+        );
+        assert_eq!(rollover.rolled_over.len(), 1);
+        assert_eq!(rollover.rolled_over[0].id, task.id);
+        assert_eq!(rollover.rolled_over[0].sprint_id, None);
+    }
+
     // ---- task links ----
 
     #[tokio::test]
@@ -1054,6 +1767,7 @@ mod tests {
                 description: String::new(),
                 status: Status::Todo,
                 priority: Priority::Medium,
+                task_type: TaskType::Feature,
                 parent_id: None,
                 reviewer: String::new(),
                 research_capability: None,
@@ -1061,6 +1775,7 @@ mod tests {
                 plan_capability: None,
                 build_capability: None,
                 verify_capability: None,
+                due_at: None,
             })
             .await
             .unwrap();
@@ -1098,6 +1813,7 @@ mod tests {
                 pr_url: "https://github.com/org/repo/pull/42".into(),
                 pr_number: 42,
                 branch_name: "flowstate/test".into(),
+                draft: false,
             })
             .await
             .unwrap();
@@ -1121,6 +1837,11 @@ mod tests {
                 task_id: task.id.clone(),
                 action: ClaudeAction::Research,
                 required_capability: None,
+                priority: 0,
+                feedback: None,
+                idempotency_key: None,
+                run_window: None,
+                trace_context: None,
             })
             .await
             .unwrap();
@@ -1148,6 +1869,31 @@ mod tests {
         assert_eq!(run.action, ClaudeAction::Research);
     }
 
+    #[tokio::test]
+    async fn trigger_claude_run_idempotent_replays_run() {
+        let (svc, _server) = setup().await;
+        let project = svc.create_project(&test_project()).await.unwrap();
+        let task = svc.create_task(&test_task(&project.id)).await.unwrap();
+
+        let first = svc
+            .trigger_claude_run_idempotent(&task.id, "research", "retry-1")
+            .await
+            .unwrap();
+        let again = svc
+            .trigger_claude_run_idempotent(&task.id, "research", "retry-1")
+            .await
+            .unwrap();
+        assert_eq!(again.id, first.id);
+        assert_eq!(first.idempotency_key.as_deref(), Some("retry-1"));
+
+        let other = svc
+            .trigger_claude_run_idempotent(&task.id, "research", "retry-2")
+            .await
+            .unwrap();
+        assert_ne!(other.id, first.id);
+        assert_eq!(svc.list_claude_runs(&task.id).await.unwrap().len(), 2);
+    }
+
     // ---- convenience: claim_claude_run ----
 
     #[tokio::test]
@@ -1246,6 +1992,31 @@ mod tests {
             .unwrap();
     }
 
+    #[tokio::test]
+    async fn append_claude_run_log_until_finished() {
+        let (mut svc, _server) = setup().await;
+        svc.set_runner_id("runner-4".into());
+        svc.register_runner("runner-4", "claude-cli", "standard")
+            .await
+            .unwrap();
+
+        let project = svc.create_project(&test_project()).await.unwrap();
+        let task = svc.create_task(&test_task(&project.id)).await.unwrap();
+        svc.trigger_claude_run(&task.id, "research").await.unwrap();
+
+        let claimed = svc.claim_claude_run().await.unwrap().unwrap();
+        svc.append_claude_run_log(&claimed.id, "reading files\n")
+            .await
+            .unwrap();
+        svc.update_claude_run_status(&claimed.id, "completed", None, Some(0))
+            .await
+            .unwrap();
+        assert!(svc
+            .append_claude_run_log(&claimed.id, "too late\n")
+            .await
+            .is_err());
+    }
+
     // ---- convenience: spec/plan/research/verification roundtrip ----
 
     #[tokio::test]
@@ -1277,6 +2048,18 @@ mod tests {
             .unwrap();
         let verification = svc.read_task_verification(&task.id).await.unwrap();
         assert_eq!(verification, "# Verification");
+
+        // Scope findings
+        let finding = flowstate_core::ScopeFinding::new(
+            flowstate_core::FindingKind::CiChange,
+            ".github/workflows/ci.yml",
+            "CI configuration changed but not in the plan".into(),
+        );
+        svc.write_task_scope_findings(&task.id, std::slice::from_ref(&finding))
+            .await
+            .unwrap();
+        let findings = svc.read_task_scope_findings(&task.id).await.unwrap();
+        assert_eq!(findings, [finding]);
     }
 
     // ---- convenience: repo token ----
@@ -1302,6 +2085,46 @@ mod tests {
         assert_eq!(status.server, "ok");
     }
 
+    #[tokio::test]
+    async fn db_stats_returns_counts() {
+        let (svc, _server) = setup().await;
+        let stats = svc.db_stats().await.unwrap();
+        assert_eq!(stats.backend, "sqlite");
+        assert_eq!(stats.total_rows(), 0);
+    }
+
+    #[tokio::test]
+    async fn drain_runner_queues_drain() {
+        let (svc, _server) = setup().await;
+        svc.register_runner("admin-runner", "claude-cli", "standard")
+            .await
+            .unwrap();
+
+        let runners = svc.list_runners().await.unwrap();
+        assert_eq!(runners.len(), 1);
+        assert_eq!(runners[0].runner_id, "admin-runner");
+
+        let config = PendingConfigResponse {
+            poll_interval: Some(20),
+            drain: None,
+        };
+        svc.update_runner_config("admin-runner", &config)
+            .await
+            .unwrap();
+        let runner = svc.drain_runner("admin-runner").await.unwrap();
+        assert_eq!(
+            runner.pending_config,
+            Some(PendingConfigResponse {
+                poll_interval: Some(20),
+                drain: Some(true),
+            })
+        );
+        assert!(matches!(
+            svc.drain_runner("missing").await,
+            Err(ServiceError::NotFound(_))
+        ));
+    }
+
     // ---- convenience: update_claude_run_pr ----
 
     #[tokio::test]
@@ -1405,6 +2228,165 @@ mod tests {
         );
     }
 
+    // ---- bulk operations ----
+
+    #[tokio::test]
+    async fn bulk_create_and_update_tasks() {
+        let (svc, _server) = setup().await;
+        let project = svc.create_project(&test_project()).await.unwrap();
+        let created = svc
+            .bulk_create_tasks(&[test_task(&project.id), test_task(&project.id)])
+            .await
+            .unwrap();
+        assert_eq!(created.len(), 2);
+
+        let ids: Vec<String> = created.iter().map(|t| t.id.clone()).collect();
+        let updated = svc
+            .bulk_update_tasks(
+                &ids,
+                &UpdateTask {
+                    status: Some(Status::Plan),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert!(updated.iter().all(|t| t.status == Status::Plan));
+    }
+
+    #[tokio::test]
+    async fn reorder_task_moves_within_column() {
+        let (svc, _server) = setup().await;
+        let project = svc.create_project(&test_project()).await.unwrap();
+        let a = svc.create_task(&test_task(&project.id)).await.unwrap();
+        let b = svc.create_task(&test_task(&project.id)).await.unwrap();
+
+        let moved = svc.reorder_task(&a.id, Some(&b.id)).await.unwrap();
+        assert!(moved.sort_order > svc.get_task(&b.id).await.unwrap().sort_order);
+
+        let err = svc.reorder_task(&a.id, Some(&a.id)).await.unwrap_err();
+        assert!(matches!(err, ServiceError::InvalidInput(_)));
+    }
+
+    // ---- users ----
+
+    #[tokio::test]
+    async fn user_crud_and_assignee_filter() {
+        let (svc, _server) = setup().await;
+        let project = svc.create_project(&test_project()).await.unwrap();
+
+        let user = svc
+            .create_user(&CreateUser {
+                name: "Alice".into(),
+                email: "alice@example.com".into(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(svc.get_user(&user.id).await.unwrap().name, "Alice");
+        let err = svc
+            .create_user(&CreateUser {
+                name: "Alice again".into(),
+                email: "alice@example.com".into(),
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServiceError::InvalidInput(_)));
+
+        let task = svc.create_task(&test_task(&project.id)).await.unwrap();
+        svc.create_task(&test_task(&project.id)).await.unwrap();
+        svc.update_task(
+            &task.id,
+            &UpdateTask {
+                assignee_id: Some(Some(user.id.clone())),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        let assigned = svc
+            .list_tasks(&TaskFilter {
+                assignee_id: Some(user.id.clone()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(assigned.len(), 1);
+        assert_eq!(assigned[0].id, task.id);
+
+        let renamed = svc
+            .update_user(
+                &user.id,
+                &UpdateUser {
+                    name: Some("Alicia".into()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(renamed.name, "Alicia");
+        assert_eq!(svc.list_users().await.unwrap().len(), 1);
+
+        svc.delete_user(&user.id).await.unwrap();
+        assert!(svc.get_task(&task.id).await.unwrap().assignee_id.is_none());
+    }
+
+    #[tokio::test]
+    async fn list_tasks_with_text_and_unassigned() {
+        let (svc, _server) = setup().await;
+        let project = svc.create_project(&test_project()).await.unwrap();
+        let user = svc
+            .create_user(&CreateUser {
+                name: "Alice".into(),
+                email: String::new(),
+            })
+            .await
+            .unwrap();
+        let mut tasks = Vec::new();
+        for title in ["Fix 50% & more", "Fix login", "Write docs"] {
+            let task = svc
+                .create_task(&CreateTask {
+                    title: title.into(),
+                    ..test_task(&project.id)
+                })
+                .await
+                .unwrap();
+            tasks.push(task);
+        }
+        svc.update_task(
+            &tasks[1].id,
+            &UpdateTask {
+                assignee_id: Some(Some(user.id.clone())),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        // reserved characters in the text survive the query string
+        let found = svc
+            .list_tasks(&TaskFilter {
+                project_id: Some(project.id.clone()),
+                text: Some("50% &".into()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].title, "Fix 50% & more");
+
+        let unassigned_fixes = svc
+            .list_tasks(&TaskFilter {
+                project_id: Some(project.id.clone()),
+                text: Some("fix".into()),
+                unassigned: true,
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(unassigned_fixes.len(), 1);
+        assert_eq!(unassigned_fixes[0].id, tasks[0].id);
+    }
+
     // ---- base_url trailing slash trimming ----
 
     #[tokio::test]
@@ -1414,4 +2396,20 @@ mod tests {
         let svc = HttpService::new(&url_with_slash);
         svc.health_check().await.unwrap();
     }
+
+    // ---- unix domain socket ----
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn unix_socket_base_url_routes_over_socket() {
+        let server = flowstate_server::test_helpers::spawn_unix_test_server().await;
+        assert!(server.base_url.starts_with("unix:"));
+        let svc = HttpService::new(&server.base_url);
+        svc.health_check().await.unwrap();
+        let project = svc.create_project(&test_project()).await.unwrap();
+        assert_eq!(
+            svc.get_project(&project.id).await.unwrap().slug,
+            "test-project"
+        );
+    }
 }
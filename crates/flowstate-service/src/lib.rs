@@ -1,12 +1,14 @@
 mod blocking;
 mod http;
 mod local;
+#[cfg(feature = "telemetry")]
+pub mod telemetry;
 mod traits;
 
 pub use blocking::BlockingHttpService;
 pub use http::{
     HttpService, PendingConfigResponse, RegisterResponse, RunnerStatus, RunnerUtilization,
-    SystemStatus,
+    StuckRun, SystemStatus,
 };
 pub use local::LocalService;
 pub use traits::{ServiceError, TaskService};
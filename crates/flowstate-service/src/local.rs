@@ -1,12 +1,26 @@
 use async_trait::async_trait;
+use chrono::Utc;
 use flowstate_core::attachment::Attachment;
+use flowstate_core::board::ProjectLane;
 use flowstate_core::claude_run::{ClaudeRun, CreateClaudeRun};
+use flowstate_core::custom_field::{
+    CreateCustomField, CustomField, TaskFieldValue, UpdateCustomField,
+};
+use flowstate_core::dashboard::ProjectDashboard;
+use flowstate_core::epic::{CreateEpic, Epic, UpdateEpic};
+use flowstate_core::feedback::FeedbackEntry;
+use flowstate_core::notification::Notification;
 use flowstate_core::project::{CreateProject, Project, UpdateProject};
-use flowstate_core::sprint::{CreateSprint, Sprint, UpdateSprint};
-use flowstate_core::task::{CreateTask, Task, TaskFilter, UpdateTask};
+use flowstate_core::saved_filter::{CreateSavedFilter, SavedFilter, UpdateSavedFilter};
+use flowstate_core::sprint::{
+    CompleteSprint, CreateSprint, Sprint, SprintRollover, SprintStatus, UpdateSprint,
+};
+use flowstate_core::task::{CreateTask, Status, Task, TaskFilter, UpdateTask};
 use flowstate_core::task_link::{CreateTaskLink, TaskLink};
 use flowstate_core::task_pr::{CreateTaskPr, TaskPr};
+use flowstate_core::user::{CreateUser, UpdateUser, User};
 use flowstate_db::Database;
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use crate::{ServiceError, TaskService};
@@ -20,6 +34,117 @@ impl LocalService {
     pub fn new(db: Arc<dyn Database>) -> Self {
         Self { db }
     }
+
+    /// Check custom field values against their fields' types (and, given a
+    /// project, that the fields belong to it), returning them in stored
+    /// form. Cleared (`None`) values pass through.
+    async fn normalize_field_values(
+        &self,
+        project_id: Option<&str>,
+        values: &BTreeMap<String, Option<String>>,
+    ) -> Result<BTreeMap<String, Option<String>>, ServiceError> {
+        let mut normalized = BTreeMap::new();
+        for (field_id, value) in values {
+            let field = self.db.get_custom_field(field_id).await?;
+            if project_id.is_some_and(|p| p != field.project_id) {
+                return Err(ServiceError::InvalidInput(format!(
+                    "custom field {field_id} belongs to a different project"
+                )));
+            }
+            let value = match value {
+                Some(v) => Some(field.normalize_value(v)?),
+                None => None,
+            };
+            normalized.insert(field_id.clone(), value);
+        }
+        Ok(normalized)
+    }
+
+    /// A project runs one sprint at a time; refuse to activate `sprint`
+    /// while another is active.
+    async fn check_no_other_active_sprint(&self, sprint: &Sprint) -> Result<(), ServiceError> {
+        let sprints = self.db.list_sprints(&sprint.project_id).await?;
+        match sprints
+            .iter()
+            .find(|s| s.id != sprint.id && s.status == SprintStatus::Active && !s.archived)
+        {
+            Some(active) => Err(ServiceError::InvalidInput(format!(
+                "sprint {} is already active; complete it first",
+                active.name
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Reject a field name already used in the project.
+    async fn check_field_name_free(
+        &self,
+        project_id: &str,
+        name: &str,
+        except_id: Option<&str>,
+    ) -> Result<(), ServiceError> {
+        let taken = self
+            .db
+            .list_custom_fields(project_id)
+            .await?
+            .iter()
+            .any(|f| f.name == name && Some(f.id.as_str()) != except_id);
+        if taken {
+            return Err(ServiceError::InvalidInput(format!(
+                "a custom field named {name:?} already exists"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Reject a filter name already used by the same owner in the project.
+    async fn check_filter_name_free(
+        &self,
+        project_id: &str,
+        user_id: Option<&str>,
+        name: &str,
+        except_id: Option<&str>,
+    ) -> Result<(), ServiceError> {
+        let taken = self
+            .db
+            .list_saved_filters(project_id, None)
+            .await?
+            .iter()
+            .any(|f| {
+                f.name == name
+                    && f.user_id.as_deref() == user_id
+                    && Some(f.id.as_str()) != except_id
+            });
+        if taken {
+            return Err(ServiceError::InvalidInput(format!(
+                "a saved filter named {name:?} already exists"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Reject an email already used by another user. Empty emails never clash.
+    async fn check_email_free(
+        &self,
+        email: &str,
+        except_id: Option<&str>,
+    ) -> Result<(), ServiceError> {
+        if email.is_empty() {
+            return Ok(());
+        }
+        let taken = self
+            .db
+            .list_users()
+            .await?
+            .iter()
+            .any(|u| u.email == email && Some(u.id.as_str()) != except_id);
+        if taken {
+            return Err(ServiceError::InvalidInput(format!(
+                "a user with email {email:?} already exists"
+            )));
+        }
+        Ok(())
+    }
 }
 
 impl From<flowstate_db::DbError> for ServiceError {
@@ -61,8 +186,21 @@ impl TaskService for LocalService {
         Ok(self.db.delete_project(id).await?)
     }
 
+    async fn project_dashboard(&self, id: &str) -> Result<ProjectDashboard, ServiceError> {
+        // The aggregate query can't tell an empty project from a missing one
+        self.db.get_project(id).await?;
+        Ok(self.db.project_dashboard(id).await?)
+    }
+
     async fn list_tasks(&self, filter: &TaskFilter) -> Result<Vec<Task>, ServiceError> {
-        Ok(self.db.list_tasks(filter).await?)
+        let Some((ref field_id, ref value)) = filter.custom_field else {
+            return Ok(self.db.list_tasks(filter).await?);
+        };
+        // Stored values are normalized, so the filter value must be too.
+        let field = self.db.get_custom_field(field_id).await?;
+        let mut filter = filter.clone();
+        filter.custom_field = Some((field_id.clone(), field.normalize_value(value)?));
+        Ok(self.db.list_tasks(&filter).await?)
     }
 
     async fn get_task(&self, id: &str) -> Result<Task, ServiceError> {
@@ -74,13 +212,69 @@ impl TaskService for LocalService {
     }
 
     async fn update_task(&self, id: &str, update: &UpdateTask) -> Result<Task, ServiceError> {
-        Ok(self.db.update_task(id, update).await?)
+        if update.custom_fields.is_empty() {
+            return Ok(self.db.update_task(id, update).await?);
+        }
+        let task = self.db.get_task(id).await?;
+        let mut update = update.clone();
+        update.custom_fields = self
+            .normalize_field_values(Some(&task.project_id), &update.custom_fields)
+            .await?;
+        Ok(self.db.update_task(id, &update).await?)
+    }
+
+    async fn bulk_create_tasks(&self, inputs: &[CreateTask]) -> Result<Vec<Task>, ServiceError> {
+        Ok(self.db.bulk_create_tasks(inputs).await?)
+    }
+
+    async fn bulk_update_tasks(
+        &self,
+        ids: &[String],
+        update: &UpdateTask,
+    ) -> Result<Vec<Task>, ServiceError> {
+        if update.custom_fields.is_empty() {
+            return Ok(self.db.bulk_update_tasks(ids, update).await?);
+        }
+        let mut update = update.clone();
+        update.custom_fields = self
+            .normalize_field_values(None, &update.custom_fields)
+            .await?;
+        Ok(self.db.bulk_update_tasks(ids, &update).await?)
+    }
+
+    async fn reorder_task(&self, id: &str, after_id: Option<&str>) -> Result<Task, ServiceError> {
+        if let Some(after_id) = after_id {
+            if after_id == id {
+                return Err(ServiceError::InvalidInput(
+                    "a task cannot be placed after itself".into(),
+                ));
+            }
+            let task = self.db.get_task(id).await?;
+            let after = self.db.get_task(after_id).await?;
+            if after.project_id != task.project_id || after.status != task.status {
+                return Err(ServiceError::InvalidInput(format!(
+                    "task {after_id} is not in the same column as {id}"
+                )));
+            }
+        }
+        Ok(self.db.reorder_task(id, after_id).await?)
     }
 
     async fn delete_task(&self, id: &str) -> Result<(), ServiceError> {
         Ok(self.db.delete_task(id).await?)
     }
 
+    async fn archive_task(&self, id: &str) -> Result<Task, ServiceError> {
+        let task = self.db.get_task(id).await?;
+        if !matches!(task.status, Status::Done | Status::Cancelled) {
+            return Err(ServiceError::InvalidInput(format!(
+                "only done or cancelled tasks can be archived (current: {})",
+                task.status.display_name()
+            )));
+        }
+        Ok(self.db.archive_task(id).await?)
+    }
+
     async fn count_tasks_by_status(
         &self,
         project_id: &str,
@@ -88,10 +282,42 @@ impl TaskService for LocalService {
         Ok(self.db.count_tasks_by_status(project_id).await?)
     }
 
+    async fn rollup_board(&self, project_ids: &[String]) -> Result<Vec<ProjectLane>, ServiceError> {
+        let projects = if project_ids.is_empty() {
+            self.db.list_projects().await?
+        } else {
+            let mut projects = Vec::with_capacity(project_ids.len());
+            for id in project_ids {
+                projects.push(self.db.get_project(id).await?);
+            }
+            projects
+        };
+        let mut lanes = Vec::with_capacity(projects.len());
+        for project in projects {
+            let tasks = self
+                .db
+                .list_tasks(&TaskFilter {
+                    project_id: Some(project.id.clone()),
+                    parent_id: Some(None),
+                    ..Default::default()
+                })
+                .await?;
+            lanes.push(ProjectLane { project, tasks });
+        }
+        Ok(lanes)
+    }
+
     async fn list_child_tasks(&self, parent_id: &str) -> Result<Vec<Task>, ServiceError> {
         Ok(self.db.list_child_tasks(parent_id).await?)
     }
 
+    async fn list_feedback_history(
+        &self,
+        task_id: &str,
+    ) -> Result<Vec<FeedbackEntry>, ServiceError> {
+        Ok(self.db.list_feedback_history(task_id).await?)
+    }
+
     async fn create_sprint(&self, input: &CreateSprint) -> Result<Sprint, ServiceError> {
         Ok(self.db.create_sprint(input).await?)
     }
@@ -105,6 +331,10 @@ impl TaskService for LocalService {
     }
 
     async fn update_sprint(&self, id: &str, update: &UpdateSprint) -> Result<Sprint, ServiceError> {
+        if update.status == Some(SprintStatus::Active) {
+            let sprint = self.db.get_sprint(id).await?;
+            self.check_no_other_active_sprint(&sprint).await?;
+        }
         Ok(self.db.update_sprint(id, update).await?)
     }
 
@@ -112,6 +342,278 @@ impl TaskService for LocalService {
         Ok(self.db.delete_sprint(id).await?)
     }
 
+    async fn archive_sprint(&self, id: &str) -> Result<Sprint, ServiceError> {
+        let sprint = self.db.get_sprint(id).await?;
+        if sprint.status != SprintStatus::Completed {
+            return Err(ServiceError::InvalidInput(format!(
+                "only completed sprints can be archived (current: {})",
+                sprint.status.display_name()
+            )));
+        }
+        Ok(self.db.archive_sprint(id).await?)
+    }
+
+    async fn start_sprint(&self, id: &str) -> Result<Sprint, ServiceError> {
+        let sprint = self.db.get_sprint(id).await?;
+        if sprint.status != SprintStatus::Planned {
+            return Err(ServiceError::InvalidInput(format!(
+                "only planned sprints can be started (current: {})",
+                sprint.status.display_name()
+            )));
+        }
+        self.check_no_other_active_sprint(&sprint).await?;
+        let update = UpdateSprint {
+            status: Some(SprintStatus::Active),
+            starts_at: sprint.starts_at.is_none().then(|| Some(Utc::now())),
+            ..Default::default()
+        };
+        Ok(self.db.update_sprint(id, &update).await?)
+    }
+
+    async fn complete_sprint(
+        &self,
+        id: &str,
+        input: &CompleteSprint,
+    ) -> Result<SprintRollover, ServiceError> {
+        let sprint = self.db.get_sprint(id).await?;
+        if sprint.status != SprintStatus::Active {
+            return Err(ServiceError::InvalidInput(format!(
+                "only active sprints can be completed (current: {})",
+                sprint.status.display_name()
+            )));
+        }
+        if let Some(next_id) = &input.next_sprint_id {
+            let next = self.db.get_sprint(next_id).await?;
+            if next.id == sprint.id
+                || next.project_id != sprint.project_id
+                || next.status == SprintStatus::Completed
+                || next.archived
+            {
+                return Err(ServiceError::InvalidInput(format!(
+                    "tasks can only roll over into another open sprint of the same project, not {}",
+                    next.name
+                )));
+            }
+        }
+
+        // Tasks move before the sprint closes, so a failure leaves it active
+        // to complete again
+        let tasks = self
+            .db
+            .list_tasks(&TaskFilter {
+                sprint_id: Some(id.to_string()),
+                ..Default::default()
+            })
+            .await?;
+        let (finished, unfinished): (Vec<_>, Vec<_>) = tasks
+            .into_iter()
+            .partition(|t| matches!(t.status, Status::Done | Status::Cancelled));
+        let unfinished: Vec<String> = unfinished.into_iter().map(|t| t.id).collect();
+        let rolled_over = if unfinished.is_empty() {
+            vec![]
+        } else {
+            self.db
+                .bulk_update_tasks(
+                    &unfinished,
+                    &UpdateTask {
+                        sprint_id: Some(input.next_sprint_id.clone()),
+                        ..Default::default()
+                    },
+                )
+                .await?
+        };
+
+        let update = UpdateSprint {
+            status: Some(SprintStatus::Completed),
+            ends_at: sprint.ends_at.is_none().then(|| Some(Utc::now())),
+            ..Default::default()
+        };
+        let sprint = self.db.update_sprint(id, &update).await?;
+        Ok(SprintRollover {
+            sprint,
+            next_sprint_id: input.next_sprint_id.clone(),
+            done: finished.iter().filter(|t| t.status == Status::Done).count(),
+            cancelled: finished
+                .iter()
+                .filter(|t| t.status == Status::Cancelled)
+                .count(),
+            rolled_over,
+        })
+    }
+
+    async fn create_epic(&self, input: &CreateEpic) -> Result<Epic, ServiceError> {
+        Ok(self.db.create_epic(input).await?)
+    }
+
+    async fn get_epic(&self, id: &str) -> Result<Epic, ServiceError> {
+        Ok(self.db.get_epic(id).await?)
+    }
+
+    async fn list_epics(&self, project_id: &str) -> Result<Vec<Epic>, ServiceError> {
+        Ok(self.db.list_epics(project_id).await?)
+    }
+
+    async fn update_epic(&self, id: &str, update: &UpdateEpic) -> Result<Epic, ServiceError> {
+        Ok(self.db.update_epic(id, update).await?)
+    }
+
+    async fn delete_epic(&self, id: &str) -> Result<(), ServiceError> {
+        Ok(self.db.delete_epic(id).await?)
+    }
+
+    async fn create_user(&self, input: &CreateUser) -> Result<User, ServiceError> {
+        input.validate()?;
+        self.check_email_free(&input.email, None).await?;
+        Ok(self.db.create_user(input).await?)
+    }
+
+    async fn get_user(&self, id: &str) -> Result<User, ServiceError> {
+        Ok(self.db.get_user(id).await?)
+    }
+
+    async fn list_users(&self) -> Result<Vec<User>, ServiceError> {
+        Ok(self.db.list_users().await?)
+    }
+
+    async fn update_user(&self, id: &str, update: &UpdateUser) -> Result<User, ServiceError> {
+        update.validate()?;
+        if let Some(ref email) = update.email {
+            self.check_email_free(email, Some(id)).await?;
+        }
+        Ok(self.db.update_user(id, update).await?)
+    }
+
+    async fn delete_user(&self, id: &str) -> Result<(), ServiceError> {
+        Ok(self.db.delete_user(id).await?)
+    }
+
+    async fn create_saved_filter(
+        &self,
+        input: &CreateSavedFilter,
+    ) -> Result<SavedFilter, ServiceError> {
+        input.validate()?;
+        self.db.get_project(&input.project_id).await?;
+        if let Some(ref user_id) = input.user_id {
+            self.db.get_user(user_id).await?;
+        }
+        self.check_filter_name_free(
+            &input.project_id,
+            input.user_id.as_deref(),
+            &input.name,
+            None,
+        )
+        .await?;
+        Ok(self.db.create_saved_filter(input).await?)
+    }
+
+    async fn get_saved_filter(&self, id: &str) -> Result<SavedFilter, ServiceError> {
+        Ok(self.db.get_saved_filter(id).await?)
+    }
+
+    async fn list_saved_filters(
+        &self,
+        project_id: &str,
+        user_id: Option<&str>,
+    ) -> Result<Vec<SavedFilter>, ServiceError> {
+        Ok(self.db.list_saved_filters(project_id, user_id).await?)
+    }
+
+    async fn update_saved_filter(
+        &self,
+        id: &str,
+        update: &UpdateSavedFilter,
+    ) -> Result<SavedFilter, ServiceError> {
+        update.validate()?;
+        if let Some(ref name) = update.name {
+            let filter = self.db.get_saved_filter(id).await?;
+            self.check_filter_name_free(
+                &filter.project_id,
+                filter.user_id.as_deref(),
+                name,
+                Some(id),
+            )
+            .await?;
+        }
+        Ok(self.db.update_saved_filter(id, update).await?)
+    }
+
+    async fn delete_saved_filter(&self, id: &str) -> Result<(), ServiceError> {
+        Ok(self.db.delete_saved_filter(id).await?)
+    }
+
+    async fn watch_task(&self, task_id: &str, user_id: &str) -> Result<Vec<User>, ServiceError> {
+        self.db.get_task(task_id).await?;
+        self.db.get_user(user_id).await?;
+        self.db.watch_task(task_id, user_id).await?;
+        Ok(self.db.list_task_watchers(task_id).await?)
+    }
+
+    async fn unwatch_task(&self, task_id: &str, user_id: &str) -> Result<(), ServiceError> {
+        self.db.get_task(task_id).await?;
+        Ok(self.db.unwatch_task(task_id, user_id).await?)
+    }
+
+    async fn list_task_watchers(&self, task_id: &str) -> Result<Vec<User>, ServiceError> {
+        self.db.get_task(task_id).await?;
+        Ok(self.db.list_task_watchers(task_id).await?)
+    }
+
+    async fn list_notifications(
+        &self,
+        user_id: &str,
+        unread_only: bool,
+    ) -> Result<Vec<Notification>, ServiceError> {
+        self.db.get_user(user_id).await?;
+        Ok(self.db.list_notifications(user_id, unread_only).await?)
+    }
+
+    async fn mark_notification_read(&self, id: &str) -> Result<Notification, ServiceError> {
+        Ok(self.db.mark_notification_read(id).await?)
+    }
+
+    async fn create_custom_field(
+        &self,
+        input: &CreateCustomField,
+    ) -> Result<CustomField, ServiceError> {
+        input.validate()?;
+        self.check_field_name_free(&input.project_id, &input.name, None)
+            .await?;
+        Ok(self.db.create_custom_field(input).await?)
+    }
+
+    async fn get_custom_field(&self, id: &str) -> Result<CustomField, ServiceError> {
+        Ok(self.db.get_custom_field(id).await?)
+    }
+
+    async fn list_custom_fields(&self, project_id: &str) -> Result<Vec<CustomField>, ServiceError> {
+        Ok(self.db.list_custom_fields(project_id).await?)
+    }
+
+    async fn update_custom_field(
+        &self,
+        id: &str,
+        update: &UpdateCustomField,
+    ) -> Result<CustomField, ServiceError> {
+        let field = self.db.get_custom_field(id).await?;
+        update.validate(&field)?;
+        if let Some(ref name) = update.name {
+            self.check_field_name_free(&field.project_id, name, Some(id))
+                .await?;
+        }
+        Ok(self.db.update_custom_field(id, update).await?)
+    }
+
+    async fn delete_custom_field(&self, id: &str) -> Result<(), ServiceError> {
+        Ok(self.db.delete_custom_field(id).await?)
+    }
+
+    async fn list_task_field_values(
+        &self,
+        task_id: &str,
+    ) -> Result<Vec<TaskFieldValue>, ServiceError> {
+        Ok(self.db.list_task_field_values(task_id).await?)
+    }
+
     async fn create_task_link(&self, input: &CreateTaskLink) -> Result<TaskLink, ServiceError> {
         Ok(self.db.create_task_link(input).await?)
     }
@@ -154,7 +656,7 @@ mod tests {
     use super::*;
     use flowstate_core::project::CreateProject;
     use flowstate_core::sprint::CreateSprint;
-    use flowstate_core::task::{CreateTask, Priority, Status, TaskFilter, UpdateTask};
+    use flowstate_core::task::{CreateTask, Priority, Status, TaskFilter, TaskType, UpdateTask};
     use flowstate_db::SqliteDatabase;
 
     async fn make_service() -> LocalService {
@@ -243,6 +745,7 @@ mod tests {
                 description: "Do something".into(),
                 status: Status::Todo,
                 priority: Priority::Medium,
+                task_type: TaskType::Feature,
                 parent_id: None,
                 reviewer: String::new(),
                 research_capability: None,
@@ -250,6 +753,7 @@ mod tests {
                 plan_capability: None,
                 build_capability: None,
                 verify_capability: None,
+                due_at: None,
             })
             .await
             .unwrap();
@@ -342,6 +846,207 @@ mod tests {
         assert_eq!(sprints.len(), 0);
     }
 
+    #[tokio::test]
+    async fn local_service_archives_only_finished_work() {
+        let svc = make_service().await;
+        let project = svc
+            .create_project(&CreateProject {
+                name: "Archive".into(),
+                slug: "archive".into(),
+                description: String::new(),
+                repo_url: String::new(),
+            })
+            .await
+            .unwrap();
+        let sprint = svc
+            .create_sprint(&CreateSprint {
+                project_id: project.id.clone(),
+                name: "Sprint 1".into(),
+                goal: String::new(),
+                starts_at: None,
+                ends_at: None,
+            })
+            .await
+            .unwrap();
+        let err = svc.archive_sprint(&sprint.id).await.unwrap_err();
+        assert!(matches!(err, ServiceError::InvalidInput(_)));
+
+        svc.update_sprint(
+            &sprint.id,
+            &flowstate_core::sprint::UpdateSprint {
+                status: Some(SprintStatus::Completed),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert!(svc.archive_sprint(&sprint.id).await.unwrap().archived);
+
+        let task = svc
+            .create_task(&CreateTask {
+                project_id: project.id.clone(),
+                title: "Open".into(),
+                description: String::new(),
+                status: Status::Build,
+                priority: Priority::Medium,
+                task_type: TaskType::Feature,
+                parent_id: None,
+                reviewer: String::new(),
+                research_capability: None,
+                design_capability: None,
+                plan_capability: None,
+                build_capability: None,
+                verify_capability: None,
+                due_at: None,
+            })
+            .await
+            .unwrap();
+        let err = svc.archive_task(&task.id).await.unwrap_err();
+        assert!(matches!(err, ServiceError::InvalidInput(_)));
+    }
+
+    #[tokio::test]
+    async fn local_service_sprint_lifecycle_rolls_over_unfinished_tasks() {
+        let svc = make_service().await;
+        let project = svc
+            .create_project(&CreateProject {
+                name: "Lifecycle".into(),
+                slug: "lifecycle".into(),
+                description: String::new(),
+                repo_url: String::new(),
+            })
+            .await
+            .unwrap();
+        let sprint = |name: &str| CreateSprint {
+            project_id: project.id.clone(),
+            name: name.into(),
+            goal: String::new(),
+            starts_at: None,
+            ends_at: None,
+        };
+        let first = svc.create_sprint(&sprint("Sprint 1")).await.unwrap();
+        let second = svc.create_sprint(&sprint("Sprint 2")).await.unwrap();
+
+        let started = svc.start_sprint(&first.id).await.unwrap();
+        assert_eq!(started.status, SprintStatus::Active);
+        assert!(started.starts_at.is_some());
+        // One active sprint per project, however it is activated
+        let err = svc.start_sprint(&second.id).await.unwrap_err();
+        assert!(matches!(err, ServiceError::InvalidInput(_)));
+        let err = svc
+            .update_sprint(
+                &second.id,
+                &UpdateSprint {
+                    status: Some(SprintStatus::Active),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServiceError::InvalidInput(_)));
+        let err = svc.start_sprint(&first.id).await.unwrap_err();
+        assert!(matches!(err, ServiceError::InvalidInput(_)));
+
+        let mut ids = Vec::new();
+        for (title, status) in [
+            ("Shipped", Status::Done),
+            ("Dropped", Status::Cancelled),
+            ("Halfway", Status::Build),
+            ("Untouched", Status::Todo),
+        ] {
+            let task = svc
+                .create_task(&CreateTask {
+                    project_id: project.id.clone(),
+                    title: title.into(),
+                    description: String::new(),
+                    status,
+                    priority: Priority::Medium,
+                    task_type: TaskType::Feature,
+                    parent_id: None,
+                    reviewer: String::new(),
+                    research_capability: None,
+                    design_capability: None,
+                    plan_capability: None,
+                    build_capability: None,
+                    verify_capability: None,
+                    due_at: None,
+                })
+                .await
+                .unwrap();
+            ids.push(task.id);
+        }
+        svc.bulk_update_tasks(
+            &ids,
+            &UpdateTask {
+                sprint_id: Some(Some(first.id.clone())),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        // Only into an open sprint of the same project
+        let err = svc
+            .complete_sprint(
+                &first.id,
+                &CompleteSprint {
+                    next_sprint_id: Some(first.id.clone()),
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServiceError::InvalidInput(_)));
+        let err = svc
+            .complete_sprint(&second.id, &CompleteSprint::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ServiceError::InvalidInput(_)));
+
+        let rollover = svc
+            .complete_sprint(
+                &first.id,
+                &CompleteSprint {
+                    next_sprint_id: Some(second.id.clone()),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(rollover.sprint.status, SprintStatus::Completed);
+        assert!(rollover.sprint.ends_at.is_some());
+        assert_eq!((rollover.done, rollover.cancelled), (1, 1));
+        let mut rolled: Vec<_> = rollover
+            .rolled_over
+            .iter()
+            .map(|t| (t.title.as_str(), t.sprint_id.as_deref()))
+            .collect();
+        rolled.sort();
+        assert_eq!(
+            rolled,
+            vec![
+                ("Halfway", Some(second.id.as_str())),
+                ("Untouched", Some(second.id.as_str())),
+            ]
+        );
+        let kept = svc
+            .list_tasks(&TaskFilter {
+                sprint_id: Some(first.id.clone()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(kept.len(), 2);
+
+        // With the first one done, the next can start; completing it
+        // without a next sprint sends its tasks to the backlog
+        svc.start_sprint(&second.id).await.unwrap();
+        let rollover = svc
+            .complete_sprint(&second.id, &CompleteSprint::default())
+            .await
+            .unwrap();
+        assert_eq!(rollover.rolled_over.len(), 2);
+        assert!(rollover.rolled_over.iter().all(|t| t.sprint_id.is_none()));
+    }
+
     #[tokio::test]
     async fn local_service_not_found_error() {
         let svc = make_service().await;
@@ -0,0 +1,238 @@
+//! OpenTelemetry tracing for the server and the runner.
+//!
+//! With `FLOWSTATE_OTLP_ENDPOINT` set, spans are exported over OTLP/HTTP
+//! to `<endpoint>/v1/traces`. Trace context crosses processes as a W3C
+//! `traceparent`: on every request [`HttpService`](crate::HttpService)
+//! makes, and on each queued run, so a run executing on a runner joins the
+//! trace of the request that queued it.
+
+use std::collections::HashMap;
+
+use opentelemetry::propagation::TextMapPropagator;
+use opentelemetry::trace::{TraceContextExt, TracerProvider as _};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::{Sampler, SdkTracerProvider};
+use opentelemetry_sdk::Resource;
+use tracing::Span;
+use tracing_opentelemetry::{OpenTelemetryLayer, OpenTelemetrySpanExt};
+use tracing_subscriber::registry::LookupSpan;
+
+type Error = Box<dyn std::error::Error + Send + Sync>;
+
+/// Where and how much to export, from `FLOWSTATE_OTLP_*`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelemetryConfig {
+    /// Collector base URL, such as `http://localhost:4318`.
+    pub endpoint: String,
+    /// Share of new traces kept, from 0 to 1. Traces started elsewhere
+    /// follow the sampling decision in their `traceparent`.
+    pub sample_ratio: f64,
+}
+
+impl TelemetryConfig {
+    /// `Ok(None)` when `FLOWSTATE_OTLP_ENDPOINT` is unset.
+    pub fn from_env() -> Result<Option<Self>, Error> {
+        Self::from_getter(|key| std::env::var(key).ok())
+    }
+
+    /// Build from an arbitrary variable-lookup function (testable without env mutation).
+    pub fn from_getter(get: impl Fn(&str) -> Option<String>) -> Result<Option<Self>, Error> {
+        let get = |key: &str| get(key).filter(|v| !v.trim().is_empty());
+        let Some(endpoint) = get("FLOWSTATE_OTLP_ENDPOINT") else {
+            return Ok(None);
+        };
+        let sample_ratio = match get("FLOWSTATE_OTLP_SAMPLE_RATIO") {
+            Some(v) => match v.parse::<f64>() {
+                Ok(ratio) if (0.0..=1.0).contains(&ratio) => ratio,
+                _ => {
+                    return Err(format!(
+                        "FLOWSTATE_OTLP_SAMPLE_RATIO: {v:?} is not between 0 and 1"
+                    )
+                    .into())
+                }
+            },
+            None => 1.0,
+        };
+        Ok(Some(Self {
+            endpoint,
+            sample_ratio,
+        }))
+    }
+
+    /// The OTLP/HTTP traces URL under the endpoint.
+    pub fn traces_url(&self) -> String {
+        let base = self.endpoint.trim_end_matches('/');
+        if base.ends_with("/v1/traces") {
+            base.to_string()
+        } else {
+            format!("{base}/v1/traces")
+        }
+    }
+}
+
+/// An installed trace exporter. Dropping it flushes the spans not yet sent.
+pub struct Telemetry {
+    provider: SdkTracerProvider,
+}
+
+impl Telemetry {
+    /// Start exporting as `service_name` when `FLOWSTATE_OTLP_ENDPOINT` is
+    /// set. Pass [`Telemetry::layer`] to the tracing subscriber.
+    pub fn from_env(service_name: &'static str) -> Result<Option<Self>, Error> {
+        TelemetryConfig::from_env()?
+            .map(|config| Self::new(&config, service_name))
+            .transpose()
+    }
+
+    pub fn new(config: &TelemetryConfig, service_name: &'static str) -> Result<Self, Error> {
+        let exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_http()
+            .with_endpoint(config.traces_url())
+            .build()?;
+        let provider = SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+                config.sample_ratio,
+            ))))
+            .with_resource(Resource::builder().with_service_name(service_name).build())
+            .build();
+        Ok(Self { provider })
+    }
+
+    /// A tracing layer sending spans to this exporter.
+    pub fn layer<S>(&self) -> OpenTelemetryLayer<S, opentelemetry_sdk::trace::Tracer>
+    where
+        S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+    {
+        tracing_opentelemetry::layer().with_tracer(self.provider.tracer("flowstate"))
+    }
+}
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        if let Err(e) = self.provider.shutdown() {
+            eprintln!("flushing traces: {e}");
+        }
+    }
+}
+
+/// The W3C `traceparent` of the current span, or `None` when it is not
+/// being traced.
+pub fn current_traceparent() -> Option<String> {
+    let cx = Span::current().context();
+    if !cx.span().span_context().is_valid() {
+        return None;
+    }
+    let mut carrier = HashMap::new();
+    TraceContextPropagator::new().inject_context(&cx, &mut carrier);
+    carrier.remove("traceparent")
+}
+
+/// Continue the trace in `traceparent` with `span`. An unreadable value
+/// leaves `span` where it is.
+pub fn set_parent(span: &Span, traceparent: &str) {
+    let cx = extract(traceparent);
+    if cx.span().span_context().is_valid() {
+        let _ = span.set_parent(cx);
+    }
+}
+
+/// Point `span` at the trace in `traceparent` without joining it, for work
+/// done on behalf of a trace that already has its own parent.
+pub fn add_link(span: &Span, traceparent: &str) {
+    span.add_link(extract(traceparent).span().span_context().clone());
+}
+
+/// Continue the trace named by an incoming request's `traceparent` header.
+pub fn set_parent_from_headers(span: &Span, headers: &reqwest::header::HeaderMap) {
+    if let Some(traceparent) = headers.get("traceparent").and_then(|v| v.to_str().ok()) {
+        set_parent(span, traceparent);
+    }
+}
+
+/// Carry the current span's trace on an outgoing request.
+pub(crate) fn inject(builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    match current_traceparent() {
+        Some(traceparent) => builder.header("traceparent", traceparent),
+        None => builder,
+    }
+}
+
+fn extract(traceparent: &str) -> opentelemetry::Context {
+    let carrier = HashMap::from([("traceparent".to_string(), traceparent.to_string())]);
+    TraceContextPropagator::new().extract(&carrier)
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry_sdk::trace::InMemorySpanExporter;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use super::*;
+
+    #[test]
+    fn config_reads_endpoint_and_ratio() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |key: &str| {
+                vars.iter()
+                    .find(|(k, _)| *k == key)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+        assert_eq!(TelemetryConfig::from_getter(env(&[])).unwrap(), None);
+        let config = TelemetryConfig::from_getter(env(&[(
+            "FLOWSTATE_OTLP_ENDPOINT",
+            "http://collector:4318/",
+        )]))
+        .unwrap()
+        .unwrap();
+        assert_eq!(config.sample_ratio, 1.0);
+        assert_eq!(config.traces_url(), "http://collector:4318/v1/traces");
+        assert!(TelemetryConfig::from_getter(env(&[
+            ("FLOWSTATE_OTLP_ENDPOINT", "http://collector:4318"),
+            ("FLOWSTATE_OTLP_SAMPLE_RATIO", "1.5"),
+        ]))
+        .is_err());
+    }
+
+    #[test]
+    fn traceparent_round_trips_through_spans() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let subscriber = tracing_subscriber::registry()
+            .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+
+        tracing::subscriber::with_default(subscriber, || {
+            assert_eq!(current_traceparent(), None);
+            let queued = tracing::info_span!("queue");
+            let traceparent = queued.in_scope(current_traceparent).unwrap();
+            drop(queued);
+
+            let executed = tracing::info_span!("execute");
+            set_parent(&executed, &traceparent);
+            let child = executed.in_scope(current_traceparent).unwrap();
+            drop(executed);
+            // Same trace, new span
+            assert_eq!(child[..35], traceparent[..35]);
+            assert_ne!(child, traceparent);
+
+            let ignored = tracing::info_span!("garbage");
+            set_parent(&ignored, "not a traceparent");
+        });
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let trace_of = |name: &str| {
+            spans
+                .iter()
+                .find(|s| s.name == name)
+                .unwrap()
+                .span_context
+                .trace_id()
+        };
+        assert_eq!(trace_of("queue"), trace_of("execute"));
+        assert_ne!(trace_of("queue"), trace_of("garbage"));
+    }
+}
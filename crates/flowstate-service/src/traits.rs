@@ -1,11 +1,21 @@
 use async_trait::async_trait;
 use flowstate_core::attachment::Attachment;
+use flowstate_core::board::ProjectLane;
 use flowstate_core::claude_run::{ClaudeRun, CreateClaudeRun};
+use flowstate_core::custom_field::{
+    CreateCustomField, CustomField, TaskFieldValue, UpdateCustomField,
+};
+use flowstate_core::dashboard::ProjectDashboard;
+use flowstate_core::epic::{CreateEpic, Epic, UpdateEpic};
+use flowstate_core::feedback::FeedbackEntry;
+use flowstate_core::notification::Notification;
 use flowstate_core::project::{CreateProject, Project, UpdateProject};
-use flowstate_core::sprint::{CreateSprint, Sprint, UpdateSprint};
+use flowstate_core::saved_filter::{CreateSavedFilter, SavedFilter, UpdateSavedFilter};
+use flowstate_core::sprint::{CompleteSprint, CreateSprint, Sprint, SprintRollover, UpdateSprint};
 use flowstate_core::task::{CreateTask, Task, TaskFilter, UpdateTask};
 use flowstate_core::task_link::{CreateTaskLink, TaskLink};
 use flowstate_core::task_pr::{CreateTaskPr, TaskPr};
+use flowstate_core::user::{CreateUser, UpdateUser, User};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -20,6 +30,16 @@ pub enum ServiceError {
     Internal(String),
 }
 
+impl From<flowstate_core::FlowstateError> for ServiceError {
+    fn from(e: flowstate_core::FlowstateError) -> Self {
+        match e {
+            flowstate_core::FlowstateError::NotFound(msg) => ServiceError::NotFound(msg),
+            flowstate_core::FlowstateError::InvalidInput(msg) => ServiceError::InvalidInput(msg),
+            flowstate_core::FlowstateError::Database(msg) => ServiceError::Internal(msg),
+        }
+    }
+}
+
 /// Abstraction over task tracking operations.
 ///
 /// The TUI and MCP server program against this trait.
@@ -38,18 +58,37 @@ pub trait TaskService: Send + Sync {
         update: &UpdateProject,
     ) -> Result<Project, ServiceError>;
     async fn delete_project(&self, id: &str) -> Result<(), ServiceError>;
+    async fn project_dashboard(&self, id: &str) -> Result<ProjectDashboard, ServiceError>;
 
     // -- Tasks --
     async fn list_tasks(&self, filter: &TaskFilter) -> Result<Vec<Task>, ServiceError>;
     async fn get_task(&self, id: &str) -> Result<Task, ServiceError>;
     async fn create_task(&self, input: &CreateTask) -> Result<Task, ServiceError>;
     async fn update_task(&self, id: &str, update: &UpdateTask) -> Result<Task, ServiceError>;
+    async fn bulk_create_tasks(&self, inputs: &[CreateTask]) -> Result<Vec<Task>, ServiceError>;
+    async fn bulk_update_tasks(
+        &self,
+        ids: &[String],
+        update: &UpdateTask,
+    ) -> Result<Vec<Task>, ServiceError>;
+    /// Move a task to just after `after_id` (or to the top) in its column.
+    async fn reorder_task(&self, id: &str, after_id: Option<&str>) -> Result<Task, ServiceError>;
     async fn delete_task(&self, id: &str) -> Result<(), ServiceError>;
+    /// Archive a done or cancelled task along with its subtasks.
+    async fn archive_task(&self, id: &str) -> Result<Task, ServiceError>;
     async fn count_tasks_by_status(
         &self,
         project_id: &str,
     ) -> Result<Vec<(String, i64)>, ServiceError>;
+    /// One lane of top-level tasks per project, in the order given; every
+    /// project when `project_ids` is empty.
+    async fn rollup_board(&self, project_ids: &[String]) -> Result<Vec<ProjectLane>, ServiceError>;
     async fn list_child_tasks(&self, parent_id: &str) -> Result<Vec<Task>, ServiceError>;
+    /// Every rejection's feedback on a task's documents, newest first.
+    async fn list_feedback_history(
+        &self,
+        task_id: &str,
+    ) -> Result<Vec<FeedbackEntry>, ServiceError>;
 
     // -- Sprints --
     async fn create_sprint(&self, input: &CreateSprint) -> Result<Sprint, ServiceError>;
@@ -57,6 +96,80 @@ pub trait TaskService: Send + Sync {
     async fn list_sprints(&self, project_id: &str) -> Result<Vec<Sprint>, ServiceError>;
     async fn update_sprint(&self, id: &str, update: &UpdateSprint) -> Result<Sprint, ServiceError>;
     async fn delete_sprint(&self, id: &str) -> Result<(), ServiceError>;
+    /// Archive a completed sprint along with its tasks.
+    async fn archive_sprint(&self, id: &str) -> Result<Sprint, ServiceError>;
+    /// Make a planned sprint its project's active one.
+    async fn start_sprint(&self, id: &str) -> Result<Sprint, ServiceError>;
+    /// Complete the active sprint, moving its unfinished tasks on.
+    async fn complete_sprint(
+        &self,
+        id: &str,
+        input: &CompleteSprint,
+    ) -> Result<SprintRollover, ServiceError>;
+
+    // -- Epics --
+    async fn create_epic(&self, input: &CreateEpic) -> Result<Epic, ServiceError>;
+    async fn get_epic(&self, id: &str) -> Result<Epic, ServiceError>;
+    async fn list_epics(&self, project_id: &str) -> Result<Vec<Epic>, ServiceError>;
+    async fn update_epic(&self, id: &str, update: &UpdateEpic) -> Result<Epic, ServiceError>;
+    async fn delete_epic(&self, id: &str) -> Result<(), ServiceError>;
+
+    // -- Users --
+    async fn create_user(&self, input: &CreateUser) -> Result<User, ServiceError>;
+    async fn get_user(&self, id: &str) -> Result<User, ServiceError>;
+    async fn list_users(&self) -> Result<Vec<User>, ServiceError>;
+    async fn update_user(&self, id: &str, update: &UpdateUser) -> Result<User, ServiceError>;
+    async fn delete_user(&self, id: &str) -> Result<(), ServiceError>;
+
+    // -- Saved Filters --
+    async fn create_saved_filter(
+        &self,
+        input: &CreateSavedFilter,
+    ) -> Result<SavedFilter, ServiceError>;
+    async fn get_saved_filter(&self, id: &str) -> Result<SavedFilter, ServiceError>;
+    /// All of a project's filters, or with `user_id` the shared ones plus
+    /// that user's own.
+    async fn list_saved_filters(
+        &self,
+        project_id: &str,
+        user_id: Option<&str>,
+    ) -> Result<Vec<SavedFilter>, ServiceError>;
+    async fn update_saved_filter(
+        &self,
+        id: &str,
+        update: &UpdateSavedFilter,
+    ) -> Result<SavedFilter, ServiceError>;
+    async fn delete_saved_filter(&self, id: &str) -> Result<(), ServiceError>;
+
+    // -- Watchers and Notifications --
+    /// Subscribe a user to a task's updates; returns the task's watchers.
+    async fn watch_task(&self, task_id: &str, user_id: &str) -> Result<Vec<User>, ServiceError>;
+    async fn unwatch_task(&self, task_id: &str, user_id: &str) -> Result<(), ServiceError>;
+    async fn list_task_watchers(&self, task_id: &str) -> Result<Vec<User>, ServiceError>;
+    async fn list_notifications(
+        &self,
+        user_id: &str,
+        unread_only: bool,
+    ) -> Result<Vec<Notification>, ServiceError>;
+    async fn mark_notification_read(&self, id: &str) -> Result<Notification, ServiceError>;
+
+    // -- Custom Fields --
+    async fn create_custom_field(
+        &self,
+        input: &CreateCustomField,
+    ) -> Result<CustomField, ServiceError>;
+    async fn get_custom_field(&self, id: &str) -> Result<CustomField, ServiceError>;
+    async fn list_custom_fields(&self, project_id: &str) -> Result<Vec<CustomField>, ServiceError>;
+    async fn update_custom_field(
+        &self,
+        id: &str,
+        update: &UpdateCustomField,
+    ) -> Result<CustomField, ServiceError>;
+    async fn delete_custom_field(&self, id: &str) -> Result<(), ServiceError>;
+    async fn list_task_field_values(
+        &self,
+        task_id: &str,
+    ) -> Result<Vec<TaskFieldValue>, ServiceError>;
 
     // -- Task Links --
     async fn create_task_link(&self, input: &CreateTaskLink) -> Result<TaskLink, ServiceError>;
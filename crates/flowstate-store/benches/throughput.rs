@@ -0,0 +1,111 @@
+// Put/get throughput for the object store, buffered and streamed, across a
+// range of object sizes.
+//
+// The local store always runs, in a temp dir. The S3 store runs with
+// `--features bench-s3`, which then needs the usual FLOWSTATE_S3_* (or
+// AWS_*) variables; objects are written under `bench/` and deleted
+// afterwards.
+//
+// Run with:
+//   cargo bench -p flowstate-store
+//   FLOWSTATE_S3_ENDPOINT=... cargo bench -p flowstate-store --features bench-s3
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use flowstate_store::{create_store, Durability, MultipartConfig, ObjectStore, StoreConfig};
+use futures_util::{stream, StreamExt, TryStreamExt};
+use tokio::runtime::Runtime;
+
+const SIZES: [usize; 3] = [64 * 1024, 1024 * 1024, 16 * 1024 * 1024];
+/// Chunk size used to feed `put_stream`, matching a typical HTTP body frame.
+const CHUNK: usize = 64 * 1024;
+
+fn stores() -> Vec<(
+    &'static str,
+    Arc<dyn ObjectStore>,
+    Option<tempfile::TempDir>,
+)> {
+    let dir = tempfile::tempdir().unwrap();
+    let local = create_store(&StoreConfig {
+        endpoint_url: None,
+        region: None,
+        bucket: None,
+        access_key_id: None,
+        secret_access_key: None,
+        local_data_dir: Some(dir.path().to_string_lossy().into_owned()),
+        gcs: None,
+        azure: None,
+        durability: Durability::Atomic,
+        multipart: MultipartConfig::default(),
+    })
+    .unwrap();
+    #[allow(unused_mut)]
+    let mut stores = vec![("local", local, Some(dir))];
+
+    #[cfg(feature = "bench-s3")]
+    {
+        let config = StoreConfig::from_env();
+        assert!(
+            config.is_s3(),
+            "FLOWSTATE_S3_* must be set for the s3 benchmarks"
+        );
+        stores.push(("s3", create_store(&config).unwrap(), None));
+    }
+    stores
+}
+
+fn chunked(data: &Bytes) -> flowstate_store::ByteStream {
+    let chunks: Vec<_> = (0..data.len())
+        .step_by(CHUNK)
+        .map(|start| Ok(data.slice(start..(start + CHUNK).min(data.len()))))
+        .collect();
+    stream::iter(chunks).boxed()
+}
+
+fn bench_store(c: &mut Criterion) {
+    let rt = Runtime::new().unwrap();
+    for (name, store, _dir) in stores() {
+        let mut group = c.benchmark_group(format!("store/{name}"));
+        group.sample_size(10);
+        for size in SIZES {
+            let data = Bytes::from(vec![0x5a; size]);
+            let key = format!("bench/object-{size}.bin");
+            group.throughput(Throughput::Bytes(size as u64));
+
+            group.bench_with_input(BenchmarkId::new("put", size), &data, |b, data| {
+                b.to_async(&rt)
+                    .iter(|| async { store.put(&key, data.clone()).await.unwrap() });
+            });
+            group.bench_with_input(BenchmarkId::new("get", size), &size, |b, &size| {
+                b.to_async(&rt).iter(|| async {
+                    let read = store.get(&key).await.unwrap();
+                    assert_eq!(read.len(), size);
+                });
+            });
+            group.bench_with_input(BenchmarkId::new("put_stream", size), &data, |b, data| {
+                b.to_async(&rt)
+                    .iter(|| async { store.put_stream(&key, chunked(data)).await.unwrap() });
+            });
+            group.bench_with_input(BenchmarkId::new("get_stream", size), &size, |b, &size| {
+                b.to_async(&rt).iter(|| async {
+                    let read = store
+                        .get_stream(&key)
+                        .await
+                        .unwrap()
+                        .try_fold(0, |n, chunk| async move { Ok(n + chunk.len()) })
+                        .await
+                        .unwrap();
+                    assert_eq!(read, size);
+                });
+            });
+
+            rt.block_on(store.delete(&key)).unwrap();
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_store);
+criterion_main!(benches);
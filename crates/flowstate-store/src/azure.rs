@@ -0,0 +1,598 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use bytes::{Bytes, BytesMut};
+use chrono::{DateTime, Utc};
+use futures_util::{StreamExt, TryStreamExt};
+use hmac::{Hmac, Mac};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use reqwest::{Client, Method, Request, RequestBuilder, Response, StatusCode, Url};
+use sha2::Sha256;
+
+use crate::{content_type_for_key, AzureConfig, ByteStream, ObjectMeta, ObjectStore, StoreError};
+
+/// Blob service REST API version sent with every request and SAS.
+const API_VERSION: &str = "2021-08-06";
+
+/// Streamed uploads stage blocks of this size before committing them.
+const BLOCK_SIZE: usize = 8 * 1024 * 1024;
+
+/// RFC 3986 unreserved characters, the only ones left unencoded.
+const UNRESERVED: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Blob names in a URL path keep their slashes.
+const PATH: &AsciiSet = &UNRESERVED.remove(b'/');
+
+/// Azure Blob Storage, authenticated with the account's shared key.
+pub struct AzureStore {
+    client: Client,
+    endpoint: String,
+    account: String,
+    key: Vec<u8>,
+    container: String,
+}
+
+impl std::fmt::Debug for AzureStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AzureStore")
+            .field("account", &self.account)
+            .field("container", &self.container)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AzureStore {
+    pub fn new(config: &AzureConfig) -> Result<Self, StoreError> {
+        if config.container.is_empty() {
+            return Err(StoreError::Internal("container name required".into()));
+        }
+        let key = STANDARD
+            .decode(&config.access_key)
+            .map_err(|e| StoreError::Internal(format!("azure access key: {e}")))?;
+        let endpoint = config
+            .endpoint_url
+            .clone()
+            .unwrap_or_else(|| format!("https://{}.blob.core.windows.net", config.account));
+        Ok(Self {
+            client: Client::new(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            account: config.account.clone(),
+            key,
+            container: config.container.clone(),
+        })
+    }
+
+    fn blob_url(&self, key: &str) -> String {
+        format!(
+            "{}/{}/{}",
+            self.endpoint,
+            self.container,
+            utf8_percent_encode(key, PATH)
+        )
+    }
+
+    fn signature(&self, string_to_sign: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.key).expect("any key length");
+        mac.update(string_to_sign.as_bytes());
+        STANDARD.encode(mac.finalize().into_bytes())
+    }
+
+    /// Date, version and shared-key authorization headers, then send.
+    async fn send(&self, request: RequestBuilder) -> Result<Response, StoreError> {
+        let mut request: Request = request.build().map_err(map_http_error)?;
+        let date = Utc::now().format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+        let headers = request.headers_mut();
+        headers.insert("x-ms-date", header_value(&date)?);
+        headers.insert("x-ms-version", HeaderValue::from_static(API_VERSION));
+
+        let content_length = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map_or(0, |body| body.len());
+        let string_to_sign = shared_key_string_to_sign(
+            &self.account,
+            request.method(),
+            request.url(),
+            request.headers(),
+            content_length,
+        );
+        let authorization = format!(
+            "SharedKey {}:{}",
+            self.account,
+            self.signature(&string_to_sign)
+        );
+        request
+            .headers_mut()
+            .insert(AUTHORIZATION, header_value(&authorization)?);
+        self.client.execute(request).await.map_err(map_http_error)
+    }
+
+    async fn get_response(&self, key: &str) -> Result<Response, StoreError> {
+        let response = self.send(self.client.get(self.blob_url(key))).await?;
+        check(response, "get", key).await
+    }
+
+    /// Stage the stream as blocks of `BLOCK_SIZE`, returning their IDs in
+    /// order.
+    async fn put_blocks(
+        &self,
+        key: &str,
+        mut stream: ByteStream,
+    ) -> Result<Vec<String>, StoreError> {
+        let mut ids = Vec::new();
+        let mut buf = BytesMut::new();
+        loop {
+            let chunk = stream.next().await.transpose()?;
+            if let Some(chunk) = &chunk {
+                buf.extend_from_slice(chunk);
+            }
+            while buf.len() >= BLOCK_SIZE || (chunk.is_none() && !buf.is_empty()) {
+                let block = buf.split_to(buf.len().min(BLOCK_SIZE)).freeze();
+                // IDs must all have the same length before encoding
+                let id = STANDARD.encode(format!("{:08}", ids.len()));
+                let url = format!(
+                    "{}?comp=block&blockid={}",
+                    self.blob_url(key),
+                    utf8_percent_encode(&id, UNRESERVED)
+                );
+                let response = self.send(self.client.put(url).body(block)).await?;
+                check(response, "put block", key).await?;
+                ids.push(id);
+            }
+            if chunk.is_none() {
+                return Ok(ids);
+            }
+        }
+    }
+
+    /// Service SAS query string granting `permissions` on one blob.
+    fn sas_query(&self, key: &str, permissions: &str, expiry: DateTime<Utc>) -> String {
+        let expiry = expiry.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let resource = format!("/blob/{}/{}/{key}", self.account, self.container);
+        // Fields in the order the service rebuilds them; unused ones stay empty
+        let string_to_sign = [
+            permissions,
+            "",
+            &expiry,
+            &resource,
+            "",
+            "",
+            "",
+            API_VERSION,
+            "b",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+            "",
+        ]
+        .join("\n");
+        format!(
+            "sv={API_VERSION}&sr=b&sp={permissions}&se={}&sig={}",
+            utf8_percent_encode(&expiry, UNRESERVED),
+            utf8_percent_encode(&self.signature(&string_to_sign), UNRESERVED)
+        )
+    }
+
+    fn presign(&self, key: &str, permissions: &str, ttl: Duration) -> Result<String, StoreError> {
+        let secs = crate::presign::ttl_secs(ttl)?;
+        let expiry = Utc::now() + chrono::Duration::seconds(i64::from(secs));
+        Ok(format!(
+            "{}?{}",
+            self.blob_url(key),
+            self.sas_query(key, permissions, expiry)
+        ))
+    }
+}
+
+/// The string a shared-key request signs: the verb, the standard headers
+/// in a fixed order, the `x-ms-` headers and the account-qualified
+/// resource with its query parameters.
+fn shared_key_string_to_sign(
+    account: &str,
+    method: &Method,
+    url: &Url,
+    headers: &HeaderMap,
+    content_length: usize,
+) -> String {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("")
+    };
+    let content_length = if content_length == 0 {
+        String::new()
+    } else {
+        content_length.to_string()
+    };
+
+    let mut ms_headers: Vec<(String, &str)> = headers
+        .iter()
+        .filter(|(name, _)| name.as_str().starts_with("x-ms-"))
+        .map(|(name, value)| (name.as_str().to_string(), value.to_str().unwrap_or("")))
+        .collect();
+    ms_headers.sort();
+    let canonical_headers: String = ms_headers
+        .iter()
+        .map(|(name, value)| format!("{name}:{}\n", value.trim()))
+        .collect();
+
+    let mut params: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (name, value) in url.query_pairs() {
+        params
+            .entry(name.to_lowercase())
+            .or_default()
+            .push(value.into_owned());
+    }
+    let mut canonical_resource = format!("/{account}{}", url.path());
+    for (name, mut values) in params {
+        values.sort();
+        canonical_resource.push_str(&format!("\n{name}:{}", values.join(",")));
+    }
+
+    format!(
+        "{method}\n{}\n{}\n{content_length}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{canonical_headers}{canonical_resource}",
+        header("content-encoding"),
+        header("content-language"),
+        header("content-md5"),
+        header("content-type"),
+        header("date"),
+        header("if-modified-since"),
+        header("if-match"),
+        header("if-none-match"),
+        header("if-unmodified-since"),
+        header("range"),
+    )
+}
+
+/// Blobs and the continuation marker from a List Blobs response.
+fn parse_blob_list(xml: &str) -> (Vec<ObjectMeta>, Option<String>) {
+    let blobs = xml
+        .split("<Blob>")
+        .skip(1)
+        .filter_map(|blob| {
+            Some(ObjectMeta {
+                key: unescape_xml(element_text(blob, "Name")?),
+                size: element_text(blob, "Content-Length")
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(0),
+                last_modified: element_text(blob, "Last-Modified")
+                    .and_then(|v| DateTime::parse_from_rfc2822(v).ok())
+                    .map(|t| t.with_timezone(&Utc)),
+            })
+        })
+        .collect();
+    let marker = element_text(xml, "NextMarker")
+        .filter(|marker| !marker.is_empty())
+        .map(unescape_xml);
+    (blobs, marker)
+}
+
+fn element_text<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    let open = format!("<{name}>");
+    let start = xml.find(&open)? + open.len();
+    let end = xml[start..].find(&format!("</{name}>"))?;
+    Some(&xml[start..start + end])
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn header_value(value: &str) -> Result<HeaderValue, StoreError> {
+    HeaderValue::from_str(value).map_err(|e| StoreError::Internal(format!("azure header: {e}")))
+}
+
+/// Turn an error status into a `StoreError`, with 404 as `NotFound`.
+async fn check(response: Response, op: &str, key: &str) -> Result<Response, StoreError> {
+    let status = response.status();
+    if status == StatusCode::NOT_FOUND {
+        return Err(StoreError::NotFound(key.to_string()));
+    }
+    if status.is_client_error() || status.is_server_error() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(StoreError::Internal(format!(
+            "azure {op} {key}: status {status}: {body}"
+        )));
+    }
+    Ok(response)
+}
+
+fn map_http_error(e: reqwest::Error) -> StoreError {
+    StoreError::Internal(format!("azure: {e}"))
+}
+
+#[async_trait]
+impl ObjectStore for AzureStore {
+    async fn put(&self, key: &str, data: Bytes) -> Result<(), StoreError> {
+        let request = self
+            .client
+            .put(self.blob_url(key))
+            .header("x-ms-blob-type", "BlockBlob")
+            .header(CONTENT_TYPE, content_type_for_key(key))
+            .body(data);
+        let response = self.send(request).await?;
+        check(response, "put", key).await?;
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes, StoreError> {
+        self.get_response(key)
+            .await?
+            .bytes()
+            .await
+            .map_err(map_http_error)
+    }
+
+    /// Stages the stream as uncommitted blocks, so only a block at a time
+    /// is held in memory, then commits them. Blocks from a failed upload
+    /// are never committed and the service discards them.
+    async fn put_stream(&self, key: &str, stream: ByteStream) -> Result<(), StoreError> {
+        let ids = self.put_blocks(key, stream).await?;
+        let block_list: String = ids
+            .iter()
+            .map(|id| format!("<Latest>{id}</Latest>"))
+            .collect();
+        let request = self
+            .client
+            .put(format!("{}?comp=blocklist", self.blob_url(key)))
+            .header("x-ms-blob-content-type", content_type_for_key(key))
+            .body(format!(
+                "<?xml version=\"1.0\" encoding=\"utf-8\"?><BlockList>{block_list}</BlockList>"
+            ));
+        let response = self.send(request).await?;
+        check(response, "put", key).await?;
+        Ok(())
+    }
+
+    async fn get_stream(&self, key: &str) -> Result<ByteStream, StoreError> {
+        let response = self.get_response(key).await?;
+        Ok(response.bytes_stream().map_err(map_http_error).boxed())
+    }
+
+    async fn presign_get(&self, key: &str, ttl: Duration) -> Result<String, StoreError> {
+        self.presign(key, "r", ttl)
+    }
+
+    async fn presign_put(&self, key: &str, ttl: Duration) -> Result<String, StoreError> {
+        self.presign(key, "cw", ttl)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StoreError> {
+        let response = self.send(self.client.delete(self.blob_url(key))).await?;
+        match check(response, "delete", key).await {
+            Ok(_) | Err(StoreError::NotFound(_)) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn list_with_meta(&self, prefix: &str) -> Result<Vec<ObjectMeta>, StoreError> {
+        let url = format!("{}/{}", self.endpoint, self.container);
+        let mut objects = Vec::new();
+        let mut marker: Option<String> = None;
+        loop {
+            let mut request = self.client.get(&url).query(&[
+                ("restype", "container"),
+                ("comp", "list"),
+                ("prefix", prefix),
+            ]);
+            if let Some(marker) = &marker {
+                request = request.query(&[("marker", marker)]);
+            }
+            let response = self.send(request).await?;
+            let body = check(response, "list", prefix)
+                .await?
+                .text()
+                .await
+                .map_err(map_http_error)?;
+            let (blobs, next) = parse_blob_list(&body);
+            objects.extend(blobs);
+            match next {
+                Some(next) => marker = Some(next),
+                None => break,
+            }
+        }
+        objects.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(objects)
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, StoreError> {
+        let response = self.send(self.client.head(self.blob_url(key))).await?;
+        match check(response, "exists", key).await {
+            Ok(_) => Ok(true),
+            Err(StoreError::NotFound(_)) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    async fn size(&self, key: &str) -> Result<u64, StoreError> {
+        let response = self.send(self.client.head(self.blob_url(key))).await?;
+        let response = check(response, "size", key).await?;
+        response
+            .headers()
+            .get(reqwest::header::CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| StoreError::Internal(format!("azure size {key}: no Content-Length")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn test_store() -> AzureStore {
+        AzureStore::new(&AzureConfig {
+            account: "devstoreaccount1".into(),
+            access_key: STANDARD.encode("secret"),
+            container: "docs".into(),
+            endpoint_url: Some("http://127.0.0.1:10000/devstoreaccount1".into()),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn invalid_key_or_container_produces_error() {
+        let mut config = AzureConfig {
+            account: "acct".into(),
+            access_key: "not base64!".into(),
+            container: "docs".into(),
+            endpoint_url: None,
+        };
+        let err = AzureStore::new(&config).unwrap_err();
+        assert!(err.to_string().contains("azure access key"));
+
+        config.access_key = STANDARD.encode("secret");
+        config.container = String::new();
+        let err = AzureStore::new(&config).unwrap_err();
+        assert!(err.to_string().contains("container name required"));
+    }
+
+    #[test]
+    fn shared_key_string_to_sign_canonicalizes_headers_and_query() {
+        let store = test_store();
+        let url = Url::parse(&format!(
+            "{}?comp=block&blockid=MDAwMDAwMDA%3D",
+            store.blob_url("tasks/t1/spec file.md")
+        ))
+        .unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ms-version", HeaderValue::from_static(API_VERSION));
+        headers.insert(
+            "x-ms-date",
+            HeaderValue::from_static("Fri, 16 Oct 2026 12:30:00 GMT"),
+        );
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/markdown"));
+
+        let string_to_sign =
+            shared_key_string_to_sign("devstoreaccount1", &Method::PUT, &url, &headers, 6);
+        assert_eq!(
+            string_to_sign,
+            "PUT\n\n\n6\n\ntext/markdown\n\n\n\n\n\n\n\
+             x-ms-date:Fri, 16 Oct 2026 12:30:00 GMT\n\
+             x-ms-version:2021-08-06\n\
+             /devstoreaccount1/devstoreaccount1/docs/tasks/t1/spec%20file.md\n\
+             blockid:MDAwMDAwMDA=\n\
+             comp:block"
+        );
+
+        // An empty body signs an empty length, not 0
+        let string_to_sign =
+            shared_key_string_to_sign("devstoreaccount1", &Method::GET, &url, &HeaderMap::new(), 0);
+        assert!(string_to_sign.starts_with("GET\n\n\n\n"));
+    }
+
+    #[test]
+    fn sas_query_carries_permissions_and_expiry() {
+        let store = test_store();
+        let expiry = Utc.with_ymd_and_hms(2026, 10, 16, 12, 45, 0).unwrap();
+        let query = store.sas_query("a/b.txt", "r", expiry);
+        assert!(query.starts_with("sv=2021-08-06&sr=b&sp=r&se=2026-10-16T12%3A45%3A00Z&sig="));
+        assert_ne!(query, store.sas_query("a/b.txt", "cw", expiry));
+        assert_ne!(query, store.sas_query("a/c.txt", "r", expiry));
+    }
+
+    #[test]
+    fn parse_blob_list_reads_names_and_marker() {
+        let xml = r#"<?xml version="1.0" encoding="utf-8"?>
+<EnumerationResults ContainerName="docs"><Prefix>tasks/</Prefix><Blobs>
+<Blob><Name>tasks/a.md</Name><Properties><Last-Modified>Tue, 03 Jun 2025 10:20:30 GMT</Last-Modified><Content-Length>3</Content-Length></Properties></Blob>
+<Blob><Name>tasks/b &amp; c.md</Name></Blob>
+</Blobs><NextMarker>2!72!abc</NextMarker></EnumerationResults>"#;
+        let (blobs, marker) = parse_blob_list(xml);
+        let names: Vec<_> = blobs.iter().map(|b| b.key.as_str()).collect();
+        assert_eq!(names, vec!["tasks/a.md", "tasks/b & c.md"]);
+        assert_eq!(blobs[0].size, 3);
+        assert_eq!(
+            blobs[0].last_modified,
+            Some(Utc.with_ymd_and_hms(2025, 6, 3, 10, 20, 30).unwrap())
+        );
+        assert_eq!((blobs[1].size, blobs[1].last_modified), (0, None));
+        assert_eq!(marker.as_deref(), Some("2!72!abc"));
+
+        let (blobs, marker) =
+            parse_blob_list("<EnumerationResults><Blobs /><NextMarker /></EnumerationResults>");
+        assert!(blobs.is_empty());
+        assert!(marker.is_none());
+    }
+
+    // -- Azure integration tests (require Azurite or a real account) --
+
+    fn azure_config() -> Option<AzureConfig> {
+        crate::StoreConfig::from_env().azure
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn azure_crud_roundtrip() {
+        let config = azure_config().expect("FLOWSTATE_AZURE_* must be set for this test");
+        let store = AzureStore::new(&config).unwrap();
+        let key = "integration-test/azure roundtrip.md";
+
+        store.put(key, Bytes::from("# spec")).await.unwrap();
+        assert_eq!(store.get(key).await.unwrap(), Bytes::from("# spec"));
+        assert!(store.exists(key).await.unwrap());
+        assert!(store
+            .list("integration-test/")
+            .await
+            .unwrap()
+            .contains(&key.to_string()));
+
+        let url = store
+            .presign_get(key, Duration::from_secs(300))
+            .await
+            .unwrap();
+        assert!(url.contains("sig="));
+
+        store.delete(key).await.unwrap();
+        store.delete(key).await.unwrap();
+        assert!(!store.exists(key).await.unwrap());
+        let err = store.get(key).await.unwrap_err();
+        assert!(matches!(err, StoreError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn azure_stream_roundtrip() {
+        let config = azure_config().expect("FLOWSTATE_AZURE_* must be set for this test");
+        let store = AzureStore::new(&config).unwrap();
+        let key = "integration-test/stream-roundtrip.bin";
+
+        // Spans a block boundary so two blocks are committed
+        let data = Bytes::from(vec![7u8; BLOCK_SIZE + 1024]);
+        let chunks: Vec<Result<Bytes, StoreError>> = data
+            .chunks(64 * 1024)
+            .map(|c| Ok(Bytes::copy_from_slice(c)))
+            .collect();
+        store
+            .put_stream(key, futures_util::stream::iter(chunks).boxed())
+            .await
+            .unwrap();
+        let read: Vec<Bytes> = store
+            .get_stream(key)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(read.concat(), data);
+
+        store.delete(key).await.unwrap();
+    }
+}
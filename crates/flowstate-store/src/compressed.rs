@@ -0,0 +1,235 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures_util::{StreamExt, TryStreamExt};
+
+use crate::{ByteStream, ObjectMeta, ObjectStore, PresignMethod, StoreError};
+
+/// Every zstd frame starts with these bytes. Run output is UTF-8 text, which
+/// can never start with them because 0xB5 is a continuation byte.
+const ZSTD_MAGIC: &[u8; 4] = b"\x28\xB5\x2F\xFD";
+
+/// zstd's own default: most of the ratio at a fraction of the CPU of the
+/// higher levels.
+const LEVEL: i32 = 3;
+
+/// Wraps another store and compresses run output (`claude_runs/*/output.txt`)
+/// with zstd before it reaches the inner store. Agent output runs to tens of
+/// megabytes and is mostly repetitive text, so it shrinks several times over.
+///
+/// Reads decompress whatever carries a zstd header and pass anything else
+/// through, so output written before compression was turned on, or after it
+/// was turned off, stays readable. Compressed objects are buffered in memory
+/// rather than streamed. Other keys pass through untouched. Sizes from
+/// `size` and `list_with_meta` are of the compressed bytes, which is what the
+/// store holds, and presigned URLs to a remote store hand out the compressed
+/// object.
+///
+/// Wrap this around an `EncryptedStore`, not inside it: ciphertext does not
+/// compress.
+pub struct CompressedStore {
+    inner: Arc<dyn ObjectStore>,
+    compress_writes: bool,
+}
+
+impl CompressedStore {
+    /// With `compress_writes` off, new output is stored as it is but
+    /// compressed output already in the store is still decompressed on read.
+    pub fn new(inner: Arc<dyn ObjectStore>, compress_writes: bool) -> Self {
+        Self {
+            inner,
+            compress_writes,
+        }
+    }
+
+    fn compressible(key: &str) -> bool {
+        key.starts_with("claude_runs/") && key.ends_with("/output.txt")
+    }
+
+    fn compress(key: &str, data: &[u8]) -> Result<Bytes, StoreError> {
+        zstd::bulk::compress(data, LEVEL)
+            .map(Bytes::from)
+            .map_err(|e| StoreError::Internal(format!("compress {key}: {e}")))
+    }
+
+    fn decompress(key: &str, data: Bytes) -> Result<Bytes, StoreError> {
+        if !data.starts_with(ZSTD_MAGIC) {
+            return Ok(data);
+        }
+        zstd::stream::decode_all(data.as_ref())
+            .map(Bytes::from)
+            .map_err(|e| StoreError::Internal(format!("decompress {key}: {e}")))
+    }
+}
+
+#[async_trait]
+impl ObjectStore for CompressedStore {
+    async fn put(&self, key: &str, data: Bytes) -> Result<(), StoreError> {
+        if self.compress_writes && Self::compressible(key) {
+            let compressed = Self::compress(key, &data)?;
+            return self.inner.put(key, compressed).await;
+        }
+        self.inner.put(key, data).await
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes, StoreError> {
+        let data = self.inner.get(key).await?;
+        if Self::compressible(key) {
+            return Self::decompress(key, data);
+        }
+        Ok(data)
+    }
+
+    async fn put_stream(&self, key: &str, stream: ByteStream) -> Result<(), StoreError> {
+        if self.compress_writes && Self::compressible(key) {
+            let chunks: Vec<Bytes> = stream.try_collect().await?;
+            return self.put(key, Bytes::from(chunks.concat())).await;
+        }
+        self.inner.put_stream(key, stream).await
+    }
+
+    async fn get_stream(&self, key: &str) -> Result<ByteStream, StoreError> {
+        if Self::compressible(key) {
+            let data = self.get(key).await?;
+            return Ok(futures_util::stream::once(async move { Ok(data) }).boxed());
+        }
+        self.inner.get_stream(key).await
+    }
+
+    async fn get_range_stream(
+        &self,
+        key: &str,
+        start: u64,
+        len: u64,
+    ) -> Result<ByteStream, StoreError> {
+        if Self::compressible(key) {
+            let data = self.get(key).await?;
+            let start = (start as usize).min(data.len());
+            let end = start.saturating_add(len as usize).min(data.len());
+            let data = data.slice(start..end);
+            return Ok(futures_util::stream::once(async move { Ok(data) }).boxed());
+        }
+        self.inner.get_range_stream(key, start, len).await
+    }
+
+    async fn presign_get(&self, key: &str, ttl: Duration) -> Result<String, StoreError> {
+        self.inner.presign_get(key, ttl).await
+    }
+
+    async fn presign_put(&self, key: &str, ttl: Duration) -> Result<String, StoreError> {
+        self.inner.presign_put(key, ttl).await
+    }
+
+    fn verify_presigned(&self, token: &str, method: PresignMethod) -> Result<String, StoreError> {
+        self.inner.verify_presigned(token, method)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StoreError> {
+        self.inner.delete(key).await
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, StoreError> {
+        self.inner.list(prefix).await
+    }
+
+    async fn list_with_meta(&self, prefix: &str) -> Result<Vec<ObjectMeta>, StoreError> {
+        self.inner.list_with_meta(prefix).await
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, StoreError> {
+        self.inner.exists(key).await
+    }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<(), StoreError> {
+        self.inner.rename(from, to).await
+    }
+
+    async fn size(&self, key: &str) -> Result<u64, StoreError> {
+        self.inner.size(key).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{claude_run_output_key, Durability, LocalStore, MultipartConfig, StoreConfig};
+
+    fn test_stores(
+        dir: &std::path::Path,
+        compress_writes: bool,
+    ) -> (Arc<dyn ObjectStore>, CompressedStore) {
+        let inner: Arc<dyn ObjectStore> = Arc::new(LocalStore::new(&StoreConfig {
+            endpoint_url: None,
+            region: None,
+            bucket: None,
+            access_key_id: None,
+            secret_access_key: None,
+            local_data_dir: Some(dir.to_string_lossy().to_string()),
+            gcs: None,
+            azure: None,
+            durability: Durability::Atomic,
+            multipart: MultipartConfig::default(),
+        }));
+        (inner.clone(), CompressedStore::new(inner, compress_writes))
+    }
+
+    #[tokio::test]
+    async fn run_output_is_compressed_at_rest() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (inner, store) = test_stores(tmp.path(), true);
+
+        let key = claude_run_output_key("run-1");
+        let output = "test result: ok. 12 passed; 0 failed\n".repeat(1000);
+        let chunks = futures_util::stream::iter(vec![
+            Ok(Bytes::from(output[..100].to_string())),
+            Ok(Bytes::from(output[100..].to_string())),
+        ]);
+        store.put_stream(&key, Box::pin(chunks)).await.unwrap();
+
+        let at_rest = inner.get(&key).await.unwrap();
+        assert!(at_rest.starts_with(ZSTD_MAGIC));
+        assert!(at_rest.len() < output.len() / 10);
+        assert_eq!(store.get(&key).await.unwrap(), output.as_bytes());
+        let streamed: Vec<Bytes> = store
+            .get_stream(&key)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(streamed.concat(), output.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn other_keys_pass_through() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (inner, store) = test_stores(tmp.path(), true);
+
+        let key = "claude_runs/run-1/prompt.md";
+        store.put(key, Bytes::from("a prompt")).await.unwrap();
+        assert_eq!(inner.get(key).await.unwrap().as_ref(), b"a prompt");
+    }
+
+    #[tokio::test]
+    async fn reads_work_whichever_way_output_was_written() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (inner, store) = test_stores(tmp.path(), false);
+
+        let plain = claude_run_output_key("plain");
+        inner.put(&plain, Bytes::from("old output")).await.unwrap();
+        assert_eq!(store.get(&plain).await.unwrap().as_ref(), b"old output");
+
+        // Compression was on when this was written and has since been
+        // turned off
+        let packed = claude_run_output_key("packed");
+        CompressedStore::new(inner.clone(), true)
+            .put(&packed, Bytes::from("new output"))
+            .await
+            .unwrap();
+        store.put(&plain, Bytes::from("rewritten")).await.unwrap();
+        assert_eq!(inner.get(&plain).await.unwrap().as_ref(), b"rewritten");
+        assert_eq!(store.get(&packed).await.unwrap().as_ref(), b"new output");
+    }
+}
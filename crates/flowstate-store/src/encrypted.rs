@@ -0,0 +1,223 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use aes_gcm::aead::{Aead, OsRng};
+use aes_gcm::{AeadCore, Aes256Gcm, Key, KeyInit, Nonce};
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use crate::{ObjectMeta, ObjectStore, PresignMethod, StoreError, PRESIGNED_PATH};
+
+/// Marks an encrypted object: the format version, then a 12-byte nonce and
+/// the AES-256-GCM ciphertext.
+const MAGIC: &[u8; 4] = b"FSE\x01";
+const NONCE_LEN: usize = 12;
+
+/// Wraps another store and encrypts every object with AES-256-GCM before it
+/// reaches the inner store, so specs, prompts and attachments are not
+/// plaintext at rest.
+///
+/// Objects written before encryption was turned on carry no header and are
+/// read back as they are, so existing stores keep working. Encryption needs
+/// the whole object, so streamed reads and writes are buffered. Presigned
+/// URLs only work when they are served by this server, which decrypts; a
+/// remote store's own URLs would hand out ciphertext and are refused.
+pub struct EncryptedStore {
+    inner: Arc<dyn ObjectStore>,
+    cipher: Aes256Gcm,
+}
+
+impl EncryptedStore {
+    pub fn new(inner: Arc<dyn ObjectStore>, key: &Key<Aes256Gcm>) -> Self {
+        Self {
+            inner,
+            cipher: Aes256Gcm::new(key),
+        }
+    }
+
+    fn encrypt(&self, key: &str, plaintext: &[u8]) -> Result<Bytes, StoreError> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext)
+            .map_err(|e| StoreError::Internal(format!("encrypt {key}: {e}")))?;
+        let mut sealed = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+        sealed.extend_from_slice(MAGIC);
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(Bytes::from(sealed))
+    }
+
+    fn decrypt(&self, key: &str, data: Bytes) -> Result<Bytes, StoreError> {
+        let Some(sealed) = data.strip_prefix(MAGIC) else {
+            return Ok(data);
+        };
+        if sealed.len() < NONCE_LEN {
+            return Err(StoreError::Internal(format!("decrypt {key}: too short")));
+        }
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        self.cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map(Bytes::from)
+            .map_err(|e| StoreError::Internal(format!("decrypt {key}: {e}")))
+    }
+
+    /// Pass through a presigned URL only if it points back at this server.
+    fn served_here(url: String) -> Result<String, StoreError> {
+        if url.starts_with(PRESIGNED_PATH) {
+            Ok(url)
+        } else {
+            Err(StoreError::Internal(
+                "presigned URLs to the remote store are unavailable with store encryption".into(),
+            ))
+        }
+    }
+}
+
+#[async_trait]
+impl ObjectStore for EncryptedStore {
+    async fn put(&self, key: &str, data: Bytes) -> Result<(), StoreError> {
+        let sealed = self.encrypt(key, &data)?;
+        self.inner.put(key, sealed).await
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes, StoreError> {
+        let data = self.inner.get(key).await?;
+        self.decrypt(key, data)
+    }
+
+    async fn presign_get(&self, key: &str, ttl: Duration) -> Result<String, StoreError> {
+        Self::served_here(self.inner.presign_get(key, ttl).await?)
+    }
+
+    async fn presign_put(&self, key: &str, ttl: Duration) -> Result<String, StoreError> {
+        Self::served_here(self.inner.presign_put(key, ttl).await?)
+    }
+
+    fn verify_presigned(&self, token: &str, method: PresignMethod) -> Result<String, StoreError> {
+        self.inner.verify_presigned(token, method)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), StoreError> {
+        self.inner.delete(key).await
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, StoreError> {
+        self.inner.list(prefix).await
+    }
+
+    /// Sizes are of the ciphertext, which is what the store holds.
+    async fn list_with_meta(&self, prefix: &str) -> Result<Vec<ObjectMeta>, StoreError> {
+        self.inner.list_with_meta(prefix).await
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool, StoreError> {
+        self.inner.exists(key).await
+    }
+
+    /// Ciphertext is not bound to its object key, so it moves as it is.
+    async fn rename(&self, from: &str, to: &str) -> Result<(), StoreError> {
+        self.inner.rename(from, to).await
+    }
+
+    async fn size(&self, key: &str) -> Result<u64, StoreError> {
+        self.inner.size(key).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Durability, LocalStore, MultipartConfig, StoreConfig};
+
+    fn test_stores(dir: &std::path::Path) -> (Arc<dyn ObjectStore>, EncryptedStore) {
+        let inner: Arc<dyn ObjectStore> = Arc::new(LocalStore::new(&StoreConfig {
+            endpoint_url: None,
+            region: None,
+            bucket: None,
+            access_key_id: None,
+            secret_access_key: None,
+            local_data_dir: Some(dir.to_string_lossy().to_string()),
+            gcs: None,
+            azure: None,
+            durability: Durability::Atomic,
+            multipart: MultipartConfig::default(),
+        }));
+        let key = Aes256Gcm::generate_key(OsRng);
+        (inner.clone(), EncryptedStore::new(inner, &key))
+    }
+
+    #[tokio::test]
+    async fn objects_are_encrypted_at_rest() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (inner, store) = test_stores(tmp.path());
+
+        let key = "tasks/abc/specification.md";
+        store.put(key, Bytes::from("secret spec")).await.unwrap();
+        let at_rest = inner.get(key).await.unwrap();
+        assert!(at_rest.starts_with(MAGIC));
+        assert!(!at_rest.windows(b"secret".len()).any(|w| w == b"secret"));
+        assert_eq!(store.get(key).await.unwrap().as_ref(), b"secret spec");
+    }
+
+    #[tokio::test]
+    async fn streams_roundtrip_through_encryption() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (_, store) = test_stores(tmp.path());
+
+        let chunks =
+            futures_util::stream::iter(vec![Ok(Bytes::from("hello ")), Ok(Bytes::from("world"))]);
+        store.put_stream("a/b.txt", Box::pin(chunks)).await.unwrap();
+        let data: Vec<Bytes> =
+            futures_util::TryStreamExt::try_collect(store.get_stream("a/b.txt").await.unwrap())
+                .await
+                .unwrap();
+        assert_eq!(data.concat(), b"hello world");
+    }
+
+    #[tokio::test]
+    async fn plaintext_objects_are_still_readable() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (inner, store) = test_stores(tmp.path());
+
+        inner
+            .put("old.md", Bytes::from("written before"))
+            .await
+            .unwrap();
+        assert_eq!(
+            store.get("old.md").await.unwrap().as_ref(),
+            b"written before"
+        );
+    }
+
+    #[tokio::test]
+    async fn wrong_key_fails_to_decrypt() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (inner, store) = test_stores(tmp.path());
+        store.put("k", Bytes::from("data")).await.unwrap();
+
+        let other = EncryptedStore::new(inner, &Aes256Gcm::generate_key(OsRng));
+        assert!(matches!(other.get("k").await, Err(StoreError::Internal(_))));
+        assert!(matches!(
+            store.get("missing").await,
+            Err(StoreError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn server_presigned_urls_pass_through() {
+        let tmp = tempfile::tempdir().unwrap();
+        let (_, store) = test_stores(tmp.path());
+
+        let url = store
+            .presign_get("k", Duration::from_secs(60))
+            .await
+            .unwrap();
+        let token = url.strip_prefix(PRESIGNED_PATH).unwrap();
+        assert_eq!(
+            store.verify_presigned(token, PresignMethod::Get).unwrap(),
+            "k"
+        );
+        assert!(EncryptedStore::served_here("https://bucket.example.com/k".into()).is_err());
+    }
+}
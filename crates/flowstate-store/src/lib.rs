@@ -1,15 +1,33 @@
+#[cfg(feature = "azure")]
+mod azure;
+mod compressed;
+mod encrypted;
+#[cfg(feature = "gcs")]
+mod gcs;
 mod local;
+mod presign;
 #[cfg(feature = "s3")]
 mod s3;
 
+#[cfg(feature = "azure")]
+pub use azure::AzureStore;
+pub use compressed::CompressedStore;
+pub use encrypted::EncryptedStore;
+#[cfg(feature = "gcs")]
+pub use gcs::GcsStore;
 pub use local::LocalStore;
+pub use presign::{PresignMethod, MAX_PRESIGN_TTL, PRESIGNED_PATH};
 #[cfg(feature = "s3")]
 pub use s3::S3Store;
 
+use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures_util::{Stream, StreamExt, TryStreamExt};
 
 #[derive(Debug, thiserror::Error)]
 pub enum StoreError {
@@ -18,6 +36,23 @@ pub enum StoreError {
 
     #[error("store error: {0}")]
     Internal(String),
+
+    #[error("invalid presigned URL: {0}")]
+    InvalidToken(String),
+}
+
+/// An object's contents as a stream of chunks, so large objects never have
+/// to fit in memory at once.
+pub type ByteStream = Pin<Box<dyn Stream<Item = Result<Bytes, StoreError>> + Send>>;
+
+/// A listed object: its key, the bytes it takes up in the store and when it
+/// was last written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectMeta {
+    pub key: String,
+    pub size: u64,
+    /// `None` when the store did not report a time it could parse.
+    pub last_modified: Option<DateTime<Utc>>,
 }
 
 /// A store for opaque blobs keyed by string paths.
@@ -38,11 +73,71 @@ pub trait ObjectStore: Send + Sync {
         }
     }
 
+    /// Write (create or overwrite) an object from a stream of chunks. If
+    /// the stream fails part way, the object is left as it was.
+    ///
+    /// The default collects the stream and calls `put`; stores that can
+    /// write incrementally override it.
+    async fn put_stream(&self, key: &str, stream: ByteStream) -> Result<(), StoreError> {
+        let chunks: Vec<Bytes> = stream.try_collect().await?;
+        self.put(key, Bytes::from(chunks.concat())).await
+    }
+
+    /// Read an object as a stream of chunks. Returns `StoreError::NotFound`
+    /// if absent.
+    ///
+    /// The default reads the whole object with `get`; stores that can read
+    /// incrementally override it.
+    async fn get_stream(&self, key: &str) -> Result<ByteStream, StoreError> {
+        let data = self.get(key).await?;
+        Ok(futures_util::stream::once(async move { Ok(data) }).boxed())
+    }
+
+    /// Read `len` bytes of an object starting at `start` as a stream of
+    /// chunks, for HTTP range requests. A range running past the end is cut
+    /// short. Returns `StoreError::NotFound` if absent.
+    ///
+    /// The default reads from the start with `get_stream` and discards what
+    /// comes before `start`; stores that can seek override it.
+    async fn get_range_stream(
+        &self,
+        key: &str,
+        start: u64,
+        len: u64,
+    ) -> Result<ByteStream, StoreError> {
+        Ok(slice_stream(self.get_stream(key).await?, start, len))
+    }
+
+    /// A URL a client can read the object from for `ttl` without
+    /// credentials. S3 returns a real presigned URL; the local store returns
+    /// a server path under `PRESIGNED_PATH` carrying a signed token.
+    async fn presign_get(&self, key: &str, ttl: Duration) -> Result<String, StoreError>;
+
+    /// A URL a client can upload the object to with `PUT` for `ttl`.
+    async fn presign_put(&self, key: &str, ttl: Duration) -> Result<String, StoreError>;
+
+    /// The object key a token from a server-path presigned URL grants
+    /// `method` on. Stores whose presigned URLs point elsewhere reject every
+    /// token.
+    fn verify_presigned(&self, token: &str, method: PresignMethod) -> Result<String, StoreError> {
+        let _ = (token, method);
+        Err(StoreError::InvalidToken(
+            "this store does not issue server tokens".into(),
+        ))
+    }
+
     /// Delete an object. No-op if absent.
     async fn delete(&self, key: &str) -> Result<(), StoreError>;
 
-    /// List object keys under a prefix.
-    async fn list(&self, prefix: &str) -> Result<Vec<String>, StoreError>;
+    /// List object keys under a prefix, sorted.
+    async fn list(&self, prefix: &str) -> Result<Vec<String>, StoreError> {
+        let objects = self.list_with_meta(prefix).await?;
+        Ok(objects.into_iter().map(|o| o.key).collect())
+    }
+
+    /// List the objects under a prefix with their size and modification
+    /// time, sorted by key, in the same requests as `list`.
+    async fn list_with_meta(&self, prefix: &str) -> Result<Vec<ObjectMeta>, StoreError>;
 
     /// Check if an object exists.
     async fn exists(&self, key: &str) -> Result<bool, StoreError> {
@@ -52,6 +147,51 @@ pub trait ObjectStore: Send + Sync {
             Err(e) => Err(e),
         }
     }
+
+    /// Move an object to a new key, replacing anything there.
+    ///
+    /// The default copies it through `get_stream` and `put_stream` and then
+    /// deletes the original; stores that can move objects in place
+    /// override it.
+    async fn rename(&self, from: &str, to: &str) -> Result<(), StoreError> {
+        let stream = self.get_stream(from).await?;
+        self.put_stream(to, stream).await?;
+        self.delete(from).await
+    }
+
+    /// Bytes an object takes up in the store. Returns
+    /// `StoreError::NotFound` if absent.
+    ///
+    /// The default reads the whole object; stores that can look up its
+    /// metadata override it.
+    async fn size(&self, key: &str) -> Result<u64, StoreError> {
+        Ok(self.get(key).await?.len() as u64)
+    }
+}
+
+/// The `len` bytes of `stream` starting at `start`. Stops reading once they
+/// have been taken.
+fn slice_stream(stream: ByteStream, start: u64, len: u64) -> ByteStream {
+    futures_util::stream::try_unfold(
+        (stream, start, len),
+        |(mut stream, mut skip, remaining)| async move {
+            if remaining == 0 {
+                return Ok(None);
+            }
+            while let Some(mut chunk) = stream.try_next().await? {
+                if skip >= chunk.len() as u64 {
+                    skip -= chunk.len() as u64;
+                    continue;
+                }
+                chunk = chunk.slice(skip as usize..);
+                let take = remaining.min(chunk.len() as u64);
+                chunk.truncate(take as usize);
+                return Ok(Some((chunk, (stream, 0, remaining - take))));
+            }
+            Ok(None)
+        },
+    )
+    .boxed()
 }
 
 // -- Key helpers --
@@ -72,10 +212,34 @@ pub fn task_verification_key(task_id: &str) -> String {
     format!("tasks/{task_id}/verification.md")
 }
 
+/// Findings from comparing a task's build diff against its plan.
+pub fn task_scope_findings_key(task_id: &str) -> String {
+    format!("tasks/{task_id}/scope_findings.json")
+}
+
 pub fn task_attachment_key(task_id: &str, attachment_id: &str, filename: &str) -> String {
     format!("tasks/{task_id}/attachments/{attachment_id}/{filename}")
 }
 
+/// Prefix of content-addressed attachment blobs, which tasks share.
+pub const ATTACHMENT_BLOB_PREFIX: &str = "attachments/sha256/";
+
+/// Where an attachment's bytes live, by the lowercase hex SHA-256 of its
+/// contents, so identical files are stored once.
+pub fn attachment_blob_key(sha256: &str) -> String {
+    format!("{ATTACHMENT_BLOB_PREFIX}{sha256}")
+}
+
+/// Where an upload is staged until its hash is known.
+pub fn attachment_upload_key(upload_id: &str) -> String {
+    format!("attachments/uploads/{upload_id}")
+}
+
+/// Prefix of every object stored for a run.
+pub fn claude_run_prefix(run_id: &str) -> String {
+    format!("claude_runs/{run_id}/")
+}
+
 pub fn claude_run_prompt_key(run_id: &str) -> String {
     format!("claude_runs/{run_id}/prompt.md")
 }
@@ -84,6 +248,20 @@ pub fn claude_run_output_key(run_id: &str) -> String {
     format!("claude_runs/{run_id}/output.txt")
 }
 
+/// Content type to record on a remote object, from its key's extension.
+#[cfg(any(feature = "s3", feature = "gcs", feature = "azure"))]
+pub(crate) fn content_type_for_key(key: &str) -> &'static str {
+    if key.ends_with(".md") {
+        "text/markdown"
+    } else if key.ends_with(".txt") {
+        "text/plain"
+    } else if key.ends_with(".json") {
+        "application/json"
+    } else {
+        "application/octet-stream"
+    }
+}
+
 // -- Configuration --
 
 /// Configuration for the object store backend.
@@ -99,8 +277,121 @@ pub struct StoreConfig {
     pub access_key_id: Option<String>,
     /// AWS secret access key.
     pub secret_access_key: Option<String>,
-    /// Local filesystem base directory (used when S3 is not configured).
+    /// Local filesystem base directory (used when no remote store is
+    /// configured).
     pub local_data_dir: Option<String>,
+    /// Google Cloud Storage, used when S3 is not configured.
+    pub gcs: Option<GcsConfig>,
+    /// Azure Blob Storage, used when neither S3 nor GCS is configured.
+    pub azure: Option<AzureConfig>,
+    /// How the local store commits writes to disk.
+    pub durability: Durability,
+    /// When S3 uploads switch to multipart, and how parts are sent.
+    pub multipart: MultipartConfig,
+}
+
+/// How the local store commits a write. Either way an object is written to
+/// a temporary file beside it and renamed into place, so a crash mid-write
+/// leaves the old object or the new one, never a truncated mix.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Durability {
+    /// Leave flushing to the OS; the latest writes can be lost on power
+    /// failure.
+    #[default]
+    Atomic,
+    /// Also fsync the file before the rename and its directory after, so a
+    /// write that returned survives power failure. Slower.
+    Fsync,
+}
+
+impl Durability {
+    pub fn parse_str(s: &str) -> Option<Self> {
+        match s {
+            "atomic" => Some(Durability::Atomic),
+            "fsync" => Some(Durability::Fsync),
+            _ => None,
+        }
+    }
+}
+
+/// How `S3Store::put_stream` splits large objects into a multipart upload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MultipartConfig {
+    /// Objects larger than this many bytes are uploaded in parts; smaller
+    /// ones in a single request.
+    pub threshold: u64,
+    /// Bytes in every part but the last. S3 requires at least 5 MiB.
+    pub part_size: u64,
+    /// Further attempts at a part whose upload failed in transit.
+    pub part_retries: u32,
+}
+
+/// S3's smallest allowed part, other than the last.
+pub const MIN_PART_SIZE: u64 = 5 * 1024 * 1024;
+
+impl Default for MultipartConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 16 * 1024 * 1024,
+            part_size: 8 * 1024 * 1024,
+            part_retries: 3,
+        }
+    }
+}
+
+impl MultipartConfig {
+    /// Read `FLOWSTATE_S3_MULTIPART_THRESHOLD_MB`, `FLOWSTATE_S3_PART_SIZE_MB`
+    /// and `FLOWSTATE_S3_PART_RETRIES`, keeping the default for any that is
+    /// unset or unparseable. Part sizes below S3's minimum are raised to it.
+    fn from_getter(get: impl Fn(&str) -> Option<String>) -> Self {
+        let defaults = Self::default();
+        let number = |key: &str| -> Option<u64> {
+            let value = get(key)?;
+            let parsed = value.trim().parse().ok();
+            if parsed.is_none() {
+                tracing::warn!("invalid {key} {value:?}, using the default");
+            }
+            parsed
+        };
+        let mib = |key: &str| number(key).map(|mb| mb * 1024 * 1024);
+        Self {
+            threshold: mib("FLOWSTATE_S3_MULTIPART_THRESHOLD_MB").unwrap_or(defaults.threshold),
+            part_size: mib("FLOWSTATE_S3_PART_SIZE_MB")
+                .unwrap_or(defaults.part_size)
+                .max(MIN_PART_SIZE),
+            part_retries: number("FLOWSTATE_S3_PART_RETRIES")
+                .map(|n| n.min(u32::MAX as u64) as u32)
+                .unwrap_or(defaults.part_retries),
+        }
+    }
+}
+
+/// Google Cloud Storage settings.
+#[derive(Debug, Clone)]
+pub struct GcsConfig {
+    pub bucket: String,
+    /// API endpoint; defaults to `https://storage.googleapis.com`. Point it
+    /// at an emulator for testing.
+    pub endpoint_url: Option<String>,
+    /// OAuth access token. When `None`, tokens come from the GCE metadata
+    /// server, as on GCE, GKE and Cloud Run.
+    pub access_token: Option<String>,
+    /// Service account that signs presigned URLs through the IAM
+    /// Credentials API; defaults to the metadata server's account.
+    pub signer_email: Option<String>,
+}
+
+/// Azure Blob Storage settings, authenticated with the account's shared key.
+#[derive(Debug, Clone)]
+pub struct AzureConfig {
+    pub account: String,
+    /// Base64 account access key.
+    pub access_key: String,
+    pub container: String,
+    /// Blob service endpoint; defaults to
+    /// `https://<account>.blob.core.windows.net`. For Azurite, include the
+    /// account in the path, e.g. `http://127.0.0.1:10000/devstoreaccount1`.
+    pub endpoint_url: Option<String>,
 }
 
 impl StoreConfig {
@@ -121,6 +412,35 @@ impl StoreConfig {
             secret_access_key: get("FLOWSTATE_S3_SECRET_ACCESS_KEY")
                 .or_else(|| get("AWS_SECRET_ACCESS_KEY")),
             local_data_dir: None,
+            gcs: get("FLOWSTATE_GCS_BUCKET").map(|bucket| GcsConfig {
+                bucket,
+                endpoint_url: get("FLOWSTATE_GCS_ENDPOINT"),
+                access_token: get("FLOWSTATE_GCS_ACCESS_TOKEN"),
+                signer_email: get("FLOWSTATE_GCS_SIGNER_EMAIL"),
+            }),
+            azure: match (
+                get("FLOWSTATE_AZURE_ACCOUNT").or_else(|| get("AZURE_STORAGE_ACCOUNT")),
+                get("FLOWSTATE_AZURE_ACCESS_KEY").or_else(|| get("AZURE_STORAGE_KEY")),
+                get("FLOWSTATE_AZURE_CONTAINER"),
+            ) {
+                (Some(account), Some(access_key), Some(container)) => Some(AzureConfig {
+                    account,
+                    access_key,
+                    container,
+                    endpoint_url: get("FLOWSTATE_AZURE_ENDPOINT"),
+                }),
+                _ => None,
+            },
+            durability: match get("FLOWSTATE_STORE_DURABILITY") {
+                Some(value) => Durability::parse_str(&value).unwrap_or_else(|| {
+                    tracing::warn!(
+                        "unknown FLOWSTATE_STORE_DURABILITY {value:?}, using \"atomic\""
+                    );
+                    Durability::Atomic
+                }),
+                None => Durability::Atomic,
+            },
+            multipart: MultipartConfig::from_getter(&get),
         }
     }
 
@@ -134,7 +454,8 @@ impl StoreConfig {
 
 // -- Factory --
 
-/// Create an `ObjectStore` from configuration.
+/// Create an `ObjectStore` from configuration. S3 wins over GCS, and GCS
+/// over Azure, when more than one is configured.
 pub fn create_store(config: &StoreConfig) -> Result<Arc<dyn ObjectStore>, StoreError> {
     if config.is_s3() {
         #[cfg(feature = "s3")]
@@ -147,6 +468,30 @@ pub fn create_store(config: &StoreConfig) -> Result<Arc<dyn ObjectStore>, StoreE
                 "S3 configuration detected but the 's3' feature is not enabled".into(),
             ))
         }
+    } else if let Some(gcs) = &config.gcs {
+        #[cfg(feature = "gcs")]
+        {
+            Ok(Arc::new(GcsStore::new(gcs)?))
+        }
+        #[cfg(not(feature = "gcs"))]
+        {
+            let _ = gcs;
+            Err(StoreError::Internal(
+                "GCS configuration detected but the 'gcs' feature is not enabled".into(),
+            ))
+        }
+    } else if let Some(azure) = &config.azure {
+        #[cfg(feature = "azure")]
+        {
+            Ok(Arc::new(AzureStore::new(azure)?))
+        }
+        #[cfg(not(feature = "azure"))]
+        {
+            let _ = azure;
+            Err(StoreError::Internal(
+                "Azure configuration detected but the 'azure' feature is not enabled".into(),
+            ))
+        }
     } else {
         Ok(Arc::new(LocalStore::new(config)))
     }
@@ -156,6 +501,24 @@ pub fn create_store(config: &StoreConfig) -> Result<Arc<dyn ObjectStore>, StoreE
 mod tests {
     use super::*;
 
+    #[tokio::test]
+    async fn slice_stream_spans_chunks() {
+        let read = |start, len| async move {
+            let chunks = ["abc", "def", "ghi"].map(|c| Ok(Bytes::from(c)));
+            let stream = futures_util::stream::iter(chunks).boxed();
+            let parts: Vec<Bytes> = slice_stream(stream, start, len)
+                .try_collect()
+                .await
+                .unwrap();
+            parts.concat()
+        };
+        assert_eq!(read(0, 9).await, b"abcdefghi");
+        assert_eq!(read(2, 5).await, b"cdefg");
+        assert_eq!(read(3, 3).await, b"def");
+        assert_eq!(read(7, 10).await, b"hi");
+        assert_eq!(read(9, 1).await, b"");
+    }
+
     #[test]
     fn key_helpers_produce_expected_paths() {
         assert_eq!(task_spec_key("abc-123"), "tasks/abc-123/specification.md");
@@ -164,6 +527,7 @@ mod tests {
             task_attachment_key("abc-123", "att-1", "image.png"),
             "tasks/abc-123/attachments/att-1/image.png"
         );
+        assert_eq!(claude_run_prefix("run-1"), "claude_runs/run-1/");
         assert_eq!(
             claude_run_prompt_key("run-1"),
             "claude_runs/run-1/prompt.md"
@@ -172,11 +536,20 @@ mod tests {
             claude_run_output_key("run-1"),
             "claude_runs/run-1/output.txt"
         );
+        assert_eq!(
+            attachment_blob_key("e3b0c442"),
+            "attachments/sha256/e3b0c442"
+        );
+        assert_eq!(attachment_upload_key("up-1"), "attachments/uploads/up-1");
         assert_eq!(task_research_key("abc-123"), "tasks/abc-123/research.md");
         assert_eq!(
             task_verification_key("abc-123"),
             "tasks/abc-123/verification.md"
         );
+        assert_eq!(
+            task_scope_findings_key("abc-123"),
+            "tasks/abc-123/scope_findings.json"
+        );
     }
 
     #[test]
@@ -188,6 +561,10 @@ mod tests {
             access_key_id: Some("key".into()),
             secret_access_key: Some("secret".into()),
             local_data_dir: None,
+            gcs: None,
+            azure: None,
+            durability: Durability::Atomic,
+            multipart: MultipartConfig::default(),
         };
         assert!(config.is_s3());
 
@@ -199,6 +576,10 @@ mod tests {
             access_key_id: Some("key".into()),
             secret_access_key: Some("secret".into()),
             local_data_dir: None,
+            gcs: None,
+            azure: None,
+            durability: Durability::Atomic,
+            multipart: MultipartConfig::default(),
         };
         assert!(!config.is_s3());
 
@@ -210,6 +591,10 @@ mod tests {
             access_key_id: None,
             secret_access_key: None,
             local_data_dir: None,
+            gcs: None,
+            azure: None,
+            durability: Durability::Atomic,
+            multipart: MultipartConfig::default(),
         };
         assert!(!config.is_s3());
 
@@ -221,6 +606,10 @@ mod tests {
             access_key_id: None,
             secret_access_key: None,
             local_data_dir: None,
+            gcs: None,
+            azure: None,
+            durability: Durability::Atomic,
+            multipart: MultipartConfig::default(),
         };
         assert!(!config.is_s3());
     }
@@ -235,6 +624,10 @@ mod tests {
             access_key_id: None,
             secret_access_key: None,
             local_data_dir: Some(tmp.path().to_string_lossy().to_string()),
+            gcs: None,
+            azure: None,
+            durability: Durability::Atomic,
+            multipart: MultipartConfig::default(),
         };
         assert!(!config.is_s3());
         let store = create_store(&config);
@@ -250,6 +643,10 @@ mod tests {
             access_key_id: None,
             secret_access_key: None,
             local_data_dir: None,
+            gcs: None,
+            azure: None,
+            durability: Durability::Atomic,
+            multipart: MultipartConfig::default(),
         };
         let store = create_store(&config);
         assert!(store.is_ok(), "should fall back to default local dir");
@@ -263,9 +660,73 @@ mod tests {
         assert!(config.bucket.is_none());
         assert!(config.access_key_id.is_none());
         assert!(config.secret_access_key.is_none());
+        assert!(config.gcs.is_none());
+        assert!(config.azure.is_none());
+        assert_eq!(config.durability, Durability::Atomic);
         assert!(!config.is_s3());
     }
 
+    #[test]
+    fn store_config_from_getter_durability() {
+        let config = StoreConfig::from_getter(|key| {
+            (key == "FLOWSTATE_STORE_DURABILITY").then(|| "fsync".to_string())
+        });
+        assert_eq!(config.durability, Durability::Fsync);
+        let config = StoreConfig::from_getter(|key| {
+            (key == "FLOWSTATE_STORE_DURABILITY").then(|| "paranoid".to_string())
+        });
+        assert_eq!(config.durability, Durability::Atomic);
+    }
+
+    #[test]
+    fn store_config_from_getter_multipart() {
+        assert_eq!(
+            StoreConfig::from_getter(|_| None).multipart,
+            MultipartConfig::default()
+        );
+        let config = StoreConfig::from_getter(|key| match key {
+            "FLOWSTATE_S3_MULTIPART_THRESHOLD_MB" => Some("64".into()),
+            "FLOWSTATE_S3_PART_SIZE_MB" => Some("1".into()),
+            "FLOWSTATE_S3_PART_RETRIES" => Some("lots".into()),
+            _ => None,
+        });
+        assert_eq!(config.multipart.threshold, 64 * 1024 * 1024);
+        assert_eq!(config.multipart.part_size, MIN_PART_SIZE);
+        assert_eq!(config.multipart.part_retries, 3);
+    }
+
+    #[test]
+    fn store_config_from_getter_gcs_and_azure() {
+        use std::collections::HashMap;
+        let vars: HashMap<&str, &str> = [
+            ("FLOWSTATE_GCS_BUCKET", "gcs-bucket"),
+            ("FLOWSTATE_GCS_ENDPOINT", "http://127.0.0.1:4443"),
+            ("AZURE_STORAGE_ACCOUNT", "acct"),
+            ("AZURE_STORAGE_KEY", "a2V5"),
+            ("FLOWSTATE_AZURE_CONTAINER", "docs"),
+        ]
+        .into_iter()
+        .collect();
+
+        let config = StoreConfig::from_getter(|key| vars.get(key).map(|v| v.to_string()));
+        let gcs = config.gcs.unwrap();
+        assert_eq!(gcs.bucket, "gcs-bucket");
+        assert_eq!(gcs.endpoint_url.as_deref(), Some("http://127.0.0.1:4443"));
+        assert!(gcs.access_token.is_none());
+        let azure = config.azure.unwrap();
+        assert_eq!(azure.account, "acct");
+        assert_eq!(azure.access_key, "a2V5");
+        assert_eq!(azure.container, "docs");
+
+        // Azure needs a container as well as credentials
+        let config = StoreConfig::from_getter(|key| match key {
+            "AZURE_STORAGE_ACCOUNT" => Some("acct".into()),
+            "AZURE_STORAGE_KEY" => Some("a2V5".into()),
+            _ => None,
+        });
+        assert!(config.azure.is_none());
+    }
+
     #[test]
     fn store_config_from_getter_aws_fallbacks() {
         use std::collections::HashMap;
@@ -1,12 +1,23 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use async_trait::async_trait;
 use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures_util::{StreamExt, TryStreamExt};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio_util::io::ReaderStream;
 
-use crate::{ObjectStore, StoreConfig, StoreError};
+use crate::presign::Signer;
+use crate::{
+    ByteStream, Durability, ObjectMeta, ObjectStore, PresignMethod, StoreConfig, StoreError,
+    PRESIGNED_PATH,
+};
 
 pub struct LocalStore {
     base_dir: PathBuf,
+    signer: Signer,
+    durability: Durability,
 }
 
 impl LocalStore {
@@ -16,7 +27,11 @@ impl LocalStore {
             .as_ref()
             .map(PathBuf::from)
             .unwrap_or_else(default_data_dir);
-        Self { base_dir }
+        Self {
+            base_dir,
+            signer: Signer::random(),
+            durability: config.durability,
+        }
     }
 
     pub fn base_dir(&self) -> &PathBuf {
@@ -26,6 +41,71 @@ impl LocalStore {
     fn resolve(&self, key: &str) -> PathBuf {
         self.base_dir.join(key)
     }
+
+    /// Write `stream` to a temporary file beside `path` and rename it into
+    /// place, so readers never see a partial object and a failed or
+    /// interrupted write leaves the old one. With [`Durability::Fsync`] the
+    /// file and its directory are synced as well.
+    async fn write_atomic(&self, path: &Path, mut stream: ByteStream) -> Result<(), StoreError> {
+        let dir = path.parent().unwrap_or(&self.base_dir);
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|e| StoreError::Internal(format!("mkdir: {e}")))?;
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let tmp = dir.join(format!(
+            ".{name}.{:016x}{TMP_SUFFIX}",
+            rand::random::<u64>()
+        ));
+        let write_err =
+            |e: std::io::Error| StoreError::Internal(format!("write {}: {e}", tmp.display()));
+        let result = async {
+            let mut file = tokio::fs::File::create(&tmp).await.map_err(write_err)?;
+            while let Some(chunk) = stream.try_next().await? {
+                file.write_all(&chunk).await.map_err(write_err)?;
+            }
+            file.flush().await.map_err(write_err)?;
+            if self.durability == Durability::Fsync {
+                file.sync_all().await.map_err(write_err)?;
+            }
+            tokio::fs::rename(&tmp, path)
+                .await
+                .map_err(|e| StoreError::Internal(format!("rename {}: {e}", path.display())))
+        }
+        .await;
+        if result.is_err() {
+            let _ = tokio::fs::remove_file(&tmp).await;
+        }
+        result?;
+        self.sync_dir(dir).await
+    }
+
+    /// Make a rename into `dir` durable, if configured to.
+    async fn sync_dir(&self, dir: &Path) -> Result<(), StoreError> {
+        if self.durability != Durability::Fsync {
+            return Ok(());
+        }
+        // Directories cannot be opened for syncing on Windows, where
+        // renames are journaled with the file.
+        #[cfg(unix)]
+        {
+            let sync_err =
+                |e: std::io::Error| StoreError::Internal(format!("sync {}: {e}", dir.display()));
+            let handle = tokio::fs::File::open(dir).await.map_err(sync_err)?;
+            handle.sync_all().await.map_err(sync_err)?;
+        }
+        #[cfg(not(unix))]
+        let _ = dir;
+        Ok(())
+    }
+}
+
+/// Suffix of in-progress writes, which `list` skips.
+const TMP_SUFFIX: &str = ".tmp";
+
+fn is_in_progress_write(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with('.') && name.ends_with(TMP_SUFFIX))
 }
 
 /// Reproduce the same default data directory logic as `flowstate_db::data_dir()`
@@ -45,14 +125,11 @@ fn default_data_dir() -> PathBuf {
 impl ObjectStore for LocalStore {
     async fn put(&self, key: &str, data: Bytes) -> Result<(), StoreError> {
         let path = self.resolve(key);
-        if let Some(parent) = path.parent() {
-            tokio::fs::create_dir_all(parent)
-                .await
-                .map_err(|e| StoreError::Internal(format!("mkdir: {e}")))?;
-        }
-        tokio::fs::write(&path, &data)
-            .await
-            .map_err(|e| StoreError::Internal(format!("write {}: {e}", path.display())))
+        self.write_atomic(
+            &path,
+            futures_util::stream::once(async { Ok(data) }).boxed(),
+        )
+        .await
     }
 
     async fn get(&self, key: &str) -> Result<Bytes, StoreError> {
@@ -69,6 +146,70 @@ impl ObjectStore for LocalStore {
         }
     }
 
+    async fn put_stream(&self, key: &str, stream: ByteStream) -> Result<(), StoreError> {
+        let path = self.resolve(key);
+        self.write_atomic(&path, stream).await
+    }
+
+    async fn get_stream(&self, key: &str) -> Result<ByteStream, StoreError> {
+        let path = self.resolve(key);
+        let file = match tokio::fs::File::open(&path).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(StoreError::NotFound(key.to_string()))
+            }
+            Err(e) => {
+                return Err(StoreError::Internal(format!(
+                    "read {}: {e}",
+                    path.display()
+                )))
+            }
+        };
+        Ok(ReaderStream::new(file)
+            .map_err(move |e| StoreError::Internal(format!("read {}: {e}", path.display())))
+            .boxed())
+    }
+
+    async fn get_range_stream(
+        &self,
+        key: &str,
+        start: u64,
+        len: u64,
+    ) -> Result<ByteStream, StoreError> {
+        let path = self.resolve(key);
+        let read_error =
+            move |e: std::io::Error| StoreError::Internal(format!("read {}: {e}", path.display()));
+        let mut file = match tokio::fs::File::open(self.resolve(key)).await {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(StoreError::NotFound(key.to_string()))
+            }
+            Err(e) => return Err(read_error(e)),
+        };
+        file.seek(std::io::SeekFrom::Start(start))
+            .await
+            .map_err(read_error.clone())?;
+        Ok(ReaderStream::new(file.take(len))
+            .map_err(read_error)
+            .boxed())
+    }
+
+    async fn presign_get(&self, key: &str, ttl: Duration) -> Result<String, StoreError> {
+        crate::presign::ttl_secs(ttl)?;
+        let token = self.signer.sign(PresignMethod::Get, key, ttl);
+        Ok(format!("{PRESIGNED_PATH}{token}"))
+    }
+
+    async fn presign_put(&self, key: &str, ttl: Duration) -> Result<String, StoreError> {
+        crate::presign::ttl_secs(ttl)?;
+        let token = self.signer.sign(PresignMethod::Put, key, ttl);
+        Ok(format!("{PRESIGNED_PATH}{token}"))
+    }
+
+    fn verify_presigned(&self, token: &str, method: PresignMethod) -> Result<String, StoreError> {
+        self.signer.verify(token, method)
+    }
+
     async fn delete(&self, key: &str) -> Result<(), StoreError> {
         let path = self.resolve(key);
         match tokio::fs::remove_file(&path).await {
@@ -81,12 +222,12 @@ impl ObjectStore for LocalStore {
         }
     }
 
-    async fn list(&self, prefix: &str) -> Result<Vec<String>, StoreError> {
+    async fn list_with_meta(&self, prefix: &str) -> Result<Vec<ObjectMeta>, StoreError> {
         let dir = self.resolve(prefix);
         if !dir.exists() {
             return Ok(vec![]);
         }
-        let mut keys = Vec::new();
+        let mut objects = Vec::new();
         let mut stack = vec![dir];
         while let Some(current) = stack.pop() {
             let mut entries = match tokio::fs::read_dir(&current).await {
@@ -111,16 +252,24 @@ impl ObjectStore for LocalStore {
                     .map_err(|e| StoreError::Internal(format!("file_type: {e}")))?;
                 if ft.is_dir() {
                     stack.push(path);
-                } else {
+                } else if !is_in_progress_write(&path) {
                     // Produce a key relative to base_dir
                     if let Ok(rel) = path.strip_prefix(&self.base_dir) {
-                        keys.push(rel.to_string_lossy().to_string());
+                        let metadata = entry
+                            .metadata()
+                            .await
+                            .map_err(|e| StoreError::Internal(format!("metadata: {e}")))?;
+                        objects.push(ObjectMeta {
+                            key: rel.to_string_lossy().to_string(),
+                            size: metadata.len(),
+                            last_modified: metadata.modified().ok().map(DateTime::<Utc>::from),
+                        });
                     }
                 }
             }
         }
-        keys.sort();
-        Ok(keys)
+        objects.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(objects)
     }
 
     async fn exists(&self, key: &str) -> Result<bool, StoreError> {
@@ -133,11 +282,45 @@ impl ObjectStore for LocalStore {
             ))),
         }
     }
+
+    async fn rename(&self, from: &str, to: &str) -> Result<(), StoreError> {
+        let target = self.resolve(to);
+        if let Some(parent) = target.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| StoreError::Internal(format!("mkdir: {e}")))?;
+        }
+        match tokio::fs::rename(self.resolve(from), &target).await {
+            Ok(()) => {
+                self.sync_dir(target.parent().unwrap_or(&self.base_dir))
+                    .await
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Err(StoreError::NotFound(from.to_string()))
+            }
+            Err(e) => Err(StoreError::Internal(format!("rename {from} to {to}: {e}"))),
+        }
+    }
+
+    async fn size(&self, key: &str) -> Result<u64, StoreError> {
+        let path = self.resolve(key);
+        match tokio::fs::metadata(&path).await {
+            Ok(metadata) => Ok(metadata.len()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Err(StoreError::NotFound(key.to_string()))
+            }
+            Err(e) => Err(StoreError::Internal(format!(
+                "size {}: {e}",
+                path.display()
+            ))),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{MultipartConfig, MAX_PRESIGN_TTL};
 
     fn test_store(dir: &std::path::Path) -> LocalStore {
         let config = StoreConfig {
@@ -147,10 +330,44 @@ mod tests {
             access_key_id: None,
             secret_access_key: None,
             local_data_dir: Some(dir.to_string_lossy().to_string()),
+            gcs: None,
+            azure: None,
+            durability: Durability::Atomic,
+            multipart: MultipartConfig::default(),
         };
         LocalStore::new(&config)
     }
 
+    #[tokio::test]
+    async fn get_range_stream_reads_part_of_an_object() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = test_store(tmp.path());
+        store
+            .put("a/b.bin", Bytes::from("0123456789"))
+            .await
+            .unwrap();
+
+        let read = |start, len| {
+            let store = &store;
+            async move {
+                let chunks: Vec<Bytes> = store
+                    .get_range_stream("a/b.bin", start, len)
+                    .await
+                    .unwrap()
+                    .try_collect()
+                    .await
+                    .unwrap();
+                chunks.concat()
+            }
+        };
+        assert_eq!(read(2, 3).await, b"234");
+        assert_eq!(read(8, 10).await, b"89");
+        assert!(matches!(
+            store.get_range_stream("missing", 0, 1).await,
+            Err(StoreError::NotFound(_))
+        ));
+    }
+
     #[tokio::test]
     async fn put_then_get_roundtrip() {
         let tmp = tempfile::tempdir().unwrap();
@@ -182,6 +399,94 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[tokio::test]
+    async fn put_stream_then_get_stream_roundtrip() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = test_store(tmp.path());
+
+        let chunks = vec![Ok(Bytes::from("hello ")), Ok(Bytes::from("world"))];
+        store
+            .put_stream("big/file.bin", futures_util::stream::iter(chunks).boxed())
+            .await
+            .unwrap();
+        let read: Vec<Bytes> = store
+            .get_stream("big/file.bin")
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(read.concat(), b"hello world");
+
+        let err = store.get_stream("missing").await.err().unwrap();
+        assert!(matches!(err, StoreError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn failed_put_stream_keeps_previous_object() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = test_store(tmp.path());
+        store.put("key", Bytes::from("old")).await.unwrap();
+
+        let chunks = vec![
+            Ok(Bytes::from("partial")),
+            Err(StoreError::Internal("client went away".into())),
+        ];
+        let err = store
+            .put_stream("key", futures_util::stream::iter(chunks).boxed())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("client went away"));
+        assert_eq!(store.get("key").await.unwrap().as_ref(), b"old");
+        assert_eq!(store.list("").await.unwrap(), vec!["key"]);
+    }
+
+    #[tokio::test]
+    async fn fsync_puts_leave_no_temp_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut store = test_store(tmp.path());
+        store.durability = Durability::Fsync;
+
+        store.put("a/b.md", Bytes::from("one")).await.unwrap();
+        store.put("a/b.md", Bytes::from("two")).await.unwrap();
+        store.rename("a/b.md", "c/d.md").await.unwrap();
+        assert_eq!(store.get("c/d.md").await.unwrap().as_ref(), b"two");
+
+        let mut names = Vec::new();
+        for dir in ["a", "c"] {
+            let mut entries = tokio::fs::read_dir(tmp.path().join(dir)).await.unwrap();
+            while let Some(entry) = entries.next_entry().await.unwrap() {
+                names.push(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+        assert_eq!(names, ["d.md"]);
+    }
+
+    #[tokio::test]
+    async fn list_skips_in_progress_writes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = test_store(tmp.path());
+        store.put("a/b.md", Bytes::from("done")).await.unwrap();
+        tokio::fs::write(tmp.path().join("a/.b.md.0123456789abcdef.tmp"), "part")
+            .await
+            .unwrap();
+        assert_eq!(store.list("a").await.unwrap(), vec!["a/b.md"]);
+    }
+
+    #[tokio::test]
+    async fn list_with_meta_reports_size_and_mtime() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = test_store(tmp.path());
+        let before = chrono::Utc::now() - chrono::Duration::seconds(5);
+        store.put("a/two.md", Bytes::from("xy")).await.unwrap();
+        store.put("a/one.md", Bytes::from("x")).await.unwrap();
+
+        let objects = store.list_with_meta("a").await.unwrap();
+        let listed: Vec<_> = objects.iter().map(|o| (o.key.as_str(), o.size)).collect();
+        assert_eq!(listed, vec![("a/one.md", 1), ("a/two.md", 2)]);
+        assert!(objects[0].last_modified.unwrap() > before);
+    }
+
     #[tokio::test]
     async fn put_overwrites_existing() {
         let tmp = tempfile::tempdir().unwrap();
@@ -206,6 +511,32 @@ mod tests {
         assert!(!store.exists("key").await.unwrap());
     }
 
+    #[tokio::test]
+    async fn presigned_urls_carry_verifiable_tokens() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = test_store(tmp.path());
+        let ttl = Duration::from_secs(60);
+
+        let url = store.presign_get("a/b.txt", ttl).await.unwrap();
+        let token = url.strip_prefix(PRESIGNED_PATH).unwrap();
+        assert_eq!(
+            store.verify_presigned(token, PresignMethod::Get).unwrap(),
+            "a/b.txt"
+        );
+        assert!(store.verify_presigned(token, PresignMethod::Put).is_err());
+
+        let url = store.presign_put("a/c.txt", ttl).await.unwrap();
+        let token = url.strip_prefix(PRESIGNED_PATH).unwrap();
+        assert_eq!(
+            store.verify_presigned(token, PresignMethod::Put).unwrap(),
+            "a/c.txt"
+        );
+        assert!(store
+            .presign_get("a/b.txt", MAX_PRESIGN_TTL * 2)
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn delete_missing_is_noop() {
         let tmp = tempfile::tempdir().unwrap();
@@ -265,6 +596,34 @@ mod tests {
         assert!(store.exists("key").await.unwrap());
     }
 
+    #[tokio::test]
+    async fn rename_moves_the_object() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = test_store(tmp.path());
+
+        store.put("from/key", Bytes::from("data")).await.unwrap();
+        store.rename("from/key", "to/nested/key").await.unwrap();
+        assert!(!store.exists("from/key").await.unwrap());
+        assert_eq!(store.get("to/nested/key").await.unwrap().as_ref(), b"data");
+        assert!(matches!(
+            store.rename("from/key", "elsewhere").await,
+            Err(StoreError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn size_reports_stored_bytes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = test_store(tmp.path());
+
+        store.put("key", Bytes::from("12345")).await.unwrap();
+        assert_eq!(store.size("key").await.unwrap(), 5);
+        assert!(matches!(
+            store.size("missing").await,
+            Err(StoreError::NotFound(_))
+        ));
+    }
+
     #[tokio::test]
     async fn unicode_content_roundtrip() {
         let tmp = tempfile::tempdir().unwrap();
@@ -0,0 +1,133 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::StoreError;
+
+/// Path the server serves presigned tokens on; a token is appended.
+pub const PRESIGNED_PATH: &str = "/api/store/presigned/";
+
+/// Longest a presigned URL may stay valid (S3's own limit).
+pub const MAX_PRESIGN_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// The operation a presigned URL allows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PresignMethod {
+    Get,
+    Put,
+}
+
+impl PresignMethod {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Get => "GET",
+            Self::Put => "PUT",
+        }
+    }
+}
+
+/// Signs and checks the tokens in server-proxied presigned URLs. A token is
+/// `<key>.<expires>.<signature>`: the base64url object key, the expiry in
+/// Unix seconds and an HMAC-SHA256 over the method, key and expiry.
+pub(crate) struct Signer {
+    secret: [u8; 32],
+}
+
+impl Signer {
+    /// A signer with a random secret, so tokens stop working when the
+    /// process restarts.
+    pub(crate) fn random() -> Self {
+        Self {
+            secret: rand::random(),
+        }
+    }
+
+    fn mac(&self, method: PresignMethod, key: &str, expires: u64) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.secret).expect("any key length");
+        mac.update(format!("{}\n{key}\n{expires}", method.as_str()).as_bytes());
+        mac
+    }
+
+    pub(crate) fn sign(&self, method: PresignMethod, key: &str, ttl: Duration) -> String {
+        let expires = unix_now() + ttl.as_secs();
+        let signature = self.mac(method, key, expires).finalize().into_bytes();
+        format!(
+            "{}.{expires}.{}",
+            URL_SAFE_NO_PAD.encode(key),
+            URL_SAFE_NO_PAD.encode(signature)
+        )
+    }
+
+    /// The object key a token grants `method` on, if it is genuine and has
+    /// not expired.
+    pub(crate) fn verify(&self, token: &str, method: PresignMethod) -> Result<String, StoreError> {
+        let invalid = || StoreError::InvalidToken("malformed token".into());
+        let mut parts = token.splitn(3, '.');
+        let (key, expires, signature) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(k), Some(e), Some(s)) => (k, e, s),
+            _ => return Err(invalid()),
+        };
+        let key = URL_SAFE_NO_PAD
+            .decode(key)
+            .ok()
+            .and_then(|k| String::from_utf8(k).ok())
+            .ok_or_else(invalid)?;
+        let expires: u64 = expires.parse().map_err(|_| invalid())?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| invalid())?;
+
+        self.mac(method, &key, expires)
+            .verify_slice(&signature)
+            .map_err(|_| StoreError::InvalidToken("bad signature".into()))?;
+        if unix_now() >= expires {
+            return Err(StoreError::InvalidToken("token has expired".into()));
+        }
+        Ok(key)
+    }
+}
+
+/// Reject TTLs a presigned URL cannot have, returning it in whole seconds.
+pub(crate) fn ttl_secs(ttl: Duration) -> Result<u32, StoreError> {
+    if ttl.is_zero() || ttl > MAX_PRESIGN_TTL {
+        return Err(StoreError::Internal(format!(
+            "presign TTL must be between 1s and {}s",
+            MAX_PRESIGN_TTL.as_secs()
+        )));
+    }
+    Ok(ttl.as_secs().max(1) as u32)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_roundtrip_checks_method_signature_and_expiry() {
+        let signer = Signer::random();
+        let key = "tasks/t1/attachments/a1/report final.pdf";
+        let token = signer.sign(PresignMethod::Get, key, Duration::from_secs(60));
+        assert_eq!(signer.verify(&token, PresignMethod::Get).unwrap(), key);
+
+        // Wrong method, other signer, tampered key, expired
+        assert!(signer.verify(&token, PresignMethod::Put).is_err());
+        assert!(Signer::random().verify(&token, PresignMethod::Get).is_err());
+        let (_, rest) = token.split_once('.').unwrap();
+        let forged = format!("{}.{rest}", URL_SAFE_NO_PAD.encode("tasks/other"));
+        assert!(signer.verify(&forged, PresignMethod::Get).is_err());
+        let expired = signer.sign(PresignMethod::Get, key, Duration::ZERO);
+        assert!(matches!(
+            signer.verify(&expired, PresignMethod::Get),
+            Err(StoreError::InvalidToken(_))
+        ));
+        assert!(signer.verify("garbage", PresignMethod::Get).is_err());
+    }
+}
@@ -1,14 +1,24 @@
+use std::time::Duration;
+
 use async_trait::async_trait;
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
+use chrono::{DateTime, Utc};
+use futures_util::{StreamExt, TryStreamExt};
 use s3::creds::Credentials;
 use s3::error::S3Error;
 use s3::region::Region;
+use s3::serde_types::Part;
 use s3::Bucket;
+use tracing::warn;
 
-use crate::{ObjectStore, StoreConfig, StoreError};
+use crate::{
+    content_type_for_key, ByteStream, MultipartConfig, ObjectMeta, ObjectStore, StoreConfig,
+    StoreError,
+};
 
 pub struct S3Store {
     bucket: Box<Bucket>,
+    multipart: MultipartConfig,
 }
 
 impl std::fmt::Debug for S3Store {
@@ -42,17 +52,31 @@ impl S3Store {
             .map_err(|e| StoreError::Internal(format!("bucket: {e}")))?;
         bucket.set_path_style();
 
-        Ok(Self { bucket })
+        Ok(Self {
+            bucket,
+            multipart: config.multipart,
+        })
     }
-}
 
-fn content_type_for_key(key: &str) -> &'static str {
-    if key.ends_with(".md") {
-        "text/markdown"
-    } else if key.ends_with(".txt") {
-        "text/plain"
-    } else {
-        "application/octet-stream"
+    async fn upload_parts(
+        &self,
+        key: &str,
+        upload_id: &str,
+        parts: &mut PartReader,
+    ) -> Result<Vec<Part>, StoreError> {
+        let content_type = content_type_for_key(key);
+        let mut uploaded = Vec::new();
+        while let Some(data) = parts.next_part().await? {
+            let number = uploaded.len() as u32 + 1;
+            let part = with_retries(self.multipart.part_retries, PART_RETRY_BACKOFF, || {
+                self.bucket
+                    .put_multipart_chunk(data.to_vec(), key, number, upload_id, content_type)
+            })
+            .await
+            .map_err(|e| StoreError::Internal(format!("s3 upload {key} part {number}: {e}")))?;
+            uploaded.push(part);
+        }
+        Ok(uploaded)
     }
 }
 
@@ -60,6 +84,66 @@ fn map_s3_error(e: S3Error) -> StoreError {
     StoreError::Internal(format!("s3: {e}"))
 }
 
+/// Wait before the first retry of a part; doubles with each further one.
+const PART_RETRY_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Cuts a stream into parts of `part_size` bytes, the last one shorter.
+struct PartReader {
+    buf: BytesMut,
+    stream: ByteStream,
+    part_size: usize,
+    done: bool,
+}
+
+impl PartReader {
+    fn new(head: BytesMut, stream: ByteStream, part_size: usize) -> Self {
+        Self {
+            buf: head,
+            stream,
+            part_size,
+            done: false,
+        }
+    }
+
+    async fn next_part(&mut self) -> Result<Option<Bytes>, StoreError> {
+        while !self.done && self.buf.len() < self.part_size {
+            match self.stream.try_next().await? {
+                Some(chunk) => self.buf.extend_from_slice(&chunk),
+                None => self.done = true,
+            }
+        }
+        if self.buf.is_empty() {
+            return Ok(None);
+        }
+        let len = self.part_size.min(self.buf.len());
+        Ok(Some(self.buf.split_to(len).freeze()))
+    }
+}
+
+/// Run `op`, retrying up to `retries` more times after errors in transit
+/// with exponential backoff. An error status from S3 is returned at once:
+/// rust-s3 aborts the upload when a part is rejected, so retrying the part
+/// could not succeed.
+async fn with_retries<T, F, Fut>(retries: u32, backoff: Duration, mut op: F) -> Result<T, S3Error>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, S3Error>>,
+{
+    let mut attempt = 0;
+    loop {
+        match op().await {
+            Ok(value) => return Ok(value),
+            Err(e @ (S3Error::Reqwest(_) | S3Error::Io(_))) if attempt < retries => {
+                let delay = backoff * 2u32.saturating_pow(attempt);
+                attempt += 1;
+                warn!("s3 request failed ({e}), retry {attempt}/{retries} in {delay:?}");
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
 #[async_trait]
 impl ObjectStore for S3Store {
     async fn put(&self, key: &str, data: Bytes) -> Result<(), StoreError> {
@@ -86,37 +170,136 @@ impl ObjectStore for S3Store {
         Ok(Bytes::from(response.to_vec()))
     }
 
+    /// Objects larger than the multipart threshold are sent as a multipart
+    /// upload, so only a part at a time is held in memory beyond the
+    /// threshold, and a part that fails in transit is retried on its own
+    /// rather than restarting the whole object.
+    async fn put_stream(&self, key: &str, mut stream: ByteStream) -> Result<(), StoreError> {
+        let mut head = BytesMut::new();
+        while head.len() as u64 <= self.multipart.threshold {
+            match stream.try_next().await? {
+                Some(chunk) => head.extend_from_slice(&chunk),
+                None => return self.put(key, head.freeze()).await,
+            }
+        }
+
+        let content_type = content_type_for_key(key);
+        let upload = self
+            .bucket
+            .initiate_multipart_upload(key, content_type)
+            .await
+            .map_err(map_s3_error)?;
+        let mut parts = PartReader::new(head, stream, self.multipart.part_size as usize);
+        let uploaded = self.upload_parts(key, &upload.upload_id, &mut parts).await;
+        let completed = match uploaded {
+            Ok(uploaded) => self
+                .bucket
+                .complete_multipart_upload(key, &upload.upload_id, uploaded)
+                .await
+                .map_err(map_s3_error)
+                .and_then(|response| match response.status_code() {
+                    200..=299 => Ok(()),
+                    status => Err(StoreError::Internal(format!(
+                        "s3 complete upload {key}: status {status}"
+                    ))),
+                }),
+            Err(e) => Err(e),
+        };
+        if completed.is_err() {
+            // Best effort: S3 would otherwise keep the parts, and bill for
+            // them, until a lifecycle rule clears them
+            if let Err(e) = self.bucket.abort_upload(key, &upload.upload_id).await {
+                warn!("s3 abort upload {key}: {e}");
+            }
+        }
+        completed
+    }
+
+    async fn get_stream(&self, key: &str) -> Result<ByteStream, StoreError> {
+        let response = self
+            .bucket
+            .get_object_stream(key)
+            .await
+            .map_err(map_s3_error)?;
+        if response.status_code == 404 {
+            return Err(StoreError::NotFound(key.to_string()));
+        }
+        if response.status_code >= 400 {
+            return Err(StoreError::Internal(format!(
+                "s3 get {}: status {}",
+                key, response.status_code
+            )));
+        }
+        Ok(response.bytes.map_err(map_s3_error).boxed())
+    }
+
+    async fn presign_get(&self, key: &str, ttl: Duration) -> Result<String, StoreError> {
+        let secs = crate::presign::ttl_secs(ttl)?;
+        self.bucket
+            .presign_get(key, secs, None)
+            .await
+            .map_err(map_s3_error)
+    }
+
+    async fn presign_put(&self, key: &str, ttl: Duration) -> Result<String, StoreError> {
+        let secs = crate::presign::ttl_secs(ttl)?;
+        self.bucket
+            .presign_put(key, secs, None, None)
+            .await
+            .map_err(map_s3_error)
+    }
+
     async fn delete(&self, key: &str) -> Result<(), StoreError> {
         self.bucket.delete_object(key).await.map_err(map_s3_error)?;
         Ok(())
     }
 
-    async fn list(&self, prefix: &str) -> Result<Vec<String>, StoreError> {
+    async fn list_with_meta(&self, prefix: &str) -> Result<Vec<ObjectMeta>, StoreError> {
         let results = self
             .bucket
             .list(prefix.to_string(), None)
             .await
             .map_err(map_s3_error)?;
 
-        let mut keys = Vec::new();
+        let mut objects = Vec::new();
         for result in results {
             for object in result.contents {
-                keys.push(object.key);
+                objects.push(ObjectMeta {
+                    last_modified: DateTime::parse_from_rfc3339(&object.last_modified)
+                        .ok()
+                        .map(|t| t.with_timezone(&Utc)),
+                    key: object.key,
+                    size: object.size,
+                });
             }
         }
-        keys.sort();
-        Ok(keys)
+        objects.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(objects)
     }
 
     async fn exists(&self, key: &str) -> Result<bool, StoreError> {
         let response = self.bucket.get_object(key).await.map_err(map_s3_error)?;
         Ok(response.status_code() != 404)
     }
+
+    async fn size(&self, key: &str) -> Result<u64, StoreError> {
+        let (head, status) = self.bucket.head_object(key).await.map_err(map_s3_error)?;
+        if status == 404 {
+            return Err(StoreError::NotFound(key.to_string()));
+        }
+        if status >= 400 {
+            return Err(StoreError::Internal(format!(
+                "s3 size {key}: status {status}"
+            )));
+        }
+        Ok(head.content_length.unwrap_or(0).max(0) as u64)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{Durability, MultipartConfig, PresignMethod};
 
     #[test]
     fn missing_bucket_produces_error() {
@@ -127,6 +310,10 @@ mod tests {
             access_key_id: Some("key".into()),
             secret_access_key: Some("secret".into()),
             local_data_dir: None,
+            gcs: None,
+            azure: None,
+            durability: Durability::Atomic,
+            multipart: MultipartConfig::default(),
         };
         let err = S3Store::new(&config).unwrap_err();
         assert!(err.to_string().contains("bucket name required"));
@@ -141,6 +328,10 @@ mod tests {
             access_key_id: Some("key".into()),
             secret_access_key: Some("secret".into()),
             local_data_dir: None,
+            gcs: None,
+            azure: None,
+            durability: Durability::Atomic,
+            multipart: MultipartConfig::default(),
         };
         let store = S3Store::new(&config);
         assert!(store.is_ok());
@@ -162,6 +353,52 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn parts_are_cut_to_size() {
+        let chunks = ["abc", "defgh", "ij"].map(|c| Ok(Bytes::from(c)));
+        let stream = futures_util::stream::iter(chunks).boxed();
+        let mut parts = PartReader::new(BytesMut::from("xy"), stream, 4);
+        let mut cut = Vec::new();
+        while let Some(part) = parts.next_part().await.unwrap() {
+            cut.push(part);
+        }
+        assert_eq!(cut, ["xyab", "cdef", "ghij"]);
+        assert_eq!(parts.next_part().await.unwrap(), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn transient_part_failures_are_retried() {
+        let attempts = std::sync::atomic::AtomicU32::new(0);
+        let result = with_retries(3, Duration::from_secs(1), || async {
+            match attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst) {
+                0 | 1 => Err(S3Error::Io(std::io::Error::other("connection reset"))),
+                _ => Ok("etag"),
+            }
+        })
+        .await;
+        assert_eq!(result.unwrap(), "etag");
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 3);
+
+        // Out of retries
+        let result: Result<(), _> = with_retries(1, Duration::from_secs(1), || async {
+            Err(S3Error::Io(std::io::Error::other("connection reset")))
+        })
+        .await;
+        assert!(matches!(result, Err(S3Error::Io(_))));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rejected_parts_are_not_retried() {
+        let attempts = std::sync::atomic::AtomicU32::new(0);
+        let result: Result<(), _> = with_retries(3, Duration::from_secs(1), || async {
+            attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Err(S3Error::HttpFailWithBody(403, "denied".into()))
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
     // -- S3 integration tests (require running Garage/MinIO) --
 
     fn s3_config() -> Option<StoreConfig> {
@@ -173,6 +410,77 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    #[ignore]
+    async fn s3_stream_roundtrip() {
+        let config = s3_config().expect("S3 not configured — skipped via #[ignore]");
+        let store = S3Store::new(&config).unwrap();
+        let key = "integration-test/stream-roundtrip.bin";
+
+        let chunks = vec![Ok(Bytes::from("hello ")), Ok(Bytes::from("stream"))];
+        store
+            .put_stream(key, futures_util::stream::iter(chunks).boxed())
+            .await
+            .unwrap();
+        let read: Vec<Bytes> = store
+            .get_stream(key)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(read.concat(), b"hello stream");
+
+        store.delete(key).await.unwrap();
+        let err = store.get_stream(key).await.err().unwrap();
+        assert!(matches!(err, StoreError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn s3_multipart_roundtrip() {
+        let mut config = s3_config().expect("S3 not configured — skipped via #[ignore]");
+        config.multipart.threshold = 0;
+        let store = S3Store::new(&config).unwrap();
+        let key = "integration-test/multipart.bin";
+        // Two full 5 MiB parts and a short last one
+        let data: Vec<u8> = (0..11 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+
+        let chunks: Vec<_> = data
+            .chunks(1024 * 1024)
+            .map(|c| Ok(Bytes::copy_from_slice(c)))
+            .collect();
+        store
+            .put_stream(key, futures_util::stream::iter(chunks).boxed())
+            .await
+            .unwrap();
+        assert_eq!(store.size(key).await.unwrap(), data.len() as u64);
+        assert_eq!(store.get(key).await.unwrap().as_ref(), data.as_slice());
+
+        store.delete(key).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore]
+    async fn s3_presigned_urls_are_signed() {
+        let config = s3_config().expect("S3 not configured — skipped via #[ignore]");
+        let store = S3Store::new(&config).unwrap();
+        let key = "integration-test/presigned.txt";
+
+        let get = store
+            .presign_get(key, Duration::from_secs(300))
+            .await
+            .unwrap();
+        assert!(get.contains(key) && get.contains("X-Amz-Signature"));
+        let put = store
+            .presign_put(key, Duration::from_secs(300))
+            .await
+            .unwrap();
+        assert!(put.contains("X-Amz-Signature"));
+        assert!(store.presign_get(key, Duration::ZERO).await.is_err());
+        assert!(store.verify_presigned("token", PresignMethod::Get).is_err());
+    }
+
     #[tokio::test]
     #[ignore]
     async fn s3_crud_roundtrip() {
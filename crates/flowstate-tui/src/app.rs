@@ -2,19 +2,34 @@ use std::path::PathBuf;
 
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent};
+use flowstate_core::board::ProjectLane;
+use flowstate_core::claude_run::{ClaudeAction, ClaudeRun};
+use flowstate_core::dashboard::ProjectDashboard;
 use flowstate_core::project::{CreateProject, UpdateProject};
+use flowstate_core::runner::{RunnerRecord, RunnerStatus};
+use flowstate_core::saved_filter::{FilterQuery, SavedFilter};
 use flowstate_core::sprint::{CreateSprint, Sprint};
 use flowstate_core::task::{
     next_subtask_status, prev_subtask_status, ApprovalStatus, CreateTask, Priority, Status, Task,
-    TaskFilter, UpdateTask,
+    TaskFeedback, TaskFilter, TaskType, UpdateTask,
 };
-use flowstate_core::Project;
-use flowstate_service::BlockingHttpService;
+use flowstate_core::user::User;
+use flowstate_core::{DisplayZone, Project, TaskLinks};
+use flowstate_db::DbStats;
+use flowstate_service::{BlockingHttpService, PendingConfigResponse};
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap};
 
+use crate::components::dashboard;
+use crate::components::rollup_board;
 use crate::components::task_board::TaskBoard;
 
+/// Most recent feedback history entries shown in the task detail.
+const FEEDBACK_HISTORY_SHOWN: usize = 5;
+
+/// Seconds `+`/`-` change a runner's poll interval by.
+const POLL_INTERVAL_STEP: u64 = 5;
+
 /// What the app is currently doing
 #[derive(Debug, Clone)]
 pub enum Mode {
@@ -32,6 +47,12 @@ pub enum Mode {
     ConfirmDelete { task: Task },
     /// Priority picker
     PriorityPick { task_id: String, current: Priority },
+    /// Assignee picker; the first entry is "Unassigned"
+    AssigneePick {
+        task: Task,
+        users: Vec<User>,
+        list_state: ListState,
+    },
     /// Project list/switcher
     ProjectList {
         projects: Vec<Project>,
@@ -80,6 +101,12 @@ pub enum Mode {
         input: String,
         status: ApprovalStatus,
     },
+    /// Offer to queue the phase's distill run after rejecting with feedback
+    ConfirmDistill {
+        task: Task,
+        field: String,
+        feedback: String,
+    },
     /// Editing a project's repo URL
     EditRepoUrl { project_id: String, input: String },
     /// Editing a project's repo token (PAT)
@@ -93,8 +120,30 @@ pub enum Mode {
     },
     /// Creating a new sprint
     NewSprint { input: String },
+    /// Saved filter (view) picker
+    FilterList {
+        filters: Vec<SavedFilter>,
+        list_state: ListState,
+    },
     /// Creating a subtask
     NewSubtask { parent: Task, input: String },
+    /// Cross-project roll-up board, one swimlane per project
+    Rollup {
+        lanes: Vec<ProjectLane>,
+        offset: usize,
+    },
+    /// Archived tasks of the current project
+    Archived {
+        tasks: Vec<Task>,
+        list_state: ListState,
+    },
+    /// Task, run and sprint figures of the current project
+    Dashboard { dashboard: ProjectDashboard },
+    /// Runner registry, for draining and retuning runners
+    Runners {
+        runners: Vec<RunnerRecord>,
+        list_state: ListState,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -127,6 +176,23 @@ pub struct App {
     pub editor_request: Option<EditorRequest>,
     /// Active sprint filter (if set, board shows only tasks in this sprint)
     active_sprint: Option<Sprint>,
+    /// The project's saved filters, switched to with the number keys
+    saved_filters: Vec<SavedFilter>,
+    /// Active saved filter (if set, board shows only tasks matching it)
+    active_filter: Option<SavedFilter>,
+    /// Projects marked in the project list for the roll-up board
+    rollup_projects: Vec<String>,
+    /// Who is using the TUI, for watching tasks
+    user: Option<User>,
+    /// Show only the tasks `user` is watching
+    watched_only: bool,
+    /// Task, action and idempotency key of the last run trigger that
+    /// failed, so retrying it can't queue the run twice
+    pending_trigger: Option<(String, String, String)>,
+    /// Deep links shown in the task detail view.
+    task_links: TaskLinks,
+    /// Timezone dates and times are shown in.
+    display_zone: DisplayZone,
 }
 
 /// Request to open a file in $EDITOR, with context for what to do after.
@@ -150,7 +216,10 @@ impl App {
             })?,
         };
 
-        let board = Self::load_board(&service, &project.id, None)?;
+        let board = Self::load_board(&service, &project.id, None, None)?;
+        let saved_filters = service
+            .list_saved_filters(&project.id, None)
+            .unwrap_or_default();
 
         Ok(Self {
             service,
@@ -160,22 +229,78 @@ impl App {
             status_message: None,
             editor_request: None,
             active_sprint: None,
+            saved_filters,
+            active_filter: None,
+            rollup_projects: Vec::new(),
+            user: None,
+            watched_only: false,
+            pending_trigger: None,
+            task_links: TaskLinks::default(),
+            display_zone: DisplayZone::default(),
         })
     }
 
+    /// Act as `user` when watching tasks.
+    pub fn set_user(&mut self, user: User) {
+        self.user = Some(user);
+    }
+
+    /// Show task links in this format instead of the default.
+    pub fn set_task_links(&mut self, task_links: TaskLinks) {
+        self.task_links = task_links;
+    }
+
+    /// Show dates and times in `zone` instead of UTC.
+    pub fn set_display_zone(&mut self, zone: DisplayZone) {
+        self.display_zone = zone;
+    }
+
+    /// Show a task's detail view, switching to its project first, as when
+    /// following a deep link.
+    pub fn open_task(&mut self, task_id: &str) {
+        match self.service.get_task(task_id) {
+            Ok(task) => {
+                if task.project_id != self.project.id {
+                    match self.service.get_project(&task.project_id) {
+                        Ok(project) => self.switch_project(project),
+                        Err(e) => {
+                            self.status_message = Some(format!("Error: {e}"));
+                            return;
+                        }
+                    }
+                }
+                self.board.select_task_by_id(&task.id);
+                self.mode = Mode::TaskDetail { task };
+            }
+            Err(e) => self.status_message = Some(format!("Cannot open task {task_id}: {e}")),
+        }
+    }
+
     fn load_board(
         service: &BlockingHttpService,
         project_id: &str,
         sprint_id: Option<String>,
+        query: Option<&FilterQuery>,
     ) -> Result<TaskBoard> {
-        let mut columns: Vec<(Status, Vec<Task>)> = Vec::new();
-        for &status in Status::BOARD_COLUMNS {
-            let tasks = service.list_tasks(&TaskFilter {
+        let base = match query {
+            Some(query) => query.to_task_filter(project_id),
+            None => TaskFilter {
                 project_id: Some(project_id.to_string()),
-                status: Some(status),
-                sprint_id: sprint_id.clone(),
                 ..Default::default()
-            })?;
+            },
+        };
+        let mut columns: Vec<(Status, Vec<Task>)> = Vec::new();
+        for &status in Status::BOARD_COLUMNS {
+            // A filter on one status leaves the other columns empty
+            let tasks = if base.status.is_some_and(|s| s != status) {
+                Vec::new()
+            } else {
+                service.list_tasks(&TaskFilter {
+                    status: Some(status),
+                    sprint_id: sprint_id.clone().or_else(|| base.sprint_id.clone()),
+                    ..base.clone()
+                })?
+            };
             columns.push((status, tasks));
         }
         Ok(TaskBoard::new(columns))
@@ -184,7 +309,13 @@ impl App {
     fn refresh(&mut self) {
         let selected_id = self.board.selected_task().map(|t| t.id.clone());
         let sprint_id = self.active_sprint.as_ref().map(|s| s.id.clone());
-        if let Ok(board) = Self::load_board(&self.service, &self.project.id, sprint_id) {
+        let mut query = self.active_filter.as_ref().map(|f| f.query.clone());
+        if let (true, Some(user)) = (self.watched_only, &self.user) {
+            query.get_or_insert_with(FilterQuery::default).watched_by = Some(user.id.clone());
+        }
+        if let Ok(board) =
+            Self::load_board(&self.service, &self.project.id, sprint_id, query.as_ref())
+        {
             self.board = board;
             if let Some(id) = selected_id {
                 self.board.select_task_by_id(&id);
@@ -194,6 +325,11 @@ impl App {
 
     fn switch_project(&mut self, project: Project) {
         self.project = project;
+        self.active_filter = None;
+        self.saved_filters = self
+            .service
+            .list_saved_filters(&self.project.id, None)
+            .unwrap_or_default();
         self.refresh();
         self.mode = Mode::Normal;
     }
@@ -321,6 +457,11 @@ impl App {
             Mode::PriorityPick { task_id, current } => {
                 self.handle_priority_pick(key, task_id.clone(), *current)
             }
+            Mode::AssigneePick {
+                task,
+                users,
+                list_state,
+            } => self.handle_assignee_pick(key, task.clone(), users.clone(), list_state.clone()),
             Mode::ProjectList {
                 projects,
                 list_state,
@@ -367,6 +508,11 @@ impl App {
             } => {
                 self.handle_feedback_input(key, task.clone(), field.clone(), input.clone(), *status)
             }
+            Mode::ConfirmDistill {
+                task,
+                field,
+                feedback,
+            } => self.handle_confirm_distill(key, task.clone(), field.clone(), feedback.clone()),
             Mode::EditRepoUrl { project_id, input } => {
                 self.handle_edit_repo_url(key, project_id.clone(), input.clone())
             }
@@ -378,6 +524,19 @@ impl App {
                 list_state,
             } => self.handle_sprint_list(key, sprints.clone(), list_state.clone()),
             Mode::NewSprint { input } => self.handle_new_sprint(key, input.clone()),
+            Mode::FilterList {
+                filters,
+                list_state,
+            } => self.handle_filter_list(key, filters.clone(), list_state.clone()),
+            Mode::Rollup { lanes, offset } => self.handle_rollup(key, lanes.clone(), *offset),
+            Mode::Archived { tasks, list_state } => {
+                self.handle_archived(key, tasks.clone(), list_state.clone())
+            }
+            Mode::Dashboard { .. } => self.handle_dashboard(key),
+            Mode::Runners {
+                runners,
+                list_state,
+            } => self.handle_runners(key, runners.clone(), list_state.clone()),
             Mode::NewSubtask { parent, input } => {
                 self.handle_new_subtask(key, parent.clone(), input.clone())
             }
@@ -478,7 +637,8 @@ impl App {
             }
             // Sprint list
             KeyCode::Char('x') => {
-                if let Ok(sprints) = self.service.list_sprints(&self.project.id) {
+                if let Ok(mut sprints) = self.service.list_sprints(&self.project.id) {
+                    sprints.retain(|s| !s.archived);
                     let mut list_state = ListState::default();
                     if !sprints.is_empty() {
                         // Select current active sprint if any
@@ -501,6 +661,55 @@ impl App {
                 self.refresh();
                 self.status_message = Some("Sprint filter cleared".into());
             }
+            // Saved filter picker
+            KeyCode::Char('f') => {
+                if let Ok(filters) = self.service.list_saved_filters(&self.project.id, None) {
+                    let mut list_state = ListState::default();
+                    if !filters.is_empty() {
+                        let idx = self
+                            .active_filter
+                            .as_ref()
+                            .and_then(|active| filters.iter().position(|f| f.id == active.id))
+                            .unwrap_or(0);
+                        list_state.select(Some(idx));
+                    }
+                    self.saved_filters = filters.clone();
+                    self.mode = Mode::FilterList {
+                        filters,
+                        list_state,
+                    };
+                }
+            }
+            // Quick-switch to the Nth saved filter; 0 clears
+            KeyCode::Char('0') => self.apply_filter(None),
+            KeyCode::Char(c @ '1'..='9') => {
+                let idx = c as usize - '1' as usize;
+                match self.saved_filters.get(idx).cloned() {
+                    Some(filter) => self.apply_filter(Some(filter)),
+                    None => self.status_message = Some(format!("No saved filter {c}")),
+                }
+            }
+            // Cross-project roll-up
+            KeyCode::Char('R') => self.open_rollup(),
+            // Archived task browser
+            KeyCode::Char('A') => self.open_archived(),
+            // Project dashboard
+            KeyCode::Char('D') => self.open_dashboard(),
+            KeyCode::Char('U') => self.open_runners(None),
+            // Toggle watched-only view
+            KeyCode::Char('w') => {
+                if self.user.is_none() {
+                    self.status_message = Some("Start with --user to watch tasks".into());
+                } else {
+                    self.watched_only = !self.watched_only;
+                    self.status_message = Some(if self.watched_only {
+                        "Showing watched tasks".into()
+                    } else {
+                        "Showing all tasks".into()
+                    });
+                    self.refresh();
+                }
+            }
             // Jump to next task needing attention
             KeyCode::Char('N') => {
                 if !self.board.select_next_attention() {
@@ -511,6 +720,277 @@ impl App {
         }
     }
 
+    fn open_archived(&mut self) {
+        let filter = TaskFilter {
+            project_id: Some(self.project.id.clone()),
+            archived: true,
+            ..Default::default()
+        };
+        match self.service.list_tasks(&filter) {
+            Ok(tasks) => {
+                let mut list_state = ListState::default();
+                if !tasks.is_empty() {
+                    list_state.select(Some(0));
+                }
+                self.mode = Mode::Archived { tasks, list_state };
+            }
+            Err(e) => self.status_message = Some(format!("Error: {e}")),
+        }
+    }
+
+    fn handle_archived(&mut self, key: KeyEvent, tasks: Vec<Task>, mut list_state: ListState) {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.mode = Mode::Normal,
+            KeyCode::Char('j') | KeyCode::Down => {
+                let i = list_state.selected().unwrap_or(0);
+                if i + 1 < tasks.len() {
+                    list_state.select(Some(i + 1));
+                }
+                self.mode = Mode::Archived { tasks, list_state };
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                let i = list_state.selected().unwrap_or(0);
+                if i > 0 {
+                    list_state.select(Some(i - 1));
+                }
+                self.mode = Mode::Archived { tasks, list_state };
+            }
+            KeyCode::Enter => {
+                if let Some(task) = list_state.selected().and_then(|i| tasks.get(i)) {
+                    self.mode = Mode::TaskDetail { task: task.clone() };
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Load the runner registry, keeping the cursor on `selected` when the
+    /// runner is still listed.
+    fn open_runners(&mut self, selected: Option<&str>) {
+        match self.service.list_runners() {
+            Ok(runners) => {
+                let mut list_state = ListState::default();
+                if !runners.is_empty() {
+                    let i = selected
+                        .and_then(|id| runners.iter().position(|r| r.runner_id == id))
+                        .unwrap_or(0);
+                    list_state.select(Some(i));
+                }
+                self.mode = Mode::Runners {
+                    runners,
+                    list_state,
+                };
+            }
+            Err(e) => self.status_message = Some(format!("Error: {e}")),
+        }
+    }
+
+    fn handle_runners(
+        &mut self,
+        key: KeyEvent,
+        runners: Vec<RunnerRecord>,
+        mut list_state: ListState,
+    ) {
+        let selected = list_state.selected().and_then(|i| runners.get(i)).cloned();
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.mode = Mode::Normal,
+            KeyCode::Char('j') | KeyCode::Down => {
+                let i = list_state.selected().unwrap_or(0);
+                if i + 1 < runners.len() {
+                    list_state.select(Some(i + 1));
+                }
+                self.mode = Mode::Runners {
+                    runners,
+                    list_state,
+                };
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                let i = list_state.selected().unwrap_or(0);
+                if i > 0 {
+                    list_state.select(Some(i - 1));
+                }
+                self.mode = Mode::Runners {
+                    runners,
+                    list_state,
+                };
+            }
+            KeyCode::Char('r') => {
+                self.open_runners(selected.as_ref().map(|r| r.runner_id.as_str()))
+            }
+            KeyCode::Char('d') => {
+                if let Some(runner) = selected {
+                    match self.service.drain_runner(&runner.runner_id) {
+                        Ok(_) => {
+                            self.status_message =
+                                Some(format!("Drain queued for {}", runner.runner_id))
+                        }
+                        Err(e) => self.status_message = Some(format!("Error: {e}")),
+                    }
+                    self.open_runners(Some(&runner.runner_id));
+                }
+            }
+            KeyCode::Char(c @ ('+' | '-')) => {
+                if let Some(runner) = selected {
+                    let interval = retuned_poll_interval(&runner, c == '+');
+                    let config = PendingConfigResponse {
+                        poll_interval: Some(interval),
+                        drain: None,
+                    };
+                    match self
+                        .service
+                        .update_runner_config(&runner.runner_id, &config)
+                    {
+                        Ok(_) => {
+                            self.status_message = Some(format!(
+                                "Poll interval {interval}s queued for {}",
+                                runner.runner_id
+                            ))
+                        }
+                        Err(e) => self.status_message = Some(format!("Error: {e}")),
+                    }
+                    self.open_runners(Some(&runner.runner_id));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn open_dashboard(&mut self) {
+        match self.service.project_dashboard(&self.project.id) {
+            Ok(dashboard) => self.mode = Mode::Dashboard { dashboard },
+            Err(e) => self.status_message = Some(format!("Error: {e}")),
+        }
+    }
+
+    fn handle_dashboard(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.mode = Mode::Normal,
+            KeyCode::Char('r') => self.open_dashboard(),
+            _ => {}
+        }
+    }
+
+    /// Load the roll-up board for the marked projects, or all of them
+    /// when none are marked.
+    fn open_rollup(&mut self) {
+        match self.service.rollup_board(&self.rollup_projects) {
+            Ok(lanes) => self.mode = Mode::Rollup { lanes, offset: 0 },
+            Err(e) => self.status_message = Some(format!("Error: {e}")),
+        }
+    }
+
+    fn handle_rollup(&mut self, key: KeyEvent, lanes: Vec<ProjectLane>, offset: usize) {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.mode = Mode::Normal,
+            KeyCode::Char('j') | KeyCode::Down => {
+                let offset = if offset + 1 < lanes.len() {
+                    offset + 1
+                } else {
+                    offset
+                };
+                self.mode = Mode::Rollup { lanes, offset };
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.mode = Mode::Rollup {
+                    lanes,
+                    offset: offset.saturating_sub(1),
+                };
+            }
+            KeyCode::Char('r') => match self.service.rollup_board(&self.rollup_projects) {
+                Ok(lanes) => {
+                    let offset = offset.min(lanes.len().saturating_sub(1));
+                    self.mode = Mode::Rollup { lanes, offset };
+                }
+                Err(e) => self.status_message = Some(format!("Error: {e}")),
+            },
+            // Open the project of the top lane
+            KeyCode::Enter => {
+                if let Some(lane) = lanes.get(offset) {
+                    self.switch_project(lane.project.clone());
+                    self.status_message = Some(format!("Switched to: {}", self.project.name));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Watch `task` as the TUI's user, or stop watching it.
+    fn toggle_watch(&mut self, task: &Task) {
+        let Some(user_id) = self.user.as_ref().map(|u| u.id.clone()) else {
+            self.status_message = Some("Start with --user to watch tasks".into());
+            return;
+        };
+        let watching = self
+            .service
+            .list_task_watchers(&task.id)
+            .map(|watchers| watchers.iter().any(|w| w.id == user_id));
+        let result = match watching {
+            Ok(true) => self
+                .service
+                .unwatch_task(&task.id, &user_id)
+                .map(|_| "Stopped watching"),
+            Ok(false) => self
+                .service
+                .watch_task(&task.id, &user_id)
+                .map(|_| "Watching"),
+            Err(e) => Err(e),
+        };
+        self.status_message = Some(match result {
+            Ok(msg) => format!("{msg}: {}", task.title),
+            Err(e) => format!("Error: {e}"),
+        });
+        if self.watched_only {
+            self.refresh();
+        }
+    }
+
+    fn apply_filter(&mut self, filter: Option<SavedFilter>) {
+        self.status_message = Some(match filter {
+            Some(ref f) => format!("View: {}", f.name),
+            None => "View cleared".into(),
+        });
+        self.active_filter = filter;
+        self.refresh();
+    }
+
+    fn handle_filter_list(
+        &mut self,
+        key: KeyEvent,
+        filters: Vec<SavedFilter>,
+        mut list_state: ListState,
+    ) {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.mode = Mode::Normal,
+            KeyCode::Char('j') | KeyCode::Down => {
+                let i = list_state.selected().unwrap_or(0);
+                if i + 1 < filters.len() {
+                    list_state.select(Some(i + 1));
+                }
+                self.mode = Mode::FilterList {
+                    filters,
+                    list_state,
+                };
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                let i = list_state.selected().unwrap_or(0);
+                if i > 0 {
+                    list_state.select(Some(i - 1));
+                }
+                self.mode = Mode::FilterList {
+                    filters,
+                    list_state,
+                };
+            }
+            KeyCode::Enter => {
+                if let Some(filter) = list_state.selected().and_then(|i| filters.get(i)) {
+                    self.apply_filter(Some(filter.clone()));
+                    self.mode = Mode::Normal;
+                }
+            }
+            _ => {}
+        }
+    }
+
     fn handle_new_task(&mut self, key: KeyEvent, mut input: String) {
         match key.code {
             KeyCode::Enter => {
@@ -523,6 +1003,7 @@ impl App {
                         description: String::new(),
                         status,
                         priority: Priority::Medium,
+                        task_type: TaskType::Feature,
                         parent_id: None,
                         reviewer: String::new(),
                         research_capability: None,
@@ -530,6 +1011,7 @@ impl App {
                         plan_capability: None,
                         build_capability: None,
                         verify_capability: None,
+                        due_at: None,
                     }) {
                         Ok(_) => {
                             self.refresh();
@@ -580,6 +1062,24 @@ impl App {
                     current: task.priority,
                 };
             }
+            KeyCode::Char('u') => match self.service.list_users() {
+                Ok(users) => {
+                    let mut list_state = ListState::default();
+                    let idx = task
+                        .assignee_id
+                        .as_ref()
+                        .and_then(|id| users.iter().position(|u| &u.id == id))
+                        .map_or(0, |i| i + 1);
+                    list_state.select(Some(idx));
+                    self.mode = Mode::AssigneePick {
+                        task,
+                        users,
+                        list_state,
+                    };
+                }
+                Err(e) => self.status_message = Some(format!("Error: {e}")),
+            },
+            KeyCode::Char('f') => self.toggle_watch(&task),
             KeyCode::Char('m') => {
                 let next = if task.is_subtask() {
                     next_subtask_status(task.status)
@@ -854,6 +1354,68 @@ impl App {
         self.mode = Mode::Normal;
     }
 
+    fn handle_assignee_pick(
+        &mut self,
+        key: KeyEvent,
+        task: Task,
+        users: Vec<User>,
+        mut list_state: ListState,
+    ) {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.mode = Mode::TaskDetail { task },
+            KeyCode::Char('j') | KeyCode::Down => {
+                let i = list_state.selected().unwrap_or(0);
+                // users.len() + 1 entries, counting "Unassigned"
+                if i < users.len() {
+                    list_state.select(Some(i + 1));
+                }
+                self.mode = Mode::AssigneePick {
+                    task,
+                    users,
+                    list_state,
+                };
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                let i = list_state.selected().unwrap_or(0);
+                if i > 0 {
+                    list_state.select(Some(i - 1));
+                }
+                self.mode = Mode::AssigneePick {
+                    task,
+                    users,
+                    list_state,
+                };
+            }
+            KeyCode::Enter => {
+                let assignee = list_state
+                    .selected()
+                    .and_then(|i| i.checked_sub(1))
+                    .and_then(|i| users.get(i));
+                match self.service.update_task(
+                    &task.id,
+                    &UpdateTask {
+                        assignee_id: Some(assignee.map(|u| u.id.clone())),
+                        ..Default::default()
+                    },
+                ) {
+                    Ok(updated) => {
+                        self.refresh();
+                        self.status_message = Some(match assignee {
+                            Some(u) => format!("Assigned to {}", u.name),
+                            None => "Unassigned".into(),
+                        });
+                        self.mode = Mode::TaskDetail { task: updated };
+                    }
+                    Err(e) => {
+                        self.status_message = Some(format!("Error: {e}"));
+                        self.mode = Mode::TaskDetail { task };
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
     fn handle_project_list(
         &mut self,
         key: KeyEvent,
@@ -890,6 +1452,20 @@ impl App {
                     }
                 }
             }
+            KeyCode::Char(' ') => {
+                if let Some(project) = list_state.selected().and_then(|i| projects.get(i)) {
+                    if let Some(pos) = self.rollup_projects.iter().position(|id| *id == project.id)
+                    {
+                        self.rollup_projects.remove(pos);
+                    } else {
+                        self.rollup_projects.push(project.id.clone());
+                    }
+                }
+                self.mode = Mode::ProjectList {
+                    projects,
+                    list_state,
+                };
+            }
             KeyCode::Char('n') => {
                 self.mode = Mode::NewProject {
                     name: String::new(),
@@ -1045,6 +1621,24 @@ impl App {
         }
     }
 
+    /// Trigger `action` on a task. A retry of a trigger that failed reuses
+    /// its idempotency key, so a request that reached the server before the
+    /// error can't leave a duplicate run queued.
+    fn trigger_run(&mut self, task_id: &str, action: &str) -> Result<ClaudeRun> {
+        let key = match &self.pending_trigger {
+            Some((t, a, key)) if t == task_id && a == action => key.clone(),
+            _ => uuid::Uuid::new_v4().to_string(),
+        };
+        let result = self
+            .service
+            .trigger_claude_run_idempotent(task_id, action, &key);
+        self.pending_trigger = match result {
+            Ok(_) => None,
+            Err(_) => Some((task_id.to_string(), action.to_string(), key)),
+        };
+        Ok(result?)
+    }
+
     fn handle_claude_action_pick(&mut self, key: KeyEvent, task: Task) {
         // Subtasks only support build and verify actions
         if task.is_subtask() {
@@ -1064,7 +1658,7 @@ impl App {
             }
         }
         match key.code {
-            KeyCode::Char('r') => match self.service.trigger_claude_run(&task.id, "research") {
+            KeyCode::Char('r') => match self.trigger_run(&task.id, "research") {
                 Ok(run) => {
                     self.status_message = Some("Claude researching...".into());
                     self.mode = Mode::ClaudeRunning {
@@ -1078,7 +1672,7 @@ impl App {
                     self.mode = Mode::TaskDetail { task };
                 }
             },
-            KeyCode::Char('d') => match self.service.trigger_claude_run(&task.id, "design") {
+            KeyCode::Char('d') => match self.trigger_run(&task.id, "design") {
                 Ok(run) => {
                     self.status_message = Some("Claude designing...".into());
                     self.mode = Mode::ClaudeRunning {
@@ -1101,7 +1695,7 @@ impl App {
                     self.mode = Mode::TaskDetail { task };
                     return;
                 }
-                match self.service.trigger_claude_run(&task.id, "plan") {
+                match self.trigger_run(&task.id, "plan") {
                     Ok(run) => {
                         self.status_message = Some("Claude planning...".into());
                         self.mode = Mode::ClaudeRunning {
@@ -1116,7 +1710,7 @@ impl App {
                     }
                 }
             }
-            KeyCode::Char('b') => match self.service.trigger_claude_run(&task.id, "build") {
+            KeyCode::Char('b') => match self.trigger_run(&task.id, "build") {
                 Ok(run) => {
                     self.status_message = Some("Claude building...".into());
                     self.mode = Mode::ClaudeRunning {
@@ -1130,7 +1724,7 @@ impl App {
                     self.mode = Mode::TaskDetail { task };
                 }
             },
-            KeyCode::Char('v') => match self.service.trigger_claude_run(&task.id, "verify") {
+            KeyCode::Char('v') => match self.trigger_run(&task.id, "verify") {
                 Ok(run) => {
                     self.status_message = Some("Claude verifying...".into());
                     self.mode = Mode::ClaudeRunning {
@@ -1144,42 +1738,35 @@ impl App {
                     self.mode = Mode::TaskDetail { task };
                 }
             },
-            KeyCode::Char('R') => {
-                match self
-                    .service
-                    .trigger_claude_run(&task.id, "research_distill")
-                {
-                    Ok(run) => {
-                        self.status_message = Some("Claude refining research...".into());
-                        self.mode = Mode::ClaudeRunning {
-                            task,
-                            run_id: run.id,
-                            progress: None,
-                        };
-                    }
-                    Err(e) => {
-                        self.status_message = Some(format!("Error: {e}"));
-                        self.mode = Mode::TaskDetail { task };
-                    }
+            KeyCode::Char('R') => match self.trigger_run(&task.id, "research_distill") {
+                Ok(run) => {
+                    self.status_message = Some("Claude refining research...".into());
+                    self.mode = Mode::ClaudeRunning {
+                        task,
+                        run_id: run.id,
+                        progress: None,
+                    };
                 }
-            }
-            KeyCode::Char('D') => {
-                match self.service.trigger_claude_run(&task.id, "design_distill") {
-                    Ok(run) => {
-                        self.status_message = Some("Claude refining design...".into());
-                        self.mode = Mode::ClaudeRunning {
-                            task,
-                            run_id: run.id,
-                            progress: None,
-                        };
-                    }
-                    Err(e) => {
-                        self.status_message = Some(format!("Error: {e}"));
-                        self.mode = Mode::TaskDetail { task };
-                    }
+                Err(e) => {
+                    self.status_message = Some(format!("Error: {e}"));
+                    self.mode = Mode::TaskDetail { task };
                 }
-            }
-            KeyCode::Char('P') => match self.service.trigger_claude_run(&task.id, "plan_distill") {
+            },
+            KeyCode::Char('D') => match self.trigger_run(&task.id, "design_distill") {
+                Ok(run) => {
+                    self.status_message = Some("Claude refining design...".into());
+                    self.mode = Mode::ClaudeRunning {
+                        task,
+                        run_id: run.id,
+                        progress: None,
+                    };
+                }
+                Err(e) => {
+                    self.status_message = Some(format!("Error: {e}"));
+                    self.mode = Mode::TaskDetail { task };
+                }
+            },
+            KeyCode::Char('P') => match self.trigger_run(&task.id, "plan_distill") {
                 Ok(run) => {
                     self.status_message = Some("Claude refining plan...".into());
                     self.mode = Mode::ClaudeRunning {
@@ -1193,22 +1780,20 @@ impl App {
                     self.mode = Mode::TaskDetail { task };
                 }
             },
-            KeyCode::Char('V') => {
-                match self.service.trigger_claude_run(&task.id, "verify_distill") {
-                    Ok(run) => {
-                        self.status_message = Some("Claude refining verification...".into());
-                        self.mode = Mode::ClaudeRunning {
-                            task,
-                            run_id: run.id,
-                            progress: None,
-                        };
-                    }
-                    Err(e) => {
-                        self.status_message = Some(format!("Error: {e}"));
-                        self.mode = Mode::TaskDetail { task };
-                    }
+            KeyCode::Char('V') => match self.trigger_run(&task.id, "verify_distill") {
+                Ok(run) => {
+                    self.status_message = Some("Claude refining verification...".into());
+                    self.mode = Mode::ClaudeRunning {
+                        task,
+                        run_id: run.id,
+                        progress: None,
+                    };
                 }
-            }
+                Err(e) => {
+                    self.status_message = Some(format!("Error: {e}"));
+                    self.mode = Mode::TaskDetail { task };
+                }
+            },
             KeyCode::Esc => self.mode = Mode::TaskDetail { task },
             _ => {}
         }
@@ -1224,60 +1809,17 @@ impl App {
     ) {
         match key.code {
             KeyCode::Enter => {
-                let feedback_update = match field.as_str() {
-                    "research" => UpdateTask {
-                        research_feedback: Some(input.clone()),
-                        research_status: Some(status),
-                        ..Default::default()
-                    },
-                    "spec" | "design" => UpdateTask {
-                        spec_feedback: Some(input.clone()),
-                        spec_status: Some(status),
-                        ..Default::default()
-                    },
-                    "plan" => UpdateTask {
-                        plan_feedback: Some(input.clone()),
-                        plan_status: Some(status),
-                        ..Default::default()
-                    },
-                    "verify" => UpdateTask {
-                        verify_feedback: Some(input.clone()),
-                        verify_status: Some(status),
-                        ..Default::default()
-                    },
-                    _ => UpdateTask::default(),
-                };
-                let label = if input.is_empty() {
-                    if status == ApprovalStatus::Approved {
-                        "approved"
-                    } else {
-                        "rejected"
-                    }
-                } else if status == ApprovalStatus::Approved {
-                    "approved with notes"
+                let offer_distill = status == ApprovalStatus::Rejected
+                    && !input.is_empty()
+                    && ClaudeAction::distill_for_phase(&field).is_some();
+                if offer_distill {
+                    self.mode = Mode::ConfirmDistill {
+                        task,
+                        field,
+                        feedback: input,
+                    };
                 } else {
-                    "rejected with feedback"
-                };
-                match self.service.update_task(&task.id, &feedback_update) {
-                    Ok(updated) => {
-                        self.refresh();
-                        let msg = if status == ApprovalStatus::Approved
-                            && updated.status != task.status
-                        {
-                            format!(
-                                "{field} {label} — moved to {}",
-                                updated.status.display_name()
-                            )
-                        } else {
-                            format!("{field} {label}")
-                        };
-                        self.status_message = Some(msg);
-                        self.mode = Mode::TaskDetail { task: updated };
-                    }
-                    Err(e) => {
-                        self.status_message = Some(format!("Error: {e}"));
-                        self.mode = Mode::TaskDetail { task };
-                    }
+                    self.apply_feedback(task, field, input, status);
                 }
             }
             KeyCode::Esc => {
@@ -1292,13 +1834,113 @@ impl App {
                     status,
                 };
             }
-            KeyCode::Char(c) => {
-                input.push(c);
+            KeyCode::Char(c) => {
+                input.push(c);
+                self.mode = Mode::FeedbackInput {
+                    task,
+                    field,
+                    input,
+                    status,
+                };
+            }
+            _ => {}
+        }
+    }
+
+    fn apply_feedback(&mut self, task: Task, field: String, input: String, status: ApprovalStatus) {
+        let feedback_update = match field.as_str() {
+            "research" => UpdateTask {
+                research_feedback: Some(input.clone()),
+                research_status: Some(status),
+                ..Default::default()
+            },
+            "spec" | "design" => UpdateTask {
+                spec_feedback: Some(input.clone()),
+                spec_status: Some(status),
+                ..Default::default()
+            },
+            "plan" => UpdateTask {
+                plan_feedback: Some(input.clone()),
+                plan_status: Some(status),
+                ..Default::default()
+            },
+            "verify" => UpdateTask {
+                verify_feedback: Some(input.clone()),
+                verify_status: Some(status),
+                ..Default::default()
+            },
+            _ => UpdateTask::default(),
+        };
+        let label = if input.is_empty() {
+            if status == ApprovalStatus::Approved {
+                "approved"
+            } else {
+                "rejected"
+            }
+        } else if status == ApprovalStatus::Approved {
+            "approved with notes"
+        } else {
+            "rejected with feedback"
+        };
+        match self.service.update_task(&task.id, &feedback_update) {
+            Ok(updated) => {
+                self.refresh();
+                let msg = if status == ApprovalStatus::Approved && updated.status != task.status {
+                    format!(
+                        "{field} {label} — moved to {}",
+                        updated.status.display_name()
+                    )
+                } else {
+                    format!("{field} {label}")
+                };
+                self.status_message = Some(msg);
+                self.mode = Mode::TaskDetail { task: updated };
+            }
+            Err(e) => {
+                self.status_message = Some(format!("Error: {e}"));
+                self.mode = Mode::TaskDetail { task };
+            }
+        }
+    }
+
+    fn handle_confirm_distill(
+        &mut self,
+        key: KeyEvent,
+        task: Task,
+        field: String,
+        feedback: String,
+    ) {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                let input = TaskFeedback {
+                    phase: field.clone(),
+                    feedback,
+                    distill: true,
+                };
+                match self.service.submit_feedback(&task.id, &input) {
+                    Ok(run) => {
+                        self.refresh();
+                        let queued = run.map(|r| r.action.to_string()).unwrap_or_default();
+                        self.status_message =
+                            Some(format!("{field} rejected — queued {queued} run"));
+                        let task = self.service.get_task(&task.id).unwrap_or(task);
+                        self.mode = Mode::TaskDetail { task };
+                    }
+                    Err(e) => {
+                        self.status_message = Some(format!("Error: {e}"));
+                        self.mode = Mode::TaskDetail { task };
+                    }
+                }
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') => {
+                self.apply_feedback(task, field, feedback, ApprovalStatus::Rejected);
+            }
+            KeyCode::Esc => {
                 self.mode = Mode::FeedbackInput {
                     task,
                     field,
-                    input,
-                    status,
+                    input: feedback,
+                    status: ApprovalStatus::Rejected,
                 };
             }
             _ => {}
@@ -1536,7 +2178,12 @@ impl App {
                         let detail = if r.connected {
                             format!("{} (connected)", r.runner_id)
                         } else {
-                            format!("{} (last seen: {})", r.runner_id, r.last_seen)
+                            let last_seen = chrono::DateTime::parse_from_rfc3339(&r.last_seen)
+                                .map(|at| {
+                                    self.display_zone.relative(at.to_utc(), chrono::Utc::now())
+                                })
+                                .unwrap_or_else(|_| r.last_seen.clone());
+                            format!("{} (last seen: {last_seen})", r.runner_id)
                         };
                         checks.push(HealthCheck {
                             name: "Runner".into(),
@@ -1559,7 +2206,21 @@ impl App {
             }
         }
 
-        // 3. Repo token — check via get_repo_token (returns error if not set)
+        // 3. Database
+        checks.push(match self.service.db_stats() {
+            Ok(stats) => HealthCheck {
+                name: "Database".into(),
+                status: CheckStatus::Passed,
+                detail: db_stats_detail(&stats),
+            },
+            Err(e) => HealthCheck {
+                name: "Database".into(),
+                status: CheckStatus::Failed,
+                detail: format!("{e}"),
+            },
+        });
+
+        // 4. Repo token — check via get_repo_token (returns error if not set)
         if !self.project.repo_url.is_empty() {
             let has_token = self.service.get_repo_token(&self.project.id).is_ok();
             checks.push(HealthCheck {
@@ -1577,7 +2238,7 @@ impl App {
             });
         }
 
-        // 4. Git
+        // 5. Git
         let git = match std::process::Command::new("git").arg("--version").output() {
             Ok(out) if out.status.success() => {
                 let ver = String::from_utf8_lossy(&out.stdout).trim().to_string();
@@ -1595,7 +2256,7 @@ impl App {
         };
         checks.push(git);
 
-        // 4. Claude CLI
+        // 6. Claude CLI
         let claude = match std::process::Command::new("claude")
             .arg("--version")
             .output()
@@ -1616,7 +2277,7 @@ impl App {
         };
         checks.push(claude);
 
-        // 5. VCS Provider
+        // 7. VCS Provider
         let provider_name = self
             .project
             .provider_type
@@ -1624,6 +2285,10 @@ impl App {
             .unwrap_or_else(|| {
                 if self.project.repo_url.contains("github.com") {
                     "github (auto-detected)".to_string()
+                } else if self.project.repo_url.contains("dev.azure.com")
+                    || self.project.repo_url.contains(".visualstudio.com")
+                {
+                    "azure_devops (auto-detected)".to_string()
                 } else if self.project.repo_url.is_empty() {
                     "not configured".to_string()
                 } else {
@@ -1688,6 +2353,24 @@ impl App {
                     input: String::new(),
                 };
             }
+            KeyCode::Char('a') => {
+                if let Some(sprint) = list_state.selected().and_then(|i| sprints.get(i)) {
+                    let name = sprint.name.clone();
+                    let id = sprint.id.clone();
+                    match self.service.archive_sprint(&id) {
+                        Ok(_) => {
+                            // An archived sprint has no tasks left on the board
+                            if self.active_sprint.as_ref().map(|s| &s.id) == Some(&id) {
+                                self.active_sprint = None;
+                            }
+                            self.refresh();
+                            self.status_message = Some(format!("Archived sprint: {name}"));
+                        }
+                        Err(e) => self.status_message = Some(format!("Error: {e}")),
+                    }
+                    self.mode = Mode::Normal;
+                }
+            }
             KeyCode::Char('d') => {
                 if let Some(idx) = list_state.selected() {
                     if let Some(sprint) = sprints.get(idx) {
@@ -1758,6 +2441,7 @@ impl App {
                         description: String::new(),
                         status: Status::Todo,
                         priority: parent.priority,
+                        task_type: parent.task_type,
                         parent_id: Some(parent.id.clone()),
                         reviewer: String::new(),
                         research_capability: None,
@@ -1765,6 +2449,7 @@ impl App {
                         plan_capability: None,
                         build_capability: None,
                         verify_capability: None,
+                        due_at: None,
                     }) {
                         Ok(_) => {
                             self.refresh();
@@ -1856,7 +2541,13 @@ impl App {
                 self.render_description_editor(frame, input, area)
             }
             Mode::ConfirmDelete { task } => self.render_confirm_delete_dialog(frame, task, area),
+            Mode::ConfirmDistill { field, .. } => self.render_confirm_distill(frame, field, area),
             Mode::PriorityPick { current, .. } => self.render_priority_pick(frame, *current, area),
+            Mode::AssigneePick {
+                task,
+                users,
+                list_state,
+            } => self.render_assignee_pick(frame, task, users, list_state, area),
             Mode::ProjectList {
                 projects,
                 list_state,
@@ -1949,9 +2640,26 @@ impl App {
                 list_state,
             } => self.render_sprint_list(frame, sprints, list_state, area),
             Mode::NewSprint { input } => self.render_input_bar(frame, "New sprint: ", input, area),
+            Mode::FilterList {
+                filters,
+                list_state,
+            } => self.render_filter_list(frame, filters, list_state, area),
             Mode::NewSubtask { input, .. } => {
                 self.render_input_bar(frame, "New subtask: ", input, area)
             }
+            Mode::Rollup { lanes, offset } => {
+                rollup_board::render(frame, lanes, *offset, layout[1])
+            }
+            Mode::Archived { tasks, list_state } => {
+                self.render_archived(frame, tasks, list_state, area)
+            }
+            Mode::Dashboard { dashboard } => {
+                dashboard::render(frame, &self.project.name, dashboard, layout[1])
+            }
+            Mode::Runners {
+                runners,
+                list_state,
+            } => self.render_runners(frame, runners, list_state, area),
         }
     }
 
@@ -1970,6 +2678,17 @@ impl App {
                 Style::default().fg(Color::Magenta),
             ));
         }
+        if let Some(ref filter) = self.active_filter {
+            spans.push(Span::raw(" | "));
+            spans.push(Span::styled(
+                format!("View: {}", filter.name),
+                Style::default().fg(Color::Green),
+            ));
+        }
+        if self.watched_only {
+            spans.push(Span::raw(" | "));
+            spans.push(Span::styled("Watching", Style::default().fg(Color::Cyan)));
+        }
         let title = Line::from(spans);
         frame.render_widget(title, area);
     }
@@ -1997,6 +2716,12 @@ impl App {
                 ("P", "projects"),
                 ("x", "sprints"),
                 ("X", "clear sprint"),
+                ("f", "views"),
+                ("1-9/0", "view"),
+                ("R", "roll-up"),
+                ("D", "dashboard"),
+                ("w", "watched"),
+                ("U", "runners"),
                 ("H", "health"),
             ],
             Mode::NewTask { .. } => vec![("Enter", "create"), ("Esc", "cancel")],
@@ -2005,6 +2730,8 @@ impl App {
                 ("e", "desc"),
                 ("n", "subtask"),
                 ("p", "priority"),
+                ("u", "assign"),
+                ("f", "watch"),
                 ("m", "move"),
                 ("d", "del"),
                 ("c", "claude"),
@@ -2020,6 +2747,12 @@ impl App {
             Mode::ConfirmDelete { .. } | Mode::ConfirmDeleteProject { .. } => {
                 vec![("y", "confirm"), ("any", "cancel")]
             }
+            Mode::ConfirmDistill { .. } => {
+                vec![("y", "distill"), ("n", "reject only"), ("Esc", "edit")]
+            }
+            Mode::AssigneePick { .. } => {
+                vec![("j/k", "nav"), ("Enter", "assign"), ("Esc", "back")]
+            }
             Mode::PriorityPick { .. } => vec![
                 ("1", "urgent"),
                 ("2", "high"),
@@ -2030,6 +2763,7 @@ impl App {
             Mode::ProjectList { .. } => vec![
                 ("j/k", "nav"),
                 ("Enter", "switch"),
+                ("Space", "roll-up"),
                 ("n", "new"),
                 ("r", "repo url"),
                 ("T", "repo token"),
@@ -2082,11 +2816,28 @@ impl App {
                 ("j/k", "nav"),
                 ("Enter", "select"),
                 ("n", "new"),
+                ("a", "archive"),
                 ("d", "del"),
                 ("Esc", "back"),
             ],
             Mode::NewSprint { .. } => vec![("Enter", "create"), ("Esc", "cancel")],
+            Mode::FilterList { .. } => vec![("j/k", "nav"), ("Enter", "apply"), ("Esc", "back")],
             Mode::NewSubtask { .. } => vec![("Enter", "create"), ("Esc", "cancel")],
+            Mode::Archived { .. } => vec![("j/k", "nav"), ("Enter", "view"), ("Esc", "back")],
+            Mode::Dashboard { .. } => vec![("r", "refresh"), ("Esc", "back")],
+            Mode::Runners { .. } => vec![
+                ("j/k", "nav"),
+                ("d", "drain"),
+                ("+/-", "poll interval"),
+                ("r", "refresh"),
+                ("Esc", "back"),
+            ],
+            Mode::Rollup { .. } => vec![
+                ("j/k", "lanes"),
+                ("Enter", "open project"),
+                ("r", "refresh"),
+                ("Esc", "back"),
+            ],
         };
 
         let spans: Vec<Span> = hints
@@ -2144,8 +2895,59 @@ impl App {
                 Span::styled("Priority: ", Style::default().bold()),
                 Span::styled(task.priority.display_name(), priority_style(task.priority)),
             ]),
+            Line::from(vec![
+                Span::styled("Type: ", Style::default().bold()),
+                Span::raw(task.task_type.display_name()),
+            ]),
+            Line::from(vec![
+                Span::styled("Link: ", Style::default().bold()),
+                Span::raw(self.task_links.task_url(&task.id)),
+            ]),
+            Line::from(vec![
+                Span::styled("Updated: ", Style::default().bold()),
+                Span::raw(
+                    self.display_zone
+                        .relative(task.updated_at, chrono::Utc::now()),
+                ),
+            ]),
         ];
 
+        if let Some(ref assignee_id) = task.assignee_id {
+            let name = self
+                .service
+                .get_user(assignee_id)
+                .map_or_else(|_| assignee_id.clone(), |u| u.name);
+            lines.push(Line::from(vec![
+                Span::styled("Assignee: ", Style::default().bold()),
+                Span::raw(name),
+            ]));
+        }
+
+        if let Ok(watchers) = self.service.list_task_watchers(&task.id) {
+            if !watchers.is_empty() {
+                let names: Vec<&str> = watchers.iter().map(|u| u.name.as_str()).collect();
+                lines.push(Line::from(vec![
+                    Span::styled("Watchers: ", Style::default().bold()),
+                    Span::raw(names.join(", ")),
+                ]));
+            }
+        }
+
+        if let Some(due_at) = task.due_at {
+            let mut due = vec![
+                Span::styled("Due: ", Style::default().bold()),
+                Span::raw(format!(
+                    "{} ({})",
+                    self.display_zone.format(due_at),
+                    self.display_zone.relative(due_at, chrono::Utc::now())
+                )),
+            ];
+            if task.is_overdue(chrono::Utc::now()) {
+                due.push(Span::styled(" (overdue)", Style::default().fg(Color::Red)));
+            }
+            lines.push(Line::from(due));
+        }
+
         // Approval statuses
         lines.push(Line::from(vec![
             Span::styled("Research: ", Style::default().bold()),
@@ -2225,6 +3027,36 @@ impl App {
             }
         }
 
+        // Earlier rejections, which the task's own feedback fields overwrite
+        if let Ok(history) = self.service.list_feedback_history(&task.id) {
+            if !history.is_empty() {
+                lines.push(Line::from(""));
+                lines.push(Line::from(Span::styled(
+                    format!("Feedback history ({}):", history.len()),
+                    Style::default().bold(),
+                )));
+                for entry in history.iter().take(FEEDBACK_HISTORY_SHOWN) {
+                    let author = if entry.author.is_empty() {
+                        "unknown"
+                    } else {
+                        &entry.author
+                    };
+                    lines.push(Line::from(vec![
+                        Span::styled(
+                            format!("  {} ", self.display_zone.format_date(entry.created_at)),
+                            Style::default().fg(Color::DarkGray),
+                        ),
+                        Span::styled(
+                            format!("[{}] ", entry.phase),
+                            Style::default().fg(Color::Red),
+                        ),
+                        Span::styled(format!("{author}: "), Style::default().fg(Color::DarkGray)),
+                        Span::raw(entry.feedback.clone()),
+                    ]));
+                }
+            }
+        }
+
         let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false });
         frame.render_widget(paragraph, inner);
     }
@@ -2261,6 +3093,25 @@ impl App {
         frame.render_widget(paragraph, popup);
     }
 
+    fn render_confirm_distill(&self, frame: &mut Frame, field: &str, area: Rect) {
+        let popup = centered_rect(50, 20, area);
+        frame.render_widget(Clear, popup);
+
+        let block = Block::default()
+            .title(" Reject with Feedback ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow));
+
+        let text = format!(
+            "Queue a distill run to revise the {field} with this feedback?\n\n(y)es / (n)o, just reject / Esc edit"
+        );
+        let paragraph = Paragraph::new(text)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .alignment(Alignment::Center);
+        frame.render_widget(paragraph, popup);
+    }
+
     fn render_priority_pick(&self, frame: &mut Frame, current: Priority, area: Rect) {
         let popup = centered_rect(30, 30, area);
         frame.render_widget(Clear, popup);
@@ -2297,6 +3148,56 @@ impl App {
         frame.render_widget(paragraph, popup);
     }
 
+    fn render_assignee_pick(
+        &self,
+        frame: &mut Frame,
+        task: &Task,
+        users: &[User],
+        list_state: &ListState,
+        area: Rect,
+    ) {
+        let popup = centered_rect(40, 50, area);
+        frame.render_widget(Clear, popup);
+
+        let block = Block::default()
+            .title(" Assignee ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow));
+
+        let marker = |assigned: bool| if assigned { "* " } else { "  " };
+        let mut items = vec![ListItem::new(Line::from(vec![
+            Span::styled(
+                marker(task.assignee_id.is_none()),
+                Style::default().fg(Color::Cyan),
+            ),
+            Span::styled("Unassigned", Style::default().fg(Color::DarkGray)),
+        ]))];
+        items.extend(users.iter().map(|u| {
+            let mut spans = vec![
+                Span::styled(
+                    marker(task.assignee_id.as_deref() == Some(u.id.as_str())),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::styled(&u.name, Style::default().bold()),
+            ];
+            if !u.email.is_empty() {
+                spans.push(Span::styled(
+                    format!(" <{}>", u.email),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        }));
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().fg(Color::Black).bg(Color::Yellow).bold())
+            .highlight_symbol("> ");
+
+        let mut state = list_state.clone();
+        frame.render_stateful_widget(list, popup, &mut state);
+    }
+
     fn render_project_list(
         &self,
         frame: &mut Frame,
@@ -2316,8 +3217,14 @@ impl App {
             .iter()
             .map(|p| {
                 let marker = if p.id == self.project.id { "* " } else { "  " };
+                let rollup = if self.rollup_projects.contains(&p.id) {
+                    "+ "
+                } else {
+                    "  "
+                };
                 let mut spans = vec![
                     Span::styled(marker, Style::default().fg(Color::Cyan)),
+                    Span::styled(rollup, Style::default().fg(Color::Green)),
                     Span::styled(&p.name, Style::default().bold()),
                     Span::styled(
                         format!(" ({})", p.slug),
@@ -2387,6 +3294,149 @@ impl App {
         frame.render_stateful_widget(list, popup, &mut state);
     }
 
+    fn render_archived(
+        &self,
+        frame: &mut Frame,
+        tasks: &[Task],
+        list_state: &ListState,
+        area: Rect,
+    ) {
+        let popup = centered_rect(60, 60, area);
+        frame.render_widget(Clear, popup);
+
+        let block = Block::default()
+            .title(format!(" Archived ({}) ", tasks.len()))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::DarkGray));
+
+        let items: Vec<ListItem> = tasks
+            .iter()
+            .map(|t| {
+                let spans = vec![
+                    Span::styled(&t.title, Style::default().bold()),
+                    Span::styled(
+                        format!(" ({})", t.status.display_name()),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ];
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().fg(Color::Black).bg(Color::Gray).bold())
+            .highlight_symbol("> ");
+
+        let mut state = list_state.clone();
+        frame.render_stateful_widget(list, popup, &mut state);
+    }
+
+    fn render_runners(
+        &self,
+        frame: &mut Frame,
+        runners: &[RunnerRecord],
+        list_state: &ListState,
+        area: Rect,
+    ) {
+        let popup = centered_rect(70, 60, area);
+        frame.render_widget(Clear, popup);
+
+        let block = Block::default()
+            .title(format!(" Runners ({}) ", runners.len()))
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+
+        let now = chrono::Utc::now();
+        let items: Vec<ListItem> = runners
+            .iter()
+            .map(|r| {
+                let status_color = match r.status {
+                    RunnerStatus::Active => Color::Green,
+                    RunnerStatus::Draining => Color::Yellow,
+                    RunnerStatus::Drained => Color::DarkGray,
+                };
+                let mut spans = vec![
+                    Span::styled(&r.runner_id, Style::default().bold()),
+                    Span::styled(format!(" {}", r.status), Style::default().fg(status_color)),
+                    Span::styled(
+                        format!(
+                            " {} poll {}  seen {}",
+                            r.capability.as_deref().unwrap_or("-"),
+                            r.poll_interval
+                                .map_or_else(|| "-".into(), |s| format!("{s}s")),
+                            self.display_zone.relative(r.last_seen_at, now),
+                        ),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ];
+                if let Some(pending) = &r.pending_config {
+                    spans.push(Span::styled(
+                        format!("  pending: {}", pending_config_summary(pending)),
+                        Style::default().fg(Color::Magenta),
+                    ));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().fg(Color::Black).bg(Color::Gray).bold())
+            .highlight_symbol("> ");
+
+        let mut state = list_state.clone();
+        frame.render_stateful_widget(list, popup, &mut state);
+    }
+
+    fn render_filter_list(
+        &self,
+        frame: &mut Frame,
+        filters: &[SavedFilter],
+        list_state: &ListState,
+        area: Rect,
+    ) {
+        let popup = centered_rect(50, 50, area);
+        frame.render_widget(Clear, popup);
+
+        let block = Block::default()
+            .title(" Saved Filters ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Green));
+
+        let items: Vec<ListItem> = filters
+            .iter()
+            .enumerate()
+            .map(|(i, f)| {
+                let marker = if self.active_filter.as_ref().map(|a| &a.id) == Some(&f.id) {
+                    "* "
+                } else {
+                    "  "
+                };
+                // Only the first nine have a quick-switch key
+                let key = if i < 9 {
+                    format!("[{}] ", i + 1)
+                } else {
+                    "    ".into()
+                };
+                let spans = vec![
+                    Span::styled(marker, Style::default().fg(Color::Cyan)),
+                    Span::styled(key, Style::default().fg(Color::Yellow)),
+                    Span::styled(&f.name, Style::default().bold()),
+                ];
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::default().fg(Color::Black).bg(Color::Green).bold())
+            .highlight_symbol("> ");
+
+        let mut state = list_state.clone();
+        frame.render_stateful_widget(list, popup, &mut state);
+    }
+
     fn render_new_project(
         &self,
         frame: &mut Frame,
@@ -2664,6 +3714,53 @@ impl App {
     }
 }
 
+/// One-line summary of database stats for the health screen.
+/// The poll interval `+`/`-` asks for: a step up or down from the one
+/// already waiting for the runner, else the one it runs with.
+fn retuned_poll_interval(runner: &RunnerRecord, up: bool) -> u64 {
+    let current = runner
+        .pending_config
+        .as_ref()
+        .and_then(|p| p.poll_interval)
+        .or(runner.poll_interval)
+        .unwrap_or(POLL_INTERVAL_STEP);
+    if up {
+        current + POLL_INTERVAL_STEP
+    } else {
+        current.saturating_sub(POLL_INTERVAL_STEP).max(1)
+    }
+}
+
+fn pending_config_summary(pending: &PendingConfigResponse) -> String {
+    let mut parts = Vec::new();
+    if let Some(interval) = pending.poll_interval {
+        parts.push(format!("poll {interval}s"));
+    }
+    if pending.drain == Some(true) {
+        parts.push("drain".to_string());
+    }
+    parts.join(", ")
+}
+
+fn db_stats_detail(stats: &DbStats) -> String {
+    let mut detail = format!(
+        "{} schema v{}, {} rows",
+        stats.backend,
+        stats.schema_version,
+        stats.total_rows()
+    );
+    if let Some(bytes) = stats.size_bytes {
+        detail.push_str(&format!(", {:.1} MB", bytes as f64 / (1024.0 * 1024.0)));
+    }
+    if let Some(pool) = &stats.pool {
+        detail.push_str(&format!(
+            ", pool {}/{} ({} idle)",
+            pool.size, pool.max_connections, pool.idle
+        ));
+    }
+    detail
+}
+
 fn next_status(s: Status) -> Option<Status> {
     match s {
         Status::Todo => Some(Status::Research),
@@ -2928,6 +4025,70 @@ mod tests {
         assert!(matches!(check.status, CheckStatus::Passed));
     }
 
+    #[test]
+    fn db_stats_detail_formats_backend_specifics() {
+        let mut stats = DbStats {
+            backend: "sqlite".into(),
+            schema_version: 19,
+            tables: vec![flowstate_db::stats::TableCount {
+                name: "tasks".into(),
+                rows: 42,
+            }],
+            size_bytes: Some(3 * 1024 * 1024),
+            pool: None,
+        };
+        assert_eq!(
+            db_stats_detail(&stats),
+            "sqlite schema v19, 42 rows, 3.0 MB"
+        );
+
+        stats.backend = "postgres".into();
+        stats.size_bytes = None;
+        stats.pool = Some(flowstate_db::stats::PoolStats {
+            size: 3,
+            idle: 2,
+            max_connections: 10,
+        });
+        assert_eq!(
+            db_stats_detail(&stats),
+            "postgres schema v19, 42 rows, pool 3/10 (2 idle)"
+        );
+    }
+
+    #[test]
+    fn retuned_poll_interval_steps_from_pending_config() {
+        let now = chrono::Utc::now();
+        let mut runner = RunnerRecord {
+            runner_id: "r".into(),
+            org_id: None,
+            backend_name: None,
+            capability: None,
+            poll_interval: Some(10),
+            max_concurrent: None,
+            max_builds: None,
+            active_count: None,
+            active_builds: None,
+            status: RunnerStatus::Active,
+            pending_config: None,
+            benchmark: None,
+            first_seen_at: now,
+            last_seen_at: now,
+        };
+        assert_eq!(retuned_poll_interval(&runner, true), 15);
+        assert_eq!(retuned_poll_interval(&runner, false), 5);
+
+        runner.pending_config = Some(PendingConfigResponse {
+            poll_interval: Some(3),
+            drain: Some(true),
+        });
+        assert_eq!(retuned_poll_interval(&runner, true), 8);
+        assert_eq!(retuned_poll_interval(&runner, false), 1);
+        assert_eq!(
+            pending_config_summary(runner.pending_config.as_ref().unwrap()),
+            "poll 3s, drain"
+        );
+    }
+
     #[test]
     fn check_status_failed() {
         let status = CheckStatus::Failed;
@@ -2967,6 +4128,7 @@ mod tests {
             description: String::new(),
             status: Status::Todo,
             priority: Priority::Medium,
+            task_type: TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -2974,6 +4136,7 @@ mod tests {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
             spec_status: ApprovalStatus::None,
             plan_status: ApprovalStatus::None,
             research_status: ApprovalStatus::None,
@@ -2985,7 +4148,11 @@ mod tests {
             spec_approved_hash: String::new(),
             research_approved_hash: String::new(),
             sort_order: 0.0,
+            archived: false,
+            autopilot: false,
             sprint_id: None,
+            epic_id: None,
+            assignee_id: None,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         }
@@ -0,0 +1,168 @@
+use flowstate_core::dashboard::{ProjectDashboard, RunOutcomes};
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem};
+
+use super::task_board::priority_color;
+
+/// Width of the bars drawn next to counts.
+const BAR_WIDTH: usize = 20;
+
+/// A bar of `value` out of `max`, `width` cells long.
+fn bar(value: i64, max: i64, width: usize) -> String {
+    let filled = if max > 0 {
+        ((value.max(0) as f64 / max as f64) * width as f64).round() as usize
+    } else {
+        0
+    };
+    let filled = filled.min(width);
+    format!("{}{}", "█".repeat(filled), "·".repeat(width - filled))
+}
+
+fn percent(rate: Option<f64>) -> String {
+    rate.map(|r| format!("{:.0}%", r * 100.0))
+        .unwrap_or_else(|| "-".into())
+}
+
+fn panel(title: String) -> Block<'static> {
+    Block::default()
+        .title(title)
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+}
+
+/// Render a project's dashboard: task counts by status and priority on top,
+/// run outcomes and sprint progress below.
+pub fn render(frame: &mut Frame, project_name: &str, dashboard: &ProjectDashboard, area: Rect) {
+    frame.render_widget(Clear, area);
+    let outer = Block::default()
+        .title(format!(" Dashboard: {project_name} "))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Magenta));
+    let inner = outer.inner(area);
+    frame.render_widget(outer, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(11), Constraint::Min(0)])
+        .split(inner);
+    let top = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[0]);
+    let bottom = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[1]);
+
+    let total = dashboard.total_tasks();
+    let status_items: Vec<ListItem> = dashboard
+        .tasks_by_status
+        .iter()
+        .map(|c| {
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{:<10}", c.status.display_name())),
+                Span::styled(bar(c.count, total, BAR_WIDTH), Color::Green),
+                Span::raw(format!(" {}", c.count)),
+            ]))
+        })
+        .collect();
+    frame.render_widget(
+        List::new(status_items).block(panel(format!(" Tasks ({total}) "))),
+        top[0],
+    );
+
+    let priority_items: Vec<ListItem> = dashboard
+        .tasks_by_priority
+        .iter()
+        .map(|c| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{} {:<8}", c.priority.symbol(), c.priority.display_name()),
+                    priority_color(c.priority),
+                ),
+                Span::styled(bar(c.count, total, BAR_WIDTH), Color::Yellow),
+                Span::raw(format!(" {}", c.count)),
+            ]))
+        })
+        .collect();
+    frame.render_widget(
+        List::new(priority_items).block(panel(" By Priority ".into())),
+        top[1],
+    );
+
+    let all = dashboard.all_runs();
+    let run_line = |label: String, r: &RunOutcomes| {
+        ListItem::new(Line::from(vec![
+            Span::raw(format!("{label:<17}")),
+            Span::styled(format!("{:>4} ok", r.completed), Color::Green),
+            Span::styled(format!("{:>4} failed", r.failed + r.timed_out), Color::Red),
+            Span::styled(
+                format!("{:>4} cancelled", r.cancelled),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::raw(format!("  {:>4}", percent(r.success_rate()))),
+        ]))
+    };
+    let mut run_items: Vec<ListItem> = dashboard
+        .runs
+        .iter()
+        .map(|r| {
+            let label = r.action.map(|a| a.as_str()).unwrap_or("?").to_string();
+            run_line(label, r)
+        })
+        .collect();
+    if dashboard.runs.is_empty() {
+        run_items.push(ListItem::new(Span::styled(
+            "No finished runs",
+            Style::default().fg(Color::DarkGray),
+        )));
+    } else {
+        run_items.push(run_line("all".into(), &all).style(Style::default().bold()));
+    }
+    frame.render_widget(
+        List::new(run_items).block(panel(format!(
+            " Runs (success {}) ",
+            percent(all.success_rate())
+        ))),
+        bottom[0],
+    );
+
+    let mut sprint_items: Vec<ListItem> = dashboard
+        .sprints
+        .iter()
+        .map(|s| {
+            ListItem::new(Line::from(vec![
+                Span::raw(format!("{:<16.16} ", s.name)),
+                Span::styled(
+                    format!("{:<10}", s.status.display_name()),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(bar(s.done_tasks, s.total_tasks, BAR_WIDTH), Color::Magenta),
+                Span::raw(format!(" {}/{}", s.done_tasks, s.total_tasks)),
+            ]))
+        })
+        .collect();
+    if sprint_items.is_empty() {
+        sprint_items.push(ListItem::new(Span::styled(
+            "No sprints",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    frame.render_widget(
+        List::new(sprint_items).block(panel(" Sprints ".into())),
+        bottom[1],
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bar_scales_to_width() {
+        assert_eq!(bar(0, 0, 4), "····");
+        assert_eq!(bar(1, 2, 4), "██··");
+        assert_eq!(bar(5, 5, 4), "████");
+        assert_eq!(bar(9, 5, 4), "████");
+    }
+}
@@ -1 +1,3 @@
+pub mod dashboard;
+pub mod rollup_board;
 pub mod task_board;
@@ -0,0 +1,81 @@
+use flowstate_core::board::ProjectLane;
+use flowstate_core::task::Status;
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem};
+
+use super::task_board::priority_color;
+
+/// Rows given to each project swimlane, borders included.
+const LANE_HEIGHT: u16 = 8;
+
+/// Number of lanes that fit in `area`, at least one.
+pub fn visible_lanes(area: Rect) -> usize {
+    (area.height / LANE_HEIGHT).max(1) as usize
+}
+
+/// Render `lanes` from `offset` on, one swimlane per project with a
+/// column per board status.
+pub fn render(frame: &mut Frame, lanes: &[ProjectLane], offset: usize, area: Rect) {
+    frame.render_widget(Clear, area);
+    if lanes.is_empty() {
+        let block = Block::default()
+            .title(" Roll-up ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::DarkGray));
+        frame.render_widget(block, area);
+        return;
+    }
+
+    let shown = &lanes[offset.min(lanes.len() - 1)..];
+    let shown = &shown[..shown.len().min(visible_lanes(area))];
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            shown
+                .iter()
+                .map(|_| Constraint::Length(LANE_HEIGHT))
+                .chain([Constraint::Min(0)]),
+        )
+        .split(area);
+
+    for (lane, row) in shown.iter().zip(rows.iter()) {
+        render_lane(frame, lane, *row);
+    }
+}
+
+fn render_lane(frame: &mut Frame, lane: &ProjectLane, area: Rect) {
+    let block = Block::default()
+        .title(format!(" {} ({}) ", lane.project.name, lane.tasks.len()))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Magenta));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let col_count = Status::BOARD_COLUMNS.len() as u32;
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            Status::BOARD_COLUMNS
+                .iter()
+                .map(|_| Constraint::Ratio(1, col_count)),
+        )
+        .split(inner);
+
+    for (&status, col) in Status::BOARD_COLUMNS.iter().zip(columns.iter()) {
+        let tasks: Vec<_> = lane.column(status).collect();
+        let mut items = vec![ListItem::new(Line::from(Span::styled(
+            format!("{} ({})", status.display_name(), tasks.len()),
+            Style::default().fg(Color::DarkGray).bold(),
+        )))];
+        items.extend(tasks.iter().map(|task| {
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    format!("{} ", task.priority.symbol()),
+                    priority_color(task.priority),
+                ),
+                Span::raw(&task.title),
+            ]))
+        }));
+        frame.render_widget(List::new(items), *col);
+    }
+}
@@ -1,5 +1,5 @@
 use crossterm::event::{KeyCode, KeyEvent};
-use flowstate_core::task::{ApprovalStatus, Priority, Status, Task};
+use flowstate_core::task::{ApprovalStatus, Priority, Status, Task, TaskType};
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, List, ListItem, ListState};
 
@@ -140,15 +140,11 @@ impl TaskBoard {
 
     pub fn handle_key(&mut self, key: KeyEvent) {
         match key.code {
-            KeyCode::Char('h') | KeyCode::Left => {
-                if self.active_column > 0 {
-                    self.active_column -= 1;
-                }
+            KeyCode::Char('h') | KeyCode::Left if self.active_column > 0 => {
+                self.active_column -= 1;
             }
-            KeyCode::Char('l') | KeyCode::Right => {
-                if self.active_column + 1 < self.columns.len() {
-                    self.active_column += 1;
-                }
+            KeyCode::Char('l') | KeyCode::Right if self.active_column + 1 < self.columns.len() => {
+                self.active_column += 1;
             }
             KeyCode::Char('j') | KeyCode::Down => {
                 if let Some(col) = self.columns.get_mut(self.active_column) {
@@ -221,6 +217,7 @@ impl TaskBoard {
             .borders(Borders::ALL)
             .border_style(border_style);
 
+        let now = chrono::Utc::now();
         let items: Vec<ListItem> = col
             .tasks
             .iter()
@@ -230,10 +227,17 @@ impl TaskBoard {
                     priority_color(task.priority),
                 );
                 let mut spans = vec![priority_span];
+                if let Some(icon) = task_type_icon(task.task_type) {
+                    spans.push(icon);
+                }
                 if let Some(indicator) = phase_attention_indicator(task) {
                     spans.push(indicator);
                 }
-                spans.push(Span::raw(&task.title));
+                if task.is_overdue(now) {
+                    spans.push(Span::styled(&task.title, Style::default().fg(Color::Red)));
+                } else {
+                    spans.push(Span::raw(&task.title));
+                }
                 ListItem::new(Line::from(spans))
             })
             .collect();
@@ -265,7 +269,18 @@ fn phase_attention_indicator(task: &Task) -> Option<Span<'_>> {
     Some(Span::styled(symbol, style))
 }
 
-fn priority_color(priority: Priority) -> Style {
+/// Features are the common case and carry no icon.
+fn task_type_icon(task_type: TaskType) -> Option<Span<'static>> {
+    let (symbol, color) = match task_type {
+        TaskType::Feature => return None,
+        TaskType::Bug => ("󰃤 ", Color::Red),
+        TaskType::Chore => ("󰖷 ", Color::DarkGray),
+        TaskType::Spike => ("󰂓 ", Color::Magenta),
+    };
+    Some(Span::styled(symbol, Style::default().fg(color)))
+}
+
+pub(crate) fn priority_color(priority: Priority) -> Style {
     match priority {
         Priority::Urgent => Style::default().fg(Color::Red).bold(),
         Priority::High => Style::default().fg(Color::LightRed),
@@ -279,13 +294,15 @@ fn priority_color(priority: Priority) -> Style {
 mod tests {
     use super::*;
     use chrono::Utc;
-    use flowstate_core::task::{ApprovalStatus, Priority, Status, Task};
+    use flowstate_core::task::{ApprovalStatus, Priority, Status, Task, TaskType};
 
     fn make_task(id: &str, status: Status) -> Task {
         Task {
             id: id.to_string(),
             project_id: "proj".to_string(),
             sprint_id: None,
+            epic_id: None,
+            assignee_id: None,
             parent_id: None,
             title: format!("Task {id}"),
             description: String::new(),
@@ -295,6 +312,7 @@ mod tests {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
             research_status: ApprovalStatus::default(),
             spec_status: ApprovalStatus::default(),
             plan_status: ApprovalStatus::default(),
@@ -307,7 +325,10 @@ mod tests {
             verify_feedback: String::new(),
             status,
             priority: Priority::Medium,
+            task_type: TaskType::Feature,
             sort_order: 0.0,
+            archived: false,
+            autopilot: false,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
@@ -669,4 +690,12 @@ mod tests {
         assert_eq!(board.active_column, 1);
         assert_eq!(board.selected_task().unwrap().id, "v1");
     }
+
+    #[test]
+    fn only_non_feature_tasks_get_a_type_icon() {
+        assert!(task_type_icon(TaskType::Feature).is_none());
+        for t in [TaskType::Bug, TaskType::Chore, TaskType::Spike] {
+            assert!(task_type_icon(t).is_some(), "{t} has no icon");
+        }
+    }
 }
@@ -12,6 +12,8 @@ use crossterm::{
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
+use flowstate_core::user::User;
+use flowstate_core::{DisplayZone, TaskLinks};
 use flowstate_service::BlockingHttpService;
 use ratatui::prelude::*;
 
@@ -27,6 +29,38 @@ fn main() -> Result<()> {
     // No args or "up" → spawn server locally then run TUI
     // --server URL → connect to existing server
     // --api-key KEY → authenticate with API key (also reads FLOWSTATE_API_KEY env var)
+    // --user ID|EMAIL → act as this user when watching tasks (also FLOWSTATE_USER)
+    // --tz ZONE → show times in this zone, e.g. Europe/Berlin (also FLOWSTATE_TZ)
+    // open LINK|ID → start with that task open; the handler for flowstate:// links
+    // Links in FLOWSTATE_TASK_LINK's format, as the server and runner emit
+    let task_links = match std::env::var("FLOWSTATE_TASK_LINK") {
+        Ok(template) if !template.is_empty() => {
+            TaskLinks::new(&template).context("FLOWSTATE_TASK_LINK")?
+        }
+        _ => TaskLinks::default(),
+    };
+    let tz = if let Some(pos) = args.iter().position(|a| a == "--tz") {
+        Some(
+            args.get(pos + 1)
+                .context("--tz requires a timezone")?
+                .clone(),
+        )
+    } else {
+        std::env::var("FLOWSTATE_TZ").ok().filter(|z| !z.is_empty())
+    };
+    let display_zone = match tz {
+        Some(tz) => DisplayZone::parse_str(&tz)?,
+        None => DisplayZone::default(),
+    };
+    let open_task = if args.get(1).is_some_and(|a| a == "open") {
+        let target = args
+            .get(2)
+            .context("open requires a task link or id argument")?;
+        Some(task_id_from_arg(target, &task_links)?)
+    } else {
+        None
+    };
+
     let (server_url, mut child) = if let Some(pos) = args.iter().position(|a| a == "--server") {
         let url = args
             .get(pos + 1)
@@ -57,8 +91,32 @@ fn main() -> Result<()> {
     };
     wait_for_server(&service)?;
 
+    // Act as a user (for watching tasks) from --user or FLOWSTATE_USER,
+    // matched against user ids and emails
+    let user = if let Some(pos) = args.iter().position(|a| a == "--user") {
+        Some(
+            args.get(pos + 1)
+                .context("--user requires a user id or email")?
+                .clone(),
+        )
+    } else {
+        std::env::var("FLOWSTATE_USER")
+            .ok()
+            .filter(|u| !u.is_empty())
+    };
+    let user = match user {
+        Some(key) => Some(
+            service
+                .list_users()?
+                .into_iter()
+                .find(|u| u.id == key || (!u.email.is_empty() && u.email == key))
+                .with_context(|| format!("no user with id or email {key}"))?,
+        ),
+        None => None,
+    };
+
     // Run TUI
-    let result = run_tui(service);
+    let result = run_tui(service, user, task_links, display_zone, open_task);
 
     // Cleanup: kill server if we spawned it
     if let Some(ref mut child) = child {
@@ -69,6 +127,21 @@ fn main() -> Result<()> {
     result
 }
 
+/// The task a `flowstate open` argument names: a link in the configured or
+/// default format, or a bare task id.
+fn task_id_from_arg(arg: &str, task_links: &TaskLinks) -> Result<String> {
+    if let Some(id) = task_links
+        .task_id(arg)
+        .or_else(|| TaskLinks::default().task_id(arg))
+    {
+        return Ok(id);
+    }
+    if arg.contains("://") {
+        bail!("not a flowstate task link: {arg}");
+    }
+    Ok(arg.to_string())
+}
+
 fn spawn_server() -> Result<Child> {
     // Look for flowstate-server binary next to our own binary first,
     // then fall back to PATH
@@ -110,14 +183,27 @@ fn wait_for_server(service: &BlockingHttpService) -> Result<()> {
     }
 }
 
-fn run_tui(service: BlockingHttpService) -> Result<()> {
+fn run_tui(
+    service: BlockingHttpService,
+    user: Option<User>,
+    task_links: TaskLinks,
+    display_zone: DisplayZone,
+    open_task: Option<String>,
+) -> Result<()> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let result = event_loop(&mut terminal, service);
+    let result = event_loop(
+        &mut terminal,
+        service,
+        user,
+        task_links,
+        display_zone,
+        open_task,
+    );
 
     disable_raw_mode()?;
     execute!(
@@ -137,8 +223,20 @@ fn run_tui(service: BlockingHttpService) -> Result<()> {
 fn event_loop(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     service: BlockingHttpService,
+    user: Option<User>,
+    task_links: TaskLinks,
+    display_zone: DisplayZone,
+    open_task: Option<String>,
 ) -> Result<()> {
     let mut app = App::new(service)?;
+    if let Some(user) = user {
+        app.set_user(user);
+    }
+    app.set_task_links(task_links);
+    app.set_display_zone(display_zone);
+    if let Some(task_id) = open_task {
+        app.open_task(&task_id);
+    }
 
     loop {
         terminal.draw(|frame| app.render(frame))?;
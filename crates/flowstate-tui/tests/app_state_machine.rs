@@ -63,6 +63,7 @@ fn make_app_with_task() -> (App, String) {
             description: "A test description".into(),
             status: flowstate_core::task::Status::Todo,
             priority: flowstate_core::task::Priority::Medium,
+            task_type: flowstate_core::task::TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -70,6 +71,7 @@ fn make_app_with_task() -> (App, String) {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .unwrap();
 
@@ -806,6 +808,7 @@ fn make_app_with_pending_approval() -> (App, String) {
             description: "Needs approval".into(),
             status: flowstate_core::task::Status::Todo,
             priority: flowstate_core::task::Priority::Medium,
+            task_type: flowstate_core::task::TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -813,6 +816,7 @@ fn make_app_with_pending_approval() -> (App, String) {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .unwrap();
 
@@ -1472,6 +1476,7 @@ fn subtask_claude_action_restricts_non_build_verify() {
             description: String::new(),
             status: flowstate_core::task::Status::Todo,
             priority: flowstate_core::task::Priority::Medium,
+            task_type: flowstate_core::task::TaskType::Feature,
             parent_id: None,
             reviewer: String::new(),
             research_capability: None,
@@ -1479,6 +1484,7 @@ fn subtask_claude_action_restricts_non_build_verify() {
             plan_capability: None,
             build_capability: None,
             verify_capability: None,
+            due_at: None,
         })
         .unwrap();
 
@@ -1489,6 +1495,7 @@ fn subtask_claude_action_restricts_non_build_verify() {
         description: String::new(),
         status: flowstate_core::task::Status::Todo,
         priority: flowstate_core::task::Priority::Medium,
+        task_type: flowstate_core::task::TaskType::Feature,
         parent_id: Some(parent.id.clone()),
         reviewer: String::new(),
         research_capability: None,
@@ -1496,6 +1503,7 @@ fn subtask_claude_action_restricts_non_build_verify() {
         plan_capability: None,
         build_capability: None,
         verify_capability: None,
+        due_at: None,
     })
     .unwrap();
 
@@ -1,14 +1,18 @@
 use std::path::Path;
+use std::sync::Arc;
 
 use chrono::Utc;
 use flowstate_core::verification::VerificationStep;
+use serde::Serialize;
 use tokio::process::Command;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 use tokio::time::{timeout, Duration};
 
 #[derive(Debug, Clone)]
 pub struct Runner;
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct StepResult {
     pub step_name: String,
     pub command: String,
@@ -19,75 +23,157 @@ pub struct StepResult {
     pub finished_at: chrono::DateTime<Utc>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum RunStatus {
     Passed,
     Failed,
     Error,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct RunResult {
     pub status: RunStatus,
     pub steps: Vec<StepResult>,
 }
 
+/// Most output kept per failed step in [`RunResult::summary`].
+const SUMMARY_OUTPUT_LIMIT: usize = 4000;
+
+impl StepResult {
+    pub fn passed(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+impl RunResult {
+    fn from_steps(steps: Vec<StepResult>) -> Self {
+        let status = if steps.iter().all(StepResult::passed) {
+            RunStatus::Passed
+        } else {
+            RunStatus::Failed
+        };
+        Self { status, steps }
+    }
+
+    /// Markdown report: one table row per step, then the tail of each failed
+    /// step's output.
+    pub fn summary(&self) -> String {
+        let mut out = String::from("| Step | Command | Result | Time |\n|---|---|---|---|\n");
+        for step in &self.steps {
+            let result = match step.exit_code {
+                Some(0) => "passed".to_string(),
+                Some(code) => format!("failed (exit {code})"),
+                None => "error".to_string(),
+            };
+            let secs = (step.finished_at - step.started_at).num_milliseconds() as f64 / 1000.0;
+            out.push_str(&format!(
+                "| {} | `{}` | {result} | {secs:.1}s |\n",
+                step.step_name, step.command
+            ));
+        }
+        for step in self.steps.iter().filter(|s| !s.passed()) {
+            let output = format!("{}{}", step.stdout, step.stderr);
+            out.push_str(&format!(
+                "\n### {}\n\n```\n{}\n```\n",
+                step.step_name,
+                tail(output.trim_end(), SUMMARY_OUTPUT_LIMIT)
+            ));
+        }
+        out
+    }
+
+    /// Failed steps grouped by the first error line of their output, so steps
+    /// that broke for the same reason land together. A step with no
+    /// recognisable error line forms a cluster of its own.
+    pub fn failure_clusters(&self) -> Vec<FailureCluster<'_>> {
+        let mut clusters: Vec<(String, FailureCluster<'_>)> = Vec::new();
+        for step in self.steps.iter().filter(|s| !s.passed()) {
+            let (key, signature) = match error_line(step) {
+                Some(line) => (normalize(line), line.to_string()),
+                None => (format!("step:{}", step.step_name), step.step_name.clone()),
+            };
+            match clusters.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, cluster)) => cluster.steps.push(step),
+                None => clusters.push((
+                    key,
+                    FailureCluster {
+                        signature,
+                        steps: vec![step],
+                    },
+                )),
+            }
+        }
+        clusters.into_iter().map(|(_, c)| c).collect()
+    }
+}
+
+/// Failed steps that share a cause; see [`RunResult::failure_clusters`].
+#[derive(Debug)]
+pub struct FailureCluster<'a> {
+    /// The shared error line, or the step's name when none was found.
+    pub signature: String,
+    pub steps: Vec<&'a StepResult>,
+}
+
+impl FailureCluster<'_> {
+    /// Markdown evidence: each step's command, how it exited and the tail of
+    /// its output.
+    pub fn evidence(&self) -> String {
+        let mut out = String::new();
+        for step in &self.steps {
+            let result = match step.exit_code {
+                Some(code) => format!("exit {code}"),
+                None => "error".to_string(),
+            };
+            let output = format!("{}{}", step.stdout, step.stderr);
+            out.push_str(&format!(
+                "### {}\n\n`{}` ({result})\n\n```\n{}\n```\n\n",
+                step.step_name,
+                step.command,
+                tail(output.trim_end(), SUMMARY_OUTPUT_LIMIT)
+            ));
+        }
+        out.truncate(out.trim_end().len());
+        out
+    }
+}
+
+/// The first line of a failed step's output that reports an error: stderr
+/// is searched before stdout, since that is where compilers and test
+/// harnesses put their diagnostics.
+fn error_line(step: &StepResult) -> Option<&str> {
+    step.stderr
+        .lines()
+        .chain(step.stdout.lines())
+        .map(str::trim)
+        .find(|line| {
+            let lower = line.to_lowercase();
+            lower.starts_with("error")
+                || lower.starts_with("fail")
+                || lower.starts_with("timeout")
+                || lower.contains("panicked at")
+                || line.ends_with("FAILED")
+        })
+}
+
+/// Drop digits so line numbers, counts and timings do not split a cluster.
+fn normalize(line: &str) -> String {
+    line.chars().filter(|c| !c.is_ascii_digit()).collect()
+}
+
 impl Runner {
     pub fn new() -> Self {
         Self
     }
 
+    /// Run `steps` in order, stopping at the first failure.
     pub async fn execute(&self, steps: &[VerificationStep], working_dir: &Path) -> RunResult {
         let mut results = Vec::new();
 
         for step in steps {
-            let dir = step
-                .working_dir
-                .as_ref()
-                .map(|d| working_dir.join(d))
-                .unwrap_or_else(|| working_dir.to_path_buf());
-
-            let started_at = Utc::now();
-
-            let result = timeout(
-                Duration::from_secs(step.timeout_s as u64),
-                run_command(&step.command, &dir),
-            )
-            .await;
-
-            let finished_at = Utc::now();
-
-            let step_result = match result {
-                Ok(Ok(output)) => StepResult {
-                    step_name: step.name.clone(),
-                    command: step.command.clone(),
-                    exit_code: output.status.code(),
-                    stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
-                    stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
-                    started_at,
-                    finished_at,
-                },
-                Ok(Err(e)) => StepResult {
-                    step_name: step.name.clone(),
-                    command: step.command.clone(),
-                    exit_code: None,
-                    stdout: String::new(),
-                    stderr: format!("Process error: {e}"),
-                    started_at,
-                    finished_at,
-                },
-                Err(_) => StepResult {
-                    step_name: step.name.clone(),
-                    command: step.command.clone(),
-                    exit_code: None,
-                    stdout: String::new(),
-                    stderr: format!("Timeout after {}s", step.timeout_s),
-                    started_at,
-                    finished_at,
-                },
-            };
-
-            let failed = step_result.exit_code != Some(0);
+            let step_result = run_step(step, working_dir).await;
+            let failed = !step_result.passed();
             results.push(step_result);
 
             if failed {
@@ -95,16 +181,38 @@ impl Runner {
             }
         }
 
-        let status = if results.iter().all(|r| r.exit_code == Some(0)) {
-            RunStatus::Passed
-        } else {
-            RunStatus::Failed
-        };
+        RunResult::from_steps(results)
+    }
+
+    /// Run every step, up to `max_parallel` at a time, without stopping on
+    /// failure. Suits independent checks (build, tests, lint, docs) where a
+    /// full picture is worth more than failing fast. Results keep the order
+    /// of `steps`.
+    pub async fn execute_parallel(
+        &self,
+        steps: &[VerificationStep],
+        working_dir: &Path,
+        max_parallel: usize,
+    ) -> RunResult {
+        let permits = Arc::new(Semaphore::new(max_parallel.max(1)));
+        let mut set = JoinSet::new();
+        for (index, step) in steps.iter().cloned().enumerate() {
+            let permits = permits.clone();
+            let working_dir = working_dir.to_path_buf();
+            set.spawn(async move {
+                let _permit = permits.acquire_owned().await;
+                (index, run_step(&step, &working_dir).await)
+            });
+        }
 
-        RunResult {
-            status,
-            steps: results,
+        let mut results: Vec<Option<StepResult>> = steps.iter().map(|_| None).collect();
+        while let Some(joined) = set.join_next().await {
+            match joined {
+                Ok((index, result)) => results[index] = Some(result),
+                Err(e) => std::panic::resume_unwind(e.into_panic()),
+            }
         }
+        RunResult::from_steps(results.into_iter().flatten().collect())
     }
 }
 
@@ -114,11 +222,69 @@ impl Default for Runner {
     }
 }
 
+async fn run_step(step: &VerificationStep, working_dir: &Path) -> StepResult {
+    let dir = step
+        .working_dir
+        .as_ref()
+        .map(|d| working_dir.join(d))
+        .unwrap_or_else(|| working_dir.to_path_buf());
+
+    let started_at = Utc::now();
+
+    let result = timeout(
+        Duration::from_secs(step.timeout_s as u64),
+        run_command(&step.command, &dir),
+    )
+    .await;
+
+    let finished_at = Utc::now();
+
+    match result {
+        Ok(Ok(output)) => StepResult {
+            step_name: step.name.clone(),
+            command: step.command.clone(),
+            exit_code: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            started_at,
+            finished_at,
+        },
+        Ok(Err(e)) => StepResult {
+            step_name: step.name.clone(),
+            command: step.command.clone(),
+            exit_code: None,
+            stdout: String::new(),
+            stderr: format!("Process error: {e}"),
+            started_at,
+            finished_at,
+        },
+        Err(_) => StepResult {
+            step_name: step.name.clone(),
+            command: step.command.clone(),
+            exit_code: None,
+            stdout: String::new(),
+            stderr: format!("Timeout after {}s", step.timeout_s),
+            started_at,
+            finished_at,
+        },
+    }
+}
+
+/// The last `limit` bytes of `s`, on a char boundary.
+fn tail(s: &str, limit: usize) -> &str {
+    let mut start = s.len().saturating_sub(limit);
+    while !s.is_char_boundary(start) {
+        start += 1;
+    }
+    &s[start..]
+}
+
 async fn run_command(command: &str, dir: &Path) -> std::io::Result<std::process::Output> {
     Command::new("sh")
         .arg("-c")
         .arg(command)
         .current_dir(dir)
+        .kill_on_drop(true)
         .output()
         .await
 }
@@ -225,7 +391,81 @@ mod tests {
         assert_eq!(result.steps.len(), 2);
     }
 
+    #[tokio::test]
+    async fn parallel_runs_every_step_concurrently() {
+        let dir = tempfile::tempdir().unwrap();
+        let runner = Runner::new();
+        let steps = vec![
+            make_step("slow", "sleep 1 && echo slow", 10),
+            make_step("fail", "sleep 1 && false", 10),
+            make_step("fast", "echo fast", 10),
+        ];
+        let started = std::time::Instant::now();
+        let result = runner.execute_parallel(&steps, dir.path(), 3).await;
+        assert!(started.elapsed() < std::time::Duration::from_millis(1900));
+        assert!(matches!(result.status, RunStatus::Failed));
+        let names: Vec<&str> = result.steps.iter().map(|s| s.step_name.as_str()).collect();
+        assert_eq!(names, vec!["slow", "fail", "fast"]);
+        assert!(result.steps[0].passed() && result.steps[2].passed());
+
+        let summary = result.summary();
+        assert!(summary.contains("| fail | `sleep 1 && false` | failed (exit 1) |"));
+        assert!(summary.contains("### fail"));
+        assert!(!summary.contains("### fast"));
+    }
+
+    fn failed(name: &str, stdout: &str, stderr: &str) -> StepResult {
+        StepResult {
+            step_name: name.into(),
+            command: format!("run {name}"),
+            exit_code: Some(101),
+            stdout: stdout.into(),
+            stderr: stderr.into(),
+            started_at: chrono::Utc::now(),
+            finished_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn failure_clusters_group_by_error_line() {
+        let mut ok = failed("fmt", "", "");
+        ok.exit_code = Some(0);
+        let result = RunResult::from_steps(vec![
+            ok,
+            failed(
+                "build",
+                "",
+                "   Compiling x\nerror[E0425]: cannot find value `y`\n --> src/a.rs:3:9",
+            ),
+            failed(
+                "clippy",
+                "",
+                "error[E0425]: cannot find value `y`\n --> src/a.rs:4:1",
+            ),
+            failed("test", "test a::b ... FAILED\n", "error: test failed"),
+            failed("lint", "something odd\n", ""),
+        ]);
+        let clusters = result.failure_clusters();
+        let signatures: Vec<_> = clusters.iter().map(|c| c.signature.as_str()).collect();
+        assert_eq!(
+            signatures,
+            vec![
+                "error[E0425]: cannot find value `y`",
+                "error: test failed",
+                "lint"
+            ]
+        );
+        assert_eq!(clusters[0].steps.len(), 2);
+
+        let evidence = clusters[0].evidence();
+        assert!(evidence.contains("### build\n\n`run build` (exit 101)"));
+        assert!(evidence.contains("### clippy"));
+        assert!(evidence.contains("src/a.rs:4:1"));
+        assert!(!evidence.contains("### fmt"));
+    }
+
     #[test]
+    #[allow(clippy::default_constructed_unit_structs)]
     fn runner_default() {
         let _runner = Runner::default();
     }
@@ -121,6 +121,7 @@ let
     echo ""
 
     # ── 4. Run cargo llvm-cov ──
+    # No GCS or Azure emulator is started, so their integration tests are skipped.
     echo "Running cargo llvm-cov (workspace, all features, including ignored tests)..."
     echo ""
 
@@ -132,6 +133,8 @@ let
       --exclude flowstate-mcp \
       --no-fail-fast \
       -- --include-ignored \
+      --skip gcs::tests::gcs_ \
+      --skip azure::tests::azure_ \
       || CARGO_EXIT=$?
 
     echo ""